@@ -0,0 +1,632 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{
+    console::{
+        account::{Address, GraphKey, ViewKey},
+        network::{CanaryV0, MainnetV0, Network, TestnetV0},
+        program::{Argument, Identifier, Literal, Plaintext, ProgramID, Record, Value},
+        types::Field,
+    },
+    ledger::{
+        Ledger,
+        block::{Block, Output, Transition},
+        store::{BlockStorage, BlockStore, ConsensusStorage, ConsensusStore, helpers::rocksdb::ConsensusDB},
+    },
+};
+
+use aleo_std::StorageMode;
+use anyhow::{Result, anyhow, bail, ensure};
+use clap::{Parser, ValueEnum};
+use colored::Colorize;
+use core::{cmp::Ordering, str::FromStr};
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    fmt::Write as _,
+    io::{Write, stdout},
+    path::{Path, PathBuf},
+};
+use zeroize::Zeroize;
+
+/// The format of an exported account history.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum HistoryFormat {
+    Csv,
+    Json,
+}
+
+/// Exports the transaction history of an account from a local ledger.
+///
+/// The ledger is read directly, so no node may be running on it. As the ledger does not index transactions by
+/// address, every block in the range is scanned. With a view key, the scan starts from genesis instead, so that
+/// records created before `--from` and spent within the range are recognized.
+///
+/// Note: Staking rewards are credited to the bonded balances during finalization, and are not recorded in the
+/// blocks. To export them, the blocks are also replayed from genesis into a temporary ledger, which can be
+/// skipped with `--skip-staking-rewards`.
+#[derive(Debug, Parser, Zeroize)]
+pub struct History {
+    /// Specify the network of the ledger.
+    #[clap(default_value = "0", long = "network")]
+    pub network: u16,
+    /// Specify the address of the account.
+    #[clap(long = "address")]
+    pub address: String,
+    /// Specify the view key of the account, to include its private records.
+    #[clap(long = "view-key")]
+    view_key: Option<String>,
+    /// Specify the path to the ledger.
+    #[clap(long = "ledger")]
+    #[zeroize(skip)]
+    pub ledger: PathBuf,
+    /// Specify the first block height to export (inclusive).
+    #[clap(default_value = "0", long = "from")]
+    pub from: u32,
+    /// Specify the last block height to export (inclusive), which defaults to the latest block height.
+    #[clap(long = "to")]
+    pub to: Option<u32>,
+    /// Specify the format of the export.
+    #[clap(default_value = "csv", long = "format")]
+    #[zeroize(skip)]
+    pub format: HistoryFormat,
+    /// Specify the path to the export.
+    #[clap(long = "output")]
+    #[zeroize(skip)]
+    pub output: PathBuf,
+    /// If the flag is set, the staking rewards are not exported, which avoids replaying the blocks
+    #[clap(long = "skip-staking-rewards")]
+    pub skip_staking_rewards: bool,
+}
+
+impl History {
+    /// Scans the ledger for the history of the account, and writes the export.
+    pub fn parse(self) -> Result<String> {
+        let rows = match self.network {
+            MainnetV0::ID => self.export::<MainnetV0>()?,
+            TestnetV0::ID => self.export::<TestnetV0>()?,
+            CanaryV0::ID => self.export::<CanaryV0>()?,
+            unknown_id => bail!("Unknown network ID ({unknown_id})"),
+        };
+
+        write_history(&rows, self.format, &self.output)?;
+        let output_path = format!("(in \"{}\")", self.output.display()).dimmed();
+        Ok(format!("✅ Exported {} events of '{}' {output_path}", rows.len(), self.address))
+    }
+
+    /// Returns the history of the account in the requested range of the ledger.
+    fn export<N: Network>(&self) -> Result<Vec<HistoryRow>> {
+        let address = Address::<N>::from_str(&self.address).map_err(|_| anyhow!("Failed to parse a valid address"))?;
+        let view_key = match &self.view_key {
+            Some(view_key) => {
+                let view_key =
+                    ViewKey::<N>::from_str(view_key).map_err(|_| anyhow!("Failed to parse a valid view key"))?;
+                ensure!(view_key.to_address() == address, "The view key does not belong to '{address}'");
+                Some(view_key)
+            }
+            None => None,
+        };
+
+        // Open the ledger, which fails if a node is still running on it.
+        ensure!(self.ledger.exists(), "The ledger at {} does not exist", self.ledger.display());
+        let store = ConsensusStore::<N, ConsensusDB<N>>::open(StorageMode::Custom(self.ledger.clone()))
+            .map_err(|error| anyhow!("Failed to open the ledger (is a node running on it?) - {error}"))?;
+        let latest_height = store.block_store().max_height().ok_or_else(|| anyhow!("The ledger is empty"))?;
+        let to = self.to.unwrap_or(latest_height);
+        ensure!(self.from <= to, "The '--from' height must not be greater than the '--to' height");
+        ensure!(to <= latest_height, "The ledger only contains blocks up to height {latest_height}");
+
+        // Initialize a temporary ledger to replay the blocks into, unless the staking rewards are skipped.
+        let storage_path = std::env::temp_dir().join(format!("snarkos-history-{}", rand::random::<u64>()));
+        let staking = match self.skip_staking_rewards {
+            true => None,
+            false => {
+                let genesis = get_block(store.block_store(), 0)?;
+                let ledger = Ledger::<N, ConsensusDB<N>>::load(genesis, StorageMode::Custom(storage_path.clone()))?;
+                Some(StakingReplay::new(ledger, address))
+            }
+        };
+
+        // Scan the blocks, reporting the progress.
+        let mut scanner = HistoryScanner::new(address, view_key)?;
+        let rows =
+            scan_history(store.block_store(), &mut scanner, staking.as_ref(), self.from, to, |percentage_complete| {
+                print!("\rScanning the ledger for the history of the account ({percentage_complete:.2}% complete)...");
+                let _ = stdout().flush();
+            });
+        println!();
+
+        // Remove the temporary ledger.
+        drop(staking);
+        if storage_path.exists() {
+            if let Err(error) = std::fs::remove_dir_all(&storage_path) {
+                eprintln!("Failed to remove the temporary ledger at {} - {error}", storage_path.display());
+            }
+        }
+        rows
+    }
+}
+
+/// The category of an event in the history of an account.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryCategory {
+    PublicTransferIn,
+    PublicTransferOut,
+    PrivateTransferIn,
+    PrivateTransferOut,
+    Fee,
+    Deployment,
+    Bond,
+    Unbond,
+    Reward,
+}
+
+impl HistoryCategory {
+    /// Returns the name of the category.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::PublicTransferIn => "public_transfer_in",
+            Self::PublicTransferOut => "public_transfer_out",
+            Self::PrivateTransferIn => "private_transfer_in",
+            Self::PrivateTransferOut => "private_transfer_out",
+            Self::Fee => "fee",
+            Self::Deployment => "deployment",
+            Self::Bond => "bond",
+            Self::Unbond => "unbond",
+            Self::Reward => "reward",
+        }
+    }
+}
+
+/// An event in the history of an account.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct HistoryRow {
+    /// The height of the block.
+    pub height: u32,
+    /// The timestamp of the block.
+    pub timestamp: i64,
+    /// The ID of the confirmed transaction, if the event is part of a transaction.
+    pub transaction_id: Option<String>,
+    /// The category of the event.
+    pub category: HistoryCategory,
+    /// The amount moved by the event, in microcredits.
+    pub amount: u64,
+    /// The address of the counterparty (the program ID of a deployment, or the validator of a staking reward),
+    /// when it is determinable.
+    pub counterparty: Option<String>,
+}
+
+/// Writes the rows to the given path, in the given format.
+fn write_history(rows: &[HistoryRow], format: HistoryFormat, path: &Path) -> Result<()> {
+    let contents = match format {
+        HistoryFormat::Csv => history_to_csv(rows),
+        HistoryFormat::Json => serde_json::to_string_pretty(rows)?,
+    };
+    Ok(std::fs::write(path, contents)?)
+}
+
+/// Returns the rows in CSV format.
+fn history_to_csv(rows: &[HistoryRow]) -> String {
+    let mut csv = "height,timestamp,transaction_id,category,amount,counterparty\n".to_string();
+    for row in rows {
+        let _ = writeln!(
+            csv,
+            "{},{},{},{},{},{}",
+            row.height,
+            row.timestamp,
+            row.transaction_id.as_deref().unwrap_or_default(),
+            row.category.as_str(),
+            row.amount,
+            row.counterparty.as_deref().unwrap_or_default()
+        );
+    }
+    csv
+}
+
+/// Returns the block at the given height from the given block store.
+fn get_block<N: Network, B: BlockStorage<N>>(source: &BlockStore<N, B>, height: u32) -> Result<Block<N>> {
+    let hash = source.get_block_hash(height)?.ok_or_else(|| anyhow!("Missing block {height} in the ledger"))?;
+    source.get_block(&hash)?.ok_or_else(|| anyhow!("Missing block {height} in the ledger"))
+}
+
+/// Scans the given block store for the history of the account, reporting the percentage of blocks scanned.
+/// The blocks are read one at a time, so that memory usage is bounded regardless of the range.
+fn scan_history<N: Network, B: BlockStorage<N>, C: ConsensusStorage<N>>(
+    source: &BlockStore<N, B>,
+    scanner: &mut HistoryScanner<N>,
+    staking: Option<&StakingReplay<N, C>>,
+    from: u32,
+    to: u32,
+    mut on_progress: impl FnMut(f64),
+) -> Result<Vec<HistoryRow>> {
+    // Records created before the range may be spent within it, and the stake bonded before the range earns rewards
+    // within it, so the private history and the staking rewards are scanned from genesis.
+    let start = if scanner.view_key.is_some() || staking.is_some() { 0 } else { from };
+
+    let mut rows = Vec::new();
+    for height in start..=to {
+        let block = get_block(source, height)?;
+        let mut block_rows = scanner.scan_block(&block)?;
+        if let Some(staking) = staking {
+            block_rows.extend(staking.replay(&block, &block_rows)?);
+        }
+        if height >= from {
+            rows.extend(block_rows);
+        }
+        on_progress(f64::from(height - start + 1) * 100.0 / (f64::from(to - start) + 1.0));
+    }
+    Ok(rows)
+}
+
+/// The amount moved by a transition for an account, with its category and counterparty.
+type TransitionEvent = (HistoryCategory, u64, Option<String>);
+
+/// A scanner of the history of an account, which is fed the blocks in order.
+struct HistoryScanner<N: Network> {
+    /// The address of the account.
+    address: Address<N>,
+    /// The view key of the account and its tag secret key, if the private records are scanned.
+    view_key: Option<(ViewKey<N>, Field<N>)>,
+    /// The amounts of the unspent credits records of the account, by tag.
+    records: HashMap<Field<N>, u64>,
+}
+
+impl<N: Network> HistoryScanner<N> {
+    /// Initializes a new scanner.
+    fn new(address: Address<N>, view_key: Option<ViewKey<N>>) -> Result<Self> {
+        let view_key = match view_key {
+            Some(view_key) => {
+                let sk_tag = GraphKey::try_from(&view_key)?.sk_tag();
+                Some((view_key, sk_tag))
+            }
+            None => None,
+        };
+        Ok(Self { address, view_key, records: Default::default() })
+    }
+
+    /// Returns the history of the account in the given block.
+    fn scan_block(&mut self, block: &Block<N>) -> Result<Vec<HistoryRow>> {
+        let row = |transaction_id: Option<String>, (category, amount, counterparty): TransitionEvent| HistoryRow {
+            height: block.height(),
+            timestamp: block.timestamp(),
+            transaction_id,
+            category,
+            amount,
+            counterparty,
+        };
+
+        let mut rows = Vec::new();
+        for confirmed in block.transactions().iter() {
+            let transaction = confirmed.transaction();
+            // Note: The fee of a deployment is recorded as the deployment itself.
+            let program_id = transaction.deployment().map(|deployment| deployment.program_id().to_string());
+            for transition in transaction.transitions() {
+                for event in self.scan_transition(transition)? {
+                    let event = match (event, &program_id) {
+                        ((HistoryCategory::Fee, amount, _), Some(program_id)) => {
+                            (HistoryCategory::Deployment, amount, Some(program_id.clone()))
+                        }
+                        (event, _) => event,
+                    };
+                    rows.push(row(Some(confirmed.id().to_string()), event));
+                }
+            }
+        }
+
+        // Record the puzzle rewards of the solutions of the account.
+        if let Some(solutions) = block.solutions().as_ref() {
+            for (solution_id, solution) in solutions.iter() {
+                if solution.address() != self.address {
+                    continue;
+                }
+                match snarkos_node_rest::solution_reward(block, solution_id) {
+                    Some(reward) if reward > 0 => rows.push(row(None, (HistoryCategory::Reward, reward, None))),
+                    _ => {}
+                }
+            }
+        }
+        Ok(rows)
+    }
+
+    /// Returns the events of the account in the given transition.
+    fn scan_transition(&mut self, transition: &Transition<N>) -> Result<Vec<TransitionEvent>> {
+        // Only the credits program moves credits.
+        if transition.program_id().to_string() != "credits.aleo" {
+            return Ok(vec![]);
+        }
+        let function = transition.function_name().to_string();
+        let arguments = future_literals(transition);
+        let address = |index: usize| match arguments.get(index) {
+            Some(Some(Literal::Address(address))) => Some(*address),
+            _ => None,
+        };
+        let amount = |index: usize| match arguments.get(index) {
+            Some(Some(Literal::U64(amount))) => Some(**amount),
+            _ => None,
+        };
+        let account = Some(self.address);
+
+        // Classify the public credits moved by the transition, using the arguments of its finalize scope.
+        let mut events = Vec::new();
+        let mut push = |category, amount: Option<u64>, counterparty: Option<Address<N>>| {
+            if let Some(amount) = amount {
+                events.push((category, amount, counterparty.map(|counterparty| counterparty.to_string())));
+            }
+        };
+        match function.as_str() {
+            // The arguments are the sender, the receiver, and the amount.
+            "transfer_public" | "transfer_public_as_signer" if address(0) != address(1) => {
+                if address(0) == account {
+                    push(HistoryCategory::PublicTransferOut, amount(2), address(1));
+                } else if address(1) == account {
+                    push(HistoryCategory::PublicTransferIn, amount(2), address(0));
+                }
+            }
+            // The arguments are the sender, and the amount.
+            "transfer_public_to_private" if address(0) == account => {
+                push(HistoryCategory::PublicTransferOut, amount(1), None)
+            }
+            // The arguments are the receiver, and the amount.
+            "transfer_private_to_public" if address(0) == account => {
+                push(HistoryCategory::PublicTransferIn, amount(1), None)
+            }
+            // The arguments are the payer, and the amount.
+            "fee_public" if address(0) == account => push(HistoryCategory::Fee, amount(1), None),
+            // The arguments are the staker, the validator, the withdrawal address, and the amount.
+            "bond_public" if address(0) == account => push(HistoryCategory::Bond, amount(3), address(1)),
+            // The arguments are the validator, the withdrawal address, the amount, and the commission.
+            "bond_validator" if address(0) == account => push(HistoryCategory::Bond, amount(2), None),
+            // The arguments are the caller, the staker, and the amount.
+            "unbond_public" if address(1) == account => push(HistoryCategory::Unbond, amount(2), None),
+            _ => {}
+        }
+
+        // Classify the private credits moved by the transition, as the difference between the records of the
+        // account it spent and created.
+        if let Some((view_key, sk_tag)) = &self.view_key {
+            let spent: u64 = transition.tags().filter_map(|tag| self.records.remove(tag)).sum();
+            let mut created = 0u64;
+            for (commitment, record) in transition.records() {
+                if record.is_owner(view_key) {
+                    let microcredits = record.decrypt(view_key)?.microcredits()?;
+                    self.records.insert(Record::<N, Plaintext<N>>::tag(*sk_tag, *commitment)?, microcredits);
+                    created += microcredits;
+                }
+            }
+            match (function.as_str(), spent.cmp(&created)) {
+                ("fee_private", Ordering::Greater) => push(HistoryCategory::Fee, Some(spent - created), None),
+                // The receiver of a transfer to public credits is known, unlike the receiver of a record.
+                ("transfer_private_to_public", Ordering::Greater) => {
+                    push(HistoryCategory::PrivateTransferOut, Some(spent - created), address(0))
+                }
+                (_, Ordering::Greater) => push(HistoryCategory::PrivateTransferOut, Some(spent - created), None),
+                (_, Ordering::Less) => push(HistoryCategory::PrivateTransferIn, Some(created - spent), None),
+                (_, Ordering::Equal) => {}
+            }
+        }
+        Ok(events)
+    }
+}
+
+/// A replay of the blocks into a ledger, which recovers the staking rewards of an account from its bonded balance.
+struct StakingReplay<N: Network, C: ConsensusStorage<N>> {
+    /// The ledger the blocks are replayed into.
+    ledger: Ledger<N, C>,
+    /// The address of the account.
+    address: Address<N>,
+}
+
+impl<N: Network, C: ConsensusStorage<N>> StakingReplay<N, C> {
+    /// Initializes a new replay into the given ledger.
+    fn new(ledger: Ledger<N, C>, address: Address<N>) -> Self {
+        Self { ledger, address }
+    }
+
+    /// Returns the validator and the bonded balance of the account, if it is bonded.
+    fn bonded(&self) -> Result<Option<(Address<N>, u64)>> {
+        let credits = ProgramID::from_str("credits.aleo")?;
+        let key = Plaintext::from(Literal::Address(self.address));
+        let value =
+            self.ledger.vm().finalize_store().get_value_confirmed(credits, Identifier::from_str("bonded")?, &key)?;
+        let Some(Value::Plaintext(Plaintext::Struct(members, _))) = value else {
+            return Ok(None);
+        };
+        match (members.get(&Identifier::from_str("validator")?), members.get(&Identifier::from_str("microcredits")?)) {
+            (
+                Some(Plaintext::Literal(Literal::Address(validator), _)),
+                Some(Plaintext::Literal(Literal::U64(microcredits), _)),
+            ) => Ok(Some((*validator, **microcredits))),
+            _ => bail!("Malformed bonded balance of '{}'", self.address),
+        }
+    }
+
+    /// Applies the given block to the ledger, and returns the staking reward of the account in it, if any.
+    /// The given rows are the history of the account in the block, whose bonds and unbonds precede the rewards.
+    fn replay(&self, block: &Block<N>, rows: &[HistoryRow]) -> Result<Option<HistoryRow>> {
+        // Skip the blocks that were already applied, such as the genesis block.
+        if block.height() <= self.ledger.latest_height() {
+            return Ok(None);
+        }
+        let before = self.bonded()?.map_or(0, |(_, microcredits)| microcredits);
+        self.ledger.advance_to_next_block(block)?;
+        // An account unbonding below the minimum stake is removed from the bonded balances, and earns no reward.
+        let Some((validator, after)) = self.bonded()? else {
+            return Ok(None);
+        };
+
+        // The staking reward is the change in the bonded balance, which is not explained by the bonds and unbonds.
+        let expected = rows.iter().fold(i128::from(before), |stake, row| match row.category {
+            HistoryCategory::Bond => stake + i128::from(row.amount),
+            HistoryCategory::Unbond => stake - i128::from(row.amount),
+            _ => stake,
+        });
+        match u64::try_from(i128::from(after) - expected) {
+            Ok(reward) if reward > 0 => Ok(Some(HistoryRow {
+                height: block.height(),
+                timestamp: block.timestamp(),
+                transaction_id: None,
+                category: HistoryCategory::Reward,
+                amount: reward,
+                counterparty: Some(validator.to_string()),
+            })),
+            _ => Ok(None),
+        }
+    }
+}
+
+/// Returns the literal arguments of the future output by the given transition, in order.
+fn future_literals<N: Network>(transition: &Transition<N>) -> Vec<Option<Literal<N>>> {
+    transition
+        .outputs()
+        .iter()
+        .find_map(|output| match output {
+            Output::Future(_, Some(future)) => Some(
+                future
+                    .arguments()
+                    .iter()
+                    .map(|argument| match argument {
+                        Argument::Plaintext(Plaintext::Literal(literal, _)) => Some(literal.clone()),
+                        _ => None,
+                    })
+                    .collect(),
+            ),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkos_node_bft_ledger_service::test_helpers::{sample_genesis_block, sample_ledger_from_genesis};
+    use snarkvm::{
+        ledger::{Ledger, store::helpers::memory::ConsensusMemory},
+        prelude::{Identifier, PrivateKey, ProgramID, U64, Value},
+    };
+
+    use rand::SeedableRng;
+    use rand_chacha::ChaChaRng;
+
+    type CurrentNetwork = MainnetV0;
+
+    /// A scan that skips the staking rewards.
+    const NO_STAKING: Option<&StakingReplay<CurrentNetwork, ConsensusMemory<CurrentNetwork>>> = None;
+
+    /// The amount of the sample public transfer, in microcredits.
+    const PUBLIC_AMOUNT: u64 = 1_000;
+    /// The amount of the sample transfer from public to private credits, in microcredits.
+    const PRIVATE_AMOUNT: u64 = 500;
+
+    #[test]
+    fn test_history_of_devnet_transfers() {
+        let rng = &mut ChaChaRng::seed_from_u64(1234567890u64);
+
+        // Initialize a devnet ledger, funding the sender.
+        let (sender, genesis) = sample_genesis_block::<CurrentNetwork, _>(rng);
+        let ledger = sample_ledger_from_genesis(&sender, &genesis, 0, rng);
+
+        let receiver = PrivateKey::<CurrentNetwork>::new(rng).unwrap();
+        let sender_address = Address::try_from(&sender).unwrap();
+        let receiver_address = Address::try_from(&receiver).unwrap();
+
+        // Send a public transfer, and a transfer from public to private credits, to the receiver.
+        let mut transfer = |function: &str, amount: u64| {
+            let locator = (ProgramID::from_str("credits.aleo").unwrap(), Identifier::from_str(function).unwrap());
+            let inputs = [Value::from(Literal::Address(receiver_address)), Value::from(Literal::U64(U64::new(amount)))];
+            ledger.vm().execute(&sender, locator, inputs.into_iter(), None, 0, None, rng).unwrap()
+        };
+        let transactions =
+            vec![transfer("transfer_public", PUBLIC_AMOUNT), transfer("transfer_public_to_private", PRIVATE_AMOUNT)];
+        let block = ledger.prepare_advance_to_next_beacon_block(&sender, vec![], vec![], transactions, rng).unwrap();
+        ledger.advance_to_next_block(&block).unwrap();
+        let transaction_ids =
+            block.transactions().iter().map(|confirmed| confirmed.id().to_string()).collect::<Vec<_>>();
+        assert_eq!(transaction_ids.len(), 2);
+
+        // Ensure the history of the sender lists both transfers out, each followed by its fee.
+        let mut scanner = HistoryScanner::new(sender_address, None).unwrap();
+        let rows = scan_history(ledger.vm().block_store(), &mut scanner, NO_STAKING, 1, 1, |_| {}).unwrap();
+        let events = rows.iter().map(|row| (row.category, row.counterparty.clone())).collect::<Vec<_>>();
+        assert_eq!(events, [
+            (HistoryCategory::PublicTransferOut, Some(receiver_address.to_string())),
+            (HistoryCategory::Fee, None),
+            (HistoryCategory::PublicTransferOut, None),
+            (HistoryCategory::Fee, None),
+        ]);
+        assert_eq!((rows[0].amount, rows[2].amount), (PUBLIC_AMOUNT, PRIVATE_AMOUNT));
+        assert!(rows[1].amount > 0 && rows[3].amount > 0);
+        assert!(rows.iter().all(|row| row.height == 1 && row.timestamp == block.timestamp()));
+        assert_eq!(rows[0].transaction_id.as_ref(), Some(&transaction_ids[0]));
+        assert_eq!(rows[2].transaction_id.as_ref(), Some(&transaction_ids[1]));
+
+        // Ensure the sender, a validator of the devnet, also earns a staking reward once the blocks are replayed.
+        let replay = Ledger::<CurrentNetwork, ConsensusMemory<CurrentNetwork>>::load(genesis, StorageMode::Production);
+        let staking = StakingReplay::new(replay.unwrap(), sender_address);
+        let (validator, bonded_at_genesis) = staking.bonded().unwrap().unwrap();
+        let mut scanner = HistoryScanner::new(sender_address, None).unwrap();
+        let staking_rows = scan_history(ledger.vm().block_store(), &mut scanner, Some(&staking), 1, 1, |_| {}).unwrap();
+        assert_eq!(staking_rows[..4], rows[..]);
+        assert_eq!(staking_rows.len(), 5);
+        assert_eq!(staking_rows[4].category, HistoryCategory::Reward);
+        assert_eq!(staking_rows[4].counterparty, Some(validator.to_string()));
+        assert_eq!(staking_rows[4].transaction_id, None);
+        // Ensure the reward matches the bonded balance of the sender in the original ledger.
+        let (_, bonded) = StakingReplay::new(ledger.clone(), sender_address).bonded().unwrap().unwrap();
+        assert!(staking_rows[4].amount > 0);
+        assert_eq!(staking_rows[4].amount, bonded - bonded_at_genesis);
+
+        // Ensure the history of the receiver lists the public transfer, and the record only with the view key.
+        let mut scanner = HistoryScanner::new(receiver_address, None).unwrap();
+        let rows = scan_history(ledger.vm().block_store(), &mut scanner, NO_STAKING, 1, 1, |_| {}).unwrap();
+        let events = rows.iter().map(|row| (row.category, row.amount)).collect::<Vec<_>>();
+        assert_eq!(events, [(HistoryCategory::PublicTransferIn, PUBLIC_AMOUNT)]);
+        assert_eq!(rows[0].counterparty, Some(sender_address.to_string()));
+
+        let view_key = ViewKey::try_from(&receiver).unwrap();
+        let mut scanner = HistoryScanner::new(receiver_address, Some(view_key)).unwrap();
+        let rows = scan_history(ledger.vm().block_store(), &mut scanner, NO_STAKING, 1, 1, |_| {}).unwrap();
+        let events = rows.iter().map(|row| (row.category, row.amount)).collect::<Vec<_>>();
+        assert_eq!(events, [
+            (HistoryCategory::PublicTransferIn, PUBLIC_AMOUNT),
+            (HistoryCategory::PrivateTransferIn, PRIVATE_AMOUNT)
+        ]);
+
+        // Ensure the history is written in both formats.
+        let path = std::env::temp_dir().join(format!("snarkos-account-history-{}", rand::random::<u64>()));
+        let csv_path = path.with_extension("csv");
+        write_history(&rows, HistoryFormat::Csv, &csv_path).unwrap();
+        let csv = std::fs::read_to_string(&csv_path).unwrap();
+        assert_eq!(
+            csv.lines().nth(1),
+            Some(
+                format!(
+                    "1,{},{},public_transfer_in,{PUBLIC_AMOUNT},{sender_address}",
+                    block.timestamp(),
+                    transaction_ids[0]
+                )
+                .as_str()
+            )
+        );
+        let json_path = path.with_extension("json");
+        write_history(&rows, HistoryFormat::Json, &json_path).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&json_path).unwrap()).unwrap();
+        assert_eq!(json[1]["category"], "private_transfer_in");
+        assert_eq!(json[1]["amount"], PRIVATE_AMOUNT);
+
+        std::fs::remove_file(csv_path).unwrap();
+        std::fs::remove_file(json_path).unwrap();
+    }
+}
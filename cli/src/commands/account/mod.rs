@@ -13,6 +13,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod history;
+pub use history::*;
+
 use snarkvm::console::{
     account::{Address, PrivateKey, Signature},
     network::{CanaryV0, MainnetV0, Network, TestnetV0},
@@ -87,6 +90,8 @@ pub enum Account {
         #[clap(short = 'r', long)]
         raw: bool,
     },
+    /// Exports the transaction history of an account from a local ledger
+    History(History),
 }
 
 /// Parse a raw Aleo input into fields
@@ -150,6 +155,7 @@ impl Account {
                     unknown_id => bail!("Unknown network ID ({unknown_id})"),
                 }
             }
+            Self::History(history) => history.parse(),
         }
     }
 
@@ -0,0 +1,35 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod verify_certificates;
+pub use verify_certificates::*;
+
+use anyhow::Result;
+use clap::Parser;
+
+/// Commands to inspect and validate the BFT data.
+#[derive(Debug, Parser)]
+pub enum BftCommand {
+    /// Verify that a chain of batch certificates is internally consistent.
+    VerifyCertificates(VerifyCertificates),
+}
+
+impl BftCommand {
+    pub fn parse(self) -> Result<String> {
+        match self {
+            Self::VerifyCertificates(verify) => verify.parse(),
+        }
+    }
+}
@@ -0,0 +1,171 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkos_node::bft::{
+    helpers::{ChainReport, verify_certificate_chain},
+    ledger_service::committee_lookback_round,
+};
+use snarkvm::{
+    console::network::{CanaryV0, MainnetV0, Network, TestnetV0},
+    ledger::{
+        authority::Authority,
+        block::Block,
+        committee::Committee,
+        narwhal::{BatchCertificate, Subdag},
+        store::{ConsensusStore, helpers::rocksdb::ConsensusDB},
+    },
+};
+
+use aleo_std::StorageMode;
+
+use anyhow::{Result, anyhow, bail, ensure};
+use clap::Parser;
+use colored::Colorize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::{fmt::Write as _, path::PathBuf};
+
+/// Verifies that a chain of batch certificates is internally consistent, without a node.
+///
+/// The signatures and the quorum of each certificate are checked against the committee in charge of its round,
+/// and the previous certificate references are checked across the rounds of the chain. With a ledger, a previous
+/// certificate committed in a block preceding the input is looked up in the ledger, instead of being reported.
+#[derive(Debug, Parser)]
+pub struct VerifyCertificates {
+    /// Specify the network of the certificates.
+    #[clap(default_value = "0", long = "network")]
+    pub network: u16,
+    /// Specify the path to the certificates, as a JSON array of blocks, authorities, subdags, or batch certificates.
+    #[clap(long = "input")]
+    pub input: PathBuf,
+    /// Specify the path to the committees in charge of the certificates, as a JSON committee or array of committees.
+    #[clap(long = "committee")]
+    pub committee: PathBuf,
+    /// Specify the path to a ledger holding the blocks that precede the certificates, to look up their parents.
+    #[clap(long = "ledger")]
+    pub ledger: Option<PathBuf>,
+    /// Specify the path to write the report to, as JSON.
+    #[clap(long = "report")]
+    pub report: Option<PathBuf>,
+}
+
+impl VerifyCertificates {
+    /// Verifies the certificates, and writes the report if requested.
+    pub fn parse(self) -> Result<String> {
+        let report = match self.network {
+            MainnetV0::ID => self.verify::<MainnetV0>()?,
+            TestnetV0::ID => self.verify::<TestnetV0>()?,
+            CanaryV0::ID => self.verify::<CanaryV0>()?,
+            unknown_id => bail!("Unknown network ID ({unknown_id})"),
+        };
+
+        // Write the report, even if violations were found.
+        if let Some(path) = &self.report {
+            std::fs::write(path, serde_json::to_string_pretty(&report)?)?;
+        }
+
+        let rounds = match (report.first_round, report.last_round) {
+            (Some(first_round), Some(last_round)) => format!("(rounds {first_round} to {last_round})").dimmed(),
+            _ => "(no rounds)".dimmed(),
+        };
+        if !report.is_valid() {
+            let mut violations = String::new();
+            for violation in &report.violations {
+                let _ = write!(violations, "\n  {violation}");
+            }
+            bail!(
+                "❌ Found {} violations in {} certificates {rounds}{violations}",
+                report.violations.len(),
+                report.num_certificates
+            );
+        }
+        Ok(format!("✅ Verified {} certificates with no violations {rounds}", report.num_certificates))
+    }
+
+    /// Reads the certificates and the committees, and verifies the certificates.
+    fn verify<N: Network>(&self) -> Result<ChainReport> {
+        let certificates = read_certificates::<N>(&read_entries(&self.input)?)?;
+        let committees = read_entries(&self.committee)?
+            .into_iter()
+            .map(serde_json::from_value::<Committee<N>>)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|error| anyhow!("Failed to parse the committees - {error}"))?;
+        let committee_provider = |round| committee_lookback(&committees, round);
+        match &self.ledger {
+            Some(path) => {
+                ensure!(path.exists(), "The ledger at {} does not exist", path.display());
+                let store = ConsensusStore::<N, ConsensusDB<N>>::open(StorageMode::Custom(path.clone()))
+                    .map_err(|error| anyhow!("Failed to open the ledger (is a node running on it?) - {error}"))?;
+                let ledger_certificate = |id: &_| store.block_store().get_batch_certificate(id);
+                Ok(verify_certificate_chain(&certificates, committee_provider, ledger_certificate))
+            }
+            None => Ok(verify_certificate_chain(&certificates, committee_provider, |_| Ok(None))),
+        }
+    }
+}
+
+/// Returns the entries of the JSON file at the given path, which holds either an array or a single entry.
+fn read_entries(path: &PathBuf) -> Result<Vec<Value>> {
+    let contents =
+        std::fs::read_to_string(path).map_err(|error| anyhow!("Failed to read {} - {error}", path.display()))?;
+    match serde_json::from_str(&contents)? {
+        Value::Array(entries) => Ok(entries),
+        entry => Ok(vec![entry]),
+    }
+}
+
+/// Returns the certificates in the given entries, each being a block, an authority, a subdag, or a certificate.
+fn read_certificates<N: Network>(entries: &[Value]) -> Result<Vec<BatchCertificate<N>>> {
+    // Parses the entry as the given type.
+    fn parse<T: DeserializeOwned>(entry: &Value) -> Option<T> {
+        serde_json::from_value(entry.clone()).ok()
+    }
+    // Returns the certificates of the given subdag.
+    fn subdag_certificates<N: Network>(subdag: &Subdag<N>) -> impl Iterator<Item = BatchCertificate<N>> + '_ {
+        subdag.values().flatten().cloned()
+    }
+
+    let mut certificates = Vec::new();
+    for (index, entry) in entries.iter().enumerate() {
+        if let Some(block) = parse::<Block<N>>(entry) {
+            if let Authority::Quorum(subdag) = block.authority() {
+                certificates.extend(subdag_certificates(subdag));
+            }
+        } else if let Some(authority) = parse::<Authority<N>>(entry) {
+            if let Authority::Quorum(subdag) = &authority {
+                certificates.extend(subdag_certificates(subdag));
+            }
+        } else if let Some(subdag) = parse::<Subdag<N>>(entry) {
+            certificates.extend(subdag_certificates(&subdag));
+        } else if let Some(certificate) = parse::<BatchCertificate<N>>(entry) {
+            certificates.push(certificate);
+        } else {
+            bail!("Entry {index} of the input is not a block, an authority, a subdag, or a batch certificate");
+        }
+    }
+    Ok(certificates)
+}
+
+/// Returns the committee in charge of the given round, i.e. the latest committee starting at or before
+/// its committee lookback round.
+fn committee_lookback<N: Network>(committees: &[Committee<N>], round: u64) -> Result<Committee<N>> {
+    let lookback_round = committee_lookback_round::<N>(round);
+    committees
+        .iter()
+        .filter(|committee| committee.starting_round() <= lookback_round)
+        .max_by_key(|committee| committee.starting_round())
+        .cloned()
+        .ok_or_else(|| anyhow!("No committee starts at or before round {lookback_round}"))
+}
@@ -13,10 +13,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use snarkos_node::bft::helpers::proposal_cache_path;
+use crate::helpers::{StorageLock, canonicalize_storage_path};
+use snarkos_node::{
+    bft::helpers::{dev_storage_mode, proposal_cache_path},
+    sync::sync_journal_path,
+};
 
 use aleo_std::StorageMode;
-use anyhow::{Result, bail};
+use anyhow::{Result, anyhow, bail};
 use clap::Parser;
 use colored::Colorize;
 use std::path::PathBuf;
@@ -45,11 +49,21 @@ impl Clean {
                 bail!("Failed to remove the current proposal cache file at {}: {err}", proposal_cache_path.display());
             }
         }
+        // Determine the storage mode of the ledger.
+        let mode = match self.path {
+            Some(path) => StorageMode::Custom(canonicalize_storage_path(&path)?),
+            None => dev_storage_mode(self.dev),
+        };
         // Remove the specified ledger from storage.
-        Self::remove_ledger(self.network, match self.path {
-            Some(path) => StorageMode::Custom(path),
-            None => StorageMode::from(self.dev),
-        })
+        let message = Self::remove_ledger(self.network, mode.clone())?;
+        // Remove the sync journal of the ledger, if it exists.
+        let sync_journal_path = sync_journal_path(self.network, &mode);
+        if sync_journal_path.exists() {
+            if let Err(err) = std::fs::remove_dir_all(&sync_journal_path) {
+                bail!("Failed to remove the sync journal at {}: {err}", sync_journal_path.display());
+            }
+        }
+        Ok(message)
     }
 
     /// Removes the specified ledger from storage.
@@ -62,6 +76,10 @@ impl Clean {
 
         // Check if the path to the ledger exists in storage.
         if path.exists() {
+            // Ensure the ledger is not opened by a running node.
+            let lock = StorageLock::acquire(&path)
+                .map_err(|error| anyhow!("Failed to clean the snarkOS node storage {path_string}\n{error}"))?;
+            drop(lock);
             // Remove the ledger files from storage.
             match std::fs::remove_dir_all(&path) {
                 Ok(_) => Ok(format!("✅ Cleaned the snarkOS node storage {path_string}")),
@@ -74,3 +92,35 @@ impl Clean {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(target_family = "unix")]
+    #[test]
+    fn test_clean_symlinked_storage() {
+        use crate::helpers::prepare_storage;
+
+        let dir = std::env::temp_dir().join(format!("snarkos-clean-{}", rand::random::<u64>()));
+        let ledger_dir = dir.join("disk").join("ledger");
+        std::fs::create_dir_all(&ledger_dir).unwrap();
+        std::os::unix::fs::symlink(dir.join("disk"), dir.join("link")).unwrap();
+        let clean = || {
+            let path = canonicalize_storage_path(&dir.join("link").join("ledger")).unwrap();
+            Clean::remove_ledger(1, StorageMode::Custom(path))
+        };
+
+        // Ensure a ledger opened by a node is not cleaned.
+        let lock = prepare_storage(1, &StorageMode::Custom(ledger_dir.clone())).unwrap();
+        assert!(clean().unwrap_err().to_string().contains("already opened by process"));
+        assert!(ledger_dir.exists());
+
+        // Ensure the ledger behind the symlink is cleaned, once it is released.
+        drop(lock);
+        clean().unwrap();
+        assert!(!ledger_dir.exists());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
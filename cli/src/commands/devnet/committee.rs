@@ -0,0 +1,238 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    commands::{BondedBalances, DEVELOPMENT_MODE_NUM_GENESIS_COMMITTEE_MEMBERS, DEVELOPMENT_MODE_RNG_SEED},
+    helpers::{StorageLock, canonicalize_storage_path},
+};
+use snarkvm::{
+    console::{
+        account::{Address, PrivateKey},
+        network::{CanaryV0, MainnetV0, Network, TestnetV0},
+    },
+    ledger::{
+        committee::{Committee, MIN_VALIDATOR_STAKE},
+        store::{ConsensusStore, helpers::rocksdb::ConsensusDB},
+    },
+};
+
+use aleo_std::StorageMode;
+use anyhow::{Result, anyhow, bail, ensure};
+use clap::Parser;
+use colored::Colorize;
+use indexmap::IndexMap;
+use rand::SeedableRng;
+use rand_chacha::ChaChaRng;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Writes the committee of a development network, mirroring the committee of a network at a given height.
+///
+/// Each member of the source committee is mapped to a development validator, in descending order of stake,
+/// and the mapping is recorded in the committee file alongside the bonded balances of the development network.
+#[derive(Debug, Parser)]
+pub struct CommitteeFromSnapshot {
+    /// Specify the network of the source committee.
+    #[clap(default_value = "0", long = "network")]
+    pub network: u16,
+    /// Specify the source of the committee, either the path to a synced ledger or the URL of a node's REST API.
+    #[clap(long = "source")]
+    pub source: String,
+    /// Specify the block height of the committee.
+    #[clap(long = "height")]
+    pub height: u32,
+    /// Specify the path to the committee file, to pass to 'snarkos start --dev-committee'.
+    #[clap(default_value = "committee.json", long = "output")]
+    pub output: PathBuf,
+}
+
+impl CommitteeFromSnapshot {
+    /// Snapshots the committee, and writes the committee file.
+    pub fn parse(self) -> Result<String> {
+        let spec = match self.network {
+            MainnetV0::ID => self.snapshot::<MainnetV0>()?,
+            TestnetV0::ID => self.snapshot::<TestnetV0>()?,
+            CanaryV0::ID => self.snapshot::<CanaryV0>()?,
+            unknown_id => bail!("Unknown network ID ({unknown_id})"),
+        };
+        spec.store(&self.output)?;
+
+        let output_path = format!("(in \"{}\")", self.output.display()).dimmed();
+        Ok(format!(
+            "✅ Wrote the committee of {} members at height {} {output_path}",
+            spec.mapping.len(),
+            spec.height
+        ))
+    }
+
+    /// Returns the committee file of the source committee.
+    fn snapshot<N: Network>(&self) -> Result<CommitteeSpec> {
+        let committee = match self.source.starts_with("http://") || self.source.starts_with("https://") {
+            true => fetch_committee::<N>(&self.source, self.height)?,
+            false => read_committee::<N>(Path::new(&self.source), self.height)?,
+        };
+        CommitteeSpec::new(self.height, &committee)
+    }
+}
+
+/// Reads the committee at the given height from the ledger at the given path.
+fn read_committee<N: Network>(path: &Path, height: u32) -> Result<Committee<N>> {
+    let path = canonicalize_storage_path(path)?;
+    ensure!(path.exists(), "The ledger at {} does not exist", path.display());
+    // Ensure the ledger is not opened by a running node.
+    let _lock = StorageLock::acquire(&path)?;
+    let store = ConsensusStore::<N, ConsensusDB<N>>::open(StorageMode::Custom(path.clone()))?;
+    store
+        .finalize_store()
+        .committee_store()
+        .get_committee(height)?
+        .ok_or_else(|| anyhow!("The ledger at {} has no committee at height {height}", path.display()))
+}
+
+/// Fetches the committee at the given height from the REST API at the given URL.
+fn fetch_committee<N: Network>(endpoint: &str, height: u32) -> Result<Committee<N>> {
+    // Get the network being used.
+    let network = match N::ID {
+        MainnetV0::ID => "mainnet",
+        TestnetV0::ID => "testnet",
+        CanaryV0::ID => "canary",
+        unknown_id => bail!("Unknown network ID ({unknown_id})"),
+    };
+
+    // Send a request to the node.
+    let endpoint = endpoint.trim_end_matches('/');
+    let response = ureq::get(&format!("{endpoint}/{network}/committee/{height}")).call();
+
+    // Deserialize the committee.
+    match response {
+        Ok(response) => response.into_json().map_err(|err| err.into()),
+        Err(ureq::Error::Status(_status, response)) => {
+            bail!("Failed to fetch the committee at height {height}: {response:?}")
+        }
+        Err(err) => bail!(err),
+    }
+}
+
+/// The mapping of a member of the source committee to a development validator.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct CommitteeMapping {
+    /// The address of the member in the source committee.
+    pub source_address: String,
+    /// The index of the development validator, as in '--dev <index>'.
+    pub dev_index: u16,
+    /// The address of the development validator.
+    pub dev_address: String,
+    /// The stake of the member in the source committee, in microcredits.
+    pub source_stake: u64,
+    /// The stake of the development validator, in microcredits.
+    pub stake: u64,
+}
+
+/// The committee file of a development network, consumed by 'snarkos start --dev-committee'.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct CommitteeSpec {
+    /// The network ID of the source committee.
+    pub network: u16,
+    /// The block height of the source committee.
+    pub height: u32,
+    /// The bonded balances of the development network.
+    pub bonded_balances: BondedBalances,
+    /// The mapping of the source committee members to the development validators, in descending order of stake.
+    pub mapping: Vec<CommitteeMapping>,
+}
+
+impl CommitteeSpec {
+    /// Initializes the committee file of a development network mirroring the given committee.
+    ///
+    /// The development validators are the first development accounts, which are assigned the source members
+    /// in descending order of stake (ties are broken by address), so that the mapping is deterministic.
+    ///
+    /// Note: The delegated stake of a member is bonded as its own stake, as the genesis block of a development
+    /// network only bonds its validators. If the source committee holds more than half of the starting supply,
+    /// the stakes are scaled down proportionally (to at least `MIN_VALIDATOR_STAKE`), to leave the development
+    /// validators a public balance.
+    pub fn new<N: Network>(height: u32, committee: &Committee<N>) -> Result<Self> {
+        let num_members = committee.members().len();
+        ensure!(
+            num_members >= DEVELOPMENT_MODE_NUM_GENESIS_COMMITTEE_MEMBERS as usize,
+            "The committee at height {height} has {num_members} members, but a development network needs at least \
+             {DEVELOPMENT_MODE_NUM_GENESIS_COMMITTEE_MEMBERS}"
+        );
+        let num_members = u16::try_from(num_members)?;
+
+        // Sort the members in descending order of stake.
+        let mut members =
+            committee.members().iter().map(|(address, (stake, _, _))| (address.to_string(), *stake)).collect::<Vec<_>>();
+        members.sort_by(|(a, stake_a), (b, stake_b)| stake_b.cmp(stake_a).then_with(|| a.cmp(b)));
+
+        // Scale the stakes down, if the committee holds more than half of the starting supply.
+        let total_stake = committee.total_stake();
+        let max_total_stake = N::STARTING_SUPPLY / 2;
+        let scale = |stake: u64| -> u64 {
+            match total_stake > max_total_stake {
+                true => {
+                    let scaled = stake as u128 * max_total_stake as u128 / total_stake as u128;
+                    (scaled as u64).max(MIN_VALIDATOR_STAKE)
+                }
+                false => stake,
+            }
+        };
+
+        // Initialize the development addresses, as in 'snarkos start --dev'.
+        let mut rng = ChaChaRng::seed_from_u64(DEVELOPMENT_MODE_RNG_SEED);
+        let dev_addresses = (0..num_members)
+            .map(|_| Address::<N>::try_from(PrivateKey::<N>::new(&mut rng)?))
+            .collect::<Result<Vec<_>>>()?;
+
+        // Map the members to the development validators.
+        let mapping = members
+            .into_iter()
+            .zip(dev_addresses)
+            .enumerate()
+            .map(|(dev_index, ((source_address, source_stake), dev_address))| CommitteeMapping {
+                source_address,
+                dev_index: dev_index as u16,
+                dev_address: dev_address.to_string(),
+                source_stake,
+                stake: scale(source_stake),
+            })
+            .collect::<Vec<_>>();
+
+        // Construct the bonded balances.
+        // Note: The withdrawal address is set to the staker address.
+        let bonded_balances = mapping
+            .iter()
+            .map(|member| {
+                (member.dev_address.clone(), (member.dev_address.clone(), member.dev_address.clone(), member.stake))
+            })
+            .collect::<IndexMap<_, _>>();
+
+        Ok(Self { network: N::ID, height, bonded_balances: BondedBalances(bonded_balances), mapping })
+    }
+
+    /// Loads the committee file at the given path.
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|error| anyhow!("Unable to read the committee file at {} - {error}", path.display()))?;
+        serde_json::from_str(&contents)
+            .map_err(|error| anyhow!("Malformed committee file at {} - {error}", path.display()))
+    }
+
+    /// Writes the committee file to the given path.
+    pub fn store(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)
+            .map_err(|error| anyhow!("Unable to write the committee file at {} - {error}", path.display()))
+    }
+}
@@ -0,0 +1,41 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod replay;
+pub use replay::*;
+
+mod rewrite;
+pub use rewrite::*;
+
+use anyhow::Result;
+use clap::Parser;
+
+/// Commands to inspect, validate, and maintain the ledger.
+#[derive(Debug, Parser)]
+pub enum LedgerCommand {
+    /// Replay a range of historical blocks through the consensus checks of a fresh ledger.
+    Replay(Replay),
+    /// Rewrite the ledger into a fresh, compacted database in another directory.
+    Rewrite(Rewrite),
+}
+
+impl LedgerCommand {
+    pub fn parse(self) -> Result<String> {
+        match self {
+            Self::Replay(replay) => replay.parse(),
+            Self::Rewrite(rewrite) => rewrite.parse(),
+        }
+    }
+}
@@ -0,0 +1,416 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{
+    console::network::{CanaryV0, MainnetV0, Network, TestnetV0},
+    ledger::{
+        Ledger,
+        block::Block,
+        store::{BlockStorage, BlockStore, ConsensusStorage, ConsensusStore, helpers::rocksdb::ConsensusDB},
+    },
+    prelude::FromBytes,
+};
+
+use aleo_std::StorageMode;
+use anyhow::{Result, anyhow, bail, ensure};
+use clap::Parser;
+use colored::Colorize;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::{
+    fmt::Write as _,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Instant,
+};
+
+/// Replays a range of historical blocks through the consensus checks of a fresh ledger.
+///
+/// As the fresh ledger starts from genesis, the blocks preceding `--from` are also validated and applied,
+/// but only the blocks in the given range are reported.
+#[derive(Debug, Parser)]
+pub struct Replay {
+    /// Specify the network of the blocks to replay.
+    #[clap(default_value = "0", long = "network")]
+    pub network: u16,
+    /// Specify the first block height to report (inclusive).
+    #[clap(long = "from")]
+    pub from: u32,
+    /// Specify the last block height to report (inclusive).
+    #[clap(long = "to")]
+    pub to: u32,
+    /// Specify the source of the blocks, either the path to a synced ledger or the URL of a block archive (CDN).
+    #[clap(long = "source")]
+    pub source: String,
+    /// Specify the path to the report, which is written as JSON if the extension is `.json`, and as CSV otherwise.
+    #[clap(default_value = "replay-report.csv", long = "report")]
+    pub report: PathBuf,
+}
+
+impl Replay {
+    /// Replays the blocks, and writes the report.
+    pub fn parse(self) -> Result<String> {
+        ensure!(self.from > 0, "The '--from' height must be greater than 0 (the genesis block is not replayed)");
+        ensure!(self.from <= self.to, "The '--from' height must not be greater than the '--to' height");
+
+        // Replay the blocks.
+        let report = match self.network {
+            MainnetV0::ID => self.replay::<MainnetV0>()?,
+            TestnetV0::ID => self.replay::<TestnetV0>()?,
+            CanaryV0::ID => self.replay::<CanaryV0>()?,
+            unknown_id => bail!("Unknown network ID ({unknown_id})"),
+        };
+
+        // Write the report, even if a block was rejected.
+        report.write(&self.report)?;
+        let report_path = format!("(in \"{}\")", self.report.display()).dimmed();
+
+        // Fail loudly if any block was rejected, as it indicates a consensus-rule regression.
+        if let Some(rejected) = report.blocks.iter().find(|record| record.rejection.is_some()) {
+            bail!(
+                "❌ Block {} was rejected during the replay {report_path}\n{}",
+                rejected.height,
+                rejected.rejection.as_deref().unwrap_or_default().dimmed()
+            );
+        }
+        Ok(format!("✅ Replayed {} blocks with no rejections {report_path}", report.num_blocks))
+    }
+
+    /// Replays the blocks from the source, into a fresh ledger in a temporary directory.
+    fn replay<N: Network>(&self) -> Result<ReplayReport> {
+        // Initialize a temporary directory for the fresh ledger.
+        let storage_path = std::env::temp_dir().join(format!("snarkos-replay-{}", rand::random::<u64>()));
+        let storage_mode = StorageMode::Custom(storage_path.clone());
+
+        let report = match self.source.starts_with("http://") || self.source.starts_with("https://") {
+            true => self.replay_from_cdn::<N>(storage_mode),
+            false => {
+                let store = ConsensusStore::<N, ConsensusDB<N>>::open(StorageMode::Custom(self.source.clone().into()))?;
+                replay_from_store::<N, _, ConsensusDB<N>>(store.block_store(), storage_mode, self.from, self.to)
+            }
+        };
+
+        // Remove the temporary ledger.
+        if storage_path.exists() {
+            if let Err(error) = std::fs::remove_dir_all(&storage_path) {
+                eprintln!("Failed to remove the temporary ledger at {} - {error}", storage_path.display());
+            }
+        }
+        report
+    }
+
+    /// Replays the blocks from a block archive (CDN), into a fresh ledger.
+    fn replay_from_cdn<N: Network>(&self, storage_mode: StorageMode) -> Result<ReplayReport> {
+        // The block archive does not serve the genesis block, so the network's genesis block is used.
+        let genesis = Block::from_bytes_le(N::genesis_bytes())?;
+        let ledger = Ledger::<N, ConsensusDB<N>>::load(genesis, storage_mode)?;
+        let replayer = Arc::new(Mutex::new(Replayer::new(ledger, self.from)));
+
+        // Stream the blocks from the block archive into the replayer.
+        let replayer_ = replayer.clone();
+        let (from, to) = (self.from, self.to);
+        let result = tokio::runtime::Runtime::new()?.block_on(snarkos_node_cdn::load_blocks(
+            &self.source,
+            1,
+            Some(to + 1),
+            Default::default(),
+            move |block: Block<N>| replayer_.lock().replay(&block),
+        ));
+
+        let report = replayer.lock().report(from, to);
+        match result {
+            // If a block was rejected, return the report, so that the rejection is recorded.
+            Err(_) if report.num_rejected > 0 => Ok(report),
+            Err((height, error)) => Err(anyhow!("Failed to load the blocks after height {height} - {error}")),
+            Ok(_) => Ok(report),
+        }
+    }
+}
+
+/// Replays the blocks from the given block store, into a fresh ledger with the given storage mode.
+/// The blocks are read one at a time, so that memory usage is bounded regardless of the range.
+fn replay_from_store<N: Network, B: BlockStorage<N>, C: ConsensusStorage<N>>(
+    source: &BlockStore<N, B>,
+    storage_mode: StorageMode,
+    from: u32,
+    to: u32,
+) -> Result<ReplayReport> {
+    // Retrieves the block at the given height from the source.
+    let get_block = |height: u32| -> Result<Block<N>> {
+        let hash = source.get_block_hash(height)?.ok_or_else(|| anyhow!("Missing block {height} in the source"))?;
+        source.get_block(&hash)?.ok_or_else(|| anyhow!("Missing block {height} in the source"))
+    };
+
+    // Initialize a fresh ledger from the source's genesis block.
+    let ledger = Ledger::<N, C>::load(get_block(0)?, storage_mode)?;
+    let mut replayer = Replayer::new(ledger, from);
+
+    // Replay the blocks, stopping at the first rejection.
+    for height in 1..=to {
+        if replayer.replay(&get_block(height)?).is_err() {
+            break;
+        }
+    }
+    Ok(replayer.report(from, to))
+}
+
+/// The record of a replayed block.
+#[derive(Clone, Debug, Serialize)]
+pub struct ReplayRecord {
+    /// The block height.
+    pub height: u32,
+    /// The block hash.
+    pub hash: String,
+    /// The number of transactions in the block.
+    pub num_transactions: usize,
+    /// The time spent in `check_next_block`, in microseconds.
+    pub validation_us: u128,
+    /// The time spent in `advance_to_next_block`, in microseconds.
+    pub storage_us: u128,
+    /// The reason the block was rejected, if it was rejected.
+    pub rejection: Option<String>,
+}
+
+/// The report of a replay.
+#[derive(Clone, Debug, Serialize)]
+pub struct ReplayReport {
+    /// The first reported block height.
+    pub from: u32,
+    /// The last reported block height.
+    pub to: u32,
+    /// The number of reported blocks.
+    pub num_blocks: usize,
+    /// The number of rejected blocks.
+    pub num_rejected: usize,
+    /// The total time spent in `check_next_block` for the reported blocks, in microseconds.
+    pub total_validation_us: u128,
+    /// The total time spent in `advance_to_next_block` for the reported blocks, in microseconds.
+    pub total_storage_us: u128,
+    /// The records of the reported blocks.
+    pub blocks: Vec<ReplayRecord>,
+}
+
+impl ReplayReport {
+    /// Writes the report to the given path, as JSON if the extension is `.json`, and as CSV otherwise.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let contents = match path.extension().is_some_and(|extension| extension == "json") {
+            true => serde_json::to_string_pretty(self)?,
+            false => self.to_csv(),
+        };
+        Ok(std::fs::write(path, contents)?)
+    }
+
+    /// Returns the block records in CSV format.
+    fn to_csv(&self) -> String {
+        let mut csv = "height,hash,num_transactions,validation_us,storage_us,rejection\n".to_string();
+        for record in &self.blocks {
+            // Quote the rejection reason, as it may contain commas.
+            let rejection =
+                record.rejection.as_ref().map(|r| format!("\"{}\"", r.replace('"', "\"\""))).unwrap_or_default();
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{},{rejection}",
+                record.height, record.hash, record.num_transactions, record.validation_us, record.storage_us
+            );
+        }
+        csv
+    }
+}
+
+/// A replayer, which validates and applies blocks to a fresh ledger while recording their timings.
+struct Replayer<N: Network, C: ConsensusStorage<N>> {
+    /// The fresh ledger.
+    ledger: Ledger<N, C>,
+    /// The first block height to record.
+    from: u32,
+    /// The records of the replayed blocks.
+    records: Vec<ReplayRecord>,
+}
+
+impl<N: Network, C: ConsensusStorage<N>> Replayer<N, C> {
+    /// Initializes a new replayer.
+    fn new(ledger: Ledger<N, C>, from: u32) -> Self {
+        Self { ledger, from, records: Default::default() }
+    }
+
+    /// Validates and applies the given block to the ledger, recording its timings if it is within the range.
+    /// Rejected blocks are always recorded.
+    fn replay(&mut self, block: &Block<N>) -> Result<()> {
+        // Skip any blocks that were already applied.
+        if block.height() <= self.ledger.latest_height() {
+            return Ok(());
+        }
+        ensure!(
+            block.height() == self.ledger.latest_height() + 1,
+            "Expected block {}, found block {}",
+            self.ledger.latest_height() + 1,
+            block.height()
+        );
+
+        let mut record = ReplayRecord {
+            height: block.height(),
+            hash: block.hash().to_string(),
+            num_transactions: block.transactions().len(),
+            validation_us: 0,
+            storage_us: 0,
+            rejection: None,
+        };
+
+        // Validate the block.
+        let timer = Instant::now();
+        let result = self.ledger.check_next_block(block, &mut rand::thread_rng());
+        record.validation_us = timer.elapsed().as_micros();
+        if let Err(error) = result {
+            record.rejection = Some(error.to_string());
+            self.records.push(record);
+            bail!("Block {} was rejected - {error}", block.height());
+        }
+
+        // Apply the block.
+        let timer = Instant::now();
+        let result = self.ledger.advance_to_next_block(block);
+        record.storage_us = timer.elapsed().as_micros();
+        if let Err(error) = result {
+            record.rejection = Some(format!("Failed to advance to the block - {error}"));
+            self.records.push(record);
+            bail!("Failed to advance to block {} - {error}", block.height());
+        }
+
+        if block.height() >= self.from {
+            self.records.push(record);
+        }
+        Ok(())
+    }
+
+    /// Returns the report of the replayed blocks.
+    fn report(&self, from: u32, to: u32) -> ReplayReport {
+        let blocks: Vec<_> = self.records.iter().filter(|r| r.height <= to || r.rejection.is_some()).cloned().collect();
+        let reported = blocks.iter().filter(|r| r.height >= from && r.height <= to);
+        ReplayReport {
+            from,
+            to,
+            num_blocks: reported.clone().count(),
+            num_rejected: blocks.iter().filter(|r| r.rejection.is_some()).count(),
+            total_validation_us: reported.clone().map(|r| r.validation_us).sum(),
+            total_storage_us: reported.map(|r| r.storage_us).sum(),
+            blocks,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkos_node_bft_ledger_service::test_helpers::sample_ledger;
+    use snarkvm::ledger::store::helpers::memory::ConsensusMemory;
+
+    use rand::SeedableRng;
+    use rand_chacha::ChaChaRng;
+
+    type CurrentNetwork = MainnetV0;
+
+    /// The number of blocks in the sample devnet ledger.
+    const NUM_BLOCKS: u32 = 200;
+
+    /// Returns a sample devnet ledger with `NUM_BLOCKS` blocks after genesis.
+    fn sample_devnet_ledger() -> Ledger<CurrentNetwork, ConsensusMemory<CurrentNetwork>> {
+        sample_devnet_ledger_with_seed(1234567890u64, NUM_BLOCKS)
+    }
+
+    /// Returns a sample devnet ledger from the given seed, with the given number of blocks after genesis.
+    fn sample_devnet_ledger_with_seed(
+        seed: u64,
+        num_blocks: u32,
+    ) -> Ledger<CurrentNetwork, ConsensusMemory<CurrentNetwork>> {
+        sample_ledger(num_blocks, &mut ChaChaRng::seed_from_u64(seed)).1
+    }
+
+    #[test]
+    fn test_replay_devnet_blocks() {
+        let source = sample_devnet_ledger();
+        let (from, to) = (NUM_BLOCKS / 2, NUM_BLOCKS);
+
+        // Replay the blocks into a fresh ledger.
+        let report = replay_from_store::<CurrentNetwork, _, ConsensusMemory<CurrentNetwork>>(
+            source.vm().block_store(),
+            StorageMode::Production,
+            from,
+            to,
+        )
+        .unwrap();
+
+        // Ensure there are no rejections, and the reported heights are monotonic.
+        assert_eq!(report.num_rejected, 0);
+        assert_eq!(report.num_blocks, (to - from + 1) as usize);
+        assert_eq!(report.blocks.iter().map(|r| r.height).collect::<Vec<_>>(), (from..=to).collect::<Vec<_>>());
+        assert!(report.blocks.iter().all(|r| r.hash == source.get_hash(r.height).unwrap().to_string()));
+
+        // Ensure the report is written in both formats.
+        let path = std::env::temp_dir().join(format!("snarkos-replay-report-{}", rand::random::<u64>()));
+        let csv_path = path.with_extension("csv");
+        report.write(&csv_path).unwrap();
+        let csv = std::fs::read_to_string(&csv_path).unwrap();
+        assert_eq!(csv.lines().count(), report.num_blocks + 1);
+        let json_path = path.with_extension("json");
+        report.write(&json_path).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&json_path).unwrap()).unwrap();
+        assert_eq!(json["num_rejected"], 0);
+
+        std::fs::remove_file(csv_path).unwrap();
+        std::fs::remove_file(json_path).unwrap();
+    }
+
+    #[test]
+    fn test_replay_rejects_invalid_block() {
+        let source = sample_devnet_ledger_with_seed(1234567890u64, 1);
+        let genesis = source.get_block(0).unwrap();
+        let ledger =
+            Ledger::<CurrentNetwork, ConsensusMemory<CurrentNetwork>>::load(genesis, StorageMode::Production).unwrap();
+        let mut replayer = Replayer::new(ledger, 1);
+
+        // Ensure a block of another chain, which does not extend the genesis block, is rejected and recorded.
+        let other = sample_devnet_ledger_with_seed(987654321u64, 1);
+        let error = replayer.replay(&other.get_block(1).unwrap()).unwrap_err();
+        assert!(error.to_string().contains("Block 1 was rejected"));
+        let report = replayer.report(1, 1);
+        assert_eq!(report.num_rejected, 1);
+        assert!(report.blocks[0].rejection.is_some());
+        assert_eq!(report.blocks[0].hash, other.get_hash(1).unwrap().to_string());
+
+        // Ensure the block of the chain itself is still accepted afterwards.
+        replayer.replay(&source.get_block(1).unwrap()).unwrap();
+        assert_eq!(replayer.ledger.latest_height(), 1);
+    }
+
+    #[test]
+    fn test_replay_skips_applied_and_refuses_out_of_order_blocks() {
+        let source = sample_devnet_ledger();
+        let genesis = source.get_block(0).unwrap();
+        let ledger =
+            Ledger::<CurrentNetwork, ConsensusMemory<CurrentNetwork>>::load(genesis, StorageMode::Production).unwrap();
+        let mut replayer = Replayer::new(ledger, 1);
+
+        // Ensure an out-of-order block is refused.
+        replayer.replay(&source.get_block(1).unwrap()).unwrap();
+        assert!(replayer.replay(&source.get_block(3).unwrap()).is_err());
+
+        // Ensure an already-applied block is skipped, and not recorded twice.
+        replayer.replay(&source.get_block(2).unwrap()).unwrap();
+        replayer.replay(&source.get_block(1).unwrap()).unwrap();
+        let report = replayer.report(1, 2);
+        assert_eq!(report.num_rejected, 0);
+        assert_eq!(report.num_blocks, 2);
+    }
+}
@@ -0,0 +1,362 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::helpers::{StorageLock, StorageMetadata, canonicalize_storage_path};
+
+use anyhow::{Result, anyhow, bail, ensure};
+use clap::Parser;
+use rand::Rng;
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+/// The length of the prefix of every key in the ledger, i.e. the network ID and the map ID (both `u16`).
+const MAP_PREFIX_LEN: usize = 4;
+/// The size of a write batch to the destination, in bytes.
+const BATCH_SIZE_IN_BYTES: usize = 64 * 1024 * 1024;
+
+/// Rewrites a ledger into a fresh, compacted database in another directory, to reclaim the space
+/// of overwritten and deleted entries.
+///
+/// The source ledger is opened read-only, and is never modified.
+#[derive(Debug, Parser)]
+pub struct Rewrite {
+    /// Specify the path to the ledger to rewrite.
+    #[clap(long = "source")]
+    pub source: PathBuf,
+    /// Specify the path to the rewritten ledger, which must not exist or be an empty directory.
+    #[clap(long = "destination")]
+    pub destination: PathBuf,
+    /// Specify the number of entries per map whose values are verified after the rewrite.
+    #[clap(default_value = "16", long = "samples")]
+    pub num_samples: usize,
+}
+
+/// The summary of the entries copied from a map of the source.
+#[derive(Default)]
+struct MapSummary {
+    /// The number of entries in the map.
+    num_entries: u64,
+    /// A uniform sample of the entries in the map.
+    samples: Vec<(Box<[u8]>, Box<[u8]>)>,
+}
+
+impl Rewrite {
+    /// Rewrites the ledger, and verifies the result.
+    pub fn parse(self) -> Result<String> {
+        let source = canonicalize_storage_path(&self.source)?;
+        let destination = canonicalize_storage_path(&self.destination)?;
+        ensure!(source.is_dir(), "The source ledger {} is not a directory", source.display());
+        ensure!(
+            !destination.starts_with(&source) && !source.starts_with(&destination),
+            "The destination {} must not overlap with the source ledger {}",
+            destination.display(),
+            source.display()
+        );
+        if destination.exists() {
+            ensure!(
+                destination.is_dir() && std::fs::read_dir(&destination)?.next().is_none(),
+                "The destination {} already exists and is not an empty directory",
+                destination.display()
+            );
+        }
+
+        // Lock the source, so that no node opens it during the rewrite.
+        let _lock = StorageLock::acquire_shared(&source)?;
+
+        // Note: RocksDB writes an info log even for a read-only database, so the source's is kept in a temporary
+        // directory, to leave the source untouched.
+        let log_dir = std::env::temp_dir().join(format!("snarkos-rewrite-{}", rand::random::<u64>()));
+        let result = self.rewrite(&source, &destination, &log_dir);
+        if log_dir.exists() {
+            if let Err(error) = std::fs::remove_dir_all(&log_dir) {
+                eprintln!("Failed to remove the temporary directory at {} - {error}", log_dir.display());
+            }
+        }
+
+        // Remove the partial destination on failure.
+        let (num_entries, num_maps) = match result {
+            Ok(counts) => counts,
+            Err(error) => {
+                if let Err(error) = std::fs::remove_dir_all(&destination) {
+                    eprintln!("Failed to remove the partial ledger at {} - {error}", destination.display());
+                }
+                bail!("❌ Failed to rewrite the ledger at {} - {error}", source.display());
+            }
+        };
+
+        // Report the size reduction.
+        let (source_size, destination_size) = (directory_size(&source)?, directory_size(&destination)?);
+        let change = match destination_size <= source_size {
+            true => {
+                format!("{:.1}% smaller", 100.0 * (source_size - destination_size) as f64 / source_size.max(1) as f64)
+            }
+            false => format!("{:.1}% larger", 100.0 * (destination_size - source_size) as f64 / source_size as f64),
+        };
+        Ok(format!(
+            "✅ Rewrote {num_entries} entries in {num_maps} maps, from {} ({}) to {} ({}, {change})",
+            source.display(),
+            format_size(source_size),
+            destination.display(),
+            format_size(destination_size),
+        ))
+    }
+
+    /// Copies every entry from the source into a fresh database at the destination, and verifies it.
+    /// Returns the number of entries and maps copied.
+    fn rewrite(&self, source: &Path, destination: &Path, log_dir: &Path) -> Result<(u64, usize)> {
+        let mut source_options = rocksdb::Options::default();
+        source_options.set_db_log_dir(log_dir);
+        let source_db = rocksdb::DB::open_for_read_only(&source_options, source, false)
+            .map_err(|error| anyhow!("The ledger is not in a supported storage format - {error}"))?;
+        std::fs::create_dir_all(destination)
+            .map_err(|error| anyhow!("Unable to create the directory {} - {error}", destination.display()))?;
+        let destination_db = rocksdb::DB::open(&destination_options(), destination)?;
+
+        // The destination is flushed explicitly once written, so the write-ahead log is unnecessary.
+        let mut write_options = rocksdb::WriteOptions::default();
+        write_options.disable_wal(true);
+        // Avoid evicting useful blocks from the cache, as every entry is read once.
+        let mut read_options = rocksdb::ReadOptions::default();
+        read_options.fill_cache(false);
+
+        // Copy the entries in key order, in large batches.
+        let mut rng = rand::thread_rng();
+        let mut maps = BTreeMap::<Box<[u8]>, MapSummary>::new();
+        let mut batch = rocksdb::WriteBatch::default();
+        for entry in source_db.iterator_opt(rocksdb::IteratorMode::Start, read_options) {
+            let (key, value) = entry?;
+            batch.put(&key, &value);
+            if batch.size_in_bytes() >= BATCH_SIZE_IN_BYTES {
+                destination_db.write_opt(std::mem::take(&mut batch), &write_options)?;
+            }
+
+            // Sample the entries of each map uniformly (reservoir sampling).
+            let map = maps.entry(map_prefix(&key).into()).or_default();
+            map.num_entries += 1;
+            if map.samples.len() < self.num_samples {
+                map.samples.push((key, value));
+            } else if let Some(sample) = map.samples.get_mut(rng.gen_range(0..map.num_entries) as usize) {
+                *sample = (key, value);
+            }
+        }
+        destination_db.write_opt(batch, &write_options)?;
+        destination_db.flush()?;
+        destination_db.compact_range::<&[u8], &[u8]>(None, None);
+
+        // Resolve the network of the ledger, from its keys and its metadata.
+        let first_key = maps.keys().next().ok_or_else(|| anyhow!("The ledger is empty"))?;
+        ensure!(first_key.len() >= 2, "The ledger is not in a supported storage format");
+        let network = u16::from_le_bytes([first_key[0], first_key[1]]);
+        if let Some(metadata) = StorageMetadata::load(source)? {
+            ensure!(metadata.network == network, "The ledger belongs to network {network}, not {}", metadata.network);
+        }
+
+        // Verify the number of entries in each map, and the sampled values.
+        let mut counts = BTreeMap::<Box<[u8]>, u64>::new();
+        for entry in destination_db.iterator(rocksdb::IteratorMode::Start) {
+            let (key, _) = entry?;
+            *counts.entry(map_prefix(&key).into()).or_default() += 1;
+        }
+        for (prefix, map) in &maps {
+            let count = counts.remove(prefix).unwrap_or_default();
+            ensure!(count == map.num_entries, "Map {prefix:?} has {count} entries instead of {}", map.num_entries);
+            for (key, value) in &map.samples {
+                ensure!(
+                    destination_db.get(key)?.as_deref() == Some(&**value),
+                    "Map {prefix:?} has a mismatching value for key {key:?}"
+                );
+            }
+        }
+        ensure!(counts.is_empty(), "The rewritten ledger has unexpected maps {:?}", counts.keys());
+
+        // Record the metadata, so that a node opens the destination as-is.
+        drop(destination_db);
+        StorageMetadata { network, path: destination.to_path_buf() }.store(destination)?;
+
+        Ok((maps.values().map(|map| map.num_entries).sum(), maps.len()))
+    }
+}
+
+/// Returns the options of the destination database.
+fn destination_options() -> rocksdb::Options {
+    let mut options = rocksdb::Options::default();
+    options.create_if_missing(true);
+    options.set_error_if_exists(true);
+    // Match the layout of the ledger storage, which the node reopens the destination with.
+    options.set_prefix_extractor(rocksdb::SliceTransform::create_fixed_prefix(MAP_PREFIX_LEN));
+    options.set_compression_type(rocksdb::DBCompressionType::Lz4);
+    // Defer compactions until the end, as the entries are written once, in key order.
+    options.prepare_for_bulk_load();
+    options
+}
+
+/// Returns the prefix of the map of the given key.
+fn map_prefix(key: &[u8]) -> &[u8] {
+    &key[..key.len().min(MAP_PREFIX_LEN)]
+}
+
+/// Returns the total size of the files in the given directory, in bytes.
+fn directory_size(path: &Path) -> Result<u64> {
+    let mut size = 0;
+    for entry in std::fs::read_dir(path)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        size += match metadata.is_dir() {
+            true => directory_size(&entry.path())?,
+            false => metadata.len(),
+        };
+    }
+    Ok(size)
+}
+
+/// Returns the given size in bytes, in human-readable units.
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkos_node_bft_ledger_service::test_helpers::{advance_sample_ledger, sample_genesis_block};
+    use snarkvm::{
+        ledger::{Ledger, block::Block, store::helpers::rocksdb::ConsensusDB},
+        prelude::{MainnetV0, Network, PrivateKey},
+    };
+
+    use aleo_std::StorageMode;
+    use rand::SeedableRng;
+    use rand_chacha::ChaChaRng;
+
+    type CurrentNetwork = MainnetV0;
+    type CurrentLedger = Ledger<CurrentNetwork, ConsensusDB<CurrentNetwork>>;
+
+    /// The number of blocks in the sample devnet ledger, after genesis.
+    const NUM_BLOCKS: u32 = 20;
+
+    /// Returns a new temporary directory.
+    fn sample_dir() -> PathBuf {
+        let path = std::env::temp_dir().join(format!("snarkos-rewrite-{}", rand::random::<u64>()));
+        std::fs::create_dir_all(&path).unwrap();
+        std::fs::canonicalize(path).unwrap()
+    }
+
+    /// Writes a devnet ledger to the given directory, and returns its beacon private key and genesis block.
+    ///
+    /// Every block overwrites the staking entries of the genesis committee, in the finalize store.
+    fn write_sample_ledger(path: &Path, rng: &mut ChaChaRng) -> (PrivateKey<CurrentNetwork>, Block<CurrentNetwork>) {
+        let (private_key, genesis) = sample_genesis_block(rng);
+        let ledger = CurrentLedger::load(genesis.clone(), StorageMode::Custom(path.to_path_buf())).unwrap();
+        advance_sample_ledger(&ledger, &private_key, NUM_BLOCKS, rng);
+        (private_key, genesis)
+    }
+
+    /// Returns the entries of the ledger at the given directory.
+    fn read_entries(path: &Path) -> Vec<(Box<[u8]>, Box<[u8]>)> {
+        let db = rocksdb::DB::open_for_read_only(&rocksdb::Options::default(), path, false).unwrap();
+        db.iterator(rocksdb::IteratorMode::Start).map(|entry| entry.unwrap()).collect()
+    }
+
+    /// Returns the name, size, and modification time of every file in the given directory.
+    fn list_files(path: &Path) -> Vec<(std::ffi::OsString, u64, std::time::SystemTime)> {
+        let mut files: Vec<_> = std::fs::read_dir(path)
+            .unwrap()
+            .map(|entry| {
+                let entry = entry.unwrap();
+                let metadata = entry.metadata().unwrap();
+                (entry.file_name(), metadata.len(), metadata.modified().unwrap())
+            })
+            .collect();
+        files.sort();
+        files
+    }
+
+    #[test]
+    fn test_rewrite() {
+        let rng = &mut ChaChaRng::seed_from_u64(1234567890u64);
+        let dir = sample_dir();
+        let (source, destination) = (dir.join("source"), dir.join("destination"));
+        let (private_key, genesis) = write_sample_ledger(&source, rng);
+        let files = list_files(&source);
+        let entries = read_entries(&source);
+
+        let rewrite = Rewrite { source: source.clone(), destination: destination.clone(), num_samples: 16 };
+        let output = rewrite.parse().unwrap();
+        assert!(output.contains(&format!("Rewrote {} entries", entries.len())), "{output}");
+
+        // Ensure the source is unchanged, and the destination holds the same entries, in less space.
+        assert_eq!(list_files(&source), files);
+        assert!(directory_size(&destination).unwrap() < directory_size(&source).unwrap());
+        assert_eq!(read_entries(&destination), entries);
+
+        // Ensure the destination records its metadata.
+        let metadata = StorageMetadata::load(&destination).unwrap().unwrap();
+        assert_eq!(metadata, StorageMetadata { network: CurrentNetwork::ID, path: destination.clone() });
+
+        // Ensure a node opens the destination at the same tip as the source.
+        let source = CurrentLedger::load(genesis.clone(), StorageMode::Custom(source)).unwrap();
+        let rewritten = CurrentLedger::load(genesis, StorageMode::Custom(destination)).unwrap();
+        assert_eq!(rewritten.latest_height(), NUM_BLOCKS);
+        assert_eq!(rewritten.latest_hash(), source.latest_hash());
+        assert_eq!(rewritten.latest_state_root(), source.latest_state_root());
+
+        // Ensure the destination syncs the next block of the source.
+        let block = source.prepare_advance_to_next_beacon_block(&private_key, vec![], vec![], vec![], rng).unwrap();
+        source.advance_to_next_block(&block).unwrap();
+        rewritten.check_next_block(&block, rng).unwrap();
+        rewritten.advance_to_next_block(&block).unwrap();
+        assert_eq!(rewritten.latest_hash(), block.hash());
+        drop((source, rewritten));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(target_family = "unix")]
+    #[test]
+    fn test_rewrite_refusals() {
+        let dir = sample_dir();
+        let (source, destination) = (dir.join("source"), dir.join("destination"));
+        write_sample_ledger(&source, &mut ChaChaRng::seed_from_u64(1234567890u64));
+        let rewrite = || Rewrite { source: source.clone(), destination: destination.clone(), num_samples: 16 };
+
+        // Ensure a ledger opened by a node is not rewritten.
+        let lock = StorageLock::acquire(&source).unwrap();
+        let error = rewrite().parse().unwrap_err();
+        assert!(error.to_string().contains(&format!("is opened by process {}", std::process::id())));
+        assert!(!destination.exists());
+        drop(lock);
+
+        // Ensure a non-empty destination, or one inside the source, is not written to.
+        std::fs::create_dir_all(&destination).unwrap();
+        std::fs::write(destination.join("file"), "").unwrap();
+        assert!(rewrite().parse().unwrap_err().to_string().contains("is not an empty directory"));
+        let nested = Rewrite { source: source.clone(), destination: source.join("nested"), num_samples: 16 };
+        assert!(nested.parse().unwrap_err().to_string().contains("must not overlap"));
+
+        // Ensure the rewrite succeeds once the destination is emptied.
+        std::fs::remove_file(destination.join("file")).unwrap();
+        assert!(rewrite().parse().is_ok());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
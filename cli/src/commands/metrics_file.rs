@@ -0,0 +1,162 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::{Context, Result, anyhow};
+use clap::Parser;
+use colored::Colorize;
+use serde_json::Value;
+use std::{
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    path::PathBuf,
+    time::Duration,
+};
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+
+/// The interval at which the metrics file is polled for new snapshots, when following it.
+const FOLLOW_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Commands to inspect the metrics exported by a node.
+#[derive(Debug, Parser)]
+pub enum MetricsCommand {
+    /// Pretty-print the latest snapshots of a metrics file written with '--metrics-file'.
+    Tail(TailMetrics),
+}
+
+impl MetricsCommand {
+    pub fn parse(self) -> Result<String> {
+        match self {
+            Self::Tail(tail) => tail.parse(),
+        }
+    }
+}
+
+/// Pretty-prints the latest snapshots of a metrics file.
+#[derive(Debug, Parser)]
+pub struct TailMetrics {
+    /// Specify the path to the metrics file
+    pub path: PathBuf,
+    /// Specify the number of latest snapshots to print
+    #[clap(default_value = "1", short = 'n', long = "snapshots")]
+    pub snapshots: usize,
+    /// If the flag is set, the new snapshots are printed as they are written
+    #[clap(short = 'f', long = "follow")]
+    pub follow: bool,
+}
+
+impl TailMetrics {
+    /// Prints the latest snapshots of the metrics file, and follows it if requested.
+    pub fn parse(self) -> Result<String> {
+        let contents = std::fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read the metrics file at '{}'", self.path.display()))?;
+        let lines: Vec<&str> = contents.lines().collect();
+        let snapshots = lines[lines.len().saturating_sub(self.snapshots)..]
+            .iter()
+            .map(|line| format_snapshot(line))
+            .collect::<Result<Vec<_>>>()?;
+
+        if !self.follow {
+            return Ok(snapshots.join("\n"));
+        }
+        for snapshot in snapshots {
+            println!("{snapshot}");
+        }
+        self.follow(contents.len() as u64)
+    }
+
+    /// Prints the snapshots appended to the metrics file from the given offset, across rotations.
+    fn follow(&self, mut offset: u64) -> Result<String> {
+        let mut pending = String::new();
+        loop {
+            std::thread::sleep(FOLLOW_INTERVAL);
+            // The metrics file may be briefly missing while it is rotated.
+            let Ok(mut file) = File::open(&self.path) else {
+                continue;
+            };
+            let size = file.metadata()?.len();
+            // If the metrics file shrank, it was rotated, so start from its beginning.
+            if size < offset {
+                offset = 0;
+                pending.clear();
+            }
+            if size == offset {
+                continue;
+            }
+            let mut bytes = vec![];
+            file.seek(SeekFrom::Start(offset))?;
+            file.read_to_end(&mut bytes)?;
+            offset += bytes.len() as u64;
+            pending.push_str(&String::from_utf8_lossy(&bytes));
+
+            // Print the complete lines, and keep any partially-written line for the next poll.
+            while let Some(index) = pending.find('\n') {
+                let line: String = pending.drain(..=index).collect();
+                println!("{}", format_snapshot(line.trim_end())?);
+            }
+        }
+    }
+}
+
+/// Formats the given line of the metrics file as a table of the metrics, sorted by name.
+fn format_snapshot(line: &str) -> Result<String> {
+    let snapshot: Value = serde_json::from_str(line).context("Malformed snapshot in the metrics file")?;
+    let timestamp = snapshot["timestamp"].as_i64().ok_or_else(|| anyhow!("Missing timestamp in the snapshot"))?;
+    let metrics = snapshot["metrics"].as_object().ok_or_else(|| anyhow!("Missing metrics in the snapshot"))?;
+
+    let date_time = OffsetDateTime::from_unix_timestamp(timestamp)?.format(&Rfc3339)?;
+    let mut output = format!("📈 Metrics at {}\n", date_time.bold());
+    let width = metrics.keys().map(String::len).max().unwrap_or_default();
+    let mut metrics: Vec<_> = metrics.iter().collect();
+    metrics.sort_by(|(a, _), (b, _)| a.cmp(b));
+    for (series, value) in metrics {
+        let value = match value.as_f64() {
+            Some(value) => value.to_string(),
+            None => "-".to_string(),
+        };
+        output += &format!("  {series:<width$}  {}\n", value.cyan());
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{CLI, Command};
+
+    #[test]
+    fn test_format_snapshot() {
+        let line = r#"{"metrics":{"snarkos_router_connected_total":3.0,"snarkos_bft_height_total":7.5},"timestamp":0}"#;
+        let output = format_snapshot(line).unwrap();
+        assert!(output.contains("1970-01-01T00:00:00Z"));
+        // Ensure the metrics are sorted by name.
+        let bft = output.find("snarkos_bft_height_total").unwrap();
+        let router = output.find("snarkos_router_connected_total").unwrap();
+        assert!(bft < router);
+        assert!(output.contains("7.5"));
+
+        // Ensure a malformed snapshot is rejected.
+        assert!(format_snapshot("{\"timestamp\":").is_err());
+        assert!(format_snapshot("{}").is_err());
+    }
+
+    #[test]
+    fn test_parse_tail() {
+        let cli = CLI::try_parse_from(["snarkos", "metrics", "tail", "metrics.jsonl", "-n", "3"]).unwrap();
+        let Command::Metrics(MetricsCommand::Tail(tail)) = cli.command else { panic!("Unexpected command") };
+        assert_eq!(tail.path, PathBuf::from("metrics.jsonl"));
+        assert_eq!(tail.snapshots, 3);
+        assert!(!tail.follow);
+    }
+}
@@ -16,15 +16,30 @@
 mod account;
 pub use account::*;
 
+mod bft;
+pub use bft::*;
+
 mod clean;
 pub use clean::*;
 
 mod developer;
 pub use developer::*;
 
+mod devnet;
+pub use devnet::*;
+
+mod ledger;
+pub use ledger::*;
+
+mod metrics_file;
+pub use metrics_file::*;
+
 mod start;
 pub use start::*;
 
+mod tools;
+pub use tools::*;
+
 mod update;
 pub use update::*;
 
@@ -54,12 +69,22 @@ pub struct CLI {
 pub enum Command {
     #[clap(subcommand)]
     Account(Account),
+    #[clap(subcommand)]
+    Bft(BftCommand),
     #[clap(name = "clean")]
     Clean(Clean),
     #[clap(subcommand)]
     Developer(Developer),
+    #[clap(subcommand)]
+    Devnet(Devnet),
+    #[clap(subcommand)]
+    Ledger(LedgerCommand),
+    #[clap(subcommand)]
+    Metrics(MetricsCommand),
     #[clap(name = "start")]
     Start(Box<Start>),
+    #[clap(subcommand)]
+    Tools(ToolsCommand),
     #[clap(name = "update")]
     Update(Update),
 }
@@ -69,9 +94,14 @@ impl Command {
     pub fn parse(self) -> Result<String> {
         match self {
             Self::Account(command) => command.parse(),
+            Self::Bft(command) => command.parse(),
             Self::Clean(command) => command.parse(),
             Self::Developer(command) => command.parse(),
+            Self::Devnet(command) => command.parse(),
+            Self::Ledger(command) => command.parse(),
+            Self::Metrics(command) => command.parse(),
             Self::Start(command) => command.parse(),
+            Self::Tools(command) => command.parse(),
             Self::Update(command) => command.parse(),
         }
     }
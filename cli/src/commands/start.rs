@@ -13,9 +13,33 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use super::CommitteeSpec;
+use crate::helpers::{
+    CheckReport,
+    DEFAULT_MAX_LOG_FILES,
+    LogRotation,
+    canonicalize_storage_path,
+    check_listener,
+    check_peers,
+    check_storage,
+    prepare_storage,
+};
 use snarkos_account::Account;
 use snarkos_display::Display;
-use snarkos_node::{Node, bft::MEMORY_POOL_PORT, router::messages::NodeType};
+use snarkos_node::{
+    HealthAlertConfig,
+    Node,
+    ProvingPoolConfig,
+    bft::{
+        MEMORY_POOL_PORT,
+        helpers::{DevInstance, METRICS_PORT, ValidatorsResponseMode, dev_storage_mode},
+    },
+    consensus::{MempoolPolicyFile, parse_mempool_policy},
+    rest::LogFileStatus,
+    router::{Experiments, PeerExport, messages::NodeType},
+    sync::DEFAULT_MAX_REORG_DEPTH,
+    tcp::HandshakeLimits,
+};
 use snarkvm::{
     console::{
         account::{Address, PrivateKey},
@@ -33,7 +57,7 @@ use snarkvm::{
 };
 
 use aleo_std::StorageMode;
-use anyhow::{Result, bail, ensure};
+use anyhow::{Result, anyhow, bail, ensure};
 use clap::Parser;
 use colored::Colorize;
 use core::str::FromStr;
@@ -45,6 +69,7 @@ use std::{
     net::SocketAddr,
     path::PathBuf,
     sync::{Arc, atomic::AtomicBool},
+    time::Duration,
 };
 use tokio::runtime::{self, Runtime};
 
@@ -54,16 +79,21 @@ use tokio::runtime::{self, Runtime};
 const RECOMMENDED_MIN_NOFILES_LIMIT: u64 = 2048;
 
 /// The development mode RNG seed.
-const DEVELOPMENT_MODE_RNG_SEED: u64 = 1234567890u64;
+pub(crate) const DEVELOPMENT_MODE_RNG_SEED: u64 = 1234567890u64;
 /// The development mode number of genesis committee members.
-const DEVELOPMENT_MODE_NUM_GENESIS_COMMITTEE_MEMBERS: u16 = 4;
+pub(crate) const DEVELOPMENT_MODE_NUM_GENESIS_COMMITTEE_MEMBERS: u16 = 4;
 
 /// The CDN base url.
 const CDN_BASE_URL: &str = "https://blocks.aleo.org";
 
+/// Returns the default path of the log file.
+fn default_log_file() -> PathBuf {
+    std::env::temp_dir().join("snarkos.log")
+}
+
 /// A mapping of `staker_address` to `(validator_address, withdrawal_address, amount)`.
 #[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
-pub struct BondedBalances(IndexMap<String, (String, String, u64)>);
+pub struct BondedBalances(pub IndexMap<String, (String, String, u64)>);
 
 impl FromStr for BondedBalances {
     type Err = serde_json::Error;
@@ -90,6 +120,13 @@ pub struct Start {
     #[clap(long = "client")]
     pub client: bool,
 
+    /// Specify the number of threads the prover solves the puzzle with (defaults to one per core)
+    #[clap(long = "proving-threads")]
+    pub proving_threads: Option<usize>,
+    /// Specify the cores to pin the proving threads of the prover to, as a list of cores such as `0-7,16-23`
+    #[clap(long = "proving-cores")]
+    pub proving_cores: Option<String>,
+
     /// Specify the account private key of the node
     #[clap(long = "private-key")]
     pub private_key: Option<String>,
@@ -109,12 +146,76 @@ pub struct Start {
     /// Specify the IP address and port of the validator(s) to connect to
     #[clap(default_value = "", long = "validators")]
     pub validators: String,
+    /// Specify the validators a validator shares with peers outside the committee: 'full', 'bootstrap' (trusted validators only), or 'refuse'
+    #[clap(default_value = "full", long = "validators-response")]
+    pub validators_response: ValidatorsResponseMode,
+    /// If the flag is set, a validator refuses to propose and sign batches while another validator is using its account
+    #[clap(long = "pause-on-duplicate-identity")]
+    pub pause_on_duplicate_identity: bool,
+    /// Specify the local policy of a validator for admitting transmissions into its memory pool:
+    /// 'default', 'fee-floor=<microcredits>', or 'program-allowlist=<program ID>,<program ID>,...'
+    #[clap(default_value = "default", long = "mempool-policy")]
+    pub mempool_policy: String,
+    /// Specify the path to a file containing the mempool policy of a validator, which is re-read when the node
+    /// reloads its configuration (on SIGHUP, or via the REST API), instead of '--mempool-policy'
+    #[clap(long = "mempool-policy-file", conflicts_with = "mempool_policy")]
+    pub mempool_policy_file: Option<PathBuf>,
+    /// Specify the time in seconds a validator keeps unconfirmed transmissions queued, e.g. while it is not synced
+    #[clap(default_value = "300", long = "inbound-queue-ttl", value_parser = clap::value_parser!(u64).range(1..))]
+    pub inbound_queue_ttl: u64,
+    /// Specify the path to a file where a validator saves its queued unconfirmed transmissions on shutdown,
+    /// and loads them from on startup
+    #[clap(long = "mempool-file")]
+    pub mempool_file: Option<PathBuf>,
     /// If the flag is set, a validator will allow untrusted peers to connect
     #[clap(long = "allow-external-peers")]
     pub allow_external_peers: bool,
     /// If the flag is set, a client will periodically evict more external peers
     #[clap(long = "rotate-external-peers")]
     pub rotate_external_peers: bool,
+    /// Specify how many of its blocks a client may be asked to rewind, before it rejects the history of a peer
+    #[clap(long = "max-reorg-depth")]
+    pub max_reorg_depth: Option<u32>,
+    /// If the flag is set, a validator announces its committed blocks to clients, which request them right away
+    #[clap(long = "early-block-announce")]
+    pub early_block_announce: bool,
+    /// Specify the rollout of the experiments to the peers, e.g. 'block_announce=25%' ('on', 'off', or a percentage)
+    #[clap(long = "experiments")]
+    pub experiments: Option<Experiments>,
+    /// Specify how many blocks the node may be behind its peers before it stops relaying transmissions and blocks
+    #[clap(default_value = "100", long = "relay-gate-blocks")]
+    pub relay_gate_blocks: u32,
+    /// Specify the path to the peer export of another node (see '/node/peers/export'), to seed the node with,
+    /// which is re-read when the node reloads its configuration (on SIGHUP, or via the REST API)
+    #[clap(long = "import-peers")]
+    pub import_peers: Option<PathBuf>,
+    /// If the flag is set, the node restricts the account address of a restricted peer, in addition to its IP
+    #[clap(long = "restrict-by-account")]
+    pub restrict_by_account: bool,
+    /// If the flag is set, a validator will shut down if its address has no bond or stake once synced
+    #[clap(long = "strict-account")]
+    pub strict_account: bool,
+    /// Specify the URL a validator POSTs a JSON alert to, when its block-production health drops and recovers
+    #[clap(long = "alert-webhook")]
+    pub alert_webhook: Option<String>,
+    /// Specify the block-production health score (0-100) below which a validator alerts its webhook
+    #[clap(default_value = "50", long = "alert-threshold", value_parser = clap::value_parser!(u8).range(0..=100))]
+    pub alert_threshold: u8,
+    /// Specify the maximum number of peers of the node (defaults to a value suited to the node type)
+    #[clap(long = "max-peers", value_parser = clap::value_parser!(u16).range(1..))]
+    pub max_peers: Option<u16>,
+    /// Specify the maximum number of inbound connections performing the handshake at the same time
+    #[clap(default_value = "16", long = "max-handshakes", value_parser = clap::value_parser!(u16).range(1..))]
+    pub max_handshakes: u16,
+    /// Specify the maximum time in milliseconds an inbound connection waits for a handshake slot before it is dropped
+    #[clap(default_value = "200", long = "handshake-slot-timeout", value_parser = clap::value_parser!(u16).range(1..))]
+    pub handshake_slot_timeout: u16,
+    /// Specify the approximate maximum memory in bytes of the memory pool and the caches, beyond which they are shrunk
+    #[clap(long = "max-pool-memory", value_parser = clap::value_parser!(u64).range(1..))]
+    pub max_pool_memory: Option<u64>,
+    /// If the flag is set, the node will refuse to start with a maximum number of peers unsuited to its node type
+    #[clap(long = "strict-config")]
+    pub strict_config: bool,
 
     /// Specify the IP address and port for the REST server
     #[clap(long = "rest")]
@@ -122,6 +223,12 @@ pub struct Start {
     /// Specify the requests per second (RPS) rate limit per IP for the REST server
     #[clap(default_value = "10", long = "rest-rps")]
     pub rest_rps: u32,
+    /// Specify the path to a file where the broadcasts accepted by the REST server will be journaled
+    #[clap(long = "broadcast-journal")]
+    pub broadcast_journal: Option<PathBuf>,
+    /// Specify the number of recent block summaries served by the REST server
+    #[clap(default_value = "100", long = "recent-blocks")]
+    pub recent_blocks: usize,
     /// If the flag is set, the node will not initialize the REST server
     #[clap(long)]
     pub norest: bool,
@@ -133,8 +240,23 @@ pub struct Start {
     #[clap(default_value = "1", long = "verbosity")]
     pub verbosity: u8,
     /// Specify the path to the file where logs will be stored
-    #[clap(default_value_os_t = std::env::temp_dir().join("snarkos.log"), long = "logfile")]
+    #[clap(default_value_os_t = default_log_file(), long = "logfile")]
     pub logfile: PathBuf,
+    /// Specify the maximum size in bytes of the log file, before it is rotated
+    #[clap(long = "logfile-max-size")]
+    pub logfile_max_size: Option<u64>,
+    /// If the flag is set, the log file is rotated when the (UTC) day changes
+    #[clap(long = "logfile-daily")]
+    pub logfile_daily: bool,
+    /// Specify the number of rotated log files to retain, beyond which the oldest are deleted
+    #[clap(default_value_t = DEFAULT_MAX_LOG_FILES, long = "logfile-max-files")]
+    pub logfile_max_files: usize,
+    /// If the flag is set, the rotated log files are compressed with gzip
+    #[clap(long = "logfile-compress")]
+    pub logfile_compress: bool,
+    /// If the flag is set, the logs are written to the log file as lines of JSON
+    #[clap(long = "logfile-json")]
+    pub logfile_json: bool,
 
     /// Enables the metrics exporter
     #[clap(default_value = "false", long = "metrics")]
@@ -142,11 +264,26 @@ pub struct Start {
     /// Specify the IP address and port for the metrics exporter
     #[clap(long = "metrics-ip")]
     pub metrics_ip: Option<SocketAddr>,
+    /// Specify the path to a file where snapshots of the metrics will be periodically written, as lines of JSON
+    #[clap(long = "metrics-file", requires = "metrics")]
+    pub metrics_file: Option<PathBuf>,
+    /// Specify the interval in seconds between the snapshots written to the metrics file
+    #[clap(default_value_t = metrics::DEFAULT_METRICS_FILE_INTERVAL_IN_SECS, long = "metrics-file-interval")]
+    pub metrics_file_interval: u64,
+    /// Specify the maximum size in bytes of the metrics file, before it is rotated
+    #[clap(default_value_t = metrics::DEFAULT_MAX_METRICS_FILE_SIZE, long = "metrics-file-max-size")]
+    pub metrics_file_max_size: u64,
+    /// Specify the maximum number of metrics files to keep, including the current one
+    #[clap(default_value_t = metrics::DEFAULT_MAX_METRICS_FILES, long = "metrics-file-max-count")]
+    pub metrics_file_max_count: usize,
 
     /// Specify the path to a directory containing the storage database for the ledger
     #[clap(long = "storage")]
     pub storage: Option<PathBuf>,
-    /// Enables the node to prefetch initial blocks from a CDN
+    /// If the flag is set, each block written to the ledger is read back from storage, to detect storage corruption
+    #[clap(long = "verify-writes")]
+    pub verify_writes: bool,
+    /// Enables the node to prefetch initial blocks from a CDN, with any alternate sources separated by commas
     #[clap(long = "cdn")]
     pub cdn: Option<String>,
     /// If the flag is set, the node will not prefetch from a CDN
@@ -165,17 +302,51 @@ pub struct Start {
     /// If development mode is enabled, specify the custom bonded balances as a JSON object (default: None)
     #[clap(long)]
     pub dev_bonded_balances: Option<BondedBalances>,
+    /// If development mode is enabled, specify the path to a committee file from 'snarkos devnet' (default: None)
+    #[clap(long)]
+    pub dev_committee: Option<PathBuf>,
+
+    /// If the flag is set, the node starts with networking disabled, serving a snapshot of its ledger over the REST server
+    #[clap(long = "safe-mode")]
+    pub safe_mode: bool,
+
+    /// If the flag is set, the configurations are validated without starting the node
+    #[clap(long)]
+    pub check: bool,
 }
 
 impl Start {
     /// Starts the snarkOS node.
-    pub fn parse(self) -> Result<String> {
+    pub fn parse(mut self) -> Result<String> {
+        // Parse the log file of a node in development mode.
+        self.parse_log_file()?;
+
+        // If the check flag is set, validate the configurations instead of starting the node.
+        if self.check {
+            let report = match self.network {
+                MainnetV0::ID => self.check::<MainnetV0>(),
+                TestnetV0::ID => self.check::<TestnetV0>(),
+                CanaryV0::ID => self.check::<CanaryV0>(),
+                unknown_id => bail!("Unknown network ID ({unknown_id})"),
+            };
+            return match report.is_success() {
+                true => Ok(format!("{report}\n✅ The configurations are valid")),
+                false => bail!("{report}\n❌ The configurations are invalid"),
+            };
+        }
+
         // Prepare the shutdown flag.
         let shutdown: Arc<AtomicBool> = Default::default();
 
         // Initialize the logger.
-        let log_receiver =
-            crate::helpers::initialize_logger(self.verbosity, self.nodisplay, self.logfile.clone(), shutdown.clone());
+        let log_receiver = crate::helpers::initialize_logger(
+            self.verbosity,
+            self.nodisplay,
+            self.logfile.clone(),
+            self.parse_log_rotation(),
+            self.logfile_json,
+            shutdown.clone(),
+        );
         // Initialize the runtime.
         Self::runtime().block_on(async move {
             // Clone the configurations.
@@ -296,11 +467,11 @@ impl Start {
         match self.dev {
             None => match (&self.private_key, &self.private_key_file) {
                 // Parse the private key directly.
-                (Some(private_key), None) => Account::from_str(private_key.trim()),
+                (Some(private_key), None) => parse_account(private_key.trim()),
                 // Parse the private key from a file.
                 (None, Some(path)) => {
                     check_permissions(path)?;
-                    Account::from_str(std::fs::read_to_string(path)?.trim())
+                    parse_account(std::fs::read_to_string(path)?.trim())
                 }
                 // Ensure the private key is provided to the CLI, except for clients or nodes in development mode.
                 (None, None) => match self.client {
@@ -336,15 +507,14 @@ impl Start {
         trusted_validators: &mut Vec<SocketAddr>,
     ) -> Result<()> {
         // If `--dev` is set, assume the dev nodes are initialized from 0 to `dev`,
-        // and add each of them to the trusted peers. In addition, set the node IP to `4130 + dev`,
-        // and the REST IP to `3030 + dev`.
+        // and add each of them to the trusted peers. In addition, derive the unset listening addresses
+        // from the development ID, as documented in `DevInstance`.
         if let Some(dev) = self.dev {
+            let instance = DevInstance::new(dev)?;
             // Add the dev nodes to the trusted peers.
             if trusted_peers.is_empty() {
                 for i in 0..dev {
-                    if i != dev {
-                        trusted_peers.push(SocketAddr::from_str(&format!("127.0.0.1:{}", 4130 + i))?);
-                    }
+                    trusted_peers.push(DevInstance::new(i)?.peer_ip());
                 }
             }
             // Add the dev nodes to the trusted validators.
@@ -352,7 +522,7 @@ impl Start {
                 // To avoid ambiguity, we define the first few nodes to be the trusted validators to connect to.
                 for i in 0..2 {
                     if i != dev {
-                        trusted_validators.push(SocketAddr::from_str(&format!("127.0.0.1:{}", MEMORY_POOL_PORT + i))?);
+                        trusted_validators.push(DevInstance::new(i)?.bft_ip());
                     }
                 }
             }
@@ -360,13 +530,28 @@ impl Start {
             //
             // Note: the `node` flag is an option to detect remote devnet testing.
             if self.node.is_none() {
-                self.node = Some(SocketAddr::from_str(&format!("0.0.0.0:{}", 4130 + dev))?);
+                self.node = Some(instance.node_ip());
+            }
+            // Set the BFT IP of a validator to `5000 + dev`.
+            if self.validator && self.bft.is_none() {
+                self.bft = Some(instance.bft_ip());
             }
-
             // If the `norest` flag is not set and the REST IP is not already specified set the REST IP to `3030 + dev`.
             if !self.norest && self.rest.is_none() {
-                self.rest = Some(SocketAddr::from_str(&format!("0.0.0.0:{}", 3030 + dev)).unwrap());
+                self.rest = Some(instance.rest_ip());
+            }
+            // If the metrics are enabled and the metrics IP is not already specified, set it to `9000 + dev`.
+            if self.metrics && self.metrics_ip.is_none() {
+                self.metrics_ip = Some(instance.metrics_ip());
             }
+
+            // Ensure the explicitly provided flags do not conflict with the derived ports.
+            instance.ensure_distinct_ports(&[
+                ("--node", self.node),
+                ("--bft", self.bft),
+                ("--rest", self.rest.filter(|_| !self.norest)),
+                ("--metrics-ip", self.metrics_ip.filter(|_| self.metrics)),
+            ])?;
         }
         Ok(())
     }
@@ -375,10 +560,26 @@ impl Start {
     /// Otherwise, returns the actual genesis block.
     fn parse_genesis<N: Network>(&self) -> Result<Block<N>> {
         if self.dev.is_some() {
+            // Load the committee file, if one is specified.
+            let dev_committee = self.dev_committee.as_deref().map(CommitteeSpec::load).transpose()?;
+            if let Some(dev_committee) = &dev_committee {
+                ensure!(
+                    self.dev_bonded_balances.is_none(),
+                    "The '--dev-committee' and '--dev-bonded-balances' flags cannot be used together"
+                );
+                ensure!(
+                    dev_committee.network == N::ID,
+                    "The committee file belongs to network {}, not network {}",
+                    dev_committee.network,
+                    N::ID
+                );
+            }
+
             // Determine the number of genesis committee members.
-            let num_committee_members = match self.dev_num_validators {
-                Some(num_committee_members) => num_committee_members,
-                None => DEVELOPMENT_MODE_NUM_GENESIS_COMMITTEE_MEMBERS,
+            let num_committee_members = match (self.dev_num_validators, &dev_committee) {
+                (Some(num_committee_members), _) => num_committee_members,
+                (None, Some(dev_committee)) => u16::try_from(dev_committee.mapping.len())?,
+                (None, None) => DEVELOPMENT_MODE_NUM_GENESIS_COMMITTEE_MEMBERS,
             };
             ensure!(
                 num_committee_members >= DEVELOPMENT_MODE_NUM_GENESIS_COMMITTEE_MEMBERS,
@@ -395,7 +596,8 @@ impl Start {
                 development_private_keys.iter().map(Address::<N>::try_from).collect::<Result<Vec<_>>>()?;
 
             // Construct the committee based on the state of the bonded balances.
-            let (committee, bonded_balances) = match &self.dev_bonded_balances {
+            let bonded_balances = dev_committee.map(|dev_committee| dev_committee.bonded_balances);
+            let (committee, bonded_balances) = match bonded_balances.as_ref().or(self.dev_bonded_balances.as_ref()) {
                 Some(bonded_balances) => {
                     // Parse the bonded balances.
                     let bonded_balances = bonded_balances
@@ -498,6 +700,10 @@ impl Start {
             if self.dev_num_validators.is_some() {
                 eprintln!("The '--dev-num-validators' flag is ignored because '--dev' is not set");
             }
+            // If the `dev_committee` flag is set, inform the user that it is ignored.
+            if self.dev_committee.is_some() {
+                eprintln!("The '--dev-committee' flag is ignored because '--dev' is not set");
+            }
 
             Block::from_bytes_le(N::genesis_bytes())
         }
@@ -514,6 +720,177 @@ impl Start {
         }
     }
 
+    /// Returns the IP address and port for the node server, from the given configurations.
+    fn parse_node_ip(&self) -> SocketAddr {
+        self.node.unwrap_or_else(|| SocketAddr::from_str("0.0.0.0:4130").unwrap())
+    }
+
+    /// Returns the IP address and port for the REST server, if it is enabled, from the given configurations.
+    fn parse_rest_ip(&self) -> Option<SocketAddr> {
+        match self.norest {
+            true => None,
+            false => self.rest.or_else(|| Some("0.0.0.0:3030".parse().unwrap())),
+        }
+    }
+
+    /// Ensures the configurations are compatible with safe mode, if it is enabled.
+    fn parse_safe_mode(&self) -> Result<()> {
+        if self.safe_mode {
+            // Ensure the node has a ledger to inspect.
+            ensure!(!self.prover, "Safe mode is not available for provers, as they do not persist a ledger");
+            // Ensure the ledger can be inspected.
+            ensure!(!self.norest, "Safe mode requires the REST server, and cannot be used with '--norest'");
+        }
+        Ok(())
+    }
+
+    /// Returns the storage mode, from the given configurations.
+    /// A custom storage path is made absolute and canonical, so it does not depend on the working directory.
+    fn parse_storage_mode(&self) -> Result<StorageMode> {
+        match &self.storage {
+            Some(path) => Ok(StorageMode::Custom(canonicalize_storage_path(path)?)),
+            None => Ok(dev_storage_mode(self.dev)),
+        }
+    }
+
+    /// Returns the peer export to seed the peers of the node with, if one is specified.
+    fn parse_peer_import(&self) -> Result<Option<PeerExport>> {
+        self.import_peers.as_deref().map(PeerExport::load).transpose()
+    }
+
+    /// Returns the configuration of the health alerts, if an alert webhook is specified for a validator.
+    fn parse_health_alert(&self) -> Option<HealthAlertConfig> {
+        let webhook = self.alert_webhook.clone()?;
+        // If the node is not a validator, inform the user that the webhook is ignored.
+        if !self.validator {
+            eprintln!("The '--alert-webhook' flag is ignored because the node is not a validator");
+            return None;
+        }
+        Some(HealthAlertConfig { webhook, threshold: self.alert_threshold })
+    }
+
+    /// Returns the limits on the inbound connections performing the handshake at the same time.
+    fn parse_handshake_limits(&self) -> HandshakeLimits {
+        HandshakeLimits { max_concurrent: self.max_handshakes, slot_timeout_ms: self.handshake_slot_timeout }
+    }
+
+    /// Returns the maximum reorg depth, which is only guarded by clients.
+    fn parse_max_reorg_depth(&self) -> u32 {
+        // If the node is not a client, inform the user that the maximum reorg depth is ignored.
+        if self.max_reorg_depth.is_some() && !self.client {
+            eprintln!("The '--max-reorg-depth' flag is ignored because the node is not a client");
+        }
+        self.max_reorg_depth.unwrap_or(DEFAULT_MAX_REORG_DEPTH)
+    }
+
+    /// Returns the configuration of the proving thread pool, which is only used by provers.
+    fn parse_proving_pool(&self) -> Result<ProvingPoolConfig> {
+        let cores = self.proving_cores.as_deref().map(ProvingPoolConfig::parse_cores).transpose()?;
+        // If the node is not a prover, inform the user that the proving flags are ignored.
+        if !self.prover && (self.proving_threads.is_some() || cores.is_some()) {
+            eprintln!(
+                "The '--proving-threads' and '--proving-cores' flags are ignored because the node is not a prover"
+            );
+        }
+        ProvingPoolConfig::new(self.proving_threads, cores)
+    }
+
+    /// Returns the configuration of the metrics file exporter, if a metrics file is specified.
+    fn parse_metrics_file(&self) -> Option<metrics::MetricsFileConfig> {
+        self.metrics_file.as_ref().map(|path| metrics::MetricsFileConfig {
+            path: path.clone(),
+            // Ensure the exporter never spins, and always keeps the current file.
+            interval: Duration::from_secs(self.metrics_file_interval.max(1)),
+            max_file_size: self.metrics_file_max_size,
+            max_files: self.metrics_file_max_count.max(1),
+        })
+    }
+
+    /// Suffixes the default log file with the development ID, so that development nodes never share a log file.
+    fn parse_log_file(&mut self) -> Result<()> {
+        if let Some(dev) = self.dev {
+            if self.logfile == default_log_file() {
+                self.logfile = DevInstance::new(dev)?.log_file(&self.logfile);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the rotation settings of the log file.
+    fn parse_log_rotation(&self) -> LogRotation {
+        LogRotation {
+            // Ensure the log file always holds at least one line.
+            max_file_size: self.logfile_max_size.map(|max_file_size| max_file_size.max(1)),
+            daily: self.logfile_daily,
+            max_files: self.logfile_max_files,
+            compress: self.logfile_compress,
+        }
+    }
+
+    /// Returns the path and rotation settings of the log file, as reported by the node status.
+    fn parse_log_file_status(&self) -> LogFileStatus {
+        let rotation = self.parse_log_rotation();
+        LogFileStatus {
+            path: self.logfile.clone(),
+            format: if self.logfile_json { "json" } else { "text" }.to_string(),
+            max_file_size: rotation.max_file_size,
+            daily: rotation.daily,
+            max_files: rotation.max_files,
+            compress: rotation.compress,
+        }
+    }
+
+    /// Validates the configurations without starting the node, returning a report of each check.
+    ///
+    /// The checks neither spawn any tasks nor write to disk: the ledger is opened read-only,
+    /// and the listening addresses are released as soon as they are bound.
+    fn check<N: Network>(&self) -> CheckReport {
+        let mut cli = self.clone();
+        let mut report = CheckReport::default();
+
+        // Check the flags, applying the development configurations as the node would.
+        report.record("flags", {
+            let num_node_types = [cli.validator, cli.prover, cli.client].into_iter().filter(|is_set| *is_set).count();
+            let mut trusted_peers = cli.parse_trusted_peers().unwrap_or_default();
+            let mut trusted_validators = cli.parse_trusted_validators().unwrap_or_default();
+            match num_node_types <= 1 {
+                true => cli
+                    .parse_development(&mut trusted_peers, &mut trusted_validators)
+                    .map(|_| format!("Configured {} on {}", cli.parse_node_type().description(), N::NAME)),
+                false => Err(anyhow!("Only one of '--validator', '--prover', or '--client' may be set")),
+            }
+        });
+
+        // Check the account.
+        report.record("account", cli.parse_private_key::<N>().map(|account| format!("Loaded {}", account.address())));
+
+        // Check the peers.
+        report.record("peers", check_peers("--peers", &cli.peers));
+        report.record("validators", check_peers("--validators", &cli.validators));
+
+        // Check the listening addresses; in safe mode, only the REST server listens.
+        if !cli.safe_mode {
+            report.record("node port", check_listener(cli.parse_node_ip()));
+        }
+        if cli.validator && !cli.safe_mode {
+            // Note: In development mode, the BFT IP is derived from the development ID by `parse_development`.
+            let bft_ip = cli.bft.unwrap_or_else(|| SocketAddr::from(([0, 0, 0, 0], MEMORY_POOL_PORT)));
+            report.record("bft port", check_listener(bft_ip));
+        }
+        if let Some(rest_ip) = cli.parse_rest_ip() {
+            report.record("rest port", check_listener(rest_ip));
+        }
+        if cli.metrics {
+            let metrics_ip = cli.metrics_ip.unwrap_or(([0, 0, 0, 0], METRICS_PORT).into());
+            report.record("metrics port", check_listener(metrics_ip));
+        }
+
+        // Check the storage.
+        report.record("storage", cli.parse_storage_mode().and_then(|mode| check_storage(N::ID, mode)));
+
+        report
+    }
+
     /// Returns the node type corresponding to the given configurations.
     #[rustfmt::skip]
     async fn parse_node<N: Network>(&mut self, shutdown: Arc<AtomicBool>) -> Result<Node<N>> {
@@ -529,6 +906,8 @@ impl Start {
 
         // Parse the CDN.
         let cdn = self.parse_cdn::<N>();
+        // Parse the safe mode.
+        self.parse_safe_mode()?;
 
         // Parse the genesis block.
         let genesis = self.parse_genesis::<N>()?;
@@ -538,31 +917,33 @@ impl Start {
         let node_type = self.parse_node_type();
 
         // Parse the node IP.
-        let node_ip = match self.node {
-            Some(node_ip) => node_ip,
-            None => SocketAddr::from_str("0.0.0.0:4130").unwrap(),
-        };
-
+        let node_ip = self.parse_node_ip();
         // Parse the REST IP.
-        let rest_ip = match self.norest {
-            true => None,
-            false => self.rest.or_else(|| Some("0.0.0.0:3030".parse().unwrap())),
-        };
+        let rest_ip = self.parse_rest_ip();
+        // Parse the proving thread pool.
+        let proving_pool = self.parse_proving_pool()?;
 
         // If the display is not enabled, render the welcome message.
         if self.nodisplay {
             // Print the Aleo address.
             println!("👛 Your Aleo address is {}.\n", account.address().to_string().bold());
             // Print the node type and network.
-            println!(
-                "🧭 Starting {} on {} at {}.\n",
-                node_type.description().bold(),
-                N::NAME.bold(),
-                node_ip.to_string().bold()
-            );
+            match self.safe_mode {
+                true => println!(
+                    "🧭 Starting {} on {} in safe mode (networking is disabled).\n",
+                    node_type.description().bold(),
+                    N::NAME.bold()
+                ),
+                false => println!(
+                    "🧭 Starting {} on {} at {}.\n",
+                    node_type.description().bold(),
+                    N::NAME.bold(),
+                    node_ip.to_string().bold()
+                ),
+            }
 
             // If the node is running a REST server, print the REST IP and JWT.
-            if node_type.is_validator() {
+            if node_type.is_validator() || self.safe_mode {
                 if let Some(rest_ip) = rest_ip {
                     println!("🌐 Starting the REST server at {}.\n", rest_ip.to_string().bold());
 
@@ -571,6 +952,12 @@ impl Start {
                     }
                 }
             }
+
+            // If the node is a prover, print the proving thread pool.
+            if node_type.is_prover() {
+                let cores = proving_pool.cores().map(|cores| format!(" pinned to cores {cores:?}")).unwrap_or_default();
+                println!("⛏️  Proving with {} threads{cores}.\n", proving_pool.num_threads().to_string().bold());
+            }
         }
 
         // If the node is a validator, check if the open files limit is lower than recommended.
@@ -583,14 +970,29 @@ impl Start {
 
         // Initialize the metrics.
         if self.metrics {
-            metrics::initialize_metrics(self.metrics_ip);
+            let handle = metrics::initialize_metrics(self.metrics_ip);
+            // Initialize the metrics file exporter.
+            if let Some(config) = self.parse_metrics_file() {
+                metrics::spawn_metrics_file_exporter(handle, config)?;
+            }
         }
 
         // Initialize the storage mode.
-        let storage_mode = match &self.storage {
-            Some(path) => StorageMode::Custom(path.clone()),
-            None => StorageMode::from(self.dev),
-        };
+        let storage_mode = self.parse_storage_mode()?;
+        // Ensure a development node does not open the ledger of another node.
+        if let Some(dev) = self.dev {
+            DevInstance::new(dev)?.ensure_distinct_storage(N::ID, &storage_mode)?;
+        }
+        // Lock the ledger, so that no other process opens it while the node is running.
+        if !node_type.is_prover() {
+            let lock = prepare_storage(N::ID, &storage_mode)?;
+            // Note: The lock is held until the process exits, at which point it is released by the OS.
+            std::mem::forget(lock);
+        }
+        // If the node is a prover, inform the user that the `verify_writes` flag is ignored.
+        if node_type.is_prover() && self.verify_writes {
+            eprintln!("The '--verify-writes' flag is ignored because provers do not persist a ledger");
+        }
 
         // Determine whether to generate background transactions in dev mode.
         let dev_txs = match self.dev {
@@ -604,12 +1006,46 @@ impl Start {
             }
         };
 
+        // Parse the log file status.
+        let log_file = Some(self.parse_log_file_status());
+        // Parse the mempool policy.
+        let (mempool_policy, mempool_policy_file) = match &self.mempool_policy_file {
+            Some(path) => {
+                let (mempool_policy_file, mempool_policy) = MempoolPolicyFile::open::<N>(path.clone())?;
+                (mempool_policy, Some(mempool_policy_file))
+            }
+            None => (parse_mempool_policy::<N>(&self.mempool_policy)?, None),
+        };
+        // Parse the experiments.
+        let experiments = self.experiments.clone().unwrap_or_default();
+        // Parse the peer export to seed the peers of the node with.
+        let peer_import = self.parse_peer_import()?;
+
+        // If safe mode is enabled, initialize the node without networking.
+        if self.safe_mode {
+            return Node::new_safe(node_type, rest_ip, self.rest_rps, self.recent_blocks, log_file, account, genesis, storage_mode, shutdown).await;
+        }
+
         // Initialize the node.
-        match node_type {
-            NodeType::Validator => Node::new_validator(node_ip, self.bft, rest_ip, self.rest_rps, account, &trusted_peers, &trusted_validators, genesis, cdn, storage_mode, self.allow_external_peers, dev_txs, shutdown.clone()).await,
-            NodeType::Prover => Node::new_prover(node_ip, account, &trusted_peers, genesis, storage_mode, shutdown.clone()).await,
-            NodeType::Client => Node::new_client(node_ip, rest_ip, self.rest_rps, account, &trusted_peers, genesis, cdn, storage_mode, self.rotate_external_peers, shutdown).await,
+        let node = match node_type {
+            NodeType::Validator => Node::new_validator(node_ip, self.bft, rest_ip, self.rest_rps, self.broadcast_journal.clone(), self.recent_blocks, log_file, account, &trusted_peers, self.max_peers, self.parse_handshake_limits(), self.max_pool_memory, self.strict_config, &trusted_validators, self.validators_response, self.pause_on_duplicate_identity, mempool_policy, mempool_policy_file, Duration::from_secs(self.inbound_queue_ttl), self.mempool_file.clone(), genesis, cdn, storage_mode, self.verify_writes, self.allow_external_peers, self.early_block_announce, experiments, self.parse_health_alert(), dev_txs, self.strict_account, shutdown.clone()).await,
+            NodeType::Prover => Node::new_prover(node_ip, account, &trusted_peers, self.max_peers, self.parse_handshake_limits(), self.max_pool_memory, self.strict_config, genesis, storage_mode, experiments, proving_pool, shutdown.clone()).await,
+            NodeType::Client => Node::new_client(node_ip, rest_ip, self.rest_rps, self.broadcast_journal.clone(), self.recent_blocks, log_file, account, &trusted_peers, self.max_peers, self.parse_handshake_limits(), self.max_pool_memory, self.strict_config, genesis, cdn, storage_mode, self.verify_writes, self.parse_max_reorg_depth(), self.rotate_external_peers, self.early_block_announce, experiments, shutdown).await,
+        }?;
+
+        // Set the number of blocks the node may be behind, before its gossip is suppressed.
+        if let Some(router) = node.router() {
+            router.relay_gate().set_threshold(self.relay_gate_blocks);
+            // Set whether the node restricts the account address of a restricted peer.
+            router.set_restrict_by_account(self.restrict_by_account);
+        }
+        // Seed the peers of the node with the peer export, and import the peers added to it on every reload.
+        if let (Some(export), Some(path), Some(router)) = (peer_import, &self.import_peers, node.router()) {
+            let summary = router.import_peers(&export);
+            println!("📥 Imported {summary} from the peer export.\n");
+            router.register_peer_import(path.clone());
         }
+        Ok(node)
     }
 
     /// Returns a runtime for the node.
@@ -661,6 +1097,23 @@ fn check_permissions(path: &PathBuf) -> Result<(), snarkvm::prelude::Error> {
     Ok(())
 }
 
+/// Parses the given private key for the network, returning the Aleo account.
+///
+/// Note: Neither an Aleo private key nor its address encodes a network ID, and the same key derives the same
+/// address on every network, so a key for another network cannot be told apart here. Instead, the account is
+/// checked against the ledger of the selected network once it is available (see `check_account_status`).
+fn parse_account<N: Network>(private_key: &str) -> Result<Account<N>> {
+    // Catch the common mistake of providing a view key or an address, instead of a private key.
+    if private_key.starts_with("AViewKey1") {
+        bail!("Expected a private key for {}, found a view key", N::NAME)
+    }
+    if private_key.starts_with("aleo1") {
+        bail!("Expected a private key for {}, found an address", N::NAME)
+    }
+    Account::<N>::from_str(private_key)
+        .map_err(|error| anyhow!("The provided private key is invalid for {} - {error}", N::NAME))
+}
+
 /// Loads or computes the genesis block.
 fn load_or_compute_genesis<N: Network>(
     genesis_private_key: PrivateKey<N>,
@@ -775,6 +1228,7 @@ fn load_or_compute_genesis<N: Network>(
 mod tests {
     use super::*;
     use crate::commands::{CLI, Command};
+    use snarkos_node::router::{BLOCK_ANNOUNCE_EXPERIMENT, DEFAULT_RELAY_GATE_BLOCKS, Rollout};
     use snarkvm::prelude::MainnetV0;
 
     type CurrentNetwork = MainnetV0;
@@ -910,6 +1364,225 @@ mod tests {
         assert!(config.parse_cdn::<CurrentNetwork>().is_none());
     }
 
+    #[test]
+    fn test_parse_safe_mode() {
+        // Ensure safe mode is available to validators and clients.
+        let config = Start::try_parse_from(["snarkos", "--validator", "--safe-mode"].iter()).unwrap();
+        assert!(config.parse_safe_mode().is_ok());
+        let config = Start::try_parse_from(["snarkos", "--client", "--safe-mode"].iter()).unwrap();
+        assert!(config.parse_safe_mode().is_ok());
+
+        // Ensure safe mode is rejected for provers, and without the REST server.
+        let config = Start::try_parse_from(["snarkos", "--prover", "--safe-mode"].iter()).unwrap();
+        assert!(config.parse_safe_mode().is_err());
+        let config = Start::try_parse_from(["snarkos", "--client", "--safe-mode", "--norest"].iter()).unwrap();
+        assert!(config.parse_safe_mode().is_err());
+        let config = Start::try_parse_from(["snarkos", "--client", "--norest"].iter()).unwrap();
+        assert!(config.parse_safe_mode().is_ok());
+    }
+
+    #[test]
+    fn test_parse_peer_limits() {
+        // Ensure the maximum number of peers defaults to the node type.
+        let config = Start::try_parse_from(["snarkos", "--prover"].iter()).unwrap();
+        assert_eq!(config.max_peers, None);
+        assert!(!config.strict_config);
+
+        // Ensure the maximum number of peers and the strict configuration are parsed.
+        let config =
+            Start::try_parse_from(["snarkos", "--prover", "--max-peers", "200", "--strict-config"].iter()).unwrap();
+        assert_eq!(config.max_peers, Some(200));
+        assert!(config.strict_config);
+
+        // Ensure a node must allow for at least one peer.
+        assert!(Start::try_parse_from(["snarkos", "--max-peers", "0"].iter()).is_err());
+
+        // Ensure the handshake limits default to the ones of the TCP stack.
+        let config = Start::try_parse_from(["snarkos", "--prover"].iter()).unwrap();
+        assert_eq!(config.parse_handshake_limits(), HandshakeLimits::default());
+
+        // Ensure the handshake limits are parsed, and allow for at least one handshake.
+        let config = Start::try_parse_from(
+            ["snarkos", "--prover", "--max-handshakes", "4", "--handshake-slot-timeout", "50"].iter(),
+        )
+        .unwrap();
+        assert_eq!(config.parse_handshake_limits(), HandshakeLimits { max_concurrent: 4, slot_timeout_ms: 50 });
+        assert!(Start::try_parse_from(["snarkos", "--max-handshakes", "0"].iter()).is_err());
+    }
+
+    #[test]
+    fn test_parse_mempool_policy() {
+        // Ensure the default mempool policy is selected by default.
+        let config = Start::try_parse_from(["snarkos", "--validator"].iter()).unwrap();
+        assert_eq!(parse_mempool_policy::<CurrentNetwork>(&config.mempool_policy).unwrap().name(), "default");
+        // Ensure the inbound queue TTL defaults to the one of consensus, and cannot be zero.
+        assert_eq!(config.inbound_queue_ttl, snarkos_node::consensus::DEFAULT_INBOUND_QUEUE_TTL_IN_SECS);
+        assert!(Start::try_parse_from(["snarkos", "--validator", "--inbound-queue-ttl", "0"].iter()).is_err());
+
+        // Ensure the bundled mempool policies are selectable.
+        let config =
+            Start::try_parse_from(["snarkos", "--validator", "--mempool-policy", "fee-floor=10000"].iter()).unwrap();
+        assert_eq!(parse_mempool_policy::<CurrentNetwork>(&config.mempool_policy).unwrap().name(), "fee-floor");
+        let args = ["snarkos", "--validator", "--mempool-policy", "program-allowlist=credits.aleo,token.aleo"];
+        let config = Start::try_parse_from(args.iter()).unwrap();
+        assert_eq!(parse_mempool_policy::<CurrentNetwork>(&config.mempool_policy).unwrap().name(), "program-allowlist");
+
+        // Ensure an invalid mempool policy is rejected.
+        let config = Start::try_parse_from(["snarkos", "--validator", "--mempool-policy", "fee-floor"].iter()).unwrap();
+        assert!(parse_mempool_policy::<CurrentNetwork>(&config.mempool_policy).is_err());
+
+        // Ensure the mempool policy is either given inline, or in a file.
+        let args = ["snarkos", "--validator", "--mempool-policy-file", "policy.txt"];
+        let config = Start::try_parse_from(args.iter()).unwrap();
+        assert_eq!(config.mempool_policy_file, Some(PathBuf::from("policy.txt")));
+        let args = ["snarkos", "--validator", "--mempool-policy", "default", "--mempool-policy-file", "policy.txt"];
+        assert!(Start::try_parse_from(args.iter()).is_err());
+    }
+
+    #[test]
+    fn test_parse_experiments() {
+        // Ensure the experiments keep their default rollout by default.
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert_eq!(config.experiments, None);
+
+        // Ensure the experiments are parsed, and unknown experiments are rejected.
+        let config = Start::try_parse_from(["snarkos", "--experiments", "block_announce=25%"].iter()).unwrap();
+        assert_eq!(config.experiments.unwrap().rollout(BLOCK_ANNOUNCE_EXPERIMENT), Some(Rollout::percent(25).unwrap()));
+        assert!(Start::try_parse_from(["snarkos", "--experiments", "gossip_v2=on"].iter()).is_err());
+    }
+
+    #[test]
+    fn test_parse_relay_gate_blocks() {
+        // Ensure the relay gate uses the default threshold by default.
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert_eq!(config.relay_gate_blocks, DEFAULT_RELAY_GATE_BLOCKS);
+
+        // Ensure the relay gate threshold is parsed.
+        let config = Start::try_parse_from(["snarkos", "--relay-gate-blocks", "1000"].iter()).unwrap();
+        assert_eq!(config.relay_gate_blocks, 1000);
+        assert!(Start::try_parse_from(["snarkos", "--relay-gate-blocks", "-1"].iter()).is_err());
+    }
+
+    #[test]
+    fn test_parse_max_pool_memory() {
+        // Ensure the pool memory is unlimited by default.
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert_eq!(config.max_pool_memory, None);
+
+        // Ensure the maximum pool memory is parsed, and must be positive.
+        let config = Start::try_parse_from(["snarkos", "--max-pool-memory", "2147483648"].iter()).unwrap();
+        assert_eq!(config.max_pool_memory, Some(2 << 30));
+        assert!(Start::try_parse_from(["snarkos", "--max-pool-memory", "0"].iter()).is_err());
+    }
+
+    #[test]
+    fn test_parse_max_reorg_depth() {
+        // Ensure the maximum reorg depth defaults to the finality of the committee.
+        let config = Start::try_parse_from(["snarkos", "--client"].iter()).unwrap();
+        assert_eq!(config.parse_max_reorg_depth(), DEFAULT_MAX_REORG_DEPTH);
+
+        // Ensure the maximum reorg depth is parsed.
+        let config = Start::try_parse_from(["snarkos", "--client", "--max-reorg-depth", "0"].iter()).unwrap();
+        assert_eq!(config.parse_max_reorg_depth(), 0);
+        assert!(Start::try_parse_from(["snarkos", "--client", "--max-reorg-depth", "-1"].iter()).is_err());
+    }
+
+    #[test]
+    fn test_parse_proving_pool() {
+        // Ensure the proving thread pool matches the global thread pool by default.
+        let config = Start::try_parse_from(["snarkos", "--prover"].iter()).unwrap();
+        let proving_pool = config.parse_proving_pool().unwrap();
+        assert_eq!(proving_pool, ProvingPoolConfig::default());
+        assert_eq!(proving_pool.num_threads(), num_cpus::get());
+
+        // Ensure the number of threads and cores are parsed.
+        let config =
+            Start::try_parse_from(["snarkos", "--prover", "--proving-threads", "6", "--proving-cores", "0-3,8"].iter())
+                .unwrap();
+        let proving_pool = config.parse_proving_pool().unwrap();
+        assert_eq!(proving_pool.num_threads(), 6);
+        assert_eq!(proving_pool.cores(), Some(&[0, 1, 2, 3, 8][..]));
+
+        // Ensure the cores bound the number of threads, unless it is given.
+        let config = Start::try_parse_from(["snarkos", "--prover", "--proving-cores", "4-7"].iter()).unwrap();
+        assert_eq!(config.parse_proving_pool().unwrap().num_threads(), 4);
+
+        // Ensure the invalid configurations are rejected.
+        let config = Start::try_parse_from(["snarkos", "--prover", "--proving-threads", "0"].iter()).unwrap();
+        assert!(config.parse_proving_pool().is_err());
+        let config = Start::try_parse_from(["snarkos", "--prover", "--proving-cores", "7-4"].iter()).unwrap();
+        assert!(config.parse_proving_pool().is_err());
+    }
+
+    #[test]
+    fn test_parse_metrics_file() {
+        // Ensure there is no metrics file by default.
+        let config = Start::try_parse_from(["snarkos", "--metrics"].iter()).unwrap();
+        assert_eq!(config.parse_metrics_file(), None);
+
+        // Ensure the metrics file requires the metrics.
+        assert!(Start::try_parse_from(["snarkos", "--metrics-file", "metrics.jsonl"].iter()).is_err());
+
+        // Ensure the metrics file is parsed with the default interval and caps.
+        let config = Start::try_parse_from(["snarkos", "--metrics", "--metrics-file", "metrics.jsonl"].iter()).unwrap();
+        assert_eq!(config.parse_metrics_file(), Some(metrics::MetricsFileConfig::new(PathBuf::from("metrics.jsonl"))));
+
+        // Ensure the interval and caps are parsed, and clamped to at least one.
+        let config = Start::try_parse_from(
+            [
+                "snarkos",
+                "--metrics",
+                "--metrics-file",
+                "metrics.jsonl",
+                "--metrics-file-interval",
+                "0",
+                "--metrics-file-max-size",
+                "1024",
+                "--metrics-file-max-count",
+                "0",
+            ]
+            .iter(),
+        )
+        .unwrap();
+        let metrics_file = config.parse_metrics_file().unwrap();
+        assert_eq!(metrics_file.interval, Duration::from_secs(1));
+        assert_eq!(metrics_file.max_file_size, 1024);
+        assert_eq!(metrics_file.max_files, 1);
+    }
+
+    #[test]
+    fn test_parse_log_rotation() {
+        // Ensure the log file is not rotated by default.
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert_eq!(config.parse_log_rotation(), LogRotation::default());
+        assert!(!config.parse_log_rotation().is_enabled());
+        assert_eq!(config.parse_log_file_status().format, "text");
+
+        // Ensure the rotation settings are parsed, and reported in the node status.
+        let config = Start::try_parse_from(
+            [
+                "snarkos",
+                "--logfile",
+                "snarkos.log",
+                "--logfile-max-size",
+                "0",
+                "--logfile-daily",
+                "--logfile-max-files",
+                "3",
+                "--logfile-compress",
+                "--logfile-json",
+            ]
+            .iter(),
+        )
+        .unwrap();
+        let rotation = LogRotation { max_file_size: Some(1), daily: true, max_files: 3, compress: true };
+        assert_eq!(config.parse_log_rotation(), rotation);
+        let status = config.parse_log_file_status();
+        assert_eq!(status.path, PathBuf::from("snarkos.log"));
+        assert_eq!(status.format, "json");
+        assert_eq!((status.max_file_size, status.daily, status.max_files, status.compress), (Some(1), true, 3, true));
+    }
+
     #[test]
     fn test_parse_development_and_genesis() {
         let prod_genesis = Block::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
@@ -943,6 +1616,34 @@ mod tests {
         config.parse_development(&mut trusted_peers, &mut trusted_validators).unwrap();
         assert!(config.rest.is_none());
 
+        // Ensure an explicit flag that conflicts with a derived port is rejected.
+        let mut trusted_peers = vec![];
+        let mut trusted_validators = vec![];
+        let mut config = Start::try_parse_from(["snarkos", "--dev", "1", "--rest", "0.0.0.0:4131"].iter()).unwrap();
+        let error = config.parse_development(&mut trusted_peers, &mut trusted_validators).unwrap_err();
+        assert!(error.to_string().contains("'--node'"));
+
+        // Ensure the validator and metrics ports are derived from the development ID.
+        let mut trusted_peers = vec![];
+        let mut trusted_validators = vec![];
+        let args = ["snarkos", "--dev", "2", "--validator", "--metrics"];
+        let mut config = Start::try_parse_from(args.iter()).unwrap();
+        config.parse_development(&mut trusted_peers, &mut trusted_validators).unwrap();
+        assert_eq!(config.bft, Some(SocketAddr::from_str("127.0.0.1:5002").unwrap()));
+        assert_eq!(config.metrics_ip, Some(SocketAddr::from_str("0.0.0.0:9002").unwrap()));
+        assert_eq!(trusted_peers, vec![
+            SocketAddr::from_str("127.0.0.1:4130").unwrap(),
+            "127.0.0.1:4131".parse().unwrap()
+        ]);
+
+        // Ensure the default log file is suffixed with the development ID, but an explicit log file is not.
+        let mut config = Start::try_parse_from(["snarkos", "--dev", "2"].iter()).unwrap();
+        config.parse_log_file().unwrap();
+        assert_eq!(config.logfile, std::env::temp_dir().join("snarkos-dev-2.log"));
+        let mut config = Start::try_parse_from(["snarkos", "--dev", "2", "--logfile", "/tmp/node.log"].iter()).unwrap();
+        config.parse_log_file().unwrap();
+        assert_eq!(config.logfile, PathBuf::from("/tmp/node.log"));
+
         let mut trusted_peers = vec![];
         let mut trusted_validators = vec![];
         let mut config = Start::try_parse_from(["snarkos", "--dev", "0"].iter()).unwrap();
@@ -1003,6 +1704,189 @@ mod tests {
         assert_eq!(genesis, expected_genesis);
     }
 
+    #[test]
+    fn test_parse_dev_committee() {
+        use snarkvm::ledger::{Ledger, store::helpers::memory::ConsensusMemory};
+
+        /// Returns the stakes of the committee of the given genesis block, in descending order.
+        fn committee_stakes(genesis: Block<CurrentNetwork>) -> Vec<u64> {
+            let ledger =
+                Ledger::<CurrentNetwork, ConsensusMemory<CurrentNetwork>>::load(genesis, StorageMode::Production);
+            let committee = ledger.unwrap().get_committee(0).unwrap().unwrap();
+            let mut stakes = committee.members().values().map(|(stake, _, _)| *stake).collect::<Vec<_>>();
+            stakes.sort_unstable_by(|a, b| b.cmp(a));
+            stakes
+        }
+
+        // Generate a devnet with a non-uniform stake distribution, in ascending order of stake.
+        let mut rng = ChaChaRng::seed_from_u64(DEVELOPMENT_MODE_RNG_SEED);
+        let bonded_balances = (1..=5u64)
+            .map(|i| {
+                let address = Address::try_from(PrivateKey::<CurrentNetwork>::new(&mut rng).unwrap()).unwrap();
+                (address.to_string(), (address.to_string(), address.to_string(), MIN_VALIDATOR_STAKE * i))
+            })
+            .collect::<IndexMap<_, _>>();
+        let bonded_balances = serde_json::to_string(&bonded_balances).unwrap();
+        let args = ["snarkos", "--dev", "0", "--dev-num-validators", "5", "--dev-bonded-balances", &bonded_balances];
+        let source_genesis = Start::try_parse_from(args.iter()).unwrap().parse_genesis::<CurrentNetwork>().unwrap();
+
+        // Snapshot the committee of the devnet.
+        let ledger = Ledger::<CurrentNetwork, ConsensusMemory<CurrentNetwork>>::load(
+            source_genesis.clone(),
+            StorageMode::Production,
+        )
+        .unwrap();
+        let spec = CommitteeSpec::new(0, &ledger.get_committee(0).unwrap().unwrap()).unwrap();
+        let path = std::env::temp_dir().join(format!("snarkos-committee-{}.json", rand::random::<u64>()));
+        spec.store(&path).unwrap();
+
+        // Ensure the members are mapped to the development validators in descending order of stake.
+        let dev_stakes = spec.mapping.iter().map(|member| member.stake).collect::<Vec<_>>();
+        assert_eq!(dev_stakes, (1..=5u64).rev().map(|i| MIN_VALIDATOR_STAKE * i).collect::<Vec<_>>());
+        assert!(spec.mapping.iter().all(|member| member.stake == member.source_stake));
+        assert_eq!(spec.mapping[4].dev_address, spec.mapping[0].source_address);
+
+        // Regenerate a devnet from the committee file, and ensure the stake distribution matches.
+        let path_arg = path.to_str().unwrap();
+        let config = Start::try_parse_from(["snarkos", "--dev", "0", "--dev-committee", path_arg].iter()).unwrap();
+        let genesis = config.parse_genesis::<CurrentNetwork>().unwrap();
+        assert_ne!(genesis, source_genesis);
+        assert_eq!(committee_stakes(genesis), committee_stakes(source_genesis));
+
+        // Ensure the committee file cannot be combined with custom bonded balances.
+        let args = ["snarkos", "--dev", "0", "--dev-committee", path_arg, "--dev-bonded-balances", &bonded_balances];
+        assert!(Start::try_parse_from(args.iter()).unwrap().parse_genesis::<CurrentNetwork>().is_err());
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    /// Returns the path to a fresh directory in the temporary directory.
+    fn sample_dir() -> PathBuf {
+        let path = std::env::temp_dir().join(format!("snarkos-check-{}", rand::random::<u64>()));
+        std::fs::create_dir_all(&path).unwrap();
+        #[cfg(target_family = "unix")]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o700)).unwrap();
+        }
+        path
+    }
+
+    /// Returns the configurations to check, with the REST server disabled and the given storage.
+    fn sample_check_config(storage: &std::path::Path, args: &[&str]) -> Start {
+        let storage = storage.to_str().unwrap();
+        let default_args = ["snarkos", "--check", "--norest", "--storage", storage];
+        Start::try_parse_from(default_args.iter().chain(args)).unwrap()
+    }
+
+    #[test]
+    fn test_check_bad_key_file() {
+        let dir = sample_dir();
+        // Write a malformed private key, readable only by the owner.
+        let key_path = dir.join("private-key");
+        std::fs::write(&key_path, "APrivateKey1invalid").unwrap();
+        #[cfg(target_family = "unix")]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&key_path, std::fs::Permissions::from_mode(0o600)).unwrap();
+        }
+
+        let key_path = key_path.to_str().unwrap();
+        let config = sample_check_config(&dir.join("ledger"), &[
+            "--client",
+            "--node",
+            "127.0.0.1:0",
+            "--private-key-file",
+            key_path,
+        ]);
+        let report = config.check::<CurrentNetwork>();
+
+        // Ensure only the account check fails.
+        assert!(!report.is_success());
+        assert!(report.failure("account").unwrap().contains("The provided private key is invalid"));
+        assert_eq!(report.entries().iter().filter(|entry| entry.result.is_err()).count(), 1);
+        // Ensure the missing ledger was not created.
+        assert!(!dir.join("ledger").exists());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_check_occupied_port() {
+        let dir = sample_dir();
+        // Occupy a port.
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+
+        let config = sample_check_config(&dir.join("ledger"), &["--dev", "0", "--client", "--node", &addr]);
+        let report = config.check::<CurrentNetwork>();
+
+        // Ensure only the node port check fails.
+        assert!(report.failure("node port").unwrap().contains(&format!("Unable to listen on {addr}")));
+        assert_eq!(report.entries().iter().filter(|entry| entry.result.is_err()).count(), 1);
+
+        // Ensure the check passes once the port is released.
+        drop(listener);
+        let report = config.check::<CurrentNetwork>();
+        assert!(report.is_success(), "{report}");
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_check_incompatible_storage() {
+        let dir = sample_dir();
+
+        // Ensure a directory that is not a ledger is rejected.
+        let ledger_path = dir.join("garbage");
+        std::fs::create_dir_all(&ledger_path).unwrap();
+        std::fs::write(ledger_path.join("CURRENT"), "garbage").unwrap();
+        let report = sample_check_config(&ledger_path, &["--dev", "0", "--client", "--node", "127.0.0.1:0"])
+            .check::<CurrentNetwork>();
+        assert!(report.failure("storage").unwrap().contains("is not in a supported storage format"));
+
+        // Ensure a ledger of another network is rejected.
+        let ledger_path = dir.join("testnet");
+        {
+            let database = rocksdb::DB::open_default(&ledger_path).unwrap();
+            let mut key = TestnetV0::ID.to_le_bytes().to_vec();
+            key.extend_from_slice(&[0u8; 4]);
+            database.put(key, [0u8]).unwrap();
+        }
+        let report = sample_check_config(&ledger_path, &["--dev", "0", "--client", "--node", "127.0.0.1:0"])
+            .check::<CurrentNetwork>();
+        let expected = format!("belongs to network {}, not network {}", TestnetV0::ID, CurrentNetwork::ID);
+        assert!(report.failure("storage").unwrap().contains(&expected));
+        // Ensure the same ledger passes for its own network.
+        let report = sample_check_config(&ledger_path, &["--dev", "0", "--client", "--node", "127.0.0.1:0"])
+            .check::<TestnetV0>();
+        assert!(report.failure("storage").is_none());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_parse_account() {
+        let mut rng = ChaChaRng::seed_from_u64(DEVELOPMENT_MODE_RNG_SEED);
+        let private_key = PrivateKey::<CurrentNetwork>::new(&mut rng).unwrap();
+        let view_key = snarkvm::prelude::ViewKey::try_from(&private_key).unwrap();
+        let address = Address::try_from(&private_key).unwrap();
+
+        // Ensure a valid private key is parsed, and derives the expected address.
+        let account = parse_account::<CurrentNetwork>(&private_key.to_string()).unwrap();
+        assert_eq!(account.address(), address);
+
+        // Ensure a view key, an address, or a malformed key is rejected.
+        assert!(parse_account::<CurrentNetwork>(&view_key.to_string()).is_err());
+        assert!(parse_account::<CurrentNetwork>(&address.to_string()).is_err());
+        assert!(parse_account::<CurrentNetwork>("APrivateKey1invalid").is_err());
+
+        // Ensure the same key derives the same address on another network, as keys do not encode a network,
+        // which is why the network of the account is only checked against the ledger.
+        let account = parse_account::<TestnetV0>(&private_key.to_string()).unwrap();
+        assert_eq!(account.address().to_string(), address.to_string());
+    }
+
     #[test]
     fn clap_snarkos_start() {
         let arg_vec = vec![
@@ -0,0 +1,559 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkos_node_rest::HttpMethod;
+use snarkvm::{
+    console::network::{CanaryV0, MainnetV0, Network, TestnetV0},
+    ledger::block::{Block, Transaction, Transactions},
+};
+
+use anyhow::{Result, anyhow, bail, ensure};
+use clap::{Parser, ValueEnum};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// The number of blocks per block range requested by the explorer profile.
+const EXPLORER_BLOCK_RANGE: u32 = 10;
+/// The multiplier spreading the requests of the explorer profile over the blocks.
+const EXPLORER_SPREAD: u64 = 7_919;
+/// The duration to back off for after a `429`, if the response has no valid `Retry-After` header.
+const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(1);
+/// The timeout of a single request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The request profiles of the REST benchmark.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Profile {
+    /// A mix of requests for the latest block, block height, and state root.
+    ReadHeavy,
+    /// Requests for block ranges and the transactions of blocks, as made by an explorer.
+    Explorer,
+    /// Broadcasts of the pre-signed transactions in the `--transactions` file.
+    Broadcast,
+}
+
+impl Profile {
+    /// Returns the name of the profile.
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::ReadHeavy => "read-heavy",
+            Self::Explorer => "explorer",
+            Self::Broadcast => "broadcast",
+        }
+    }
+
+    /// Returns the endpoints requested by the profile, as listed in the OpenAPI specification of the node.
+    const fn endpoints(&self) -> &'static [(HttpMethod, &'static str)] {
+        match self {
+            Self::ReadHeavy => &[
+                (HttpMethod::Get, "/block/height/latest"),
+                (HttpMethod::Get, "/block/latest"),
+                (HttpMethod::Get, "/stateRoot/latest"),
+            ],
+            Self::Explorer => &[
+                (HttpMethod::Get, "/block/height/latest"),
+                (HttpMethod::Get, "/blocks"),
+                (HttpMethod::Get, "/block/{height_or_hash}/transactions"),
+            ],
+            Self::Broadcast => &[(HttpMethod::Post, "/transaction/broadcast")],
+        }
+    }
+
+    /// Ensures the endpoints requested by the profile are still served by the node,
+    /// according to the given OpenAPI specification of the node.
+    fn ensure_documented<N: Network>(&self, spec: &serde_json::Value) -> Result<()> {
+        let network = network_name::<N>()?;
+        for (method, path) in self.endpoints() {
+            ensure!(
+                spec["paths"][format!("/{network}{path}")][method.as_str()].is_object(),
+                "The '{}' profile requests '{} {path}', which is no longer served by the REST API",
+                self.name(),
+                method.as_str().to_uppercase()
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Drives load against the REST API of a node, to validate its rate limiting and capacity settings.
+///
+/// The report lists the latency percentiles, the distribution of the status codes, and the achieved requests
+/// per second. A `429` is honored by backing off for its `Retry-After`. The responses are decoded into the
+/// types of the node, so that a drift of the REST schema surfaces as decode errors.
+#[derive(Debug, Parser)]
+pub struct RestBench {
+    /// Specify the base URL of the REST API, e.g. 'http://127.0.0.1:3030'.
+    #[clap(long = "url")]
+    pub url: String,
+    /// Specify the network of the node.
+    #[clap(default_value = "0", long = "network")]
+    pub network: u16,
+    /// Specify the request profile.
+    #[clap(long = "profile")]
+    pub profile: Profile,
+    /// Specify the duration of the benchmark, in seconds.
+    #[clap(default_value = "30", long = "duration")]
+    pub duration: u64,
+    /// Specify the number of concurrent requests.
+    #[clap(default_value = "8", long = "concurrency")]
+    pub concurrency: usize,
+    /// Specify the file of pre-signed transactions to broadcast, with one JSON transaction per line.
+    #[clap(long = "transactions")]
+    pub transactions: Option<PathBuf>,
+    /// Confirm that the broadcast profile submits the transactions to the network of the node.
+    #[clap(long = "i-know-this-submits-transactions")]
+    pub i_know_this_submits_transactions: bool,
+}
+
+impl RestBench {
+    /// Runs the benchmark, and returns its report.
+    pub fn parse(self) -> Result<String> {
+        let report = match self.network {
+            MainnetV0::ID => self.run::<MainnetV0>()?,
+            TestnetV0::ID => self.run::<TestnetV0>()?,
+            CanaryV0::ID => self.run::<CanaryV0>()?,
+            unknown_id => bail!("Unknown network ID ({unknown_id})"),
+        };
+        Ok(serde_json::to_string_pretty(&report)?)
+    }
+
+    /// Runs the benchmark against the REST API of a node on the given network.
+    pub fn run<N: Network>(&self) -> Result<BenchReport> {
+        ensure!(self.duration > 0, "The duration must be at least 1 second");
+        ensure!(self.concurrency > 0, "The concurrency must be at least 1");
+        // Refuse to submit transactions, unless the operator explicitly consented to it.
+        if self.profile == Profile::Broadcast && !self.i_know_this_submits_transactions {
+            bail!(
+                "The 'broadcast' profile submits transactions to '{}' - pass '--i-know-this-submits-transactions' \
+                 to proceed",
+                self.url
+            );
+        }
+
+        let base_url = format!("{}/{}", self.url.trim_end_matches('/'), network_name::<N>()?);
+        let agent = ureq::AgentBuilder::new().timeout(REQUEST_TIMEOUT).build();
+        let workload = Workload::new::<N>(self.profile, &agent, &base_url, self.transactions.as_deref())?;
+        // Ensure the node still serves the endpoints of the profile, according to its OpenAPI specification.
+        let spec: serde_json::Value = agent
+            .get(&format!("{base_url}/openapi.json"))
+            .call()
+            .map_err(|error| anyhow!("Failed to fetch the OpenAPI specification of the node - {error}"))?
+            .into_json()?;
+        self.profile.ensure_documented::<N>(&spec)?;
+
+        // Drive the load from the workers, until the deadline.
+        let counter = AtomicU64::new(0);
+        let start = Instant::now();
+        let deadline = start + Duration::from_secs(self.duration);
+        let samples = thread::scope(|scope| {
+            let workers: Vec<_> = (0..self.concurrency)
+                .map(|_| scope.spawn(|| run_worker::<N>(&agent, &base_url, &workload, &counter, deadline)))
+                .collect();
+            workers
+                .into_iter()
+                .map(|worker| worker.join().map_err(|_| anyhow!("A benchmark worker panicked")))
+                .collect::<Result<Vec<_>>>()
+        })?;
+        Ok(BenchReport::new(self.profile, self.concurrency, start.elapsed(), samples))
+    }
+}
+
+/// Returns the name of the given network, as used in the paths of the REST API.
+fn network_name<N: Network>() -> Result<&'static str> {
+    match N::ID {
+        MainnetV0::ID => Ok("mainnet"),
+        TestnetV0::ID => Ok("testnet"),
+        CanaryV0::ID => Ok("canary"),
+        unknown_id => bail!("Unknown network ID ({unknown_id})"),
+    }
+}
+
+/// The state of a request profile, prepared prior to the benchmark.
+struct Workload {
+    /// The request profile.
+    profile: Profile,
+    /// The latest block height, when the benchmark started.
+    latest_height: u32,
+    /// The JSON bodies of the transactions to broadcast.
+    transactions: Vec<String>,
+}
+
+/// A request of the benchmark.
+struct BenchRequest<'a> {
+    /// The path, relative to the network prefix.
+    path: String,
+    /// The JSON body to post, if any.
+    body: Option<&'a str>,
+    /// Returns `true` if the body of a successful response decodes into the expected type.
+    decodes: fn(&str) -> bool,
+}
+
+impl BenchRequest<'_> {
+    /// Initializes a `GET` request.
+    fn get(path: impl Into<String>, decodes: fn(&str) -> bool) -> Self {
+        Self { path: path.into(), body: None, decodes }
+    }
+}
+
+/// Returns `true` if the given JSON decodes into the given type.
+fn decodes<T: DeserializeOwned>(json: &str) -> bool {
+    serde_json::from_str::<T>(json).is_ok()
+}
+
+impl Workload {
+    /// Prepares the given request profile.
+    fn new<N: Network>(
+        profile: Profile,
+        agent: &ureq::Agent,
+        base_url: &str,
+        transactions: Option<&Path>,
+    ) -> Result<Self> {
+        let (latest_height, transactions) = match profile {
+            Profile::ReadHeavy => (0, vec![]),
+            Profile::Explorer => {
+                let latest_height = agent.get(&format!("{base_url}/block/height/latest")).call()?.into_json()?;
+                (latest_height, vec![])
+            }
+            Profile::Broadcast => {
+                let Some(path) = transactions else {
+                    bail!("The 'broadcast' profile requires the '--transactions' file");
+                };
+                // Ensure the transactions are well-formed, so that a malformed file is not reported as rejections.
+                let transactions = fs::read_to_string(path)?
+                    .lines()
+                    .filter(|line| !line.trim().is_empty())
+                    .map(|line| {
+                        serde_json::from_str::<Transaction<N>>(line)?;
+                        Ok(line.to_string())
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                ensure!(!transactions.is_empty(), "The file '{}' has no transactions", path.display());
+                (0, transactions)
+            }
+        };
+        Ok(Self { profile, latest_height, transactions })
+    }
+
+    /// Returns the request of the given index.
+    fn request<N: Network>(&self, index: u64) -> BenchRequest<'_> {
+        match self.profile {
+            Profile::ReadHeavy => match index % 3 {
+                0 => BenchRequest::get("/block/height/latest", decodes::<u32>),
+                1 => BenchRequest::get("/block/latest", decodes::<Block<N>>),
+                _ => BenchRequest::get("/stateRoot/latest", decodes::<N::StateRoot>),
+            },
+            Profile::Explorer => {
+                // Spread the requests over the blocks, so that they are not served from a single cached block.
+                let num_blocks = self.latest_height as u64 + 1;
+                let height = (index.wrapping_mul(EXPLORER_SPREAD) % num_blocks) as u32;
+                match index % 2 {
+                    0 => {
+                        let end = height.saturating_add(EXPLORER_BLOCK_RANGE).min(self.latest_height.saturating_add(1));
+                        BenchRequest::get(format!("/blocks?start={height}&end={end}"), decodes::<Vec<Block<N>>>)
+                    }
+                    _ => BenchRequest::get(format!("/block/{height}/transactions"), decodes::<Transactions<N>>),
+                }
+            }
+            Profile::Broadcast => BenchRequest {
+                path: "/transaction/broadcast".to_string(),
+                body: Some(&self.transactions[(index % self.transactions.len() as u64) as usize]),
+                decodes: decodes::<N::TransactionID>,
+            },
+        }
+    }
+}
+
+/// The samples collected by a worker of the benchmark.
+#[derive(Default)]
+struct Samples {
+    /// The latencies of the responses.
+    latencies: Vec<Duration>,
+    /// The number of responses per status code.
+    status_codes: BTreeMap<u16, u64>,
+    /// The number of successful responses that did not decode into the expected type.
+    num_decode_errors: u64,
+    /// The number of requests that failed without a response.
+    num_transport_errors: u64,
+    /// The total time backed off for, as requested by `Retry-After`.
+    retry_after_wait: Duration,
+}
+
+/// Sends the requests of the given workload, until the deadline.
+fn run_worker<N: Network>(
+    agent: &ureq::Agent,
+    base_url: &str,
+    workload: &Workload,
+    counter: &AtomicU64,
+    deadline: Instant,
+) -> Samples {
+    let mut samples = Samples::default();
+    while Instant::now() < deadline {
+        let request = workload.request::<N>(counter.fetch_add(1, Ordering::Relaxed));
+        let url = format!("{base_url}{}", request.path);
+        let start = Instant::now();
+        let result = match request.body {
+            Some(body) => agent.post(&url).set("Content-Type", "application/json").send_string(body),
+            None => agent.get(&url).call(),
+        };
+        match result {
+            Ok(response) => {
+                let status = response.status();
+                let body = response.into_string();
+                samples.latencies.push(start.elapsed());
+                *samples.status_codes.entry(status).or_default() += 1;
+                if !body.is_ok_and(|body| (request.decodes)(&body)) {
+                    samples.num_decode_errors += 1;
+                }
+            }
+            Err(ureq::Error::Status(status, response)) => {
+                samples.latencies.push(start.elapsed());
+                *samples.status_codes.entry(status).or_default() += 1;
+                // Back off for as long as the server requests, without overrunning the deadline.
+                if status == 429 {
+                    let wait = retry_after(&response).min(deadline.saturating_duration_since(Instant::now()));
+                    samples.retry_after_wait += wait;
+                    thread::sleep(wait);
+                }
+            }
+            Err(ureq::Error::Transport(_)) => samples.num_transport_errors += 1,
+        }
+    }
+    samples
+}
+
+/// Returns the duration to back off for, as requested by the `Retry-After` header of the given response.
+fn retry_after(response: &ureq::Response) -> Duration {
+    response
+        .header("retry-after")
+        .and_then(|seconds| seconds.trim().parse().ok())
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_RETRY_AFTER)
+}
+
+/// The latency percentiles of the responses, in milliseconds.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct LatencyPercentiles {
+    pub p50: f64,
+    pub p90: f64,
+    pub p99: f64,
+    pub max: f64,
+}
+
+impl LatencyPercentiles {
+    /// Returns the percentiles of the given latencies.
+    fn new(mut latencies: Vec<Duration>) -> Self {
+        latencies.sort_unstable();
+        // Note: The nearest-rank method is used, so each percentile is an observed latency.
+        let percentile = |percentile: f64| match latencies.len() {
+            0 => 0.0,
+            len => {
+                let rank = (percentile / 100.0 * len as f64).ceil() as usize;
+                latencies[rank.clamp(1, len) - 1].as_secs_f64() * 1000.0
+            }
+        };
+        Self { p50: percentile(50.0), p90: percentile(90.0), p99: percentile(99.0), max: percentile(100.0) }
+    }
+}
+
+/// The report of a REST benchmark.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct BenchReport {
+    /// The request profile.
+    pub profile: String,
+    /// The number of concurrent requests.
+    pub concurrency: usize,
+    /// The elapsed time of the benchmark, in seconds.
+    pub elapsed_secs: f64,
+    /// The number of responses, including the rate-limited ones.
+    pub num_responses: u64,
+    /// The number of responses per second, including the rate-limited ones.
+    pub achieved_rps: f64,
+    /// The number of successful (`2xx`) responses per second.
+    pub achieved_success_rps: f64,
+    /// The number of responses per status code.
+    pub status_codes: BTreeMap<u16, u64>,
+    /// The number of rate-limited (`429`) responses.
+    pub num_rate_limited: u64,
+    /// The total time the workers backed off for, as requested by `Retry-After`, in seconds.
+    pub retry_after_wait_secs: f64,
+    /// The number of successful responses that did not decode into the types of the node.
+    pub num_decode_errors: u64,
+    /// The number of requests that failed without a response.
+    pub num_transport_errors: u64,
+    /// The latency percentiles of the responses.
+    pub latency_ms: LatencyPercentiles,
+}
+
+impl BenchReport {
+    /// Aggregates the samples of the workers into the report.
+    fn new(profile: Profile, concurrency: usize, elapsed: Duration, samples: Vec<Samples>) -> Self {
+        let mut latencies = Vec::new();
+        let mut status_codes = BTreeMap::new();
+        let (mut num_decode_errors, mut num_transport_errors, mut retry_after_wait) = (0, 0, Duration::ZERO);
+        for sample in samples {
+            latencies.extend(sample.latencies);
+            for (status, count) in sample.status_codes {
+                *status_codes.entry(status).or_default() += count;
+            }
+            num_decode_errors += sample.num_decode_errors;
+            num_transport_errors += sample.num_transport_errors;
+            retry_after_wait += sample.retry_after_wait;
+        }
+        let num_responses = status_codes.values().sum();
+        let num_successes: u64 = status_codes.range(200..300).map(|(_, count)| count).sum();
+        let elapsed_secs = elapsed.as_secs_f64().max(f64::EPSILON);
+        Self {
+            profile: profile.name().to_string(),
+            concurrency,
+            elapsed_secs,
+            num_responses,
+            achieved_rps: num_responses as f64 / elapsed_secs,
+            achieved_success_rps: num_successes as f64 / elapsed_secs,
+            num_rate_limited: status_codes.get(&429).copied().unwrap_or_default(),
+            status_codes,
+            retry_after_wait_secs: retry_after_wait.as_secs_f64(),
+            num_decode_errors,
+            num_transport_errors,
+            latency_ms: LatencyPercentiles::new(latencies),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkos_account::Account;
+    use snarkos_node::Client;
+    use snarkvm::{ledger::store::helpers::memory::ConsensusMemory, prelude::FromBytes};
+
+    use aleo_std::StorageMode;
+    use std::{net::TcpListener, str::FromStr};
+
+    type CurrentNetwork = MainnetV0;
+
+    /// Returns a benchmark of the given profile against the given URL.
+    fn bench(url: &str, profile: Profile, duration: u64, concurrency: usize) -> RestBench {
+        RestBench {
+            url: url.to_string(),
+            network: CurrentNetwork::ID,
+            profile,
+            duration,
+            concurrency,
+            transactions: None,
+            i_know_this_submits_transactions: false,
+        }
+    }
+
+    #[test]
+    fn test_broadcast_requires_consent() {
+        // Ensure the broadcast profile is refused without the flag, before any request is sent.
+        let error = bench("http://127.0.0.1:1", Profile::Broadcast, 1, 1).run::<CurrentNetwork>().unwrap_err();
+        assert!(error.to_string().contains("--i-know-this-submits-transactions"));
+
+        // Ensure the broadcast profile proceeds with the flag, and requires the transactions.
+        let mut bench = bench("http://127.0.0.1:1", Profile::Broadcast, 1, 1);
+        bench.i_know_this_submits_transactions = true;
+        let error = bench.run::<CurrentNetwork>().unwrap_err();
+        assert!(error.to_string().contains("--transactions"));
+    }
+
+    #[test]
+    fn test_latency_percentiles() {
+        let latencies = (1..=100).rev().map(Duration::from_millis).collect();
+        let percentiles = LatencyPercentiles::new(latencies);
+        assert_eq!(percentiles, LatencyPercentiles { p50: 50.0, p90: 90.0, p99: 99.0, max: 100.0 });
+        assert_eq!(LatencyPercentiles::new(vec![]), LatencyPercentiles::default());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_read_heavy_against_dev_node() {
+        // Reserve a port for the REST server.
+        let rest_ip = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+        // Start a node, with a low rate limit.
+        let rest_rps = 5;
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let account =
+            Account::<CurrentNetwork>::from_str("APrivateKey1zkp2oVPTci9kKcUprnbzMwq95Di1MQERpYBhEeqvkrDirK1");
+        let _node = Client::<CurrentNetwork, ConsensusMemory<CurrentNetwork>>::new(
+            "127.0.0.1:0".parse().unwrap(),
+            Some(rest_ip),
+            rest_rps,
+            None, // No broadcast journal.
+            0,    // No recent block summaries.
+            None, // No log file.
+            account.unwrap(),
+            &[],
+            None,               // The default peer limits.
+            Default::default(), // The default handshake limits.
+            None,               // No maximum pool memory.
+            false,              // No strict configuration check.
+            genesis,
+            None, // No CDN.
+            StorageMode::Production,
+            false, // No write verification.
+            false, // No extra peer rotation.
+            false, // No early block announcements.
+            Default::default(),
+            Default::default(),
+        )
+        .await
+        .unwrap();
+
+        // Ensure the endpoints of every profile are listed in the OpenAPI specification of the node.
+        let spec_url = format!("http://{rest_ip}/mainnet/openapi.json");
+        let spec = tokio::task::spawn_blocking(move || {
+            ureq::get(&spec_url).call().unwrap().into_json::<serde_json::Value>().unwrap()
+        })
+        .await
+        .unwrap();
+        for profile in Profile::value_variants() {
+            profile.ensure_documented::<CurrentNetwork>(&spec).unwrap();
+        }
+
+        // Run the read-heavy profile.
+        let bench = bench(&format!("http://{rest_ip}"), Profile::ReadHeavy, 3, 4);
+        let report = tokio::task::spawn_blocking(move || bench.run::<CurrentNetwork>()).await.unwrap().unwrap();
+
+        // Ensure the report is consistent.
+        assert_eq!(report.profile, "read-heavy");
+        assert_eq!(report.concurrency, 4);
+        assert!(report.elapsed_secs >= 3.0);
+        assert_eq!(report.num_responses, report.status_codes.values().sum::<u64>());
+        assert!(report.achieved_rps > 0.0);
+        assert_eq!(report.num_transport_errors, 0);
+        let latency = &report.latency_ms;
+        assert!(latency.p50 <= latency.p90 && latency.p90 <= latency.p99 && latency.p99 <= latency.max);
+
+        // Ensure the successful responses decode into the types of the node.
+        assert!(report.status_codes.get(&200).is_some_and(|count| *count > 0));
+        assert_eq!(report.num_decode_errors, 0);
+
+        // Ensure the rate limit is observed, and honored by backing off.
+        assert!(report.num_rate_limited > 0);
+        assert!(report.retry_after_wait_secs > 0.0);
+        assert!(report.achieved_success_rps < 4.0 * rest_rps as f64);
+
+        // Ensure the report round-trips as JSON.
+        let json = serde_json::to_string_pretty(&report).unwrap();
+        assert_eq!(serde_json::from_str::<BenchReport>(&json).unwrap(), report);
+    }
+}
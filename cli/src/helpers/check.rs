@@ -0,0 +1,134 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use aleo_std::StorageMode;
+use anyhow::{Result, anyhow, bail, ensure};
+use colored::Colorize;
+use std::{
+    fmt,
+    net::{SocketAddr, TcpListener, ToSocketAddrs},
+};
+
+/// A single entry of a configuration check.
+pub struct CheckEntry {
+    /// The name of the check.
+    pub name: &'static str,
+    /// The outcome of the check, with a description of what was checked.
+    pub result: Result<String>,
+}
+
+/// The report of a configuration check, in the order the checks were performed.
+#[derive(Default)]
+pub struct CheckReport {
+    entries: Vec<CheckEntry>,
+}
+
+impl CheckReport {
+    /// Records the outcome of the given check.
+    pub fn record(&mut self, name: &'static str, result: Result<String>) {
+        self.entries.push(CheckEntry { name, result });
+    }
+
+    /// Returns the entries of the report.
+    pub fn entries(&self) -> &[CheckEntry] {
+        &self.entries
+    }
+
+    /// Returns the error of the first failed check with the given name, if any.
+    pub fn failure(&self, name: &str) -> Option<String> {
+        self.entries.iter().filter(|entry| entry.name == name).find_map(|entry| match &entry.result {
+            Ok(_) => None,
+            Err(error) => Some(error.to_string()),
+        })
+    }
+
+    /// Returns `true` if every check passed.
+    pub fn is_success(&self) -> bool {
+        self.entries.iter().all(|entry| entry.result.is_ok())
+    }
+}
+
+impl fmt::Display for CheckReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Align the descriptions on the longest name.
+        let width = self.entries.iter().map(|entry| entry.name.len()).max().unwrap_or_default();
+        for entry in &self.entries {
+            match &entry.result {
+                Ok(description) => writeln!(f, "✅ {:width$}  {}", entry.name.bold(), description)?,
+                Err(error) => writeln!(f, "❌ {:width$}  {}", entry.name.bold(), error.to_string().red())?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Ensures each of the comma-separated peers supplied to the given flag is an IP address.
+/// Any hostname is resolved, so that the operator is told whether it is a typo or merely unsupported.
+pub fn check_peers(flag: &str, peers: &str) -> Result<String> {
+    let mut num_peers = 0;
+    for peer in peers.split(',').filter(|peer| !peer.is_empty()) {
+        if peer.parse::<SocketAddr>().is_err() {
+            match peer.to_socket_addrs().map(|mut addrs| addrs.next()) {
+                Ok(Some(addr)) => {
+                    bail!(
+                        "The hostname '{peer}' supplied to {flag} resolves to {addr}, but only IP addresses are supported"
+                    )
+                }
+                Ok(None) => bail!("The peer '{peer}' supplied to {flag} does not resolve to any address"),
+                Err(error) => bail!("The peer '{peer}' supplied to {flag} is malformed - {error}"),
+            }
+        }
+        num_peers += 1;
+    }
+    Ok(format!("Parsed {num_peers} peer(s) from {flag}"))
+}
+
+/// Ensures the given address can be listened on, by binding and immediately releasing it.
+pub fn check_listener(addr: SocketAddr) -> Result<String> {
+    let listener = TcpListener::bind(addr).map_err(|error| anyhow!("Unable to listen on {addr} - {error}"))?;
+    drop(listener);
+    Ok(format!("Able to listen on {addr}"))
+}
+
+/// Ensures the ledger in the given storage, if any, is readable and belongs to the given network.
+///
+/// The ledger is opened read-only, so no files are created or modified, even if the ledger is missing.
+pub fn check_storage(network: u16, mode: StorageMode) -> Result<String> {
+    let path = aleo_std::aleo_ledger_dir(network, mode);
+    if !path.exists() {
+        return Ok(format!("No ledger found at {}, a new ledger will be initialized", path.display()));
+    }
+    ensure!(path.is_dir(), "The ledger path {} is not a directory", path.display());
+
+    // Open the ledger, which fails if it is not a database in a format supported by this build.
+    let database = rocksdb::DB::open_for_read_only(&rocksdb::Options::default(), &path, false)
+        .map_err(|error| anyhow!("The ledger at {} is not in a supported storage format - {error}", path.display()))?;
+
+    // Every key in the ledger is prefixed with the ID of the network, in little-endian.
+    match database.iterator(rocksdb::IteratorMode::Start).next() {
+        None => Ok(format!("Found an empty ledger at {}", path.display())),
+        Some(Err(error)) => bail!("Unable to read the ledger at {} - {error}", path.display()),
+        Some(Ok((key, _))) => {
+            ensure!(key.len() >= 2, "The ledger at {} is not in a supported storage format", path.display());
+            let ledger_network = u16::from_le_bytes([key[0], key[1]]);
+            ensure!(
+                ledger_network == network,
+                "The ledger at {} belongs to network {ledger_network}, not network {network}",
+                path.display()
+            );
+            Ok(format!("Found a ledger for network {network} at {}", path.display()))
+        }
+    }
+}
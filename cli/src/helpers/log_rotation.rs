@@ -0,0 +1,463 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use flate2::{Compression, write::GzEncoder};
+use parking_lot::{Mutex, MutexGuard};
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, mpsc},
+    time::{Duration, Instant},
+};
+use time::{Date, OffsetDateTime};
+use tracing_subscriber::fmt::MakeWriter;
+
+/// The default number of rotated log files that are retained.
+pub const DEFAULT_MAX_LOG_FILES: usize = 10;
+/// The interval at which the log file is checked for having been removed out from under the node.
+const LOG_FILE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+/// The extension of the compressed rotated log files.
+const COMPRESSED_EXTENSION: &str = "gz";
+/// The extension of the rotated log files that are being compressed.
+const PARTIAL_EXTENSION: &str = "partial";
+
+/// The rotation and retention settings of the log file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogRotation {
+    /// The maximum size in bytes of the log file before it is rotated, if rotating by size.
+    pub max_file_size: Option<u64>,
+    /// Whether the log file is rotated when the (UTC) day changes.
+    pub daily: bool,
+    /// The number of rotated log files that are retained; the oldest are deleted beyond it.
+    pub max_files: usize,
+    /// Whether the rotated log files are compressed with gzip.
+    pub compress: bool,
+}
+
+impl Default for LogRotation {
+    /// Returns the settings of a log file that is never rotated.
+    fn default() -> Self {
+        Self { max_file_size: None, daily: false, max_files: DEFAULT_MAX_LOG_FILES, compress: false }
+    }
+}
+
+impl LogRotation {
+    /// Returns `true` if the log file is rotated at all.
+    pub const fn is_enabled(&self) -> bool {
+        self.max_file_size.is_some() || self.daily
+    }
+}
+
+/// A log file that rotates itself according to its settings, for use as the writer of a `tracing` layer.
+///
+/// Each log line is written under a lock, and the rotation only happens between two writes, so the lines
+/// are never split nor interleaved across the rotation boundary. The rotated files are renamed to
+/// `<path>.<timestamp>`, compressed in order on a dedicated thread if requested, and pruned beyond the retention count.
+/// If the log file is removed out from under the node, it is recreated, instead of writing to a deleted inode.
+#[derive(Clone)]
+pub struct RotatingLogFile {
+    /// The state of the log file.
+    inner: Arc<Mutex<LogFile>>,
+}
+
+impl RotatingLogFile {
+    /// Opens the log file at the given path, creating it if it does not exist.
+    pub fn open(path: PathBuf, rotation: LogRotation) -> io::Result<Self> {
+        let mut log_file = LogFile {
+            path,
+            rotation,
+            file: None,
+            size: 0,
+            date: today(),
+            last_check: Instant::now(),
+            compressor: None,
+        };
+        log_file.reopen()?;
+        Ok(Self { inner: Arc::new(Mutex::new(log_file)) })
+    }
+}
+
+impl<'a> MakeWriter<'a> for RotatingLogFile {
+    type Writer = RotatingLogWriter<'a>;
+
+    /// Returns a writer that holds the lock on the log file, until the log line is written.
+    fn make_writer(&'a self) -> Self::Writer {
+        RotatingLogWriter(self.inner.lock())
+    }
+}
+
+/// The writer of a single log line to a rotating log file.
+pub struct RotatingLogWriter<'a>(MutexGuard<'a, LogFile>);
+
+impl io::Write for RotatingLogWriter<'_> {
+    /// Writes the given log line, rotating the log file first if needed.
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write_line(buf)?;
+        Ok(buf.len())
+    }
+
+    /// Flushes the log file.
+    fn flush(&mut self) -> io::Result<()> {
+        match &mut self.0.file {
+            Some(file) => file.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The state of a rotating log file.
+struct LogFile {
+    /// The path to the log file.
+    path: PathBuf,
+    /// The rotation settings.
+    rotation: LogRotation,
+    /// The open log file, if any.
+    file: Option<File>,
+    /// The size in bytes of the log file.
+    size: u64,
+    /// The (UTC) day on which the log file was started.
+    date: Date,
+    /// The last time the log file was checked for having been removed.
+    last_check: Instant,
+    /// The sender of the rotated files to the compression thread, once it is spawned.
+    compressor: Option<mpsc::Sender<PathBuf>>,
+}
+
+impl LogFile {
+    /// Writes the given log line, rotating the log file first if needed.
+    fn write_line(&mut self, line: &[u8]) -> io::Result<()> {
+        // Recreate the log file, if it was removed out from under the node.
+        if self.file.is_none() || (self.last_check.elapsed() >= LOG_FILE_CHECK_INTERVAL && self.was_removed()) {
+            self.reopen()?;
+        }
+        // Rotate the log file, if the line would exceed the maximum size, or if the day changed.
+        let exceeds_size = self.rotation.max_file_size.is_some_and(|max_size| self.size + line.len() as u64 > max_size);
+        let is_new_day = self.rotation.daily && today() != self.date;
+        if self.size > 0 && (exceeds_size || is_new_day) {
+            self.rotate()?;
+        }
+        // Write the line.
+        match &mut self.file {
+            Some(file) => file.write_all(line)?,
+            None => return Err(io::Error::new(io::ErrorKind::NotFound, "the log file is not open")),
+        }
+        self.size += line.len() as u64;
+        Ok(())
+    }
+
+    /// Returns `true` if the log file was removed (or replaced) since it was opened.
+    fn was_removed(&mut self) -> bool {
+        self.last_check = Instant::now();
+        match (&self.file, std::fs::metadata(&self.path)) {
+            (Some(file), Ok(metadata)) => !is_same_file(file, &metadata),
+            _ => true,
+        }
+    }
+
+    /// Opens the log file for appending, creating it (and its directory) if it does not exist.
+    fn reopen(&mut self) -> io::Result<()> {
+        self.file = None;
+        if let Some(directory) = self.path.parent().filter(|directory| !directory.as_os_str().is_empty()) {
+            std::fs::create_dir_all(directory)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        let metadata = file.metadata()?;
+        self.size = metadata.len();
+        // Determine the day the log file was last written to, so that a restart on a new day still rotates it.
+        self.date = match metadata.modified() {
+            Ok(modified) if self.size > 0 => OffsetDateTime::from(modified).date(),
+            _ => today(),
+        };
+        self.last_check = Instant::now();
+        self.file = Some(file);
+        Ok(())
+    }
+
+    /// Moves the log file to its rotated path, opens a new log file, and prunes the oldest rotated files.
+    fn rotate(&mut self) -> io::Result<()> {
+        if let Some(mut file) = self.file.take() {
+            file.flush()?;
+        }
+        let rotated = rotated_path(&self.path, OffsetDateTime::now_utc());
+        std::fs::rename(&self.path, &rotated)?;
+        self.reopen()?;
+
+        // Compress the rotated file on a dedicated thread, as it may take a while.
+        if self.rotation.compress {
+            match self.compressor().send(rotated) {
+                Ok(()) => return Ok(()),
+                Err(error) => eprintln!("Failed to compress the rotated log file '{}'", error.0.display()),
+            }
+        }
+        prune_rotated_files(&self.path, self.rotation.max_files);
+        Ok(())
+    }
+
+    /// Returns the sender to the compression thread, spawning it on the first rotation.
+    ///
+    /// A single thread compresses the rotated files in the order they were rotated, and prunes the oldest ones,
+    /// so that a file is never pruned while it is being compressed.
+    fn compressor(&mut self) -> &mpsc::Sender<PathBuf> {
+        let (path, max_files) = (self.path.clone(), self.rotation.max_files);
+        self.compressor.get_or_insert_with(|| {
+            let (sender, receiver) = mpsc::channel::<PathBuf>();
+            let spawned = std::thread::Builder::new().name("log-compression".to_string()).spawn(move || {
+                while let Ok(rotated) = receiver.recv() {
+                    if let Err(error) = compress(&rotated) {
+                        eprintln!("Failed to compress the rotated log file '{}' - {error}", rotated.display());
+                    }
+                    prune_rotated_files(&path, max_files);
+                }
+            });
+            if let Err(error) = spawned {
+                eprintln!("Failed to spawn the log compression - {error}");
+            }
+            sender
+        })
+    }
+}
+
+/// Returns the current (UTC) day.
+fn today() -> Date {
+    OffsetDateTime::now_utc().date()
+}
+
+/// Returns `true` if the given open file is the file with the given metadata.
+#[cfg(target_family = "unix")]
+fn is_same_file(file: &File, metadata: &std::fs::Metadata) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    file.metadata().is_ok_and(|open| open.dev() == metadata.dev() && open.ino() == metadata.ino())
+}
+
+/// Returns `true` if the given open file is the file with the given metadata.
+/// Note: On non-unix platforms, an open file cannot be removed, so the file is assumed to be the same.
+#[cfg(not(target_family = "unix"))]
+fn is_same_file(_file: &File, _metadata: &std::fs::Metadata) -> bool {
+    true
+}
+
+/// Returns the path of the log file rotated at the given time, which sorts in the order of rotation.
+fn rotated_path(path: &Path, now: OffsetDateTime) -> PathBuf {
+    let timestamp = format!(
+        "{:04}{:02}{:02}T{:02}{:02}{:02}",
+        now.year(),
+        now.month() as u8,
+        now.day(),
+        now.hour(),
+        now.minute(),
+        now.second()
+    );
+    // Disambiguate multiple rotations within the same second.
+    let mut index = 0;
+    loop {
+        let mut rotated = path.as_os_str().to_owned();
+        match index {
+            0 => rotated.push(format!(".{timestamp}")),
+            _ => rotated.push(format!(".{timestamp}-{index:03}")),
+        }
+        let rotated = PathBuf::from(rotated);
+        let mut compressed = rotated.clone().into_os_string();
+        compressed.push(format!(".{COMPRESSED_EXTENSION}"));
+        if !rotated.exists() && !Path::new(&compressed).exists() {
+            return rotated;
+        }
+        index += 1;
+    }
+}
+
+/// Compresses the given rotated log file to `<path>.gz`, and removes the uncompressed file.
+fn compress(path: &Path) -> io::Result<()> {
+    let mut compressed = path.as_os_str().to_owned();
+    compressed.push(format!(".{COMPRESSED_EXTENSION}"));
+    let mut partial = compressed.clone();
+    partial.push(format!(".{PARTIAL_EXTENSION}"));
+
+    // Compress to a partial file first, so that an interrupted compression never leaves a truncated archive.
+    let mut source = File::open(path)?;
+    let mut encoder = GzEncoder::new(File::create(&partial)?, Compression::default());
+    io::copy(&mut source, &mut encoder)?;
+    encoder.finish()?.sync_all()?;
+    std::fs::rename(&partial, &compressed)?;
+    std::fs::remove_file(path)
+}
+
+/// Returns the rotated files of the given log file, from the oldest to the newest.
+pub fn rotated_files(path: &Path) -> Vec<PathBuf> {
+    let (Some(directory), Some(file_name)) = (path.parent(), path.file_name().and_then(|name| name.to_str())) else {
+        return vec![];
+    };
+    let directory = if directory.as_os_str().is_empty() { Path::new(".") } else { directory };
+    let prefix = format!("{file_name}.");
+    let Ok(entries) = std::fs::read_dir(directory) else {
+        return vec![];
+    };
+    let mut rotated: Vec<(String, PathBuf)> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let suffix = name.strip_prefix(&prefix)?;
+            // Skip the files that are being compressed.
+            if suffix.ends_with(PARTIAL_EXTENSION) {
+                return None;
+            }
+            // Order the files by their rotation timestamp, regardless of whether they are compressed.
+            let key = suffix.trim_end_matches(&format!(".{COMPRESSED_EXTENSION}")).to_string();
+            Some((key, entry.path()))
+        })
+        .collect();
+    rotated.sort();
+    rotated.into_iter().map(|(_, path)| path).collect()
+}
+
+/// Removes the oldest rotated files of the given log file, beyond the given retention count.
+fn prune_rotated_files(path: &Path, max_files: usize) {
+    let rotated = rotated_files(path);
+    let num_pruned = rotated.len().saturating_sub(max_files);
+    for path in &rotated[..num_pruned] {
+        if let Err(error) = std::fs::remove_file(path) {
+            eprintln!("Failed to remove the rotated log file '{}' - {error}", path.display());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    /// Returns a unique log file path in a new temporary directory.
+    fn sample_log_path(name: &str) -> PathBuf {
+        let directory = std::env::temp_dir().join(format!("snarkos-{name}-{}", rand::random::<u64>()));
+        std::fs::create_dir_all(&directory).unwrap();
+        directory.join("snarkos.log")
+    }
+
+    /// Returns the lines of the given log file, decompressing it if needed.
+    fn read_lines(path: &Path) -> Vec<String> {
+        let mut contents = String::new();
+        match path.extension().is_some_and(|extension| extension == COMPRESSED_EXTENSION) {
+            true => GzDecoder::new(File::open(path).unwrap()).read_to_string(&mut contents).unwrap(),
+            false => File::open(path).unwrap().read_to_string(&mut contents).unwrap(),
+        };
+        contents.lines().map(str::to_string).collect()
+    }
+
+    /// Writes the given log lines through the rotating log file, as the `tracing` layer does.
+    fn write_lines(log_file: &RotatingLogFile, lines: impl IntoIterator<Item = String>) {
+        for line in lines {
+            log_file.make_writer().write_all(format!("{line}\n").as_bytes()).unwrap();
+        }
+    }
+
+    /// Returns the sample text and JSON log lines with the given index.
+    fn sample_line(index: usize) -> String {
+        match index % 2 {
+            0 => format!("2024-01-01T00:00:00.000000Z  INFO snarkos: Sample log line {index:06} {}", "x".repeat(64)),
+            _ => {
+                format!(r#"{{"level":"INFO","fields":{{"message":"Sample log line {index:06}"}},"target":"snarkos"}}"#)
+            }
+        }
+    }
+
+    #[test]
+    fn test_rotation_by_size() {
+        let path = sample_log_path("log-rotation");
+        let rotation = LogRotation { max_file_size: Some(4 * 1024), max_files: 3, ..Default::default() };
+        let log_file = RotatingLogFile::open(path.clone(), rotation).unwrap();
+
+        // Write enough log lines to rotate the log file many times.
+        let num_lines = 1_000;
+        write_lines(&log_file, (0..num_lines).map(sample_line));
+
+        // Ensure the retention count is respected, and each file is within the maximum size.
+        let rotated = rotated_files(&path);
+        assert_eq!(rotated.len(), 3);
+        for path in rotated.iter().chain([&path]) {
+            assert!(std::fs::metadata(path).unwrap().len() <= 4 * 1024);
+        }
+
+        // Ensure the lines are continuous across the rotation boundaries, and none was split.
+        let lines: Vec<String> = rotated.iter().chain([&path]).flat_map(|path| read_lines(path)).collect();
+        let first = num_lines - lines.len();
+        assert_eq!(lines, (first..num_lines).map(sample_line).collect::<Vec<_>>());
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_rotation_with_compression() {
+        let path = sample_log_path("log-compression");
+        let rotation =
+            LogRotation { max_file_size: Some(8 * 1024), max_files: 2, compress: true, ..Default::default() };
+        let log_file = RotatingLogFile::open(path.clone(), rotation).unwrap();
+
+        // Write enough log lines to rotate the log file a few times, and wait for the compression.
+        let num_lines = 500;
+        write_lines(&log_file, (0..num_lines).map(sample_line));
+        let start = Instant::now();
+        while !rotated_files(&path).iter().all(|path| path.extension().is_some_and(|ext| ext == COMPRESSED_EXTENSION))
+            || rotated_files(&path).len() > 2
+        {
+            assert!(start.elapsed() < Duration::from_secs(10), "The rotated log files were not compressed");
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        // Ensure the compressed files are pruned beyond the retention count, and the lines are continuous.
+        let rotated = rotated_files(&path);
+        assert_eq!(rotated.len(), 2);
+        let lines: Vec<String> = rotated.iter().chain([&path]).flat_map(|path| read_lines(path)).collect();
+        let first = num_lines - lines.len();
+        assert_eq!(lines, (first..num_lines).map(sample_line).collect::<Vec<_>>());
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_removed_log_file_is_recreated() {
+        let path = sample_log_path("log-removed");
+        let log_file = RotatingLogFile::open(path.clone(), LogRotation::default()).unwrap();
+        write_lines(&log_file, (0..10).map(sample_line));
+
+        // Remove the log file out from under the writer, and ensure it is recreated on a subsequent write.
+        std::fs::remove_file(&path).unwrap();
+        log_file.inner.lock().last_check -= LOG_FILE_CHECK_INTERVAL;
+        write_lines(&log_file, (10..20).map(sample_line));
+        assert_eq!(read_lines(&path), (10..20).map(sample_line).collect::<Vec<_>>());
+
+        // Ensure a log file that is never rotated keeps growing.
+        write_lines(&log_file, (20..1_000).map(sample_line));
+        assert!(rotated_files(&path).is_empty());
+        assert_eq!(read_lines(&path).len(), 990);
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_daily_rotation() {
+        let path = sample_log_path("log-daily");
+        let rotation = LogRotation { daily: true, ..Default::default() };
+        let log_file = RotatingLogFile::open(path.clone(), rotation).unwrap();
+        write_lines(&log_file, (0..10).map(sample_line));
+        assert!(rotated_files(&path).is_empty());
+
+        // Pretend the log file was started yesterday, and ensure the next line starts a new log file.
+        log_file.inner.lock().date = today().previous_day().unwrap();
+        write_lines(&log_file, (10..20).map(sample_line));
+        let rotated = rotated_files(&path);
+        assert_eq!(rotated.len(), 1);
+        assert_eq!(read_lines(&rotated[0]), (0..10).map(sample_line).collect::<Vec<_>>());
+        assert_eq!(read_lines(&path), (10..20).map(sample_line).collect::<Vec<_>>());
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
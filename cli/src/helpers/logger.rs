@@ -13,11 +13,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::helpers::{DynamicFormatter, LogWriter};
+use crate::helpers::{DynamicFormatter, LogRotation, LogWriter, RotatingLogFile};
+use snarkos_node::router::{TraceLevel, set_trace_filter_reload};
 
 use crossterm::tty::IsTty;
 use std::{
-    fs::File,
     io,
     path::Path,
     sync::{Arc, atomic::AtomicBool},
@@ -26,6 +26,7 @@ use tokio::sync::mpsc;
 use tracing_subscriber::{
     EnvFilter,
     layer::{Layer, SubscriberExt},
+    reload,
     util::SubscriberInitExt,
 };
 
@@ -44,6 +45,8 @@ pub fn initialize_logger<P: AsRef<Path>>(
     verbosity: u8,
     nodisplay: bool,
     logfile: P,
+    rotation: LogRotation,
+    json: bool,
     shutdown: Arc<AtomicBool>,
 ) -> mpsc::Receiver<Vec<u8>> {
     match verbosity {
@@ -52,48 +55,10 @@ pub fn initialize_logger<P: AsRef<Path>>(
         2.. => std::env::set_var("RUST_LOG", "trace"),
     };
 
-    // Filter out undesirable logs. (unfortunately EnvFilter cannot be cloned)
-    let [filter, filter2] = std::array::from_fn(|_| {
-        let filter = EnvFilter::from_default_env()
-            .add_directive("mio=off".parse().unwrap())
-            .add_directive("tokio_util=off".parse().unwrap())
-            .add_directive("hyper=off".parse().unwrap())
-            .add_directive("reqwest=off".parse().unwrap())
-            .add_directive("want=off".parse().unwrap())
-            .add_directive("warp=off".parse().unwrap());
-
-        let filter = if verbosity >= 2 {
-            filter.add_directive("snarkos_node_sync=trace".parse().unwrap())
-        } else {
-            filter.add_directive("snarkos_node_sync=debug".parse().unwrap())
-        };
-
-        let filter = if verbosity >= 3 {
-            filter
-                .add_directive("snarkos_node_bft=trace".parse().unwrap())
-                .add_directive("snarkos_node_bft::gateway=debug".parse().unwrap())
-        } else {
-            filter.add_directive("snarkos_node_bft=debug".parse().unwrap())
-        };
-
-        let filter = if verbosity >= 4 {
-            filter.add_directive("snarkos_node_bft::gateway=trace".parse().unwrap())
-        } else {
-            filter.add_directive("snarkos_node_bft::gateway=debug".parse().unwrap())
-        };
-
-        let filter = if verbosity >= 5 {
-            filter.add_directive("snarkos_node_router=trace".parse().unwrap())
-        } else {
-            filter.add_directive("snarkos_node_router=debug".parse().unwrap())
-        };
-
-        if verbosity >= 6 {
-            filter.add_directive("snarkos_node_tcp=trace".parse().unwrap())
-        } else {
-            filter.add_directive("snarkos_node_tcp=off".parse().unwrap())
-        }
-    });
+    // Filter out undesirable logs, with a reloadable filter per layer, as the trace scopes elevate the logging
+    // at runtime. (unfortunately EnvFilter cannot be cloned)
+    let (filter, filter_handle) = reload::Layer::new(log_filter(verbosity, &[]));
+    let (filter2, filter2_handle) = reload::Layer::new(log_filter(verbosity, &[]));
 
     // Create the directories tree for a logfile if it doesn't exist.
     let logfile_dir = logfile.as_ref().parent().expect("Root directory passed as a logfile");
@@ -101,9 +66,9 @@ pub fn initialize_logger<P: AsRef<Path>>(
         std::fs::create_dir_all(logfile_dir)
             .expect("Failed to create a directories: '{logfile_dir}', please check if user has permissions");
     }
-    // Create a file to write logs to.
-    let logfile =
-        File::options().append(true).create(true).open(logfile).expect("Failed to open the file for writing logs");
+    // Create a file to write logs to, which is rotated according to the given settings.
+    let logfile = RotatingLogFile::open(logfile.as_ref().to_path_buf(), rotation)
+        .expect("Failed to open the file for writing logs");
 
     // Initialize the log channel.
     let (log_sender, log_receiver) = mpsc::channel(1024);
@@ -114,6 +79,13 @@ pub fn initialize_logger<P: AsRef<Path>>(
         false => Some(log_sender),
     };
 
+    // Initialize the layer redirecting logs to the file, as lines of either text or JSON.
+    let file_layer = tracing_subscriber::fmt::Layer::default().with_ansi(false).with_writer(logfile);
+    let file_layer = match json {
+        true => file_layer.json().with_target(true).with_filter(filter2).boxed(),
+        false => file_layer.with_target(verbosity > 2).with_filter(filter2).boxed(),
+    };
+
     // Initialize tracing.
     let _ = tracing_subscriber::registry()
         .with(
@@ -127,17 +99,67 @@ pub fn initialize_logger<P: AsRef<Path>>(
         )
         .with(
             // Add layer redirecting logs to the file
-            tracing_subscriber::fmt::Layer::default()
-                .with_ansi(false)
-                .with_writer(logfile)
-                .with_target(verbosity > 2)
-                .with_filter(filter2),
+            file_layer,
         )
         .try_init();
 
+    // Reload the filters with the directives of the active trace scopes, whenever their levels change.
+    // Note: The reloads only fail once the subscriber is dropped, in which case there is nothing to log to.
+    set_trace_filter_reload(move |levels| {
+        let _ = filter_handle.reload(log_filter(verbosity, levels));
+        let _ = filter2_handle.reload(log_filter(verbosity, levels));
+    });
+
     log_receiver
 }
 
+/// Returns the filter of the logs for the given verbosity, including the directives of the trace scopes
+/// of the given levels.
+fn log_filter(verbosity: u8, scope_levels: &[TraceLevel]) -> EnvFilter {
+    let filter = EnvFilter::from_default_env()
+        .add_directive("mio=off".parse().unwrap())
+        .add_directive("tokio_util=off".parse().unwrap())
+        .add_directive("hyper=off".parse().unwrap())
+        .add_directive("reqwest=off".parse().unwrap())
+        .add_directive("want=off".parse().unwrap())
+        .add_directive("warp=off".parse().unwrap());
+
+    let filter = if verbosity >= 2 {
+        filter.add_directive("snarkos_node_sync=trace".parse().unwrap())
+    } else {
+        filter.add_directive("snarkos_node_sync=debug".parse().unwrap())
+    };
+
+    let filter = if verbosity >= 3 {
+        filter
+            .add_directive("snarkos_node_bft=trace".parse().unwrap())
+            .add_directive("snarkos_node_bft::gateway=debug".parse().unwrap())
+    } else {
+        filter.add_directive("snarkos_node_bft=debug".parse().unwrap())
+    };
+
+    let filter = if verbosity >= 4 {
+        filter.add_directive("snarkos_node_bft::gateway=trace".parse().unwrap())
+    } else {
+        filter.add_directive("snarkos_node_bft::gateway=debug".parse().unwrap())
+    };
+
+    let filter = if verbosity >= 5 {
+        filter.add_directive("snarkos_node_router=trace".parse().unwrap())
+    } else {
+        filter.add_directive("snarkos_node_router=debug".parse().unwrap())
+    };
+
+    let filter = if verbosity >= 6 {
+        filter.add_directive("snarkos_node_tcp=trace".parse().unwrap())
+    } else {
+        filter.add_directive("snarkos_node_tcp=off".parse().unwrap())
+    };
+
+    // Elevate the log events within the spans of the active trace scopes, regardless of the verbosity.
+    scope_levels.iter().fold(filter, |filter, level| filter.add_directive(level.directive().parse().unwrap()))
+}
+
 /// Returns the welcome message as a string.
 pub fn welcome_message() -> String {
     use colored::Colorize;
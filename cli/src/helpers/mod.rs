@@ -16,6 +16,12 @@
 mod bech32m;
 pub use bech32m::*;
 
+mod check;
+pub use check::*;
+
+mod log_rotation;
+pub use log_rotation::*;
+
 mod log_writer;
 use log_writer::*;
 
@@ -25,6 +31,9 @@ use dynamic_format::*;
 pub mod logger;
 pub use logger::*;
 
+mod storage;
+pub use storage::*;
+
 pub mod updater;
 pub use updater::*;
 
@@ -0,0 +1,316 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use aleo_std::StorageMode;
+use anyhow::{Result, anyhow, bail, ensure};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{File, OpenOptions},
+    io::{Seek, Write},
+    path::{Path, PathBuf},
+};
+
+/// The name of the file recording the metadata of the ledger, in the ledger directory.
+pub const STORAGE_METADATA_FILE_NAME: &str = "snarkos-storage.json";
+/// The name of the lock file of the ledger, in the ledger directory.
+pub const STORAGE_LOCK_FILE_NAME: &str = "snarkos.lock";
+
+/// Returns the absolute path of the given storage path, with every symlink in it resolved.
+///
+/// A relative path is resolved against the current working directory. The path need not exist:
+/// its longest existing ancestor is resolved, and the remaining components are appended as-is.
+pub fn canonicalize_storage_path(path: &Path) -> Result<PathBuf> {
+    let absolute = match path.is_absolute() {
+        true => path.to_path_buf(),
+        false => std::env::current_dir()
+            .map_err(|error| anyhow!("Unable to resolve the storage path {} - {error}", path.display()))?
+            .join(path),
+    };
+    // Find the longest existing ancestor of the path.
+    let mut ancestor = absolute.as_path();
+    let mut missing = Vec::new();
+    while !ancestor.exists() {
+        match (ancestor.parent(), ancestor.file_name()) {
+            (Some(parent), Some(name)) => {
+                missing.push(name.to_os_string());
+                ancestor = parent;
+            }
+            // Note: A '..' component is kept as-is, as it cannot be resolved before its parent exists.
+            _ => return Ok(absolute),
+        }
+    }
+    let canonical = std::fs::canonicalize(ancestor)
+        .map_err(|error| anyhow!("Unable to resolve the storage path {} - {error}", path.display()))?;
+    Ok(missing.into_iter().rev().fold(canonical, |path, name| path.join(name)))
+}
+
+/// Returns the given storage mode, with its custom path (if any) made absolute and canonical.
+pub fn canonicalize_storage_mode(mode: StorageMode) -> Result<StorageMode> {
+    match mode {
+        StorageMode::Custom(path) => Ok(StorageMode::Custom(canonicalize_storage_path(&path)?)),
+        mode => Ok(mode),
+    }
+}
+
+/// The metadata of a ledger, as recorded in its directory.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct StorageMetadata {
+    /// The network ID of the ledger.
+    pub network: u16,
+    /// The canonical path of the ledger, when it was last opened.
+    pub path: PathBuf,
+}
+
+impl StorageMetadata {
+    /// Loads the metadata from the given ledger directory, if it was recorded.
+    pub fn load(ledger_dir: &Path) -> Result<Option<Self>> {
+        let path = ledger_dir.join(STORAGE_METADATA_FILE_NAME);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let contents = std::fs::read_to_string(&path)
+            .map_err(|error| anyhow!("Unable to read the storage metadata at {} - {error}", path.display()))?;
+        let metadata = serde_json::from_str(&contents)
+            .map_err(|error| anyhow!("Malformed storage metadata at {} - {error}", path.display()))?;
+        Ok(Some(metadata))
+    }
+
+    /// Records the metadata in the given ledger directory.
+    pub fn store(&self, ledger_dir: &Path) -> Result<()> {
+        let path = ledger_dir.join(STORAGE_METADATA_FILE_NAME);
+        std::fs::write(&path, serde_json::to_string_pretty(self)?)
+            .map_err(|error| anyhow!("Unable to write the storage metadata at {} - {error}", path.display()))
+    }
+}
+
+/// An advisory lock on a ledger directory, recording the PID of the process holding it.
+///
+/// The lock is released when it is dropped, or when the process exits.
+#[derive(Debug)]
+pub struct StorageLock {
+    /// The open lock file, which holds the lock.
+    _file: File,
+    /// The path of the lock file.
+    path: PathBuf,
+}
+
+impl StorageLock {
+    /// Acquires the lock on the given ledger directory, or fails if another process holds it.
+    pub fn acquire(ledger_dir: &Path) -> Result<Self> {
+        let path = ledger_dir.join(STORAGE_LOCK_FILE_NAME);
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)
+            .map_err(|error| anyhow!("Unable to open the storage lock file at {} - {error}", path.display()))?;
+
+        #[cfg(target_family = "unix")]
+        {
+            use nix::fcntl::{FlockArg, flock};
+            use std::os::unix::io::AsRawFd;
+
+            if flock(file.as_raw_fd(), FlockArg::LockExclusiveNonblock).is_err() {
+                bail!(
+                    "The ledger at {} is already opened by {} (see {}). Stop the other node first, \
+                     or specify a different path with '--storage'",
+                    ledger_dir.display(),
+                    Self::holder(&mut file),
+                    path.display()
+                );
+            }
+        }
+
+        // Record the PID of this process.
+        file.set_len(0)?;
+        file.rewind()?;
+        write!(file, "{}", std::process::id())?;
+        file.flush()?;
+        Ok(Self { _file: file, path })
+    }
+
+    /// Acquires a shared lock on the given ledger directory, or fails if a process has opened the ledger.
+    ///
+    /// Unlike [`Self::acquire`], the ledger directory is not modified, and several processes may hold
+    /// the shared lock at once. While it is held, no node can open the ledger.
+    /// Returns `None` if the ledger was never opened by a node, and so has no lock file.
+    pub fn acquire_shared(ledger_dir: &Path) -> Result<Option<Self>> {
+        let path = ledger_dir.join(STORAGE_LOCK_FILE_NAME);
+        if !path.exists() {
+            return Ok(None);
+        }
+        #[allow(unused_mut)]
+        let mut file = File::open(&path)
+            .map_err(|error| anyhow!("Unable to open the storage lock file at {} - {error}", path.display()))?;
+
+        #[cfg(target_family = "unix")]
+        {
+            use nix::fcntl::{FlockArg, flock};
+            use std::os::unix::io::AsRawFd;
+
+            if flock(file.as_raw_fd(), FlockArg::LockSharedNonblock).is_err() {
+                bail!(
+                    "The ledger at {} is opened by {} (see {}). Stop the node first",
+                    ledger_dir.display(),
+                    Self::holder(&mut file),
+                    path.display()
+                );
+            }
+        }
+
+        Ok(Some(Self { _file: file, path }))
+    }
+
+    /// Returns a description of the process holding the given lock file, from the PID it records.
+    #[cfg(target_family = "unix")]
+    fn holder(file: &mut File) -> String {
+        use std::io::Read;
+
+        let mut pid = String::new();
+        let _ = file.read_to_string(&mut pid);
+        match pid.trim().parse::<u32>() {
+            Ok(pid) => format!("process {pid}"),
+            Err(_) => "another process".to_string(),
+        }
+    }
+
+    /// Returns the path of the lock file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+/// Prepares the ledger directory of the given (canonical) storage mode to be opened by this process.
+///
+/// This locks the ledger directory, and verifies the recorded metadata of the ledger, if any.
+/// If the ledger was relocated since it was last opened, a warning is printed and the metadata is updated.
+pub fn prepare_storage(network: u16, mode: &StorageMode) -> Result<StorageLock> {
+    let ledger_dir = aleo_std::aleo_ledger_dir(network, mode.clone());
+    std::fs::create_dir_all(&ledger_dir)
+        .map_err(|error| anyhow!("Unable to create the ledger directory {} - {error}", ledger_dir.display()))?;
+    // Resolve the ledger directory, in case the directory of a non-custom mode is behind a symlink.
+    let ledger_dir = canonicalize_storage_path(&ledger_dir)?;
+
+    // Lock the ledger directory, before inspecting its metadata.
+    let lock = StorageLock::acquire(&ledger_dir)?;
+
+    let metadata = StorageMetadata { network, path: ledger_dir.clone() };
+    match StorageMetadata::load(&ledger_dir)? {
+        Some(recorded) if recorded == metadata => return Ok(lock),
+        Some(recorded) => {
+            ensure!(
+                recorded.network == network,
+                "The ledger at {} belongs to network {}, not network {network}",
+                ledger_dir.display(),
+                recorded.network
+            );
+            let warning = format!(
+                "⚠️  The ledger was moved from {} to {} since it was last opened.",
+                recorded.path.display(),
+                ledger_dir.display()
+            );
+            eprintln!("{}\n", warning.yellow().bold());
+        }
+        None => (),
+    }
+    metadata.store(&ledger_dir)?;
+    Ok(lock)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a new temporary directory.
+    #[cfg(target_family = "unix")]
+    fn sample_dir() -> PathBuf {
+        let path = std::env::temp_dir().join(format!("snarkos-storage-{}", rand::random::<u64>()));
+        std::fs::create_dir_all(&path).unwrap();
+        // Note: The temporary directory may itself be behind a symlink, e.g. on macOS.
+        std::fs::canonicalize(path).unwrap()
+    }
+
+    #[test]
+    fn test_canonicalize_relative_path() {
+        let current_dir = std::fs::canonicalize(std::env::current_dir().unwrap()).unwrap();
+
+        // Ensure a relative path is resolved against the working directory, even if it does not exist.
+        let path = canonicalize_storage_path(Path::new("missing/ledger")).unwrap();
+        assert_eq!(path, current_dir.join("missing").join("ledger"));
+        assert!(!current_dir.join("missing").exists());
+
+        // Ensure the storage mode is only changed for a custom path.
+        let StorageMode::Custom(path) = canonicalize_storage_mode(StorageMode::Custom("ledger".into())).unwrap() else {
+            panic!("The custom storage mode was not preserved");
+        };
+        assert_eq!(path, current_dir.join("ledger"));
+        assert!(matches!(canonicalize_storage_mode(StorageMode::Development(1)).unwrap(), StorageMode::Development(1)));
+    }
+
+    #[cfg(target_family = "unix")]
+    #[test]
+    fn test_symlinked_storage() {
+        let dir = sample_dir();
+        let ledger_dir = dir.join("disk").join("ledger");
+        std::fs::create_dir_all(&ledger_dir).unwrap();
+        std::os::unix::fs::symlink(dir.join("disk"), dir.join("link")).unwrap();
+
+        // Ensure the symlink is resolved, including for a missing child of it.
+        assert_eq!(canonicalize_storage_path(&dir.join("link").join("ledger")).unwrap(), ledger_dir);
+        assert_eq!(canonicalize_storage_path(&dir.join("link").join("new")).unwrap(), dir.join("disk").join("new"));
+
+        // Ensure the ledger opened through the symlink records its canonical path.
+        let mode = canonicalize_storage_mode(StorageMode::Custom(dir.join("link").join("ledger"))).unwrap();
+        let lock = prepare_storage(1, &mode).unwrap();
+        let metadata = StorageMetadata::load(&ledger_dir).unwrap().unwrap();
+        assert_eq!(metadata, StorageMetadata { network: 1, path: ledger_dir.clone() });
+        drop(lock);
+
+        // Ensure the ledger is opened once it is moved, and its metadata is updated.
+        let moved_dir = dir.join("other").join("ledger");
+        std::fs::create_dir_all(dir.join("other")).unwrap();
+        std::fs::rename(&ledger_dir, &moved_dir).unwrap();
+        let lock = prepare_storage(1, &StorageMode::Custom(moved_dir.clone())).unwrap();
+        assert_eq!(StorageMetadata::load(&moved_dir).unwrap().unwrap().path, moved_dir);
+        drop(lock);
+
+        // Ensure the ledger of another network is rejected.
+        let error = prepare_storage(2, &StorageMode::Custom(moved_dir)).unwrap_err();
+        assert!(error.to_string().contains("belongs to network 1, not network 2"));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(target_family = "unix")]
+    #[test]
+    fn test_double_open_lock() {
+        let dir = sample_dir();
+        let mode = StorageMode::Custom(dir.join("ledger"));
+
+        // Ensure the ledger cannot be opened twice, and the error names the holder.
+        let lock = prepare_storage(1, &mode).unwrap();
+        assert_eq!(std::fs::read_to_string(lock.path()).unwrap(), std::process::id().to_string());
+        let error = prepare_storage(1, &mode).unwrap_err();
+        assert!(error.to_string().contains(&format!("already opened by process {}", std::process::id())));
+
+        // Ensure the ledger can be opened once the lock is released.
+        drop(lock);
+        assert!(prepare_storage(1, &mode).is_ok());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,136 @@
+#!/bin/bash
+
+# Floods a running devnet (see 'devnet.sh') with deployments, and checks that executions keep landing in blocks.
+
+# Read the network ID from user or use a default value of 1
+read -p "Enter the network ID (mainnet = 0, testnet = 1, canary = 2) (default: 1): " network_id
+network_id=${network_id:-1}
+
+case $network_id in
+  0) network_name="mainnet" ;;
+  1) network_name="testnet" ;;
+  2) network_name="canary" ;;
+  *) echo "Unknown network ID ($network_id)"; exit 1 ;;
+esac
+
+# Read the REST endpoint of a devnet node from the user or use a default value
+read -p "Enter the REST endpoint of a devnet node (default: http://localhost:3030): " endpoint
+endpoint=${endpoint:-http://localhost:3030}
+
+# Read the private key funding the transactions from the user or use the development private key of node 0
+read -p "Enter the private key funding the transactions (default: development private key of node 0): " private_key
+private_key=${private_key:-APrivateKey1zkp8CZNn3yeCseEtxuVPbDCwSyhGW6yZKUYKfgXmcpoGPWH}
+
+# Read the recipient of the public transfers from the user or use the development address of node 0
+read -p "Enter the recipient of the executions (default: development address of node 0): " recipient
+recipient=${recipient:-aleo1rhgdu77hgyqd3xjj8ucu3jj9r2krwz6mnzyd80gncr5fxcwlh5rsvzp9px}
+
+# Read the number of deployments and executions from the user or use default values
+read -p "Enter the number of deployments (default: 30): " num_deployments
+num_deployments=${num_deployments:-30}
+read -p "Enter the number of executions (default: 10): " num_executions
+num_executions=${num_executions:-10}
+
+# Read the number of seconds to wait for the executions to be confirmed from the user or use a default value
+read -p "Enter the number of seconds to wait for the executions (default: 300): " timeout
+timeout=${timeout:-300}
+
+# Create a working directory for the programs and transactions
+work_dir=".flood-$(date +"%Y%m%d%H%M%S")"
+mkdir -p "$work_dir"
+run_id=$(date +"%s")
+
+# Generates a deployment of a unique program, and stores it to the given file
+create_deployment() {
+  local index=$1
+  local program="flood_${run_id}_${index}"
+  local program_dir="$work_dir/$program"
+  mkdir -p "$program_dir"
+  cat > "$program_dir/program.json" << EOF
+{
+  "program": "$program.aleo",
+  "version": "0.0.0",
+  "description": "",
+  "license": "MIT"
+}
+EOF
+  cat > "$program_dir/main.aleo" << EOF
+program $program.aleo;
+
+function main:
+    input r0 as u32.public;
+    add r0 $index u32 into r1;
+    output r1 as u32.public;
+EOF
+  snarkos developer deploy "$program.aleo" --network $network_id --path "$program_dir" --private-key $private_key \
+    --query $endpoint --priority-fee 0 --store "$work_dir/deployment-$index.json" > /dev/null || exit 1
+}
+
+# Generates an execution of a public transfer to the recipient, and stores it to the given file
+create_execution() {
+  local index=$1
+  snarkos developer execute credits.aleo transfer_public "$recipient" "$((index + 1))u64" --network $network_id \
+    --private-key $private_key --query $endpoint --priority-fee 0 --store "$work_dir/execution-$index.json" > /dev/null || exit 1
+}
+
+# Broadcasts the transaction in the given file, and prints its ID
+broadcast() {
+  curl -s -X POST -H "Content-Type: application/json" --data @"$1" "$endpoint/$network_name/transaction/broadcast" | tr -d '"'
+}
+
+# Generate the transactions up front, so that they can be broadcast in a burst
+echo "Generating $num_deployments deployments and $num_executions executions in '$work_dir'..."
+for ((index = 0; index < num_deployments; index++)); do
+  create_deployment $index
+done
+for ((index = 0; index < num_executions; index++)); do
+  create_execution $index
+done
+
+start_height=$(curl -s "$endpoint/$network_name/block/height/latest")
+
+# Flood the deployments, and then broadcast the executions behind them
+deployment_ids=()
+execution_ids=()
+for ((index = 0; index < num_deployments; index++)); do
+  deployment_ids+=($(broadcast "$work_dir/deployment-$index.json"))
+done
+for ((index = 0; index < num_executions; index++)); do
+  execution_ids+=($(broadcast "$work_dir/execution-$index.json"))
+done
+echo "Broadcast ${#deployment_ids[@]} deployments and ${#execution_ids[@]} executions from height $start_height."
+
+# Counts the given transactions that are confirmed
+count_confirmed() {
+  local count=0
+  for id in "$@"; do
+    if curl -s -f "$endpoint/$network_name/transaction/confirmed/$id" > /dev/null; then
+      count=$((count + 1))
+    fi
+  done
+  echo $count
+}
+
+# Wait for the executions to be confirmed, while the deployments are still landing
+deadline=$(($(date +%s) + timeout))
+while true; do
+  confirmed_deployments=$(count_confirmed "${deployment_ids[@]}")
+  confirmed_executions=$(count_confirmed "${execution_ids[@]}")
+  height=$(curl -s "$endpoint/$network_name/block/height/latest")
+  echo "Height $height - confirmed $confirmed_deployments/${#deployment_ids[@]} deployments and $confirmed_executions/${#execution_ids[@]} executions"
+  if [ "$confirmed_executions" -eq "${#execution_ids[@]}" ]; then
+    break
+  fi
+  if [ "$(date +%s)" -ge "$deadline" ]; then
+    echo "❌ The executions were starved by the deployments (confirmed $confirmed_executions/${#execution_ids[@]} executions)."
+    exit 1
+  fi
+  sleep 5
+done
+
+# The executions must not have waited for all the deployments to land
+if [ "$confirmed_deployments" -lt "${#deployment_ids[@]}" ]; then
+  echo "✅ The executions landed while $((${#deployment_ids[@]} - confirmed_deployments)) deployments were still pending."
+else
+  echo "✅ The executions landed (all the deployments have landed as well)."
+fi
@@ -0,0 +1,137 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{
+    console::{
+        program::{Identifier, Literal, ProgramID, Value},
+        types::U64,
+    },
+    prelude::{Address, FromBytes, Ledger, Network, PrivateKey, ToBytes, block::Transaction, store::ConsensusStorage},
+};
+
+use anyhow::{Result, ensure};
+use rand::SeedableRng;
+use rand_chacha::ChaChaRng;
+use rayon::prelude::*;
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// The amount of each transfer in the corpus, in microcredits.
+const TRANSFER_AMOUNT: u64 = 1;
+
+/// Loads the corpus of public transfers from the cache directory, or generates it and caches it.
+///
+/// The corpus is keyed by the network, the genesis block, its size, and its seed, as proving it is slow.
+pub fn load_or_generate_corpus<N: Network, C: ConsensusStorage<N>>(
+    ledger: &Ledger<N, C>,
+    senders: &[PrivateKey<N>],
+    size: usize,
+    seed: u64,
+    cache_dir: &Path,
+) -> Result<Vec<Transaction<N>>> {
+    let path = corpus_path::<N>(cache_dir, &ledger.get_hash(0)?.to_string(), size, seed);
+    if path.exists() {
+        println!("Loading the corpus of {size} transactions from {}...", path.display());
+        let corpus = read_corpus(&std::fs::read(&path)?)?;
+        ensure!(corpus.len() == size, "The cached corpus at {} is corrupted", path.display());
+        return Ok(corpus);
+    }
+
+    println!("Generating a corpus of {size} transactions, this may take a while...");
+    let corpus = generate_corpus(ledger, senders, size, seed)?;
+    // Write the corpus to a temporary file first, so that an interrupted run does not leave a partial corpus behind.
+    std::fs::create_dir_all(cache_dir)?;
+    let temp_path = path.with_extension("tmp");
+    std::fs::write(&temp_path, write_corpus(&corpus)?)?;
+    std::fs::rename(&temp_path, &path)?;
+    println!("Cached the corpus at {}", path.display());
+    Ok(corpus)
+}
+
+/// Returns the path of the cached corpus with the given genesis hash, size, and seed.
+fn corpus_path<N: Network>(cache_dir: &Path, genesis_hash: &str, size: usize, seed: u64) -> PathBuf {
+    cache_dir.join(format!("corpus-{}-{genesis_hash}-{size}-{seed}.bin", N::ID))
+}
+
+/// Generates the given number of public transfers, sent round-robin from the given senders to the next sender.
+///
+/// Each transfer is proven with its own stream of the seeded RNG, so the corpus does not depend on the scheduling.
+fn generate_corpus<N: Network, C: ConsensusStorage<N>>(
+    ledger: &Ledger<N, C>,
+    senders: &[PrivateKey<N>],
+    size: usize,
+    seed: u64,
+) -> Result<Vec<Transaction<N>>> {
+    ensure!(!senders.is_empty(), "The corpus requires at least one sender");
+    let locator = (ProgramID::from_str("credits.aleo")?, Identifier::from_str("transfer_public")?);
+    (0..size)
+        .into_par_iter()
+        .map(|index| {
+            let mut rng = ChaChaRng::seed_from_u64(seed);
+            rng.set_stream(index as u64);
+            let sender = &senders[index % senders.len()];
+            let recipient = Address::try_from(&senders[(index + 1) % senders.len()])?;
+            let inputs =
+                [Value::from(Literal::Address(recipient)), Value::from(Literal::U64(U64::new(TRANSFER_AMOUNT)))];
+            ledger.vm().execute(sender, locator, inputs.into_iter(), None, 0, None, &mut rng)
+        })
+        .collect()
+}
+
+/// Returns the serialized corpus.
+fn write_corpus<N: Network>(corpus: &[Transaction<N>]) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    u32::try_from(corpus.len())?.write_le(&mut bytes)?;
+    for transaction in corpus {
+        transaction.write_le(&mut bytes)?;
+    }
+    Ok(bytes)
+}
+
+/// Returns the deserialized corpus.
+fn read_corpus<N: Network>(mut bytes: &[u8]) -> Result<Vec<Transaction<N>>> {
+    let size = u32::read_le(&mut bytes)?;
+    let corpus = (0..size).map(|_| Transaction::read_le(&mut bytes)).collect::<Result<Vec<_>, _>>()?;
+    ensure!(bytes.is_empty(), "The corpus has trailing bytes");
+    Ok(corpus)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::MainnetV0;
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn test_corpus_path() {
+        let cache_dir = Path::new("cache");
+        let path = corpus_path::<CurrentNetwork>(cache_dir, "ab1genesis", 100, 7);
+        assert_eq!(path, cache_dir.join("corpus-0-ab1genesis-100-7.bin"));
+        // Ensure a corpus of another size or seed is cached separately.
+        assert_ne!(path, corpus_path::<CurrentNetwork>(cache_dir, "ab1genesis", 101, 7));
+        assert_ne!(path, corpus_path::<CurrentNetwork>(cache_dir, "ab1genesis", 100, 8));
+    }
+
+    #[test]
+    fn test_empty_corpus() {
+        let bytes = write_corpus::<CurrentNetwork>(&[]).unwrap();
+        assert!(read_corpus::<CurrentNetwork>(&bytes).unwrap().is_empty());
+        // Ensure trailing bytes are rejected.
+        assert!(read_corpus::<CurrentNetwork>(&[bytes, vec![0]].concat()).is_err());
+    }
+}
@@ -0,0 +1,210 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkos_account::Account;
+use snarkos_node::{
+    SharedMemoryBudget,
+    SupervisedTasks,
+    router::{MemoryBudget, TaskSupervisor},
+};
+use snarkos_node_bft::{MEMORY_POOL_PORT, helpers::init_primary_channels};
+use snarkos_node_bft_ledger_service::CoreLedgerService;
+use snarkos_node_consensus::{Consensus, DEFAULT_INBOUND_QUEUE_TTL_IN_SECS, DefaultMempoolPolicy};
+use snarkvm::{
+    ledger::committee::{Committee, MIN_VALIDATOR_STAKE},
+    prelude::{
+        FromBytes,
+        Ledger,
+        Network,
+        ToBytes,
+        VM,
+        block::Block,
+        store::{ConsensusStore, helpers::memory::ConsensusMemory},
+    },
+};
+
+use aleo_std::{StorageMode, aleo_ledger_dir};
+use anyhow::{Result, bail};
+use indexmap::IndexMap;
+use rand::SeedableRng;
+use rand_chacha::ChaChaRng;
+use std::{
+    net::{Ipv4Addr, SocketAddr},
+    path::Path,
+    sync::{Arc, atomic::AtomicBool},
+    time::{Duration, Instant},
+};
+
+/// The offset of the development IDs of the validators, so that their storage is apart from a local devnet.
+pub const BENCH_DEV_OFFSET: u16 = 100;
+/// The seed of the genesis block.
+const GENESIS_SEED: u64 = 1234567890;
+
+/// A development network of validators, running in this process.
+pub struct Devnet<N: Network> {
+    /// The consensus of each validator.
+    validators: Vec<Consensus<N>>,
+    /// The shutdown flag of the ledgers.
+    shutdown: Arc<AtomicBool>,
+}
+
+/// Returns the accounts of the given number of development validators.
+pub fn devnet_accounts<N: Network>(num_validators: u16) -> Result<Vec<Account<N>>> {
+    (0..num_validators).map(|i| Account::new(&mut ChaChaRng::seed_from_u64(i as u64))).collect()
+}
+
+/// Loads the genesis block of the given validators from the cache directory, or computes it and caches it.
+pub fn load_or_compute_genesis<N: Network>(accounts: &[Account<N>], cache_dir: &Path) -> Result<Block<N>> {
+    let path = cache_dir.join(format!("genesis-{}-{}.bin", N::ID, accounts.len()));
+    if path.exists() {
+        return Block::from_bytes_le(&std::fs::read(&path)?);
+    }
+
+    // Bond the minimum stake of each validator to itself, and share the rest of the supply between them.
+    let num_validators = accounts.len() as u64;
+    let public_balance = (N::STARTING_SUPPLY - num_validators * MIN_VALIDATOR_STAKE) / num_validators;
+    let members = accounts.iter().map(|account| (account.address(), (MIN_VALIDATOR_STAKE, false, 0u8))).collect();
+    let committee = Committee::<N>::new(0u64, members)?;
+    let public_balances = accounts.iter().map(|account| (account.address(), public_balance)).collect();
+    let bonded_balances = accounts
+        .iter()
+        .map(|account| (account.address(), (account.address(), account.address(), MIN_VALIDATOR_STAKE)))
+        .collect::<IndexMap<_, _>>();
+
+    // Compute the genesis block.
+    let vm = VM::from(ConsensusStore::<N, ConsensusMemory<N>>::open(None)?)?;
+    let rng = &mut ChaChaRng::seed_from_u64(GENESIS_SEED);
+    let genesis = vm.genesis_quorum(accounts[0].private_key(), committee, public_balances, bonded_balances, rng)?;
+
+    std::fs::create_dir_all(cache_dir)?;
+    std::fs::write(&path, genesis.to_bytes_le()?)?;
+    Ok(genesis)
+}
+
+impl<N: Network> Devnet<N> {
+    /// Starts a validator for each of the given accounts, from the given genesis block.
+    ///
+    /// The ledgers are kept in memory, while the BFT storage of a previous run is removed.
+    pub async fn start(accounts: &[Account<N>], genesis: &Block<N>) -> Result<Self> {
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let gateway_ip =
+            |i: usize| SocketAddr::from((Ipv4Addr::LOCALHOST, MEMORY_POOL_PORT + BENCH_DEV_OFFSET + i as u16));
+
+        let mut validators = Vec::with_capacity(accounts.len());
+        for (i, account) in accounts.iter().enumerate() {
+            let storage_mode = StorageMode::Development(BENCH_DEV_OFFSET + i as u16);
+            let storage_path = aleo_ledger_dir(N::ID, storage_mode.clone());
+            if storage_path.exists() {
+                std::fs::remove_dir_all(&storage_path)?;
+            }
+
+            let ledger = Ledger::<N, ConsensusMemory<N>>::load(genesis.clone(), storage_mode.clone())?;
+            let trusted_validators = (0..accounts.len()).filter(|j| *j != i).map(gateway_ip).collect::<Vec<_>>();
+            let mut consensus = Consensus::new(
+                account.clone(),
+                Arc::new(CoreLedgerService::new(ledger, shutdown.clone())),
+                None,
+                &trusted_validators,
+                storage_mode,
+                Arc::new(SharedMemoryBudget(Arc::new(MemoryBudget::new(None)))),
+                Arc::new(SupervisedTasks(TaskSupervisor::new("consensus"))),
+                Arc::new(DefaultMempoolPolicy),
+                Duration::from_secs(DEFAULT_INBOUND_QUEUE_TTL_IN_SECS),
+                Default::default(),
+            )?;
+            let (primary_sender, primary_receiver) = init_primary_channels::<N>();
+            consensus.run(primary_sender, primary_receiver).await?;
+            validators.push(consensus);
+        }
+        Ok(Self { validators, shutdown })
+    }
+
+    /// Returns the consensus of each validator.
+    pub fn validators(&self) -> &[Consensus<N>] {
+        &self.validators
+    }
+
+    /// Waits until every validator is synced, or fails after the given timeout.
+    pub async fn wait_until_synced(&self, timeout: Duration) -> Result<()> {
+        let start = Instant::now();
+        while !self.validators.iter().all(|consensus| consensus.bft().is_synced()) {
+            if start.elapsed() >= timeout {
+                bail!("The devnet did not sync within {}s", timeout.as_secs());
+            }
+            tokio::time::sleep(Duration::from_millis(500)).await;
+        }
+        Ok(())
+    }
+
+    /// Shuts down the validators.
+    pub async fn shut_down(self) {
+        self.shutdown.store(true, std::sync::atomic::Ordering::Release);
+        for consensus in &self.validators {
+            consensus.shut_down().await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkos_node_consensus::BlockPreview;
+
+    type CurrentNetwork = snarkvm::prelude::MainnetV0;
+
+    /// The number of previews to compare against the committed blocks.
+    const NUM_PREVIEWS: usize = 3;
+
+    #[tokio::test(flavor = "multi_thread")]
+    #[ignore = "long-running e2e test"]
+    async fn test_block_preview_matches_committed_block() {
+        let accounts = devnet_accounts::<CurrentNetwork>(4).unwrap();
+        let genesis = load_or_compute_genesis(&accounts, &std::env::temp_dir().join("snarkos-bench")).unwrap();
+        let devnet = Devnet::start(&accounts, &genesis).await.unwrap();
+        devnet.wait_until_synced(Duration::from_secs(60)).await.unwrap();
+
+        let validator = &devnet.validators()[0];
+        let mut committed_blocks = validator.subscribe_committed_blocks().unwrap();
+
+        let start = Instant::now();
+        let mut num_matches = 0;
+        while num_matches < NUM_PREVIEWS {
+            assert!(start.elapsed() < Duration::from_secs(300), "Only {num_matches} previews matched in time");
+            let Some(preview) = validator.preview_next_block().await.unwrap() else {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                continue;
+            };
+            // Wait for the block at the previewed height.
+            let block = loop {
+                let block = committed_blocks.recv().await.unwrap();
+                if block.height() >= preview.height {
+                    break block;
+                }
+            };
+            // Skip the preview if another leader certificate was committed at its height.
+            if block.height() != preview.height || block.round() != preview.round {
+                continue;
+            }
+            // Ensure the preview matches the committed block, on a quiet devnet.
+            let aborted_transaction_ids =
+                preview.aborted_transactions.iter().map(|aborted| aborted.id).collect::<Vec<_>>();
+            assert_eq!(&aborted_transaction_ids, block.aborted_transaction_ids());
+            let expected = BlockPreview::new(&block, preview.num_transmissions, preview.aborted_transactions.clone());
+            assert_eq!(preview, expected);
+            num_matches += 1;
+        }
+        devnet.shut_down().await;
+    }
+}
@@ -0,0 +1,36 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A benchmark of the mempool-to-block throughput of a development network.
+//!
+//! The benchmark starts a development network of validators in this process, injects a corpus of public transfers
+//! into their memory pools at a given rate, and reports how the transfers are admitted, proposed, and included.
+
+#![forbid(unsafe_code)]
+
+mod corpus;
+pub use corpus::*;
+
+mod devnet;
+pub use devnet::*;
+
+mod recorder;
+pub use recorder::*;
+
+mod report;
+pub use report::*;
+
+mod stats;
+pub use stats::*;
@@ -0,0 +1,160 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkos_node_bench::{
+    Devnet,
+    Parameters,
+    Recorder,
+    Report,
+    devnet_accounts,
+    load_or_compute_genesis,
+    load_or_generate_corpus,
+};
+use snarkvm::prelude::{Ledger, MainnetV0, Network, store::helpers::memory::ConsensusMemory};
+
+use aleo_std::StorageMode;
+use anyhow::Result;
+use clap::Parser;
+use parking_lot::Mutex;
+use std::{
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::runtime::{self, Runtime};
+
+type CurrentNetwork = MainnetV0;
+
+/// The time to wait for the development network to sync, before the injection starts.
+const SYNC_TIMEOUT_IN_SECS: u64 = 120;
+
+/// Benchmarks the mempool-to-block throughput of a development network.
+#[derive(Debug, Parser)]
+#[clap(name = "snarkos-bench")]
+struct Bench {
+    /// Specify the number of validators of the development network.
+    #[clap(default_value = "4", long = "validators", value_parser = clap::value_parser!(u16).range(1..))]
+    validators: u16,
+    /// Specify the number of transactions to inject.
+    #[clap(default_value = "500", long = "transactions")]
+    transactions: usize,
+    /// Specify the injection rate, in transactions per second.
+    #[clap(default_value = "20", long = "rate", value_parser = clap::value_parser!(u32).range(1..))]
+    rate: u32,
+    /// Specify the seed of the corpus of transactions.
+    #[clap(default_value = "0", long = "seed")]
+    seed: u64,
+    /// Specify the time in seconds to wait for the injected transactions to be included, after the injection.
+    #[clap(default_value = "120", long = "drain-timeout")]
+    drain_timeout: u64,
+    /// Specify the directory of the cached genesis block and corpus [default: a 'snarkos-bench' temporary directory]
+    #[clap(long = "cache-dir")]
+    cache_dir: Option<PathBuf>,
+    /// Specify the path of the JSON report.
+    #[clap(default_value = "bench-report.json", long = "output")]
+    output: PathBuf,
+}
+
+fn main() -> Result<()> {
+    let bench = Bench::parse();
+    let report = runtime().block_on(bench.run())?;
+
+    std::fs::write(&bench.output, serde_json::to_string_pretty(&report)?)?;
+    println!("\n{report}\n");
+    println!("Wrote the report to {}", bench.output.display());
+    Ok(())
+}
+
+impl Bench {
+    /// Runs the benchmark, and returns its report.
+    async fn run(&self) -> Result<Report> {
+        let cache_dir = self.cache_dir.clone().unwrap_or_else(|| std::env::temp_dir().join("snarkos-bench"));
+        let accounts = devnet_accounts::<CurrentNetwork>(self.validators)?;
+
+        // Prepare the genesis block and the corpus, which are cached on disk, as proving them is slow.
+        let (genesis, corpus) = {
+            let (accounts, size, seed) = (accounts.clone(), self.transactions, self.seed);
+            tokio::task::spawn_blocking(move || {
+                let genesis = load_or_compute_genesis(&accounts, &cache_dir)?;
+                let ledger = Ledger::<CurrentNetwork, ConsensusMemory<CurrentNetwork>>::load(
+                    genesis.clone(),
+                    StorageMode::Production,
+                )?;
+                let senders = accounts.iter().map(|account| *account.private_key()).collect::<Vec<_>>();
+                let corpus = load_or_generate_corpus(&ledger, &senders, size, seed, &cache_dir)?;
+                Ok::<_, anyhow::Error>((genesis, corpus))
+            })
+            .await??
+        };
+
+        // Start the development network, and wait for the validators to connect.
+        println!("Starting a development network of {} validators...", self.validators);
+        let devnet = Devnet::start(&accounts, &genesis).await?;
+        devnet.wait_until_synced(Duration::from_secs(SYNC_TIMEOUT_IN_SECS)).await?;
+
+        // Record the blocks committed by the first validator, from the start of the injection.
+        let start = Instant::now();
+        let recorder = Arc::new(Mutex::new(Recorder::<CurrentNetwork>::default()));
+        let mut committed_blocks = devnet.validators()[0].subscribe_committed_blocks()?;
+        let recorder_ = recorder.clone();
+        let collector = tokio::spawn(async move {
+            while let Some(block) = committed_blocks.recv().await {
+                recorder_.lock().record_block(&block, start.elapsed());
+            }
+        });
+
+        // Inject the corpus at the given rate, round-robin across the validators.
+        println!("Injecting {} transactions at {} tx/s...", corpus.len(), self.rate);
+        let mut interval = tokio::time::interval(Duration::from_secs_f64(1.0 / self.rate as f64));
+        for (index, transaction) in corpus.into_iter().enumerate() {
+            interval.tick().await;
+            let consensus = &devnet.validators()[index % devnet.validators().len()];
+            let transaction_id = transaction.id();
+            let is_admitted = consensus.add_unconfirmed_transaction(transaction, None).await.is_ok();
+            recorder.lock().record_submission(transaction_id, is_admitted, start.elapsed());
+        }
+        recorder.lock().record_injection_end(start.elapsed());
+
+        // Wait for the admitted transactions to be included, up to the drain timeout.
+        println!("Waiting for the transactions to be included...");
+        let drain_start = Instant::now();
+        while !recorder.lock().is_drained() && drain_start.elapsed() < Duration::from_secs(self.drain_timeout) {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+        }
+
+        collector.abort();
+        devnet.shut_down().await;
+
+        let parameters = Parameters {
+            network: CurrentNetwork::NAME.to_string(),
+            validators: self.validators,
+            transactions: self.transactions,
+            rate: self.rate,
+            seed: self.seed,
+        };
+        let report = recorder.lock().report(parameters);
+        Ok(report)
+    }
+}
+
+/// Returns a runtime for the development network, with a parallelization that matches a node.
+fn runtime() -> Runtime {
+    rayon::ThreadPoolBuilder::new().stack_size(8 * 1024 * 1024).build_global().unwrap();
+    runtime::Builder::new_multi_thread()
+        .enable_all()
+        .thread_stack_size(8 * 1024 * 1024)
+        .build()
+        .expect("Failed to initialize a runtime for the benchmark")
+}
@@ -0,0 +1,217 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    Distribution,
+    report::{AdmissionReport, BlocksReport, InclusionReport, Parameters, ProposalsReport, Report},
+    stats::rate,
+};
+use snarkvm::prelude::{
+    Network,
+    block::{Authority, Block},
+};
+
+use indexmap::{IndexMap, IndexSet};
+use std::time::Duration;
+
+/// Records the lifecycle of the injected transactions, and the blocks that include them.
+///
+/// All times are measured from the start of the injection.
+pub struct Recorder<N: Network> {
+    /// The number of transactions submitted to consensus.
+    submitted: usize,
+    /// The time each admitted transaction was admitted at.
+    admitted: IndexMap<N::TransactionID, Duration>,
+    /// The time the injection ended at, once it ended.
+    injection_ended: Option<Duration>,
+    /// The time each admitted transaction was included at, in a block or as an aborted transaction.
+    included: IndexMap<N::TransactionID, Duration>,
+    /// The admitted transactions that were aborted.
+    aborted: IndexSet<N::TransactionID>,
+    /// The number of transmissions in each certificate of the committed subdags.
+    proposal_sizes: Vec<u64>,
+    /// The time each block was committed at.
+    blocks: Vec<Duration>,
+}
+
+impl<N: Network> Default for Recorder<N> {
+    fn default() -> Self {
+        Self {
+            submitted: 0,
+            admitted: Default::default(),
+            injection_ended: None,
+            included: Default::default(),
+            aborted: Default::default(),
+            proposal_sizes: Default::default(),
+            blocks: Default::default(),
+        }
+    }
+}
+
+impl<N: Network> Recorder<N> {
+    /// Records the submission of a transaction at the given time, and whether consensus admitted it.
+    pub fn record_submission(&mut self, transaction_id: N::TransactionID, is_admitted: bool, at: Duration) {
+        self.submitted += 1;
+        if is_admitted {
+            self.admitted.entry(transaction_id).or_insert(at);
+        }
+    }
+
+    /// Records the end of the injection at the given time.
+    pub fn record_injection_end(&mut self, at: Duration) {
+        self.injection_ended = Some(at);
+    }
+
+    /// Records a block committed at the given time.
+    pub fn record_block(&mut self, block: &Block<N>, at: Duration) {
+        let proposal_sizes = match block.authority() {
+            Authority::Quorum(subdag) => {
+                subdag.values().flatten().map(|certificate| certificate.transmission_ids().len() as u64).collect()
+            }
+            Authority::Beacon(_) => vec![],
+        };
+        self.record_committed(block.transaction_ids(), block.aborted_transaction_ids(), proposal_sizes, at);
+    }
+
+    /// Records the transactions and proposal sizes of a block committed at the given time.
+    fn record_committed<'a>(
+        &mut self,
+        transaction_ids: impl Iterator<Item = &'a N::TransactionID>,
+        aborted_transaction_ids: impl IntoIterator<Item = &'a N::TransactionID>,
+        proposal_sizes: Vec<u64>,
+        at: Duration,
+    ) {
+        for transaction_id in transaction_ids {
+            // Note: Only the injected transactions are recorded, as the genesis committee may transact too.
+            if self.admitted.contains_key(transaction_id) {
+                self.included.entry(*transaction_id).or_insert(at);
+            }
+        }
+        for transaction_id in aborted_transaction_ids {
+            if self.admitted.contains_key(transaction_id) {
+                self.included.entry(*transaction_id).or_insert(at);
+                self.aborted.insert(*transaction_id);
+            }
+        }
+        self.proposal_sizes.extend(proposal_sizes);
+        self.blocks.push(at);
+    }
+
+    /// Returns `true` if every admitted transaction was included, in a block or as an aborted transaction.
+    pub fn is_drained(&self) -> bool {
+        self.included.len() == self.admitted.len()
+    }
+
+    /// Returns the report of the run with the given parameters.
+    pub fn report(&self, parameters: Parameters) -> Report {
+        Report {
+            parameters,
+            admission: self.admission(),
+            proposals: self.proposals(),
+            inclusion: self.inclusion(),
+            blocks: self.blocks(),
+        }
+    }
+
+    /// Returns the admission report.
+    fn admission(&self) -> AdmissionReport {
+        let duration_in_secs = self.injection_ended.unwrap_or_default().as_secs_f64();
+        AdmissionReport {
+            submitted: self.submitted,
+            admitted: self.admitted.len(),
+            rejected: self.submitted - self.admitted.len(),
+            duration_in_secs,
+            submitted_per_sec: rate(self.submitted, duration_in_secs),
+            admitted_per_sec: rate(self.admitted.len(), duration_in_secs),
+        }
+    }
+
+    /// Returns the proposals report.
+    fn proposals(&self) -> ProposalsReport {
+        ProposalsReport { transmissions: Distribution::new(self.proposal_sizes.clone()) }
+    }
+
+    /// Returns the inclusion report.
+    fn inclusion(&self) -> InclusionReport {
+        let time_to_inclusion_in_ms = self
+            .included
+            .iter()
+            .map(|(transaction_id, included_at)| {
+                let admitted_at = self.admitted.get(transaction_id).copied().unwrap_or_default();
+                included_at.saturating_sub(admitted_at).as_millis() as u64
+            })
+            .collect();
+        InclusionReport {
+            included: self.included.len() - self.aborted.len(),
+            aborted: self.aborted.len(),
+            pending: self.admitted.len() - self.included.len(),
+            time_to_inclusion_in_ms: Distribution::new(time_to_inclusion_in_ms),
+        }
+    }
+
+    /// Returns the blocks report, over the time from the start of the injection to the last block.
+    fn blocks(&self) -> BlocksReport {
+        let duration_in_secs = self.blocks.last().copied().unwrap_or_default().as_secs_f64();
+        BlocksReport {
+            committed: self.blocks.len(),
+            duration_in_secs,
+            per_minute: rate(self.blocks.len(), duration_in_secs / 60.0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{Field, MainnetV0};
+
+    type CurrentNetwork = MainnetV0;
+
+    /// Returns the transaction ID with the given index.
+    fn sample_id(index: u64) -> <CurrentNetwork as Network>::TransactionID {
+        Field::<CurrentNetwork>::from_u64(index).into()
+    }
+
+    #[test]
+    fn test_recorder() {
+        let mut recorder = Recorder::<CurrentNetwork>::default();
+        let secs = Duration::from_secs;
+
+        // Submit three transactions, of which one is rejected.
+        recorder.record_submission(sample_id(1), true, secs(0));
+        recorder.record_submission(sample_id(2), false, secs(1));
+        recorder.record_submission(sample_id(3), true, secs(1));
+        recorder.record_injection_end(secs(2));
+        assert!(!recorder.is_drained());
+
+        // Commit a block with the first transaction and a foreign one, then a block aborting the third transaction.
+        recorder.record_committed([sample_id(1), sample_id(9)].iter(), &[], vec![2, 0], secs(3));
+        recorder.record_committed([].iter(), &[sample_id(3)], vec![1], secs(6));
+        assert!(recorder.is_drained());
+
+        let admission = recorder.admission();
+        assert_eq!((admission.submitted, admission.admitted, admission.rejected), (3, 2, 1));
+        assert_eq!((admission.submitted_per_sec, admission.admitted_per_sec), (1.5, 1.0));
+
+        // Ensure the foreign transaction is not counted, and the aborted transaction is counted once.
+        let inclusion = recorder.inclusion();
+        assert_eq!((inclusion.included, inclusion.aborted, inclusion.pending), (1, 1, 0));
+        assert_eq!((inclusion.time_to_inclusion_in_ms.min, inclusion.time_to_inclusion_in_ms.max), (3_000, 5_000));
+
+        assert_eq!(recorder.proposals().transmissions, Distribution::new(vec![2, 0, 1]));
+        let blocks = recorder.blocks();
+        assert_eq!((blocks.committed, blocks.per_minute), (2, 20.0));
+    }
+}
@@ -0,0 +1,141 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::Distribution;
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// The report of a benchmark run.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Report {
+    /// The parameters of the run.
+    pub parameters: Parameters,
+    /// The admission of the transactions into the memory pool.
+    pub admission: AdmissionReport,
+    /// The sizes of the proposals in the committed subdags.
+    pub proposals: ProposalsReport,
+    /// The inclusion of the admitted transactions in blocks.
+    pub inclusion: InclusionReport,
+    /// The blocks committed during the run.
+    pub blocks: BlocksReport,
+}
+
+/// The parameters of a benchmark run.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Parameters {
+    /// The name of the network.
+    pub network: String,
+    /// The number of validators of the development network.
+    pub validators: u16,
+    /// The number of transactions in the corpus.
+    pub transactions: usize,
+    /// The target injection rate, in transactions per second.
+    pub rate: u32,
+    /// The seed of the corpus.
+    pub seed: u64,
+}
+
+/// The admission of the transactions into the memory pool.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct AdmissionReport {
+    /// The number of transactions submitted to consensus.
+    pub submitted: usize,
+    /// The number of transactions admitted into the memory pool.
+    pub admitted: usize,
+    /// The number of transactions rejected by consensus.
+    pub rejected: usize,
+    /// The duration of the injection in seconds.
+    pub duration_in_secs: f64,
+    /// The number of transactions submitted per second.
+    pub submitted_per_sec: f64,
+    /// The number of transactions admitted per second.
+    pub admitted_per_sec: f64,
+}
+
+/// The sizes of the proposals in the committed subdags.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct ProposalsReport {
+    /// The distribution of the number of transmissions per certified proposal.
+    pub transmissions: Distribution,
+}
+
+/// The inclusion of the admitted transactions in blocks.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct InclusionReport {
+    /// The number of admitted transactions that were included in a block.
+    pub included: usize,
+    /// The number of admitted transactions that were aborted.
+    pub aborted: usize,
+    /// The number of admitted transactions that were neither included nor aborted by the end of the run.
+    pub pending: usize,
+    /// The distribution of the time from admission to inclusion in milliseconds, of included or aborted transactions.
+    pub time_to_inclusion_in_ms: Distribution,
+}
+
+/// The blocks committed during the run.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct BlocksReport {
+    /// The number of blocks committed.
+    pub committed: usize,
+    /// The time from the start of the injection to the last committed block in seconds.
+    pub duration_in_secs: f64,
+    /// The number of blocks committed per minute.
+    pub per_minute: f64,
+}
+
+impl fmt::Display for Report {
+    /// Formats the report as a human-readable summary.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let Self { parameters, admission, proposals, inclusion, blocks } = self;
+        let (sizes, latency) = (&proposals.transmissions, &inclusion.time_to_inclusion_in_ms);
+        writeln!(
+            f,
+            "Benchmark of {} transactions at {} tx/s on a {}-validator {} devnet (seed {})",
+            parameters.transactions, parameters.rate, parameters.validators, parameters.network, parameters.seed
+        )?;
+        writeln!(
+            f,
+            "  Admission:  {}/{} admitted ({} rejected) in {:.1}s - {:.1} tx/s admitted, {:.1} tx/s submitted",
+            admission.admitted,
+            admission.submitted,
+            admission.rejected,
+            admission.duration_in_secs,
+            admission.admitted_per_sec,
+            admission.submitted_per_sec
+        )?;
+        writeln!(
+            f,
+            "  Proposals:  {} certified - transmissions p50 {}, p90 {}, max {} (mean {:.1})",
+            sizes.count, sizes.p50, sizes.p90, sizes.max, sizes.mean
+        )?;
+        writeln!(
+            f,
+            "  Inclusion:  {} included, {} aborted, {} pending - p50 {}ms, p90 {}ms, p99 {}ms, max {}ms",
+            inclusion.included,
+            inclusion.aborted,
+            inclusion.pending,
+            latency.p50,
+            latency.p90,
+            latency.p99,
+            latency.max
+        )?;
+        write!(
+            f,
+            "  Blocks:     {} committed in {:.1}s - {:.2} blocks/min",
+            blocks.committed, blocks.duration_in_secs, blocks.per_minute
+        )
+    }
+}
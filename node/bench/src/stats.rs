@@ -0,0 +1,98 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use serde::{Deserialize, Serialize};
+
+/// The distribution of a set of samples, e.g. of the time-to-inclusion of transactions in milliseconds.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct Distribution {
+    /// The number of samples.
+    pub count: usize,
+    /// The smallest sample.
+    pub min: u64,
+    /// The mean of the samples.
+    pub mean: f64,
+    /// The median of the samples.
+    pub p50: u64,
+    /// The 90th percentile of the samples.
+    pub p90: u64,
+    /// The 99th percentile of the samples.
+    pub p99: u64,
+    /// The largest sample.
+    pub max: u64,
+}
+
+impl Distribution {
+    /// Returns the distribution of the given samples, or the default distribution if there are none.
+    pub fn new(mut samples: Vec<u64>) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        samples.sort_unstable();
+        let sum = samples.iter().map(|sample| u128::from(*sample)).sum::<u128>();
+        Self {
+            count: samples.len(),
+            min: samples[0],
+            mean: sum as f64 / samples.len() as f64,
+            p50: percentile(&samples, 50),
+            p90: percentile(&samples, 90),
+            p99: percentile(&samples, 99),
+            max: samples[samples.len() - 1],
+        }
+    }
+}
+
+/// Returns the given percentile of the given non-empty sorted samples, with the nearest-rank method.
+fn percentile(sorted: &[u64], percentile: usize) -> u64 {
+    let rank = (percentile * sorted.len()).div_ceil(100);
+    sorted[rank.saturating_sub(1)]
+}
+
+/// Returns the rate of the given number of events over the given duration in seconds, or zero if no time elapsed.
+pub fn rate(count: usize, duration_in_secs: f64) -> f64 {
+    match duration_in_secs > 0.0 {
+        true => count as f64 / duration_in_secs,
+        false => 0.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distribution() {
+        // Ensure the distribution of no samples is empty.
+        assert_eq!(Distribution::new(vec![]), Distribution::default());
+
+        // Ensure the percentiles follow the nearest-rank method, regardless of the order of the samples.
+        let distribution = Distribution::new((1..=100).rev().collect());
+        assert_eq!(distribution.count, 100);
+        assert_eq!((distribution.min, distribution.max), (1, 100));
+        assert_eq!((distribution.p50, distribution.p90, distribution.p99), (50, 90, 99));
+        assert_eq!(distribution.mean, 50.5);
+
+        // Ensure a single sample is every percentile.
+        let distribution = Distribution::new(vec![7]);
+        assert_eq!((distribution.min, distribution.p50, distribution.p99, distribution.max), (7, 7, 7, 7));
+    }
+
+    #[test]
+    fn test_rate() {
+        assert_eq!(rate(120, 60.0), 2.0);
+        // Ensure a rate over no time does not divide by zero.
+        assert_eq!(rate(10, 0.0), 0.0);
+    }
+}
@@ -30,6 +30,35 @@ impl<N: Network> EventTrait for ChallengeResponse<N> {
     }
 }
 
+impl<N: Network> ChallengeResponse<N> {
+    /// Returns the bytes the signer of a challenge response signs, given the versions of the signer and the verifier.
+    ///
+    /// The signature always covers the nonce of the challenge request of the verifier, and the response nonce.
+    /// If both sides are on the `FRESH_HANDSHAKE_VERSION` or later, it also covers the version of the signer,
+    /// and the addresses of the signer and the verifier, so that it cannot be presented in another handshake.
+    pub fn signed_bytes(
+        signer_version: u32,
+        verifier_version: u32,
+        signer: Address<N>,
+        verifier: Address<N>,
+        request_nonce: u64,
+        response_nonce: u64,
+    ) -> IoResult<Vec<u8>> {
+        let mut bytes = Vec::new();
+        if signer_version.min(verifier_version) >= Event::<N>::FRESH_HANDSHAKE_VERSION {
+            signer_version.write_le(&mut bytes)?;
+            request_nonce.write_le(&mut bytes)?;
+            response_nonce.write_le(&mut bytes)?;
+            signer.write_le(&mut bytes)?;
+            verifier.write_le(&mut bytes)?;
+        } else {
+            request_nonce.write_le(&mut bytes)?;
+            response_nonce.write_le(&mut bytes)?;
+        }
+        Ok(bytes)
+    }
+}
+
 impl<N: Network> ToBytes for ChallengeResponse<N> {
     fn write_le<W: Write>(&self, mut writer: W) -> IoResult<()> {
         self.restrictions_id.write_le(&mut writer)?;
@@ -29,6 +29,9 @@ pub enum DisconnectReason {
     ProtocolViolation,
     /// The peer's client is outdated, judging by its version.
     OutdatedClientVersion,
+    /// The node is shutting down.
+    /// Note: This is only sent to peers on the `SHUTTING_DOWN_VERSION` or later, as it is unknown to older peers.
+    ShuttingDown,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -64,6 +67,7 @@ impl FromBytes for Disconnect {
             Ok(1) => DisconnectReason::NoReasonGiven,
             Ok(2) => DisconnectReason::ProtocolViolation,
             Ok(3) => DisconnectReason::OutdatedClientVersion,
+            Ok(4) => DisconnectReason::ShuttingDown,
             _ => return Err(io::Error::new(io::ErrorKind::Other, "Invalid 'Disconnect' event")),
         };
 
@@ -73,7 +77,7 @@ impl FromBytes for Disconnect {
 
 #[cfg(test)]
 mod tests {
-    use crate::{Disconnect, DisconnectReason};
+    use crate::{Disconnect, DisconnectReason, Event};
     use snarkvm::console::prelude::{FromBytes, ToBytes};
 
     use bytes::{Buf, BufMut, BytesMut};
@@ -86,6 +90,7 @@ mod tests {
             DisconnectReason::NoReasonGiven,
             DisconnectReason::InvalidChallengeResponse,
             DisconnectReason::OutdatedClientVersion,
+            DisconnectReason::ShuttingDown,
         ];
 
         for reason in all_reasons.iter() {
@@ -98,6 +103,17 @@ mod tests {
         }
     }
 
+    #[test]
+    fn shutting_down_is_versioned() {
+        type CurrentNetwork = snarkvm::prelude::MainnetV0;
+
+        // Ensure the shutting down reason is a version after the minimum one, and up to the current one.
+        assert!(Event::<CurrentNetwork>::MINIMUM_VERSION < Event::<CurrentNetwork>::SHUTTING_DOWN_VERSION);
+        assert!(Event::<CurrentNetwork>::SHUTTING_DOWN_VERSION <= Event::<CurrentNetwork>::VERSION);
+        // Ensure it is introduced by a version bump, as older peers cannot read it.
+        assert!(Event::<CurrentNetwork>::SHUTTING_DOWN_VERSION > Event::<CurrentNetwork>::BOUNDED_VALIDATORS_VERSION);
+    }
+
     #[test]
     #[should_panic(expected = "Invalid 'Disconnect' event")]
     fn deserializing_invalid_data_panics() {
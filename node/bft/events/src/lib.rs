@@ -51,6 +51,12 @@ pub use helpers::*;
 mod primary_ping;
 pub use primary_ping::PrimaryPing;
 
+mod transmission_chunk;
+pub use transmission_chunk::{TRANSMISSION_CHUNK_SIZE, TransmissionChunk};
+
+mod transmission_chunk_request;
+pub use transmission_chunk_request::TransmissionChunkRequest;
+
 mod transmission_request;
 pub use transmission_request::TransmissionRequest;
 
@@ -108,6 +114,8 @@ pub enum Event<N: Network> {
     ValidatorsRequest(ValidatorsRequest),
     ValidatorsResponse(ValidatorsResponse<N>),
     WorkerPing(WorkerPing<N>),
+    TransmissionChunk(TransmissionChunk<N>),
+    TransmissionChunkRequest(TransmissionChunkRequest<N>),
 }
 
 impl<N: Network> From<DisconnectReason> for Event<N> {
@@ -117,8 +125,39 @@ impl<N: Network> From<DisconnectReason> for Event<N> {
 }
 
 impl<N: Network> Event<N> {
-    /// The version of the event protocol; it can be incremented in order to force users to update.
-    pub const VERSION: u32 = 8;
+    /// The version of the event protocol from which validators requests carry the maximum number of validators to send.
+    pub const BOUNDED_VALIDATORS_VERSION: u32 = 11;
+    /// The version of the event protocol from which transmissions may be transferred in chunks.
+    pub const CHUNKED_TRANSMISSIONS_VERSION: u32 = 9;
+    /// The version of the event protocol from which challenge responses sign the version and the addresses.
+    pub const FRESH_HANDSHAKE_VERSION: u32 = 10;
+    /// The minimum version of the event protocol accepted from peers; it can be incremented in order to force users to update.
+    pub const MINIMUM_VERSION: u32 = 8;
+    /// The names of the events, indexed by their event ID.
+    pub const NAMES: [&'static str; 18] = [
+        "BatchPropose",
+        "BatchSignature",
+        "BatchCertified",
+        "BlockRequest",
+        "BlockResponse",
+        "CertificateRequest",
+        "CertificateResponse",
+        "ChallengeRequest",
+        "ChallengeResponse",
+        "Disconnect",
+        "PrimaryPing",
+        "TransmissionRequest",
+        "TransmissionResponse",
+        "ValidatorsRequest",
+        "ValidatorsResponse",
+        "WorkerPing",
+        "TransmissionChunk",
+        "TransmissionChunkRequest",
+    ];
+    /// The version of the event protocol from which a disconnect may give the `ShuttingDown` reason.
+    pub const SHUTTING_DOWN_VERSION: u32 = 12;
+    /// The version of the event protocol.
+    pub const VERSION: u32 = 12;
 
     /// Returns the event name.
     #[inline]
@@ -140,6 +179,8 @@ impl<N: Network> Event<N> {
             Self::ValidatorsRequest(event) => event.name(),
             Self::ValidatorsResponse(event) => event.name(),
             Self::WorkerPing(event) => event.name(),
+            Self::TransmissionChunk(event) => event.name(),
+            Self::TransmissionChunkRequest(event) => event.name(),
         }
     }
 
@@ -163,6 +204,8 @@ impl<N: Network> Event<N> {
             Self::ValidatorsRequest(..) => 13,
             Self::ValidatorsResponse(..) => 14,
             Self::WorkerPing(..) => 15,
+            Self::TransmissionChunk(..) => 16,
+            Self::TransmissionChunkRequest(..) => 17,
         }
     }
 }
@@ -188,6 +231,8 @@ impl<N: Network> ToBytes for Event<N> {
             Self::ValidatorsRequest(event) => event.write_le(writer),
             Self::ValidatorsResponse(event) => event.write_le(writer),
             Self::WorkerPing(event) => event.write_le(writer),
+            Self::TransmissionChunk(event) => event.write_le(writer),
+            Self::TransmissionChunkRequest(event) => event.write_le(writer),
         }
     }
 }
@@ -215,7 +260,9 @@ impl<N: Network> FromBytes for Event<N> {
             13 => Self::ValidatorsRequest(ValidatorsRequest::read_le(&mut reader)?),
             14 => Self::ValidatorsResponse(ValidatorsResponse::read_le(&mut reader)?),
             15 => Self::WorkerPing(WorkerPing::read_le(&mut reader)?),
-            16.. => return Err(error(format!("Unknown event ID {id}"))),
+            16 => Self::TransmissionChunk(TransmissionChunk::read_le(&mut reader)?),
+            17 => Self::TransmissionChunkRequest(TransmissionChunkRequest::read_le(&mut reader)?),
+            18.. => return Err(error(format!("Unknown event ID {id}"))),
         };
 
         // Ensure that there are no "dangling" bytes.
@@ -259,8 +306,12 @@ pub mod prop_tests {
         certificate_response::prop_tests::any_certificate_response,
         challenge_request::prop_tests::any_challenge_request,
         challenge_response::prop_tests::any_challenge_response,
+        transmission_chunk::prop_tests::any_transmission_chunk,
+        transmission_chunk_request::prop_tests::any_transmission_chunk_request,
         transmission_request::prop_tests::any_transmission_request,
         transmission_response::prop_tests::any_transmission_response,
+        validators_request::prop_tests::any_validators_request,
+        validators_response::prop_tests::any_validators_response,
         worker_ping::prop_tests::any_worker_ping,
     };
     use snarkvm::{
@@ -321,13 +372,18 @@ pub mod prop_tests {
                     DisconnectReason::NoReasonGiven,
                     DisconnectReason::InvalidChallengeResponse,
                     DisconnectReason::OutdatedClientVersion,
+                    DisconnectReason::ShuttingDown,
                 ]),
                 any::<Selector>()
             )
                 .prop_map(|(reasons, selector)| Event::Disconnect(Disconnect::from(selector.select(reasons)))),
             any_transmission_request().prop_map(Event::TransmissionRequest),
             any_transmission_response().prop_map(Event::TransmissionResponse),
-            any_worker_ping().prop_map(Event::WorkerPing)
+            any_validators_request().prop_map(Event::ValidatorsRequest),
+            any_validators_response().prop_map(Event::ValidatorsResponse),
+            any_worker_ping().prop_map(Event::WorkerPing),
+            any_transmission_chunk().prop_map(Event::TransmissionChunk),
+            any_transmission_chunk_request().prop_map(Event::TransmissionChunkRequest)
         ]
         .boxed()
     }
@@ -341,4 +397,9 @@ pub mod prop_tests {
         assert_eq!(original.id(), deserialized.id());
         assert_eq!(original.name(), deserialized.name());
     }
+
+    #[proptest]
+    fn names_match_ids(#[strategy(any_event())] event: Event<CurrentNetwork>) {
+        assert_eq!(Event::<CurrentNetwork>::NAMES[event.id() as usize], event.name());
+    }
 }
@@ -0,0 +1,122 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+use bytes::Bytes;
+
+/// The size in bytes of a transmission chunk; transmissions larger than this are transferred in chunks.
+pub const TRANSMISSION_CHUNK_SIZE: usize = 256 * 1024; // 256 KiB
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransmissionChunk<N: Network> {
+    pub transmission_id: TransmissionID<N>,
+    pub index: u32,
+    pub num_chunks: u32,
+    pub bytes: Bytes,
+}
+
+impl<N: Network> TransmissionChunk<N> {
+    /// Initializes a new transmission chunk event.
+    pub fn new(transmission_id: TransmissionID<N>, index: u32, num_chunks: u32, bytes: Bytes) -> Self {
+        Self { transmission_id, index, num_chunks, bytes }
+    }
+
+    /// Splits the serialized transmission into chunks of (at most) `TRANSMISSION_CHUNK_SIZE` bytes.
+    pub fn split(transmission_id: TransmissionID<N>, transmission: &Transmission<N>) -> IoResult<Vec<Self>> {
+        Self::split_bytes(transmission_id, Bytes::from(transmission.to_bytes_le()?))
+    }
+
+    /// Splits the given serialized transmission into chunks of (at most) `TRANSMISSION_CHUNK_SIZE` bytes,
+    /// without copying the bytes.
+    pub fn split_bytes(transmission_id: TransmissionID<N>, bytes: Bytes) -> IoResult<Vec<Self>> {
+        let num_chunks = u32::try_from(bytes.len().div_ceil(TRANSMISSION_CHUNK_SIZE))
+            .map_err(|_| error("Too many transmission chunks"))?;
+        Ok((0..num_chunks)
+            .map(|index| {
+                let start = index as usize * TRANSMISSION_CHUNK_SIZE;
+                let end = bytes.len().min(start + TRANSMISSION_CHUNK_SIZE);
+                Self::new(transmission_id, index, num_chunks, bytes.slice(start..end))
+            })
+            .collect())
+    }
+}
+
+impl<N: Network> EventTrait for TransmissionChunk<N> {
+    /// Returns the event name.
+    #[inline]
+    fn name(&self) -> Cow<'static, str> {
+        "TransmissionChunk".into()
+    }
+}
+
+impl<N: Network> ToBytes for TransmissionChunk<N> {
+    fn write_le<W: Write>(&self, mut writer: W) -> IoResult<()> {
+        self.transmission_id.write_le(&mut writer)?;
+        self.index.write_le(&mut writer)?;
+        self.num_chunks.write_le(&mut writer)?;
+        u32::try_from(self.bytes.len()).map_err(error)?.write_le(&mut writer)?;
+        writer.write_all(&self.bytes)?;
+        Ok(())
+    }
+}
+
+impl<N: Network> FromBytes for TransmissionChunk<N> {
+    fn read_le<R: Read>(mut reader: R) -> IoResult<Self> {
+        let transmission_id = TransmissionID::read_le(&mut reader)?;
+        let index = u32::read_le(&mut reader)?;
+        let num_chunks = u32::read_le(&mut reader)?;
+        let num_bytes = u32::read_le(&mut reader)? as usize;
+        if num_bytes > TRANSMISSION_CHUNK_SIZE {
+            return Err(error(format!("Transmission chunk exceeds the maximum size ({num_bytes} bytes)")));
+        }
+        let mut bytes = vec![0u8; num_bytes];
+        reader.read_exact(&mut bytes)?;
+
+        Ok(Self { transmission_id, index, num_chunks, bytes: bytes.into() })
+    }
+}
+
+#[cfg(test)]
+pub mod prop_tests {
+    use crate::{TransmissionChunk, prop_tests::any_transmission_id};
+    use snarkvm::console::prelude::{FromBytes, ToBytes};
+
+    use bytes::{Buf, BufMut, Bytes, BytesMut};
+    use proptest::{
+        collection,
+        prelude::{BoxedStrategy, Strategy, any},
+    };
+    use test_strategy::proptest;
+
+    type CurrentNetwork = snarkvm::prelude::MainnetV0;
+
+    pub fn any_transmission_chunk() -> BoxedStrategy<TransmissionChunk<CurrentNetwork>> {
+        (any_transmission_id(), any::<u32>(), any::<u32>(), collection::vec(any::<u8>(), 0..=1024))
+            .prop_map(|(id, index, num_chunks, bytes)| {
+                TransmissionChunk::new(id, index, num_chunks, Bytes::from(bytes))
+            })
+            .boxed()
+    }
+
+    #[proptest]
+    fn serialize_deserialize(#[strategy(any_transmission_chunk())] original: TransmissionChunk<CurrentNetwork>) {
+        let mut buf = BytesMut::default().writer();
+        TransmissionChunk::write_le(&original, &mut buf).unwrap();
+
+        let deserialized = TransmissionChunk::read_le(buf.into_inner().reader()).unwrap();
+        assert_eq!(original, deserialized);
+    }
+}
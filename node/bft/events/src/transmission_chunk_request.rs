@@ -0,0 +1,87 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+/// A request for the chunks in the range `start..end` of a transmission, to resume a chunked transfer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransmissionChunkRequest<N: Network> {
+    pub transmission_id: TransmissionID<N>,
+    pub start: u32,
+    pub end: u32,
+}
+
+impl<N: Network> TransmissionChunkRequest<N> {
+    /// Initializes a new transmission chunk request event.
+    pub const fn new(transmission_id: TransmissionID<N>, start: u32, end: u32) -> Self {
+        Self { transmission_id, start, end }
+    }
+}
+
+impl<N: Network> EventTrait for TransmissionChunkRequest<N> {
+    /// Returns the event name.
+    #[inline]
+    fn name(&self) -> Cow<'static, str> {
+        "TransmissionChunkRequest".into()
+    }
+}
+
+impl<N: Network> ToBytes for TransmissionChunkRequest<N> {
+    fn write_le<W: Write>(&self, mut writer: W) -> IoResult<()> {
+        self.transmission_id.write_le(&mut writer)?;
+        self.start.write_le(&mut writer)?;
+        self.end.write_le(&mut writer)?;
+        Ok(())
+    }
+}
+
+impl<N: Network> FromBytes for TransmissionChunkRequest<N> {
+    fn read_le<R: Read>(mut reader: R) -> IoResult<Self> {
+        let transmission_id = TransmissionID::read_le(&mut reader)?;
+        let start = u32::read_le(&mut reader)?;
+        let end = u32::read_le(&mut reader)?;
+
+        Ok(Self { transmission_id, start, end })
+    }
+}
+
+#[cfg(test)]
+pub mod prop_tests {
+    use crate::{TransmissionChunkRequest, prop_tests::any_transmission_id};
+    use snarkvm::console::prelude::{FromBytes, ToBytes};
+
+    use bytes::{Buf, BufMut, BytesMut};
+    use proptest::prelude::{BoxedStrategy, Strategy, any};
+    use test_strategy::proptest;
+
+    type CurrentNetwork = snarkvm::prelude::MainnetV0;
+
+    pub fn any_transmission_chunk_request() -> BoxedStrategy<TransmissionChunkRequest<CurrentNetwork>> {
+        (any_transmission_id(), any::<u32>(), any::<u32>())
+            .prop_map(|(id, start, end)| TransmissionChunkRequest::new(id, start, end))
+            .boxed()
+    }
+
+    #[proptest]
+    fn serialize_deserialize(
+        #[strategy(any_transmission_chunk_request())] original: TransmissionChunkRequest<CurrentNetwork>,
+    ) {
+        let mut buf = BytesMut::default().writer();
+        TransmissionChunkRequest::write_le(&original, &mut buf).unwrap();
+
+        let deserialized = TransmissionChunkRequest::read_le(buf.into_inner().reader()).unwrap();
+        assert_eq!(original, deserialized);
+    }
+}
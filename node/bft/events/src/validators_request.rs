@@ -15,8 +15,20 @@
 
 use super::*;
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
-pub struct ValidatorsRequest;
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ValidatorsRequest {
+    /// The maximum number of validators the requester accepts in the response, if any.
+    ///
+    /// Note: Peers before the `BOUNDED_VALIDATORS_VERSION` send an empty request, which is read as `None`.
+    pub max_validators: Option<u16>,
+}
+
+impl ValidatorsRequest {
+    /// Initializes a new validators request, with the given maximum number of validators in the response.
+    pub const fn new(max_validators: Option<u16>) -> Self {
+        Self { max_validators }
+    }
+}
 
 impl EventTrait for ValidatorsRequest {
     /// Returns the event name.
@@ -27,30 +39,71 @@ impl EventTrait for ValidatorsRequest {
 }
 
 impl ToBytes for ValidatorsRequest {
-    fn write_le<W: Write>(&self, _writer: W) -> IoResult<()> {
-        Ok(())
+    fn write_le<W: Write>(&self, mut writer: W) -> IoResult<()> {
+        // Note: A request without a maximum is written as the empty request of the previous versions.
+        match self.max_validators {
+            Some(max_validators) => max_validators.write_le(&mut writer),
+            None => Ok(()),
+        }
     }
 }
 
 impl FromBytes for ValidatorsRequest {
-    fn read_le<R: Read>(_reader: R) -> IoResult<Self> {
-        Ok(Self)
+    fn read_le<R: Read>(mut reader: R) -> IoResult<Self> {
+        // Note: The request is the last field of its event, so it extends to the end of the reader.
+        let mut bytes = Vec::with_capacity(2);
+        reader.take(3).read_to_end(&mut bytes)?;
+        let max_validators = match bytes.len() {
+            0 => None,
+            2 => Some(u16::from_le_bytes([bytes[0], bytes[1]])),
+            _ => return Err(error("Invalid validators request")),
+        };
+        Ok(Self { max_validators })
     }
 }
 
 #[cfg(test)]
-pub mod tests {
-    use crate::ValidatorsRequest;
+pub mod prop_tests {
+    use crate::{Event, ValidatorsRequest};
 
     use bytes::{Buf, BufMut, BytesMut};
+    use proptest::prelude::{BoxedStrategy, Strategy, any};
     use snarkvm::utilities::{FromBytes, ToBytes};
+    use test_strategy::proptest;
 
-    #[test]
-    fn validators_request_roundtrip() {
-        let validators_request = ValidatorsRequest;
+    type CurrentNetwork = snarkvm::prelude::MainnetV0;
+
+    pub fn any_validators_request() -> BoxedStrategy<ValidatorsRequest> {
+        any::<Option<u16>>().prop_map(ValidatorsRequest::new).boxed()
+    }
+
+    #[proptest]
+    fn validators_request_roundtrip(#[strategy(any_validators_request())] validators_request: ValidatorsRequest) {
         let mut bytes = BytesMut::default().writer();
         validators_request.write_le(&mut bytes).unwrap();
         let decoded = ValidatorsRequest::read_le(&mut bytes.into_inner().reader()).unwrap();
         assert_eq![decoded, validators_request];
     }
+
+    #[test]
+    fn validators_request_legacy_compat() {
+        // Ensure the bounded validators requests are a version after the minimum one, and up to the current one.
+        assert!(Event::<CurrentNetwork>::MINIMUM_VERSION < Event::<CurrentNetwork>::BOUNDED_VALIDATORS_VERSION);
+        assert!(Event::<CurrentNetwork>::BOUNDED_VALIDATORS_VERSION <= Event::<CurrentNetwork>::VERSION);
+
+        // The event of a validators request before the bounded validators requests, which is only its event ID.
+        let legacy_bytes = 13u16.to_le_bytes().to_vec();
+        // Ensure a legacy validators request is read without a maximum.
+        let event = Event::<CurrentNetwork>::read_le(&legacy_bytes[..]).unwrap();
+        assert_eq!(event, Event::ValidatorsRequest(ValidatorsRequest::new(None)));
+        // Ensure a validators request without a maximum is written as a legacy one.
+        assert_eq!(event.to_bytes_le().unwrap(), legacy_bytes);
+
+        // Ensure a validators request with a maximum is read back, and a truncated one is rejected.
+        let event = Event::<CurrentNetwork>::ValidatorsRequest(ValidatorsRequest::new(Some(100)));
+        let bytes = event.to_bytes_le().unwrap();
+        assert_eq!(bytes.len(), legacy_bytes.len() + 2);
+        assert_eq!(Event::<CurrentNetwork>::read_le(&bytes[..]).unwrap(), event);
+        assert!(Event::<CurrentNetwork>::read_le(&bytes[..bytes.len() - 1]).is_err());
+    }
 }
@@ -20,6 +20,11 @@ pub struct ValidatorsResponse<N: Network> {
     pub validators: IndexMap<SocketAddr, Address<N>>,
 }
 
+impl<N: Network> ValidatorsResponse<N> {
+    /// The maximum number of validators that can be sent in a single response.
+    pub const MAX_VALIDATORS: usize = 200;
+}
+
 impl<N: Network> EventTrait for ValidatorsResponse<N> {
     /// Returns the event name.
     #[inline]
@@ -30,6 +35,10 @@ impl<N: Network> EventTrait for ValidatorsResponse<N> {
 
 impl<N: Network> ToBytes for ValidatorsResponse<N> {
     fn write_le<W: Write>(&self, mut writer: W) -> IoResult<()> {
+        // Ensure the number of validators is within the maximum.
+        if self.validators.len() > Self::MAX_VALIDATORS {
+            return Err(error(format!("Too many validators in a validators response ({})", self.validators.len())));
+        }
         // Write the number of validators.
         u16::try_from(self.validators.len()).map_err(error)?.write_le(&mut writer)?;
         // Write the validators.
@@ -45,6 +54,10 @@ impl<N: Network> FromBytes for ValidatorsResponse<N> {
     fn read_le<R: Read>(mut reader: R) -> IoResult<Self> {
         // Read the number of validators.
         let num_validators = u16::read_le(&mut reader)?;
+        // Ensure the number of validators is within the maximum, before allocating them.
+        if num_validators as usize > Self::MAX_VALIDATORS {
+            return Err(error(format!("Too many validators in a validators response ({num_validators})")));
+        }
         // Read the validators.
         let mut validators = IndexMap::with_capacity(num_validators as usize);
         for _ in 0..num_validators {
@@ -98,4 +111,25 @@ pub mod prop_tests {
         let decoded = ValidatorsResponse::<CurrentNetwork>::read_le(&mut bytes.into_inner().reader()).unwrap();
         assert_eq![decoded, validators_response];
     }
+
+    #[proptest]
+    fn validators_response_over_maximum(
+        #[strategy(any_validators_response())] validators_response: ValidatorsResponse<CurrentNetwork>,
+        #[strategy(any_valid_address())] address: Address<CurrentNetwork>,
+    ) {
+        let max_validators = ValidatorsResponse::<CurrentNetwork>::MAX_VALIDATORS;
+        // Ensure a response over the maximum is not written.
+        let validators = (0..=max_validators as u16).map(|port| (SocketAddr::from(([127, 0, 0, 1], port)), address));
+        let oversized = ValidatorsResponse::<CurrentNetwork> { validators: validators.collect() };
+        assert!(oversized.to_bytes_le().is_err());
+
+        // Ensure a response claiming more validators than the maximum is not read, regardless of its contents.
+        let mut bytes = BytesMut::default().writer();
+        (max_validators as u16 + 1).write_le(&mut bytes).unwrap();
+        for (socket_addr, address) in &validators_response.validators {
+            socket_addr.write_le(&mut bytes).unwrap();
+            address.write_le(&mut bytes).unwrap();
+        }
+        assert!(ValidatorsResponse::<CurrentNetwork>::read_le(&mut bytes.into_inner().reader()).is_err());
+    }
 }
@@ -0,0 +1,81 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Benchmarks the leader elections of the BFT, with and without the leader schedule.
+//!
+//! Run with `cargo run --release --example leader_schedule`.
+//!
+//! Each election of the committee clones and sorts its members, while the leader schedule sorts them
+//! once per committee. The leaders of the rounds are computed once each, on synthetic committees
+//! of increasing size, so the comparison excludes the repeated lookups that the schedule memoizes.
+
+use snarkos_node_bft::helpers::LeaderSchedule;
+use snarkvm::{
+    console::{account::PrivateKey, types::Address},
+    ledger::committee::{Committee, MIN_VALIDATOR_STAKE},
+    prelude::MainnetV0,
+    utilities::TestRng,
+};
+
+use anyhow::Result;
+use indexmap::IndexMap;
+use rand::Rng;
+use std::time::{Duration, Instant};
+
+type CurrentNetwork = MainnetV0;
+
+/// The number of even rounds whose leaders are computed.
+const NUM_ROUNDS: u64 = 10_000;
+
+/// Returns a synthetic committee with the given number of members, of distinct stakes.
+fn sample_committee(num_members: usize, rng: &mut TestRng) -> Result<Committee<CurrentNetwork>> {
+    let mut members = IndexMap::with_capacity(num_members);
+    for _ in 0..num_members {
+        let address = Address::try_from(PrivateKey::<CurrentNetwork>::new(rng)?)?;
+        members.insert(address, (MIN_VALIDATOR_STAKE + rng.gen_range(0..MIN_VALIDATOR_STAKE), false, 0));
+    }
+    Committee::new(0, members)
+}
+
+/// Returns the duration of the elections of the even rounds, with the given election function.
+fn time(mut elect: impl FnMut(u64) -> Result<Address<CurrentNetwork>>) -> Result<Duration> {
+    let start = Instant::now();
+    for round in (1..=NUM_ROUNDS).map(|index| index * 2) {
+        std::hint::black_box(elect(round)?);
+    }
+    Ok(start.elapsed())
+}
+
+fn main() -> Result<()> {
+    let rng = &mut TestRng::default();
+    println!("Electing the leaders of {NUM_ROUNDS} even rounds\n");
+
+    for num_members in [100, 1_000] {
+        // The committee size is bounded by snarkVM, so a larger synthetic committee may be rejected.
+        let committee = match sample_committee(num_members, rng) {
+            Ok(committee) => committee,
+            Err(error) => {
+                println!("{num_members:>5} members: skipped - {error}");
+                continue;
+            }
+        };
+        let elections = time(|round| committee.get_leader(round))?;
+        let schedule = LeaderSchedule::default();
+        let scheduled = time(|round| schedule.get_leader(&committee, round))?;
+        let speedup = elections.as_secs_f64() / scheduled.as_secs_f64();
+        println!("{num_members:>5} members: elections {elections:>10.2?}, schedule {scheduled:>10.2?} ({speedup:.1}x)");
+    }
+    Ok(())
+}
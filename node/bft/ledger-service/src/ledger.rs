@@ -13,7 +13,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{LedgerService, fmt_id, spawn_blocking};
+use crate::{
+    LedgerService,
+    committee_lookback_round,
+    fmt_id,
+    read_block,
+    read_block_hash,
+    read_block_height,
+    read_blocks,
+    spawn_blocking,
+};
+use snarkos_node_bft_storage_service::WriteVerifier;
 use snarkvm::{
     ledger::{
         Ledger,
@@ -49,13 +59,26 @@ pub struct CoreLedgerService<N: Network, C: ConsensusStorage<N>> {
     committee_cache: Arc<Mutex<LruCache<u64, Committee<N>>>>,
     latest_leader: Arc<RwLock<Option<(u64, Address<N>)>>>,
     shutdown: Arc<AtomicBool>,
+    write_verifier: Arc<WriteVerifier>,
 }
 
 impl<N: Network, C: ConsensusStorage<N>> CoreLedgerService<N, C> {
     /// Initializes a new core ledger service.
     pub fn new(ledger: Ledger<N, C>, shutdown: Arc<AtomicBool>) -> Self {
         let committee_cache = Arc::new(Mutex::new(LruCache::new(COMMITTEE_CACHE_SIZE.try_into().unwrap())));
-        Self { ledger, committee_cache, latest_leader: Default::default(), shutdown }
+        Self {
+            ledger,
+            committee_cache,
+            latest_leader: Default::default(),
+            shutdown,
+            write_verifier: Default::default(),
+        }
+    }
+
+    /// Sets the verifier of the critical ledger writes, which reads back each block that is added to the ledger.
+    pub fn with_write_verifier(mut self, write_verifier: Arc<WriteVerifier>) -> Self {
+        self.write_verifier = write_verifier;
+        self
     }
 }
 
@@ -105,28 +128,28 @@ impl<N: Network, C: ConsensusStorage<N>> LedgerService<N> for CoreLedgerService<
 
     /// Returns the block height for the given block hash, if it exists.
     fn get_block_height(&self, hash: &N::BlockHash) -> Result<u32> {
-        self.ledger.get_height(hash)
+        read_block_height(&self.ledger, hash)
     }
 
     /// Returns the block hash for the given block height, if it exists.
     fn get_block_hash(&self, height: u32) -> Result<N::BlockHash> {
-        self.ledger.get_hash(height)
+        read_block_hash(&self.ledger, height)
     }
 
     /// Returns the block round for the given block height, if it exists.
     fn get_block_round(&self, height: u32) -> Result<u64> {
-        self.ledger.get_block(height).map(|block| block.round())
+        read_block(&self.ledger, height).map(|block| block.round())
     }
 
     /// Returns the block for the given block height.
     fn get_block(&self, height: u32) -> Result<Block<N>> {
-        self.ledger.get_block(height)
+        read_block(&self.ledger, height)
     }
 
     /// Returns the blocks in the given block range.
     /// The range is inclusive of the start and exclusive of the end.
     fn get_blocks(&self, heights: Range<u32>) -> Result<Vec<Block<N>>> {
-        self.ledger.get_blocks(heights)
+        read_blocks(&self.ledger, heights)
     }
 
     /// Returns the solution for the given solution ID.
@@ -183,18 +206,8 @@ impl<N: Network, C: ConsensusStorage<N>> LedgerService<N> for CoreLedgerService<
 
     /// Returns the committee lookback for the given round.
     fn get_committee_lookback_for_round(&self, round: u64) -> Result<Committee<N>> {
-        // Get the round number for the previous committee. Note, we subtract 2 from odd rounds,
-        // because committees are updated in even rounds.
-        let previous_round = match round % 2 == 0 {
-            true => round.saturating_sub(1),
-            false => round.saturating_sub(2),
-        };
-
-        // Get the committee lookback round.
-        let committee_lookback_round = previous_round.saturating_sub(Committee::<N>::COMMITTEE_LOOKBACK_RANGE);
-
         // Retrieve the committee for the committee lookback round.
-        self.get_committee_for_round(committee_lookback_round)
+        self.get_committee_for_round(committee_lookback_round::<N>(round))
     }
 
     /// Returns `true` if the ledger contains the given certificate ID in block history.
@@ -363,8 +376,13 @@ impl<N: Network, C: ConsensusStorage<N>> LedgerService<N> for CoreLedgerService<
         if self.shutdown.load(Ordering::Acquire) {
             bail!("Skipping advancing to block {} - The node is shutting down", block.height());
         }
-        // Advance to the next block.
-        self.ledger.advance_to_next_block(block)?;
+        // Advance to the next block, and read back its critical writes if write verification is enabled.
+        if let Err(error) = crate::advance_with_write_verification(&self.write_verifier, &self.ledger, block) {
+            if let Some(mismatch) = crate::WriteMismatch::of(&error) {
+                tracing::error!("{mismatch} - the node stopped advancing the ledger, as its storage is corrupted");
+            }
+            return Err(error);
+        }
         // Update BFT metrics.
         #[cfg(feature = "metrics")]
         {
@@ -38,9 +38,31 @@ pub mod translucent;
 #[cfg(feature = "translucent")]
 pub use translucent::*;
 
+pub mod read;
+pub use read::*;
+
+#[cfg(feature = "test")]
+pub mod test_helpers;
+
 pub mod traits;
 pub use traits::*;
 
+#[cfg(feature = "ledger-write")]
+pub mod write_verification;
+#[cfg(feature = "ledger-write")]
+pub use write_verification::*;
+
+/// Returns the round of the committee that is in charge of the given round, i.e. its committee lookback.
+///
+/// Note: Two rounds are subtracted from odd rounds, because committees are updated in even rounds.
+pub fn committee_lookback_round<N: snarkvm::prelude::Network>(round: u64) -> u64 {
+    let previous_round = match round % 2 == 0 {
+        true => round.saturating_sub(1),
+        false => round.saturating_sub(2),
+    };
+    previous_round.saturating_sub(snarkvm::ledger::committee::Committee::<N>::COMMITTEE_LOOKBACK_RANGE)
+}
+
 /// Formats an ID into a truncated identifier (for logging purposes).
 pub fn fmt_id(id: impl ToString) -> String {
     let id = id.to_string();
@@ -13,7 +13,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{LedgerService, fmt_id};
+use crate::{LedgerReadError, LedgerService, fmt_id};
 use snarkvm::{
     ledger::{
         block::{Block, Transaction},
@@ -24,9 +24,13 @@ use snarkvm::{
     prelude::{Address, Field, Network, Result, Zero, bail, ensure},
 };
 
-use indexmap::IndexMap;
+use indexmap::{IndexMap, IndexSet};
 use parking_lot::Mutex;
-use std::{collections::BTreeMap, ops::Range};
+use std::{
+    collections::BTreeMap,
+    ops::Range,
+    sync::atomic::{AtomicUsize, Ordering},
+};
 use tracing::*;
 
 /// A mock ledger service that always returns `false`.
@@ -34,12 +38,27 @@ use tracing::*;
 pub struct MockLedgerService<N: Network> {
     committee: Committee<N>,
     height_to_round_and_hash: Mutex<BTreeMap<u32, (u64, N::BlockHash)>>,
+    /// The number of upcoming block hash reads that fail transiently.
+    transient_failures: AtomicUsize,
+    /// The certificates recorded as committed to the ledger.
+    certificates: Mutex<IndexMap<Field<N>, BatchCertificate<N>>>,
+    /// The transactions that fail the basic checks.
+    invalid_transactions: Mutex<IndexSet<N::TransactionID>>,
+    /// The transactions recorded as confirmed in the ledger.
+    confirmed_transactions: Mutex<IndexSet<N::TransactionID>>,
 }
 
 impl<N: Network> MockLedgerService<N> {
     /// Initializes a new mock ledger service.
     pub fn new(committee: Committee<N>) -> Self {
-        Self { committee, height_to_round_and_hash: Default::default() }
+        Self {
+            committee,
+            height_to_round_and_hash: Default::default(),
+            transient_failures: Default::default(),
+            certificates: Default::default(),
+            invalid_transactions: Default::default(),
+            confirmed_transactions: Default::default(),
+        }
     }
 
     /// Initializes a new mock ledger service at the specified height.
@@ -48,7 +67,34 @@ impl<N: Network> MockLedgerService<N> {
         for i in 0..=height {
             height_to_hash.insert(i, (i as u64 * 2, Field::<N>::from_u32(i).into()));
         }
-        Self { committee, height_to_round_and_hash: Mutex::new(height_to_hash) }
+        Self {
+            committee,
+            height_to_round_and_hash: Mutex::new(height_to_hash),
+            transient_failures: Default::default(),
+            certificates: Default::default(),
+            invalid_transactions: Default::default(),
+            confirmed_transactions: Default::default(),
+        }
+    }
+
+    /// Records the given certificate as committed to the ledger.
+    pub fn insert_certificate(&self, certificate: BatchCertificate<N>) {
+        self.certificates.lock().insert(certificate.id(), certificate);
+    }
+
+    /// Fails the basic checks of the given transaction, as an invalid proof would.
+    pub fn invalidate_transaction(&self, transaction_id: N::TransactionID) {
+        self.invalid_transactions.lock().insert(transaction_id);
+    }
+
+    /// Records the given transaction as confirmed in the ledger.
+    pub fn confirm_transaction(&self, transaction_id: N::TransactionID) {
+        self.confirmed_transactions.lock().insert(transaction_id);
+    }
+
+    /// Fails the given number of upcoming block hash reads transiently, as a storage hiccup would.
+    pub fn fail_transiently(&self, num_reads: usize) {
+        self.transient_failures.store(num_reads, Ordering::SeqCst);
     }
 }
 
@@ -102,6 +148,10 @@ impl<N: Network> LedgerService<N> for MockLedgerService<N> {
 
     /// Returns the canonical block hash for the given block height, if it exists.
     fn get_block_hash(&self, height: u32) -> Result<N::BlockHash> {
+        // Fail the read transiently, if a failure was injected.
+        if self.transient_failures.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1)).is_ok() {
+            return Err(LedgerReadError::Transient(format!("Injected failure to read block {height}")).into());
+        }
         match self.height_to_round_and_hash.lock().get(&height).cloned() {
             Some((_, hash)) => Ok(hash),
             None => bail!("Missing block {height}"),
@@ -143,8 +193,11 @@ impl<N: Network> LedgerService<N> for MockLedgerService<N> {
     }
 
     /// Returns the batch certificate for the given batch certificate ID.
-    fn get_batch_certificate(&self, _certificate_id: &Field<N>) -> Result<BatchCertificate<N>> {
-        unreachable!("MockLedgerService does not support get_batch_certificate")
+    fn get_batch_certificate(&self, certificate_id: &Field<N>) -> Result<BatchCertificate<N>> {
+        match self.certificates.lock().get(certificate_id) {
+            Some(certificate) => Ok(certificate.clone()),
+            None => bail!("Certificate {} does not exist in the mock ledger", fmt_id(certificate_id)),
+        }
     }
 
     /// Returns the current committee.
@@ -162,20 +215,27 @@ impl<N: Network> LedgerService<N> for MockLedgerService<N> {
         Ok(self.committee.clone())
     }
 
-    /// Returns `false` for all queries.
+    /// Returns `true` if the certificate was recorded with `insert_certificate`.
     fn contains_certificate(&self, certificate_id: &Field<N>) -> Result<bool> {
-        trace!("[MockLedgerService] Contains certificate ID {} - false", fmt_id(certificate_id));
-        Ok(false)
+        let contains = self.certificates.lock().contains_key(certificate_id);
+        trace!("[MockLedgerService] Contains certificate ID {} - {contains}", fmt_id(certificate_id));
+        Ok(contains)
     }
 
     /// Returns `false` for all queries.
     fn contains_transmission(&self, transmission_id: &TransmissionID<N>) -> Result<bool> {
+        let contains = match transmission_id {
+            TransmissionID::Transaction(transaction_id, _) => {
+                self.confirmed_transactions.lock().contains(transaction_id)
+            }
+            _ => false,
+        };
         trace!(
-            "[MockLedgerService] Contains transmission ID {}.{} - false",
+            "[MockLedgerService] Contains transmission ID {}.{} - {contains}",
             fmt_id(transmission_id),
             fmt_id(transmission_id.checksum().unwrap_or_default())
         );
-        Ok(false)
+        Ok(contains)
     }
 
     /// Ensures that the given transmission is not a fee and matches the given transmission ID.
@@ -204,6 +264,9 @@ impl<N: Network> LedgerService<N> for MockLedgerService<N> {
         transaction_id: N::TransactionID,
         _transaction: Data<Transaction<N>>,
     ) -> Result<()> {
+        if self.invalid_transactions.lock().contains(&transaction_id) {
+            bail!("Transaction '{}' is invalid", fmt_id(transaction_id));
+        }
         trace!("[MockLedgerService] Check transaction basic {:?} - Ok", fmt_id(transaction_id));
         Ok(())
     }
@@ -220,7 +283,7 @@ impl<N: Network> LedgerService<N> for MockLedgerService<N> {
         _subdag: Subdag<N>,
         _transmissions: IndexMap<TransmissionID<N>, Transmission<N>>,
     ) -> Result<Block<N>> {
-        unreachable!("MockLedgerService does not support prepare_advance_to_next_quorum_block")
+        bail!("MockLedgerService does not support prepare_advance_to_next_quorum_block")
     }
 
     /// Adds the given block as the next block in the ledger.
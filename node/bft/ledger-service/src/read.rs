@@ -0,0 +1,359 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{Error, Result};
+#[cfg(feature = "ledger")]
+use snarkvm::{
+    ledger::{
+        Ledger,
+        authority::Authority,
+        block::{Block, Header},
+        store::ConsensusStorage,
+    },
+    prelude::Network,
+};
+
+#[cfg(feature = "ledger")]
+use std::ops::Range;
+use std::{fmt, io, time::Duration};
+
+/// The maximum number of attempts to read from the ledger, while the reads fail transiently.
+pub const MAX_LEDGER_READ_ATTEMPTS: usize = 3;
+/// The delay in milliseconds in between the attempts to read from the ledger.
+const LEDGER_READ_RETRY_DELAY_IN_MS: u64 = 50;
+
+/// A classified failure to read from the ledger.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LedgerReadError {
+    /// The data is definitively absent from the ledger, e.g. as it is beyond the latest block.
+    Absent(String),
+    /// The data should be in the ledger, yet the read failed, e.g. on a storage hiccup, so it may be retried.
+    Transient(String),
+    /// The data was in the ledger, yet it is no longer stored, e.g. as it was pruned.
+    Pruned(String),
+    /// The data is in the ledger, yet it could not be decoded, so it is not retried.
+    Corrupt(String),
+}
+
+impl LedgerReadError {
+    /// Returns the classified failure to read from the ledger in the given error, if any.
+    pub fn of(error: &Error) -> Option<&Self> {
+        error.downcast_ref::<Self>()
+    }
+
+    /// Returns `true` if the given error is a transient failure to read from the ledger.
+    pub fn is_transient(error: &Error) -> bool {
+        matches!(Self::of(error), Some(Self::Transient(_)))
+    }
+
+    /// Returns `true` if the given error is a failure of this node to read data it should have,
+    /// i.e. the requester of the data is not at fault.
+    pub fn is_unavailable(error: &Error) -> bool {
+        matches!(Self::of(error), Some(Self::Transient(_) | Self::Pruned(_) | Self::Corrupt(_)))
+    }
+}
+
+impl fmt::Display for LedgerReadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Absent(message) | Self::Transient(message) | Self::Pruned(message) | Self::Corrupt(message) => {
+                write!(f, "{message}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for LedgerReadError {}
+
+/// Returns `true` if the given error was caused by data that could not be decoded.
+fn is_corrupt(error: &Error) -> bool {
+    error.chain().any(|cause| {
+        cause
+            .downcast_ref::<io::Error>()
+            .is_some_and(|error| matches!(error.kind(), io::ErrorKind::InvalidData | io::ErrorKind::UnexpectedEof))
+    })
+}
+
+/// Classifies the failure of a read at the given block height, by the latest block height of the ledger,
+/// and by whether the ledger still stores the block height.
+///
+/// Note: The latest block height is only retrieved on a failure, so that a block added in between the read
+/// and the classification is not mistaken for an absent one.
+pub fn classify_read<T>(
+    result: Result<T>,
+    height: u32,
+    latest_height: impl FnOnce() -> u32,
+    contains_height: impl FnOnce() -> Result<bool>,
+) -> Result<T> {
+    result.map_err(|error| {
+        let latest_height = latest_height();
+        if height > latest_height {
+            return LedgerReadError::Absent(format!("Block {height} is beyond the latest block {latest_height}"))
+                .into();
+        }
+        if is_corrupt(&error) {
+            return LedgerReadError::Corrupt(format!("Failed to decode block {height} from the ledger - {error}"))
+                .into();
+        }
+        match contains_height() {
+            Ok(false) => LedgerReadError::Pruned(format!("Block {height} is no longer stored in the ledger")),
+            _ => LedgerReadError::Transient(format!("Failed to read block {height} from the ledger - {error}")),
+        }
+        .into()
+    })
+}
+
+/// Classifies the failure of a read of the given block hash, by whether the ledger contains the block hash.
+pub fn classify_read_by_hash<T, H: fmt::Display>(
+    result: Result<T>,
+    hash: &H,
+    contains_hash: impl FnOnce() -> Result<bool>,
+) -> Result<T> {
+    result.map_err(|error| match contains_hash() {
+        Ok(false) => LedgerReadError::Absent(format!("Block '{hash}' is not in the ledger")).into(),
+        _ if is_corrupt(&error) => {
+            LedgerReadError::Corrupt(format!("Failed to decode block '{hash}' from the ledger - {error}")).into()
+        }
+        _ => LedgerReadError::Transient(format!("Failed to read block '{hash}' from the ledger - {error}")).into(),
+    })
+}
+
+/// Reads from the ledger with the given closure, retrying it while it fails transiently,
+/// up to `MAX_LEDGER_READ_ATTEMPTS` times in total.
+///
+/// Note: This blocks the current thread in between the attempts, so it must only be called from a blocking task.
+/// On an async path, use [`retry_transient_async`] instead.
+pub fn retry_transient<T>(mut read: impl FnMut() -> Result<T>) -> Result<T> {
+    let mut attempt = 1;
+    loop {
+        match read() {
+            Err(error) if LedgerReadError::is_transient(&error) && attempt < MAX_LEDGER_READ_ATTEMPTS => {
+                attempt += 1;
+                std::thread::sleep(Duration::from_millis(LEDGER_READ_RETRY_DELAY_IN_MS));
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Reads from the ledger with the given closure, retrying it while it fails transiently,
+/// up to `MAX_LEDGER_READ_ATTEMPTS` times in total.
+///
+/// Note: This yields to the runtime in between the attempts.
+#[cfg(feature = "ledger")]
+pub async fn retry_transient_async<T>(mut read: impl FnMut() -> Result<T>) -> Result<T> {
+    let mut attempt = 1;
+    loop {
+        match read() {
+            Err(error) if LedgerReadError::is_transient(&error) && attempt < MAX_LEDGER_READ_ATTEMPTS => {
+                attempt += 1;
+                tokio::time::sleep(Duration::from_millis(LEDGER_READ_RETRY_DELAY_IN_MS)).await;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Returns the block hash at the given height, classifying a failure to read it.
+#[cfg(feature = "ledger")]
+pub fn read_block_hash<N: Network, C: ConsensusStorage<N>>(ledger: &Ledger<N, C>, height: u32) -> Result<N::BlockHash> {
+    classify_read(ledger.get_hash(height), height, || ledger.latest_height(), || ledger.contains_block_height(height))
+}
+
+/// Returns the block height of the given block hash, classifying a failure to read it.
+#[cfg(feature = "ledger")]
+pub fn read_block_height<N: Network, C: ConsensusStorage<N>>(
+    ledger: &Ledger<N, C>,
+    hash: &N::BlockHash,
+) -> Result<u32> {
+    classify_read_by_hash(ledger.get_height(hash), hash, || ledger.contains_block_hash(hash))
+}
+
+/// Returns the block at the given height, classifying a failure to read it.
+#[cfg(feature = "ledger")]
+pub fn read_block<N: Network, C: ConsensusStorage<N>>(ledger: &Ledger<N, C>, height: u32) -> Result<Block<N>> {
+    classify_read(ledger.get_block(height), height, || ledger.latest_height(), || ledger.contains_block_height(height))
+}
+
+/// Returns the block with the given hash, classifying a failure to read it.
+#[cfg(feature = "ledger")]
+pub fn read_block_by_hash<N: Network, C: ConsensusStorage<N>>(
+    ledger: &Ledger<N, C>,
+    hash: &N::BlockHash,
+) -> Result<Block<N>> {
+    classify_read_by_hash(ledger.get_block_by_hash(hash), hash, || ledger.contains_block_hash(hash))
+}
+
+/// Returns the header of the block with the given hash, classifying a failure to read it.
+#[cfg(feature = "ledger")]
+pub fn read_block_header<N: Network, C: ConsensusStorage<N>>(
+    ledger: &Ledger<N, C>,
+    hash: &N::BlockHash,
+) -> Result<Header<N>> {
+    let header = ledger
+        .vm()
+        .block_store()
+        .get_block_header(hash)
+        .and_then(|header| header.ok_or_else(|| snarkvm::prelude::anyhow!("Missing the header of block '{hash}'")));
+    classify_read_by_hash(header, hash, || ledger.contains_block_hash(hash))
+}
+
+/// Returns the authority of the block with the given hash, classifying a failure to read it.
+#[cfg(feature = "ledger")]
+pub fn read_block_authority<N: Network, C: ConsensusStorage<N>>(
+    ledger: &Ledger<N, C>,
+    hash: &N::BlockHash,
+) -> Result<Authority<N>> {
+    let authority = ledger.vm().block_store().get_block_authority(hash).and_then(|authority| {
+        authority.ok_or_else(|| snarkvm::prelude::anyhow!("Missing the authority of block '{hash}'"))
+    });
+    classify_read_by_hash(authority, hash, || ledger.contains_block_hash(hash))
+}
+
+/// Returns the transaction IDs of the block with the given hash, classifying a failure to read them.
+///
+/// Note: The IDs are read from the index of the block in the block store, so the transactions are not loaded.
+#[cfg(feature = "ledger")]
+pub fn read_block_transaction_ids<N: Network, C: ConsensusStorage<N>>(
+    ledger: &Ledger<N, C>,
+    hash: &N::BlockHash,
+) -> Result<Vec<N::TransactionID>> {
+    let transaction_ids =
+        ledger.vm().block_store().get_block_transaction_ids(hash).and_then(|ids| {
+            ids.ok_or_else(|| snarkvm::prelude::anyhow!("Missing the transaction IDs of block '{hash}'"))
+        });
+    classify_read_by_hash(transaction_ids, hash, || ledger.contains_block_hash(hash))
+}
+
+/// Returns the blocks in the given range, classifying a failure to read them by the last block of the range,
+/// and by whether the first block of the range is still stored, as the blocks are pruned from the oldest one.
+#[cfg(feature = "ledger")]
+pub fn read_blocks<N: Network, C: ConsensusStorage<N>>(
+    ledger: &Ledger<N, C>,
+    heights: Range<u32>,
+) -> Result<Vec<Block<N>>> {
+    let (first_height, last_height) = (heights.start, heights.end.saturating_sub(1));
+    classify_read(
+        ledger.get_blocks(heights),
+        last_height,
+        || ledger.latest_height(),
+        || ledger.contains_block_height(first_height),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::anyhow;
+
+    use std::cell::Cell;
+
+    #[test]
+    fn test_classify_read() {
+        // Ensure a failed read beyond the latest block is absent, and a failed read below it is transient.
+        let error = classify_read::<()>(Err(anyhow!("missing")), 11, || 10, || Ok(false)).unwrap_err();
+        assert!(matches!(LedgerReadError::of(&error), Some(LedgerReadError::Absent(_))));
+        let error = classify_read::<()>(Err(anyhow!("compaction")), 10, || 10, || Ok(true)).unwrap_err();
+        assert!(LedgerReadError::is_transient(&error));
+        // Ensure a failed read of a block that is no longer stored is pruned.
+        let error = classify_read::<()>(Err(anyhow!("missing")), 3, || 10, || Ok(false)).unwrap_err();
+        assert!(matches!(LedgerReadError::of(&error), Some(LedgerReadError::Pruned(_))));
+        assert!(!LedgerReadError::is_transient(&error) && LedgerReadError::is_unavailable(&error));
+        // Ensure a failed read of undecodable data is corrupt, rather than transient.
+        let undecodable = || anyhow::Error::from(io::Error::new(io::ErrorKind::InvalidData, "bad bytes"));
+        let error = classify_read::<()>(Err(undecodable().context("block")), 3, || 10, || Ok(true)).unwrap_err();
+        assert!(matches!(LedgerReadError::of(&error), Some(LedgerReadError::Corrupt(_))));
+        assert!(!LedgerReadError::is_transient(&error) && LedgerReadError::is_unavailable(&error));
+        // Ensure a successful read is untouched.
+        assert_eq!(classify_read(Ok(1), 11, || unreachable!(), || unreachable!()).unwrap(), 1);
+
+        // Ensure a failed read of a hash is classified by whether the ledger contains it.
+        let error = classify_read_by_hash::<(), _>(Err(anyhow!("missing")), &"ab1", || Ok(false)).unwrap_err();
+        assert!(matches!(LedgerReadError::of(&error), Some(LedgerReadError::Absent(_))));
+        let error = classify_read_by_hash::<(), _>(Err(anyhow!("missing")), &"ab1", || Ok(true)).unwrap_err();
+        assert!(LedgerReadError::is_transient(&error));
+        let error = classify_read_by_hash::<(), _>(Err(anyhow!("io")), &"ab1", || Err(anyhow!("io"))).unwrap_err();
+        assert!(LedgerReadError::is_transient(&error));
+        let error = classify_read_by_hash::<(), _>(Err(undecodable()), &"ab1", || Ok(true)).unwrap_err();
+        assert!(matches!(LedgerReadError::of(&error), Some(LedgerReadError::Corrupt(_))));
+    }
+
+    #[test]
+    fn test_retry_transient() {
+        fn transient<T>() -> Result<T> {
+            Err(LedgerReadError::Transient("hiccup".to_string()).into())
+        }
+
+        // Ensure a transient failure is retried until the read succeeds.
+        let attempts = Cell::new(0);
+        let result = retry_transient(|| {
+            attempts.set(attempts.get() + 1);
+            if attempts.get() < MAX_LEDGER_READ_ATTEMPTS { transient() } else { Ok(attempts.get()) }
+        });
+        assert_eq!(result.unwrap(), MAX_LEDGER_READ_ATTEMPTS);
+
+        // Ensure a transient failure is retried a bounded number of times.
+        let attempts = Cell::new(0);
+        let result = retry_transient::<()>(|| {
+            attempts.set(attempts.get() + 1);
+            transient()
+        });
+        assert!(LedgerReadError::is_transient(&result.unwrap_err()));
+        assert_eq!(attempts.get(), MAX_LEDGER_READ_ATTEMPTS);
+
+        // Ensure an absent, pruned, or corrupt read, or an unclassified failure, is not retried.
+        let errors = [
+            LedgerReadError::Absent("beyond".to_string()).into(),
+            LedgerReadError::Pruned("pruned".to_string()).into(),
+            LedgerReadError::Corrupt("undecodable".to_string()).into(),
+            anyhow!("unclassified"),
+        ];
+        for error in errors {
+            let (attempts, error) = (Cell::new(0), Cell::new(Some(error)));
+            let result = retry_transient::<()>(|| {
+                attempts.set(attempts.get() + 1);
+                Err(error.take().unwrap_or_else(|| anyhow!("retried")))
+            });
+            assert!(!LedgerReadError::is_transient(&result.unwrap_err()));
+            assert_eq!(attempts.get(), 1);
+        }
+    }
+
+    #[cfg(feature = "ledger")]
+    #[tokio::test]
+    async fn test_retry_transient_async() {
+        // Ensure a transient failure is retried a bounded number of times, without blocking the runtime.
+        let attempts = Cell::new(0);
+        let result = retry_transient_async::<()>(|| {
+            attempts.set(attempts.get() + 1);
+            Err(LedgerReadError::Transient("hiccup".to_string()).into())
+        })
+        .await;
+        assert!(LedgerReadError::is_transient(&result.unwrap_err()));
+        assert_eq!(attempts.get(), MAX_LEDGER_READ_ATTEMPTS);
+
+        // Ensure the read succeeds once the failures stop.
+        let attempts = Cell::new(0);
+        let result = retry_transient_async(|| {
+            attempts.set(attempts.get() + 1);
+            match attempts.get() < MAX_LEDGER_READ_ATTEMPTS {
+                true => Err(LedgerReadError::Transient("hiccup".to_string()).into()),
+                false => Ok(attempts.get()),
+            }
+        })
+        .await;
+        assert_eq!(result.unwrap(), MAX_LEDGER_READ_ATTEMPTS);
+    }
+}
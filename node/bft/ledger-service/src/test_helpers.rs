@@ -0,0 +1,100 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{
+    ledger::{
+        Ledger,
+        block::{Block, Fee, Input, Transaction, Transition},
+        store::{ConsensusStorage, ConsensusStore, helpers::memory::ConsensusMemory},
+    },
+    prelude::{Literal, Network, Plaintext, PrivateKey, U64},
+    synthesizer::VM,
+};
+
+use aleo_std::StorageMode;
+use rand::{CryptoRng, Rng};
+
+/// Returns a sample beacon private key, and a development genesis block created by it.
+pub fn sample_genesis_block<N: Network, R: Rng + CryptoRng>(rng: &mut R) -> (PrivateKey<N>, Block<N>) {
+    let private_key = PrivateKey::<N>::new(rng).unwrap();
+    let store = ConsensusStore::<N, ConsensusMemory<N>>::open(None).unwrap();
+    let genesis = VM::from(store).unwrap().genesis_beacon(&private_key, rng).unwrap();
+    (private_key, genesis)
+}
+
+/// Returns a sample ledger, loaded from the given genesis block and advanced with the given number of empty blocks.
+pub fn sample_ledger_from_genesis<N: Network, R: Rng + CryptoRng>(
+    private_key: &PrivateKey<N>,
+    genesis: &Block<N>,
+    num_blocks: u32,
+    rng: &mut R,
+) -> Ledger<N, ConsensusMemory<N>> {
+    let ledger = Ledger::<N, ConsensusMemory<N>>::load(genesis.clone(), StorageMode::Production).unwrap();
+    advance_sample_ledger(&ledger, private_key, num_blocks, rng);
+    ledger
+}
+
+/// Returns a sample beacon private key, and a development ledger advanced with the given number of empty blocks.
+pub fn sample_ledger<N: Network, R: Rng + CryptoRng>(
+    num_blocks: u32,
+    rng: &mut R,
+) -> (PrivateKey<N>, Ledger<N, ConsensusMemory<N>>) {
+    let (private_key, genesis) = sample_genesis_block(rng);
+    let ledger = sample_ledger_from_genesis(&private_key, &genesis, num_blocks, rng);
+    (private_key, ledger)
+}
+
+/// Advances the given sample ledger with the given number of empty blocks, created by the beacon private key.
+pub fn advance_sample_ledger<N: Network, C: ConsensusStorage<N>, R: Rng + CryptoRng>(
+    ledger: &Ledger<N, C>,
+    private_key: &PrivateKey<N>,
+    num_blocks: u32,
+    rng: &mut R,
+) {
+    for _ in 0..num_blocks {
+        let block = ledger.prepare_advance_to_next_beacon_block(private_key, vec![], vec![], vec![], rng).unwrap();
+        ledger.advance_to_next_block(&block).unwrap();
+    }
+}
+
+/// Returns a copy of the given transaction with a public fee, whose fee claims the given priority fee, e.g. to stand
+/// in for a distinct transaction, or for a forged one. The proof of the fee does not cover the claimed priority fee,
+/// so the copy only passes the checks that do not verify the proofs.
+pub fn sample_transaction_with_priority_fee<N: Network>(
+    transaction: &Transaction<N>,
+    priority_fee: u64,
+) -> Transaction<N> {
+    let fee = transaction.fee_transition().unwrap();
+    // Note: The priority fee is the second input of a public fee.
+    let mut inputs = fee.inputs().to_vec();
+    let priority_fee = Plaintext::from(Literal::U64(U64::new(priority_fee)));
+    inputs[1] = Input::Public(*inputs[1].id(), Some(priority_fee));
+    let transition = Transition::new(
+        *fee.program_id(),
+        *fee.function_name(),
+        inputs,
+        fee.outputs().to_vec(),
+        *fee.tpk(),
+        *fee.tcm(),
+        *fee.scm(),
+    )
+    .unwrap();
+    let fee = Fee::from_unchecked(transition, fee.global_state_root(), fee.proof().cloned());
+    match (transaction.owner(), transaction.deployment(), transaction.execution()) {
+        (Some(owner), Some(deployment), _) => Transaction::from_deployment(*owner, deployment.clone(), fee).unwrap(),
+        (_, _, Some(execution)) => Transaction::from_execution(execution.clone(), Some(fee)).unwrap(),
+        _ => panic!("Expected a deployment or an execution with a fee"),
+    }
+}
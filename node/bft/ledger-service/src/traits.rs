@@ -53,6 +53,8 @@ pub trait LedgerService<N: Network>: Debug + Send + Sync {
     fn get_block_height(&self, hash: &N::BlockHash) -> Result<u32>;
 
     /// Returns the block hash for the given block height, if it exists.
+    ///
+    /// Note: A failure to read a block may be a [`LedgerReadError`], telling an absent block from a transient failure.
     fn get_block_hash(&self, height: u32) -> Result<N::BlockHash>;
 
     /// Returns the block round for the given block height, if it exists.
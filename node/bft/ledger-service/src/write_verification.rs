@@ -0,0 +1,188 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+pub use snarkos_node_bft_storage_service::{WriteMismatch, WriteVerifier};
+
+use snarkvm::{
+    ledger::{Ledger, block::Block, store::ConsensusStorage},
+    prelude::{Network, Result},
+};
+
+/// The storage of the blocks, from which the critical writes of the block-advance path are read back.
+pub trait BlockWriteStorage<N: Network> {
+    /// Adds the given block as the next block in storage.
+    fn write_next_block(&self, block: &Block<N>) -> Result<()>;
+
+    /// Returns the state root that was written for the latest block.
+    fn written_state_root(&self) -> N::StateRoot;
+
+    /// Reads back the block hash at the given height from storage.
+    fn read_block_hash(&self, height: u32) -> Result<Option<N::BlockHash>>;
+
+    /// Reads back the block height of the given block hash from storage.
+    fn read_block_height(&self, hash: &N::BlockHash) -> Result<Option<u32>>;
+
+    /// Returns `true` if the given state root was written to storage.
+    fn contains_state_root(&self, state_root: &N::StateRoot) -> Result<bool>;
+}
+
+impl<N: Network, C: ConsensusStorage<N>> BlockWriteStorage<N> for Ledger<N, C> {
+    fn write_next_block(&self, block: &Block<N>) -> Result<()> {
+        self.advance_to_next_block(block)
+    }
+
+    fn written_state_root(&self) -> N::StateRoot {
+        self.latest_state_root()
+    }
+
+    fn read_block_hash(&self, height: u32) -> Result<Option<N::BlockHash>> {
+        self.vm().block_store().get_block_hash(height)
+    }
+
+    fn read_block_height(&self, hash: &N::BlockHash) -> Result<Option<u32>> {
+        self.vm().block_store().get_block_height(hash)
+    }
+
+    fn contains_state_root(&self, state_root: &N::StateRoot) -> Result<bool> {
+        self.vm().block_store().contains_state_root(state_root)
+    }
+}
+
+/// Adds the given block to storage, and reads back its critical writes if write verification is enabled.
+///
+/// The read-back uses point reads of the keys that were just written, instead of deserializing the block.
+/// On a mismatch, the storage is flagged as corrupted, and every subsequent block is refused.
+pub fn advance_with_write_verification<N: Network>(
+    verifier: &WriteVerifier,
+    storage: &impl BlockWriteStorage<N>,
+    block: &Block<N>,
+) -> Result<()> {
+    // Ensure no corruption was detected, as the block would build on top of corrupted storage.
+    verifier.ensure_not_corrupted()?;
+    // Add the block to storage.
+    storage.write_next_block(block)?;
+    if !verifier.is_enabled() {
+        return Ok(());
+    }
+
+    let (height, hash) = (block.height(), block.hash());
+    // Read back the block hash, and the block height it maps to.
+    verifier.verify("block hash", height, &hash, storage.read_block_hash(height)?)?;
+    verifier.verify("block height", hash, &height, storage.read_block_height(&hash)?)?;
+    // Read back the state root.
+    let state_root = storage.written_state_root();
+    let read_back = storage.contains_state_root(&state_root)?.then_some(state_root);
+    verifier.verify("state root", height, &state_root, read_back)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{FromBytes, MainnetV0};
+
+    use std::{cell::RefCell, collections::HashMap};
+
+    type CurrentNetwork = MainnetV0;
+
+    /// A mock block storage, which corrupts the block hash written at the given height.
+    #[derive(Default)]
+    struct MockStorage {
+        /// The height at which the written block hash is corrupted, if any.
+        corrupt_at: Option<u32>,
+        /// The written block hashes.
+        hashes: RefCell<HashMap<u32, <CurrentNetwork as Network>::BlockHash>>,
+        /// The written block heights.
+        heights: RefCell<HashMap<<CurrentNetwork as Network>::BlockHash, u32>>,
+        /// The number of written blocks.
+        num_writes: RefCell<usize>,
+    }
+
+    impl BlockWriteStorage<CurrentNetwork> for MockStorage {
+        fn write_next_block(&self, block: &Block<CurrentNetwork>) -> Result<()> {
+            let (height, hash) = (block.height(), block.hash());
+            // Corrupt the written block hash, by writing the previous block hash instead.
+            let written_hash = match self.corrupt_at == Some(height) {
+                true => block.previous_hash(),
+                false => hash,
+            };
+            self.hashes.borrow_mut().insert(height, written_hash);
+            self.heights.borrow_mut().insert(hash, height);
+            *self.num_writes.borrow_mut() += 1;
+            Ok(())
+        }
+
+        fn written_state_root(&self) -> <CurrentNetwork as Network>::StateRoot {
+            Default::default()
+        }
+
+        fn read_block_hash(&self, height: u32) -> Result<Option<<CurrentNetwork as Network>::BlockHash>> {
+            Ok(self.hashes.borrow().get(&height).copied())
+        }
+
+        fn read_block_height(&self, hash: &<CurrentNetwork as Network>::BlockHash) -> Result<Option<u32>> {
+            Ok(self.heights.borrow().get(hash).copied())
+        }
+
+        fn contains_state_root(&self, _state_root: &<CurrentNetwork as Network>::StateRoot) -> Result<bool> {
+            Ok(true)
+        }
+    }
+
+    fn sample_block() -> Block<CurrentNetwork> {
+        Block::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_write_verification_passes() {
+        let verifier = WriteVerifier::new(true);
+        let storage = MockStorage::default();
+        advance_with_write_verification(&verifier, &storage, &sample_block()).unwrap();
+        // Ensure the block hash, block height, and state root were read back.
+        assert_eq!(verifier.num_verifications(), 3);
+        assert!(!verifier.is_corrupted());
+    }
+
+    #[test]
+    fn test_write_verification_halts_on_corruption() {
+        let block = sample_block();
+        let verifier = WriteVerifier::new(true);
+        let storage = MockStorage { corrupt_at: Some(block.height()), ..Default::default() };
+
+        // Ensure the corrupted write is detected, with the specific mismatch.
+        let error = advance_with_write_verification(&verifier, &storage, &block).unwrap_err();
+        let mismatch = WriteMismatch::of(&error).unwrap().clone();
+        assert_eq!(mismatch.entry, "block hash");
+        assert_eq!(mismatch.written, block.hash().to_string());
+        assert_eq!(mismatch.read_back, Some(block.previous_hash().to_string()));
+        assert!(verifier.is_corrupted());
+
+        // Ensure the advancement is halted, without writing to storage.
+        let error = advance_with_write_verification(&verifier, &storage, &block).unwrap_err();
+        assert_eq!(WriteMismatch::of(&error), Some(&mismatch));
+        assert_eq!(*storage.num_writes.borrow(), 1);
+    }
+
+    #[test]
+    fn test_write_verification_disabled() {
+        let block = sample_block();
+        let verifier = WriteVerifier::default();
+        let storage = MockStorage { corrupt_at: Some(block.height()), ..Default::default() };
+        // Ensure the writes are not read back by default.
+        advance_with_write_verification(&verifier, &storage, &block).unwrap();
+        assert_eq!(verifier.num_verifications(), 0);
+        assert!(!verifier.is_corrupted());
+    }
+}
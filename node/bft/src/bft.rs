@@ -20,6 +20,7 @@ use crate::{
         BFTReceiver,
         ConsensusSender,
         DAG,
+        LeaderSchedule,
         PrimaryReceiver,
         PrimarySender,
         Storage,
@@ -54,7 +55,7 @@ use std::{
     },
 };
 use tokio::{
-    sync::{Mutex as TMutex, OnceCell, oneshot},
+    sync::{Mutex as TMutex, OnceCell, oneshot, watch},
     task::JoinHandle,
 };
 
@@ -68,6 +69,8 @@ pub struct BFT<N: Network> {
     leader_certificate: Arc<RwLock<Option<BatchCertificate<N>>>>,
     /// The timer for the leader certificate to be received.
     leader_certificate_timer: Arc<AtomicI64>,
+    /// The leaders of the most recent even rounds.
+    leader_schedule: Arc<LeaderSchedule<N>>,
     /// The consensus sender.
     consensus_sender: Arc<OnceCell<ConsensusSender<N>>>,
     /// The spawned handles.
@@ -91,6 +94,7 @@ impl<N: Network> BFT<N> {
             dag: Default::default(),
             leader_certificate: Default::default(),
             leader_certificate_timer: Default::default(),
+            leader_schedule: Default::default(),
             consensus_sender: Default::default(),
             handles: Default::default(),
             lock: Default::default(),
@@ -124,6 +128,11 @@ impl<N: Network> BFT<N> {
         self.primary.is_synced()
     }
 
+    /// Subscribes to the sync status of the primary, which is updated periodically.
+    pub fn subscribe_is_synced(&self) -> watch::Receiver<bool> {
+        self.primary.subscribe_is_synced()
+    }
+
     /// Returns the primary.
     pub const fn primary(&self) -> &Primary<N> {
         &self.primary
@@ -183,6 +192,25 @@ impl<N: Network> BFT<N> {
         self.primary.worker_transmissions()
     }
 
+    /// Returns the worker transmission with the given ID, if it is in a ready queue.
+    pub fn worker_transmission(&self, transmission_id: TransmissionID<N>) -> Option<Transmission<N>> {
+        self.primary.worker_transmission(transmission_id)
+    }
+
+    /// Returns the ID, the size in bytes (if known), and the insertion timestamp of each worker transmission.
+    pub fn worker_transmission_summaries(&self) -> Vec<(TransmissionID<N>, Option<usize>, i64)> {
+        self.primary.worker_transmission_summaries()
+    }
+
+    /// Returns up to the given number of worker transmissions that match the filter.
+    pub fn worker_transmissions_matching(
+        &self,
+        limit: usize,
+        filter: impl Fn(&TransmissionID<N>) -> bool,
+    ) -> IndexMap<TransmissionID<N>, Transmission<N>> {
+        self.primary.worker_transmissions_matching(limit, filter)
+    }
+
     /// Returns the worker solutions.
     pub fn worker_solutions(&self) -> impl '_ + Iterator<Item = (SolutionID<N>, Data<Solution<N>>)> {
         self.primary.worker_solutions()
@@ -301,22 +329,11 @@ impl<N: Network> BFT<N> {
             }
         };
         // Determine the leader of the current round.
-        let leader = match self.ledger().latest_leader() {
-            Some((cached_round, cached_leader)) if cached_round == current_round => cached_leader,
-            _ => {
-                // Compute the leader for the current round.
-                let computed_leader = match committee_lookback.get_leader(current_round) {
-                    Ok(leader) => leader,
-                    Err(e) => {
-                        error!("BFT failed to compute the leader for the even round {current_round} - {e}");
-                        return false;
-                    }
-                };
-
-                // Cache the computed leader.
-                self.ledger().update_latest_leader(current_round, computed_leader);
-
-                computed_leader
+        let leader = match self.leader_for_round(&committee_lookback, current_round) {
+            Ok(leader) => leader,
+            Err(e) => {
+                error!("BFT failed to compute the leader for the even round {current_round} - {e}");
+                return false;
             }
         };
         // Find and set the leader certificate, if the leader was present in the current even round.
@@ -326,6 +343,17 @@ impl<N: Network> BFT<N> {
         self.is_even_round_ready_for_next_round(current_certificates, committee_lookback, current_round)
     }
 
+    /// Returns the leader of the given even round, as elected by its committee lookback.
+    ///
+    /// Note: The leaders are retained for the most recent rounds, as the walk over the uncommitted
+    /// leader rounds of a commit would otherwise re-elect each of them.
+    fn leader_for_round(&self, committee_lookback: &Committee<N>, round: u64) -> Result<Address<N>> {
+        let leader = self.leader_schedule.get_leader(committee_lookback, round)?;
+        // Keep the latest leader of the ledger up to date.
+        self.ledger().update_latest_leader(round, leader);
+        Ok(leader)
+    }
+
     /// Returns 'true' if the quorum threshold `(N - f)` is reached for this round under one of the following conditions:
     ///  - If the leader certificate is set for the current even round.
     ///  - The timer for the leader certificate has expired.
@@ -389,8 +417,8 @@ impl<N: Network> BFT<N> {
                 return false;
             }
         };
-        // Retrieve the authors of the current certificates.
-        let authors = current_certificates.clone().into_iter().map(|c| c.author()).collect();
+        // Retrieve the authors of the current certificates, without cloning the certificates.
+        let authors = current_certificates.iter().map(|c| c.author()).collect();
         // Check if quorum threshold is reached.
         if !committee_lookback.is_quorum_threshold_reached(&authors) {
             trace!("BFT failed reach quorum threshold in odd round {current_round}. ");
@@ -479,20 +507,9 @@ impl<N: Network> BFT<N> {
             bail!("BFT failed to retrieve the committee with lag for commit round {commit_round}");
         };
 
-        // Either retrieve the cached leader or compute it.
-        let leader = match self.ledger().latest_leader() {
-            Some((cached_round, cached_leader)) if cached_round == commit_round => cached_leader,
-            _ => {
-                // Compute the leader for the commit round.
-                let Ok(computed_leader) = committee_lookback.get_leader(commit_round) else {
-                    bail!("BFT failed to compute the leader for commit round {commit_round}");
-                };
-
-                // Cache the computed leader.
-                self.ledger().update_latest_leader(commit_round, computed_leader);
-
-                computed_leader
-            }
+        // Determine the leader of the commit round.
+        let Ok(leader) = self.leader_for_round(&committee_lookback, commit_round) else {
+            bail!("BFT failed to compute the leader for commit round {commit_round}");
         };
 
         // Retrieve the leader certificate for the commit round.
@@ -528,6 +545,32 @@ impl<N: Network> BFT<N> {
         self.commit_leader_certificate::<ALLOW_LEDGER_ACCESS, IS_SYNCING>(leader_certificate).await
     }
 
+    /// Returns the subdag and transmissions that the next commit would hand to consensus,
+    /// if the current leader certificate were committed now. This does not modify the DAG.
+    ///
+    /// Returns `None` if there is no leader certificate for the current even round,
+    /// or if it was already committed.
+    pub fn preview_commit(&self) -> Result<Option<(Subdag<N>, IndexMap<TransmissionID<N>, Transmission<N>>)>> {
+        // Retrieve the current leader certificate.
+        let Some(leader_certificate) = self.leader_certificate.read().clone() else {
+            return Ok(None);
+        };
+        // Ensure the leader certificate has not been committed yet.
+        if leader_certificate.round() <= self.dag.read().last_committed_round() {
+            return Ok(None);
+        }
+        // Retrieve the oldest leader certificate that would be committed, as it is the next block.
+        let Some(leader_certificate) = self.leader_certificates_to_commit(leader_certificate)?.pop() else {
+            return Ok(None);
+        };
+        // Compute the commit subdag.
+        let commit_subdag = self.order_dag_with_dfs::<true>(leader_certificate)?;
+        // Retrieve the deduped transmissions of the subdag.
+        let transmissions = self.commit_transmissions(&commit_subdag)?;
+        // Construct the subdag.
+        Ok(Some((Subdag::from(commit_subdag)?, transmissions)))
+    }
+
     /// Commits the leader certificate, and all previous leader certificates since the last committed round.
     async fn commit_leader_certificate<const ALLOW_LEDGER_ACCESS: bool, const IS_SYNCING: bool>(
         &self,
@@ -536,54 +579,7 @@ impl<N: Network> BFT<N> {
         // Fetch the leader round.
         let latest_leader_round = leader_certificate.round();
         // Determine the list of all previous leader certificates since the last committed round.
-        // The order of the leader certificates is from **newest** to **oldest**.
-        let mut leader_certificates = vec![leader_certificate.clone()];
-        {
-            // Retrieve the leader round.
-            let leader_round = leader_certificate.round();
-
-            let mut current_certificate = leader_certificate;
-            for round in (self.dag.read().last_committed_round() + 2..=leader_round.saturating_sub(2)).rev().step_by(2)
-            {
-                // Retrieve the previous committee for the leader round.
-                let previous_committee_lookback = match self.ledger().get_committee_lookback_for_round(round) {
-                    Ok(committee) => committee,
-                    Err(e) => {
-                        bail!("BFT failed to retrieve a previous committee lookback for the even round {round} - {e}");
-                    }
-                };
-                // Either retrieve the cached leader or compute it.
-                let leader = match self.ledger().latest_leader() {
-                    Some((cached_round, cached_leader)) if cached_round == round => cached_leader,
-                    _ => {
-                        // Compute the leader for the commit round.
-                        let computed_leader = match previous_committee_lookback.get_leader(round) {
-                            Ok(leader) => leader,
-                            Err(e) => {
-                                bail!("BFT failed to compute the leader for the even round {round} - {e}");
-                            }
-                        };
-
-                        // Cache the computed leader.
-                        self.ledger().update_latest_leader(round, computed_leader);
-
-                        computed_leader
-                    }
-                };
-                // Retrieve the previous leader certificate.
-                let Some(previous_certificate) = self.dag.read().get_certificate_for_round_with_author(round, leader)
-                else {
-                    continue;
-                };
-                // Determine if there is a path between the previous certificate and the current certificate.
-                if self.is_linked(previous_certificate.clone(), current_certificate.clone())? {
-                    // Add the previous leader certificate to the list of certificates to commit.
-                    leader_certificates.push(previous_certificate.clone());
-                    // Update the current certificate to the previous leader certificate.
-                    current_certificate = previous_certificate;
-                }
-            }
-        }
+        let leader_certificates = self.leader_certificates_to_commit(leader_certificate)?;
 
         // Iterate over the leader certificates to commit.
         for leader_certificate in leader_certificates.into_iter().rev() {
@@ -596,68 +592,8 @@ impl<N: Network> BFT<N> {
             };
             // If the node is not syncing, trigger consensus, as this will build a new block for the ledger.
             if !IS_SYNCING {
-                // Initialize a map for the deduped transmissions.
-                let mut transmissions = IndexMap::new();
-                // Initialize a map for the deduped transaction ids.
-                let mut seen_transaction_ids = IndexSet::new();
-                // Initialize a map for the deduped solution ids.
-                let mut seen_solution_ids = IndexSet::new();
-                // Start from the oldest leader certificate.
-                for certificate in commit_subdag.values().flatten() {
-                    // Retrieve the transmissions.
-                    for transmission_id in certificate.transmission_ids() {
-                        // If the transaction ID or solution ID already exists in the map, skip it.
-                        // Note: This additional check is done to ensure that we do not include duplicate
-                        // transaction IDs or solution IDs that may have a different transmission ID.
-                        match transmission_id {
-                            TransmissionID::Solution(solution_id, _) => {
-                                // If the solution already exists, skip it.
-                                if seen_solution_ids.contains(&solution_id) {
-                                    continue;
-                                }
-                            }
-                            TransmissionID::Transaction(transaction_id, _) => {
-                                // If the transaction already exists, skip it.
-                                if seen_transaction_ids.contains(transaction_id) {
-                                    continue;
-                                }
-                            }
-                            TransmissionID::Ratification => {
-                                bail!("Ratifications are currently not supported in the BFT.")
-                            }
-                        }
-                        // If the transmission already exists in the map, skip it.
-                        if transmissions.contains_key(transmission_id) {
-                            continue;
-                        }
-                        // If the transmission already exists in the ledger, skip it.
-                        // Note: On failure to read from the ledger, we skip including this transmission, out of safety.
-                        if self.ledger().contains_transmission(transmission_id).unwrap_or(true) {
-                            continue;
-                        }
-                        // Retrieve the transmission.
-                        let Some(transmission) = self.storage().get_transmission(*transmission_id) else {
-                            bail!(
-                                "BFT failed to retrieve transmission '{}.{}' from round {}",
-                                fmt_id(transmission_id),
-                                fmt_id(transmission_id.checksum().unwrap_or_default()).dimmed(),
-                                certificate.round()
-                            );
-                        };
-                        // Insert the transaction ID or solution ID into the map.
-                        match transmission_id {
-                            TransmissionID::Solution(id, _) => {
-                                seen_solution_ids.insert(id);
-                            }
-                            TransmissionID::Transaction(id, _) => {
-                                seen_transaction_ids.insert(id);
-                            }
-                            TransmissionID::Ratification => {}
-                        }
-                        // Add the transmission to the set.
-                        transmissions.insert(*transmission_id, transmission);
-                    }
-                }
+                // Retrieve the deduped transmissions of the subdag, which are not in the ledger yet.
+                let transmissions = self.commit_transmissions(&commit_subdag)?;
                 // Trigger consensus, as this will build a new block for the ledger.
                 // Construct the subdag.
                 let subdag = Subdag::from(commit_subdag.clone())?;
@@ -712,6 +648,117 @@ impl<N: Network> BFT<N> {
         Ok(())
     }
 
+    /// Returns the given leader certificate, and all previous leader certificates since the last committed round
+    /// that are linked to it. The order of the leader certificates is from **newest** to **oldest**.
+    fn leader_certificates_to_commit(
+        &self,
+        leader_certificate: BatchCertificate<N>,
+    ) -> Result<Vec<BatchCertificate<N>>> {
+        // Retrieve the leader round.
+        let leader_round = leader_certificate.round();
+        // Initialize the list of leader certificates to commit.
+        let mut leader_certificates = vec![leader_certificate.clone()];
+
+        let mut current_certificate = leader_certificate;
+        for round in (self.dag.read().last_committed_round() + 2..=leader_round.saturating_sub(2)).rev().step_by(2) {
+            // Retrieve the previous committee for the leader round.
+            let previous_committee_lookback = match self.ledger().get_committee_lookback_for_round(round) {
+                Ok(committee) => committee,
+                Err(e) => {
+                    bail!("BFT failed to retrieve a previous committee lookback for the even round {round} - {e}");
+                }
+            };
+            // Determine the leader of the previous round.
+            let leader = match self.leader_for_round(&previous_committee_lookback, round) {
+                Ok(leader) => leader,
+                Err(e) => bail!("BFT failed to compute the leader for the even round {round} - {e}"),
+            };
+            // Retrieve the previous leader certificate.
+            let Some(previous_certificate) = self.dag.read().get_certificate_for_round_with_author(round, leader)
+            else {
+                continue;
+            };
+            // Determine if there is a path between the previous certificate and the current certificate.
+            if self.is_linked(previous_certificate.clone(), current_certificate.clone())? {
+                // Add the previous leader certificate to the list of certificates to commit.
+                leader_certificates.push(previous_certificate.clone());
+                // Update the current certificate to the previous leader certificate.
+                current_certificate = previous_certificate;
+            }
+        }
+        Ok(leader_certificates)
+    }
+
+    /// Returns the deduped transmissions of the given subdag, in commit order, skipping those already in the ledger.
+    fn commit_transmissions(
+        &self,
+        commit_subdag: &BTreeMap<u64, IndexSet<BatchCertificate<N>>>,
+    ) -> Result<IndexMap<TransmissionID<N>, Transmission<N>>> {
+        // Initialize a map for the deduped transmissions.
+        let mut transmissions = IndexMap::new();
+        // Initialize a map for the deduped transaction ids.
+        let mut seen_transaction_ids = IndexSet::new();
+        // Initialize a map for the deduped solution ids.
+        let mut seen_solution_ids = IndexSet::new();
+        // Start from the oldest leader certificate.
+        for certificate in commit_subdag.values().flatten() {
+            // Retrieve the transmissions.
+            for transmission_id in certificate.transmission_ids() {
+                // If the transaction ID or solution ID already exists in the map, skip it.
+                // Note: This additional check is done to ensure that we do not include duplicate
+                // transaction IDs or solution IDs that may have a different transmission ID.
+                match transmission_id {
+                    TransmissionID::Solution(solution_id, _) => {
+                        // If the solution already exists, skip it.
+                        if seen_solution_ids.contains(&solution_id) {
+                            continue;
+                        }
+                    }
+                    TransmissionID::Transaction(transaction_id, _) => {
+                        // If the transaction already exists, skip it.
+                        if seen_transaction_ids.contains(transaction_id) {
+                            continue;
+                        }
+                    }
+                    TransmissionID::Ratification => {
+                        bail!("Ratifications are currently not supported in the BFT.")
+                    }
+                }
+                // If the transmission already exists in the map, skip it.
+                if transmissions.contains_key(transmission_id) {
+                    continue;
+                }
+                // If the transmission already exists in the ledger, skip it.
+                // Note: On failure to read from the ledger, we skip including this transmission, out of safety.
+                if self.ledger().contains_transmission(transmission_id).unwrap_or(true) {
+                    continue;
+                }
+                // Retrieve the transmission.
+                let Some(transmission) = self.storage().get_transmission(*transmission_id) else {
+                    bail!(
+                        "BFT failed to retrieve transmission '{}.{}' from round {}",
+                        fmt_id(transmission_id),
+                        fmt_id(transmission_id.checksum().unwrap_or_default()).dimmed(),
+                        certificate.round()
+                    );
+                };
+                // Insert the transaction ID or solution ID into the map.
+                match transmission_id {
+                    TransmissionID::Solution(id, _) => {
+                        seen_solution_ids.insert(id);
+                    }
+                    TransmissionID::Transaction(id, _) => {
+                        seen_transaction_ids.insert(id);
+                    }
+                    TransmissionID::Ratification => {}
+                }
+                // Add the transmission to the set.
+                transmissions.insert(*transmission_id, transmission);
+            }
+        }
+        Ok(transmissions)
+    }
+
     /// Returns the subdag of batch certificates to commit.
     fn order_dag_with_dfs<const ALLOW_LEDGER_ACCESS: bool>(
         &self,
@@ -796,11 +843,11 @@ impl<N: Network> BFT<N> {
                 // If this error is hit, it is likely that the maximum GC rounds should be increased.
                 bail!("BFT failed to retrieve the certificates for past round {round}");
             };
+            // Collect the previous certificate IDs of the traversal once, instead of once per certificate.
+            let previous_ids: HashSet<_> =
+                traversal.iter().flat_map(|c| c.previous_certificate_ids().iter().copied()).collect();
             // Filter the certificates to only include those that are in the traversal.
-            traversal = certificates
-                .into_values()
-                .filter(|p| traversal.iter().any(|c| c.previous_certificate_ids().contains(&p.id())))
-                .collect();
+            traversal = certificates.into_values().filter(|p| previous_ids.contains(&p.id())).collect();
         }
         Ok(traversal.contains(&previous_certificate))
     }
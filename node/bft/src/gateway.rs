@@ -19,7 +19,28 @@ use crate::{
     MEMORY_POOL_PORT,
     Worker,
     events::{EventCodec, PrimaryPing},
-    helpers::{Cache, PrimarySender, Resolver, Storage, SyncSender, WorkerSender, assign_to_worker},
+    helpers::{
+        Cache,
+        ConnectionRegistry,
+        ConnectionSnapshot,
+        ConnectionStats,
+        DevInstance,
+        DuplicateIdentity,
+        MeteredCodec,
+        PrimarySender,
+        RecentNonces,
+        Resolver,
+        Storage,
+        SyncSender,
+        TRANSFER_STALL_TIMEOUT_IN_MS,
+        Transfers,
+        ValidatorsRequestOutcome,
+        ValidatorsRequests,
+        ValidatorsResponseMode,
+        WorkerSender,
+        assign_to_worker,
+        now,
+    },
     spawn_blocking,
 };
 use snarkos_account::Account;
@@ -34,12 +55,13 @@ use snarkos_node_bft_events::{
     DisconnectReason,
     Event,
     EventTrait,
+    TransmissionChunk,
     TransmissionRequest,
     TransmissionResponse,
     ValidatorsRequest,
     ValidatorsResponse,
 };
-use snarkos_node_bft_ledger_service::LedgerService;
+use snarkos_node_bft_ledger_service::{LedgerReadError, LedgerService, retry_transient};
 use snarkos_node_sync::{MAX_BLOCKS_BEHIND, communication_service::CommunicationService};
 use snarkos_node_tcp::{
     Config,
@@ -49,23 +71,30 @@ use snarkos_node_tcp::{
     Tcp,
     is_bogon_ip,
     is_unspecified_or_broadcast_ip,
-    protocols::{Disconnect, Handshake, OnConnect, Reading, Writing},
+    protocols::{Disconnect, Handshake, MessagePriority, OnConnect, Reading, Writing},
 };
 use snarkvm::{
     console::prelude::*,
     ledger::{
         committee::Committee,
-        narwhal::{BatchHeader, Data},
+        narwhal::{BatchHeader, Data, Transmission, TransmissionID},
     },
     prelude::{Address, Field},
 };
 
 use colored::Colorize;
 use futures::SinkExt;
-use indexmap::{IndexMap, IndexSet};
+use indexmap::{IndexMap, IndexSet, map::Entry};
 use parking_lot::{Mutex, RwLock};
 use rand::seq::{IteratorRandom, SliceRandom};
-use std::{collections::HashSet, future::Future, io, net::SocketAddr, sync::Arc, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    future::Future,
+    io,
+    net::SocketAddr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use tokio::{
     net::TcpStream,
     sync::{OnceCell, oneshot},
@@ -83,11 +112,16 @@ const CACHE_REQUESTS_INTERVAL: i64 = (MAX_BATCH_DELAY_IN_MS / 1000) as i64; // s
 const MAX_CONNECTION_ATTEMPTS: usize = 10;
 /// The maximum interval to restrict a peer.
 const RESTRICTED_INTERVAL: i64 = (MAX_CONNECTION_ATTEMPTS as u64 * MAX_BATCH_DELAY_IN_MS / 1000) as i64; // seconds
+/// The maximum time in milliseconds between sending a challenge request and receiving its challenge response.
+const CHALLENGE_RESPONSE_DEADLINE_IN_MS: u64 = 2_000; // 2 seconds
 
 /// The minimum number of validators to maintain a connection to.
 const MIN_CONNECTED_VALIDATORS: usize = 175;
-/// The maximum number of validators to send in a validators response event.
-const MAX_VALIDATORS_TO_SEND: usize = 200;
+/// The maximum number of validators to request in a validators request event,
+/// which is enough to reach the minimum number of connected validators.
+const MAX_VALIDATORS_TO_REQUEST: u16 = MIN_CONNECTED_VALIDATORS as u16;
+/// The maximum time in milliseconds to wait for the shutdown notifications to be delivered to the validators.
+const SHUTDOWN_NOTIFICATION_TIMEOUT_IN_MS: u64 = 500;
 
 /// Part of the Gateway API that deals with networking.
 /// This is a separate trait to allow for easier testing/mocking.
@@ -118,8 +152,24 @@ pub struct Gateway<N: Network> {
     /// The set of handshaking peers. While `Tcp` already recognizes the connecting IP addresses
     /// and prevents duplicate outbound connection attempts to the same IP address, it is unable to
     /// prevent simultaneous "two-way" connections between two peers (i.e. both nodes simultaneously
-    /// attempt to connect to each other). This set is used to prevent this from happening.
-    connecting_peers: Arc<Mutex<IndexSet<SocketAddr>>>,
+    /// attempt to connect to each other). This map of peer IPs to the side of the peer in the
+    /// connection is used to resolve this deterministically (see `Gateway::is_preferred_initiator`).
+    connecting_peers: Arc<Mutex<IndexMap<SocketAddr, ConnectionSide>>>,
+    /// The map of connected peer IPs to the version of the event protocol they reported.
+    peer_versions: Arc<RwLock<HashMap<SocketAddr, u32>>>,
+    /// The map of validator addresses to the UNIX timestamp of the last event received from them.
+    /// Note: This map is not cleared on disconnect, so that the last activity of a missing validator is retained.
+    last_activity: Arc<RwLock<HashMap<Address<N>, i64>>>,
+    /// The reassembly buffers for the transmissions being received in chunks.
+    transfers: Arc<Transfers<N>>,
+    /// The admission control for inbound validators requests.
+    validators_requests: Arc<ValidatorsRequests>,
+    /// The tracker of other validators that authenticated as the account of this validator.
+    duplicate_identity: Arc<DuplicateIdentity>,
+    /// The nonces the validators recently used in their handshakes.
+    recent_nonces: Arc<RecentNonces>,
+    /// The registry of the connections, with their statistics.
+    connections: Arc<ConnectionRegistry<N>>,
     /// The primary sender.
     primary_sender: Arc<OnceCell<PrimarySender<N>>>,
     /// The worker senders.
@@ -144,7 +194,7 @@ impl<N: Network> Gateway<N> {
     ) -> Result<Self> {
         // Initialize the gateway IP.
         let ip = match (ip, dev) {
-            (None, Some(dev)) => SocketAddr::from_str(&format!("127.0.0.1:{}", MEMORY_POOL_PORT + dev))?,
+            (None, Some(dev)) => DevInstance::new(dev)?.bft_ip(),
             (None, None) => SocketAddr::from_str(&format!("0.0.0.0:{}", MEMORY_POOL_PORT))?,
             (Some(ip), _) => ip,
         };
@@ -161,6 +211,13 @@ impl<N: Network> Gateway<N> {
             trusted_validators: trusted_validators.iter().copied().collect(),
             connected_peers: Default::default(),
             connecting_peers: Default::default(),
+            peer_versions: Default::default(),
+            last_activity: Default::default(),
+            transfers: Default::default(),
+            validators_requests: Default::default(),
+            duplicate_identity: Default::default(),
+            recent_nonces: Default::default(),
+            connections: Default::default(),
             primary_sender: Default::default(),
             worker_senders: Default::default(),
             sync_sender: Default::default(),
@@ -200,6 +257,8 @@ impl<N: Network> Gateway<N> {
 
         // Initialize the heartbeat.
         self.initialize_heartbeat();
+        // Initialize the transfer resumption.
+        self.initialize_transfer_resumption();
 
         info!("Started the gateway for the memory pool at '{}'", self.local_ip());
     }
@@ -272,6 +331,11 @@ impl<N: Network> Gateway<N> {
         self.tcp.listening_addr().expect("The TCP listener is not enabled")
     }
 
+    /// Returns the trusted validators.
+    pub const fn trusted_validators(&self) -> &IndexSet<SocketAddr> {
+        &self.trusted_validators
+    }
+
     /// Returns `true` if the given IP is this node.
     pub fn is_local_ip(&self, ip: SocketAddr) -> bool {
         ip == self.local_ip()
@@ -321,7 +385,7 @@ impl<N: Network> Gateway<N> {
 
     /// Returns `true` if the node is connecting to the given peer IP.
     pub fn is_connecting_ip(&self, ip: SocketAddr) -> bool {
-        self.connecting_peers.lock().contains(&ip)
+        self.connecting_peers.lock().contains_key(&ip)
     }
 
     /// Returns `true` if the given peer IP is an authorized validator.
@@ -394,6 +458,79 @@ impl<N: Network> Gateway<N> {
         &self.connected_peers
     }
 
+    /// Returns a snapshot of the UNIX timestamp of the last event received from each validator.
+    pub fn last_activity(&self) -> HashMap<Address<N>, i64> {
+        self.last_activity.read().clone()
+    }
+
+    /// Returns a snapshot of the statistics of each connection with a validator.
+    pub fn connection_stats(&self) -> Vec<ConnectionSnapshot<N>> {
+        self.connections.snapshot(now())
+    }
+
+    /// Records the given error with the connection of the validator at the given peer IP.
+    pub(crate) fn record_peer_error(&self, peer_ip: SocketAddr, error: String) {
+        self.connections.record_error(&peer_ip, error, now());
+    }
+
+    /// Records a batch proposal of the given validator, whose transmissions it never served.
+    pub(crate) fn record_withheld_proposal(&self, address: &Address<N>) {
+        self.connections.record_withheld_proposal(address);
+    }
+
+    /// Sets whether the batch proposals of the given validator are declined, as it withholds their transmissions.
+    pub(crate) fn set_declined_proposer(&self, address: &Address<N>, is_declined: bool) {
+        self.connections.set_declined_proposer(address, is_declined);
+    }
+
+    /// Returns the statistics of the connection with the given (ambiguous) peer address.
+    /// If the connection is not registered, detached statistics are returned, so that the traffic is still decoded.
+    fn connection_stats_of(&self, peer_addr: SocketAddr) -> Arc<ConnectionStats<N>> {
+        self.resolver.get_listener(peer_addr).and_then(|peer_ip| self.connections.get(&peer_ip)).unwrap_or_default()
+    }
+
+    /// Returns the level of detail of the validators responses to peers outside the committee.
+    pub fn validators_response_mode(&self) -> ValidatorsResponseMode {
+        self.validators_requests.mode()
+    }
+
+    /// Sets the level of detail of the validators responses to peers outside the committee.
+    pub fn set_validators_response_mode(&self, mode: ValidatorsResponseMode) {
+        self.validators_requests.set_mode(mode);
+    }
+
+    /// Returns the tracker of other validators that authenticated as the account of this validator.
+    pub fn duplicate_identity(&self) -> &DuplicateIdentity {
+        &self.duplicate_identity
+    }
+
+    /// Returns the peer IPs of the other validators that recently authenticated as the account of this validator,
+    /// from the most recent.
+    pub fn duplicate_identity_peers(&self) -> Vec<SocketAddr> {
+        self.duplicate_identity.peer_ips(Instant::now())
+    }
+
+    /// Returns `true` if another validator recently authenticated as the account of this validator.
+    pub fn is_duplicate_identity(&self) -> bool {
+        self.duplicate_identity.is_active(Instant::now())
+    }
+
+    /// Returns `true` if the file descriptors of the node are exhausted, as reported by the connections of the gateway.
+    pub fn is_fd_exhausted(&self) -> bool {
+        self.tcp.fd_exhaustion().is_exhausted(Instant::now())
+    }
+
+    /// Reports that the validator at the given peer IP authenticated as the account of this validator.
+    fn report_duplicate_identity(&self, peer_ip: SocketAddr) {
+        if self.duplicate_identity.report(peer_ip, Instant::now()) {
+            let message =
+                format!("Another validator is using this account ({}) at '{peer_ip}'", self.account.address());
+            warn!("{CONTEXT} {}", message.bold().red());
+        }
+        #[cfg(feature = "metrics")]
+        metrics::increment_counter(metrics::bft::DUPLICATE_IDENTITIES);
+    }
+
     /// Attempts to connect to the given peer IP.
     pub fn connect(&self, peer_ip: SocketAddr) -> Option<JoinHandle<()>> {
         // Return early if the attempt is against the protocol rules.
@@ -408,7 +545,11 @@ impl<N: Network> Gateway<N> {
             // Attempt to connect to the peer.
             if let Err(error) = self_.tcp.connect(peer_ip).await {
                 self_.connecting_peers.lock().shift_remove(&peer_ip);
-                warn!("Unable to connect to '{peer_ip}' - {error}");
+                // If the connection initiated by the peer was kept instead, this is not a failure.
+                match self_.is_connected_ip(peer_ip) {
+                    true => debug!("{CONTEXT} Kept the simultaneous connection initiated by '{peer_ip}'"),
+                    false => warn!("Unable to connect to '{peer_ip}' - {error}"),
+                }
             }
         }))
     }
@@ -428,21 +569,41 @@ impl<N: Network> Gateway<N> {
             bail!("{CONTEXT} Dropping connection attempt to '{peer_ip}' (already connected)")
         }
         // Ensure the node is not already connecting to this peer.
-        if self.is_connecting_ip(peer_ip) {
+        let mut connecting_peers = self.connecting_peers.lock();
+        if connecting_peers.contains_key(&peer_ip) {
             bail!("{CONTEXT} Dropping connection attempt to '{peer_ip}' (already connecting)")
         }
+        connecting_peers.insert(peer_ip, ConnectionSide::Responder);
         Ok(())
     }
 
     /// Ensure the peer is allowed to connect.
-    fn ensure_peer_is_allowed(&self, peer_ip: SocketAddr) -> Result<()> {
+    fn ensure_peer_is_allowed(&self, peer_ip: SocketAddr, peer_address: Address<N>) -> Result<()> {
         // Ensure the peer IP is not this node.
         if self.is_local_ip(peer_ip) {
             bail!("{CONTEXT} Dropping connection request from '{peer_ip}' (attempted to self-connect)")
         }
-        // Ensure the node is not already connecting to this peer.
-        if !self.connecting_peers.lock().insert(peer_ip) {
-            bail!("{CONTEXT} Dropping connection request from '{peer_ip}' (already shaking hands as the initiator)")
+        // Ensure the node is not already connecting to this peer. If both nodes are dialing each other
+        // simultaneously, both keep the connection of the preferred initiator, and drop the other one.
+        match self.connecting_peers.lock().entry(peer_ip) {
+            Entry::Vacant(entry) => {
+                entry.insert(ConnectionSide::Initiator);
+            }
+            Entry::Occupied(entry) => match entry.get() {
+                ConnectionSide::Responder if self.is_preferred_initiator(peer_address) => {
+                    debug!("{CONTEXT} Keeping the simultaneous connection initiated by '{peer_ip}'")
+                }
+                ConnectionSide::Responder => {
+                    bail!(
+                        "{CONTEXT} Dropping connection request from '{peer_ip}' (already shaking hands as the initiator)"
+                    )
+                }
+                ConnectionSide::Initiator => {
+                    bail!(
+                        "{CONTEXT} Dropping connection request from '{peer_ip}' (already shaking hands as the responder)"
+                    )
+                }
+            },
         }
         // Ensure the node is not already connected to this peer.
         if self.is_connected_ip(peer_ip) {
@@ -460,6 +621,15 @@ impl<N: Network> Gateway<N> {
         Ok(())
     }
 
+    /// Returns `true` if the connection initiated by the given validator takes precedence over the one initiated
+    /// by this node, when both nodes are dialing each other simultaneously.
+    ///
+    /// Both nodes must independently arrive at the same decision, so the tie is broken on the validator addresses,
+    /// which are unique and known to both sides, unlike the listening addresses.
+    fn is_preferred_initiator(&self, peer_address: Address<N>) -> bool {
+        peer_address.to_string() < self.account.address().to_string()
+    }
+
     #[cfg(feature = "metrics")]
     fn update_metrics(&self) {
         metrics::gauge(metrics::bft::CONNECTED, self.connected_peers.read().len() as f64);
@@ -473,6 +643,8 @@ impl<N: Network> Gateway<N> {
         self.resolver.insert_peer(peer_ip, peer_addr, address);
         // Add a transmission for this peer in the connected peers.
         self.connected_peers.write().insert(peer_ip);
+        // Register the connection, to track its statistics.
+        self.connections.insert(peer_ip, address, now());
         #[cfg(feature = "metrics")]
         self.update_metrics();
     }
@@ -485,6 +657,8 @@ impl<N: Network> Gateway<N> {
         self.resolver.insert_peer(peer_ip, peer_addr, address);
         // Add a transmission for this peer in the connected peers.
         self.connected_peers.write().insert(peer_ip);
+        // Register the connection, to track its statistics.
+        self.connections.insert(peer_ip, address, now());
     }
 
     /// Removes the connected peer and adds them to the candidate peers.
@@ -502,6 +676,11 @@ impl<N: Network> Gateway<N> {
         self.resolver.remove_peer(peer_ip);
         // Remove this peer from the connected peers, if it exists.
         self.connected_peers.write().shift_remove(&peer_ip);
+        // Remove the statistics of the connection; the history of the validator is retained.
+        self.connections.remove(&peer_ip);
+        // Remove the version and any incomplete transfers of this peer.
+        self.peer_versions.write().remove(&peer_ip);
+        self.transfers.remove_peer(peer_ip);
         #[cfg(feature = "metrics")]
         self.update_metrics();
     }
@@ -525,6 +704,7 @@ impl<N: Network> Gateway<N> {
         // If the event was unable to be sent, disconnect.
         if let Err(e) = &result {
             warn!("{CONTEXT} Failed to send '{name}' to '{peer_ip}': {e}");
+            self.connections.record_error(&peer_ip, format!("Failed to send '{name}': {e}"), now());
             debug!("{CONTEXT} Disconnecting from '{peer_ip}' (unable to send)");
             self.disconnect(peer_ip);
         }
@@ -541,6 +721,10 @@ impl<N: Network> Gateway<N> {
         if !self.is_authorized_validator_ip(peer_ip) {
             bail!("{CONTEXT} Dropping '{}' from '{peer_ip}' (not authorized)", event.name())
         }
+        // Update the last activity of the validator.
+        if let Some(address) = self.resolver.get_address(peer_ip) {
+            self.last_activity.write().insert(address, now());
+        }
         // Drop the peer, if they have exceeded the rate limit (i.e. they are requesting too much from us).
         let num_events = self.cache.insert_inbound_event(peer_ip, CACHE_EVENTS_INTERVAL);
         if num_events >= self.max_cache_events() {
@@ -611,16 +795,20 @@ impl<N: Network> Gateway<N> {
 
                 let self_ = self.clone();
                 let blocks = match task::spawn_blocking(move || {
-                    // Retrieve the blocks within the requested range.
-                    match self_.ledger.get_blocks(start_height..end_height) {
-                        Ok(blocks) => Ok(Data::Object(DataBlocks(blocks))),
-                        Err(error) => bail!("Missing blocks {start_height} to {end_height} from ledger - {error}"),
-                    }
+                    // Retrieve the blocks within the requested range, reading them again on a transient failure.
+                    retry_transient(|| self_.ledger.get_blocks(start_height..end_height))
                 })
                 .await
                 {
-                    Ok(Ok(blocks)) => blocks,
-                    Ok(Err(error)) => return Err(error),
+                    Ok(Ok(blocks)) => Data::Object(DataBlocks(blocks)),
+                    // If the ledger failed to read the blocks, the peer is not at fault, and may request them again.
+                    Ok(Err(error)) if LedgerReadError::is_unavailable(&error) => {
+                        warn!(
+                            "{CONTEXT} Unable to serve blocks {start_height} to {end_height} to '{peer_ip}' - {error}"
+                        );
+                        return Ok(());
+                    }
+                    Ok(Err(error)) => bail!("Missing blocks {start_height} to {end_height} from ledger - {error}"),
                     Err(error) => return Err(anyhow!("[BlockRequest] {error}")),
                 };
 
@@ -673,15 +861,23 @@ impl<N: Network> Gateway<N> {
                 bail!("{CONTEXT} Peer '{peer_ip}' is not following the protocol")
             }
             Event::Disconnect(disconnect) => {
+                // A validator that is shutting down is not violating the protocol, so it is disconnected gracefully.
+                if disconnect.reason == DisconnectReason::ShuttingDown {
+                    info!("{CONTEXT} Disconnecting from '{peer_ip}' (validator is shutting down)");
+                    self.disconnect(peer_ip);
+                    return Ok(());
+                }
                 bail!("{CONTEXT} {:?}", disconnect.reason)
             }
             Event::PrimaryPing(ping) => {
                 let PrimaryPing { version, block_locators, primary_certificate } = ping;
 
                 // Ensure the event version is not outdated.
-                if version < Event::<N>::VERSION {
+                if version < Event::<N>::MINIMUM_VERSION {
                     bail!("Dropping '{peer_ip}' on event version {version} (outdated)");
                 }
+                // Update the version of the peer.
+                self.peer_versions.write().insert(peer_ip, version);
 
                 // If a sync sender was provided, update the peer locators.
                 if let Some(sync_sender) = self.sync_sender.get() {
@@ -710,21 +906,52 @@ impl<N: Network> Gateway<N> {
                 Ok(())
             }
             Event::TransmissionResponse(response) => {
+                self.forward_transmission_response(peer_ip, response).await;
+                Ok(())
+            }
+            Event::TransmissionChunk(chunk) => {
+                // Add the chunk to its transfer, and forward the transmission once it is reassembled.
+                match self.transfers.insert(peer_ip, chunk) {
+                    Ok(Some(response)) => self.forward_transmission_response(peer_ip, response).await,
+                    Ok(None) => (),
+                    Err(error) => bail!("{CONTEXT} Invalid transmission chunk from '{peer_ip}' - {error}"),
+                }
+                Ok(())
+            }
+            Event::TransmissionChunkRequest(request) => {
                 // Determine the worker ID.
-                let Ok(worker_id) = assign_to_worker(response.transmission_id, self.num_workers()) else {
-                    warn!("{CONTEXT} Unable to assign transmission ID '{}' to a worker", response.transmission_id);
+                let Ok(worker_id) = assign_to_worker(request.transmission_id, self.num_workers()) else {
+                    warn!("{CONTEXT} Unable to assign transmission ID '{}' to a worker", request.transmission_id);
                     return Ok(());
                 };
-                // Send the transmission response to the worker.
+                // Send the transmission chunk request to the worker.
                 if let Some(sender) = self.get_worker_sender(worker_id) {
-                    // Send the transmission response to the worker.
-                    let _ = sender.tx_transmission_response.send((peer_ip, response)).await;
+                    let _ = sender.tx_transmission_chunk_request.send((peer_ip, request)).await;
                 }
                 Ok(())
             }
-            Event::ValidatorsRequest(_) => {
+            Event::ValidatorsRequest(request) => {
+                // Determine if the peer is a member of the current committee.
+                let is_committee_member = self.resolver.get_address(peer_ip).is_some_and(|address| {
+                    self.ledger.current_committee().map_or(false, |committee| committee.is_committee_member(address))
+                });
+                // Ensure the request is admitted, and determine the level of detail of the response.
+                let (mode, permit) = match self.validators_requests.process(peer_ip, is_committee_member, now()) {
+                    ValidatorsRequestOutcome::Respond { mode, permit } => (mode, permit),
+                    ValidatorsRequestOutcome::Drop => {
+                        #[cfg(feature = "metrics")]
+                        metrics::increment_counter(metrics::bft::DROPPED_VALIDATORS_REQUESTS);
+                        return Ok(());
+                    }
+                    ValidatorsRequestOutcome::Spam => {
+                        #[cfg(feature = "metrics")]
+                        metrics::increment_counter(metrics::bft::DROPPED_VALIDATORS_REQUESTS);
+                        bail!("{CONTEXT} Dropping '{peer_ip}' for spamming validators requests")
+                    }
+                };
+
                 // Retrieve the connected peers.
-                let mut connected_peers: Vec<_> = match self.dev.is_some() {
+                let connected_peers: Vec<_> = match self.dev.is_some() {
                     // In development mode, relax the validity requirements to make operating devnets more flexible.
                     true => self.connected_peers.read().iter().copied().collect(),
                     // In production mode, ensure the peer IPs are valid.
@@ -732,15 +959,23 @@ impl<N: Network> Gateway<N> {
                         self.connected_peers.read().iter().copied().filter(|ip| self.is_valid_peer_ip(*ip)).collect()
                     }
                 };
+                // Retain the connected peers to respond with.
+                let mut connected_peers = mode.filter(connected_peers, &self.trusted_validators);
                 // Shuffle the connected peers.
                 connected_peers.shuffle(&mut rand::thread_rng());
+                // Determine the maximum number of validators to send, within the maximum requested by the peer.
+                let max_validators = request.max_validators.map_or(ValidatorsResponse::<N>::MAX_VALIDATORS, |max| {
+                    ValidatorsResponse::<N>::MAX_VALIDATORS.min(max.into())
+                });
 
                 let self_ = self.clone();
                 tokio::spawn(async move {
+                    // Hold the permit until the response is sent.
+                    let _permit = permit;
                     // Initialize the validators.
-                    let mut validators = IndexMap::with_capacity(MAX_VALIDATORS_TO_SEND);
+                    let mut validators = IndexMap::with_capacity(max_validators);
                     // Iterate over the validators.
-                    for validator_ip in connected_peers.into_iter().take(MAX_VALIDATORS_TO_SEND) {
+                    for validator_ip in connected_peers.into_iter().take(max_validators) {
                         // Retrieve the validator address.
                         if let Some(validator_address) = self_.resolver.get_address(validator_ip) {
                             // Add the validator to the list of validators.
@@ -755,8 +990,13 @@ impl<N: Network> Gateway<N> {
             }
             Event::ValidatorsResponse(response) => {
                 let ValidatorsResponse { validators } = response;
-                // Ensure the number of validators is not too large.
-                ensure!(validators.len() <= MAX_VALIDATORS_TO_SEND, "{CONTEXT} Received too many validators");
+                // Ensure the number of validators is within the maximum requested from this peer.
+                // Note: The maximum of a validators response is already enforced when it is deserialized.
+                let max_validators = match self.supports_bounded_validators(peer_ip) {
+                    true => MAX_VALIDATORS_TO_REQUEST.into(),
+                    false => ValidatorsResponse::<N>::MAX_VALIDATORS,
+                };
+                ensure!(validators.len() <= max_validators, "{CONTEXT} Received too many validators from '{peer_ip}'");
                 // Ensure the cache contains a validators request for this peer.
                 if !self.cache.contains_outbound_validators_request(peer_ip) {
                     bail!("{CONTEXT} Received validators response from '{peer_ip}' without a validators request")
@@ -843,6 +1083,97 @@ impl<N: Network> Gateway<N> {
         })
     }
 
+    /// Forwards the given transmission response to the worker it is assigned to.
+    async fn forward_transmission_response(&self, peer_ip: SocketAddr, response: TransmissionResponse<N>) {
+        // Determine the worker ID.
+        let Ok(worker_id) = assign_to_worker(response.transmission_id, self.num_workers()) else {
+            warn!("{CONTEXT} Unable to assign transmission ID '{}' to a worker", response.transmission_id);
+            return;
+        };
+        // Send the transmission response to the worker.
+        if let Some(sender) = self.get_worker_sender(worker_id) {
+            // Send the transmission response to the worker.
+            let _ = sender.tx_transmission_response.send((peer_ip, response)).await;
+        }
+    }
+
+    /// Returns the chunks of the given transmission, to be sent to a peer.
+    pub(crate) fn transmission_chunks(
+        &self,
+        transmission_id: TransmissionID<N>,
+        transmission: &Transmission<N>,
+    ) -> Result<Vec<TransmissionChunk<N>>> {
+        self.transfers.split(transmission_id, transmission)
+    }
+
+    /// Returns `true` if the given peer supports receiving transmissions in chunks.
+    fn supports_chunked_transmissions(&self, peer_ip: SocketAddr) -> bool {
+        self.peer_versions
+            .read()
+            .get(&peer_ip)
+            .is_some_and(|version| *version >= Event::<N>::CHUNKED_TRANSMISSIONS_VERSION)
+    }
+
+    /// Returns `true` if the given peer understands the `ShuttingDown` disconnect reason.
+    fn supports_shutting_down(&self, peer_ip: SocketAddr) -> bool {
+        self.peer_versions.read().get(&peer_ip).is_some_and(|version| *version >= Event::<N>::SHUTTING_DOWN_VERSION)
+    }
+
+    /// Returns `true` if the given peer honors the maximum number of validators in a validators request.
+    fn supports_bounded_validators(&self, peer_ip: SocketAddr) -> bool {
+        self.peer_versions
+            .read()
+            .get(&peer_ip)
+            .is_some_and(|version| *version >= Event::<N>::BOUNDED_VALIDATORS_VERSION)
+    }
+
+    /// Sends the given transmission response to the specified peer in chunks.
+    /// Returns `None` if the response is small enough to be sent in full.
+    async fn send_transmission_chunks(
+        &self,
+        peer_ip: SocketAddr,
+        response: &TransmissionResponse<N>,
+    ) -> Option<Option<oneshot::Receiver<io::Result<()>>>> {
+        // Split the transmission into chunks.
+        let chunks = match self.transfers.split(response.transmission_id, &response.transmission) {
+            Ok(chunks) if chunks.len() > 1 => chunks,
+            Ok(_) => return None,
+            Err(error) => {
+                warn!("{CONTEXT} Failed to split transmission '{}' - {error}", response.transmission_id);
+                return None;
+            }
+        };
+        // Send the chunks in order, so that other events may be interleaved between them.
+        let mut result = None;
+        for chunk in chunks {
+            result = Transport::send(self, peer_ip, Event::TransmissionChunk(chunk)).await;
+            // If a chunk could not be sent, the peer has been disconnected.
+            if result.is_none() {
+                break;
+            }
+        }
+        Some(result)
+    }
+
+    /// Initialize a new instance of the transfer resumption, which re-requests the missing chunks of stalled transfers.
+    fn initialize_transfer_resumption(&self) {
+        let self_clone = self.clone();
+        self.spawn(async move {
+            loop {
+                // Sleep for half of the stall timeout.
+                tokio::time::sleep(Duration::from_millis(TRANSFER_STALL_TIMEOUT_IN_MS / 2)).await;
+                // Re-request the missing chunks of the stalled transfers.
+                for (peer_ip, request) in self_clone.transfers.resume_stalled(Instant::now()) {
+                    debug!("{CONTEXT} Resuming the transfer of '{}' from '{peer_ip}'", request.transmission_id);
+                    let self_ = self_clone.clone();
+                    tokio::spawn(async move {
+                        Transport::send(&self_, peer_ip, Event::TransmissionChunkRequest(request)).await;
+                    });
+                }
+            }
+        });
+    }
+
     /// Initialize a new instance of the heartbeat.
     fn initialize_heartbeat(&self) {
         let self_clone = self.clone();
@@ -865,11 +1196,32 @@ impl<N: Network> Gateway<N> {
         self.handles.lock().push(tokio::spawn(future));
     }
 
+    /// Notifies the connected validators that the node is shutting down, and waits for the notifications
+    /// to be delivered, up to the shutdown notification timeout.
+    /// Note: The validators on an older version are not notified, as they cannot read the disconnect reason.
+    async fn notify_shutdown(&self) {
+        let connected_peers = self.connected_peers.read().clone();
+        let notify = async {
+            let mut notifications = Vec::with_capacity(connected_peers.len());
+            for peer_ip in connected_peers.into_iter().filter(|peer_ip| self.supports_shutting_down(*peer_ip)) {
+                notifications.extend(Transport::send(self, peer_ip, DisconnectReason::ShuttingDown.into()).await);
+            }
+            for notification in notifications {
+                let _ = notification.await;
+            }
+        };
+        if tokio::time::timeout(Duration::from_millis(SHUTDOWN_NOTIFICATION_TIMEOUT_IN_MS), notify).await.is_err() {
+            debug!("{CONTEXT} Timed out delivering the shutdown notifications to the validators");
+        }
+    }
+
     /// Shuts down the gateway.
     pub async fn shut_down(&self) {
         info!("Shutting down the gateway...");
         // Abort the tasks.
         self.handles.lock().iter().for_each(|handle| handle.abort());
+        // Notify the connected validators, so they do not wait for the connections to time out.
+        self.notify_shutdown().await;
         // Close the listener.
         self.tcp.shut_down().await;
     }
@@ -879,12 +1231,74 @@ impl<N: Network> Gateway<N> {
     /// Handles the heartbeat request.
     fn heartbeat(&self) {
         self.log_connected_validators();
+        // Repeat the warning while another validator is using the account of this validator.
+        self.log_duplicate_identity();
         // Keep the trusted validators connected.
         self.handle_trusted_validators();
         // Removes any validators that not in the current committee.
         self.handle_unauthorized_validators();
         // If the number of connected validators is less than the minimum, send a `ValidatorsRequest`.
         self.handle_min_connected_validators();
+        // Update the connection metrics.
+        #[cfg(feature = "metrics")]
+        self.update_connection_metrics();
+    }
+
+    /// Logs the other validators that recently authenticated as the account of this validator, if any.
+    fn log_duplicate_identity(&self) {
+        let peer_ips = self.duplicate_identity_peers();
+        if !peer_ips.is_empty() {
+            let message = format!(
+                "Another validator is using this account ({}) at {peer_ips:?} - stop all but one of the validators",
+                self.account.address()
+            );
+            warn!("{CONTEXT} {}", message.bold().red());
+        }
+    }
+
+    /// Updates the metrics of the connection with each validator.
+    #[cfg(feature = "metrics")]
+    fn update_connection_metrics(&self) {
+        for connection in self.connection_stats() {
+            let validator = connection.address.to_string();
+            let (messages_sent, bytes_sent, messages_received, bytes_received) = connection.totals();
+            metrics::gauge_label(
+                metrics::bft::CONNECTION_BYTES_SENT,
+                "validator",
+                validator.clone(),
+                bytes_sent as f64,
+            );
+            metrics::gauge_label(
+                metrics::bft::CONNECTION_BYTES_RECEIVED,
+                "validator",
+                validator.clone(),
+                bytes_received as f64,
+            );
+            metrics::gauge_label(
+                metrics::bft::CONNECTION_MESSAGES_SENT,
+                "validator",
+                validator.clone(),
+                messages_sent as f64,
+            );
+            metrics::gauge_label(
+                metrics::bft::CONNECTION_MESSAGES_RECEIVED,
+                "validator",
+                validator.clone(),
+                messages_received as f64,
+            );
+            metrics::gauge_label(
+                metrics::bft::CONNECTION_UPTIME,
+                "validator",
+                validator.clone(),
+                connection.uptime as f64,
+            );
+            metrics::gauge_label(
+                metrics::bft::CONNECTION_RECONNECTS,
+                "validator",
+                validator,
+                connection.num_reconnects as f64,
+            );
+        }
     }
 
     /// Logs the connected validators.
@@ -959,8 +1373,12 @@ impl<N: Network> Gateway<N> {
                 tokio::spawn(async move {
                     // Increment the number of outbound validators requests for this validator.
                     self_.cache.increment_outbound_validators_requests(validator_ip);
-                    // Send a `ValidatorsRequest` to the validator.
-                    let _ = Transport::send(&self_, validator_ip, Event::ValidatorsRequest(ValidatorsRequest)).await;
+                    // Send a `ValidatorsRequest` to the validator, with a maximum if the validator honors it.
+                    // Note: The validators before the bounded validators requests reject a request with a maximum.
+                    let max_validators =
+                        self_.supports_bounded_validators(validator_ip).then_some(MAX_VALIDATORS_TO_REQUEST);
+                    let request = ValidatorsRequest::new(max_validators);
+                    let _ = Transport::send(&self_, validator_ip, Event::ValidatorsRequest(request)).await;
                 });
             }
         }
@@ -989,6 +1407,15 @@ impl<N: Network> Transport<N> for Gateway<N> {
             }};
         }
 
+        // If the peer supports chunked transfers, send large transmission responses in chunks.
+        if let Event::TransmissionResponse(response) = &event {
+            if self.supports_chunked_transmissions(peer_ip) {
+                if let Some(result) = self.send_transmission_chunks(peer_ip, response).await {
+                    return result;
+                }
+            }
+        }
+
         // Increment the cache for certificate, transmission and block events.
         match event {
             Event::CertificateRequest(_) | Event::CertificateResponse(_) => {
@@ -998,6 +1425,10 @@ impl<N: Network> Transport<N> for Gateway<N> {
                 send!(self, insert_outbound_certificate, CACHE_REQUESTS_INTERVAL, max_cache_certificates)
             }
             Event::TransmissionRequest(_) | Event::TransmissionResponse(_) => {
+                // Record the outbound request, so that the chunks of the response are accepted.
+                if let Event::TransmissionRequest(request) = &event {
+                    self.transfers.expect(peer_ip, request.transmission_id);
+                }
                 // Update the outbound event cache. This is necessary to ensure we don't under count the outbound events.
                 self.cache.insert_outbound_event(peer_ip, CACHE_EVENTS_INTERVAL);
                 // Send the event to the peer.
@@ -1044,7 +1475,7 @@ impl<N: Network> P2P for Gateway<N> {
 
 #[async_trait]
 impl<N: Network> Reading for Gateway<N> {
-    type Codec = EventCodec<N>;
+    type Codec = MeteredCodec<N>;
     type Message = Event<N>;
 
     /// The maximum queue depth of incoming messages for a single peer.
@@ -1055,8 +1486,16 @@ impl<N: Network> Reading for Gateway<N> {
 
     /// Creates a [`Decoder`] used to interpret messages from the network.
     /// The `side` param indicates the connection side **from the node's perspective**.
-    fn codec(&self, _peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
-        Default::default()
+    fn codec(&self, peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+        MeteredCodec::new(self.connection_stats_of(peer_addr))
+    }
+
+    /// Classifies a message received from the network, so that a disconnect skips the queued batch data.
+    fn message_priority(&self, message: &Self::Message) -> MessagePriority {
+        match message {
+            Event::Disconnect(..) => MessagePriority::Terminal,
+            _ => MessagePriority::Bulk,
+        }
     }
 
     /// Processes a message received from the network.
@@ -1065,6 +1504,7 @@ impl<N: Network> Reading for Gateway<N> {
         if let Err(error) = self.inbound(peer_addr, message).await {
             if let Some(peer_ip) = self.resolver.get_listener(peer_addr) {
                 warn!("{CONTEXT} Disconnecting from '{peer_ip}' - {error}");
+                self.connections.record_error(&peer_ip, error.to_string(), now());
                 let self_ = self.clone();
                 tokio::spawn(async move {
                     Transport::send(&self_, peer_ip, DisconnectReason::ProtocolViolation.into()).await;
@@ -1079,7 +1519,7 @@ impl<N: Network> Reading for Gateway<N> {
 
 #[async_trait]
 impl<N: Network> Writing for Gateway<N> {
-    type Codec = EventCodec<N>;
+    type Codec = MeteredCodec<N>;
     type Message = Event<N>;
 
     /// The maximum queue depth of outgoing messages for a single peer.
@@ -1090,8 +1530,8 @@ impl<N: Network> Writing for Gateway<N> {
 
     /// Creates an [`Encoder`] used to write the outbound messages to the target stream.
     /// The `side` parameter indicates the connection side **from the node's perspective**.
-    fn codec(&self, _peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
-        Default::default()
+    fn codec(&self, peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+        MeteredCodec::new(self.connection_stats_of(peer_addr))
     }
 }
 
@@ -1224,9 +1664,12 @@ impl<N: Network> Gateway<N> {
 
         // Sample a random nonce.
         let our_nonce = rng.gen();
+        // Record the nonce, so that the node recognizes the request if it is connecting to itself.
+        self.duplicate_identity.insert_own_nonce(our_nonce);
         // Send a challenge request to the peer.
         let our_request = ChallengeRequest::new(self.local_ip().port(), self.account.address(), our_nonce);
         send_event(&mut framed, peer_addr, Event::ChallengeRequest(our_request)).await?;
+        let request_sent_at = Instant::now();
 
         /* Step 2: Receive the peer's challenge response followed by the challenge request. */
 
@@ -1237,12 +1680,25 @@ impl<N: Network> Gateway<N> {
 
         // Verify the challenge response. If a disconnect reason was returned, send the disconnect message and abort.
         if let Some(reason) = self
-            .verify_challenge_response(peer_addr, peer_request.address, peer_response, restrictions_id, our_nonce)
+            .verify_challenge_response(
+                peer_addr,
+                &peer_request,
+                peer_response,
+                restrictions_id,
+                our_nonce,
+                request_sent_at.elapsed(),
+            )
             .await
         {
             send_event(&mut framed, peer_addr, reason.into()).await?;
             return Err(error(format!("Dropped '{peer_addr}' for reason: {reason:?}")));
         }
+        // Ensure the peer is not another validator using the account of this validator.
+        if peer_request.address == self.account.address() {
+            self.report_duplicate_identity(peer_ip);
+            send_event(&mut framed, peer_addr, DisconnectReason::ProtocolViolation.into()).await?;
+            return Err(error(format!("Dropped '{peer_addr}' for using the account of this validator")));
+        }
         // Verify the challenge request. If a disconnect reason was returned, send the disconnect message and abort.
         if let Some(reason) = self.verify_challenge_request(peer_addr, &peer_request) {
             send_event(&mut framed, peer_addr, reason.into()).await?;
@@ -1253,7 +1709,7 @@ impl<N: Network> Gateway<N> {
 
         // Sign the counterparty nonce.
         let response_nonce: u64 = rng.gen();
-        let data = [peer_request.nonce.to_le_bytes(), response_nonce.to_le_bytes()].concat();
+        let data = self.challenge_response_bytes(&peer_request, response_nonce)?;
         let Ok(our_signature) = self.account.sign_bytes(&data, rng) else {
             return Err(error(format!("Failed to sign the challenge request nonce from '{peer_addr}'")));
         };
@@ -1264,6 +1720,7 @@ impl<N: Network> Gateway<N> {
 
         // Add the peer to the gateway.
         self.insert_connected_peer(peer_ip, peer_addr, peer_request.address);
+        self.peer_versions.write().insert(peer_ip, peer_request.version);
 
         Ok((peer_ip, framed))
     }
@@ -1284,8 +1741,9 @@ impl<N: Network> Gateway<N> {
         // Listen for the challenge request message.
         let peer_request = expect_event!(Event::ChallengeRequest, framed, peer_addr);
 
-        // Ensure the address is not the same as this node.
-        if self.account.address() == peer_request.address {
+        // Ensure the node is not connecting to itself. Another validator using the account of this validator
+        // is only reported once it proves control of the account.
+        if self.account.address() == peer_request.address && self.duplicate_identity.is_own_nonce(peer_request.nonce) {
             return Err(error("Skipping request to connect to self".to_string()));
         }
 
@@ -1294,7 +1752,7 @@ impl<N: Network> Gateway<N> {
         let peer_ip = peer_ip.unwrap();
 
         // Knowing the peer's listening address, ensure it is allowed to connect.
-        if let Err(forbidden_message) = self.ensure_peer_is_allowed(peer_ip) {
+        if let Err(forbidden_message) = self.ensure_peer_is_allowed(peer_ip, peer_request.address) {
             return Err(error(format!("{forbidden_message}")));
         }
         // Verify the challenge request. If a disconnect reason was returned, send the disconnect message and abort.
@@ -1310,7 +1768,7 @@ impl<N: Network> Gateway<N> {
 
         // Sign the counterparty nonce.
         let response_nonce: u64 = rng.gen();
-        let data = [peer_request.nonce.to_le_bytes(), response_nonce.to_le_bytes()].concat();
+        let data = self.challenge_response_bytes(&peer_request, response_nonce)?;
         let Ok(our_signature) = self.account.sign_bytes(&data, rng) else {
             return Err(error(format!("Failed to sign the challenge request nonce from '{peer_addr}'")));
         };
@@ -1324,6 +1782,7 @@ impl<N: Network> Gateway<N> {
         // Send the challenge request.
         let our_request = ChallengeRequest::new(self.local_ip().port(), self.account.address(), our_nonce);
         send_event(&mut framed, peer_addr, Event::ChallengeRequest(our_request)).await?;
+        let request_sent_at = Instant::now();
 
         /* Step 3: Receive the challenge response. */
 
@@ -1331,14 +1790,28 @@ impl<N: Network> Gateway<N> {
         let peer_response = expect_event!(Event::ChallengeResponse, framed, peer_addr);
         // Verify the challenge response. If a disconnect reason was returned, send the disconnect message and abort.
         if let Some(reason) = self
-            .verify_challenge_response(peer_addr, peer_request.address, peer_response, restrictions_id, our_nonce)
+            .verify_challenge_response(
+                peer_addr,
+                &peer_request,
+                peer_response,
+                restrictions_id,
+                our_nonce,
+                request_sent_at.elapsed(),
+            )
             .await
         {
             send_event(&mut framed, peer_addr, reason.into()).await?;
             return Err(error(format!("Dropped '{peer_addr}' for reason: {reason:?}")));
         }
+        // Ensure the peer is not another validator using the account of this validator.
+        if peer_request.address == self.account.address() {
+            self.report_duplicate_identity(peer_ip);
+            send_event(&mut framed, peer_addr, DisconnectReason::ProtocolViolation.into()).await?;
+            return Err(error(format!("Dropped '{peer_addr}' for using the account of this validator")));
+        }
         // Add the peer to the gateway.
         self.insert_connected_peer(peer_ip, peer_addr, peer_request.address);
+        self.peer_versions.write().insert(peer_ip, peer_request.version);
 
         Ok((peer_ip, framed))
     }
@@ -1346,9 +1819,9 @@ impl<N: Network> Gateway<N> {
     /// Verifies the given challenge request. Returns a disconnect reason if the request is invalid.
     fn verify_challenge_request(&self, peer_addr: SocketAddr, event: &ChallengeRequest<N>) -> Option<DisconnectReason> {
         // Retrieve the components of the challenge request.
-        let &ChallengeRequest { version, listener_port: _, address, nonce: _ } = event;
+        let &ChallengeRequest { version, listener_port: _, address, nonce } = event;
         // Ensure the event protocol version is not outdated.
-        if version < Event::<N>::VERSION {
+        if version < Event::<N>::MINIMUM_VERSION {
             warn!("{CONTEXT} Gateway is dropping '{peer_addr}' on version {version} (outdated)");
             return Some(DisconnectReason::OutdatedClientVersion);
         }
@@ -1362,20 +1835,49 @@ impl<N: Network> Gateway<N> {
             warn!("{CONTEXT} Gateway is dropping '{peer_addr}' for being already connected ({address})");
             return Some(DisconnectReason::ProtocolViolation);
         }
+        // Ensure the nonce was not used in a recent handshake, as the request would be a replay.
+        if !self.recent_nonces.insert(nonce) {
+            warn!("{CONTEXT} Gateway is dropping '{peer_addr}' (replayed challenge request nonce)");
+            return Some(DisconnectReason::ProtocolViolation);
+        }
         None
     }
 
-    /// Verifies the given challenge response. Returns a disconnect reason if the response is invalid.
+    /// Returns the bytes to sign in the challenge response to the given challenge request of the peer.
+    fn challenge_response_bytes(&self, peer_request: &ChallengeRequest<N>, response_nonce: u64) -> io::Result<Vec<u8>> {
+        ChallengeResponse::signed_bytes(
+            Event::<N>::VERSION,
+            peer_request.version,
+            self.account.address(),
+            peer_request.address,
+            peer_request.nonce,
+            response_nonce,
+        )
+    }
+
+    /// Verifies the given challenge response, which was received the given time after sending the challenge request.
+    /// Returns a disconnect reason if the response is invalid.
     async fn verify_challenge_response(
         &self,
         peer_addr: SocketAddr,
-        peer_address: Address<N>,
+        peer_request: &ChallengeRequest<N>,
         response: ChallengeResponse<N>,
         expected_restrictions_id: Field<N>,
         expected_nonce: u64,
+        elapsed: Duration,
     ) -> Option<DisconnectReason> {
         // Retrieve the components of the challenge response.
         let ChallengeResponse { restrictions_id, signature, nonce } = response;
+        let peer_address = peer_request.address;
+
+        // Ensure the challenge response was received in time.
+        if elapsed > Duration::from_millis(CHALLENGE_RESPONSE_DEADLINE_IN_MS) {
+            warn!(
+                "{CONTEXT} Gateway handshake with '{peer_addr}' failed (late challenge response after {}ms)",
+                elapsed.as_millis()
+            );
+            return Some(DisconnectReason::InvalidChallengeResponse);
+        }
 
         // Verify the restrictions ID.
         if restrictions_id != expected_restrictions_id {
@@ -1387,11 +1889,28 @@ impl<N: Network> Gateway<N> {
             warn!("{CONTEXT} Gateway handshake with '{peer_addr}' failed (cannot deserialize the signature)");
             return Some(DisconnectReason::InvalidChallengeResponse);
         };
+        // Construct the bytes the peer signed, in the format of the lower protocol version of both sides.
+        let Ok(data) = ChallengeResponse::signed_bytes(
+            peer_request.version,
+            Event::<N>::VERSION,
+            peer_address,
+            self.account.address(),
+            expected_nonce,
+            nonce,
+        ) else {
+            warn!("{CONTEXT} Gateway handshake with '{peer_addr}' failed (cannot serialize the signed bytes)");
+            return Some(DisconnectReason::InvalidChallengeResponse);
+        };
         // Verify the signature.
-        if !signature.verify_bytes(&peer_address, &[expected_nonce.to_le_bytes(), nonce.to_le_bytes()].concat()) {
+        if !signature.verify_bytes(&peer_address, &data) {
             warn!("{CONTEXT} Gateway handshake with '{peer_addr}' failed (invalid signature)");
             return Some(DisconnectReason::InvalidChallengeResponse);
         }
+        // Ensure the response nonce was not used in a recent handshake, as the response would be a replay.
+        if !self.recent_nonces.insert(nonce) {
+            warn!("{CONTEXT} Gateway handshake with '{peer_addr}' failed (replayed challenge response nonce)");
+            return Some(DisconnectReason::InvalidChallengeResponse);
+        }
         None
     }
 }
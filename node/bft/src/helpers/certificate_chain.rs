@@ -0,0 +1,542 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{
+    console::{network::Network, types::Field},
+    ledger::{committee::Committee, narwhal::BatchCertificate},
+    prelude::Result,
+};
+
+use indexmap::IndexMap;
+use serde::Serialize;
+use std::{collections::HashSet, fmt};
+
+/// A violation of the consistency of a chain of batch certificates.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+pub enum ChainViolationKind {
+    /// The committee of the round could not be retrieved.
+    MissingCommittee { error: String },
+    /// The author is not a member of the committee.
+    AuthorNotInCommittee { author: String },
+    /// The signature of the author over the batch is invalid.
+    InvalidAuthorSignature,
+    /// The signer is not a member of the committee.
+    SignerNotInCommittee { signer: String },
+    /// The signature of the signer over the batch is invalid.
+    InvalidSignature { signer: String },
+    /// The author and the signers did not reach the quorum threshold.
+    QuorumNotReached,
+    /// The author already has another certificate in the round.
+    DuplicateAuthor { author: String },
+    /// The previous certificate is neither in the chain nor in the ledger, although its round is in the chain.
+    UnknownPreviousCertificate { previous_certificate_id: String },
+    /// The previous certificate could not be read from the ledger.
+    UnreadablePreviousCertificate { previous_certificate_id: String, error: String },
+    /// The previous certificate is not from the previous round.
+    PreviousCertificateWrongRound { previous_certificate_id: String, round: u64 },
+    /// The previous certificates contain several certificates from the same author.
+    DuplicatePreviousAuthor { author: String },
+    /// The authors of the previous certificates did not reach the quorum threshold.
+    PreviousQuorumNotReached,
+}
+
+impl fmt::Display for ChainViolationKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::MissingCommittee { error } => write!(f, "the committee is missing - {error}"),
+            Self::AuthorNotInCommittee { author } => write!(f, "the author {author} is not in the committee"),
+            Self::InvalidAuthorSignature => write!(f, "the signature of the author is invalid"),
+            Self::SignerNotInCommittee { signer } => write!(f, "the signer {signer} is not in the committee"),
+            Self::InvalidSignature { signer } => write!(f, "the signature of {signer} is invalid"),
+            Self::QuorumNotReached => write!(f, "the signatures did not reach the quorum threshold"),
+            Self::DuplicateAuthor { author } => write!(f, "the author {author} has several certificates in the round"),
+            Self::UnknownPreviousCertificate { previous_certificate_id } => {
+                write!(f, "the previous certificate '{previous_certificate_id}' is not in the chain or the ledger")
+            }
+            Self::UnreadablePreviousCertificate { previous_certificate_id, error } => {
+                write!(f, "the previous certificate '{previous_certificate_id}' could not be read - {error}")
+            }
+            Self::PreviousCertificateWrongRound { previous_certificate_id, round } => {
+                write!(f, "the previous certificate '{previous_certificate_id}' is from round {round}")
+            }
+            Self::DuplicatePreviousAuthor { author } => {
+                write!(f, "the previous certificates contain several certificates from {author}")
+            }
+            Self::PreviousQuorumNotReached => {
+                write!(f, "the previous certificates did not reach the quorum threshold")
+            }
+        }
+    }
+}
+
+/// A violation found in a certificate of a chain.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ChainViolation {
+    /// The ID of the offending certificate.
+    pub certificate_id: String,
+    /// The round of the offending certificate.
+    pub round: u64,
+    /// The violation.
+    #[serde(flatten)]
+    pub kind: ChainViolationKind,
+}
+
+impl fmt::Display for ChainViolation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Certificate '{}' (round {}): {}", self.certificate_id, self.round, self.kind)
+    }
+}
+
+/// The report of the verification of a chain of batch certificates.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct ChainReport {
+    /// The number of distinct certificates in the chain.
+    pub num_certificates: usize,
+    /// The first round of the chain, if it is not empty.
+    pub first_round: Option<u64>,
+    /// The last round of the chain, if it is not empty.
+    pub last_round: Option<u64>,
+    /// The violations, in the order of the certificates.
+    pub violations: Vec<ChainViolation>,
+}
+
+impl ChainReport {
+    /// Returns `true` if no violations were found.
+    pub fn is_valid(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+/// Verifies that the given chain of batch certificates is internally consistent, using the given provider of the
+/// committee lookback of each round (i.e. the committee in charge of the round), and the given lookup of the
+/// certificates committed in the ledger, if any.
+///
+/// This method checks the following invariants, and reports each violation with the offending certificate ID:
+/// - The author is a member of the committee, and signed the batch.
+/// - The signers are members of the committee, and signed the batch.
+/// - The author and the signers reached the quorum threshold (N - f).
+/// - The author has a single certificate in the round.
+/// - The previous certificates are in the chain or in the ledger, if their round is in the chain.
+/// - The previous certificates are for the previous round, and contain a unique author.
+/// - The previous certificates reached the quorum threshold (N - f).
+///
+/// Note: The first round of the chain has no previous certificates to check, so the chain is expected
+/// to cover consecutive rounds, such as the subdags of consecutive blocks. As a previous certificate may have been
+/// committed in a block preceding the chain, it is looked up in the ledger before it is reported as unknown.
+/// A certificate listed more than once is verified once.
+pub fn verify_certificate_chain<N: Network>(
+    certificates: &[BatchCertificate<N>],
+    committee_provider: impl Fn(u64) -> Result<Committee<N>>,
+    ledger_certificate: impl Fn(&Field<N>) -> Result<Option<BatchCertificate<N>>>,
+) -> ChainReport {
+    // Index the distinct certificates, in the order of the rounds.
+    let mut chain = certificates.iter().map(|certificate| (certificate.id(), certificate)).collect::<IndexMap<_, _>>();
+    chain.sort_by(|_, a, _, b| a.round().cmp(&b.round()));
+    let first_round = chain.values().map(|certificate| certificate.round()).min();
+    let last_round = chain.values().map(|certificate| certificate.round()).max();
+
+    let mut violations = Vec::new();
+    let mut authors_per_round = HashSet::new();
+    for (certificate_id, certificate) in &chain {
+        let round = certificate.round();
+        let mut report =
+            |kind| violations.push(ChainViolation { certificate_id: certificate_id.to_string(), round, kind });
+
+        // Ensure the author has a single certificate in the round.
+        if !authors_per_round.insert((round, certificate.author())) {
+            report(ChainViolationKind::DuplicateAuthor { author: certificate.author().to_string() });
+        }
+
+        // Retrieve the committee lookback for the round.
+        let committee = match committee_provider(round) {
+            Ok(committee) => committee,
+            Err(error) => {
+                report(ChainViolationKind::MissingCommittee { error: error.to_string() });
+                continue;
+            }
+        };
+        for kind in check_signatures(certificate, &committee) {
+            report(kind);
+        }
+
+        // Check the previous certificates, if their round is in the chain.
+        let previous_round = round.saturating_sub(1);
+        if round == 0 || first_round.map_or(true, |first_round| previous_round < first_round) {
+            continue;
+        }
+        let previous_committee = match committee_provider(previous_round) {
+            Ok(committee) => committee,
+            Err(error) => {
+                report(ChainViolationKind::MissingCommittee { error: error.to_string() });
+                continue;
+            }
+        };
+        for kind in check_previous_certificates(certificate, &chain, &ledger_certificate, &previous_committee) {
+            report(kind);
+        }
+    }
+
+    ChainReport { num_certificates: chain.len(), first_round, last_round, violations }
+}
+
+/// Returns the violations in the signatures of the given certificate.
+fn check_signatures<N: Network>(
+    certificate: &BatchCertificate<N>,
+    committee: &Committee<N>,
+) -> Vec<ChainViolationKind> {
+    let mut violations = Vec::new();
+    let batch_id = certificate.batch_id();
+
+    // Check the author, who signs the batch header.
+    let author = certificate.author();
+    if !committee.is_committee_member(author) {
+        violations.push(ChainViolationKind::AuthorNotInCommittee { author: author.to_string() });
+    }
+    if !certificate.batch_header().signature().verify(&author, &[batch_id]) {
+        violations.push(ChainViolationKind::InvalidAuthorSignature);
+    }
+
+    // Check the signers, only counting the valid signatures from members towards the quorum.
+    let mut signers = HashSet::with_capacity(certificate.signatures().len() + 1);
+    signers.insert(author);
+    for signature in certificate.signatures() {
+        let signer = signature.to_address();
+        if !committee.is_committee_member(signer) {
+            violations.push(ChainViolationKind::SignerNotInCommittee { signer: signer.to_string() });
+        } else if !signature.verify(&signer, &[batch_id]) {
+            violations.push(ChainViolationKind::InvalidSignature { signer: signer.to_string() });
+        } else {
+            signers.insert(signer);
+        }
+    }
+    if !committee.is_quorum_threshold_reached(&signers) {
+        violations.push(ChainViolationKind::QuorumNotReached);
+    }
+    violations
+}
+
+/// Returns the violations in the previous certificates of the given certificate, whose round is in the chain.
+fn check_previous_certificates<N: Network>(
+    certificate: &BatchCertificate<N>,
+    chain: &IndexMap<Field<N>, &BatchCertificate<N>>,
+    ledger_certificate: impl Fn(&Field<N>) -> Result<Option<BatchCertificate<N>>>,
+    previous_committee: &Committee<N>,
+) -> Vec<ChainViolationKind> {
+    let mut violations = Vec::new();
+    let previous_round = certificate.round().saturating_sub(1);
+
+    let mut previous_authors = HashSet::with_capacity(certificate.previous_certificate_ids().len());
+    let mut is_complete = true;
+    for previous_certificate_id in certificate.previous_certificate_ids() {
+        // Resolve the round and the author of the previous certificate, from the chain or else from the ledger.
+        let resolved = match chain.get(previous_certificate_id) {
+            Some(previous_certificate) => Ok(Some((previous_certificate.round(), previous_certificate.author()))),
+            None => ledger_certificate(previous_certificate_id)
+                .map(|previous_certificate| previous_certificate.map(|c| (c.round(), c.author()))),
+        };
+        let (round, author) = match resolved {
+            Ok(Some(resolved)) => resolved,
+            Ok(None) => {
+                violations.push(ChainViolationKind::UnknownPreviousCertificate {
+                    previous_certificate_id: previous_certificate_id.to_string(),
+                });
+                is_complete = false;
+                continue;
+            }
+            Err(error) => {
+                violations.push(ChainViolationKind::UnreadablePreviousCertificate {
+                    previous_certificate_id: previous_certificate_id.to_string(),
+                    error: error.to_string(),
+                });
+                is_complete = false;
+                continue;
+            }
+        };
+        if round != previous_round {
+            violations.push(ChainViolationKind::PreviousCertificateWrongRound {
+                previous_certificate_id: previous_certificate_id.to_string(),
+                round,
+            });
+            is_complete = false;
+            continue;
+        }
+        if !previous_authors.insert(author) {
+            violations.push(ChainViolationKind::DuplicatePreviousAuthor { author: author.to_string() });
+        }
+    }
+    // Note: The quorum of the previous authors is only meaningful if every previous certificate was resolved.
+    if is_complete && !previous_committee.is_quorum_threshold_reached(&previous_authors) {
+        violations.push(ChainViolationKind::PreviousQuorumNotReached);
+    }
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ledger_service::{CoreLedgerService, LedgerService};
+    use snarkvm::{
+        console::{
+            account::{Address, PrivateKey},
+            prelude::Uniform,
+        },
+        ledger::{
+            authority::Authority,
+            committee::MIN_VALIDATOR_STAKE,
+            narwhal::{BatchHeader, Subdag},
+            store::{ConsensusStore, helpers::memory::ConsensusMemory},
+        },
+        prelude::{Ledger, MainnetV0, TestRng, VM},
+    };
+
+    use aleo_std::StorageMode;
+    use indexmap::IndexSet;
+    use rand::Rng;
+    use std::collections::BTreeMap;
+
+    type CurrentNetwork = MainnetV0;
+
+    /// The number of rounds in the sample chain.
+    const NUM_ROUNDS: u64 = 4;
+
+    /// Returns a certificate from the validator at the given index, signed by the given number of other validators.
+    fn sample_certificate(
+        private_keys: &[PrivateKey<CurrentNetwork>],
+        committee: &Committee<CurrentNetwork>,
+        index: usize,
+        round: u64,
+        previous_certificate_ids: IndexSet<Field<CurrentNetwork>>,
+        num_signers: usize,
+        rng: &mut TestRng,
+    ) -> BatchCertificate<CurrentNetwork> {
+        let timestamp = time::OffsetDateTime::now_utc().unix_timestamp();
+        let batch_header = BatchHeader::new(
+            &private_keys[index],
+            round,
+            timestamp,
+            committee.id(),
+            Default::default(),
+            previous_certificate_ids,
+            rng,
+        )
+        .unwrap();
+        let signatures = private_keys
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != index)
+            .take(num_signers)
+            .map(|(_, private_key)| private_key.sign(&[batch_header.batch_id()], rng).unwrap())
+            .collect();
+        BatchCertificate::from(batch_header, signatures).unwrap()
+    }
+
+    /// Returns a valid chain of certificates for rounds 1 to `NUM_ROUNDS`, from a devnet committee of 4 validators.
+    fn sample_chain(
+        rng: &mut TestRng,
+    ) -> (Vec<PrivateKey<CurrentNetwork>>, Committee<CurrentNetwork>, Vec<BatchCertificate<CurrentNetwork>>) {
+        let private_keys = (0..4).map(|_| PrivateKey::new(rng).unwrap()).collect::<Vec<_>>();
+        let members = private_keys
+            .iter()
+            .map(|private_key| (Address::try_from(private_key).unwrap(), (MIN_VALIDATOR_STAKE, false, 0)))
+            .collect();
+        let committee = Committee::new(0, members).unwrap();
+
+        let mut chain = Vec::new();
+        let mut previous_certificate_ids = IndexSet::new();
+        for round in 1..=NUM_ROUNDS {
+            let certificates = (0..private_keys.len())
+                .map(|index| {
+                    sample_certificate(
+                        &private_keys,
+                        &committee,
+                        index,
+                        round,
+                        previous_certificate_ids.clone(),
+                        3,
+                        rng,
+                    )
+                })
+                .collect::<Vec<_>>();
+            previous_certificate_ids = certificates.iter().map(|certificate| certificate.id()).collect();
+            chain.extend(certificates);
+        }
+        (private_keys, committee, chain)
+    }
+
+    /// Returns the violations of the given chain, as the offending certificate IDs with their kinds.
+    fn violations(
+        chain: &[BatchCertificate<CurrentNetwork>],
+        committee: &Committee<CurrentNetwork>,
+    ) -> Vec<(String, ChainViolationKind)> {
+        let report = verify_certificate_chain(chain, |_| Ok(committee.clone()), |_| Ok(None));
+        report.violations.into_iter().map(|violation| (violation.certificate_id, violation.kind)).collect()
+    }
+
+    #[test]
+    fn test_valid_chain() {
+        let rng = &mut TestRng::default();
+        let (_, committee, chain) = sample_chain(rng);
+
+        let report = verify_certificate_chain(&chain, |_| Ok(committee.clone()), |_| Ok(None));
+        assert!(report.is_valid(), "{:?}", report.violations);
+        assert_eq!(report.num_certificates, chain.len());
+        assert_eq!((report.first_round, report.last_round), (Some(1), Some(NUM_ROUNDS)));
+
+        // Ensure a certificate listed twice is verified once.
+        let mut duplicated = chain.clone();
+        duplicated.push(chain[0].clone());
+        assert_eq!(verify_certificate_chain(&duplicated, |_| Ok(committee.clone()), |_| Ok(None)), report);
+    }
+
+    #[test]
+    fn test_forged_signature() {
+        let rng = &mut TestRng::default();
+        let (private_keys, committee, mut chain) = sample_chain(rng);
+
+        // Replace a signature of the last certificate with a signature over another batch.
+        let certificate = chain.pop().unwrap();
+        let mut signatures = certificate.signatures().cloned().collect::<IndexSet<_>>();
+        let forged = signatures.pop().unwrap();
+        let signer = forged.to_address();
+        let private_key = private_keys.iter().find(|key| Address::try_from(*key).unwrap() == signer).unwrap();
+        signatures.insert(private_key.sign(&[Field::rand(rng)], rng).unwrap());
+        let forged_certificate =
+            BatchCertificate::from_unchecked(certificate.batch_header().clone(), signatures).unwrap();
+        chain.push(forged_certificate.clone());
+
+        // Ensure only the forged signature is reported, as the remaining signatures still reach the quorum.
+        assert_eq!(violations(&chain, &committee), [(
+            forged_certificate.id().to_string(),
+            ChainViolationKind::InvalidSignature { signer: signer.to_string() }
+        )]);
+    }
+
+    #[test]
+    fn test_insufficient_quorum() {
+        let rng = &mut TestRng::default();
+        let (private_keys, committee, mut chain) = sample_chain(rng);
+
+        // Replace the last certificate with one signed by a single other validator.
+        let certificate = chain.pop().unwrap();
+        let previous_certificate_ids = certificate.previous_certificate_ids().clone();
+        let weak = sample_certificate(&private_keys, &committee, 3, NUM_ROUNDS, previous_certificate_ids, 1, rng);
+        chain.push(weak.clone());
+
+        assert_eq!(violations(&chain, &committee), [(weak.id().to_string(), ChainViolationKind::QuorumNotReached)]);
+    }
+
+    #[test]
+    fn test_broken_parent_link() {
+        let rng = &mut TestRng::default();
+        let (private_keys, committee, mut chain) = sample_chain(rng);
+
+        // Replace the last certificate with one referencing an unknown certificate, and a certificate of round 1.
+        let certificate = chain.pop().unwrap();
+        let unknown_id = Field::rand(rng);
+        let mut previous_certificate_ids = certificate.previous_certificate_ids().clone();
+        previous_certificate_ids.pop();
+        previous_certificate_ids.insert(unknown_id);
+        previous_certificate_ids.insert(chain[0].id());
+        let broken = sample_certificate(&private_keys, &committee, 3, NUM_ROUNDS, previous_certificate_ids, 3, rng);
+        chain.push(broken.clone());
+
+        assert_eq!(violations(&chain, &committee), [
+            (broken.id().to_string(), ChainViolationKind::UnknownPreviousCertificate {
+                previous_certificate_id: unknown_id.to_string()
+            }),
+            (broken.id().to_string(), ChainViolationKind::PreviousCertificateWrongRound {
+                previous_certificate_id: chain[0].id().to_string(),
+                round: 1
+            }),
+        ]);
+    }
+
+    #[test]
+    fn test_chain_of_ledger_blocks() {
+        let rng = &mut TestRng::default();
+
+        // Initialize a devnet ledger, sampling the private keys of its genesis committee from the same seed.
+        let private_key = PrivateKey::<CurrentNetwork>::new(rng).unwrap();
+        let seed: u64 = rng.gen();
+        let store = ConsensusStore::<CurrentNetwork, ConsensusMemory<CurrentNetwork>>::open(None).unwrap();
+        let genesis = VM::from(store).unwrap().genesis_beacon(&private_key, &mut TestRng::from_seed(seed)).unwrap();
+        let genesis_rng = &mut TestRng::from_seed(seed);
+        let mut private_keys = vec![private_key];
+        private_keys.extend((0..3).map(|_| PrivateKey::new(genesis_rng).unwrap()));
+        let core_ledger =
+            Ledger::<CurrentNetwork, ConsensusMemory<CurrentNetwork>>::load(genesis, StorageMode::Production).unwrap();
+        let ledger = CoreLedgerService::new(core_ledger.clone(), Default::default());
+        let committee = ledger.current_committee().unwrap();
+
+        // Sample the certificates of rounds 1 to 6, each referencing every certificate of the previous round.
+        let mut rounds = Vec::new();
+        let mut previous_certificate_ids = IndexSet::new();
+        for round in 1..=6 {
+            let certificates = (0..private_keys.len())
+                .map(|index| {
+                    let previous_certificate_ids = previous_certificate_ids.clone();
+                    sample_certificate(&private_keys, &committee, index, round, previous_certificate_ids, 3, rng)
+                })
+                .collect::<IndexSet<_>>();
+            previous_certificate_ids = certificates.iter().map(|certificate| certificate.id()).collect();
+            rounds.push(certificates);
+        }
+        let certificates = |round: u64| rounds[round as usize - 1].clone();
+        let leader = |round: u64| {
+            let leader = committee.get_leader(round).unwrap();
+            certificates(round).into_iter().find(|certificate| certificate.author() == leader).unwrap()
+        };
+
+        // Commit a block for each even round, whose subdag holds the certificates not committed by the previous block.
+        let mut blocks = Vec::new();
+        for leader_round in [2, 4, 6] {
+            let mut subdag = BTreeMap::new();
+            subdag.insert(leader_round, IndexSet::from([leader(leader_round)]));
+            subdag.insert(leader_round - 1, certificates(leader_round - 1));
+            if leader_round > 2 {
+                let mut previous = certificates(leader_round - 2);
+                previous.shift_remove(&leader(leader_round - 2));
+                subdag.insert(leader_round - 2, previous);
+            }
+            let subdag = Subdag::from(subdag).unwrap();
+            let block = ledger.prepare_advance_to_next_quorum_block(subdag, Default::default()).unwrap();
+            ledger.advance_to_next_block(&block).unwrap();
+            blocks.push(block);
+        }
+
+        // Extract the chain from the last two blocks, which reference the leader certificate of the first block.
+        let chain = blocks[1..]
+            .iter()
+            .flat_map(|block| match block.authority() {
+                Authority::Quorum(subdag) => subdag.values().flatten().cloned().collect(),
+                Authority::Beacon(_) => vec![],
+            })
+            .collect::<Vec<_>>();
+        let committee_provider = |round| ledger.get_committee_lookback_for_round(round);
+
+        // Without the ledger, the leader certificate of the first block is unknown to each certificate of round 3.
+        let report = verify_certificate_chain(&chain, committee_provider, |_| Ok(None));
+        let unknown =
+            ChainViolationKind::UnknownPreviousCertificate { previous_certificate_id: leader(2).id().to_string() };
+        assert_eq!(report.violations.len(), 4, "{:?}", report.violations);
+        assert!(report.violations.iter().all(|violation| violation.round == 3 && violation.kind == unknown));
+
+        // Ensure the chain is valid once the previous certificates are looked up in the ledger.
+        let report = verify_certificate_chain(&chain, committee_provider, |id| core_ledger.get_batch_certificate(id));
+        assert!(report.is_valid(), "{:?}", report.violations);
+        assert_eq!((report.first_round, report.last_round), (Some(2), Some(6)));
+    }
+}
@@ -18,6 +18,7 @@ use crate::events::{
     BatchSignature,
     CertificateRequest,
     CertificateResponse,
+    TransmissionChunkRequest,
     TransmissionRequest,
     TransmissionResponse,
 };
@@ -206,6 +207,7 @@ pub struct WorkerSender<N: Network> {
     pub tx_worker_ping: mpsc::Sender<(SocketAddr, TransmissionID<N>)>,
     pub tx_transmission_request: mpsc::Sender<(SocketAddr, TransmissionRequest<N>)>,
     pub tx_transmission_response: mpsc::Sender<(SocketAddr, TransmissionResponse<N>)>,
+    pub tx_transmission_chunk_request: mpsc::Sender<(SocketAddr, TransmissionChunkRequest<N>)>,
 }
 
 #[derive(Debug)]
@@ -213,6 +215,7 @@ pub struct WorkerReceiver<N: Network> {
     pub rx_worker_ping: mpsc::Receiver<(SocketAddr, TransmissionID<N>)>,
     pub rx_transmission_request: mpsc::Receiver<(SocketAddr, TransmissionRequest<N>)>,
     pub rx_transmission_response: mpsc::Receiver<(SocketAddr, TransmissionResponse<N>)>,
+    pub rx_transmission_chunk_request: mpsc::Receiver<(SocketAddr, TransmissionChunkRequest<N>)>,
 }
 
 /// Initializes the worker channels.
@@ -220,9 +223,20 @@ pub fn init_worker_channels<N: Network>() -> (WorkerSender<N>, WorkerReceiver<N>
     let (tx_worker_ping, rx_worker_ping) = mpsc::channel(MAX_CHANNEL_SIZE);
     let (tx_transmission_request, rx_transmission_request) = mpsc::channel(MAX_CHANNEL_SIZE);
     let (tx_transmission_response, rx_transmission_response) = mpsc::channel(MAX_CHANNEL_SIZE);
+    let (tx_transmission_chunk_request, rx_transmission_chunk_request) = mpsc::channel(MAX_CHANNEL_SIZE);
 
-    let sender = WorkerSender { tx_worker_ping, tx_transmission_request, tx_transmission_response };
-    let receiver = WorkerReceiver { rx_worker_ping, rx_transmission_request, rx_transmission_response };
+    let sender = WorkerSender {
+        tx_worker_ping,
+        tx_transmission_request,
+        tx_transmission_response,
+        tx_transmission_chunk_request,
+    };
+    let receiver = WorkerReceiver {
+        rx_worker_ping,
+        rx_transmission_request,
+        rx_transmission_response,
+        rx_transmission_chunk_request,
+    };
 
     (sender, receiver)
 }
@@ -0,0 +1,428 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    events::{Event, EventCodec},
+    helpers::now,
+};
+use snarkvm::prelude::{Address, Network};
+
+use ::bytes::BytesMut;
+use indexmap::IndexMap;
+use parking_lot::{Mutex, RwLock};
+use std::{
+    collections::HashMap,
+    io,
+    marker::PhantomData,
+    net::SocketAddr,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+use tokio_util::codec::{Decoder, Encoder};
+
+/// The maximum number of validators whose connection history is retained.
+pub const MAX_TRACKED_VALIDATORS: usize = 1_000;
+
+/// The traffic of a single event type over a connection.
+#[derive(Default)]
+struct EventTraffic {
+    messages_sent: AtomicU64,
+    bytes_sent: AtomicU64,
+    messages_received: AtomicU64,
+    bytes_received: AtomicU64,
+}
+
+/// The statistics of a single connection with a validator.
+///
+/// The counters are updated by the codecs of the connection, so they are plain relaxed atomics.
+pub struct ConnectionStats<N: Network> {
+    /// The UNIX timestamp at which the connection was established.
+    established: i64,
+    /// The traffic of each event type, indexed by event ID.
+    traffic: Vec<EventTraffic>,
+    /// PhantomData.
+    _phantom: PhantomData<N>,
+}
+
+impl<N: Network> Default for ConnectionStats<N> {
+    /// Initializes the statistics of a connection established now.
+    fn default() -> Self {
+        Self::new(now())
+    }
+}
+
+impl<N: Network> ConnectionStats<N> {
+    /// Initializes the statistics of a connection established at the given UNIX timestamp.
+    pub fn new(established: i64) -> Self {
+        Self {
+            established,
+            traffic: (0..Event::<N>::NAMES.len()).map(|_| Default::default()).collect(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns the UNIX timestamp at which the connection was established.
+    pub const fn established(&self) -> i64 {
+        self.established
+    }
+
+    /// Records an event with the given ID and encoded size, sent over the connection.
+    pub fn record_sent(&self, event_id: u16, num_bytes: usize) {
+        if let Some(traffic) = self.traffic.get(event_id as usize) {
+            traffic.messages_sent.fetch_add(1, Ordering::Relaxed);
+            traffic.bytes_sent.fetch_add(num_bytes as u64, Ordering::Relaxed);
+        }
+    }
+
+    /// Records an event with the given ID and encoded size, received over the connection.
+    pub fn record_received(&self, event_id: u16, num_bytes: usize) {
+        if let Some(traffic) = self.traffic.get(event_id as usize) {
+            traffic.messages_received.fetch_add(1, Ordering::Relaxed);
+            traffic.bytes_received.fetch_add(num_bytes as u64, Ordering::Relaxed);
+        }
+    }
+
+    /// Returns a snapshot of the traffic of each event type that was sent or received over the connection.
+    pub fn traffic(&self) -> Vec<EventTrafficStats> {
+        self.traffic
+            .iter()
+            .zip(Event::<N>::NAMES)
+            .map(|(traffic, name)| EventTrafficStats {
+                name,
+                messages_sent: traffic.messages_sent.load(Ordering::Relaxed),
+                bytes_sent: traffic.bytes_sent.load(Ordering::Relaxed),
+                messages_received: traffic.messages_received.load(Ordering::Relaxed),
+                bytes_received: traffic.bytes_received.load(Ordering::Relaxed),
+            })
+            .filter(|stats| stats.messages_sent > 0 || stats.messages_received > 0)
+            .collect()
+    }
+}
+
+/// A snapshot of the traffic of a single event type over a connection.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EventTrafficStats {
+    /// The event name.
+    pub name: &'static str,
+    /// The number of events sent.
+    pub messages_sent: u64,
+    /// The number of bytes sent.
+    pub bytes_sent: u64,
+    /// The number of events received.
+    pub messages_received: u64,
+    /// The number of bytes received.
+    pub bytes_received: u64,
+}
+
+/// A snapshot of a connection with a validator.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConnectionSnapshot<N: Network> {
+    /// The listener IP of the validator.
+    pub peer_ip: SocketAddr,
+    /// The address of the validator.
+    pub address: Address<N>,
+    /// The UNIX timestamp at which the connection was established.
+    pub established: i64,
+    /// The number of seconds since the connection was established.
+    pub uptime: i64,
+    /// The traffic of each event type that was sent or received over the connection.
+    pub traffic: Vec<EventTrafficStats>,
+    /// The last error with the validator and its UNIX timestamp, if any, from this or a prior connection.
+    pub last_error: Option<(String, i64)>,
+    /// The number of times the validator has reconnected.
+    pub num_reconnects: u32,
+    /// The number of batch proposals of the validator whose transmissions it never served.
+    pub num_withheld_proposals: u32,
+    /// Whether the batch proposals of the validator are declined, as it withholds their transmissions.
+    pub is_declined_proposer: bool,
+}
+
+impl<N: Network> ConnectionSnapshot<N> {
+    /// Returns the total number of events sent and received, and bytes sent and received.
+    pub fn totals(&self) -> (u64, u64, u64, u64) {
+        self.traffic.iter().fold((0, 0, 0, 0), |(messages_sent, bytes_sent, messages_received, bytes_received), t| {
+            (
+                messages_sent + t.messages_sent,
+                bytes_sent + t.bytes_sent,
+                messages_received + t.messages_received,
+                bytes_received + t.bytes_received,
+            )
+        })
+    }
+}
+
+/// The history of a validator, which outlives its connections.
+#[derive(Clone, Debug, Default)]
+struct ValidatorRecord {
+    /// The number of connections established with the validator.
+    num_connections: u32,
+    /// The last error with the validator and its UNIX timestamp, if any.
+    last_error: Option<(String, i64)>,
+    /// The number of batch proposals of the validator whose transmissions it never served.
+    num_withheld_proposals: u32,
+    /// Whether the batch proposals of the validator are declined, as it withholds their transmissions.
+    is_declined_proposer: bool,
+}
+
+/// The registry of the connections of the gateway.
+pub struct ConnectionRegistry<N: Network> {
+    /// The map of connected peer IPs to the validator address and the statistics of the connection.
+    connections: RwLock<HashMap<SocketAddr, (Address<N>, Arc<ConnectionStats<N>>)>>,
+    /// The history of each validator, in the order they were last connected.
+    validators: Mutex<IndexMap<Address<N>, ValidatorRecord>>,
+}
+
+impl<N: Network> Default for ConnectionRegistry<N> {
+    /// Initializes an empty registry.
+    fn default() -> Self {
+        Self { connections: Default::default(), validators: Default::default() }
+    }
+}
+
+impl<N: Network> ConnectionRegistry<N> {
+    /// Registers a connection with the given validator, established at the given UNIX timestamp.
+    pub fn insert(&self, peer_ip: SocketAddr, address: Address<N>, now: i64) -> Arc<ConnectionStats<N>> {
+        let stats = Arc::new(ConnectionStats::new(now));
+        // Update the history of the validator, moving it to the back, so that the least recently connected
+        // validators are evicted first.
+        {
+            let mut validators = self.validators.lock();
+            let mut record = validators.shift_remove(&address).unwrap_or_default();
+            record.num_connections = record.num_connections.saturating_add(1);
+            validators.insert(address, record);
+            while validators.len() > MAX_TRACKED_VALIDATORS {
+                validators.shift_remove_index(0);
+            }
+        }
+        self.connections.write().insert(peer_ip, (address, stats.clone()));
+        stats
+    }
+
+    /// Returns the statistics of the connection with the given peer IP, if it is registered.
+    pub fn get(&self, peer_ip: &SocketAddr) -> Option<Arc<ConnectionStats<N>>> {
+        self.connections.read().get(peer_ip).map(|(_, stats)| stats.clone())
+    }
+
+    /// Removes the connection with the given peer IP, retaining the history of the validator.
+    pub fn remove(&self, peer_ip: &SocketAddr) {
+        self.connections.write().remove(peer_ip);
+    }
+
+    /// Records the given error with the validator at the given peer IP, at the given UNIX timestamp.
+    pub fn record_error(&self, peer_ip: &SocketAddr, error: String, now: i64) {
+        let Some(address) = self.connections.read().get(peer_ip).map(|(address, _)| *address) else {
+            return;
+        };
+        if let Some(record) = self.validators.lock().get_mut(&address) {
+            record.last_error = Some((error, now));
+        }
+    }
+
+    /// Records a batch proposal of the given validator, whose transmissions it never served.
+    pub fn record_withheld_proposal(&self, address: &Address<N>) {
+        if let Some(record) = self.validators.lock().get_mut(address) {
+            record.num_withheld_proposals = record.num_withheld_proposals.saturating_add(1);
+        }
+    }
+
+    /// Sets whether the batch proposals of the given validator are declined, as it withholds their transmissions.
+    pub fn set_declined_proposer(&self, address: &Address<N>, is_declined: bool) {
+        if let Some(record) = self.validators.lock().get_mut(address) {
+            record.is_declined_proposer = is_declined;
+        }
+    }
+
+    /// Returns a snapshot of the registered connections, at the given UNIX timestamp.
+    pub fn snapshot(&self, now: i64) -> Vec<ConnectionSnapshot<N>> {
+        // Collect the connections first, so that the two locks are never held together.
+        let connections: Vec<_> = self
+            .connections
+            .read()
+            .iter()
+            .map(|(peer_ip, (address, stats))| (*peer_ip, *address, stats.clone()))
+            .collect();
+        let validators = self.validators.lock();
+        connections
+            .into_iter()
+            .map(|(peer_ip, address, stats)| {
+                let record = validators.get(&address).cloned().unwrap_or_default();
+                ConnectionSnapshot {
+                    peer_ip,
+                    address,
+                    established: stats.established(),
+                    uptime: now.saturating_sub(stats.established()),
+                    traffic: stats.traffic(),
+                    last_error: record.last_error,
+                    num_reconnects: record.num_connections.saturating_sub(1),
+                    num_withheld_proposals: record.num_withheld_proposals,
+                    is_declined_proposer: record.is_declined_proposer,
+                }
+            })
+            .collect()
+    }
+}
+
+/// The event codec of a connection, which records the traffic in the statistics of the connection.
+pub struct MeteredCodec<N: Network> {
+    /// The event codec.
+    codec: EventCodec<N>,
+    /// The statistics of the connection.
+    stats: Arc<ConnectionStats<N>>,
+    /// The number of bytes consumed by the decoder towards the next event.
+    num_pending_bytes: usize,
+}
+
+impl<N: Network> MeteredCodec<N> {
+    /// Initializes a new codec, recording the traffic in the given statistics.
+    pub fn new(stats: Arc<ConnectionStats<N>>) -> Self {
+        Self { codec: Default::default(), stats, num_pending_bytes: 0 }
+    }
+}
+
+impl<N: Network> Encoder<Event<N>> for MeteredCodec<N> {
+    type Error = io::Error;
+
+    fn encode(&mut self, event: Event<N>, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        let (event_id, num_bytes_before) = (event.id(), dst.len());
+        self.codec.encode(event, dst)?;
+        self.stats.record_sent(event_id, dst.len().saturating_sub(num_bytes_before));
+        Ok(())
+    }
+}
+
+impl<N: Network> Decoder for MeteredCodec<N> {
+    type Error = io::Error;
+    type Item = Event<N>;
+
+    fn decode(&mut self, source: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        // The frame may be consumed across several calls, i.e. the length prefix first and then the payload.
+        let num_bytes_before = source.len();
+        let event = self.codec.decode(source)?;
+        self.num_pending_bytes += num_bytes_before.saturating_sub(source.len());
+        if let Some(event) = &event {
+            self.stats.record_received(event.id(), std::mem::take(&mut self.num_pending_bytes));
+        }
+        Ok(event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{DisconnectReason, ValidatorsRequest};
+    use snarkvm::prelude::{MainnetV0, TestRng};
+
+    use rand::Rng;
+
+    type CurrentNetwork = MainnetV0;
+
+    /// Returns the sample peer IP with the given index.
+    fn sample_peer_ip(index: u16) -> SocketAddr {
+        SocketAddr::from(([127, 0, 0, 1], 5000 + index))
+    }
+
+    #[test]
+    fn test_metered_codec() {
+        let stats = Arc::new(ConnectionStats::<CurrentNetwork>::new(0));
+        let mut codec = MeteredCodec::new(stats.clone());
+
+        // Encode a few events.
+        let mut buffer = BytesMut::new();
+        let events: Vec<Event<CurrentNetwork>> = vec![
+            Event::ValidatorsRequest(ValidatorsRequest::default()),
+            Event::ValidatorsRequest(ValidatorsRequest::default()),
+            DisconnectReason::NoReasonGiven.into(),
+        ];
+        for event in events {
+            codec.encode(event, &mut buffer).unwrap();
+        }
+        let num_bytes = buffer.len() as u64;
+
+        // Decode the events, feeding the bytes one at a time.
+        let mut source = BytesMut::new();
+        let mut num_decoded = 0;
+        for byte in buffer.iter() {
+            source.extend_from_slice(&[*byte]);
+            while codec.decode(&mut source).unwrap().is_some() {
+                num_decoded += 1;
+            }
+        }
+        assert_eq!(num_decoded, 3);
+
+        // Ensure the traffic is recorded by event type, and the bytes add up on both sides.
+        let traffic = stats.traffic();
+        assert_eq!(traffic.len(), 2);
+        assert_eq!(traffic[0].name, "Disconnect");
+        assert_eq!((traffic[0].messages_sent, traffic[0].messages_received), (1, 1));
+        assert_eq!(traffic[1].name, "ValidatorsRequest");
+        assert_eq!((traffic[1].messages_sent, traffic[1].messages_received), (2, 2));
+        let bytes_sent: u64 = traffic.iter().map(|t| t.bytes_sent).sum();
+        let bytes_received: u64 = traffic.iter().map(|t| t.bytes_received).sum();
+        assert_eq!((bytes_sent, bytes_received), (num_bytes, num_bytes));
+    }
+
+    #[test]
+    fn test_registry() {
+        let rng = &mut TestRng::default();
+        let registry = ConnectionRegistry::<CurrentNetwork>::default();
+        let (peer_ip, address) = (sample_peer_ip(0), Address::new(rng.gen()));
+
+        // Ensure a new connection has no reconnects nor errors.
+        let stats = registry.insert(peer_ip, address, 10);
+        stats.record_sent(0, 100);
+        let snapshot = registry.snapshot(15);
+        assert_eq!(snapshot.len(), 1);
+        assert_eq!((snapshot[0].established, snapshot[0].uptime), (10, 5));
+        assert_eq!((snapshot[0].num_reconnects, snapshot[0].last_error.clone()), (0, None));
+        assert_eq!(snapshot[0].totals(), (1, 100, 0, 0));
+
+        // Ensure the history of the validator is retained across connections, while the traffic is not.
+        registry.record_error(&peer_ip, "oops".to_string(), 20);
+        registry.remove(&peer_ip);
+        assert!(registry.snapshot(25).is_empty());
+        registry.insert(peer_ip, address, 30);
+        let snapshot = registry.snapshot(30);
+        assert_eq!(snapshot[0].num_reconnects, 1);
+        assert_eq!(snapshot[0].last_error, Some(("oops".to_string(), 20)));
+        assert_eq!(snapshot[0].totals(), (0, 0, 0, 0));
+
+        // Ensure errors for unknown peers are ignored.
+        registry.record_error(&sample_peer_ip(1), "unknown".to_string(), 40);
+        assert_eq!(registry.snapshot(40)[0].last_error, Some(("oops".to_string(), 20)));
+
+        // Ensure the withheld proposals and the declined status of the validator are reported.
+        registry.record_withheld_proposal(&address);
+        registry.record_withheld_proposal(&address);
+        registry.set_declined_proposer(&address, true);
+        let snapshot = registry.snapshot(50);
+        assert_eq!((snapshot[0].num_withheld_proposals, snapshot[0].is_declined_proposer), (2, true));
+        registry.set_declined_proposer(&address, false);
+        assert!(!registry.snapshot(50)[0].is_declined_proposer);
+    }
+
+    #[test]
+    fn test_tracked_validators_are_bounded() {
+        let rng = &mut TestRng::default();
+        let registry = ConnectionRegistry::<CurrentNetwork>::default();
+        for i in 0..(MAX_TRACKED_VALIDATORS as u16 + 10) {
+            let peer_ip = sample_peer_ip(i);
+            registry.insert(peer_ip, Address::new(rng.gen()), 0);
+            registry.remove(&peer_ip);
+        }
+        assert_eq!(registry.validators.lock().len(), MAX_TRACKED_VALIDATORS);
+    }
+}
@@ -0,0 +1,304 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::MEMORY_POOL_PORT;
+
+use aleo_std::{StorageMode, aleo_ledger_dir};
+use anyhow::{Result, bail, ensure};
+use std::{
+    net::SocketAddr,
+    path::{Path, PathBuf},
+};
+
+/// The port on which the node listens for incoming connections.
+pub const NODE_PORT: u16 = 4130; // port
+/// The port on which the REST server listens for incoming requests.
+pub const REST_PORT: u16 = 3030; // port
+/// The port on which the metrics exporter listens for incoming requests.
+pub const METRICS_PORT: u16 = 9000; // port
+/// The number of development IDs, such that the ports derived for any two IDs never overlap.
+///
+/// Note: The REST ports (`3030..`) are followed by the node ports (`4130..`), the memory pool ports (`5000..`),
+/// and the metrics ports (`9000..`), so the tightest gap bounds the number of development IDs.
+pub const MAX_DEV_INSTANCES: u16 = MEMORY_POOL_PORT - NODE_PORT;
+
+/// Returns the storage mode of the given development ID, if any.
+///
+/// Note: The consensus store and the BFT storage both open their maps in the ledger directory of this storage mode.
+pub fn dev_storage_mode(dev: Option<u16>) -> StorageMode {
+    match dev {
+        Some(id) => StorageMode::Development(id),
+        None => StorageMode::Production,
+    }
+}
+
+/// The configurations derived for a node in development mode, from its development ID.
+///
+/// The derivation is the single source of truth for development mode, so that several development nodes
+/// on one machine never share a port, a ledger, or a log file:
+///   - node port: `4130 + id`
+///   - memory pool (BFT) port: `5000 + id`
+///   - REST port: `3030 + id`
+///   - metrics port: `9000 + id`
+///   - storage: the development ledger directory of `id`, shared by the consensus store and the BFT storage
+///   - log file: the given log file, suffixed with `dev-{id}`
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DevInstance {
+    /// The development ID.
+    id: u16,
+}
+
+impl DevInstance {
+    /// Initializes the development instance of the given ID.
+    pub fn new(id: u16) -> Result<Self> {
+        ensure!(id < MAX_DEV_INSTANCES, "The development ID must be less than {MAX_DEV_INSTANCES}, found {id}");
+        Ok(Self { id })
+    }
+
+    /// Returns the development ID.
+    pub const fn id(&self) -> u16 {
+        self.id
+    }
+
+    /// Returns the port on which the node listens for incoming connections.
+    pub const fn node_port(&self) -> u16 {
+        NODE_PORT + self.id
+    }
+
+    /// Returns the port on which the memory pool listens for incoming connections.
+    pub const fn bft_port(&self) -> u16 {
+        MEMORY_POOL_PORT + self.id
+    }
+
+    /// Returns the port on which the REST server listens for incoming requests.
+    pub const fn rest_port(&self) -> u16 {
+        REST_PORT + self.id
+    }
+
+    /// Returns the port on which the metrics exporter listens for incoming requests.
+    pub const fn metrics_port(&self) -> u16 {
+        METRICS_PORT + self.id
+    }
+
+    /// Returns the address on which the node listens for incoming connections.
+    pub fn node_ip(&self) -> SocketAddr {
+        SocketAddr::from(([0, 0, 0, 0], self.node_port()))
+    }
+
+    /// Returns the address on which the other development nodes connect to this node.
+    pub fn peer_ip(&self) -> SocketAddr {
+        SocketAddr::from(([127, 0, 0, 1], self.node_port()))
+    }
+
+    /// Returns the address on which the memory pool listens for incoming connections.
+    pub fn bft_ip(&self) -> SocketAddr {
+        SocketAddr::from(([127, 0, 0, 1], self.bft_port()))
+    }
+
+    /// Returns the address on which the REST server listens for incoming requests.
+    pub fn rest_ip(&self) -> SocketAddr {
+        SocketAddr::from(([0, 0, 0, 0], self.rest_port()))
+    }
+
+    /// Returns the address on which the metrics exporter listens for incoming requests.
+    pub fn metrics_ip(&self) -> SocketAddr {
+        SocketAddr::from(([0, 0, 0, 0], self.metrics_port()))
+    }
+
+    /// Returns the storage mode, shared by the consensus store and the BFT storage.
+    pub fn storage_mode(&self) -> StorageMode {
+        dev_storage_mode(Some(self.id))
+    }
+
+    /// Returns the ledger directory on the given network, shared by the consensus store and the BFT storage.
+    pub fn ledger_dir(&self, network: u16) -> PathBuf {
+        aleo_ledger_dir(network, self.storage_mode())
+    }
+
+    /// Returns the suffix of the log file.
+    pub fn log_file_suffix(&self) -> String {
+        format!("dev-{}", self.id)
+    }
+
+    /// Returns the given log file, with the suffix of this instance, e.g. `snarkos.log` becomes `snarkos-dev-1.log`.
+    pub fn log_file(&self, path: &Path) -> PathBuf {
+        let stem = path.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+        let mut file_name = format!("{stem}-{}", self.log_file_suffix());
+        if let Some(extension) = path.extension() {
+            file_name = format!("{file_name}.{}", extension.to_string_lossy());
+        }
+        path.with_file_name(file_name)
+    }
+
+    /// Ensures none of the given listening addresses share a port, returning an error naming the conflicting flags.
+    ///
+    /// Each address is labeled with its flag, e.g. `("--rest", rest_ip)`, and `None` if the listener is disabled.
+    pub fn ensure_distinct_ports(&self, listeners: &[(&str, Option<SocketAddr>)]) -> Result<()> {
+        let listeners: Vec<_> = listeners.iter().filter_map(|(flag, ip)| ip.map(|ip| (*flag, ip))).collect();
+        for (i, (flag, ip)) in listeners.iter().enumerate() {
+            for (other_flag, other_ip) in &listeners[i + 1..] {
+                if ip.port() == other_ip.port() {
+                    bail!(
+                        "The development node {} uses port {} for both '{flag}' ({ip}) and '{other_flag}' ({other_ip})",
+                        self.id,
+                        ip.port()
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Ensures the given storage mode does not open the ledger of the production node, or of another development node.
+    pub fn ensure_distinct_storage(&self, network: u16, storage_mode: &StorageMode) -> Result<()> {
+        // Note: Only a custom storage path may alias the ledger of another node.
+        let StorageMode::Custom(path) = storage_mode else {
+            return Ok(());
+        };
+        if *path == aleo_ledger_dir(network, StorageMode::Production) {
+            bail!("The development node {} cannot use the production ledger at '{}'", self.id, path.display());
+        }
+        let aliased = (0..MAX_DEV_INSTANCES).find(|id| *id != self.id && *path == Self { id: *id }.ledger_dir(network));
+        if let Some(other_id) = aliased {
+            bail!(
+                "The development node {} cannot use the ledger of development node {other_id} at '{}'",
+                self.id,
+                path.display()
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::proposal_cache_path;
+
+    use snarkvm::console::network::{MainnetV0, Network};
+
+    use std::collections::HashSet;
+
+    type CurrentNetwork = MainnetV0;
+
+    /// The number of development instances to check.
+    const NUM_INSTANCES: u16 = 20;
+
+    fn sample_instances() -> Vec<DevInstance> {
+        (0..NUM_INSTANCES).map(|id| DevInstance::new(id).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_documented_scheme() {
+        for instance in sample_instances() {
+            let id = instance.id();
+            assert_eq!(instance.node_ip(), SocketAddr::from(([0, 0, 0, 0], 4130 + id)));
+            assert_eq!(instance.peer_ip(), SocketAddr::from(([127, 0, 0, 1], 4130 + id)));
+            assert_eq!(instance.bft_ip(), SocketAddr::from(([127, 0, 0, 1], 5000 + id)));
+            assert_eq!(instance.rest_ip(), SocketAddr::from(([0, 0, 0, 0], 3030 + id)));
+            assert_eq!(instance.metrics_ip(), SocketAddr::from(([0, 0, 0, 0], 9000 + id)));
+            assert_eq!(instance.storage_mode(), StorageMode::Development(id));
+            assert_eq!(
+                instance.ledger_dir(CurrentNetwork::ID),
+                aleo_ledger_dir(CurrentNetwork::ID, StorageMode::Development(id))
+            );
+            assert_eq!(
+                instance.log_file(Path::new("/tmp/snarkos.log")),
+                PathBuf::from(format!("/tmp/snarkos-dev-{id}.log"))
+            );
+        }
+        // Ensure a log file without an extension is suffixed.
+        let instance = DevInstance::new(2).unwrap();
+        assert_eq!(instance.log_file(Path::new("/tmp/snarkos")), PathBuf::from("/tmp/snarkos-dev-2"));
+    }
+
+    #[test]
+    fn test_pairwise_unique() {
+        let instances = sample_instances();
+
+        // Ensure no two listeners share a port, across all instances.
+        let ports: Vec<_> = instances
+            .iter()
+            .flat_map(|instance| {
+                [instance.node_port(), instance.bft_port(), instance.rest_port(), instance.metrics_port()]
+            })
+            .collect();
+        assert_eq!(ports.iter().collect::<HashSet<_>>().len(), ports.len());
+
+        // Ensure no two instances share a ledger, a proposal cache, or a log file.
+        let ledger_dirs: HashSet<_> =
+            instances.iter().map(|instance| instance.ledger_dir(CurrentNetwork::ID)).collect();
+        let proposal_caches: HashSet<_> =
+            instances.iter().map(|instance| proposal_cache_path(CurrentNetwork::ID, Some(instance.id()))).collect();
+        let log_files: HashSet<_> =
+            instances.iter().map(|instance| instance.log_file(Path::new("/tmp/snarkos.log"))).collect();
+        let suffixes: HashSet<_> = instances.iter().map(|instance| instance.log_file_suffix()).collect();
+        for paths in [ledger_dirs.len(), proposal_caches.len(), log_files.len(), suffixes.len()] {
+            assert_eq!(paths, NUM_INSTANCES as usize);
+        }
+        // Ensure the storage of a development instance never aliases the production storage.
+        let production = aleo_ledger_dir(CurrentNetwork::ID, StorageMode::Production);
+        assert!(!ledger_dirs.contains(&production));
+        assert!(ledger_dirs.is_disjoint(&proposal_caches));
+    }
+
+    #[test]
+    fn test_max_dev_instances() {
+        // Ensure the ports of the last instance do not overlap with the ports of the first instance.
+        let first = DevInstance::new(0).unwrap();
+        let last = DevInstance::new(MAX_DEV_INSTANCES - 1).unwrap();
+        assert!(last.rest_port() < first.node_port());
+        assert!(last.node_port() < first.bft_port());
+        assert!(last.bft_port() < first.metrics_port());
+        // Ensure the development IDs past the limit are rejected.
+        assert!(DevInstance::new(MAX_DEV_INSTANCES).is_err());
+        assert!(DevInstance::new(u16::MAX).is_err());
+    }
+
+    #[test]
+    fn test_ensure_distinct_ports() {
+        let instance = DevInstance::new(1).unwrap();
+        let derived = [
+            ("--node", Some(instance.node_ip())),
+            ("--bft", Some(instance.bft_ip())),
+            ("--rest", Some(instance.rest_ip())),
+            ("--metrics-ip", Some(instance.metrics_ip())),
+        ];
+        instance.ensure_distinct_ports(&derived).unwrap();
+
+        // Ensure an explicit flag that collides with a derived port is rejected.
+        let conflicting = [("--node", Some(instance.node_ip())), ("--rest", Some("127.0.0.1:4131".parse().unwrap()))];
+        let error = instance.ensure_distinct_ports(&conflicting).unwrap_err();
+        assert!(error.to_string().contains("'--node'") && error.to_string().contains("'--rest'"));
+
+        // Ensure a disabled listener is ignored.
+        instance.ensure_distinct_ports(&[("--node", Some(instance.node_ip())), ("--rest", None)]).unwrap();
+    }
+
+    #[test]
+    fn test_ensure_distinct_storage() {
+        let instance = DevInstance::new(1).unwrap();
+        let network = CurrentNetwork::ID;
+        // Ensure the derived storage, and an unrelated custom storage, are accepted.
+        instance.ensure_distinct_storage(network, &instance.storage_mode()).unwrap();
+        instance.ensure_distinct_storage(network, &StorageMode::Custom(PathBuf::from("/tmp/snarkos-ledger"))).unwrap();
+        instance.ensure_distinct_storage(network, &StorageMode::Custom(instance.ledger_dir(network))).unwrap();
+        // Ensure the ledger of the production node, or of another development node, is rejected.
+        let production = aleo_ledger_dir(network, StorageMode::Production);
+        assert!(instance.ensure_distinct_storage(network, &StorageMode::Custom(production)).is_err());
+        let other = DevInstance::new(2).unwrap().ledger_dir(network);
+        assert!(instance.ensure_distinct_storage(network, &StorageMode::Custom(other)).is_err());
+    }
+}
@@ -0,0 +1,95 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use indexmap::{IndexMap, IndexSet};
+use parking_lot::Mutex;
+use std::{
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
+
+/// The time in seconds after another node last authenticated as this node's account,
+/// for which the duplicate identity is considered active.
+pub const DUPLICATE_IDENTITY_WINDOW_IN_SECS: u64 = 600; // 10 minutes
+/// The maximum number of peer IPs tracked as another node authenticated as this node's account.
+pub const MAX_DUPLICATE_IDENTITY_PEERS: usize = 16;
+/// The maximum number of the most recent challenge nonces sent by this node, used to recognize self-connections.
+pub const MAX_OWN_NONCES: usize = 64;
+
+/// The tracker of other validators that authenticated as this validator's own account in the gateway.
+///
+/// Two validators running with the same private key may sign conflicting batches, and this is only detected
+/// once the other validator proves control of the account in a handshake. A validator dialing itself through
+/// another address is recognized by its own challenge nonce, and is not reported.
+#[derive(Debug, Default)]
+pub struct DuplicateIdentity {
+    /// The peer IPs that authenticated as this node's account, in the order they were last seen.
+    peer_ips: Mutex<IndexMap<SocketAddr, Instant>>,
+    /// The most recent challenge nonces sent by this node, from the oldest.
+    own_nonces: Mutex<IndexSet<u64>>,
+}
+
+impl DuplicateIdentity {
+    /// Records a challenge nonce sent by this node.
+    pub fn insert_own_nonce(&self, nonce: u64) {
+        let mut own_nonces = self.own_nonces.lock();
+        own_nonces.insert(nonce);
+        while own_nonces.len() > MAX_OWN_NONCES {
+            own_nonces.shift_remove_index(0);
+        }
+    }
+
+    /// Returns `true` if the given challenge nonce was sent by this node.
+    pub fn is_own_nonce(&self, nonce: u64) -> bool {
+        self.own_nonces.lock().contains(&nonce)
+    }
+
+    /// Records that another node at the given peer IP authenticated as this node's account.
+    /// Returns `true` if the peer IP was not already active.
+    pub fn report(&self, peer_ip: SocketAddr, now: Instant) -> bool {
+        let mut peer_ips = self.peer_ips.lock();
+        // Move the peer IP to the back, so that the least recently seen peer IPs are evicted first.
+        let is_new = match peer_ips.shift_remove(&peer_ip) {
+            Some(last_seen) => now.saturating_duration_since(last_seen) > Self::window(),
+            None => true,
+        };
+        peer_ips.insert(peer_ip, now);
+        while peer_ips.len() > MAX_DUPLICATE_IDENTITY_PEERS {
+            peer_ips.shift_remove_index(0);
+        }
+        is_new
+    }
+
+    /// Returns the peer IPs that authenticated as this node's account within the window, from the most recent.
+    pub fn peer_ips(&self, now: Instant) -> Vec<SocketAddr> {
+        self.peer_ips
+            .lock()
+            .iter()
+            .rev()
+            .filter(|(_, last_seen)| now.saturating_duration_since(**last_seen) <= Self::window())
+            .map(|(peer_ip, _)| *peer_ip)
+            .collect()
+    }
+
+    /// Returns `true` if another node authenticated as this node's account within the window.
+    pub fn is_active(&self, now: Instant) -> bool {
+        !self.peer_ips(now).is_empty()
+    }
+
+    /// Returns the window for which a duplicate identity is considered active.
+    const fn window() -> Duration {
+        Duration::from_secs(DUPLICATE_IDENTITY_WINDOW_IN_SECS)
+    }
+}
@@ -0,0 +1,203 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{
+    ledger::committee::Committee,
+    prelude::{Address, Field, Literal, LiteralType, Network, Result, bail, ensure},
+};
+
+use parking_lot::RwLock;
+use std::{collections::BTreeMap, sync::Arc};
+
+/// The default number of most recent rounds whose leaders are retained.
+pub const DEFAULT_LEADER_SCHEDULE_CAPACITY: usize = 1_024;
+/// The number of most recent committees whose election order is retained.
+/// Note: The rounds looked up at a time span the committee lookbacks of a few blocks at most.
+const NUM_ELECTION_ORDERS: usize = 4;
+
+/// The members of a committee, in the order of the leader election, with their cumulative stakes.
+#[derive(Debug)]
+struct ElectionOrder<N: Network> {
+    /// The ID of the committee.
+    committee_id: Field<N>,
+    /// The members, by decreasing stake then by decreasing x-coordinate, with the stake up to and including them.
+    members: Vec<(Address<N>, u64)>,
+}
+
+impl<N: Network> ElectionOrder<N> {
+    /// Sorts the members of the given committee, as `Committee::get_leader` does for every election.
+    fn new(committee: &Committee<N>) -> Self {
+        let mut members: Vec<_> = committee
+            .members()
+            .iter()
+            .map(|(address, (stake, ..))| (*address, address.to_x_coordinate(), *stake))
+            .collect();
+        members.sort_unstable_by(|(_, x1, stake1), (_, x2, stake2)| stake2.cmp(stake1).then_with(|| x2.cmp(x1)));
+        let mut cumulative_stake = 0u64;
+        let members = members
+            .into_iter()
+            .map(|(address, _, stake)| {
+                cumulative_stake = cumulative_stake.saturating_add(stake);
+                (address, cumulative_stake)
+            })
+            .collect();
+        Self { committee_id: committee.id(), members }
+    }
+
+    /// Returns the leader of the given round, as elected by `Committee::get_leader`, without sorting the members.
+    fn elect(&self, committee: &Committee<N>, round: u64) -> Result<Address<N>> {
+        ensure!(round >= committee.starting_round(), "Current round must be at least the starting round");
+        // Derive the stake index from the round seed.
+        let total_stake = committee.total_stake();
+        let seed = [committee.starting_round(), round, total_stake].map(Field::from_u64);
+        let hash = Literal::Field(N::hash_to_group_psd4(&seed)?.to_x_coordinate());
+        let stake_index = match hash.cast_lossy(LiteralType::U64)? {
+            Literal::U64(output) => (*output) % total_stake,
+            _ => bail!("BFT failed to downcast the stake index to a u64"),
+        };
+        // Elect the first member whose cumulative stake exceeds the stake index.
+        let index = self.members.partition_point(|(_, cumulative_stake)| *cumulative_stake <= stake_index);
+        match self.members.get(index) {
+            Some((leader, _)) => Ok(*leader),
+            None => bail!("BFT failed to elect a leader for round {round} (stake index {stake_index})"),
+        }
+    }
+}
+
+/// The leaders of the most recent even rounds, as elected by their committee lookback.
+///
+/// Electing a leader sorts the members of the committee, which is costly for large committees,
+/// while the leader of a round is looked up repeatedly: to advance past the round, to commit it,
+/// and to walk back over the uncommitted leader rounds of a later commit. The leaders are keyed
+/// by the ID of the electing committee, so a round is re-elected if its committee lookback differs.
+/// As the members of a committee are immutable, their election order is sorted once per committee,
+/// and the leader of a new round is found by a binary search over their cumulative stakes.
+#[derive(Debug)]
+pub struct LeaderSchedule<N: Network> {
+    /// The maximum number of rounds whose leaders are retained.
+    capacity: usize,
+    /// The map of rounds to the ID of the electing committee, and the elected leader.
+    leaders: RwLock<BTreeMap<u64, (Field<N>, Address<N>)>>,
+    /// The election orders of the most recent committees, from the oldest to the newest.
+    orders: RwLock<Vec<Arc<ElectionOrder<N>>>>,
+}
+
+impl<N: Network> Default for LeaderSchedule<N> {
+    fn default() -> Self {
+        Self::new(DEFAULT_LEADER_SCHEDULE_CAPACITY)
+    }
+}
+
+impl<N: Network> LeaderSchedule<N> {
+    /// Initializes a new schedule, retaining the leaders of up to the given number of rounds.
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity, leaders: Default::default(), orders: Default::default() }
+    }
+
+    /// Returns the leader of the given round, as elected by the given committee.
+    pub fn get_leader(&self, committee: &Committee<N>, round: u64) -> Result<Address<N>> {
+        if let Some((committee_id, leader)) = self.leaders.read().get(&round) {
+            if *committee_id == committee.id() {
+                return Ok(*leader);
+            }
+        }
+        let leader = self.election_order(committee).elect(committee, round)?;
+        let mut leaders = self.leaders.write();
+        leaders.insert(round, (committee.id(), leader));
+        // Evict the oldest rounds, as the lookups are for the most recent rounds.
+        while leaders.len() > self.capacity {
+            leaders.pop_first();
+        }
+        Ok(leader)
+    }
+
+    /// Returns the election order of the given committee, sorting its members if the committee is new.
+    fn election_order(&self, committee: &Committee<N>) -> Arc<ElectionOrder<N>> {
+        let committee_id = committee.id();
+        if let Some(order) = self.orders.read().iter().find(|order| order.committee_id == committee_id) {
+            return order.clone();
+        }
+        let order = Arc::new(ElectionOrder::new(committee));
+        let mut orders = self.orders.write();
+        if !orders.iter().any(|order| order.committee_id == committee_id) {
+            orders.push(order.clone());
+            // Evict the oldest committees, as the lookups are for the most recent rounds.
+            if orders.len() > NUM_ELECTION_ORDERS {
+                orders.remove(0);
+            }
+        }
+        order
+    }
+
+    /// Returns the number of rounds whose leaders are retained.
+    pub fn len(&self) -> usize {
+        self.leaders.read().len()
+    }
+
+    /// Returns `true` if no leaders are retained.
+    pub fn is_empty(&self) -> bool {
+        self.leaders.read().is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::{
+        ledger::committee::test_helpers::{sample_committee_for_round, sample_committee_for_round_and_size},
+        utilities::TestRng,
+    };
+
+    type CurrentNetwork = snarkvm::prelude::MainnetV0;
+
+    #[test]
+    fn test_leader_schedule() {
+        let rng = &mut TestRng::default();
+        let committee = sample_committee_for_round::<CurrentNetwork>(2, rng);
+        let other_committee = sample_committee_for_round::<CurrentNetwork>(2, rng);
+        let schedule = LeaderSchedule::new(10);
+        assert!(schedule.is_empty());
+
+        // Ensure the scheduled leaders are the ones elected by the committee, whether they are retained or not.
+        for _ in 0..2 {
+            for round in (2..42).step_by(2) {
+                assert_eq!(schedule.get_leader(&committee, round).unwrap(), committee.get_leader(round).unwrap());
+            }
+        }
+        // Ensure the leaders of the most recent rounds are retained, up to the capacity.
+        assert_eq!(schedule.len(), 10);
+        assert_eq!(schedule.leaders.read().keys().next(), Some(&22));
+
+        // Ensure a round is re-elected by a different committee.
+        assert_eq!(schedule.get_leader(&other_committee, 40).unwrap(), other_committee.get_leader(40).unwrap());
+        assert_eq!(schedule.get_leader(&committee, 40).unwrap(), committee.get_leader(40).unwrap());
+        // Ensure the election order is sorted once per committee.
+        assert_eq!(schedule.orders.read().len(), 2);
+    }
+
+    #[test]
+    fn test_election_order_matches_the_committee() {
+        let rng = &mut TestRng::default();
+        for num_members in [4, 25, 100] {
+            let committee = sample_committee_for_round_and_size::<CurrentNetwork>(10, num_members, rng);
+            let order = ElectionOrder::new(&committee);
+            // Ensure the cached order elects the same leaders as the committee, including before its starting round.
+            assert!(order.elect(&committee, 8).is_err());
+            for round in 10..1_000 {
+                assert_eq!(order.elect(&committee, round).unwrap(), committee.get_leader(round).unwrap());
+            }
+        }
+    }
+}
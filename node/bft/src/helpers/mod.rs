@@ -16,12 +16,27 @@
 pub mod cache;
 pub use cache::*;
 
+pub mod certificate_chain;
+pub use certificate_chain::*;
+
 pub mod channels;
 pub use channels::*;
 
+pub mod connection_stats;
+pub use connection_stats::*;
+
 pub mod dag;
 pub use dag::*;
 
+pub mod dev_instance;
+pub use dev_instance::*;
+
+pub mod duplicate_identity;
+pub use duplicate_identity::*;
+
+pub mod leader_schedule;
+pub use leader_schedule::*;
+
 pub mod partition;
 pub use partition::*;
 
@@ -34,9 +49,18 @@ pub use proposal::*;
 pub mod proposal_cache;
 pub use proposal_cache::*;
 
+pub mod proposal_outcomes;
+pub use proposal_outcomes::*;
+
+pub mod proposer_availability;
+pub use proposer_availability::*;
+
 pub mod ready;
 pub use ready::*;
 
+pub mod recent_nonces;
+pub use recent_nonces::*;
+
 pub mod resolver;
 pub use resolver::*;
 
@@ -46,9 +70,18 @@ pub use signed_proposals::*;
 pub mod storage;
 pub use storage::*;
 
+pub mod storage_backpressure;
+pub use storage_backpressure::*;
+
 pub mod timestamp;
 pub use timestamp::*;
 
+pub mod transfers;
+pub use transfers::*;
+
+pub mod validators_requests;
+pub use validators_requests::*;
+
 /// Formats an ID into a truncated identifier (for logging purposes).
 pub fn fmt_id(id: impl ToString) -> String {
     let id = id.to_string();
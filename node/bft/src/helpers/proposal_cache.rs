@@ -13,7 +13,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::helpers::{Proposal, SignedProposals};
+use crate::helpers::{Proposal, SignedProposals, dev_storage_mode};
 
 use snarkvm::{
     console::{account::Address, network::Network, program::SUBDAG_CERTIFICATES_DEPTH},
@@ -21,7 +21,7 @@ use snarkvm::{
     prelude::{FromBytes, IoResult, Read, Result, ToBytes, Write, anyhow, bail, error},
 };
 
-use aleo_std::{StorageMode, aleo_ledger_dir};
+use aleo_std::aleo_ledger_dir;
 use indexmap::IndexSet;
 use std::{fs, path::PathBuf};
 
@@ -30,7 +30,7 @@ pub fn proposal_cache_path(network: u16, dev: Option<u16>) -> PathBuf {
     const PROPOSAL_CACHE_FILE_NAME: &str = "current-proposal-cache";
 
     // Obtain the path to the ledger.
-    let mut path = aleo_ledger_dir(network, StorageMode::from(dev));
+    let mut path = aleo_ledger_dir(network, dev_storage_mode(dev));
     // Go to the folder right above the ledger.
     path.pop();
     // Append the proposal store's file name.
@@ -0,0 +1,78 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+
+/// The number of most recent batch proposals of this node whose outcome is kept.
+pub const NUM_RECENT_PROPOSAL_OUTCOMES: usize = 10;
+
+/// The tracker of whether the most recent batch proposals of this node reached the quorum threshold.
+///
+/// This is purely observational, and is only used to report the health of the node.
+#[derive(Debug, Default)]
+pub struct ProposalOutcomes {
+    /// The round of each recent proposal, and whether it was certified, from oldest to newest.
+    outcomes: Mutex<VecDeque<(u64, bool)>>,
+}
+
+impl ProposalOutcomes {
+    /// Records whether the proposal of the given round was certified, or expired without a quorum.
+    pub fn record(&self, round: u64, is_certified: bool) {
+        let mut outcomes = self.outcomes.lock();
+        // Keep a single outcome per round, as a round may be proposed again after a restart.
+        if let Some(outcome) = outcomes.iter_mut().find(|(r, _)| *r == round) {
+            outcome.1 |= is_certified;
+            return;
+        }
+        if outcomes.len() == NUM_RECENT_PROPOSAL_OUTCOMES {
+            outcomes.pop_front();
+        }
+        outcomes.push_back((round, is_certified));
+    }
+
+    /// Returns the fraction of the recent proposals that were certified, or `None` if there were no proposals.
+    pub fn certified_ratio(&self) -> Option<f64> {
+        let outcomes = self.outcomes.lock();
+        let num_certified = outcomes.iter().filter(|(_, is_certified)| *is_certified).count();
+        (!outcomes.is_empty()).then(|| num_certified as f64 / outcomes.len() as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_certified_ratio() {
+        let tracker = ProposalOutcomes::default();
+        assert_eq!(tracker.certified_ratio(), None);
+
+        // Ensure the ratio reflects the recorded outcomes.
+        tracker.record(1, true);
+        tracker.record(2, false);
+        assert_eq!(tracker.certified_ratio(), Some(0.5));
+        // Ensure a round is only counted once, and keeps its certification.
+        tracker.record(2, true);
+        tracker.record(2, false);
+        assert_eq!(tracker.certified_ratio(), Some(1.0));
+
+        // Ensure only the most recent outcomes are kept.
+        for round in 3..3 + NUM_RECENT_PROPOSAL_OUTCOMES as u64 {
+            tracker.record(round, false);
+        }
+        assert_eq!(tracker.certified_ratio(), Some(0.0));
+    }
+}
@@ -0,0 +1,197 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::MAX_BATCH_DELAY_IN_MS;
+use snarkvm::prelude::{Address, Network};
+
+use parking_lot::RwLock;
+use std::collections::{BTreeMap, HashMap};
+
+/// The number of most recent rounds over which the fetch outcomes of a proposer are considered.
+pub const AVAILABILITY_WINDOW_IN_ROUNDS: u64 = 50;
+/// The minimum number of proposals in the window before a proposer is judged.
+pub const MIN_PROPOSALS_FOR_AVAILABILITY: usize = 10;
+/// The ratio of never-served proposals in the window above which the proposals of a proposer are no longer signed.
+pub const MAX_NEVER_SERVED_RATIO: f64 = 0.5;
+/// The ratio of never-served proposals in the window at or below which the proposals of a proposer are signed again.
+pub const RECOVERED_NEVER_SERVED_RATIO: f64 = 0.2;
+/// The duration in milliseconds after which the missing transmissions of a proposal are considered served late.
+pub const SERVED_LATE_IN_MS: u64 = MAX_BATCH_DELAY_IN_MS;
+
+/// The outcome of fetching the missing transmissions of a proposal from its proposer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FetchOutcome {
+    /// All the missing transmissions were served in time.
+    Served,
+    /// All the missing transmissions were served, but slowly.
+    ServedLate,
+    /// Some of the missing transmissions were never served.
+    NeverServed,
+}
+
+/// The fetch outcomes of the recent proposals of a proposer.
+#[derive(Clone, Debug, Default)]
+struct ProposerRecord {
+    /// The (worst) fetch outcome of the proposal of each recent round.
+    outcomes: BTreeMap<u64, FetchOutcome>,
+    /// Whether the proposals of the proposer are currently not signed.
+    is_withholding: bool,
+}
+
+impl ProposerRecord {
+    /// Returns the number of served, served-late, and never-served proposals in the window.
+    fn counts(&self) -> (usize, usize, usize) {
+        self.outcomes.values().fold((0, 0, 0), |(served, late, never), outcome| match outcome {
+            FetchOutcome::Served => (served + 1, late, never),
+            FetchOutcome::ServedLate => (served, late + 1, never),
+            FetchOutcome::NeverServed => (served, late, never + 1),
+        })
+    }
+}
+
+/// The tracker of whether the validators serve the transmissions of their own batch proposals.
+///
+/// A validator that proposes batches whose transmissions it never serves makes the other validators waste
+/// their fetch attempts every round. Once the ratio of never-served proposals of a validator in the recent
+/// rounds is too high, its proposals are no longer signed, until the ratio recovers. This is purely local.
+#[derive(Debug)]
+pub struct ProposerAvailability<N: Network> {
+    /// The record of each proposer.
+    records: RwLock<HashMap<Address<N>, ProposerRecord>>,
+}
+
+impl<N: Network> Default for ProposerAvailability<N> {
+    /// Initializes a new instance of the tracker.
+    fn default() -> Self {
+        Self { records: Default::default() }
+    }
+}
+
+impl<N: Network> ProposerAvailability<N> {
+    /// Returns `true` if the proposals of the given proposer are not to be signed.
+    pub fn is_withholding(&self, proposer: &Address<N>) -> bool {
+        self.records.read().get(proposer).map_or(false, |record| record.is_withholding)
+    }
+
+    /// Returns the number of served, served-late, and never-served proposals of the given proposer in the window.
+    pub fn counts(&self, proposer: &Address<N>) -> (usize, usize, usize) {
+        self.records.read().get(proposer).map_or((0, 0, 0), ProposerRecord::counts)
+    }
+
+    /// Records the fetch outcome of the proposal of the given proposer in the given round.
+    /// Returns the new status of the proposer, if it changed.
+    pub fn record(&self, proposer: Address<N>, round: u64, outcome: FetchOutcome) -> Option<bool> {
+        let mut records = self.records.write();
+        let record = records.entry(proposer).or_default();
+        // Keep the worst outcome of the round, as a proposal may be fetched more than once.
+        let entry = record.outcomes.entry(round).or_insert(outcome);
+        *entry = (*entry).max(outcome);
+        // Remove the outcomes that fell out of the window.
+        let latest_round = record.outcomes.keys().next_back().copied().unwrap_or(round);
+        record.outcomes = record.outcomes.split_off(&latest_round.saturating_sub(AVAILABILITY_WINDOW_IN_ROUNDS - 1));
+
+        // Determine the ratio of never-served proposals, once enough proposals were observed.
+        let (served, late, never) = record.counts();
+        let total = served + late + never;
+        if total < MIN_PROPOSALS_FOR_AVAILABILITY {
+            return None;
+        }
+        let ratio = never as f64 / total as f64;
+        // Update the status of the proposer, with a hysteresis to avoid flapping.
+        let is_withholding = match record.is_withholding {
+            false => ratio > MAX_NEVER_SERVED_RATIO,
+            true => ratio > RECOVERED_NEVER_SERVED_RATIO,
+        };
+        if is_withholding == record.is_withholding {
+            return None;
+        }
+        record.is_withholding = is_withholding;
+        Some(is_withholding)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::utilities::TestRng;
+
+    use rand::Rng;
+
+    type CurrentNetwork = snarkvm::prelude::MainnetV0;
+
+    fn sample_address(rng: &mut TestRng) -> Address<CurrentNetwork> {
+        Address::new(rng.gen())
+    }
+
+    #[test]
+    fn test_transient_failures_are_tolerated() {
+        let rng = &mut TestRng::default();
+        let tracker = ProposerAvailability::<CurrentNetwork>::default();
+        let proposer = sample_address(rng);
+
+        // A proposer whose proposals are never served, but not yet observed enough, is still signed.
+        for round in 0..MIN_PROPOSALS_FOR_AVAILABILITY as u64 - 1 {
+            assert_eq!(tracker.record(proposer, round, FetchOutcome::NeverServed), None);
+        }
+        assert!(!tracker.is_withholding(&proposer));
+
+        // A proposer that serves most of its proposals, even late, is still signed.
+        let other = sample_address(rng);
+        for round in 0..AVAILABILITY_WINDOW_IN_ROUNDS * 2 {
+            let outcome = match round % 3 {
+                0 => FetchOutcome::NeverServed,
+                1 => FetchOutcome::ServedLate,
+                _ => FetchOutcome::Served,
+            };
+            assert_eq!(tracker.record(other, round, outcome), None);
+        }
+        assert!(!tracker.is_withholding(&other));
+        // Ensure only the window is kept.
+        let (served, late, never) = tracker.counts(&other);
+        assert_eq!(served + late + never, AVAILABILITY_WINDOW_IN_ROUNDS as usize);
+    }
+
+    #[test]
+    fn test_withholding_and_recovery() {
+        let rng = &mut TestRng::default();
+        let tracker = ProposerAvailability::<CurrentNetwork>::default();
+        let proposer = sample_address(rng);
+
+        // A proposer that never serves its proposals is no longer signed.
+        let mut round = 0;
+        let mut changes = vec![];
+        while !tracker.is_withholding(&proposer) {
+            changes.extend(tracker.record(proposer, round, FetchOutcome::NeverServed));
+            round += 1;
+        }
+        assert_eq!(changes, vec![true]);
+        assert_eq!(round, MIN_PROPOSALS_FOR_AVAILABILITY as u64);
+
+        // A proposal fetched again in the same round keeps its worst outcome.
+        tracker.record(proposer, round - 1, FetchOutcome::Served);
+        assert_eq!(tracker.counts(&proposer), (0, 0, MIN_PROPOSALS_FOR_AVAILABILITY));
+
+        // The proposer is signed again, only once its ratio has recovered below the lower threshold.
+        let mut changes = vec![];
+        while tracker.is_withholding(&proposer) {
+            changes.extend(tracker.record(proposer, round, FetchOutcome::Served));
+            round += 1;
+        }
+        assert_eq!(changes, vec![false]);
+        let (served, _, never) = tracker.counts(&proposer);
+        assert!(never as f64 / (served + never) as f64 <= RECOVERED_NEVER_SERVED_RATIO);
+        assert!(never as f64 / (served + never) as f64 > RECOVERED_NEVER_SERVED_RATIO / 2.0);
+    }
+}
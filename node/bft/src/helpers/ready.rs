@@ -22,14 +22,56 @@ use snarkvm::{
     },
 };
 
+use crate::helpers::now;
+
 use indexmap::{IndexMap, IndexSet};
-use parking_lot::RwLock;
-use std::sync::Arc;
+use parking_lot::{Mutex, RwLock};
+use std::{
+    collections::{HashSet, VecDeque},
+    net::SocketAddr,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+
+/// The window in seconds over which the drain rate of the ready queue is measured.
+pub const DRAIN_RATE_WINDOW_IN_SECS: i64 = 60; // 1 minute
+/// The minimum number of distinct peers that gossiped a transmission for it to be drained ahead of the others.
+pub const MIN_SIGHTINGS_FOR_PRIORITY: u16 = 2;
+/// The age in seconds after which a transmission is drained first, regardless of its sightings.
+/// Note: This ensures the transmissions that are not gossiped by other validators are never starved.
+pub const MAX_PRIORITY_DELAY_IN_SECS: i64 = 10;
+
+/// A transmission in the ready queue.
+#[derive(Clone, Debug)]
+struct ReadyEntry<N: Network> {
+    /// The transmission.
+    transmission: Transmission<N>,
+    /// The insertion timestamp.
+    timestamp: i64,
+    /// The size of the transmission in bytes, computed once on insertion.
+    num_bytes: usize,
+    /// The distinct peers that gossiped the transmission.
+    sighted_by: HashSet<SocketAddr>,
+}
+
+impl<N: Network> ReadyEntry<N> {
+    /// Returns the number of distinct peers that gossiped the transmission.
+    fn num_sightings(&self) -> u16 {
+        u16::try_from(self.sighted_by.len()).unwrap_or(u16::MAX)
+    }
+}
 
 #[derive(Clone, Debug)]
 pub struct Ready<N: Network> {
-    /// The current map of `(transmission ID, transmission)` entries.
-    transmissions: Arc<RwLock<IndexMap<TransmissionID<N>, Transmission<N>>>>,
+    /// The current map of `(transmission ID, entry)` entries.
+    transmissions: Arc<RwLock<IndexMap<TransmissionID<N>, ReadyEntry<N>>>>,
+    /// The total size in bytes of the transmissions in the ready queue.
+    /// Note: This is only updated while the write lock on the transmissions is held.
+    num_bytes: Arc<AtomicUsize>,
+    /// The recent drains, as `(timestamp, number of drained transmissions)` entries.
+    drains: Arc<Mutex<VecDeque<(i64, usize)>>>,
 }
 
 impl<N: Network> Default for Ready<N> {
@@ -42,7 +84,7 @@ impl<N: Network> Default for Ready<N> {
 impl<N: Network> Ready<N> {
     /// Initializes a new instance of the ready queue.
     pub fn new() -> Self {
-        Self { transmissions: Default::default() }
+        Self { transmissions: Default::default(), num_bytes: Default::default(), drains: Default::default() }
     }
 
     /// Returns `true` if the ready queue is empty.
@@ -55,6 +97,11 @@ impl<N: Network> Ready<N> {
         self.transmissions.read().len()
     }
 
+    /// Returns the total size in bytes of the transmissions in the ready queue, without serializing them.
+    pub fn num_bytes(&self) -> usize {
+        self.num_bytes.load(Ordering::Relaxed)
+    }
+
     /// Returns the number of ratifications in the ready queue.
     pub fn num_ratifications(&self) -> usize {
         self.transmissions.read().keys().filter(|id| matches!(id, TransmissionID::Ratification)).count()
@@ -77,12 +124,44 @@ impl<N: Network> Ready<N> {
 
     /// Returns the transmissions in the ready queue.
     pub fn transmissions(&self) -> IndexMap<TransmissionID<N>, Transmission<N>> {
-        self.transmissions.read().clone()
+        self.transmissions.read().iter().map(|(id, entry)| (*id, entry.transmission.clone())).collect()
+    }
+
+    /// Returns the ID, the size in bytes (if the transmission is serialized), and the insertion timestamp
+    /// of each transmission in the ready queue, without copying the transmissions.
+    pub fn transmission_summaries(&self) -> Vec<(TransmissionID<N>, Option<usize>, i64)> {
+        let size_in_bytes = |transmission: &Transmission<N>| match transmission {
+            Transmission::Ratification => Some(0),
+            Transmission::Solution(Data::Buffer(bytes)) | Transmission::Transaction(Data::Buffer(bytes)) => {
+                Some(bytes.len())
+            }
+            Transmission::Solution(Data::Object(_)) | Transmission::Transaction(Data::Object(_)) => None,
+        };
+        self.transmissions
+            .read()
+            .iter()
+            .map(|(id, entry)| (*id, size_in_bytes(&entry.transmission), entry.timestamp))
+            .collect()
+    }
+
+    /// Returns up to the given number of transmissions in the ready queue that match the filter, in arrival order.
+    pub fn transmissions_matching(
+        &self,
+        limit: usize,
+        filter: impl Fn(&TransmissionID<N>) -> bool,
+    ) -> IndexMap<TransmissionID<N>, Transmission<N>> {
+        self.transmissions
+            .read()
+            .iter()
+            .filter(|(id, _)| filter(id))
+            .take(limit)
+            .map(|(id, entry)| (*id, entry.transmission.clone()))
+            .collect()
     }
 
     /// Returns the solutions in the ready queue.
     pub fn solutions(&self) -> impl '_ + Iterator<Item = (SolutionID<N>, Data<Solution<N>>)> {
-        self.transmissions.read().clone().into_iter().filter_map(|(id, transmission)| match (id, transmission) {
+        self.transmissions().into_iter().filter_map(|(id, transmission)| match (id, transmission) {
             (TransmissionID::Solution(id, _), Transmission::Solution(solution)) => Some((id, solution)),
             _ => None,
         })
@@ -90,11 +169,24 @@ impl<N: Network> Ready<N> {
 
     /// Returns the transactions in the ready queue.
     pub fn transactions(&self) -> impl '_ + Iterator<Item = (N::TransactionID, Data<Transaction<N>>)> {
-        self.transmissions.read().clone().into_iter().filter_map(|(id, transmission)| match (id, transmission) {
+        self.transmissions().into_iter().filter_map(|(id, transmission)| match (id, transmission) {
             (TransmissionID::Transaction(id, _), Transmission::Transaction(tx)) => Some((id, tx)),
             _ => None,
         })
     }
+
+    /// Returns the insertion timestamp of the oldest transmission in the ready queue, if it exists.
+    pub fn oldest_timestamp(&self) -> Option<i64> {
+        self.transmissions.read().values().map(|entry| entry.timestamp).min()
+    }
+
+    /// Returns the number of transmissions drained from the ready queue within the last `DRAIN_RATE_WINDOW_IN_SECS`.
+    pub fn num_recently_drained(&self) -> usize {
+        let mut drains = self.drains.lock();
+        // Remove the drains that are outside of the window.
+        prune_drains(&mut drains, now());
+        drains.iter().map(|(_, num_drained)| num_drained).sum()
+    }
 }
 
 impl<N: Network> Ready<N> {
@@ -105,35 +197,138 @@ impl<N: Network> Ready<N> {
 
     /// Returns the transmission, given the specified `transmission ID`.
     pub fn get(&self, transmission_id: impl Into<TransmissionID<N>>) -> Option<Transmission<N>> {
-        self.transmissions.read().get(&transmission_id.into()).cloned()
+        self.transmissions.read().get(&transmission_id.into()).map(|entry| entry.transmission.clone())
+    }
+
+    /// Returns the number of distinct peers that gossiped the specified `transmission ID`, if it is in the ready queue.
+    pub fn num_sightings(&self, transmission_id: impl Into<TransmissionID<N>>) -> Option<u16> {
+        self.transmissions.read().get(&transmission_id.into()).map(|entry| entry.num_sightings())
     }
 
     /// Inserts the specified (`transmission ID`, `transmission`) to the ready queue.
     /// Returns `true` if the transmission is new, and was added to the ready queue.
     pub fn insert(&self, transmission_id: impl Into<TransmissionID<N>>, transmission: Transmission<N>) -> bool {
         let transmission_id = transmission_id.into();
-        // Insert the transmission ID.
-        let is_new = self.transmissions.write().insert(transmission_id, transmission).is_none();
+        // Compute the size of the transmission, before acquiring the write lock.
+        let num_bytes = serialized_size(&transmission);
+        let entry = ReadyEntry { transmission, timestamp: now(), num_bytes, sighted_by: Default::default() };
+        // Acquire the write lock.
+        let mut transmissions = self.transmissions.write();
+        // Insert the transmission ID, updating the size of the ready queue.
+        let previous = transmissions.insert(transmission_id, entry);
+        self.num_bytes.fetch_add(num_bytes, Ordering::Relaxed);
+        if let Some(previous) = &previous {
+            self.num_bytes.fetch_sub(previous.num_bytes, Ordering::Relaxed);
+        }
         // Return whether the transmission is new.
-        is_new
+        previous.is_none()
+    }
+
+    /// Records that the given peer gossiped the specified `transmission ID`.
+    /// Returns `true` if the transmission is in the ready queue.
+    ///
+    /// Note: Repeated sightings from the same peer are only counted once, so that a chatty peer
+    /// cannot promote a transmission on its own.
+    pub fn record_sighting(&self, peer_ip: SocketAddr, transmission_id: impl Into<TransmissionID<N>>) -> bool {
+        match self.transmissions.write().get_mut(&transmission_id.into()) {
+            Some(entry) => {
+                entry.sighted_by.insert(peer_ip);
+                true
+            }
+            None => false,
+        }
     }
 
     /// Removes up to the specified number of transmissions and returns them.
+    ///
+    /// The transmissions are drained in the following order, each in arrival order:
+    ///   1. The transmissions older than `MAX_PRIORITY_DELAY_IN_SECS`.
+    ///   2. The transmissions gossiped by at least `MIN_SIGHTINGS_FOR_PRIORITY` distinct peers,
+    ///      as they are most likely to also be in the ready queues of the other validators.
+    ///   3. The remaining transmissions.
     pub fn drain(&self, num_transmissions: usize) -> IndexMap<TransmissionID<N>, Transmission<N>> {
         // Acquire the write lock.
         let mut transmissions = self.transmissions.write();
-        // Determine the number of transmissions to drain.
-        let range = 0..transmissions.len().min(num_transmissions);
         // Drain the transmission IDs.
-        transmissions.drain(range).collect::<IndexMap<_, _>>()
+        let drained: IndexMap<_, _> = match transmissions.len() <= num_transmissions {
+            // If all transmissions are drained, drain them in arrival order.
+            true => {
+                self.num_bytes.store(0, Ordering::Relaxed);
+                transmissions.drain(..).map(|(id, entry)| (id, entry.transmission)).collect()
+            }
+            false => {
+                // Rank the transmissions, keeping the arrival order within each rank.
+                let cutoff = now().saturating_sub(MAX_PRIORITY_DELAY_IN_SECS);
+                let mut ranked = transmissions
+                    .iter()
+                    .map(|(id, entry)| match (entry.timestamp <= cutoff, entry.num_sightings()) {
+                        (true, _) => (0u8, *id),
+                        (false, num_sightings) if num_sightings >= MIN_SIGHTINGS_FOR_PRIORITY => (1, *id),
+                        (false, _) => (2, *id),
+                    })
+                    .collect::<Vec<_>>();
+                ranked.sort_by_key(|(rank, _)| *rank);
+                // Select the highest-ranked transmissions.
+                let selected = ranked.into_iter().take(num_transmissions).map(|(_, id)| id).collect::<IndexSet<_>>();
+                // Remove the selected transmissions, preserving the arrival order of the others.
+                let mut drained = IndexMap::with_capacity(selected.len());
+                *transmissions = std::mem::take(&mut *transmissions)
+                    .into_iter()
+                    .filter_map(|(id, entry)| match selected.contains(&id) {
+                        true => {
+                            self.num_bytes.fetch_sub(entry.num_bytes, Ordering::Relaxed);
+                            drained.insert(id, entry.transmission);
+                            None
+                        }
+                        false => Some((id, entry)),
+                    })
+                    .collect();
+                drained
+            }
+        };
+        // Record the drain, to track the drain rate.
+        if !drained.is_empty() {
+            let mut drains = self.drains.lock();
+            let now = now();
+            drains.push_back((now, drained.len()));
+            // Remove the drains that are outside of the window.
+            prune_drains(&mut drains, now);
+        }
+        drained
     }
 
     /// Clears all solutions from the ready queue.
     pub(crate) fn clear_solutions(&self) {
         // Acquire the write lock.
         let mut transmissions = self.transmissions.write();
-        // Remove all solutions.
-        transmissions.retain(|id, _| !matches!(id, TransmissionID::Solution(..)));
+        // Remove all solutions, updating the size of the ready queue.
+        transmissions.retain(|id, entry| match id {
+            TransmissionID::Solution(..) => {
+                self.num_bytes.fetch_sub(entry.num_bytes, Ordering::Relaxed);
+                false
+            }
+            _ => true,
+        });
+    }
+}
+
+/// Returns the size in bytes of the given transmission.
+/// Note: A buffered transmission is already serialized, so only the objects are serialized to be measured.
+fn serialized_size<N: Network>(transmission: &Transmission<N>) -> usize {
+    match transmission {
+        Transmission::Ratification => 0,
+        Transmission::Solution(Data::Buffer(bytes)) | Transmission::Transaction(Data::Buffer(bytes)) => bytes.len(),
+        Transmission::Solution(Data::Object(_)) | Transmission::Transaction(Data::Object(_)) => {
+            transmission.to_bytes_le().map_or(0, |bytes| bytes.len())
+        }
+    }
+}
+
+/// Removes the drains that occurred before the drain rate window, relative to the given timestamp.
+fn prune_drains(drains: &mut VecDeque<(i64, usize)>, now: i64) {
+    let cutoff = now.saturating_sub(DRAIN_RATE_WINDOW_IN_SECS);
+    while drains.front().is_some_and(|(timestamp, _)| *timestamp < cutoff) {
+        drains.pop_front();
     }
 }
 
@@ -201,8 +396,12 @@ mod tests {
         assert_eq!(ready.get(solution_id_3), Some(solution_3.clone()));
         assert_eq!(ready.get(solution_id_unknown), None);
 
+        // Check the size of the ready queue.
+        assert_eq!(ready.num_bytes(), 3 * 512);
+
         // Drain the ready queue.
         let transmissions = ready.drain(3);
+        assert_eq!(ready.num_bytes(), 0);
 
         // Check the number of transmissions.
         assert!(ready.is_empty());
@@ -247,4 +446,109 @@ mod tests {
         // Check the number of transmissions.
         assert_eq!(ready.num_transmissions(), 1);
     }
+
+    #[test]
+    fn test_ready_drain_priority() {
+        let rng = &mut TestRng::default();
+
+        // Initialize the ready queue.
+        let ready = Ready::<CurrentNetwork>::new();
+
+        // Insert 5 solutions.
+        let solution_ids = (0..5)
+            .map(|_| {
+                let solution_id = TransmissionID::Solution(
+                    rng.gen::<u64>().into(),
+                    rng.gen::<<CurrentNetwork as Network>::TransmissionChecksum>(),
+                );
+                assert!(ready.insert(solution_id, Transmission::Solution(Data::Buffer(Bytes::from_static(&[0u8])))));
+                solution_id
+            })
+            .collect::<Vec<_>>();
+
+        // Ensure the sightings are only recorded for the transmissions in the ready queue.
+        let peer_ip = |port: u16| SocketAddr::from(([127, 0, 0, 1], port));
+        for port in 0..MIN_SIGHTINGS_FOR_PRIORITY {
+            assert!(ready.record_sighting(peer_ip(port), solution_ids[3]));
+            assert!(ready.record_sighting(peer_ip(port), solution_ids[4]));
+        }
+        // Ensure a single peer gossiping a transmission repeatedly counts as one sighting.
+        for _ in 0..10 {
+            assert!(ready.record_sighting(peer_ip(0), solution_ids[2]));
+        }
+        assert_eq!(ready.num_sightings(solution_ids[4]), Some(MIN_SIGHTINGS_FOR_PRIORITY));
+        assert_eq!(ready.num_sightings(solution_ids[2]), Some(1));
+        assert_eq!(ready.num_sightings(solution_ids[0]), Some(0));
+
+        // Age the second solution past the priority delay.
+        ready.transmissions.write().get_mut(&solution_ids[1]).unwrap().timestamp -= MAX_PRIORITY_DELAY_IN_SECS;
+
+        // Ensure the overdue solution is drained first, followed by the solutions gossiped by multiple peers.
+        let drained = ready.drain(2);
+        assert_eq!(drained.keys().copied().collect::<Vec<_>>(), vec![solution_ids[1], solution_ids[3]]);
+        let drained = ready.drain(1);
+        assert_eq!(drained.keys().copied().collect::<Vec<_>>(), vec![solution_ids[4]]);
+
+        // Ensure the remaining solutions are kept in arrival order.
+        assert_eq!(ready.transmission_ids().into_iter().collect::<Vec<_>>(), vec![solution_ids[0], solution_ids[2]]);
+        assert!(!ready.record_sighting(peer_ip(0), solution_ids[4]));
+        assert_eq!(ready.drain(10).len(), 2);
+        assert!(ready.is_empty());
+    }
+
+    #[test]
+    fn test_ready_snapshot() {
+        let rng = &mut TestRng::default();
+
+        // Initialize the ready queue.
+        let ready = Ready::<CurrentNetwork>::new();
+
+        // Insert 5 buffered solutions, and a ratification.
+        let solution_ids = (0..5u8)
+            .map(|i| {
+                let solution_id = TransmissionID::Solution(
+                    rng.gen::<u64>().into(),
+                    rng.gen::<<CurrentNetwork as Network>::TransmissionChecksum>(),
+                );
+                let bytes = Bytes::from(vec![0u8; 100 + i as usize]);
+                assert!(ready.insert(solution_id, Transmission::Solution(Data::Buffer(bytes))));
+                solution_id
+            })
+            .collect::<Vec<_>>();
+        assert!(ready.insert(TransmissionID::Ratification, Transmission::Ratification));
+
+        // Ensure the summaries list every transmission in arrival order, with its size.
+        let summaries = ready.transmission_summaries();
+        assert_eq!(summaries.len(), 6);
+        for (i, (id, size_in_bytes, timestamp)) in summaries.iter().take(5).enumerate() {
+            assert_eq!((*id, *size_in_bytes), (solution_ids[i], Some(100 + i)));
+            assert!(*timestamp <= now());
+        }
+
+        // Ensure the matching transmissions are capped, and filtered.
+        let is_solution = |id: &TransmissionID<CurrentNetwork>| matches!(id, TransmissionID::Solution(..));
+        let selected = ready.transmissions_matching(3, is_solution);
+        assert_eq!(selected.keys().copied().collect::<Vec<_>>(), solution_ids[..3]);
+        assert_eq!(ready.transmissions_matching(10, is_solution).len(), 5);
+        assert_eq!(ready.transmissions_matching(0, is_solution).len(), 0);
+
+        // Ensure the snapshots can be taken while the ready queue is concurrently mutated.
+        let ready_ = ready.clone();
+        let writer = std::thread::spawn(move || {
+            let rng = &mut TestRng::default();
+            for _ in 0..1_000 {
+                let solution_id = TransmissionID::Solution(
+                    rng.gen::<u64>().into(),
+                    rng.gen::<<CurrentNetwork as Network>::TransmissionChecksum>(),
+                );
+                ready_.insert(solution_id, Transmission::Solution(Data::Buffer(Bytes::from_static(&[0u8]))));
+                ready_.drain(1);
+            }
+        });
+        for _ in 0..1_000 {
+            assert!(ready.transmission_summaries().len() <= 7);
+            assert!(ready.transmissions_matching(3, is_solution).len() <= 3);
+        }
+        writer.join().unwrap();
+    }
 }
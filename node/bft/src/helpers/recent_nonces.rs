@@ -0,0 +1,49 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use indexmap::IndexSet;
+use parking_lot::Mutex;
+
+/// The maximum number of recent handshake nonces of the validators that are remembered.
+pub const MAX_RECENT_NONCES: usize = 1_024;
+
+/// The bounded record of the nonces the validators recently used in their handshakes with the gateway.
+///
+/// A nonce that was already used in a handshake indicates a replayed challenge request or response,
+/// as honest validators sample a fresh random nonce for each handshake.
+#[derive(Debug, Default)]
+pub struct RecentNonces {
+    /// The recent nonces, from the oldest.
+    nonces: Mutex<IndexSet<u64>>,
+}
+
+impl RecentNonces {
+    /// Returns `true` if the given nonce was recently used in a handshake.
+    pub fn contains(&self, nonce: u64) -> bool {
+        self.nonces.lock().contains(&nonce)
+    }
+
+    /// Records the given nonce, and returns `false` if it was recently used in a handshake.
+    pub fn insert(&self, nonce: u64) -> bool {
+        let mut nonces = self.nonces.lock();
+        if !nonces.insert(nonce) {
+            return false;
+        }
+        while nonces.len() > MAX_RECENT_NONCES {
+            nonces.shift_remove_index(0);
+        }
+        true
+    }
+}
@@ -757,6 +757,150 @@ impl<N: Network> Storage<N> {
     }
 }
 
+/// The outcome of an audit of the transmissions in storage against the certificates in memory.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct StorageAudit {
+    /// The number of references to certificates of garbage collected rounds, that were released from storage.
+    pub num_orphans_released: usize,
+    /// The number of transmissions in storage that were linked to a certificate in memory that was missing them.
+    pub num_relinked: usize,
+    /// The number of transmissions missing from storage that were re-hydrated for a certificate in memory.
+    pub num_rehydrated: usize,
+    /// The number of transmissions missing from storage that could not be recovered.
+    pub num_unrecoverable: usize,
+}
+
+impl StorageAudit {
+    /// Returns the number of repairs made by the audit.
+    pub const fn num_repairs(&self) -> usize {
+        self.num_orphans_released + self.num_relinked + self.num_rehydrated
+    }
+
+    /// Returns `true` if the audit found storage consistent with the certificates in memory.
+    pub const fn is_consistent(&self) -> bool {
+        self.num_repairs() == 0 && self.num_unrecoverable == 0
+    }
+}
+
+impl std::fmt::Display for StorageAudit {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{} orphaned references released, {} relinked, {} rehydrated, {} unrecoverable",
+            self.num_orphans_released, self.num_relinked, self.num_rehydrated, self.num_unrecoverable
+        )
+    }
+}
+
+impl<N: Network> Storage<N> {
+    /// Audits the transmissions in storage against the certificates in memory, and repairs their divergence.
+    ///
+    /// The audit is driven by the transmissions in storage:
+    /// - A stored transmission of a certificate in memory, that is not stored for it, is linked to it.
+    /// - The references to the certificates of garbage collected rounds, i.e. those no greater than the GC round,
+    ///   are released, which removes the transmissions that are no longer referenced by any certificate.
+    ///   Note: The round of a certificate that is no longer in memory is resolved from the ledger, and the
+    ///   references to a certificate whose round is unknown, or still live, are kept.
+    ///
+    /// The transmissions of the certificates in memory, that are missing from storage altogether,
+    /// are then re-hydrated with `find_transmission`.
+    ///
+    /// The entries are audited in batches of `batch_size`, and the certificates are only locked to read a batch,
+    /// so that the audit never blocks the workers for long.
+    pub fn audit_transmissions(
+        &self,
+        batch_size: usize,
+        find_transmission: impl Fn(TransmissionID<N>) -> Option<Transmission<N>>,
+    ) -> StorageAudit {
+        let batch_size = batch_size.max(1);
+        let gc_round = self.gc_round();
+        let mut audit = StorageAudit::default();
+
+        // Collect the certificates in memory that reference each transmission.
+        let mut in_memory = HashMap::<TransmissionID<N>, Vec<Field<N>>>::new();
+        let certificate_ids: Vec<_> = self.certificates.read().keys().copied().collect();
+        for certificate_ids in certificate_ids.chunks(batch_size) {
+            {
+                let certificates = self.certificates.read();
+                for certificate in certificate_ids.iter().filter_map(|certificate_id| certificates.get(certificate_id))
+                {
+                    for transmission_id in certificate.transmission_ids() {
+                        if !matches!(transmission_id, TransmissionID::Ratification) {
+                            in_memory.entry(*transmission_id).or_default().push(certificate.id());
+                        }
+                    }
+                }
+            }
+            std::thread::yield_now();
+        }
+
+        // Reconcile each transmission in storage with the certificates in memory.
+        for transmission_ids in self.transmissions.transmission_ids().chunks(batch_size) {
+            for transmission_id in transmission_ids {
+                let stored_for = self.transmissions.get_certificate_ids(*transmission_id);
+                // Link the transmission to the certificates in memory that are missing it.
+                for certificate_id in in_memory.remove(transmission_id).unwrap_or_default() {
+                    if stored_for.contains(&certificate_id) || !self.contains_certificate(certificate_id) {
+                        continue;
+                    }
+                    // Note: A transmission that is not stored, yet is in storage, is an aborted transmission.
+                    let aborted_transmission_ids = match self.transmissions.get_transmission(*transmission_id) {
+                        Some(_) => Default::default(),
+                        None => HashSet::from([*transmission_id]),
+                    };
+                    self.transmissions.insert_transmissions(
+                        certificate_id,
+                        IndexSet::from([*transmission_id]),
+                        aborted_transmission_ids,
+                        Default::default(),
+                    );
+                    audit.num_relinked += 1;
+                }
+                // Release the references to the certificates of garbage collected rounds.
+                for certificate_id in stored_for {
+                    if self.contains_certificate(certificate_id) {
+                        continue;
+                    }
+                    let round =
+                        self.ledger.get_batch_certificate(&certificate_id).ok().map(|certificate| certificate.round());
+                    if round.is_some_and(|round| round <= gc_round) {
+                        self.transmissions.remove_transmissions(&certificate_id, &IndexSet::from([*transmission_id]));
+                        audit.num_orphans_released += 1;
+                    }
+                }
+            }
+            std::thread::yield_now();
+        }
+
+        // Re-hydrate the transmissions of the certificates in memory, that are missing from storage.
+        for (transmission_id, certificate_ids) in in_memory {
+            let Some(transmission) = find_transmission(transmission_id) else {
+                for certificate_id in certificate_ids {
+                    warn!(
+                        "Transmission '{}' of certificate '{}' is missing from storage",
+                        fmt_id(transmission_id),
+                        fmt_id(certificate_id)
+                    );
+                    audit.num_unrecoverable += 1;
+                }
+                continue;
+            };
+            for certificate_id in certificate_ids {
+                if self.contains_certificate(certificate_id) {
+                    self.transmissions.insert_transmissions(
+                        certificate_id,
+                        IndexSet::from([transmission_id]),
+                        Default::default(),
+                        HashMap::from([(transmission_id, transmission.clone())]),
+                    );
+                    audit.num_rehydrated += 1;
+                }
+            }
+        }
+        audit
+    }
+}
+
 #[cfg(test)]
 impl<N: Network> Storage<N> {
     /// Returns the ledger service.
@@ -831,7 +975,7 @@ pub(crate) mod tests {
     use snarkos_node_bft_ledger_service::MockLedgerService;
     use snarkos_node_bft_storage_service::BFTMemoryService;
     use snarkvm::{
-        ledger::narwhal::Data,
+        ledger::narwhal::{Data, batch_certificate::test_helpers::sample_batch_certificate_for_round},
         prelude::{Rng, TestRng},
     };
 
@@ -967,6 +1111,89 @@ pub(crate) mod tests {
         assert_storage(&storage, &[], &[], &[], &Default::default());
     }
 
+    #[test]
+    fn test_audit_transmissions() {
+        let rng = &mut TestRng::default();
+
+        // Sample a committee.
+        let committee = snarkvm::ledger::committee::test_helpers::sample_committee(rng);
+        // Initialize the ledger.
+        let ledger = Arc::new(MockLedgerService::new(committee));
+        // Initialize the storage, sharing its transmissions to fabricate the divergences.
+        let transmissions = Arc::new(BFTMemoryService::new());
+        let storage = Storage::<CurrentNetwork>::new(ledger.clone(), transmissions.clone(), 1);
+        // Garbage collect the rounds up to round 5.
+        storage.garbage_collect_certificates(6);
+        assert_eq!(storage.gc_round(), 5);
+
+        // Insert a certificate, along with its transmissions.
+        let certificate = sample_batch_certificate_for_round(10, rng);
+        let certificate_id = certificate.id();
+        let (missing_transmissions, expected_transmissions) = sample_transmissions(&certificate, rng);
+        storage.insert_certificate_atomic(certificate.clone(), Default::default(), missing_transmissions.clone());
+        // Ensure the audit finds the storage consistent.
+        assert!(storage.audit_transmissions(2, |_| None).is_consistent());
+
+        // Lose three transmissions of the certificate in memory, as if the node crashed before storing them.
+        let lost: IndexSet<_> = certificate.transmission_ids().iter().take(3).copied().collect();
+        assert_eq!(lost.len(), 3);
+        transmissions.remove_transmissions(&certificate_id, &lost);
+        // Store the first lost transmission for a committed certificate of a garbage collected round.
+        let orphan = sample_batch_certificate_for_round(3, rng);
+        ledger.insert_certificate(orphan.clone());
+        let relinked = (lost[0], missing_transmissions[&lost[0]].clone());
+        transmissions.insert_transmissions(orphan.id(), indexset! { lost[0] }, Default::default(), [relinked].into());
+        // Store a transmission of the certificate that is no longer in memory.
+        let orphaned_id = *orphan.transmission_ids().first().unwrap();
+        let orphaned = (orphaned_id, sample_transmission(rng));
+        transmissions.insert_transmissions(
+            orphan.id(),
+            indexset! { orphaned_id },
+            Default::default(),
+            [orphaned].into(),
+        );
+
+        // Store a transmission for a committed certificate of a live round, and one for an unknown certificate,
+        // neither of which is in memory.
+        let live = sample_batch_certificate_for_round(7, rng);
+        ledger.insert_certificate(live.clone());
+        let unknown = sample_batch_certificate_for_round(2, rng);
+        let mut retained_transmissions = HashMap::new();
+        for kept in [&live, &unknown] {
+            let transmission_id = *kept.transmission_ids().first().unwrap();
+            let transmission = sample_transmission(rng);
+            let entry = (transmission_id, transmission.clone());
+            transmissions.insert_transmissions(
+                kept.id(),
+                indexset! { transmission_id },
+                Default::default(),
+                [entry].into(),
+            );
+            retained_transmissions.insert(transmission_id, (transmission, indexset! { kept.id() }));
+        }
+
+        // Audit the storage, while only the second lost transmission can be found.
+        let audit = storage.audit_transmissions(2, |transmission_id| {
+            (transmission_id == lost[1]).then(|| missing_transmissions[&lost[1]].clone())
+        });
+        let expected_audit =
+            StorageAudit { num_orphans_released: 2, num_relinked: 1, num_rehydrated: 1, num_unrecoverable: 1 };
+        assert_eq!(audit, expected_audit);
+        assert_eq!(audit.num_repairs(), 4);
+
+        // Ensure the storage converged to the certificate in memory, except for the unrecoverable transmission,
+        // and kept the transmissions of the certificates that are not garbage collected.
+        let mut expected_transmissions = expected_transmissions;
+        expected_transmissions.remove(&lost[2]);
+        expected_transmissions.extend(retained_transmissions);
+        assert_eq!(storage.transmissions_iter().collect::<HashMap<_, _>>(), expected_transmissions);
+
+        // Ensure a second audit has nothing left to repair.
+        let audit = storage.audit_transmissions(2, |_| None);
+        assert_eq!(audit, StorageAudit { num_unrecoverable: 1, ..Default::default() });
+        assert_eq!(audit.num_repairs(), 0);
+    }
+
     #[test]
     fn test_certificate_duplicate() {
         let rng = &mut TestRng::default();
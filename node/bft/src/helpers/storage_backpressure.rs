@@ -0,0 +1,215 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use parking_lot::Mutex;
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+/// The duration in milliseconds of a storage advance above which the storage is considered slow.
+pub const STORAGE_SLOW_THRESHOLD_IN_MS: u64 = 5_000;
+/// The duration in milliseconds of a storage advance at or below which the storage is considered recovered.
+pub const STORAGE_RECOVERED_THRESHOLD_IN_MS: u64 = 2_000;
+/// The number of consecutive recovered storage advances required to release the throttle.
+pub const NUM_RECOVERED_STORAGE_ADVANCES: usize = 3;
+/// The maximum duration in milliseconds for which new work is throttled, even if no storage advance recovers.
+pub const MAX_STORAGE_THROTTLE_IN_MS: u64 = 60_000;
+/// The number of most recent storage advance latencies that are kept.
+const NUM_RECENT_STORAGE_LATENCIES: usize = 10;
+
+/// The state of the storage advances.
+#[derive(Debug, Default)]
+struct StorageLatencies {
+    /// The most recent storage advance latencies, from oldest to newest.
+    recent: VecDeque<Duration>,
+    /// The time at which new work was throttled, if it is currently throttled.
+    throttled_since: Option<Instant>,
+}
+
+/// The tracker of the storage advance latencies, which throttles new work while the storage is slow.
+///
+/// When the storage stalls, each block takes longer to be written than the committee takes to commit it,
+/// so the committed subdags pile up in memory. While the storage is slow, the primary does not propose new
+/// batches and the memory pool sheds new admissions; the committed subdags are always written, in order.
+///
+/// The throttle is released by fast storage advances, which require new blocks. If a quorum of validators is
+/// throttled at once, no new blocks are produced, so the throttle is also released after a maximum duration.
+/// If the storage is still slow, the next slow advance engages it again.
+#[derive(Debug)]
+pub struct StorageBackpressure {
+    /// The duration of a storage advance above which the storage is considered slow.
+    slow_threshold: Duration,
+    /// The duration of a storage advance at or below which the storage is considered recovered.
+    recovered_threshold: Duration,
+    /// The maximum duration for which new work is throttled.
+    max_throttle: Duration,
+    /// The state of the storage advances.
+    latencies: Mutex<StorageLatencies>,
+}
+
+impl Default for StorageBackpressure {
+    /// Initializes a new instance of the tracker, with the default thresholds.
+    fn default() -> Self {
+        Self::new(
+            Duration::from_millis(STORAGE_SLOW_THRESHOLD_IN_MS),
+            Duration::from_millis(STORAGE_RECOVERED_THRESHOLD_IN_MS),
+            Duration::from_millis(MAX_STORAGE_THROTTLE_IN_MS),
+        )
+    }
+}
+
+impl StorageBackpressure {
+    /// Initializes a new instance of the tracker, with the given thresholds and maximum throttle duration.
+    pub fn new(slow_threshold: Duration, recovered_threshold: Duration, max_throttle: Duration) -> Self {
+        debug_assert!(recovered_threshold <= slow_threshold);
+        Self { slow_threshold, recovered_threshold, max_throttle, latencies: Default::default() }
+    }
+
+    /// Returns `true` if new work is throttled, as the storage is slow.
+    pub fn is_throttled(&self) -> bool {
+        self.is_throttled_at(Instant::now())
+    }
+
+    /// Returns `true` if new work is throttled at the given time.
+    /// If the throttle has been held for the maximum duration, it is released.
+    fn is_throttled_at(&self, now: Instant) -> bool {
+        let mut latencies = self.latencies.lock();
+        match latencies.throttled_since {
+            Some(since) if now.saturating_duration_since(since) >= self.max_throttle => {
+                warn!(
+                    "Storage has been slow for {}s without recovering - resuming new proposals and transmissions",
+                    self.max_throttle.as_secs()
+                );
+                latencies.throttled_since = None;
+                false
+            }
+            throttled_since => throttled_since.is_some(),
+        }
+    }
+
+    /// Returns the most recent storage advance latencies, from oldest to newest.
+    pub fn recent_latencies(&self) -> Vec<Duration> {
+        self.latencies.lock().recent.iter().copied().collect()
+    }
+
+    /// Records the latency of a storage advance.
+    /// Returns the new throttle status, if it changed.
+    pub fn record(&self, latency: Duration) -> Option<bool> {
+        self.record_at(latency, Instant::now())
+    }
+
+    /// Records the latency of a storage advance, completed at the given time.
+    /// Returns the new throttle status, if it changed.
+    fn record_at(&self, latency: Duration, now: Instant) -> Option<bool> {
+        // Release the throttle first, if it has been held for the maximum duration.
+        let was_throttled = self.is_throttled_at(now);
+        let mut latencies = self.latencies.lock();
+        // Keep the most recent latencies.
+        if latencies.recent.len() == NUM_RECENT_STORAGE_LATENCIES {
+            latencies.recent.pop_front();
+        }
+        latencies.recent.push_back(latency);
+
+        // Update the throttle, with a hysteresis to avoid flapping.
+        let is_throttled = match was_throttled {
+            // A single slow advance engages the throttle, as it already delays every subsequent commit.
+            false => latency > self.slow_threshold,
+            // The throttle is released once the most recent advances are all fast again.
+            true => {
+                latencies.recent.len() < NUM_RECOVERED_STORAGE_ADVANCES
+                    || latencies
+                        .recent
+                        .iter()
+                        .rev()
+                        .take(NUM_RECOVERED_STORAGE_ADVANCES)
+                        .any(|latency| *latency > self.recovered_threshold)
+            }
+        };
+        if is_throttled == was_throttled {
+            return None;
+        }
+        latencies.throttled_since = is_throttled.then_some(now);
+        Some(is_throttled)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_throttle_engages_and_releases() {
+        let tracker = StorageBackpressure::default();
+        let fast = Duration::from_millis(STORAGE_RECOVERED_THRESHOLD_IN_MS);
+        let medium = Duration::from_millis(STORAGE_SLOW_THRESHOLD_IN_MS);
+        let slow = Duration::from_millis(STORAGE_SLOW_THRESHOLD_IN_MS + 1);
+
+        // Fast and medium advances do not engage the throttle.
+        for latency in [fast, medium, fast, medium] {
+            assert_eq!(tracker.record(latency), None);
+        }
+        assert!(!tracker.is_throttled());
+
+        // A single slow advance engages the throttle.
+        assert_eq!(tracker.record(slow), Some(true));
+        assert!(tracker.is_throttled());
+
+        // The throttle is held until enough consecutive advances are fast again.
+        for _ in 0..NUM_RECOVERED_STORAGE_ADVANCES - 1 {
+            assert_eq!(tracker.record(fast), None);
+        }
+        assert_eq!(tracker.record(medium), None);
+        for _ in 0..NUM_RECOVERED_STORAGE_ADVANCES - 1 {
+            assert_eq!(tracker.record(fast), None);
+        }
+        assert!(tracker.is_throttled());
+        assert_eq!(tracker.record(fast), Some(false));
+        assert!(!tracker.is_throttled());
+    }
+
+    #[test]
+    fn test_throttle_is_released_after_maximum_duration() {
+        let tracker = StorageBackpressure::default();
+        let max_throttle = Duration::from_millis(MAX_STORAGE_THROTTLE_IN_MS);
+        let slow = Duration::from_millis(STORAGE_SLOW_THRESHOLD_IN_MS + 1);
+
+        // Engage the throttle, and emulate that no storage advance happens afterwards.
+        let start = Instant::now();
+        assert_eq!(tracker.record_at(slow, start), Some(true));
+        assert!(tracker.is_throttled_at(start + max_throttle - Duration::from_millis(1)));
+
+        // Ensure the throttle is released once it has been held for the maximum duration.
+        assert!(!tracker.is_throttled_at(start + max_throttle));
+        assert!(!tracker.is_throttled_at(start + max_throttle));
+
+        // Ensure the next slow advance engages the throttle again, from that time on.
+        let restart = start + max_throttle * 2;
+        assert_eq!(tracker.record_at(slow, restart), Some(true));
+        assert!(tracker.is_throttled_at(restart + max_throttle - Duration::from_millis(1)));
+        assert!(!tracker.is_throttled_at(restart + max_throttle));
+    }
+
+    #[test]
+    fn test_recent_latencies_are_bounded() {
+        let tracker = StorageBackpressure::default();
+        for i in 0..NUM_RECENT_STORAGE_LATENCIES as u64 * 2 {
+            tracker.record(Duration::from_millis(i));
+        }
+        let latencies = tracker.recent_latencies();
+        assert_eq!(latencies.len(), NUM_RECENT_STORAGE_LATENCIES);
+        assert_eq!(latencies.last(), Some(&Duration::from_millis(NUM_RECENT_STORAGE_LATENCIES as u64 * 2 - 1)));
+    }
+}
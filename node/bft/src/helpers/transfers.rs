@@ -0,0 +1,453 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    MAX_FETCH_TIMEOUT_IN_MS,
+    events::{TRANSMISSION_CHUNK_SIZE, TransmissionChunk, TransmissionChunkRequest, TransmissionResponse},
+};
+use snarkvm::{
+    console::prelude::*,
+    ledger::narwhal::{Transmission, TransmissionID},
+};
+
+use ::bytes::{Bytes, BytesMut};
+use indexmap::IndexMap;
+use parking_lot::Mutex;
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    ops::Range,
+    time::{Duration, Instant},
+};
+
+/// The maximum size in bytes of a transmission transferred in chunks.
+pub const MAX_TRANSFER_SIZE: usize = 32 * 1024 * 1024; // 32 MiB
+/// The maximum number of concurrent transfers from a single peer.
+pub const MAX_TRANSFERS_PER_PEER: usize = 16;
+/// The duration in milliseconds without progress, after which the missing chunks of a transfer are re-requested.
+pub const TRANSFER_STALL_TIMEOUT_IN_MS: u64 = 1000; // ms
+/// The maximum number of times a transfer is resumed, before it is abandoned.
+const MAX_TRANSFER_RESUMES: u32 = 3;
+/// The maximum number of serialized transmissions cached for the outbound transfers.
+const MAX_SERIALIZED_TRANSMISSIONS: usize = 16;
+
+/// A transmission that is being received in chunks.
+struct Transfer {
+    /// The received chunks, by index.
+    chunks: Vec<Option<Bytes>>,
+    /// The number of received chunks.
+    num_received: usize,
+    /// The timestamp of the last received chunk, or of the last resumption.
+    last_progress: Instant,
+    /// The number of times the transfer has been resumed.
+    num_resumes: u32,
+}
+
+impl Transfer {
+    /// Returns the ranges of chunk indices that have not been received.
+    fn missing_ranges(&self) -> Vec<Range<u32>> {
+        let mut ranges: Vec<Range<u32>> = Vec::new();
+        for (index, _) in self.chunks.iter().enumerate().filter(|(_, chunk)| chunk.is_none()) {
+            let index = index as u32;
+            match ranges.last_mut() {
+                Some(range) if range.end == index => range.end += 1,
+                _ => ranges.push(index..index + 1),
+            }
+        }
+        ranges
+    }
+}
+
+/// The reassembly buffers for the transmissions that are being received in chunks,
+/// and the serialized transmissions that are being sent in chunks.
+pub struct Transfers<N: Network> {
+    /// The map of `(peer IP, transmission ID)` to the transfer.
+    transfers: Mutex<HashMap<(SocketAddr, TransmissionID<N>), Transfer>>,
+    /// The map of `(peer IP, transmission ID)` to the time the transmission was requested from the peer.
+    /// Note: A transfer is only started for a transmission that was requested, and is not yet being transferred.
+    requested: Mutex<HashMap<(SocketAddr, TransmissionID<N>), Instant>>,
+    /// The serialized transmissions that were recently sent in chunks, in order of insertion.
+    serialized: Mutex<IndexMap<TransmissionID<N>, Bytes>>,
+}
+
+impl<N: Network> Default for Transfers<N> {
+    /// Initializes a new instance of the transfers.
+    fn default() -> Self {
+        Self { transfers: Default::default(), requested: Default::default(), serialized: Default::default() }
+    }
+}
+
+impl<N: Network> Transfers<N> {
+    /// Records that the given transmission was requested from the peer, so that its chunks are accepted.
+    pub fn expect(&self, peer_ip: SocketAddr, transmission_id: TransmissionID<N>) {
+        self.requested.lock().insert((peer_ip, transmission_id), Instant::now());
+    }
+
+    /// Returns the chunks of the given transmission, to be sent to a peer.
+    /// The transmission is serialized once, and the serialized bytes are reused for the resumptions of its transfers.
+    pub fn split(
+        &self,
+        transmission_id: TransmissionID<N>,
+        transmission: &Transmission<N>,
+    ) -> Result<Vec<TransmissionChunk<N>>> {
+        let cached = self.serialized.lock().get(&transmission_id).cloned();
+        let bytes = match cached {
+            Some(bytes) => bytes,
+            None => {
+                let bytes = Bytes::from(transmission.to_bytes_le()?);
+                // Cache the transmission only if it is transferred in chunks.
+                if bytes.len() > TRANSMISSION_CHUNK_SIZE {
+                    let mut serialized = self.serialized.lock();
+                    serialized.insert(transmission_id, bytes.clone());
+                    if serialized.len() > MAX_SERIALIZED_TRANSMISSIONS {
+                        serialized.shift_remove_index(0);
+                    }
+                }
+                bytes
+            }
+        };
+        Ok(TransmissionChunk::split_bytes(transmission_id, bytes)?)
+    }
+
+    /// Inserts the given chunk from the peer, and returns the transmission response once all chunks are received.
+    pub fn insert(&self, peer_ip: SocketAddr, chunk: TransmissionChunk<N>) -> Result<Option<TransmissionResponse<N>>> {
+        let TransmissionChunk { transmission_id, index, num_chunks, bytes } = chunk;
+        // Ensure the chunk is well-formed, and the transfer is within the size bound.
+        ensure!(num_chunks > 1, "A chunked transfer must consist of more than one chunk");
+        ensure!(index < num_chunks, "Chunk index {index} is out of bounds (of {num_chunks} chunks)");
+        ensure!(
+            num_chunks as usize <= MAX_TRANSFER_SIZE.div_ceil(TRANSMISSION_CHUNK_SIZE),
+            "Transfer of {num_chunks} chunks exceeds the maximum size"
+        );
+        ensure!(
+            index + 1 == num_chunks || bytes.len() == TRANSMISSION_CHUNK_SIZE,
+            "Chunk {index} has an invalid size ({} bytes)",
+            bytes.len()
+        );
+
+        let mut transfers = self.transfers.lock();
+        let key = (peer_ip, transmission_id);
+        if !transfers.contains_key(&key) {
+            // Drop the chunk, if the transmission was not requested from the peer.
+            if self.requested.lock().remove(&key).is_none() {
+                trace!("Dropping an unrequested chunk of '{transmission_id}' from '{peer_ip}'");
+                return Ok(None);
+            }
+            // Ensure the peer does not exceed the maximum number of concurrent transfers.
+            let num_transfers = transfers.keys().filter(|(ip, _)| *ip == peer_ip).count();
+            ensure!(num_transfers < MAX_TRANSFERS_PER_PEER, "Too many concurrent transfers from '{peer_ip}'");
+        }
+        let transfer = transfers.entry(key).or_insert_with(|| Transfer {
+            chunks: vec![None; num_chunks as usize],
+            num_received: 0,
+            last_progress: Instant::now(),
+            num_resumes: 0,
+        });
+        // Ensure the chunk is consistent with the transfer.
+        ensure!(transfer.chunks.len() == num_chunks as usize, "Mismatching number of chunks for the transfer");
+
+        // Store the chunk, ignoring duplicates.
+        if transfer.chunks[index as usize].is_none() {
+            transfer.chunks[index as usize] = Some(bytes);
+            transfer.num_received += 1;
+            transfer.last_progress = Instant::now();
+        }
+        // If the transfer is incomplete, return early.
+        if transfer.num_received < transfer.chunks.len() {
+            return Ok(None);
+        }
+
+        // Reassemble the transmission.
+        let Some(transfer) = transfers.remove(&key) else { unreachable!("The transfer exists") };
+        drop(transfers);
+        let mut bytes = BytesMut::with_capacity(transfer.chunks.iter().flatten().map(|chunk| chunk.len()).sum());
+        transfer.chunks.into_iter().flatten().for_each(|chunk| bytes.extend_from_slice(&chunk));
+        let transmission = Transmission::from_bytes_le(&bytes)?;
+        Ok(Some(TransmissionResponse::new(transmission_id, transmission)))
+    }
+
+    /// Returns the requests for the missing chunks of the transfers that have not progressed within
+    /// `TRANSFER_STALL_TIMEOUT_IN_MS`, and abandons the transfers that have exhausted their resumptions.
+    /// The transmission requests older than `MAX_FETCH_TIMEOUT_IN_MS` are forgotten.
+    pub fn resume_stalled(&self, now: Instant) -> Vec<(SocketAddr, TransmissionChunkRequest<N>)> {
+        let fetch_timeout = Duration::from_millis(MAX_FETCH_TIMEOUT_IN_MS);
+        self.requested.lock().retain(|_, requested_at| now.saturating_duration_since(*requested_at) < fetch_timeout);
+
+        let timeout = Duration::from_millis(TRANSFER_STALL_TIMEOUT_IN_MS);
+        let mut requests = Vec::new();
+        self.transfers.lock().retain(|(peer_ip, transmission_id), transfer| {
+            // Skip the transfers that are progressing.
+            if now.saturating_duration_since(transfer.last_progress) < timeout {
+                return true;
+            }
+            // Abandon the transfer, if it has exhausted its resumptions.
+            if transfer.num_resumes >= MAX_TRANSFER_RESUMES {
+                debug!("Abandoning the transfer of '{transmission_id}' from '{peer_ip}'");
+                return false;
+            }
+            transfer.num_resumes += 1;
+            transfer.last_progress = now;
+            for range in transfer.missing_ranges() {
+                requests.push((*peer_ip, TransmissionChunkRequest::new(*transmission_id, range.start, range.end)));
+            }
+            true
+        });
+        requests
+    }
+
+    /// Removes the transfers from the given peer, and the requests sent to it.
+    pub fn remove_peer(&self, peer_ip: SocketAddr) {
+        self.transfers.lock().retain(|(ip, _), _| *ip != peer_ip);
+        self.requested.lock().retain(|(ip, _), _| *ip != peer_ip);
+    }
+
+    /// Returns the number of transfers in progress.
+    pub fn len(&self) -> usize {
+        self.transfers.lock().len()
+    }
+
+    /// Returns `true` if there are no transfers in progress.
+    pub fn is_empty(&self) -> bool {
+        self.transfers.lock().is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::{
+        ledger::{block::Transaction, narwhal::Data},
+        prelude::{Field, TestRng, Uniform},
+    };
+
+    use std::collections::VecDeque;
+
+    type CurrentNetwork = snarkvm::prelude::MainnetV0;
+
+    /// An in-memory transport that drops the chunks matching the fault, the first time they are sent.
+    struct FaultyTransport {
+        /// The chunks in flight.
+        queue: VecDeque<TransmissionChunk<CurrentNetwork>>,
+        /// The indices of the chunks to drop, the first time they are sent.
+        faults: Vec<u32>,
+    }
+
+    impl FaultyTransport {
+        /// Sends the given chunks, dropping any faulty chunks.
+        fn send(&mut self, chunks: impl IntoIterator<Item = TransmissionChunk<CurrentNetwork>>) {
+            for chunk in chunks {
+                match self.faults.iter().position(|index| *index == chunk.index) {
+                    Some(position) => drop(self.faults.remove(position)),
+                    None => self.queue.push_back(chunk),
+                }
+            }
+        }
+
+        /// Delivers the chunks in flight to the given transfers, returning any reassembled response.
+        fn deliver(
+            &mut self,
+            peer_ip: SocketAddr,
+            transfers: &Transfers<CurrentNetwork>,
+        ) -> Option<TransmissionResponse<CurrentNetwork>> {
+            let mut response = None;
+            while let Some(chunk) = self.queue.pop_front() {
+                if let Some(reassembled) = transfers.insert(peer_ip, chunk).unwrap() {
+                    response = Some(reassembled);
+                }
+            }
+            response
+        }
+    }
+
+    /// Returns a synthetic transaction transmission of the given size, and its transmission ID.
+    fn sample_transmission(
+        num_bytes: usize,
+        rng: &mut TestRng,
+    ) -> (TransmissionID<CurrentNetwork>, Transmission<CurrentNetwork>) {
+        let bytes: Vec<u8> = (0..num_bytes).map(|_| rng.gen()).collect();
+        let data = Data::<Transaction<CurrentNetwork>>::Buffer(Bytes::from(bytes));
+        let checksum = data.to_checksum::<CurrentNetwork>().unwrap();
+        let transaction_id = <CurrentNetwork as Network>::TransactionID::from(Field::rand(rng));
+        (TransmissionID::Transaction(transaction_id, checksum), Transmission::Transaction(data))
+    }
+
+    #[test]
+    fn test_resume_dropped_chunk() {
+        let rng = &mut TestRng::default();
+        let peer_ip = SocketAddr::from(([127, 0, 0, 1], 5000));
+        let transfers = Transfers::<CurrentNetwork>::default();
+
+        // Split a synthetic 4 MiB transmission into chunks.
+        let (transmission_id, transmission) = sample_transmission(4 * 1024 * 1024, rng);
+        let chunks = TransmissionChunk::split(transmission_id, &transmission).unwrap();
+        let num_chunks = chunks.len() as u32;
+        assert_eq!(num_chunks, 17);
+
+        // Send the chunks, dropping a middle chunk.
+        let dropped_index = num_chunks / 2;
+        let mut transport = FaultyTransport { queue: Default::default(), faults: vec![dropped_index] };
+        transport.send(chunks.clone());
+        transfers.expect(peer_ip, transmission_id);
+
+        // Ensure the transfer is incomplete.
+        assert!(transport.deliver(peer_ip, &transfers).is_none());
+        assert_eq!(transfers.len(), 1);
+
+        // Ensure the transfer is not resumed while it is progressing.
+        assert!(transfers.resume_stalled(Instant::now()).is_empty());
+
+        // Ensure the stalled transfer requests only the missing chunk.
+        let stalled = Instant::now() + Duration::from_millis(TRANSFER_STALL_TIMEOUT_IN_MS);
+        let requests = transfers.resume_stalled(stalled);
+        assert_eq!(requests, vec![(
+            peer_ip,
+            TransmissionChunkRequest::new(transmission_id, dropped_index, dropped_index + 1)
+        )]);
+
+        // Serve the requested range, and ensure the transmission is reassembled.
+        let TransmissionChunkRequest { start, end, .. } = requests[0].1;
+        transport.send(chunks.into_iter().filter(|chunk| (start..end).contains(&chunk.index)));
+        let response = transport.deliver(peer_ip, &transfers).unwrap();
+        assert!(transfers.is_empty());
+
+        // Ensure the reassembled transmission matches the original, and its checksum matches the transmission ID.
+        assert_eq!(response.transmission_id, transmission_id);
+        assert_eq!(response.transmission, transmission);
+        let (TransmissionID::Transaction(_, checksum), Transmission::Transaction(data)) =
+            (transmission_id, response.transmission)
+        else {
+            panic!("Expected a transaction transmission")
+        };
+        assert_eq!(data.to_checksum::<CurrentNetwork>().unwrap(), checksum);
+    }
+
+    #[test]
+    fn test_abandon_stalled_transfer() {
+        let rng = &mut TestRng::default();
+        let peer_ip = SocketAddr::from(([127, 0, 0, 1], 5000));
+        let transfers = Transfers::<CurrentNetwork>::default();
+
+        // Deliver only the first chunk of a transmission.
+        let (transmission_id, transmission) = sample_transmission(2 * TRANSMISSION_CHUNK_SIZE, rng);
+        let chunk = TransmissionChunk::split(transmission_id, &transmission).unwrap().remove(0);
+        transfers.expect(peer_ip, transmission_id);
+        assert!(transfers.insert(peer_ip, chunk).unwrap().is_none());
+
+        // Ensure the transfer is resumed a bounded number of times, and then abandoned.
+        let mut now = Instant::now();
+        for _ in 0..MAX_TRANSFER_RESUMES {
+            now += Duration::from_millis(TRANSFER_STALL_TIMEOUT_IN_MS);
+            assert_eq!(transfers.resume_stalled(now).len(), 1);
+        }
+        now += Duration::from_millis(TRANSFER_STALL_TIMEOUT_IN_MS);
+        assert!(transfers.resume_stalled(now).is_empty());
+        assert!(transfers.is_empty());
+    }
+
+    #[test]
+    fn test_reject_invalid_chunks() {
+        let rng = &mut TestRng::default();
+        let peer_ip = SocketAddr::from(([127, 0, 0, 1], 5000));
+        let transfers = Transfers::<CurrentNetwork>::default();
+        let (transmission_id, _) = sample_transmission(0, rng);
+        transfers.expect(peer_ip, transmission_id);
+        let chunk = |index, num_chunks, num_bytes| {
+            TransmissionChunk::new(transmission_id, index, num_chunks, Bytes::from(vec![0u8; num_bytes]))
+        };
+
+        // Ensure out-of-bounds, oversized, and inconsistent chunks are rejected.
+        assert!(transfers.insert(peer_ip, chunk(2, 2, 1)).is_err());
+        assert!(transfers.insert(peer_ip, chunk(0, u32::MAX, TRANSMISSION_CHUNK_SIZE)).is_err());
+        assert!(transfers.insert(peer_ip, chunk(0, 2, 1)).is_err());
+        assert!(transfers.insert(peer_ip, chunk(0, 2, TRANSMISSION_CHUNK_SIZE)).unwrap().is_none());
+        assert!(transfers.insert(peer_ip, chunk(1, 3, 1)).is_err());
+
+        // Ensure the number of concurrent transfers from a peer is bounded.
+        for _ in 1..MAX_TRANSFERS_PER_PEER {
+            let (transmission_id, _) = sample_transmission(0, rng);
+            transfers.expect(peer_ip, transmission_id);
+            let chunk = TransmissionChunk::new(transmission_id, 0, 2, Bytes::from(vec![0u8; TRANSMISSION_CHUNK_SIZE]));
+            assert!(transfers.insert(peer_ip, chunk).unwrap().is_none());
+        }
+        let (transmission_id, _) = sample_transmission(0, rng);
+        transfers.expect(peer_ip, transmission_id);
+        let chunk = TransmissionChunk::new(transmission_id, 0, 2, Bytes::from(vec![0u8; TRANSMISSION_CHUNK_SIZE]));
+        assert!(transfers.insert(peer_ip, chunk).is_err());
+
+        // Ensure the transfers are removed with the peer.
+        transfers.remove_peer(peer_ip);
+        assert!(transfers.is_empty());
+    }
+
+    #[test]
+    fn test_drop_unrequested_chunks() {
+        let rng = &mut TestRng::default();
+        let peer_ip = SocketAddr::from(([127, 0, 0, 1], 5000));
+        let other_ip = SocketAddr::from(([127, 0, 0, 1], 5001));
+        let transfers = Transfers::<CurrentNetwork>::default();
+        let (transmission_id, transmission) = sample_transmission(2 * TRANSMISSION_CHUNK_SIZE, rng);
+        let chunks = TransmissionChunk::split(transmission_id, &transmission).unwrap();
+
+        // Ensure the chunks of a transmission that was not requested are dropped, without being buffered.
+        for chunk in chunks.iter().cloned() {
+            assert!(transfers.insert(peer_ip, chunk).unwrap().is_none());
+        }
+        assert!(transfers.is_empty());
+
+        // Ensure the chunks of a transmission requested from another peer are dropped.
+        transfers.expect(other_ip, transmission_id);
+        assert!(transfers.insert(peer_ip, chunks[0].clone()).unwrap().is_none());
+        assert!(transfers.is_empty());
+
+        // Ensure the requests expire.
+        let expired = Instant::now() + Duration::from_millis(MAX_FETCH_TIMEOUT_IN_MS);
+        assert!(transfers.resume_stalled(expired).is_empty());
+        assert!(transfers.insert(other_ip, chunks[0].clone()).unwrap().is_none());
+        assert!(transfers.is_empty());
+
+        // Ensure the chunks of a requested transmission are reassembled.
+        transfers.expect(peer_ip, transmission_id);
+        assert!(transfers.insert(peer_ip, chunks[0].clone()).unwrap().is_none());
+        assert_eq!(transfers.len(), 1);
+        let response = transfers.insert(peer_ip, chunks[1].clone()).unwrap().unwrap();
+        assert_eq!(response.transmission, transmission);
+    }
+
+    #[test]
+    fn test_split_serializes_once() {
+        let rng = &mut TestRng::default();
+        let transfers = Transfers::<CurrentNetwork>::default();
+
+        // Ensure the chunks of a large transmission share the cached serialized bytes.
+        let (transmission_id, transmission) = sample_transmission(2 * TRANSMISSION_CHUNK_SIZE, rng);
+        let first = transfers.split(transmission_id, &transmission).unwrap();
+        let second = transfers.split(transmission_id, &transmission).unwrap();
+        assert_eq!(first, second);
+        assert_eq!(first[0].bytes.as_ptr(), second[0].bytes.as_ptr());
+
+        // Ensure the transmissions sent in full are not cached.
+        let (transmission_id, transmission) = sample_transmission(TRANSMISSION_CHUNK_SIZE / 2, rng);
+        let first = transfers.split(transmission_id, &transmission).unwrap();
+        let second = transfers.split(transmission_id, &transmission).unwrap();
+        assert_ne!(first[0].bytes.as_ptr(), second[0].bytes.as_ptr());
+
+        // Ensure the cache is bounded.
+        for _ in 0..MAX_SERIALIZED_TRANSMISSIONS {
+            let (transmission_id, transmission) = sample_transmission(2 * TRANSMISSION_CHUNK_SIZE, rng);
+            transfers.split(transmission_id, &transmission).unwrap();
+        }
+        assert_eq!(transfers.serialized.lock().len(), MAX_SERIALIZED_TRANSMISSIONS);
+    }
+}
@@ -0,0 +1,285 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::{Result, bail};
+use indexmap::IndexSet;
+use parking_lot::{Mutex, RwLock};
+use std::{collections::HashMap, fmt, net::SocketAddr, str::FromStr, sync::Arc};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// The interval in seconds at which a peer outside the committee is granted a `ValidatorsRequest`.
+pub const VALIDATORS_REQUEST_INTERVAL_IN_SECS: i64 = 30; // seconds
+/// The maximum number of excess `ValidatorsRequest`s a peer may send within an interval, before it is considered spam.
+pub const MAX_EXCESS_VALIDATORS_REQUESTS: u32 = 10;
+/// The maximum number of `ValidatorsResponse`s built concurrently for peers outside the committee.
+pub const MAX_CONCURRENT_VALIDATORS_RESPONSES: usize = 4;
+
+/// The level of detail of the `ValidatorsResponse` sent to peers outside the committee.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ValidatorsResponseMode {
+    /// Respond with the connected validators.
+    #[default]
+    Full,
+    /// Respond with the connected trusted validators only.
+    Bootstrap,
+    /// Do not respond.
+    Refuse,
+}
+
+impl ValidatorsResponseMode {
+    /// Returns the validators to respond with, out of the given connected validators.
+    pub fn filter(
+        &self,
+        mut connected_peers: Vec<SocketAddr>,
+        trusted_validators: &IndexSet<SocketAddr>,
+    ) -> Vec<SocketAddr> {
+        match self {
+            Self::Full => connected_peers,
+            Self::Bootstrap => {
+                connected_peers.retain(|peer_ip| trusted_validators.contains(peer_ip));
+                connected_peers
+            }
+            Self::Refuse => vec![],
+        }
+    }
+}
+
+impl FromStr for ValidatorsResponseMode {
+    type Err = anyhow::Error;
+
+    fn from_str(mode: &str) -> Result<Self> {
+        match mode {
+            "full" => Ok(Self::Full),
+            "bootstrap" => Ok(Self::Bootstrap),
+            "refuse" => Ok(Self::Refuse),
+            _ => bail!("Invalid validators response mode '{mode}' (expected 'full', 'bootstrap', or 'refuse')"),
+        }
+    }
+}
+
+impl fmt::Display for ValidatorsResponseMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Full => write!(f, "full"),
+            Self::Bootstrap => write!(f, "bootstrap"),
+            Self::Refuse => write!(f, "refuse"),
+        }
+    }
+}
+
+/// The outcome of an inbound `ValidatorsRequest`.
+#[derive(Debug)]
+pub enum ValidatorsRequestOutcome {
+    /// Respond in the given mode, holding the permit (if any) until the response is sent.
+    Respond { mode: ValidatorsResponseMode, permit: Option<OwnedSemaphorePermit> },
+    /// Silently drop the request.
+    Drop,
+    /// Drop the request, as the peer has grossly exceeded the rate limit.
+    Spam,
+}
+
+/// The admission control for inbound `ValidatorsRequest`s.
+///
+/// Committee members are always answered in full. Any other peer is rate limited,
+/// answered in the configured mode, and shares a bounded number of concurrent responses.
+pub struct ValidatorsRequests {
+    /// The level of detail of the responses to peers outside the committee.
+    mode: RwLock<ValidatorsResponseMode>,
+    /// The map of peer IPs to the UNIX timestamp of their last granted request,
+    /// and the number of excess requests since.
+    peers: Mutex<HashMap<SocketAddr, (i64, u32)>>,
+    /// The permits for the concurrent responses.
+    responses: Arc<Semaphore>,
+}
+
+impl Default for ValidatorsRequests {
+    /// Initializes the admission control, responding in full.
+    fn default() -> Self {
+        Self {
+            mode: Default::default(),
+            peers: Default::default(),
+            responses: Arc::new(Semaphore::new(MAX_CONCURRENT_VALIDATORS_RESPONSES)),
+        }
+    }
+}
+
+impl ValidatorsRequests {
+    /// Returns the level of detail of the responses to peers outside the committee.
+    pub fn mode(&self) -> ValidatorsResponseMode {
+        *self.mode.read()
+    }
+
+    /// Sets the level of detail of the responses to peers outside the committee.
+    pub fn set_mode(&self, mode: ValidatorsResponseMode) {
+        *self.mode.write() = mode;
+    }
+
+    /// Returns the outcome of a `ValidatorsRequest` from the given peer, at the given UNIX timestamp.
+    pub fn process(&self, peer_ip: SocketAddr, is_committee_member: bool, now: i64) -> ValidatorsRequestOutcome {
+        // Committee members are not limited.
+        if is_committee_member {
+            return ValidatorsRequestOutcome::Respond { mode: ValidatorsResponseMode::Full, permit: None };
+        }
+
+        // Ensure the peer is within its rate limit.
+        {
+            let mut peers = self.peers.lock();
+            // Remove the peers whose last granted request has expired, so that the map remains bounded.
+            peers
+                .retain(|_, (last_granted, _)| now.saturating_sub(*last_granted) < VALIDATORS_REQUEST_INTERVAL_IN_SECS);
+            let (last_granted, num_excess) = peers.entry(peer_ip).or_insert((i64::MIN, 0));
+            if now.saturating_sub(*last_granted) < VALIDATORS_REQUEST_INTERVAL_IN_SECS {
+                *num_excess += 1;
+                return match *num_excess > MAX_EXCESS_VALIDATORS_REQUESTS {
+                    true => ValidatorsRequestOutcome::Spam,
+                    false => ValidatorsRequestOutcome::Drop,
+                };
+            }
+            (*last_granted, *num_excess) = (now, 0);
+        }
+
+        // Ensure the response is permitted in the configured mode, and within the concurrency limit.
+        match self.mode() {
+            ValidatorsResponseMode::Refuse => ValidatorsRequestOutcome::Drop,
+            mode => match self.responses.clone().try_acquire_owned() {
+                Ok(permit) => ValidatorsRequestOutcome::Respond { mode, permit: Some(permit) },
+                Err(_) => ValidatorsRequestOutcome::Drop,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the sample peer IP with the given index.
+    fn sample_peer_ip(index: u16) -> SocketAddr {
+        SocketAddr::from(([127, 0, 0, 1], 5000 + index))
+    }
+
+    /// Returns the number of each outcome, out of the given number of requests from the given peer outside the committee.
+    fn hammer(requests: &ValidatorsRequests, peer_ip: SocketAddr, num_requests: u32, now: i64) -> (u32, u32, u32) {
+        let (mut num_responses, mut num_dropped, mut num_spam) = (0, 0, 0);
+        for _ in 0..num_requests {
+            match requests.process(peer_ip, false, now) {
+                ValidatorsRequestOutcome::Respond { .. } => num_responses += 1,
+                ValidatorsRequestOutcome::Drop => num_dropped += 1,
+                ValidatorsRequestOutcome::Spam => num_spam += 1,
+            }
+        }
+        (num_responses, num_dropped, num_spam)
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let requests = ValidatorsRequests::default();
+        let peer_ip = sample_peer_ip(0);
+
+        // Ensure only the first request is answered, and the excess requests are dropped, and then flagged as spam.
+        let num_requests = 1 + MAX_EXCESS_VALIDATORS_REQUESTS + 5;
+        assert_eq!(hammer(&requests, peer_ip, num_requests, 0), (1, MAX_EXCESS_VALIDATORS_REQUESTS, 5));
+        // Ensure the other peers are not affected.
+        assert_eq!(hammer(&requests, sample_peer_ip(1), 1, 0), (1, 0, 0));
+
+        // Ensure the peer is granted a request once the interval has elapsed.
+        assert_eq!(hammer(&requests, peer_ip, 2, VALIDATORS_REQUEST_INTERVAL_IN_SECS - 1), (0, 0, 2));
+        assert_eq!(hammer(&requests, peer_ip, 2, VALIDATORS_REQUEST_INTERVAL_IN_SECS), (1, 1, 0));
+
+        // Ensure the expired peers are removed.
+        hammer(&requests, sample_peer_ip(2), 1, 3 * VALIDATORS_REQUEST_INTERVAL_IN_SECS);
+        assert_eq!(requests.peers.lock().len(), 1);
+    }
+
+    #[test]
+    fn test_committee_members_are_not_limited() {
+        let requests = ValidatorsRequests::default();
+        requests.set_mode(ValidatorsResponseMode::Refuse);
+
+        // Ensure committee members are always answered in full, regardless of the mode and the concurrency limit.
+        let permits: Vec<_> = (0..MAX_CONCURRENT_VALIDATORS_RESPONSES)
+            .map(|_| requests.responses.clone().try_acquire_owned().unwrap())
+            .collect();
+        for _ in 0..100 {
+            match requests.process(sample_peer_ip(0), true, 0) {
+                ValidatorsRequestOutcome::Respond { mode, permit } => {
+                    assert_eq!(mode, ValidatorsResponseMode::Full);
+                    assert!(permit.is_none());
+                }
+                outcome => panic!("Unexpected outcome {outcome:?}"),
+            }
+        }
+        drop(permits);
+    }
+
+    #[test]
+    fn test_modes() {
+        let requests = ValidatorsRequests::default();
+        let connected_peers: Vec<_> = (0..4).map(sample_peer_ip).collect();
+        let trusted_validators: IndexSet<_> = [sample_peer_ip(1), sample_peer_ip(3), sample_peer_ip(9)].into();
+
+        for (i, mode) in
+            [ValidatorsResponseMode::Full, ValidatorsResponseMode::Bootstrap, ValidatorsResponseMode::Refuse]
+                .into_iter()
+                .enumerate()
+        {
+            requests.set_mode(mode);
+            match requests.process(sample_peer_ip(i as u16), false, 0) {
+                ValidatorsRequestOutcome::Respond { mode: response_mode, permit } => {
+                    assert_eq!(response_mode, mode);
+                    assert!(permit.is_some());
+                }
+                ValidatorsRequestOutcome::Drop => assert_eq!(mode, ValidatorsResponseMode::Refuse),
+                ValidatorsRequestOutcome::Spam => panic!("Unexpected spam outcome"),
+            }
+        }
+
+        // Ensure the validators are filtered according to the mode.
+        assert_eq!(ValidatorsResponseMode::Full.filter(connected_peers.clone(), &trusted_validators), connected_peers);
+        assert_eq!(ValidatorsResponseMode::Bootstrap.filter(connected_peers.clone(), &trusted_validators), vec![
+            sample_peer_ip(1),
+            sample_peer_ip(3)
+        ]);
+        assert!(ValidatorsResponseMode::Refuse.filter(connected_peers, &trusted_validators).is_empty());
+
+        // Ensure the modes round-trip through their string representation.
+        for mode in [ValidatorsResponseMode::Full, ValidatorsResponseMode::Bootstrap, ValidatorsResponseMode::Refuse] {
+            assert_eq!(mode.to_string().parse::<ValidatorsResponseMode>().unwrap(), mode);
+        }
+        assert!("all".parse::<ValidatorsResponseMode>().is_err());
+    }
+
+    #[test]
+    fn test_concurrency_limit() {
+        let requests = ValidatorsRequests::default();
+
+        // Hold the permits of the maximum number of concurrent responses.
+        let mut permits = vec![];
+        for i in 0..MAX_CONCURRENT_VALIDATORS_RESPONSES as u16 {
+            match requests.process(sample_peer_ip(i), false, 0) {
+                ValidatorsRequestOutcome::Respond { permit, .. } => permits.push(permit),
+                outcome => panic!("Unexpected outcome {outcome:?}"),
+            }
+        }
+        // Ensure the next request is dropped.
+        let peer_ip = sample_peer_ip(MAX_CONCURRENT_VALIDATORS_RESPONSES as u16);
+        assert!(matches!(requests.process(peer_ip, false, 0), ValidatorsRequestOutcome::Drop));
+
+        // Ensure the requests are answered again once a response is sent.
+        permits.pop();
+        let peer_ip = sample_peer_ip(MAX_CONCURRENT_VALIDATORS_RESPONSES as u16 + 1);
+        assert!(matches!(requests.process(peer_ip, false, 0), ValidatorsRequestOutcome::Respond { .. }));
+    }
+}
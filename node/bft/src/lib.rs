@@ -66,6 +66,10 @@ pub const MAX_WORKERS: u8 = 1; // worker(s)
 pub const PRIMARY_PING_IN_MS: u64 = 2 * MAX_BATCH_DELAY_IN_MS; // ms
 /// The frequency at which each worker broadcasts a ping to every other node.
 pub const WORKER_PING_IN_MS: u64 = 4 * MAX_BATCH_DELAY_IN_MS; // ms
+/// The frequency at which each primary audits its storage against the certificates in memory.
+pub const STORAGE_AUDIT_INTERVAL_IN_SECS: u64 = 300; // seconds
+/// The maximum number of entries audited at once, before yielding to the other tasks.
+pub const STORAGE_AUDIT_BATCH_SIZE: usize = 256; // entries
 
 /// A helper macro to spawn a blocking task.
 #[macro_export]
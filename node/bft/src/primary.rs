@@ -19,19 +19,28 @@ use crate::{
     MAX_WORKERS,
     MIN_BATCH_DELAY_IN_SECS,
     PRIMARY_PING_IN_MS,
+    STORAGE_AUDIT_BATCH_SIZE,
+    STORAGE_AUDIT_INTERVAL_IN_SECS,
     Sync,
+    TransmissionNotServed,
     Transport,
     WORKER_PING_IN_MS,
     Worker,
+    WorkerQueueStats,
     events::{BatchPropose, BatchSignature, Event},
     helpers::{
         BFTSender,
+        FetchOutcome,
         PrimaryReceiver,
         PrimarySender,
         Proposal,
         ProposalCache,
+        ProposalOutcomes,
+        ProposerAvailability,
+        SERVED_LATE_IN_MS,
         SignedProposals,
         Storage,
+        StorageBackpressure,
         assign_to_worker,
         assign_to_workers,
         fmt_id,
@@ -44,7 +53,7 @@ use crate::{
 use snarkos_account::Account;
 use snarkos_node_bft_events::PrimaryPing;
 use snarkos_node_bft_ledger_service::LedgerService;
-use snarkos_node_sync::DUMMY_SELF_IP;
+use snarkos_node_sync::{DUMMY_SELF_IP, SyncProgress};
 use snarkvm::{
     console::{
         prelude::*,
@@ -67,11 +76,14 @@ use std::{
     collections::{HashMap, HashSet},
     future::Future,
     net::SocketAddr,
-    sync::Arc,
-    time::Duration,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
 };
 use tokio::{
-    sync::{Mutex as TMutex, OnceCell},
+    sync::{Mutex as TMutex, OnceCell, watch},
     task::JoinHandle,
 };
 
@@ -98,6 +110,18 @@ pub struct Primary<N: Network> {
     latest_proposed_batch_timestamp: Arc<RwLock<i64>>,
     /// The recently-signed batch proposals.
     signed_proposals: Arc<RwLock<SignedProposals<N>>>,
+    /// The round and signers of the most recent certificate formed from our own batch proposal.
+    latest_certificate_signers: Arc<RwLock<Option<(u64, HashSet<Address<N>>)>>>,
+    /// The tracker of whether the validators serve the transmissions of their own batch proposals.
+    proposer_availability: Arc<ProposerAvailability<N>>,
+    /// The tracker of whether the most recent batch proposals of this node reached the quorum threshold.
+    proposal_outcomes: Arc<ProposalOutcomes>,
+    /// The tracker of the storage advance latencies, which throttles new proposals while the storage is slow.
+    storage_backpressure: Arc<StorageBackpressure>,
+    /// The number of transmissions in the batches from peers that were already held locally, and that were fetched.
+    transmission_overlap: Arc<(AtomicU64, AtomicU64)>,
+    /// If the flag is set, the primary does not propose or sign while another validator is using its account.
+    pause_on_duplicate_identity: Arc<AtomicBool>,
     /// The spawned handles.
     handles: Arc<Mutex<Vec<JoinHandle<()>>>>,
     /// The lock for propose_batch.
@@ -133,6 +157,12 @@ impl<N: Network> Primary<N> {
             proposed_batch: Default::default(),
             latest_proposed_batch_timestamp: Default::default(),
             signed_proposals: Default::default(),
+            latest_certificate_signers: Default::default(),
+            proposer_availability: Default::default(),
+            proposal_outcomes: Default::default(),
+            storage_backpressure: Default::default(),
+            transmission_overlap: Default::default(),
+            pause_on_duplicate_identity: Default::default(),
             handles: Default::default(),
             propose_lock: Default::default(),
         })
@@ -246,6 +276,16 @@ impl<N: Network> Primary<N> {
         self.sync.is_synced()
     }
 
+    /// Subscribes to the sync status of the primary, which is updated periodically.
+    pub fn subscribe_is_synced(&self) -> watch::Receiver<bool> {
+        self.sync.subscribe_is_synced()
+    }
+
+    /// Returns the progress of the block sync of the primary.
+    pub fn sync_progress(&self) -> SyncProgress {
+        self.sync.progress()
+    }
+
     /// Returns the gateway.
     pub const fn gateway(&self) -> &Gateway<N> {
         &self.gateway
@@ -275,6 +315,32 @@ impl<N: Network> Primary<N> {
     pub fn proposed_batch(&self) -> &Arc<ProposedBatch<N>> {
         &self.proposed_batch
     }
+
+    /// Returns the tracker of the storage advance latencies.
+    pub const fn storage_backpressure(&self) -> &Arc<StorageBackpressure> {
+        &self.storage_backpressure
+    }
+
+    /// Returns the tracker of whether the most recent batch proposals of this node reached the quorum threshold.
+    pub const fn proposal_outcomes(&self) -> &Arc<ProposalOutcomes> {
+        &self.proposal_outcomes
+    }
+
+    /// Returns `true` if the primary refuses to propose and sign batches while another validator is using its account.
+    pub fn pause_on_duplicate_identity(&self) -> bool {
+        self.pause_on_duplicate_identity.load(Ordering::Relaxed)
+    }
+
+    /// Sets whether the primary refuses to propose and sign batches while another validator is using its account.
+    pub fn set_pause_on_duplicate_identity(&self, pause: bool) {
+        self.pause_on_duplicate_identity.store(pause, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if the primary is refusing to propose and sign batches,
+    /// as another validator is using its account.
+    pub fn is_paused_on_duplicate_identity(&self) -> bool {
+        self.pause_on_duplicate_identity() && self.gateway.is_duplicate_identity()
+    }
 }
 
 impl<N: Network> Primary<N> {
@@ -297,6 +363,17 @@ impl<N: Network> Primary<N> {
     pub fn num_unconfirmed_transactions(&self) -> usize {
         self.workers.iter().map(|worker| worker.num_transactions()).sum()
     }
+
+    /// Returns a snapshot of the queue statistics of each worker.
+    pub fn worker_queue_stats(&self) -> Vec<WorkerQueueStats> {
+        self.workers.iter().map(|worker| worker.queue_stats()).collect()
+    }
+
+    /// Returns the round and signers of the most recent certificate formed from our own batch proposal, if any.
+    /// Note: The signers include this node, as the author of the batch.
+    pub fn latest_certificate_signers(&self) -> Option<(u64, HashSet<Address<N>>)> {
+        self.latest_certificate_signers.read().clone()
+    }
 }
 
 impl<N: Network> Primary<N> {
@@ -310,6 +387,33 @@ impl<N: Network> Primary<N> {
         self.workers.iter().flat_map(|worker| worker.transmissions())
     }
 
+    /// Returns the worker transmission with the given ID, if it is in a ready queue.
+    pub fn worker_transmission(&self, transmission_id: TransmissionID<N>) -> Option<Transmission<N>> {
+        self.workers.iter().find_map(|worker| worker.ready_transmission(transmission_id))
+    }
+
+    /// Returns the ID, the size in bytes (if known), and the insertion timestamp of each worker transmission.
+    pub fn worker_transmission_summaries(&self) -> Vec<(TransmissionID<N>, Option<usize>, i64)> {
+        self.workers.iter().flat_map(|worker| worker.transmission_summaries()).collect()
+    }
+
+    /// Returns up to the given number of worker transmissions that match the filter.
+    pub fn worker_transmissions_matching(
+        &self,
+        limit: usize,
+        filter: impl Fn(&TransmissionID<N>) -> bool,
+    ) -> IndexMap<TransmissionID<N>, Transmission<N>> {
+        let mut transmissions = IndexMap::new();
+        for worker in self.workers.iter() {
+            let remaining = limit.saturating_sub(transmissions.len());
+            if remaining == 0 {
+                break;
+            }
+            transmissions.extend(worker.transmissions_matching(remaining, &filter));
+        }
+        transmissions
+    }
+
     /// Returns the worker solutions.
     pub fn worker_solutions(&self) -> impl '_ + Iterator<Item = (SolutionID<N>, Data<Solution<N>>)> {
         self.workers.iter().flat_map(|worker| worker.solutions())
@@ -441,6 +545,18 @@ impl<N: Network> Primary<N> {
             return Ok(());
         }
 
+        // Ensure the primary does not initiate a new proposal while the storage is slow.
+        // Note: The committed subdags are still written; only new batches are throttled.
+        if self.storage_backpressure.is_throttled() {
+            debug!("Primary is safely skipping a batch proposal - {}", "(storage is slow)".dimmed());
+            return Ok(());
+        }
+        // Ensure the primary does not propose while another validator is using its account.
+        if self.is_paused_on_duplicate_identity() {
+            warn!("Primary is skipping a batch proposal - {}", "(another validator is using this account)".dimmed());
+            return Ok(());
+        }
+
         // Retrieve the committee to check against.
         let committee_lookback = self.ledger.get_committee_lookback_for_round(round)?;
         // Check if the primary is connected to enough validators to reach quorum threshold.
@@ -574,6 +690,8 @@ impl<N: Network> Primary<N> {
                 }
             }
         }
+        // Order the transmissions by ID, so the proposal does not depend on the arrival order of this validator.
+        transmissions.sort_by_cached_key(|id, _| id.to_bytes_le().unwrap_or_default());
 
         // Determine the current timestamp.
         let current_timestamp = now();
@@ -755,6 +873,24 @@ impl<N: Network> Primary<N> {
         // Inserts the missing transmissions into the workers.
         self.insert_missing_transmissions_into_workers(peer_ip, missing_transmissions.into_iter())?;
 
+        // Ensure the batch author serves the transmissions of its proposals, otherwise decline to sign the batch.
+        if self.proposer_availability.is_withholding(&batch_author) {
+            debug!(
+                "Primary is declining to sign a batch proposal from '{peer_ip}' - {}",
+                format!("the author ({batch_author}) withholds the transmissions of its proposals").dimmed()
+            );
+            return Ok(());
+        }
+
+        // Ensure the primary does not sign while another validator is using its account.
+        if self.is_paused_on_duplicate_identity() {
+            warn!(
+                "Primary is declining to sign a batch proposal from '{peer_ip}' - {}",
+                "(another validator is using this account)".dimmed()
+            );
+            return Ok(());
+        }
+
         /* Proceeding to sign the batch. */
 
         // Retrieve the batch ID.
@@ -1102,6 +1238,54 @@ impl<N: Network> Primary<N> {
             });
         }
 
+        // Start the worker queue metrics.
+        #[cfg(feature = "metrics")]
+        {
+            let self_ = self.clone();
+            self.spawn(async move {
+                loop {
+                    tokio::time::sleep(Duration::from_millis(WORKER_PING_IN_MS)).await;
+                    // Update the queue metrics of each worker.
+                    let current_timestamp = now();
+                    for stats in self_.worker_queue_stats() {
+                        let worker_id = stats.worker_id.to_string();
+                        let oldest_age =
+                            stats.oldest_timestamp.map_or(0, |timestamp| current_timestamp.saturating_sub(timestamp));
+                        metrics::gauge_label(
+                            metrics::bft::WORKER_TRANSMISSIONS,
+                            "worker_id",
+                            worker_id.clone(),
+                            stats.num_transmissions as f64,
+                        );
+                        metrics::gauge_label(
+                            metrics::bft::WORKER_PENDING,
+                            "worker_id",
+                            worker_id.clone(),
+                            stats.num_pending as f64,
+                        );
+                        metrics::gauge_label(
+                            metrics::bft::WORKER_BYTES,
+                            "worker_id",
+                            worker_id.clone(),
+                            stats.num_bytes as f64,
+                        );
+                        metrics::gauge_label(
+                            metrics::bft::WORKER_OLDEST_AGE,
+                            "worker_id",
+                            worker_id.clone(),
+                            oldest_age as f64,
+                        );
+                        metrics::gauge_label(
+                            metrics::bft::WORKER_RECENTLY_DRAINED,
+                            "worker_id",
+                            worker_id,
+                            stats.num_recently_drained as f64,
+                        );
+                    }
+                }
+            });
+        }
+
         // Start the batch proposer.
         let self_ = self.clone();
         self.spawn(async move {
@@ -1233,6 +1417,20 @@ impl<N: Network> Primary<N> {
             }
         });
 
+        // Periodically audit the storage against the certificates in memory, starting right away.
+        // Note: This repairs the divergence left behind by a crash, and any that occurs while running.
+        let self_ = self.clone();
+        self.spawn(async move {
+            loop {
+                let self__ = self_.clone();
+                let _ = spawn_blocking!({
+                    self__.audit_storage();
+                    Ok(())
+                });
+                tokio::time::sleep(Duration::from_secs(STORAGE_AUDIT_INTERVAL_IN_SECS)).await;
+            }
+        });
+
         // Process the unconfirmed solutions.
         let self_ = self.clone();
         self.spawn(async move {
@@ -1300,6 +1498,7 @@ impl<N: Network> Primary<N> {
             let proposal = self.proposed_batch.write().take();
             if let Some(proposal) = proposal {
                 debug!("Cleared expired proposal for round {}", proposal.round());
+                self.proposal_outcomes.record(proposal.round(), false);
                 self.reinsert_transmissions_into_workers(proposal.into_transmissions())?;
             }
         }
@@ -1411,6 +1610,10 @@ impl<N: Network> Primary<N> {
     async fn store_and_broadcast_certificate(&self, proposal: &Proposal<N>, committee: &Committee<N>) -> Result<()> {
         // Create the batch certificate and transmissions.
         let (certificate, transmissions) = tokio::task::block_in_place(|| proposal.to_certificate(committee))?;
+        // Record the signers of the certificate, including the author.
+        let signers = certificate.signatures().map(|signature| signature.to_address()).chain([certificate.author()]);
+        *self.latest_certificate_signers.write() = Some((certificate.round(), signers.collect()));
+        self.proposal_outcomes.record(certificate.round(), true);
         // Convert the transmissions into a HashMap.
         // Note: Do not change the `Proposal` to use a HashMap. The ordering there is necessary for safety.
         let transmissions = transmissions.into_iter().collect::<HashMap<_, _>>();
@@ -1459,6 +1662,44 @@ impl<N: Network> Primary<N> {
         })
     }
 
+    /// Audits the storage against the certificates in memory, recovering the missing transmissions
+    /// from the workers or the ledger, and reports the repairs.
+    fn audit_storage(&self) {
+        let audit = self.storage.audit_transmissions(STORAGE_AUDIT_BATCH_SIZE, |transmission_id| {
+            // Retrieve the transmission from the workers.
+            if let Some(transmission) = self.workers.iter().find_map(|worker| worker.get_transmission(transmission_id))
+            {
+                return Some(transmission);
+            }
+            // Otherwise, retrieve the transmission from the ledger.
+            match transmission_id {
+                TransmissionID::Ratification => None,
+                TransmissionID::Solution(solution_id, _) => self.ledger.get_solution(&solution_id).ok().map(Into::into),
+                TransmissionID::Transaction(transaction_id, _) => {
+                    self.ledger.get_unconfirmed_transaction(transaction_id).ok().map(Into::into)
+                }
+            }
+        });
+        match audit.is_consistent() {
+            true => debug!("The storage is consistent with the certificates in memory"),
+            false => warn!("Repaired the storage against the certificates in memory - {audit}"),
+        }
+        #[cfg(feature = "metrics")]
+        for (repair, amount) in [
+            ("orphan_released", audit.num_orphans_released),
+            ("relinked", audit.num_relinked),
+            ("rehydrated", audit.num_rehydrated),
+            ("unrecoverable", audit.num_unrecoverable),
+        ] {
+            metrics::add_counter_label(
+                metrics::bft::STORAGE_AUDIT_REPAIRS,
+                "repair",
+                repair.to_string(),
+                amount as u64,
+            );
+        }
+    }
+
     /// Recursively stores a given batch certificate, after ensuring:
     ///   - Ensure the round matches the committee round.
     ///   - Ensure the address is a member of the committee.
@@ -1605,6 +1846,8 @@ impl<N: Network> Primary<N> {
 
         // Retrieve the number of workers.
         let num_workers = self.num_workers();
+        // Initialize a counter for the transmissions that are not held locally.
+        let mut num_fetched = 0;
         // Iterate through the transmission IDs.
         for transmission_id in batch_header.transmission_ids() {
             // If the transmission does not exist in storage, proceed to fetch the transmission.
@@ -1615,24 +1858,104 @@ impl<N: Network> Primary<N> {
                 };
                 // Retrieve the worker.
                 let Some(worker) = workers.get(worker_id as usize) else { bail!("Unable to find worker {worker_id}") };
+                // Count the transmission if the worker must request it from the peer.
+                if !worker.contains_transmission(*transmission_id) {
+                    num_fetched += 1;
+                }
                 // Push the callback onto the list.
                 fetch_transmissions.push(worker.get_or_fetch_transmission(peer_ip, *transmission_id));
             }
         }
+        // Record the overlap of the batch with the transmissions held locally.
+        self.record_transmission_overlap(batch_header, num_fetched);
 
         // Initialize a set for the transmissions.
         let mut transmissions = HashMap::with_capacity(fetch_transmissions.len());
         // Wait for all of the transmissions to be fetched.
+        let start = Instant::now();
         while let Some(result) = fetch_transmissions.next().await {
             // Retrieve the transmission.
-            let (transmission_id, transmission) = result?;
+            let (transmission_id, transmission) = match result {
+                Ok(transmission) => transmission,
+                Err(error) => {
+                    // Only count the failures of the peer, rather than the local ones, e.g. to send the request.
+                    if error.downcast_ref::<TransmissionNotServed>().is_some() {
+                        self.record_transmission_fetch(peer_ip, batch_header, FetchOutcome::NeverServed);
+                    }
+                    return Err(error);
+                }
+            };
             // Insert the transmission into the set.
             transmissions.insert(transmission_id, transmission);
         }
+        // Record whether the transmissions were served in time.
+        let outcome = match start.elapsed() > Duration::from_millis(SERVED_LATE_IN_MS) {
+            true => FetchOutcome::ServedLate,
+            false => FetchOutcome::Served,
+        };
+        self.record_transmission_fetch(peer_ip, batch_header, outcome);
         // Return the transmissions.
         Ok(transmissions)
     }
 
+    /// Returns the number of transmissions in the batches from peers that were held locally, and that were fetched.
+    pub fn transmission_overlap(&self) -> (u64, u64) {
+        (self.transmission_overlap.0.load(Ordering::Relaxed), self.transmission_overlap.1.load(Ordering::Relaxed))
+    }
+
+    /// Records the number of transmissions of the given batch header that were held locally, and that were fetched.
+    /// A batch is only recorded on its first receipt: the certificate of a signed proposal is skipped, as the
+    /// transmissions fetched for the proposal are then held locally.
+    fn record_transmission_overlap(&self, batch_header: &BatchHeader<N>, num_fetched: u64) {
+        let is_signed =
+            self.signed_proposals.read().get(&batch_header.author()).is_some_and(|(round, batch_id, _)| {
+                *round == batch_header.round() && *batch_id == batch_header.batch_id()
+            });
+        if is_signed {
+            return;
+        }
+        let num_held = (batch_header.transmission_ids().len() as u64).saturating_sub(num_fetched);
+        self.transmission_overlap.0.fetch_add(num_held, Ordering::Relaxed);
+        self.transmission_overlap.1.fetch_add(num_fetched, Ordering::Relaxed);
+        #[cfg(feature = "metrics")]
+        {
+            let (total_held, total_fetched) = self.transmission_overlap();
+            metrics::gauge(metrics::bft::TRANSMISSIONS_HELD, total_held as f64);
+            metrics::gauge(metrics::bft::TRANSMISSIONS_FETCHED, total_fetched as f64);
+        }
+    }
+
+    /// Records the outcome of fetching the missing transmissions of the given batch header from the given peer.
+    /// The outcome is only recorded if the peer is the batch author, as it is responsible for serving them.
+    fn record_transmission_fetch(&self, peer_ip: SocketAddr, batch_header: &BatchHeader<N>, outcome: FetchOutcome) {
+        let author = batch_header.author();
+        if self.gateway.resolver().get_address(peer_ip) != Some(author) {
+            return;
+        }
+        // Feed the withheld proposal into the score of the validator.
+        if outcome == FetchOutcome::NeverServed {
+            self.gateway.record_withheld_proposal(&author);
+        }
+        match self.proposer_availability.record(author, batch_header.round(), outcome) {
+            Some(true) => {
+                let (served, late, never) = self.proposer_availability.counts(&author);
+                let reason = format!(
+                    "Withheld the transmissions of {never} of its {} recent batch proposals",
+                    served + late + never
+                );
+                warn!("{}", format!("Declining to sign the batch proposals of '{author}' - {reason}").bold().red());
+                // Record the decision with the connection of the validator.
+                self.gateway.record_peer_error(peer_ip, reason);
+                self.gateway.set_declined_proposer(&author, true);
+            }
+            Some(false) => {
+                info!("Resuming signing the batch proposals of '{author}' - it serves its transmissions");
+                self.gateway.set_declined_proposer(&author, false);
+            }
+            None => (),
+        }
+    }
+
     /// Fetches any missing previous certificates for the specified batch header from the specified peer.
     async fn fetch_missing_previous_certificates(
         &self,
@@ -1736,6 +2059,12 @@ impl<N: Network> Primary<N> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::helpers::{
+        MIN_PROPOSALS_FOR_AVAILABILITY,
+        NUM_RECOVERED_STORAGE_ADVANCES,
+        STORAGE_RECOVERED_THRESHOLD_IN_MS,
+        STORAGE_SLOW_THRESHOLD_IN_MS,
+    };
     use snarkos_node_bft_ledger_service::MockLedgerService;
     use snarkos_node_bft_storage_service::BFTMemoryService;
     use snarkvm::{
@@ -2007,6 +2336,141 @@ mod tests {
         assert!(primary.proposed_batch.read().is_some());
     }
 
+    #[tokio::test]
+    async fn test_propose_batch_while_storage_is_slow() {
+        let mut rng = TestRng::default();
+        let (primary, _) = primary_without_handlers(&mut rng).await;
+
+        // Generate a solution and a transaction.
+        let (solution_id, solution) = sample_unconfirmed_solution(&mut rng);
+        let (transaction_id, transaction) = sample_unconfirmed_transaction(&mut rng);
+
+        // Store it on one of the workers.
+        primary.workers[0].process_unconfirmed_solution(solution_id, solution).await.unwrap();
+        primary.workers[0].process_unconfirmed_transaction(transaction_id, transaction).await.unwrap();
+
+        // Slow down the storage.
+        let slow = Duration::from_millis(STORAGE_SLOW_THRESHOLD_IN_MS + 1);
+        assert_eq!(primary.storage_backpressure().record(slow), Some(true));
+
+        // Ensure the primary does not propose a batch, and keeps the transmissions.
+        assert!(primary.propose_batch().await.is_ok());
+        assert!(primary.proposed_batch.read().is_none());
+        assert_eq!(primary.num_unconfirmed_transmissions(), 2);
+
+        // Once the storage recovers, ensure the primary proposes a batch.
+        let fast = Duration::from_millis(STORAGE_RECOVERED_THRESHOLD_IN_MS);
+        for _ in 0..NUM_RECOVERED_STORAGE_ADVANCES {
+            primary.storage_backpressure().record(fast);
+        }
+        assert!(!primary.storage_backpressure().is_throttled());
+        assert!(primary.propose_batch().await.is_ok());
+        assert!(primary.proposed_batch.read().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_propose_batch_while_storage_is_slow_everywhere() {
+        const NUM_NODES: usize = 4;
+        const MAX_THROTTLE_IN_MS: u64 = 500;
+
+        let mut rng = TestRng::default();
+        let slow = Duration::from_millis(STORAGE_SLOW_THRESHOLD_IN_MS + 1);
+
+        // Slow down the storage of every node at once, so that no new block is ever produced to release the throttle.
+        let mut primaries = Vec::with_capacity(NUM_NODES);
+        for _ in 0..NUM_NODES {
+            let (mut primary, _) = primary_without_handlers(&mut rng).await;
+            primary.storage_backpressure = Arc::new(StorageBackpressure::new(
+                Duration::from_millis(STORAGE_SLOW_THRESHOLD_IN_MS),
+                Duration::from_millis(STORAGE_RECOVERED_THRESHOLD_IN_MS),
+                Duration::from_millis(MAX_THROTTLE_IN_MS),
+            ));
+            let (solution_id, solution) = sample_unconfirmed_solution(&mut rng);
+            primary.workers[0].process_unconfirmed_solution(solution_id, solution).await.unwrap();
+            assert_eq!(primary.storage_backpressure().record(slow), Some(true));
+            primaries.push(primary);
+        }
+
+        // Ensure no node proposes a batch while throttled.
+        for primary in &primaries {
+            assert!(primary.propose_batch().await.is_ok());
+            assert!(primary.proposed_batch.read().is_none());
+        }
+
+        // Ensure every node proposes a batch again, once the throttle has been held for the maximum duration.
+        tokio::time::sleep(Duration::from_millis(MAX_THROTTLE_IN_MS)).await;
+        for primary in &primaries {
+            assert!(primary.propose_batch().await.is_ok());
+            assert!(primary.proposed_batch.read().is_some());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_propose_batch_with_duplicate_identity() {
+        let mut rng = TestRng::default();
+        let (primary, _) = primary_without_handlers(&mut rng).await;
+
+        // Generate a solution and a transaction.
+        let (solution_id, solution) = sample_unconfirmed_solution(&mut rng);
+        let (transaction_id, transaction) = sample_unconfirmed_transaction(&mut rng);
+
+        // Store it on one of the workers.
+        primary.workers[0].process_unconfirmed_solution(solution_id, solution).await.unwrap();
+        primary.workers[0].process_unconfirmed_transaction(transaction_id, transaction).await.unwrap();
+
+        // Report another validator using the account of the primary, and pause on it.
+        primary.gateway.duplicate_identity().report("1.2.3.4:5000".parse().unwrap(), Instant::now());
+        primary.set_pause_on_duplicate_identity(true);
+        assert!(primary.is_paused_on_duplicate_identity());
+
+        // Ensure the primary does not propose a batch, and keeps the transmissions.
+        assert!(primary.propose_batch().await.is_ok());
+        assert!(primary.proposed_batch.read().is_none());
+        assert_eq!(primary.num_unconfirmed_transmissions(), 2);
+
+        // Ensure the primary proposes a batch once the pause is disabled.
+        primary.set_pause_on_duplicate_identity(false);
+        assert!(primary.propose_batch().await.is_ok());
+        assert!(primary.proposed_batch.read().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_worker_queue_stats() {
+        let mut rng = TestRng::default();
+        let (primary, _) = primary_without_handlers(&mut rng).await;
+
+        // Check the worker queues are empty.
+        let stats = primary.worker_queue_stats();
+        assert_eq!(stats.len(), primary.num_workers() as usize);
+        assert!(stats.iter().all(|stats| stats.num_transmissions == 0 && stats.oldest_timestamp.is_none()));
+
+        // Generate a solution and a transaction.
+        let (solution_id, solution) = sample_unconfirmed_solution(&mut rng);
+        let (transaction_id, transaction) = sample_unconfirmed_transaction(&mut rng);
+        let solution_size = solution.to_bytes_le().unwrap().len();
+        let transaction_size = transaction.to_bytes_le().unwrap().len();
+
+        // Store them on one of the workers.
+        primary.workers[0].process_unconfirmed_solution(solution_id, solution).await.unwrap();
+        primary.workers[0].process_unconfirmed_transaction(transaction_id, transaction).await.unwrap();
+
+        // Check the worker stats reflect the transmissions.
+        let stats = primary.worker_queue_stats();
+        assert_eq!(stats[0].worker_id, 0);
+        assert_eq!(stats[0].num_transmissions, 2);
+        assert_eq!(stats[0].num_solutions, 1);
+        assert_eq!(stats[0].num_transactions, 1);
+        assert!(stats[0].num_bytes >= solution_size + transaction_size);
+        assert!(stats[0].oldest_timestamp.is_some_and(|timestamp| timestamp <= now()));
+
+        // Propose a batch, which drains the ready queues.
+        assert!(primary.propose_batch().await.is_ok());
+        let stats = primary.worker_queue_stats();
+        assert_eq!(stats[0].num_transmissions, 0);
+        assert_eq!(stats[0].num_recently_drained, 2);
+        assert!(stats[0].oldest_timestamp.is_none());
+    }
+
     #[tokio::test]
     async fn test_propose_batch_with_no_transmissions() {
         let mut rng = TestRng::default();
@@ -2153,6 +2617,46 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_batch_propose_from_peer_with_duplicate_identity() {
+        let mut rng = TestRng::default();
+        let (primary, accounts) = primary_without_handlers(&mut rng).await;
+
+        // Create a valid proposal with an author that isn't the primary.
+        let round = 1;
+        let peer_account = &accounts[1];
+        let peer_ip = peer_account.0;
+        let timestamp = now() + MIN_BATCH_DELAY_IN_SECS as i64;
+        let proposal = create_test_proposal(
+            &peer_account.1,
+            primary.ledger.current_committee().unwrap(),
+            round,
+            Default::default(),
+            timestamp,
+            &mut rng,
+        );
+
+        // Make sure the primary is aware of the transmissions in the proposal.
+        for (transmission_id, transmission) in proposal.transmissions() {
+            primary.workers[0].process_transmission_from_peer(peer_ip, *transmission_id, transmission.clone())
+        }
+
+        // The author must be known to resolver to pass propose checks.
+        primary.gateway.resolver().insert_peer(peer_ip, peer_ip, peer_account.1.address());
+        // The primary must be considered synced.
+        primary.sync.block_sync().try_block_sync(&primary.gateway.clone()).await;
+
+        // Report another validator using the account of the primary, and pause on it.
+        primary.gateway.duplicate_identity().report("1.2.3.4:5000".parse().unwrap(), Instant::now());
+        primary.set_pause_on_duplicate_identity(true);
+
+        // Ensure the primary declines to sign the batch proposal.
+        assert!(
+            primary.process_batch_propose_from_peer(peer_ip, (*proposal.batch_header()).clone().into()).await.is_ok()
+        );
+        assert!(!primary.signed_proposals.read().0.contains_key(&peer_account.1.address()));
+    }
+
     #[tokio::test]
     async fn test_batch_propose_from_peer_when_not_synced() {
         let mut rng = TestRng::default();
@@ -2383,6 +2887,75 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_batch_propose_from_withholding_peer() {
+        let mut rng = TestRng::default();
+        let (primary, accounts) = primary_without_handlers(&mut rng).await;
+        let committee = primary.ledger.current_committee().unwrap();
+
+        // The authors must be known to resolver to pass propose checks.
+        for (peer_ip, account) in &accounts[1..] {
+            primary.gateway.resolver().insert_peer(*peer_ip, *peer_ip, account.address());
+        }
+        // The primary must be considered synced.
+        primary.sync.block_sync().try_block_sync(&primary.gateway.clone()).await;
+
+        // One proposer withholds the transmissions of its proposals, while another one serves them.
+        let (withholder_ip, withholder) = &accounts[1];
+        let (honest_ip, honest) = &accounts[2];
+        for round in 1..=MIN_PROPOSALS_FOR_AVAILABILITY as u64 {
+            let previous_certificate_ids: IndexSet<_> = match round {
+                1 => Default::default(),
+                _ => [Field::rand(&mut rng)].into(),
+            };
+            // The transmissions of the withholder are never served.
+            let proposal = create_test_proposal(
+                withholder,
+                committee.clone(),
+                round,
+                previous_certificate_ids.clone(),
+                now(),
+                &mut rng,
+            );
+            // The requests cannot be sent, as the withholder is not connected, which is not held against it.
+            assert!(primary.fetch_missing_transmissions(*withholder_ip, proposal.batch_header()).await.is_err());
+            assert_eq!(primary.proposer_availability.counts(&withholder.address()), (0, 0, 0));
+            // Emulate the withholder not answering the requests.
+            primary.record_transmission_fetch(*withholder_ip, proposal.batch_header(), FetchOutcome::NeverServed);
+            // The transmissions of the honest proposer are served.
+            let proposal =
+                create_test_proposal(honest, committee.clone(), round, previous_certificate_ids, now(), &mut rng);
+            for (transmission_id, transmission) in proposal.transmissions() {
+                primary.workers[0].process_transmission_from_peer(*honest_ip, *transmission_id, transmission.clone())
+            }
+            assert!(primary.fetch_missing_transmissions(*honest_ip, proposal.batch_header()).await.is_ok());
+        }
+        assert!(primary.proposer_availability.is_withholding(&withholder.address()));
+        assert!(!primary.proposer_availability.is_withholding(&honest.address()));
+        // Ensure the withheld proposals and the decision are reported with the connection of each proposer.
+        let connections = primary.gateway.connection_stats();
+        for (peer_ip, num_withheld_proposals, is_declined_proposer) in
+            [(withholder_ip, MIN_PROPOSALS_FOR_AVAILABILITY as u32, true), (honest_ip, 0, false)]
+        {
+            let connection = connections.iter().find(|connection| connection.peer_ip == *peer_ip).unwrap();
+            assert_eq!(connection.num_withheld_proposals, num_withheld_proposals);
+            assert_eq!(connection.is_declined_proposer, is_declined_proposer);
+        }
+
+        // Ensure the primary declines to sign the proposal of the withholder, even if its transmissions are served.
+        let timestamp = now() + MIN_BATCH_DELAY_IN_SECS as i64;
+        for (peer_ip, account) in [(withholder_ip, withholder), (honest_ip, honest)] {
+            let proposal = create_test_proposal(account, committee.clone(), 1, Default::default(), timestamp, &mut rng);
+            for (transmission_id, transmission) in proposal.transmissions() {
+                primary.workers[0].process_transmission_from_peer(*peer_ip, *transmission_id, transmission.clone())
+            }
+            primary.process_batch_propose_from_peer(*peer_ip, (*proposal.batch_header()).clone().into()).await.unwrap();
+        }
+        assert!(primary.signed_proposals.read().get(&withholder.address()).is_none());
+        // Ensure the primary continues to sign the proposals of the other validators.
+        assert!(primary.signed_proposals.read().get(&honest.address()).is_some());
+    }
+
     #[tokio::test]
     async fn test_propose_batch_with_storage_round_behind_proposal_lock() {
         let round = 3;
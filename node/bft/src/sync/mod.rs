@@ -22,8 +22,8 @@ use crate::{
     spawn_blocking,
 };
 use snarkos_node_bft_events::{CertificateRequest, CertificateResponse, Event};
-use snarkos_node_bft_ledger_service::LedgerService;
-use snarkos_node_sync::{BlockSync, BlockSyncMode, locators::BlockLocators};
+use snarkos_node_bft_ledger_service::{LedgerService, retry_transient_async};
+use snarkos_node_sync::{BlockSync, BlockSyncMode, SyncProgress, locators::BlockLocators};
 use snarkvm::{
     console::{network::Network, types::Field},
     ledger::{authority::Authority, block::Block, narwhal::BatchCertificate},
@@ -33,9 +33,15 @@ use snarkvm::{
 use anyhow::{Result, bail};
 use parking_lot::Mutex;
 use rayon::prelude::*;
-use std::{collections::HashMap, future::Future, net::SocketAddr, sync::Arc, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    future::Future,
+    net::SocketAddr,
+    sync::Arc,
+    time::Duration,
+};
 use tokio::{
-    sync::{Mutex as TMutex, OnceCell, oneshot},
+    sync::{Mutex as TMutex, OnceCell, oneshot, watch},
     task::JoinHandle,
 };
 
@@ -61,6 +67,8 @@ pub struct Sync<N: Network> {
     sync_lock: Arc<TMutex<()>>,
     /// The latest block responses.
     latest_block_responses: Arc<TMutex<HashMap<u32, Block<N>>>>,
+    /// The sender of the sync status, which is updated on every iteration of the block sync loop.
+    is_synced_sender: Arc<watch::Sender<bool>>,
 }
 
 impl<N: Network> Sync<N> {
@@ -80,6 +88,7 @@ impl<N: Network> Sync<N> {
             response_lock: Default::default(),
             sync_lock: Default::default(),
             latest_block_responses: Default::default(),
+            is_synced_sender: Arc::new(watch::channel(false).0),
         }
     }
 
@@ -122,9 +131,12 @@ impl<N: Network> Sync<N> {
                 }
 
                 // If the node is synced, clear the `latest_block_responses`.
-                if self_.is_synced() {
+                let is_synced = self_.is_synced();
+                if is_synced {
                     self_.latest_block_responses.lock().await.clear();
                 }
+                // Notify the subscribers, if the sync status changed.
+                self_.is_synced_sender.send_if_modified(|status| std::mem::replace(status, is_synced) != is_synced);
             }
         }));
 
@@ -232,8 +244,9 @@ impl<N: Network> Sync<N> {
         // Determine the earliest height, conservatively set to the block height minus the max GC rounds.
         // By virtue of the BFT protocol, we can guarantee that all GC range blocks will be loaded.
         let gc_height = block_height.saturating_sub(max_gc_blocks);
-        // Retrieve the blocks.
-        let blocks = self.ledger.get_blocks(gc_height..block_height.saturating_add(1))?;
+        // Retrieve the blocks, reading them again on a transient failure.
+        let blocks =
+            retry_transient_async(|| self.ledger.get_blocks(gc_height..block_height.saturating_add(1))).await?;
 
         // Acquire the sync lock.
         let _lock = self.sync_lock.lock().await;
@@ -524,11 +537,11 @@ impl<N: Network> Sync<N> {
         for round in (previous_certificate.round()..current_certificate.round()).rev() {
             // Retrieve all of the certificates for this past round.
             let certificates = self.storage.get_certificates_for_round(round);
+            // Collect the previous certificate IDs of the traversal once, instead of once per certificate.
+            let previous_ids: HashSet<_> =
+                traversal.iter().flat_map(|c| c.previous_certificate_ids().iter().copied()).collect();
             // Filter the certificates to only include those that are in the traversal.
-            traversal = certificates
-                .into_iter()
-                .filter(|p| traversal.iter().any(|c| c.previous_certificate_ids().contains(&p.id())))
-                .collect();
+            traversal = certificates.into_iter().filter(|p| previous_ids.contains(&p.id())).collect();
         }
         Ok(traversal.contains(&previous_certificate))
     }
@@ -544,11 +557,21 @@ impl<N: Network> Sync<N> {
         self.block_sync.is_block_synced()
     }
 
+    /// Subscribes to the sync status, which is updated on every iteration of the block sync loop.
+    pub fn subscribe_is_synced(&self) -> watch::Receiver<bool> {
+        self.is_synced_sender.subscribe()
+    }
+
     /// Returns the number of blocks the node is behind the greatest peer height.
     pub fn num_blocks_behind(&self) -> u32 {
         self.block_sync.num_blocks_behind()
     }
 
+    /// Returns the progress of the block sync.
+    pub fn progress(&self) -> SyncProgress {
+        self.block_sync.progress()
+    }
+
     /// Returns `true` if the node is in gateway mode.
     pub const fn is_gateway_mode(&self) -> bool {
         self.block_sync.mode().is_gateway()
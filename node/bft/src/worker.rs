@@ -18,7 +18,7 @@ use crate::{
     MAX_WORKERS,
     ProposedBatch,
     Transport,
-    events::{Event, TransmissionRequest, TransmissionResponse},
+    events::{Event, TransmissionChunkRequest, TransmissionRequest, TransmissionResponse},
     helpers::{Pending, Ready, Storage, WorkerReceiver, fmt_id, max_redundant_requests},
     spawn_blocking,
 };
@@ -36,9 +36,45 @@ use colored::Colorize;
 use indexmap::{IndexMap, IndexSet};
 use parking_lot::Mutex;
 use rand::seq::IteratorRandom;
-use std::{future::Future, net::SocketAddr, sync::Arc, time::Duration};
+use std::{fmt, future::Future, net::SocketAddr, sync::Arc, time::Duration};
 use tokio::{sync::oneshot, task::JoinHandle, time::timeout};
 
+/// A snapshot of the queue of a worker.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WorkerQueueStats {
+    /// The worker ID.
+    pub worker_id: u8,
+    /// The number of transmissions in the ready queue.
+    pub num_transmissions: usize,
+    /// The number of ratifications in the ready queue.
+    pub num_ratifications: usize,
+    /// The number of solutions in the ready queue.
+    pub num_solutions: usize,
+    /// The number of transactions in the ready queue.
+    pub num_transactions: usize,
+    /// The number of transmissions in the pending queue.
+    pub num_pending: usize,
+    /// The estimated number of bytes of the transmissions in the ready queue.
+    pub num_bytes: usize,
+    /// The insertion timestamp of the oldest transmission in the ready queue, if it exists.
+    pub oldest_timestamp: Option<i64>,
+    /// The number of transmissions drained from the ready queue within the last `DRAIN_RATE_WINDOW_IN_SECS`.
+    pub num_recently_drained: usize,
+}
+
+/// The error of a transmission request that was sent to the peer, and that the peer did not answer in time with
+/// a well-formed transmission. Unlike the local failures, e.g. to send the request, it is the fault of the peer.
+#[derive(Debug)]
+pub(crate) struct TransmissionNotServed(tokio::time::error::Elapsed);
+
+impl fmt::Display for TransmissionNotServed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Unable to fetch transmission - (timeout) {}", self.0)
+    }
+}
+
+impl std::error::Error for TransmissionNotServed {}
+
 #[derive(Clone)]
 pub struct Worker<N: Network> {
     /// The worker ID.
@@ -142,6 +178,25 @@ impl<N: Network> Worker<N> {
         self.ready.transmissions()
     }
 
+    /// Returns the transmission with the given ID, if it is in the ready queue.
+    pub fn ready_transmission(&self, transmission_id: TransmissionID<N>) -> Option<Transmission<N>> {
+        self.ready.get(transmission_id)
+    }
+
+    /// Returns the ID, the size in bytes (if known), and the insertion timestamp of each ready transmission.
+    pub fn transmission_summaries(&self) -> Vec<(TransmissionID<N>, Option<usize>, i64)> {
+        self.ready.transmission_summaries()
+    }
+
+    /// Returns up to the given number of transmissions in the ready queue that match the filter.
+    pub fn transmissions_matching(
+        &self,
+        limit: usize,
+        filter: impl Fn(&TransmissionID<N>) -> bool,
+    ) -> IndexMap<TransmissionID<N>, Transmission<N>> {
+        self.ready.transmissions_matching(limit, filter)
+    }
+
     /// Returns the solutions in the ready queue.
     pub fn solutions(&self) -> impl '_ + Iterator<Item = (SolutionID<N>, Data<Solution<N>>)> {
         self.ready.solutions()
@@ -153,6 +208,35 @@ impl<N: Network> Worker<N> {
     }
 }
 
+impl<N: Network> Worker<N> {
+    /// Returns a snapshot of the queue statistics of the worker.
+    /// Note: The statistics are computed from the summaries of the ready queue, without copying nor serializing
+    /// the transmissions, and its size is read from the running byte counter of the ready queue.
+    pub fn queue_stats(&self) -> WorkerQueueStats {
+        // Take a snapshot of the summaries of the ready queue.
+        let summaries = self.ready.transmission_summaries();
+
+        let mut stats = WorkerQueueStats {
+            worker_id: self.id,
+            num_transmissions: summaries.len(),
+            num_pending: self.pending.len(),
+            num_bytes: self.ready.num_bytes(),
+            num_recently_drained: self.ready.num_recently_drained(),
+            ..Default::default()
+        };
+        for (transmission_id, _, timestamp) in summaries {
+            match transmission_id {
+                TransmissionID::Ratification => stats.num_ratifications += 1,
+                TransmissionID::Solution(..) => stats.num_solutions += 1,
+                TransmissionID::Transaction(..) => stats.num_transactions += 1,
+            }
+            // Track the oldest insertion timestamp.
+            stats.oldest_timestamp = Some(stats.oldest_timestamp.map_or(timestamp, |oldest| oldest.min(timestamp)));
+        }
+        stats
+    }
+}
+
 impl<N: Network> Worker<N> {
     /// Clears the solutions from the ready queue.
     pub(super) fn clear_solutions(&self) {
@@ -247,6 +331,10 @@ impl<N: Network> Worker<N> {
 impl<N: Network> Worker<N> {
     /// Handles the incoming transmission ID from a worker ping event.
     fn process_transmission_id_from_ping(&self, peer_ip: SocketAddr, transmission_id: TransmissionID<N>) {
+        // If the transmission is in the ready queue, record that the peer gossiped it.
+        if self.ready.record_sighting(peer_ip, transmission_id) {
+            return;
+        }
         // Check if the transmission ID exists.
         if self.contains_transmission(transmission_id) {
             return;
@@ -269,6 +357,8 @@ impl<N: Network> Worker<N> {
                         // Note: This method checks `contains_transmission` again, because by the time the transmission is fetched,
                         // it could have already been inserted into the ready queue.
                         self_.process_transmission_from_peer(peer_ip, transmission_id, transmission);
+                        // Record that the peer gossiped the transmission.
+                        self_.ready.record_sighting(peer_ip, transmission_id);
                     }
                 }
                 // If the transmission was not fetched, then attempt to fetch it again.
@@ -385,7 +475,12 @@ impl<N: Network> Worker<N> {
 impl<N: Network> Worker<N> {
     /// Starts the worker handlers.
     fn start_handlers(&self, receiver: WorkerReceiver<N>) {
-        let WorkerReceiver { mut rx_worker_ping, mut rx_transmission_request, mut rx_transmission_response } = receiver;
+        let WorkerReceiver {
+            mut rx_worker_ping,
+            mut rx_transmission_request,
+            mut rx_transmission_response,
+            mut rx_transmission_chunk_request,
+        } = receiver;
 
         // Start the pending queue expiration loop.
         let self_ = self.clone();
@@ -431,6 +526,14 @@ impl<N: Network> Worker<N> {
                 });
             }
         });
+
+        // Process the transmission chunk requests.
+        let self_ = self.clone();
+        self.spawn(async move {
+            while let Some((peer_ip, transmission_chunk_request)) = rx_transmission_chunk_request.recv().await {
+                self_.send_transmission_chunks(peer_ip, transmission_chunk_request);
+            }
+        });
     }
 
     /// Sends a transmission request to the specified peer.
@@ -471,6 +574,8 @@ impl<N: Network> Worker<N> {
         match timeout(Duration::from_millis(MAX_FETCH_TIMEOUT_IN_MS), callback_receiver).await {
             // If the transmission was fetched, return it.
             Ok(result) => Ok((transmission_id, result?)),
+            // If the transmission was not served by the peer, return an error attributing the failure to the peer.
+            Err(e) if should_send_request => Err(TransmissionNotServed(e).into()),
             // If the transmission was not fetched, return an error.
             Err(e) => bail!("Unable to fetch transmission - (timeout) {e}"),
         }
@@ -508,6 +613,31 @@ impl<N: Network> Worker<N> {
         }
     }
 
+    /// Sends the requested chunks of the transmission to the specified peer, to resume a chunked transfer.
+    fn send_transmission_chunks(&self, peer_ip: SocketAddr, request: TransmissionChunkRequest<N>) {
+        let TransmissionChunkRequest { transmission_id, start, end } = request;
+        // Attempt to retrieve the transmission.
+        if let Some(transmission) = self.get_transmission(transmission_id) {
+            // Send the requested chunks to the peer.
+            let self_ = self.clone();
+            tokio::spawn(async move {
+                let chunks = match self_.gateway.transmission_chunks(transmission_id, &transmission) {
+                    Ok(chunks) => chunks,
+                    Err(error) => {
+                        warn!("Failed to split transmission '{}' - {error}", fmt_id(transmission_id));
+                        return;
+                    }
+                };
+                for chunk in chunks.into_iter().filter(|chunk| (start..end).contains(&chunk.index)) {
+                    // If the chunk could not be sent, the peer has been disconnected.
+                    if self_.gateway.send(peer_ip, Event::TransmissionChunk(chunk)).await.is_none() {
+                        break;
+                    }
+                }
+            });
+        }
+    }
+
     /// Spawns a task with the given future; it should only be used for long-running tasks.
     fn spawn<T: Future<Output = ()> + Send + 'static>(&self, future: T) {
         self.handles.lock().push(tokio::spawn(future));
@@ -704,6 +834,44 @@ mod tests {
         assert!(!worker.pending.contains(transmission_id));
     }
 
+    #[tokio::test]
+    async fn test_transmission_not_served_is_attributed_to_peer() {
+        let rng = &mut TestRng::default();
+        // Sample a committee.
+        let committee = snarkvm::ledger::committee::test_helpers::sample_committee(rng);
+        let committee_clone = committee.clone();
+        // Setup the mock gateway, which fails to send to the unreachable peer, and the mock ledger.
+        let unreachable_ip = SocketAddr::from(([127, 0, 0, 1], 1234));
+        let withholding_ip = SocketAddr::from(([127, 0, 0, 1], 1235));
+        let mut gateway = MockGateway::default();
+        gateway.expect_send().returning(move |peer_ip, _| match peer_ip == unreachable_ip {
+            true => None,
+            false => Some(oneshot::channel().1),
+        });
+        let mut mock_ledger = MockLedger::default();
+        mock_ledger.expect_current_committee().returning(move || Ok(committee.clone()));
+        mock_ledger.expect_get_committee_lookback_for_round().returning(move |_| Ok(committee_clone.clone()));
+        let ledger: Arc<dyn LedgerService<CurrentNetwork>> = Arc::new(mock_ledger);
+        // Initialize the storage.
+        let storage = Storage::<CurrentNetwork>::new(ledger.clone(), Arc::new(BFTMemoryService::new()), 1);
+
+        // Create the Worker.
+        let worker = Worker::new(0, Arc::new(gateway), storage, ledger, Default::default()).unwrap();
+        let mut sample_transmission_id = || {
+            TransmissionID::Solution(
+                rng.gen::<u64>().into(),
+                rng.gen::<<CurrentNetwork as Network>::TransmissionChecksum>(),
+            )
+        };
+
+        // Ensure a request that could not be sent is not attributed to the peer.
+        let error = worker.send_transmission_request(unreachable_ip, sample_transmission_id()).await.unwrap_err();
+        assert!(error.downcast_ref::<TransmissionNotServed>().is_none());
+        // Ensure a request that the peer did not answer is attributed to the peer.
+        let error = worker.send_transmission_request(withholding_ip, sample_transmission_id()).await.unwrap_err();
+        assert!(error.downcast_ref::<TransmissionNotServed>().is_some());
+    }
+
     #[tokio::test]
     async fn test_process_solution_ok() {
         let rng = &mut TestRng::default();
@@ -28,3 +28,6 @@ pub use persistent::*;
 
 pub mod traits;
 pub use traits::*;
+
+pub mod write_verifier;
+pub use write_verifier::*;
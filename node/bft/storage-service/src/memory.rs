@@ -62,6 +62,27 @@ impl<N: Network> StorageService<N> for BFTMemoryService<N> {
         self.transmissions.read().get(&transmission_id).map(|(transmission, _)| transmission).cloned()
     }
 
+    /// Returns the IDs of the transmissions and aborted transmissions in storage.
+    fn transmission_ids(&self) -> Vec<TransmissionID<N>> {
+        let mut transmission_ids: IndexSet<_> = self.transmissions.read().keys().copied().collect();
+        transmission_ids.extend(self.aborted_transmission_ids.read().keys().copied());
+        transmission_ids.into_iter().collect()
+    }
+
+    /// Returns the certificate IDs that reference the given `transmission ID`, including as an aborted transmission.
+    fn get_certificate_ids(&self, transmission_id: TransmissionID<N>) -> IndexSet<Field<N>> {
+        let mut certificate_ids = self
+            .transmissions
+            .read()
+            .get(&transmission_id)
+            .map(|(_, certificate_ids)| certificate_ids.clone())
+            .unwrap_or_default();
+        if let Some(aborted_certificate_ids) = self.aborted_transmission_ids.read().get(&transmission_id) {
+            certificate_ids.extend(aborted_certificate_ids);
+        }
+        certificate_ids
+    }
+
     /// Returns the missing transmissions in storage from the given transmissions.
     fn find_missing_transmissions(
         &self,
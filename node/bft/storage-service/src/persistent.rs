@@ -13,12 +13,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::StorageService;
+use crate::{StorageService, WriteVerifier};
 use snarkvm::{
     ledger::{
         narwhal::{BatchHeader, Transmission, TransmissionID},
         store::{
             cow_to_cloned,
+            cow_to_copied,
             helpers::{
                 Map,
                 MapRead,
@@ -37,6 +38,7 @@ use indexmap::{IndexSet, indexset};
 use std::{
     borrow::Cow,
     collections::{HashMap, HashSet},
+    sync::Arc,
 };
 use tracing::error;
 
@@ -47,6 +49,8 @@ pub struct BFTPersistentStorage<N: Network> {
     transmissions: DataMap<TransmissionID<N>, (Transmission<N>, IndexSet<Field<N>>)>,
     /// The map of `aborted transmission ID` to `certificate IDs` entries.
     aborted_transmission_ids: DataMap<TransmissionID<N>, IndexSet<Field<N>>>,
+    /// The verifier of the transmission writes.
+    write_verifier: Arc<WriteVerifier>,
 }
 
 impl<N: Network> BFTPersistentStorage<N> {
@@ -59,6 +63,7 @@ impl<N: Network> BFTPersistentStorage<N> {
                 storage_mode,
                 MapID::BFT(BFTMap::AbortedTransmissionIDs),
             )?,
+            write_verifier: Default::default(),
         })
     }
 
@@ -76,8 +81,36 @@ impl<N: Network> BFTPersistentStorage<N> {
                 dev,
                 MapID::BFT(BFTMap::AbortedTransmissionIDs),
             )?,
+            write_verifier: Default::default(),
         })
     }
+
+    /// Sets the verifier of the transmission writes, which is shared with the ledger, so that a detected
+    /// corruption stops the ledger from advancing.
+    pub fn with_write_verifier(mut self, write_verifier: Arc<WriteVerifier>) -> Self {
+        self.write_verifier = write_verifier;
+        self
+    }
+
+    /// Reads back the entry of the given transmission ID, to ensure it was written with the given certificate ID.
+    ///
+    /// Note: Only the key that was just written is read, instead of the entire certificate.
+    fn verify_transmission_write(&self, transmission_id: TransmissionID<N>, certificate_id: Field<N>) {
+        if !self.write_verifier.is_enabled() {
+            return;
+        }
+        let read_back = match self.transmissions.get_confirmed(&transmission_id) {
+            Ok(entry) => entry.and_then(|entry| entry.1.contains(&certificate_id).then_some(certificate_id)),
+            Err(e) => {
+                error!("Failed to read back transmission {transmission_id} from storage - {e}");
+                None
+            }
+        };
+        let entry = "certificate ID of transmission";
+        if let Err(mismatch) = self.write_verifier.verify(entry, transmission_id, &certificate_id, read_back) {
+            error!("{mismatch} - the node stopped advancing the ledger, as its storage is corrupted");
+        }
+    }
 }
 
 impl<N: Network> StorageService<N> for BFTPersistentStorage<N> {
@@ -114,6 +147,38 @@ impl<N: Network> StorageService<N> for BFTPersistentStorage<N> {
         }
     }
 
+    /// Returns the IDs of the transmissions and aborted transmissions in storage.
+    fn transmission_ids(&self) -> Vec<TransmissionID<N>> {
+        let mut transmission_ids: IndexSet<_> =
+            self.transmissions.keys_confirmed().map(|transmission_id| cow_to_copied!(transmission_id)).collect();
+        transmission_ids.extend(
+            self.aborted_transmission_ids.keys_confirmed().map(|transmission_id| cow_to_copied!(transmission_id)),
+        );
+        transmission_ids.into_iter().collect()
+    }
+
+    /// Returns the certificate IDs that reference the given `transmission ID`, including as an aborted transmission.
+    fn get_certificate_ids(&self, transmission_id: TransmissionID<N>) -> IndexSet<Field<N>> {
+        let mut certificate_ids = match self.transmissions.get_confirmed(&transmission_id) {
+            Ok(Some(entry)) => cow_to_cloned!(entry).1,
+            Ok(None) => Default::default(),
+            Err(error) => {
+                error!("Failed to get the certificate IDs of transmission {transmission_id} from storage - {error}");
+                Default::default()
+            }
+        };
+        match self.aborted_transmission_ids.get_confirmed(&transmission_id) {
+            Ok(Some(entry)) => certificate_ids.extend(cow_to_cloned!(entry)),
+            Ok(None) => (),
+            Err(error) => {
+                error!(
+                    "Failed to get the certificate IDs of aborted transmission {transmission_id} from storage - {error}"
+                )
+            }
+        }
+        certificate_ids
+    }
+
     /// Returns the missing transmissions in storage from the given transmissions.
     fn find_missing_transmissions(
         &self,
@@ -168,6 +233,7 @@ impl<N: Network> StorageService<N> for BFTPersistentStorage<N> {
                         error!("Failed to insert transmission {transmission_id} into storage - {e}");
                         continue 'outer;
                     }
+                    self.verify_transmission_write(transmission_id, certificate_id);
                 }
                 Ok(None) => {
                     // Retrieve the missing transmission.
@@ -186,6 +252,7 @@ impl<N: Network> StorageService<N> for BFTPersistentStorage<N> {
                         error!("Failed to insert transmission {transmission_id} into storage - {e}");
                         continue 'outer;
                     }
+                    self.verify_transmission_write(transmission_id, certificate_id);
                 }
                 Err(e) => {
                     error!("Failed to process the 'insert' for transmission {transmission_id} into storage - {e}");
@@ -295,7 +362,6 @@ impl<N: Network> StorageService<N> for BFTPersistentStorage<N> {
     /// Returns a HashMap over the `(transmission ID, (transmission, certificate IDs))` entries.
     #[cfg(any(test, feature = "test"))]
     fn as_hashmap(&self) -> HashMap<TransmissionID<N>, (Transmission<N>, IndexSet<Field<N>>)> {
-        use snarkvm::ledger::store::cow_to_copied;
         self.transmissions.iter_confirmed().map(|(k, v)| (cow_to_copied!(k), cow_to_cloned!(v))).collect()
     }
 }
@@ -32,6 +32,12 @@ pub trait StorageService<N: Network>: Debug + Send + Sync {
     /// If the transmission ID does not exist in storage, `None` is returned.
     fn get_transmission(&self, transmission_id: TransmissionID<N>) -> Option<Transmission<N>>;
 
+    /// Returns the IDs of the transmissions and aborted transmissions in storage.
+    fn transmission_ids(&self) -> Vec<TransmissionID<N>>;
+
+    /// Returns the certificate IDs that reference the given `transmission ID`, including as an aborted transmission.
+    fn get_certificate_ids(&self, transmission_id: TransmissionID<N>) -> IndexSet<Field<N>>;
+
     /// Returns the missing transmissions in storage from the given transmissions.
     fn find_missing_transmissions(
         &self,
@@ -0,0 +1,160 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{Error, Result};
+
+use std::{
+    fmt,
+    sync::{
+        OnceLock,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+/// A mismatch between a critical write and the value read back from storage.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WriteMismatch {
+    /// The written entry, e.g. `block hash`.
+    pub entry: &'static str,
+    /// The key of the written entry.
+    pub key: String,
+    /// The written value.
+    pub written: String,
+    /// The value read back from storage, if it was found.
+    pub read_back: Option<String>,
+}
+
+impl WriteMismatch {
+    /// Returns the write mismatch in the given error, if any.
+    pub fn of(error: &Error) -> Option<&Self> {
+        error.downcast_ref::<Self>()
+    }
+}
+
+impl fmt::Display for WriteMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let read_back = self.read_back.as_deref().unwrap_or("nothing");
+        write!(
+            f,
+            "Storage corruption detected - the {} at '{}' was written as '{}', but read back as '{read_back}'",
+            self.entry, self.key, self.written
+        )
+    }
+}
+
+impl std::error::Error for WriteMismatch {}
+
+/// The verifier of critical storage writes, which reads back each write and compares it against the written value.
+///
+/// The verifier is meant for operators on hardware that may silently drop or corrupt writes. Once a mismatch
+/// is detected, the storage is flagged as corrupted for the lifetime of the node, as any further write would
+/// build on top of the corrupted one.
+#[derive(Debug, Default)]
+pub struct WriteVerifier {
+    /// Whether the critical writes are read back.
+    is_enabled: bool,
+    /// The first mismatch that was detected, if any.
+    mismatch: OnceLock<WriteMismatch>,
+    /// The number of writes that were read back.
+    num_verifications: AtomicU64,
+}
+
+impl WriteVerifier {
+    /// Initializes a new write verifier.
+    pub fn new(is_enabled: bool) -> Self {
+        Self { is_enabled, ..Default::default() }
+    }
+
+    /// Returns `true` if the critical writes are read back.
+    pub const fn is_enabled(&self) -> bool {
+        self.is_enabled
+    }
+
+    /// Returns `true` if a mismatch was detected.
+    pub fn is_corrupted(&self) -> bool {
+        self.mismatch.get().is_some()
+    }
+
+    /// Returns the number of writes that were read back.
+    pub fn num_verifications(&self) -> u64 {
+        self.num_verifications.load(Ordering::Relaxed)
+    }
+
+    /// Ensures no mismatch was detected, returning the first mismatch otherwise.
+    pub fn ensure_not_corrupted(&self) -> Result<()> {
+        match self.mismatch.get() {
+            Some(mismatch) => Err(mismatch.clone().into()),
+            None => Ok(()),
+        }
+    }
+
+    /// Compares the value read back from storage against the written value, and flags the storage
+    /// as corrupted on a mismatch.
+    pub fn verify<T: PartialEq + fmt::Display>(
+        &self,
+        entry: &'static str,
+        key: impl fmt::Display,
+        written: &T,
+        read_back: Option<T>,
+    ) -> Result<(), WriteMismatch> {
+        self.num_verifications.fetch_add(1, Ordering::Relaxed);
+        #[cfg(feature = "metrics")]
+        metrics::increment_counter(metrics::storage::WRITE_VERIFICATIONS);
+
+        if read_back.as_ref() == Some(written) {
+            return Ok(());
+        }
+        let mismatch = WriteMismatch {
+            entry,
+            key: key.to_string(),
+            written: written.to_string(),
+            read_back: read_back.map(|value| value.to_string()),
+        };
+        // Note: Only the first mismatch is kept, as it is the earliest sign of the corruption.
+        let _ = self.mismatch.set(mismatch.clone());
+        #[cfg(feature = "metrics")]
+        metrics::gauge(metrics::storage::CORRUPTED, 1.0);
+        Err(mismatch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify() {
+        let verifier = WriteVerifier::new(true);
+        assert!(verifier.is_enabled());
+
+        // Ensure a matching read-back passes.
+        verifier.verify("block hash", 1, &"ab1", Some("ab1")).unwrap();
+        verifier.ensure_not_corrupted().unwrap();
+        assert!(!verifier.is_corrupted());
+
+        // Ensure a mismatching, or missing, read-back is detected.
+        let mismatch = verifier.verify("block hash", 2, &"ab2", Some("ab3")).unwrap_err();
+        assert_eq!(mismatch.read_back.as_deref(), Some("ab3"));
+        let missing = verifier.verify("block hash", 3, &"ab4", None).unwrap_err();
+        assert_eq!(missing.read_back, None);
+        assert_eq!(verifier.num_verifications(), 3);
+
+        // Ensure the storage stays flagged as corrupted, with the first mismatch.
+        assert!(verifier.is_corrupted());
+        let error = verifier.ensure_not_corrupted().unwrap_err();
+        assert_eq!(WriteMismatch::of(&error), Some(&mismatch));
+        assert!(error.to_string().starts_with("Storage corruption detected - the block hash at '2'"));
+    }
+}
@@ -23,7 +23,7 @@ use crate::common::{
     utils::{sample_gateway, sample_ledger, sample_storage},
 };
 use snarkos_account::Account;
-use snarkos_node_bft::{Gateway, helpers::init_primary_channels};
+use snarkos_node_bft::{Gateway, Transport, helpers::init_primary_channels};
 use snarkos_node_bft_events::{ChallengeRequest, ChallengeResponse, Disconnect, DisconnectReason, Event, WorkerPing};
 use snarkos_node_tcp::P2P;
 use snarkvm::{ledger::narwhal::Data, prelude::TestRng};
@@ -229,13 +229,10 @@ async fn handshake_responder_side_invalid_challenge_response() {
 
     // Check the sender is the gateway.
     assert_eq!(peer_addr, gateway.local_ip());
-    // Check the nonce we sent is in the signature.
-    assert!(
-        signature.deserialize_blocking().unwrap().verify_bytes(
-            &accounts.first().unwrap().address(),
-            &[our_nonce.to_le_bytes(), nonce.to_le_bytes()].concat()
-        )
-    );
+    // Check the nonce we sent, the versions and the addresses are in the signature.
+    let gateway_address = accounts.first().unwrap().address();
+    let data = ChallengeResponse::signed_bytes(version, version, gateway_address, address, our_nonce, nonce).unwrap();
+    assert!(signature.deserialize_blocking().unwrap().verify_bytes(&gateway_address, &data));
 
     // Receive the gateway's challenge request.
     let (peer_addr, Event::ChallengeRequest(challenge_request)) = test_peer.recv_timeout(Duration::from_secs(1)).await
@@ -271,3 +268,219 @@ async fn handshake_responder_side_invalid_challenge_response() {
     assert!(gateway.connected_peers().read().is_empty());
     assert_eq!(gateway.tcp().num_connected(), 0);
 }
+
+/* Legacy challenge response */
+
+#[tokio::test(flavor = "multi_thread")]
+async fn handshake_responder_side_legacy_version() {
+    const NUM_NODES: u16 = 4;
+
+    let mut rng = TestRng::default();
+    let (accounts, gateway) = new_test_gateway(NUM_NODES, &mut rng).await;
+    let mut test_peer = TestPeer::new().await;
+
+    // Initiate a connection with the gateway.
+    assert!(test_peer.connect(gateway.local_ip()).await.is_ok());
+
+    // Send a challenge request on the minimum version, which predates the signed versions and addresses.
+    let listener_port = test_peer.listening_addr().port();
+    let address = accounts.get(1).unwrap().address();
+    let our_nonce = rng.gen();
+    let version = Event::<CurrentNetwork>::MINIMUM_VERSION;
+    assert!(version < Event::<CurrentNetwork>::FRESH_HANDSHAKE_VERSION);
+    let challenge_request = ChallengeRequest { version, listener_port, address, nonce: our_nonce };
+    let _ = test_peer.unicast(gateway.local_ip(), Event::ChallengeRequest(challenge_request));
+
+    // Receive the gateway's challenge response, and check it only signs the nonces.
+    let (_, Event::ChallengeResponse(ChallengeResponse { restrictions_id, signature, nonce })) =
+        test_peer.recv_timeout(Duration::from_secs(1)).await
+    else {
+        panic!("Expected challenge response")
+    };
+    let gateway_address = accounts.first().unwrap().address();
+    let data = [our_nonce.to_le_bytes(), nonce.to_le_bytes()].concat();
+    assert!(signature.deserialize_blocking().unwrap().verify_bytes(&gateway_address, &data));
+
+    // Receive the gateway's challenge request, and sign it in the previous format.
+    let (_, Event::ChallengeRequest(challenge_request)) = test_peer.recv_timeout(Duration::from_secs(1)).await else {
+        panic!("Expected challenge request")
+    };
+    let response_nonce: u64 = rng.gen();
+    let data = [challenge_request.nonce.to_le_bytes(), response_nonce.to_le_bytes()].concat();
+    let signature = accounts.get(1).unwrap().sign_bytes(&data, &mut rng).unwrap();
+    let _ = test_peer.unicast(
+        gateway.local_ip(),
+        Event::ChallengeResponse(ChallengeResponse {
+            restrictions_id,
+            signature: Data::Object(signature),
+            nonce: response_nonce,
+        }),
+    );
+
+    // Check the gateway accepts the peer, until the minimum version is raised.
+    let gateway_clone = gateway.clone();
+    deadline!(Duration::from_secs(1), move || gateway_clone.connected_peers().read().len() == 1);
+}
+
+// Two gateways dial each other simultaneously, repeatedly. Both should always converge to
+// exactly one stable connection.
+#[tokio::test(flavor = "multi_thread")]
+async fn simultaneous_connections_converge() {
+    const NUM_NODES: u16 = 4;
+    const NUM_ATTEMPTS: usize = 20;
+
+    let mut rng = TestRng::default();
+    let (accounts, committee) = new_test_committee(NUM_NODES, &mut rng);
+    let ledger = sample_ledger(&accounts, &committee, &mut rng);
+
+    // Initialize two gateways, listening on random ports.
+    let mut gateways = Vec::new();
+    for account in &accounts[..2] {
+        let storage = sample_storage(ledger.clone());
+        let ip = Some("127.0.0.1:0".parse().unwrap());
+        let gateway = Gateway::new(account.clone(), storage, ledger.clone(), ip, &[], None).unwrap();
+        let (primary_tx, _primary_rx) = init_primary_channels();
+        gateway.run(primary_tx, [].into(), None).await;
+        gateways.push(gateway);
+    }
+    let (gateway0, gateway1) = (gateways[0].clone(), gateways[1].clone());
+    let (ip0, ip1) = (gateway0.local_ip(), gateway1.local_ip());
+
+    for _ in 0..NUM_ATTEMPTS {
+        // Connect the gateways to each other, simultaneously.
+        let handles = [gateway0.connect(ip1), gateway1.connect(ip0)];
+        for handle in handles.into_iter().flatten() {
+            handle.await.unwrap();
+        }
+
+        // Await for both gateways to be connected.
+        let (gateway0_, gateway1_) = (gateway0.clone(), gateway1.clone());
+        deadline!(Duration::from_secs(5), move || {
+            gateway0_.is_connected_ip(ip1)
+                && gateway1_.is_connected_ip(ip0)
+                && gateway0_.tcp().num_connecting() == 0
+                && gateway1_.tcp().num_connecting() == 0
+        });
+
+        // Ensure exactly one connection survives on both sides, and remains stable.
+        for _ in 0..5 {
+            assert_eq!(gateway0.tcp().num_connected(), 1);
+            assert_eq!(gateway1.tcp().num_connected(), 1);
+            assert_eq!(gateway0.number_of_connected_peers(), 1);
+            assert_eq!(gateway1.number_of_connected_peers(), 1);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+
+        // Disconnect the gateways.
+        gateway0.disconnect(ip1).await.unwrap();
+        let (gateway0_, gateway1_) = (gateway0.clone(), gateway1.clone());
+        deadline!(Duration::from_secs(5), move || {
+            gateway0_.tcp().num_connected() == 0
+                && gateway1_.tcp().num_connected() == 0
+                && gateway0_.number_of_connected_peers() == 0
+                && gateway1_.number_of_connected_peers() == 0
+        });
+    }
+}
+
+// Two gateways exchange events and reconnect. Both should track the traffic of each connection,
+// and the number of reconnects across connections.
+#[tokio::test(flavor = "multi_thread")]
+async fn connection_stats_track_traffic_and_reconnects() {
+    const NUM_NODES: u16 = 4;
+    const NUM_CONNECTIONS: u32 = 3;
+    const NUM_PINGS: u64 = 5;
+
+    /// Returns the number of worker pings and their bytes, sent and received over the only connection of the gateway,
+    /// and the number of reconnects.
+    fn worker_pings(gateway: &Gateway<CurrentNetwork>) -> Option<(u64, u64, u64, u64, u32)> {
+        let connection = gateway.connection_stats().pop()?;
+        let traffic = connection.traffic.into_iter().find(|traffic| traffic.name == "WorkerPing").unwrap_or_default();
+        Some((
+            traffic.messages_sent,
+            traffic.bytes_sent,
+            traffic.messages_received,
+            traffic.bytes_received,
+            connection.num_reconnects,
+        ))
+    }
+
+    let mut rng = TestRng::default();
+    let (accounts, committee) = new_test_committee(NUM_NODES, &mut rng);
+    let ledger = sample_ledger(&accounts, &committee, &mut rng);
+
+    // Initialize two gateways, listening on random ports.
+    let mut gateways = Vec::new();
+    for account in &accounts[..2] {
+        let storage = sample_storage(ledger.clone());
+        let ip = Some("127.0.0.1:0".parse().unwrap());
+        let gateway = Gateway::new(account.clone(), storage, ledger.clone(), ip, &[], None).unwrap();
+        let (primary_tx, _primary_rx) = init_primary_channels();
+        gateway.run(primary_tx, [].into(), None).await;
+        gateways.push(gateway);
+    }
+    let (gateway0, gateway1) = (gateways[0].clone(), gateways[1].clone());
+    let (ip0, ip1) = (gateway0.local_ip(), gateway1.local_ip());
+
+    for num_reconnects in 0..NUM_CONNECTIONS {
+        // Connect the gateways.
+        gateway0.connect(ip1).unwrap().await.unwrap();
+        let (gateway0_, gateway1_) = (gateway0.clone(), gateway1.clone());
+        deadline!(Duration::from_secs(5), move || gateway0_.is_connected_ip(ip1) && gateway1_.is_connected_ip(ip0));
+
+        // Ensure the new connection starts without any worker pings, but with the reconnects of the validator.
+        assert_eq!(worker_pings(&gateway0), Some((0, 0, 0, 0, num_reconnects)));
+        assert_eq!(worker_pings(&gateway1), Some((0, 0, 0, 0, num_reconnects)));
+
+        // Send a few worker pings, and wait for them to be received.
+        for _ in 0..NUM_PINGS {
+            let event = Event::WorkerPing(WorkerPing::new([].into()));
+            Transport::send(&gateway0, ip1, event).await.unwrap().await.unwrap().unwrap();
+        }
+        let gateway1_ = gateway1.clone();
+        deadline!(Duration::from_secs(5), move || {
+            worker_pings(&gateway1_).is_some_and(|(_, _, num_received, _, _)| num_received == NUM_PINGS)
+        });
+
+        // Ensure both sides agree on the traffic.
+        let (num_sent, bytes_sent, ..) = worker_pings(&gateway0).unwrap();
+        let (_, _, num_received, bytes_received, _) = worker_pings(&gateway1).unwrap();
+        assert_eq!((num_sent, num_received), (NUM_PINGS, NUM_PINGS));
+        assert!(bytes_sent > 0);
+        assert_eq!(bytes_sent, bytes_received);
+        // Ensure the connection reports its uptime, without any errors.
+        let connection = gateway1.connection_stats().pop().unwrap();
+        assert_eq!(connection.address, accounts[0].address());
+        assert!(connection.uptime >= 0);
+        assert_eq!(connection.last_error, None);
+
+        // Disconnect the gateways.
+        gateway0.disconnect(ip1).await.unwrap();
+        let (gateway0_, gateway1_) = (gateway0.clone(), gateway1.clone());
+        deadline!(Duration::from_secs(5), move || {
+            gateway0_.number_of_connected_peers() == 0
+                && gateway1_.number_of_connected_peers() == 0
+                && gateway0_.tcp().num_connected() == 0
+                && gateway1_.tcp().num_connected() == 0
+        });
+        assert!(gateway0.connection_stats().is_empty());
+        assert!(gateway1.connection_stats().is_empty());
+    }
+
+    // Connect the gateways, and disconnect with a reason, which the peer records as an error.
+    gateway0.connect(ip1).unwrap().await.unwrap();
+    let gateway1_ = gateway1.clone();
+    deadline!(Duration::from_secs(5), move || gateway1_.is_connected_ip(ip0));
+    Transport::send(&gateway0, ip1, DisconnectReason::NoReasonGiven.into()).await.unwrap().await.unwrap().unwrap();
+    let gateway1_ = gateway1.clone();
+    deadline!(Duration::from_secs(5), move || gateway1_.number_of_connected_peers() == 0);
+
+    // Ensure the error outlives the connection.
+    gateway0.connect(ip1).unwrap().await.unwrap();
+    let gateway1_ = gateway1.clone();
+    deadline!(Duration::from_secs(5), move || gateway1_.is_connected_ip(ip0));
+    let connection = gateway1.connection_stats().pop().unwrap();
+    assert_eq!(connection.num_reconnects, NUM_CONNECTIONS + 1);
+    let (error, _timestamp) = connection.last_error.unwrap();
+    assert!(error.contains("NoReasonGiven"), "{error}");
+}
@@ -17,7 +17,7 @@
 mod common;
 
 use crate::common::primary::{TestNetwork, TestNetworkConfig};
-use snarkos_node_bft::MAX_FETCH_TIMEOUT_IN_MS;
+use snarkos_node_bft::{MAX_FETCH_TIMEOUT_IN_MS, helpers::now};
 
 use std::time::Duration;
 
@@ -163,3 +163,89 @@ async fn test_storage_coherence() {
     // the nodes have completed the round.
     assert!(network.is_certificate_round_coherent(1..TARGET_ROUND - 1));
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_committee_connectivity() {
+    // Start N nodes, connect them and start the cannons for each.
+    const N: u16 = 4;
+    const TRANSMISSION_INTERVAL_MS: u64 = 10;
+    let mut network = TestNetwork::new(TestNetworkConfig {
+        num_nodes: N,
+        bft: false,
+        connect_all: true,
+        fire_transmissions: Some(TRANSMISSION_INTERVAL_MS),
+        // Set this to Some(0..=4) to see the logs.
+        log_level: None,
+        log_connections: true,
+    });
+    network.start().await;
+
+    // Check the nodes have started advancing through the rounds.
+    const TARGET_ROUND: u64 = 4;
+    let network_clone = network.clone();
+    deadline!(Duration::from_secs(20), move || { network_clone.is_round_reached(TARGET_ROUND) });
+
+    // Check the first node is connected to, and has heard from, every other member.
+    let observer = network.validators.get(&0).unwrap().primary.clone();
+    let addresses =
+        (1..N).map(|id| network.validators.get(&id).unwrap().primary.gateway().account().address()).collect::<Vec<_>>();
+    let connected = observer.gateway().connected_addresses();
+    let last_activity = observer.gateway().last_activity();
+    for address in &addresses {
+        assert!(connected.contains(address));
+        assert!(last_activity.contains_key(address));
+    }
+    // Check the signers of the latest certificate of the first node include itself and a quorum.
+    let (_, signers) = observer.latest_certificate_signers().unwrap();
+    assert!(signers.contains(&observer.gateway().account().address()));
+    assert!(signers.len() >= 3);
+
+    // Stop the gateway of the last node.
+    network.validators.get(&(N - 1)).unwrap().primary.gateway().shut_down().await;
+    let stopped_at = now();
+    sleep(Duration::from_secs(3)).await;
+
+    // Check the last node is flagged as disconnected, with a stale last activity.
+    let stopped = addresses[addresses.len() - 1];
+    let connected = observer.gateway().connected_addresses();
+    let last_activity = observer.gateway().last_activity();
+    assert!(!connected.contains(&stopped));
+    assert!(last_activity[&stopped] <= stopped_at);
+    // Check the remaining nodes are still connected and active.
+    for address in &addresses[..addresses.len() - 1] {
+        assert!(connected.contains(address));
+        assert!(last_activity[address] > stopped_at);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_transmission_overlap() {
+    // Start N nodes, connect them and start the cannons for each.
+    // Note: Half of the fired transmissions are shared by all nodes, and half are unique to each node.
+    const N: u16 = 4;
+    const TRANSMISSION_INTERVAL_MS: u64 = 10;
+    let mut network = TestNetwork::new(TestNetworkConfig {
+        num_nodes: N,
+        bft: false,
+        connect_all: true,
+        fire_transmissions: Some(TRANSMISSION_INTERVAL_MS),
+        // Set this to Some(0..=4) to see the logs.
+        log_level: None,
+        log_connections: true,
+    });
+    network.start().await;
+
+    // Check the nodes have started advancing through the rounds.
+    const TARGET_ROUND: u64 = 8;
+    let network_clone = network.clone();
+    deadline!(Duration::from_secs(30), move || { network_clone.is_round_reached(TARGET_ROUND) });
+
+    // Check the batches from peers mostly contain transmissions the nodes already held, as the proposals
+    // prefer the shared transmissions (gossiped by the other nodes) over the unique ones.
+    // Note: In arrival order, the shared and unique transmissions would be proposed in equal parts.
+    for validator in network.validators.values() {
+        let (num_held, num_fetched) = validator.primary.transmission_overlap();
+        assert!(num_held + num_fetched > 0);
+        assert!(num_held > num_fetched, "Validator {} held {num_held} and fetched {num_fetched}", validator.id);
+    }
+}
@@ -20,25 +20,31 @@
 use snarkvm::prelude::{
     Deserialize,
     DeserializeOwned,
+    Field,
     Ledger,
     Network,
     Serialize,
+    SizeInBytes,
     block::Block,
     store::{ConsensusStorage, cow_to_copied},
 };
 
-use anyhow::{Result, anyhow, bail};
+use anyhow::{Result, anyhow, bail, ensure};
+use bincode::Options;
 use colored::Colorize;
 use parking_lot::Mutex;
 use reqwest::Client;
+use sha2::{Digest, Sha256};
 use std::{
     cmp,
+    collections::HashMap,
     sync::{
         Arc,
         atomic::{AtomicBool, AtomicU32, Ordering},
     },
     time::{Duration, Instant},
 };
+use tokio::sync::{Semaphore, SemaphorePermit};
 
 /// The number of blocks per file.
 const BLOCKS_PER_FILE: u32 = 50;
@@ -48,6 +54,27 @@ const CONCURRENT_REQUESTS: u32 = 16;
 const MAXIMUM_PENDING_BLOCKS: u32 = BLOCKS_PER_FILE * CONCURRENT_REQUESTS * 2;
 /// Maximum number of attempts for a request to the CDN.
 const MAXIMUM_REQUEST_ATTEMPTS: u8 = 10;
+/// The maximum size of a block in bytes, as bounded by the maximum size of a `BlockResponse` message
+/// (128 MiB for up to 5 blocks).
+const MAXIMUM_BLOCK_SIZE: u64 = 128 * 1024 * 1024 / 5;
+/// The maximum size of a block file in bytes.
+const MAXIMUM_BLOCK_FILE_SIZE: u64 = MAXIMUM_BLOCK_SIZE * BLOCKS_PER_FILE as u64;
+/// The maximum number of bytes of the block files held in memory at once, across all the concurrent requests,
+/// from the start of their download until their blocks are parsed.
+const MAXIMUM_IN_FLIGHT_BYTES: u64 = MAXIMUM_BLOCK_FILE_SIZE;
+/// The maximum size of a metadata file in bytes, such as the latest state or the checksum manifest.
+const MAXIMUM_METADATA_FILE_SIZE: u64 = 16 * 1024 * 1024;
+/// The name of the optional checksum manifest of a CDN source, which maps each block file name
+/// to the hex-encoded SHA-256 digest of its contents.
+const CHECKSUM_MANIFEST: &str = "checksums.json";
+
+/// A source of block files, with its checksum manifest, if it provides one.
+struct CdnSource {
+    /// The base URL of the source.
+    base_url: String,
+    /// The checksum manifest of the source, if any.
+    checksums: Option<HashMap<String, String>>,
+}
 
 /// Loads blocks from a CDN into the ledger.
 ///
@@ -97,6 +124,9 @@ pub async fn sync_ledger_with_cdn<N: Network, C: ConsensusStorage<N>>(
 
 /// Loads blocks from a CDN and process them with the given function.
 ///
+/// The base URL may list alternate sources separated by commas, in which case a block file that fails
+/// to download or validate from one source is retried from the next one.
+///
 /// On success, this function returns the completed block height.
 /// On failure, this function returns the last successful block height (if any), along with the error.
 pub async fn load_blocks<N: Network>(
@@ -114,8 +144,22 @@ pub async fn load_blocks<N: Network>(
         }
     };
 
-    // Fetch the CDN height.
-    let cdn_height = match cdn_height::<BLOCKS_PER_FILE>(&client, base_url).await {
+    // Parse the sources.
+    let base_urls = parse_base_urls(base_url);
+    if base_urls.is_empty() {
+        return Err((start_height, anyhow!("No CDN source was given")));
+    }
+
+    // Fetch the CDN height, from the first source that responds.
+    let mut cdn_height_result = Err(anyhow!("No CDN source was given"));
+    for base_url in &base_urls {
+        cdn_height_result = cdn_height::<BLOCKS_PER_FILE>(&client, base_url).await;
+        match &cdn_height_result {
+            Ok(_) => break,
+            Err(error) => warn!("{error}"),
+        }
+    }
+    let cdn_height = match cdn_height_result {
         Ok(cdn_height) => cdn_height,
         Err(error) => return Err((start_height, error)),
     };
@@ -153,12 +197,19 @@ pub async fn load_blocks<N: Network>(
     // Start a timer.
     let timer = Instant::now();
 
+    // Fetch the checksum manifest of each source, if it provides one.
+    let mut sources = Vec::with_capacity(base_urls.len());
+    for base_url in base_urls {
+        let checksums = cdn_checksums(&client, &base_url).await;
+        sources.push(CdnSource { base_url, checksums });
+    }
+
     // Spawn a background task responsible for concurrent downloads.
     let pending_blocks_clone = pending_blocks.clone();
-    let base_url = base_url.to_owned();
+    let sources = Arc::new(sources);
     let shutdown_clone = shutdown.clone();
     tokio::spawn(async move {
-        download_block_bundles(client, base_url, cdn_start, cdn_end, pending_blocks_clone, shutdown_clone).await;
+        download_block_bundles(client, sources, cdn_start, cdn_end, pending_blocks_clone, shutdown_clone).await;
     });
 
     // A loop for inserting the pending blocks into the ledger.
@@ -232,7 +283,7 @@ pub async fn load_blocks<N: Network>(
 
 async fn download_block_bundles<N: Network>(
     client: Client,
-    base_url: String,
+    sources: Arc<Vec<CdnSource>>,
     cdn_start: u32,
     cdn_end: u32,
     pending_blocks: Arc<Mutex<Vec<Block<N>>>>,
@@ -240,6 +291,8 @@ async fn download_block_bundles<N: Network>(
 ) {
     // Keep track of the number of concurrent requests.
     let active_requests: Arc<AtomicU32> = Default::default();
+    // Bound the bytes held in memory across the concurrent requests.
+    let in_flight_bytes = Arc::new(Semaphore::new(MAXIMUM_IN_FLIGHT_BYTES as usize));
 
     let mut start = cdn_start;
     while start < cdn_end - 1 {
@@ -275,9 +328,10 @@ async fn download_block_bundles<N: Network>(
             }
 
             let client_clone = client.clone();
-            let base_url_clone = base_url.clone();
+            let sources_clone = sources.clone();
             let pending_blocks_clone = pending_blocks.clone();
             let active_requests_clone = active_requests.clone();
+            let in_flight_bytes_clone = in_flight_bytes.clone();
             let shutdown_clone = shutdown.clone();
             tokio::spawn(async move {
                 // Increment the number of active requests.
@@ -286,17 +340,16 @@ async fn download_block_bundles<N: Network>(
                 let ctx = format!("blocks {start} to {end}");
                 debug!("Requesting {ctx} (of {cdn_end})");
 
-                // Prepare the URL.
-                let blocks_url = format!("{base_url_clone}/{start}.{end}.blocks");
-                let ctx = format!("blocks {start} to {end}");
                 // Download blocks, retrying on failure.
                 let mut attempts = 0;
                 let request_time = Instant::now();
 
                 loop {
+                    // Rotate through the sources on each attempt.
+                    let source = &sources_clone[attempts as usize % sources_clone.len()];
                     // Fetch the blocks.
-                    match cdn_get(client_clone.clone(), &blocks_url, &ctx).await {
-                        Ok::<Vec<Block<N>>, _>(blocks) => {
+                    match cdn_get_blocks::<N>(&client_clone, source, &in_flight_bytes_clone, start, end, &ctx).await {
+                        Ok(blocks) => {
                             // Keep the collection of pending blocks sorted by the height.
                             let mut pending_blocks = pending_blocks_clone.lock();
                             for block in blocks {
@@ -309,15 +362,19 @@ async fn download_block_bundles<N: Network>(
                             break;
                         }
                         Err(error) => {
-                            // Increment the attempt counter, and wait with a linear backoff, or abort in
+                            // Increment the attempt counter, and retry from the next source, or abort in
                             // case the maximum number of attempts has been breached.
                             attempts += 1;
                             if attempts > MAXIMUM_REQUEST_ATTEMPTS {
-                                warn!("Maximum number of requests to {blocks_url} reached - shutting down...");
+                                warn!("Maximum number of requests for {ctx} reached - shutting down...");
                                 shutdown_clone.store(true, Ordering::Relaxed);
                                 break;
                             }
-                            tokio::time::sleep(Duration::from_secs(attempts as u64 * 10)).await;
+                            // Wait with a linear backoff, once every source has failed.
+                            let num_rounds = attempts as usize / sources_clone.len();
+                            if attempts as usize % sources_clone.len() == 0 {
+                                tokio::time::sleep(Duration::from_secs(num_rounds as u64 * 10)).await;
+                            }
                             warn!("{error} - retrying ({attempts} attempt(s) so far)");
                         }
                     }
@@ -352,18 +409,10 @@ async fn cdn_height<const BLOCKS_PER_FILE: u32>(client: &Client, base_url: &str)
     }
     // Prepare the URL.
     let latest_json_url = format!("{base_url}/latest.json");
-    // Send the request.
-    let response = match client.get(latest_json_url).send().await {
-        Ok(response) => response,
-        Err(error) => bail!("Failed to fetch the CDN height - {error}"),
-    };
-    // Parse the response.
-    let bytes = match response.bytes().await {
-        Ok(bytes) => bytes,
-        Err(error) => bail!("Failed to parse the CDN height response - {error}"),
-    };
+    // Fetch the bytes.
+    let bytes = cdn_get_bytes(client, &latest_json_url, "the CDN height", MAXIMUM_METADATA_FILE_SIZE).await?;
     // Parse the bytes for the string.
-    let latest_state_string = match bincode::deserialize::<String>(&bytes) {
+    let latest_state_string = match deserialize_bounded::<String>(&bytes) {
         Ok(string) => string,
         Err(error) => bail!("Failed to deserialize the CDN height response - {error}"),
     };
@@ -378,26 +427,202 @@ async fn cdn_height<const BLOCKS_PER_FILE: u32>(client: &Client, base_url: &str)
     Ok(tip - (tip % BLOCKS_PER_FILE) + BLOCKS_PER_FILE)
 }
 
-/// Retrieves the objects from the CDN with the given URL.
-async fn cdn_get<T: 'static + DeserializeOwned + Send>(client: Client, url: &str, ctx: &str) -> Result<T> {
-    // Fetch the bytes from the given URL.
+/// Retrieves the blocks in the given range from the given source, and ensures they are the expected blocks.
+///
+/// The bytes of the block file are reserved from the given in-flight budget until its blocks are parsed.
+async fn cdn_get_blocks<N: Network>(
+    client: &Client,
+    source: &CdnSource,
+    in_flight_bytes: &Semaphore,
+    start: u32,
+    end: u32,
+    ctx: &str,
+) -> Result<Vec<Block<N>>> {
+    let file_name = format!("{start}.{end}.blocks");
+    let url = format!("{}/{file_name}", source.base_url);
+    // Fetch the bytes of the block file.
+    let (bytes, _permit) =
+        cdn_get_bytes_within(client, &url, ctx, in_flight_bytes, MAXIMUM_BLOCK_FILE_SIZE.min(MAXIMUM_IN_FLIGHT_BYTES))
+            .await?;
+    // Verify the checksum of the block file, if the source provides one.
+    if let Some(expected) = source.checksums.as_ref().and_then(|checksums| checksums.get(&file_name)) {
+        let checksum = format!("{:x}", Sha256::digest(&bytes));
+        if !checksum.eq_ignore_ascii_case(expected) {
+            bail!("Failed to verify {ctx} from {url} - the checksum does not match the manifest");
+        }
+    }
+    // Ensure the block file belongs to the network, before parsing its blocks.
+    if let Err(error) = check_block_file_network::<N>(&bytes) {
+        bail!("Failed to validate {ctx} from {url} - {error}");
+    }
+    // Parse the blocks, and ensure they are the expected blocks, before they are verified by the ledger.
+    let ctx = ctx.to_string();
+    match tokio::task::spawn_blocking(move || deserialize_bounded::<Vec<Block<N>>>(&bytes)).await {
+        Ok(Ok(blocks)) => match check_blocks(&blocks, start, end) {
+            Ok(()) => Ok(blocks),
+            Err(error) => bail!("Failed to validate {ctx} from {url} - {error}"),
+        },
+        Ok(Err(error)) => bail!("Failed to deserialize {ctx} from {url} - {error}"),
+        Err(error) => bail!("Failed to join task for {ctx} - {error}"),
+    }
+}
+
+/// Retrieves the bytes from the CDN with the given URL, failing as soon as they exceed the given size.
+async fn cdn_get_bytes(client: &Client, url: &str, ctx: &str, max_size: u64) -> Result<Vec<u8>> {
+    let response = cdn_get_response(client, url, ctx, max_size).await?;
+    read_bytes(response, url, ctx, max_size).await
+}
+
+/// Retrieves the bytes from the CDN with the given URL, once the bytes they may take are reserved from
+/// the given budget, and returns them with the reservation, which is released once it is dropped.
+///
+/// The advertised size of the response is reserved, or the given size if it does not advertise one.
+/// As the reservation is taken at once, the concurrent requests cannot wait on each other while holding
+/// parts of the budget.
+async fn cdn_get_bytes_within<'a>(
+    client: &Client,
+    url: &str,
+    ctx: &str,
+    budget: &'a Semaphore,
+    max_size: u64,
+) -> Result<(Vec<u8>, SemaphorePermit<'a>)> {
+    let response = cdn_get_response(client, url, ctx, max_size).await?;
+    // Reserve the bytes of the response from the budget.
+    let reserved = response.content_length().unwrap_or(max_size);
+    let permit = match budget.acquire_many(u32::try_from(reserved)?).await {
+        Ok(permit) => permit,
+        Err(error) => bail!("Failed to reserve the bytes of {ctx} - {error}"),
+    };
+    let bytes = read_bytes(response, url, ctx, reserved).await?;
+    Ok((bytes, permit))
+}
+
+/// Sends a request to the CDN with the given URL, and ensures it succeeded, and its advertised size is
+/// within the given size.
+async fn cdn_get_response(client: &Client, url: &str, ctx: &str, max_size: u64) -> Result<reqwest::Response> {
+    // Send the request.
     let response = match client.get(url).send().await {
         Ok(response) => response,
         Err(error) => bail!("Failed to fetch {ctx} - {error}"),
     };
-    // Parse the response.
-    let bytes = match response.bytes().await {
+    // Ensure the request succeeded.
+    if !response.status().is_success() {
+        bail!("Failed to fetch {ctx} - {url} responded with '{}'", response.status());
+    }
+    // Ensure the advertised size is within the limit.
+    if let Some(content_length) = response.content_length() {
+        if content_length > max_size {
+            bail!("Failed to fetch {ctx} - {url} exceeds the maximum size ({content_length} > {max_size} bytes)");
+        }
+    }
+    Ok(response)
+}
+
+/// Streams the body of the given response, failing as soon as it exceeds the given size.
+async fn read_bytes(mut response: reqwest::Response, url: &str, ctx: &str, max_size: u64) -> Result<Vec<u8>> {
+    // Stream the response, ensuring the actual size is within the limit.
+    let mut bytes = Vec::new();
+    loop {
+        match response.chunk().await {
+            Ok(Some(chunk)) => {
+                if bytes.len() as u64 + chunk.len() as u64 > max_size {
+                    bail!("Failed to fetch {ctx} - {url} exceeds the maximum size ({max_size} bytes)");
+                }
+                bytes.extend_from_slice(&chunk);
+            }
+            Ok(None) => return Ok(bytes),
+            Err(error) => bail!("Failed to parse {ctx} - {error}"),
+        }
+    }
+}
+
+/// Retrieves the checksum manifest of the given source, if it provides one.
+async fn cdn_checksums(client: &Client, base_url: &str) -> Option<HashMap<String, String>> {
+    let url = format!("{base_url}/{CHECKSUM_MANIFEST}");
+    let bytes = match cdn_get_bytes(client, &url, "the checksum manifest", MAXIMUM_METADATA_FILE_SIZE).await {
         Ok(bytes) => bytes,
-        Err(error) => bail!("Failed to parse {ctx} - {error}"),
+        Err(error) => {
+            debug!("Skipping the checksum verification for {base_url} - {error}");
+            return None;
+        }
     };
-    // Parse the objects.
-    match tokio::task::spawn_blocking(move || bincode::deserialize::<T>(&bytes)).await {
-        Ok(Ok(objects)) => Ok(objects),
-        Ok(Err(error)) => bail!("Failed to deserialize {ctx} - {error}"),
-        Err(error) => bail!("Failed to join task for {ctx} - {error}"),
+    match serde_json::from_slice(&bytes) {
+        Ok(checksums) => Some(checksums),
+        Err(error) => {
+            warn!("Skipping the checksum verification for {base_url} - malformed manifest - {error}");
+            None
+        }
+    }
+}
+
+/// Deserializes the given bytes, without reading past them.
+///
+/// This bounds the allocations of any length-prefixed collection to the size of the bytes,
+/// so that a malformed length prefix fails instead of exhausting the memory.
+fn deserialize_bounded<T: DeserializeOwned>(bytes: &[u8]) -> bincode::Result<T> {
+    bincode::DefaultOptions::new()
+        .with_fixint_encoding()
+        .allow_trailing_bytes()
+        .with_limit(bytes.len() as u64)
+        .deserialize(bytes)
+}
+
+/// Returns the offset of the network ID of the first block in a block file: the length of the file and of the block,
+/// the version, hash, and previous hash of the block, the version and the six roots of its header,
+/// and the version of its metadata.
+fn first_block_network_offset<N: Network>() -> usize {
+    // The hashes of the block and the roots of its header are field elements of the network.
+    let field_size = Field::<N>::size_in_bytes();
+    2 * std::mem::size_of::<u64>() + 1 + 2 * field_size + 1 + 6 * field_size + 1
+}
+
+/// Returns the network ID of the first block in the given block file, without deserializing the blocks.
+fn peek_block_file_network<N: Network>(bytes: &[u8]) -> Option<u16> {
+    let offset = first_block_network_offset::<N>();
+    let network = bytes.get(offset..offset + 2)?;
+    Some(u16::from_le_bytes([network[0], network[1]]))
+}
+
+/// Ensures the given block file belongs to the network of the node.
+///
+/// Note: A file too short to hold a block is left to fail its deserialization.
+fn check_block_file_network<N: Network>(bytes: &[u8]) -> Result<()> {
+    match peek_block_file_network::<N>(bytes) {
+        Some(network) if network != N::ID => {
+            bail!("The block file belongs to network {network}, not network {}", N::ID)
+        }
+        _ => Ok(()),
     }
 }
 
+/// Ensures the given blocks are consecutive from the start height, within the given range,
+/// and that each block links to the previous one.
+fn check_blocks<N: Network>(blocks: &[Block<N>], start: u32, end: u32) -> Result<()> {
+    ensure!(!blocks.is_empty(), "The block file is empty");
+    ensure!(blocks.len() <= (end - start) as usize, "The block file contains {} blocks", blocks.len());
+    for (expected_height, block) in (start..end).zip(blocks) {
+        ensure!(block.height() == expected_height, "Expected block {expected_height}, found block {}", block.height());
+    }
+    for pair in blocks.windows(2) {
+        ensure!(
+            pair[1].previous_hash() == pair[0].hash(),
+            "Block {} does not link to the previous block",
+            pair[1].height()
+        );
+    }
+    Ok(())
+}
+
+/// Returns the base URLs of the given comma-separated sources.
+fn parse_base_urls(base_url: &str) -> Vec<String> {
+    base_url
+        .split(',')
+        .map(|base_url| base_url.trim().trim_end_matches('/'))
+        .filter(|base_url| !base_url.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
 /// Logs the progress of the sync.
 fn log_progress<const OBJECTS_PER_FILE: u32>(
     timer: Instant,
@@ -429,18 +654,132 @@ fn log_progress<const OBJECTS_PER_FILE: u32>(
 #[cfg(test)]
 mod tests {
     use crate::{
-        blocks::{BLOCKS_PER_FILE, cdn_get, cdn_height, log_progress},
+        blocks::{
+            BLOCKS_PER_FILE,
+            MAXIMUM_METADATA_FILE_SIZE,
+            cdn_get_bytes,
+            cdn_get_bytes_within,
+            cdn_height,
+            check_block_file_network,
+            check_blocks,
+            deserialize_bounded,
+            first_block_network_offset,
+            log_progress,
+            peek_block_file_network,
+        },
         load_blocks,
     };
-    use snarkvm::prelude::{MainnetV0, block::Block};
+    use snarkos_node_bft_ledger_service::test_helpers::sample_ledger;
+    use snarkvm::prelude::{FromBytes, MainnetV0, Network, TestnetV0, block::Block};
 
     use parking_lot::RwLock;
-    use std::{sync::Arc, time::Instant};
+    use rand::SeedableRng;
+    use rand_chacha::ChaChaRng;
+    use sha2::{Digest, Sha256};
+    use std::{
+        collections::HashMap,
+        sync::{Arc, OnceLock},
+        time::{Duration, Instant},
+    };
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+        sync::Semaphore,
+    };
 
     type CurrentNetwork = MainnetV0;
 
     const TEST_BASE_URL: &str = "https://s3.us-west-1.amazonaws.com/testnet3.blocks/phase3";
 
+    /// The height of the latest sample block.
+    const SAMPLE_HEIGHT: u32 = 60;
+
+    /// Returns the blocks of a devnet ledger, from the genesis block up to `SAMPLE_HEIGHT`.
+    fn sample_blocks() -> &'static [Block<CurrentNetwork>] {
+        static BLOCKS: OnceLock<Vec<Block<CurrentNetwork>>> = OnceLock::new();
+        BLOCKS.get_or_init(|| {
+            let rng = &mut ChaChaRng::seed_from_u64(1234567890u64);
+            let (_, ledger) = sample_ledger::<CurrentNetwork, _>(SAMPLE_HEIGHT, rng);
+            (0..=SAMPLE_HEIGHT).map(|height| ledger.get_block(height).unwrap()).collect()
+        })
+    }
+
+    /// Returns the bytes of the `latest.json` file of a CDN with the sample blocks.
+    fn sample_latest_json() -> Vec<u8> {
+        let latest = serde_json::json!({
+            "exclusive_height": SAMPLE_HEIGHT + 1,
+            "inclusive_height": SAMPLE_HEIGHT,
+            "hash": sample_blocks().last().unwrap().hash().to_string(),
+        });
+        bincode::serialize(&latest.to_string()).unwrap()
+    }
+
+    /// The response of the local CDN server for a path.
+    #[derive(Clone)]
+    enum Route {
+        /// The given bytes.
+        Bytes(Vec<u8>),
+        /// An endless body, without a `Content-Length`.
+        Endless,
+        /// A `Content-Length` of 1 TiB, with an endless body.
+        Oversized,
+    }
+
+    /// Starts a local CDN server with the given routes, and returns its URL and the log of the requested paths.
+    async fn start_server(routes: HashMap<String, Route>) -> (String, Arc<RwLock<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests: Arc<RwLock<Vec<String>>> = Default::default();
+        let requests_clone = requests.clone();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let routes = routes.clone();
+                let requests = requests_clone.clone();
+                tokio::spawn(async move {
+                    // Read the request head.
+                    let mut head = Vec::new();
+                    let mut buffer = [0u8; 1024];
+                    while !head.windows(4).any(|window| window == b"\r\n\r\n") {
+                        match stream.read(&mut buffer).await {
+                            Ok(0) | Err(_) => return,
+                            Ok(num_bytes) => head.extend_from_slice(&buffer[..num_bytes]),
+                        }
+                    }
+                    let path = String::from_utf8_lossy(&head).split_whitespace().nth(1).unwrap_or_default().to_string();
+                    requests.write().push(path.clone());
+                    // Write the response, until the client hangs up.
+                    let (header, body): (_, Option<&[u8]>) = match routes.get(&path) {
+                        Some(Route::Bytes(bytes)) => {
+                            (format!("200 OK\r\nContent-Length: {}", bytes.len()), Some(bytes.as_slice()))
+                        }
+                        Some(Route::Endless) => ("200 OK".to_string(), None),
+                        Some(Route::Oversized) => ("200 OK\r\nContent-Length: 1099511627776".to_string(), None),
+                        None => ("404 Not Found\r\nContent-Length: 0".to_string(), Some(&[][..])),
+                    };
+                    let header = format!("HTTP/1.1 {header}\r\nConnection: close\r\n\r\n");
+                    if stream.write_all(header.as_bytes()).await.is_err() {
+                        return;
+                    }
+                    match body {
+                        Some(bytes) => {
+                            let _ = stream.write_all(bytes).await;
+                        }
+                        None => {
+                            let chunk = vec![0u8; 64 * 1024];
+                            while stream.write_all(&chunk).await.is_ok() {}
+                        }
+                    }
+                });
+            }
+        });
+        (url, requests)
+    }
+
+    /// Returns the number of times the given path was requested.
+    fn num_requests(requests: &RwLock<Vec<String>>, path: &str) -> usize {
+        requests.read().iter().filter(|request| *request == path).count()
+    }
+
     fn check_load_blocks(start: u32, end: Option<u32>, expected: usize) {
         let blocks = Arc::new(RwLock::new(Vec::new()));
         let blocks_clone = blocks.clone();
@@ -502,12 +841,13 @@ mod tests {
     }
 
     #[test]
-    fn test_cdn_get() {
+    fn test_cdn_get_bytes() {
         let rt = tokio::runtime::Runtime::new().unwrap();
         rt.block_on(async {
             let client = reqwest::Client::new();
-            let height =
-                cdn_get::<u32>(client, &format!("{TEST_BASE_URL}/mainnet/latest/height"), "height").await.unwrap();
+            let url = format!("{TEST_BASE_URL}/mainnet/latest/height");
+            let bytes = cdn_get_bytes(&client, &url, "height", MAXIMUM_METADATA_FILE_SIZE).await.unwrap();
+            let height = deserialize_bounded::<u32>(&bytes).unwrap();
             assert!(height > 0);
         });
     }
@@ -531,4 +871,195 @@ mod tests {
         log_progress::<10>(timer, 90, cdn_start, cdn_end, object_name);
         log_progress::<10>(timer, 100, cdn_start, cdn_end, object_name);
     }
+
+    #[test]
+    fn test_cdn_get_bytes_is_bounded() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async {
+            let routes = HashMap::from([
+                ("/small".to_string(), Route::Bytes(vec![1; 10])),
+                ("/endless".to_string(), Route::Endless),
+                ("/oversized".to_string(), Route::Oversized),
+            ]);
+            let (url, _) = start_server(routes).await;
+            let client = reqwest::Client::new();
+
+            // Ensure a file within the limit is fetched.
+            assert_eq!(cdn_get_bytes(&client, &format!("{url}/small"), "small", 10).await.unwrap(), vec![1; 10]);
+            // Ensure a file beyond the limit is rejected.
+            let error = cdn_get_bytes(&client, &format!("{url}/small"), "small", 9).await.unwrap_err();
+            assert!(error.to_string().contains("exceeds the maximum size"), "{error}");
+            // Ensure an endless file is rejected once the limit is reached, rather than exhausting the memory.
+            let error = cdn_get_bytes(&client, &format!("{url}/endless"), "endless", 1024 * 1024).await.unwrap_err();
+            assert!(error.to_string().contains("exceeds the maximum size (1048576 bytes)"), "{error}");
+            // Ensure a file advertising an oversized length is rejected before its body is read.
+            let error = cdn_get_bytes(&client, &format!("{url}/oversized"), "oversized", 1024).await.unwrap_err();
+            assert!(error.to_string().contains("(1099511627776 > 1024 bytes)"), "{error}");
+            // Ensure a missing file is rejected, rather than parsed.
+            let error = cdn_get_bytes(&client, &format!("{url}/missing"), "missing", 1024).await.unwrap_err();
+            assert!(error.to_string().contains("404"), "{error}");
+        });
+    }
+
+    #[test]
+    fn test_cdn_get_bytes_within_budget() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async {
+            let routes = HashMap::from([
+                ("/small".to_string(), Route::Bytes(vec![1; 10])),
+                ("/endless".to_string(), Route::Endless),
+            ]);
+            let (url, _) = start_server(routes).await;
+            let client = reqwest::Client::new();
+            let budget = Semaphore::new(1024);
+
+            // Ensure the bytes of a file are reserved until the reservation is dropped.
+            let (bytes, permit) =
+                cdn_get_bytes_within(&client, &format!("{url}/small"), "small", &budget, 1024).await.unwrap();
+            assert_eq!(bytes, vec![1; 10]);
+            assert_eq!(budget.available_permits(), 1014);
+            drop(permit);
+            assert_eq!(budget.available_permits(), 1024);
+
+            // Ensure a request waits while the budget is taken by the other requests.
+            let taken = budget.acquire_many(1020).await.unwrap();
+            let request = cdn_get_bytes_within(&client, &format!("{url}/small"), "small", &budget, 1024);
+            tokio::pin!(request);
+            assert!(tokio::time::timeout(Duration::from_millis(200), &mut request).await.is_err());
+            // Ensure the request proceeds once the budget is released.
+            drop(taken);
+            let (bytes, _permit) = request.await.unwrap();
+            assert_eq!(bytes, vec![1; 10]);
+
+            // Ensure a file without an advertised size reserves the maximum size, and releases it on failure.
+            let error =
+                cdn_get_bytes_within(&client, &format!("{url}/endless"), "endless", &budget, 1000).await.unwrap_err();
+            assert!(error.to_string().contains("exceeds the maximum size (1000 bytes)"), "{error}");
+            assert_eq!(budget.available_permits(), 1014);
+        });
+    }
+
+    #[test]
+    fn test_deserialize_bounded() {
+        // Ensure a well-formed file is deserialized.
+        let bytes = bincode::serialize(&vec![1u8, 2, 3]).unwrap();
+        assert_eq!(deserialize_bounded::<Vec<u8>>(&bytes).unwrap(), vec![1, 2, 3]);
+        // Ensure a length prefix beyond the file is rejected, without allocating it.
+        assert!(deserialize_bounded::<String>(&u64::MAX.to_le_bytes()).is_err());
+        assert!(deserialize_bounded::<Vec<Vec<u8>>>(&u64::MAX.to_le_bytes()).is_err());
+        // Ensure a truncated file is rejected.
+        assert!(deserialize_bounded::<Vec<u8>>(&bytes[..bytes.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_check_blocks() {
+        let blocks = sample_blocks();
+        assert!(check_blocks(&blocks[0..50], 0, 50).is_ok());
+        assert!(check_blocks(&blocks[50..], 50, 100).is_ok());
+        // Ensure blocks outside of the range of the file are rejected.
+        assert!(check_blocks(&blocks[50..], 0, 50).is_err());
+        assert!(check_blocks(&blocks[1..51], 0, 50).is_err());
+        assert!(check_blocks(&blocks[0..51], 0, 50).is_err());
+        assert!(check_blocks::<CurrentNetwork>(&[], 0, 50).is_err());
+        // Ensure blocks that are not consecutive are rejected.
+        assert!(check_blocks(&[blocks[0].clone(), blocks[2].clone()], 0, 50).is_err());
+    }
+
+    #[test]
+    fn test_check_block_file_network() {
+        // Ensure the network is read from the first block of a file.
+        let file = bincode::serialize(&sample_blocks()[0..50]).unwrap();
+        assert_eq!(peek_block_file_network::<CurrentNetwork>(&file), Some(CurrentNetwork::ID));
+        assert!(check_block_file_network::<CurrentNetwork>(&file).is_ok());
+
+        // Ensure a block file of another network is rejected, naming both networks.
+        let genesis = Block::<TestnetV0>::from_bytes_le(TestnetV0::genesis_bytes()).unwrap();
+        let file = bincode::serialize(&vec![genesis]).unwrap();
+        assert_eq!(peek_block_file_network::<TestnetV0>(&file), Some(TestnetV0::ID));
+        let error = check_block_file_network::<CurrentNetwork>(&file).unwrap_err();
+        assert_eq!(error.to_string(), "The block file belongs to network 1, not network 0");
+        assert!(check_block_file_network::<TestnetV0>(&file).is_ok());
+
+        // Ensure a file too short to hold a block is left to its deserialization.
+        let offset = first_block_network_offset::<TestnetV0>();
+        assert_eq!(peek_block_file_network::<TestnetV0>(&file[..offset]), None);
+        assert!(check_block_file_network::<CurrentNetwork>(&[]).is_ok());
+    }
+
+    #[test]
+    fn test_load_blocks_falls_back_to_alternate_source() {
+        let blocks = sample_blocks();
+        let file_0 = bincode::serialize(&blocks[0..50]).unwrap();
+        let file_1 = bincode::serialize(&blocks[50..]).unwrap();
+        let checksum_1 = format!("{:x}", Sha256::digest(&file_1));
+
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async {
+            // The primary source serves a malformed first file, and the alternate source serves the blocks.
+            let routes = HashMap::from([
+                ("/a/latest.json".to_string(), Route::Bytes(sample_latest_json())),
+                ("/a/0.50.blocks".to_string(), Route::Bytes(file_0[..file_0.len() / 2].to_vec())),
+                ("/a/50.100.blocks".to_string(), Route::Bytes(file_1.clone())),
+                (
+                    "/a/checksums.json".to_string(),
+                    Route::Bytes(format!(r#"{{"50.100.blocks":"{checksum_1}"}}"#).into()),
+                ),
+                ("/b/latest.json".to_string(), Route::Bytes(sample_latest_json())),
+                ("/b/0.50.blocks".to_string(), Route::Bytes(file_0.clone())),
+                ("/b/50.100.blocks".to_string(), Route::Endless),
+            ]);
+            let (url, requests) = start_server(routes).await;
+
+            let loaded = Arc::new(RwLock::new(Vec::new()));
+            let loaded_clone = loaded.clone();
+            let process = move |block: Block<CurrentNetwork>| {
+                loaded_clone.write().push(block);
+                Ok(())
+            };
+            let base_url = format!("{url}/a,{url}/b");
+            let completed_height = load_blocks(&base_url, 0, Some(SAMPLE_HEIGHT), Default::default(), process).await;
+            assert_eq!(completed_height.unwrap(), SAMPLE_HEIGHT - 1);
+            assert_eq!(*loaded.read(), blocks[..SAMPLE_HEIGHT as usize]);
+
+            // Ensure only the malformed range was retried, from the alternate source.
+            assert_eq!(num_requests(&requests, "/a/0.50.blocks"), 1);
+            assert_eq!(num_requests(&requests, "/b/0.50.blocks"), 1);
+            assert_eq!(num_requests(&requests, "/a/50.100.blocks"), 1);
+            assert_eq!(num_requests(&requests, "/b/50.100.blocks"), 0);
+        });
+    }
+
+    #[test]
+    fn test_load_blocks_verifies_checksums() {
+        let blocks = sample_blocks();
+        let file_0 = bincode::serialize(&blocks[0..50]).unwrap();
+
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async {
+            // The primary source serves a file that does not match its manifest.
+            let checksums = format!(r#"{{"0.50.blocks":"{}"}}"#, "0".repeat(64));
+            let routes = HashMap::from([
+                ("/a/latest.json".to_string(), Route::Bytes(sample_latest_json())),
+                ("/a/0.50.blocks".to_string(), Route::Bytes(file_0.clone())),
+                ("/a/checksums.json".to_string(), Route::Bytes(checksums.into())),
+                ("/b/0.50.blocks".to_string(), Route::Bytes(file_0.clone())),
+            ]);
+            let (url, requests) = start_server(routes).await;
+
+            let loaded = Arc::new(RwLock::new(Vec::new()));
+            let loaded_clone = loaded.clone();
+            let process = move |block: Block<CurrentNetwork>| {
+                loaded_clone.write().push(block);
+                Ok(())
+            };
+            let base_url = format!("{url}/a,{url}/b");
+            let completed_height = load_blocks(&base_url, 0, Some(40), Default::default(), process).await;
+            assert_eq!(completed_height.unwrap(), 39);
+            assert_eq!(*loaded.read(), blocks[..40]);
+
+            // Ensure the file was rejected from the primary source, and fetched from the alternate source.
+            assert_eq!(num_requests(&requests, "/a/0.50.blocks"), 1);
+            assert_eq!(num_requests(&requests, "/b/0.50.blocks"), 1);
+        });
+    }
 }
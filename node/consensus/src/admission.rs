@@ -0,0 +1,255 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{DeploymentRateLimiter, MempoolPolicy, PolicyContext, TransactionsQueue, check_admission};
+use snarkos_node_bft::helpers::fmt_id;
+use snarkos_node_bft_ledger_service::LedgerService;
+use snarkvm::{
+    ledger::{block::Transaction, narwhal::TransmissionID},
+    prelude::{Field, Network},
+};
+
+use anyhow::Result;
+use lru::LruCache;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::{net::SocketAddr, sync::Arc, time::Instant};
+
+/// A check of the admission pipeline of an unconfirmed transaction, listed in the order the checks are run.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AdmissionCheck {
+    /// The storage of the node keeps up, so the transaction is not shed.
+    StorageBackpressure,
+    /// The transaction is not a fee transaction.
+    NotFee,
+    /// The transaction was not recently seen.
+    NotRecentlySeen,
+    /// The transaction is admitted by the mempool policy.
+    MempoolPolicy,
+    /// The transaction is not in the ledger.
+    NotInLedger,
+    /// The transaction is not in the inbound queue.
+    NotInMemoryPool,
+    /// The transaction is not a deployment from a peer above its deployment rate limit.
+    DeploymentRate,
+}
+
+/// The outcome of the admission pipeline of an unconfirmed transaction.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AdmissionOutcome {
+    /// The transaction passed every check, and is queued.
+    Accepted,
+    /// The transaction was recently seen, and is ignored without an error.
+    Ignored,
+    /// The transaction was deferred by the mempool policy, and is ignored without an error until it is received again.
+    Deferred,
+    /// The transaction failed a check, and is rejected with an error.
+    Rejected,
+}
+
+/// The result of a check of the admission pipeline.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct AdmissionCheckResult {
+    /// The check.
+    pub check: AdmissionCheck,
+    /// Whether the transaction passed the check.
+    pub passed: bool,
+}
+
+/// The verdict of the admission pipeline on an unconfirmed transaction.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct AdmissionVerdict {
+    /// The ID of the transaction.
+    pub transaction_id: String,
+    /// The outcome of the pipeline.
+    pub outcome: AdmissionOutcome,
+    /// The checks that were run, in order. The pipeline stops at the first failed check.
+    pub checks: Vec<AdmissionCheckResult>,
+    /// The error a submission of the transaction returns, if it is rejected.
+    pub error: Option<String>,
+}
+
+impl AdmissionVerdict {
+    /// Initializes a verdict on the given transaction, before any check is run.
+    fn new<N: Network>(transaction_id: N::TransactionID) -> Self {
+        Self {
+            transaction_id: transaction_id.to_string(),
+            outcome: AdmissionOutcome::Accepted,
+            checks: vec![],
+            error: None,
+        }
+    }
+
+    /// Records that the transaction passed the given check.
+    fn pass(&mut self, check: AdmissionCheck) {
+        self.checks.push(AdmissionCheckResult { check, passed: true });
+    }
+
+    /// Records that the transaction failed the given check, with the given outcome, and returns the verdict.
+    fn fail(mut self, check: AdmissionCheck, outcome: AdmissionOutcome, error: Option<String>) -> Self {
+        self.checks.push(AdmissionCheckResult { check, passed: false });
+        self.outcome = outcome;
+        self.error = error;
+        self
+    }
+
+    /// Returns the check the transaction failed, if any.
+    pub fn failed_check(&self) -> Option<AdmissionCheck> {
+        self.checks.iter().find(|result| !result.passed).map(|result| result.check)
+    }
+}
+
+/// The state of the node consulted by the admission pipeline of an unconfirmed transaction.
+pub(crate) struct TransactionAdmission<'a, N: Network> {
+    /// The IP of the peer the transaction was received from, if any.
+    pub peer_ip: Option<SocketAddr>,
+    /// The limit on the rate of the deployments received from each peer.
+    pub deployment_limiter: &'a DeploymentRateLimiter,
+    /// Whether the storage of the node is slow, and new work is shed.
+    pub is_throttled: bool,
+    /// The recently-seen unconfirmed transactions.
+    pub seen_transactions: &'a Mutex<LruCache<N::TransactionID, ()>>,
+    /// The unconfirmed transactions queue.
+    pub transactions_queue: &'a Mutex<TransactionsQueue<N>>,
+    /// The local policy deciding which unconfirmed transmissions are admitted into the memory pool.
+    pub mempool_policy: Arc<dyn MempoolPolicy<N>>,
+    /// The snapshot of the node facts, for the mempool policy.
+    pub policy_context: PolicyContext,
+    /// The ledger.
+    pub ledger: &'a dyn LedgerService<N>,
+}
+
+impl<N: Network> TransactionAdmission<'_, N> {
+    /// Runs the admission checks on the given transaction, with the given checksum, up to its insertion in the queue.
+    ///
+    /// If `commit` is `true`, the transaction is marked as seen, and forgotten again if it is deferred, as for a
+    /// submission. Otherwise, the checks are read-only, and only tell whether a submission would be accepted.
+    pub fn check(&self, transaction: &Transaction<N>, checksum: Field<N>, commit: bool) -> Result<AdmissionVerdict> {
+        let transaction_id = transaction.id();
+        let mut verdict = AdmissionVerdict::new::<N>(transaction_id);
+
+        // Check that the storage is not slow.
+        if self.is_throttled {
+            let error =
+                format!("Unable to add transaction '{}' to the memory pool - storage is slow", fmt_id(transaction_id));
+            return Ok(verdict.fail(AdmissionCheck::StorageBackpressure, AdmissionOutcome::Rejected, Some(error)));
+        }
+        verdict.pass(AdmissionCheck::StorageBackpressure);
+        // Check that the transaction is not a fee transaction.
+        if transaction.is_fee() {
+            let error = format!("Transaction '{}' is a fee transaction (skipping)", fmt_id(transaction_id));
+            return Ok(verdict.fail(AdmissionCheck::NotFee, AdmissionOutcome::Rejected, Some(error)));
+        }
+        verdict.pass(AdmissionCheck::NotFee);
+        // Check if the transaction was recently seen.
+        let is_seen = match commit {
+            true => self.seen_transactions.lock().put(transaction_id, ()).is_some(),
+            false => self.seen_transactions.lock().contains(&transaction_id),
+        };
+        if is_seen {
+            return Ok(verdict.fail(AdmissionCheck::NotRecentlySeen, AdmissionOutcome::Ignored, None));
+        }
+        verdict.pass(AdmissionCheck::NotRecentlySeen);
+        // Check if the transaction is admitted by the mempool policy.
+        let admission = self.mempool_policy.admit_transaction(transaction, &self.policy_context);
+        let transmission = format!("Transaction '{}'", fmt_id(transaction_id));
+        match check_admission(self.mempool_policy.name(), &transmission, admission, commit) {
+            Ok(true) => verdict.pass(AdmissionCheck::MempoolPolicy),
+            Ok(false) => {
+                // If the transaction is deferred, forget it, so that it is reconsidered if it is received again.
+                if commit {
+                    self.seen_transactions.lock().pop(&transaction_id);
+                }
+                return Ok(verdict.fail(AdmissionCheck::MempoolPolicy, AdmissionOutcome::Deferred, None));
+            }
+            Err(error) => {
+                let error = Some(error.to_string());
+                return Ok(verdict.fail(AdmissionCheck::MempoolPolicy, AdmissionOutcome::Rejected, error));
+            }
+        }
+        // Check if the transaction already exists in the ledger.
+        if self.ledger.contains_transmission(&TransmissionID::Transaction(transaction_id, checksum))? {
+            let error = format!("Transaction '{}' exists in the ledger (skipping)", fmt_id(transaction_id));
+            return Ok(verdict.fail(AdmissionCheck::NotInLedger, AdmissionOutcome::Rejected, Some(error)));
+        }
+        verdict.pass(AdmissionCheck::NotInLedger);
+        // Check if the transaction already exists in the inbound queue.
+        if self.transactions_queue.lock().contains(&transaction_id) {
+            let error = format!("Transaction '{}' exists in the memory pool", fmt_id(transaction_id));
+            return Ok(verdict.fail(AdmissionCheck::NotInMemoryPool, AdmissionOutcome::Rejected, Some(error)));
+        }
+        verdict.pass(AdmissionCheck::NotInMemoryPool);
+        // Check that the peer of a deployment is below its deployment rate limit.
+        if let (true, Some(peer_ip)) = (transaction.is_deploy(), self.peer_ip) {
+            if let Err(error) = self.deployment_limiter.check(peer_ip, Instant::now(), commit) {
+                // Forget the transaction, so that it is reconsidered if it is received from another peer.
+                if commit {
+                    self.seen_transactions.lock().pop(&transaction_id);
+                }
+                let error = Some(error.to_string());
+                return Ok(verdict.fail(AdmissionCheck::DeploymentRate, AdmissionOutcome::Rejected, error));
+            }
+        }
+        verdict.pass(AdmissionCheck::DeploymentRate);
+        Ok(verdict)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DefaultMempoolPolicy;
+    use snarkos_node_bft_ledger_service::MockLedgerService;
+    use snarkvm::{
+        ledger::{committee::test_helpers::sample_committee, narwhal::Data},
+        prelude::TestRng,
+    };
+
+    type CurrentNetwork = snarkvm::prelude::MainnetV0;
+
+    #[test]
+    fn test_preflight_parity_while_throttled() {
+        let rng = &mut TestRng::default();
+        let transaction = snarkvm::ledger::ledger_test_helpers::sample_execution_transaction_with_fee(false, rng);
+        let checksum = Data::Object(transaction.clone()).to_checksum::<CurrentNetwork>().unwrap();
+        let seen_transactions = Mutex::new(LruCache::new(100.try_into().unwrap()));
+        let (transactions_queue, deployment_limiter) = (Default::default(), Default::default());
+        let ledger = MockLedgerService::new(sample_committee(rng));
+        // Note: The storage backpressure of a running BFT cannot be engaged in a unit test, so it is set here.
+        let admission = TransactionAdmission::<CurrentNetwork> {
+            peer_ip: None,
+            deployment_limiter: &deployment_limiter,
+            is_throttled: true,
+            seen_transactions: &seen_transactions,
+            transactions_queue: &transactions_queue,
+            mempool_policy: Arc::new(DefaultMempoolPolicy),
+            policy_context: Default::default(),
+            ledger: &ledger,
+        };
+
+        // Ensure the transaction is shed before any other check, whether or not the checks are committed.
+        let preflight = admission.check(&transaction, checksum, false).unwrap();
+        assert_eq!(admission.check(&transaction, checksum, true).unwrap(), preflight);
+        assert_eq!(preflight.outcome, AdmissionOutcome::Rejected);
+        assert_eq!(preflight.checks, vec![AdmissionCheckResult {
+            check: AdmissionCheck::StorageBackpressure,
+            passed: false
+        }]);
+        // Ensure the shed transaction was not marked as seen.
+        assert!(seen_transactions.lock().is_empty());
+    }
+}
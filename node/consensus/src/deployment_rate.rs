@@ -0,0 +1,151 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use parking_lot::Mutex;
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
+
+/// The maximum number of deployments a peer can add to the inbound queue in each window.
+pub const MAX_DEPLOYMENTS_PER_PEER: usize = 5;
+/// The duration in seconds of the sliding window of the deployments of each peer.
+pub const DEPLOYMENT_RATE_WINDOW_IN_SECS: u64 = 60;
+/// The number of tracked peers beyond which the peers without recent deployments are forgotten.
+const MAX_TRACKED_PEERS: usize = 1 << 10;
+
+/// The rejection of a deployment from a peer that exceeded the deployment rate limit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DeploymentRateExceeded {
+    /// The IP of the peer.
+    pub peer_ip: SocketAddr,
+    /// The maximum number of deployments of a peer in each window.
+    pub max_deployments: usize,
+    /// The duration of the window in seconds.
+    pub window_in_secs: u64,
+}
+
+impl DeploymentRateExceeded {
+    /// Returns the rejection the given error originates from, if any.
+    pub fn of(error: &anyhow::Error) -> Option<&Self> {
+        error.downcast_ref::<Self>()
+    }
+}
+
+impl fmt::Display for DeploymentRateExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Peer '{}' exceeded the limit of {} deployments per {} seconds",
+            self.peer_ip, self.max_deployments, self.window_in_secs
+        )
+    }
+}
+
+impl std::error::Error for DeploymentRateExceeded {}
+
+/// A sliding window limit on the number of deployments each peer can add to the inbound queue,
+/// so that a single peer cannot crowd out the deployments of the others.
+#[derive(Debug)]
+pub(crate) struct DeploymentRateLimiter {
+    /// The maximum number of deployments of a peer in each window.
+    max_deployments: usize,
+    /// The duration of the window.
+    window: Duration,
+    /// The times of the recent deployments of each peer.
+    deployments: Mutex<HashMap<SocketAddr, VecDeque<Instant>>>,
+}
+
+impl Default for DeploymentRateLimiter {
+    fn default() -> Self {
+        Self::new(MAX_DEPLOYMENTS_PER_PEER, Duration::from_secs(DEPLOYMENT_RATE_WINDOW_IN_SECS))
+    }
+}
+
+impl DeploymentRateLimiter {
+    /// Initializes a new limiter, admitting up to the given number of deployments of a peer in each window.
+    pub fn new(max_deployments: usize, window: Duration) -> Self {
+        Self { max_deployments, window, deployments: Default::default() }
+    }
+
+    /// Checks that the given peer is below the deployment rate limit at the given time.
+    ///
+    /// If `commit` is `true` and the peer is below the limit, the deployment is recorded in its window.
+    pub fn check(&self, peer_ip: SocketAddr, now: Instant, commit: bool) -> Result<(), DeploymentRateExceeded> {
+        let mut deployments = self.deployments.lock();
+        // Forget the peers without recent deployments, if there are too many peers.
+        if deployments.len() >= MAX_TRACKED_PEERS {
+            deployments.retain(|_, times| {
+                Self::expire(times, self.window, now);
+                !times.is_empty()
+            });
+        }
+        let times = deployments.entry(peer_ip).or_default();
+        Self::expire(times, self.window, now);
+        if times.len() >= self.max_deployments {
+            return Err(DeploymentRateExceeded {
+                peer_ip,
+                max_deployments: self.max_deployments,
+                window_in_secs: self.window.as_secs(),
+            });
+        }
+        if commit {
+            times.push_back(now);
+        }
+        Ok(())
+    }
+
+    /// Removes the times that are outside of the window ending at the given time.
+    fn expire(times: &mut VecDeque<Instant>, window: Duration, now: Instant) {
+        while times.front().is_some_and(|time| now.saturating_duration_since(*time) >= window) {
+            times.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deployment_rate_limiter() {
+        let limiter = DeploymentRateLimiter::default();
+        let (spammer, honest) = ("1.1.1.1:4130".parse().unwrap(), "2.2.2.2:4130".parse().unwrap());
+        let start = Instant::now();
+
+        // Ensure the spamming peer is limited once it reaches the maximum number of deployments.
+        for i in 0..MAX_DEPLOYMENTS_PER_PEER {
+            assert!(limiter.check(spammer, start + Duration::from_secs(i as u64), true).is_ok());
+        }
+        let rejection = limiter.check(spammer, start + Duration::from_secs(10), true).unwrap_err();
+        assert_eq!(rejection.peer_ip, spammer);
+        assert_eq!(
+            rejection.to_string(),
+            format!("Peer '1.1.1.1:4130' exceeded the limit of {MAX_DEPLOYMENTS_PER_PEER} deployments per 60 seconds")
+        );
+        // Ensure the honest peer is not affected.
+        assert!(limiter.check(honest, start + Duration::from_secs(10), true).is_ok());
+
+        // Ensure a read-only check does not record the deployment.
+        let window = Duration::from_secs(DEPLOYMENT_RATE_WINDOW_IN_SECS);
+        assert!(limiter.check(honest, start + window, false).is_ok());
+        // Ensure the window slides, one deployment at a time.
+        assert!(limiter.check(spammer, start + window, true).is_ok());
+        assert!(limiter.check(spammer, start + window, true).is_err());
+        assert!(limiter.check(spammer, start + window + Duration::from_secs(1), true).is_ok());
+    }
+}
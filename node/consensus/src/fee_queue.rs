@@ -0,0 +1,276 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::inbound::Queued;
+use snarkvm::prelude::{Network, Transaction};
+
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, HashMap},
+    hash::Hash,
+    time::{Duration, Instant},
+};
+
+/// A transmission that is ordered by its priority fee in a [`FeeQueue`].
+pub(crate) trait PriorityFee {
+    /// Returns the priority fee of the transmission, in microcredits.
+    fn priority_fee(&self) -> u64;
+}
+
+impl<N: Network> PriorityFee for Transaction<N> {
+    fn priority_fee(&self) -> u64 {
+        // Note: A transaction without a fee has the lowest priority.
+        self.priority_fee_amount().map(|fee| *fee).unwrap_or(0)
+    }
+}
+
+/// The priority of a queued transmission, i.e. its priority fee, and then its arrival, with the earliest one first.
+type Priority = (u64, Reverse<u64>);
+
+/// The outcome of inserting a transmission into a [`FeeQueue`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Insertion<K> {
+    /// The transmission was queued.
+    Inserted,
+    /// The transmission was queued, in place of the given transmission with the lowest priority fee.
+    Displaced(K),
+    /// The transmission is already queued.
+    Duplicate,
+    /// The queue is full, and the priority fee of the transmission is not higher than the lowest queued one.
+    Rejected,
+}
+
+/// A bounded queue of unconfirmed transmissions, ordered by their priority fees.
+///
+/// Once the queue is full, an incoming transmission only displaces the queued transmission with the lowest
+/// priority fee if its own priority fee is strictly higher. Among equal priority fees, the earliest arrival
+/// is drained first and displaced last.
+#[derive(Clone)]
+pub(crate) struct FeeQueue<K, T> {
+    /// The maximum number of queued transmissions.
+    capacity: usize,
+    /// The queued transmissions, with their priorities.
+    entries: HashMap<K, (Priority, Queued<T>)>,
+    /// The keys of the queued transmissions, in the order of their priorities, with the lowest one first.
+    order: BTreeMap<Priority, K>,
+    /// The arrival sequence number of the next queued transmission.
+    sequence: u64,
+}
+
+impl<K: Hash + Eq + Clone, T: PriorityFee> FeeQueue<K, T> {
+    /// Initializes a new queue with the given capacity, of at least one transmission.
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity: capacity.max(1), entries: Default::default(), order: Default::default(), sequence: 0 }
+    }
+
+    /// Returns the capacity of the queue.
+    pub const fn cap(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of queued transmissions.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the given transmission is queued.
+    pub fn contains(&self, key: &K) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    /// Returns `true` if inserting the given transmission would displace a queued one, as the queue is full.
+    pub fn displaces(&self, transmission: &T) -> bool {
+        self.entries.len() >= self.capacity
+            && self
+                .order
+                .first_key_value()
+                .is_some_and(|((lowest_fee, _), _)| transmission.priority_fee() > *lowest_fee)
+    }
+
+    /// Returns the given queued transmission, if any.
+    pub fn get(&self, key: &K) -> Option<&Queued<T>> {
+        self.entries.get(key).map(|(_, queued)| queued)
+    }
+
+    /// Returns the queued transmissions, with the highest priority first.
+    pub fn iter(&self) -> impl '_ + Iterator<Item = (&K, &Queued<T>)> {
+        self.order
+            .values()
+            .rev()
+            .filter_map(|key| self.entries.get_key_value(key).map(|(key, (_, queued))| (key, queued)))
+    }
+
+    /// Inserts the given transmission, displacing the transmission with the lowest priority fee if the queue is full.
+    pub fn put(&mut self, key: K, queued: Queued<T>) -> Insertion<K> {
+        if self.entries.contains_key(&key) {
+            return Insertion::Duplicate;
+        }
+        let priority_fee = queued.transmission.priority_fee();
+        let mut insertion = Insertion::Inserted;
+        // If the queue is full, displace the lowest priority fee, if it is strictly lower.
+        if self.entries.len() >= self.capacity {
+            match self.order.first_key_value() {
+                Some(((lowest_fee, _), _)) if priority_fee > *lowest_fee => {
+                    if let Some((lowest_key, _)) = self.pop_lowest() {
+                        insertion = Insertion::Displaced(lowest_key);
+                    }
+                }
+                _ => return Insertion::Rejected,
+            }
+        }
+        let priority = (priority_fee, Reverse(self.sequence));
+        self.sequence += 1;
+        self.order.insert(priority, key.clone());
+        self.entries.insert(key, (priority, queued));
+        insertion
+    }
+
+    /// Removes the given transmission, and returns it.
+    pub fn pop(&mut self, key: &K) -> Option<Queued<T>> {
+        let (priority, queued) = self.entries.remove(key)?;
+        self.order.remove(&priority);
+        Some(queued)
+    }
+
+    /// Removes the transmission with the highest priority, and returns it.
+    pub fn pop_highest(&mut self) -> Option<(K, Queued<T>)> {
+        let (_, key) = self.order.pop_last()?;
+        self.entries.remove(&key).map(|(_, queued)| (key, queued))
+    }
+
+    /// Removes the transmission with the lowest priority, and returns it.
+    fn pop_lowest(&mut self) -> Option<(K, Queued<T>)> {
+        let (_, key) = self.order.pop_first()?;
+        self.entries.remove(&key).map(|(_, queued)| (key, queued))
+    }
+
+    /// Sets the capacity of the queue, displacing the transmissions with the lowest priorities beyond it,
+    /// and returns the keys of the displaced transmissions.
+    pub fn resize(&mut self, capacity: usize) -> Vec<K> {
+        self.capacity = capacity.max(1);
+        let mut displaced = Vec::new();
+        while self.entries.len() > self.capacity {
+            match self.pop_lowest() {
+                Some((key, _)) => displaced.push(key),
+                None => break,
+            }
+        }
+        displaced
+    }
+
+    /// Removes the transmissions that were queued for at least the given TTL, returning the number of removed ones.
+    pub fn expire(&mut self, ttl: Duration, now: Instant) -> usize {
+        let expired = self
+            .entries
+            .iter()
+            .filter(|(_, (_, queued))| now.saturating_duration_since(queued.queued_at) >= ttl)
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+        for key in &expired {
+            self.pop(key);
+        }
+        expired.len()
+    }
+}
+
+impl<K: Hash + Eq + Clone, T: PriorityFee> IntoIterator for FeeQueue<K, T> {
+    type IntoIter = std::vec::IntoIter<(K, Queued<T>)>;
+    type Item = (K, Queued<T>);
+
+    /// Returns the queued transmissions, with the highest priority first.
+    fn into_iter(mut self) -> Self::IntoIter {
+        std::iter::from_fn(|| self.pop_highest()).collect::<Vec<_>>().into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A sample transmission, with the given priority fee.
+    #[derive(Clone, Debug)]
+    struct Sample(u64);
+
+    impl PriorityFee for Sample {
+        fn priority_fee(&self) -> u64 {
+            self.0
+        }
+    }
+
+    /// Returns a queue of the given capacity, filled with the given keys and priority fees, in order.
+    fn sample_queue(capacity: usize, entries: &[(u32, u64)]) -> FeeQueue<u32, Sample> {
+        let mut queue = FeeQueue::new(capacity);
+        for (key, fee) in entries {
+            assert_eq!(queue.put(*key, Queued::new(Sample(*fee), 0)), Insertion::Inserted);
+        }
+        queue
+    }
+
+    /// Returns the keys of the given queue, in the drain order.
+    fn drain(queue: &mut FeeQueue<u32, Sample>) -> Vec<u32> {
+        std::iter::from_fn(|| queue.pop_highest()).map(|(key, _)| key).collect()
+    }
+
+    #[test]
+    fn test_eviction_by_fee() {
+        // Fill the queue with mixed fees.
+        let mut queue = sample_queue(4, &[(1, 100), (2, 0), (3, 50), (4, 0)]);
+        assert_eq!(queue.len(), 4);
+
+        // Ensure a spam wave of zero-fee transmissions cannot displace any queued transmission.
+        for key in 10..20 {
+            assert_eq!(queue.put(key, Queued::new(Sample(0), 0)), Insertion::Rejected);
+        }
+        // Ensure a transmission with the lowest fee is not displaced by an equal fee.
+        assert_eq!(queue.put(5, Queued::new(Sample(0), 0)), Insertion::Rejected);
+        // Ensure a strictly higher fee displaces the lowest fee, with the latest arrival first.
+        assert_eq!(queue.put(6, Queued::new(Sample(1), 0)), Insertion::Displaced(4));
+        assert_eq!(queue.put(7, Queued::new(Sample(75), 0)), Insertion::Displaced(2));
+        // Ensure the queued transmissions are not duplicated.
+        assert_eq!(queue.put(1, Queued::new(Sample(1000), 0)), Insertion::Duplicate);
+        assert_eq!(queue.len(), 4);
+
+        // Ensure shrinking the queue displaces the lowest fees.
+        assert_eq!(queue.resize(2), vec![6, 3]);
+        assert!(!queue.contains(&6) && !queue.contains(&3));
+        assert_eq!(queue.iter().map(|(key, _)| *key).collect::<Vec<_>>(), vec![1, 7]);
+    }
+
+    #[test]
+    fn test_drain_by_fee() {
+        // Fill the queue with mixed fees, some of them equal.
+        let mut queue = sample_queue(8, &[(1, 0), (2, 30), (3, 10), (4, 30), (5, 0), (6, 20)]);
+
+        // Ensure the highest fees are drained first, with the earliest arrival first among equal fees.
+        assert_eq!(queue.clone().into_iter().map(|(key, _)| key).collect::<Vec<_>>(), vec![2, 4, 6, 3, 1, 5]);
+        assert_eq!(queue.pop(&6).map(|queued| queued.transmission.0), Some(20));
+        assert_eq!(drain(&mut queue), vec![2, 4, 3, 1, 5]);
+        assert_eq!(queue.len(), 0);
+    }
+
+    #[test]
+    fn test_expire() {
+        let mut queue = FeeQueue::new(8);
+        let start = Instant::now();
+        queue.put(1, Queued { transmission: Sample(10), queued_at: start, size_in_bytes: 0 });
+        queue.put(2, Queued { transmission: Sample(0), queued_at: start + Duration::from_secs(10), size_in_bytes: 0 });
+
+        // Ensure only the transmissions that reached the TTL are removed, regardless of their fees.
+        assert_eq!(queue.expire(Duration::from_secs(30), start + Duration::from_secs(29)), 0);
+        assert_eq!(queue.expire(Duration::from_secs(30), start + Duration::from_secs(30)), 1);
+        assert!(!queue.contains(&1) && queue.contains(&2));
+        assert_eq!(drain(&mut queue), vec![2]);
+    }
+}
@@ -0,0 +1,206 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkos_node_bft::helpers::{STORAGE_RECOVERED_THRESHOLD_IN_MS, STORAGE_SLOW_THRESHOLD_IN_MS};
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// The number of most recent committed rounds over which the inclusion of our certificates is considered.
+pub const INCLUSION_WINDOW_IN_ROUNDS: usize = 50;
+
+/// The weight of the inclusion of our certificates in the committed subdags, out of 100.
+pub const INCLUSION_WEIGHT: f64 = 30.0;
+/// The weight of the certification of our recent batch proposals, out of 100.
+pub const PROPOSAL_QUORUM_WEIGHT: f64 = 25.0;
+/// The weight of the connectivity of the gateway to the committee, out of 100.
+pub const CONNECTIVITY_WEIGHT: f64 = 20.0;
+/// The weight of the sync status, out of 100.
+pub const SYNC_WEIGHT: f64 = 15.0;
+/// The weight of the storage advance latency, out of 100.
+pub const STORAGE_LATENCY_WEIGHT: f64 = 10.0;
+/// The maximum health score while the file descriptors of the node are exhausted.
+pub const FD_EXHAUSTION_MAX_SCORE: u8 = 25;
+
+/// The components of the block-production health of a validator.
+///
+/// A component without observations yet (e.g. right after startup) does not lower the score.
+/// Another validator using the account of this validator overrides the other components, and scores zero.
+/// The exhaustion of the file descriptors caps the score, as the connections of the node are failing.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct HealthComponents {
+    /// The fraction of the recent committed rounds whose subdag includes our certificate, if any were committed.
+    pub inclusion_ratio: Option<f64>,
+    /// The fraction of our recent batch proposals that reached the quorum threshold, if any were proposed.
+    pub proposal_quorum_ratio: Option<f64>,
+    /// The fraction of the other committee members connected to the gateway.
+    pub connectivity_ratio: f64,
+    /// Whether the node is synced with the network.
+    pub is_synced: bool,
+    /// The latency of the most recent storage advance in milliseconds, if any.
+    pub storage_latency_ms: Option<u64>,
+    /// Whether another validator recently authenticated as the account of this validator.
+    #[serde(default)]
+    pub duplicate_identity: bool,
+    /// Whether the file descriptors of the node are exhausted.
+    #[serde(default)]
+    pub fd_exhausted: bool,
+}
+
+impl HealthComponents {
+    /// Returns the fraction of the storage latency component, which decreases linearly from the
+    /// recovered threshold of the storage backpressure, down to zero at its slow threshold.
+    fn storage_latency_ratio(&self) -> f64 {
+        match self.storage_latency_ms {
+            Some(latency) if latency >= STORAGE_SLOW_THRESHOLD_IN_MS => 0.0,
+            Some(latency) if latency > STORAGE_RECOVERED_THRESHOLD_IN_MS => {
+                (STORAGE_SLOW_THRESHOLD_IN_MS - latency) as f64
+                    / (STORAGE_SLOW_THRESHOLD_IN_MS - STORAGE_RECOVERED_THRESHOLD_IN_MS) as f64
+            }
+            _ => 1.0,
+        }
+    }
+
+    /// Returns the health score from 0 (unhealthy) to 100 (healthy), as the weighted sum of the components.
+    pub fn score(&self) -> u8 {
+        if self.duplicate_identity {
+            return 0;
+        }
+        let score = INCLUSION_WEIGHT * self.inclusion_ratio.unwrap_or(1.0)
+            + PROPOSAL_QUORUM_WEIGHT * self.proposal_quorum_ratio.unwrap_or(1.0)
+            + CONNECTIVITY_WEIGHT * self.connectivity_ratio
+            + SYNC_WEIGHT * f64::from(u8::from(self.is_synced))
+            + STORAGE_LATENCY_WEIGHT * self.storage_latency_ratio();
+        let score = score.round().clamp(0.0, 100.0) as u8;
+        match self.fd_exhausted {
+            true => score.min(FD_EXHAUSTION_MAX_SCORE),
+            false => score,
+        }
+    }
+}
+
+/// The block-production health of a validator.
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct BlockProductionHealth {
+    /// The health score, from 0 (unhealthy) to 100 (healthy).
+    pub score: u8,
+    /// The components of the score.
+    pub components: HealthComponents,
+}
+
+impl From<HealthComponents> for BlockProductionHealth {
+    /// Scores the given components.
+    fn from(components: HealthComponents) -> Self {
+        Self { score: components.score(), components }
+    }
+}
+
+/// The tracker of whether our certificates are included in the recent committed subdags.
+///
+/// This is purely observational, and is only used to report the health of the node.
+#[derive(Debug, Default)]
+pub struct CertificateInclusion {
+    /// Whether each recent committed round includes our certificate, from oldest to newest.
+    rounds: Mutex<VecDeque<bool>>,
+}
+
+impl CertificateInclusion {
+    /// Records whether each round of a committed subdag includes our certificate, in round order.
+    pub fn record(&self, rounds: impl IntoIterator<Item = bool>) {
+        let mut window = self.rounds.lock();
+        for is_included in rounds {
+            if window.len() == INCLUSION_WINDOW_IN_ROUNDS {
+                window.pop_front();
+            }
+            window.push_back(is_included);
+        }
+    }
+
+    /// Returns the fraction of the recent committed rounds that include our certificate, if any were committed.
+    pub fn included_ratio(&self) -> Option<f64> {
+        let window = self.rounds.lock();
+        let num_included = window.iter().filter(|is_included| **is_included).count();
+        (!window.is_empty()).then(|| num_included as f64 / window.len() as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the components of a fully healthy validator.
+    fn healthy() -> HealthComponents {
+        HealthComponents {
+            inclusion_ratio: Some(1.0),
+            proposal_quorum_ratio: Some(1.0),
+            connectivity_ratio: 1.0,
+            is_synced: true,
+            storage_latency_ms: Some(100),
+            duplicate_identity: false,
+            fd_exhausted: false,
+        }
+    }
+
+    #[test]
+    fn test_health_score() {
+        assert_eq!(healthy().score(), 100);
+        // Ensure the components without observations do not lower the score.
+        let fresh = HealthComponents { inclusion_ratio: None, proposal_quorum_ratio: None, ..healthy() };
+        assert_eq!(fresh.score(), 100);
+
+        // Ensure each component contributes its weight.
+        assert_eq!(HealthComponents { inclusion_ratio: Some(0.5), ..healthy() }.score(), 85);
+        assert_eq!(HealthComponents { proposal_quorum_ratio: Some(0.0), ..healthy() }.score(), 75);
+        assert_eq!(HealthComponents { connectivity_ratio: 0.25, ..healthy() }.score(), 85);
+        assert_eq!(HealthComponents { is_synced: false, ..healthy() }.score(), 85);
+        // Ensure a duplicate identity overrides the other components.
+        assert_eq!(HealthComponents { duplicate_identity: true, ..healthy() }.score(), 0);
+        // Ensure the exhaustion of the file descriptors caps the score.
+        assert_eq!(HealthComponents { fd_exhausted: true, ..healthy() }.score(), FD_EXHAUSTION_MAX_SCORE);
+
+        // Ensure the storage latency lowers the score linearly between the backpressure thresholds.
+        let latency = |latency_ms| HealthComponents { storage_latency_ms: Some(latency_ms), ..healthy() }.score();
+        assert_eq!(latency(STORAGE_RECOVERED_THRESHOLD_IN_MS), 100);
+        assert_eq!(latency((STORAGE_RECOVERED_THRESHOLD_IN_MS + STORAGE_SLOW_THRESHOLD_IN_MS) / 2), 95);
+        assert_eq!(latency(STORAGE_SLOW_THRESHOLD_IN_MS), 90);
+        assert_eq!(latency(u64::MAX), 90);
+
+        // Ensure a validator that is down on every component scores zero.
+        let down = HealthComponents {
+            inclusion_ratio: Some(0.0),
+            proposal_quorum_ratio: Some(0.0),
+            connectivity_ratio: 0.0,
+            is_synced: false,
+            storage_latency_ms: Some(STORAGE_SLOW_THRESHOLD_IN_MS),
+            duplicate_identity: false,
+            fd_exhausted: false,
+        };
+        assert_eq!(BlockProductionHealth::from(down).score, 0);
+    }
+
+    #[test]
+    fn test_certificate_inclusion() {
+        let tracker = CertificateInclusion::default();
+        assert_eq!(tracker.included_ratio(), None);
+
+        tracker.record([true, false, true, true]);
+        assert_eq!(tracker.included_ratio(), Some(0.75));
+
+        // Ensure only the most recent rounds are kept.
+        tracker.record(std::iter::repeat(false).take(INCLUSION_WINDOW_IN_ROUNDS));
+        assert_eq!(tracker.included_ratio(), Some(0.0));
+    }
+}
@@ -0,0 +1,100 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use lru::LruCache;
+use std::{
+    future::Future,
+    hash::Hash,
+    time::{Duration, Instant},
+};
+use tokio::sync::watch;
+
+/// The default time in seconds that an unconfirmed transmission is kept in the inbound queues,
+/// e.g. while the BFT is not synced.
+pub const DEFAULT_INBOUND_QUEUE_TTL_IN_SECS: u64 = 300;
+
+/// An unconfirmed transmission in an inbound queue, with the time it was first queued.
+#[derive(Clone, Debug)]
+pub(crate) struct Queued<T> {
+    /// The transmission.
+    pub transmission: T,
+    /// The time the transmission was first queued.
+    pub queued_at: Instant,
+    /// The size of the serialized transmission in bytes.
+    pub size_in_bytes: usize,
+}
+
+impl<T> Queued<T> {
+    /// Initializes a new queued transmission of the given serialized size, queued now.
+    pub fn new(transmission: T, size_in_bytes: usize) -> Self {
+        Self { transmission, queued_at: Instant::now(), size_in_bytes }
+    }
+}
+
+/// Removes the transmissions that were queued for at least the given TTL, returning the number of removed ones.
+pub(crate) fn expire_queued<K: Hash + Eq + Clone, T>(
+    queue: &mut LruCache<K, Queued<T>>,
+    ttl: Duration,
+    now: Instant,
+) -> usize {
+    // Note: A transmission returned to the queue keeps its original time, so the queue is not ordered by time.
+    let expired = queue
+        .iter()
+        .filter(|(_, queued)| now.saturating_duration_since(queued.queued_at) >= ttl)
+        .map(|(key, _)| key.clone())
+        .collect::<Vec<_>>();
+    for key in &expired {
+        queue.pop(key);
+    }
+    expired.len()
+}
+
+/// Calls the given drain every time the given sync status flips to synced, until the status sender is dropped.
+pub(crate) async fn drain_when_synced<F, T>(mut is_synced: watch::Receiver<bool>, drain: F)
+where
+    F: Fn() -> T,
+    T: Future<Output = ()>,
+{
+    while is_synced.changed().await.is_ok() {
+        // Note: The status is copied out, so that the lock on it is not held across the drain.
+        let is_synced = *is_synced.borrow_and_update();
+        if is_synced {
+            drain().await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::num::NonZeroUsize;
+
+    #[test]
+    fn test_expire_queued() {
+        let mut queue = LruCache::new(NonZeroUsize::new(8).unwrap());
+        let start = Instant::now();
+        queue.put(1, Queued { transmission: (), queued_at: start, size_in_bytes: 0 });
+        queue.put(2, Queued { transmission: (), queued_at: start + Duration::from_secs(10), size_in_bytes: 0 });
+        // Return the oldest transmission to the queue, as after a failed send.
+        let returned = queue.pop(&1).unwrap();
+        queue.put(1, returned);
+
+        // Ensure only the transmissions that reached the TTL are removed, regardless of their order in the queue.
+        assert_eq!(expire_queued(&mut queue, Duration::from_secs(30), start + Duration::from_secs(29)), 0);
+        assert_eq!(expire_queued(&mut queue, Duration::from_secs(30), start + Duration::from_secs(30)), 1);
+        assert!(!queue.contains(&1) && queue.contains(&2));
+    }
+}
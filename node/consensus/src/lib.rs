@@ -18,25 +18,70 @@
 #[macro_use]
 extern crate tracing;
 
+mod admission;
+use admission::TransactionAdmission;
+pub use admission::{AdmissionCheck, AdmissionCheckResult, AdmissionOutcome, AdmissionVerdict};
+
+mod deployment_rate;
+use deployment_rate::DeploymentRateLimiter;
+pub use deployment_rate::{DEPLOYMENT_RATE_WINDOW_IN_SECS, DeploymentRateExceeded, MAX_DEPLOYMENTS_PER_PEER};
+
+mod fee_queue;
+use fee_queue::{FeeQueue, Insertion, PriorityFee};
+
+mod health;
+pub use health::*;
+
+mod inbound;
+pub use inbound::DEFAULT_INBOUND_QUEUE_TTL_IN_SECS;
+
+mod memory;
+use memory::{AverageSize, estimate_entries};
+pub use memory::{MemoryAccounting, MemoryPool};
+
+mod mempool_file;
+use inbound::{Queued, drain_when_synced, expire_queued};
+pub use mempool_file::MEMPOOL_FILE_VERSION;
+
+mod policy;
+pub use policy::*;
+
+mod pressure;
+pub use pressure::*;
+
+mod preview;
+pub use preview::{AbortedTransaction, BlockPreview, MAX_PREVIEW_ABORTED_CHECKS};
+
+mod snapshot;
+use mempool_file::MempoolFile;
+pub use snapshot::{MAX_MEMORY_POOL_TRANSMISSIONS, MemoryPoolStage, TransmissionKind, TransmissionSummary};
+use snapshot::{merge_summaries, merge_transmissions};
+
+mod tasks;
+pub use tasks::{TaskFactory, TaskFuture, TaskKind, TaskSpawner};
+
 use snarkos_account::Account;
 use snarkos_node_bft::{
     BFT,
     Primary,
+    WorkerQueueStats,
     helpers::{
+        ConnectionSnapshot,
         ConsensusReceiver,
         PrimaryReceiver,
         PrimarySender,
         Storage as NarwhalStorage,
+        StorageBackpressure,
         fmt_id,
         init_consensus_channels,
     },
     spawn_blocking,
 };
 use snarkos_node_bft_ledger_service::LedgerService;
-use snarkos_node_bft_storage_service::BFTPersistentStorage;
+use snarkos_node_bft_storage_service::{BFTPersistentStorage, WriteVerifier};
 use snarkvm::{
     ledger::{
-        block::Transaction,
+        block::{Block, Transaction},
         narwhal::{BatchHeader, Data, Subdag, Transmission, TransmissionID},
         puzzle::{Solution, SolutionID},
     },
@@ -48,15 +93,23 @@ use anyhow::Result;
 use colored::Colorize;
 use indexmap::IndexMap;
 use lru::LruCache;
-use parking_lot::Mutex;
-use std::{future::Future, net::SocketAddr, num::NonZeroUsize, sync::Arc};
-use tokio::{
-    sync::{OnceCell, oneshot},
-    task::JoinHandle,
+use parking_lot::{Mutex, RwLock};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    future::Future,
+    net::SocketAddr,
+    num::NonZeroUsize,
+    path::Path,
+    sync::Arc,
+    time::{Duration, Instant},
 };
+use tokio::sync::{OnceCell, mpsc, oneshot, watch};
 
-#[cfg(feature = "metrics")]
-use std::collections::HashMap;
+/// Whether the inbound transactions are sent to the BFT in the order of their priority fees.
+/// Note: The deployments are still interleaved with the executions, and each class is ordered by priority fee,
+/// so a higher priority fee advances a transaction in the queue of this node.
+pub const IS_FEE_PRIORITIZED: bool = true;
 
 /// The capacity of the queue reserved for deployments.
 /// Note: This is an inbound queue capacity, not a Narwhal-enforced capacity.
@@ -67,25 +120,111 @@ const CAPACITY_FOR_EXECUTIONS: usize = 1 << 10;
 /// The capacity of the queue reserved for solutions.
 /// Note: This is an inbound queue capacity, not a Narwhal-enforced capacity.
 const CAPACITY_FOR_SOLUTIONS: usize = 1 << 10;
+/// The capacity of each cache of recently-seen solutions and transactions.
+const CAPACITY_FOR_SEEN_TRANSMISSIONS: usize = 1 << 16;
+/// The capacity of the channel of the blocks committed by this node.
+const CAPACITY_FOR_COMMITTED_BLOCKS: usize = 16;
 /// The **suggested** maximum number of deployments in each interval.
 /// Note: This is an inbound queue limit, not a Narwhal-enforced limit.
 const MAX_DEPLOYMENTS_PER_INTERVAL: usize = 1;
 
-/// Helper struct to track incoming transactions.
+/// Helper struct to track incoming transactions, ordered by their priority fees.
 struct TransactionsQueue<N: Network> {
-    pub deployments: LruCache<N::TransactionID, Transaction<N>>,
-    pub executions: LruCache<N::TransactionID, Transaction<N>>,
+    pub deployments: FeeQueue<N::TransactionID, Transaction<N>>,
+    pub executions: FeeQueue<N::TransactionID, Transaction<N>>,
+    /// Whether the deployments take the first slot in the next interval.
+    pub deployments_first: bool,
 }
 
 impl<N: Network> Default for TransactionsQueue<N> {
     fn default() -> Self {
         Self {
-            deployments: LruCache::new(NonZeroUsize::new(CAPACITY_FOR_DEPLOYMENTS).unwrap()),
-            executions: LruCache::new(NonZeroUsize::new(CAPACITY_FOR_EXECUTIONS).unwrap()),
+            deployments: FeeQueue::new(CAPACITY_FOR_DEPLOYMENTS),
+            executions: FeeQueue::new(CAPACITY_FOR_EXECUTIONS),
+            deployments_first: true,
         }
     }
 }
 
+impl<N: Network> TransactionsQueue<N> {
+    /// Returns `true` if the given transaction is in the queue.
+    fn contains(&self, transaction_id: &N::TransactionID) -> bool {
+        self.deployments.contains(transaction_id) || self.executions.contains(transaction_id)
+    }
+
+    /// Returns the given queued transaction, if any.
+    fn get(&self, transaction_id: &N::TransactionID) -> Option<&Transaction<N>> {
+        self.deployments
+            .get(transaction_id)
+            .or_else(|| self.executions.get(transaction_id))
+            .map(|queued| &queued.transmission)
+    }
+}
+
+/// The running averages of the serialized sizes of the transmissions in the inbound queues.
+#[derive(Default)]
+struct InboundSizes {
+    solutions: AverageSize,
+    deployments: AverageSize,
+    executions: AverageSize,
+}
+
+/// Resizes the given LRU cache to the given capacity, evicting its least recently used entries beyond it,
+/// and returns the keys of the evicted entries.
+fn resize_lru<K: core::hash::Hash + Eq, V>(cache: &mut LruCache<K, V>, capacity: usize) -> Vec<K> {
+    let capacity = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN);
+    let mut evicted = Vec::with_capacity(cache.len().saturating_sub(capacity.get()));
+    while cache.len() > capacity.get() {
+        match cache.pop_lru() {
+            Some((key, _)) => evicted.push(key),
+            None => break,
+        }
+    }
+    if cache.cap() != capacity {
+        cache.resize(capacity);
+    }
+    evicted
+}
+
+/// Returns the classes of the transactions to drain from the queue in an interval, in order,
+/// where `true` selects a deployment and `false` selects an execution.
+///
+/// Neither class is starved while the other one has pending transactions: each class with pending
+/// transactions gets at least one slot if the capacity allows for both, and the classes take turns
+/// with the first slot otherwise. The classes are interleaved, starting with the given class.
+fn select_transactions(
+    num_deployments: usize,
+    num_executions: usize,
+    capacity: usize,
+    deployments_first: bool,
+) -> Vec<bool> {
+    // Determine the number of deployments and executions to send.
+    let mut selected_deployments = num_deployments.min(capacity).min(MAX_DEPLOYMENTS_PER_INTERVAL);
+    let mut selected_executions = num_executions.min(capacity.saturating_sub(selected_deployments));
+    // If both classes are pending but only one was selected, give the other class a slot.
+    if selected_deployments > 0 && selected_executions == 0 && num_executions > 0 {
+        // Reserve a slot for the executions, unless there is only one slot and it is the deployments' turn.
+        if capacity >= 2 || !deployments_first {
+            selected_deployments -= 1;
+            selected_executions += 1;
+        }
+    } else if selected_executions > 0 && selected_deployments == 0 && num_deployments > 0 {
+        // Reserve a slot for the deployments, unless there is only one slot and it is the executions' turn.
+        if capacity >= 2 || deployments_first {
+            selected_executions -= 1;
+            selected_deployments += 1;
+        }
+    }
+    // Interleave the classes, starting with the given class.
+    // Note: interleaving ensures we will never have consecutive invalid deployments blocking the queue.
+    let deployments = (0..selected_deployments).map(|_| true);
+    let executions = (0..selected_executions).map(|_| false);
+    match deployments_first {
+        true => deployments.interleave(executions).collect(),
+        false => executions.interleave(deployments).collect(),
+    }
+}
+
 #[derive(Clone)]
 pub struct Consensus<N: Network> {
     /// The ledger.
@@ -94,18 +233,41 @@ pub struct Consensus<N: Network> {
     bft: BFT<N>,
     /// The primary sender.
     primary_sender: Arc<OnceCell<PrimarySender<N>>>,
+    /// The sender of the blocks committed by this node, if there is a subscriber.
+    committed_blocks_sender: Arc<OnceCell<mpsc::Sender<Block<N>>>>,
     /// The unconfirmed solutions queue.
-    solutions_queue: Arc<Mutex<LruCache<SolutionID<N>, Solution<N>>>>,
+    solutions_queue: Arc<Mutex<LruCache<SolutionID<N>, Queued<Solution<N>>>>>,
     /// The unconfirmed transactions queue.
     transactions_queue: Arc<Mutex<TransactionsQueue<N>>>,
+    /// The priority fees of the transactions sent to the BFT, so that the fees of its ready queues are known
+    /// without deserializing them.
+    ready_fees: Arc<Mutex<HashMap<N::TransactionID, u64>>>,
+    /// The time that an unconfirmed transmission is kept in the inbound queues, e.g. while the BFT is not synced.
+    inbound_queue_ttl: Duration,
     /// The recently-seen unconfirmed solutions.
     seen_solutions: Arc<Mutex<LruCache<SolutionID<N>, ()>>>,
     /// The recently-seen unconfirmed transactions.
     seen_transactions: Arc<Mutex<LruCache<N::TransactionID, ()>>>,
+    /// The limit on the rate of the deployments received from each peer.
+    deployment_limiter: Arc<DeploymentRateLimiter>,
+    /// The running averages of the serialized sizes of the transmissions in the inbound queues.
+    inbound_sizes: Arc<InboundSizes>,
+    /// The accounting of the memory used by the memory pool and the deduplication caches.
+    memory_budget: Arc<dyn MemoryAccounting>,
+    /// The pressure on the inbound queues.
+    mempool_pressure: Arc<MempoolPressure>,
+    /// The local policy deciding which unconfirmed transmissions are admitted into the memory pool.
+    /// Note: The policy may be replaced at runtime, e.g. when the node reloads its configuration.
+    mempool_policy: Arc<RwLock<Arc<dyn MempoolPolicy<N>>>>,
+    /// The tracker of whether our certificates are included in the recent committed subdags.
+    certificate_inclusion: Arc<CertificateInclusion>,
     #[cfg(feature = "metrics")]
     transmissions_queue_timestamps: Arc<Mutex<HashMap<TransmissionID<N>, i64>>>,
-    /// The spawned handles.
-    handles: Arc<Mutex<Vec<JoinHandle<()>>>>,
+    /// The spawner of the long-running tasks, which supervises them.
+    tasks: Arc<dyn TaskSpawner>,
+    /// The sync status standing in for the one of the BFT, for the unit tests.
+    #[cfg(test)]
+    is_synced_override: Arc<OnceCell<watch::Receiver<bool>>>,
 }
 
 impl<N: Network> Consensus<N> {
@@ -116,6 +278,11 @@ impl<N: Network> Consensus<N> {
         ip: Option<SocketAddr>,
         trusted_validators: &[SocketAddr],
         storage_mode: StorageMode,
+        memory_budget: Arc<dyn MemoryAccounting>,
+        tasks: Arc<dyn TaskSpawner>,
+        mempool_policy: Arc<dyn MempoolPolicy<N>>,
+        inbound_queue_ttl: Duration,
+        write_verifier: Arc<WriteVerifier>,
     ) -> Result<Self> {
         // Recover the development ID, if it is present.
         let dev = match storage_mode {
@@ -123,7 +290,7 @@ impl<N: Network> Consensus<N> {
             StorageMode::Production | StorageMode::Custom(..) => None,
         };
         // Initialize the Narwhal transmissions.
-        let transmissions = Arc::new(BFTPersistentStorage::open(storage_mode)?);
+        let transmissions = Arc::new(BFTPersistentStorage::open(storage_mode)?.with_write_verifier(write_verifier));
         // Initialize the Narwhal storage.
         let storage = NarwhalStorage::new(ledger.clone(), transmissions, BatchHeader::<N>::MAX_GC_ROUNDS as u64);
         // Initialize the BFT.
@@ -133,13 +300,28 @@ impl<N: Network> Consensus<N> {
             ledger,
             bft,
             primary_sender: Default::default(),
+            committed_blocks_sender: Default::default(),
             solutions_queue: Arc::new(Mutex::new(LruCache::new(NonZeroUsize::new(CAPACITY_FOR_SOLUTIONS).unwrap()))),
             transactions_queue: Default::default(),
-            seen_solutions: Arc::new(Mutex::new(LruCache::new(NonZeroUsize::new(1 << 16).unwrap()))),
-            seen_transactions: Arc::new(Mutex::new(LruCache::new(NonZeroUsize::new(1 << 16).unwrap()))),
+            ready_fees: Default::default(),
+            inbound_queue_ttl,
+            seen_solutions: Arc::new(Mutex::new(LruCache::new(
+                NonZeroUsize::new(CAPACITY_FOR_SEEN_TRANSMISSIONS).unwrap(),
+            ))),
+            seen_transactions: Arc::new(Mutex::new(LruCache::new(
+                NonZeroUsize::new(CAPACITY_FOR_SEEN_TRANSMISSIONS).unwrap(),
+            ))),
+            deployment_limiter: Default::default(),
+            inbound_sizes: Default::default(),
+            memory_budget,
+            mempool_pressure: Default::default(),
+            mempool_policy: Arc::new(RwLock::new(mempool_policy)),
+            certificate_inclusion: Default::default(),
             #[cfg(feature = "metrics")]
             transmissions_queue_timestamps: Default::default(),
-            handles: Default::default(),
+            tasks,
+            #[cfg(test)]
+            is_synced_override: Default::default(),
         })
     }
 
@@ -168,6 +350,16 @@ impl<N: Network> Consensus<N> {
         &self.bft
     }
 
+    /// Subscribes to the blocks committed by this node, i.e. advanced to from a committed subdag, rather than synced.
+    /// There can only be a single subscriber, and the blocks are dropped if it falls behind.
+    pub fn subscribe_committed_blocks(&self) -> Result<mpsc::Receiver<Block<N>>> {
+        let (sender, receiver) = mpsc::channel(CAPACITY_FOR_COMMITTED_BLOCKS);
+        if self.committed_blocks_sender.set(sender).is_err() {
+            anyhow::bail!("The committed blocks already have a subscriber");
+        }
+        Ok(receiver)
+    }
+
     /// Returns the primary sender.
     pub fn primary_sender(&self) -> &PrimarySender<N> {
         self.primary_sender.get().expect("Primary sender not set")
@@ -194,6 +386,62 @@ impl<N: Network> Consensus<N> {
     pub fn num_unconfirmed_transactions(&self) -> usize {
         self.bft.num_unconfirmed_transactions()
     }
+
+    /// Returns a snapshot of the queue statistics of each worker.
+    pub fn worker_queue_stats(&self) -> Vec<WorkerQueueStats> {
+        self.bft.primary().worker_queue_stats()
+    }
+
+    /// Returns the addresses of the validators connected to the gateway.
+    pub fn connected_validators(&self) -> HashSet<Address<N>> {
+        self.bft.primary().gateway().connected_addresses()
+    }
+
+    /// Returns a snapshot of the statistics of each connection of the gateway.
+    pub fn connection_stats(&self) -> Vec<ConnectionSnapshot<N>> {
+        self.bft.primary().gateway().connection_stats()
+    }
+
+    /// Returns a snapshot of the UNIX timestamp of the last event received from each validator.
+    pub fn validator_last_activity(&self) -> HashMap<Address<N>, i64> {
+        self.bft.primary().gateway().last_activity()
+    }
+
+    /// Returns the round and signers of the most recent certificate formed from our own batch proposal, if any.
+    pub fn latest_certificate_signers(&self) -> Option<(u64, HashSet<Address<N>>)> {
+        self.bft.primary().latest_certificate_signers()
+    }
+
+    /// Returns the block-production health of this validator, given whether the node is synced,
+    /// and whether the file descriptors are exhausted, as reported by the router.
+    /// Note: The health is purely observational, and has no impact on consensus.
+    pub fn block_production_health(&self, is_synced: bool, is_fd_exhausted: bool) -> BlockProductionHealth {
+        let primary = self.bft.primary();
+        let address = primary.gateway().account().address();
+        // Determine the fraction of the other committee members connected to the gateway.
+        let connectivity_ratio = match self.ledger.current_committee() {
+            Ok(committee) => {
+                let connected = self.connected_validators();
+                let others = committee.members().keys().filter(|member| **member != address);
+                let (num_others, num_connected) =
+                    others.fold((0, 0), |(n, c), member| (n + 1, c + usize::from(connected.contains(member))));
+                match num_others {
+                    0 => 1.0,
+                    _ => num_connected as f64 / num_others as f64,
+                }
+            }
+            Err(_) => 0.0,
+        };
+        BlockProductionHealth::from(HealthComponents {
+            inclusion_ratio: self.certificate_inclusion.included_ratio(),
+            proposal_quorum_ratio: primary.proposal_outcomes().certified_ratio(),
+            connectivity_ratio,
+            is_synced,
+            storage_latency_ms: primary.storage_backpressure().recent_latencies().last().map(|l| l.as_millis() as u64),
+            duplicate_identity: primary.gateway().is_duplicate_identity(),
+            fd_exhausted: is_fd_exhausted || primary.gateway().is_fd_exhausted(),
+        })
+    }
 }
 
 impl<N: Network> Consensus<N> {
@@ -218,6 +466,257 @@ impl<N: Network> Consensus<N> {
     }
 }
 
+impl<N: Network> Consensus<N> {
+    /// Returns the summaries of the unconfirmed transmissions, as a consistent snapshot of the memory pool.
+    ///
+    /// The inbound queues are captured under a single acquisition of their locks, and before the ready queues,
+    /// so that a transmission sent to the BFT in between is listed exactly once.
+    pub fn memory_pool_summaries(&self) -> Vec<TransmissionSummary> {
+        let inbound = self.with_inbound_queues(|solutions, tx_queue| {
+            let now = Instant::now();
+            let solutions = solutions.iter().map(|(id, queued)| {
+                TransmissionSummary::inbound(id.to_string(), TransmissionKind::Solution, queued, now)
+            });
+            let transactions = tx_queue.deployments.iter().chain(tx_queue.executions.iter()).map(|(id, queued)| {
+                TransmissionSummary::inbound(id.to_string(), TransmissionKind::Transaction, queued, now)
+            });
+            transactions.chain(solutions).collect()
+        });
+        let now = snarkos_node_bft::helpers::now();
+        let ready = self
+            .bft
+            .worker_transmission_summaries()
+            .into_iter()
+            .map(|(id, size_in_bytes, timestamp)| TransmissionSummary::ready(&id, size_in_bytes, timestamp, now))
+            .collect();
+        merge_summaries(inbound, ready)
+    }
+
+    /// Returns the number of unconfirmed transactions, and the priority fees of the ones with a known fee,
+    /// as a snapshot of the same transactions that are listed by `memory_pool_summaries`.
+    ///
+    /// The fees are recorded as the transactions enter the memory pool, so that no transaction is deserialized.
+    /// Note: A transaction that was reinserted into the ready queues as bytes is counted, without its fee.
+    pub fn memory_pool_priority_fees(&self) -> (usize, Vec<u64>) {
+        // Note: The inbound queues are captured before the ready queues, as in `memory_pool_summaries`.
+        let mut fees: HashMap<_, _> = self.with_inbound_queues(|_, tx_queue| {
+            let queued = tx_queue.deployments.iter().chain(tx_queue.executions.iter());
+            queued.map(|(id, queued)| (*id, Some(queued.transmission.priority_fee()))).collect()
+        });
+        let ready_ids = self.ready_transaction_ids();
+        let ready_fees = self.ready_fees.lock();
+        for id in ready_ids {
+            fees.entry(id).or_insert_with(|| ready_fees.get(&id).copied());
+        }
+        (fees.len(), fees.into_values().flatten().collect())
+    }
+
+    /// Returns the IDs of the transactions in the ready queues of the BFT.
+    fn ready_transaction_ids(&self) -> HashSet<N::TransactionID> {
+        self.bft
+            .worker_transmission_ids()
+            .filter_map(|id| match id {
+                TransmissionID::Transaction(id, _) => Some(id),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Records the priority fee of the given transaction, as it is sent to the ready queues of the BFT.
+    fn record_ready_fee(&self, transaction_id: N::TransactionID, priority_fee: u64) {
+        let num_ready_fees = {
+            let mut ready_fees = self.ready_fees.lock();
+            ready_fees.insert(transaction_id, priority_fee);
+            ready_fees.len()
+        };
+        // Forget the fees of the transactions that left the ready queues, once they outnumber the ready queues.
+        if num_ready_fees > 2 * Primary::<N>::MAX_TRANSMISSIONS_TOLERANCE {
+            let ready_ids = self.ready_transaction_ids();
+            self.ready_fees.lock().retain(|id, _| *id == transaction_id || ready_ids.contains(id));
+        }
+    }
+
+    /// Returns up to `limit` unconfirmed transmissions of the given kind (or of any kind),
+    /// as a consistent snapshot of the memory pool.
+    pub fn memory_pool_transmissions(
+        &self,
+        kind: Option<TransmissionKind>,
+        limit: usize,
+    ) -> IndexMap<TransmissionID<N>, Transmission<N>> {
+        self.memory_pool_transmission_ids(kind, limit)
+            .into_iter()
+            .filter_map(|id| Some((id, self.memory_pool_transmission(id)?)))
+            .collect()
+    }
+
+    /// Returns the IDs of up to `limit` unconfirmed transmissions of the given kind (or of any kind), in the order of
+    /// `memory_pool_transmissions`, without copying the transmissions out of the memory pool.
+    /// The transmissions can then be read one at a time, with `memory_pool_transmission`.
+    pub fn memory_pool_transmission_ids(&self, kind: Option<TransmissionKind>, limit: usize) -> Vec<TransmissionID<N>> {
+        let is_selected = |other: TransmissionKind| kind.map_or(true, |kind| kind == other);
+        // Copy at most `limit` IDs out of the inbound queues.
+        let (transaction_ids, solution_ids) = self.with_inbound_queues(|solutions, tx_queue| {
+            let transaction_ids = match is_selected(TransmissionKind::Transaction) {
+                true => tx_queue
+                    .deployments
+                    .iter()
+                    .chain(tx_queue.executions.iter())
+                    .take(limit)
+                    .map(|(id, _)| *id)
+                    .collect::<Vec<_>>(),
+                false => Vec::new(),
+            };
+            let solution_ids = match is_selected(TransmissionKind::Solution) {
+                true => solutions.iter().take(limit - transaction_ids.len()).map(|(id, _)| *id).collect::<Vec<_>>(),
+                false => Vec::new(),
+            };
+            (transaction_ids, solution_ids)
+        });
+        // Note: The checksums are computed one transmission at a time, after the locks on the inbound queues are
+        // released. The transmissions that left the inbound queues in the meantime are skipped.
+        let inbound = transaction_ids
+            .into_iter()
+            .filter_map(|id| {
+                let tx = self.with_inbound_queues(|_, tx_queue| tx_queue.get(&id).cloned())?;
+                Some((TransmissionID::Transaction(id, Data::Object(tx).to_checksum::<N>().unwrap_or_default()), ()))
+            })
+            .chain(solution_ids.into_iter().filter_map(|id| {
+                let solution = self.with_inbound_queues(|solutions, _| {
+                    solutions.peek(&id).map(|queued| queued.transmission.clone())
+                })?;
+                Some((TransmissionID::Solution(id, Data::Object(solution).to_checksum::<N>().unwrap_or_default()), ()))
+            }))
+            .collect();
+        let ready = self
+            .bft
+            .worker_transmission_ids()
+            .filter(|id| is_selected(TransmissionKind::of(id)))
+            .take(limit)
+            .map(|id| (id, ()))
+            .collect();
+        merge_transmissions(inbound, ready, limit).into_keys().collect()
+    }
+
+    /// Returns the unconfirmed transmission with the given ID, if it is still in the memory pool.
+    pub fn memory_pool_transmission(&self, transmission_id: TransmissionID<N>) -> Option<Transmission<N>> {
+        self.bft.worker_transmission(transmission_id).or_else(|| {
+            self.with_inbound_queues(|solutions, tx_queue| match transmission_id {
+                TransmissionID::Solution(id, _) => {
+                    solutions.peek(&id).map(|queued| Transmission::Solution(Data::Object(queued.transmission.clone())))
+                }
+                TransmissionID::Transaction(id, _) => {
+                    tx_queue.get(&id).map(|tx| Transmission::Transaction(Data::Object(tx.clone())))
+                }
+                TransmissionID::Ratification => None,
+            })
+        })
+    }
+
+    /// Calls the given function on the inbound queues, under a single acquisition of their locks.
+    fn with_inbound_queues<T>(
+        &self,
+        f: impl FnOnce(&LruCache<SolutionID<N>, Queued<Solution<N>>>, &TransactionsQueue<N>) -> T,
+    ) -> T {
+        // Note: The solutions queue is locked first, as when the inbound queues are expired.
+        let solutions_queue = self.solutions_queue.lock();
+        let transactions_queue = self.transactions_queue.lock();
+        f(&solutions_queue, &transactions_queue)
+    }
+}
+
+impl<N: Network> Consensus<N> {
+    /// Saves the unconfirmed solutions and transactions of the inbound queues to the given file,
+    /// and returns the number of saved transmissions.
+    ///
+    /// The transmissions already sent to the BFT are not saved, as they are held by the workers.
+    pub fn save_mempool(&self, path: &Path) -> Result<usize> {
+        let mempool = self.with_inbound_queues(|solutions_queue, tx_queue| MempoolFile {
+            // Note: The solutions are saved from the least recently queued, so that loading them preserves their order.
+            solutions: solutions_queue.iter().rev().map(|(_, queued)| queued.transmission.clone()).collect(),
+            transactions: tx_queue
+                .deployments
+                .iter()
+                .chain(tx_queue.executions.iter())
+                .map(|(_, queued)| queued.transmission.clone())
+                .collect(),
+        });
+        mempool.save(path)?;
+        Ok(mempool.solutions.len() + mempool.transactions.len())
+    }
+
+    /// Loads the unconfirmed solutions and transactions saved to the given file into the inbound queues,
+    /// and returns the number of loaded transmissions.
+    ///
+    /// Each transmission is added as if it was received again, so it is revalidated against the ledger,
+    /// and the ones already confirmed, rejected or beyond the capacity of the inbound queues are skipped.
+    pub async fn load_mempool(&self, path: &Path) -> Result<usize> {
+        let mempool = MempoolFile::<N>::load(path)?;
+        let mut num_loaded = 0;
+        for solution in mempool.solutions {
+            let solution_id = solution.id();
+            match self.add_unconfirmed_solution(solution).await {
+                Ok(SolutionOutcome::Accepted) => num_loaded += 1,
+                Ok(status) => trace!("Skipped the saved solution '{}' - {status:?}", fmt_id(solution_id)),
+                Err(error) => trace!("Skipped the saved solution '{}' - {error}", fmt_id(solution_id)),
+            }
+        }
+        for transaction in mempool.transactions {
+            let transaction_id = transaction.id();
+            match self.add_unconfirmed_transaction(transaction, None).await {
+                Ok(()) => num_loaded += 1,
+                Err(error) => trace!("Skipped the saved transaction '{}' - {error}", fmt_id(transaction_id)),
+            }
+        }
+        Ok(num_loaded)
+    }
+}
+
+impl<N: Network> Consensus<N> {
+    /// Returns a preview of the block that committing the current leader certificate would produce,
+    /// or `None` if there is no leader certificate to commit yet.
+    ///
+    /// The preview runs the same speculation as the commit path, on the blocking pool, and nothing is written
+    /// to the ledger. As the VM speculates one block at a time, a concurrent commit may wait for one preview.
+    pub async fn preview_next_block(&self) -> Result<Option<BlockPreview<N>>> {
+        // Retrieve the subdag and transmissions that the next commit would hand to consensus.
+        let Some((subdag, transmissions)) = self.bft.preview_commit()? else {
+            return Ok(None);
+        };
+        // Ensure the subdag was not committed in the meantime.
+        if subdag.anchor_round() <= self.ledger.latest_round() {
+            return Ok(None);
+        }
+        let num_transmissions = transmissions.len();
+
+        // Speculate the next block, without checking or advancing to it.
+        let ledger = self.ledger.clone();
+        let transmissions_ = transmissions.clone();
+        let block = spawn_blocking! { ledger.prepare_advance_to_next_quorum_block(subdag, transmissions_) }?;
+
+        // Check the aborted transactions again, to report the reason they were aborted.
+        let mut aborted_transactions = Vec::with_capacity(block.aborted_transaction_ids().len());
+        for (index, transaction_id) in block.aborted_transaction_ids().iter().enumerate() {
+            let transaction = transmissions.iter().find_map(|(id, transmission)| match (id, transmission) {
+                (TransmissionID::Transaction(id, _), Transmission::Transaction(tx)) if id == transaction_id => {
+                    Some(tx.clone())
+                }
+                _ => None,
+            });
+            let reason = match transaction {
+                Some(transaction) if index < MAX_PREVIEW_ABORTED_CHECKS => {
+                    match self.ledger.check_transaction_basic(*transaction_id, transaction).await {
+                        Ok(()) => Some("aborted during speculation".to_string()),
+                        Err(error) => Some(error.to_string()),
+                    }
+                }
+                _ => None,
+            };
+            aborted_transactions.push(AbortedTransaction { id: *transaction_id, reason });
+        }
+        Ok(Some(BlockPreview::new(&block, num_transmissions, aborted_transactions)))
+    }
+}
+
 impl<N: Network> Consensus<N> {
     /// Returns the worker transmission IDs.
     pub fn worker_transmission_ids(&self) -> impl '_ + Iterator<Item = TransmissionID<N>> {
@@ -266,7 +765,7 @@ impl<N: Network> Consensus<N> {
     /// Returns the solutions in the inbound queue.
     pub fn inbound_solutions(&self) -> impl '_ + Iterator<Item = (SolutionID<N>, Data<Solution<N>>)> {
         // Return an iterator over the solutions in the inbound queue.
-        self.solutions_queue.lock().clone().into_iter().map(|(id, solution)| (id, Data::Object(solution)))
+        self.solutions_queue.lock().clone().into_iter().map(|(id, queued)| (id, Data::Object(queued.transmission)))
     }
 
     /// Returns the transactions in the inbound queue.
@@ -279,15 +778,25 @@ impl<N: Network> Consensus<N> {
             .clone()
             .into_iter()
             .chain(tx_queue.executions.clone())
-            .map(|(id, tx)| (id, Data::Object(tx)))
+            .map(|(id, queued)| (id, Data::Object(queued.transmission)))
     }
 }
 
 impl<N: Network> Consensus<N> {
-    /// Adds the given unconfirmed solution to the memory pool.
-    pub async fn add_unconfirmed_solution(&self, solution: Solution<N>) -> Result<()> {
+    /// Adds the given unconfirmed solution to the memory pool, and returns its outcome.
+    /// If the solution is rejected, the returned error is a [`SolutionRejection`], which carries its outcome.
+    pub async fn add_unconfirmed_solution(&self, solution: Solution<N>) -> Result<SolutionOutcome> {
+        // Shed the unconfirmed solution, if the storage is slow.
+        if self.bft.primary().storage_backpressure().is_throttled() {
+            let reason =
+                format!("Unable to add solution '{}' to the memory pool - storage is slow", fmt_id(solution.id()));
+            return Err(SolutionRejection::new(SolutionOutcome::QueueFull, reason));
+        }
         // Calculate the transmission checksum.
-        let checksum = Data::<Solution<N>>::Buffer(solution.to_bytes_le()?.into()).to_checksum::<N>()?;
+        let bytes = solution.to_bytes_le()?;
+        let size_in_bytes = bytes.len();
+        self.inbound_sizes.solutions.record(size_in_bytes);
+        let checksum = Data::<Solution<N>>::Buffer(bytes.into()).to_checksum::<N>()?;
         #[cfg(feature = "metrics")]
         {
             metrics::increment_gauge(metrics::consensus::UNCONFIRMED_SOLUTIONS, 1f64);
@@ -303,26 +812,182 @@ impl<N: Network> Consensus<N> {
             // Check if the transaction was recently seen.
             if self.seen_solutions.lock().put(solution_id, ()).is_some() {
                 // If the transaction was recently seen, return early.
-                return Ok(());
+                return Ok(SolutionOutcome::Duplicate);
+            }
+            // Check if the solution is admitted by the mempool policy.
+            let mempool_policy = self.mempool_policy();
+            let admission = mempool_policy.admit_solution(&solution, &self.policy_context());
+            let transmission = format!("Solution '{}'", fmt_id(solution_id));
+            let is_admitted = check_admission(mempool_policy.name(), &transmission, admission, true)
+                .map_err(|error| SolutionRejection::new(SolutionOutcome::Rejected, error.to_string()))?;
+            if !is_admitted {
+                // If the solution is deferred, forget it, so that it is reconsidered if it is received again.
+                self.seen_solutions.lock().pop(&solution_id);
+                return Ok(SolutionOutcome::QueueFull);
             }
             // Check if the solution already exists in the ledger.
             if self.ledger.contains_transmission(&TransmissionID::Solution(solution_id, checksum))? {
-                bail!("Solution '{}' exists in the ledger {}", fmt_id(solution_id), "(skipping)".dimmed());
+                let reason =
+                    format!("Solution '{}' exists in the ledger {}", fmt_id(solution_id), "(skipping)".dimmed());
+                return Err(SolutionRejection::new(SolutionOutcome::Duplicate, reason));
             }
             // Add the solution to the memory pool.
             trace!("Received unconfirmed solution '{}' in the queue", fmt_id(solution_id));
-            if self.solutions_queue.lock().put(solution_id, solution).is_some() {
-                bail!("Solution '{}' exists in the memory pool", fmt_id(solution_id));
+            if self.solutions_queue.lock().put(solution_id, Queued::new(solution, size_in_bytes)).is_some() {
+                let reason = format!("Solution '{}' exists in the memory pool", fmt_id(solution_id));
+                return Err(SolutionRejection::new(SolutionOutcome::Duplicate, reason));
+            }
+        }
+        // Keep the inbound queues and the seen caches within the memory budget.
+        self.update_memory_budget();
+        // Send the queued solutions to the primary.
+        self.drain_solutions().await;
+        Ok(SolutionOutcome::Accepted)
+    }
+
+    /// Adds the given unconfirmed transaction to the memory pool.
+    ///
+    /// If the transaction was received from a peer, the peer IP is given, so that the deployments
+    /// of each peer are rate limited. Otherwise, e.g. for a transaction submitted via REST, it is `None`.
+    pub async fn add_unconfirmed_transaction(
+        &self,
+        transaction: Transaction<N>,
+        peer_ip: Option<SocketAddr>,
+    ) -> Result<()> {
+        // Calculate the transmission checksum.
+        let bytes = transaction.to_bytes_le()?;
+        let size_in_bytes = bytes.len();
+        let checksum = Data::<Transaction<N>>::Buffer(bytes.into()).to_checksum::<N>()?;
+        // Run the admission checks, marking the transaction as seen.
+        let verdict = self.transaction_admission(peer_ip).check(&transaction, checksum, true)?;
+        // Record the unconfirmed transaction, unless it was shed as the storage is slow.
+        if verdict.failed_check() != Some(AdmissionCheck::StorageBackpressure) {
+            match transaction.is_deploy() {
+                true => self.inbound_sizes.deployments.record(size_in_bytes),
+                false => self.inbound_sizes.executions.record(size_in_bytes),
+            }
+            #[cfg(feature = "metrics")]
+            {
+                metrics::increment_gauge(metrics::consensus::UNCONFIRMED_TRANSACTIONS, 1f64);
+                let timestamp = snarkos_node_bft::helpers::now();
+                self.transmissions_queue_timestamps
+                    .lock()
+                    .insert(TransmissionID::Transaction(transaction.id(), checksum), timestamp);
+            }
+        }
+        match verdict.outcome {
+            AdmissionOutcome::Accepted => {}
+            // If the transaction was recently seen or deferred, return early.
+            AdmissionOutcome::Ignored | AdmissionOutcome::Deferred => return Ok(()),
+            AdmissionOutcome::Rejected => match (verdict.failed_check(), peer_ip) {
+                // If the peer exceeded its deployment rate limit, return a distinct error, to drop it quietly.
+                (Some(AdmissionCheck::DeploymentRate), Some(peer_ip)) => {
+                    #[cfg(feature = "metrics")]
+                    metrics::increment_counter(metrics::consensus::RATE_LIMITED_DEPLOYMENTS);
+                    return Err(DeploymentRateExceeded {
+                        peer_ip,
+                        max_deployments: MAX_DEPLOYMENTS_PER_PEER,
+                        window_in_secs: DEPLOYMENT_RATE_WINDOW_IN_SECS,
+                    }
+                    .into());
+                }
+                _ => bail!("{}", verdict.error.unwrap_or_default()),
+            },
+        }
+        // Add the transaction to the memory pool.
+        {
+            let transaction_id = transaction.id();
+            trace!("Received unconfirmed transaction '{}' in the queue", fmt_id(transaction_id));
+            let is_deploy = transaction.is_deploy();
+            let queued = Queued::new(transaction, size_in_bytes);
+            let mut is_verified = false;
+            let insertion = loop {
+                {
+                    let mut tx_queue = self.transactions_queue.lock();
+                    let queue = if is_deploy { &mut tx_queue.deployments } else { &mut tx_queue.executions };
+                    // Note: Only a verified transaction may displace a queued one, as its priority fee could be forged.
+                    if is_verified || !queue.displaces(&queued.transmission) {
+                        break queue.put(transaction_id, queued);
+                    }
+                }
+                // Verify the transaction, outside of the lock, before it displaces a queued transaction.
+                let transaction = Data::Object(queued.transmission.clone());
+                if let Err(error) = self.ledger.check_transaction_basic(transaction_id, transaction).await {
+                    // Forget the transaction, so that it is reconsidered if it is received again.
+                    self.seen_transactions.lock().pop(&transaction_id);
+                    bail!("Unable to add transaction '{}' to the full memory pool - {error}", fmt_id(transaction_id))
+                }
+                is_verified = true;
+            };
+            match insertion {
+                Insertion::Inserted => {}
+                Insertion::Displaced(displaced_id) => trace!(
+                    "Dropped unconfirmed transaction '{}' from the full queue, for a higher priority fee",
+                    fmt_id(displaced_id)
+                ),
+                Insertion::Duplicate => bail!("Transaction '{}' exists in the memory pool", fmt_id(transaction_id)),
+                Insertion::Rejected => {
+                    // Forget the transaction, so that it is reconsidered if it is received again.
+                    self.seen_transactions.lock().pop(&transaction_id);
+                    bail!(
+                        "Unable to add transaction '{}' to the memory pool - the queue is full of higher priority fees",
+                        fmt_id(transaction_id)
+                    )
+                }
             }
         }
+        // Keep the inbound queues and the seen caches within the memory budget.
+        self.update_memory_budget();
+        // Send the queued transactions to the primary.
+        self.drain_transactions().await;
+        Ok(())
+    }
+
+    /// Returns whether the given unconfirmed transaction would be accepted into the memory pool right now,
+    /// without submitting it.
+    ///
+    /// The admission checks are the ones of `add_unconfirmed_transaction`, run read-only,
+    /// so the transaction is neither marked as seen nor queued.
+    pub fn preflight_unconfirmed_transaction(&self, transaction: &Transaction<N>) -> Result<AdmissionVerdict> {
+        let checksum = Data::<Transaction<N>>::Buffer(transaction.to_bytes_le()?.into()).to_checksum::<N>()?;
+        self.transaction_admission(None).check(transaction, checksum, false)
+    }
+
+    /// Returns the state consulted by the admission checks of an unconfirmed transaction,
+    /// received from the given peer, if any.
+    fn transaction_admission(&self, peer_ip: Option<SocketAddr>) -> TransactionAdmission<'_, N> {
+        TransactionAdmission {
+            peer_ip,
+            deployment_limiter: &self.deployment_limiter,
+            is_throttled: self.bft.primary().storage_backpressure().is_throttled(),
+            seen_transactions: &self.seen_transactions,
+            transactions_queue: &self.transactions_queue,
+            mempool_policy: self.mempool_policy(),
+            policy_context: self.policy_context(),
+            ledger: &*self.ledger,
+        }
+    }
+}
 
+impl<N: Network> Consensus<N> {
+    /// Sends the queued solutions to the primary, within the capacity of its memory pool.
+    ///
+    /// While the BFT is not synced, the solutions are held in the queue, until they are drained once it is synced,
+    /// or until they expire.
+    async fn drain_solutions(&self) {
+        // Drop the transmissions that waited in the inbound queues for too long.
+        self.expire_inbound_queues();
+        // If the BFT is not synced, hold the solutions in the queue.
+        if !self.is_synced() {
+            return;
+        }
         // If the memory pool of this node is full, return early.
         let num_unconfirmed_solutions = self.num_unconfirmed_solutions();
         let num_unconfirmed_transmissions = self.num_unconfirmed_transmissions();
         if num_unconfirmed_solutions >= N::MAX_SOLUTIONS
             || num_unconfirmed_transmissions >= Primary::<N>::MAX_TRANSMISSIONS_TOLERANCE
         {
-            return Ok(());
+            return;
         }
         // Retrieve the solutions.
         let solutions = {
@@ -333,70 +998,48 @@ impl<N: Network> Consensus<N> {
             // Determine the number of solutions to send.
             let num_solutions = queue.len().min(capacity);
             // Drain the solutions from the queue.
-            (0..num_solutions).filter_map(|_| queue.pop_lru().map(|(_, solution)| solution)).collect::<Vec<_>>()
+            let solutions =
+                (0..num_solutions).filter_map(|_| queue.pop_lru().map(|(_, queued)| queued)).collect::<Vec<_>>();
+            self.mempool_pressure.solutions().record_drained(solutions.len(), Instant::now());
+            solutions
         };
+        self.update_mempool_pressure();
         // Iterate over the solutions.
-        for solution in solutions.into_iter() {
-            let solution_id = solution.id();
+        for queued in solutions.into_iter() {
+            let solution_id = queued.transmission.id();
             trace!("Adding unconfirmed solution '{}' to the memory pool...", fmt_id(solution_id));
             // Send the unconfirmed solution to the primary.
-            if let Err(e) = self.primary_sender().send_unconfirmed_solution(solution_id, Data::Object(solution)).await {
+            let solution = Data::Object(queued.transmission.clone());
+            if let Err(e) = self.primary_sender().send_unconfirmed_solution(solution_id, solution).await {
                 // If the BFT is synced, then log the warning.
-                if self.bft.is_synced() {
+                if self.is_synced() {
                     // If error occurs after the first 10 blocks of the epoch, log it as a warning, otherwise ignore.
                     if self.ledger().latest_block_height() % N::NUM_BLOCKS_PER_EPOCH > 10 {
                         warn!("Failed to add unconfirmed solution '{}' to the memory pool - {e}", fmt_id(solution_id))
                     };
+                } else {
+                    // Otherwise, return the solution to the queue, to be sent again once the BFT is synced.
+                    self.solutions_queue.lock().put(solution_id, queued);
                 }
             }
         }
-        Ok(())
     }
 
-    /// Adds the given unconfirmed transaction to the memory pool.
-    pub async fn add_unconfirmed_transaction(&self, transaction: Transaction<N>) -> Result<()> {
-        // Calculate the transmission checksum.
-        let checksum = Data::<Transaction<N>>::Buffer(transaction.to_bytes_le()?.into()).to_checksum::<N>()?;
-        #[cfg(feature = "metrics")]
-        {
-            metrics::increment_gauge(metrics::consensus::UNCONFIRMED_TRANSACTIONS, 1f64);
-            let timestamp = snarkos_node_bft::helpers::now();
-            self.transmissions_queue_timestamps
-                .lock()
-                .insert(TransmissionID::Transaction(transaction.id(), checksum), timestamp);
-        }
-        // Process the unconfirmed transaction.
-        {
-            let transaction_id = transaction.id();
-
-            // Check that the transaction is not a fee transaction.
-            if transaction.is_fee() {
-                bail!("Transaction '{}' is a fee transaction {}", fmt_id(transaction_id), "(skipping)".dimmed());
-            }
-            // Check if the transaction was recently seen.
-            if self.seen_transactions.lock().put(transaction_id, ()).is_some() {
-                // If the transaction was recently seen, return early.
-                return Ok(());
-            }
-            // Check if the transaction already exists in the ledger.
-            if self.ledger.contains_transmission(&TransmissionID::Transaction(transaction_id, checksum))? {
-                bail!("Transaction '{}' exists in the ledger {}", fmt_id(transaction_id), "(skipping)".dimmed());
-            }
-            // Add the transaction to the memory pool.
-            trace!("Received unconfirmed transaction '{}' in the queue", fmt_id(transaction_id));
-            if transaction.is_deploy() {
-                if self.transactions_queue.lock().deployments.put(transaction_id, transaction).is_some() {
-                    bail!("Transaction '{}' exists in the memory pool", fmt_id(transaction_id));
-                }
-            } else if self.transactions_queue.lock().executions.put(transaction_id, transaction).is_some() {
-                bail!("Transaction '{}' exists in the memory pool", fmt_id(transaction_id));
-            }
+    /// Sends the queued transactions to the primary, within the capacity of its memory pool.
+    ///
+    /// While the BFT is not synced, the transactions are held in the queue, until they are drained once it is synced,
+    /// or until they expire.
+    async fn drain_transactions(&self) {
+        // Drop the transmissions that waited in the inbound queues for too long.
+        self.expire_inbound_queues();
+        // If the BFT is not synced, hold the transactions in the queue.
+        if !self.is_synced() {
+            return;
         }
-
         // If the memory pool of this node is full, return early.
         let num_unconfirmed_transmissions = self.num_unconfirmed_transmissions();
         if num_unconfirmed_transmissions >= Primary::<N>::MAX_TRANSMISSIONS_TOLERANCE {
-            return Ok(());
+            return;
         }
         // Retrieve the transactions.
         let transactions = {
@@ -404,55 +1047,370 @@ impl<N: Network> Consensus<N> {
             let capacity = Primary::<N>::MAX_TRANSMISSIONS_TOLERANCE.saturating_sub(num_unconfirmed_transmissions);
             // Acquire the lock on the transactions queue.
             let mut tx_queue = self.transactions_queue.lock();
-            // Determine the number of deployments to send.
-            let num_deployments = tx_queue.deployments.len().min(capacity).min(MAX_DEPLOYMENTS_PER_INTERVAL);
-            // Determine the number of executions to send.
-            let num_executions = tx_queue.executions.len().min(capacity.saturating_sub(num_deployments));
-            // Create an iterator which will select interleaved deployments and executions within the capacity.
-            // Note: interleaving ensures we will never have consecutive invalid deployments blocking the queue.
-            let selector_iter = (0..num_deployments).map(|_| true).interleave((0..num_executions).map(|_| false));
-            // Drain the transactions from the queue, interleaving deployments and executions.
-            selector_iter
+            // Select the interleaved deployments and executions within the capacity.
+            let selection = select_transactions(
+                tx_queue.deployments.len(),
+                tx_queue.executions.len(),
+                capacity,
+                tx_queue.deployments_first,
+            );
+            // Alternate the class that takes the first slot, once a transaction is drained.
+            if !selection.is_empty() {
+                tx_queue.deployments_first = !tx_queue.deployments_first;
+            }
+            // Drain the transactions from the queue, interleaving deployments and executions,
+            // with the highest priority fees first in each class.
+            let transactions = selection
+                .into_iter()
                 .filter_map(|select_deployment| {
                     if select_deployment {
-                        tx_queue.deployments.pop_lru().map(|(_, tx)| tx)
+                        tx_queue.deployments.pop_highest().map(|(_, queued)| queued)
                     } else {
-                        tx_queue.executions.pop_lru().map(|(_, tx)| tx)
+                        tx_queue.executions.pop_highest().map(|(_, queued)| queued)
                     }
                 })
-                .collect_vec()
+                .collect_vec();
+            self.mempool_pressure.transactions().record_drained(transactions.len(), Instant::now());
+            transactions
         };
+        self.update_mempool_pressure();
         // Iterate over the transactions.
-        for transaction in transactions.into_iter() {
-            let transaction_id = transaction.id();
+        for queued in transactions.into_iter() {
+            let transaction_id = queued.transmission.id();
             trace!("Adding unconfirmed transaction '{}' to the memory pool...", fmt_id(transaction_id));
+            // Record the priority fee before the transaction reaches the ready queues, to keep the fees complete.
+            self.record_ready_fee(transaction_id, queued.transmission.priority_fee());
             // Send the unconfirmed transaction to the primary.
-            if let Err(e) =
-                self.primary_sender().send_unconfirmed_transaction(transaction_id, Data::Object(transaction)).await
-            {
+            let transaction = Data::Object(queued.transmission.clone());
+            if let Err(e) = self.primary_sender().send_unconfirmed_transaction(transaction_id, transaction).await {
                 // If the BFT is synced, then log the warning.
-                if self.bft.is_synced() {
+                if self.is_synced() {
                     warn!(
                         "Failed to add unconfirmed transaction '{}' to the memory pool - {e}",
                         fmt_id(transaction_id)
                     );
+                } else {
+                    // Otherwise, return the transaction to the queue, to be sent again once the BFT is synced.
+                    let mut tx_queue = self.transactions_queue.lock();
+                    match queued.transmission.is_deploy() {
+                        true => tx_queue.deployments.put(transaction_id, queued),
+                        false => tx_queue.executions.put(transaction_id, queued),
+                    };
                 }
             }
         }
-        Ok(())
     }
+
+    /// Drops the transmissions that waited in the inbound queues for longer than the TTL.
+    fn expire_inbound_queues(&self) {
+        let now = Instant::now();
+        let ttl = self.inbound_queue_ttl;
+        let num_expired_solutions = expire_queued(&mut self.solutions_queue.lock(), ttl, now);
+        let num_expired_transactions = {
+            let mut tx_queue = self.transactions_queue.lock();
+            tx_queue.deployments.expire(ttl, now) + tx_queue.executions.expire(ttl, now)
+        };
+        // The expired transmissions relieve the pressure on the queues, as the drained ones do.
+        self.mempool_pressure.solutions().record_drained(num_expired_solutions, now);
+        self.mempool_pressure.transactions().record_drained(num_expired_transactions, now);
+        let num_expired = num_expired_solutions + num_expired_transactions;
+        if num_expired > 0 {
+            debug!("Dropped {num_expired} unconfirmed transmissions that waited in the inbound queues for too long");
+            self.update_mempool_pressure();
+            #[cfg(feature = "metrics")]
+            (0..num_expired)
+                .for_each(|_| metrics::increment_counter(metrics::consensus::EXPIRED_INBOUND_TRANSMISSIONS));
+        }
+    }
+}
+
+impl<N: Network> Consensus<N> {
+    /// Returns the time that an unconfirmed transmission is kept in the inbound queues.
+    pub const fn inbound_queue_ttl(&self) -> Duration {
+        self.inbound_queue_ttl
+    }
+
+    /// Returns the mempool policy.
+    pub fn mempool_policy(&self) -> Arc<dyn MempoolPolicy<N>> {
+        self.mempool_policy.read().clone()
+    }
+
+    /// Replaces the mempool policy, which applies to the unconfirmed transmissions received from now on.
+    pub fn set_mempool_policy(&self, mempool_policy: Arc<dyn MempoolPolicy<N>>) {
+        *self.mempool_policy.write() = mempool_policy;
+    }
+
+    /// Returns `true` if the BFT is synced, i.e. if the inbound queues may be drained into the primary.
+    fn is_synced(&self) -> bool {
+        #[cfg(test)]
+        if let Some(is_synced) = self.is_synced_override.get() {
+            return *is_synced.borrow();
+        }
+        self.bft.is_synced()
+    }
+
+    /// Subscribes to the sync status of the BFT.
+    fn subscribe_is_synced(&self) -> watch::Receiver<bool> {
+        #[cfg(test)]
+        if let Some(is_synced) = self.is_synced_override.get() {
+            return is_synced.clone();
+        }
+        self.bft.subscribe_is_synced()
+    }
+
+    /// Returns the pressure on the inbound queues.
+    pub const fn mempool_pressure(&self) -> &Arc<MempoolPressure> {
+        &self.mempool_pressure
+    }
+
+    /// Updates the pressure on the inbound queues, from their occupancy.
+    fn update_mempool_pressure(&self) {
+        {
+            let solutions_queue = self.solutions_queue.lock();
+            self.mempool_pressure.solutions().update(solutions_queue.len(), solutions_queue.cap().get());
+        }
+        let tx_queue = self.transactions_queue.lock();
+        let len = tx_queue.deployments.len() + tx_queue.executions.len();
+        let capacity = tx_queue.deployments.cap() + tx_queue.executions.cap();
+        self.mempool_pressure.transactions().update(len, capacity);
+    }
+
+    /// Returns a snapshot of the node facts, for the mempool policy.
+    fn policy_context(&self) -> PolicyContext {
+        PolicyContext {
+            latest_height: self.ledger.latest_block_height(),
+            num_unconfirmed_transmissions: self.num_unconfirmed_transmissions(),
+            num_unconfirmed_solutions: self.num_unconfirmed_solutions(),
+            num_unconfirmed_transactions: self.num_unconfirmed_transactions(),
+            num_queued_solutions: self.solutions_queue.lock().len(),
+            num_queued_transactions: {
+                let tx_queue = self.transactions_queue.lock();
+                tx_queue.deployments.len() + tx_queue.executions.len()
+            },
+        }
+    }
+
+    /// Resizes the inbound queues and the seen caches to their capacities in the memory budget,
+    /// and reports their estimated memory.
+    ///
+    /// Note: The memory budget itself is rebalanced periodically by the node.
+    fn update_memory_budget(&self) {
+        let budget = &self.memory_budget;
+        let seen_capacity = budget.capacity(MemoryPool::SeenTransmissions, CAPACITY_FOR_SEEN_TRANSMISSIONS);
+        let seen_usage = {
+            let mut seen_solutions = self.seen_solutions.lock();
+            resize_lru(&mut seen_solutions, seen_capacity);
+            estimate_entries::<SolutionID<N>, ()>(seen_solutions.len())
+        } + {
+            let mut seen_transactions = self.seen_transactions.lock();
+            resize_lru(&mut seen_transactions, seen_capacity);
+            estimate_entries::<N::TransactionID, ()>(seen_transactions.len())
+        };
+        let (evicted_solutions, solutions_usage) = {
+            let mut solutions_queue = self.solutions_queue.lock();
+            let capacity = budget.capacity(MemoryPool::TransmissionsQueue, CAPACITY_FOR_SOLUTIONS);
+            let evicted = resize_lru(&mut solutions_queue, capacity);
+            (evicted, self.inbound_sizes.solutions.estimate(solutions_queue.len()))
+        };
+        let (evicted_transactions, transactions_usage) = {
+            let mut tx_queue = self.transactions_queue.lock();
+            let mut evicted =
+                tx_queue.deployments.resize(budget.capacity(MemoryPool::TransmissionsQueue, CAPACITY_FOR_DEPLOYMENTS));
+            evicted.extend(
+                tx_queue.executions.resize(budget.capacity(MemoryPool::TransmissionsQueue, CAPACITY_FOR_EXECUTIONS)),
+            );
+            let usage = self.inbound_sizes.deployments.estimate(tx_queue.deployments.len())
+                + self.inbound_sizes.executions.estimate(tx_queue.executions.len());
+            (evicted, usage)
+        };
+        budget.set_usage(MemoryPool::SeenTransmissions, seen_usage);
+        budget.set_usage(MemoryPool::TransmissionsQueue, solutions_usage + transactions_usage);
+        // The evicted transmissions are no longer unconfirmed, so their gauges are updated.
+        if !evicted_solutions.is_empty() || !evicted_transactions.is_empty() {
+            debug!(
+                "Evicted {} unconfirmed transmissions from the inbound queues to stay within the memory budget",
+                evicted_solutions.len() + evicted_transactions.len()
+            );
+            #[cfg(feature = "metrics")]
+            self.forget_evicted_transmissions(&evicted_solutions, &evicted_transactions);
+        }
+        // The capacities of the queues may have changed, so their pressure is updated.
+        self.update_mempool_pressure();
+    }
+
+    /// Removes the given transmissions, evicted from the inbound queues, from the unconfirmed gauges
+    /// and from the queue timestamps, so that they are not reported as stale later on.
+    #[cfg(feature = "metrics")]
+    fn forget_evicted_transmissions(&self, solution_ids: &[SolutionID<N>], transaction_ids: &[N::TransactionID]) {
+        metrics::decrement_gauge(metrics::consensus::UNCONFIRMED_SOLUTIONS, solution_ids.len() as f64);
+        metrics::decrement_gauge(metrics::consensus::UNCONFIRMED_TRANSACTIONS, transaction_ids.len() as f64);
+        let solution_ids = solution_ids.iter().collect::<HashSet<_>>();
+        let transaction_ids = transaction_ids.iter().collect::<HashSet<_>>();
+        self.transmissions_queue_timestamps.lock().retain(|transmission_id, _| match transmission_id {
+            TransmissionID::Solution(solution_id, _) => !solution_ids.contains(solution_id),
+            TransmissionID::Transaction(transaction_id, _) => !transaction_ids.contains(transaction_id),
+            TransmissionID::Ratification => true,
+        });
+    }
+}
+
+/// The outcome of adding an unconfirmed solution to the memory pool, which the node acknowledges to its prover.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SolutionOutcome {
+    /// The solution was added to the memory pool.
+    Accepted,
+    /// The solution was already seen, or already exists in the memory pool or the ledger.
+    Duplicate,
+    /// The solution was dropped, as the memory pool is not admitting solutions at the moment.
+    QueueFull,
+    /// The solution was rejected for another reason.
+    Rejected,
+}
+
+/// The rejection of an unconfirmed solution by the memory pool, with the outcome acknowledged to its prover.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SolutionRejection {
+    /// The outcome of the rejected solution.
+    pub status: SolutionOutcome,
+    /// The reason the solution was rejected.
+    pub reason: String,
+}
+
+impl SolutionRejection {
+    /// Returns the error of a solution rejected with the given status and reason.
+    fn new(status: SolutionOutcome, reason: String) -> anyhow::Error {
+        Self { status, reason }.into()
+    }
+
+    /// Returns the rejection the given error originates from, if any.
+    pub fn of(error: &anyhow::Error) -> Option<&Self> {
+        error.downcast_ref::<Self>()
+    }
+}
+
+impl fmt::Display for SolutionRejection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.reason)
+    }
+}
+
+impl std::error::Error for SolutionRejection {}
+
+/// Applies the decision of the given mempool policy on the given transmission, returning `true` if it is admitted,
+/// `false` if it is deferred, and an error with the reason if it is rejected.
+/// The decision is only recorded in the metrics if `commit` is `true`, i.e. if the transmission was submitted.
+fn check_admission(policy: &str, transmission: &str, admission: Admission, commit: bool) -> Result<bool> {
+    match admission {
+        Admission::Admit => Ok(true),
+        Admission::Reject(reason) => {
+            #[cfg(feature = "metrics")]
+            if commit {
+                metrics::increment_counter_label(metrics::consensus::POLICY_REJECTIONS, "policy", policy.to_string());
+            }
+            bail!("{transmission} was rejected by the '{policy}' mempool policy - {reason}")
+        }
+        Admission::Defer => {
+            if commit {
+                #[cfg(feature = "metrics")]
+                metrics::increment_counter_label(metrics::consensus::POLICY_DEFERRALS, "policy", policy.to_string());
+                trace!("{transmission} was deferred by the '{policy}' mempool policy");
+            }
+            Ok(false)
+        }
+    }
+}
+
+/// Returns the transmissions of a block that failed to advance, in the order they should be reinserted into the memory pool.
+///
+/// The transactions are reinserted before the solutions, so that their reinsertion never waits on the solutions.
+/// If the block would have started a new epoch, its solutions are dropped instead, as they are cleared from the
+/// memory pool as soon as the block is retried, and would otherwise hold back transactions while the memory pool is full.
+fn transmissions_to_reinsert<N: Network>(
+    transmissions: IndexMap<TransmissionID<N>, Transmission<N>>,
+    starts_new_epoch: bool,
+) -> Vec<(TransmissionID<N>, Transmission<N>)> {
+    let (solutions, transactions): (Vec<_>, Vec<_>) = transmissions
+        .into_iter()
+        // Ratifications are not reinserted.
+        .filter(|(transmission_id, _)| !matches!(transmission_id, TransmissionID::Ratification))
+        .partition(|(transmission_id, _)| matches!(transmission_id, TransmissionID::Solution(..)));
+    match starts_new_epoch {
+        true => transactions,
+        false => transactions.into_iter().chain(solutions).collect(),
+    }
+}
+
+/// Performs the given storage advance, and records its latency to throttle new work while the storage is slow.
+///
+/// The advance itself is never throttled, as a committed subdag must never be dropped.
+fn advance_with_backpressure<T>(backpressure: &StorageBackpressure, advance: impl FnOnce() -> Result<T>) -> Result<T> {
+    // Measure the duration of the advance.
+    let start = Instant::now();
+    let result = advance();
+    let latency = start.elapsed();
+
+    #[cfg(feature = "metrics")]
+    metrics::histogram(metrics::consensus::STORAGE_ADVANCE_LATENCY, latency.as_secs_f64());
+
+    // Update the throttle on new work.
+    if let Some(is_throttled) = backpressure.record(latency) {
+        let latencies = backpressure
+            .recent_latencies()
+            .iter()
+            .map(|latency| format!("{}ms", latency.as_millis()))
+            .collect::<Vec<_>>()
+            .join(", ");
+        match is_throttled {
+            true => warn!(
+                "{} - pausing new proposals and shedding new transmissions (recent advances: {latencies})",
+                "Storage is slow".red().bold()
+            ),
+            false => {
+                info!("Storage has recovered - resuming new proposals and transmissions (recent advances: {latencies})")
+            }
+        }
+    }
+    result
 }
 
 impl<N: Network> Consensus<N> {
     /// Starts the consensus handlers.
     fn start_handlers(&self, consensus_receiver: ConsensusReceiver<N>) {
-        let ConsensusReceiver { mut rx_consensus_subdag } = consensus_receiver;
+        let ConsensusReceiver { rx_consensus_subdag } = consensus_receiver;
+        // The receiver is shared, so that it can be reacquired if the task is restarted.
+        let rx_consensus_subdag = Arc::new(tokio::sync::Mutex::new(rx_consensus_subdag));
 
         // Process the committed subdag and transmissions from the BFT.
         let self_ = self.clone();
-        self.spawn(async move {
-            while let Some((committed_subdag, transmissions, callback)) = rx_consensus_subdag.recv().await {
-                self_.process_bft_subdag(committed_subdag, transmissions, callback).await;
+        self.spawn("process_bft_subdag", TaskKind::Critical { max_restarts: 3 }, move || {
+            let (self_, rx_consensus_subdag) = (self_.clone(), rx_consensus_subdag.clone());
+            async move {
+                let mut rx_consensus_subdag = rx_consensus_subdag.lock().await;
+                while let Some((committed_subdag, transmissions, callback)) = rx_consensus_subdag.recv().await {
+                    self_.process_bft_subdag(committed_subdag, transmissions, callback).await;
+                }
+            }
+        });
+
+        // Drain the inbound queues every time the BFT becomes synced, as they are held while it is not.
+        self.spawn_inbound_drain();
+    }
+
+    /// Spawns the task that drains the inbound queues every time the BFT becomes synced.
+    fn spawn_inbound_drain(&self) {
+        let self_ = self.clone();
+        self.spawn("drain_inbound_queues", TaskKind::Auxiliary, move || {
+            let self_ = self_.clone();
+            async move {
+                let is_synced = self_.subscribe_is_synced();
+                drain_when_synced(is_synced, || {
+                    let self_ = self_.clone();
+                    async move {
+                        self_.drain_transactions().await;
+                        self_.drain_solutions().await;
+                    }
+                })
+                .await
             }
         });
     }
@@ -472,8 +1430,11 @@ impl<N: Network> Consensus<N> {
         // If the block failed to advance, reinsert the transmissions into the memory pool.
         if let Err(e) = &result {
             error!("Unable to advance to the next block - {e}");
+            // Determine if the block would have started a new epoch.
+            let next_height = self.ledger.latest_block_height().saturating_add(1);
+            let starts_new_epoch = next_height % N::NUM_BLOCKS_PER_EPOCH == 0;
             // On failure, reinsert the transmissions into the memory pool.
-            self.reinsert_transmissions(transmissions).await;
+            self.reinsert_transmissions(transmissions_to_reinsert(transmissions, starts_new_epoch)).await;
         }
         // Send the callback **after** advancing to the next block.
         // Note: We must await the block to be advanced before sending the callback.
@@ -493,12 +1454,29 @@ impl<N: Network> Consensus<N> {
         #[cfg(feature = "metrics")]
         let current_block_timestamp = self.ledger.latest_block().header().metadata().timestamp();
 
+        // Determine whether each round of the subdag includes our certificate.
+        let address = self.bft.primary().gateway().account().address();
+        let inclusion = subdag
+            .values()
+            .map(|certificates| certificates.iter().any(|certificate| certificate.author() == address))
+            .collect::<Vec<_>>();
+
         // Create the candidate next block.
         let next_block = self.ledger.prepare_advance_to_next_quorum_block(subdag, transmissions)?;
         // Check that the block is well-formed.
         self.ledger.check_next_block(&next_block)?;
-        // Advance to the next block.
-        self.ledger.advance_to_next_block(&next_block)?;
+        // Advance to the next block, throttling new work if the storage is slow.
+        advance_with_backpressure(self.bft.primary().storage_backpressure(), || {
+            self.ledger.advance_to_next_block(&next_block)
+        })?;
+        // Record the inclusion of our certificates in the committed subdag.
+        self.certificate_inclusion.record(inclusion);
+        // Notify the subscriber of the committed block, if any, without waiting for it.
+        if let Some(sender) = self.committed_blocks_sender.get() {
+            if let Err(error) = sender.try_send(next_block.clone()) {
+                debug!("Unable to notify the subscriber of block {} - {error}", next_block.height());
+            }
+        }
 
         // If the next block starts a new epoch, clear the existing solutions.
         if next_block.height() % N::NUM_BLOCKS_PER_EPOCH == 0 {
@@ -529,8 +1507,8 @@ impl<N: Network> Consensus<N> {
         Ok(())
     }
 
-    /// Reinserts the given transmissions into the memory pool.
-    async fn reinsert_transmissions(&self, transmissions: IndexMap<TransmissionID<N>, Transmission<N>>) {
+    /// Reinserts the given transmissions into the memory pool, in the given order.
+    async fn reinsert_transmissions(&self, transmissions: Vec<(TransmissionID<N>, Transmission<N>)>) {
         // Iterate over the transmissions.
         for (transmission_id, transmission) in transmissions.into_iter() {
             // Reinsert the transmission into the memory pool.
@@ -560,6 +1538,10 @@ impl<N: Network> Consensus<N> {
                 self.primary_sender().tx_unconfirmed_solution.send((solution_id, solution, callback)).await?;
             }
             (TransmissionID::Transaction(transaction_id, _), Transmission::Transaction(transaction)) => {
+                // Record the priority fee, if it is known without deserializing the transaction.
+                if let Data::Object(transaction) = &transaction {
+                    self.record_ready_fee(transaction_id, transaction.priority_fee());
+                }
                 // Send the transaction to the primary.
                 self.primary_sender().tx_unconfirmed_transaction.send((transaction_id, transaction, callback)).await?;
             }
@@ -569,17 +1551,695 @@ impl<N: Network> Consensus<N> {
         callback_receiver.await?
     }
 
-    /// Spawns a task with the given future; it should only be used for long-running tasks.
-    fn spawn<T: Future<Output = ()> + Send + 'static>(&self, future: T) {
-        self.handles.lock().push(tokio::spawn(future));
+    /// Spawns a supervised task with the given name and kind, from the given factory;
+    /// it should only be used for long-running tasks.
+    fn spawn<F, T>(&self, name: &str, kind: TaskKind, factory: F)
+    where
+        F: Fn() -> T + Send + Sync + 'static,
+        T: Future<Output = ()> + Send + 'static,
+    {
+        self.tasks.spawn_task(name, kind, Box::new(move || Box::pin(factory())));
     }
 
     /// Shuts down the BFT.
     pub async fn shut_down(&self) {
         info!("Shutting down consensus...");
+        // Abort the tasks, before the BFT closes the channels they consume.
+        self.tasks.shut_down().await;
         // Shut down the BFT.
         self.bft.shut_down().await;
-        // Abort the tasks.
-        self.handles.lock().iter().for_each(|handle| handle.abort());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkos_node_bft::helpers::init_primary_channels;
+    use snarkos_node_bft_ledger_service::{MockLedgerService, test_helpers::sample_transaction_with_priority_fee};
+    use snarkvm::ledger::puzzle::PartialSolution;
+
+    use ::bytes::Bytes;
+    use test_strategy::proptest;
+
+    type CurrentNetwork = snarkvm::prelude::MainnetV0;
+
+    /// Returns a sample transaction transmission with the given index.
+    fn sample_transaction(index: u64) -> (TransmissionID<CurrentNetwork>, Transmission<CurrentNetwork>) {
+        let data = Data::<Transaction<CurrentNetwork>>::Buffer(Bytes::from(index.to_le_bytes().to_vec()));
+        let checksum = data.to_checksum::<CurrentNetwork>().unwrap();
+        let transaction_id = <CurrentNetwork as Network>::TransactionID::from(Field::from_u64(index));
+        (TransmissionID::Transaction(transaction_id, checksum), Transmission::Transaction(data))
+    }
+
+    /// Returns a sample solution transmission with the given index.
+    fn sample_solution(index: u64) -> (TransmissionID<CurrentNetwork>, Transmission<CurrentNetwork>) {
+        let data = Data::<Solution<CurrentNetwork>>::Buffer(Bytes::from(index.to_le_bytes().to_vec()));
+        let checksum = data.to_checksum::<CurrentNetwork>().unwrap();
+        (TransmissionID::Solution(SolutionID::from(index), checksum), Transmission::Solution(data))
+    }
+
+    #[test]
+    fn test_transmissions_to_reinsert() {
+        // Interleave the solutions and transactions, as in a committed subdag.
+        let transmissions: IndexMap<_, _> = [
+            (TransmissionID::Ratification, Transmission::Ratification),
+            sample_solution(1),
+            sample_transaction(2),
+            sample_solution(3),
+            sample_transaction(4),
+        ]
+        .into_iter()
+        .collect();
+        let ids = |transmissions: Vec<(TransmissionID<CurrentNetwork>, _)>| {
+            transmissions.into_iter().map(|(transmission_id, _)| transmission_id).collect::<Vec<_>>()
+        };
+
+        // Ensure the transactions are reinserted before the solutions, in their original order.
+        let expected =
+            vec![sample_transaction(2).0, sample_transaction(4).0, sample_solution(1).0, sample_solution(3).0];
+        assert_eq!(ids(transmissions_to_reinsert(transmissions.clone(), false)), expected);
+
+        // Ensure only the transactions are reinserted at an epoch boundary.
+        let expected = vec![sample_transaction(2).0, sample_transaction(4).0];
+        assert_eq!(ids(transmissions_to_reinsert(transmissions, true)), expected);
+    }
+
+    #[proptest]
+    fn test_select_transactions(
+        #[strategy(0usize..64)] num_deployments: usize,
+        #[strategy(0usize..64)] num_executions: usize,
+        #[strategy(0usize..16)] capacity: usize,
+        deployments_first: bool,
+    ) {
+        let selection = select_transactions(num_deployments, num_executions, capacity, deployments_first);
+        let selected_deployments = selection.iter().filter(|select_deployment| **select_deployment).count();
+        let selected_executions = selection.len() - selected_deployments;
+
+        // Ensure the selection is within the capacity and the queues.
+        assert!(selected_deployments <= num_deployments.min(MAX_DEPLOYMENTS_PER_INTERVAL));
+        assert!(selected_executions <= num_executions);
+        // Ensure the capacity is used up, as far as the queues allow.
+        assert_eq!(selection.len(), capacity.min(num_deployments.min(MAX_DEPLOYMENTS_PER_INTERVAL) + num_executions));
+        // Ensure each class with pending transactions gets a slot, if the capacity allows for both.
+        if capacity >= 2 && num_deployments > 0 && num_executions > 0 {
+            assert!(selected_deployments > 0 && selected_executions > 0);
+        }
+        // Ensure the first slot goes to the given class, if it has pending transactions.
+        if let Some(first) = selection.first() {
+            assert_eq!(*first, if deployments_first { num_deployments > 0 } else { num_executions == 0 });
+        }
+        // Ensure the classes are interleaved.
+        let num_interleaved = 2 * selected_deployments.min(selected_executions);
+        assert!(selection[..num_interleaved].windows(2).all(|window| window[0] != window[1]));
+    }
+
+    /// The capacity, and the number of pending deployments and executions, in an interval.
+    type Interval = (usize, usize, usize);
+
+    #[proptest]
+    fn test_select_transactions_without_starvation(
+        #[strategy(proptest::collection::vec((1usize..4, 1usize..64, 1usize..64), 1..100))] intervals: Vec<Interval>,
+    ) {
+        // Simulate the drain over adversarial intervals, in which both classes have pending transactions.
+        let mut deployments_first = true;
+        let (mut deployments_wait, mut executions_wait) = (0, 0);
+        for (capacity, num_deployments, num_executions) in intervals {
+            let selection = select_transactions(num_deployments, num_executions, capacity, deployments_first);
+            if !selection.is_empty() {
+                deployments_first = !deployments_first;
+            }
+            // Track the number of consecutive intervals each class was not selected in.
+            deployments_wait = if selection.contains(&true) { 0 } else { deployments_wait + 1 };
+            executions_wait = if selection.contains(&false) { 0 } else { executions_wait + 1 };
+            // Ensure neither class waits for more than one interval.
+            assert!(deployments_wait <= 1 && executions_wait <= 1);
+        }
+    }
+
+    #[test]
+    fn test_select_transactions_with_deployment_flood() {
+        // With room for a single transaction, the classes take turns.
+        assert_eq!(select_transactions(1000, 1000, 1, true), vec![true]);
+        assert_eq!(select_transactions(1000, 1000, 1, false), vec![false]);
+        // A lone class takes the slot, whichever turn it is.
+        assert_eq!(select_transactions(1000, 0, 1, false), vec![true]);
+        assert_eq!(select_transactions(0, 1000, 1, true), vec![false]);
+        // With more room, the deployments are still limited per interval.
+        assert_eq!(select_transactions(1000, 1000, 4, true), vec![true, false, false, false]);
+        assert_eq!(select_transactions(1000, 1000, 4, false), vec![false, true, false, false]);
+        assert_eq!(select_transactions(1000, 0, 4, true), vec![true]);
+        assert!(select_transactions(1000, 1000, 0, true).is_empty());
+    }
+
+    /// A test mempool policy, which defers the transactions until a height, and rejects them if the queue is long.
+    struct TestPolicy {
+        /// The height from which the transactions are admitted.
+        admission_height: u32,
+        /// The number of queued transactions from which the transactions are rejected.
+        max_queued_transactions: usize,
+    }
+
+    impl MempoolPolicy<CurrentNetwork> for TestPolicy {
+        fn name(&self) -> &str {
+            "test"
+        }
+
+        fn admit_transaction(&self, _transaction: &Transaction<CurrentNetwork>, context: &PolicyContext) -> Admission {
+            if context.num_queued_transactions >= self.max_queued_transactions {
+                Admission::Reject(format!("{} transactions are queued", context.num_queued_transactions))
+            } else if context.latest_height < self.admission_height {
+                Admission::Defer
+            } else {
+                Admission::Admit
+            }
+        }
+    }
+
+    #[test]
+    fn test_custom_mempool_policy() {
+        let rng = &mut TestRng::default();
+        let transaction = snarkvm::ledger::ledger_test_helpers::sample_execution_transaction_with_fee(false, rng);
+        let policy: Arc<dyn MempoolPolicy<CurrentNetwork>> =
+            Arc::new(TestPolicy { admission_height: 10, max_queued_transactions: 3 });
+        let check = |context: PolicyContext| {
+            check_admission(policy.name(), "Transaction 'at1'", policy.admit_transaction(&transaction, &context), true)
+        };
+
+        // Ensure the transaction is deferred, then admitted, as the ledger advances.
+        assert!(!check(PolicyContext { latest_height: 9, ..Default::default() }).unwrap());
+        assert!(check(PolicyContext { latest_height: 10, ..Default::default() }).unwrap());
+
+        // Ensure the rejection names the policy and its reason.
+        let error = check(PolicyContext { num_queued_transactions: 3, ..Default::default() }).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Transaction 'at1' was rejected by the 'test' mempool policy - 3 transactions are queued"
+        );
+
+        // Ensure the default policy admits everything.
+        let policy: Arc<dyn MempoolPolicy<CurrentNetwork>> = Arc::new(DefaultMempoolPolicy);
+        assert_eq!(policy.admit_transaction(&transaction, &PolicyContext::default()), Admission::Admit);
+    }
+
+    /// A memory budget that scales the capacity of each structure by a fixed percentage.
+    struct ScaledBudget {
+        seen_percent: usize,
+        queue_percent: usize,
+    }
+
+    impl MemoryAccounting for ScaledBudget {
+        fn capacity(&self, pool: MemoryPool, nominal_capacity: usize) -> usize {
+            match pool {
+                MemoryPool::SeenTransmissions => nominal_capacity * self.seen_percent / 100,
+                MemoryPool::TransmissionsQueue => nominal_capacity * self.queue_percent / 100,
+            }
+        }
+
+        fn set_usage(&self, _pool: MemoryPool, _bytes: u64) {}
+    }
+
+    /// A memory budget that caps the capacity of each inbound queue at a fixed number of transmissions.
+    struct CappedBudget {
+        max_queued: usize,
+    }
+
+    impl MemoryAccounting for CappedBudget {
+        fn capacity(&self, pool: MemoryPool, nominal_capacity: usize) -> usize {
+            match pool {
+                MemoryPool::SeenTransmissions => nominal_capacity,
+                MemoryPool::TransmissionsQueue => nominal_capacity.min(self.max_queued),
+            }
+        }
+
+        fn set_usage(&self, _pool: MemoryPool, _bytes: u64) {}
+    }
+
+    #[test]
+    fn test_resize_with_memory_budget() {
+        let (num_seen, num_queued) = (CAPACITY_FOR_SEEN_TRANSMISSIONS, CAPACITY_FOR_EXECUTIONS);
+        // Fill a seen cache and an inbound queue.
+        let mut seen = LruCache::new(NonZeroUsize::new(num_seen).unwrap());
+        let mut queue = LruCache::new(NonZeroUsize::new(num_queued).unwrap());
+        (0..num_seen as u64).for_each(|i| assert!(seen.put(i, ()).is_none()));
+        (0..num_queued as u64).for_each(|i| assert!(queue.put(i, ()).is_none()));
+
+        // Shrink the memory budget step by step, resizing the structures as consensus does.
+        let mut lengths = vec![];
+        let mut evicted = vec![];
+        for (seen_percent, queue_percent) in [(100, 100), (50, 100), (10, 50), (0, 0)] {
+            let budget: Arc<dyn MemoryAccounting> = Arc::new(ScaledBudget { seen_percent, queue_percent });
+            resize_lru(&mut seen, budget.capacity(MemoryPool::SeenTransmissions, num_seen));
+            evicted.extend(resize_lru(&mut queue, budget.capacity(MemoryPool::TransmissionsQueue, num_queued)));
+            lengths.push((seen.len(), queue.len()));
+        }
+
+        // Ensure the structures are shrunk to their capacities, and are never emptied.
+        assert_eq!(lengths, vec![
+            (num_seen, num_queued),
+            (num_seen / 2, num_queued),
+            (num_seen / 10, num_queued / 2),
+            (1, 1),
+        ]);
+        // Ensure the most recent entries are retained.
+        assert!(queue.contains(&(num_queued as u64 - 1)));
+        assert!(!queue.contains(&0));
+        // Ensure every evicted entry is reported once, oldest first, so that the gauges can be updated.
+        assert_eq!(evicted, (0..num_queued as u64 - 1).collect::<Vec<_>>());
+    }
+
+    /// A mock ledger, whose storage advances take as long as configured.
+    #[derive(Default)]
+    struct SlowLedger {
+        /// The heights of the advanced blocks.
+        heights: Mutex<Vec<u32>>,
+    }
+
+    impl SlowLedger {
+        /// Advances to the block with the given height, after the given delay.
+        fn advance_to_next_block(&self, height: u32, delay: Duration) -> Result<()> {
+            std::thread::sleep(delay);
+            let mut heights = self.heights.lock();
+            ensure!(height == heights.len() as u32 + 1, "Tried to advance to block {height} out of order");
+            heights.push(height);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_storage_backpressure() {
+        let ledger = SlowLedger::default();
+        let backpressure =
+            StorageBackpressure::new(Duration::from_millis(100), Duration::from_millis(20), Duration::from_secs(60));
+        let (fast, slow) = (Duration::ZERO, Duration::from_millis(150));
+
+        // Advance through a storage stall, recording the throttle status after each block.
+        let delays = [fast, fast, slow, slow, fast, fast, fast, fast];
+        let mut statuses = vec![];
+        for (i, delay) in delays.into_iter().enumerate() {
+            advance_with_backpressure(&backpressure, || ledger.advance_to_next_block(i as u32 + 1, delay)).unwrap();
+            statuses.push(backpressure.is_throttled());
+        }
+
+        // Ensure the throttle engages on the first slow advance, and releases once the advances are fast again.
+        assert_eq!(statuses, vec![false, false, true, true, true, true, false, false]);
+        // Ensure every committed block was advanced, in order.
+        assert_eq!(*ledger.heights.lock(), (1..=delays.len() as u32).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_transactions_are_never_dropped() {
+        // Ensure every transaction is reinserted, whether or not the block starts a new epoch.
+        for starts_new_epoch in [false, true] {
+            let transmissions: IndexMap<_, _> =
+                (0..100).map(|i| if i % 3 == 0 { sample_solution(i) } else { sample_transaction(i) }).collect();
+            let num_transactions =
+                transmissions.keys().filter(|id| matches!(id, TransmissionID::Transaction(..))).count();
+            let reinserted = transmissions_to_reinsert(transmissions, starts_new_epoch);
+            let num_reinserted_transactions =
+                reinserted.iter().filter(|(id, _)| matches!(id, TransmissionID::Transaction(..))).count();
+            assert_eq!(num_reinserted_transactions, num_transactions);
+        }
+    }
+
+    /// A task spawner that spawns the tasks without supervising them.
+    struct UnsupervisedTasks;
+
+    impl TaskSpawner for UnsupervisedTasks {
+        fn spawn_task(&self, _name: &str, _kind: TaskKind, factory: TaskFactory) {
+            tokio::spawn(factory());
+        }
+
+        fn shut_down(&self) -> TaskFuture {
+            Box::pin(async {})
+        }
+    }
+
+    /// Returns a consensus instance on the given mock ledger, and the path of its storage.
+    fn sample_consensus(
+        ledger: MockLedgerService<CurrentNetwork>,
+        rng: &mut TestRng,
+    ) -> (Consensus<CurrentNetwork>, std::path::PathBuf) {
+        let storage_path = std::env::temp_dir().join(format!("snarkos-consensus-{}", rng.gen::<u64>()));
+        let consensus = Consensus::new(
+            Account::new(rng).unwrap(),
+            Arc::new(ledger),
+            None,
+            &[],
+            StorageMode::Custom(storage_path.clone()),
+            Arc::new(ScaledBudget { seen_percent: 100, queue_percent: 100 }),
+            Arc::new(UnsupervisedTasks),
+            Arc::new(DefaultMempoolPolicy),
+            Duration::from_secs(DEFAULT_INBOUND_QUEUE_TTL_IN_SECS),
+            Default::default(),
+        )
+        .unwrap();
+        (consensus, storage_path)
+    }
+
+    /// Returns the IDs of the transmissions reinserted by consensus, after a committed subdag fails to advance
+    /// the given mock ledger, in the order they were sent to the primary.
+    async fn reinserted_after_failure(
+        ledger: MockLedgerService<CurrentNetwork>,
+        transmissions: IndexMap<TransmissionID<CurrentNetwork>, Transmission<CurrentNetwork>>,
+        rng: &mut TestRng,
+    ) -> Vec<TransmissionID<CurrentNetwork>> {
+        let (consensus, storage_path) = sample_consensus(ledger, rng);
+
+        // Stand in for the primary, acknowledging every reinserted transmission.
+        let (primary_sender, primary_receiver) = init_primary_channels();
+        consensus.primary_sender.set(primary_sender).unwrap();
+        let PrimaryReceiver { mut rx_unconfirmed_solution, mut rx_unconfirmed_transaction, .. } = primary_receiver;
+        let reinserted = Arc::new(Mutex::new(Vec::new()));
+        let reinserted_ = reinserted.clone();
+        tokio::spawn(async move {
+            loop {
+                let (transmission_id, callback) = tokio::select! {
+                    Some((solution_id, solution, callback)) = rx_unconfirmed_solution.recv() => {
+                        let checksum = solution.to_checksum::<CurrentNetwork>().unwrap();
+                        (TransmissionID::Solution(solution_id, checksum), callback)
+                    }
+                    Some((transaction_id, transaction, callback)) = rx_unconfirmed_transaction.recv() => {
+                        let checksum = transaction.to_checksum::<CurrentNetwork>().unwrap();
+                        (TransmissionID::Transaction(transaction_id, checksum), callback)
+                    }
+                    else => break,
+                };
+                // Record the transmission before acknowledging it, so it is visible once consensus moves on.
+                reinserted_.lock().push(transmission_id);
+                callback.send(Ok(())).unwrap();
+            }
+        });
+
+        // Commit a subdag, which the mock ledger fails to advance to.
+        let subdag = snarkvm::ledger::narwhal::subdag::test_helpers::sample_subdag(rng);
+        let (callback, callback_receiver) = oneshot::channel();
+        consensus.process_bft_subdag(subdag, transmissions, callback).await;
+        assert!(callback_receiver.await.unwrap().is_err());
+
+        std::fs::remove_dir_all(storage_path).ok();
+        let reinserted = reinserted.lock().clone();
+        reinserted
+    }
+
+    #[tokio::test]
+    async fn test_epoch_boundary_failure_reinserts_transactions() {
+        let rng = &mut TestRng::default();
+        // Interleave the solutions and transactions, as in a committed subdag.
+        let transmissions: IndexMap<_, _> =
+            (0..12).map(|i| if i % 2 == 0 { sample_solution(i) } else { sample_transaction(i) }).collect();
+        let transactions: Vec<_> =
+            transmissions.keys().filter(|id| matches!(id, TransmissionID::Transaction(..))).copied().collect();
+        let solutions: Vec<_> =
+            transmissions.keys().filter(|id| matches!(id, TransmissionID::Solution(..))).copied().collect();
+
+        // Fail the block at the epoch boundary, where the committee for the next epoch takes effect.
+        let epoch = <CurrentNetwork as Network>::NUM_BLOCKS_PER_EPOCH;
+        let committee = snarkvm::ledger::committee::test_helpers::sample_committee_for_round(epoch as u64 * 2, rng);
+        let ledger = MockLedgerService::new_at_height(committee, epoch - 1);
+        // Ensure every transaction is reinserted, in order, and the solutions of the ending epoch are dropped.
+        assert_eq!(reinserted_after_failure(ledger, transmissions.clone(), rng).await, transactions);
+
+        // Fail a block within the epoch.
+        let committee = snarkvm::ledger::committee::test_helpers::sample_committee(rng);
+        let ledger = MockLedgerService::new_at_height(committee, epoch - 2);
+        // Ensure the transactions are reinserted ahead of the solutions, which are kept.
+        let expected: Vec<_> = transactions.into_iter().chain(solutions).collect();
+        assert_eq!(reinserted_after_failure(ledger, transmissions, rng).await, expected);
+    }
+
+    #[tokio::test]
+    async fn test_inbound_queues_are_drained_once_synced() {
+        let rng = &mut TestRng::default();
+        let committee = snarkvm::ledger::committee::test_helpers::sample_committee(rng);
+        let (consensus, storage_path) = sample_consensus(MockLedgerService::new_at_height(committee, 1), rng);
+
+        // Stand in for the BFT, which is not synced, and for the primary.
+        let (is_synced_sender, is_synced) = watch::channel(false);
+        consensus.is_synced_override.set(is_synced).unwrap();
+        let (primary_sender, mut primary_receiver) = init_primary_channels();
+        consensus.primary_sender.set(primary_sender).unwrap();
+        consensus.spawn_inbound_drain();
+
+        // Submit the solutions while the BFT is not synced, and ensure they are held in the queue.
+        let solutions = (0..4)
+            .map(|_| {
+                let address = Address::try_from(PrivateKey::<CurrentNetwork>::new(rng).unwrap()).unwrap();
+                Solution::new(PartialSolution::new(rng.gen(), address, rng.gen()).unwrap(), rng.gen())
+            })
+            .collect::<Vec<_>>();
+        for solution in &solutions {
+            assert_eq!(consensus.add_unconfirmed_solution(*solution).await.unwrap(), SolutionOutcome::Accepted);
+        }
+        assert_eq!(consensus.solutions_queue.lock().len(), solutions.len());
+        assert!(primary_receiver.rx_unconfirmed_solution.try_recv().is_err());
+
+        // Flip the BFT to synced, and ensure every solution reaches the primary, in arrival order.
+        is_synced_sender.send_replace(true);
+        for solution in &solutions {
+            let (solution_id, _, callback) = primary_receiver.rx_unconfirmed_solution.recv().await.unwrap();
+            assert_eq!(solution_id, solution.id());
+            callback.send(Ok(())).unwrap();
+        }
+        assert!(consensus.solutions_queue.lock().is_empty());
+
+        std::fs::remove_dir_all(storage_path).ok();
+    }
+
+    /// Returns a consensus instance on a mock ledger, which holds the transactions in its inbound queue,
+    /// as the BFT is not synced.
+    fn sample_unsynced_consensus(rng: &mut TestRng) -> (Consensus<CurrentNetwork>, std::path::PathBuf) {
+        let committee = snarkvm::ledger::committee::test_helpers::sample_committee(rng);
+        let (consensus, storage_path) = sample_consensus(MockLedgerService::new_at_height(committee, 1), rng);
+        consensus.is_synced_override.set(watch::channel(false).1).unwrap();
+        (consensus, storage_path)
+    }
+
+    /// Ensures the pre-flight on the given transaction has the verdict of its immediate submission to consensus,
+    /// and returns the verdict.
+    async fn assert_preflight_parity(
+        consensus: &Consensus<CurrentNetwork>,
+        transaction: &Transaction<CurrentNetwork>,
+    ) -> AdmissionVerdict {
+        let preflight = consensus.preflight_unconfirmed_transaction(transaction).unwrap();
+        // Ensure the pre-flight is read-only.
+        assert_eq!(consensus.preflight_unconfirmed_transaction(transaction).unwrap(), preflight);
+
+        // Submit the transaction, and ensure it is rejected with the error of the pre-flight, or queued if accepted.
+        let submission = consensus.add_unconfirmed_transaction(transaction.clone(), None).await;
+        match preflight.outcome {
+            AdmissionOutcome::Rejected => {
+                assert_eq!(Some(submission.unwrap_err().to_string()), preflight.error)
+            }
+            AdmissionOutcome::Accepted => {
+                submission.unwrap();
+                assert!(consensus.transactions_queue.lock().contains(&transaction.id()));
+            }
+            AdmissionOutcome::Ignored | AdmissionOutcome::Deferred => submission.unwrap(),
+        }
+        preflight
+    }
+
+    #[tokio::test]
+    async fn test_preflight_parity_for_duplicates() {
+        let rng = &mut TestRng::default();
+        let transaction = snarkvm::ledger::ledger_test_helpers::sample_execution_transaction_with_fee(false, rng);
+        let (consensus, storage_path) = sample_unsynced_consensus(rng);
+
+        // Ensure a new transaction passes every check.
+        let verdict = assert_preflight_parity(&consensus, &transaction).await;
+        assert_eq!(verdict.outcome, AdmissionOutcome::Accepted);
+        assert_eq!(verdict.checks.len(), 7);
+        assert!(verdict.checks.iter().all(|result| result.passed));
+        assert_eq!(verdict.error, None);
+
+        // Ensure a duplicate is ignored without an error.
+        let verdict = assert_preflight_parity(&consensus, &transaction).await;
+        assert_eq!(verdict.outcome, AdmissionOutcome::Ignored);
+        assert_eq!(verdict.failed_check(), Some(AdmissionCheck::NotRecentlySeen));
+        assert_eq!(verdict.error, None);
+
+        std::fs::remove_dir_all(storage_path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_preflight_parity_for_fee_transactions() {
+        let rng = &mut TestRng::default();
+        let transaction = snarkvm::ledger::ledger_test_helpers::sample_execution_transaction_with_fee(false, rng);
+        let fee_transaction = Transaction::from_fee(transaction.fee_transition().unwrap()).unwrap();
+        let (consensus, storage_path) = sample_unsynced_consensus(rng);
+
+        // Ensure a fee transaction is rejected, with a plain error, as it is returned in the JSON verdict.
+        let verdict = assert_preflight_parity(&consensus, &fee_transaction).await;
+        assert_eq!(verdict.outcome, AdmissionOutcome::Rejected);
+        assert_eq!(verdict.failed_check(), Some(AdmissionCheck::NotFee));
+        assert_eq!(
+            verdict.error.unwrap(),
+            format!("Transaction '{}' is a fee transaction (skipping)", fmt_id(fee_transaction.id()))
+        );
+
+        std::fs::remove_dir_all(storage_path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_preflight_parity_over_capacity() {
+        let rng = &mut TestRng::default();
+        let queued = snarkvm::ledger::ledger_test_helpers::sample_execution_transaction_with_fee(false, rng);
+        let transaction = snarkvm::ledger::ledger_test_helpers::sample_execution_transaction_with_fee(true, rng);
+        let (consensus, storage_path) = sample_unsynced_consensus(rng);
+        consensus.set_mempool_policy(Arc::new(TestPolicy { admission_height: 0, max_queued_transactions: 1 }));
+
+        // Fill the queue.
+        assert_eq!(assert_preflight_parity(&consensus, &queued).await.outcome, AdmissionOutcome::Accepted);
+
+        // Ensure the transaction is rejected by the policy, with the error of a submission.
+        let verdict = assert_preflight_parity(&consensus, &transaction).await;
+        assert_eq!(verdict.outcome, AdmissionOutcome::Rejected);
+        assert_eq!(verdict.failed_check(), Some(AdmissionCheck::MempoolPolicy));
+        assert_eq!(
+            verdict.error.unwrap(),
+            format!(
+                "Transaction '{}' was rejected by the 'test' mempool policy - 1 transactions are queued",
+                fmt_id(transaction.id())
+            )
+        );
+
+        std::fs::remove_dir_all(storage_path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_deployment_rate_limit_per_peer() {
+        let rng = &mut TestRng::default();
+        let spam = snarkvm::ledger::ledger_test_helpers::sample_deployment_transaction(true, rng);
+        let deployment = snarkvm::ledger::ledger_test_helpers::sample_deployment_transaction(false, rng);
+        let (consensus, storage_path) = sample_unsynced_consensus(rng);
+        let (spammer, honest) = ("1.1.1.1:4130".parse().unwrap(), "2.2.2.2:4130".parse().unwrap());
+
+        // Let the spamming peer reach its deployment rate limit.
+        for _ in 0..MAX_DEPLOYMENTS_PER_PEER {
+            consensus.deployment_limiter.check(spammer, Instant::now(), true).unwrap();
+        }
+
+        // Ensure the next deployment of the spamming peer is rejected with the distinct error.
+        let error = consensus.add_unconfirmed_transaction(spam.clone(), Some(spammer)).await.unwrap_err();
+        assert!(error.downcast_ref::<DeploymentRateExceeded>().is_some());
+        // Ensure the rejected deployment was forgotten, so that it is reconsidered if it is received from another peer.
+        assert!(!consensus.seen_transactions.lock().contains(&spam.id()));
+
+        // Ensure the deployment of the honest peer is queued.
+        consensus.add_unconfirmed_transaction(deployment.clone(), Some(honest)).await.unwrap();
+        let tx_queue = consensus.transactions_queue.lock();
+        assert!(tx_queue.deployments.contains(&deployment.id()));
+        assert!(!tx_queue.deployments.contains(&spam.id()));
+        drop(tx_queue);
+
+        std::fs::remove_dir_all(storage_path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_memory_pool_priority_fees() {
+        let rng = &mut TestRng::default();
+        let transactions = [false, true].map(|is_fee_private| {
+            snarkvm::ledger::ledger_test_helpers::sample_execution_transaction_with_fee(is_fee_private, rng)
+        });
+        let (consensus, storage_path) = sample_unsynced_consensus(rng);
+        for transaction in &transactions {
+            consensus.add_unconfirmed_transaction(transaction.clone(), None).await.unwrap();
+        }
+
+        // Ensure the fees cover the same transactions as the summaries of the memory pool.
+        let (queue_depth, mut fees) = consensus.memory_pool_priority_fees();
+        let summaries = consensus.memory_pool_summaries();
+        assert_eq!(
+            queue_depth,
+            summaries.iter().filter(|summary| summary.kind == TransmissionKind::Transaction).count()
+        );
+        assert_eq!(queue_depth, transactions.len());
+        let mut expected = transactions.iter().map(|transaction| transaction.priority_fee()).collect::<Vec<_>>();
+        fees.sort_unstable();
+        expected.sort_unstable();
+        assert_eq!(fees, expected);
+
+        // Ensure the fee of a transaction that is no longer in the ready queues is not counted.
+        let transaction_id = <CurrentNetwork as Network>::TransactionID::from(Field::from_u64(1));
+        consensus.record_ready_fee(transaction_id, u64::MAX);
+        let (queue_depth, fees) = consensus.memory_pool_priority_fees();
+        assert_eq!(queue_depth, transactions.len());
+        assert!(!fees.contains(&u64::MAX));
+
+        std::fs::remove_dir_all(storage_path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_displacement_requires_verification() {
+        let rng = &mut TestRng::default();
+        let transaction = snarkvm::ledger::ledger_test_helpers::sample_execution_transaction_with_fee(false, rng);
+        let [low, mid, high, forged, cheap] =
+            [10, 20, 30, 1_000_000, 0].map(|fee| sample_transaction_with_priority_fee(&transaction, fee));
+        // Fail the basic checks of the forged transaction, as well as of the cheap one, which is never verified.
+        let committee = snarkvm::ledger::committee::test_helpers::sample_committee(rng);
+        let ledger = MockLedgerService::new_at_height(committee, 1);
+        ledger.invalidate_transaction(forged.id());
+        ledger.invalidate_transaction(cheap.id());
+        // Hold the transactions in a queue of two executions, as the BFT is not synced.
+        let (mut consensus, storage_path) = sample_consensus(ledger, rng);
+        consensus.memory_budget = Arc::new(CappedBudget { max_queued: 2 });
+        consensus.is_synced_override.set(watch::channel(false).1).unwrap();
+        let queued = |consensus: &Consensus<CurrentNetwork>| {
+            consensus.transactions_queue.lock().executions.iter().map(|(id, _)| *id).collect::<Vec<_>>()
+        };
+
+        // Fill the queue.
+        consensus.add_unconfirmed_transaction(low.clone(), None).await.unwrap();
+        consensus.add_unconfirmed_transaction(mid.clone(), None).await.unwrap();
+        assert_eq!(queued(&consensus), vec![mid.id(), low.id()]);
+
+        // Ensure a lower priority fee is rejected, without being verified.
+        let error = consensus.add_unconfirmed_transaction(cheap.clone(), None).await.unwrap_err();
+        assert!(error.to_string().contains("the queue is full of higher priority fees"), "{error}");
+
+        // Ensure a forged priority fee fails verification, and does not displace any queued transaction.
+        let error = consensus.add_unconfirmed_transaction(forged.clone(), None).await.unwrap_err();
+        assert!(error.to_string().contains("to the full memory pool"), "{error}");
+        assert_eq!(queued(&consensus), vec![mid.id(), low.id()]);
+        // Ensure the forged transaction was forgotten, so that it is reconsidered if it is received again.
+        assert!(!consensus.seen_transactions.lock().contains(&forged.id()));
+
+        // Ensure a verified higher priority fee displaces the lowest one, and is drained first.
+        consensus.add_unconfirmed_transaction(high.clone(), None).await.unwrap();
+        assert_eq!(queued(&consensus), vec![high.id(), mid.id()]);
+
+        std::fs::remove_dir_all(storage_path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_load_mempool_drops_confirmed_transactions() {
+        let rng = &mut TestRng::default();
+        let transaction = snarkvm::ledger::ledger_test_helpers::sample_execution_transaction_with_fee(false, rng);
+        let [pending, confirmed] = [10, 20].map(|fee| sample_transaction_with_priority_fee(&transaction, fee));
+        let committee = snarkvm::ledger::committee::test_helpers::sample_committee(rng);
+        let queued = |consensus: &Consensus<CurrentNetwork>| {
+            consensus.transactions_queue.lock().executions.iter().map(|(id, _)| *id).collect::<Vec<_>>()
+        };
+
+        // Save the memory pool of a node holding both transactions, as the BFT is not synced.
+        let (consensus, storage_path) = sample_consensus(MockLedgerService::new_at_height(committee.clone(), 1), rng);
+        consensus.is_synced_override.set(watch::channel(false).1).unwrap();
+        consensus.add_unconfirmed_transaction(pending.clone(), None).await.unwrap();
+        consensus.add_unconfirmed_transaction(confirmed.clone(), None).await.unwrap();
+        let path = storage_path.join("mempool.bin");
+        assert_eq!(consensus.save_mempool(&path).unwrap(), 2);
+
+        // Load the memory pool after a restart, while one of the transactions was confirmed in the meantime.
+        let ledger = MockLedgerService::new_at_height(committee, 1);
+        ledger.confirm_transaction(confirmed.id());
+        let (restarted, restarted_storage_path) = sample_consensus(ledger, rng);
+        restarted.is_synced_override.set(watch::channel(false).1).unwrap();
+        assert_eq!(restarted.load_mempool(&path).await.unwrap(), 1);
+
+        // Ensure the confirmed transaction was dropped on revalidation, and the pending one was queued again.
+        assert_eq!(queued(&restarted), vec![pending.id()]);
+
+        std::fs::remove_dir_all(storage_path).ok();
+        std::fs::remove_dir_all(restarted_storage_path).ok();
     }
 }
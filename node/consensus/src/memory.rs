@@ -0,0 +1,82 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// The approximate overhead in bytes of an entry in a hash map or LRU cache, beyond its key and value.
+const ENTRY_OVERHEAD_IN_BYTES: u64 = 32;
+
+/// The structures of consensus whose memory is accounted for.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MemoryPool {
+    /// The caches of the recently-seen unconfirmed transmissions.
+    SeenTransmissions,
+    /// The inbound queues of the unconfirmed transmissions.
+    TransmissionsQueue,
+}
+
+/// The accounting of the memory of the node, by which consensus sizes its inbound queues and seen caches.
+///
+/// The node implements this trait, so that consensus shares a memory budget with its other components.
+pub trait MemoryAccounting: Send + Sync {
+    /// Returns the capacity of the given structure, scaled down from its nominal capacity under memory pressure.
+    fn capacity(&self, pool: MemoryPool, nominal_capacity: usize) -> usize;
+
+    /// Records the estimated memory in bytes used by the given structure.
+    fn set_usage(&self, pool: MemoryPool, bytes: u64);
+}
+
+/// Returns the estimated memory in bytes of the given number of entries of a hash map or LRU cache.
+pub(crate) const fn estimate_entries<K, V>(num_entries: usize) -> u64 {
+    num_entries as u64 * (std::mem::size_of::<K>() as u64 + std::mem::size_of::<V>() as u64 + ENTRY_OVERHEAD_IN_BYTES)
+}
+
+/// The running average of the serialized size of the entries of a structure, whose entries vary in size.
+#[derive(Debug, Default)]
+pub(crate) struct AverageSize(AtomicU64);
+
+impl AverageSize {
+    /// Records the serialized size of a new entry.
+    pub(crate) fn record(&self, size: usize) {
+        let _ = self.0.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |average| match average {
+            0 => Some(size as u64),
+            // Weigh the new entry by 1/8, so the average follows the recent entries.
+            _ => Some((average * 7 + size as u64) / 8),
+        });
+    }
+
+    /// Returns the estimated memory in bytes of the given number of entries.
+    pub(crate) fn estimate(&self, num_entries: usize) -> u64 {
+        num_entries as u64 * (self.0.load(Ordering::Relaxed) + ENTRY_OVERHEAD_IN_BYTES)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimates() {
+        assert_eq!(estimate_entries::<u64, ()>(10), 10 * (8 + ENTRY_OVERHEAD_IN_BYTES));
+
+        let average = AverageSize::default();
+        assert_eq!(average.estimate(10), 10 * ENTRY_OVERHEAD_IN_BYTES);
+        average.record(1_000);
+        assert_eq!(average.estimate(2), 2 * (1_000 + ENTRY_OVERHEAD_IN_BYTES));
+        // Ensure the average follows the recent entries.
+        (0..100).for_each(|_| average.record(100));
+        assert!(average.estimate(1) < 110 + ENTRY_OVERHEAD_IN_BYTES);
+    }
+}
@@ -0,0 +1,147 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{CAPACITY_FOR_DEPLOYMENTS, CAPACITY_FOR_EXECUTIONS, CAPACITY_FOR_SOLUTIONS};
+use snarkvm::{
+    ledger::{block::Transaction, puzzle::Solution},
+    prelude::{FromBytes, Network, ToBytes},
+};
+
+use anyhow::{Result, ensure};
+use std::{fs, path::Path};
+
+/// The version of the serialization of a saved memory pool.
+pub const MEMPOOL_FILE_VERSION: u8 = 1;
+
+/// The unconfirmed solutions and transactions of the inbound queues, saved across restarts.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct MempoolFile<N: Network> {
+    /// The queued solutions, oldest first.
+    pub solutions: Vec<Solution<N>>,
+    /// The queued deployments and executions.
+    pub transactions: Vec<Transaction<N>>,
+}
+
+impl<N: Network> MempoolFile<N> {
+    /// Writes the memory pool to the given file.
+    ///
+    /// The memory pool is written to a partial file first, so that a crash never leaves a truncated file behind.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let mut bytes = Vec::new();
+        MEMPOOL_FILE_VERSION.write_le(&mut bytes)?;
+        u32::try_from(self.solutions.len())?.write_le(&mut bytes)?;
+        for solution in &self.solutions {
+            solution.write_le(&mut bytes)?;
+        }
+        u32::try_from(self.transactions.len())?.write_le(&mut bytes)?;
+        for transaction in &self.transactions {
+            transaction.write_le(&mut bytes)?;
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let partial_path = path.with_extension("partial");
+        fs::write(&partial_path, bytes)?;
+        fs::rename(&partial_path, path)?;
+        Ok(())
+    }
+
+    /// Reads the memory pool from the given file.
+    ///
+    /// The file is rejected if it was written by another version, or if it holds more transmissions
+    /// than the inbound queues, so that a corrupt file never allocates beyond their capacity.
+    pub fn load(path: &Path) -> Result<Self> {
+        let bytes = fs::read(path)?;
+        let mut reader = &bytes[..];
+        // Ensure the memory pool was saved by this version.
+        let version = u8::read_le(&mut reader)?;
+        ensure!(version == MEMPOOL_FILE_VERSION, "Unsupported memory pool version {version} in '{}'", path.display());
+        // Read the solutions.
+        let num_solutions = u32::read_le(&mut reader)? as usize;
+        ensure!(num_solutions <= CAPACITY_FOR_SOLUTIONS, "The saved memory pool has too many solutions");
+        let solutions = (0..num_solutions).map(|_| Solution::read_le(&mut reader)).collect::<Result<_, _>>()?;
+        // Read the transactions.
+        let num_transactions = u32::read_le(&mut reader)? as usize;
+        ensure!(
+            num_transactions <= CAPACITY_FOR_DEPLOYMENTS + CAPACITY_FOR_EXECUTIONS,
+            "The saved memory pool has too many transactions"
+        );
+        let transactions =
+            (0..num_transactions).map(|_| Transaction::read_le(&mut reader)).collect::<Result<_, _>>()?;
+        // Ensure the whole file was read.
+        ensure!(reader.is_empty(), "The saved memory pool in '{}' has trailing bytes", path.display());
+        Ok(Self { solutions, transactions })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::{
+        console::account::{Address, PrivateKey},
+        ledger::{
+            ledger_test_helpers::{sample_deployment_transaction, sample_execution_transaction_with_fee},
+            puzzle::PartialSolution,
+        },
+        prelude::{Rng, TestRng},
+    };
+
+    use std::path::PathBuf;
+
+    type CurrentNetwork = snarkvm::prelude::MainnetV0;
+
+    /// Returns a unique path for a sample memory pool file.
+    fn sample_mempool_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("snarkos-mempool-{name}-{}.bin", rand::random::<u64>()))
+    }
+
+    /// Returns a sample solution.
+    fn sample_solution(rng: &mut TestRng) -> Solution<CurrentNetwork> {
+        let address = Address::try_from(PrivateKey::<CurrentNetwork>::new(rng).unwrap()).unwrap();
+        Solution::new(PartialSolution::new(rng.gen(), address, rng.gen()).unwrap(), rng.gen())
+    }
+
+    #[test]
+    fn test_mempool_file_roundtrip() {
+        let rng = &mut TestRng::default();
+        let path = sample_mempool_path("roundtrip");
+
+        // Save a memory pool with a mix of deployments, executions and solutions.
+        let mempool = MempoolFile {
+            solutions: (0..3).map(|_| sample_solution(rng)).collect(),
+            transactions: vec![
+                sample_deployment_transaction(false, rng),
+                sample_execution_transaction_with_fee(false, rng),
+                sample_deployment_transaction(true, rng),
+                sample_execution_transaction_with_fee(true, rng),
+            ],
+        };
+        mempool.save(&path).unwrap();
+        assert!(!path.with_extension("partial").exists());
+
+        // Ensure the memory pool is loaded in the order it was saved.
+        let loaded = MempoolFile::<CurrentNetwork>::load(&path).unwrap();
+        assert_eq!(loaded, mempool);
+        assert_eq!(loaded.transactions.iter().filter(|transaction| transaction.is_deploy()).count(), 2);
+
+        // Ensure a file of another version is rejected.
+        let mut bytes = fs::read(&path).unwrap();
+        bytes[0] = MEMPOOL_FILE_VERSION + 1;
+        fs::write(&path, bytes).unwrap();
+        assert!(MempoolFile::<CurrentNetwork>::load(&path).is_err());
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,346 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{
+    ledger::{block::Transaction, puzzle::Solution},
+    prelude::{Network, ProgramID},
+};
+
+use anyhow::{Result, anyhow, bail};
+use indexmap::IndexSet;
+use parking_lot::Mutex;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+};
+
+/// The decision of a mempool policy on an unconfirmed transmission.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Admission {
+    /// Admit the transmission into the memory pool.
+    Admit,
+    /// Reject the transmission, for the given reason.
+    Reject(String),
+    /// Skip the transmission for now, so that it is reconsidered if it is received again.
+    Defer,
+}
+
+/// The facts about the node a mempool policy may base its decisions on.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct PolicyContext {
+    /// The height of the latest block in the ledger.
+    pub latest_height: u32,
+    /// The number of unconfirmed transmissions in the workers.
+    pub num_unconfirmed_transmissions: usize,
+    /// The number of unconfirmed solutions in the workers.
+    pub num_unconfirmed_solutions: usize,
+    /// The number of unconfirmed transactions in the workers.
+    pub num_unconfirmed_transactions: usize,
+    /// The number of solutions in the inbound queue.
+    pub num_queued_solutions: usize,
+    /// The number of transactions in the inbound queue.
+    pub num_queued_transactions: usize,
+}
+
+/// A local policy deciding which unconfirmed transmissions a validator admits into its memory pool.
+///
+/// The policy is consulted for each new unconfirmed transmission, after the built-in checks
+/// (e.g. fee transactions and duplicates), and before the transmission is queued.
+/// It is given a snapshot of the node facts, and has no access to the node itself.
+pub trait MempoolPolicy<N: Network>: Send + Sync {
+    /// Returns the name of the policy, as reported in the rejection errors and metrics.
+    fn name(&self) -> &str;
+
+    /// Decides whether the given unconfirmed transaction is admitted into the memory pool.
+    fn admit_transaction(&self, _transaction: &Transaction<N>, _context: &PolicyContext) -> Admission {
+        Admission::Admit
+    }
+
+    /// Decides whether the given unconfirmed solution is admitted into the memory pool.
+    fn admit_solution(&self, _solution: &Solution<N>, _context: &PolicyContext) -> Admission {
+        Admission::Admit
+    }
+}
+
+/// The default mempool policy, which admits every transmission.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct DefaultMempoolPolicy;
+
+impl<N: Network> MempoolPolicy<N> for DefaultMempoolPolicy {
+    fn name(&self) -> &str {
+        "default"
+    }
+}
+
+/// A mempool policy rejecting the transactions whose fee is below a floor.
+#[derive(Copy, Clone, Debug)]
+pub struct FeeFloorPolicy {
+    /// The minimum fee in microcredits.
+    min_fee: u64,
+}
+
+impl FeeFloorPolicy {
+    /// Initializes a new fee floor policy, with the given minimum fee in microcredits.
+    pub const fn new(min_fee: u64) -> Self {
+        Self { min_fee }
+    }
+}
+
+impl<N: Network> MempoolPolicy<N> for FeeFloorPolicy {
+    fn name(&self) -> &str {
+        "fee-floor"
+    }
+
+    fn admit_transaction(&self, transaction: &Transaction<N>, _context: &PolicyContext) -> Admission {
+        match transaction.fee_amount() {
+            Ok(fee) if *fee >= self.min_fee => Admission::Admit,
+            Ok(fee) => Admission::Reject(format!(
+                "the fee of {} microcredits is below the floor of {} microcredits",
+                *fee, self.min_fee
+            )),
+            Err(error) => Admission::Reject(format!("the fee could not be determined - {error}")),
+        }
+    }
+}
+
+/// A mempool policy rejecting the transactions that deploy or execute a program outside an allowlist.
+#[derive(Clone, Debug)]
+pub struct ProgramAllowlistPolicy<N: Network> {
+    /// The allowed programs.
+    programs: IndexSet<ProgramID<N>>,
+}
+
+impl<N: Network> ProgramAllowlistPolicy<N> {
+    /// Initializes a new program allowlist policy, with the given allowed programs.
+    pub fn new(programs: impl IntoIterator<Item = ProgramID<N>>) -> Self {
+        Self { programs: programs.into_iter().collect() }
+    }
+}
+
+impl<N: Network> MempoolPolicy<N> for ProgramAllowlistPolicy<N> {
+    fn name(&self) -> &str {
+        "program-allowlist"
+    }
+
+    fn admit_transaction(&self, transaction: &Transaction<N>, _context: &PolicyContext) -> Admission {
+        // Collect the deployed program, or the programs of the executed transitions.
+        // Note: The fee transition is not part of the execution, so 'credits.aleo' need not be allowed to pay fees.
+        let mut program_ids = transaction.deployment().map(|deployment| deployment.program_id()).into_iter().chain(
+            transaction.execution().into_iter().flat_map(|execution| execution.transitions()).map(|t| t.program_id()),
+        );
+        match program_ids.find(|program_id| !self.programs.contains(*program_id)) {
+            Some(program_id) => Admission::Reject(format!("the program '{program_id}' is not in the allowlist")),
+            None => Admission::Admit,
+        }
+    }
+}
+
+/// Parses a mempool policy from the given specification:
+/// `default`, `fee-floor=<microcredits>`, or `program-allowlist=<program ID>,<program ID>,...`.
+pub fn parse_mempool_policy<N: Network>(specification: &str) -> Result<Arc<dyn MempoolPolicy<N>>> {
+    let (name, argument) = match specification.split_once('=') {
+        Some((name, argument)) => (name.trim(), Some(argument.trim())),
+        None => (specification.trim(), None),
+    };
+    match (name, argument) {
+        ("default", None) => Ok(Arc::new(DefaultMempoolPolicy)),
+        ("fee-floor", Some(min_fee)) => match min_fee.parse() {
+            Ok(min_fee) => Ok(Arc::new(FeeFloorPolicy::new(min_fee))),
+            Err(error) => bail!("Invalid fee floor '{min_fee}' in the mempool policy - {error}"),
+        },
+        ("program-allowlist", Some(programs)) => {
+            let programs = programs
+                .split(',')
+                .filter(|program_id| !program_id.trim().is_empty())
+                .map(|program_id| ProgramID::<N>::from_str(program_id.trim()))
+                .collect::<Result<IndexSet<_>>>()?;
+            if programs.is_empty() {
+                bail!("The program allowlist of the mempool policy is empty");
+            }
+            Ok(Arc::new(ProgramAllowlistPolicy::new(programs)))
+        }
+        _ => bail!(
+            "Invalid mempool policy '{specification}' (expected 'default', 'fee-floor=<microcredits>', \
+             or 'program-allowlist=<program IDs>')"
+        ),
+    }
+}
+
+/// Reads the specification of a mempool policy from the given file, without its blank lines and `#` comments.
+///
+/// Note: The specification is only read, and is expected to be parsed with [`parse_mempool_policy`].
+pub fn read_mempool_policy_file(path: &Path) -> Result<String> {
+    let contents = fs::read_to_string(path)
+        .map_err(|error| anyhow!("Unable to read the mempool policy file '{}' - {error}", path.display()))?;
+    let mut lines = contents.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#'));
+    match (lines.next(), lines.next()) {
+        (Some(specification), None) => Ok(specification.to_string()),
+        (None, _) => bail!("The mempool policy file '{}' is empty", path.display()),
+        (Some(_), Some(_)) => bail!("The mempool policy file '{}' has more than one policy", path.display()),
+    }
+}
+
+/// A file containing the specification of a mempool policy, which is re-read when the node reloads its configuration.
+pub struct MempoolPolicyFile {
+    /// The path to the file.
+    path: PathBuf,
+    /// The specification of the policy, as last read from the file.
+    specification: Mutex<String>,
+}
+
+impl MempoolPolicyFile {
+    /// Reads the mempool policy file at the given path, and returns it with its policy.
+    pub fn open<N: Network>(path: PathBuf) -> Result<(Self, Arc<dyn MempoolPolicy<N>>)> {
+        let specification = read_mempool_policy_file(&path)?;
+        let policy = parse_mempool_policy(&specification)?;
+        Ok((Self { path, specification: Mutex::new(specification) }, policy))
+    }
+
+    /// Returns the specification of the policy, as last read from the file.
+    pub fn specification(&self) -> String {
+        self.specification.lock().clone()
+    }
+
+    /// Re-reads the file, and returns the new policy with a description of the change, or `None` if it is unchanged.
+    ///
+    /// The new policy is parsed in full before the specification is updated, so that a malformed file
+    /// leaves the current policy active.
+    pub fn reload<N: Network>(&self) -> Result<Option<(Arc<dyn MempoolPolicy<N>>, String)>> {
+        let new_specification = read_mempool_policy_file(&self.path)?;
+        let policy = parse_mempool_policy(&new_specification)?;
+        let mut specification = self.specification.lock();
+        if *specification == new_specification {
+            return Ok(None);
+        }
+        let changes = format!("'{specification}' -> '{new_specification}'");
+        *specification = new_specification;
+        Ok(Some((policy, changes)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::{
+        ledger::ledger_test_helpers::{sample_deployment_transaction, sample_execution_transaction_with_fee},
+        prelude::TestRng,
+    };
+
+    type CurrentNetwork = snarkvm::prelude::MainnetV0;
+
+    /// Returns the programs of the given transaction.
+    fn programs(transaction: &Transaction<CurrentNetwork>) -> Vec<ProgramID<CurrentNetwork>> {
+        match transaction.deployment() {
+            Some(deployment) => vec![*deployment.program_id()],
+            None => transaction.execution().unwrap().transitions().map(|t| *t.program_id()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_fee_floor_policy() {
+        let rng = &mut TestRng::default();
+        let context = PolicyContext::default();
+        for transaction in [sample_execution_transaction_with_fee(false, rng), sample_deployment_transaction(true, rng)]
+        {
+            let fee = *transaction.fee_amount().unwrap();
+
+            // Ensure the transaction is admitted at the floor, and rejected above it.
+            let policy = parse_mempool_policy::<CurrentNetwork>(&format!("fee-floor={fee}")).unwrap();
+            assert_eq!(policy.name(), "fee-floor");
+            assert_eq!(policy.admit_transaction(&transaction, &context), Admission::Admit);
+            let policy = parse_mempool_policy::<CurrentNetwork>(&format!("fee-floor={}", fee + 1)).unwrap();
+            let Admission::Reject(reason) = policy.admit_transaction(&transaction, &context) else {
+                panic!("The transaction below the fee floor was admitted");
+            };
+            assert!(reason.contains(&format!("{fee} microcredits")));
+        }
+    }
+
+    #[test]
+    fn test_program_allowlist_policy() {
+        let rng = &mut TestRng::default();
+        let context = PolicyContext::default();
+        for transaction in [sample_execution_transaction_with_fee(true, rng), sample_deployment_transaction(false, rng)]
+        {
+            let programs = programs(&transaction);
+            let allowlist = programs.iter().map(ToString::to_string).collect::<Vec<_>>().join(",");
+
+            // Ensure the transaction is admitted if its programs are allowed.
+            let policy = parse_mempool_policy::<CurrentNetwork>(&format!("program-allowlist={allowlist}")).unwrap();
+            assert_eq!(policy.admit_transaction(&transaction, &context), Admission::Admit);
+
+            // Ensure the transaction is rejected otherwise, naming the program.
+            let policy = parse_mempool_policy::<CurrentNetwork>("program-allowlist=unrelated.aleo").unwrap();
+            let Admission::Reject(reason) = policy.admit_transaction(&transaction, &context) else {
+                panic!("The transaction outside the allowlist was admitted");
+            };
+            assert!(reason.contains(&programs[0].to_string()));
+        }
+    }
+
+    #[test]
+    fn test_parse_mempool_policy() {
+        assert_eq!(parse_mempool_policy::<CurrentNetwork>("default").unwrap().name(), "default");
+        assert_eq!(parse_mempool_policy::<CurrentNetwork>("fee-floor=100").unwrap().name(), "fee-floor");
+        let policy = parse_mempool_policy::<CurrentNetwork>("program-allowlist=credits.aleo, token.aleo").unwrap();
+        assert_eq!(policy.name(), "program-allowlist");
+
+        // Ensure the invalid specifications are rejected.
+        for specification in ["", "fee-floor", "fee-floor=-1", "program-allowlist=", "program-allowlist=x", "unknown"] {
+            assert!(parse_mempool_policy::<CurrentNetwork>(specification).is_err(), "{specification}");
+        }
+    }
+
+    #[test]
+    fn test_read_mempool_policy_file() {
+        let path = std::env::temp_dir().join(format!("snarkos-mempool-policy-{}.txt", rand::random::<u64>()));
+
+        // Ensure the comments and the blank lines are skipped.
+        fs::write(&path, "# The local mempool policy.\n\n  fee-floor=100  \n").unwrap();
+        assert_eq!(read_mempool_policy_file(&path).unwrap(), "fee-floor=100");
+
+        // Ensure the files without exactly one policy are rejected.
+        for contents in ["", "# No policy.\n", "default\nfee-floor=100\n"] {
+            fs::write(&path, contents).unwrap();
+            assert!(read_mempool_policy_file(&path).is_err(), "{contents}");
+        }
+        fs::remove_file(&path).unwrap();
+        assert!(read_mempool_policy_file(&path).is_err());
+    }
+
+    #[test]
+    fn test_reload_mempool_policy_file() {
+        let path = std::env::temp_dir().join(format!("snarkos-mempool-policy-{}.txt", rand::random::<u64>()));
+        fs::write(&path, "fee-floor=100\n").unwrap();
+        let (policy_file, policy) = MempoolPolicyFile::open::<CurrentNetwork>(path.clone()).unwrap();
+        assert_eq!(policy.name(), "fee-floor");
+        // Ensure an unchanged file does not replace the policy.
+        assert!(policy_file.reload::<CurrentNetwork>().unwrap().is_none());
+
+        // Edit the file, and ensure the new policy is returned with the change.
+        fs::write(&path, "program-allowlist=credits.aleo\n").unwrap();
+        let (policy, changes) = policy_file.reload::<CurrentNetwork>().unwrap().unwrap();
+        assert_eq!(policy.name(), "program-allowlist");
+        assert_eq!(changes, "'fee-floor=100' -> 'program-allowlist=credits.aleo'");
+
+        // Ensure a malformed file fails to reload, and keeps the current specification.
+        fs::write(&path, "fee-floor=many\n").unwrap();
+        assert!(policy_file.reload::<CurrentNetwork>().is_err());
+        assert_eq!(policy_file.specification(), "program-allowlist=credits.aleo");
+        fs::remove_file(&path).unwrap();
+    }
+}
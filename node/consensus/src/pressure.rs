@@ -0,0 +1,237 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    sync::atomic::{AtomicU32, AtomicU64, AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+/// The occupancy of an inbound queue in permille of its capacity, at or above which the queue is under pressure.
+pub const MEMPOOL_PRESSURE_HIGH_WATERMARK: u32 = 900;
+/// The minimum duration in seconds that the clients of a queue under pressure are asked to wait before retrying.
+pub const MIN_PRESSURE_RETRY_AFTER_IN_SECS: u64 = 1;
+/// The maximum duration in seconds that the clients of a queue under pressure are asked to wait before retrying.
+pub const MAX_PRESSURE_RETRY_AFTER_IN_SECS: u64 = 60;
+/// The window in seconds over which the drain rate of an inbound queue is measured.
+const DRAIN_RATE_WINDOW_IN_SECS: u64 = 60;
+
+/// The pressure on the inbound queues of the memory pool.
+pub struct MempoolPressure {
+    /// The pressure on the solutions queue.
+    solutions: QueuePressure,
+    /// The pressure on the transactions queue, i.e. on the deployments and executions combined.
+    transactions: QueuePressure,
+}
+
+impl Default for MempoolPressure {
+    fn default() -> Self {
+        Self { solutions: QueuePressure::new("solutions"), transactions: QueuePressure::new("transactions") }
+    }
+}
+
+impl MempoolPressure {
+    /// Returns the pressure on the solutions queue.
+    pub const fn solutions(&self) -> &QueuePressure {
+        &self.solutions
+    }
+
+    /// Returns the pressure on the transactions queue.
+    pub const fn transactions(&self) -> &QueuePressure {
+        &self.transactions
+    }
+
+    /// Returns the occupancy of the fullest inbound queue, in permille of its capacity.
+    pub fn occupancy(&self) -> u32 {
+        self.solutions.occupancy().max(self.transactions.occupancy())
+    }
+}
+
+/// The pressure on an inbound queue, derived from its occupancy and its recent drain rate.
+///
+/// The occupancy is read without locking, so that a request can be shed before doing any work.
+pub struct QueuePressure {
+    /// The name of the queue.
+    name: &'static str,
+    /// The occupancy of the queue, in permille of its capacity.
+    occupancy: AtomicU32,
+    /// The number of transmissions to drain for the queue to fall below the high watermark.
+    num_excess: AtomicUsize,
+    /// The recent drains of the queue, with their number of transmissions, oldest first.
+    drains: Mutex<VecDeque<(Instant, usize)>>,
+    /// The number of requests shed while the queue was under pressure.
+    num_shed: AtomicU64,
+}
+
+impl QueuePressure {
+    /// Initializes the pressure on the queue with the given name.
+    fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            occupancy: Default::default(),
+            num_excess: Default::default(),
+            drains: Default::default(),
+            num_shed: Default::default(),
+        }
+    }
+
+    /// Returns the occupancy of the queue, in permille of its capacity.
+    pub fn occupancy(&self) -> u32 {
+        self.occupancy.load(Ordering::Relaxed)
+    }
+
+    /// Returns `true` if the occupancy of the queue is at or above the high watermark.
+    pub fn is_high(&self) -> bool {
+        self.occupancy() >= MEMPOOL_PRESSURE_HIGH_WATERMARK
+    }
+
+    /// Returns the number of requests shed while the queue was under pressure.
+    pub fn num_shed(&self) -> u64 {
+        self.num_shed.load(Ordering::Relaxed)
+    }
+
+    /// Records a request shed while the queue was under pressure.
+    pub fn record_shed(&self) {
+        self.num_shed.fetch_add(1, Ordering::Relaxed);
+        #[cfg(feature = "metrics")]
+        metrics::increment_counter_label(metrics::consensus::SHED_BROADCASTS, "queue", self.name.to_string());
+    }
+
+    /// Updates the occupancy of the queue, given its length and capacity.
+    pub fn update(&self, len: usize, capacity: usize) {
+        let capacity = capacity.max(1);
+        let occupancy = (len.saturating_mul(1000) / capacity).min(1000) as u32;
+        // The queue falls below the high watermark once its length is under the watermark length.
+        let watermark_len = (capacity * MEMPOOL_PRESSURE_HIGH_WATERMARK as usize).div_ceil(1000);
+        let previous = self.occupancy.swap(occupancy, Ordering::Relaxed);
+        match (previous >= MEMPOOL_PRESSURE_HIGH_WATERMARK, occupancy >= MEMPOOL_PRESSURE_HIGH_WATERMARK) {
+            (false, true) => debug!("The inbound {} queue is under pressure ({len} / {capacity})", self.name),
+            (true, false) => debug!("The inbound {} queue is no longer under pressure", self.name),
+            _ => (),
+        }
+        self.num_excess.store((len + 1).saturating_sub(watermark_len), Ordering::Relaxed);
+        #[cfg(feature = "metrics")]
+        metrics::gauge_label(
+            metrics::consensus::MEMPOOL_PRESSURE,
+            "queue",
+            self.name.to_string(),
+            occupancy as f64 / 1000.0,
+        );
+    }
+
+    /// Records the given number of transmissions drained from the queue, either sent to the BFT or expired.
+    pub fn record_drained(&self, num_drained: usize, now: Instant) {
+        if num_drained == 0 {
+            return;
+        }
+        let mut drains = self.drains.lock();
+        drains.push_back((now, num_drained));
+        prune_drains(&mut drains, now);
+    }
+
+    /// Returns the number of transmissions drained from the queue per second, over the recent window.
+    pub fn drain_rate(&self, now: Instant) -> f64 {
+        let mut drains = self.drains.lock();
+        prune_drains(&mut drains, now);
+        drains.iter().map(|(_, num_drained)| *num_drained).sum::<usize>() as f64 / DRAIN_RATE_WINDOW_IN_SECS as f64
+    }
+
+    /// Returns the duration that clients should wait before retrying, i.e. the time for the queue to drain
+    /// below the high watermark at its recent drain rate, within `[MIN, MAX]_PRESSURE_RETRY_AFTER_IN_SECS`.
+    pub fn retry_after(&self, now: Instant) -> Duration {
+        let num_excess = self.num_excess.load(Ordering::Relaxed);
+        let drain_rate = self.drain_rate(now);
+        let secs = match drain_rate > 0.0 {
+            true => (num_excess as f64 / drain_rate).ceil() as u64,
+            false => MAX_PRESSURE_RETRY_AFTER_IN_SECS,
+        };
+        Duration::from_secs(secs.clamp(MIN_PRESSURE_RETRY_AFTER_IN_SECS, MAX_PRESSURE_RETRY_AFTER_IN_SECS))
+    }
+
+    /// Returns the status of the pressure on the queue.
+    pub fn status(&self, now: Instant) -> QueuePressureStatus {
+        QueuePressureStatus {
+            occupancy: self.occupancy() as f64 / 1000.0,
+            is_high: self.is_high(),
+            drain_rate: self.drain_rate(now),
+            retry_after_secs: self.is_high().then(|| self.retry_after(now).as_secs()),
+            num_shed: self.num_shed(),
+        }
+    }
+}
+
+/// Removes the drains that fell out of the drain rate window.
+fn prune_drains(drains: &mut VecDeque<(Instant, usize)>, now: Instant) {
+    let window = Duration::from_secs(DRAIN_RATE_WINDOW_IN_SECS);
+    while drains.front().is_some_and(|(time, _)| now.saturating_duration_since(*time) > window) {
+        drains.pop_front();
+    }
+}
+
+/// The status of the pressure on an inbound queue.
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct QueuePressureStatus {
+    /// The occupancy of the queue, as a fraction of its capacity.
+    pub occupancy: f64,
+    /// Whether the occupancy is at or above the high watermark.
+    pub is_high: bool,
+    /// The number of transmissions drained per second, over the recent window.
+    pub drain_rate: f64,
+    /// The duration in seconds that clients are asked to wait before retrying, if the queue is under pressure.
+    pub retry_after_secs: Option<u64>,
+    /// The number of requests shed while the queue was under pressure.
+    pub num_shed: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_queue_pressure() {
+        let pressure = MempoolPressure::default();
+        let queue = pressure.transactions();
+        let start = Instant::now();
+        assert!(!queue.is_high());
+
+        // Saturate the queue.
+        queue.update(1000, 1000);
+        assert_eq!(queue.occupancy(), 1000);
+        assert_eq!(pressure.occupancy(), 1000);
+        assert!(queue.is_high());
+        // Without any recent drain, the clients are asked to wait for the maximum duration.
+        assert_eq!(queue.retry_after(start), Duration::from_secs(MAX_PRESSURE_RETRY_AFTER_IN_SECS));
+
+        // Drain 600 transmissions over the window, i.e. 10 per second, and ensure the 101 excess ones
+        // are estimated to drain in 11 seconds.
+        queue.record_drained(600, start);
+        assert_eq!(queue.drain_rate(start), 10.0);
+        assert_eq!(queue.retry_after(start), Duration::from_secs(11));
+        // Ensure the drains fall out of the window.
+        let later = start + Duration::from_secs(DRAIN_RATE_WINDOW_IN_SECS + 1);
+        assert_eq!(queue.drain_rate(later), 0.0);
+
+        // Ensure the queue recovers once it drains below the high watermark.
+        queue.update(900, 1000);
+        assert!(queue.is_high());
+        queue.update(899, 1000);
+        assert!(!queue.is_high());
+        assert_eq!(queue.status(start).retry_after_secs, None);
+        // Ensure the pressure of the other queue is independent.
+        assert!(!pressure.solutions().is_high());
+    }
+}
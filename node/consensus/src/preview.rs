@@ -0,0 +1,118 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{
+    ledger::block::{Authority, Block, ConfirmedTransaction, Ratify},
+    prelude::{Network, ToBytes},
+};
+
+use serde::Serialize;
+
+/// The maximum number of aborted transactions that are checked again to report the reason they were aborted.
+pub const MAX_PREVIEW_ABORTED_CHECKS: usize = 16;
+
+/// A transaction that the speculation of the next block would abort.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(bound = "")]
+pub struct AbortedTransaction<N: Network> {
+    /// The transaction ID.
+    pub id: N::TransactionID,
+    /// The reason the transaction would be aborted, if it was checked again.
+    pub reason: Option<String>,
+}
+
+/// A summary of the block that the next commit would produce, from a speculation that is never written to the ledger.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(bound = "")]
+pub struct BlockPreview<N: Network> {
+    /// The height of the previewed block.
+    pub height: u32,
+    /// The round of the leader certificate anchoring the previewed block.
+    pub round: u64,
+    /// The number of certificates in the committed subdag.
+    pub num_certificates: usize,
+    /// The number of deduped transmissions handed to the speculation.
+    pub num_transmissions: usize,
+    /// The number of accepted deployments.
+    pub num_accepted_deploys: usize,
+    /// The number of accepted executions.
+    pub num_accepted_executes: usize,
+    /// The number of rejected deployments.
+    pub num_rejected_deploys: usize,
+    /// The number of rejected executions.
+    pub num_rejected_executes: usize,
+    /// The number of solutions.
+    pub num_solutions: usize,
+    /// The number of aborted solutions.
+    pub num_aborted_solutions: usize,
+    /// The sum of the fees of the confirmed transactions, in microcredits.
+    pub total_fees: u64,
+    /// The block reward, in microcredits.
+    pub block_reward: u64,
+    /// The puzzle reward, in microcredits.
+    pub puzzle_reward: u64,
+    /// The aborted transactions.
+    pub aborted_transactions: Vec<AbortedTransaction<N>>,
+    /// The size of the previewed block in bytes.
+    pub size_in_bytes: usize,
+}
+
+impl<N: Network> BlockPreview<N> {
+    /// Returns the preview of the given speculated block, with the given aborted transactions.
+    pub fn new(block: &Block<N>, num_transmissions: usize, aborted_transactions: Vec<AbortedTransaction<N>>) -> Self {
+        // Retrieve the number of certificates in the subdag.
+        let num_certificates = match block.authority() {
+            Authority::Quorum(subdag) => subdag.values().map(|c| c.len()).sum(),
+            Authority::Beacon(_) => 0,
+        };
+        // Count the confirmed transactions by type.
+        let transactions = block.transactions();
+        let count =
+            |predicate: fn(&ConfirmedTransaction<N>) -> bool| transactions.iter().filter(|tx| predicate(tx)).count();
+        // Sum the fees of the confirmed transactions.
+        // Note: A rejected transaction is confirmed as its fee transaction, so its fee is included.
+        let total_fees = transactions
+            .iter()
+            .filter_map(|confirmed| confirmed.transaction().fee_amount().ok())
+            .fold(0u64, |total, fee| total.saturating_add(*fee));
+        // Retrieve the rewards from the ratifications.
+        let (mut block_reward, mut puzzle_reward) = (0, 0);
+        for ratify in block.ratifications().iter() {
+            match ratify {
+                Ratify::BlockReward(amount) => block_reward = *amount,
+                Ratify::PuzzleReward(amount) => puzzle_reward = *amount,
+                _ => (),
+            }
+        }
+
+        Self {
+            height: block.height(),
+            round: block.round(),
+            num_certificates,
+            num_transmissions,
+            num_accepted_deploys: count(|tx| tx.is_accepted_deploy()),
+            num_accepted_executes: count(|tx| tx.is_accepted_execute()),
+            num_rejected_deploys: count(|tx| tx.is_rejected_deploy()),
+            num_rejected_executes: count(|tx| tx.is_rejected_execute()),
+            num_solutions: block.solutions().len(),
+            num_aborted_solutions: block.aborted_solution_ids().len(),
+            total_fees,
+            block_reward,
+            puzzle_reward,
+            aborted_transactions,
+            size_in_bytes: block.to_bytes_le().map(|bytes| bytes.len()).unwrap_or_default(),
+        }
+    }
+}
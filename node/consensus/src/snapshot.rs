@@ -0,0 +1,191 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::inbound::Queued;
+use snarkvm::{ledger::narwhal::TransmissionID, prelude::Network};
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, hash::Hash, time::Instant};
+
+/// The maximum number of full transmissions returned by a snapshot of the memory pool.
+pub const MAX_MEMORY_POOL_TRANSMISSIONS: usize = 100;
+
+/// The kind of an unconfirmed transmission.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransmissionKind {
+    Ratification,
+    Solution,
+    Transaction,
+}
+
+impl TransmissionKind {
+    /// Returns the kind of the given transmission ID.
+    pub const fn of<N: Network>(id: &TransmissionID<N>) -> Self {
+        match id {
+            TransmissionID::Ratification => Self::Ratification,
+            TransmissionID::Solution(..) => Self::Solution,
+            TransmissionID::Transaction(..) => Self::Transaction,
+        }
+    }
+}
+
+/// The stage of an unconfirmed transmission in the memory pool.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MemoryPoolStage {
+    /// The transmission is in the inbound queues, waiting to be sent to the BFT.
+    Inbound,
+    /// The transmission is in the ready queue of a worker, waiting to be proposed.
+    Ready,
+}
+
+/// The summary of an unconfirmed transmission, without its contents.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct TransmissionSummary {
+    /// The ID of the solution or transaction.
+    pub id: String,
+    /// The kind of the transmission.
+    pub kind: TransmissionKind,
+    /// The stage of the transmission in the memory pool.
+    pub stage: MemoryPoolStage,
+    /// The size of the serialized transmission in bytes, if it is known without serializing it.
+    pub size_in_bytes: Option<usize>,
+    /// The time in seconds since the transmission entered its stage.
+    pub age_in_secs: u64,
+}
+
+impl TransmissionSummary {
+    /// Returns the summary of a transmission in the inbound queues.
+    pub(crate) fn inbound<T>(id: String, kind: TransmissionKind, queued: &Queued<T>, now: Instant) -> Self {
+        Self {
+            id,
+            kind,
+            stage: MemoryPoolStage::Inbound,
+            size_in_bytes: Some(queued.size_in_bytes),
+            age_in_secs: now.saturating_duration_since(queued.queued_at).as_secs(),
+        }
+    }
+
+    /// Returns the summary of a transmission in a ready queue, inserted at the given timestamp.
+    pub(crate) fn ready<N: Network>(
+        id: &TransmissionID<N>,
+        size_in_bytes: Option<usize>,
+        timestamp: i64,
+        now: i64,
+    ) -> Self {
+        Self {
+            id: match id {
+                TransmissionID::Ratification => "ratification".to_string(),
+                TransmissionID::Solution(solution_id, _) => solution_id.to_string(),
+                TransmissionID::Transaction(transaction_id, _) => transaction_id.to_string(),
+            },
+            kind: TransmissionKind::of(id),
+            stage: MemoryPoolStage::Ready,
+            size_in_bytes,
+            age_in_secs: u64::try_from(now.saturating_sub(timestamp)).unwrap_or_default(),
+        }
+    }
+}
+
+/// Merges the summaries of the inbound queues with the summaries of the ready queues, taken in that order.
+///
+/// A transmission sent from the inbound queues to a ready queue in between is listed once, as ready.
+pub(crate) fn merge_summaries(
+    inbound: Vec<TransmissionSummary>,
+    ready: Vec<TransmissionSummary>,
+) -> Vec<TransmissionSummary> {
+    let ready_ids = ready.iter().map(|summary| summary.id.clone()).collect::<HashSet<_>>();
+    ready.into_iter().chain(inbound.into_iter().filter(|summary| !ready_ids.contains(&summary.id))).collect()
+}
+
+/// Merges up to `limit` transmissions of the inbound queues with the transmissions of the ready queues,
+/// taken in that order, listing a transmission found in both once.
+pub(crate) fn merge_transmissions<K: Hash + Eq, V>(
+    inbound: IndexMap<K, V>,
+    mut ready: IndexMap<K, V>,
+    limit: usize,
+) -> IndexMap<K, V> {
+    ready.truncate(limit);
+    for (id, transmission) in inbound {
+        if ready.len() >= limit {
+            break;
+        }
+        ready.entry(id).or_insert(transmission);
+    }
+    ready
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a summary of the given transmission in the given stage.
+    fn sample_summary(id: &str, stage: MemoryPoolStage) -> TransmissionSummary {
+        TransmissionSummary {
+            id: id.to_string(),
+            kind: TransmissionKind::Transaction,
+            stage,
+            size_in_bytes: Some(100),
+            age_in_secs: 0,
+        }
+    }
+
+    #[test]
+    fn test_merge_summaries() {
+        let inbound =
+            vec![sample_summary("a", MemoryPoolStage::Inbound), sample_summary("b", MemoryPoolStage::Inbound)];
+        let ready = vec![sample_summary("b", MemoryPoolStage::Ready), sample_summary("c", MemoryPoolStage::Ready)];
+
+        // Ensure a transmission that moved to a ready queue in between the snapshots is listed once, as ready.
+        let merged = merge_summaries(inbound, ready);
+        let merged = merged.iter().map(|summary| (summary.id.as_str(), summary.stage)).collect::<Vec<_>>();
+        assert_eq!(merged, [
+            ("b", MemoryPoolStage::Ready),
+            ("c", MemoryPoolStage::Ready),
+            ("a", MemoryPoolStage::Inbound)
+        ]);
+    }
+
+    #[test]
+    fn test_merge_transmissions() {
+        let inbound = IndexMap::from([(1, "inbound"), (2, "inbound"), (3, "inbound")]);
+        let ready = IndexMap::from([(2, "ready"), (4, "ready")]);
+
+        // Ensure a transmission found in both is listed once, as ready.
+        let merged = merge_transmissions(inbound.clone(), ready.clone(), 10);
+        assert_eq!(merged, IndexMap::from([(2, "ready"), (4, "ready"), (1, "inbound"), (3, "inbound")]));
+
+        // Ensure the merged transmissions are capped.
+        assert_eq!(merge_transmissions(inbound.clone(), ready.clone(), 3).len(), 3);
+        assert_eq!(merge_transmissions(inbound, ready, 1), IndexMap::from([(2, "ready")]));
+    }
+
+    #[test]
+    fn test_transmission_kind() {
+        type CurrentNetwork = snarkvm::prelude::MainnetV0;
+
+        let id = TransmissionID::<CurrentNetwork>::Ratification;
+        assert_eq!(TransmissionKind::of(&id), TransmissionKind::Ratification);
+        let summary = TransmissionSummary::ready(&id, Some(0), 10, 25);
+        assert_eq!(
+            (summary.id.as_str(), summary.stage, summary.age_in_secs),
+            ("ratification", MemoryPoolStage::Ready, 15)
+        );
+        // Ensure a timestamp from the future does not underflow the age.
+        assert_eq!(TransmissionSummary::ready(&id, None, 30, 25).age_in_secs, 0);
+    }
+}
@@ -0,0 +1,43 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{future::Future, pin::Pin};
+
+/// The future of a task spawned by consensus.
+pub type TaskFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// The factory of a task spawned by consensus, which is invoked again for each restart of the task.
+pub type TaskFactory = Box<dyn Fn() -> TaskFuture + Send + Sync>;
+
+/// The kind of a task spawned by consensus.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TaskKind {
+    /// The task is not critical: it may run to completion, and a panic is only logged.
+    Auxiliary,
+    /// The task is critical, and is restarted up to the given number of times before the node is shut down.
+    Critical { max_restarts: u32 },
+}
+
+/// The spawner of the long-running tasks of consensus.
+///
+/// Consensus does not supervise its tasks itself: the node implements this trait,
+/// so that the tasks are supervised alongside the tasks of its other components.
+pub trait TaskSpawner: Send + Sync {
+    /// Spawns a task with the given name and kind, from the given factory.
+    fn spawn_task(&self, name: &str, kind: TaskKind, factory: TaskFactory);
+
+    /// Aborts the spawned tasks, and waits for them to terminate.
+    fn shut_down(&self) -> TaskFuture;
+}
@@ -0,0 +1,298 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use metrics_exporter_prometheus::PrometheusHandle;
+use serde_json::{Map, Value};
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use time::OffsetDateTime;
+
+/// The default interval in seconds between the metrics snapshots written to the metrics file.
+pub const DEFAULT_METRICS_FILE_INTERVAL_IN_SECS: u64 = 10;
+/// The default maximum size in bytes of the metrics file, before it is rotated.
+pub const DEFAULT_MAX_METRICS_FILE_SIZE: u64 = 16 * 1024 * 1024;
+/// The default maximum number of metrics files kept, including the current one.
+pub const DEFAULT_MAX_METRICS_FILES: usize = 5;
+
+/// The configuration of the metrics file exporter.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MetricsFileConfig {
+    /// The path to the metrics file.
+    pub path: PathBuf,
+    /// The interval between the snapshots.
+    pub interval: Duration,
+    /// The maximum size in bytes of the metrics file, before it is rotated.
+    pub max_file_size: u64,
+    /// The maximum number of metrics files kept, including the current one.
+    pub max_files: usize,
+}
+
+impl MetricsFileConfig {
+    /// Initializes a new configuration for the given path, with the default interval and rotation caps.
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            interval: Duration::from_secs(DEFAULT_METRICS_FILE_INTERVAL_IN_SECS),
+            max_file_size: DEFAULT_MAX_METRICS_FILE_SIZE,
+            max_files: DEFAULT_MAX_METRICS_FILES,
+        }
+    }
+}
+
+/// Spawns the metrics file exporter, which periodically appends a snapshot of the metrics to the metrics file.
+///
+/// Each snapshot is a single line of JSON with a UNIX `timestamp` and the `metrics`, keyed by their name and labels.
+/// Once the metrics file would exceed its maximum size, it is rotated to `<path>.1`, `<path>.1` to `<path>.2`,
+/// and so on, and the oldest file beyond the maximum number of files is removed. The snapshots are taken and
+/// written on a dedicated thread, so the exporter never blocks the node; if the disk is full or the file is
+/// unwritable, the failure is logged once, and the exporter keeps trying at every interval.
+pub fn spawn_metrics_file_exporter(handle: PrometheusHandle, config: MetricsFileConfig) -> io::Result<()> {
+    let mut exporter = MetricsFileExporter::new(config);
+    std::thread::Builder::new().name("metrics-file".to_string()).spawn(move || {
+        loop {
+            std::thread::sleep(exporter.config.interval);
+            let snapshot = snapshot_line(&handle.render(), OffsetDateTime::now_utc().unix_timestamp());
+            exporter.write_snapshot(&snapshot);
+        }
+    })?;
+    Ok(())
+}
+
+/// Returns a snapshot of the given metrics, in the Prometheus text format, as a line of JSON.
+fn snapshot_line(rendered: &str, timestamp: i64) -> String {
+    let mut metrics = Map::new();
+    for line in rendered.lines().map(str::trim) {
+        // Skip the comments and the empty lines.
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        // Each sample is the name of the series, followed by its value.
+        if let Some((series, value)) = line.rsplit_once(' ') {
+            if let Ok(value) = value.parse::<f64>() {
+                // Note: A value that is not finite (e.g. the quantiles of an empty summary) becomes a `null`.
+                metrics.insert(series.to_string(), Value::from(value));
+            }
+        }
+    }
+
+    let mut snapshot = Map::new();
+    snapshot.insert("timestamp".to_string(), Value::from(timestamp));
+    snapshot.insert("metrics".to_string(), Value::Object(metrics));
+    let mut line = Value::Object(snapshot).to_string();
+    line.push('\n');
+    line
+}
+
+/// The writer of the metrics file, which runs on a dedicated thread.
+struct MetricsFileExporter {
+    /// The configuration of the exporter.
+    config: MetricsFileConfig,
+    /// The metrics file, if it is open.
+    file: Option<File>,
+    /// The size in bytes of the metrics file.
+    size: u64,
+    /// Whether the last write failed, so that failures are only logged once.
+    is_failing: bool,
+}
+
+impl MetricsFileExporter {
+    /// Initializes a new writer for the metrics file.
+    fn new(config: MetricsFileConfig) -> Self {
+        Self { config, file: None, size: 0, is_failing: false }
+    }
+
+    /// Appends the given snapshot to the metrics file, logging the first failure and the recovery.
+    fn write_snapshot(&mut self, snapshot: &str) {
+        match self.try_write_snapshot(snapshot) {
+            Ok(()) => {
+                if self.is_failing {
+                    info!("Resumed writing the metrics to '{}'", self.config.path.display());
+                    self.is_failing = false;
+                }
+            }
+            Err(error) => {
+                if !self.is_failing {
+                    warn!(
+                        "Failed to write the metrics to '{}' (will keep trying) - {error}",
+                        self.config.path.display()
+                    );
+                    self.is_failing = true;
+                }
+                // Reopen the metrics file on the next attempt.
+                self.file = None;
+            }
+        }
+    }
+
+    /// Appends the given snapshot to the metrics file, rotating it first if the snapshot would exceed the maximum size.
+    fn try_write_snapshot(&mut self, snapshot: &str) -> io::Result<()> {
+        if self.file.is_none() {
+            let file = open_append(&self.config.path)?;
+            self.size = file.metadata()?.len();
+            self.file = Some(file);
+        }
+        if self.size > 0 && self.size + snapshot.len() as u64 > self.config.max_file_size {
+            self.file = None;
+            self.rotate()?;
+            let file = open_append(&self.config.path)?;
+            self.size = 0;
+            self.file = Some(file);
+        }
+        if let Some(file) = &mut self.file {
+            file.write_all(snapshot.as_bytes())?;
+            self.size += snapshot.len() as u64;
+        }
+        Ok(())
+    }
+
+    /// Shifts the rotated metrics files by one, dropping the oldest, and moves the metrics file to `<path>.1`.
+    fn rotate(&self) -> io::Result<()> {
+        let path = &self.config.path;
+        // If only the current file is kept, there is nothing to rotate to.
+        if self.config.max_files <= 1 {
+            return std::fs::remove_file(path);
+        }
+        // Remove the oldest rotated file, if it exists.
+        let oldest = rotated_path(path, self.config.max_files - 1);
+        if oldest.exists() {
+            std::fs::remove_file(&oldest)?;
+        }
+        for index in (1..self.config.max_files - 1).rev() {
+            let rotated = rotated_path(path, index);
+            if rotated.exists() {
+                std::fs::rename(&rotated, rotated_path(path, index + 1))?;
+            }
+        }
+        std::fs::rename(path, rotated_path(path, 1))
+    }
+}
+
+/// Opens the file at the given path for appending, creating it if it does not exist.
+fn open_append(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+/// Returns the path of the metrics file rotated the given number of times.
+pub fn rotated_path(path: &Path, index: usize) -> PathBuf {
+    let mut rotated = path.as_os_str().to_owned();
+    rotated.push(format!(".{index}"));
+    PathBuf::from(rotated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use metrics_exporter_prometheus::PrometheusBuilder;
+
+    /// Returns a unique directory in the temporary directory.
+    fn sample_directory(name: &str) -> PathBuf {
+        let nanos = OffsetDateTime::now_utc().unix_timestamp_nanos();
+        let directory = std::env::temp_dir().join(format!("snarkos-metrics-{name}-{}-{nanos}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&directory);
+        directory
+    }
+
+    /// Returns the snapshots in the file at the given path, ensuring each line is valid JSON.
+    fn read_snapshots(path: &Path) -> Vec<Value> {
+        std::fs::read_to_string(path).unwrap().lines().map(|line| serde_json::from_str(line).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_snapshots_are_valid_json() {
+        // Record a few metrics with a local recorder.
+        let recorder = PrometheusBuilder::new().build_recorder();
+        let handle = recorder.handle();
+        ::metrics::with_local_recorder(&recorder, || {
+            ::metrics::counter!("snarkos_router_connected_total").increment(3);
+            ::metrics::gauge!("snarkos_bft_height_total", "label" => "value").set(7.0);
+            ::metrics::histogram!("snarkos_consensus_latency_secs").record(0.5);
+        });
+
+        let directory = sample_directory("snapshots");
+        std::fs::create_dir_all(&directory).unwrap();
+        let path = directory.join("metrics.jsonl");
+        let mut exporter = MetricsFileExporter::new(MetricsFileConfig::new(path.clone()));
+        for timestamp in 0..3 {
+            exporter.write_snapshot(&snapshot_line(&handle.render(), timestamp));
+        }
+
+        // Ensure each snapshot is a line of JSON, with the timestamp and the metrics.
+        let snapshots = read_snapshots(&path);
+        assert_eq!(snapshots.len(), 3);
+        for (timestamp, snapshot) in snapshots.iter().enumerate() {
+            assert_eq!(snapshot["timestamp"], timestamp);
+            let metrics = &snapshot["metrics"];
+            assert_eq!(metrics["snarkos_router_connected_total"], 3.0);
+            assert_eq!(metrics["snarkos_bft_height_total{label=\"value\"}"], 7.0);
+            assert_eq!(metrics["snarkos_consensus_latency_secs_count"], 1.0);
+        }
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_rotation_respects_the_caps() {
+        let directory = sample_directory("rotation");
+        std::fs::create_dir_all(&directory).unwrap();
+        let path = directory.join("metrics.jsonl");
+        let config = MetricsFileConfig { max_file_size: 256, max_files: 3, ..MetricsFileConfig::new(path.clone()) };
+        let mut exporter = MetricsFileExporter::new(config.clone());
+
+        // Write enough snapshots to rotate the file many times.
+        let rendered = "# TYPE snarkos_blocks_height_total gauge\nsnarkos_blocks_height_total 42\n";
+        for timestamp in 0..100 {
+            exporter.write_snapshot(&snapshot_line(rendered, timestamp));
+        }
+
+        // Ensure only the maximum number of files is kept, and that each is within the maximum size.
+        assert!(path.exists());
+        assert!(rotated_path(&path, 1).exists());
+        assert!(rotated_path(&path, 2).exists());
+        assert!(!rotated_path(&path, 3).exists());
+        assert_eq!(std::fs::read_dir(&directory).unwrap().count(), config.max_files);
+
+        // Ensure the files hold the most recent snapshots, in order.
+        let mut timestamps = vec![];
+        for path in [rotated_path(&path, 2), rotated_path(&path, 1), path.clone()] {
+            assert!(std::fs::metadata(&path).unwrap().len() <= config.max_file_size);
+            timestamps.extend(read_snapshots(&path).iter().map(|snapshot| snapshot["timestamp"].as_i64().unwrap()));
+        }
+        assert_eq!(timestamps.last(), Some(&99));
+        assert!(timestamps.windows(2).all(|pair| pair[1] == pair[0] + 1));
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_unwritable_file_is_retried() {
+        // The directory of the metrics file does not exist yet, so the writes fail.
+        let directory = sample_directory("unwritable");
+        let path = directory.join("metrics.jsonl");
+        let mut exporter = MetricsFileExporter::new(MetricsFileConfig::new(path.clone()));
+        exporter.write_snapshot(&snapshot_line("", 0));
+        exporter.write_snapshot(&snapshot_line("", 1));
+        assert!(exporter.is_failing);
+        assert!(!path.exists());
+
+        // Once the directory exists, the exporter resumes.
+        std::fs::create_dir_all(&directory).unwrap();
+        exporter.write_snapshot(&snapshot_line("", 2));
+        assert!(!exporter.is_failing);
+        assert_eq!(read_snapshots(&path).len(), 1);
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
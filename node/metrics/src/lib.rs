@@ -13,6 +13,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+#[macro_use]
+extern crate tracing;
+
+mod file_exporter;
+pub use file_exporter::*;
+
 mod names;
 
 // Expose the names at the crate level for easy access.
@@ -24,6 +30,7 @@ pub use snarkvm::metrics::*;
 #[cfg(not(feature = "serial"))]
 use rayon::prelude::*;
 
+use metrics_exporter_prometheus::PrometheusHandle;
 use parking_lot::Mutex;
 use snarkvm::{
     ledger::narwhal::TransmissionID,
@@ -36,16 +43,33 @@ use std::{
         Arc,
         atomic::{AtomicUsize, Ordering},
     },
+    time::Duration,
 };
 use time::OffsetDateTime;
 
-/// Initializes the metrics and returns a handle to the task running the metrics exporter.
-pub fn initialize_metrics(ip: Option<SocketAddr>) {
+/// The interval between the upkeeps of the metrics recorder, which drain the histograms.
+const METRICS_UPKEEP_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Initializes the metrics and returns a handle to render them, e.g. for the metrics file exporter.
+/// Note: This must be called from within a Tokio runtime, as the metrics exporter is spawned onto it.
+pub fn initialize_metrics(ip: Option<SocketAddr>) -> PrometheusHandle {
     // Build the Prometheus exporter.
     let builder = metrics_exporter_prometheus::PrometheusBuilder::new();
-    if let Some(ip) = ip { builder.with_http_listener(ip) } else { builder }
-        .install()
+    let (recorder, exporter) = if let Some(ip) = ip { builder.with_http_listener(ip) } else { builder }
+        .build()
         .expect("can't build the prometheus exporter");
+    let handle = recorder.handle();
+    ::metrics::set_global_recorder(recorder).expect("can't install the prometheus recorder");
+
+    // Spawn the metrics exporter, and the upkeep of the recorder.
+    tokio::spawn(exporter);
+    let upkeep_handle = handle.clone();
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(METRICS_UPKEEP_INTERVAL).await;
+            upkeep_handle.run_upkeep();
+        }
+    });
 
     // Register the snarkVM metrics.
     snarkvm::metrics::register_metrics();
@@ -60,6 +84,8 @@ pub fn initialize_metrics(ip: Option<SocketAddr>) {
     for name in crate::names::HISTOGRAM_NAMES {
         register_histogram(name);
     }
+
+    handle
 }
 
 pub fn update_block_metrics<N: Network>(block: &Block<N>) {
@@ -96,6 +122,21 @@ pub fn update_block_metrics<N: Network>(block: &Block<N>) {
     increment_gauge(blocks::ABORTED_SOLUTIONS, block.aborted_solution_ids().len() as f64);
 }
 
+/// Sets the gauge with the given name and label to the given value.
+pub fn gauge_label<V: Into<f64>>(name: &'static str, label_key: &'static str, label_value: String, value: V) {
+    ::metrics::gauge!(name, label_key => label_value).set(value.into());
+}
+
+/// Increments the counter with the given name and label by one.
+pub fn increment_counter_label(name: &'static str, label_key: &'static str, label_value: String) {
+    ::metrics::counter!(name, label_key => label_value).increment(1);
+}
+
+/// Increments the counter with the given name and label by the given amount.
+pub fn add_counter_label(name: &'static str, label_key: &'static str, label_value: String, amount: u64) {
+    ::metrics::counter!(name, label_key => label_value).increment(amount);
+}
+
 pub fn add_transmission_latency_metric<N: Network>(
     transmissions_queue_timestamps: &Arc<Mutex<HashMap<TransmissionID<N>, i64>>>,
     block: &Block<N>,
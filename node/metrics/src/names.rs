@@ -13,9 +13,20 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-pub(super) const COUNTER_NAMES: [&str; 2] = [bft::LEADERS_ELECTED, consensus::STALE_UNCONFIRMED_TRANSMISSIONS];
+pub(super) const COUNTER_NAMES: [&str; 10] = [
+    bft::LEADERS_ELECTED,
+    bft::DROPPED_VALIDATORS_REQUESTS,
+    bft::DUPLICATE_IDENTITIES,
+    consensus::STALE_UNCONFIRMED_TRANSMISSIONS,
+    consensus::EXPIRED_INBOUND_TRANSMISSIONS,
+    consensus::RATE_LIMITED_DEPLOYMENTS,
+    storage::WRITE_VERIFICATIONS,
+    tasks::FAILURES,
+    tcp::SHED_HANDSHAKES,
+    tcp::FD_EXHAUSTION_ERRORS,
+];
 
-pub(super) const GAUGE_NAMES: [&str; 26] = [
+pub(super) const GAUGE_NAMES: [&str; 36] = [
     bft::CONNECTED,
     bft::CONNECTING,
     bft::LAST_STORED_ROUND,
@@ -24,6 +35,8 @@ pub(super) const GAUGE_NAMES: [&str; 26] = [
     bft::HEIGHT,
     bft::LAST_COMMITTED_ROUND,
     bft::IS_SYNCED,
+    bft::TRANSMISSIONS_HELD,
+    bft::TRANSMISSIONS_FETCHED,
     blocks::SOLUTIONS,
     blocks::TRANSACTIONS,
     blocks::ACCEPTED_DEPLOY,
@@ -36,21 +49,36 @@ pub(super) const GAUGE_NAMES: [&str; 26] = [
     blocks::COINBASE_TARGET,
     blocks::CUMULATIVE_PROOF_TARGET,
     consensus::COMMITTED_CERTIFICATES,
+    consensus::HEALTH_SCORE,
     consensus::UNCONFIRMED_SOLUTIONS,
     consensus::UNCONFIRMED_TRANSACTIONS,
+    memory::POOL_BYTES,
+    memory::POOL_SHRINK_LEVEL,
     router::CONNECTED,
     router::CANDIDATE,
+    router::CANDIDATE_AGE_UNDER_1H,
+    router::CANDIDATE_AGE_UNDER_24H,
+    router::CANDIDATE_AGE_OVER_24H,
     router::RESTRICTED,
+    storage::CORRUPTED,
     tcp::TCP_TASKS,
+    tcp::HANDSHAKES,
 ];
 
-pub(super) const HISTOGRAM_NAMES: [&str; 3] =
-    [bft::COMMIT_ROUNDS_LATENCY, consensus::CERTIFICATE_COMMIT_LATENCY, consensus::BLOCK_LATENCY];
+pub(super) const HISTOGRAM_NAMES: [&str; 5] = [
+    blocks::ANNOUNCE_DELAY,
+    bft::COMMIT_ROUNDS_LATENCY,
+    consensus::CERTIFICATE_COMMIT_LATENCY,
+    consensus::BLOCK_LATENCY,
+    consensus::STORAGE_ADVANCE_LATENCY,
+];
 
 pub mod bft {
     pub const COMMIT_ROUNDS_LATENCY: &str = "snarkos_bft_commit_rounds_latency_secs"; // <-- This one doesn't even make sense.
     pub const CONNECTED: &str = "snarkos_bft_connected_total";
     pub const CONNECTING: &str = "snarkos_bft_connecting_total";
+    pub const DROPPED_VALIDATORS_REQUESTS: &str = "snarkos_bft_dropped_validators_requests_total";
+    pub const DUPLICATE_IDENTITIES: &str = "snarkos_bft_duplicate_identities_total";
     pub const LAST_STORED_ROUND: &str = "snarkos_bft_last_stored_round";
     pub const LEADERS_ELECTED: &str = "snarkos_bft_leaders_elected_total";
     pub const PROPOSAL_ROUND: &str = "snarkos_bft_primary_proposal_round";
@@ -58,9 +86,24 @@ pub mod bft {
     pub const HEIGHT: &str = "snarkos_bft_height_total";
     pub const LAST_COMMITTED_ROUND: &str = "snarkos_bft_last_committed_round";
     pub const IS_SYNCED: &str = "snarkos_bft_is_synced";
+    pub const TRANSMISSIONS_HELD: &str = "snarkos_bft_primary_transmissions_held_total";
+    pub const TRANSMISSIONS_FETCHED: &str = "snarkos_bft_primary_transmissions_fetched_total";
+    pub const STORAGE_AUDIT_REPAIRS: &str = "snarkos_bft_storage_audit_repairs_total";
+    pub const WORKER_TRANSMISSIONS: &str = "snarkos_bft_worker_transmissions_total";
+    pub const WORKER_PENDING: &str = "snarkos_bft_worker_pending_total";
+    pub const WORKER_BYTES: &str = "snarkos_bft_worker_bytes_total";
+    pub const WORKER_OLDEST_AGE: &str = "snarkos_bft_worker_oldest_age_secs";
+    pub const WORKER_RECENTLY_DRAINED: &str = "snarkos_bft_worker_recently_drained_total";
+    pub const CONNECTION_BYTES_SENT: &str = "snarkos_bft_connection_bytes_sent_total";
+    pub const CONNECTION_BYTES_RECEIVED: &str = "snarkos_bft_connection_bytes_received_total";
+    pub const CONNECTION_MESSAGES_SENT: &str = "snarkos_bft_connection_messages_sent_total";
+    pub const CONNECTION_MESSAGES_RECEIVED: &str = "snarkos_bft_connection_messages_received_total";
+    pub const CONNECTION_UPTIME: &str = "snarkos_bft_connection_uptime_secs";
+    pub const CONNECTION_RECONNECTS: &str = "snarkos_bft_connection_reconnects_total";
 }
 
 pub mod blocks {
+    pub const ANNOUNCE_DELAY: &str = "snarkos_blocks_announce_delay_secs";
     pub const TRANSACTIONS: &str = "snarkos_blocks_transactions_total";
     pub const SOLUTIONS: &str = "snarkos_blocks_solutions_total";
     pub const ACCEPTED_DEPLOY: &str = "snarkos_blocks_accepted_deploy";
@@ -82,14 +125,49 @@ pub mod consensus {
     pub const UNCONFIRMED_SOLUTIONS: &str = "snarkos_consensus_unconfirmed_solutions_total";
     pub const TRANSMISSION_LATENCY: &str = "snarkos_consensus_transmission_latency";
     pub const STALE_UNCONFIRMED_TRANSMISSIONS: &str = "snarkos_consensus_stale_unconfirmed_transmissions";
+    pub const STORAGE_ADVANCE_LATENCY: &str = "snarkos_consensus_storage_advance_latency_secs";
+    pub const POLICY_REJECTIONS: &str = "snarkos_consensus_policy_rejections_total";
+    pub const POLICY_DEFERRALS: &str = "snarkos_consensus_policy_deferrals_total";
+    pub const HEALTH_SCORE: &str = "snarkos_consensus_health_score";
+    pub const EXPIRED_INBOUND_TRANSMISSIONS: &str = "snarkos_consensus_expired_inbound_transmissions_total";
+    pub const MEMPOOL_PRESSURE: &str = "snarkos_consensus_mempool_pressure";
+    pub const SHED_BROADCASTS: &str = "snarkos_consensus_shed_broadcasts_total";
+    pub const RATE_LIMITED_DEPLOYMENTS: &str = "snarkos_consensus_rate_limited_deployments_total";
+}
+
+pub mod memory {
+    pub const POOL_BYTES: &str = "snarkos_memory_pool_bytes";
+    pub const POOL_SHRINK_LEVEL: &str = "snarkos_memory_pool_shrink_level";
+}
+
+pub mod prover {
+    pub const SOLUTION_ACKS: &str = "snarkos_prover_solution_acks_total";
 }
 
 pub mod router {
     pub const CONNECTED: &str = "snarkos_router_connected_total";
     pub const CANDIDATE: &str = "snarkos_router_candidate_total";
+    pub const CANDIDATE_AGE_UNDER_1H: &str = "snarkos_router_candidate_age_under_1h_total";
+    pub const CANDIDATE_AGE_UNDER_24H: &str = "snarkos_router_candidate_age_under_24h_total";
+    pub const CANDIDATE_AGE_OVER_24H: &str = "snarkos_router_candidate_age_over_24h_total";
     pub const RESTRICTED: &str = "snarkos_router_restricted_total";
+    pub const CACHE_OCCUPANCY: &str = "snarkos_router_cache_occupancy_total";
+    pub const EXPERIMENT_PEERS: &str = "snarkos_router_experiment_peers_total";
+    pub const DUPLICATE_IDENTITIES: &str = "snarkos_router_duplicate_identities_total";
+}
+
+pub mod storage {
+    pub const WRITE_VERIFICATIONS: &str = "snarkos_storage_write_verifications_total";
+    pub const CORRUPTED: &str = "snarkos_storage_corrupted";
+}
+
+pub mod tasks {
+    pub const FAILURES: &str = "snarkos_tasks_failures_total";
 }
 
 pub mod tcp {
     pub const TCP_TASKS: &str = "snarkos_tcp_tasks_total";
+    pub const HANDSHAKES: &str = "snarkos_tcp_handshakes_total";
+    pub const SHED_HANDSHAKES: &str = "snarkos_tcp_shed_handshakes_total";
+    pub const FD_EXHAUSTION_ERRORS: &str = "snarkos_tcp_fd_exhaustion_errors_total";
 }
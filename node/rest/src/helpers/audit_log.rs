@@ -0,0 +1,290 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{TokenIdentity, spawn_journal_writer};
+
+use anyhow::Result;
+use axum::http::StatusCode;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::{
+    collections::VecDeque,
+    net::IpAddr,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering},
+};
+use tokio::sync::mpsc;
+
+/// The default number of the most recent administrative actions retained in memory.
+pub const DEFAULT_AUDIT_LOG_CAPACITY: usize = 256;
+/// The default maximum size in bytes of the audit log file, before it is rotated.
+pub const MAX_AUDIT_LOG_FILE_SIZE: u64 = 16 * 1024 * 1024;
+/// The maximum size in bytes of a request body whose parameters are recorded.
+pub const MAX_AUDITED_BODY_SIZE: usize = 64 * 1024;
+
+/// The maximum number of characters of a recorded string parameter.
+const MAX_PARAMETER_LENGTH: usize = 256;
+/// The maximum number of recorded items of an array or object parameter.
+const MAX_PARAMETER_ITEMS: usize = 32;
+/// The parts of the names of the parameters whose values are redacted.
+const SENSITIVE_PARAMETERS: [&str; 5] = ["key", "secret", "token", "password", "seed"];
+
+/// Returns the path of the audit log, next to the given ledger path, so that every ledger has its own audit log.
+pub fn audit_log_path(ledger_path: &Path) -> PathBuf {
+    let mut path = ledger_path.to_path_buf();
+    let name = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+    path.set_file_name(format!("{name}-admin-audit.log"));
+    path
+}
+
+/// The outcome of an administrative action.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditOutcome {
+    /// The action succeeded.
+    Succeeded,
+    /// The action was denied to its token.
+    Denied,
+    /// The action failed.
+    Failed,
+}
+
+impl AuditOutcome {
+    /// Returns the outcome of an action with the given response status.
+    pub fn of(status: StatusCode) -> Self {
+        match status {
+            status if status.is_success() => Self::Succeeded,
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Self::Denied,
+            _ => Self::Failed,
+        }
+    }
+}
+
+/// A record of an authenticated administrative action.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct AuditRecord {
+    /// The UNIX timestamp of when the action completed.
+    pub timestamp: i64,
+    /// The HTTP method of the action.
+    pub method: String,
+    /// The endpoint of the action, without the network prefix.
+    pub endpoint: String,
+    /// The parameters of the action, with the sensitive values redacted and the large values truncated.
+    pub parameters: Value,
+    /// The IP address of the client that requested the action.
+    pub source_ip: IpAddr,
+    /// The subject of the token that authenticated the action.
+    pub subject: String,
+    /// The unique identifier of the token that authenticated the action.
+    pub token_id: String,
+    /// The HTTP status of the response.
+    pub status: u16,
+    /// The outcome of the action.
+    pub outcome: AuditOutcome,
+}
+
+impl AuditRecord {
+    /// Initializes a record of an action, completed now with the given response status.
+    pub fn new(
+        method: String,
+        endpoint: String,
+        parameters: Value,
+        source_ip: IpAddr,
+        identity: &TokenIdentity,
+        status: StatusCode,
+    ) -> Self {
+        Self {
+            timestamp: ::time::OffsetDateTime::now_utc().unix_timestamp(),
+            method,
+            endpoint,
+            parameters,
+            source_ip,
+            subject: identity.subject.clone(),
+            token_id: identity.token_id.clone(),
+            status: status.as_u16(),
+            outcome: AuditOutcome::of(status),
+        }
+    }
+}
+
+/// The audit log of the authenticated administrative actions.
+///
+/// The most recent records are retained in memory, and, if a file is given, every record is appended to it
+/// through the bounded channel of a journal writer, so recording an action never blocks nor fails it;
+/// if the writer is unable to keep up, the record is only retained in memory, and counted as dropped.
+pub struct AuditLog {
+    /// The maximum number of records retained in memory.
+    capacity: usize,
+    /// The most recent records, from the oldest.
+    records: Mutex<VecDeque<AuditRecord>>,
+    /// The path to the audit log file, if any.
+    path: Option<PathBuf>,
+    /// The sender for the writer of the audit log file, if any.
+    sender: Option<mpsc::Sender<AuditRecord>>,
+    /// The number of records not written to the file because the writer could not keep up.
+    num_dropped: AtomicU64,
+}
+
+impl AuditLog {
+    /// Initializes an audit log retaining the given number of the most recent records in memory.
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity, records: Default::default(), path: None, sender: None, num_dropped: Default::default() }
+    }
+
+    /// Initializes an audit log retaining the given number of the most recent records in memory,
+    /// which also appends every record to the file at the given path.
+    pub fn open(capacity: usize, path: PathBuf, max_file_size: u64) -> Result<Self> {
+        let (sender, _) = spawn_journal_writer("admin audit log", &path, max_file_size)?;
+        Ok(Self { path: Some(path), sender: Some(sender), ..Self::new(capacity) })
+    }
+
+    /// Returns the path to the audit log file, if any.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Returns the number of records not written to the file because the writer could not keep up.
+    pub fn num_dropped(&self) -> u64 {
+        self.num_dropped.load(Ordering::Relaxed)
+    }
+
+    /// Records the given action, without blocking.
+    pub fn record(&self, record: AuditRecord) {
+        if let Some(sender) = &self.sender {
+            if sender.try_send(record.clone()).is_err() {
+                self.num_dropped.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        if self.capacity > 0 {
+            let mut records = self.records.lock();
+            if records.len() >= self.capacity {
+                records.pop_front();
+            }
+            records.push_back(record);
+        }
+    }
+
+    /// Returns the most recent records retained in memory, from the oldest.
+    pub fn records(&self) -> Vec<AuditRecord> {
+        self.records.lock().iter().cloned().collect()
+    }
+}
+
+impl Default for AuditLog {
+    fn default() -> Self {
+        Self::new(DEFAULT_AUDIT_LOG_CAPACITY)
+    }
+}
+
+/// Returns the parameters of an action from its query string and its JSON body, if any,
+/// with the sensitive values redacted and the large values truncated.
+pub fn sanitize_parameters(query: Option<&str>, body: Option<&[u8]>) -> Value {
+    let mut parameters = Map::new();
+    // Note: The query parameters are recorded as they were sent, without decoding them.
+    if let Some(query) = query {
+        let query = query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| match pair.split_once('=') {
+                Some((name, value)) => (name.to_string(), Value::String(value.to_string())),
+                None => (pair.to_string(), Value::Null),
+            })
+            .collect();
+        parameters.insert("query".to_string(), Value::Object(query));
+    }
+    if let Some(body) = body.filter(|body| !body.is_empty()) {
+        let body = serde_json::from_slice(body).unwrap_or_else(|_| Value::String("<not JSON>".to_string()));
+        parameters.insert("body".to_string(), body);
+    }
+    sanitize(Value::Object(parameters))
+}
+
+/// Returns the given value, with the values of the sensitive names redacted and the large values truncated.
+fn sanitize(value: Value) -> Value {
+    match value {
+        Value::String(string) if string.chars().count() > MAX_PARAMETER_LENGTH => {
+            Value::String(format!("{}...", string.chars().take(MAX_PARAMETER_LENGTH).collect::<String>()))
+        }
+        Value::Array(items) => Value::Array(items.into_iter().take(MAX_PARAMETER_ITEMS).map(sanitize).collect()),
+        Value::Object(fields) => Value::Object(
+            fields
+                .into_iter()
+                .take(MAX_PARAMETER_ITEMS)
+                .map(|(name, value)| {
+                    let is_sensitive = SENSITIVE_PARAMETERS.iter().any(|part| name.to_lowercase().contains(part));
+                    let value = if is_sensitive { Value::String("<redacted>".to_string()) } else { sanitize(value) };
+                    (name, value)
+                })
+                .collect(),
+        ),
+        value => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Returns a sample record of the given endpoint, with the given response status.
+    fn sample_record(endpoint: &str, status: StatusCode) -> AuditRecord {
+        let identity = TokenIdentity { subject: "aleo1subject".to_string(), token_id: "0".repeat(32) };
+        let source_ip = "127.0.0.1".parse().unwrap();
+        AuditRecord::new("POST".to_string(), endpoint.to_string(), Value::Null, source_ip, &identity, status)
+    }
+
+    #[test]
+    fn test_audit_log_capacity() {
+        let audit_log = AuditLog::new(2);
+        for endpoint in ["/a", "/b", "/c"] {
+            audit_log.record(sample_record(endpoint, StatusCode::OK));
+        }
+        // Ensure only the most recent records are retained, from the oldest.
+        let endpoints: Vec<_> = audit_log.records().into_iter().map(|record| record.endpoint).collect();
+        assert_eq!(endpoints, ["/b", "/c"]);
+        assert_eq!(audit_log.num_dropped(), 0);
+    }
+
+    #[test]
+    fn test_audit_outcome() {
+        assert_eq!(sample_record("/a", StatusCode::OK).outcome, AuditOutcome::Succeeded);
+        assert_eq!(sample_record("/a", StatusCode::FORBIDDEN).outcome, AuditOutcome::Denied);
+        assert_eq!(sample_record("/a", StatusCode::INTERNAL_SERVER_ERROR).outcome, AuditOutcome::Failed);
+    }
+
+    #[test]
+    fn test_sanitize_parameters() {
+        let body = json!({
+            "peer_ip": "1.2.3.4:4130",
+            "api_key": "hunter2",
+            "nested": { "Private_Key": "APrivateKey1", "peers": (0..100).collect::<Vec<_>>() },
+            "note": "a".repeat(1000),
+        });
+        let parameters = sanitize_parameters(Some("since=1&token=abc"), Some(&serde_json::to_vec(&body).unwrap()));
+
+        // Ensure the sensitive values are redacted, at any depth, regardless of their case.
+        assert_eq!(parameters["query"], json!({ "since": "1", "token": "<redacted>" }));
+        assert_eq!(parameters["body"]["peer_ip"], "1.2.3.4:4130");
+        assert_eq!(parameters["body"]["api_key"], "<redacted>");
+        assert_eq!(parameters["body"]["nested"]["Private_Key"], "<redacted>");
+        // Ensure the large values are truncated.
+        assert_eq!(parameters["body"]["nested"]["peers"].as_array().unwrap().len(), MAX_PARAMETER_ITEMS);
+        assert_eq!(parameters["body"]["note"].as_str().unwrap().len(), MAX_PARAMETER_LENGTH + 3);
+        // Ensure a body that is not JSON is not recorded.
+        assert_eq!(sanitize_parameters(None, Some(b"\x00\x01")), json!({ "body": "<not JSON>" }));
+        assert_eq!(sanitize_parameters(None, None), json!({}));
+    }
+}
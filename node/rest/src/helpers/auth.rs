@@ -20,7 +20,7 @@ use anyhow::{Result, anyhow};
 use axum::{
     RequestPartsExt,
     body::Body,
-    http::{Request, StatusCode},
+    http::{Method, Request, StatusCode},
     middleware::Next,
     response::{IntoResponse, Response},
 };
@@ -49,6 +49,8 @@ fn jwt_secret() -> &'static Vec<u8> {
 pub struct Claims {
     /// The subject (user).
     sub: String,
+    /// The unique identifier of the token, so that the administrative requests are attributable to it.
+    jti: String,
     /// The UTC timestamp the token was issued at.
     iat: i64,
     /// Expiration time (as UTC timestamp).
@@ -59,8 +61,19 @@ impl Claims {
     pub fn new<N: Network>(address: Address<N>) -> Self {
         let issued_at = OffsetDateTime::now_utc().unix_timestamp();
         let expiration = issued_at.saturating_add(EXPIRATION);
+        let token_id = format!("{:032x}", ::rand::thread_rng().gen::<u128>());
 
-        Self { sub: address.to_string(), iat: issued_at, exp: expiration }
+        Self { sub: address.to_string(), jti: token_id, iat: issued_at, exp: expiration }
+    }
+
+    /// Returns the subject of the token.
+    pub fn subject(&self) -> &str {
+        &self.sub
+    }
+
+    /// Returns the unique identifier of the token.
+    pub fn token_id(&self) -> &str {
+        &self.jti
     }
 
     /// Returns true if the token is expired.
@@ -80,21 +93,40 @@ pub async fn auth_middleware(request: Request<Body>, next: Next) -> Result<Respo
     let auth: TypedHeader<Authorization<Bearer>> =
         parts.extract().await.map_err(|_| StatusCode::UNAUTHORIZED.into_response())?;
 
-    match decode::<Claims>(auth.token(), &DecodingKey::from_secret(jwt_secret()), &Validation::new(Algorithm::HS256)) {
-        Ok(decoded) => {
-            let claims = decoded.claims;
-            if claims.is_expired() {
-                return Err((StatusCode::UNAUTHORIZED, "Expired JSON Web Token".to_owned()).into_response());
-            }
-        }
-
+    let claims = match decode::<Claims>(
+        auth.token(),
+        &DecodingKey::from_secret(jwt_secret()),
+        &Validation::new(Algorithm::HS256),
+    ) {
+        Ok(decoded) => decoded.claims,
         Err(_) => {
             return Err(StatusCode::UNAUTHORIZED.into_response());
         }
+    };
+    if claims.is_expired() {
+        return Err((StatusCode::UNAUTHORIZED, "Expired JSON Web Token".to_owned()).into_response());
     }
+    // Attribute the response to the token, e.g. for the audit log of the administrative requests.
+    let identity = TokenIdentity { subject: claims.sub, token_id: claims.jti };
 
     // Reconstruct the request.
     let request = Request::from_parts(parts, body);
 
-    Ok(next.run(request).await)
+    let mut response = next.run(request).await;
+    response.extensions_mut().insert(identity);
+    Ok(response)
+}
+
+/// The identity of the json web token that authenticated a request, attached to its response.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TokenIdentity {
+    /// The subject of the token.
+    pub subject: String,
+    /// The unique identifier of the token.
+    pub token_id: String,
+}
+
+/// Returns `true` if a request with the given method may mutate the node.
+pub fn is_mutating(method: &Method) -> bool {
+    !matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
 }
@@ -0,0 +1,289 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{Ledger, Network, store::ConsensusStorage};
+
+use anyhow::Result;
+use serde::Serialize;
+
+/// The number of most recent block intervals the estimate is computed over.
+pub const BLOCK_ESTIMATE_NUM_INTERVALS: u32 = 50;
+/// The multiple of the median block interval above which an interval is excluded as an outlier.
+pub const BLOCK_INTERVAL_OUTLIER_FACTOR: f64 = 3.0;
+/// The smoothing factor of the moving average of the block interval, applied to each newer interval.
+pub const BLOCK_INTERVAL_SMOOTHING: f64 = 0.2;
+/// The number of most recent rounds the typical round duration is computed over.
+pub const ROUND_PROGRESS_NUM_ROUNDS: u64 = 20;
+
+/// The range of timestamps the next block is expected to land in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ConfidenceWindow {
+    /// The earliest expected UNIX timestamp.
+    pub earliest: i64,
+    /// The latest expected UNIX timestamp.
+    pub latest: i64,
+}
+
+/// The refinement of the estimate with the round progress of the BFT, as observed by a validator.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct RoundProgress {
+    /// The current round of the BFT.
+    pub current_round: u64,
+    /// The number of seconds since the current round started.
+    pub round_age: i64,
+    /// The typical duration of a round, in seconds.
+    pub typical_round_duration: f64,
+    /// The refined UNIX timestamp the next block is expected at.
+    pub estimated_next_block_timestamp: i64,
+}
+
+/// An estimate of when the next block lands, derived from the intervals between the most recent blocks.
+///
+/// This is only an estimate: blocks are produced when the BFT commits, not on a fixed schedule.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct BlockEstimate {
+    /// The height of the latest block.
+    pub latest_height: u32,
+    /// The UNIX timestamp of the latest block.
+    pub latest_timestamp: i64,
+    /// The round of the latest block.
+    pub latest_round: u64,
+    /// The smoothed recent block interval, in seconds.
+    pub average_block_interval: f64,
+    /// The median recent block interval, in seconds.
+    pub median_block_interval: f64,
+    /// The UNIX timestamp the next block is expected at.
+    pub estimated_next_block_timestamp: i64,
+    /// The range of timestamps the next block is expected to land in.
+    pub confidence_window: ConfidenceWindow,
+    /// The average number of rounds per block, in the recent blocks.
+    pub rounds_per_block: f64,
+    /// The number of block intervals the estimate was computed over.
+    pub num_intervals: usize,
+    /// The number of block intervals excluded as outliers.
+    pub num_outliers: usize,
+    /// The refinement of the estimate with the round progress of the BFT, if available.
+    pub round_progress: Option<RoundProgress>,
+}
+
+impl BlockEstimate {
+    /// Returns the estimate for the given `(timestamp, round)` of the most recent blocks, in ascending order,
+    /// where the last is the block at the given latest height, or `None` if there are fewer than two blocks.
+    pub fn new(latest_height: u32, blocks: &[(i64, u64)]) -> Option<Self> {
+        let (&(_, first_round), &(latest_timestamp, latest_round)) = (blocks.first()?, blocks.last()?);
+        if blocks.len() < 2 {
+            return None;
+        }
+        // Compute the intervals between the blocks.
+        let intervals: Vec<_> =
+            blocks.windows(2).map(|pair| pair[1].0.saturating_sub(pair[0].0).max(0) as f64).collect();
+        let median_block_interval = median(&intervals)?;
+        // Exclude the outliers, such as a stall of the network.
+        let is_outlier = |interval: f64| interval > median_block_interval * BLOCK_INTERVAL_OUTLIER_FACTOR;
+        let inliers: Vec<_> = intervals.iter().copied().filter(|interval| !is_outlier(*interval)).collect();
+        // Compute the moving average of the intervals, from the oldest.
+        let average_block_interval = inliers
+            .iter()
+            .skip(1)
+            .fold(inliers[0], |average, interval| average + BLOCK_INTERVAL_SMOOTHING * (interval - average));
+        // Compute the standard deviation of the intervals from the average, for the confidence window.
+        let variance = inliers.iter().map(|interval| (interval - average_block_interval).powi(2)).sum::<f64>()
+            / inliers.len() as f64;
+        let deviation = variance.sqrt();
+
+        let estimated_next_block_timestamp = latest_timestamp + average_block_interval.round() as i64;
+        let confidence_window = ConfidenceWindow {
+            earliest: latest_timestamp + (average_block_interval - deviation).max(0.0).floor() as i64,
+            latest: latest_timestamp + (average_block_interval + deviation).ceil() as i64,
+        };
+        // Compute the average number of rounds per block.
+        let rounds_per_block = latest_round.saturating_sub(first_round) as f64 / intervals.len() as f64;
+
+        Some(Self {
+            latest_height,
+            latest_timestamp,
+            latest_round,
+            average_block_interval,
+            median_block_interval,
+            estimated_next_block_timestamp,
+            confidence_window,
+            rounds_per_block,
+            num_intervals: intervals.len(),
+            num_outliers: intervals.len() - inliers.len(),
+            round_progress: None,
+        })
+    }
+
+    /// Returns the estimate for the latest blocks in the given ledger, or `None` if there is only the genesis block.
+    pub fn load<N: Network, C: ConsensusStorage<N>>(ledger: &Ledger<N, C>) -> Result<Option<Self>> {
+        let latest_height = ledger.latest_height();
+        let start_height = latest_height.saturating_sub(BLOCK_ESTIMATE_NUM_INTERVALS);
+        let blocks = (start_height..=latest_height)
+            .map(|height| ledger.get_header(height).map(|header| (header.timestamp(), header.round())))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self::new(latest_height, &blocks))
+    }
+
+    /// Refines the estimate with the round progress of the BFT, given the current round,
+    /// the UNIX timestamp the round started at, and the typical duration of a round.
+    pub fn with_round_progress(
+        mut self,
+        current_round: u64,
+        round_start: i64,
+        typical_round_duration: f64,
+        now: i64,
+    ) -> Self {
+        // Determine the number of rounds until the next block is expected, counting the current round.
+        let rounds_since_block = current_round.saturating_sub(self.latest_round) as f64;
+        let remaining_rounds = (self.rounds_per_block - rounds_since_block).max(0.0) + 1.0;
+        // The next block cannot land before now.
+        let estimated_next_block_timestamp =
+            (round_start + (remaining_rounds * typical_round_duration).round() as i64).max(now);
+        self.round_progress = Some(RoundProgress {
+            current_round,
+            round_age: now.saturating_sub(round_start).max(0),
+            typical_round_duration,
+            estimated_next_block_timestamp,
+        });
+        self
+    }
+}
+
+/// Returns the typical duration of a round, given the UNIX timestamps at which consecutive rounds ended,
+/// in ascending order, or `None` if there are fewer than two rounds.
+pub fn typical_round_duration(round_timestamps: &[i64]) -> Option<f64> {
+    let durations: Vec<_> =
+        round_timestamps.windows(2).map(|pair| pair[1].saturating_sub(pair[0]).max(0) as f64).collect();
+    median(&durations)
+}
+
+/// Returns the median of the given values, or `None` if there are none.
+fn median(values: &[f64]) -> Option<f64> {
+    let mut values = values.to_vec();
+    values.sort_by(f64::total_cmp);
+    let mid = values.len() / 2;
+    match values.len() {
+        0 => None,
+        len if len % 2 == 0 => Some((values[mid - 1] + values[mid]) / 2.0),
+        _ => Some(values[mid]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LatestCache;
+    use snarkos_node_bft_ledger_service::test_helpers::sample_ledger;
+    use snarkvm::prelude::MainnetV0;
+
+    use rand::SeedableRng;
+    use rand_chacha::ChaChaRng;
+
+    type CurrentNetwork = MainnetV0;
+
+    /// Returns the `(timestamp, round)` of the given number of blocks, produced every 10 seconds and 2 rounds,
+    /// starting at the given timestamp.
+    fn sample_blocks(num_blocks: usize, start: i64) -> Vec<(i64, u64)> {
+        (0..num_blocks).map(|i| (start + 10 * i as i64, 2 * i as u64)).collect()
+    }
+
+    #[test]
+    fn test_estimate_steady_cadence() {
+        let blocks = sample_blocks(11, 1_000);
+        let estimate = BlockEstimate::new(10, &blocks).unwrap();
+        assert_eq!((estimate.latest_timestamp, estimate.latest_round), (1_100, 20));
+        assert_eq!(estimate.average_block_interval, 10.0);
+        assert_eq!(estimate.median_block_interval, 10.0);
+        assert_eq!(estimate.estimated_next_block_timestamp, 1_110);
+        assert_eq!(estimate.confidence_window, ConfidenceWindow { earliest: 1_110, latest: 1_110 });
+        assert_eq!(estimate.rounds_per_block, 2.0);
+        assert_eq!((estimate.num_intervals, estimate.num_outliers), (10, 0));
+
+        // Ensure there is no estimate without a block interval.
+        assert!(BlockEstimate::new(0, &blocks[..1]).is_none());
+        assert!(BlockEstimate::new(0, &[]).is_none());
+    }
+
+    #[test]
+    fn test_estimate_excludes_outliers() {
+        // The network stalls for 5 minutes, and then resumes producing blocks every 10 seconds.
+        let mut blocks = sample_blocks(6, 1_000);
+        blocks.extend(sample_blocks(6, 1_350));
+        let estimate = BlockEstimate::new(11, &blocks).unwrap();
+        assert_eq!(estimate.median_block_interval, 10.0);
+        assert_eq!((estimate.num_intervals, estimate.num_outliers), (11, 1));
+        assert_eq!(estimate.average_block_interval, 10.0);
+        assert_eq!(estimate.estimated_next_block_timestamp, 1_410);
+    }
+
+    #[test]
+    fn test_estimate_follows_recent_cadence() {
+        // The cadence alternates between 8 and 12 seconds, then slows to 12 seconds.
+        let mut blocks = vec![(0, 0)];
+        for i in 0..40 {
+            let interval = if i < 20 { [8, 12][i % 2] } else { 12 };
+            blocks.push((blocks.last().unwrap().0 + interval, 2 * (i as u64 + 1)));
+        }
+        let estimate = BlockEstimate::new(40, &blocks).unwrap();
+        // Ensure the moving average follows the recent cadence, and the window covers the spread.
+        assert!(estimate.average_block_interval > 11.9 && estimate.average_block_interval <= 12.0);
+        assert_eq!(estimate.median_block_interval, 12.0);
+        let latest_timestamp = blocks.last().unwrap().0;
+        assert_eq!(estimate.estimated_next_block_timestamp, latest_timestamp + 12);
+        assert!(estimate.confidence_window.earliest <= latest_timestamp + 10);
+        assert!(estimate.confidence_window.latest >= latest_timestamp + 12);
+    }
+
+    #[test]
+    fn test_estimate_with_round_progress() {
+        let estimate = BlockEstimate::new(10, &sample_blocks(11, 1_000)).unwrap();
+        assert_eq!(typical_round_duration(&[1_090, 1_095, 1_100, 1_105]), Some(5.0));
+        assert_eq!(typical_round_duration(&[1_100]), None);
+
+        // The round after the latest block started 1 second ago, so the next block is expected in 2 rounds.
+        let refined = estimate.clone().with_round_progress(21, 1_105, 5.0, 1_106);
+        let progress = refined.round_progress.unwrap();
+        assert_eq!((progress.current_round, progress.round_age), (21, 1));
+        assert_eq!(progress.estimated_next_block_timestamp, 1_115);
+
+        // Ensure an overdue block is expected now, rather than in the past.
+        let refined = estimate.with_round_progress(30, 1_150, 5.0, 1_160);
+        assert_eq!(refined.round_progress.unwrap().estimated_next_block_timestamp, 1_160);
+    }
+
+    #[test]
+    fn test_estimate_cache_refreshes_on_new_block() {
+        let rng = &mut ChaChaRng::seed_from_u64(1234567890u64);
+
+        // Initialize the ledger.
+        let (private_key, ledger) = sample_ledger::<CurrentNetwork, _>(0, rng);
+        let cache = LatestCache::default();
+        let estimate =
+            || cache.get((ledger.latest_height(), ledger.latest_hash()), || BlockEstimate::load(&ledger)).unwrap();
+
+        // Ensure there is no estimate with only the genesis block.
+        assert_eq!(estimate(), None);
+
+        // Ensure the estimate is refreshed with each new block.
+        for height in 1..=3 {
+            let block = ledger.prepare_advance_to_next_beacon_block(&private_key, vec![], vec![], vec![], rng).unwrap();
+            ledger.advance_to_next_block(&block).unwrap();
+            let latest = estimate().unwrap();
+            assert_eq!(latest.latest_height, height);
+            assert_eq!(latest.latest_timestamp, block.timestamp());
+            assert_eq!(latest.num_intervals, height as usize);
+        }
+    }
+}
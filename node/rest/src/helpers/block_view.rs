@@ -0,0 +1,161 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{
+    ledger::{
+        authority::Authority,
+        block::{Block, Header},
+    },
+    prelude::Network,
+};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+/// The parts of a block included in a response.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BlockInclude {
+    /// The block hash, the header, and the authority.
+    Header,
+    /// The header parts, with the transaction IDs and their number.
+    TxIds,
+    /// The entire block.
+    #[default]
+    Full,
+}
+
+/// The query object for the parts of a block to include in a response.
+#[derive(Clone, Copy, Default, Deserialize, Serialize)]
+pub struct BlockIncludeQuery {
+    /// The parts of the block to include; defaults to the entire block.
+    #[serde(default)]
+    pub include: BlockInclude,
+}
+
+/// Returns the JSON of the given reduced parts of the block with the given hash.
+///
+/// The parts are read with the given closures, so that the transactions are never loaded from storage:
+/// the transaction IDs are read from the index of the block, and only if they are included.
+pub fn reduced_block_json<N: Network>(
+    include: BlockInclude,
+    hash: N::BlockHash,
+    header: impl FnOnce() -> Result<Header<N>>,
+    authority: impl FnOnce() -> Result<Authority<N>>,
+    transaction_ids: impl FnOnce() -> Result<Vec<N::TransactionID>>,
+) -> Result<Value> {
+    let mut json = json!({
+        "block_hash": hash,
+        "header": header()?,
+        "authority": authority()?,
+    });
+    if include == BlockInclude::TxIds {
+        let transaction_ids = transaction_ids()?;
+        json["num_transactions"] = json!(transaction_ids.len());
+        json["transaction_ids"] = json!(transaction_ids);
+    }
+    Ok(json)
+}
+
+/// Returns the JSON of the given block, with the given parts.
+pub fn block_json<N: Network>(include: BlockInclude, block: &Block<N>) -> Result<Value> {
+    match include {
+        BlockInclude::Full => Ok(serde_json::to_value(block)?),
+        reduced => reduced_block_json(
+            reduced,
+            block.hash(),
+            || Ok(*block.header()),
+            || Ok(block.authority().clone()),
+            || Ok(block.transaction_ids().copied().collect()),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{FromBytes, MainnetV0};
+
+    use axum::extract::Query;
+    use std::cell::Cell;
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn test_block_include_query() {
+        let parse = |query: &str| {
+            let uri = format!("/block/1?{query}").parse().unwrap();
+            Query::<BlockIncludeQuery>::try_from_uri(&uri).map(|Query(query)| query.include)
+        };
+        assert_eq!(parse("").unwrap(), BlockInclude::Full);
+        assert_eq!(parse("include=header").unwrap(), BlockInclude::Header);
+        assert_eq!(parse("include=txids").unwrap(), BlockInclude::TxIds);
+        assert_eq!(parse("include=full").unwrap(), BlockInclude::Full);
+        assert!(parse("include=transactions").is_err());
+    }
+
+    #[test]
+    fn test_block_json_shapes() {
+        let block = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+
+        // Ensure the full block decodes to the block.
+        let full = block_json(BlockInclude::Full, &block).unwrap();
+        assert_eq!(serde_json::from_value::<Block<CurrentNetwork>>(full).unwrap(), block);
+
+        // Ensure the header only includes the block hash, the header, and the authority.
+        let header = block_json(BlockInclude::Header, &block).unwrap();
+        let keys = header.as_object().unwrap().keys().collect::<Vec<_>>();
+        assert_eq!(keys, ["block_hash", "header", "authority"]);
+        assert_eq!(header["block_hash"], json!(block.hash()));
+        assert_eq!(
+            serde_json::from_value::<Header<CurrentNetwork>>(header["header"].clone()).unwrap(),
+            *block.header()
+        );
+
+        // Ensure the transaction IDs are added to the header.
+        let txids = block_json(BlockInclude::TxIds, &block).unwrap();
+        let keys = txids.as_object().unwrap().keys().collect::<Vec<_>>();
+        assert_eq!(keys, ["block_hash", "header", "authority", "num_transactions", "transaction_ids"]);
+        assert_eq!(txids["num_transactions"], json!(block.transactions().len()));
+        assert_eq!(txids["transaction_ids"], json!(block.transaction_ids().collect::<Vec<_>>()));
+    }
+
+    #[test]
+    fn test_reduced_block_reads() {
+        let block = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let num_id_reads = Cell::new(0);
+        let read = |include| {
+            reduced_block_json::<CurrentNetwork>(
+                include,
+                block.hash(),
+                || Ok(*block.header()),
+                || Ok(block.authority().clone()),
+                || {
+                    num_id_reads.set(num_id_reads.get() + 1);
+                    Ok(block.transaction_ids().copied().collect())
+                },
+            )
+            .unwrap()
+        };
+
+        // Ensure the header does not read the transaction IDs.
+        read(BlockInclude::Header);
+        assert_eq!(num_id_reads.get(), 0);
+        // Ensure the transaction IDs are read once.
+        read(BlockInclude::TxIds);
+        assert_eq!(num_id_reads.get(), 1);
+    }
+}
@@ -0,0 +1,107 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkos_node_consensus::QueuePressure;
+
+use axum::{
+    http::{StatusCode, header::RETRY_AFTER},
+    response::{IntoResponse, Response},
+};
+use parking_lot::Mutex;
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    time::{Duration, Instant},
+};
+
+/// The interval in seconds at which an IP may still submit a broadcast, while the memory pool is under pressure.
+pub const PRESSURE_ALLOWANCE_INTERVAL_IN_SECS: u64 = 10;
+/// The maximum number of IPs whose allowance is tracked.
+const MAX_TRACKED_ALLOWANCES: usize = 1 << 12;
+
+/// The shedder of the broadcasts, while the memory pool is under pressure.
+///
+/// A shed broadcast is rejected before its body is deserialized, so that the clients retrying aggressively
+/// do not compound the load. Each IP is still let through once per allowance interval, so that the
+/// occasional submitters are not starved by the aggressive ones.
+#[derive(Default)]
+pub struct BroadcastShedder {
+    /// The time each IP was last let through, while the memory pool was under pressure.
+    allowances: Mutex<HashMap<IpAddr, Instant>>,
+}
+
+impl BroadcastShedder {
+    /// Returns the duration the client should wait before retrying, if its broadcast is shed,
+    /// given the pressure on the queue it targets.
+    pub fn check(&self, ip: IpAddr, pressure: &QueuePressure, now: Instant) -> Option<Duration> {
+        if !pressure.is_high() {
+            return None;
+        }
+        let interval = Duration::from_secs(PRESSURE_ALLOWANCE_INTERVAL_IN_SECS);
+        {
+            let mut allowances = self.allowances.lock();
+            let is_allowed = !allowances.get(&ip).is_some_and(|last| now.saturating_duration_since(*last) < interval);
+            if is_allowed {
+                // Forget the lapsed allowances, if too many IPs are tracked.
+                if allowances.len() >= MAX_TRACKED_ALLOWANCES {
+                    allowances.retain(|_, last| now.saturating_duration_since(*last) < interval);
+                }
+                if allowances.len() < MAX_TRACKED_ALLOWANCES {
+                    allowances.insert(ip, now);
+                    return None;
+                }
+            }
+        }
+        pressure.record_shed();
+        Some(pressure.retry_after(now))
+    }
+}
+
+/// Returns the response to a shed broadcast, asking the client to retry after the given duration.
+pub fn shed_response(retry_after: Duration) -> Response {
+    let message = "The memory pool is under pressure, retry later";
+    (StatusCode::TOO_MANY_REQUESTS, [(RETRY_AFTER, retry_after.as_secs().to_string())], message).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkos_node_consensus::MempoolPressure;
+
+    #[test]
+    fn test_check_allowances() {
+        let shedder = BroadcastShedder::default();
+        let pressure = MempoolPressure::default();
+        let (first, second) = ("1.1.1.1".parse().unwrap(), "2.2.2.2".parse().unwrap());
+        let now = Instant::now();
+
+        // Without pressure, nothing is shed.
+        assert_eq!(shedder.check(first, pressure.transactions(), now), None);
+        assert_eq!(shedder.check(first, pressure.transactions(), now), None);
+
+        // Saturate the queue, which drained 60 transmissions over the last minute, i.e. one per second.
+        pressure.transactions().update(1000, 1000);
+        pressure.transactions().record_drained(60, now);
+        // Each IP is let through once per allowance interval, and is shed otherwise.
+        assert_eq!(shedder.check(first, pressure.transactions(), now), None);
+        assert_eq!(shedder.check(first, pressure.transactions(), now), Some(Duration::from_secs(60)));
+        assert_eq!(shedder.check(second, pressure.transactions(), now), None);
+        assert_eq!(shedder.check(second, pressure.transactions(), now), Some(Duration::from_secs(60)));
+        assert_eq!(pressure.transactions().num_shed(), 2);
+        // Once the interval lapses, the IP is let through again.
+        let later = now + Duration::from_secs(PRESSURE_ALLOWANCE_INTERVAL_IN_SECS);
+        assert_eq!(shedder.check(first, pressure.transactions(), later), None);
+    }
+}
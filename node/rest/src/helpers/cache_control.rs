@@ -0,0 +1,192 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use axum::{
+    body::Body,
+    http::{HeaderValue, Request, StatusCode, header::CACHE_CONTROL},
+    middleware::Next,
+    response::{IntoResponseParts, Response, ResponseParts},
+};
+use std::convert::Infallible;
+
+/// The `Cache-Control` directive of the immutable resources, which may be cached for a year.
+pub const CACHE_CONTROL_IMMUTABLE: &str = "public, max-age=31536000, immutable";
+/// The `Cache-Control` directive of the resources that follow the latest block, which may be cached briefly.
+pub const CACHE_CONTROL_LATEST: &str = "public, max-age=1";
+/// The `Cache-Control` directive of the volatile resources, which are never cached.
+pub const CACHE_CONTROL_VOLATILE: &str = "no-store";
+
+/// The mutability of the resource served by a handler, which determines how long it may be cached.
+///
+/// A handler annotates its response by returning its mutability alongside it, e.g. `(Mutability::Latest, json)`,
+/// and the `cache_control_middleware` sets the `Cache-Control` header accordingly.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Mutability {
+    /// The resource is addressed by a hash, or by the height of a committed block, so it never changes.
+    Immutable,
+    /// The resource follows the latest block, so it changes as the ledger advances.
+    Latest,
+    /// The resource may change at any time, e.g. the memory pool or the peers.
+    Volatile,
+}
+
+impl Mutability {
+    /// Returns the `Cache-Control` directive for this mutability.
+    pub const fn cache_control(self) -> &'static str {
+        match self {
+            Self::Immutable => CACHE_CONTROL_IMMUTABLE,
+            Self::Latest => CACHE_CONTROL_LATEST,
+            Self::Volatile => CACHE_CONTROL_VOLATILE,
+        }
+    }
+}
+
+impl IntoResponseParts for Mutability {
+    type Error = Infallible;
+
+    /// Annotates the response with this mutability, for the `cache_control_middleware`.
+    fn into_response_parts(self, mut response: ResponseParts) -> Result<ResponseParts, Self::Error> {
+        response.extensions_mut().insert(self);
+        Ok(response)
+    }
+}
+
+/// Sets the `Cache-Control` header of the responses, according to the mutability annotated by their handler.
+///
+/// The responses without an annotation, and the failed responses, are never cached. As a safeguard,
+/// the resources on a `latest` path are never cached as immutable, even if their handler says otherwise.
+pub async fn cache_control_middleware(request: Request<Body>, next: Next) -> Response {
+    let is_latest = request.uri().path().split('/').any(|segment| segment == "latest");
+
+    let mut response = next.run(request).await;
+
+    let is_cacheable = response.status().is_success() || response.status() == StatusCode::NOT_MODIFIED;
+    let mutability = match response.extensions().get::<Mutability>().copied() {
+        Some(Mutability::Immutable) if is_latest => Mutability::Latest,
+        Some(mutability) if is_cacheable => mutability,
+        _ => Mutability::Volatile,
+    };
+    response.headers_mut().insert(CACHE_CONTROL, HeaderValue::from_static(mutability.cache_control()));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{RestError, respond_json};
+
+    use axum::{
+        Router,
+        http::{
+            HeaderMap,
+            header::{ETAG, IF_NONE_MATCH},
+        },
+        middleware,
+        response::IntoResponse,
+        routing::get,
+    };
+    use std::sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    };
+    use tower::ServiceExt;
+
+    /// The hash of the immutable resource served by the test router.
+    const HASH: &str = "ab1hash";
+
+    /// Returns a router with a route per class of resource, and the number of loads of the immutable resource.
+    fn router() -> (Router, Arc<AtomicUsize>) {
+        let num_loads = Arc::new(AtomicUsize::new(0));
+        let num_loads_ = num_loads.clone();
+        let router = Router::new()
+            .route(
+                "/block/:hash",
+                get(move |headers: HeaderMap| async move {
+                    let response = respond_json(&headers, HASH, || {
+                        num_loads_.fetch_add(1, Ordering::SeqCst);
+                        Ok("block")
+                    });
+                    (Mutability::Immutable, response)
+                }),
+            )
+            .route("/block/latest", get(|| async { (Mutability::Latest, "latest block") }))
+            // A mis-annotated handler, to ensure the latest resources are never cached as immutable.
+            .route("/stateRoot/latest", get(|| async { (Mutability::Immutable, "latest state root") }))
+            .route("/memoryPool/transactions", get(|| async { (Mutability::Volatile, "transactions") }))
+            .route("/peers/all", get(|| async { "peers" }))
+            .route(
+                "/committee/:height",
+                get(|| async {
+                    (Mutability::Immutable, RestError::NotFound("Missing committee".to_string()).into_response())
+                }),
+            )
+            .layer(middleware::from_fn(cache_control_middleware));
+        (router, num_loads)
+    }
+
+    /// Returns the response to the given path, with the given `If-None-Match` header, if any.
+    async fn request(router: &Router, path: &str, if_none_match: Option<&str>) -> Response {
+        let mut request = Request::get(path);
+        if let Some(if_none_match) = if_none_match {
+            request = request.header(IF_NONE_MATCH, if_none_match);
+        }
+        router.clone().oneshot(request.body(Body::empty()).unwrap()).await.unwrap()
+    }
+
+    /// Returns the `Cache-Control` header of the response to the given path.
+    async fn cache_control(router: &Router, path: &str) -> String {
+        let response = request(router, path, None).await;
+        response.headers()[CACHE_CONTROL].to_str().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn test_cache_control_per_class() {
+        let (router, _) = router();
+        // Ensure the immutable resources are cached for a year.
+        assert_eq!(cache_control(&router, &format!("/block/{HASH}")).await, CACHE_CONTROL_IMMUTABLE);
+        // Ensure the latest resources are cached briefly, and never as immutable.
+        assert_eq!(cache_control(&router, "/block/latest").await, CACHE_CONTROL_LATEST);
+        assert_eq!(cache_control(&router, "/stateRoot/latest").await, CACHE_CONTROL_LATEST);
+        // Ensure the volatile, unannotated, and failed resources are never cached.
+        assert_eq!(cache_control(&router, "/memoryPool/transactions").await, CACHE_CONTROL_VOLATILE);
+        assert_eq!(cache_control(&router, "/peers/all").await, CACHE_CONTROL_VOLATILE);
+        assert_eq!(cache_control(&router, "/committee/1").await, CACHE_CONTROL_VOLATILE);
+    }
+
+    #[tokio::test]
+    async fn test_not_modified() {
+        let (router, num_loads) = router();
+        let path = format!("/block/{HASH}");
+
+        // Fetch the immutable resource, and retrieve its `ETag`.
+        let response = request(&router, &path, None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let etag = response.headers()[ETAG].to_str().unwrap().to_string();
+        assert!(etag.contains(HASH));
+        assert_eq!(num_loads.load(Ordering::SeqCst), 1);
+
+        // Ensure a conditional request with the matching `ETag` is not modified, without loading the resource.
+        let response = request(&router, &path, Some(&etag)).await;
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers()[ETAG], etag.as_str());
+        assert_eq!(response.headers()[CACHE_CONTROL], CACHE_CONTROL_IMMUTABLE);
+        assert_eq!(num_loads.load(Ordering::SeqCst), 1);
+
+        // Ensure a conditional request with another `ETag` loads the resource.
+        let response = request(&router, &path, Some("\"other\"")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(num_loads.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,170 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{
+    ledger::committee::Committee,
+    prelude::{Address, Field, Network},
+};
+
+use serde::{Deserialize, Serialize};
+
+/// The default number of committee members in a page.
+pub const DEFAULT_COMMITTEE_PAGE_SIZE: usize = 100;
+/// The maximum number of committee members in a page.
+pub const MAX_COMMITTEE_PAGE_SIZE: usize = 1_000;
+
+/// The query object of the committee endpoints.
+///
+/// If neither parameter is set, the committee is returned in full, in its snarkVM JSON encoding.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct CommitteePageQuery {
+    /// The number of members to skip, by the x-coordinate of their address.
+    pub offset: Option<usize>,
+    /// The maximum number of members to return.
+    pub limit: Option<usize>,
+}
+
+impl CommitteePageQuery {
+    /// Returns `true` if a page of the committee is requested.
+    pub const fn is_paginated(&self) -> bool {
+        self.offset.is_some() || self.limit.is_some()
+    }
+
+    /// Returns the offset and the (bounded) limit of the page.
+    pub fn bounds(&self) -> (usize, usize) {
+        let limit = self.limit.unwrap_or(DEFAULT_COMMITTEE_PAGE_SIZE).min(MAX_COMMITTEE_PAGE_SIZE);
+        (self.offset.unwrap_or(0), limit)
+    }
+}
+
+/// A member of a committee.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(bound = "")]
+pub struct CommitteeMember<N: Network> {
+    /// The address of the member.
+    pub address: Address<N>,
+    /// The stake of the member.
+    pub stake: u64,
+    /// Whether the member is open to delegators.
+    pub is_open: bool,
+    /// The commission of the member, in percent.
+    pub commission: u8,
+}
+
+/// A page of the members of a committee, sorted by the x-coordinate of their address.
+///
+/// The order of the addresses is independent of the stakes, so the pages of a committee
+/// partition its members, and a member keeps its position across the committees it is in.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(bound = "")]
+pub struct CommitteePage<N: Network> {
+    /// The ID of the committee.
+    pub id: Field<N>,
+    /// The starting round of the committee.
+    pub starting_round: u64,
+    /// The total stake of the committee.
+    pub total_stake: u64,
+    /// The number of members of the committee.
+    pub num_members: usize,
+    /// The number of members skipped.
+    pub offset: usize,
+    /// The members of the page.
+    pub members: Vec<CommitteeMember<N>>,
+    /// The offset of the next page, if there are more members.
+    pub next_offset: Option<usize>,
+}
+
+impl<N: Network> CommitteePage<N> {
+    /// Returns the page of the given committee, with up to `limit` members from the given offset.
+    pub fn new(committee: &Committee<N>, offset: usize, limit: usize) -> Self {
+        // Sort the members by the x-coordinate of their address, which identifies the address without encoding it,
+        // and without cloning the members of the committee.
+        let mut members: Vec<_> =
+            committee.members().iter().map(|(address, member)| (address.to_x_coordinate(), address, member)).collect();
+        members.sort_unstable_by(|(a, ..), (b, ..)| a.cmp(b));
+
+        let members: Vec<_> = members
+            .into_iter()
+            .skip(offset)
+            .take(limit)
+            .map(|(_, address, (stake, is_open, commission))| CommitteeMember {
+                address: *address,
+                stake: *stake,
+                is_open: *is_open,
+                commission: *commission,
+            })
+            .collect();
+        let end = offset.saturating_add(members.len());
+        Self {
+            id: committee.id(),
+            starting_round: committee.starting_round(),
+            total_stake: committee.total_stake(),
+            num_members: committee.num_members(),
+            offset,
+            next_offset: (end < committee.num_members()).then_some(end),
+            members,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::{ledger::committee::test_helpers::sample_committee_for_round_and_size, utilities::TestRng};
+
+    use std::collections::HashSet;
+
+    type CurrentNetwork = snarkvm::prelude::MainnetV0;
+
+    #[test]
+    fn test_committee_pages_partition_the_members() {
+        let rng = &mut TestRng::default();
+        let committee = sample_committee_for_round_and_size::<CurrentNetwork>(1, 25, rng);
+
+        for limit in [1, 4, 7, 25, 100] {
+            // Walk the pages, following the next offsets.
+            let mut addresses = Vec::new();
+            let mut offset = Some(0);
+            while let Some(next) = offset {
+                let page = CommitteePage::new(&committee, next, limit);
+                assert_eq!(page.offset, next);
+                assert_eq!(page.num_members, 25);
+                assert!(!page.members.is_empty() && page.members.len() <= limit);
+                addresses.extend(page.members.iter().map(|member| member.address));
+                offset = page.next_offset;
+            }
+            // Ensure the pages are complete and non-overlapping, in a stable order.
+            assert_eq!(addresses.len(), committee.num_members());
+            assert_eq!(addresses.iter().collect::<HashSet<_>>().len(), committee.num_members());
+            assert!(addresses.iter().all(|address| committee.members().contains_key(address)));
+            assert!(addresses.windows(2).all(|pair| pair[0].to_x_coordinate() < pair[1].to_x_coordinate()));
+        }
+
+        // Ensure a page past the end is empty.
+        let page = CommitteePage::new(&committee, 25, 10);
+        assert!(page.members.is_empty());
+        assert_eq!(page.next_offset, None);
+    }
+
+    #[test]
+    fn test_committee_page_query_bounds() {
+        assert!(!CommitteePageQuery::default().is_paginated());
+        let query = CommitteePageQuery { offset: Some(10), limit: None };
+        assert!(query.is_paginated());
+        assert_eq!(query.bounds(), (10, DEFAULT_COMMITTEE_PAGE_SIZE));
+        let query = CommitteePageQuery { offset: None, limit: Some(usize::MAX) };
+        assert_eq!(query.bounds(), (0, MAX_COMMITTEE_PAGE_SIZE));
+    }
+}
@@ -13,22 +13,210 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use snarkos_node_bft_ledger_service::LedgerReadError;
+use snarkos_node_router::TraceScopeError;
+
 use axum::{
-    http::StatusCode,
+    Json,
+    http::{StatusCode, header::RETRY_AFTER},
     response::{IntoResponse, Response},
 };
+use serde_json::json;
+
+/// The error message of the routes that are unavailable while the node is in safe mode.
+pub const SAFE_MODE_ERROR: &str = "Route isn't available in safe mode (networking is disabled)";
+
+/// The number of seconds after which a request that failed to read from the ledger may be retried.
+const RETRY_AFTER_IN_SECS: u64 = 1;
+
+/// The error code of a request with a malformed path parameter.
+pub const INVALID_PARAMETER_CODE: &str = "invalid_parameter";
 
 /// An enum of error handlers for the REST API server.
-pub struct RestError(pub String);
+#[derive(Debug)]
+pub enum RestError {
+    /// The request failed.
+    InternalServerError(String),
+    /// The request is malformed.
+    BadRequest(String),
+    /// The request exceeds the rate limit, so it may be retried later.
+    TooManyRequests(String),
+    /// The requested data is definitively absent from the ledger.
+    NotFound(String),
+    /// The requested data was in the ledger, yet it is no longer stored, e.g. as it was pruned.
+    Gone(String),
+    /// The ledger failed to read the requested data transiently, so the request may be retried.
+    ServiceUnavailable(String),
+    /// The route is disabled by the configuration of the node, e.g. in safe mode, so the request is not retried.
+    Disabled(String),
+    /// The path or query parameter of the given name is malformed, and was not of the expected format.
+    InvalidParameter { parameter: String, expected: &'static str },
+}
 
 impl IntoResponse for RestError {
     fn into_response(self) -> Response {
-        (StatusCode::INTERNAL_SERVER_ERROR, format!("Something went wrong: {}", self.0)).into_response()
+        match self {
+            Self::InternalServerError(message) => {
+                (StatusCode::INTERNAL_SERVER_ERROR, format!("Something went wrong: {message}")).into_response()
+            }
+            Self::BadRequest(message) => (StatusCode::BAD_REQUEST, message).into_response(),
+            Self::TooManyRequests(message) => {
+                (StatusCode::TOO_MANY_REQUESTS, [(RETRY_AFTER, RETRY_AFTER_IN_SECS.to_string())], message)
+                    .into_response()
+            }
+            Self::NotFound(message) => (StatusCode::NOT_FOUND, message).into_response(),
+            Self::Gone(message) => (StatusCode::GONE, message).into_response(),
+            Self::ServiceUnavailable(message) => {
+                (StatusCode::SERVICE_UNAVAILABLE, [(RETRY_AFTER, RETRY_AFTER_IN_SECS.to_string())], message)
+                    .into_response()
+            }
+            Self::Disabled(message) => (StatusCode::SERVICE_UNAVAILABLE, message).into_response(),
+            Self::InvalidParameter { parameter, expected } => {
+                let message = format!("Invalid parameter '{parameter}' - expected {expected}");
+                let error = json!({
+                    "code": INVALID_PARAMETER_CODE,
+                    "parameter": parameter,
+                    "expected": expected,
+                    "message": message,
+                });
+                (StatusCode::BAD_REQUEST, Json(json!({ "error": error }))).into_response()
+            }
+        }
+    }
+}
+
+impl From<TraceScopeError> for RestError {
+    fn from(err: TraceScopeError) -> Self {
+        match err {
+            TraceScopeError::InvalidDuration => Self::BadRequest(err.to_string()),
+            TraceScopeError::Full => Self::TooManyRequests(err.to_string()),
+        }
     }
 }
 
 impl From<anyhow::Error> for RestError {
     fn from(err: anyhow::Error) -> Self {
-        Self(err.to_string())
+        match LedgerReadError::of(&err) {
+            Some(LedgerReadError::Absent(_)) => Self::NotFound(err.to_string()),
+            Some(LedgerReadError::Transient(_)) => Self::ServiceUnavailable(err.to_string()),
+            Some(LedgerReadError::Pruned(_)) => Self::Gone(err.to_string()),
+            Some(LedgerReadError::Corrupt(_)) | None => Self::InternalServerError(err.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Height, Param, respond_json};
+    use snarkos_node_bft_ledger_service::classify_read;
+
+    use axum::{
+        Router,
+        body::Body,
+        http::{HeaderMap, Request},
+        routing::get,
+    };
+    use std::io;
+    use tower::ServiceExt;
+
+    /// The latest block height of the stub ledger.
+    const LATEST_HEIGHT: u32 = 10;
+    /// The block height below which the stub ledger pruned its blocks.
+    const PRUNED_HEIGHT: u32 = 3;
+    /// The block height that the stub ledger fails to read transiently.
+    const TRANSIENT_HEIGHT: u32 = 7;
+    /// The block height that the stub ledger fails to decode.
+    const CORRUPT_HEIGHT: u32 = 8;
+
+    /// Reads the block at the given height from the stub ledger, injecting the failures of each height.
+    fn read_stub_block(height: u32) -> anyhow::Result<u32> {
+        match height {
+            height if height < PRUNED_HEIGHT || height > LATEST_HEIGHT => {
+                Err(anyhow::anyhow!("Missing block {height}"))
+            }
+            TRANSIENT_HEIGHT => Err(anyhow::anyhow!("Compaction in progress")),
+            CORRUPT_HEIGHT => Err(io::Error::new(io::ErrorKind::InvalidData, "Invalid block bytes").into()),
+            height => Ok(height),
+        }
+    }
+
+    /// Returns the status and the `Retry-After` header of the response to a request for the block at the given height,
+    /// through a route that reads the block from the stub ledger.
+    async fn request_block(height: &str) -> (StatusCode, Option<String>) {
+        let router = Router::new().route(
+            "/block/:height",
+            get(|headers: HeaderMap, Param(Height(height)): Param<Height>| async move {
+                respond_json(&headers, height, || {
+                    classify_read(read_stub_block(height), height, || LATEST_HEIGHT, || Ok(height >= PRUNED_HEIGHT))
+                })
+            }),
+        );
+        let request = Request::get(format!("/block/{height}")).body(Body::empty()).unwrap();
+        let response = router.oneshot(request).await.unwrap();
+        let retry_after = response.headers().get(RETRY_AFTER).map(|value| value.to_str().unwrap().to_string());
+        (response.status(), retry_after)
+    }
+
+    #[tokio::test]
+    async fn test_routes_with_injected_read_failures() {
+        // Ensure a stored block is served.
+        assert_eq!(request_block("5").await, (StatusCode::OK, None));
+        // Ensure a block beyond the latest block is not found.
+        assert_eq!(request_block("11").await, (StatusCode::NOT_FOUND, None));
+        // Ensure a pruned block is gone.
+        assert_eq!(request_block("2").await, (StatusCode::GONE, None));
+        // Ensure a transient failure is unavailable, and may be retried.
+        assert_eq!(request_block("7").await, (StatusCode::SERVICE_UNAVAILABLE, Some("1".to_string())));
+        // Ensure a corrupt block is an internal error, which is not retried.
+        assert_eq!(request_block("8").await, (StatusCode::INTERNAL_SERVER_ERROR, None));
+        // Ensure a malformed height is a bad request.
+        assert_eq!(request_block("-1").await, (StatusCode::BAD_REQUEST, None));
+    }
+
+    #[test]
+    fn test_ledger_read_errors() {
+        // Ensure an absent block is not found.
+        let response =
+            RestError::from(anyhow::Error::from(LedgerReadError::Absent("beyond".to_string()))).into_response();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        // Ensure a transient failure is unavailable, and may be retried.
+        let error = anyhow::Error::from(LedgerReadError::Transient("compaction".to_string()));
+        let response = RestError::from(error).into_response();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(response.headers().get(RETRY_AFTER).unwrap(), "1");
+
+        // Ensure a pruned block is gone, and a corrupt block is an internal error.
+        let response =
+            RestError::from(anyhow::Error::from(LedgerReadError::Pruned("pruned".to_string()))).into_response();
+        assert_eq!(response.status(), StatusCode::GONE);
+        let error = anyhow::Error::from(LedgerReadError::Corrupt("undecodable".to_string()));
+        assert_eq!(RestError::from(error).into_response().status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+        // Ensure a disabled route is unavailable, and is not retried.
+        let response = RestError::Disabled(SAFE_MODE_ERROR.to_string()).into_response();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert!(response.headers().get(RETRY_AFTER).is_none());
+
+        // Ensure any other failure is an internal error.
+        let response = RestError::from(anyhow::anyhow!("unclassified")).into_response();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(response.headers().get(RETRY_AFTER).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_parameter_envelope() {
+        let error = RestError::InvalidParameter { parameter: "height".to_string(), expected: "a block height" };
+        let response = error.into_response();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        // Ensure the envelope names the parameter and its expected format.
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["error"]["code"], INVALID_PARAMETER_CODE);
+        assert_eq!(body["error"]["parameter"], "height");
+        assert_eq!(body["error"]["expected"], "a block height");
+        assert_eq!(body["error"]["message"], "Invalid parameter 'height' - expected a block height");
     }
 }
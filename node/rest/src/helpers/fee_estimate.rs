@@ -0,0 +1,231 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{Ledger, Network, block::Transaction, store::ConsensusStorage};
+
+use anyhow::Result;
+use indexmap::IndexMap;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::sync::Arc;
+
+/// The default number of most recent blocks the historical priority fees are aggregated over.
+pub const DEFAULT_FEE_ESTIMATE_NUM_BLOCKS: u32 = 50;
+/// The maximum number of most recent blocks the historical priority fees can be aggregated over.
+pub const MAX_FEE_ESTIMATE_NUM_BLOCKS: u32 = 500;
+
+/// The percentiles of a set of priority fees, in microcredits.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct FeePercentiles {
+    /// The number of transactions the percentiles are computed over.
+    pub num_transactions: usize,
+    /// The lowest priority fee.
+    pub min: u64,
+    /// The 25th percentile of the priority fees.
+    pub p25: u64,
+    /// The median priority fee.
+    pub p50: u64,
+    /// The 75th percentile of the priority fees.
+    pub p75: u64,
+    /// The highest priority fee.
+    pub max: u64,
+}
+
+impl FeePercentiles {
+    /// Returns the percentiles of the given priority fees, or `None` if there are none.
+    ///
+    /// Each percentile is the fee at the nearest rank below it, so that it is always one of the given fees.
+    pub fn new(mut fees: Vec<u64>) -> Option<Self> {
+        if fees.is_empty() {
+            return None;
+        }
+        fees.sort_unstable();
+        let percentile = |percent: usize| fees[(fees.len() - 1) * percent / 100];
+        Some(Self {
+            num_transactions: fees.len(),
+            min: percentile(0),
+            p25: percentile(25),
+            p50: percentile(50),
+            p75: percentile(75),
+            max: percentile(100),
+        })
+    }
+}
+
+/// Returns the priority fees of the given transactions, in microcredits.
+pub fn priority_fees<'a, N: Network>(transactions: impl IntoIterator<Item = &'a Transaction<N>>) -> Vec<u64> {
+    transactions.into_iter().filter_map(|transaction| transaction.priority_fee_amount().ok()).map(|fee| *fee).collect()
+}
+
+/// The priority fees paid by the unconfirmed and the recently included transactions, to help choose a priority fee.
+///
+/// Note: Inclusion is not ordered by the priority fee, unless the validators prioritize the transactions by fee.
+/// A higher fee than the recent transactions is therefore no guarantee of a faster inclusion.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct FeeEstimate {
+    /// Whether this node sends the unconfirmed transactions to the BFT in the order of their priority fees.
+    pub is_fee_prioritized: bool,
+    /// The number of unconfirmed transactions in the memory pool, if this node is a validator.
+    pub queue_depth: Option<usize>,
+    /// The priority fees of the unconfirmed transactions counted in the queue depth, if there are any.
+    /// Note: A transaction that was reinserted into the memory pool without being deserialized has no known fee.
+    pub memory_pool: Option<FeePercentiles>,
+    /// The height of the latest block.
+    pub latest_height: u32,
+    /// The number of most recent blocks the historical priority fees are aggregated over.
+    pub num_blocks: u32,
+    /// The priority fees of the transactions in the most recent blocks, if there are any.
+    pub recent_blocks: Option<FeePercentiles>,
+}
+
+/// A bounded cache of the priority fees of the transactions in the most recent blocks, keyed by the block hash,
+/// so that the historical priority fees are only loaded from the ledger once per block.
+#[derive(Default)]
+pub struct BlockFees<N: Network> {
+    /// The priority fees of the transactions in each block, from the oldest cached block.
+    fees: Mutex<IndexMap<N::BlockHash, Arc<Vec<u64>>>>,
+}
+
+impl<N: Network> BlockFees<N> {
+    /// Returns the latest height, and the priority fees of the transactions in the given number of most recent blocks.
+    pub fn load<C: ConsensusStorage<N>>(&self, ledger: &Ledger<N, C>, num_blocks: u32) -> Result<(u32, Vec<u64>)> {
+        let latest_height = ledger.latest_height();
+        let start_height = latest_height.saturating_add(1).saturating_sub(num_blocks.min(MAX_FEE_ESTIMATE_NUM_BLOCKS));
+        let mut fees = Vec::new();
+        for height in start_height..=latest_height {
+            fees.extend_from_slice(&self.get(ledger, height)?);
+        }
+        Ok((latest_height, fees))
+    }
+
+    /// Returns the number of cached blocks.
+    pub fn len(&self) -> usize {
+        self.fees.lock().len()
+    }
+
+    /// Returns `true` if no blocks are cached.
+    pub fn is_empty(&self) -> bool {
+        self.fees.lock().is_empty()
+    }
+
+    /// Returns the priority fees of the transactions in the block at the given height, loading them if needed.
+    fn get<C: ConsensusStorage<N>>(&self, ledger: &Ledger<N, C>, height: u32) -> Result<Arc<Vec<u64>>> {
+        // Note: The block hash identifies the block, even if the ledger was rolled back and advanced since.
+        let hash = ledger.get_hash(height)?;
+        if let Some(fees) = self.fees.lock().get(&hash) {
+            return Ok(fees.clone());
+        }
+        // Load the transactions outside of the lock, so that concurrent requests are not blocked.
+        let transactions = ledger.get_transactions(hash)?;
+        let fees = Arc::new(priority_fees(transactions.iter().map(|confirmed| confirmed.transaction())));
+        // Cache the fees, evicting the oldest cached blocks.
+        let mut cache = self.fees.lock();
+        cache.insert(hash, fees.clone());
+        while cache.len() > MAX_FEE_ESTIMATE_NUM_BLOCKS as usize {
+            cache.shift_remove_index(0);
+        }
+        Ok(fees)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkos_node_bft_ledger_service::test_helpers::sample_ledger;
+    use snarkvm::{
+        console::{
+            program::{Identifier, Literal, ProgramID, Value},
+            types::U64,
+        },
+        prelude::{Address, MainnetV0},
+    };
+
+    use rand::SeedableRng;
+    use rand_chacha::ChaChaRng;
+    use std::str::FromStr;
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn test_fee_percentiles() {
+        // Ensure there are no percentiles without fees.
+        assert_eq!(FeePercentiles::new(vec![]), None);
+
+        // Ensure a single fee is every percentile.
+        let percentiles = FeePercentiles::new(vec![7]).unwrap();
+        assert_eq!(
+            (percentiles.min, percentiles.p25, percentiles.p50, percentiles.p75, percentiles.max),
+            (7, 7, 7, 7, 7)
+        );
+
+        // Ensure the fees are sorted, and each percentile is the fee at the nearest rank below it.
+        let percentiles = FeePercentiles::new(vec![50, 10, 40, 20, 30]).unwrap();
+        assert_eq!(percentiles, FeePercentiles { num_transactions: 5, min: 10, p25: 20, p50: 30, p75: 40, max: 50 });
+        let percentiles = FeePercentiles::new((1..=100).rev().collect()).unwrap();
+        assert_eq!(percentiles, FeePercentiles { num_transactions: 100, min: 1, p25: 25, p50: 50, p75: 75, max: 100 });
+    }
+
+    #[test]
+    fn test_block_fees() {
+        let rng = &mut ChaChaRng::seed_from_u64(1234567890u64);
+
+        // Initialize the ledger.
+        let (private_key, ledger) = sample_ledger::<CurrentNetwork, _>(0, rng);
+        let address = Address::try_from(&private_key).unwrap();
+
+        // Prepare public transfers with known priority fees.
+        let locator = (ProgramID::from_str("credits.aleo").unwrap(), Identifier::from_str("transfer_public").unwrap());
+        let transfer = |priority_fee, rng: &mut ChaChaRng| {
+            let inputs = [Value::from(Literal::Address(address)), Value::from(Literal::U64(U64::new(1)))];
+            ledger.vm().execute(&private_key, locator, inputs.into_iter(), None, priority_fee, None, rng).unwrap()
+        };
+        let queued = vec![transfer(300, rng), transfer(100, rng), transfer(200, rng)];
+
+        // Ensure the priority fees of the queued transactions are aggregated.
+        let percentiles = FeePercentiles::new(priority_fees(&queued)).unwrap();
+        assert_eq!(percentiles, FeePercentiles {
+            num_transactions: 3,
+            min: 100,
+            p25: 100,
+            p50: 200,
+            p75: 200,
+            max: 300
+        });
+
+        // Include the first two transactions in a block, and the last one in the next block.
+        for transactions in [queued[..2].to_vec(), queued[2..].to_vec()] {
+            let block =
+                ledger.prepare_advance_to_next_beacon_block(&private_key, vec![], vec![], transactions, rng).unwrap();
+            ledger.advance_to_next_block(&block).unwrap();
+        }
+        assert_eq!(ledger.latest_height(), 2);
+
+        // Ensure the historical priority fees are aggregated over the most recent blocks.
+        let block_fees = BlockFees::<CurrentNetwork>::default();
+        let (latest_height, fees) = block_fees.load(&ledger, 2).unwrap();
+        assert_eq!(latest_height, 2);
+        assert_eq!(FeePercentiles::new(fees).unwrap(), percentiles);
+        assert_eq!(block_fees.len(), 2);
+        let (_, fees) = block_fees.load(&ledger, 1).unwrap();
+        assert_eq!(fees, vec![200]);
+
+        // Ensure no blocks are aggregated if none are requested.
+        assert!(block_fees.load(&ledger, 0).unwrap().1.is_empty());
+        // Ensure the aggregation stops at the genesis block, and reuses the cached blocks.
+        let (_, fees) = block_fees.load(&ledger, 10).unwrap();
+        assert!(fees.len() >= 3);
+        assert_eq!(block_fees.len(), 3);
+    }
+}
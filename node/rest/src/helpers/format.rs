@@ -0,0 +1,224 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::RestError;
+use snarkvm::prelude::ToBytes;
+
+use anyhow::Result;
+use axum::{
+    http::{
+        HeaderMap,
+        StatusCode,
+        header::{ACCEPT, CONTENT_TYPE, ETAG, IF_NONE_MATCH, VARY},
+    },
+    response::{IntoResponse, Response},
+};
+use axum_extra::response::ErasedJson;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// The media type of the canonical (little-endian) byte encoding.
+pub const OCTET_STREAM: &str = "application/octet-stream";
+
+/// The query object for endpoints that support content negotiation.
+#[derive(Clone, Default, Deserialize, Serialize)]
+pub struct FormatQuery {
+    /// The requested output format; `bytes` selects the canonical byte encoding.
+    format: Option<String>,
+}
+
+/// The output format of a response.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// The JSON encoding.
+    Json,
+    /// The canonical (little-endian) byte encoding.
+    Bytes,
+}
+
+impl OutputFormat {
+    /// Returns the output format requested with either the `format` query parameter, or the `Accept` header.
+    ///
+    /// Returns an error if the `format` query parameter is neither `json` nor `bytes`.
+    pub fn negotiate(headers: &HeaderMap, query: &FormatQuery) -> Result<Self, RestError> {
+        // The query parameter takes precedence over the `Accept` header.
+        if let Some(format) = &query.format {
+            return match format.as_str() {
+                "json" => Ok(Self::Json),
+                "bytes" => Ok(Self::Bytes),
+                _ => {
+                    Err(RestError::InvalidParameter { parameter: "format".to_string(), expected: "`json` or `bytes`" })
+                }
+            };
+        }
+        // Check if any of the accepted media types is the byte encoding, ignoring any parameters.
+        let accepts_bytes = headers.get(ACCEPT).and_then(|accept| accept.to_str().ok()).is_some_and(|accept| {
+            accept.split(',').any(|media_type| media_type.split(';').next().is_some_and(|m| m.trim() == OCTET_STREAM))
+        });
+        match accepts_bytes {
+            true => Ok(Self::Bytes),
+            false => Ok(Self::Json),
+        }
+    }
+
+    /// Returns the `ETag` of the object with the given hash, in this output format.
+    ///
+    /// Note: The output formats are distinct representations of the object, so their tags must differ.
+    pub fn etag(self, hash: impl Display) -> String {
+        match self {
+            Self::Json => format!("\"{hash}.json\""),
+            Self::Bytes => format!("\"{hash}\""),
+        }
+    }
+
+    /// Returns the response for the object with the given hash, in this output format.
+    ///
+    /// The response is tagged with an `ETag` derived from the hash, and returns `304 Not Modified`
+    /// without loading the object if the request's `If-None-Match` header matches it.
+    pub fn respond<T: Serialize + ToBytes>(
+        self,
+        headers: &HeaderMap,
+        hash: impl Display,
+        load: impl FnOnce() -> Result<T>,
+    ) -> Result<Response, RestError> {
+        let etag = self.etag(hash);
+        // The output format is negotiated with the `Accept` header, so the caches must key on it.
+        let vary = (VARY, ACCEPT.to_string());
+        // If the client already has this object, return early.
+        if is_not_modified(headers, &etag) {
+            return Ok((StatusCode::NOT_MODIFIED, [(ETAG, etag), vary]).into_response());
+        }
+        match self {
+            Self::Json => Ok(([(ETAG, etag), vary], ErasedJson::pretty(load()?)).into_response()),
+            Self::Bytes => {
+                // Serialize straight from the object, bypassing JSON.
+                let bytes = load()?.to_bytes_le()?;
+                Ok(([(CONTENT_TYPE, OCTET_STREAM.to_string()), (ETAG, etag), vary], bytes).into_response())
+            }
+        }
+    }
+}
+
+/// Returns the JSON response for the object with the given hash, for the endpoints without content negotiation.
+///
+/// The response is tagged with an `ETag` derived from the hash, and returns `304 Not Modified`
+/// without loading the object if the request's `If-None-Match` header matches it.
+pub fn respond_json<T: Serialize>(
+    headers: &HeaderMap,
+    hash: impl Display,
+    load: impl FnOnce() -> Result<T>,
+) -> Result<Response, RestError> {
+    let etag = OutputFormat::Json.etag(hash);
+    // If the client already has this object, return early.
+    if is_not_modified(headers, &etag) {
+        return Ok((StatusCode::NOT_MODIFIED, [(ETAG, etag)]).into_response());
+    }
+    Ok(([(ETAG, etag)], ErasedJson::pretty(load()?)).into_response())
+}
+
+/// Returns `true` if the request's `If-None-Match` header matches the given ETag.
+fn is_not_modified(headers: &HeaderMap, etag: &str) -> bool {
+    headers.get_all(IF_NONE_MATCH).iter().filter_map(|value| value.to_str().ok()).any(|value| {
+        value.split(',').map(str::trim).any(|candidate| candidate == "*" || candidate.trim_start_matches("W/") == etag)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{FromBytes, MainnetV0, Network, block::Block};
+
+    use axum::{body::to_bytes, http::HeaderValue};
+
+    type CurrentNetwork = MainnetV0;
+
+    /// Returns the body of the given response.
+    async fn body(response: Response) -> Vec<u8> {
+        to_bytes(response.into_body(), usize::MAX).await.unwrap().to_vec()
+    }
+
+    #[test]
+    fn test_negotiate() {
+        let mut headers = HeaderMap::new();
+        let bytes_query = FormatQuery { format: Some("bytes".to_string()) };
+
+        // Ensure JSON is the default.
+        assert_eq!(OutputFormat::negotiate(&headers, &FormatQuery::default()).ok(), Some(OutputFormat::Json));
+        assert_eq!(OutputFormat::negotiate(&headers, &bytes_query).ok(), Some(OutputFormat::Bytes));
+
+        // Ensure the byte encoding is selected by the `Accept` header.
+        headers.insert(ACCEPT, HeaderValue::from_static("text/html, application/octet-stream;q=0.9"));
+        assert_eq!(OutputFormat::negotiate(&headers, &FormatQuery::default()).ok(), Some(OutputFormat::Bytes));
+
+        // Ensure the query parameter takes precedence.
+        let json_query = FormatQuery { format: Some("json".to_string()) };
+        assert_eq!(OutputFormat::negotiate(&headers, &json_query).ok(), Some(OutputFormat::Json));
+    }
+
+    #[tokio::test]
+    async fn test_negotiate_unknown_format() {
+        // Ensure an unknown format is rejected, rather than falling back to JSON.
+        let query = FormatQuery { format: Some("byte".to_string()) };
+        let error = OutputFormat::negotiate(&HeaderMap::new(), &query).unwrap_err();
+        let response = error.into_response();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = serde_json::from_slice(&body(response).await).unwrap();
+        assert_eq!(body["error"]["code"], "invalid_parameter");
+        assert_eq!(body["error"]["parameter"], "format");
+        assert_eq!(body["error"]["expected"], "`json` or `bytes`");
+    }
+
+    #[tokio::test]
+    async fn test_block_in_both_formats() {
+        let block = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let headers = HeaderMap::new();
+
+        // Fetch the block as JSON.
+        let response = OutputFormat::Json.respond(&headers, block.hash(), || Ok(block.clone())).ok().unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[ETAG], format!("\"{}.json\"", block.hash()).as_str());
+        assert_eq!(response.headers()[VARY], ACCEPT.as_str());
+        let json_block: Block<CurrentNetwork> = serde_json::from_slice(&body(response).await).unwrap();
+
+        // Fetch the block as bytes.
+        let response = OutputFormat::Bytes.respond(&headers, block.hash(), || Ok(block.clone())).ok().unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[CONTENT_TYPE], OCTET_STREAM);
+        let etag = response.headers()[ETAG].clone();
+        assert_eq!(etag, format!("\"{}\"", block.hash()).as_str());
+        assert_eq!(response.headers()[VARY], ACCEPT.as_str());
+        let bytes_block = Block::<CurrentNetwork>::from_bytes_le(&body(response).await).unwrap();
+
+        // Ensure both formats decode to the same block.
+        assert_eq!(json_block, block);
+        assert_eq!(bytes_block, block);
+
+        // Ensure a conditional request with the matching ETag is not modified, and does not load the block.
+        let mut headers = HeaderMap::new();
+        headers.insert(IF_NONE_MATCH, etag.clone());
+        let response = OutputFormat::Bytes
+            .respond(&headers, block.hash(), || -> Result<Block<CurrentNetwork>> { panic!("The block was loaded") })
+            .ok()
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers()[ETAG], etag);
+        assert!(body(response).await.is_empty());
+
+        // Ensure a conditional request with a different ETag returns the block.
+        headers.insert(IF_NONE_MATCH, HeaderValue::from_static("\"other\""));
+        let response = OutputFormat::Bytes.respond(&headers, block.hash(), || Ok(block.clone())).ok().unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
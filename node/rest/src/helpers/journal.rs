@@ -0,0 +1,376 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::Result;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{File, OpenOptions},
+    io::{BufRead, BufReader, BufWriter, Write},
+    net::IpAddr,
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+use tokio::sync::mpsc;
+
+/// The maximum number of entries that may be queued for the journal writer.
+const JOURNAL_CHANNEL_CAPACITY: usize = 1024;
+/// The maximum number of entries written before the journal file is synced to disk.
+const JOURNAL_BATCH_SIZE: usize = 128;
+/// The default maximum size in bytes of the journal file, before it is rotated.
+pub const MAX_JOURNAL_FILE_SIZE: u64 = 64 * 1024 * 1024;
+/// The maximum number of entries returned by a journal query.
+pub const MAX_JOURNAL_ENTRIES: usize = 1000;
+
+/// The kind of broadcast recorded in the journal.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BroadcastKind {
+    Transaction,
+    Solution,
+}
+
+/// A record of a broadcast accepted by the REST server.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct JournalEntry {
+    /// The UNIX timestamp of when the broadcast was accepted.
+    pub timestamp: i64,
+    /// The kind of broadcast.
+    pub kind: BroadcastKind,
+    /// The transaction or solution ID.
+    pub id: String,
+    /// The checksum of the transaction or solution.
+    pub checksum: String,
+    /// The IP address of the client that submitted the broadcast.
+    pub source_ip: IpAddr,
+    /// The size of the transaction or solution in bytes.
+    pub num_bytes: usize,
+    /// The idempotency key provided by the client, if any.
+    pub idempotency_key: Option<String>,
+}
+
+/// An append-only journal of the broadcasts accepted by the REST server.
+///
+/// Entries are handed to a dedicated writer thread through a bounded channel, so recording an entry never
+/// blocks the broadcast path; if the writer is unable to keep up, the entry is dropped and counted instead.
+/// Once the journal file exceeds its maximum size, it is rotated to `<path>.1`, replacing any previous rotation.
+pub struct BroadcastJournal {
+    /// The path to the journal file.
+    path: PathBuf,
+    /// The sender for the journal writer.
+    sender: mpsc::Sender<JournalEntry>,
+    /// The number of entries dropped because the writer could not keep up.
+    num_dropped: Arc<AtomicU64>,
+    /// The lock held by the writer while rotating, and by readers while reading.
+    rotation_lock: Arc<RwLock<()>>,
+}
+
+impl BroadcastJournal {
+    /// Opens the journal at the given path, and spawns the journal writer.
+    pub fn open(path: PathBuf, max_file_size: u64) -> Result<Self> {
+        let (sender, rotation_lock) = spawn_journal_writer("broadcast journal", &path, max_file_size)?;
+        Ok(Self { path, sender, num_dropped: Default::default(), rotation_lock })
+    }
+
+    /// Returns the path to the journal file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the number of entries dropped because the writer could not keep up.
+    pub fn num_dropped(&self) -> u64 {
+        self.num_dropped.load(Ordering::Relaxed)
+    }
+
+    /// Records the given entry, without blocking.
+    pub fn record(&self, entry: JournalEntry) {
+        if self.sender.try_send(entry).is_err() {
+            self.num_dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Returns the most recent entries (up to `MAX_JOURNAL_ENTRIES`) with a timestamp at or after `since`,
+    /// across the current and rotated journal files.
+    pub fn entries_since(&self, since: i64) -> Result<Vec<JournalEntry>> {
+        let _guard = self.rotation_lock.read();
+
+        let mut entries = Vec::new();
+        for path in [rotated_path(&self.path), self.path.clone()] {
+            // The rotated journal file may not exist yet.
+            if !path.exists() {
+                continue;
+            }
+            for line in BufReader::new(File::open(&path)?).lines() {
+                // Skip any partially-written or malformed lines.
+                match serde_json::from_str::<JournalEntry>(&line?) {
+                    Ok(entry) if entry.timestamp >= since => entries.push(entry),
+                    _ => continue,
+                }
+            }
+        }
+        // Retain only the most recent entries.
+        let num_skipped = entries.len().saturating_sub(MAX_JOURNAL_ENTRIES);
+        entries.drain(..num_skipped);
+        Ok(entries)
+    }
+}
+
+/// Opens the journal file at the given path, and spawns its writer on a dedicated thread, as its I/O is blocking.
+///
+/// Returns the sender for the writer, and the lock held by the writer while rotating the journal file.
+pub(crate) fn spawn_journal_writer<T: Serialize + Send + 'static>(
+    name: &'static str,
+    path: &Path,
+    max_file_size: u64,
+) -> Result<(mpsc::Sender<T>, Arc<RwLock<()>>)> {
+    // Open the journal file, to surface any errors before the writer is spawned.
+    let file = open_append(path)?;
+    let (sender, receiver) = mpsc::channel(JOURNAL_CHANNEL_CAPACITY);
+    let rotation_lock: Arc<RwLock<()>> = Default::default();
+
+    let writer = JournalWriter { name, path: path.to_path_buf(), max_file_size, rotation_lock: rotation_lock.clone() };
+    std::thread::Builder::new().name(name.replace(' ', "-")).spawn(move || writer.run(file, receiver))?;
+    Ok((sender, rotation_lock))
+}
+
+/// The writer of a journal, which runs on a dedicated thread.
+struct JournalWriter {
+    /// The name of the journal.
+    name: &'static str,
+    /// The path to the journal file.
+    path: PathBuf,
+    /// The maximum size in bytes of the journal file, before it is rotated.
+    max_file_size: u64,
+    /// The lock held by the writer while rotating, and by readers while reading.
+    rotation_lock: Arc<RwLock<()>>,
+}
+
+impl JournalWriter {
+    /// Writes the received entries to the journal, until all senders are dropped.
+    fn run<T: Serialize>(self, file: File, mut receiver: mpsc::Receiver<T>) {
+        let mut size = file.metadata().map(|metadata| metadata.len()).unwrap_or_default();
+        let mut writer = BufWriter::new(file);
+        // Whether the last attempt to rotate the journal file failed, so that the failure is only logged once.
+        let mut is_rotation_failing = false;
+
+        while let Some(entry) = receiver.blocking_recv() {
+            // Collect any other queued entries into the same batch.
+            let mut batch = vec![entry];
+            while batch.len() < JOURNAL_BATCH_SIZE {
+                match receiver.try_recv() {
+                    Ok(entry) => batch.push(entry),
+                    Err(_) => break,
+                }
+            }
+
+            for entry in batch {
+                let mut line = match serde_json::to_vec(&entry) {
+                    Ok(line) => line,
+                    Err(error) => {
+                        warn!("Failed to serialize an entry of the {} - {error}", self.name);
+                        continue;
+                    }
+                };
+                line.push(b'\n');
+
+                // Rotate the journal file if this entry would exceed the maximum size.
+                // If the rotation fails, keep appending to the current journal file, and retry on the next entry.
+                if size > 0 && size + line.len() as u64 > self.max_file_size {
+                    match self.rotate(&mut writer) {
+                        Ok(file) => {
+                            writer = BufWriter::new(file);
+                            size = 0;
+                            is_rotation_failing = false;
+                        }
+                        Err(error) => {
+                            if !is_rotation_failing {
+                                error!(
+                                    "Failed to rotate the {}, appending beyond its maximum size - {error}",
+                                    self.name
+                                );
+                            }
+                            is_rotation_failing = true;
+                        }
+                    }
+                }
+
+                match writer.write_all(&line) {
+                    Ok(()) => size += line.len() as u64,
+                    Err(error) => warn!("Failed to write to the {} - {error}", self.name),
+                }
+            }
+
+            // Sync the batch to disk.
+            if let Err(error) = writer.flush().and_then(|_| writer.get_ref().sync_data()) {
+                warn!("Failed to sync the {} - {error}", self.name);
+            }
+        }
+    }
+
+    /// Moves the journal file to its rotated path, and returns a new journal file.
+    /// On failure, the given writer remains usable, so that the entries are still appended to the journal.
+    fn rotate(&self, writer: &mut BufWriter<File>) -> Result<File> {
+        writer.flush()?;
+        writer.get_ref().sync_data()?;
+
+        let _guard = self.rotation_lock.write();
+        std::fs::rename(&self.path, rotated_path(&self.path))?;
+        open_append(&self.path)
+    }
+}
+
+/// Opens the file at the given path for appending, creating it if it does not exist.
+fn open_append(path: &Path) -> Result<File> {
+    Ok(OpenOptions::new().create(true).append(true).open(path)?)
+}
+
+/// Returns the path of the rotated journal file.
+fn rotated_path(path: &Path) -> PathBuf {
+    let mut rotated = path.as_os_str().to_owned();
+    rotated.push(".1");
+    PathBuf::from(rotated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    /// Returns a unique journal path in the temporary directory.
+    fn sample_journal_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("snarkos-{name}-{}.journal", rand::random::<u64>()));
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(rotated_path(&path));
+        path
+    }
+
+    /// Returns a sample transaction entry with the given index.
+    fn sample_entry(index: usize) -> JournalEntry {
+        JournalEntry {
+            timestamp: 1_700_000_000 + index as i64,
+            kind: BroadcastKind::Transaction,
+            id: format!("at1{index:0>58}"),
+            checksum: format!("{index}field"),
+            source_ip: "127.0.0.1".parse().unwrap(),
+            num_bytes: 1024 + index,
+            idempotency_key: (index % 2 == 0).then(|| format!("key-{index}")),
+        }
+    }
+
+    /// Waits until the journal contains the given number of entries.
+    fn wait_for_entries(journal: &BroadcastJournal, num_entries: usize) -> Vec<JournalEntry> {
+        let start = Instant::now();
+        loop {
+            let entries = journal.entries_since(0).unwrap();
+            if entries.len() >= num_entries || start.elapsed() > Duration::from_secs(5) {
+                return entries;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn test_journal_rotation() {
+        let path = sample_journal_path("rotation");
+        let entry_size = serde_json::to_vec(&sample_entry(0)).unwrap().len() as u64 + 1;
+
+        // Open a journal that rotates after roughly 4 entries.
+        let journal = BroadcastJournal::open(path.clone(), 4 * entry_size + 8).unwrap();
+
+        // Record several broadcasts, exceeding the size cap.
+        for index in 0..6 {
+            journal.record(sample_entry(index));
+        }
+        let entries = wait_for_entries(&journal, 6);
+        assert_eq!(journal.num_dropped(), 0);
+
+        // Ensure the journal was rotated.
+        assert!(rotated_path(&path).exists());
+        assert!(std::fs::metadata(&path).unwrap().len() < 4 * entry_size);
+
+        // Ensure the entries are retrieved in order, across the rotation boundary.
+        assert_eq!(entries, (0..6).map(sample_entry).collect::<Vec<_>>());
+
+        // Ensure the entries are filtered by timestamp.
+        let since = sample_entry(3).timestamp;
+        assert_eq!(journal.entries_since(since).unwrap(), (3..6).map(sample_entry).collect::<Vec<_>>());
+
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(rotated_path(&path)).unwrap();
+    }
+
+    #[test]
+    fn test_journal_rotation_failure() {
+        let path = sample_journal_path("rotation-failure");
+        let entry_size = serde_json::to_vec(&sample_entry(0)).unwrap().len() as u64 + 1;
+
+        // Prevent the rotation, by occupying the rotated path with a non-empty directory.
+        let rotated = rotated_path(&path);
+        std::fs::create_dir_all(&rotated).unwrap();
+        std::fs::write(rotated.join("occupied"), b"").unwrap();
+
+        // Open a journal that rotates after roughly 2 entries, and record more entries than that.
+        let journal = BroadcastJournal::open(path.clone(), 2 * entry_size + 8).unwrap();
+        for index in 0..6 {
+            journal.record(sample_entry(index));
+        }
+
+        // Ensure the writer keeps appending to the journal file, despite the failed rotations.
+        let start = Instant::now();
+        let num_written = || std::fs::read_to_string(&path).unwrap_or_default().lines().count();
+        while num_written() != 6 {
+            assert!(start.elapsed() < Duration::from_secs(5));
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        // Ensure the writer rotates the journal file, once the rotated path is available again.
+        std::fs::remove_dir_all(&rotated).unwrap();
+        journal.record(sample_entry(6));
+        let start = Instant::now();
+        while !rotated.is_file() || num_written() != 1 {
+            assert!(start.elapsed() < Duration::from_secs(5));
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(wait_for_entries(&journal, 7), (0..7).map(sample_entry).collect::<Vec<_>>());
+
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(&rotated).unwrap();
+    }
+
+    #[test]
+    fn test_journal_drops_when_full() {
+        let path = sample_journal_path("drops");
+        let journal = BroadcastJournal::open(path.clone(), MAX_JOURNAL_FILE_SIZE).unwrap();
+
+        // Record more entries than the channel can hold, without waiting for the writer.
+        let num_entries = 4 * JOURNAL_CHANNEL_CAPACITY;
+        for index in 0..num_entries {
+            journal.record(sample_entry(index));
+        }
+
+        // Ensure every entry was either written or counted as dropped.
+        let start = Instant::now();
+        let num_written = || std::fs::read_to_string(&path).unwrap().lines().count();
+        while num_written() + journal.num_dropped() as usize != num_entries {
+            assert!(start.elapsed() < Duration::from_secs(5));
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,262 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::RestError;
+
+use axum::{
+    body::{Body, Bytes},
+    http::header::CONTENT_TYPE,
+    response::{IntoResponse, Response},
+};
+use futures_util::{StreamExt, future, stream};
+use rayon::prelude::*;
+use serde::Serialize;
+use snarkvm::prelude::cfg_into_iter;
+use std::{fmt::Display, io, sync::Arc};
+
+/// The maximum size in bytes of a streamed JSON response, beyond which the stream is aborted.
+pub const MAX_STREAMED_RESPONSE_SIZE: usize = 256 * 1024 * 1024; // 256 MiB
+
+/// The number of items read and serialized together, per chunk of a streamed response.
+const STREAM_CHUNK_SIZE: usize = 10;
+
+/// Responds with the items of the given keys as a pretty JSON array, reading and serializing them a chunk at a time
+/// as the client reads the response, so that the memory use is bounded by a chunk of items rather than by the whole
+/// response. The keys are meant to be cheap to hold, e.g. the heights of the blocks.
+///
+/// The items of a chunk are read in parallel on a blocking thread, which is released before the chunk is sent, so
+/// that a slow client does not hold a thread. If the first chunk fails, its error is the response; a later failure,
+/// or exceeding `MAX_STREAMED_RESPONSE_SIZE`, aborts the stream, which the client observes as an incomplete body.
+/// If the client disconnects, the remaining chunks are not read.
+pub async fn stream_json_array<K, T, I, F>(keys: I, read: F) -> Result<Response, RestError>
+where
+    K: 'static + Send,
+    T: Serialize,
+    I: IntoIterator<Item = K>,
+    I::IntoIter: 'static + Send,
+    F: 'static + Send + Sync + Fn(K) -> Result<T, RestError>,
+{
+    let read = move |key| read(key).and_then(|item| to_pretty_json(&item)).map(Some);
+    stream_json((b'[', b']'), keys, read, MAX_STREAMED_RESPONSE_SIZE).await
+}
+
+/// Responds with the entries of the given keys as a pretty JSON object, a chunk at a time, skipping the keys whose
+/// entry is no longer found. See `stream_json_array` for the handling of the failures.
+pub async fn stream_json_object<K, L, V, I, F>(keys: I, read: F) -> Result<Response, RestError>
+where
+    K: 'static + Send,
+    L: Display,
+    V: Serialize,
+    I: IntoIterator<Item = K>,
+    I::IntoIter: 'static + Send,
+    F: 'static + Send + Sync + Fn(K) -> Result<Option<(L, V)>, RestError>,
+{
+    let read = move |key| {
+        let Some((label, value)) = read(key)? else {
+            return Ok(None);
+        };
+        let mut element = to_pretty_json(&label.to_string())?;
+        element.extend_from_slice(b": ");
+        element.extend(to_pretty_json(&value)?);
+        Ok(Some(element))
+    };
+    stream_json((b'{', b'}'), keys, read, MAX_STREAMED_RESPONSE_SIZE).await
+}
+
+/// Serializes the given value into pretty JSON, in the format of `ErasedJson::pretty`.
+fn to_pretty_json<T: Serialize>(value: &T) -> Result<Vec<u8>, RestError> {
+    serde_json::to_vec_pretty(value)
+        .map_err(|error| RestError::InternalServerError(format!("Failed to serialize - {error}")))
+}
+
+/// Indents the given pretty JSON element by one level, as an element of the enclosing array or object.
+///
+/// Note: The newlines of a pretty JSON value are all structural, as the newlines within its strings are escaped.
+fn indent(element: &[u8]) -> Vec<u8> {
+    let mut indented = Vec::with_capacity(element.len() + 2);
+    indented.extend_from_slice(b"  ");
+    for byte in element {
+        indented.push(*byte);
+        if *byte == b'\n' {
+            indented.extend_from_slice(b"  ");
+        }
+    }
+    indented
+}
+
+/// Responds with the elements read from the given keys, enclosed in the given delimiters and separated by commas,
+/// laid out as the pretty JSON of the whole collection would be. The elements read as `None` are skipped.
+async fn stream_json<K, I, F>(delimiters: (u8, u8), keys: I, read: F, max_size: usize) -> Result<Response, RestError>
+where
+    K: 'static + Send,
+    I: IntoIterator<Item = K>,
+    I::IntoIter: 'static + Send,
+    F: 'static + Send + Sync + Fn(K) -> Result<Option<Vec<u8>>, RestError>,
+{
+    let (open, close) = delimiters;
+    let read = Arc::new(read);
+
+    // Read the chunks of elements one at a time, as the client polls the body.
+    let elements = stream::iter(keys).chunks(STREAM_CHUNK_SIZE).then(move |keys| {
+        let read = read.clone();
+        async move {
+            // Read the elements of the chunk in parallel, on a blocking thread that is released once they are read.
+            let elements = tokio::task::spawn_blocking(move || {
+                cfg_into_iter!(keys).map(|key| read(key)).collect::<Result<Vec<_>, _>>()
+            })
+            .await
+            .map_err(|error| RestError::InternalServerError(format!("Failed to read the response - {error}")))??;
+            Ok::<_, RestError>(elements.into_iter().flatten().collect::<Vec<_>>())
+        }
+    });
+
+    // Lay out the elements, and close the collection once they are all sent.
+    let (mut size, mut is_first) = (0, true);
+    let mut chunks = Box::pin(elements.map(Some).chain(stream::once(future::ready(None))).map(move |elements| {
+        let chunk = match elements {
+            Some(elements) => {
+                let mut chunk = Vec::new();
+                for element in elements? {
+                    match is_first {
+                        true => chunk.extend_from_slice(&[open, b'\n']),
+                        false => chunk.extend_from_slice(b",\n"),
+                    }
+                    chunk.extend(indent(&element));
+                    is_first = false;
+                }
+                chunk
+            }
+            None => match is_first {
+                true => vec![open, close],
+                false => vec![b'\n', close],
+            },
+        };
+        // Ensure the response is within the maximum size.
+        size += chunk.len();
+        match size > max_size {
+            true => {
+                Err(RestError::InternalServerError(format!("The response exceeds the maximum size ({max_size} bytes)")))
+            }
+            false => Ok(Bytes::from(chunk)),
+        }
+    }));
+
+    // Respond with the error of the first chunk, as the response has not started yet.
+    let first = match chunks.next().await {
+        Some(Ok(first)) => first,
+        Some(Err(error)) => return Err(error),
+        None => return Err(RestError::InternalServerError("The response ended unexpectedly".to_string())),
+    };
+    // Abort the stream on a later failure, so the client does not mistake the partial body for a complete one.
+    let chunks = stream::once(future::ready(Ok(first))).chain(chunks).map(|chunk| {
+        chunk.map_err(|error| {
+            warn!("Aborted a streamed response - {error:?}");
+            io::Error::other(format!("{error:?}"))
+        })
+    });
+
+    Ok(([(CONTENT_TYPE, "application/json")], Body::from_stream(chunks)).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::atomic::{AtomicUsize, Ordering::SeqCst};
+
+    /// Returns a blocks-like item of the given height.
+    fn sample_item(height: usize) -> String {
+        format!("block {height} {}", "x".repeat(1024))
+    }
+
+    #[tokio::test]
+    async fn test_stream_json_array() {
+        let num_items = 1_000;
+        let produced = Arc::new(AtomicUsize::new(0));
+        let produced_ = produced.clone();
+        let response = stream_json_array(0..num_items, move |height| {
+            produced_.fetch_add(1, SeqCst);
+            Ok(sample_item(height))
+        })
+        .await
+        .unwrap();
+        let mut chunks = response.into_body().into_data_stream();
+
+        // Ensure the items are read a chunk at a time as the response is read, rather than all at once.
+        let mut body = chunks.next().await.unwrap().unwrap().to_vec();
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        assert!(produced.load(SeqCst) <= 2 * STREAM_CHUNK_SIZE, "Produced {} items", produced.load(SeqCst));
+
+        // Ensure the body is the complete JSON array, in the pretty format of the whole collection.
+        while let Some(chunk) = chunks.next().await {
+            body.extend(chunk.unwrap());
+        }
+        let items = (0..num_items).map(sample_item).collect::<Vec<_>>();
+        assert_eq!(body, serde_json::to_vec_pretty(&items).unwrap());
+        assert_eq!(produced.load(SeqCst), num_items);
+
+        // Ensure an empty array is valid.
+        let response = stream_json_array(0..0, |height: u32| Ok(height)).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"[]");
+    }
+
+    #[tokio::test]
+    async fn test_stream_json_failures() {
+        let read = |height: usize| match height {
+            15 => Err(RestError::NotFound("missing".to_string())),
+            height => Ok(height),
+        };
+
+        // Ensure the failure of the first chunk is the response.
+        assert!(matches!(stream_json_array(10..20, read).await, Err(RestError::NotFound(_))));
+
+        // Ensure the failure of a later chunk aborts the stream.
+        let response = stream_json_array(0..20, read).await.unwrap();
+        assert!(axum::body::to_bytes(response.into_body(), usize::MAX).await.is_err());
+
+        // Ensure exceeding the maximum size aborts the stream.
+        let response = stream_json((b'[', b']'), 0..100, |_| Ok(Some(b"0123456789".to_vec())), 50).await.unwrap();
+        assert!(axum::body::to_bytes(response.into_body(), usize::MAX).await.is_err());
+
+        // Ensure the items are no longer read once the client disconnects.
+        let produced = Arc::new(AtomicUsize::new(0));
+        let produced_ = produced.clone();
+        let response = stream_json_array(0..1_000, move |height| {
+            produced_.fetch_add(1, SeqCst);
+            Ok(height)
+        })
+        .await
+        .unwrap();
+        drop(response);
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        assert!(produced.load(SeqCst) <= 2 * STREAM_CHUNK_SIZE, "Produced {} items", produced.load(SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_stream_json_object() {
+        // Ensure the entries that are no longer found are skipped.
+        let read = |i: u8| Ok((i != 1).then(|| (format!("id{i}"), vec![i; 2])));
+        let response = stream_json_object(0..3, read).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let expected = serde_json::json!({ "id0": [0, 0], "id2": [2, 2] });
+        assert_eq!(&body[..], serde_json::to_vec_pretty(&expected).unwrap());
+
+        // Ensure an object without entries is valid.
+        let response = stream_json_object(0..3, |_: u8| Ok(None::<(String, u8)>)).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"{}");
+    }
+}
@@ -0,0 +1,155 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{
+    Ledger,
+    Network,
+    block::{Block, Header},
+    store::ConsensusStorage,
+};
+
+use serde::{Deserialize, Serialize};
+
+/// A consistent snapshot of the latest block.
+///
+/// The ledger guards each of `latest_height`, `latest_hash`, etc. with a separate lock acquisition, so reading them
+/// one after the other may mix the fields of two blocks, if the ledger advances in between. The fields of a snapshot
+/// are all captured from the same block.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(bound = "")]
+pub struct LatestBlockInfo<N: Network> {
+    /// The height of the block.
+    pub height: u32,
+    /// The hash of the block.
+    pub hash: N::BlockHash,
+    /// The round of the block.
+    pub round: u64,
+    /// The UNIX timestamp of the block.
+    pub timestamp: i64,
+    /// The header of the block.
+    pub header: Header<N>,
+}
+
+impl<N: Network> LatestBlockInfo<N> {
+    /// Captures the snapshot of the given block.
+    pub fn new(block: &Block<N>) -> Self {
+        Self {
+            height: block.height(),
+            hash: block.hash(),
+            round: block.round(),
+            timestamp: block.timestamp(),
+            header: *block.header(),
+        }
+    }
+
+    /// Captures the snapshot of the latest block in the given ledger.
+    /// Note: The ledger clones its latest block under a single lock acquisition.
+    pub fn load<C: ConsensusStorage<N>>(ledger: &Ledger<N, C>) -> Self {
+        Self::new(&ledger.latest_block())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LatestCache;
+    use snarkos_node_bft_ledger_service::test_helpers::sample_ledger;
+    use snarkvm::prelude::MainnetV0;
+
+    use parking_lot::Mutex;
+    use rand::SeedableRng;
+    use rand_chacha::ChaChaRng;
+    use std::{
+        collections::HashMap,
+        sync::{
+            Arc,
+            atomic::{AtomicBool, Ordering},
+        },
+    };
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn test_latest_block_info_while_advancing() {
+        let rng = &mut ChaChaRng::seed_from_u64(1234567890u64);
+
+        // Initialize the ledger.
+        let (private_key, ledger) = sample_ledger::<CurrentNetwork, _>(0, rng);
+
+        // Ensure the snapshot matches the latest block.
+        let info = LatestBlockInfo::load(&ledger);
+        assert_eq!(info, LatestBlockInfo::new(&ledger.latest_block()));
+        assert_eq!((info.height, info.hash), (0, ledger.latest_hash()));
+        assert_eq!((info.round, info.timestamp), (ledger.latest_round(), ledger.latest_timestamp()));
+
+        // The snapshots are shared between the readers, as served by the REST server.
+        let cache = LatestCache::<_, Arc<LatestBlockInfo<CurrentNetwork>>>::default();
+        // The hash of the block at each height, recorded as the ledger advances.
+        let hashes = Mutex::new(HashMap::from([(0, ledger.latest_hash())]));
+        // The snapshots served to the readers.
+        let responses = Mutex::new(Vec::new());
+        let is_advancing = AtomicBool::new(true);
+
+        std::thread::scope(|scope| {
+            // Hammer the snapshots while the ledger advances.
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    while is_advancing.load(Ordering::SeqCst) {
+                        let latest_height = ledger.latest_height();
+                        let key = (latest_height, ledger.latest_hash());
+                        let info = cache.get(key, || Ok(Arc::new(LatestBlockInfo::load(&ledger)))).unwrap();
+                        assert!(info.height >= latest_height);
+                        responses.lock().push(info);
+                    }
+                });
+            }
+            // Advance the ledger.
+            for _ in 0..5 {
+                let block =
+                    ledger.prepare_advance_to_next_beacon_block(&private_key, vec![], vec![], vec![], rng).unwrap();
+                ledger.advance_to_next_block(&block).unwrap();
+                hashes.lock().insert(block.height(), block.hash());
+            }
+            is_advancing.store(false, Ordering::SeqCst);
+        });
+
+        // Ensure the height and hash of every snapshot are from the same block.
+        let hashes = hashes.into_inner();
+        let responses = responses.into_inner();
+        assert!(!responses.is_empty());
+        for info in responses {
+            assert_eq!(hashes.get(&info.height), Some(&info.hash), "mismatched hash at block {}", info.height);
+            assert_eq!(info.header.height(), info.height);
+            assert_eq!(info.header.round(), info.round);
+            assert_eq!(info.header.timestamp(), info.timestamp);
+        }
+    }
+
+    #[test]
+    fn test_latest_block_info_after_rollback() {
+        // The ledger, and the ledgers it rolls back to: a shorter one, and one whose blocks differ at the same height.
+        let ledger = sample_ledger::<CurrentNetwork, _>(3, &mut ChaChaRng::seed_from_u64(1234567890u64)).1;
+        let shorter = sample_ledger::<CurrentNetwork, _>(1, &mut ChaChaRng::seed_from_u64(987654321u64)).1;
+        let reorged = sample_ledger::<CurrentNetwork, _>(3, &mut ChaChaRng::seed_from_u64(987654321u64)).1;
+
+        // Ensure the snapshot of every ledger matches its latest block, as `/block/latest` reads it.
+        let cache = LatestCache::<_, Arc<LatestBlockInfo<CurrentNetwork>>>::default();
+        for ledger in [&ledger, &shorter, &reorged, &ledger] {
+            let key = (ledger.latest_height(), ledger.latest_hash());
+            let info = cache.get(key, || Ok(Arc::new(LatestBlockInfo::load(ledger)))).unwrap();
+            assert_eq!(*info, LatestBlockInfo::new(&ledger.latest_block()));
+        }
+    }
+}
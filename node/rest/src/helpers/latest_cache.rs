@@ -0,0 +1,172 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::Result;
+use parking_lot::RwLock;
+
+/// A read-through cache of a value derived from the latest block of the ledger.
+///
+/// The value is cached alongside the key of the latest block it was loaded at, i.e. its height and hash,
+/// and is reloaded by the first request that observes another latest block, so that repeated requests between
+/// blocks are served from memory. As the key includes the hash, a rollback of the ledger, even to a block at the
+/// same height, reloads the value instead of serving the value of a block that is no longer in the ledger.
+pub struct LatestCache<K, T> {
+    /// The cached `(block key, value)` entry, if any.
+    entry: RwLock<Option<(K, T)>>,
+}
+
+impl<K, T> Default for LatestCache<K, T> {
+    /// Initializes an empty cache.
+    fn default() -> Self {
+        Self { entry: Default::default() }
+    }
+}
+
+impl<K: PartialEq, T: Clone> LatestCache<K, T> {
+    /// Returns the value for the given key of the latest block, loading it if the cached value is for another block.
+    ///
+    /// Note: The loaded value may reflect a block after the given one, if the ledger advances concurrently,
+    /// but never a block before it.
+    pub fn get(&self, latest_key: K, load: impl FnOnce() -> Result<T>) -> Result<T> {
+        // Return the cached value, if it was loaded at the given block.
+        if let Some((key, value)) = &*self.entry.read() {
+            if *key == latest_key {
+                return Ok(value.clone());
+            }
+        }
+        // Load the value outside of the lock, so that readers are not blocked.
+        let value = load()?;
+        // Cache the value, replacing the value of any other block.
+        // Note: If a concurrent request cached the value of a newer block, the next request for it reloads the value.
+        *self.entry.write() = Some((latest_key, value.clone()));
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::bail;
+    use snarkos_node_bft_ledger_service::test_helpers::{advance_sample_ledger, sample_ledger};
+    use snarkvm::prelude::MainnetV0;
+
+    use parking_lot::Mutex;
+    use rand::SeedableRng;
+    use rand_chacha::ChaChaRng;
+    use std::{
+        collections::HashMap,
+        sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    };
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn test_latest_cache() {
+        let cache = LatestCache::default();
+        let num_loads = AtomicUsize::new(0);
+        let load = |value: u32| {
+            num_loads.fetch_add(1, Ordering::SeqCst);
+            Ok(value)
+        };
+
+        // Ensure repeated requests at the same block are served from the cache.
+        assert_eq!(cache.get((1, 'a'), || load(10)).unwrap(), 10);
+        assert_eq!(cache.get((1, 'a'), || load(11)).unwrap(), 10);
+        assert_eq!(num_loads.load(Ordering::SeqCst), 1);
+
+        // Ensure a request at a newer block refreshes the cache.
+        assert_eq!(cache.get((2, 'b'), || load(20)).unwrap(), 20);
+        assert_eq!(cache.get((2, 'b'), || load(21)).unwrap(), 20);
+        assert_eq!(num_loads.load(Ordering::SeqCst), 2);
+
+        // Ensure a request at another block of the same height, as after a reorg, refreshes the cache.
+        assert_eq!(cache.get((2, 'c'), || load(22)).unwrap(), 22);
+        // Ensure a request at a lower block, as after a rollback, refreshes the cache.
+        assert_eq!(cache.get((1, 'd'), || load(12)).unwrap(), 12);
+        assert_eq!(num_loads.load(Ordering::SeqCst), 4);
+
+        // Ensure a failed load is returned, and does not replace the cached value.
+        assert!(cache.get((3, 'e'), || Err(anyhow::anyhow!("The ledger is unavailable"))).is_err());
+        assert_eq!(cache.get((1, 'd'), || load(13)).unwrap(), 12);
+    }
+
+    #[test]
+    fn test_latest_cache_after_rollback() {
+        // The ledger, and the ledgers it rolls back to: a shorter one, and one whose blocks differ at the same height.
+        let ledger = sample_ledger::<CurrentNetwork, _>(3, &mut ChaChaRng::seed_from_u64(1234567890u64)).1;
+        let shorter = sample_ledger::<CurrentNetwork, _>(1, &mut ChaChaRng::seed_from_u64(987654321u64)).1;
+        let reorged = sample_ledger::<CurrentNetwork, _>(3, &mut ChaChaRng::seed_from_u64(987654321u64)).1;
+        assert_eq!(reorged.latest_height(), ledger.latest_height());
+        assert_ne!(reorged.latest_hash(), ledger.latest_hash());
+
+        // Ensure every ledger is served its own state root, instead of the state root cached for another ledger.
+        let cache = LatestCache::default();
+        for ledger in [&ledger, &shorter, &reorged, &ledger] {
+            let key = (ledger.latest_height(), ledger.latest_hash());
+            assert_eq!(cache.get(key, || Ok(ledger.latest_state_root())).unwrap(), ledger.latest_state_root());
+            assert_eq!(cache.get(key, || bail!("The cache was not used")).unwrap(), ledger.latest_state_root());
+        }
+    }
+
+    #[test]
+    fn test_latest_cache_while_advancing() {
+        let rng = &mut ChaChaRng::seed_from_u64(1234567890u64);
+
+        // Initialize the ledger.
+        let (private_key, ledger) = sample_ledger::<CurrentNetwork, _>(0, rng);
+
+        let cache = LatestCache::default();
+        // The state root of the ledger at each height, recorded as the ledger advances.
+        let state_roots = Mutex::new(HashMap::from([(0, ledger.latest_state_root())]));
+        // The `(observed height, served state root)` responses.
+        let responses = Mutex::new(Vec::new());
+        let is_advancing = AtomicBool::new(true);
+
+        std::thread::scope(|scope| {
+            // Hammer the cache while the ledger advances.
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    while is_advancing.load(Ordering::SeqCst) {
+                        let latest_height = ledger.latest_height();
+                        let key = (latest_height, ledger.latest_hash());
+                        let state_root = cache.get(key, || Ok(ledger.latest_state_root())).unwrap();
+                        responses.lock().push((latest_height, state_root));
+                    }
+                });
+            }
+            // Advance the ledger.
+            for _ in 0..5 {
+                advance_sample_ledger(&ledger, &private_key, 1, rng);
+                state_roots.lock().insert(ledger.latest_height(), ledger.latest_state_root());
+            }
+            is_advancing.store(false, Ordering::SeqCst);
+        });
+
+        // Ensure every response matches the ledger at or after the observed height.
+        let state_roots = state_roots.into_inner();
+        let responses = responses.into_inner();
+        assert!(!responses.is_empty());
+        for (observed_height, state_root) in responses {
+            let height =
+                state_roots.iter().find_map(|(height, root)| (*root == state_root).then_some(*height)).unwrap();
+            assert!(height >= observed_height, "served the state root of block {height} at block {observed_height}");
+        }
+        // Ensure the cache is refreshed at the latest block, and then served without loading.
+        let key = (ledger.latest_height(), ledger.latest_hash());
+        let latest_state_root = cache.get(key, || Ok(ledger.latest_state_root())).unwrap();
+        assert_eq!(latest_state_root, ledger.latest_state_root());
+        assert_eq!(cache.get(key, || bail!("The cache was not used")).unwrap(), latest_state_root);
+    }
+}
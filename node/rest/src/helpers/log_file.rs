@@ -0,0 +1,34 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// The log file of the node and its rotation settings, as reported in the node status.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct LogFileStatus {
+    /// The path to the current log file.
+    pub path: PathBuf,
+    /// The format of the log lines, either `text` or `json`.
+    pub format: String,
+    /// The maximum size in bytes of the log file before it is rotated, if rotating by size.
+    pub max_file_size: Option<u64>,
+    /// Whether the log file is rotated daily.
+    pub daily: bool,
+    /// The number of rotated log files that are retained.
+    pub max_files: usize,
+    /// Whether the rotated log files are compressed.
+    pub compress: bool,
+}
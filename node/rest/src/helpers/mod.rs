@@ -13,8 +13,83 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod audit_log;
+pub use audit_log::*;
+
 mod auth;
 pub use auth::*;
 
+mod block_estimate;
+pub use block_estimate::*;
+
+mod block_view;
+pub use block_view::*;
+
+mod broadcast_shedder;
+pub use broadcast_shedder::*;
+
+mod cache_control;
+pub use cache_control::*;
+
+mod committee_page;
+pub use committee_page::*;
+
 mod error;
 pub use error::*;
+
+mod fee_estimate;
+pub use fee_estimate::*;
+
+mod format;
+pub use format::*;
+
+mod journal;
+pub use journal::*;
+
+mod json_stream;
+pub use json_stream::*;
+
+mod latest_block_info;
+pub use latest_block_info::*;
+
+mod latest_cache;
+pub use latest_cache::*;
+
+mod log_file;
+pub use log_file::*;
+
+mod node_config;
+pub use node_config::*;
+
+mod openapi;
+pub use openapi::*;
+
+mod path_params;
+pub use path_params::*;
+
+mod preview_gate;
+pub use preview_gate::*;
+
+mod recent_blocks;
+pub use recent_blocks::*;
+
+mod rejected_deployments;
+pub use rejected_deployments::*;
+
+mod restricted_peers;
+pub use restricted_peers::*;
+
+mod solution_inclusion;
+pub use solution_inclusion::*;
+
+mod state_paths;
+pub use state_paths::*;
+
+mod sync_status;
+pub use sync_status::*;
+
+mod trace_scope;
+pub use trace_scope::*;
+
+mod trusted_peers;
+pub use trusted_peers::*;
@@ -0,0 +1,294 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::LogFileStatus;
+use snarkos_node_consensus::Consensus;
+use snarkos_node_router::{
+    Router,
+    messages::{Features, NodeType},
+};
+use snarkvm::prelude::{Address, Network};
+
+use aleo_std::{StorageMode, aleo_ledger_dir};
+use serde::{Deserialize, Serialize};
+use std::{fmt, net::SocketAddr, path::PathBuf};
+use tracing::level_filters::LevelFilter;
+
+/// The effective configuration of the node, as reported for support diagnostics.
+///
+/// The configuration is built from the running components of the node, rather than from its command-line arguments.
+/// It is sanitized by construction: it has no field for the private key, the view key, or the JWT secret of the node.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct NodeConfig {
+    /// The type of the node.
+    pub node_type: NodeType,
+    /// The network of the node.
+    pub network: String,
+    /// The address of the node account.
+    pub address: String,
+    /// The peer-to-peer settings, unless the node is in safe mode.
+    pub router: Option<RouterConfig>,
+    /// The consensus settings, if the node is a validator.
+    pub consensus: Option<ConsensusConfig>,
+    /// The REST server settings, if the REST server is enabled.
+    pub rest: Option<RestConfig>,
+    /// The proving thread pool settings, if the node is a prover.
+    pub proving: Option<ProvingConfig>,
+    /// The ledger storage settings.
+    pub storage: StorageConfig,
+    /// The logging settings.
+    pub logging: LoggingConfig,
+    /// The optional features the node was compiled with.
+    pub features: Vec<String>,
+}
+
+/// The peer-to-peer settings of the node.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct RouterConfig {
+    /// The IP address the node listens on.
+    pub node_ip: Option<SocketAddr>,
+    /// The trusted peers.
+    pub trusted_peers: Vec<SocketAddr>,
+    /// The maximum number of connected peers.
+    pub max_connected_peers: usize,
+    /// Whether the node allows untrusted peers to connect, if it is a validator.
+    pub allow_external_peers: bool,
+    /// Whether the node rotates its untrusted peers.
+    pub rotate_external_peers: bool,
+    /// Whether the node is in development mode.
+    pub is_dev: bool,
+    /// Whether the node negotiates early block announcements with its peers.
+    pub early_block_announce: bool,
+    /// The rollout of the experiments to the peers.
+    pub experiments: String,
+    /// The number of blocks the node may be behind, before its gossip is suppressed.
+    pub relay_gate_blocks: u32,
+    /// Whether the node restricts the account address of a restricted peer, in addition to its IP.
+    pub restrict_by_account: bool,
+    /// The maximum pool memory in bytes, if any.
+    pub max_pool_memory: Option<u64>,
+}
+
+/// The consensus settings of a validator.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ConsensusConfig {
+    /// The IP address the BFT listens on.
+    pub bft_ip: SocketAddr,
+    /// The trusted validators.
+    pub trusted_validators: Vec<SocketAddr>,
+    /// The level of detail of the validators responses to peers outside the committee.
+    pub validators_response: String,
+    /// The name of the mempool policy.
+    pub mempool_policy: String,
+    /// The time in seconds unconfirmed transmissions are kept in the inbound queues.
+    pub inbound_queue_ttl_in_secs: u64,
+    /// Whether the validator refuses to propose and sign batches while another validator is using its account.
+    pub pause_on_duplicate_identity: bool,
+}
+
+/// The REST server settings.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct RestConfig {
+    /// The IP address the REST server listens on.
+    pub rest_ip: SocketAddr,
+    /// The number of requests per second allowed per IP.
+    pub rest_rps: u32,
+    /// The path to the broadcast journal, if enabled.
+    pub broadcast_journal: Option<PathBuf>,
+    /// The number of recent block summaries that are retained.
+    pub recent_blocks: usize,
+}
+
+/// The proving thread pool settings of a prover.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ProvingConfig {
+    /// The number of proving threads.
+    pub num_threads: usize,
+    /// The cores the proving threads are pinned to, if any.
+    pub cores: Option<Vec<usize>>,
+    /// Whether the proving threads were successfully pinned to their cores.
+    pub is_pinned: bool,
+}
+
+/// The ledger storage settings.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct StorageConfig {
+    /// The storage mode, either `production`, `development`, or `custom`.
+    pub mode: String,
+    /// The development identifier, if the storage mode is `development`.
+    pub dev: Option<u16>,
+    /// The path to the ledger.
+    pub path: PathBuf,
+}
+
+/// The logging settings.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct LoggingConfig {
+    /// The most verbose level that is logged.
+    pub max_level: String,
+    /// The log file and its rotation settings, if it is known.
+    pub log_file: Option<LogFileStatus>,
+}
+
+impl NodeConfig {
+    /// Initializes the configuration of the node, from its running components.
+    pub fn new<N: Network>(
+        node_type: NodeType,
+        address: Address<N>,
+        router: Option<&Router<N>>,
+        consensus: Option<&Consensus<N>>,
+        storage_mode: &StorageMode,
+        log_file: Option<LogFileStatus>,
+        features: &[&str],
+    ) -> Self {
+        Self {
+            node_type,
+            network: crate::network_name::<N>().unwrap_or("unknown").to_string(),
+            address: address.to_string(),
+            router: router.map(|router| RouterConfig::new(router)),
+            consensus: consensus.map(ConsensusConfig::new),
+            rest: None,
+            proving: None,
+            storage: StorageConfig::new::<N>(storage_mode),
+            logging: LoggingConfig { max_level: LevelFilter::current().to_string(), log_file },
+            features: features.iter().map(ToString::to_string).collect(),
+        }
+    }
+}
+
+impl RouterConfig {
+    /// Initializes the peer-to-peer settings, from the router.
+    fn new<N: Network>(router: &Router<N>) -> Self {
+        let mut trusted_peers = router.trusted_peers().iter().copied().collect::<Vec<_>>();
+        trusted_peers.sort();
+        // Note: A validator shares its memory budget between consensus and the router.
+        let memory_budget = router.memory_budget();
+        Self {
+            node_ip: router.listener_ip(),
+            trusted_peers,
+            max_connected_peers: router.max_connected_peers(),
+            allow_external_peers: router.allow_external_peers(),
+            rotate_external_peers: router.rotate_external_peers(),
+            is_dev: router.is_dev(),
+            early_block_announce: router.features().contains(Features::BLOCK_ANNOUNCE),
+            experiments: router.experiments().to_string(),
+            relay_gate_blocks: router.relay_gate().threshold(),
+            restrict_by_account: router.restrict_by_account(),
+            max_pool_memory: memory_budget.max_bytes(),
+        }
+    }
+}
+
+impl ConsensusConfig {
+    /// Initializes the consensus settings, from the consensus.
+    fn new<N: Network>(consensus: &Consensus<N>) -> Self {
+        let gateway = consensus.bft().primary().gateway();
+        Self {
+            bft_ip: gateway.local_ip(),
+            trusted_validators: gateway.trusted_validators().iter().copied().collect(),
+            validators_response: gateway.validators_response_mode().to_string(),
+            mempool_policy: consensus.mempool_policy().name().to_string(),
+            inbound_queue_ttl_in_secs: consensus.inbound_queue_ttl().as_secs(),
+            pause_on_duplicate_identity: consensus.bft().primary().pause_on_duplicate_identity(),
+        }
+    }
+}
+
+impl StorageConfig {
+    /// Initializes the ledger storage settings, from the storage mode.
+    fn new<N: Network>(storage_mode: &StorageMode) -> Self {
+        let (mode, dev) = match storage_mode {
+            StorageMode::Production => ("production", None),
+            StorageMode::Development(id) => ("development", Some(*id)),
+            StorageMode::Custom(_) => ("custom", None),
+        };
+        Self { mode: mode.to_string(), dev, path: aleo_ledger_dir(N::ID, storage_mode.clone()) }
+    }
+}
+
+impl fmt::Display for NodeConfig {
+    /// Formats the configuration as compact JSON, e.g. to be logged on a single line.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", serde_json::to_string(self).map_err(|_| fmt::Error)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkos_account::Account;
+    use snarkos_node_router::{MemoryBudget, PeerLimits};
+    use snarkvm::prelude::TestRng;
+
+    use std::sync::Arc;
+
+    type CurrentNetwork = snarkvm::prelude::MainnetV0;
+
+    #[tokio::test]
+    async fn test_node_config() {
+        let rng = &mut TestRng::default();
+
+        // Initialize a client router, with non-default settings.
+        let account = Account::<CurrentNetwork>::new(rng).unwrap();
+        let trusted_peers = ["1.2.3.4:4130".parse().unwrap(), "1.2.3.5:4130".parse().unwrap()];
+        let router = Router::new(
+            "127.0.0.1:4140".parse().unwrap(),
+            NodeType::Client,
+            account.clone(),
+            &trusted_peers,
+            PeerLimits::new(NodeType::Client, Some(7)),
+            false,
+            false,
+            true,
+            true,
+            Arc::new(MemoryBudget::new(Some(1 << 30))),
+            Features::BLOCK_ANNOUNCE,
+            "block_announce=25%".parse().unwrap(),
+            None,
+        )
+        .await
+        .unwrap();
+        router.relay_gate().set_threshold(1_000);
+        router.set_restrict_by_account(true);
+
+        let storage_mode = StorageMode::Development(3);
+        let config = NodeConfig::new(NodeType::Client, account.address(), Some(&router), None, &storage_mode, None, &[
+            "metrics",
+        ]);
+        let json = config.to_string();
+
+        // Ensure the secrets are not part of the configuration.
+        assert!(!json.contains(&account.private_key().to_string()));
+        assert!(!json.contains(&account.view_key().to_string()));
+        assert!(!json.contains("private_key") && !json.contains("view_key") && !json.contains("secret"));
+
+        // Ensure the non-default settings are reported.
+        let router_config = config.router.as_ref().unwrap();
+        assert_eq!(router_config.node_ip, Some("127.0.0.1:4140".parse().unwrap()));
+        assert_eq!(router_config.trusted_peers, trusted_peers);
+        assert_eq!(router_config.max_pool_memory, Some(1 << 30));
+        assert!(router_config.is_dev);
+        assert!(router_config.early_block_announce);
+        assert_eq!(router_config.experiments, "block_announce=25%");
+        assert_eq!(router_config.relay_gate_blocks, 1_000);
+        assert!(router_config.restrict_by_account);
+        assert_eq!(config.storage.dev, Some(3));
+        assert_eq!(config.address, account.address().to_string());
+        assert_eq!(config.features, vec!["metrics".to_string()]);
+
+        // Ensure the configuration round-trips through its JSON representation.
+        assert_eq!(serde_json::from_str::<NodeConfig>(&json).unwrap(), config);
+    }
+}
@@ -0,0 +1,940 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{INVALID_PARAMETER_CODE, OCTET_STREAM, auth_middleware};
+
+use axum::{
+    extract::Request,
+    handler::Handler,
+    middleware,
+    response::IntoResponse,
+    routing::{MethodFilter, Route, on},
+};
+use axum_extra::response::ErasedJson;
+use serde_json::{Map, Value, json};
+use std::{convert::Infallible, sync::Arc};
+use tower::{Layer, Service};
+
+/// The version of the OpenAPI specification that is emitted.
+pub const OPENAPI_VERSION: &str = "3.0.3";
+
+/// The HTTP method of an endpoint.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum HttpMethod {
+    Get,
+    Post,
+}
+
+impl HttpMethod {
+    /// Returns the name of the method, as used in the OpenAPI specification.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Get => "get",
+            Self::Post => "post",
+        }
+    }
+
+    /// Returns the filter of the method, to route it.
+    const fn filter(&self) -> MethodFilter {
+        match self {
+            Self::Get => MethodFilter::GET,
+            Self::Post => MethodFilter::POST,
+        }
+    }
+}
+
+/// The schema of a JSON value.
+#[derive(Copy, Clone, Debug)]
+pub enum Schema {
+    /// An integer.
+    Integer,
+    /// A string, e.g. the string encoding of a snarkVM object.
+    String,
+    /// A boolean.
+    Boolean,
+    /// A JSON object with arbitrary properties.
+    Object,
+    /// An array of the given schema.
+    Array(&'static Schema),
+    /// A reference to the given component schema.
+    Ref(&'static str),
+}
+
+impl Schema {
+    /// Returns the schema in the OpenAPI specification.
+    fn to_json(self) -> Value {
+        match self {
+            Self::Integer => json!({ "type": "integer" }),
+            Self::String => json!({ "type": "string" }),
+            Self::Boolean => json!({ "type": "boolean" }),
+            Self::Object => json!({ "type": "object" }),
+            Self::Array(items) => json!({ "type": "array", "items": items.to_json() }),
+            Self::Ref(name) => json!({ "$ref": format!("#/components/schemas/{name}") }),
+        }
+    }
+}
+
+/// The location of a parameter.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ParameterLocation {
+    Path,
+    Query,
+}
+
+/// A path or query parameter of an endpoint.
+#[derive(Copy, Clone, Debug)]
+pub struct Parameter {
+    /// The name of the parameter.
+    pub name: &'static str,
+    /// The location of the parameter.
+    pub location: ParameterLocation,
+    /// The schema of the parameter.
+    pub schema: Schema,
+    /// The description of the parameter.
+    pub description: &'static str,
+}
+
+impl Parameter {
+    /// Initializes a (required) path parameter.
+    pub const fn path(name: &'static str, schema: Schema, description: &'static str) -> Self {
+        Self { name, location: ParameterLocation::Path, schema, description }
+    }
+
+    /// Initializes an (optional) query parameter.
+    pub const fn query(name: &'static str, schema: Schema, description: &'static str) -> Self {
+        Self { name, location: ParameterLocation::Query, schema, description }
+    }
+
+    /// Returns the parameter in the OpenAPI specification.
+    fn to_json(self) -> Value {
+        let location = match self.location {
+            ParameterLocation::Path => "path",
+            ParameterLocation::Query => "query",
+        };
+        json!({
+            "name": self.name,
+            "in": location,
+            "required": self.location == ParameterLocation::Path,
+            "description": self.description,
+            "schema": self.schema.to_json(),
+        })
+    }
+}
+
+/// The successful response body of an endpoint.
+#[derive(Copy, Clone, Debug)]
+pub enum ResponseBody {
+    /// A JSON value of the given schema.
+    Json(Schema),
+    /// A JSON value of the given schema, or its canonical byte encoding, as negotiated.
+    JsonOrBytes(Schema),
+    /// An HTML page.
+    Html,
+    /// A static file, e.g. an asset of the Swagger UI.
+    File,
+}
+
+/// An endpoint of the REST server.
+#[derive(Copy, Clone, Debug)]
+pub struct Endpoint {
+    /// The HTTP method.
+    pub method: HttpMethod,
+    /// The path, relative to the network prefix, with the parameters in braces.
+    pub path: &'static str,
+    /// The summary of the endpoint.
+    pub summary: &'static str,
+    /// Whether the endpoint requires a JSON web token.
+    pub requires_auth: bool,
+    /// The path and query parameters.
+    pub parameters: &'static [Parameter],
+    /// The schema of the JSON request body, if any.
+    pub request: Option<Schema>,
+    /// The successful response body.
+    pub response: ResponseBody,
+    /// Whether the endpoint responds with `404` if the requested object is not found.
+    pub not_found: bool,
+    /// Whether the endpoint responds with `410` if the requested object was pruned,
+    /// and with `503` if the ledger failed to read the requested object transiently.
+    pub unavailable: bool,
+}
+
+impl Endpoint {
+    /// Initializes a public endpoint.
+    pub const fn new(method: HttpMethod, path: &'static str, summary: &'static str, response: ResponseBody) -> Self {
+        Self {
+            method,
+            path,
+            summary,
+            requires_auth: false,
+            parameters: &[],
+            request: None,
+            response,
+            not_found: false,
+            unavailable: false,
+        }
+    }
+
+    /// Initializes a public `GET` endpoint, returning JSON of the given schema.
+    pub const fn get(path: &'static str, summary: &'static str, response: Schema) -> Self {
+        Self::new(HttpMethod::Get, path, summary, ResponseBody::Json(response))
+    }
+
+    /// Initializes a public `POST` endpoint, with a JSON request body and response.
+    pub const fn post(path: &'static str, summary: &'static str, request: Schema, response: Schema) -> Self {
+        Self { request: Some(request), ..Self::new(HttpMethod::Post, path, summary, ResponseBody::Json(response)) }
+    }
+
+    /// Sets the path and query parameters.
+    pub const fn with_parameters(self, parameters: &'static [Parameter]) -> Self {
+        Self { parameters, ..self }
+    }
+
+    /// Marks the response as negotiable between JSON and the canonical byte encoding.
+    pub const fn with_bytes(self) -> Self {
+        match self.response {
+            ResponseBody::Json(schema) => Self { response: ResponseBody::JsonOrBytes(schema), ..self },
+            _ => self,
+        }
+    }
+
+    /// Marks the endpoint as responding with `404` if the requested object is not found.
+    pub const fn with_not_found(self) -> Self {
+        Self { not_found: true, ..self }
+    }
+
+    /// Marks the endpoint as reading blocks from the ledger, so it responds with `404` if the block is absent,
+    /// with `410` if the block was pruned, and with `503` if the ledger failed to read the block transiently.
+    pub const fn with_block_reads(self) -> Self {
+        Self { not_found: true, unavailable: true, ..self }
+    }
+
+    /// Returns the path of the endpoint in the syntax of the `axum` router, e.g. `/block/:height_or_hash`.
+    pub fn axum_path(&self) -> String {
+        self.path
+            .split('/')
+            .map(|segment| match segment.strip_prefix('{').and_then(|segment| segment.strip_suffix('}')) {
+                Some(parameter) => format!(":{parameter}"),
+                None => segment.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    /// Returns the operation ID of the endpoint, derived from its method and path.
+    pub fn operation_id(&self) -> String {
+        let mut operation_id = self.method.as_str().to_string();
+        for segment in self.path.split(|c: char| !c.is_ascii_alphanumeric()).filter(|segment| !segment.is_empty()) {
+            let mut chars = segment.chars();
+            operation_id.extend(chars.next().map(|c| c.to_ascii_uppercase()));
+            operation_id.extend(chars);
+        }
+        operation_id
+    }
+
+    /// Returns the operation in the OpenAPI specification.
+    fn to_json(self) -> Value {
+        let mut operation = Map::new();
+        operation.insert("operationId".into(), json!(self.operation_id()));
+        operation.insert("summary".into(), json!(self.summary));
+        operation.insert("tags".into(), json!([self.path.split('/').nth(1).unwrap_or_default()]));
+        // The content-negotiated endpoints also accept the `format` query parameter.
+        let mut parameters: Vec<_> = self.parameters.iter().map(|parameter| parameter.to_json()).collect();
+        if let ResponseBody::JsonOrBytes(_) = self.response {
+            parameters.push(FORMAT.to_json());
+        }
+        if !parameters.is_empty() {
+            operation.insert("parameters".into(), json!(parameters));
+        }
+        if let Some(request) = self.request {
+            operation.insert(
+                "requestBody".into(),
+                json!({ "required": true, "content": { "application/json": { "schema": request.to_json() } } }),
+            );
+        }
+        if self.requires_auth {
+            operation.insert("security".into(), json!([{ "bearerAuth": [] }]));
+        }
+
+        // Describe the successful response.
+        let mut responses = Map::new();
+        let content = match self.response {
+            ResponseBody::Json(schema) => json!({ "application/json": { "schema": schema.to_json() } }),
+            ResponseBody::JsonOrBytes(schema) => json!({
+                "application/json": { "schema": schema.to_json() },
+                OCTET_STREAM: { "schema": { "type": "string", "format": "binary" } },
+            }),
+            ResponseBody::Html => json!({ "text/html": { "schema": { "type": "string" } } }),
+            ResponseBody::File => json!({ "*/*": { "schema": { "type": "string", "format": "binary" } } }),
+        };
+        responses.insert("200".into(), json!({ "description": "Success", "content": content }));
+        // Describe the error responses.
+        if let ResponseBody::JsonOrBytes(_) = self.response {
+            responses
+                .insert("304".into(), json!({ "description": "The byte encoding matches the `If-None-Match` tag" }));
+        }
+        if !parameters.is_empty() || self.request.is_some() {
+            responses.insert("400".into(), json!({ "$ref": "#/components/responses/BadRequest" }));
+        }
+        if self.requires_auth {
+            responses.insert("401".into(), json!({ "$ref": "#/components/responses/Unauthorized" }));
+        }
+        if self.not_found {
+            responses.insert("404".into(), json!({ "$ref": "#/components/responses/NotFound" }));
+        }
+        if self.unavailable {
+            responses.insert("410".into(), json!({ "$ref": "#/components/responses/Gone" }));
+        }
+        responses.insert("429".into(), json!({ "$ref": "#/components/responses/TooManyRequests" }));
+        responses.insert("500".into(), json!({ "$ref": "#/components/responses/Error" }));
+        if self.unavailable {
+            responses.insert("503".into(), json!({ "$ref": "#/components/responses/ServiceUnavailable" }));
+        }
+        operation.insert("responses".into(), Value::Object(responses));
+        Value::Object(operation)
+    }
+}
+
+/// The `format` query parameter of the content-negotiated endpoints.
+const FORMAT: Parameter = Parameter::query(
+    "format",
+    Schema::String,
+    "The output format: `json` (default), or `bytes` for the canonical byte encoding.",
+);
+
+/// Returns an object schema with the given description and properties.
+fn object(description: &str, properties: Value) -> Value {
+    json!({ "type": "object", "description": description, "properties": properties })
+}
+
+/// Returns the component schemas of the OpenAPI specification.
+fn component_schemas() -> Value {
+    let nullable = |schema: Schema| {
+        let mut schema = schema.to_json();
+        schema["nullable"] = json!(true);
+        schema
+    };
+    json!({
+        "Block": object("A block, in its snarkVM JSON encoding.", json!({})),
+        "Transactions": object("The transactions of a block, in their snarkVM JSON encoding.", json!({})),
+        "Transaction": object("A transaction, in its snarkVM JSON encoding.", json!({})),
+        "ConfirmedTransaction": object("A confirmed transaction, in its snarkVM JSON encoding.", json!({})),
+        "Solution": object("A puzzle solution, in its snarkVM JSON encoding.", json!({})),
+        "Committee": {
+            "description": "A committee, in its snarkVM JSON encoding, or a page of its members if paginated.",
+            "oneOf": [Schema::Object.to_json(), Schema::Ref("CommitteePage").to_json()],
+        },
+        "CommitteePage": object("A page of the committee members, sorted by the x-coordinate of their address.", json!({
+            "id": Schema::String.to_json(),
+            "starting_round": Schema::Integer.to_json(),
+            "total_stake": Schema::Integer.to_json(),
+            "num_members": Schema::Integer.to_json(),
+            "offset": Schema::Integer.to_json(),
+            "members": { "type": "array", "items": object("A committee member.", json!({
+                "address": Schema::String.to_json(),
+                "stake": Schema::Integer.to_json(),
+                "is_open": Schema::Boolean.to_json(),
+                "commission": Schema::Integer.to_json(),
+            })) },
+            "next_offset": nullable(Schema::Integer),
+        })),
+        "SolutionInclusion": object("The block containing a solution, and the reward attributed to it.", json!({
+            "height": Schema::Integer.to_json(),
+            "block_hash": Schema::String.to_json(),
+            "reward": Schema::Integer.to_json(),
+        })),
+        "AdmissionVerdict": object("The verdict of the memory pool admission checks on a transaction.", json!({
+            "transaction_id": Schema::String.to_json(),
+            "outcome": { "type": "string", "enum": ["accepted", "ignored", "deferred", "rejected"] },
+            "checks": Schema::Array(&Schema::Ref("AdmissionCheckResult")).to_json(),
+            "error": nullable(Schema::String),
+        })),
+        "AdmissionCheckResult": object("The result of an admission check, listed in the order they are run.", json!({
+            "check": {
+                "type": "string",
+                "enum": [
+                    "storage_backpressure",
+                    "not_fee",
+                    "not_recently_seen",
+                    "mempool_policy",
+                    "not_in_ledger",
+                    "not_in_memory_pool",
+                    "deployment_rate",
+                ],
+            },
+            "passed": Schema::Boolean.to_json(),
+        })),
+        "MemoryPool": {
+            "description": "The unconfirmed transmission summaries, or the transmissions by ID if `full` is set.",
+            "oneOf": [Schema::Array(&Schema::Ref("TransmissionSummary")).to_json(), Schema::Object.to_json()],
+        },
+        "TransmissionSummary": object("The summary of an unconfirmed transmission, without its contents.", json!({
+            "id": Schema::String.to_json(),
+            "kind": { "type": "string", "enum": ["ratification", "solution", "transaction"] },
+            "stage": { "type": "string", "enum": ["inbound", "ready"] },
+            "size_in_bytes": nullable(Schema::Integer),
+            "age_in_secs": Schema::Integer.to_json(),
+        })),
+        "MappingValue": {
+            "description": "The value of a mapping key, or `{ data, height }` if the metadata is requested.",
+            "oneOf": [
+                nullable(Schema::String),
+                object("The value of a mapping key, with the latest height.", json!({
+                    "data": nullable(Schema::String),
+                    "height": Schema::Integer.to_json(),
+                })),
+            ],
+        },
+        "MappingValues": {
+            "description": "The key-value pairs of a mapping, or `{ data, height }` if the metadata is requested.",
+            "oneOf": [
+                { "type": "array", "items": { "type": "array", "items": { "type": "string" } } },
+                object("The key-value pairs of a mapping, with the latest height.", json!({
+                    "data": { "type": "array", "items": { "type": "array", "items": { "type": "string" } } },
+                    "height": Schema::Integer.to_json(),
+                })),
+            ],
+        },
+        "SyncProgress": object("The progress of the block sync of the node.", json!({
+            "latest_height": Schema::Integer.to_json(),
+            "peer_height": nullable(Schema::Integer),
+            "num_blocks_remaining": Schema::Integer.to_json(),
+            "blocks_per_sec": { "type": "number", "nullable": true },
+            "estimated_completion": nullable(Schema::Integer),
+            "is_synced": Schema::Boolean.to_json(),
+        })),
+        "BlockLocators": object("The block locators of a node.", json!({
+            "recents": { "type": "object", "additionalProperties": Schema::String.to_json() },
+            "checkpoints": { "type": "object", "additionalProperties": Schema::String.to_json() },
+        })),
+        "LocatorsRelationship": object("The relationship of the block history of the node to the given one.", json!({
+            "relationship": { "type": "string", "enum": ["equal", "ahead", "behind", "forked", "incomparable"] },
+            "height": Schema::Integer.to_json(),
+            "common_ancestor": Schema::Integer.to_json(),
+            "num_blocks": Schema::Integer.to_json(),
+            "fork_height": Schema::Integer.to_json(),
+        })),
+        "SyncFromPeer": object("The peer to sync from.", json!({ "peer_ip": Schema::String.to_json() })),
+        "SyncSummary": object("The summary of a forced sync.", json!({
+            "peer_ip": Schema::String.to_json(),
+            "our_height": Schema::Integer.to_json(),
+            "peer_height": Schema::Integer.to_json(),
+            "num_blocks_requested": Schema::Integer.to_json(),
+        })),
+        "BftConnection": object("A connection of the BFT gateway.", json!({
+            "peer_ip": Schema::String.to_json(),
+            "address": nullable(Schema::String),
+            "established": Schema::Integer.to_json(),
+            "uptime": Schema::Integer.to_json(),
+            "messages_sent": Schema::Integer.to_json(),
+            "bytes_sent": Schema::Integer.to_json(),
+            "messages_received": Schema::Integer.to_json(),
+            "bytes_received": Schema::Integer.to_json(),
+            "traffic": { "type": "object", "additionalProperties": Schema::Object.to_json() },
+            "last_error": nullable(Schema::Object),
+            "num_reconnects": Schema::Integer.to_json(),
+            "num_withheld_proposals": Schema::Integer.to_json(),
+            "is_declined_proposer": Schema::Boolean.to_json(),
+        })),
+        "BlockPreview": object("The summary of the block that the next commit would produce.", json!({
+            "height": Schema::Integer.to_json(),
+            "round": Schema::Integer.to_json(),
+            "num_certificates": Schema::Integer.to_json(),
+            "num_transmissions": Schema::Integer.to_json(),
+            "num_accepted_deploys": Schema::Integer.to_json(),
+            "num_accepted_executes": Schema::Integer.to_json(),
+            "num_rejected_deploys": Schema::Integer.to_json(),
+            "num_rejected_executes": Schema::Integer.to_json(),
+            "num_solutions": Schema::Integer.to_json(),
+            "num_aborted_solutions": Schema::Integer.to_json(),
+            "total_fees": Schema::Integer.to_json(),
+            "block_reward": Schema::Integer.to_json(),
+            "puzzle_reward": Schema::Integer.to_json(),
+            "aborted_transactions": Schema::Array(&Schema::Ref("AbortedTransaction")).to_json(),
+            "size_in_bytes": Schema::Integer.to_json(),
+        })),
+        "AbortedTransaction": object("A transaction that would be aborted.", json!({
+            "id": Schema::String.to_json(),
+            "reason": nullable(Schema::String),
+        })),
+        "BftWorker": object("The queue of a BFT worker.", json!({
+            "worker_id": Schema::Integer.to_json(),
+            "num_transmissions": Schema::Integer.to_json(),
+            "num_ratifications": Schema::Integer.to_json(),
+            "num_solutions": Schema::Integer.to_json(),
+            "num_transactions": Schema::Integer.to_json(),
+            "num_pending": Schema::Integer.to_json(),
+            "num_bytes": Schema::Integer.to_json(),
+            "oldest_timestamp": nullable(Schema::Integer),
+            "num_recently_drained": Schema::Integer.to_json(),
+        })),
+        "BroadcastJournal": object("The accepted broadcasts.", json!({
+            "num_dropped": Schema::Integer.to_json(),
+            "entries": { "type": "array", "items": object("An accepted broadcast.", json!({
+                "timestamp": Schema::Integer.to_json(),
+                "kind": { "type": "string", "enum": ["transaction", "solution"] },
+                "id": Schema::String.to_json(),
+                "checksum": Schema::String.to_json(),
+                "source_ip": Schema::String.to_json(),
+                "num_bytes": Schema::Integer.to_json(),
+                "idempotency_key": nullable(Schema::String),
+            })) },
+        })),
+        "ReloadSummary": object("The outcomes of a reload of the configuration of the node.", json!({
+            "items": { "type": "array", "items": object("The outcome of the reload of an item.", json!({
+                "name": Schema::String.to_json(),
+                "status": { "type": "string", "enum": ["changed", "unchanged", "failed"] },
+                "changes": nullable(Schema::String),
+                "error": nullable(Schema::String),
+            })) },
+        })),
+        "AuditLog": object("The recent authenticated administrative actions.", json!({
+            "path": nullable(Schema::String),
+            "num_dropped": Schema::Integer.to_json(),
+            "records": { "type": "array", "items": object("An administrative action.", json!({
+                "timestamp": Schema::Integer.to_json(),
+                "method": Schema::String.to_json(),
+                "endpoint": Schema::String.to_json(),
+                "parameters": Schema::Object.to_json(),
+                "source_ip": Schema::String.to_json(),
+                "subject": Schema::String.to_json(),
+                "token_id": Schema::String.to_json(),
+                "status": Schema::Integer.to_json(),
+                "outcome": { "type": "string", "enum": ["succeeded", "denied", "failed"] },
+            })) },
+        })),
+        "Task": object("A supervised task.", json!({
+            "component": Schema::String.to_json(),
+            "name": Schema::String.to_json(),
+            "policy": Schema::Object.to_json(),
+            "num_restarts": Schema::Integer.to_json(),
+            "is_running": Schema::Boolean.to_json(),
+        })),
+        "CommitteeConnectivity": object("The connectivity of the committee.", json!({
+            "starting_round": Schema::Integer.to_json(),
+            "latest_certificate_round": nullable(Schema::Integer),
+            "members": { "type": "array", "items": object("A committee member.", json!({
+                "address": Schema::String.to_json(),
+                "stake": Schema::Integer.to_json(),
+                "is_self": Schema::Boolean.to_json(),
+                "is_connected": Schema::Boolean.to_json(),
+                "last_activity": nullable(Schema::Integer),
+                "signed_latest_certificate": nullable(Schema::Boolean),
+            })) },
+        })),
+        "PeerExport": object("The peer knowledge of a node, to seed another node with.", json!({
+            "version": Schema::Integer.to_json(),
+            "candidate_peers": { "type": "array", "items": object("A candidate peer.", json!({
+                "ip": Schema::String.to_json(),
+                "has_connected": Schema::Boolean.to_json(),
+                "age_secs": Schema::Integer.to_json(),
+            })) },
+            "trusted_peers": Schema::Array(&Schema::String).to_json(),
+            "restricted_peers": { "type": "array", "items": object("A restricted peer.", json!({
+                "ip": Schema::String.to_json(),
+                "remaining_secs": Schema::Integer.to_json(),
+            })) },
+        })),
+        "PeerImportSummary": object("The outcome of an import of peer knowledge.", json!({
+            "num_candidates": Schema::Integer.to_json(),
+            "num_restricted": Schema::Integer.to_json(),
+            "num_dropped": Schema::Integer.to_json(),
+        })),
+        "LatestBlockInfo": object("A consistent snapshot of the latest block.", json!({
+            "height": Schema::Integer.to_json(),
+            "hash": Schema::String.to_json(),
+            "round": Schema::Integer.to_json(),
+            "timestamp": Schema::Integer.to_json(),
+            "header": Schema::Object.to_json(),
+        })),
+        "BlockEstimate": object("An estimate of when the next block lands.", json!({
+            "latest_height": Schema::Integer.to_json(),
+            "latest_timestamp": Schema::Integer.to_json(),
+            "latest_round": Schema::Integer.to_json(),
+            "average_block_interval": { "type": "number" },
+            "median_block_interval": { "type": "number" },
+            "estimated_next_block_timestamp": Schema::Integer.to_json(),
+            "confidence_window": object("The range of timestamps the next block is expected to land in.", json!({
+                "earliest": Schema::Integer.to_json(),
+                "latest": Schema::Integer.to_json(),
+            })),
+            "rounds_per_block": { "type": "number" },
+            "num_intervals": Schema::Integer.to_json(),
+            "num_outliers": Schema::Integer.to_json(),
+            "round_progress": nullable(Schema::Object),
+        })),
+        "FeeEstimate": object("The priority fees of the unconfirmed and recent transactions.", json!({
+            "is_fee_prioritized": Schema::Boolean.to_json(),
+            "queue_depth": nullable(Schema::Integer),
+            "memory_pool": nullable(Schema::Ref("FeePercentiles")),
+            "latest_height": Schema::Integer.to_json(),
+            "num_blocks": Schema::Integer.to_json(),
+            "recent_blocks": nullable(Schema::Ref("FeePercentiles")),
+        })),
+        "FeePercentiles": object("The percentiles of a set of priority fees, in microcredits.", json!({
+            "num_transactions": Schema::Integer.to_json(),
+            "min": Schema::Integer.to_json(),
+            "p25": Schema::Integer.to_json(),
+            "p50": Schema::Integer.to_json(),
+            "p75": Schema::Integer.to_json(),
+            "max": Schema::Integer.to_json(),
+        })),
+        "BlockSummary": object("The summary of a block.", json!({
+            "height": Schema::Integer.to_json(),
+            "hash": Schema::String.to_json(),
+            "timestamp": Schema::Integer.to_json(),
+            "num_transactions": Schema::Integer.to_json(),
+            "num_solutions": Schema::Integer.to_json(),
+            "leader": nullable(Schema::String),
+            "block_reward": Schema::Integer.to_json(),
+        })),
+        "RejectedDeployment": object("A deployment that was rejected, so that only its fee was charged.", json!({
+            "transaction_id": Schema::String.to_json(),
+            "fee_transaction_id": Schema::String.to_json(),
+            "program_id": Schema::String.to_json(),
+            "height": Schema::Integer.to_json(),
+        })),
+        "DeploymentStatus": object(
+            "The status of a deployment, with the fields of the rejected deployment if it was rejected.",
+            json!({
+                "status": { "type": "string", "enum": ["accepted", "rejected", "unknown"] },
+                "program_id": Schema::String.to_json(),
+                "height": Schema::Integer.to_json(),
+                "transaction_id": Schema::String.to_json(),
+                "fee_transaction_id": Schema::String.to_json(),
+            }),
+        ),
+        "RestrictedPeers": object("The restricted peers, with the counts of the trusted and candidate peers.", json!({
+            "restricted_peers": Schema::Array(&Schema::Ref("RestrictedPeer")).to_json(),
+            "num_trusted_peers": Schema::Integer.to_json(),
+            "num_candidate_peers": Schema::Integer.to_json(),
+        })),
+        "RestrictedPeer": object("A restricted peer, with the remaining duration of its restriction.", json!({
+            "ip": Schema::String.to_json(),
+            "restricted_at": Schema::Integer.to_json(),
+            "remaining_secs": Schema::Integer.to_json(),
+        })),
+        "TrustedPeersUpdate": object("An update of the trusted peers of the node.", json!({
+            "insert": Schema::Array(&Schema::String).to_json(),
+            "remove": Schema::Array(&Schema::String).to_json(),
+        })),
+        "TrustedPeers": object("The trusted peers of the node, after an update.", json!({
+            "trusted_peers": Schema::Array(&Schema::String).to_json(),
+            "num_inserted": Schema::Integer.to_json(),
+            "num_removed": Schema::Integer.to_json(),
+        })),
+        "TraceScopeRequest": object("A trace scope to enable, with exactly one of `peer`, `route` or `event`.", json!({
+            "peer": Schema::String.to_json(),
+            "route": Schema::String.to_json(),
+            "event": Schema::String.to_json(),
+            "level": { "type": "string", "enum": ["debug", "trace"] },
+            "duration_in_secs": Schema::Integer.to_json(),
+        })),
+        "TraceScope": object("An active trace scope, with exactly one of `peer`, `route` or `event`.", json!({
+            "id": Schema::Integer.to_json(),
+            "peer": Schema::String.to_json(),
+            "route": Schema::String.to_json(),
+            "event": Schema::String.to_json(),
+            "level": { "type": "string", "enum": ["debug", "trace"] },
+            "expires_in_secs": Schema::Integer.to_json(),
+        })),
+        "NodeStatus": object("The status of the node.", json!({
+            "mode": { "type": "string", "enum": ["normal", "safe"] },
+            "node_type": Schema::String.to_json(),
+            "address": Schema::String.to_json(),
+            "account": nullable(Schema::Object),
+            "health": nullable(Schema::Object),
+            "num_connected_peers": Schema::Integer.to_json(),
+            "is_block_synced": Schema::Boolean.to_json(),
+            "is_storage_corrupted": Schema::Boolean.to_json(),
+            "experiments": { "type": "array", "items": Schema::Object.to_json() },
+            "duplicate_identity": Schema::Object.to_json(),
+            "relay_gate": object("The gate on the gossip of the node, while it is significantly behind.", json!({
+                "is_engaged": Schema::Boolean.to_json(),
+                "threshold": Schema::Integer.to_json(),
+                "num_blocks_behind": Schema::Integer.to_json(),
+                "num_suppressed": Schema::Integer.to_json(),
+            })),
+            "mempool_pressure": nullable(Schema::Object),
+            "fd_exhaustion": object("The exhaustion of the file descriptors, which makes the connections fail.", json!({
+                "is_exhausted": Schema::Boolean.to_json(),
+                "num_errors": Schema::Integer.to_json(),
+                "secs_since_last_error": nullable(Schema::Integer),
+                "accept_backoff_ms": nullable(Schema::Integer),
+            })),
+            "latest_height": Schema::Integer.to_json(),
+            "latest_hash": Schema::String.to_json(),
+            "log_file": nullable(Schema::Object),
+        })),
+        "NodeBootstrap": object("The connection attempts to the bootstrap and trusted peers.", json!({
+            "num_connected_peers": Schema::Integer.to_json(),
+            "bootstrap_attempts": { "type": "array", "items": object("The connection attempts to a peer.", json!({
+                "peer_ip": Schema::String.to_json(),
+                "num_attempts": Schema::Integer.to_json(),
+                "last_failure": nullable(Schema::String),
+                "likely_cause": nullable(Schema::String),
+            })) },
+            "diagnostic": nullable(Schema::String),
+        })),
+        "NodeConfig": object("The effective configuration of the node, without its secrets.", json!({
+            "node_type": Schema::String.to_json(),
+            "network": Schema::String.to_json(),
+            "address": Schema::String.to_json(),
+            "router": nullable(Schema::Object),
+            "consensus": nullable(Schema::Object),
+            "rest": nullable(Schema::Object),
+            "proving": nullable(Schema::Object),
+            "storage": object("The ledger storage settings.", json!({
+                "mode": { "type": "string", "enum": ["production", "development", "custom"] },
+                "dev": nullable(Schema::Integer),
+                "path": Schema::String.to_json(),
+            })),
+            "logging": object("The logging settings.", json!({
+                "max_level": Schema::String.to_json(),
+                "log_file": nullable(Schema::Object),
+            })),
+            "features": { "type": "array", "items": Schema::String.to_json() },
+        })),
+        "StatePaths": object("The state paths of a batch of commitments, against a single state root.", json!({
+            "global_state_root": Schema::String.to_json(),
+            "state_paths": { "type": "array", "items": Schema::String.to_json() },
+        })),
+        "InvalidParameter": object("The error envelope of a malformed path or query parameter.", json!({
+            "error": object("The error.", json!({
+                "code": { "type": "string", "enum": [INVALID_PARAMETER_CODE] },
+                "parameter": Schema::String.to_json(),
+                "expected": Schema::String.to_json(),
+                "message": Schema::String.to_json(),
+            })),
+        })),
+    })
+}
+
+/// Returns the OpenAPI specification of the given endpoints of the REST server, for the given network.
+pub fn openapi_spec(network: &str, endpoints: &[Endpoint]) -> Value {
+    // Collect the operations of each path, in the order they are registered.
+    let mut paths = Map::new();
+    for endpoint in endpoints {
+        let path = paths.entry(format!("/{network}{}", endpoint.path)).or_insert_with(|| json!({}));
+        path[endpoint.method.as_str()] = endpoint.to_json();
+    }
+    // Describe the error responses, which are shared by the endpoints.
+    let text = |description: &str| {
+        let content = json!({ "text/plain": { "schema": Schema::String.to_json() } });
+        json!({ "description": description, "content": content })
+    };
+    json!({
+        "openapi": OPENAPI_VERSION,
+        "info": {
+            "title": "snarkOS REST API",
+            "description": format!("The REST API of a snarkOS node on {network}."),
+            "version": env!("CARGO_PKG_VERSION"),
+            "license": { "name": "Apache-2.0", "url": "http://www.apache.org/licenses/LICENSE-2.0" },
+        },
+        "paths": paths,
+        "components": {
+            "schemas": component_schemas(),
+            "responses": {
+                "BadRequest": {
+                    "description": "The path, query, or body of the request is invalid",
+                    "content": {
+                        "application/json": { "schema": { "$ref": "#/components/schemas/InvalidParameter" } },
+                        "text/plain": { "schema": Schema::String.to_json() },
+                    },
+                },
+                "Unauthorized": text("The JSON web token is missing, invalid, or expired"),
+                "NotFound": text("The requested object is not in the ledger"),
+                "Gone": text("The requested object was in the ledger, yet it is no longer stored"),
+                "TooManyRequests": text("The rate limit of the IP is exceeded"),
+                "Error": text("The request failed, with the error in the body"),
+                "ServiceUnavailable": text("The ledger failed to read the requested object, retry after `Retry-After`"),
+            },
+            "securitySchemes": {
+                "bearerAuth": { "type": "http", "scheme": "bearer", "bearerFormat": "JWT" },
+            },
+        },
+    })
+}
+
+/// A router of the REST server, which documents each of its routes with an endpoint of the OpenAPI specification.
+///
+/// A route can only be registered together with its endpoint, from which its path and method are taken,
+/// so the specification lists every registered route by construction.
+pub struct DocumentedRouter<S = ()> {
+    /// The routes.
+    router: axum::Router<S>,
+    /// The endpoints of the routes, in the order they are registered.
+    endpoints: Vec<Endpoint>,
+}
+
+impl<S: Clone + Send + Sync + 'static> Default for DocumentedRouter<S> {
+    /// Initializes a new router, without routes.
+    fn default() -> Self {
+        Self { router: axum::Router::new(), endpoints: Vec::new() }
+    }
+}
+
+impl<S: Clone + Send + Sync + 'static> DocumentedRouter<S> {
+    /// Initializes a new router, without routes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the endpoints of the routes, in the order they are registered.
+    pub fn endpoints(&self) -> &[Endpoint] {
+        &self.endpoints
+    }
+
+    /// Registers the given handler for the path and the method of the given endpoint.
+    pub fn route<H: Handler<T, S>, T: 'static>(mut self, endpoint: Endpoint, handler: H) -> Self {
+        self.router = self.router.route(&endpoint.axum_path(), on(endpoint.method.filter(), handler));
+        self.endpoints.push(endpoint);
+        self
+    }
+
+    /// Applies the given middleware to the routes registered so far, see `axum::Router::route_layer`.
+    pub fn route_layer<L>(mut self, layer: L) -> Self
+    where
+        L: Layer<Route> + Clone + Send + 'static,
+        L::Service: Service<Request> + Clone + Send + 'static,
+        <L::Service as Service<Request>>::Response: IntoResponse + 'static,
+        <L::Service as Service<Request>>::Error: Into<Infallible> + 'static,
+        <L::Service as Service<Request>>::Future: Send + 'static,
+    {
+        self.router = self.router.route_layer(layer);
+        self
+    }
+
+    /// Protects the routes registered so far with JWT auth, and documents them as such.
+    pub fn require_auth(mut self) -> Self {
+        self.endpoints.iter_mut().for_each(|endpoint| endpoint.requires_auth = true);
+        self.route_layer(middleware::from_fn(auth_middleware))
+    }
+
+    /// Merges the routes of the given router into this router.
+    pub fn merge(mut self, other: DocumentedRouter<S>) -> Self {
+        self.router = self.router.merge(other.router);
+        self.endpoints.extend(other.endpoints);
+        self
+    }
+
+    /// Provides the state of the routes, see `axum::Router::with_state`.
+    pub fn with_state<S2>(self, state: S) -> DocumentedRouter<S2> {
+        DocumentedRouter { router: self.router.with_state(state), endpoints: self.endpoints }
+    }
+
+    /// Serves the OpenAPI specification of the routes registered so far, including its own, at `/openapi.json`.
+    pub fn with_openapi(self, network: &str) -> Self {
+        const ENDPOINT: Endpoint =
+            Endpoint::get("/openapi.json", "Returns the OpenAPI specification of the REST server", Schema::Object);
+
+        let mut endpoints = self.endpoints.clone();
+        endpoints.push(ENDPOINT);
+        let spec = Arc::new(openapi_spec(network, &endpoints));
+        self.route(ENDPOINT, move || async move { ErasedJson::pretty(&*spec) })
+    }
+}
+
+impl<S> From<DocumentedRouter<S>> for axum::Router<S> {
+    /// Returns the routes, e.g. to mount them in an external `axum` application.
+    fn from(router: DocumentedRouter<S>) -> Self {
+        router.router
+    }
+}
+
+/// Returns the Swagger UI page for the OpenAPI specification at the given URL.
+///
+/// The assets of the Swagger UI are bundled with the node and served under `docs/`, relative to the page.
+#[cfg(feature = "swagger-ui")]
+pub fn swagger_ui(spec_url: &str) -> String {
+    format!(
+        r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <meta charset="utf-8" />
+  <title>snarkOS REST API</title>
+  <link rel="stylesheet" href="docs/swagger-ui.css" />
+</head>
+<body>
+  <div id="swagger-ui"></div>
+  <script src="docs/swagger-ui-bundle.js"></script>
+  <script>
+    window.onload = () => {{ window.ui = SwaggerUIBundle({{ url: "{spec_url}", dom_id: "#swagger-ui" }}); }};
+  </script>
+</body>
+</html>"##
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use axum::{body::Body, http::StatusCode};
+    use std::collections::HashSet;
+    use tower::ServiceExt;
+
+    /// Returns a router with a public and a protected endpoint, and its OpenAPI specification.
+    fn sample_router() -> axum::Router {
+        let protected = DocumentedRouter::new()
+            .route(Endpoint::get("/node/address", "Returns the address", Schema::String), || async { "address" })
+            .require_auth();
+        DocumentedRouter::new()
+            .route(
+                Endpoint::get("/block/{height_or_hash}", "Returns a block", Schema::Ref("Block"))
+                    .with_parameters(&[Parameter::path("height_or_hash", Schema::String, "The block height or hash.")])
+                    .with_block_reads(),
+                || async { "block" },
+            )
+            .merge(protected)
+            .with_openapi("mainnet")
+            .into()
+    }
+
+    /// Returns the response of the given router to a `GET` request to the given path.
+    async fn get(router: &axum::Router, path: &str) -> axum::response::Response {
+        router.clone().oneshot(Request::get(path).body(Body::empty()).unwrap()).await.unwrap()
+    }
+
+    #[test]
+    fn test_axum_path() {
+        let endpoint = Endpoint::get("/program/{id}/mapping/{name}", "", Schema::Object);
+        assert_eq!(endpoint.axum_path(), "/program/:id/mapping/:name");
+        let endpoint = Endpoint::get("/block/height/latest", "", Schema::Integer);
+        assert_eq!(endpoint.axum_path(), "/block/height/latest");
+    }
+
+    #[tokio::test]
+    async fn test_spec_lists_every_registered_route() {
+        let router = sample_router();
+
+        // Ensure the documented routes are served at the paths of their endpoints.
+        let response = get(&router, "/block/1").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = get(&router, "/node/address").await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        // Ensure the specification lists every registered route, including its own, with its auth requirement.
+        let response = get(&router, "/openapi.json").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let spec: Value = serde_json::from_slice(&body).unwrap();
+        let paths: HashSet<_> = spec["paths"].as_object().unwrap().keys().cloned().collect();
+        let expected = ["/mainnet/block/{height_or_hash}", "/mainnet/node/address", "/mainnet/openapi.json"];
+        assert_eq!(paths, expected.into_iter().map(String::from).collect());
+        assert!(spec["paths"]["/mainnet/block/{height_or_hash}"]["get"]["security"].is_null());
+        assert_eq!(spec["paths"]["/mainnet/node/address"]["get"]["security"], json!([{ "bearerAuth": [] }]));
+        assert!(spec["paths"]["/mainnet/openapi.json"]["get"].is_object());
+    }
+}
@@ -0,0 +1,453 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::RestError;
+
+use snarkvm::prelude::{Address, Field, Identifier, Network, Plaintext, ProgramID, puzzle::SolutionID};
+
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, RawPathParams, rejection::RawPathParamsRejection},
+    http::request::Parts,
+};
+use std::str::FromStr;
+
+/// The maximum length of a path parameter, in bytes, beyond which it is rejected without being parsed.
+pub const MAX_PATH_PARAM_LENGTH: usize = 1024;
+
+/// The length of the data and checksum of a bech32m-encoded 32-byte ID, e.g. a block hash or an address.
+const BECH32_ID_DATA_LENGTH: usize = 58;
+/// The maximum number of decimal digits of a field element.
+const MAX_FIELD_DIGITS: usize = 77;
+
+/// A type that is parsed from a path parameter.
+pub trait PathParam: Sized {
+    /// The expected format of the parameter, as reported if it is malformed.
+    const EXPECTED: &'static str;
+
+    /// Parses the parameter from its canonical form, or from one of its tolerated alternative forms.
+    fn parse_param(value: &str) -> Option<Self>;
+}
+
+/// An extractor of a single typed path parameter.
+pub struct Param<T>(pub T);
+
+/// An extractor of a tuple of typed path parameters, in the order of the route.
+pub struct Params<T>(pub T);
+
+/// Returns the percent-decoded path parameters of the request, in the order of the route.
+async fn raw_params<S: Send + Sync>(parts: &mut Parts, state: &S) -> Result<Vec<(String, String)>, RestError> {
+    match RawPathParams::from_request_parts(parts, state).await {
+        Ok(params) => Ok(params.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect()),
+        Err(RawPathParamsRejection::InvalidUtf8InPathParam(_)) => Err(RestError::InvalidParameter {
+            parameter: "path".to_string(),
+            expected: "a percent-encoded UTF-8 string",
+        }),
+        Err(rejection) => Err(RestError::InternalServerError(rejection.body_text())),
+    }
+}
+
+/// Parses the given path parameter, or returns the error naming the parameter and its expected format.
+fn parse<T: PathParam>((name, value): &(String, String)) -> Result<T, RestError> {
+    let invalid = || RestError::InvalidParameter { parameter: name.clone(), expected: T::EXPECTED };
+    // Ensure the parameter is bounded, prior to parsing it.
+    if value.len() > MAX_PATH_PARAM_LENGTH {
+        return Err(invalid());
+    }
+    T::parse_param(value).ok_or_else(invalid)
+}
+
+/// Returns an error if the route does not have the expected number of path parameters.
+fn ensure_num_params(params: &[(String, String)], expected: usize) -> Result<(), RestError> {
+    match params.len() == expected {
+        true => Ok(()),
+        false => Err(RestError::InternalServerError(format!(
+            "Expected {expected} path parameter(s), found {}",
+            params.len()
+        ))),
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync, T: PathParam> FromRequestParts<S> for Param<T> {
+    type Rejection = RestError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let params = raw_params(parts, state).await?;
+        ensure_num_params(&params, 1)?;
+        Ok(Self(parse(&params[0])?))
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync, A: PathParam, B: PathParam> FromRequestParts<S> for Params<(A, B)> {
+    type Rejection = RestError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let params = raw_params(parts, state).await?;
+        ensure_num_params(&params, 2)?;
+        Ok(Self((parse(&params[0])?, parse(&params[1])?)))
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync, A: PathParam, B: PathParam, C: PathParam> FromRequestParts<S> for Params<(A, B, C)> {
+    type Rejection = RestError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let params = raw_params(parts, state).await?;
+        ensure_num_params(&params, 3)?;
+        Ok(Self((parse(&params[0])?, parse(&params[1])?, parse(&params[2])?)))
+    }
+}
+
+/// Returns the given block height, if it is only decimal digits and fits in a `u32`.
+/// Note: Signs, whitespace, and other radixes are rejected; leading zeros are tolerated.
+fn parse_height(value: &str) -> Option<u32> {
+    match !value.is_empty() && value.bytes().all(|byte| byte.is_ascii_digit()) {
+        true => value.parse().ok(),
+        false => None,
+    }
+}
+
+/// Returns the canonical (lowercase) form of the given bech32m string, if it has the given prefix
+/// (including the separator), and the given length if any.
+/// Note: Bech32m strings are either all-lowercase or all-uppercase, so the uppercase form is tolerated.
+fn parse_bech32<T: FromStr>(value: &str, prefix: &str, length: Option<usize>) -> Option<T> {
+    if !value.is_ascii() || length.is_some_and(|length| value.len() != length) {
+        return None;
+    }
+    let (has_lowercase, has_uppercase) =
+        (value.bytes().any(|b| b.is_ascii_lowercase()), value.bytes().any(|b| b.is_ascii_uppercase()));
+    let canonical = match (has_lowercase, has_uppercase) {
+        (_, false) => value.to_string(),
+        (false, true) => value.to_ascii_lowercase(),
+        // Mixed case is invalid in bech32m.
+        (true, true) => return None,
+    };
+    match canonical.starts_with(prefix) {
+        true => canonical.parse().ok(),
+        false => None,
+    }
+}
+
+/// Returns the length of a bech32m-encoded 32-byte ID with the given prefix (including the separator).
+const fn bech32_id_length(prefix: &str) -> usize {
+    prefix.len() + BECH32_ID_DATA_LENGTH
+}
+
+/// A block height.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Height(pub u32);
+
+impl PathParam for Height {
+    const EXPECTED: &'static str = "a block height, as a decimal u32 (e.g. 12345)";
+
+    fn parse_param(value: &str) -> Option<Self> {
+        parse_height(value).map(Self)
+    }
+}
+
+/// A block height, or a block hash.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum HeightOrHash<N: Network> {
+    Height(u32),
+    Hash(N::BlockHash),
+}
+
+impl<N: Network> PathParam for HeightOrHash<N> {
+    const EXPECTED: &'static str = "a block height, as a decimal u32, or a block hash (ab1..., 61 characters)";
+
+    fn parse_param(value: &str) -> Option<Self> {
+        // Note: A numeric parameter is never a block hash, so it is not parsed as one if it overflows.
+        match value.bytes().all(|byte| byte.is_ascii_digit()) {
+            true => parse_height(value).map(Self::Height),
+            false => BlockHash::<N>::parse_param(value).map(|BlockHash(hash)| Self::Hash(hash)),
+        }
+    }
+}
+
+/// A block hash.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct BlockHash<N: Network>(pub N::BlockHash);
+
+impl<N: Network> PathParam for BlockHash<N> {
+    const EXPECTED: &'static str = "a block hash (ab1..., 61 characters)";
+
+    fn parse_param(value: &str) -> Option<Self> {
+        parse_bech32(value, "ab1", Some(bech32_id_length("ab1"))).map(Self)
+    }
+}
+
+/// A transaction ID.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TxId<N: Network>(pub N::TransactionID);
+
+impl<N: Network> PathParam for TxId<N> {
+    const EXPECTED: &'static str = "a transaction ID (at1..., 61 characters)";
+
+    fn parse_param(value: &str) -> Option<Self> {
+        parse_bech32(value, "at1", Some(bech32_id_length("at1"))).map(Self)
+    }
+}
+
+/// A transition ID.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TransitionId<N: Network>(pub N::TransitionID);
+
+impl<N: Network> PathParam for TransitionId<N> {
+    const EXPECTED: &'static str = "a transition ID (au1..., 61 characters)";
+
+    fn parse_param(value: &str) -> Option<Self> {
+        parse_bech32(value, "au1", Some(bech32_id_length("au1"))).map(Self)
+    }
+}
+
+/// A state root.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct StateRoot<N: Network>(pub N::StateRoot);
+
+impl<N: Network> PathParam for StateRoot<N> {
+    const EXPECTED: &'static str = "a state root (sr1..., 61 characters)";
+
+    fn parse_param(value: &str) -> Option<Self> {
+        parse_bech32(value, "sr1", Some(bech32_id_length("sr1"))).map(Self)
+    }
+}
+
+/// A field element that identifies a record commitment, or the input or output of a transition.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Commitment<N: Network>(pub Field<N>);
+
+impl<N: Network> PathParam for Commitment<N> {
+    const EXPECTED: &'static str = "a field element, in decimal with an optional 'field' suffix (e.g. 123field)";
+
+    fn parse_param(value: &str) -> Option<Self> {
+        // Note: The `field` suffix is canonical, and may be omitted.
+        let digits = value.strip_suffix("field").unwrap_or(value);
+        match !digits.is_empty() && digits.len() <= MAX_FIELD_DIGITS && digits.bytes().all(|b| b.is_ascii_digit()) {
+            true => Field::from_str(&format!("{digits}field")).ok().map(Self),
+            false => None,
+        }
+    }
+}
+
+impl<N: Network> PathParam for Address<N> {
+    const EXPECTED: &'static str = "an address (aleo1..., 63 characters)";
+
+    fn parse_param(value: &str) -> Option<Self> {
+        parse_bech32(value, "aleo1", Some(bech32_id_length("aleo1")))
+    }
+}
+
+impl<N: Network> PathParam for SolutionID<N> {
+    const EXPECTED: &'static str = "a solution ID (solution1...)";
+
+    fn parse_param(value: &str) -> Option<Self> {
+        parse_bech32(value, "solution1", None)
+    }
+}
+
+impl<N: Network> PathParam for ProgramID<N> {
+    const EXPECTED: &'static str = "a program ID (e.g. credits.aleo)";
+
+    fn parse_param(value: &str) -> Option<Self> {
+        Self::from_str(value).ok()
+    }
+}
+
+impl<N: Network> PathParam for Identifier<N> {
+    const EXPECTED: &'static str = "an identifier (e.g. account)";
+
+    fn parse_param(value: &str) -> Option<Self> {
+        Self::from_str(value).ok()
+    }
+}
+
+impl<N: Network> PathParam for Plaintext<N> {
+    const EXPECTED: &'static str = "a plaintext value (e.g. 1u64, or aleo1...)";
+
+    fn parse_param(value: &str) -> Option<Self> {
+        Self::from_str(value).ok()
+    }
+}
+
+#[cfg(feature = "history")]
+impl PathParam for snarkvm::synthesizer::MappingName {
+    const EXPECTED: &'static str = "a mapping name supported by the history (e.g. bonded)";
+
+    fn parse_param(value: &str) -> Option<Self> {
+        use serde::{Deserialize, de::IntoDeserializer};
+        Self::deserialize(IntoDeserializer::<serde::de::value::Error>::into_deserializer(value)).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::INVALID_PARAMETER_CODE;
+    use snarkvm::prelude::{FromBytes, MainnetV0, block::Block};
+
+    use axum::{Router, body::Body, http::Request, routing::get};
+    use http::StatusCode;
+    use tower::ServiceExt;
+
+    type CurrentNetwork = MainnetV0;
+
+    /// Returns a router with a route for each extractor, which responds with the canonical form of the parameters.
+    fn router() -> Router {
+        Router::new()
+            .route(
+                "/block/:height_or_hash",
+                get(|Param(id): Param<HeightOrHash<CurrentNetwork>>| async move {
+                    match id {
+                        HeightOrHash::Height(height) => height.to_string(),
+                        HeightOrHash::Hash(hash) => hash.to_string(),
+                    }
+                }),
+            )
+            .route("/stateRoot/:height", get(|Param(Height(height)): Param<Height>| async move { height.to_string() }))
+            .route(
+                "/transaction/:id",
+                get(|Param(TxId(id)): Param<TxId<CurrentNetwork>>| async move { id.to_string() }),
+            )
+            .route(
+                "/statePath/:commitment",
+                get(|Param(Commitment(commitment)): Param<Commitment<CurrentNetwork>>| async move {
+                    commitment.to_string()
+                }),
+            )
+            .route(
+                "/delegators/:validator",
+                get(|Param(address): Param<Address<CurrentNetwork>>| async move { address.to_string() }),
+            )
+            .route(
+                "/program/:id/mapping/:name/:key",
+                get(
+                    |Params((id, name, key)): Params<(
+                        ProgramID<CurrentNetwork>,
+                        Identifier<CurrentNetwork>,
+                        Plaintext<CurrentNetwork>,
+                    )>| async move { format!("{id}/{name}/{key}") },
+                ),
+            )
+    }
+
+    /// Returns the status and the body of the response to the given path.
+    async fn request(path: &str) -> (StatusCode, String) {
+        let response = router().oneshot(Request::get(path).body(Body::empty()).unwrap()).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    /// Asserts that the given path is rejected, naming the given parameter.
+    async fn assert_invalid(path: &str, parameter: &str) {
+        let (status, body) = request(path).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{path}");
+        let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(body["error"]["code"], INVALID_PARAMETER_CODE, "{path}");
+        assert_eq!(body["error"]["parameter"], parameter, "{path}");
+        assert!(body["error"]["expected"].as_str().is_some_and(|expected| !expected.is_empty()), "{path}");
+    }
+
+    /// Asserts that the given path is accepted, with the given canonical form of the parameters.
+    async fn assert_valid(path: &str, canonical: &str) {
+        assert_eq!(request(path).await, (StatusCode::OK, canonical.to_string()), "{path}");
+    }
+
+    #[tokio::test]
+    async fn test_heights() {
+        // Ensure the boundaries of a `u32` are accepted.
+        assert_valid("/stateRoot/0", "0").await;
+        assert_valid(&format!("/stateRoot/{}", u32::MAX), &u32::MAX.to_string()).await;
+        // Ensure leading zeros are tolerated.
+        assert_valid("/stateRoot/007", "7").await;
+
+        // Ensure heights beyond a `u32`, signs, whitespace, and other radixes are rejected.
+        let overflow = (u32::MAX as u64 + 1).to_string();
+        for height in [overflow.as_str(), "99999999999999999999", "-1", "+1", "%201", "0x10", "1.0"] {
+            assert_invalid(&format!("/stateRoot/{height}"), "height").await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_heights_or_hashes() {
+        let hash = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap().hash().to_string();
+
+        // Ensure a height and a hash are accepted, and the uppercase form of the hash is canonicalized.
+        assert_valid("/block/12", "12").await;
+        assert_valid(&format!("/block/{hash}"), &hash).await;
+        assert_valid(&format!("/block/{}", hash.to_uppercase()), &hash).await;
+
+        // Ensure an overflowing height is not parsed as a hash.
+        assert_invalid(&format!("/block/{}", u32::MAX as u64 + 1), "height_or_hash").await;
+        // Ensure a hash of the wrong length, prefix, or mixed case is rejected.
+        assert_invalid(&format!("/block/{}", &hash[..hash.len() - 1]), "height_or_hash").await;
+        assert_invalid(&format!("/block/{hash}q"), "height_or_hash").await;
+        assert_invalid(&format!("/block/at{}", &hash[2..]), "height_or_hash").await;
+        assert_invalid(&format!("/block/AB{}", &hash[2..]), "height_or_hash").await;
+        // Ensure a hash with an invalid checksum is rejected.
+        let corrupted = format!("{}{}", &hash[..hash.len() - 1], if hash.ends_with('q') { 'p' } else { 'q' });
+        assert_invalid(&format!("/block/{corrupted}"), "height_or_hash").await;
+    }
+
+    #[tokio::test]
+    async fn test_ids() {
+        let block = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let tx_id = block.transaction_ids().next().unwrap().to_string();
+
+        // Ensure the canonical and uppercase forms of a transaction ID are accepted.
+        assert_valid(&format!("/transaction/{tx_id}"), &tx_id).await;
+        assert_valid(&format!("/transaction/{}", tx_id.to_uppercase()), &tx_id).await;
+        // Ensure an ID of another kind, or of the wrong length, is rejected.
+        assert_invalid(&format!("/transaction/{}", block.hash()), "id").await;
+        assert_invalid(&format!("/transaction/{}", &tx_id[..tx_id.len() - 1]), "id").await;
+
+        // Ensure the canonical and uppercase forms of an address are accepted.
+        let address = block.authority().to_address().to_string();
+        assert_valid(&format!("/delegators/{address}"), &address).await;
+        assert_valid(&format!("/delegators/{}", address.to_uppercase()), &address).await;
+        assert_invalid(&format!("/delegators/{}", &address[..address.len() - 1]), "validator").await;
+    }
+
+    #[tokio::test]
+    async fn test_commitments() {
+        // Ensure the `field` suffix is canonical, and may be omitted.
+        assert_valid("/statePath/123field", "123field").await;
+        assert_valid("/statePath/123", "123field").await;
+
+        // Ensure other types, signs, and unbounded values are rejected.
+        let unbounded = "9".repeat(MAX_FIELD_DIGITS + 1);
+        for commitment in ["field", "123u64", "-1field", "0x1field", unbounded.as_str()] {
+            assert_invalid(&format!("/statePath/{commitment}"), "commitment").await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_multiple_params() {
+        // Ensure the parameters are percent-decoded, and parsed in the order of the route.
+        assert_valid("/program/credits.aleo/mapping/account/1u64", "credits.aleo/account/1u64").await;
+        assert_valid("/program/credits%2Ealeo/mapping/account/1u64", "credits.aleo/account/1u64").await;
+
+        // Ensure the malformed parameter is named.
+        assert_invalid("/program/credits/mapping/account/1u64", "id").await;
+        assert_invalid("/program/credits.aleo/mapping/1account/1u64", "name").await;
+        assert_invalid("/program/credits.aleo/mapping/account/1u65", "key").await;
+        // Ensure an unbounded parameter is rejected.
+        let unbounded = "1".repeat(MAX_PATH_PARAM_LENGTH + 1);
+        assert_invalid(&format!("/program/credits.aleo/mapping/account/{unbounded}"), "key").await;
+    }
+}
@@ -0,0 +1,94 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::RestError;
+
+use parking_lot::Mutex;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// The minimum interval in seconds between the start of two block previews.
+pub const MIN_BLOCK_PREVIEW_INTERVAL_IN_SECS: u64 = 5;
+
+/// A gate admitting one block preview at a time, and at most one per interval.
+///
+/// A block preview speculates a whole block, so it is far more expensive than any other request,
+/// and is admitted regardless of the caller, on top of the rate limit of the caller's IP.
+pub struct PreviewGate {
+    /// The minimum interval between the start of two previews.
+    interval: Duration,
+    /// Whether a preview is in progress, and when the last preview started.
+    state: Mutex<(bool, Option<Instant>)>,
+}
+
+impl Default for PreviewGate {
+    /// Initializes a gate with the default interval.
+    fn default() -> Self {
+        Self::new(Duration::from_secs(MIN_BLOCK_PREVIEW_INTERVAL_IN_SECS))
+    }
+}
+
+impl PreviewGate {
+    /// Initializes a gate with the given minimum interval between the start of two previews.
+    pub fn new(interval: Duration) -> Self {
+        Self { interval, state: Default::default() }
+    }
+
+    /// Returns a permit to run a preview, or an error if a preview is in progress or started too recently.
+    pub fn try_acquire(self: &Arc<Self>) -> Result<PreviewPermit, RestError> {
+        let mut state = self.state.lock();
+        let (in_progress, last_started) = &mut *state;
+        if *in_progress {
+            return Err(RestError::ServiceUnavailable("A block preview is already in progress".to_string()));
+        }
+        if last_started.is_some_and(|last_started| last_started.elapsed() < self.interval) {
+            return Err(RestError::ServiceUnavailable("A block preview was taken too recently".to_string()));
+        }
+        *in_progress = true;
+        *last_started = Some(Instant::now());
+        Ok(PreviewPermit(self.clone()))
+    }
+}
+
+/// A permit to run a block preview, which releases the gate when dropped.
+pub struct PreviewPermit(Arc<PreviewGate>);
+
+impl Drop for PreviewPermit {
+    fn drop(&mut self) {
+        self.0.state.lock().0 = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preview_gate() {
+        // Ensure only one preview runs at a time.
+        let gate = Arc::new(PreviewGate::new(Duration::ZERO));
+        let permit = gate.try_acquire().unwrap();
+        assert!(gate.try_acquire().is_err());
+        drop(permit);
+        assert!(gate.try_acquire().is_ok());
+
+        // Ensure the previews are spaced by the interval.
+        let gate = Arc::new(PreviewGate::new(Duration::from_secs(60)));
+        drop(gate.try_acquire().unwrap());
+        assert!(gate.try_acquire().is_err());
+    }
+}
@@ -0,0 +1,273 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{
+    Address,
+    Ledger,
+    Network,
+    block::{Authority, Block, Ratify},
+    store::ConsensusStorage,
+};
+
+use anyhow::Result;
+use parking_lot::RwLock;
+use serde::Serialize;
+use std::collections::VecDeque;
+
+/// The default number of recent block summaries retained by the REST server.
+pub const DEFAULT_RECENT_BLOCKS_CAPACITY: usize = 100;
+/// The interval in milliseconds at which the recent block summaries are updated from the ledger.
+pub const RECENT_BLOCKS_UPDATE_INTERVAL_IN_MS: u64 = 500;
+
+/// A summary of a block, for lightweight explorers.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(bound = "")]
+pub struct BlockSummary<N: Network> {
+    /// The block height.
+    pub height: u32,
+    /// The block hash.
+    pub hash: N::BlockHash,
+    /// The UNIX timestamp of the block.
+    pub timestamp: i64,
+    /// The number of transactions in the block.
+    pub num_transactions: usize,
+    /// The number of solutions in the block.
+    pub num_solutions: usize,
+    /// The address of the leader of the committed subdag, if the block was produced by a quorum.
+    pub leader: Option<Address<N>>,
+    /// The block reward, in microcredits.
+    pub block_reward: u64,
+}
+
+impl<N: Network> BlockSummary<N> {
+    /// Returns the summary of the given block.
+    pub fn new(block: &Block<N>) -> Self {
+        // Retrieve the leader from the subdag, if the block was produced by a quorum.
+        let leader = match block.authority() {
+            Authority::Quorum(subdag) => Some(subdag.leader_address()),
+            Authority::Beacon(_) => None,
+        };
+        // Retrieve the block reward from the ratifications.
+        let block_reward = block
+            .ratifications()
+            .iter()
+            .find_map(|ratify| match ratify {
+                Ratify::BlockReward(amount) => Some(*amount),
+                _ => None,
+            })
+            .unwrap_or_default();
+
+        Self {
+            height: block.height(),
+            hash: block.hash(),
+            timestamp: block.timestamp(),
+            num_transactions: block.transactions().len(),
+            num_solutions: block.solutions().len(),
+            leader,
+            block_reward,
+        }
+    }
+}
+
+/// A bounded history of the summaries of the most recent blocks.
+///
+/// The history is appended to by a single task following the ledger, while any number of readers
+/// may query it concurrently; the lock is only held while a summary is inserted or copied out.
+pub struct RecentBlocks<N: Network> {
+    /// The maximum number of summaries retained.
+    capacity: usize,
+    /// The summaries, ordered by ascending height.
+    summaries: RwLock<VecDeque<BlockSummary<N>>>,
+}
+
+impl<N: Network> RecentBlocks<N> {
+    /// Initializes a new history, retaining up to the given number of summaries.
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity, summaries: RwLock::new(VecDeque::with_capacity(capacity)) }
+    }
+
+    /// Returns the maximum number of summaries retained.
+    pub const fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the height of the latest summary, if any.
+    pub fn latest_height(&self) -> Option<u32> {
+        self.summaries.read().back().map(|summary| summary.height)
+    }
+
+    /// Returns up to `limit` of the most recent summaries, newest first.
+    pub fn latest(&self, limit: usize) -> Vec<BlockSummary<N>> {
+        self.summaries.read().iter().rev().take(limit).cloned().collect()
+    }
+
+    /// Appends the given summary, evicting the oldest summary if the history is full.
+    /// If the summary does not extend the latest summary, any summaries at or above its height are replaced.
+    pub fn push(&self, summary: BlockSummary<N>) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut summaries = self.summaries.write();
+        // Remove any summaries that are not below the new summary, such as after a rollback.
+        while summaries.back().is_some_and(|latest| latest.height >= summary.height) {
+            summaries.pop_back();
+        }
+        summaries.push_back(summary);
+        // Evict the oldest summaries.
+        while summaries.len() > self.capacity {
+            summaries.pop_front();
+        }
+    }
+
+    /// Removes the summaries at or above the given height, such as after the ledger was rewound.
+    pub fn truncate(&self, height: u32) {
+        let mut summaries = self.summaries.write();
+        while summaries.back().is_some_and(|latest| latest.height >= height) {
+            summaries.pop_back();
+        }
+    }
+
+    /// Appends the summaries of any blocks in the ledger after the latest summary.
+    /// If the history is empty, such as after a restart, it is repopulated from the ledger's latest blocks.
+    ///
+    /// If the ledger was rewound, the summaries of the blocks that are no longer in the ledger are removed first,
+    /// so that they are not served, and the summaries of any blocks that replaced them are appended instead.
+    pub fn update<C: ConsensusStorage<N>>(&self, ledger: &Ledger<N, C>) -> Result<()> {
+        let latest_height = ledger.latest_height();
+        // Remove the summaries above the latest height of the ledger.
+        self.truncate(latest_height.saturating_add(1));
+        // Remove the summaries of the blocks that were replaced in the ledger, from the latest one.
+        // Note: The lock is released before the ledger is read, and before the summaries are truncated.
+        loop {
+            let latest = self.summaries.read().back().map(|latest| (latest.height, latest.hash));
+            match latest {
+                Some((height, hash)) if ledger.get_hash(height)? != hash => self.truncate(height),
+                _ => break,
+            }
+        }
+        // Determine the first height to append, skipping any blocks that would be evicted immediately.
+        let start_height = self.latest_height().map_or(0, |height| height.saturating_add(1));
+        let start_height = start_height.max(latest_height.saturating_add(1).saturating_sub(self.capacity as u32));
+        for height in start_height..=latest_height {
+            // The block is loaded outside of the lock, so readers are not blocked.
+            self.push(BlockSummary::new(&ledger.get_block(height)?));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkos_node_bft_ledger_service::test_helpers;
+    use snarkvm::{ledger::store::helpers::memory::ConsensusMemory, prelude::MainnetV0};
+
+    use rand::SeedableRng;
+    use rand_chacha::ChaChaRng;
+
+    type CurrentNetwork = MainnetV0;
+
+    /// Returns a sample devnet ledger with the given number of blocks after genesis.
+    fn sample_ledger(num_blocks: u32) -> Ledger<CurrentNetwork, ConsensusMemory<CurrentNetwork>> {
+        sample_ledger_with_seed(1234567890u64, num_blocks)
+    }
+
+    /// Returns a sample devnet ledger from the given seed, with the given number of blocks after genesis.
+    fn sample_ledger_with_seed(seed: u64, num_blocks: u32) -> Ledger<CurrentNetwork, ConsensusMemory<CurrentNetwork>> {
+        test_helpers::sample_ledger(num_blocks, &mut ChaChaRng::seed_from_u64(seed)).1
+    }
+
+    #[test]
+    fn test_recent_blocks() {
+        let ledger = sample_ledger(8);
+        let recent_blocks = RecentBlocks::new(5);
+
+        // Ensure the history is populated with the latest blocks, newest first.
+        recent_blocks.update(&ledger).unwrap();
+        let summaries = recent_blocks.latest(usize::MAX);
+        assert_eq!(summaries.iter().map(|s| s.height).collect::<Vec<_>>(), vec![8, 7, 6, 5, 4]);
+
+        // Ensure the summary fields match the blocks.
+        for summary in &summaries {
+            let block = ledger.get_block(summary.height).unwrap();
+            assert_eq!(summary.hash, block.hash());
+            assert_eq!(summary.timestamp, block.timestamp());
+            assert_eq!(summary.num_transactions, block.transactions().len());
+            assert_eq!(summary.num_solutions, block.solutions().len());
+            // Beacon blocks do not have a leader.
+            assert_eq!(summary.leader, None);
+            assert!(summary.block_reward > 0);
+        }
+
+        // Ensure the limit is respected.
+        assert_eq!(recent_blocks.latest(2), summaries[..2].to_vec());
+
+        // Ensure the JSON output has the expected fields.
+        let json = serde_json::to_value(&summaries[0]).unwrap();
+        assert_eq!(json["height"], 8);
+        assert_eq!(json["hash"], ledger.get_hash(8).unwrap().to_string());
+        assert!(json["leader"].is_null());
+    }
+
+    #[test]
+    fn test_recent_blocks_push() {
+        let ledger = sample_ledger(4);
+        let summary = |height| BlockSummary::new(&ledger.get_block(height).unwrap());
+        let heights = |recent_blocks: &RecentBlocks<CurrentNetwork>| {
+            recent_blocks.latest(usize::MAX).iter().map(|s| s.height).collect::<Vec<_>>()
+        };
+
+        // Ensure the oldest summaries are evicted.
+        let recent_blocks = RecentBlocks::new(3);
+        for height in 0..=4 {
+            recent_blocks.push(summary(height));
+        }
+        assert_eq!(heights(&recent_blocks), vec![4, 3, 2]);
+
+        // Ensure a summary at or below the latest height replaces the summaries above it.
+        recent_blocks.push(summary(3));
+        assert_eq!(heights(&recent_blocks), vec![3, 2]);
+
+        // Ensure the summaries at or above a height are removed on truncation.
+        recent_blocks.truncate(3);
+        assert_eq!(heights(&recent_blocks), vec![2]);
+
+        // Ensure a history with no capacity stays empty.
+        let recent_blocks = RecentBlocks::new(0);
+        recent_blocks.update(&ledger).unwrap();
+        assert!(recent_blocks.latest(usize::MAX).is_empty());
+    }
+
+    #[test]
+    fn test_recent_blocks_after_rewind() {
+        let ledger = sample_ledger(8);
+        let recent_blocks = RecentBlocks::new(5);
+        recent_blocks.update(&ledger).unwrap();
+        assert_eq!(recent_blocks.latest_height(), Some(8));
+
+        // Rewind to a shorter ledger whose blocks differ, and ensure none of the stale summaries are served.
+        let rewound = sample_ledger_with_seed(987654321u64, 3);
+        recent_blocks.update(&rewound).unwrap();
+        let summaries = recent_blocks.latest(usize::MAX);
+        assert_eq!(summaries.iter().map(|s| s.height).collect::<Vec<_>>(), vec![3, 2, 1, 0]);
+        assert!(summaries.iter().all(|s| s.hash == rewound.get_hash(s.height).unwrap()));
+
+        // Ensure the summaries that are still in the ledger are retained, and the new blocks are appended.
+        recent_blocks.update(&ledger).unwrap();
+        let summaries = recent_blocks.latest(usize::MAX);
+        assert_eq!(summaries.iter().map(|s| s.height).collect::<Vec<_>>(), vec![8, 7, 6, 5, 4]);
+        assert!(summaries.iter().all(|s| s.hash == ledger.get_hash(s.height).unwrap()));
+    }
+}
@@ -0,0 +1,300 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{
+    Ledger,
+    Network,
+    ProgramID,
+    block::{Block, ConfirmedTransaction},
+    store::ConsensusStorage,
+};
+
+use anyhow::Result;
+use parking_lot::RwLock;
+use serde::Serialize;
+use std::collections::VecDeque;
+
+/// The default number of recent rejected deployments retained by the REST server.
+pub const DEFAULT_REJECTED_DEPLOYMENTS_CAPACITY: usize = 100;
+/// The maximum number of the latest blocks scanned for rejected deployments, when the history is empty.
+/// This is also the number of scanned blocks whose hash is retained, to find the fork point of a reorg.
+const MAX_REJECTED_DEPLOYMENTS_BACKFILL: u32 = 100;
+
+/// A deployment that was included in a block as rejected, so that only its fee was charged.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(bound = "")]
+pub struct RejectedDeployment<N: Network> {
+    /// The ID of the deployment transaction, as it was broadcast.
+    pub transaction_id: N::TransactionID,
+    /// The ID of the fee transaction that was confirmed in its place.
+    pub fee_transaction_id: N::TransactionID,
+    /// The ID of the program that was not deployed.
+    pub program_id: ProgramID<N>,
+    /// The height of the block that includes the rejected deployment.
+    pub height: u32,
+}
+
+impl<N: Network> RejectedDeployment<N> {
+    /// Returns the rejected deployment of the given confirmed transaction, in the block at the given height,
+    /// or `None` if the transaction is not a rejected deployment.
+    ///
+    /// Note: The block does not record the error that rejected the deployment, so no reason is given.
+    pub fn new(height: u32, confirmed: &ConfirmedTransaction<N>) -> Result<Option<Self>> {
+        let ConfirmedTransaction::RejectedDeploy(_, fee_transaction, rejected, _) = confirmed else {
+            return Ok(None);
+        };
+        let Some(deployment) = rejected.deployment() else {
+            return Ok(None);
+        };
+        Ok(Some(Self {
+            transaction_id: confirmed.to_unconfirmed_transaction_id()?,
+            fee_transaction_id: fee_transaction.id(),
+            program_id: *deployment.program_id(),
+            height,
+        }))
+    }
+
+    /// Returns the rejected deployments in the given block.
+    pub fn in_block(block: &Block<N>) -> Result<Vec<Self>> {
+        let mut rejected = Vec::new();
+        for confirmed in block.transactions().iter() {
+            if let Some(deployment) = Self::new(block.height(), confirmed)? {
+                rejected.push(deployment);
+            }
+        }
+        Ok(rejected)
+    }
+}
+
+/// The status of a deployment transaction in the ledger.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(bound = "", tag = "status", rename_all = "snake_case")]
+pub enum DeploymentStatus<N: Network> {
+    /// The deployment was accepted, and the program is live.
+    Accepted { program_id: ProgramID<N>, height: u32 },
+    /// The deployment was rejected, and only its fee was charged.
+    Rejected(RejectedDeployment<N>),
+    /// The transaction is not a deployment in a block, e.g. it is still in the memory pool, or was aborted.
+    Unknown,
+}
+
+impl<N: Network> DeploymentStatus<N> {
+    /// Returns the status of the given deployment transaction, as it was broadcast.
+    ///
+    /// Note: The ledger indexes the rejected transactions by the ID they were broadcast with,
+    /// so the status persists across restarts.
+    pub fn load<C: ConsensusStorage<N>>(ledger: &Ledger<N, C>, transaction_id: &N::TransactionID) -> Result<Self> {
+        let Some(block_hash) = ledger.find_block_hash(transaction_id)? else {
+            return Ok(Self::Unknown);
+        };
+        let height = ledger.get_height(&block_hash)?;
+        let block = ledger.get_block(height)?;
+        let confirmed = block.transactions().iter().find(|confirmed| match confirmed {
+            ConfirmedTransaction::AcceptedDeploy(_, transaction, _) => transaction.id() == *transaction_id,
+            ConfirmedTransaction::RejectedDeploy(..) => {
+                confirmed.to_unconfirmed_transaction_id().is_ok_and(|id| id == *transaction_id)
+            }
+            _ => false,
+        });
+        match confirmed {
+            Some(ConfirmedTransaction::AcceptedDeploy(_, transaction, _)) => match transaction.deployment() {
+                Some(deployment) => Ok(Self::Accepted { program_id: *deployment.program_id(), height }),
+                None => Ok(Self::Unknown),
+            },
+            Some(confirmed) => Ok(RejectedDeployment::new(height, confirmed)?.map_or(Self::Unknown, Self::Rejected)),
+            None => Ok(Self::Unknown),
+        }
+    }
+}
+
+/// A bounded history of the most recent rejected deployments.
+///
+/// As with the recent block summaries, the history is appended to by a single task following the ledger.
+pub struct RejectedDeployments<N: Network> {
+    /// The maximum number of rejected deployments retained.
+    capacity: usize,
+    /// The heights and hashes of the latest blocks scanned for rejected deployments, ordered by ascending height.
+    scanned: RwLock<VecDeque<(u32, N::BlockHash)>>,
+    /// The rejected deployments, ordered by ascending height.
+    deployments: RwLock<VecDeque<RejectedDeployment<N>>>,
+}
+
+impl<N: Network> RejectedDeployments<N> {
+    /// Initializes a new history, retaining up to the given number of rejected deployments.
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity, scanned: Default::default(), deployments: Default::default() }
+    }
+
+    /// Returns the maximum number of rejected deployments retained.
+    pub const fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns up to `limit` of the most recent rejected deployments, newest first.
+    pub fn latest(&self, limit: usize) -> Vec<RejectedDeployment<N>> {
+        self.deployments.read().iter().rev().take(limit).cloned().collect()
+    }
+
+    /// Appends the rejected deployments in the given block, evicting the oldest ones if the history is full.
+    /// If the block does not extend the latest scanned block, any rejected deployments at or above its height
+    /// are replaced.
+    fn push(&self, height: u32, hash: N::BlockHash, rejected: Vec<RejectedDeployment<N>>) {
+        // Remove any rejected deployments that are not below the block, such as after a rollback.
+        self.truncate(height);
+        let mut deployments = self.deployments.write();
+        deployments.extend(rejected);
+        // Evict the oldest rejected deployments.
+        while deployments.len() > self.capacity {
+            deployments.pop_front();
+        }
+        drop(deployments);
+        let mut scanned = self.scanned.write();
+        scanned.push_back((height, hash));
+        while scanned.len() > MAX_REJECTED_DEPLOYMENTS_BACKFILL as usize {
+            scanned.pop_front();
+        }
+    }
+
+    /// Removes the rejected deployments and the scanned blocks at or above the given height.
+    fn truncate(&self, height: u32) {
+        let mut deployments = self.deployments.write();
+        while deployments.back().is_some_and(|latest| latest.height >= height) {
+            deployments.pop_back();
+        }
+        let mut scanned = self.scanned.write();
+        while scanned.back().is_some_and(|(latest, _)| *latest >= height) {
+            scanned.pop_back();
+        }
+    }
+
+    /// Scans any blocks in the ledger after the latest scanned block for rejected deployments.
+    /// If no block was scanned yet, such as after a restart, the ledger's latest blocks are scanned.
+    ///
+    /// If the ledger rolled back or reorged, the rejected deployments of the blocks that are no longer in the ledger
+    /// are removed first, and the blocks are rescanned from the fork point, as with the recent block summaries.
+    pub fn update<C: ConsensusStorage<N>>(&self, ledger: &Ledger<N, C>) -> Result<()> {
+        if self.capacity == 0 {
+            return Ok(());
+        }
+        let latest_height = ledger.latest_height();
+        // Remove the scanned blocks that are no longer in the ledger, from the latest one, to find the fork point.
+        // Note: The lock is released before the ledger is read, and before the history is truncated.
+        let mut is_reorged = false;
+        loop {
+            let latest = self.scanned.read().back().copied();
+            match latest {
+                Some((height, hash)) if height > latest_height || ledger.get_hash(height)? != hash => {
+                    self.truncate(height);
+                    is_reorged = true;
+                }
+                _ => break,
+            }
+        }
+        let start_height = match self.scanned.read().back() {
+            Some((height, _)) => height.saturating_add(1),
+            None => {
+                // If the fork point precedes every retained scanned block, no rejected deployment can be trusted.
+                if is_reorged {
+                    self.deployments.write().clear();
+                }
+                latest_height.saturating_add(1).saturating_sub(MAX_REJECTED_DEPLOYMENTS_BACKFILL)
+            }
+        };
+        for height in start_height..=latest_height {
+            // The block is loaded outside of the lock, so readers are not blocked.
+            let block = ledger.get_block(height)?;
+            self.push(height, block.hash(), RejectedDeployment::in_block(&block)?);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkos_node_bft_ledger_service::test_helpers::sample_ledger;
+    use snarkvm::prelude::{Field, MainnetV0};
+
+    use rand::SeedableRng;
+    use rand_chacha::ChaChaRng;
+    use std::str::FromStr;
+
+    type CurrentNetwork = MainnetV0;
+
+    /// Returns a sample rejected deployment at the given height.
+    fn sample_rejected(height: u32) -> RejectedDeployment<CurrentNetwork> {
+        RejectedDeployment {
+            transaction_id: Field::from_u64(height as u64).into(),
+            fee_transaction_id: Field::from_u64(height as u64).into(),
+            program_id: ProgramID::from_str("credits.aleo").unwrap(),
+            height,
+        }
+    }
+
+    /// Returns a sample block hash, which is not the hash of any block in a sample ledger.
+    fn sample_hash(height: u32) -> <CurrentNetwork as Network>::BlockHash {
+        Field::from_u64(u64::MAX - height as u64).into()
+    }
+
+    /// Returns the heights of the rejected deployments in the given history, newest first.
+    fn heights(recent: &RejectedDeployments<CurrentNetwork>) -> Vec<u32> {
+        recent.latest(usize::MAX).iter().map(|rejected| rejected.height).collect()
+    }
+
+    #[test]
+    fn test_rejected_deployments_push() {
+        // Ensure the oldest rejected deployments are evicted.
+        let recent = RejectedDeployments::new(3);
+        for height in 0..=4 {
+            recent.push(height, sample_hash(height), vec![sample_rejected(height)]);
+        }
+        assert_eq!(heights(&recent), vec![4, 3, 2]);
+
+        // Ensure a block at or below the latest height replaces the rejected deployments above it.
+        recent.push(3, sample_hash(3), vec![]);
+        assert_eq!(heights(&recent), vec![2]);
+        assert_eq!(recent.scanned.read().back(), Some(&(3, sample_hash(3))));
+    }
+
+    #[test]
+    fn test_rejected_deployments_after_reorg() {
+        let ledger = sample_ledger::<CurrentNetwork, _>(8, &mut ChaChaRng::seed_from_u64(1234567890u64)).1;
+        let recent = RejectedDeployments::new(10);
+
+        // Scan the blocks of the ledger up to height 4, then orphaned blocks up to height 6,
+        // as if the ledger reorged to its blocks after height 4 since the last update.
+        for height in 0..=4 {
+            recent.push(height, ledger.get_hash(height).unwrap(), vec![]);
+        }
+        recent.push(3, ledger.get_hash(3).unwrap(), vec![sample_rejected(3)]);
+        recent.push(4, ledger.get_hash(4).unwrap(), vec![]);
+        recent.push(5, sample_hash(5), vec![sample_rejected(5)]);
+        recent.push(6, sample_hash(6), vec![sample_rejected(6)]);
+        assert_eq!(heights(&recent), vec![6, 5, 3]);
+
+        // Ensure the update drops the rejected deployments of the orphaned blocks, even though the ledger is past
+        // their height, and rescans the ledger from the fork point.
+        recent.update(&ledger).unwrap();
+        assert_eq!(heights(&recent), vec![3]);
+        let scanned: Vec<_> = recent.scanned.read().iter().copied().collect();
+        assert_eq!(scanned, (0..=8).map(|height| (height, ledger.get_hash(height).unwrap())).collect::<Vec<_>>());
+
+        // Ensure a reorg beyond every scanned block drops every rejected deployment.
+        let reorged = sample_ledger::<CurrentNetwork, _>(10, &mut ChaChaRng::seed_from_u64(987654321u64)).1;
+        recent.update(&reorged).unwrap();
+        assert!(recent.latest(usize::MAX).is_empty());
+        assert_eq!(recent.scanned.read().back(), Some(&(10, reorged.latest_hash())));
+    }
+}
@@ -0,0 +1,92 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use serde::Serialize;
+use std::{net::SocketAddr, time::Duration};
+
+/// A restricted peer, with the time it was restricted and the remaining duration of its restriction.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct RestrictedPeer {
+    /// The IP of the peer.
+    pub ip: SocketAddr,
+    /// The UNIX timestamp at which the peer was restricted.
+    pub restricted_at: i64,
+    /// The remaining duration in seconds of the restriction.
+    pub remaining_secs: u64,
+}
+
+/// The restricted peers of the node, with the counts of its trusted and candidate peers.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct RestrictedPeers {
+    /// The restricted peers whose restriction has not expired, sorted by IP.
+    pub restricted_peers: Vec<RestrictedPeer>,
+    /// The number of trusted peers.
+    pub num_trusted_peers: usize,
+    /// The number of candidate peers.
+    pub num_candidate_peers: usize,
+}
+
+impl RestrictedPeers {
+    /// Initializes the restricted peers at the given UNIX timestamp, from the active restrictions of the router,
+    /// i.e. the restricted peers with the time elapsed since they were restricted, and their remaining duration.
+    pub fn new(
+        now: i64,
+        restrictions: impl IntoIterator<Item = (SocketAddr, Duration, u64)>,
+        num_trusted_peers: usize,
+        num_candidate_peers: usize,
+    ) -> Self {
+        let restricted_peers = restrictions
+            .into_iter()
+            .map(|(ip, elapsed, remaining_secs)| RestrictedPeer {
+                ip,
+                restricted_at: now.saturating_sub(i64::try_from(elapsed.as_secs()).unwrap_or(i64::MAX)),
+                remaining_secs,
+            })
+            .collect();
+        Self { restricted_peers, num_trusted_peers, num_candidate_peers }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_restricted_peers() {
+        let ip: SocketAddr = "1.2.3.4:4130".parse().unwrap();
+        let restrictions = [(ip, Duration::from_millis(20_500), 130)];
+
+        // The restriction time is derived from the time elapsed since the restriction.
+        let restricted_peers = RestrictedPeers::new(1_000, restrictions, 2, 3);
+        assert_eq!(restricted_peers.restricted_peers, vec![RestrictedPeer {
+            ip,
+            restricted_at: 980,
+            remaining_secs: 130
+        }]);
+        assert_eq!(
+            serde_json::to_value(&restricted_peers).unwrap(),
+            json!({
+                "restricted_peers": [{ "ip": "1.2.3.4:4130", "restricted_at": 980, "remaining_secs": 130 }],
+                "num_trusted_peers": 2,
+                "num_candidate_peers": 3,
+            })
+        );
+
+        // If nothing is restricted, the restricted peers are an empty array.
+        let restricted_peers = RestrictedPeers::new(1_000, [], 0, 0);
+        assert_eq!(serde_json::to_value(&restricted_peers).unwrap()["restricted_peers"], json!([]));
+    }
+}
@@ -0,0 +1,117 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::{
+    ledger::puzzle::SolutionID,
+    prelude::{
+        Ledger,
+        Network,
+        block::{Block, Ratify},
+        store::ConsensusStorage,
+    },
+};
+
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+
+/// The inclusion of a solution in the ledger.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct SolutionInclusion {
+    /// The height of the block containing the solution.
+    pub height: u32,
+    /// The hash of the block containing the solution.
+    pub block_hash: String,
+    /// The reward attributed to the solution, in microcredits.
+    pub reward: u64,
+}
+
+impl SolutionInclusion {
+    /// Returns the inclusion of the given solution, or `None` if the solution is not in the ledger.
+    ///
+    /// Note: The block height is found with the solution ID index of the ledger, so only that block is loaded.
+    pub fn find<N: Network, C: ConsensusStorage<N>>(
+        ledger: &Ledger<N, C>,
+        solution_id: &SolutionID<N>,
+    ) -> Result<Option<Self>> {
+        let Some(height) = ledger.find_block_height_from_solution_id(solution_id)? else {
+            return Ok(None);
+        };
+        let block = ledger.get_block(height)?;
+        let Some(reward) = solution_reward(&block, solution_id) else {
+            bail!("Block {height} does not contain the solution '{solution_id}'");
+        };
+        Ok(Some(Self { height, block_hash: block.hash().to_string(), reward }))
+    }
+}
+
+/// Returns the reward attributed to the given solution in the block, or `None` if the block does not contain it.
+///
+/// The puzzle reward of the block is shared between its solutions, in proportion to their targets.
+pub fn solution_reward<N: Network>(block: &Block<N>, solution_id: &SolutionID<N>) -> Option<u64> {
+    let solutions = block.solutions().as_ref()?;
+    let target = solutions.get(solution_id)?.target();
+    let combined_target = solutions.values().map(|solution| u128::from(solution.target())).sum();
+    let puzzle_reward = block
+        .ratifications()
+        .iter()
+        .find_map(|ratify| match ratify {
+            Ratify::PuzzleReward(reward) => Some(*reward),
+            _ => None,
+        })
+        .unwrap_or_default();
+    Some(puzzle_reward_share(puzzle_reward, target, combined_target))
+}
+
+/// Returns the share of the puzzle reward of a solution with the given target, out of the combined target.
+fn puzzle_reward_share(puzzle_reward: u64, target: u64, combined_target: u128) -> u64 {
+    match combined_target {
+        0 => 0,
+        // Note: The share cannot exceed the puzzle reward, as the target is part of the combined target.
+        _ => (u128::from(puzzle_reward) * u128::from(target) / combined_target).try_into().unwrap_or(puzzle_reward),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkos_node_bft_ledger_service::test_helpers::sample_ledger;
+    use snarkvm::prelude::MainnetV0;
+
+    use rand::SeedableRng;
+    use rand_chacha::ChaChaRng;
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn test_puzzle_reward_share() {
+        // Ensure the puzzle reward is shared in proportion to the targets.
+        assert_eq!(puzzle_reward_share(1_000, 25, 100), 250);
+        assert_eq!(puzzle_reward_share(1_000, 100, 100), 1_000);
+        // Ensure the share is rounded down.
+        assert_eq!(puzzle_reward_share(1_000, 1, 3), 333);
+        // Ensure the share does not overflow.
+        assert_eq!(puzzle_reward_share(u64::MAX, u64::MAX, 2 * u128::from(u64::MAX)), u64::MAX / 2);
+        assert_eq!(puzzle_reward_share(1_000, 0, 0), 0);
+    }
+
+    #[test]
+    fn test_find_unknown_solution() {
+        let rng = &mut ChaChaRng::seed_from_u64(1234567890u64);
+        let (_, ledger) = sample_ledger::<CurrentNetwork, _>(0, rng);
+
+        // Ensure a solution that is not in the ledger is not found.
+        assert_eq!(SolutionInclusion::find(&ledger, &SolutionID::from(123u64)).unwrap(), None);
+    }
+}
@@ -0,0 +1,142 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::{Result, bail};
+use rayon::prelude::*;
+
+/// The maximum number of commitments whose state paths can be requested at once.
+pub const MAX_STATE_PATHS: usize = 64;
+/// The number of times the state paths are computed, if the state root advances while they are being computed.
+pub const MAX_STATE_PATH_ATTEMPTS: usize = 3;
+
+/// Computes the state paths of the given commitments against a single state root, and returns the root with the paths.
+///
+/// The latest state root is snapshotted first, and the paths are computed in parallel. As a new block may
+/// arrive while the paths are being computed, the paths are recomputed against the new latest state root
+/// if any of them was computed against a different root than the snapshot.
+pub fn state_paths_at_consistent_root<F, R, P>(
+    commitments: &[F],
+    latest_state_root: impl Fn() -> R,
+    state_path: impl Fn(&F) -> Result<P> + Sync,
+    state_root_of: impl Fn(&P) -> R,
+) -> Result<(R, Vec<P>)>
+where
+    F: Sync,
+    R: Copy + PartialEq,
+    P: Send,
+{
+    for _ in 0..MAX_STATE_PATH_ATTEMPTS {
+        // Snapshot the latest state root.
+        let state_root = latest_state_root();
+        // Compute the state paths in parallel.
+        let state_paths = commitments.par_iter().map(&state_path).collect::<Result<Vec<_>>>()?;
+        // Return the state paths, if they were all computed against the snapshotted root.
+        if state_paths.iter().all(|state_path| state_root_of(state_path) == state_root) {
+            return Ok((state_root, state_paths));
+        }
+    }
+    bail!("The state root advanced while computing the state paths - please try again")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkos_node_bft_ledger_service::test_helpers::{sample_genesis_block, sample_ledger_from_genesis};
+    use snarkvm::prelude::{Field, MainnetV0, Zero};
+
+    use parking_lot::RwLock;
+    use rand::SeedableRng;
+    use rand_chacha::ChaChaRng;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn test_state_paths_against_ledger() {
+        // Initialize a ledger with a development genesis block, which contains records.
+        let rng = &mut ChaChaRng::seed_from_u64(1234567890u64);
+        let (private_key, genesis) = sample_genesis_block::<CurrentNetwork, _>(rng);
+        let ledger = sample_ledger_from_genesis(&private_key, &genesis, 0, rng);
+
+        // Collect the commitments of the genesis block.
+        let commitments: Vec<Field<CurrentNetwork>> = genesis.transactions().commitments().copied().collect();
+        assert!(!commitments.is_empty());
+
+        // Compute the state paths.
+        let (state_root, state_paths) = state_paths_at_consistent_root(
+            &commitments,
+            || ledger.latest_state_root(),
+            |commitment| ledger.get_state_path_for_commitment(commitment),
+            |state_path| state_path.global_state_root(),
+        )
+        .unwrap();
+
+        // Ensure each state path verifies against the reported state root.
+        assert_eq!(state_root, ledger.latest_state_root());
+        assert_eq!(state_paths.len(), commitments.len());
+        for state_path in state_paths {
+            assert_eq!(state_path.global_state_root(), state_root);
+            state_path.verify(true, Field::zero()).unwrap();
+        }
+
+        // Ensure an unknown commitment fails the whole batch.
+        let unknown = [commitments[0], Field::zero()];
+        assert!(
+            state_paths_at_consistent_root(
+                &unknown,
+                || ledger.latest_state_root(),
+                |commitment| ledger.get_state_path_for_commitment(commitment),
+                |state_path| state_path.global_state_root(),
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_state_paths_at_consistent_root_under_advancement() {
+        // A mock tree, whose root advances once a few paths were computed.
+        let root = RwLock::new(0u32);
+        let num_computed = AtomicUsize::new(0);
+        let state_path = |commitment: &u32| -> Result<(u32, u32)> {
+            let mut root = root.write();
+            if num_computed.fetch_add(1, Ordering::SeqCst) == 5 {
+                *root += 1;
+            }
+            Ok((*commitment, *root))
+        };
+
+        // The root advances mid-request; all the paths must still be against a single root.
+        let commitments: Vec<u32> = (0..MAX_STATE_PATHS as u32).collect();
+        let (state_root, state_paths) =
+            state_paths_at_consistent_root(&commitments, || *root.read(), state_path, |(_, root)| *root).unwrap();
+        assert_eq!(state_root, 1);
+        assert!(state_paths.iter().all(|(_, root)| *root == state_root));
+        // Ensure the paths are returned in the order of the commitments.
+        assert_eq!(state_paths.iter().map(|(commitment, _)| *commitment).collect::<Vec<_>>(), commitments);
+
+        // The root advances during every attempt.
+        let num_computed = AtomicUsize::new(0);
+        let advancing_path = |commitment: &u32| -> Result<(u32, u32)> {
+            let mut root = root.write();
+            if num_computed.fetch_add(1, Ordering::SeqCst) % commitments.len() == 1 {
+                *root += 1;
+            }
+            Ok((*commitment, *root))
+        };
+        assert!(
+            state_paths_at_consistent_root(&commitments, || *root.read(), advancing_path, |(_, root)| *root).is_err()
+        );
+    }
+}
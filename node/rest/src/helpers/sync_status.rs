@@ -0,0 +1,88 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkos_node_router::SyncProgress;
+
+use serde::Serialize;
+
+/// The progress of the block sync of the node, relative to the greatest height advertised by its peers.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SyncStatus {
+    /// The height of the latest block in the ledger.
+    pub latest_height: u32,
+    /// The median block height advertised by the peers, if any peer advertised its block locators.
+    pub peer_height: Option<u32>,
+    /// The number of blocks remaining until the node reaches the median peer height.
+    pub num_blocks_remaining: u32,
+    /// The recent rate at which blocks are added to the ledger, if it was measured.
+    pub blocks_per_sec: Option<f64>,
+    /// The estimated UNIX timestamp at which the node reaches the median peer height, if it can be estimated.
+    pub estimated_completion: Option<i64>,
+    /// Whether the node considers itself synced.
+    pub is_synced: bool,
+}
+
+impl SyncStatus {
+    /// Initializes the sync status from the latest height and the sync progress, at the given UNIX timestamp.
+    pub fn new(latest_height: u32, progress: SyncProgress, is_synced: bool, now: i64) -> Self {
+        let num_blocks_remaining =
+            progress.peer_height.map_or(0, |peer_height| peer_height.saturating_sub(latest_height));
+        // Note: Without a positive rate, the completion cannot be estimated while blocks remain.
+        let estimated_completion = match (num_blocks_remaining, progress.blocks_per_sec) {
+            (0, _) => Some(now),
+            (num_blocks, Some(rate)) if rate > 0.0 => {
+                Some(now.saturating_add((num_blocks as f64 / rate).ceil() as i64))
+            }
+            _ => None,
+        };
+        Self {
+            latest_height,
+            peer_height: progress.peer_height,
+            num_blocks_remaining,
+            blocks_per_sec: progress.blocks_per_sec,
+            estimated_completion,
+            is_synced,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sync_status() {
+        let now = 1_700_000_000;
+
+        // Ensure the completion is estimated from the remaining blocks and the rate.
+        let progress = SyncProgress { peer_height: Some(1100), blocks_per_sec: Some(30.0) };
+        let status = SyncStatus::new(1000, progress, false, now);
+        assert_eq!(status.num_blocks_remaining, 100);
+        assert_eq!(status.estimated_completion, Some(now + 4));
+
+        // Ensure the completion is not estimated without a positive rate.
+        let progress = SyncProgress { peer_height: Some(1100), blocks_per_sec: Some(0.0) };
+        assert_eq!(SyncStatus::new(1000, progress, false, now).estimated_completion, None);
+        let progress = SyncProgress { peer_height: Some(1100), blocks_per_sec: None };
+        assert_eq!(SyncStatus::new(1000, progress, false, now).estimated_completion, None);
+
+        // Ensure a node ahead of its peers, or without peers, has no remaining blocks.
+        let progress = SyncProgress { peer_height: Some(900), blocks_per_sec: None };
+        let status = SyncStatus::new(1000, progress, true, now);
+        assert_eq!(status.num_blocks_remaining, 0);
+        assert_eq!(status.estimated_completion, Some(now));
+        assert_eq!(SyncStatus::new(1000, SyncProgress::default(), true, now).num_blocks_remaining, 0);
+    }
+}
@@ -0,0 +1,30 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkos_node_router::{TraceLevel, TraceTarget};
+
+use serde::Deserialize;
+
+/// A request to elevate the logging of a peer, a REST route or a message kind, for a bounded duration.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct TraceScopeRequest {
+    /// The subject of the scope, i.e. one of `peer`, `route` or `event`.
+    #[serde(flatten)]
+    pub target: TraceTarget,
+    /// The level of the log events of the scope.
+    pub level: TraceLevel,
+    /// The number of seconds until the scope expires.
+    pub duration_in_secs: u64,
+}
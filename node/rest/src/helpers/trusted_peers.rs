@@ -0,0 +1,39 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+
+/// An update of the trusted peers of the node.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct TrustedPeersUpdate {
+    /// The peers to trust, which the node connects to on its next heartbeat.
+    #[serde(default)]
+    pub insert: Vec<SocketAddr>,
+    /// The peers to no longer trust, which remain connected, but are no longer protected from eviction.
+    #[serde(default)]
+    pub remove: Vec<SocketAddr>,
+}
+
+/// The trusted peers of the node, after an update.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct TrustedPeers {
+    /// The trusted peers, sorted by IP.
+    pub trusted_peers: Vec<SocketAddr>,
+    /// The number of peers that were newly trusted.
+    pub num_inserted: usize,
+    /// The number of peers that are no longer trusted.
+    pub num_removed: usize,
+}
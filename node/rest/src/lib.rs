@@ -21,39 +21,58 @@ extern crate tracing;
 mod helpers;
 pub use helpers::*;
 
+mod router;
+pub use router::*;
+
 mod routes;
 
-use snarkos_node_consensus::Consensus;
+use snarkos_node_consensus::{Consensus, MempoolPressure};
 use snarkos_node_router::{
     Routing,
+    TASK_SHUTDOWN_TIMEOUT_IN_SECS,
+    TaskPolicy,
+    TaskSupervisor,
+    TraceScopes,
     messages::{Message, UnconfirmedTransaction},
 };
 use snarkvm::{
     console::{program::ProgramID, types::Field},
-    ledger::narwhal::Data,
-    prelude::{Ledger, Network, cfg_into_iter, store::ConsensusStorage},
+    ledger::{committee::Committee, narwhal::Data},
+    prelude::{Ledger, Network, store::ConsensusStorage},
 };
 
-use anyhow::Result;
+use anyhow::{Result, bail};
 use axum::{
     Json,
     body::Body,
-    extract::{ConnectInfo, DefaultBodyLimit, Path, Query, State},
-    http::{Method, Request, StatusCode, header::CONTENT_TYPE},
+    extract::{ConnectInfo, DefaultBodyLimit, Query, State},
+    http::{
+        Method,
+        Request,
+        StatusCode,
+        header::{CONTENT_LENGTH, CONTENT_TYPE, RETRY_AFTER},
+    },
     middleware,
     middleware::Next,
-    response::Response,
-    routing::{get, post},
+    response::{IntoResponse, Response},
 };
 use axum_extra::response::ErasedJson;
+use governor::{Quota, RateLimiter};
 use parking_lot::Mutex;
-use std::{net::SocketAddr, sync::Arc};
-use tokio::{net::TcpListener, task::JoinHandle};
-use tower_governor::{GovernorLayer, governor::GovernorConfigBuilder};
+use std::{
+    net::{IpAddr, SocketAddr},
+    num::NonZeroU32,
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::net::TcpListener;
+use tower_governor::{GovernorError, GovernorLayer, governor::GovernorConfigBuilder};
 use tower_http::{
     cors::{Any, CorsLayer},
     trace::TraceLayer,
 };
+use tracing::Instrument;
 
 /// A REST API server for the ledger.
 #[derive(Clone)]
@@ -62,12 +81,45 @@ pub struct Rest<N: Network, C: ConsensusStorage<N>, R: Routing<N>> {
     consensus: Option<Consensus<N>>,
     /// The ledger.
     ledger: Ledger<N, C>,
-    /// The node (routing).
-    routing: Arc<R>,
-    /// The server handles.
-    handles: Arc<Mutex<Vec<JoinHandle<()>>>>,
+    /// The node (routing), unless the node is in safe mode.
+    routing: Option<Arc<R>>,
+    /// The broadcast journal, if enabled.
+    journal: Option<Arc<BroadcastJournal>>,
+    /// The audit log of the authenticated administrative actions.
+    audit_log: Arc<AuditLog>,
+    /// The effective configuration of the node.
+    config: NodeConfig,
+    /// The summaries of the most recent blocks.
+    recent_blocks: Arc<RecentBlocks<N>>,
+    /// The most recent rejected deployments.
+    rejected_deployments: Arc<RejectedDeployments<N>>,
+    /// The shared snapshot of the latest block.
+    latest_block_info: Arc<LatestCache<(u32, N::BlockHash), Arc<LatestBlockInfo<N>>>>,
+    /// The cached committee of the latest block.
+    latest_committee: Arc<LatestCache<(u32, N::BlockHash), Committee<N>>>,
+    /// The cached state root of the latest block.
+    latest_state_root: Arc<LatestCache<(u32, N::BlockHash), N::StateRoot>>,
+    /// The cached estimate of the next block, as of the latest block.
+    next_block_estimate: Arc<LatestCache<(u32, N::BlockHash), Option<BlockEstimate>>>,
+    /// The cached priority fees of the transactions in the most recent blocks.
+    block_fees: Arc<BlockFees<N>>,
+    /// The gate of the block previews.
+    block_preview: Arc<PreviewGate>,
+    /// The shedder of the broadcasts, while the memory pool is under pressure.
+    broadcast_shedder: Arc<BroadcastShedder>,
+    /// The pressure on the inbound queues of the memory pool, if the consensus module is enabled.
+    mempool_pressure: Option<Arc<MempoolPressure>>,
+    /// The trace scopes, shared with the router of the node (if any).
+    trace_scopes: Arc<TraceScopes>,
+    /// The charge of the weight of a request against the weight limit of an IP, once the server is spawned.
+    rate_limit: Option<RateLimitCharge>,
+    /// The supervisor of the server tasks.
+    supervisor: TaskSupervisor,
 }
 
+/// Charges the given weight against the weight limit of an IP, returning `false` if the limit is exceeded.
+type RateLimitCharge = Arc<dyn Fn(IpAddr, u32) -> bool + Send + Sync>;
+
 impl<N: Network, C: 'static + ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
     /// Initializes a new instance of the server.
     pub async fn start(
@@ -75,10 +127,46 @@ impl<N: Network, C: 'static + ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R>
         rest_rps: u32,
         consensus: Option<Consensus<N>>,
         ledger: Ledger<N, C>,
-        routing: Arc<R>,
+        routing: Option<Arc<R>>,
+        broadcast_journal: Option<PathBuf>,
+        recent_blocks_capacity: usize,
+        mut config: NodeConfig,
     ) -> Result<Self> {
+        // Record the REST server settings in the node configuration.
+        config.rest = Some(RestConfig {
+            rest_ip,
+            rest_rps,
+            broadcast_journal: broadcast_journal.clone(),
+            recent_blocks: recent_blocks_capacity,
+        });
+        // Open the broadcast journal, if enabled.
+        let journal = match broadcast_journal {
+            Some(path) => {
+                info!("Recording accepted broadcasts to '{}'", path.display());
+                Some(Arc::new(BroadcastJournal::open(path, MAX_JOURNAL_FILE_SIZE)?))
+            }
+            None => None,
+        };
+        // Open the audit log of the administrative actions, next to the ledger.
+        // Note: The server starts even if the audit log file cannot be opened, as the actions are still kept in memory.
+        let audit_log_path = audit_log_path(&config.storage.path);
+        let audit_log = match AuditLog::open(DEFAULT_AUDIT_LOG_CAPACITY, audit_log_path, MAX_AUDIT_LOG_FILE_SIZE) {
+            Ok(audit_log) => audit_log,
+            Err(error) => {
+                warn!("Failed to open the admin audit log - {error}");
+                AuditLog::default()
+            }
+        };
         // Initialize the server.
-        let mut server = Self { consensus, ledger, routing, handles: Default::default() };
+        let mut server = Self::new(consensus, ledger, routing, config)?;
+        server.journal = journal;
+        server.audit_log = Arc::new(audit_log);
+        server.recent_blocks = Arc::new(RecentBlocks::new(recent_blocks_capacity));
+        server.rejected_deployments = Arc::new(RejectedDeployments::new(DEFAULT_REJECTED_DEPLOYMENTS_CAPACITY));
+        // Spawn the recent block summaries updater.
+        server.spawn_recent_blocks_updater();
+        // Spawn the recent rejected deployments updater.
+        server.spawn_rejected_deployments_updater();
         // Spawn the server.
         server.spawn_server(rest_ip, rest_rps).await;
         // Return the server.
@@ -86,15 +174,168 @@ impl<N: Network, C: 'static + ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R>
     }
 }
 
+impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
+    /// Initializes the state of the routes, from the ledger, and the consensus and routing of the node (if any).
+    ///
+    /// Note: This does not spawn a server; the state can be mounted in an external `axum` application with [`routes`].
+    /// The broadcast journal, the recent block summaries and the recent rejected deployments are disabled,
+    /// the administrative actions are only audited in memory, and the requests are not rate limited.
+    pub fn new(
+        consensus: Option<Consensus<N>>,
+        ledger: Ledger<N, C>,
+        routing: Option<Arc<R>>,
+        config: NodeConfig,
+    ) -> Result<Self> {
+        // Ensure the network has a name, as the routes are nested under it.
+        if network_name::<N>().is_none() {
+            bail!("Unknown network ID ({})", N::ID);
+        }
+        // Share the trace scopes of the router, so that they are managed from a single endpoint.
+        let trace_scopes =
+            routing.as_ref().map_or_else(Default::default, |routing| routing.router().trace_scopes().clone());
+        // Shed the broadcasts against the pressure on the memory pool of the consensus module.
+        let mempool_pressure = consensus.as_ref().map(|consensus| consensus.mempool_pressure().clone());
+        Ok(Self {
+            consensus,
+            ledger,
+            routing,
+            journal: None,
+            audit_log: Default::default(),
+            config,
+            recent_blocks: Arc::new(RecentBlocks::new(0)),
+            rejected_deployments: Arc::new(RejectedDeployments::new(0)),
+            latest_block_info: Default::default(),
+            latest_committee: Default::default(),
+            latest_state_root: Default::default(),
+            next_block_estimate: Default::default(),
+            block_fees: Default::default(),
+            block_preview: Default::default(),
+            broadcast_shedder: Default::default(),
+            mempool_pressure,
+            trace_scopes,
+            rate_limit: None,
+            supervisor: TaskSupervisor::new("REST server"),
+        })
+    }
+}
+
 impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
     /// Returns the ledger.
     pub const fn ledger(&self) -> &Ledger<N, C> {
         &self.ledger
     }
 
-    /// Returns the handles.
-    pub const fn handles(&self) -> &Arc<Mutex<Vec<JoinHandle<()>>>> {
-        &self.handles
+    /// Returns the effective configuration of the node.
+    pub const fn config(&self) -> &NodeConfig {
+        &self.config
+    }
+
+    /// Returns the supervisor of the server tasks.
+    pub const fn supervisor(&self) -> &TaskSupervisor {
+        &self.supervisor
+    }
+
+    /// Returns `true` if the node is in safe mode, i.e. its networking is disabled.
+    pub const fn is_safe_mode(&self) -> bool {
+        self.routing.is_none()
+    }
+
+    /// Sets the pressure on the memory pool that the broadcasts are shed against.
+    ///
+    /// Note: Do NOT use this in production. This is for **testing only**, without a consensus module.
+    #[cfg(feature = "test")]
+    #[doc(hidden)]
+    pub fn set_mempool_pressure(&mut self, mempool_pressure: Arc<MempoolPressure>) {
+        self.mempool_pressure = Some(mempool_pressure);
+    }
+
+    /// Returns the node (routing), or an error if the node is in safe mode.
+    fn routing(&self) -> Result<&Arc<R>, RestError> {
+        self.routing.as_ref().ok_or_else(|| RestError::Disabled(SAFE_MODE_ERROR.to_string()))
+    }
+
+    /// Returns the key of the latest block in the ledger, i.e. its height and hash, by which the values derived
+    /// from the latest block are cached, so that a rollback of the ledger is never served a stale value.
+    fn latest_block_key(&self) -> (u32, N::BlockHash) {
+        (self.ledger.latest_height(), self.ledger.latest_hash())
+    }
+
+    /// Returns a consistent snapshot of the latest block, shared by the requests until the ledger advances.
+    fn latest_block_info(&self) -> Result<Arc<LatestBlockInfo<N>>, RestError> {
+        Ok(self.latest_block_info.get(self.latest_block_key(), || Ok(Arc::new(LatestBlockInfo::load(&self.ledger))))?)
+    }
+
+    /// Returns the number of blocks the node is behind. A node in safe mode does not sync, so it is never behind.
+    fn num_blocks_behind(&self) -> u32 {
+        self.routing.as_ref().map_or(0, |routing| routing.num_blocks_behind())
+    }
+
+    /// Charges the given weight, e.g. the number of items of a batch, against the weight limit of the given IP.
+    fn charge_rate_limit(&self, ip: IpAddr, weight: u32) -> Result<(), RestError> {
+        match self.rate_limit.as_ref().map_or(true, |rate_limit| rate_limit(ip, weight)) {
+            true => Ok(()),
+            false => {
+                Err(RestError::TooManyRequests("Too many requests - the batch exceeds the rate limit".to_string()))
+            }
+        }
+    }
+
+    /// Shuts down the server.
+    pub async fn shut_down(&self) {
+        info!("Shutting down the REST server...");
+        self.supervisor.shut_down(Duration::from_secs(TASK_SHUTDOWN_TIMEOUT_IN_SECS)).await;
+    }
+}
+
+impl<N: Network, C: 'static + ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
+    /// Spawns a task that appends the summaries of new blocks to the recent block summaries, as the ledger advances.
+    fn spawn_recent_blocks_updater(&self) {
+        // If the recent block summaries are disabled, return early.
+        if self.recent_blocks.capacity() == 0 {
+            return;
+        }
+        let ledger = self.ledger.clone();
+        let recent_blocks = self.recent_blocks.clone();
+        self.supervisor.spawn("recent_blocks", TaskPolicy::Restart { max_restarts: 3 }, move || {
+            let (ledger, recent_blocks) = (ledger.clone(), recent_blocks.clone());
+            async move {
+                loop {
+                    // Append any new blocks, on a blocking thread as the blocks are read from storage.
+                    let (ledger_, recent_blocks_) = (ledger.clone(), recent_blocks.clone());
+                    match tokio::task::spawn_blocking(move || recent_blocks_.update(&ledger_)).await {
+                        Ok(Err(error)) => warn!("Failed to update the recent block summaries - {error}"),
+                        Err(error) => error!("Failed to update the recent block summaries - {error}"),
+                        Ok(Ok(())) => (),
+                    }
+                    tokio::time::sleep(Duration::from_millis(RECENT_BLOCKS_UPDATE_INTERVAL_IN_MS)).await;
+                }
+            }
+        });
+    }
+
+    /// Spawns a task that scans new blocks for rejected deployments, as the ledger advances.
+    fn spawn_rejected_deployments_updater(&self) {
+        // If the recent rejected deployments are disabled, return early.
+        if self.rejected_deployments.capacity() == 0 {
+            return;
+        }
+        let ledger = self.ledger.clone();
+        let rejected_deployments = self.rejected_deployments.clone();
+        self.supervisor.spawn("rejected_deployments", TaskPolicy::Restart { max_restarts: 3 }, move || {
+            let (ledger, rejected_deployments) = (ledger.clone(), rejected_deployments.clone());
+            async move {
+                loop {
+                    // Scan any new blocks, on a blocking thread as the blocks are read from storage.
+                    let (ledger_, rejected_deployments_) = (ledger.clone(), rejected_deployments.clone());
+                    match tokio::task::spawn_blocking(move || rejected_deployments_.update(&ledger_)).await {
+                        Ok(Err(error)) => warn!("Failed to update the recent rejected deployments - {error}"),
+                        Err(error) => error!("Failed to update the recent rejected deployments - {error}"),
+                        Ok(Ok(())) => (),
+                    }
+                    tokio::time::sleep(Duration::from_millis(RECENT_BLOCKS_UPDATE_INTERVAL_IN_MS)).await;
+                }
+            }
+        });
     }
 }
 
@@ -109,109 +350,134 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
         debug!("REST rate limit per IP - {rest_rps} RPS");
 
         // Prepare the rate limiting setup.
-        let governor_config = Box::new(
+        // We can leak this because it is created only once and it persists.
+        let governor_config: &'static _ = Box::leak(Box::new(
             GovernorConfigBuilder::default()
                 .per_second(1)
                 .burst_size(rest_rps)
-                .error_handler(|error| Response::new(error.to_string().into()))
+                .error_handler(|error| {
+                    let message = error.to_string();
+                    match error {
+                        // Respond with the time until the next request is permitted, so clients can back off.
+                        GovernorError::TooManyRequests { wait_time, .. } => {
+                            (StatusCode::TOO_MANY_REQUESTS, [(RETRY_AFTER, wait_time.max(1).to_string())], message)
+                                .into_response()
+                        }
+                        _ => Response::new(message.into()),
+                    }
+                })
                 .finish()
                 .expect("Couldn't set up rate limiting for the REST server!"),
-        );
-
-        // Get the network being used.
-        let network = match N::ID {
-            snarkvm::console::network::MainnetV0::ID => "mainnet",
-            snarkvm::console::network::TestnetV0::ID => "testnet",
-            snarkvm::console::network::CanaryV0::ID => "canary",
-            unknown_id => {
-                eprintln!("Unknown network ID ({unknown_id})");
-                return;
-            }
-        };
-
-        let router = {
-            let routes = axum::Router::new()
-
-            // All the endpoints before the call to `route_layer` are protected with JWT auth.
-            .route(&format!("/{network}/node/address"), get(Self::get_node_address))
-            .route(&format!("/{network}/program/:id/mapping/:name"), get(Self::get_mapping_values))
-            .route_layer(middleware::from_fn(auth_middleware))
-
-            // GET ../block/..
-            .route(&format!("/{network}/block/height/latest"), get(Self::get_block_height_latest))
-            .route(&format!("/{network}/block/hash/latest"), get(Self::get_block_hash_latest))
-            .route(&format!("/{network}/block/latest"), get(Self::get_block_latest))
-            .route(&format!("/{network}/block/:height_or_hash"), get(Self::get_block))
-            // The path param here is actually only the height, but the name must match the route
-            // above, otherwise there'll be a conflict at runtime.
-            .route(&format!("/{network}/block/:height_or_hash/transactions"), get(Self::get_block_transactions))
-
-            // GET and POST ../transaction/..
-            .route(&format!("/{network}/transaction/:id"), get(Self::get_transaction))
-            .route(&format!("/{network}/transaction/confirmed/:id"), get(Self::get_confirmed_transaction))
-            .route(&format!("/{network}/transaction/broadcast"), post(Self::transaction_broadcast))
-
-            // POST ../solution/broadcast
-            .route(&format!("/{network}/solution/broadcast"), post(Self::solution_broadcast))
-
-            // GET ../find/..
-            .route(&format!("/{network}/find/blockHash/:tx_id"), get(Self::find_block_hash))
-            .route(&format!("/{network}/find/blockHeight/:state_root"), get(Self::find_block_height_from_state_root))
-            .route(&format!("/{network}/find/transactionID/deployment/:program_id"), get(Self::find_transaction_id_from_program_id))
-            .route(&format!("/{network}/find/transactionID/:transition_id"), get(Self::find_transaction_id_from_transition_id))
-            .route(&format!("/{network}/find/transitionID/:input_or_output_id"), get(Self::find_transition_id))
-
-            // GET ../peers/..
-            .route(&format!("/{network}/peers/count"), get(Self::get_peers_count))
-            .route(&format!("/{network}/peers/all"), get(Self::get_peers_all))
-            .route(&format!("/{network}/peers/all/metrics"), get(Self::get_peers_all_metrics))
-
-            // GET ../program/..
-            .route(&format!("/{network}/program/:id"), get(Self::get_program))
-            .route(&format!("/{network}/program/:id/mappings"), get(Self::get_mapping_names))
-            .route(&format!("/{network}/program/:id/mapping/:name/:key"), get(Self::get_mapping_value))
-
-            // GET misc endpoints.
-            .route(&format!("/{network}/blocks"), get(Self::get_blocks))
-            .route(&format!("/{network}/height/:hash"), get(Self::get_height))
-            .route(&format!("/{network}/memoryPool/transmissions"), get(Self::get_memory_pool_transmissions))
-            .route(&format!("/{network}/memoryPool/solutions"), get(Self::get_memory_pool_solutions))
-            .route(&format!("/{network}/memoryPool/transactions"), get(Self::get_memory_pool_transactions))
-            .route(&format!("/{network}/statePath/:commitment"), get(Self::get_state_path_for_commitment))
-            .route(&format!("/{network}/stateRoot/latest"), get(Self::get_state_root_latest))
-            .route(&format!("/{network}/stateRoot/:height"), get(Self::get_state_root))
-            .route(&format!("/{network}/committee/latest"), get(Self::get_committee_latest))
-            .route(&format!("/{network}/committee/:height"), get(Self::get_committee))
-            .route(&format!("/{network}/delegators/:validator"), get(Self::get_delegators_for_validator));
-
-            // If the `history` feature is enabled, enable the additional endpoint.
-            #[cfg(feature = "history")]
-            let routes =
-                routes.route(&format!("/{network}/block/:blockHeight/history/:mapping"), get(Self::get_history));
-
-            routes
-            // Pass in `Rest` to make things convenient.
-            .with_state(self.clone())
+        ));
+        // Count the items of the batch requests against a separate weight limit, replenished at the rate of
+        // the requests, whose burst permits a full batch, as the burst of the requests may be smaller.
+        let quota = Quota::with_period(Duration::from_secs(1))
+            .expect("The period of the weight limit is not zero")
+            .allow_burst(NonZeroU32::new(MAX_STATE_PATHS as u32).expect("The maximum batch size is not zero"));
+        let limiter = RateLimiter::keyed(quota);
+        self.rate_limit = Some(Arc::new(move |ip, weight| match NonZeroU32::new(weight) {
+            Some(weight) => matches!(limiter.check_key_n(&ip, weight), Ok(Ok(_))),
+            None => true,
+        }));
+
+        let router = routes(self.clone())
             // Enable tower-http tracing.
             .layer(TraceLayer::new_for_http())
             // Custom logging.
             .layer(middleware::from_fn(log_middleware))
+            // Elevate the logging of the requests covered by a trace scope.
+            .layer(middleware::from_fn_with_state(self.clone(), Self::trace_scoped_requests))
             // Enable CORS.
             .layer(cors)
             // Cap body size at 512KiB.
             .layer(DefaultBodyLimit::max(512 * 1024))
-            .layer(GovernorLayer {
-                // We can leak this because it is created only once and it persists.
-                config: Box::leak(governor_config),
-            })
-        };
+            .layer(GovernorLayer { config: governor_config })
+            // Set the caching headers, according to the mutability of the resources.
+            .layer(middleware::from_fn(cache_control_middleware));
 
         let rest_listener = TcpListener::bind(rest_ip).await.unwrap();
-        self.handles.lock().push(tokio::spawn(async move {
-            axum::serve(rest_listener, router.into_make_service_with_connect_info::<SocketAddr>())
-                .await
-                .expect("couldn't start rest server");
-        }))
+        // The listener is taken by the first run of the server; as the server is critical, it is never restarted.
+        let rest_listener = Arc::new(Mutex::new(Some(rest_listener)));
+        self.supervisor.spawn("server", TaskPolicy::Shutdown, move || {
+            let (rest_listener, router) = (rest_listener.lock().take(), router.clone());
+            async move {
+                let Some(rest_listener) = rest_listener else { return };
+                if let Err(error) =
+                    axum::serve(rest_listener, router.into_make_service_with_connect_info::<SocketAddr>()).await
+                {
+                    error!("The REST server has stopped - {error}");
+                }
+            }
+        })
+    }
+}
+
+impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
+    /// Sheds the broadcasts of a client, ahead of the deserialization of their body,
+    /// while the inbound queue of the memory pool they target is under pressure.
+    async fn shed_broadcasts(
+        State(rest): State<Self>,
+        ConnectInfo(addr): ConnectInfo<SocketAddr>,
+        request: Request<Body>,
+        next: Next,
+    ) -> Response {
+        if let Some(pressure) = &rest.mempool_pressure {
+            let (kind, pressure) = match request.uri().path().ends_with("/solution/broadcast") {
+                true => ("solution", pressure.solutions()),
+                false => ("transaction", pressure.transactions()),
+            };
+            if let Some(retry_after) = rest.broadcast_shedder.check(addr.ip(), pressure, Instant::now()) {
+                debug!("Shed a {kind} broadcast from '{addr}' - the memory pool is under pressure");
+                return shed_response(retry_after);
+            }
+        }
+        next.run(request).await
+    }
+
+    /// Records the authenticated administrative requests that may mutate the node to the audit log.
+    ///
+    /// Note: The body of a request is buffered to record its parameters, only if it is small enough;
+    /// the request is served regardless of whether it is recorded.
+    async fn audit_admin_actions(
+        State(rest): State<Self>,
+        ConnectInfo(addr): ConnectInfo<SocketAddr>,
+        request: Request<Body>,
+        next: Next,
+    ) -> Response {
+        if !is_mutating(request.method()) {
+            return next.run(request).await;
+        }
+        let method = request.method().to_string();
+        let endpoint = route_path::<N>(request.uri().path()).to_string();
+        let query = request.uri().query().map(str::to_string);
+        // Buffer the body, if its length is known and small enough.
+        let (parts, body) = request.into_parts();
+        let content_length = parts.headers.get(CONTENT_LENGTH).and_then(|length| length.to_str().ok()?.parse().ok());
+        let (request, body) = match content_length {
+            Some(length) if length <= MAX_AUDITED_BODY_SIZE => match axum::body::to_bytes(body, length).await {
+                Ok(bytes) => (Request::from_parts(parts, Body::from(bytes.clone())), Some(bytes)),
+                // If the body cannot be read, it cannot be served either.
+                Err(_) => (Request::from_parts(parts, Body::empty()), None),
+            },
+            _ => (Request::from_parts(parts, body), None),
+        };
+        let response = next.run(request).await;
+        // Record the request, if it was authenticated.
+        if let Some(identity) = response.extensions().get::<TokenIdentity>() {
+            let parameters = sanitize_parameters(query.as_deref(), body.as_deref());
+            let record = AuditRecord::new(method, endpoint, parameters, addr.ip(), identity, response.status());
+            rest.audit_log.record(record);
+        }
+        response
+    }
+
+    /// Handles the requests within the span of the trace scopes covering their route, if any,
+    /// which elevates the logging of the inner layers and of the handlers to the level of the scopes.
+    async fn trace_scoped_requests(State(rest): State<Self>, request: Request<Body>, next: Next) -> Response {
+        match rest.trace_scopes.level_for_route(route_path::<N>(request.uri().path())) {
+            Some(level) => next.run(request).instrument(level.span()).await,
+            None => next.run(request).await,
+        }
     }
 }
 
@@ -225,6 +491,21 @@ async fn log_middleware(
     Ok(next.run(request).await)
 }
 
+/// Returns the path of a route, without the name of the network the routes are nested under.
+fn route_path<N: Network>(path: &str) -> &str {
+    network_name::<N>().and_then(|network| path.strip_prefix(&format!("/{network}"))).unwrap_or(path)
+}
+
+/// Returns the name of the given network, as used in the paths of the routes.
+fn network_name<N: Network>() -> Option<&'static str> {
+    match N::ID {
+        snarkvm::console::network::MainnetV0::ID => Some("mainnet"),
+        snarkvm::console::network::TestnetV0::ID => Some("testnet"),
+        snarkvm::console::network::CanaryV0::ID => Some("canary"),
+        _ => None,
+    }
+}
+
 /// Formats an ID into a truncated identifier (for logging purposes).
 pub fn fmt_id(id: impl ToString) -> String {
     let id = id.to_string();
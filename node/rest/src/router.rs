@@ -0,0 +1,674 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use axum::handler::Handler;
+
+/// The `full` query parameter of the memory pool endpoints.
+const MEMORY_POOL_FULL: Parameter =
+    Parameter::query("full", Schema::Boolean, "Whether to return up to 100 full transmissions, instead of summaries.");
+
+/// The pagination query parameters of the committee endpoints.
+const COMMITTEE_PAGE: [Parameter; 2] = [
+    Parameter::query("offset", Schema::Integer, "The number of members to skip, by the x-coordinate of their address."),
+    Parameter::query("limit", Schema::Integer, "The maximum number of members to return (default 100, at most 1000)."),
+];
+
+/// Returns the routes of the REST API, nested under the name of the network (e.g. `/mainnet/block/latest`),
+/// with the OpenAPI specification of the routes at `/<network>/openapi.json`.
+///
+/// The routes are independent of the listener and of the middleware of the node, so they can be
+/// mounted in an external `axum` application; see the `*_routes` functions to mount a subset of them.
+/// Note: The application must be served with `into_make_service_with_connect_info::<SocketAddr>`.
+pub fn routes<N: Network, C: ConsensusStorage<N>, R: Routing<N>>(rest: Rest<N, C, R>) -> axum::Router {
+    let network = network_name::<N>().expect("The network of the REST state is checked on initialization");
+    let routes = admin_routes(rest.clone())
+        .merge(block_routes(rest.clone()))
+        .merge(transaction_routes(rest.clone()))
+        .merge(find_routes(rest.clone()))
+        .merge(peer_routes(rest.clone()))
+        .merge(node_routes(rest.clone()))
+        .merge(program_routes(rest.clone()))
+        .merge(state_routes(rest.clone()));
+
+    // If the `swagger-ui` feature is enabled, serve the Swagger UI for the OpenAPI specification.
+    #[cfg(feature = "swagger-ui")]
+    let routes = routes.merge(docs_routes(rest));
+
+    axum::Router::new().nest(&format!("/{network}"), routes.with_openapi(network).into())
+}
+
+/// Returns the administrative routes, which are protected with JWT auth.
+pub fn admin_routes<N: Network, C: ConsensusStorage<N>, R: Routing<N>>(rest: Rest<N, C, R>) -> DocumentedRouter {
+    // Note: The audit layer wraps the auth layer, to record the identity it resolves for each request.
+    let audit_admin_actions = middleware::from_fn_with_state(rest.clone(), Rest::<N, C, R>::audit_admin_actions);
+    DocumentedRouter::new()
+        .route(
+            Endpoint::get("/node/address", "Returns the address of the node", Schema::String),
+            Rest::<N, C, R>::get_node_address,
+        )
+        .route(
+            Endpoint::get(
+                "/node/config",
+                "Returns the effective configuration of the node, without its secrets",
+                Schema::Ref("NodeConfig"),
+            ),
+            Rest::<N, C, R>::get_node_config,
+        )
+        .route(
+            Endpoint::get(
+                "/node/bootstrap",
+                "Returns the connection attempts to the bootstrap and trusted peers, with a diagnostic",
+                Schema::Ref("NodeBootstrap"),
+            ),
+            Rest::<N, C, R>::get_node_bootstrap,
+        )
+        .route(
+            Endpoint::get(
+                "/program/{id}/mapping/{name}",
+                "Returns all the values of a mapping",
+                Schema::Ref("MappingValues"),
+            )
+            .with_parameters(&[
+                Parameter::path("id", Schema::String, "The program ID."),
+                Parameter::path("name", Schema::String, "The mapping name."),
+                Parameter::query("all", Schema::Boolean, "Must be `true`."),
+                Parameter::query("metadata", Schema::Boolean, "Whether to include the latest height."),
+            ]),
+            Rest::<N, C, R>::get_mapping_values,
+        )
+        .route(
+            Endpoint::post(
+                "/node/sync/from",
+                "Forces an immediate sync from a connected peer",
+                Schema::Ref("SyncFromPeer"),
+                Schema::Ref("SyncSummary"),
+            ),
+            Rest::<N, C, R>::sync_from_peer,
+        )
+        .route(
+            Endpoint::post(
+                "/node/locators/compare",
+                "Compares the block locators of the node to the given block locators",
+                Schema::Ref("BlockLocators"),
+                Schema::Ref("LocatorsRelationship"),
+            ),
+            Rest::<N, C, R>::compare_block_locators,
+        )
+        .route(
+            Endpoint::get(
+                "/bft/connections",
+                "Returns the connections of the BFT gateway",
+                Schema::Array(&Schema::Ref("BftConnection")),
+            ),
+            Rest::<N, C, R>::get_bft_connections,
+        )
+        .route(
+            Endpoint::get(
+                "/bft/workers",
+                "Returns the queues of the BFT workers",
+                Schema::Array(&Schema::Ref("BftWorker")),
+            ),
+            Rest::<N, C, R>::get_bft_workers,
+        )
+        .route(
+            Endpoint::get(
+                "/bft/preview_block",
+                "Returns a preview of the next block, speculated without writing to the ledger",
+                Schema::Ref("BlockPreview"),
+            ),
+            Rest::<N, C, R>::get_bft_preview_block,
+        )
+        .route(
+            Endpoint::get(
+                "/node/broadcast-journal",
+                "Returns the accepted broadcasts",
+                Schema::Ref("BroadcastJournal"),
+            )
+            .with_parameters(&[Parameter::query(
+                "since",
+                Schema::Integer,
+                "The UNIX timestamp from which to return entries.",
+            )]),
+            Rest::<N, C, R>::get_broadcast_journal,
+        )
+        .route(
+            Endpoint::get(
+                "/node/tasks",
+                "Returns the supervised tasks of the node",
+                Schema::Array(&Schema::Ref("Task")),
+            ),
+            Rest::<N, C, R>::get_node_tasks,
+        )
+        .route(
+            Endpoint::get(
+                "/committee/connectivity",
+                "Returns the connectivity of the committee",
+                Schema::Ref("CommitteeConnectivity"),
+            ),
+            Rest::<N, C, R>::get_committee_connectivity,
+        )
+        .route(
+            Endpoint::get("/node/peers/export", "Returns the peer knowledge of the node", Schema::Ref("PeerExport")),
+            Rest::<N, C, R>::get_peer_export,
+        )
+        .route(
+            Endpoint::post(
+                "/node/peers/import",
+                "Merges the peer knowledge of another node into the node",
+                Schema::Ref("PeerExport"),
+                Schema::Ref("PeerImportSummary"),
+            ),
+            Rest::<N, C, R>::import_peers,
+        )
+        .route(
+            Endpoint::post(
+                "/node/trusted_peers",
+                "Inserts and removes trusted peers at runtime; the removed peers remain connected",
+                Schema::Ref("TrustedPeersUpdate"),
+                Schema::Ref("TrustedPeers"),
+            ),
+            Rest::<N, C, R>::update_trusted_peers,
+        )
+        .route(
+            Endpoint::get("/node/trace", "Returns the active trace scopes", Schema::Array(&Schema::Ref("TraceScope"))),
+            Rest::<N, C, R>::get_trace_scopes,
+        )
+        .route(
+            Endpoint::post(
+                "/node/trace",
+                "Elevates the logging of a peer, a route prefix or a message kind, until the scope expires",
+                Schema::Ref("TraceScopeRequest"),
+                Schema::Ref("TraceScope"),
+            ),
+            Rest::<N, C, R>::enable_trace_scope,
+        )
+        .route(
+            Endpoint::get(
+                "/node/audit",
+                "Returns the recent authenticated administrative actions",
+                Schema::Ref("AuditLog"),
+            ),
+            Rest::<N, C, R>::get_audit_log,
+        )
+        .route(
+            Endpoint::new(
+                HttpMethod::Post,
+                "/node/reload",
+                "Reloads the configuration files of the node, e.g. the ban list, the peer export and the mempool policy",
+                ResponseBody::Json(Schema::Ref("ReloadSummary")),
+            ),
+            Rest::<N, C, R>::reload_config,
+        )
+        .require_auth()
+        .route_layer(audit_admin_actions)
+        .with_state(rest)
+}
+
+/// Returns the routes of the blocks, i.e. `block/..`, `blocks/..` and `height/..`.
+pub fn block_routes<N: Network, C: ConsensusStorage<N>, R: Routing<N>>(rest: Rest<N, C, R>) -> DocumentedRouter {
+    let routes = DocumentedRouter::new()
+        .route(
+            Endpoint::get("/block/height/latest", "Returns the latest block height", Schema::Integer),
+            Rest::<N, C, R>::get_block_height_latest,
+        )
+        .route(
+            Endpoint::get("/block/hash/latest", "Returns the latest block hash", Schema::String),
+            Rest::<N, C, R>::get_block_hash_latest,
+        )
+        .route(
+            Endpoint::get("/block/latest", "Returns the latest block", Schema::Ref("Block")).with_bytes(),
+            Rest::<N, C, R>::get_block_latest,
+        )
+        .route(
+            Endpoint::get(
+                "/block/latest/info",
+                "Returns the height, hash, round, timestamp, and header of the latest block, as a consistent snapshot",
+                Schema::Ref("LatestBlockInfo"),
+            ),
+            Rest::<N, C, R>::get_block_latest_info,
+        )
+        .route(
+            Endpoint::get(
+                "/block/next/estimate",
+                "Returns an estimate of when the next block lands",
+                Schema::Ref("BlockEstimate"),
+            ),
+            Rest::<N, C, R>::get_next_block_estimate,
+        )
+        .route(
+            Endpoint::get(
+                "/block/{height_or_hash}",
+                "Returns the block with the given height or hash",
+                Schema::Ref("Block"),
+            )
+            .with_parameters(&[
+                Parameter::path("height_or_hash", Schema::String, "The block height or block hash."),
+                Parameter::query(
+                    "include",
+                    Schema::String,
+                    "The parts of the block: 'header', 'txids' (header and transaction IDs), or 'full' (default).",
+                ),
+            ])
+            .with_bytes()
+            .with_block_reads(),
+            Rest::<N, C, R>::get_block,
+        )
+        .route(
+            // The path param here is actually only the height, but the name must match the route
+            // above, otherwise there'll be a conflict at runtime.
+            Endpoint::get(
+                "/block/{height_or_hash}/transactions",
+                "Returns the transactions of a block",
+                Schema::Ref("Transactions"),
+            )
+            .with_parameters(&[Parameter::path("height_or_hash", Schema::String, "The block height or block hash.")])
+            .with_bytes()
+            .with_block_reads(),
+            Rest::<N, C, R>::get_block_transactions,
+        )
+        .route(
+            Endpoint::get("/blocks", "Returns the blocks in a range of heights", Schema::Array(&Schema::Ref("Block")))
+                .with_parameters(&[
+                    Parameter::query("start", Schema::Integer, "The starting block height (inclusive)."),
+                    Parameter::query("end", Schema::Integer, "The ending block height (exclusive)."),
+                ])
+                .with_block_reads(),
+            Rest::<N, C, R>::get_blocks,
+        )
+        .route(
+            Endpoint::get(
+                "/blocks/recent",
+                "Returns the summaries of the most recent blocks",
+                Schema::Array(&Schema::Ref("BlockSummary")),
+            )
+            .with_parameters(&[Parameter::query(
+                "limit",
+                Schema::Integer,
+                "The maximum number of block summaries.",
+            )]),
+            Rest::<N, C, R>::get_blocks_recent,
+        )
+        .route(
+            Endpoint::get("/height/{hash}", "Returns the height of the block with the given hash", Schema::Integer)
+                .with_parameters(&[Parameter::path("hash", Schema::String, "The block hash.")])
+                .with_block_reads(),
+            Rest::<N, C, R>::get_height,
+        );
+
+    // If the `history` feature is enabled, enable the additional endpoint.
+    #[cfg(feature = "history")]
+    let routes = routes.route(
+        Endpoint::get(
+            "/block/{blockHeight}/history/{mapping}",
+            "Returns the history of a mapping at a block",
+            Schema::Object,
+        )
+        .with_parameters(&[
+            Parameter::path("blockHeight", Schema::Integer, "The block height."),
+            Parameter::path("mapping", Schema::String, "The mapping name."),
+        ]),
+        Rest::<N, C, R>::get_history,
+    );
+
+    routes.with_state(rest)
+}
+
+/// Returns the routes of the transactions and solutions, and of the memory pool.
+///
+/// The broadcasts are shed ahead of their deserialization, while the memory pool is under pressure.
+pub fn transaction_routes<N: Network, C: ConsensusStorage<N>, R: Routing<N>>(rest: Rest<N, C, R>) -> DocumentedRouter {
+    let shed_broadcasts = middleware::from_fn_with_state(rest.clone(), Rest::<N, C, R>::shed_broadcasts);
+    DocumentedRouter::new()
+        .route(
+            Endpoint::get(
+                "/fees/estimate",
+                "Returns the priority fees of the unconfirmed and the recently included transactions",
+                Schema::Ref("FeeEstimate"),
+            )
+            .with_parameters(&[Parameter::query(
+                "blocks",
+                Schema::Integer,
+                "The number of most recent blocks to aggregate the priority fees over (default 50, at most 500).",
+            )]),
+            Rest::<N, C, R>::get_fee_estimate,
+        )
+        .route(
+            Endpoint::get("/transaction/{id}", "Returns the transaction with the given ID", Schema::Ref("Transaction"))
+                .with_parameters(&[Parameter::path("id", Schema::String, "The transaction ID.")])
+                .with_bytes(),
+            Rest::<N, C, R>::get_transaction,
+        )
+        .route(
+            Endpoint::get(
+                "/transaction/confirmed/{id}",
+                "Returns the confirmed transaction",
+                Schema::Ref("ConfirmedTransaction"),
+            )
+            .with_parameters(&[Parameter::path("id", Schema::String, "The transaction ID.")]),
+            Rest::<N, C, R>::get_confirmed_transaction,
+        )
+        .route(
+            Endpoint::post(
+                "/transaction/broadcast",
+                "Broadcasts a transaction, returning its ID",
+                Schema::Ref("Transaction"),
+                Schema::String,
+            ),
+            Rest::<N, C, R>::transaction_broadcast.layer(shed_broadcasts.clone()),
+        )
+        .route(
+            Endpoint::post(
+                "/transaction/preflight",
+                "Returns whether a transaction would be accepted into the memory pool, without submitting it",
+                Schema::Ref("Transaction"),
+                Schema::Ref("AdmissionVerdict"),
+            ),
+            Rest::<N, C, R>::transaction_preflight,
+        )
+        .route(
+            Endpoint::post(
+                "/solution/broadcast",
+                "Broadcasts a solution, returning its ID",
+                Schema::Ref("Solution"),
+                Schema::String,
+            ),
+            Rest::<N, C, R>::solution_broadcast.layer(shed_broadcasts),
+        )
+        .route(
+            Endpoint::get(
+                "/memoryPool/transmissions",
+                "Returns the unconfirmed transmissions",
+                Schema::Ref("MemoryPool"),
+            )
+            .with_parameters(&[MEMORY_POOL_FULL]),
+            Rest::<N, C, R>::get_memory_pool_transmissions,
+        )
+        .route(
+            Endpoint::get("/memoryPool/solutions", "Returns the unconfirmed solutions", Schema::Ref("MemoryPool"))
+                .with_parameters(&[MEMORY_POOL_FULL]),
+            Rest::<N, C, R>::get_memory_pool_solutions,
+        )
+        .route(
+            Endpoint::get(
+                "/memoryPool/transactions",
+                "Returns the unconfirmed transactions",
+                Schema::Ref("MemoryPool"),
+            )
+            .with_parameters(&[MEMORY_POOL_FULL]),
+            Rest::<N, C, R>::get_memory_pool_transactions,
+        )
+        .with_state(rest)
+}
+
+/// Returns the lookup routes, i.e. `find/..`.
+pub fn find_routes<N: Network, C: ConsensusStorage<N>, R: Routing<N>>(rest: Rest<N, C, R>) -> DocumentedRouter {
+    DocumentedRouter::new()
+        .route(
+            Endpoint::get(
+                "/find/blockHash/{tx_id}",
+                "Returns the hash of the block containing a transaction",
+                Schema::String,
+            )
+            .with_parameters(&[Parameter::path("tx_id", Schema::String, "The transaction ID.")]),
+            Rest::<N, C, R>::find_block_hash,
+        )
+        .route(
+            Endpoint::get(
+                "/find/blockHeight/{state_root}",
+                "Returns the height of the block with a state root",
+                Schema::Integer,
+            )
+            .with_parameters(&[Parameter::path("state_root", Schema::String, "The state root.")]),
+            Rest::<N, C, R>::find_block_height_from_state_root,
+        )
+        .route(
+            Endpoint::get(
+                "/find/blockHeight/solution/{solution_id}",
+                "Returns the block containing a solution, and the reward attributed to the solution",
+                Schema::Ref("SolutionInclusion"),
+            )
+            .with_parameters(&[Parameter::path("solution_id", Schema::String, "The solution ID.")])
+            .with_not_found(),
+            Rest::<N, C, R>::find_block_height_from_solution_id,
+        )
+        .route(
+            Endpoint::get(
+                "/find/transactionID/deployment/{program_id}",
+                "Returns the ID of the transaction that deployed a program",
+                Schema::String,
+            )
+            .with_parameters(&[Parameter::path("program_id", Schema::String, "The program ID.")]),
+            Rest::<N, C, R>::find_transaction_id_from_program_id,
+        )
+        .route(
+            Endpoint::get(
+                "/find/transactionID/{transition_id}",
+                "Returns the ID of the transaction of a transition",
+                Schema::String,
+            )
+            .with_parameters(&[Parameter::path("transition_id", Schema::String, "The transition ID.")]),
+            Rest::<N, C, R>::find_transaction_id_from_transition_id,
+        )
+        .route(
+            Endpoint::get(
+                "/find/transitionID/{input_or_output_id}",
+                "Returns the ID of the transition of an input or output",
+                Schema::String,
+            )
+            .with_parameters(&[Parameter::path(
+                "input_or_output_id",
+                Schema::String,
+                "The input or output ID.",
+            )]),
+            Rest::<N, C, R>::find_transition_id,
+        )
+        .with_state(rest)
+}
+
+/// Returns the routes of the peers, i.e. `peers/..`.
+pub fn peer_routes<N: Network, C: ConsensusStorage<N>, R: Routing<N>>(rest: Rest<N, C, R>) -> DocumentedRouter {
+    DocumentedRouter::new()
+        .route(
+            Endpoint::get("/peers/count", "Returns the number of connected peers", Schema::Integer),
+            Rest::<N, C, R>::get_peers_count,
+        )
+        .route(
+            Endpoint::get("/peers/all", "Returns the IPs of the connected peers", Schema::Array(&Schema::String)),
+            Rest::<N, C, R>::get_peers_all,
+        )
+        .route(
+            Endpoint::get(
+                "/peers/all/metrics",
+                "Returns the IPs, node types, bytes sent and received, and account addresses of the connected peers",
+                Schema::Array(&Schema::Array(&Schema::String)),
+            ),
+            Rest::<N, C, R>::get_peers_all_metrics,
+        )
+        .route(
+            Endpoint::get(
+                "/peers/restricted",
+                "Returns the restricted peers, with the counts of the trusted and candidate peers",
+                Schema::Ref("RestrictedPeers"),
+            ),
+            Rest::<N, C, R>::get_peers_restricted,
+        )
+        .with_state(rest)
+}
+
+/// Returns the public routes of the node, i.e. `node/..`.
+pub fn node_routes<N: Network, C: ConsensusStorage<N>, R: Routing<N>>(rest: Rest<N, C, R>) -> DocumentedRouter {
+    DocumentedRouter::new()
+        .route(
+            Endpoint::get("/node/status", "Returns the status of the node", Schema::Ref("NodeStatus")),
+            Rest::<N, C, R>::get_node_status,
+        )
+        .route(
+            Endpoint::get(
+                "/node/sync",
+                "Returns the progress of the block sync of the node",
+                Schema::Ref("SyncProgress"),
+            ),
+            Rest::<N, C, R>::get_sync_progress,
+        )
+        .route(
+            Endpoint::get("/node/locators", "Returns the block locators of the node", Schema::Ref("BlockLocators")),
+            Rest::<N, C, R>::get_block_locators,
+        )
+        .with_state(rest)
+}
+
+/// Returns the public routes of the programs, i.e. `program/..`, `deployment/..` and `deployments/..`.
+pub fn program_routes<N: Network, C: ConsensusStorage<N>, R: Routing<N>>(rest: Rest<N, C, R>) -> DocumentedRouter {
+    DocumentedRouter::new()
+        .route(
+            Endpoint::get("/program/{id}", "Returns the program with the given ID", Schema::String)
+                .with_parameters(&[Parameter::path("id", Schema::String, "The program ID.")]),
+            Rest::<N, C, R>::get_program,
+        )
+        .route(
+            Endpoint::get(
+                "/program/{id}/mappings",
+                "Returns the mapping names of a program",
+                Schema::Array(&Schema::String),
+            )
+            .with_parameters(&[Parameter::path("id", Schema::String, "The program ID.")]),
+            Rest::<N, C, R>::get_mapping_names,
+        )
+        .route(
+            Endpoint::get(
+                "/program/{id}/mapping/{name}/{key}",
+                "Returns the value of a mapping key",
+                Schema::Ref("MappingValue"),
+            )
+            .with_parameters(&[
+                Parameter::path("id", Schema::String, "The program ID."),
+                Parameter::path("name", Schema::String, "The mapping name."),
+                Parameter::path("key", Schema::String, "The mapping key."),
+                Parameter::query("metadata", Schema::Boolean, "Whether to include the latest height."),
+            ]),
+            Rest::<N, C, R>::get_mapping_value,
+        )
+        .route(
+            Endpoint::get(
+                "/deployment/{tx_id}/status",
+                "Returns whether a deployment was accepted, or rejected so that only its fee was charged",
+                Schema::Ref("DeploymentStatus"),
+            )
+            .with_parameters(&[Parameter::path(
+                "tx_id",
+                Schema::String,
+                "The ID of the deployment transaction.",
+            )]),
+            Rest::<N, C, R>::get_deployment_status,
+        )
+        .route(
+            Endpoint::get(
+                "/deployments/rejected/recent",
+                "Returns the most recent rejected deployments",
+                Schema::Array(&Schema::Ref("RejectedDeployment")),
+            )
+            .with_parameters(&[Parameter::query(
+                "limit",
+                Schema::Integer,
+                "The maximum number of rejected deployments.",
+            )]),
+            Rest::<N, C, R>::get_rejected_deployments_recent,
+        )
+        .with_state(rest)
+}
+
+/// Returns the routes of the state, i.e. `statePath/..`, `stateRoot/..`, `committee/..` and `delegators/..`.
+pub fn state_routes<N: Network, C: ConsensusStorage<N>, R: Routing<N>>(rest: Rest<N, C, R>) -> DocumentedRouter {
+    DocumentedRouter::new()
+        .route(
+            Endpoint::get("/statePath/{commitment}", "Returns the state path of a commitment", Schema::String)
+                .with_parameters(&[Parameter::path("commitment", Schema::String, "The commitment.")]),
+            Rest::<N, C, R>::get_state_path_for_commitment,
+        )
+        .route(
+            Endpoint::post(
+                "/statePaths",
+                "Returns the state paths of a batch of commitments, against a single state root",
+                Schema::Array(&Schema::String),
+                Schema::Ref("StatePaths"),
+            ),
+            Rest::<N, C, R>::get_state_paths_for_commitments,
+        )
+        .route(
+            Endpoint::get("/stateRoot/latest", "Returns the latest state root", Schema::String),
+            Rest::<N, C, R>::get_state_root_latest,
+        )
+        .route(
+            Endpoint::get("/stateRoot/{height}", "Returns the state root of a block", Schema::String)
+                .with_parameters(&[Parameter::path("height", Schema::Integer, "The block height.")]),
+            Rest::<N, C, R>::get_state_root,
+        )
+        .route(
+            Endpoint::get(
+                "/committee/latest",
+                "Returns the latest committee, or a page of it",
+                Schema::Ref("Committee"),
+            )
+            .with_parameters(&COMMITTEE_PAGE),
+            Rest::<N, C, R>::get_committee_latest,
+        )
+        .route(
+            Endpoint::get(
+                "/committee/{height}",
+                "Returns the committee of a block, or a page of it",
+                Schema::Ref("Committee"),
+            )
+            .with_parameters(&[
+                Parameter::path("height", Schema::Integer, "The block height."),
+                COMMITTEE_PAGE[0],
+                COMMITTEE_PAGE[1],
+            ]),
+            Rest::<N, C, R>::get_committee,
+        )
+        .route(
+            Endpoint::get(
+                "/delegators/{validator}",
+                "Returns the delegators of a validator",
+                Schema::Array(&Schema::String),
+            )
+            .with_parameters(&[Parameter::path(
+                "validator",
+                Schema::String,
+                "The address of the validator.",
+            )]),
+            Rest::<N, C, R>::get_delegators_for_validator,
+        )
+        .with_state(rest)
+}
+
+/// Returns the routes of the Swagger UI, i.e. `docs/..`, whose assets are bundled with the node.
+///
+/// Note: The OpenAPI specification itself is served by `routes`, as it documents all the other routes.
+#[cfg(feature = "swagger-ui")]
+pub fn docs_routes<N: Network, C: ConsensusStorage<N>, R: Routing<N>>(rest: Rest<N, C, R>) -> DocumentedRouter {
+    DocumentedRouter::new()
+        .route(
+            Endpoint::new(HttpMethod::Get, "/docs", "Returns the Swagger UI of the REST server", ResponseBody::Html),
+            Rest::<N, C, R>::get_swagger_ui,
+        )
+        .route(
+            Endpoint::new(HttpMethod::Get, "/docs/{file}", "Returns an asset of the Swagger UI", ResponseBody::File)
+                .with_parameters(&[Parameter::path("file", Schema::String, "The file name of the asset.")])
+                .with_not_found(),
+            Rest::<N, C, R>::get_swagger_asset,
+        )
+        .with_state(rest)
+}
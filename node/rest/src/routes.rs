@@ -14,14 +14,34 @@
 // limitations under the License.
 
 use super::*;
-use snarkos_node_router::{SYNC_LENIENCY, messages::UnconfirmedSolution};
+use snarkos_node_bft_ledger_service::{
+    classify_read,
+    read_block,
+    read_block_authority,
+    read_block_by_hash,
+    read_block_hash,
+    read_block_header,
+    read_block_height,
+    read_block_transaction_ids,
+};
+use snarkos_node_consensus::{
+    IS_FEE_PRIORITIZED,
+    MAX_MEMORY_POOL_TRANSMISSIONS,
+    TransmissionKind,
+    TransmissionSummary,
+};
+use snarkos_node_router::{PeerExport, SYNC_LENIENCY, TraceTarget, messages::UnconfirmedSolution};
+use snarkos_node_sync_locators::BlockLocators;
 use snarkvm::{
-    ledger::puzzle::Solution,
+    ledger::{
+        narwhal::{Transmission, TransmissionID},
+        puzzle::{Solution, SolutionID},
+    },
     prelude::{Address, Identifier, LimitedWriter, Plaintext, ToBytes, block::Transaction},
 };
 
-use indexmap::IndexMap;
-use rayon::prelude::*;
+use ::time::OffsetDateTime;
+use axum::{http::HeaderMap, response::IntoResponse};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 
@@ -34,6 +54,34 @@ pub(crate) struct BlockRange {
     end: u32,
 }
 
+/// The `get_blocks_recent` query object.
+#[derive(Copy, Clone, Deserialize, Serialize)]
+pub(crate) struct RecentBlocksQuery {
+    /// The maximum number of block summaries to return.
+    limit: Option<usize>,
+}
+
+/// The `get_rejected_deployments_recent` query object.
+#[derive(Copy, Clone, Deserialize, Serialize)]
+pub(crate) struct RejectedDeploymentsQuery {
+    /// The maximum number of rejected deployments to return.
+    limit: Option<usize>,
+}
+
+/// The query object for the memory pool endpoints.
+#[derive(Copy, Clone, Deserialize, Serialize)]
+pub(crate) struct MemoryPoolQuery {
+    /// Whether to return the full transmissions (capped), instead of their summaries.
+    full: Option<bool>,
+}
+
+/// The `get_fee_estimate` query object.
+#[derive(Copy, Clone, Deserialize, Serialize)]
+pub(crate) struct FeeEstimateQuery {
+    /// The number of most recent blocks to aggregate the priority fees over.
+    blocks: Option<u32>,
+}
+
 /// The query object for `get_mapping_value` and `get_mapping_values`.
 #[derive(Copy, Clone, Deserialize, Serialize)]
 pub(crate) struct Metadata {
@@ -41,48 +89,153 @@ pub(crate) struct Metadata {
     all: Option<bool>,
 }
 
+/// The `get_broadcast_journal` query object.
+#[derive(Copy, Clone, Deserialize, Serialize)]
+pub(crate) struct JournalQuery {
+    /// The UNIX timestamp from which to return entries (inclusive).
+    since: Option<i64>,
+}
+
+/// The request object for `sync_from_peer`.
+#[derive(Copy, Clone, Deserialize, Serialize)]
+pub(crate) struct SyncFromPeer {
+    /// The IP address of the connected peer to sync from.
+    peer_ip: SocketAddr,
+}
+
 impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
     // GET /<network>/block/height/latest
-    pub(crate) async fn get_block_height_latest(State(rest): State<Self>) -> ErasedJson {
-        ErasedJson::pretty(rest.ledger.latest_height())
+    pub(crate) async fn get_block_height_latest(
+        State(rest): State<Self>,
+    ) -> Result<(Mutability, ErasedJson), RestError> {
+        Ok((Mutability::Latest, ErasedJson::pretty(rest.latest_block_info()?.height)))
     }
 
     // GET /<network>/block/hash/latest
-    pub(crate) async fn get_block_hash_latest(State(rest): State<Self>) -> ErasedJson {
-        ErasedJson::pretty(rest.ledger.latest_hash())
+    pub(crate) async fn get_block_hash_latest(State(rest): State<Self>) -> Result<(Mutability, ErasedJson), RestError> {
+        Ok((Mutability::Latest, ErasedJson::pretty(rest.latest_block_info()?.hash)))
+    }
+
+    // GET /<network>/block/latest/info
+    pub(crate) async fn get_block_latest_info(State(rest): State<Self>) -> Result<(Mutability, ErasedJson), RestError> {
+        Ok((Mutability::Latest, ErasedJson::pretty(&*rest.latest_block_info()?)))
     }
 
     // GET /<network>/block/latest
-    pub(crate) async fn get_block_latest(State(rest): State<Self>) -> ErasedJson {
-        ErasedJson::pretty(rest.ledger.latest_block())
+    pub(crate) async fn get_block_latest(
+        State(rest): State<Self>,
+        headers: HeaderMap,
+        Query(query): Query<FormatQuery>,
+    ) -> Result<Response, RestError> {
+        let block = rest.ledger.latest_block();
+        let response = OutputFormat::negotiate(&headers, &query)?.respond(&headers, block.hash(), || Ok(block))?;
+        Ok((Mutability::Latest, response).into_response())
+    }
+
+    // GET /<network>/block/next/estimate
+    pub(crate) async fn get_next_block_estimate(
+        State(rest): State<Self>,
+    ) -> Result<(Mutability, ErasedJson), RestError> {
+        let estimate = rest.next_block_estimate.get(rest.latest_block_key(), || BlockEstimate::load(&rest.ledger))?;
+        // A ledger with only the genesis block has no block interval yet, so the client should retry later.
+        let Some(estimate) = estimate else {
+            return Err(RestError::ServiceUnavailable(
+                "The next block cannot be estimated from the genesis block alone".to_string(),
+            ));
+        };
+        // Refine the estimate with the round progress of the BFT, if this is a validator.
+        let Some(consensus) = &rest.consensus else {
+            return Ok((Mutability::Latest, ErasedJson::pretty(estimate)));
+        };
+        let storage = consensus.bft().storage();
+        let current_round = storage.current_round();
+        // Approximate the end of each recent round by the latest certificate timestamp in the round.
+        let round_ends: Vec<_> = (current_round.saturating_sub(ROUND_PROGRESS_NUM_ROUNDS)..current_round)
+            .filter_map(|round| storage.get_certificates_for_round(round).iter().map(|c| c.timestamp()).max())
+            .collect();
+        let estimate = match (round_ends.last(), typical_round_duration(&round_ends)) {
+            (Some(round_start), Some(duration)) => {
+                let now = OffsetDateTime::now_utc().unix_timestamp();
+                estimate.with_round_progress(current_round, *round_start, duration, now)
+            }
+            _ => estimate,
+        };
+        Ok((Mutability::Latest, ErasedJson::pretty(estimate)))
+    }
+
+    // GET /<network>/fees/estimate
+    // GET /<network>/fees/estimate?blocks={n}
+    pub(crate) async fn get_fee_estimate(
+        State(rest): State<Self>,
+        Query(query): Query<FeeEstimateQuery>,
+    ) -> Result<ErasedJson, RestError> {
+        let num_blocks = query.blocks.unwrap_or(DEFAULT_FEE_ESTIMATE_NUM_BLOCKS).min(MAX_FEE_ESTIMATE_NUM_BLOCKS);
+        // Take a snapshot of the priority fees of the whole memory pool, if this is a validator.
+        // Note: The fees are kept by consensus as the transactions are queued, so none are deserialized here.
+        let (queue_depth, memory_pool) = match &rest.consensus {
+            Some(consensus) => {
+                let (queue_depth, fees) = consensus.memory_pool_priority_fees();
+                (Some(queue_depth), FeePercentiles::new(fees))
+            }
+            None => (None, None),
+        };
+        // Aggregate the historical priority fees on a blocking thread, as the transactions are read from storage.
+        let estimate = tokio::task::spawn_blocking(move || -> Result<FeeEstimate, RestError> {
+            let (latest_height, fees) = rest.block_fees.load(&rest.ledger, num_blocks)?;
+            Ok(FeeEstimate {
+                is_fee_prioritized: rest.consensus.is_some() && IS_FEE_PRIORITIZED,
+                queue_depth,
+                memory_pool,
+                latest_height,
+                num_blocks: num_blocks.min(latest_height.saturating_add(1)),
+                recent_blocks: FeePercentiles::new(fees),
+            })
+        })
+        .await;
+        match estimate {
+            Ok(estimate) => estimate.map(ErasedJson::pretty),
+            Err(error) => Err(RestError::InternalServerError(format!("Failed to estimate the fees - {error}"))),
+        }
     }
 
     // GET /<network>/block/{height}
     // GET /<network>/block/{blockHash}
+    // GET /<network>/block/{height}?include={header|txids|full}
     pub(crate) async fn get_block(
         State(rest): State<Self>,
-        Path(height_or_hash): Path<String>,
-    ) -> Result<ErasedJson, RestError> {
-        // Manually parse the height or the height of the hash, axum doesn't support different types
-        // for the same path param.
-        let block = if let Ok(height) = height_or_hash.parse::<u32>() {
-            rest.ledger.get_block(height)?
-        } else {
-            let hash = height_or_hash
-                .parse::<N::BlockHash>()
-                .map_err(|_| RestError("invalid input, it is neither a block height nor a block hash".to_string()))?;
-
-            rest.ledger.get_block_by_hash(&hash)?
+        Param(height_or_hash): Param<HeightOrHash<N>>,
+        headers: HeaderMap,
+        Query(query): Query<FormatQuery>,
+        Query(BlockIncludeQuery { include }): Query<BlockIncludeQuery>,
+    ) -> Result<Response, RestError> {
+        let hash = match height_or_hash {
+            HeightOrHash::Height(height) => read_block_hash(&rest.ledger, height)?,
+            HeightOrHash::Hash(hash) => hash,
         };
 
-        Ok(ErasedJson::pretty(block))
+        let format = OutputFormat::negotiate(&headers, &query)?;
+        let response = match include {
+            BlockInclude::Full => format.respond(&headers, hash, || read_block_by_hash(&rest.ledger, &hash))?,
+            // The reduced parts of the block are read without loading its transactions, and are only encoded as JSON.
+            reduced => respond_json(&headers, hash, || {
+                reduced_block_json(
+                    reduced,
+                    hash,
+                    || read_block_header(&rest.ledger, &hash),
+                    || read_block_authority(&rest.ledger, &hash),
+                    || read_block_transaction_ids(&rest.ledger, &hash),
+                )
+            })?,
+        };
+        // The blocks are final, so a block is immutable whether it is addressed by its height or its hash.
+        Ok((Mutability::Immutable, response).into_response())
     }
 
     // GET /<network>/blocks?start={start_height}&end={end_height}
     pub(crate) async fn get_blocks(
         State(rest): State<Self>,
         Query(block_range): Query<BlockRange>,
-    ) -> Result<ErasedJson, RestError> {
+    ) -> Result<Response, RestError> {
         let start_height = block_range.start;
         let end_height = block_range.end;
 
@@ -90,95 +243,257 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
 
         // Ensure the end height is greater than the start height.
         if start_height > end_height {
-            return Err(RestError("Invalid block range".to_string()));
+            return Err(RestError::BadRequest("Invalid block range".to_string()));
         }
 
         // Ensure the block range is bounded.
         if end_height - start_height > MAX_BLOCK_RANGE {
-            return Err(RestError(format!(
+            return Err(RestError::BadRequest(format!(
                 "Cannot request more than {MAX_BLOCK_RANGE} blocks per call (requested {})",
                 end_height - start_height
             )));
         }
 
-        // Prepare a closure for the blocking work.
-        let get_json_blocks = move || -> Result<ErasedJson, RestError> {
-            let blocks = cfg_into_iter!((start_height..end_height))
-                .map(|height| rest.ledger.get_block(height))
-                .collect::<Result<Vec<_>, _>>()?;
-
-            Ok(ErasedJson::pretty(blocks))
-        };
+        // Stream the blocks, reading them from the ledger a chunk at a time.
+        let ledger = rest.ledger;
+        stream_json_array(start_height..end_height, move |height| Ok(read_block(&ledger, height)?)).await
+    }
 
-        // Fetch the blocks from ledger and serialize to json.
-        match tokio::task::spawn_blocking(get_json_blocks).await {
-            Ok(json) => json,
-            Err(err) => Err(RestError(format!("Failed to get blocks '{start_height}..{end_height}' - {err}"))),
-        }
+    // GET /<network>/blocks/recent?limit={limit}
+    pub(crate) async fn get_blocks_recent(
+        State(rest): State<Self>,
+        Query(query): Query<RecentBlocksQuery>,
+    ) -> (Mutability, ErasedJson) {
+        // Return the most recent block summaries, newest first.
+        let limit = query.limit.unwrap_or(usize::MAX);
+        (Mutability::Latest, ErasedJson::pretty(rest.recent_blocks.latest(limit)))
     }
 
     // GET /<network>/height/{blockHash}
     pub(crate) async fn get_height(
         State(rest): State<Self>,
-        Path(hash): Path<N::BlockHash>,
-    ) -> Result<ErasedJson, RestError> {
-        Ok(ErasedJson::pretty(rest.ledger.get_height(&hash)?))
+        Param(BlockHash(hash)): Param<BlockHash<N>>,
+        headers: HeaderMap,
+    ) -> Result<Response, RestError> {
+        let response = respond_json(&headers, hash, || read_block_height(&rest.ledger, &hash))?;
+        Ok((Mutability::Immutable, response).into_response())
     }
 
     // GET /<network>/block/{height}/transactions
+    // GET /<network>/block/{blockHash}/transactions
     pub(crate) async fn get_block_transactions(
         State(rest): State<Self>,
-        Path(height): Path<u32>,
-    ) -> Result<ErasedJson, RestError> {
-        Ok(ErasedJson::pretty(rest.ledger.get_transactions(height)?))
+        Param(height_or_hash): Param<HeightOrHash<N>>,
+        headers: HeaderMap,
+        Query(query): Query<FormatQuery>,
+    ) -> Result<Response, RestError> {
+        // The transactions of a block are identified by the block hash, and read by the block height.
+        let (height, hash) = match height_or_hash {
+            HeightOrHash::Height(height) => (height, read_block_hash(&rest.ledger, height)?),
+            HeightOrHash::Hash(hash) => (read_block_height(&rest.ledger, &hash)?, hash),
+        };
+        let response = OutputFormat::negotiate(&headers, &query)?.respond(&headers, hash, || {
+            let transactions = rest.ledger.get_transactions(height);
+            classify_read(
+                transactions,
+                height,
+                || rest.ledger.latest_height(),
+                || rest.ledger.contains_block_height(height),
+            )
+        })?;
+        Ok((Mutability::Immutable, response).into_response())
     }
 
     // GET /<network>/transaction/{transactionID}
     pub(crate) async fn get_transaction(
         State(rest): State<Self>,
-        Path(tx_id): Path<N::TransactionID>,
-    ) -> Result<ErasedJson, RestError> {
-        Ok(ErasedJson::pretty(rest.ledger.get_transaction(tx_id)?))
+        Param(TxId(tx_id)): Param<TxId<N>>,
+        headers: HeaderMap,
+        Query(query): Query<FormatQuery>,
+    ) -> Result<Response, RestError> {
+        let response = OutputFormat::negotiate(&headers, &query)?
+            .respond(&headers, tx_id, || rest.ledger.get_transaction(tx_id))?;
+        Ok((Mutability::Immutable, response).into_response())
     }
 
     // GET /<network>/transaction/confirmed/{transactionID}
     pub(crate) async fn get_confirmed_transaction(
         State(rest): State<Self>,
-        Path(tx_id): Path<N::TransactionID>,
-    ) -> Result<ErasedJson, RestError> {
-        Ok(ErasedJson::pretty(rest.ledger.get_confirmed_transaction(tx_id)?))
+        Param(TxId(tx_id)): Param<TxId<N>>,
+        headers: HeaderMap,
+    ) -> Result<Response, RestError> {
+        let response = respond_json(&headers, tx_id, || rest.ledger.get_confirmed_transaction(tx_id))?;
+        Ok((Mutability::Immutable, response).into_response())
     }
 
-    // GET /<network>/memoryPool/transmissions
-    pub(crate) async fn get_memory_pool_transmissions(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
+    // GET /<network>/memoryPool/transmissions?full={bool}
+    pub(crate) async fn get_memory_pool_transmissions(
+        State(rest): State<Self>,
+        Query(query): Query<MemoryPoolQuery>,
+    ) -> Result<Response, RestError> {
+        match rest.consensus {
+            Some(consensus) => match query.full.unwrap_or(false) {
+                true => {
+                    // Stream the transmissions, reading them from the memory pool a chunk at a time.
+                    let ids = consensus.memory_pool_transmission_ids(None, MAX_MEMORY_POOL_TRANSMISSIONS);
+                    stream_json_object(ids, move |id| Ok(consensus.memory_pool_transmission(id).map(|tx| (id, tx))))
+                        .await
+                }
+                false => Ok(ErasedJson::pretty(consensus.memory_pool_summaries()).into_response()),
+            },
+            None => Err(RestError::InternalServerError("Route isn't available for this node type".to_string())),
+        }
+    }
+
+    // GET /<network>/memoryPool/solutions?full={bool}
+    pub(crate) async fn get_memory_pool_solutions(
+        State(rest): State<Self>,
+        Query(query): Query<MemoryPoolQuery>,
+    ) -> Result<Response, RestError> {
+        match rest.consensus {
+            Some(consensus) => {
+                match query.full.unwrap_or(false) {
+                    true => {
+                        let kind = Some(TransmissionKind::Solution);
+                        let ids = consensus.memory_pool_transmission_ids(kind, MAX_MEMORY_POOL_TRANSMISSIONS);
+                        stream_json_object(ids, move |id| {
+                            Ok(match (id, consensus.memory_pool_transmission(id)) {
+                                (TransmissionID::Solution(id, _), Some(Transmission::Solution(solution))) => {
+                                    Some((id, solution))
+                                }
+                                _ => None,
+                            })
+                        })
+                        .await
+                    }
+                    false => Ok(ErasedJson::pretty(memory_pool_summaries(&consensus, TransmissionKind::Solution))
+                        .into_response()),
+                }
+            }
+            None => Err(RestError::InternalServerError("Route isn't available for this node type".to_string())),
+        }
+    }
+
+    // GET /<network>/memoryPool/transactions?full={bool}
+    pub(crate) async fn get_memory_pool_transactions(
+        State(rest): State<Self>,
+        Query(query): Query<MemoryPoolQuery>,
+    ) -> Result<Response, RestError> {
+        match rest.consensus {
+            Some(consensus) => match query.full.unwrap_or(false) {
+                true => {
+                    let kind = Some(TransmissionKind::Transaction);
+                    let ids = consensus.memory_pool_transmission_ids(kind, MAX_MEMORY_POOL_TRANSMISSIONS);
+                    stream_json_object(ids, move |id| {
+                        Ok(match (id, consensus.memory_pool_transmission(id)) {
+                            (TransmissionID::Transaction(id, _), Some(Transmission::Transaction(tx))) => Some((id, tx)),
+                            _ => None,
+                        })
+                    })
+                    .await
+                }
+                false => Ok(ErasedJson::pretty(memory_pool_summaries(&consensus, TransmissionKind::Transaction))
+                    .into_response()),
+            },
+            None => Err(RestError::InternalServerError("Route isn't available for this node type".to_string())),
+        }
+    }
+
+    // GET /<network>/bft/workers
+    pub(crate) async fn get_bft_workers(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
         match rest.consensus {
             Some(consensus) => {
-                Ok(ErasedJson::pretty(consensus.unconfirmed_transmissions().collect::<IndexMap<_, _>>()))
+                // Take a snapshot of the worker queues, prior to serialization.
+                let workers: Vec<_> = consensus
+                    .worker_queue_stats()
+                    .into_iter()
+                    .map(|stats| {
+                        json!({
+                            "worker_id": stats.worker_id,
+                            "num_transmissions": stats.num_transmissions,
+                            "num_ratifications": stats.num_ratifications,
+                            "num_solutions": stats.num_solutions,
+                            "num_transactions": stats.num_transactions,
+                            "num_pending": stats.num_pending,
+                            "num_bytes": stats.num_bytes,
+                            "oldest_timestamp": stats.oldest_timestamp,
+                            "num_recently_drained": stats.num_recently_drained,
+                        })
+                    })
+                    .collect();
+                Ok(ErasedJson::pretty(workers))
             }
-            None => Err(RestError("Route isn't available for this node type".to_string())),
+            None => Err(RestError::InternalServerError("Route isn't available for this node type".to_string())),
         }
     }
 
-    // GET /<network>/memoryPool/solutions
-    pub(crate) async fn get_memory_pool_solutions(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
+    // GET /<network>/bft/preview_block
+    pub(crate) async fn get_bft_preview_block(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
         match rest.consensus {
-            Some(consensus) => Ok(ErasedJson::pretty(consensus.unconfirmed_solutions().collect::<IndexMap<_, _>>())),
-            None => Err(RestError("Route isn't available for this node type".to_string())),
+            Some(consensus) => {
+                // Admit one preview at a time, and at most one per interval, as each speculates a whole block.
+                let _permit = rest.block_preview.try_acquire()?;
+                // Note: The speculation runs on the blocking pool, and never writes to the ledger.
+                Ok(ErasedJson::pretty(consensus.preview_next_block().await?))
+            }
+            None => Err(RestError::InternalServerError("Route isn't available for this node type".to_string())),
         }
     }
 
-    // GET /<network>/memoryPool/transactions
-    pub(crate) async fn get_memory_pool_transactions(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
+    // GET /<network>/bft/connections
+    pub(crate) async fn get_bft_connections(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
         match rest.consensus {
-            Some(consensus) => Ok(ErasedJson::pretty(consensus.unconfirmed_transactions().collect::<IndexMap<_, _>>())),
-            None => Err(RestError("Route isn't available for this node type".to_string())),
+            Some(consensus) => {
+                let connections: Vec<_> = consensus
+                    .connection_stats()
+                    .into_iter()
+                    .map(|connection| {
+                        let (messages_sent, bytes_sent, messages_received, bytes_received) = connection.totals();
+                        let traffic: serde_json::Map<_, _> = connection
+                            .traffic
+                            .iter()
+                            .map(|traffic| {
+                                (
+                                    traffic.name.to_string(),
+                                    json!({
+                                        "messages_sent": traffic.messages_sent,
+                                        "bytes_sent": traffic.bytes_sent,
+                                        "messages_received": traffic.messages_received,
+                                        "bytes_received": traffic.bytes_received,
+                                    }),
+                                )
+                            })
+                            .collect();
+                        json!({
+                            "peer_ip": connection.peer_ip,
+                            "address": connection.address,
+                            "established": connection.established,
+                            "uptime": connection.uptime,
+                            "messages_sent": messages_sent,
+                            "bytes_sent": bytes_sent,
+                            "messages_received": messages_received,
+                            "bytes_received": bytes_received,
+                            "traffic": traffic,
+                            "last_error": connection.last_error.map(|(error, timestamp)| json!({
+                                "error": error,
+                                "timestamp": timestamp,
+                            })),
+                            "num_reconnects": connection.num_reconnects,
+                            "num_withheld_proposals": connection.num_withheld_proposals,
+                            "is_declined_proposer": connection.is_declined_proposer,
+                        })
+                    })
+                    .collect();
+                Ok(ErasedJson::pretty(connections))
+            }
+            None => Err(RestError::InternalServerError("Route isn't available for this node type".to_string())),
         }
     }
 
     // GET /<network>/program/{programID}
     pub(crate) async fn get_program(
         State(rest): State<Self>,
-        Path(id): Path<ProgramID<N>>,
+        Param(id): Param<ProgramID<N>>,
     ) -> Result<ErasedJson, RestError> {
         Ok(ErasedJson::pretty(rest.ledger.get_program(id)?))
     }
@@ -186,7 +501,7 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
     // GET /<network>/program/{programID}/mappings
     pub(crate) async fn get_mapping_names(
         State(rest): State<Self>,
-        Path(id): Path<ProgramID<N>>,
+        Param(id): Param<ProgramID<N>>,
     ) -> Result<ErasedJson, RestError> {
         Ok(ErasedJson::pretty(rest.ledger.vm().finalize_store().get_mapping_names_confirmed(&id)?))
     }
@@ -195,7 +510,7 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
     // GET /<network>/program/{programID}/mapping/{mappingName}/{mappingKey}?metadata={true}
     pub(crate) async fn get_mapping_value(
         State(rest): State<Self>,
-        Path((id, name, key)): Path<(ProgramID<N>, Identifier<N>, Plaintext<N>)>,
+        Params((id, name, key)): Params<(ProgramID<N>, Identifier<N>, Plaintext<N>)>,
         metadata: Query<Metadata>,
     ) -> Result<ErasedJson, RestError> {
         // Retrieve the mapping value.
@@ -216,12 +531,14 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
     // GET /<network>/program/{programID}/mapping/{mappingName}?all={true}&metadata={true}
     pub(crate) async fn get_mapping_values(
         State(rest): State<Self>,
-        Path((id, name)): Path<(ProgramID<N>, Identifier<N>)>,
+        Params((id, name)): Params<(ProgramID<N>, Identifier<N>)>,
         metadata: Query<Metadata>,
     ) -> Result<ErasedJson, RestError> {
         // Return an error if the `all` query parameter is not set to `true`.
         if metadata.all != Some(true) {
-            return Err(RestError("Invalid query parameter. At this time, 'all=true' must be included".to_string()));
+            return Err(RestError::BadRequest(
+                "Invalid query parameter. At this time, 'all=true' must be included".to_string(),
+            ));
         }
 
         // Retrieve the latest height.
@@ -243,87 +560,526 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
                 // Return the full mapping without metadata.
                 Ok(ErasedJson::pretty(mapping_values))
             }
-            Ok(Err(err)) => Err(RestError(format!("Unable to read mapping - {err}"))),
-            Err(err) => Err(RestError(format!("Unable to read mapping - {err}"))),
+            Ok(Err(err)) => Err(RestError::InternalServerError(format!("Unable to read mapping - {err}"))),
+            Err(err) => Err(RestError::InternalServerError(format!("Unable to read mapping - {err}"))),
         }
     }
 
     // GET /<network>/statePath/{commitment}
     pub(crate) async fn get_state_path_for_commitment(
         State(rest): State<Self>,
-        Path(commitment): Path<Field<N>>,
+        Param(Commitment(commitment)): Param<Commitment<N>>,
     ) -> Result<ErasedJson, RestError> {
         Ok(ErasedJson::pretty(rest.ledger.get_state_path_for_commitment(&commitment)?))
     }
 
+    // POST /<network>/statePaths
+    pub(crate) async fn get_state_paths_for_commitments(
+        State(rest): State<Self>,
+        ConnectInfo(addr): ConnectInfo<SocketAddr>,
+        Json(commitments): Json<Vec<Field<N>>>,
+    ) -> Result<ErasedJson, RestError> {
+        // Ensure the number of commitments is within bounds.
+        if commitments.is_empty() || commitments.len() > MAX_STATE_PATHS {
+            return Err(RestError::BadRequest(format!(
+                "The number of commitments must be between 1 and {MAX_STATE_PATHS}"
+            )));
+        }
+        // Count the commitments against the weight limit.
+        rest.charge_rate_limit(addr.ip(), commitments.len() as u32)?;
+
+        // Compute the state paths against a single state root.
+        let ledger = rest.ledger.clone();
+        let (global_state_root, state_paths) = tokio::task::spawn_blocking(move || {
+            state_paths_at_consistent_root(
+                &commitments,
+                || ledger.latest_state_root(),
+                |commitment| ledger.get_state_path_for_commitment(commitment),
+                |state_path| state_path.global_state_root(),
+            )
+        })
+        .await
+        .map_err(|err| RestError::InternalServerError(format!("Unable to compute the state paths - {err}")))??;
+
+        Ok(ErasedJson::pretty(json!({ "global_state_root": global_state_root, "state_paths": state_paths })))
+    }
+
     // GET /<network>/stateRoot/latest
-    pub(crate) async fn get_state_root_latest(State(rest): State<Self>) -> ErasedJson {
-        ErasedJson::pretty(rest.ledger.latest_state_root())
+    pub(crate) async fn get_state_root_latest(State(rest): State<Self>) -> Result<(Mutability, ErasedJson), RestError> {
+        let state_root = rest.latest_state_root.get(rest.latest_block_key(), || Ok(rest.ledger.latest_state_root()))?;
+        Ok((Mutability::Latest, ErasedJson::pretty(state_root)))
     }
 
     // GET /<network>/stateRoot/{height}
     pub(crate) async fn get_state_root(
         State(rest): State<Self>,
-        Path(height): Path<u32>,
-    ) -> Result<ErasedJson, RestError> {
-        Ok(ErasedJson::pretty(rest.ledger.get_state_root(height)?))
+        Param(Height(height)): Param<Height>,
+        headers: HeaderMap,
+    ) -> Result<Response, RestError> {
+        // The state root at a height is tagged with the hash of the block at that height.
+        let hash = read_block_hash(&rest.ledger, height)?;
+        let response = respond_json(&headers, hash, || rest.ledger.get_state_root(height))?;
+        Ok((Mutability::Immutable, response).into_response())
     }
 
     // GET /<network>/committee/latest
-    pub(crate) async fn get_committee_latest(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
-        Ok(ErasedJson::pretty(rest.ledger.latest_committee()?))
+    pub(crate) async fn get_committee_latest(
+        State(rest): State<Self>,
+        Query(query): Query<CommitteePageQuery>,
+    ) -> Result<(Mutability, ErasedJson), RestError> {
+        let committee = rest.latest_committee.get(rest.latest_block_key(), || rest.ledger.latest_committee())?;
+        if query.is_paginated() {
+            let (offset, limit) = query.bounds();
+            return Ok((Mutability::Latest, ErasedJson::pretty(CommitteePage::new(&committee, offset, limit))));
+        }
+        Ok((Mutability::Latest, ErasedJson::pretty(committee)))
+    }
+
+    // GET /<network>/committee/connectivity
+    pub(crate) async fn get_committee_connectivity(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
+        let Some(consensus) = &rest.consensus else {
+            return Err(RestError::InternalServerError("Route isn't available for this node type".to_string()));
+        };
+        // Take a snapshot of the connectivity, prior to serialization.
+        let committee = rest.ledger.latest_committee()?;
+        let connected_validators = consensus.connected_validators();
+        let last_activity = consensus.validator_last_activity();
+        let (certificate_round, signers) = consensus.latest_certificate_signers().unzip();
+        let address = rest.routing()?.router().address();
+
+        let members: Vec<_> = committee
+            .members()
+            .iter()
+            .map(|(member, (stake, _, _))| {
+                json!({
+                    "address": member,
+                    "stake": stake,
+                    "is_self": *member == address,
+                    "is_connected": connected_validators.contains(member),
+                    "last_activity": last_activity.get(member),
+                    "signed_latest_certificate": signers.as_ref().map(|signers| signers.contains(member)),
+                })
+            })
+            .collect();
+        Ok(ErasedJson::pretty(json!({
+            "starting_round": committee.starting_round(),
+            "latest_certificate_round": certificate_round,
+            "members": members,
+        })))
     }
 
     // GET /<network>/committee/{height}
     pub(crate) async fn get_committee(
         State(rest): State<Self>,
-        Path(height): Path<u32>,
-    ) -> Result<ErasedJson, RestError> {
-        Ok(ErasedJson::pretty(rest.ledger.get_committee(height)?))
+        Param(Height(height)): Param<Height>,
+        Query(query): Query<CommitteePageQuery>,
+        headers: HeaderMap,
+    ) -> Result<Response, RestError> {
+        // The committee at a height is tagged with the hash of the block at that height.
+        let hash = read_block_hash(&rest.ledger, height)?;
+        let response = match query.is_paginated() {
+            // The pages of the committee are tagged with their bounds as well.
+            true => {
+                let (offset, limit) = query.bounds();
+                respond_json(&headers, format!("{hash}-{offset}-{limit}"), || {
+                    Ok(rest
+                        .ledger
+                        .get_committee(height)?
+                        .map(|committee| CommitteePage::new(&committee, offset, limit)))
+                })?
+            }
+            false => respond_json(&headers, hash, || rest.ledger.get_committee(height))?,
+        };
+        Ok((Mutability::Immutable, response).into_response())
     }
 
     // GET /<network>/delegators/{validator}
     pub(crate) async fn get_delegators_for_validator(
         State(rest): State<Self>,
-        Path(validator): Path<Address<N>>,
-    ) -> Result<ErasedJson, RestError> {
+        Param(validator): Param<Address<N>>,
+    ) -> Result<Response, RestError> {
         // Do not process the request if the node is too far behind to avoid sending outdated data.
-        if rest.routing.num_blocks_behind() > SYNC_LENIENCY {
-            return Err(RestError("Unable to  request delegators (node is syncing)".to_string()));
+        if rest.num_blocks_behind() > SYNC_LENIENCY {
+            return Err(RestError::InternalServerError("Unable to  request delegators (node is syncing)".to_string()));
         }
 
         // Return the delegators for the given validator.
+        // Note: The ledger only lists the delegators as a whole, so only their addresses are held, and the response
+        // is serialized a chunk at a time.
         match tokio::task::spawn_blocking(move || rest.ledger.get_delegators_for_validator(&validator)).await {
-            Ok(Ok(delegators)) => Ok(ErasedJson::pretty(delegators)),
-            Ok(Err(err)) => Err(RestError(format!("Unable to request delegators - {err}"))),
-            Err(err) => Err(RestError(format!("Unable to request delegators - {err}"))),
+            Ok(Ok(delegators)) => stream_json_array(delegators, Ok).await,
+            Ok(Err(err)) => Err(RestError::InternalServerError(format!("Unable to request delegators - {err}"))),
+            Err(err) => Err(RestError::InternalServerError(format!("Unable to request delegators - {err}"))),
         }
     }
 
     // GET /<network>/peers/count
-    pub(crate) async fn get_peers_count(State(rest): State<Self>) -> ErasedJson {
-        ErasedJson::pretty(rest.routing.router().number_of_connected_peers())
+    pub(crate) async fn get_peers_count(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
+        Ok(ErasedJson::pretty(rest.routing()?.router().number_of_connected_peers()))
     }
 
     // GET /<network>/peers/all
-    pub(crate) async fn get_peers_all(State(rest): State<Self>) -> ErasedJson {
-        ErasedJson::pretty(rest.routing.router().connected_peers())
+    pub(crate) async fn get_peers_all(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
+        Ok(ErasedJson::pretty(rest.routing()?.router().connected_peers()))
     }
 
     // GET /<network>/peers/all/metrics
-    pub(crate) async fn get_peers_all_metrics(State(rest): State<Self>) -> ErasedJson {
-        ErasedJson::pretty(rest.routing.router().connected_metrics())
+    pub(crate) async fn get_peers_all_metrics(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
+        Ok(ErasedJson::pretty(rest.routing()?.router().connected_metrics()))
+    }
+
+    // GET /<network>/peers/restricted
+    pub(crate) async fn get_peers_restricted(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
+        let router = rest.routing()?.router();
+        Ok(ErasedJson::pretty(RestrictedPeers::new(
+            OffsetDateTime::now_utc().unix_timestamp(),
+            router.active_restricted_peers(),
+            router.trusted_peers().len(),
+            router.number_of_candidate_peers(),
+        )))
+    }
+
+    // GET /<network>/node/status
+    pub(crate) async fn get_node_status(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
+        // In safe mode, the node only serves its local ledger.
+        let Ok(routing) = rest.routing() else {
+            let latest = rest.latest_block_info()?;
+            return Ok(ErasedJson::pretty(json!({
+                "mode": "safe",
+                "latest_height": latest.height,
+                "latest_hash": latest.hash,
+                "log_file": rest.config.logging.log_file,
+            })));
+        };
+        let router = routing.router();
+        let num_connected_peers = router.number_of_connected_peers();
+
+        // Summarize the status of the node account, as observed in the local ledger.
+        let account = routing.account_status().map(|status| {
+            json!({
+                "summary": status.summary(router.node_type(), router.address()),
+                "height": status.height(),
+                "is_synced": status.is_synced(),
+                "is_committee_member": status.is_committee_member(),
+                "is_bonded": status.is_bonded(),
+                "has_public_balance": status.has_public_balance(),
+            })
+        });
+
+        // Summarize the experiments, with the share of the connected peers they are enabled for.
+        let experiments: Vec<_> = router
+            .experiment_assignments()
+            .into_iter()
+            .map(|(name, rollout, num_enabled_peers)| {
+                json!({
+                    "name": name,
+                    "rollout": rollout.to_string(),
+                    "num_enabled_peers": num_enabled_peers,
+                    "enabled_rate": match num_connected_peers {
+                        0 => 0.0,
+                        num_peers => num_enabled_peers as f64 / num_peers as f64,
+                    },
+                })
+            })
+            .collect();
+
+        // Summarize the block-production health, if the node is a validator.
+        let health = rest
+            .consensus
+            .as_ref()
+            .map(|consensus| consensus.block_production_health(routing.is_block_synced(), router.is_fd_exhausted()));
+
+        // Summarize the other nodes that recently authenticated as the account of this node.
+        let mut duplicate_peer_ips = router.duplicate_identity_peers();
+        let mut is_paused = false;
+        if let Some(consensus) = &rest.consensus {
+            let primary = consensus.bft().primary();
+            duplicate_peer_ips.extend(primary.gateway().duplicate_identity_peers());
+            is_paused = primary.is_paused_on_duplicate_identity();
+        }
+        let duplicate_identity = json!({
+            "is_detected": !duplicate_peer_ips.is_empty(),
+            "peer_ips": duplicate_peer_ips,
+            "is_paused": is_paused,
+        });
+
+        // Summarize the gate on the gossip of the node, while it is significantly behind.
+        let relay_gate = router.relay_gate();
+        let relay_gate = json!({
+            "is_engaged": relay_gate.is_engaged(),
+            "threshold": relay_gate.threshold(),
+            "num_blocks_behind": relay_gate.num_blocks_behind(),
+            "num_suppressed": relay_gate.num_suppressed(),
+        });
+
+        // Summarize the pressure on the inbound queues of the memory pool, if the node is a validator.
+        let mempool_pressure = rest.consensus.as_ref().map(|consensus| {
+            let (pressure, now) = (consensus.mempool_pressure(), Instant::now());
+            json!({
+                "solutions": pressure.solutions().status(now),
+                "transactions": pressure.transactions().status(now),
+            })
+        });
+
+        // Summarize the exhaustion of the file descriptors, as reported by the connections of the router.
+        let fd_exhaustion = router.fd_exhaustion();
+        let fd_exhaustion = json!({
+            "is_exhausted": fd_exhaustion.is_exhausted,
+            "num_errors": fd_exhaustion.num_errors,
+            "secs_since_last_error": fd_exhaustion.secs_since_last_error,
+            "accept_backoff_ms": fd_exhaustion.accept_backoff_ms,
+        });
+
+        Ok(ErasedJson::pretty(json!({
+            "mode": "normal",
+            "node_type": router.node_type(),
+            "address": router.address(),
+            "account": account,
+            "health": health,
+            "num_connected_peers": num_connected_peers,
+            "is_block_synced": routing.is_block_synced(),
+            "is_storage_corrupted": routing.is_storage_corrupted(),
+            "experiments": experiments,
+            "duplicate_identity": duplicate_identity,
+            "relay_gate": relay_gate,
+            "mempool_pressure": mempool_pressure,
+            "fd_exhaustion": fd_exhaustion,
+            "log_file": rest.config.logging.log_file,
+        })))
+    }
+
+    // GET /<network>/node/bootstrap
+    pub(crate) async fn get_node_bootstrap(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
+        let router = rest.routing()?.router();
+        let num_connected_peers = router.number_of_connected_peers();
+        // Summarize the connection attempts to the bootstrap and trusted peers.
+        // Note: This is only served to administrators, as it reveals the addresses of the trusted peers.
+        let bootstrap_attempts: Vec<_> = router
+            .bootstrap_attempts()
+            .into_iter()
+            .map(|(peer_ip, attempts)| {
+                json!({
+                    "peer_ip": peer_ip,
+                    "num_attempts": attempts.num_attempts(),
+                    "last_failure": attempts.last_failure().map(|failure| failure.to_string()),
+                    "likely_cause": attempts.last_failure().map(|failure| failure.likely_cause()),
+                })
+            })
+            .collect();
+
+        Ok(ErasedJson::pretty(json!({
+            "num_connected_peers": num_connected_peers,
+            "bootstrap_attempts": bootstrap_attempts,
+            "diagnostic": (num_connected_peers == 0).then(|| router.bootstrap_diagnostic()),
+        })))
+    }
+
+    // GET /<network>/node/sync
+    pub(crate) async fn get_sync_progress(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
+        // Note: In safe mode, the node does not sync.
+        let routing = rest.routing()?;
+        // Note: A validator syncs through its BFT, so it reports the sync status of its BFT, as its progress.
+        let is_synced = match &rest.consensus {
+            Some(consensus) => consensus.bft().is_synced(),
+            None => routing.is_block_synced(),
+        };
+        Ok(ErasedJson::pretty(SyncStatus::new(
+            rest.ledger.latest_height(),
+            routing.sync_progress(),
+            is_synced,
+            OffsetDateTime::now_utc().unix_timestamp(),
+        )))
+    }
+
+    // GET /<network>/node/locators
+    pub(crate) async fn get_block_locators(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
+        Ok(ErasedJson::pretty(rest.routing()?.block_locators()?))
+    }
+
+    // POST /<network>/node/locators/compare
+    pub(crate) async fn compare_block_locators(
+        State(rest): State<Self>,
+        Json(locators): Json<BlockLocators<N>>,
+    ) -> Result<ErasedJson, RestError> {
+        // Ensure the given block locators are well-formed.
+        locators
+            .ensure_is_valid()
+            .map_err(|error| RestError::BadRequest(format!("Invalid block locators - {error}")))?;
+        // Compare the block locators of this node to the given block locators.
+        Ok(ErasedJson::pretty(rest.routing()?.block_locators()?.compare(&locators)))
     }
 
     // GET /<network>/node/address
-    pub(crate) async fn get_node_address(State(rest): State<Self>) -> ErasedJson {
-        ErasedJson::pretty(rest.routing.router().address())
+    pub(crate) async fn get_node_address(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
+        Ok(ErasedJson::pretty(rest.routing()?.router().address()))
+    }
+
+    // GET /<network>/node/config
+    pub(crate) async fn get_node_config(State(rest): State<Self>) -> ErasedJson {
+        let mut config = rest.config.clone();
+        // Report the current trusted peers, as they may be updated at runtime.
+        if let (Some(router_config), Some(routing)) = (config.router.as_mut(), rest.routing.as_ref()) {
+            router_config.trusted_peers = routing.router().trusted_peers().into_iter().collect();
+            router_config.trusted_peers.sort();
+        }
+        ErasedJson::pretty(&config)
+    }
+
+    // POST /<network>/node/trusted_peers
+    pub(crate) async fn update_trusted_peers(
+        State(rest): State<Self>,
+        Json(update): Json<TrustedPeersUpdate>,
+    ) -> Result<ErasedJson, RestError> {
+        let router = rest.routing()?.router();
+        // Ensure the peers to trust are valid, before updating any of them.
+        // Note: The bogon addresses are allowed, as the trusted peers may be on a private network.
+        let is_invalid =
+            |peer_ip: &SocketAddr| router.is_local_ip(peer_ip) || peer_ip.ip().is_unspecified() || peer_ip.port() == 0;
+        if let Some(peer_ip) = update.insert.iter().find(|peer_ip| is_invalid(peer_ip)) {
+            return Err(RestError::BadRequest(format!("'{peer_ip}' is not a valid peer IP")));
+        }
+        let num_inserted = update.insert.iter().filter(|peer_ip| router.insert_trusted_peer(**peer_ip)).count();
+        let num_removed = update.remove.iter().filter(|peer_ip| router.remove_trusted_peer(peer_ip)).count();
+
+        let mut trusted_peers: Vec<_> = router.trusted_peers().into_iter().collect();
+        trusted_peers.sort();
+        Ok(ErasedJson::pretty(TrustedPeers { trusted_peers, num_inserted, num_removed }))
+    }
+
+    // GET /<network>/node/trace
+    pub(crate) async fn get_trace_scopes(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
+        Ok(ErasedJson::pretty(rest.trace_scopes.active()))
+    }
+
+    // POST /<network>/node/trace
+    pub(crate) async fn enable_trace_scope(
+        State(rest): State<Self>,
+        Json(request): Json<TraceScopeRequest>,
+    ) -> Result<ErasedJson, RestError> {
+        match &request.target {
+            // The peers and the messages are only traced by the router, which is disabled in safe mode.
+            TraceTarget::Peer(..) | TraceTarget::Event(..) => {
+                rest.routing()?;
+            }
+            TraceTarget::Route(prefix) if !prefix.starts_with('/') => {
+                return Err(RestError::BadRequest(format!("The route prefix '{prefix}' must start with '/'")));
+            }
+            TraceTarget::Route(..) => (),
+        }
+        let duration = Duration::from_secs(request.duration_in_secs);
+        Ok(ErasedJson::pretty(rest.trace_scopes.insert(request.target, request.level, duration)?))
+    }
+
+    // POST /<network>/node/reload
+    pub(crate) async fn reload_config(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
+        let reloads = rest.routing()?.router().reloads().clone();
+        // Reload on a blocking thread, as the items read their configuration from the disk.
+        let summary = tokio::task::spawn_blocking(move || reloads.reload_all())
+            .await
+            .map_err(|err| RestError::InternalServerError(format!("Unable to reload the configuration - {err}")))?;
+        Ok(ErasedJson::pretty(summary))
+    }
+
+    // POST /<network>/node/sync/from
+    pub(crate) async fn sync_from_peer(
+        State(rest): State<Self>,
+        Json(request): Json<SyncFromPeer>,
+    ) -> Result<ErasedJson, RestError> {
+        // Force an immediate sync from the given peer.
+        let summary = rest.routing()?.sync_from_peer(request.peer_ip).await?;
+
+        Ok(ErasedJson::pretty(json!({
+            "peer_ip": summary.peer_ip(),
+            "our_height": summary.our_height(),
+            "peer_height": summary.peer_height(),
+            "num_blocks_requested": summary.num_blocks_requested(),
+        })))
+    }
+
+    // GET /<network>/node/broadcast-journal?since={timestamp}
+    pub(crate) async fn get_broadcast_journal(
+        State(rest): State<Self>,
+        Query(query): Query<JournalQuery>,
+    ) -> Result<ErasedJson, RestError> {
+        let Some(journal) = rest.journal else {
+            return Err(RestError::Disabled("The broadcast journal is not enabled".to_string()));
+        };
+        // Read the journal in a blocking task, as it may span multiple files.
+        let journal_ = journal.clone();
+        let entries = tokio::task::spawn_blocking(move || journal_.entries_since(query.since.unwrap_or_default()))
+            .await
+            .map_err(|err| RestError::InternalServerError(format!("Unable to read the broadcast journal - {err}")))??;
+
+        Ok(ErasedJson::pretty(json!({
+            "num_dropped": journal.num_dropped(),
+            "entries": entries,
+        })))
+    }
+
+    // GET /<network>/node/audit
+    pub(crate) async fn get_audit_log(State(rest): State<Self>) -> ErasedJson {
+        ErasedJson::pretty(json!({
+            "path": rest.audit_log.path(),
+            "num_dropped": rest.audit_log.num_dropped(),
+            "records": rest.audit_log.records(),
+        }))
+    }
+
+    // GET /<network>/node/tasks
+    pub(crate) async fn get_node_tasks(State(rest): State<Self>) -> ErasedJson {
+        // Collect the supervised tasks of the node components (unless in safe mode), and of the server.
+        let mut tasks: Vec<_> = rest
+            .routing
+            .iter()
+            .flat_map(|routing| routing.supervisors())
+            .flat_map(|supervisor| supervisor.tasks())
+            .collect();
+        tasks.extend(rest.supervisor.tasks());
+        ErasedJson::pretty(tasks)
+    }
+
+    // GET /<network>/node/peers/export
+    pub(crate) async fn get_peer_export(State(rest): State<Self>) -> Result<ErasedJson, RestError> {
+        Ok(ErasedJson::pretty(rest.routing()?.router().export_peers()))
+    }
+
+    // POST /<network>/node/peers/import
+    pub(crate) async fn import_peers(
+        State(rest): State<Self>,
+        Json(export): Json<PeerExport>,
+    ) -> Result<ErasedJson, RestError> {
+        Ok(ErasedJson::pretty(rest.routing()?.router().import_peers(&export)))
+    }
+
+    // GET /<network>/deployment/{transactionID}/status
+    pub(crate) async fn get_deployment_status(
+        State(rest): State<Self>,
+        Param(TxId(tx_id)): Param<TxId<N>>,
+    ) -> Result<ErasedJson, RestError> {
+        // Load the status on a blocking thread, as the block of the deployment is read from storage.
+        let ledger = rest.ledger.clone();
+        match tokio::task::spawn_blocking(move || DeploymentStatus::load(&ledger, &tx_id)).await {
+            Ok(status) => Ok(ErasedJson::pretty(status?)),
+            Err(err) => {
+                Err(RestError::InternalServerError(format!("Failed to get the status of deployment '{tx_id}' - {err}")))
+            }
+        }
+    }
+
+    // GET /<network>/deployments/rejected/recent?limit={limit}
+    pub(crate) async fn get_rejected_deployments_recent(
+        State(rest): State<Self>,
+        Query(query): Query<RejectedDeploymentsQuery>,
+    ) -> (Mutability, ErasedJson) {
+        // Return the most recent rejected deployments, newest first.
+        let limit = query.limit.unwrap_or(usize::MAX);
+        (Mutability::Latest, ErasedJson::pretty(rest.rejected_deployments.latest(limit)))
     }
 
     // GET /<network>/find/blockHash/{transactionID}
     pub(crate) async fn find_block_hash(
         State(rest): State<Self>,
-        Path(tx_id): Path<N::TransactionID>,
+        Param(TxId(tx_id)): Param<TxId<N>>,
     ) -> Result<ErasedJson, RestError> {
         Ok(ErasedJson::pretty(rest.ledger.find_block_hash(&tx_id)?))
     }
@@ -331,15 +1087,26 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
     // GET /<network>/find/blockHeight/{stateRoot}
     pub(crate) async fn find_block_height_from_state_root(
         State(rest): State<Self>,
-        Path(state_root): Path<N::StateRoot>,
+        Param(StateRoot(state_root)): Param<StateRoot<N>>,
     ) -> Result<ErasedJson, RestError> {
         Ok(ErasedJson::pretty(rest.ledger.find_block_height_from_state_root(state_root)?))
     }
 
+    // GET /<network>/find/blockHeight/solution/{solutionID}
+    pub(crate) async fn find_block_height_from_solution_id(
+        State(rest): State<Self>,
+        Param(solution_id): Param<SolutionID<N>>,
+    ) -> Result<ErasedJson, RestError> {
+        match SolutionInclusion::find(&rest.ledger, &solution_id)? {
+            Some(inclusion) => Ok(ErasedJson::pretty(inclusion)),
+            None => Err(RestError::NotFound(format!("Solution '{solution_id}' is not in the ledger"))),
+        }
+    }
+
     // GET /<network>/find/transactionID/deployment/{programID}
     pub(crate) async fn find_transaction_id_from_program_id(
         State(rest): State<Self>,
-        Path(program_id): Path<ProgramID<N>>,
+        Param(program_id): Param<ProgramID<N>>,
     ) -> Result<ErasedJson, RestError> {
         Ok(ErasedJson::pretty(rest.ledger.find_transaction_id_from_program_id(&program_id)?))
     }
@@ -347,7 +1114,7 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
     // GET /<network>/find/transactionID/{transitionID}
     pub(crate) async fn find_transaction_id_from_transition_id(
         State(rest): State<Self>,
-        Path(transition_id): Path<N::TransitionID>,
+        Param(TransitionId(transition_id)): Param<TransitionId<N>>,
     ) -> Result<ErasedJson, RestError> {
         Ok(ErasedJson::pretty(rest.ledger.find_transaction_id_from_transition_id(&transition_id)?))
     }
@@ -355,7 +1122,7 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
     // GET /<network>/find/transitionID/{inputOrOutputID}
     pub(crate) async fn find_transition_id(
         State(rest): State<Self>,
-        Path(input_or_output_id): Path<Field<N>>,
+        Param(Commitment(input_or_output_id)): Param<Commitment<N>>,
     ) -> Result<ErasedJson, RestError> {
         Ok(ErasedJson::pretty(rest.ledger.find_transition_id(&input_or_output_id)?))
     }
@@ -363,49 +1130,88 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
     // POST /<network>/transaction/broadcast
     pub(crate) async fn transaction_broadcast(
         State(rest): State<Self>,
+        ConnectInfo(peer_addr): ConnectInfo<SocketAddr>,
+        headers: HeaderMap,
         Json(tx): Json<Transaction<N>>,
     ) -> Result<ErasedJson, RestError> {
+        // Do not process the transaction if the node is in safe mode, as it cannot be broadcast.
+        let routing = rest.routing()?.clone();
         // Do not process the transaction if the node is too far behind.
-        if rest.routing.num_blocks_behind() > SYNC_LENIENCY {
-            return Err(RestError(format!("Unable to broadcast transaction '{}' (node is syncing)", fmt_id(tx.id()))));
+        if routing.num_blocks_behind() > SYNC_LENIENCY {
+            return Err(RestError::InternalServerError(format!(
+                "Unable to broadcast transaction '{}' (node is syncing)",
+                fmt_id(tx.id())
+            )));
         }
 
         // If the transaction exceeds the transaction size limit, return an error.
         // The buffer is initially roughly sized to hold a `transfer_public`,
         // most transactions will be smaller and this reduces unnecessary allocations.
         // TODO: Should this be a blocking task?
-        let buffer = Vec::with_capacity(3000);
-        if tx.write_le(LimitedWriter::new(buffer, N::MAX_TRANSACTION_SIZE)).is_err() {
-            return Err(RestError("Transaction size exceeds the byte limit".to_string()));
+        let mut buffer = Vec::with_capacity(3000);
+        if tx.write_le(LimitedWriter::new(&mut buffer, N::MAX_TRANSACTION_SIZE)).is_err() {
+            return Err(RestError::BadRequest("Transaction size exceeds the byte limit".to_string()));
         }
 
         // If the consensus module is enabled, add the unconfirmed transaction to the memory pool.
         if let Some(consensus) = rest.consensus {
             // Add the unconfirmed transaction to the memory pool.
-            consensus.add_unconfirmed_transaction(tx.clone()).await?;
+            consensus.add_unconfirmed_transaction(tx.clone(), None).await?;
         }
 
         // Prepare the unconfirmed transaction message.
         let tx_id = tx.id();
-        let message = Message::UnconfirmedTransaction(UnconfirmedTransaction {
-            transaction_id: tx_id,
-            transaction: Data::Object(tx),
-        });
+        let transaction = Data::Object(tx);
+        // If the broadcast journal is enabled, prepare the journal entry.
+        let entry = match rest.journal {
+            Some(_) => Some(JournalEntry {
+                timestamp: OffsetDateTime::now_utc().unix_timestamp(),
+                kind: BroadcastKind::Transaction,
+                id: tx_id.to_string(),
+                checksum: transaction.to_checksum::<N>()?.to_string(),
+                source_ip: peer_addr.ip(),
+                num_bytes: buffer.len(),
+                idempotency_key: idempotency_key(&headers),
+            }),
+            None => None,
+        };
+        let message = Message::UnconfirmedTransaction(UnconfirmedTransaction { transaction_id: tx_id, transaction });
 
         // Broadcast the transaction.
-        rest.routing.propagate(message, &[]);
+        routing.propagate(message, &[]);
+
+        // Record the accepted broadcast.
+        if let (Some(journal), Some(entry)) = (&rest.journal, entry) {
+            journal.record(entry);
+        }
 
         Ok(ErasedJson::pretty(tx_id))
     }
 
+    // POST /<network>/transaction/preflight
+    pub(crate) async fn transaction_preflight(
+        State(rest): State<Self>,
+        Json(tx): Json<Transaction<N>>,
+    ) -> Result<ErasedJson, RestError> {
+        // Run the admission checks of the memory pool on the transaction, without submitting it.
+        match rest.consensus {
+            Some(consensus) => Ok(ErasedJson::pretty(consensus.preflight_unconfirmed_transaction(&tx)?)),
+            None => Err(RestError::InternalServerError("Route isn't available for this node type".to_string())),
+        }
+    }
+
     // POST /<network>/solution/broadcast
     pub(crate) async fn solution_broadcast(
         State(rest): State<Self>,
+        ConnectInfo(peer_addr): ConnectInfo<SocketAddr>,
+        headers: HeaderMap,
         Json(solution): Json<Solution<N>>,
     ) -> Result<ErasedJson, RestError> {
+        // Do not process the solution if the node is in safe mode, as it cannot be broadcast.
+        let routing = rest.routing()?.clone();
         // Do not process the solution if the node is too far behind.
-        if rest.routing.num_blocks_behind() > SYNC_LENIENCY {
-            return Err(RestError(format!(
+        if routing.num_blocks_behind() > SYNC_LENIENCY {
+            return Err(RestError::InternalServerError(format!(
                 "Unable to broadcast solution '{}' (node is syncing)",
                 fmt_id(solution.id())
             )));
@@ -415,7 +1221,9 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
         // Otherwise, verify it prior to broadcasting.
         match rest.consensus {
             // Add the unconfirmed solution to the memory pool.
-            Some(consensus) => consensus.add_unconfirmed_solution(solution).await?,
+            Some(consensus) => {
+                consensus.add_unconfirmed_solution(solution).await?;
+            }
             // Verify the solution.
             None => {
                 // Compute the current epoch hash.
@@ -430,36 +1238,92 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
                 {
                     Ok(Ok(())) => {}
                     Ok(Err(err)) => {
-                        return Err(RestError(format!("Invalid solution '{}' - {err}", fmt_id(solution.id()))));
+                        return Err(RestError::BadRequest(format!(
+                            "Invalid solution '{}' - {err}",
+                            fmt_id(solution.id())
+                        )));
+                    }
+                    Err(err) => {
+                        return Err(RestError::InternalServerError(format!(
+                            "Invalid solution '{}' - {err}",
+                            fmt_id(solution.id())
+                        )));
                     }
-                    Err(err) => return Err(RestError(format!("Invalid solution '{}' - {err}", fmt_id(solution.id())))),
                 }
             }
         }
 
         let solution_id = solution.id();
+        // If the broadcast journal is enabled, prepare the journal entry.
+        let entry = match rest.journal {
+            Some(_) => Some(JournalEntry {
+                timestamp: OffsetDateTime::now_utc().unix_timestamp(),
+                kind: BroadcastKind::Solution,
+                id: solution_id.to_string(),
+                checksum: Data::Object(solution.clone()).to_checksum::<N>()?.to_string(),
+                source_ip: peer_addr.ip(),
+                num_bytes: solution.to_bytes_le()?.len(),
+                idempotency_key: idempotency_key(&headers),
+            }),
+            None => None,
+        };
         // Prepare the unconfirmed solution message.
         let message =
             Message::UnconfirmedSolution(UnconfirmedSolution { solution_id, solution: Data::Object(solution) });
 
         // Broadcast the unconfirmed solution message.
-        rest.routing.propagate(message, &[]);
+        routing.propagate(message, &[]);
+
+        // Record the accepted broadcast.
+        if let (Some(journal), Some(entry)) = (&rest.journal, entry) {
+            journal.record(entry);
+        }
 
         Ok(ErasedJson::pretty(solution_id))
     }
 
+    // GET /<network>/docs
+    #[cfg(feature = "swagger-ui")]
+    pub(crate) async fn get_swagger_ui() -> axum::response::Html<String> {
+        axum::response::Html(swagger_ui("openapi.json"))
+    }
+
+    // GET /<network>/docs/{file}
+    #[cfg(feature = "swagger-ui")]
+    pub(crate) async fn get_swagger_asset(
+        axum::extract::Path(file): axum::extract::Path<String>,
+    ) -> Result<impl IntoResponse, RestError> {
+        // Serve the asset from the Swagger UI bundled with the node, rather than from a third-party CDN.
+        let config = Arc::new(utoipa_swagger_ui::Config::from("openapi.json"));
+        match utoipa_swagger_ui::serve(&file, config) {
+            Ok(Some(asset)) => Ok(([(CONTENT_TYPE, asset.content_type)], asset.bytes.into_owned())),
+            Ok(None) => Err(RestError::NotFound(format!("Unknown asset '{file}'"))),
+            Err(error) => Err(RestError::InternalServerError(format!("Failed to serve asset '{file}': {error}"))),
+        }
+    }
+
     // GET /{network}/block/{blockHeight}/history/{mapping}
     #[cfg(feature = "history")]
     pub(crate) async fn get_history(
         State(rest): State<Self>,
-        Path((height, mapping)): Path<(u32, snarkvm::synthesizer::MappingName)>,
+        Params((Height(height), mapping)): Params<(Height, snarkvm::synthesizer::MappingName)>,
     ) -> Result<impl axum::response::IntoResponse, RestError> {
         // Retrieve the history for the given block height and variant.
         let history = snarkvm::synthesizer::History::new(N::ID, rest.ledger.vm().finalize_store().storage_mode());
-        let result = history
-            .load_mapping(height, mapping)
-            .map_err(|_| RestError(format!("Could not load mapping '{mapping}' from block '{height}'")))?;
+        let result = history.load_mapping(height, mapping).map_err(|_| {
+            RestError::InternalServerError(format!("Could not load mapping '{mapping}' from block '{height}'"))
+        })?;
 
         Ok((StatusCode::OK, [(CONTENT_TYPE, "application/json")], result))
     }
 }
+
+/// Returns the idempotency key provided in the request headers, if any.
+fn idempotency_key(headers: &HeaderMap) -> Option<String> {
+    headers.get("Idempotency-Key").and_then(|value| value.to_str().ok()).map(|key| key.to_string())
+}
+
+/// Returns the summaries of the unconfirmed transmissions of the given kind.
+fn memory_pool_summaries<N: Network>(consensus: &Consensus<N>, kind: TransmissionKind) -> Vec<TransmissionSummary> {
+    consensus.memory_pool_summaries().into_iter().filter(|summary| summary.kind == kind).collect()
+}
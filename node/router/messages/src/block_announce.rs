@@ -0,0 +1,120 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+use snarkvm::prelude::{FromBytes, ToBytes, block::Block};
+
+use std::borrow::Cow;
+
+/// A compact announcement of a block that a validator committed, sent to the connected clients
+/// that negotiated `Features::BLOCK_ANNOUNCE`, ahead of the block gossip.
+///
+/// The announcement is a hint for the client to request the block, and is not a trusted header.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct BlockAnnounce<N: Network> {
+    /// The block height.
+    pub height: u32,
+    /// The block hash.
+    pub hash: N::BlockHash,
+    /// The block timestamp.
+    pub timestamp: i64,
+    /// The number of transactions in the block.
+    pub num_transactions: u32,
+}
+
+impl<N: Network> BlockAnnounce<N> {
+    /// Initializes the announcement of the given block.
+    pub fn new(block: &Block<N>) -> Self {
+        Self {
+            height: block.height(),
+            hash: block.hash(),
+            timestamp: block.timestamp(),
+            num_transactions: block.transactions().len() as u32,
+        }
+    }
+}
+
+impl<N: Network> MessageTrait for BlockAnnounce<N> {
+    /// Returns the message name.
+    #[inline]
+    fn name(&self) -> Cow<'static, str> {
+        format!("BlockAnnounce {}", self.height).into()
+    }
+}
+
+impl<N: Network> ToBytes for BlockAnnounce<N> {
+    fn write_le<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        self.height.write_le(&mut writer)?;
+        self.hash.write_le(&mut writer)?;
+        self.timestamp.write_le(&mut writer)?;
+        self.num_transactions.write_le(&mut writer)?;
+        Ok(())
+    }
+}
+
+impl<N: Network> FromBytes for BlockAnnounce<N> {
+    fn read_le<R: io::Read>(mut reader: R) -> io::Result<Self> {
+        let height = u32::read_le(&mut reader)?;
+        let hash = N::BlockHash::read_le(&mut reader)?;
+        let timestamp = i64::read_le(&mut reader)?;
+        let num_transactions = u32::read_le(&mut reader)?;
+        Ok(Self { height, hash, timestamp, num_transactions })
+    }
+}
+
+#[cfg(test)]
+pub mod prop_tests {
+    use crate::{BlockAnnounce, block_response::prop_tests::any_block, puzzle_response::prop_tests::any_epoch_hash};
+    use snarkvm::{
+        prelude::block::Block,
+        utilities::{FromBytes, ToBytes},
+    };
+
+    use bytes::{Buf, BufMut, BytesMut};
+    use proptest::prelude::{BoxedStrategy, ProptestConfig, Strategy, any};
+    use test_strategy::proptest;
+
+    type CurrentNetwork = snarkvm::prelude::MainnetV0;
+
+    pub fn any_block_announce() -> BoxedStrategy<BlockAnnounce<CurrentNetwork>> {
+        (any::<u32>(), any_epoch_hash(), any::<i64>(), any::<u32>())
+            .prop_map(|(height, hash, timestamp, num_transactions)| BlockAnnounce {
+                height,
+                hash,
+                timestamp,
+                num_transactions,
+            })
+            .boxed()
+    }
+
+    #[proptest]
+    fn block_announce_roundtrip(#[strategy(any_block_announce())] original: BlockAnnounce<CurrentNetwork>) {
+        let mut buf = BytesMut::default().writer();
+        BlockAnnounce::write_le(&original, &mut buf).unwrap();
+
+        let deserialized: BlockAnnounce<CurrentNetwork> = BlockAnnounce::read_le(buf.into_inner().reader()).unwrap();
+        assert_eq!(original, deserialized);
+    }
+
+    #[proptest(ProptestConfig { cases : 10, ..ProptestConfig::default() })]
+    fn block_announce_matches_block(#[strategy(any_block())] block: Block<CurrentNetwork>) {
+        let announce = BlockAnnounce::new(&block);
+        assert_eq!(announce.height, block.height());
+        assert_eq!(announce.hash, block.hash());
+        assert_eq!(announce.timestamp, block.timestamp());
+        assert_eq!(announce.num_transactions as usize, block.transactions().len());
+    }
+}
@@ -22,10 +22,12 @@ use std::borrow::Cow;
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct ChallengeRequest<N: Network> {
     pub version: u32,
+    pub network: u16,
     pub listener_port: u16,
     pub node_type: NodeType,
     pub address: Address<N>,
     pub nonce: u64,
+    pub features: Features,
 }
 
 impl<N: Network> MessageTrait for ChallengeRequest<N> {
@@ -39,10 +41,13 @@ impl<N: Network> MessageTrait for ChallengeRequest<N> {
 impl<N: Network> ToBytes for ChallengeRequest<N> {
     fn write_le<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
         self.version.write_le(&mut writer)?;
+        self.network.write_le(&mut writer)?;
         self.listener_port.write_le(&mut writer)?;
         self.node_type.write_le(&mut writer)?;
         self.address.write_le(&mut writer)?;
         self.nonce.write_le(&mut writer)?;
+        // The features are optional on the wire, so that the peers that opt into none stay compatible.
+        self.features.write_optional_le(&mut writer)?;
         Ok(())
     }
 }
@@ -50,24 +55,26 @@ impl<N: Network> ToBytes for ChallengeRequest<N> {
 impl<N: Network> FromBytes for ChallengeRequest<N> {
     fn read_le<R: io::Read>(mut reader: R) -> io::Result<Self> {
         let version = u32::read_le(&mut reader)?;
+        let network = u16::read_le(&mut reader)?;
         let listener_port = u16::read_le(&mut reader)?;
         let node_type = NodeType::read_le(&mut reader)?;
         let address = Address::<N>::read_le(&mut reader)?;
         let nonce = u64::read_le(&mut reader)?;
+        let features = Features::read_optional_le(&mut reader)?;
 
-        Ok(Self { version, listener_port, node_type, address, nonce })
+        Ok(Self { version, network, listener_port, node_type, address, nonce, features })
     }
 }
 
 impl<N: Network> ChallengeRequest<N> {
-    pub fn new(listener_port: u16, node_type: NodeType, address: Address<N>, nonce: u64) -> Self {
-        Self { version: Message::<N>::VERSION, listener_port, node_type, address, nonce }
+    pub fn new(listener_port: u16, node_type: NodeType, address: Address<N>, nonce: u64, features: Features) -> Self {
+        Self { version: Message::<N>::VERSION, network: N::ID, listener_port, node_type, address, nonce, features }
     }
 }
 
 #[cfg(test)]
 pub mod prop_tests {
-    use crate::{ChallengeRequest, NodeType};
+    use crate::{ChallengeRequest, Features, NodeType};
     use snarkvm::{
         console::prelude::{FromBytes, ToBytes},
         prelude::{Address, TestRng, Uniform},
@@ -95,13 +102,18 @@ pub mod prop_tests {
     }
 
     pub fn any_challenge_request() -> BoxedStrategy<ChallengeRequest<CurrentNetwork>> {
-        (any_valid_address(), any::<u64>(), any::<u32>(), any::<u16>(), any_node_type())
-            .prop_map(|(address, nonce, version, listener_port, node_type)| ChallengeRequest {
+        (any_valid_address(), any::<u64>(), any::<u32>(), any::<u16>(), any::<u16>(), any_node_type(), any::<bool>())
+            .prop_map(|(address, nonce, version, network, listener_port, node_type, block_announce)| ChallengeRequest {
                 address,
                 nonce,
                 version,
+                network,
                 listener_port,
                 node_type,
+                features: match block_announce {
+                    true => Features::BLOCK_ANNOUNCE,
+                    false => Features::NONE,
+                },
             })
             .boxed()
     }
@@ -28,6 +28,8 @@ pub struct ChallengeResponse<N: Network> {
     pub restrictions_id: Field<N>,
     pub signature: Data<Signature<N>>,
     pub nonce: u64,
+    /// The maximum frame size the sender is willing to receive, if it states one.
+    pub max_frame_size: Option<u32>,
 }
 
 impl<N: Network> MessageTrait for ChallengeResponse<N> {
@@ -38,33 +40,76 @@ impl<N: Network> MessageTrait for ChallengeResponse<N> {
     }
 }
 
+impl<N: Network> ChallengeResponse<N> {
+    /// Returns the bytes the signer of a challenge response signs, given the versions of the signer and the verifier.
+    ///
+    /// The signature always covers the nonce of the challenge request of the verifier, and the response nonce.
+    /// If both sides are on the `FRESH_HANDSHAKE_VERSION` or later, it also covers the version of the signer,
+    /// and the addresses of the signer and the verifier, so that it cannot be presented in another handshake.
+    /// Note: The previous format is accepted from peers on older versions, until the minimum version is raised.
+    pub fn signed_bytes(
+        signer_version: u32,
+        verifier_version: u32,
+        signer: Address<N>,
+        verifier: Address<N>,
+        request_nonce: u64,
+        response_nonce: u64,
+    ) -> io::Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        if signer_version.min(verifier_version) >= Message::<N>::FRESH_HANDSHAKE_VERSION {
+            signer_version.write_le(&mut bytes)?;
+            request_nonce.write_le(&mut bytes)?;
+            response_nonce.write_le(&mut bytes)?;
+            signer.write_le(&mut bytes)?;
+            verifier.write_le(&mut bytes)?;
+        } else {
+            request_nonce.write_le(&mut bytes)?;
+            response_nonce.write_le(&mut bytes)?;
+        }
+        Ok(bytes)
+    }
+}
+
 impl<N: Network> ToBytes for ChallengeResponse<N> {
     fn write_le<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
         self.genesis_header.write_le(&mut writer)?;
         self.restrictions_id.write_le(&mut writer)?;
         self.signature.write_le(&mut writer)?;
-        self.nonce.write_le(&mut writer)
+        self.nonce.write_le(&mut writer)?;
+        // Note: The maximum frame size is a trailing field, which older peers do not send.
+        if let Some(max_frame_size) = self.max_frame_size {
+            max_frame_size.write_le(&mut writer)?;
+        }
+        Ok(())
     }
 }
 
 impl<N: Network> FromBytes for ChallengeResponse<N> {
     fn read_le<R: io::Read>(mut reader: R) -> io::Result<Self> {
-        Ok(Self {
-            genesis_header: Header::read_le(&mut reader)?,
-            restrictions_id: Field::read_le(&mut reader)?,
-            signature: Data::read_le(&mut reader)?,
-            nonce: u64::read_le(reader)?,
-        })
+        let genesis_header = Header::read_le(&mut reader)?;
+        let restrictions_id = Field::read_le(&mut reader)?;
+        let signature = Data::read_le(&mut reader)?;
+        let nonce = u64::read_le(&mut reader)?;
+        // Read the maximum frame size, if the sender stated one.
+        let mut trailing_bytes = Vec::new();
+        reader.read_to_end(&mut trailing_bytes)?;
+        let max_frame_size = match trailing_bytes.len() {
+            0 => None,
+            4 => Some(u32::read_le(&trailing_bytes[..])?),
+            _ => return Err(error("Invalid maximum frame size in a ChallengeResponse")),
+        };
+
+        Ok(Self { genesis_header, restrictions_id, signature, nonce, max_frame_size })
     }
 }
 
 #[cfg(test)]
 pub mod prop_tests {
-    use crate::ChallengeResponse;
+    use crate::{ChallengeResponse, Message};
     use snarkvm::{
         console::prelude::{FromBytes, ToBytes},
         ledger::{ledger_test_helpers::sample_genesis_block, narwhal::Data},
-        prelude::{Field, PrivateKey, Signature, block::Header},
+        prelude::{Address, Field, PrivateKey, Signature, block::Header},
         utilities::rand::{TestRng, Uniform},
     };
 
@@ -94,12 +139,13 @@ pub mod prop_tests {
     }
 
     pub fn any_challenge_response() -> BoxedStrategy<ChallengeResponse<CurrentNetwork>> {
-        (any_genesis_header(), any_signature(), any::<u64>())
-            .prop_map(|(genesis_header, sig, nonce)| ChallengeResponse {
+        (any_genesis_header(), any_signature(), any::<u64>(), any::<Option<u32>>())
+            .prop_map(|(genesis_header, sig, nonce, max_frame_size)| ChallengeResponse {
                 genesis_header,
                 restrictions_id: any_restrictions_id(),
                 signature: Data::Object(sig),
                 nonce,
+                max_frame_size,
             })
             .boxed()
     }
@@ -113,9 +159,33 @@ pub mod prop_tests {
             ChallengeResponse::read_le(buf.into_inner().reader()).unwrap();
 
         assert_eq!(original.genesis_header, deserialized.genesis_header);
+        assert_eq!(original.max_frame_size, deserialized.max_frame_size);
         assert_eq!(
             original.signature.deserialize_blocking().unwrap(),
             deserialized.signature.deserialize_blocking().unwrap()
         );
     }
+
+    #[test]
+    fn challenge_response_signed_bytes() {
+        let rng = &mut TestRng::default();
+        let signer = Address::<CurrentNetwork>::try_from(PrivateKey::new(rng).unwrap()).unwrap();
+        let verifier = Address::<CurrentNetwork>::try_from(PrivateKey::new(rng).unwrap()).unwrap();
+        let (fresh, legacy) = (Message::<CurrentNetwork>::VERSION, Message::<CurrentNetwork>::MINIMUM_VERSION);
+        let signed_bytes = |signer_version, verifier_version, signer, verifier| {
+            ChallengeResponse::signed_bytes(signer_version, verifier_version, signer, verifier, 1, 2).unwrap()
+        };
+
+        // Ensure the previous format only covers the nonces, if either side is on an older version.
+        let nonces = [1u64.to_le_bytes(), 2u64.to_le_bytes()].concat();
+        assert_eq!(signed_bytes(legacy, fresh, signer, verifier), nonces);
+        assert_eq!(signed_bytes(fresh, legacy, signer, verifier), nonces);
+
+        // Ensure the fresh format covers the version and the addresses of both sides.
+        let bytes = signed_bytes(fresh, fresh, signer, verifier);
+        assert_eq!(&bytes[..4], &fresh.to_le_bytes());
+        assert_eq!(&bytes[4..20], &nonces[..]);
+        assert_ne!(bytes, signed_bytes(fresh, fresh, verifier, signer));
+        assert_ne!(bytes, signed_bytes(fresh + 1, fresh, signer, verifier));
+    }
 }
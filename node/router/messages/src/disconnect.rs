@@ -79,6 +79,7 @@ mod tests {
             DisconnectReason::TooManyPeers,
             DisconnectReason::YouNeedToSyncFirst,
             DisconnectReason::YourPortIsClosed(TestRng::default().gen()),
+            DisconnectReason::NetworkMismatch(TestRng::default().gen()),
         ];
 
         for reason in all_reasons.iter() {
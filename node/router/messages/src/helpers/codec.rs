@@ -13,37 +13,103 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::Message;
+use crate::{Message, NodeType};
 use snarkvm::prelude::{FromBytes, Network, ToBytes};
 
 use ::bytes::{Buf, BufMut, BytesMut};
 use core::marker::PhantomData;
+use std::sync::{
+    Arc,
+    atomic::{AtomicU64, Ordering},
+};
 use tokio_util::codec::{Decoder, Encoder, LengthDelimitedCodec};
 
 /// The maximum size of a message that can be transmitted during the handshake.
 const MAXIMUM_HANDSHAKE_MESSAGE_SIZE: usize = 1024 * 1024; // 1 MiB
 
 /// The maximum size of a message that can be transmitted in the network.
-pub(crate) const MAXIMUM_MESSAGE_SIZE: usize = 128 * 1024 * 1024; // 128 MiB
+/// Note: This is also the frame size limit of the peers that do not negotiate one.
+pub const MAXIMUM_MESSAGE_SIZE: usize = 128 * 1024 * 1024; // 128 MiB
+
+/// The maximum frame size a validator or a prover is willing to receive from a client, by default.
+pub const CLIENT_MAX_FRAME_SIZE: usize = 32 * 1024 * 1024; // 32 MiB
+
+/// The maximum frame size a node is willing to receive from a prover, by default.
+pub const PROVER_MAX_FRAME_SIZE: usize = 4 * 1024 * 1024; // 4 MiB
+
+/// The size of the length prefix of each frame.
+const LENGTH_PREFIX_SIZE: usize = 4;
+
+/// The lowest frame size limit that can be negotiated, so that every non-bulk message fits in a frame.
+pub const MINIMUM_MAX_FRAME_SIZE: usize = MAXIMUM_HANDSHAKE_MESSAGE_SIZE;
+
+/// Returns the maximum frame size a node of the given type is willing to receive from a peer of the given type,
+/// by default.
+///
+/// Note: The clients sync blocks from each other, so the connections between clients keep the global limit.
+pub const fn default_max_frame_size(our_type: NodeType, peer_type: NodeType) -> usize {
+    match (our_type, peer_type) {
+        (NodeType::Client, NodeType::Client) => MAXIMUM_MESSAGE_SIZE,
+        (_, NodeType::Client) => CLIENT_MAX_FRAME_SIZE,
+        (_, NodeType::Prover) => PROVER_MAX_FRAME_SIZE,
+        (_, NodeType::Validator) => MAXIMUM_MESSAGE_SIZE,
+    }
+}
+
+/// Returns the frame size limit of a connection, given the maximum frame size each side is willing to receive.
+///
+/// The limit is the minimum of the two, floored at `MINIMUM_MAX_FRAME_SIZE`.
+/// A peer that does not state its maximum frame size is held to `MAXIMUM_MESSAGE_SIZE`.
+pub fn negotiate_max_frame_size(ours: usize, theirs: Option<u32>) -> usize {
+    match theirs {
+        Some(theirs) => ours.min(theirs as usize).clamp(MINIMUM_MAX_FRAME_SIZE, MAXIMUM_MESSAGE_SIZE),
+        None => MAXIMUM_MESSAGE_SIZE,
+    }
+}
 
 /// The codec used to decode and encode network `Message`s.
 pub struct MessageCodec<N: Network> {
     codec: LengthDelimitedCodec,
+    /// The counter of the bytes of the encoded and decoded frames, if any.
+    byte_counter: Option<Arc<AtomicU64>>,
     _phantom: PhantomData<N>,
 }
 
 impl<N: Network> MessageCodec<N> {
     pub fn handshake() -> Self {
+        Self::with_max_frame_size(MAXIMUM_HANDSHAKE_MESSAGE_SIZE)
+    }
+
+    /// Initializes a codec that rejects the frames larger than the given size, as soon as their length is read.
+    pub fn with_max_frame_size(max_frame_size: usize) -> Self {
         let mut codec = Self::default();
-        codec.codec.set_max_frame_length(MAXIMUM_HANDSHAKE_MESSAGE_SIZE);
+        codec.codec.set_max_frame_length(max_frame_size);
         codec
     }
+
+    /// Adds the size of every encoded or decoded frame, including its length prefix, to the given counter.
+    pub fn with_byte_counter(mut self, byte_counter: Arc<AtomicU64>) -> Self {
+        self.byte_counter = Some(byte_counter);
+        self
+    }
+
+    /// Adds the given frame size to the byte counter, if any.
+    fn count_bytes(&self, num_bytes: usize) {
+        if let Some(byte_counter) = &self.byte_counter {
+            byte_counter.fetch_add(num_bytes as u64, Ordering::Relaxed);
+        }
+    }
 }
 
 impl<N: Network> Default for MessageCodec<N> {
     fn default() -> Self {
         Self {
-            codec: LengthDelimitedCodec::builder().max_frame_length(MAXIMUM_MESSAGE_SIZE).little_endian().new_codec(),
+            codec: LengthDelimitedCodec::builder()
+                .length_field_length(LENGTH_PREFIX_SIZE)
+                .max_frame_length(MAXIMUM_MESSAGE_SIZE)
+                .little_endian()
+                .new_codec(),
+            byte_counter: None,
             _phantom: Default::default(),
         }
     }
@@ -61,7 +127,9 @@ impl<N: Network> Encoder<Message<N>> for MessageCodec<N> {
 
         let serialized_message = dst.split_to(dst.len()).freeze();
 
-        self.codec.encode(serialized_message, dst)
+        self.codec.encode(serialized_message, dst)?;
+        self.count_bytes(dst.len());
+        Ok(())
     }
 }
 
@@ -75,6 +143,7 @@ impl<N: Network> Decoder for MessageCodec<N> {
             Some(bytes) => bytes,
             None => return Ok(None),
         };
+        self.count_bytes(LENGTH_PREFIX_SIZE + bytes.len());
 
         Self::Item::check_size(&bytes)?;
 
@@ -112,6 +181,54 @@ mod tests {
         assert!(codec.decode(&mut bytes).is_ok());
     }
 
+    #[test]
+    fn test_negotiate_max_frame_size() {
+        // Ensure the limit is the minimum of what both sides are willing to receive.
+        let prover_max_frame_size = PROVER_MAX_FRAME_SIZE as u32;
+        assert_eq!(negotiate_max_frame_size(CLIENT_MAX_FRAME_SIZE, Some(prover_max_frame_size)), PROVER_MAX_FRAME_SIZE);
+        assert_eq!(negotiate_max_frame_size(PROVER_MAX_FRAME_SIZE, Some(u32::MAX)), PROVER_MAX_FRAME_SIZE);
+        // Ensure the limit is floored.
+        assert_eq!(negotiate_max_frame_size(CLIENT_MAX_FRAME_SIZE, Some(0)), MINIMUM_MAX_FRAME_SIZE);
+        // Ensure a peer that does not state a limit is held to the global limit.
+        assert_eq!(negotiate_max_frame_size(PROVER_MAX_FRAME_SIZE, None), MAXIMUM_MESSAGE_SIZE);
+
+        // Ensure the connections between clients keep the global limit, while the other nodes cap the clients.
+        let client_max_frame_size = default_max_frame_size(NodeType::Client, NodeType::Client);
+        assert_eq!(
+            negotiate_max_frame_size(client_max_frame_size, Some(client_max_frame_size as u32)),
+            MAXIMUM_MESSAGE_SIZE
+        );
+        assert_eq!(default_max_frame_size(NodeType::Validator, NodeType::Client), CLIENT_MAX_FRAME_SIZE);
+        assert_eq!(default_max_frame_size(NodeType::Client, NodeType::Prover), PROVER_MAX_FRAME_SIZE);
+    }
+
+    #[test]
+    fn test_byte_counter() {
+        let (sent, received) = (Arc::new(AtomicU64::new(0)), Arc::new(AtomicU64::new(0)));
+        let mut encoder = MessageCodec::<CurrentNetwork>::default().with_byte_counter(sent.clone());
+        let mut decoder = MessageCodec::<CurrentNetwork>::default().with_byte_counter(received.clone());
+
+        // Ensure both sides count the frames, including their length prefixes.
+        let mut bytes = BytesMut::new();
+        encoder.encode(Message::PeerRequest(crate::PeerRequest), &mut bytes).unwrap();
+        let frame_size = bytes.len() as u64;
+        assert!(decoder.decode(&mut bytes).unwrap().is_some());
+        assert_eq!(sent.load(Ordering::Relaxed), frame_size);
+        assert_eq!(received.load(Ordering::Relaxed), frame_size);
+        // Ensure an incomplete frame is not counted.
+        let mut bytes = BytesMut::from(&8u32.to_le_bytes()[..]);
+        assert!(decoder.decode(&mut bytes).unwrap().is_none());
+        assert_eq!(received.load(Ordering::Relaxed), frame_size);
+    }
+
+    #[test]
+    fn test_oversized_frame() {
+        let mut codec = MessageCodec::<CurrentNetwork>::with_max_frame_size(MINIMUM_MAX_FRAME_SIZE);
+        // Ensure a frame above the limit is rejected as soon as its length is read, without its payload.
+        let mut bytes = BytesMut::from(&((MINIMUM_MAX_FRAME_SIZE + 1) as u32).to_le_bytes()[..]);
+        assert!(matches!(codec.decode(&mut bytes), Err(err) if err.kind() == std::io::ErrorKind::InvalidData));
+    }
+
     #[proptest(ProptestConfig { cases : 10, ..ProptestConfig::default() })]
     fn overly_large_unconfirmed_transaction(
         #[strategy(any_large_unconfirmed_transaction())] tx: UnconfirmedTransaction<CurrentNetwork>,
@@ -50,6 +50,8 @@ pub enum DisconnectReason {
     YouNeedToSyncFirst,
     /// The peer's listening port is closed.
     YourPortIsClosed(u16),
+    /// The peer is on another network than the node, whose network ID is given.
+    NetworkMismatch(u16),
 }
 
 impl ToBytes for DisconnectReason {
@@ -73,6 +75,10 @@ impl ToBytes for DisconnectReason {
                 14u8.write_le(&mut writer)?;
                 port.write_le(writer)
             }
+            Self::NetworkMismatch(network) => {
+                15u8.write_le(&mut writer)?;
+                network.write_le(writer)
+            }
         }
     }
 }
@@ -98,6 +104,10 @@ impl FromBytes for DisconnectReason {
                 let port = u16::read_le(reader)?;
                 Ok(Self::YourPortIsClosed(port))
             }
+            15 => {
+                let network = u16::read_le(reader)?;
+                Ok(Self::NetworkMismatch(network))
+            }
             _ => Err(error("Invalid disconnect reason")),
         }
     }
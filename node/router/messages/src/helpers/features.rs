@@ -0,0 +1,115 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{FromBytes, ToBytes};
+
+use std::io::{self, Read};
+
+/// The set of optional protocol features a node supports, as advertised in its `ChallengeRequest`.
+///
+/// A feature is only used on a connection if both peers advertised it. Unknown features are ignored,
+/// so that new features can be introduced without breaking the handshake.
+///
+/// The set is optional on the wire: it is omitted when empty, and read as empty when absent,
+/// so that the nodes that do not opt into any feature keep the same handshake as the older nodes.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Features(u32);
+
+impl Features {
+    /// The validator announces the blocks it committed to its connected clients, ahead of the block gossip.
+    pub const BLOCK_ANNOUNCE: Self = Self(1 << 0);
+    /// The empty set of features.
+    pub const NONE: Self = Self(0);
+    /// The full nodes acknowledge the outcome of the solutions the prover submits to them.
+    pub const SOLUTION_ACK: Self = Self(1 << 1);
+
+    /// Returns the set with the given features added.
+    pub const fn with(self, features: Self) -> Self {
+        Self(self.0 | features.0)
+    }
+
+    /// Returns `true` if the set contains all of the given features.
+    pub const fn contains(self, features: Self) -> bool {
+        self.0 & features.0 == features.0
+    }
+
+    /// Writes the set of features, unless it is empty.
+    pub fn write_optional_le<W: io::Write>(&self, writer: W) -> io::Result<()> {
+        match *self == Self::NONE {
+            true => Ok(()),
+            false => self.write_le(writer),
+        }
+    }
+
+    /// Reads the set of features, if the reader is not exhausted, and returns the empty set otherwise.
+    pub fn read_optional_le<R: io::Read>(reader: R) -> io::Result<Self> {
+        let mut bytes = Vec::with_capacity(4);
+        reader.take(4).read_to_end(&mut bytes)?;
+        match bytes.len() {
+            0 => Ok(Self::NONE),
+            4 => Self::read_le(&bytes[..]),
+            _ => Err(io::Error::new(io::ErrorKind::InvalidData, "Truncated set of features")),
+        }
+    }
+}
+
+impl ToBytes for Features {
+    fn write_le<W: io::Write>(&self, writer: W) -> io::Result<()> {
+        self.0.write_le(writer)
+    }
+}
+
+impl FromBytes for Features {
+    fn read_le<R: io::Read>(reader: R) -> io::Result<Self> {
+        Ok(Self(u32::read_le(reader)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_features() {
+        assert!(!Features::NONE.contains(Features::BLOCK_ANNOUNCE));
+        assert!(Features::NONE.with(Features::BLOCK_ANNOUNCE).contains(Features::BLOCK_ANNOUNCE));
+        assert!(Features::BLOCK_ANNOUNCE.contains(Features::NONE));
+        let both = Features::BLOCK_ANNOUNCE.with(Features::SOLUTION_ACK);
+        assert!(both.contains(Features::SOLUTION_ACK) && both.contains(Features::BLOCK_ANNOUNCE));
+        assert!(!Features::SOLUTION_ACK.contains(Features::BLOCK_ANNOUNCE));
+
+        // Ensure the unknown features survive a roundtrip, and do not imply the known ones.
+        let unknown = Features::from_bytes_le(&(1u32 << 31).to_le_bytes()).unwrap();
+        assert!(!unknown.contains(Features::BLOCK_ANNOUNCE));
+        assert!(!unknown.contains(Features::SOLUTION_ACK));
+        assert_eq!(Features::from_bytes_le(&unknown.to_bytes_le().unwrap()).unwrap(), unknown);
+    }
+
+    #[test]
+    fn test_optional_features() {
+        // Ensure the empty set is omitted, and read back from an exhausted reader.
+        let mut bytes = vec![];
+        Features::NONE.write_optional_le(&mut bytes).unwrap();
+        assert!(bytes.is_empty());
+        assert_eq!(Features::read_optional_le(&bytes[..]).unwrap(), Features::NONE);
+
+        // Ensure a non-empty set survives a roundtrip.
+        Features::SOLUTION_ACK.write_optional_le(&mut bytes).unwrap();
+        assert_eq!(Features::read_optional_le(&bytes[..]).unwrap(), Features::SOLUTION_ACK);
+
+        // Ensure a truncated set is rejected.
+        assert!(Features::read_optional_le(&bytes[..2]).is_err());
+    }
+}
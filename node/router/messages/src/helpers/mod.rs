@@ -14,10 +14,21 @@
 // limitations under the License.
 
 mod codec;
-pub use codec::MessageCodec;
+pub use codec::{
+    CLIENT_MAX_FRAME_SIZE,
+    MAXIMUM_MESSAGE_SIZE,
+    MINIMUM_MAX_FRAME_SIZE,
+    MessageCodec,
+    PROVER_MAX_FRAME_SIZE,
+    default_max_frame_size,
+    negotiate_max_frame_size,
+};
 
 mod disconnect;
 pub use disconnect::DisconnectReason;
 
+mod features;
+pub use features::*;
+
 mod node_type;
 pub use node_type::*;
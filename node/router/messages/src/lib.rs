@@ -21,6 +21,9 @@ extern crate tracing;
 pub mod helpers;
 pub use helpers::*;
 
+mod block_announce;
+pub use block_announce::BlockAnnounce;
+
 mod block_request;
 pub use block_request::BlockRequest;
 
@@ -54,6 +57,9 @@ pub use puzzle_request::PuzzleRequest;
 mod puzzle_response;
 pub use puzzle_response::PuzzleResponse;
 
+mod solution_ack;
+pub use solution_ack::{SolutionAck, SolutionStatus};
+
 mod unconfirmed_solution;
 pub use unconfirmed_solution::UnconfirmedSolution;
 
@@ -102,6 +108,8 @@ pub enum Message<N: Network> {
     PuzzleResponse(PuzzleResponse<N>),
     UnconfirmedSolution(UnconfirmedSolution<N>),
     UnconfirmedTransaction(UnconfirmedTransaction<N>),
+    BlockAnnounce(BlockAnnounce<N>),
+    SolutionAck(SolutionAck<N>),
 }
 
 impl<N: Network> From<DisconnectReason> for Message<N> {
@@ -111,8 +119,12 @@ impl<N: Network> From<DisconnectReason> for Message<N> {
 }
 
 impl<N: Network> Message<N> {
-    /// The version of the network protocol; it can be incremented in order to force users to update.
-    pub const VERSION: u32 = 17;
+    /// The version of the network protocol from which challenge responses sign the version and the addresses.
+    pub const FRESH_HANDSHAKE_VERSION: u32 = 19;
+    /// The minimum version of the network protocol accepted from peers; it can be incremented to force users to update.
+    pub const MINIMUM_VERSION: u32 = 18;
+    /// The version of the network protocol.
+    pub const VERSION: u32 = 19;
 
     /// Returns the message name.
     #[inline]
@@ -131,6 +143,8 @@ impl<N: Network> Message<N> {
             Self::PuzzleResponse(message) => message.name(),
             Self::UnconfirmedSolution(message) => message.name(),
             Self::UnconfirmedTransaction(message) => message.name(),
+            Self::BlockAnnounce(message) => message.name(),
+            Self::SolutionAck(message) => message.name(),
         }
     }
 
@@ -151,6 +165,8 @@ impl<N: Network> Message<N> {
             Self::PuzzleResponse(..) => 10,
             Self::UnconfirmedSolution(..) => 11,
             Self::UnconfirmedTransaction(..) => 12,
+            Self::BlockAnnounce(..) => 13,
+            Self::SolutionAck(..) => 14,
         }
     }
 
@@ -195,6 +211,8 @@ impl<N: Network> ToBytes for Message<N> {
             Self::PuzzleResponse(message) => message.write_le(writer),
             Self::UnconfirmedSolution(message) => message.write_le(writer),
             Self::UnconfirmedTransaction(message) => message.write_le(writer),
+            Self::BlockAnnounce(message) => message.write_le(writer),
+            Self::SolutionAck(message) => message.write_le(writer),
         }
     }
 }
@@ -221,7 +239,9 @@ impl<N: Network> FromBytes for Message<N> {
             10 => Self::PuzzleResponse(PuzzleResponse::read_le(&mut reader)?),
             11 => Self::UnconfirmedSolution(UnconfirmedSolution::read_le(&mut reader)?),
             12 => Self::UnconfirmedTransaction(UnconfirmedTransaction::read_le(&mut reader)?),
-            13.. => return Err(error("Unknown message ID {id}")),
+            13 => Self::BlockAnnounce(BlockAnnounce::read_le(&mut reader)?),
+            14 => Self::SolutionAck(SolutionAck::read_le(&mut reader)?),
+            15.. => return Err(error("Unknown message ID {id}")),
         };
 
         // Ensure that there are no "dangling" bytes.
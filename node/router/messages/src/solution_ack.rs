@@ -0,0 +1,168 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+use snarkvm::prelude::{FromBytes, ToBytes};
+
+use std::borrow::Cow;
+
+/// The outcome of an unconfirmed solution that a node received from the prover that submitted it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SolutionStatus {
+    /// The solution was accepted, and relayed or added to the memory pool.
+    Accepted,
+    /// The solution was already seen, or already exists in the memory pool or the ledger.
+    Duplicate,
+    /// The solution does not meet the proof target.
+    BelowTarget,
+    /// The solution is for another epoch than the latest one.
+    StaleEpoch,
+    /// The solution was dropped, as the memory pool is not admitting solutions at the moment.
+    QueueFull,
+    /// The solution was dropped, as the node is syncing.
+    Syncing,
+    /// The solution was rejected for another reason.
+    Rejected,
+}
+
+impl SolutionStatus {
+    /// The list of all solution statuses.
+    pub const ALL: [Self; 7] = [
+        Self::Accepted,
+        Self::Duplicate,
+        Self::BelowTarget,
+        Self::StaleEpoch,
+        Self::QueueFull,
+        Self::Syncing,
+        Self::Rejected,
+    ];
+
+    /// Returns `true` if the solution was accepted.
+    pub const fn is_accepted(&self) -> bool {
+        matches!(self, Self::Accepted)
+    }
+}
+
+impl fmt::Display for SolutionStatus {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Accepted => write!(f, "accepted"),
+            Self::Duplicate => write!(f, "duplicate"),
+            Self::BelowTarget => write!(f, "below target"),
+            Self::StaleEpoch => write!(f, "stale epoch"),
+            Self::QueueFull => write!(f, "queue full"),
+            Self::Syncing => write!(f, "syncing"),
+            Self::Rejected => write!(f, "rejected"),
+        }
+    }
+}
+
+impl ToBytes for SolutionStatus {
+    fn write_le<W: io::Write>(&self, writer: W) -> io::Result<()> {
+        match self {
+            Self::Accepted => 0u8.write_le(writer),
+            Self::Duplicate => 1u8.write_le(writer),
+            Self::BelowTarget => 2u8.write_le(writer),
+            Self::StaleEpoch => 3u8.write_le(writer),
+            Self::QueueFull => 4u8.write_le(writer),
+            Self::Syncing => 5u8.write_le(writer),
+            Self::Rejected => 6u8.write_le(writer),
+        }
+    }
+}
+
+impl FromBytes for SolutionStatus {
+    fn read_le<R: io::Read>(reader: R) -> io::Result<Self> {
+        match u8::read_le(reader)? {
+            0 => Ok(Self::Accepted),
+            1 => Ok(Self::Duplicate),
+            2 => Ok(Self::BelowTarget),
+            3 => Ok(Self::StaleEpoch),
+            4 => Ok(Self::QueueFull),
+            5 => Ok(Self::Syncing),
+            6 => Ok(Self::Rejected),
+            _ => Err(error("Invalid solution status")),
+        }
+    }
+}
+
+/// The acknowledgment of an unconfirmed solution, sent back to the prover that submitted it,
+/// if the prover negotiated `Features::SOLUTION_ACK`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SolutionAck<N: Network> {
+    /// The ID of the acknowledged solution.
+    pub solution_id: SolutionID<N>,
+    /// The outcome of the solution.
+    pub status: SolutionStatus,
+}
+
+impl<N: Network> MessageTrait for SolutionAck<N> {
+    /// Returns the message name.
+    #[inline]
+    fn name(&self) -> Cow<'static, str> {
+        format!("SolutionAck ({})", self.status).into()
+    }
+}
+
+impl<N: Network> ToBytes for SolutionAck<N> {
+    fn write_le<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        self.solution_id.write_le(&mut writer)?;
+        self.status.write_le(&mut writer)
+    }
+}
+
+impl<N: Network> FromBytes for SolutionAck<N> {
+    fn read_le<R: io::Read>(mut reader: R) -> io::Result<Self> {
+        let solution_id = SolutionID::read_le(&mut reader)?;
+        let status = SolutionStatus::read_le(&mut reader)?;
+        Ok(Self { solution_id, status })
+    }
+}
+
+#[cfg(test)]
+pub mod prop_tests {
+    use crate::{SolutionAck, SolutionStatus, unconfirmed_solution::prop_tests::any_solution_id};
+    use snarkvm::utilities::{FromBytes, ToBytes};
+
+    use bytes::{Buf, BufMut, BytesMut};
+    use proptest::{
+        prelude::{BoxedStrategy, Strategy},
+        sample::select,
+    };
+    use test_strategy::proptest;
+
+    type CurrentNetwork = snarkvm::prelude::MainnetV0;
+
+    pub fn any_solution_ack() -> BoxedStrategy<SolutionAck<CurrentNetwork>> {
+        (any_solution_id(), select(SolutionStatus::ALL.to_vec()))
+            .prop_map(|(solution_id, status)| SolutionAck { solution_id, status })
+            .boxed()
+    }
+
+    #[proptest]
+    fn solution_ack_roundtrip(#[strategy(any_solution_ack())] original: SolutionAck<CurrentNetwork>) {
+        let mut buf = BytesMut::default().writer();
+        SolutionAck::write_le(&original, &mut buf).unwrap();
+
+        let deserialized: SolutionAck<CurrentNetwork> = SolutionAck::read_le(buf.into_inner().reader()).unwrap();
+        assert_eq!(original, deserialized);
+    }
+
+    #[test]
+    fn solution_status_rejects_unknown() {
+        assert!(SolutionStatus::read_le(&[SolutionStatus::ALL.len() as u8][..]).is_err());
+    }
+}
@@ -14,10 +14,21 @@
 // limitations under the License.
 
 use crate::{
-    NodeType,
+    ConnectionFailure,
     Peer,
+    PeerEvent,
+    RestrictionCause,
     Router,
-    messages::{ChallengeRequest, ChallengeResponse, DisconnectReason, Message, MessageCodec, MessageTrait},
+    messages::{
+        ChallengeRequest,
+        ChallengeResponse,
+        DisconnectReason,
+        Message,
+        MessageCodec,
+        MessageTrait,
+        default_max_frame_size,
+        negotiate_max_frame_size,
+    },
 };
 use snarkos_node_tcp::{ConnectionSide, P2P, Tcp};
 use snarkvm::{
@@ -28,7 +39,12 @@ use snarkvm::{
 use anyhow::{Result, bail};
 use futures::SinkExt;
 use rand::{Rng, rngs::OsRng};
-use std::{io, net::SocketAddr};
+use std::{
+    collections::hash_map::Entry,
+    io,
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
 use tokio::net::TcpStream;
 use tokio_stream::StreamExt;
 use tokio_util::codec::Framed;
@@ -50,6 +66,10 @@ macro_rules! expect_message {
                 trace!("Received '{}' from '{}'", data.name(), $peer_addr);
                 data
             }
+            // Received a disconnect message for being on another network, abort.
+            Some(Message::Disconnect($crate::messages::Disconnect {
+                reason: $crate::messages::DisconnectReason::NetworkMismatch(network),
+            })) => return Err($crate::ConnectionFailure::NetworkMismatch(network).into()),
             // Received a disconnect message, abort.
             Some(Message::Disconnect(reason)) => {
                 return Err(error(format!("'{}' disconnected: {reason:?}", $peer_addr)))
@@ -82,6 +102,9 @@ async fn send<N: Network>(
 }
 
 impl<N: Network> Router<N> {
+    /// The maximum time in milliseconds between sending a challenge request and receiving its challenge response.
+    const CHALLENGE_RESPONSE_DEADLINE_IN_MS: u64 = 2_000; // 2 seconds
+
     /// Executes the handshake protocol.
     pub async fn handshake<'a>(
         &'a self,
@@ -113,9 +136,21 @@ impl<N: Network> Router<N> {
             self.connecting_peers.lock().remove(&ip);
         }
 
-        // If the handshake succeeded, announce it.
-        if let Ok((ref peer_ip, _)) = handshake_result {
-            info!("Connected to '{peer_ip}'");
+        match &handshake_result {
+            // If the handshake succeeded, announce it.
+            Ok((peer_ip, _)) => info!("Connected to '{peer_ip}'"),
+            Err(error) => {
+                let error_kind = ConnectionFailure::from_io_error(error);
+                // If the peer is on another network, report both networks.
+                if let ConnectionFailure::NetworkMismatch(network) = error_kind {
+                    warn!("Dropped '{peer_addr}' (it belongs to network {network}, not network {})", N::ID);
+                }
+                // Announce the failure, with the listener address of the peer if it is known.
+                self.publish_peer_event(PeerEvent::HandshakeFailed {
+                    peer_addr: peer_ip.unwrap_or(peer_addr),
+                    error_kind,
+                });
+            }
         }
 
         handshake_result
@@ -142,9 +177,13 @@ impl<N: Network> Router<N> {
 
         // Sample a random nonce.
         let our_nonce = rng.gen();
+        // Record the nonce, so that the node recognizes the request if it is connecting to itself.
+        self.duplicate_identity().insert_own_nonce(our_nonce);
         // Send a challenge request to the peer.
-        let our_request = ChallengeRequest::new(self.local_ip().port(), self.node_type, self.address(), our_nonce);
+        let our_request =
+            ChallengeRequest::new(self.local_ip().port(), self.node_type, self.address(), our_nonce, self.features);
         send(&mut framed, peer_addr, Message::ChallengeRequest(our_request)).await?;
+        let request_sent_at = Instant::now();
 
         /* Step 2: Receive the peer's challenge response followed by the challenge request. */
 
@@ -153,46 +192,70 @@ impl<N: Network> Router<N> {
         // Listen for the challenge request message.
         let peer_request = expect_message!(Message::ChallengeRequest, framed, peer_addr);
 
+        // Ensure the peer is on the same network, before verifying its challenge response.
+        if let Some(reason) = self.verify_network(&peer_request) {
+            send(&mut framed, peer_addr, reason.into()).await?;
+            return Err(ConnectionFailure::NetworkMismatch(peer_request.network).into());
+        }
+        // Determine if the peer is on a different network, to report the reason for a failed handshake.
+        let is_genesis_mismatch = peer_response.genesis_header != genesis_header;
+        // Retrieve the maximum frame size the peer is willing to receive.
+        let peer_max_frame_size = peer_response.max_frame_size;
         // Verify the challenge response. If a disconnect reason was returned, send the disconnect message and abort.
         if let Some(reason) = self
             .verify_challenge_response(
                 peer_addr,
-                peer_request.address,
-                peer_request.node_type,
+                &peer_request,
                 peer_response,
                 genesis_header,
                 restrictions_id,
                 our_nonce,
+                request_sent_at.elapsed(),
             )
             .await
         {
             send(&mut framed, peer_addr, reason.into()).await?;
-            return Err(error(format!("Dropped '{peer_addr}' for reason: {reason:?}")));
+            debug!("Dropped '{peer_addr}' for reason: {reason:?}");
+            return match is_genesis_mismatch {
+                true => Err(ConnectionFailure::GenesisMismatch.into()),
+                false => Err(ConnectionFailure::HandshakeRejected.into()),
+            };
         }
         // Verify the challenge request. If a disconnect reason was returned, send the disconnect message and abort.
         if let Some(reason) = self.verify_challenge_request(peer_addr, &peer_request) {
             send(&mut framed, peer_addr, reason.into()).await?;
-            return Err(error(format!("Dropped '{peer_addr}' for reason: {reason:?}")));
+            debug!("Dropped '{peer_addr}' for reason: {reason:?}");
+            return Err(ConnectionFailure::from_disconnect_reason(reason).into());
+        }
+        // Ensure the account of the peer is not restricted.
+        if self.is_restricted_address(&peer_request.address) {
+            return Err(error(format!(
+                "Dropping connection to '{peer_ip}' (restricted account '{}')",
+                peer_request.address
+            )));
         }
         /* Step 3: Send the challenge response. */
 
         let response_nonce: u64 = rng.gen();
-        let data = [peer_request.nonce.to_le_bytes(), response_nonce.to_le_bytes()].concat();
+        let data = self.challenge_response_bytes(&peer_request, response_nonce)?;
         // Sign the counterparty nonce.
         let Ok(our_signature) = self.account.sign_bytes(&data, rng) else {
             return Err(error(format!("Failed to sign the challenge request nonce from '{peer_addr}'")));
         };
-        // Send the challenge response.
+        // Send the challenge response, with the maximum frame size we are willing to receive from the peer.
+        let our_max_frame_size = default_max_frame_size(self.node_type, peer_request.node_type);
         let our_response = ChallengeResponse {
             genesis_header,
             restrictions_id,
             signature: Data::Object(our_signature),
             nonce: response_nonce,
+            max_frame_size: Some(our_max_frame_size as u32),
         };
         send(&mut framed, peer_addr, Message::ChallengeResponse(our_response)).await?;
 
-        // Add the peer to the router.
-        self.insert_connected_peer(Peer::new(peer_ip, &peer_request), peer_addr);
+        // Add the peer to the router, with the negotiated maximum frame size.
+        let max_frame_size = negotiate_max_frame_size(our_max_frame_size, peer_max_frame_size);
+        self.insert_connected_peer(Peer::new(peer_ip, &peer_request, max_frame_size), peer_addr);
 
         Ok((peer_ip, framed))
     }
@@ -214,12 +277,22 @@ impl<N: Network> Router<N> {
         // Listen for the challenge request message.
         let peer_request = expect_message!(Message::ChallengeRequest, framed, peer_addr);
 
+        // Ensure the peer is on the same network, before any other work.
+        if let Some(reason) = self.verify_network(&peer_request) {
+            send(&mut framed, peer_addr, reason.into()).await?;
+            return Err(ConnectionFailure::NetworkMismatch(peer_request.network).into());
+        }
+
         // Obtain the peer's listening address.
         *peer_ip = Some(SocketAddr::new(peer_addr.ip(), peer_request.listener_port));
         let peer_ip = peer_ip.unwrap();
 
+        // Ensure the node is not connecting to itself through another address.
+        if peer_request.address == self.address() && self.duplicate_identity().is_own_nonce(peer_request.nonce) {
+            return Err(error(format!("Dropping connection request from '{peer_ip}' (attempted to self-connect)")));
+        }
         // Knowing the peer's listening address, ensure it is allowed to connect.
-        if let Err(forbidden_message) = self.ensure_peer_is_allowed(peer_ip) {
+        if let Err(forbidden_message) = self.ensure_peer_is_allowed(peer_ip, peer_request.address) {
             return Err(error(format!("{forbidden_message}")));
         }
         // Verify the challenge request. If a disconnect reason was returned, send the disconnect message and abort.
@@ -234,60 +307,85 @@ impl<N: Network> Router<N> {
 
         // Sign the counterparty nonce.
         let response_nonce: u64 = rng.gen();
-        let data = [peer_request.nonce.to_le_bytes(), response_nonce.to_le_bytes()].concat();
+        let data = self.challenge_response_bytes(&peer_request, response_nonce)?;
         let Ok(our_signature) = self.account.sign_bytes(&data, rng) else {
             return Err(error(format!("Failed to sign the challenge request nonce from '{peer_addr}'")));
         };
-        // Send the challenge response.
+        // Send the challenge response, with the maximum frame size we are willing to receive from the peer.
+        let our_max_frame_size = default_max_frame_size(self.node_type, peer_request.node_type);
         let our_response = ChallengeResponse {
             genesis_header,
             restrictions_id,
             signature: Data::Object(our_signature),
             nonce: response_nonce,
+            max_frame_size: Some(our_max_frame_size as u32),
         };
         send(&mut framed, peer_addr, Message::ChallengeResponse(our_response)).await?;
 
         // Sample a random nonce.
         let our_nonce = rng.gen();
         // Send the challenge request.
-        let our_request = ChallengeRequest::new(self.local_ip().port(), self.node_type, self.address(), our_nonce);
+        let our_request =
+            ChallengeRequest::new(self.local_ip().port(), self.node_type, self.address(), our_nonce, self.features);
         send(&mut framed, peer_addr, Message::ChallengeRequest(our_request)).await?;
+        let request_sent_at = Instant::now();
 
         /* Step 3: Receive the challenge response. */
 
         // Listen for the challenge response message.
         let peer_response = expect_message!(Message::ChallengeResponse, framed, peer_addr);
+        // Retrieve the maximum frame size the peer is willing to receive.
+        let peer_max_frame_size = peer_response.max_frame_size;
         // Verify the challenge response. If a disconnect reason was returned, send the disconnect message and abort.
         if let Some(reason) = self
             .verify_challenge_response(
                 peer_addr,
-                peer_request.address,
-                peer_request.node_type,
+                &peer_request,
                 peer_response,
                 genesis_header,
                 restrictions_id,
                 our_nonce,
+                request_sent_at.elapsed(),
             )
             .await
         {
             send(&mut framed, peer_addr, reason.into()).await?;
             return Err(error(format!("Dropped '{peer_addr}' for reason: {reason:?}")));
         }
-        // Add the peer to the router.
-        self.insert_connected_peer(Peer::new(peer_ip, &peer_request), peer_addr);
+        // Add the peer to the router, with the negotiated maximum frame size.
+        let max_frame_size = negotiate_max_frame_size(our_max_frame_size, peer_max_frame_size);
+        self.insert_connected_peer(Peer::new(peer_ip, &peer_request, max_frame_size), peer_addr);
 
         Ok((peer_ip, framed))
     }
 
     /// Ensure the peer is allowed to connect.
-    fn ensure_peer_is_allowed(&self, peer_ip: SocketAddr) -> Result<()> {
+    fn ensure_peer_is_allowed(&self, peer_ip: SocketAddr, peer_address: Address<N>) -> Result<()> {
         // Ensure the peer IP is not this node.
         if self.is_local_ip(&peer_ip) {
             bail!("Dropping connection request from '{peer_ip}' (attempted to self-connect)")
         }
-        // Ensure the node is not already connecting to this peer.
-        if !self.connecting_peers.lock().insert(peer_ip) {
-            bail!("Dropping connection request from '{peer_ip}' (already shaking hands as the initiator)")
+        // Ensure the IP of the peer is not banned, on any port.
+        if self.is_banned(&peer_ip.ip()) {
+            bail!("Dropping connection request from '{peer_ip}' (banned)")
+        }
+        // Ensure the node is not already connecting to this peer. If both nodes are dialing each other
+        // simultaneously, both keep the connection of the preferred initiator, and drop the other one.
+        match self.connecting_peers.lock().entry(peer_ip) {
+            Entry::Vacant(entry) => {
+                entry.insert(ConnectionSide::Initiator);
+            }
+            Entry::Occupied(entry) => match entry.get() {
+                ConnectionSide::Responder if self.is_preferred_initiator(peer_ip, peer_address) => {
+                    debug!("Keeping the simultaneous connection initiated by '{peer_ip}'")
+                }
+                ConnectionSide::Responder => {
+                    bail!("Dropping connection request from '{peer_ip}' (already shaking hands as the initiator)")
+                }
+                ConnectionSide::Initiator => {
+                    bail!("Dropping connection request from '{peer_ip}' (already shaking hands as the responder)")
+                }
+            },
         }
         // Ensure the node is not already connected to this peer.
         if self.is_connected(&peer_ip) {
@@ -301,6 +399,10 @@ impl<N: Network> Router<N> {
         if self.is_restricted(&peer_ip) {
             bail!("Dropping connection request from '{peer_ip}' (restricted)")
         }
+        // Ensure the account of the peer is not restricted.
+        if self.is_restricted_address(&peer_address) {
+            bail!("Dropping connection request from '{peer_ip}' (restricted account '{peer_address}')")
+        }
         // Ensure the peer is not spamming connection attempts.
         if !peer_ip.ip().is_loopback() {
             // Add this connection attempt and retrieve the number of attempts.
@@ -308,13 +410,35 @@ impl<N: Network> Router<N> {
             // Ensure the connecting peer has not surpassed the connection attempt limit.
             if num_attempts > Self::MAXIMUM_CONNECTION_FAILURES {
                 // Restrict the peer.
-                self.insert_restricted_peer(peer_ip);
+                self.insert_restricted_peer(peer_ip, RestrictionCause::TooManyConnectionAttempts(num_attempts));
                 bail!("Dropping connection request from '{peer_ip}' (tried {num_attempts} times)")
             }
         }
         Ok(())
     }
 
+    /// Returns `true` if the connection initiated by the given peer takes precedence over the one initiated
+    /// by this node, when both nodes are dialing each other simultaneously.
+    ///
+    /// Both nodes must independently arrive at the same decision, so the tie is broken on the account addresses,
+    /// which are known to both sides. The listening addresses are only used if the accounts are the same,
+    /// as a node listening on an unspecified address does not know the address its peers see.
+    fn is_preferred_initiator(&self, peer_ip: SocketAddr, peer_address: Address<N>) -> bool {
+        match peer_address == self.address() {
+            true => peer_ip < self.local_ip(),
+            false => peer_address.to_string() < self.address().to_string(),
+        }
+    }
+
+    /// Verifies the network of the given challenge request. Returns a disconnect reason if it is another network.
+    fn verify_network(&self, message: &ChallengeRequest<N>) -> Option<DisconnectReason> {
+        // Note: A peer on an outdated version does not send its network, and is dropped for its version instead.
+        match message.version >= Message::<N>::MINIMUM_VERSION && message.network != N::ID {
+            true => Some(DisconnectReason::NetworkMismatch(N::ID)),
+            false => None,
+        }
+    }
+
     /// Verifies the given challenge request. Returns a disconnect reason if the request is invalid.
     fn verify_challenge_request(
         &self,
@@ -322,30 +446,56 @@ impl<N: Network> Router<N> {
         message: &ChallengeRequest<N>,
     ) -> Option<DisconnectReason> {
         // Retrieve the components of the challenge request.
-        let &ChallengeRequest { version, listener_port: _, node_type: _, address: _, nonce: _ } = message;
+        let &ChallengeRequest { version, network: _, listener_port: _, node_type: _, address: _, nonce, features: _ } =
+            message;
 
         // Ensure the message protocol version is not outdated.
-        if version < Message::<N>::VERSION {
+        if version < Message::<N>::MINIMUM_VERSION {
             warn!("Dropping '{peer_addr}' on version {version} (outdated)");
             return Some(DisconnectReason::OutdatedClientVersion);
         }
+        // Ensure the nonce was not used in a recent handshake, as the request would be a replay.
+        if !self.recent_nonces.insert(nonce) {
+            warn!("Dropping '{peer_addr}' (replayed challenge request nonce)");
+            return Some(DisconnectReason::ProtocolViolation);
+        }
         None
     }
 
-    /// Verifies the given challenge response. Returns a disconnect reason if the response is invalid.
+    /// Returns the bytes to sign in the challenge response to the given challenge request of the peer.
+    fn challenge_response_bytes(&self, peer_request: &ChallengeRequest<N>, response_nonce: u64) -> io::Result<Vec<u8>> {
+        ChallengeResponse::signed_bytes(
+            Message::<N>::VERSION,
+            peer_request.version,
+            self.address(),
+            peer_request.address,
+            peer_request.nonce,
+            response_nonce,
+        )
+    }
+
+    /// Verifies the given challenge response, which was received the given time after sending the challenge request.
+    /// Returns a disconnect reason if the response is invalid.
     #[allow(clippy::too_many_arguments)]
     async fn verify_challenge_response(
         &self,
         peer_addr: SocketAddr,
-        peer_address: Address<N>,
-        peer_node_type: NodeType,
+        peer_request: &ChallengeRequest<N>,
         response: ChallengeResponse<N>,
         expected_genesis_header: Header<N>,
         expected_restrictions_id: Field<N>,
         expected_nonce: u64,
+        elapsed: Duration,
     ) -> Option<DisconnectReason> {
         // Retrieve the components of the challenge response.
-        let ChallengeResponse { genesis_header, restrictions_id, signature, nonce } = response;
+        let ChallengeResponse { genesis_header, restrictions_id, signature, nonce, max_frame_size: _ } = response;
+        let (peer_address, peer_node_type) = (peer_request.address, peer_request.node_type);
+
+        // Ensure the challenge response was received in time.
+        if elapsed > Duration::from_millis(Self::CHALLENGE_RESPONSE_DEADLINE_IN_MS) {
+            warn!("Handshake with '{peer_addr}' failed (late challenge response after {}ms)", elapsed.as_millis());
+            return Some(DisconnectReason::InvalidChallengeResponse);
+        }
 
         // Verify the challenge response, by checking that the block header matches.
         if genesis_header != expected_genesis_header {
@@ -362,11 +512,28 @@ impl<N: Network> Router<N> {
             warn!("Handshake with '{peer_addr}' failed (cannot deserialize the signature)");
             return Some(DisconnectReason::InvalidChallengeResponse);
         };
+        // Construct the bytes the peer signed, in the format of the lower protocol version of both sides.
+        let Ok(data) = ChallengeResponse::signed_bytes(
+            peer_request.version,
+            Message::<N>::VERSION,
+            peer_address,
+            self.address(),
+            expected_nonce,
+            nonce,
+        ) else {
+            warn!("Handshake with '{peer_addr}' failed (cannot serialize the signed bytes)");
+            return Some(DisconnectReason::InvalidChallengeResponse);
+        };
         // Verify the signature.
-        if !signature.verify_bytes(&peer_address, &[expected_nonce.to_le_bytes(), nonce.to_le_bytes()].concat()) {
+        if !signature.verify_bytes(&peer_address, &data) {
             warn!("Handshake with '{peer_addr}' failed (invalid signature)");
             return Some(DisconnectReason::InvalidChallengeResponse);
         }
+        // Ensure the response nonce was not used in a recent handshake, as the response would be a replay.
+        if !self.recent_nonces.insert(nonce) {
+            warn!("Handshake with '{peer_addr}' failed (replayed challenge response nonce)");
+            return Some(DisconnectReason::InvalidChallengeResponse);
+        }
         None
     }
 }
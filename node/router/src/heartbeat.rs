@@ -21,36 +21,36 @@ use crate::{
 use snarkvm::prelude::Network;
 
 use colored::Colorize;
-use rand::{prelude::IteratorRandom, rngs::OsRng, Rng};
-
-/// A helper function to compute the maximum of two numbers.
-/// See Rust issue 92391: https://github.com/rust-lang/rust/issues/92391.
-pub const fn max(a: usize, b: usize) -> usize {
-    match a > b {
-        true => a,
-        false => b,
-    }
-}
+use rand::{Rng, prelude::IteratorRandom, rngs::OsRng};
+use std::{cmp::Reverse, collections::HashMap, net::IpAddr, time::Duration};
 
 pub trait Heartbeat<N: Network>: Outbound<N> {
     /// The duration in seconds to sleep in between heartbeat executions.
     const HEARTBEAT_IN_SECS: u64 = 25; // 25 seconds
-    /// The minimum number of peers required to maintain connections with.
-    const MINIMUM_NUMBER_OF_PEERS: usize = 3;
-    /// The median number of peers to maintain connections with.
-    const MEDIAN_NUMBER_OF_PEERS: usize = max(Self::MAXIMUM_NUMBER_OF_PEERS / 2, Self::MINIMUM_NUMBER_OF_PEERS);
-    /// The maximum number of peers permitted to maintain connections with.
-    const MAXIMUM_NUMBER_OF_PEERS: usize = 21;
-    /// The maximum number of provers to maintain connections with.
-    const MAXIMUM_NUMBER_OF_PROVERS: usize = Self::MAXIMUM_NUMBER_OF_PEERS / 4;
+    /// The maximum age of a candidate peer that the node has never connected to.
+    const MAXIMUM_CANDIDATE_PEER_AGE_IN_SECS: u64 = 24 * 60 * 60; // 24 hours
+    /// The maximum age of a candidate peer that the node has successfully connected to in the past.
+    const MAXIMUM_CONNECTED_CANDIDATE_PEER_AGE_IN_SECS: u64 = 7 * 24 * 60 * 60; // 7 days
+    /// The share of the connected peers to disconnect from on each heartbeat, while the file descriptors are exhausted.
+    const FD_EXHAUSTION_SHED_PERCENT: usize = 10;
 
     /// Handles the heartbeat request.
     fn heartbeat(&self) {
         self.safety_check_minimum_number_of_peers();
         self.log_connected_peers();
+        self.log_duplicate_identity();
+        self.update_relay_gate();
 
         // Remove any stale connected peers.
         self.remove_stale_connected_peers();
+        // Remove any expired candidate peers.
+        self.remove_expired_candidate_peers();
+        // Remove any expired bans.
+        self.remove_expired_bans();
+        // Keep the pools and caches within the maximum pool memory.
+        self.handle_memory_budget();
+        // Free file descriptors, while they are exhausted.
+        self.handle_fd_exhaustion();
         // Remove the oldest connected peer.
         self.remove_oldest_connected_peer();
         // Keep the number of connected peers within the allowed range.
@@ -67,11 +67,12 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
     /// This function performs safety checks on the setting for the minimum number of peers.
     fn safety_check_minimum_number_of_peers(&self) {
         // Perform basic sanity checks on the configuration for the number of peers.
-        assert!(Self::MINIMUM_NUMBER_OF_PEERS >= 1, "The minimum number of peers must be at least 1.");
-        assert!(Self::MINIMUM_NUMBER_OF_PEERS <= Self::MAXIMUM_NUMBER_OF_PEERS);
-        assert!(Self::MINIMUM_NUMBER_OF_PEERS <= Self::MEDIAN_NUMBER_OF_PEERS);
-        assert!(Self::MEDIAN_NUMBER_OF_PEERS <= Self::MAXIMUM_NUMBER_OF_PEERS);
-        assert!(Self::MAXIMUM_NUMBER_OF_PROVERS <= Self::MAXIMUM_NUMBER_OF_PEERS);
+        let limits = self.router().peer_limits();
+        assert!(limits.minimum() >= 1, "The minimum number of peers must be at least 1.");
+        assert!(limits.minimum() <= limits.maximum());
+        assert!(limits.minimum() <= limits.median());
+        assert!(limits.median() <= limits.maximum());
+        assert!(limits.maximum_provers() <= limits.maximum());
     }
 
     /// This function logs the connected peers.
@@ -86,6 +87,24 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
         }
     }
 
+    /// This function repeats the warning while another node is using the account of this node.
+    fn log_duplicate_identity(&self) {
+        self.router().refresh_duplicate_identity();
+        let peer_ips = self.router().duplicate_identity_peers();
+        if !peer_ips.is_empty() {
+            let message = format!(
+                "Another node is using this account ({}) at {peer_ips:?} - stop all but one of the nodes",
+                self.router().address()
+            );
+            warn!("{}", message.bold().red());
+        }
+    }
+
+    /// This function updates the relay gate with the current sync status, so that its status is current without gossip.
+    fn update_relay_gate(&self) {
+        self.router().relay_gate().update(self.num_blocks_behind());
+    }
+
     /// This function removes any connected peers that have not communicated within the predefined time.
     fn remove_stale_connected_peers(&self) {
         // Check if any connected peer is stale.
@@ -100,11 +119,77 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
         }
     }
 
+    /// This function removes any bans that have expired.
+    fn remove_expired_bans(&self) {
+        let num_removed = self.router().remove_expired_bans();
+        if num_removed > 0 {
+            debug!("Removed {num_removed} expired bans");
+        }
+    }
+
+    /// This function removes any candidate peers that have exceeded their maximum age.
+    fn remove_expired_candidate_peers(&self) {
+        let num_removed = self.router().remove_expired_candidate_peers(
+            Duration::from_secs(Self::MAXIMUM_CANDIDATE_PEER_AGE_IN_SECS),
+            Duration::from_secs(Self::MAXIMUM_CONNECTED_CANDIDATE_PEER_AGE_IN_SECS),
+        );
+        if num_removed > 0 {
+            debug!("Removed {num_removed} expired candidate peers");
+        }
+    }
+
+    /// This function updates the memory accounting, and shrinks (or restores) the pools and caches accordingly.
+    fn handle_memory_budget(&self) {
+        self.router().update_memory_budget();
+    }
+
+    /// This function disconnects from the lowest-value peers while the file descriptors are exhausted, to free some.
+    /// The lowest-value peers are the non-validators in the most crowded subnets, that were seen the least recently;
+    /// the trusted and bootstrap peers, and the peers the node is syncing from, are kept.
+    fn handle_fd_exhaustion(&self) {
+        // Skip if the file descriptors are not exhausted.
+        if !self.router().is_fd_exhausted() {
+            return;
+        }
+
+        // Retrieve the trusted peers.
+        let trusted = self.router().trusted_peers();
+        // Retrieve the bootstrap peers.
+        let bootstrap = self.router().bootstrap_peers();
+
+        // Count the connected peers in each subnet, as the peers in crowded subnets add little diversity.
+        let connected_peers = self.router().get_connected_peers();
+        let mut subnets = HashMap::<IpAddr, usize>::new();
+        for peer in &connected_peers {
+            *subnets.entry(subnet(peer.ip().ip())).or_default() += 1;
+        }
+
+        // Rank the peers that can be disconnected from, from the lowest value.
+        let num_to_shed = (connected_peers.len() * Self::FD_EXHAUSTION_SHED_PERCENT).div_ceil(100);
+        let mut peers: Vec<_> = connected_peers
+            .iter()
+            .filter(|peer| !trusted.contains(&peer.ip()) && !bootstrap.contains(&peer.ip()))
+            // Skip if you are syncing from this peer.
+            .filter(|peer| self.is_block_synced() || self.router().cache.num_outbound_block_requests(&peer.ip()) == 0)
+            .collect();
+        peers.sort_by_key(|peer| {
+            (peer.node_type().is_validator(), Reverse(subnets[&subnet(peer.ip().ip())]), peer.last_seen())
+        });
+
+        // Disconnect from the lowest-value peers.
+        for peer in peers.into_iter().take(num_to_shed) {
+            warn!("Disconnecting from '{}' (the file descriptors are exhausted)", peer.ip());
+            let _ = self.send(peer.ip(), Message::Disconnect(DisconnectReason::TooManyPeers.into()));
+            // Disconnect from this peer.
+            self.router().disconnect(peer.ip());
+        }
+    }
+
     /// This function removes the oldest connected peer, to keep the connections fresh.
     /// This function only triggers if the router is above the minimum number of connected peers.
     fn remove_oldest_connected_peer(&self) {
         // Skip if the router is at or below the minimum number of connected peers.
-        if self.router().number_of_connected_peers() <= Self::MINIMUM_NUMBER_OF_PEERS {
+        if self.router().number_of_connected_peers() <= self.router().peer_limits().minimum() {
             return;
         }
 
@@ -151,12 +236,10 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
 
         // Consider rotating more external peers every ~10 heartbeats.
         let reduce_peers = self.router().rotate_external_peers() && rng.gen_range(0..10) == 0;
-        // Determine the maximum number of peers and provers to keep.
-        let (max_peers, max_provers) = if reduce_peers {
-            (Self::MEDIAN_NUMBER_OF_PEERS, 0)
-        } else {
-            (Self::MAXIMUM_NUMBER_OF_PEERS, Self::MAXIMUM_NUMBER_OF_PROVERS)
-        };
+        // Determine the maximum number of peers and provers to keep, as derived from the node type.
+        let limits = self.router().peer_limits();
+        let (max_peers, max_provers) =
+            if reduce_peers { (limits.median(), 0) } else { (limits.maximum(), limits.maximum_provers()) };
 
         // Compute the number of surplus peers.
         let num_surplus_peers = num_connected.saturating_sub(max_peers);
@@ -228,14 +311,15 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
         // Obtain the number of connected peers.
         let num_connected = self.router().number_of_connected_peers();
         // Compute the number of deficit peers.
-        let num_deficient = Self::MEDIAN_NUMBER_OF_PEERS.saturating_sub(num_connected);
+        let num_deficient = self.router().peer_limits().median().saturating_sub(num_connected);
 
-        if num_deficient > 0 {
+        // Note: While the file descriptors are exhausted, the node does not dial more peers.
+        if num_deficient > 0 && !self.router().is_fd_exhausted() {
             // Initialize an RNG.
             let rng = &mut OsRng;
 
-            // Attempt to connect to more peers.
-            for peer_ip in self.router().candidate_peers().into_iter().choose_multiple(rng, num_deficient) {
+            // Attempt to connect to more peers, skipping those that recently shut down.
+            for peer_ip in self.router().dialable_candidate_peers().into_iter().choose_multiple(rng, num_deficient) {
                 self.router().connect(peer_ip);
             }
             if self.router().allow_external_peers() {
@@ -249,6 +333,10 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
 
     /// This function keeps the number of bootstrap peers within the allowed range.
     fn handle_bootstrap_peers(&self) {
+        // Skip if the node is not allowed to connect to external peers.
+        if !self.router().allow_external_peers() {
+            return;
+        }
         // Split the bootstrap peers into connected and candidate lists.
         let mut connected_bootstrap = Vec::new();
         let mut candidate_bootstrap = Vec::new();
@@ -284,12 +372,12 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
 
     /// This function attempts to connect to any disconnected trusted peers.
     fn handle_trusted_peers(&self) {
-        // Ensure that the trusted nodes are connected.
+        // Ensure that the trusted nodes are connected, including those trusted since the last heartbeat.
         for peer_ip in self.router().trusted_peers() {
             // If the peer is not connected, attempt to connect to it.
-            if !self.router().is_connected(peer_ip) {
+            if !self.router().is_connected(&peer_ip) {
                 // Attempt to connect to the trusted peer.
-                self.router().connect(*peer_ip);
+                self.router().connect(peer_ip);
             }
         }
     }
@@ -299,3 +387,18 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
         // No-op
     }
 }
+
+/// Returns the subnet of the given IP, i.e. its `/24` prefix for IPv4, and its `/48` prefix for IPv6.
+fn subnet(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, c, _] = ip.octets();
+            IpAddr::from([a, b, c, 0])
+        }
+        IpAddr::V6(ip) => {
+            let mut octets = ip.octets();
+            octets[6..].fill(0);
+            IpAddr::from(octets)
+        }
+    }
+}
@@ -0,0 +1,89 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::messages::NodeType;
+use snarkvm::prelude::{Address, Network};
+use std::net::SocketAddr;
+
+/// The status of the node account, as observed in the local ledger.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct AccountStatus {
+    /// The block height of the local ledger, at the time of the evaluation.
+    height: u32,
+    /// The flag indicating whether the local ledger has finished syncing, at the time of the evaluation.
+    is_synced: bool,
+    /// The flag indicating whether the address is a member of the latest committee.
+    is_committee_member: bool,
+    /// The flag indicating whether the address has a bond.
+    is_bonded: bool,
+    /// The flag indicating whether the address has a public balance.
+    has_public_balance: bool,
+}
+
+impl AccountStatus {
+    /// Initializes a new instance of `AccountStatus`.
+    pub const fn new(
+        height: u32,
+        is_synced: bool,
+        is_committee_member: bool,
+        is_bonded: bool,
+        has_public_balance: bool,
+    ) -> Self {
+        Self { height, is_synced, is_committee_member, is_bonded, has_public_balance }
+    }
+
+    /// Returns the block height of the local ledger, at the time of the evaluation.
+    pub const fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns `true` if the local ledger had finished syncing, at the time of the evaluation.
+    pub const fn is_synced(&self) -> bool {
+        self.is_synced
+    }
+
+    /// Returns `true` if the address is a member of the latest committee.
+    pub const fn is_committee_member(&self) -> bool {
+        self.is_committee_member
+    }
+
+    /// Returns `true` if the address has a bond.
+    pub const fn is_bonded(&self) -> bool {
+        self.is_bonded
+    }
+
+    /// Returns `true` if the address has a public balance.
+    pub const fn has_public_balance(&self) -> bool {
+        self.has_public_balance
+    }
+
+    /// Returns `true` if the address is a committee member or has a bond.
+    pub const fn is_staked(&self) -> bool {
+        self.is_committee_member || self.is_bonded
+    }
+
+    /// Returns a one-line summary of the account status for the given node type and address.
+    pub fn summary<N: Network>(&self, node_type: NodeType, address: Address<N>) -> String {
+        format!(
+            "{node_type} '{address}' on {} - committee member: {}, bonded: {}, public balance: {} (as of block {}{})",
+            N::NAME,
+            self.is_committee_member,
+            self.is_bonded,
+            self.has_public_balance,
+            self.height,
+            if self.is_synced { "" } else { ", not yet synced" },
+        )
+    }
+}
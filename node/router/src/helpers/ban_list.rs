@@ -0,0 +1,438 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use aleo_std::{StorageMode, aleo_ledger_dir};
+use anyhow::{Result, ensure};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fmt,
+    fs,
+    net::IpAddr,
+    path::{Path, PathBuf},
+};
+
+/// The current version of the ban list format.
+pub const BAN_LIST_VERSION: u32 = 1;
+
+/// Returns the path of the ban list, next to the ledger of the given network and storage mode.
+pub fn ban_list_path(network: u16, storage_mode: &StorageMode) -> PathBuf {
+    // Obtain the path to the ledger.
+    let mut path = aleo_ledger_dir(network, storage_mode.clone());
+    // Name the ban list after the ledger, so that every ledger has its own ban list.
+    let name = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+    path.set_file_name(format!("{name}-ban-list.json"));
+    path
+}
+
+/// Returns the path of the operator's ban list, next to the given ban list.
+///
+/// The node never writes to the operator's ban list, so that the edits of the operator are never overwritten.
+pub fn operator_ban_list_path(path: &Path) -> PathBuf {
+    path.with_extension("operator.json")
+}
+
+/// A banned IP, with the expiry of its ban.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BannedIp {
+    /// The banned IP, on every port.
+    pub ip: IpAddr,
+    /// The UNIX timestamp at which the ban expires, or `None` if the IP is banned until it is unbanned.
+    #[serde(default)]
+    pub expires_at: Option<i64>,
+}
+
+impl BannedIp {
+    /// Returns `true` if the ban has expired at the given UNIX timestamp.
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+}
+
+/// The changes applied to the ban list by a reload of the operator's ban list.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BanListChanges {
+    /// The IPs that were not banned, sorted by IP.
+    pub banned: Vec<IpAddr>,
+    /// The IPs that are no longer banned, sorted by IP.
+    pub unbanned: Vec<IpAddr>,
+    /// The IPs whose ban has a new expiry, sorted by IP.
+    pub updated: Vec<IpAddr>,
+}
+
+impl BanListChanges {
+    /// Returns `true` if the reload did not change the ban list.
+    pub fn is_empty(&self) -> bool {
+        self.banned.is_empty() && self.unbanned.is_empty() && self.updated.is_empty()
+    }
+}
+
+impl fmt::Display for BanListChanges {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let changes = [("banned", &self.banned), ("unbanned", &self.unbanned), ("updated", &self.updated)]
+            .into_iter()
+            .filter(|(_, ips)| !ips.is_empty())
+            .map(|(change, ips)| {
+                format!("{change} {}", ips.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "))
+            })
+            .collect::<Vec<_>>();
+        match changes.is_empty() {
+            true => write!(f, "no changes"),
+            false => write!(f, "{}", changes.join("; ")),
+        }
+    }
+}
+
+/// The serialized ban list.
+#[derive(Debug, Serialize, Deserialize)]
+struct BanListFile {
+    /// The version of the format.
+    version: u32,
+    /// The banned IPs.
+    #[serde(default)]
+    bans: Vec<BannedIp>,
+}
+
+/// The IPs banned by the operator of the node, persisted across restarts.
+///
+/// Unlike a restriction, which the router applies to a misbehaving peer for a short while, a ban applies
+/// to every port of the IP, and lasts for the given duration, or until the IP is unbanned.
+///
+/// The bans come from two sources: the bans applied at runtime, e.g. via the REST API, which the node persists
+/// to its ban list, and the bans in the operator's ban list (see `operator_ban_list_path`), which the operator
+/// edits and the node only reads. An IP is banned if either source bans it, until the later of the two expiries.
+#[derive(Debug, Default)]
+pub struct BanList {
+    /// The file the ban list is persisted to, if any.
+    path: Option<PathBuf>,
+    /// The map of the IPs banned at runtime to the UNIX timestamp at which their ban expires, if any.
+    bans: RwLock<HashMap<IpAddr, Option<i64>>>,
+    /// The map of the IPs banned in the operator's ban list to the UNIX timestamp at which their ban expires, if any.
+    operator_bans: RwLock<HashMap<IpAddr, Option<i64>>>,
+}
+
+impl BanList {
+    /// Opens the ban list persisted to the given file, if any, along with the operator's ban list next to it,
+    /// without the bans expired at the given UNIX timestamp.
+    pub fn open(path: Option<PathBuf>, now: i64) -> Result<Self> {
+        let (bans, operator_bans) = match &path {
+            Some(path) => (load_if_exists(path)?, load_if_exists(&operator_ban_list_path(path))?),
+            None => Default::default(),
+        };
+        let ban_list = Self { path, bans: Default::default(), operator_bans: Default::default() };
+        ban_list.bans.write().extend(bans.into_iter().map(|ban| (ban.ip, ban.expires_at)));
+        *ban_list.operator_bans.write() = unexpired(operator_bans, now);
+        // Remove the bans that expired while the node was down.
+        ban_list.prune(now);
+        if !ban_list.is_empty() {
+            info!("Loaded {} banned IP(s) from the ban list", ban_list.len());
+        }
+        Ok(ban_list)
+    }
+
+    /// Returns the file the ban list is persisted to, if any.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Replaces the operator's bans with the ones in the operator's ban list, without the bans expired at the given
+    /// UNIX timestamp, and returns the changes to the banned IPs, e.g. once the operator edited the file.
+    ///
+    /// The file is read and validated in full before any ban is replaced, so that a malformed file leaves
+    /// the ban list unchanged. A missing file lifts every ban of the operator, as it does on startup.
+    /// The bans applied at runtime are kept, so an IP the operator removed from the file may remain banned.
+    pub fn reload(&self, now: i64) -> Result<BanListChanges> {
+        let Some(path) = &self.path else { return Ok(Default::default()) };
+        let operator_bans = unexpired(load_if_exists(&operator_ban_list_path(path))?, now);
+
+        // Swap in the new bans of the operator, and determine the changes to the banned IPs.
+        let bans = self.bans.read();
+        let mut current = self.operator_bans.write();
+        let previous = merge(&bans, &current, now);
+        *current = operator_bans;
+        let next = merge(&bans, &current, now);
+        drop(current);
+        drop(bans);
+
+        let mut changes = BanListChanges::default();
+        for (ip, expires_at) in &next {
+            match previous.get(ip) {
+                None => changes.banned.push(*ip),
+                Some(previous) if previous != expires_at => changes.updated.push(*ip),
+                Some(_) => (),
+            }
+        }
+        changes.unbanned = previous.keys().filter(|ip| !next.contains_key(ip)).copied().collect();
+        changes.banned.sort_unstable();
+        changes.unbanned.sort_unstable();
+        changes.updated.sort_unstable();
+        Ok(changes)
+    }
+
+    /// Returns the number of banned IPs, including the ones whose ban expired since the last pruning.
+    pub fn len(&self) -> usize {
+        let (bans, operator_bans) = (self.bans.read(), self.operator_bans.read());
+        operator_bans.len() + bans.keys().filter(|ip| !operator_bans.contains_key(ip)).count()
+    }
+
+    /// Returns `true` if no IP is banned.
+    pub fn is_empty(&self) -> bool {
+        self.bans.read().is_empty() && self.operator_bans.read().is_empty()
+    }
+
+    /// Returns `true` if the given IP is banned at the given UNIX timestamp.
+    pub fn is_banned(&self, ip: &IpAddr, now: i64) -> bool {
+        let is_banned = |bans: &HashMap<IpAddr, Option<i64>>| {
+            bans.get(ip).is_some_and(|expires_at| !BannedIp { ip: *ip, expires_at: *expires_at }.is_expired(now))
+        };
+        is_banned(&self.bans.read()) || is_banned(&self.operator_bans.read())
+    }
+
+    /// Returns the banned IPs whose ban has not expired at the given UNIX timestamp, sorted by IP.
+    pub fn banned_ips(&self, now: i64) -> Vec<BannedIp> {
+        let mut bans: Vec<_> = merge(&self.bans.read(), &self.operator_bans.read(), now)
+            .into_iter()
+            .map(|(ip, expires_at)| BannedIp { ip, expires_at })
+            .collect();
+        bans.sort_unstable_by_key(|ban| ban.ip);
+        bans
+    }
+
+    /// Bans the given IP until the given UNIX timestamp, or until it is unbanned if `None`,
+    /// replacing its previous ban at runtime, if any.
+    pub fn ban(&self, ip: IpAddr, expires_at: Option<i64>) {
+        self.bans.write().insert(ip, expires_at);
+        self.persist();
+    }
+
+    /// Unbans the given IP, returning `true` if it was banned at runtime.
+    ///
+    /// Note: An IP banned in the operator's ban list remains banned until it is removed from that file.
+    pub fn unban(&self, ip: &IpAddr) -> bool {
+        let was_banned = self.bans.write().remove(ip).is_some();
+        if was_banned {
+            self.persist();
+        }
+        was_banned
+    }
+
+    /// Removes the bans expired at the given UNIX timestamp, returning the number of removed bans.
+    pub fn prune(&self, now: i64) -> usize {
+        let retain = |bans: &mut HashMap<IpAddr, Option<i64>>| {
+            let num_bans = bans.len();
+            bans.retain(|ip, expires_at| !BannedIp { ip: *ip, expires_at: *expires_at }.is_expired(now));
+            num_bans - bans.len()
+        };
+        let num_removed = retain(&mut self.bans.write());
+        let num_removed_by_operator = retain(&mut self.operator_bans.write());
+        if num_removed > 0 {
+            self.persist();
+        }
+        num_removed + num_removed_by_operator
+    }
+
+    /// Writes the ban list to its file, if any.
+    ///
+    /// Note: The bans are applied even if they cannot be persisted, so a failure is only logged.
+    fn persist(&self) {
+        if let Err(error) = self.try_persist() {
+            warn!("Failed to persist the ban list - {error}");
+        }
+    }
+
+    /// Writes the bans applied at runtime to the file of the ban list, if any.
+    ///
+    /// The ban list is written to a temporary file first, so that an interrupted write never corrupts it.
+    fn try_persist(&self) -> Result<()> {
+        let Some(path) = &self.path else { return Ok(()) };
+        let mut bans: Vec<_> =
+            self.bans.read().iter().map(|(ip, expires_at)| BannedIp { ip: *ip, expires_at: *expires_at }).collect();
+        bans.sort_unstable_by_key(|ban| ban.ip);
+        let bytes = serde_json::to_vec_pretty(&BanListFile { version: BAN_LIST_VERSION, bans })?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let temp_path = path.with_extension("json.partial");
+        fs::write(&temp_path, bytes)?;
+        fs::rename(&temp_path, path)?;
+        Ok(())
+    }
+}
+
+/// Loads the banned IPs from the given file, if it exists.
+fn load_if_exists(path: &Path) -> Result<Vec<BannedIp>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let file: BanListFile = serde_json::from_slice(&fs::read(path)?)?;
+    ensure!(file.version <= BAN_LIST_VERSION, "Unsupported ban list version {} in '{}'", file.version, path.display());
+    Ok(file.bans)
+}
+
+/// Returns the map of the given banned IPs to their expiry, without the bans expired at the given UNIX timestamp.
+fn unexpired(bans: Vec<BannedIp>, now: i64) -> HashMap<IpAddr, Option<i64>> {
+    bans.into_iter().filter(|ban| !ban.is_expired(now)).map(|ban| (ban.ip, ban.expires_at)).collect()
+}
+
+/// Returns the union of the given bans, without the bans expired at the given UNIX timestamp.
+/// An IP banned in both keeps the later expiry, where `None` never expires.
+fn merge(
+    bans: &HashMap<IpAddr, Option<i64>>,
+    operator_bans: &HashMap<IpAddr, Option<i64>>,
+    now: i64,
+) -> HashMap<IpAddr, Option<i64>> {
+    let mut merged = HashMap::with_capacity(bans.len() + operator_bans.len());
+    for (ip, expires_at) in bans.iter().chain(operator_bans) {
+        if (BannedIp { ip: *ip, expires_at: *expires_at }).is_expired(now) {
+            continue;
+        }
+        merged
+            .entry(*ip)
+            .and_modify(|current: &mut Option<i64>| {
+                *current = match (*current, *expires_at) {
+                    (Some(current), Some(expires_at)) => Some(current.max(expires_at)),
+                    _ => None,
+                }
+            })
+            .or_insert(*expires_at);
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a unique path for a sample ban list.
+    fn sample_ban_list_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("snarkos-ban-list-{name}-{}.json", rand::random::<u64>()))
+    }
+
+    #[test]
+    fn test_ban_list_round_trip() {
+        let path = sample_ban_list_path("round-trip");
+        let (permanent, temporary, expiring): (IpAddr, IpAddr, IpAddr) =
+            ("1.2.3.4".parse().unwrap(), "5.6.7.8".parse().unwrap(), "::1".parse().unwrap());
+
+        // Ban the IPs, which persists the ban list.
+        let ban_list = BanList::open(Some(path.clone()), 1_000).unwrap();
+        assert!(ban_list.is_empty());
+        ban_list.ban(permanent, None);
+        ban_list.ban(temporary, Some(5_000));
+        ban_list.ban(expiring, Some(2_000));
+        assert!(ban_list.is_banned(&expiring, 1_999));
+        assert!(!ban_list.is_banned(&expiring, 2_000));
+        drop(ban_list);
+
+        // Ensure the ban list is restored, without the bans that expired in the meantime.
+        let ban_list = BanList::open(Some(path.clone()), 3_000).unwrap();
+        assert_eq!(ban_list.banned_ips(3_000), vec![BannedIp { ip: permanent, expires_at: None }, BannedIp {
+            ip: temporary,
+            expires_at: Some(5_000)
+        },]);
+        assert!(ban_list.is_banned(&permanent, i64::MAX));
+        assert!(!ban_list.is_banned(&temporary, 5_000));
+
+        // Ensure an unban is persisted, and the pruning of the expired bans as well.
+        assert!(ban_list.unban(&permanent));
+        assert!(!ban_list.unban(&permanent));
+        assert_eq!(ban_list.prune(5_000), 1);
+        drop(ban_list);
+        assert!(BanList::open(Some(path.clone()), 5_000).unwrap().is_empty());
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_ban_list_format() {
+        let path = sample_ban_list_path("format");
+        // Ensure the ban list of a newer node is rejected, instead of silently dropping its bans.
+        fs::write(&path, r#"{ "version": 2, "bans": [] }"#).unwrap();
+        assert!(BanList::open(Some(path.clone()), 0).is_err());
+        // Ensure a ban without an expiry is permanent.
+        fs::write(&path, r#"{ "version": 1, "bans": [{ "ip": "1.2.3.4" }] }"#).unwrap();
+        let ban_list = BanList::open(Some(path.clone()), 0).unwrap();
+        assert!(ban_list.is_banned(&"1.2.3.4".parse().unwrap(), i64::MAX));
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_ban_list_reload() {
+        let path = sample_ban_list_path("reload");
+        let operator_path = operator_ban_list_path(&path);
+        let (kept, updated, unbanned, banned): (IpAddr, IpAddr, IpAddr, IpAddr) =
+            ("1.2.3.4".parse().unwrap(), "5.6.7.8".parse().unwrap(), "::1".parse().unwrap(), "::2".parse().unwrap());
+        let runtime: IpAddr = "9.9.9.9".parse().unwrap();
+
+        // Ban the IPs in the operator's ban list, and one IP at runtime.
+        let bans = serde_json::json!({ "version": 1, "bans": [
+            { "ip": kept },
+            { "ip": updated, "expires_at": 5_000 },
+            { "ip": unbanned },
+        ] });
+        fs::write(&operator_path, bans.to_string()).unwrap();
+        let ban_list = BanList::open(Some(path.clone()), 0).unwrap();
+        assert_eq!(ban_list.len(), 3);
+        ban_list.ban(runtime, Some(2_000));
+        assert_eq!(ban_list.len(), 4);
+        // Ensure the ban at runtime is persisted to the ban list, without touching the operator's ban list.
+        assert_eq!(fs::read_to_string(&operator_path).unwrap(), bans.to_string());
+        assert_eq!(load_if_exists(&path).unwrap(), vec![BannedIp { ip: runtime, expires_at: Some(2_000) }]);
+
+        // Edit the operator's ban list, and ensure the reload applies the changes, and keeps the ban at runtime.
+        let bans = serde_json::json!({ "version": 1, "bans": [
+            { "ip": kept },
+            { "ip": updated, "expires_at": 9_000 },
+            { "ip": banned },
+            { "ip": runtime, "expires_at": 500 },
+        ] });
+        fs::write(&operator_path, bans.to_string()).unwrap();
+        let changes = ban_list.reload(1_000).unwrap();
+        assert_eq!(changes, BanListChanges { banned: vec![banned], unbanned: vec![unbanned], updated: vec![updated] });
+        assert_eq!(changes.to_string(), "banned ::2; unbanned ::1; updated 5.6.7.8");
+        assert!(ban_list.is_banned(&banned, 1_000));
+        assert!(!ban_list.is_banned(&unbanned, 1_000));
+        assert!(ban_list.is_banned(&updated, 8_999));
+        assert!(ban_list.is_banned(&runtime, 1_999));
+        // Ensure a reload of the same file changes nothing.
+        assert!(ban_list.reload(1_000).unwrap().is_empty());
+
+        // Ensure an IP banned both at runtime and by the operator is banned until the later expiry,
+        // and that it cannot be unbanned at runtime.
+        ban_list.ban(updated, Some(12_000));
+        assert!(ban_list.banned_ips(1_000).contains(&BannedIp { ip: updated, expires_at: Some(12_000) }));
+        ban_list.ban(kept, Some(3_000));
+        assert!(ban_list.banned_ips(1_000).contains(&BannedIp { ip: kept, expires_at: None }));
+        assert!(ban_list.unban(&kept));
+        assert!(ban_list.is_banned(&kept, 1_000));
+
+        // Ensure a malformed file leaves the bans unchanged.
+        fs::write(&operator_path, r#"{ "version": 1, "bans": [{ "ip": "not an IP" }] }"#).unwrap();
+        assert!(ban_list.reload(1_000).is_err());
+        assert_eq!(ban_list.banned_ips(1_000).len(), 4);
+
+        // Ensure a missing file lifts every ban of the operator, but keeps the bans at runtime.
+        fs::remove_file(&operator_path).unwrap();
+        let changes = ban_list.reload(1_000).unwrap();
+        assert_eq!(changes, BanListChanges { unbanned: vec![kept, banned], ..Default::default() });
+        assert_eq!(ban_list.banned_ips(1_000), vec![BannedIp { ip: updated, expires_at: Some(12_000) }, BannedIp {
+            ip: runtime,
+            expires_at: Some(2_000)
+        },]);
+
+        fs::remove_file(path).unwrap();
+    }
+}
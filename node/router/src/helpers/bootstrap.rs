@@ -0,0 +1,341 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::messages::DisconnectReason;
+
+use indexmap::IndexSet;
+use rand::Rng;
+use std::{
+    collections::BTreeMap,
+    fmt,
+    io,
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
+
+/// The base delay in seconds between connection attempts to a bootstrap peer.
+const BOOTSTRAP_BACKOFF_BASE_IN_SECS: u64 = 1;
+/// The maximum delay in seconds between connection attempts to a bootstrap peer.
+const BOOTSTRAP_BACKOFF_MAX_IN_SECS: u64 = 64;
+
+/// The reason an outbound connection attempt failed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConnectionFailure {
+    /// The connection attempt timed out.
+    Timeout,
+    /// The connection attempt was refused.
+    Refused,
+    /// The handshake was rejected, or failed verification.
+    HandshakeRejected,
+    /// The handshake failed due to a protocol version mismatch.
+    VersionMismatch,
+    /// The handshake failed due to a genesis block mismatch.
+    GenesisMismatch,
+    /// The handshake failed as the peer is on another network, whose ID is given.
+    NetworkMismatch(u16),
+    /// The handshake was dropped, as the challenge request of the peer was invalid for the given reason.
+    Dropped(DisconnectReason),
+    /// The connection attempt failed for another reason.
+    Other(String),
+}
+
+impl ConnectionFailure {
+    /// Returns the connection failure for the given I/O error.
+    pub fn from_io_error(error: &io::Error) -> Self {
+        // If the error was raised with a connection failure, return it directly.
+        if let Some(failure) = error.get_ref().and_then(|inner| inner.downcast_ref::<Self>()) {
+            return failure.clone();
+        }
+        match error.kind() {
+            io::ErrorKind::TimedOut => Self::Timeout,
+            io::ErrorKind::ConnectionRefused => Self::Refused,
+            // Errors raised during the handshake (including disconnects from the peer) are of kind `Other`.
+            io::ErrorKind::Other => Self::HandshakeRejected,
+            _ => Self::Other(error.to_string()),
+        }
+    }
+
+    /// Returns the connection failure for a handshake dropped for the given reason.
+    pub const fn from_disconnect_reason(reason: DisconnectReason) -> Self {
+        match reason {
+            DisconnectReason::OutdatedClientVersion => Self::VersionMismatch,
+            DisconnectReason::NetworkMismatch(network) => Self::NetworkMismatch(network),
+            reason => Self::Dropped(reason),
+        }
+    }
+
+    /// Returns the likely cause of the connection failure.
+    pub const fn likely_cause(&self) -> &'static str {
+        match self {
+            Self::Timeout => "outbound connections on port 4130 may be blocked, or the peer is offline",
+            Self::Refused => "the peer is not accepting connections, or outbound connections are rejected locally",
+            Self::HandshakeRejected => "the handshake failed verification, which may indicate clock skew",
+            Self::VersionMismatch => "this node or the peer is running an outdated version of snarkOS",
+            Self::GenesisMismatch => "the peer is on a different network (genesis mismatch)",
+            Self::NetworkMismatch(_) => {
+                "the peer is configured for a different network, e.g. testnet instead of mainnet"
+            }
+            Self::Dropped(_) => "the peer sent an invalid challenge request, e.g. a replayed one",
+            Self::Other(_) => "unknown",
+        }
+    }
+}
+
+impl From<ConnectionFailure> for io::Error {
+    fn from(failure: ConnectionFailure) -> Self {
+        io::Error::new(io::ErrorKind::Other, failure)
+    }
+}
+
+impl fmt::Display for ConnectionFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Timeout => write!(f, "timed out"),
+            Self::Refused => write!(f, "connection refused"),
+            Self::HandshakeRejected => write!(f, "handshake rejected"),
+            Self::VersionMismatch => write!(f, "version mismatch"),
+            Self::GenesisMismatch => write!(f, "genesis mismatch"),
+            Self::NetworkMismatch(network) => write!(f, "network mismatch (the peer is on network {network})"),
+            Self::Dropped(reason) => write!(f, "handshake dropped ({reason:?})"),
+            Self::Other(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for ConnectionFailure {}
+
+/// The connection attempts to a bootstrap or trusted peer.
+#[derive(Clone, Debug)]
+pub struct BootstrapAttempts {
+    /// The number of connection attempts.
+    num_attempts: u32,
+    /// The reason the last connection attempt failed, if it failed.
+    last_failure: Option<ConnectionFailure>,
+    /// The timestamp after which the next connection attempt may be made.
+    next_attempt: Instant,
+}
+
+impl BootstrapAttempts {
+    /// Returns the number of connection attempts.
+    pub const fn num_attempts(&self) -> u32 {
+        self.num_attempts
+    }
+
+    /// Returns the reason the last connection attempt failed, if it failed.
+    pub const fn last_failure(&self) -> Option<&ConnectionFailure> {
+        self.last_failure.as_ref()
+    }
+
+    /// Returns the timestamp after which the next connection attempt may be made.
+    pub const fn next_attempt(&self) -> Instant {
+        self.next_attempt
+    }
+}
+
+/// The state of the bootstrap phase, which tracks the connection attempts to bootstrap and trusted peers.
+#[derive(Debug)]
+pub struct Bootstrap {
+    /// The timestamp of when the bootstrap phase started.
+    started: Instant,
+    /// The map of peer IPs to their connection attempts.
+    attempts: BTreeMap<SocketAddr, BootstrapAttempts>,
+    /// The timestamp of when the last diagnostic was emitted.
+    last_diagnostic: Option<Instant>,
+}
+
+impl Default for Bootstrap {
+    /// Initializes a new instance of `Bootstrap`.
+    fn default() -> Self {
+        Self { started: Instant::now(), attempts: Default::default(), last_diagnostic: None }
+    }
+}
+
+impl Bootstrap {
+    /// Returns the time elapsed since the bootstrap phase started.
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Returns the map of peer IPs to their connection attempts.
+    pub const fn attempts(&self) -> &BTreeMap<SocketAddr, BootstrapAttempts> {
+        &self.attempts
+    }
+
+    /// Returns `true` if the backoff for the given peer IP has elapsed.
+    pub fn is_due(&self, peer_ip: &SocketAddr, now: Instant) -> bool {
+        self.attempts.get(peer_ip).map_or(true, |attempts| now >= attempts.next_attempt)
+    }
+
+    /// Records a successful connection attempt to the given peer IP.
+    pub fn record_success(&mut self, peer_ip: SocketAddr) {
+        let attempts = self.attempts.entry(peer_ip).or_insert_with(new_attempts);
+        attempts.num_attempts = attempts.num_attempts.saturating_add(1);
+        attempts.last_failure = None;
+        attempts.next_attempt = Instant::now();
+    }
+
+    /// Records a failed connection attempt to the given peer IP, and schedules the next attempt
+    /// using an exponential backoff with jitter.
+    pub fn record_failure(&mut self, peer_ip: SocketAddr, failure: ConnectionFailure) {
+        let attempts = self.attempts.entry(peer_ip).or_insert_with(new_attempts);
+        attempts.num_attempts = attempts.num_attempts.saturating_add(1);
+        attempts.last_failure = Some(failure);
+        attempts.next_attempt = Instant::now() + backoff(attempts.num_attempts, &mut rand::thread_rng());
+    }
+
+    /// Returns the diagnostic if no peers are connected after the given window has elapsed,
+    /// and no diagnostic has been emitted within the given interval.
+    pub fn next_diagnostic(
+        &mut self,
+        now: Instant,
+        num_connected_peers: usize,
+        window: Duration,
+        interval: Duration,
+    ) -> Option<String> {
+        // Ensure there are no connected peers, and the window has elapsed.
+        if num_connected_peers > 0 || now.saturating_duration_since(self.started) < window {
+            return None;
+        }
+        // Ensure the diagnostic was not emitted within the interval.
+        if let Some(last_diagnostic) = self.last_diagnostic {
+            if now.saturating_duration_since(last_diagnostic) < interval {
+                return None;
+            }
+        }
+        self.last_diagnostic = Some(now);
+        Some(self.diagnostic())
+    }
+
+    /// Returns a multi-line diagnostic summarizing the connection attempts and their likely causes.
+    pub fn diagnostic(&self) -> String {
+        let mut diagnostic = format!("No peers have connected after {} seconds.", self.elapsed().as_secs());
+        // Summarize the connection attempts.
+        if self.attempts.is_empty() {
+            diagnostic.push_str("\n  No connection attempts have completed yet.");
+        }
+        for (peer_ip, attempts) in &self.attempts {
+            let last_failure = attempts.last_failure.as_ref().map_or("none".to_string(), |f| f.to_string());
+            diagnostic.push_str(&format!(
+                "\n  '{peer_ip}' - {} attempt(s), last failure: {last_failure}",
+                attempts.num_attempts
+            ));
+        }
+        // Summarize the likely causes.
+        let causes: IndexSet<_> = self
+            .attempts
+            .values()
+            .filter_map(|attempts| attempts.last_failure.as_ref())
+            .map(|f| f.likely_cause())
+            .collect();
+        if !causes.is_empty() {
+            diagnostic.push_str("\nLikely causes:");
+            for cause in causes {
+                diagnostic.push_str(&format!("\n  - {cause}"));
+            }
+        }
+        diagnostic
+    }
+}
+
+/// Returns a new instance of `BootstrapAttempts`.
+fn new_attempts() -> BootstrapAttempts {
+    BootstrapAttempts { num_attempts: 0, last_failure: None, next_attempt: Instant::now() }
+}
+
+/// Returns the delay before the next connection attempt, which doubles with each attempt (up to a maximum),
+/// with up to 50% of random jitter added to avoid synchronized retries.
+fn backoff<R: Rng>(num_attempts: u32, rng: &mut R) -> Duration {
+    let exponent = num_attempts.saturating_sub(1).min(u64::BITS - 1);
+    let delay_in_secs = BOOTSTRAP_BACKOFF_BASE_IN_SECS.saturating_mul(1 << exponent).min(BOOTSTRAP_BACKOFF_MAX_IN_SECS);
+    let delay = Duration::from_secs(delay_in_secs);
+    delay + delay.mul_f64(rng.gen_range(0.0..0.5))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff() {
+        let rng = &mut rand::thread_rng();
+
+        // Ensure the delay grows exponentially, with bounded jitter.
+        for num_attempts in 1..=7 {
+            let delay = Duration::from_secs(1 << (num_attempts - 1));
+            let backoff = backoff(num_attempts, rng);
+            assert!(backoff >= delay && backoff < delay.mul_f64(1.5));
+        }
+        // Ensure the delay is capped.
+        let max_delay = Duration::from_secs(BOOTSTRAP_BACKOFF_MAX_IN_SECS);
+        assert!(backoff(100, rng) < max_delay.mul_f64(1.5));
+    }
+
+    #[test]
+    fn test_connection_failure_from_io_error() {
+        assert_eq!(ConnectionFailure::from_io_error(&io::ErrorKind::TimedOut.into()), ConnectionFailure::Timeout);
+        assert_eq!(
+            ConnectionFailure::from_io_error(&io::ErrorKind::ConnectionRefused.into()),
+            ConnectionFailure::Refused
+        );
+        assert_eq!(
+            ConnectionFailure::from_io_error(&ConnectionFailure::GenesisMismatch.into()),
+            ConnectionFailure::GenesisMismatch
+        );
+        assert_eq!(
+            ConnectionFailure::from_io_error(&ConnectionFailure::NetworkMismatch(1).into()),
+            ConnectionFailure::NetworkMismatch(1)
+        );
+    }
+
+    #[test]
+    fn test_connection_failure_from_disconnect_reason() {
+        // Ensure the reason the handshake was dropped is retained.
+        assert_eq!(
+            ConnectionFailure::from_disconnect_reason(DisconnectReason::OutdatedClientVersion),
+            ConnectionFailure::VersionMismatch
+        );
+        assert_eq!(
+            ConnectionFailure::from_disconnect_reason(DisconnectReason::ProtocolViolation),
+            ConnectionFailure::Dropped(DisconnectReason::ProtocolViolation)
+        );
+        let failure = ConnectionFailure::from_disconnect_reason(DisconnectReason::ProtocolViolation);
+        assert_eq!(ConnectionFailure::from_io_error(&failure.clone().into()), failure);
+    }
+
+    #[test]
+    fn test_next_diagnostic() {
+        let mut bootstrap = Bootstrap::default();
+        let peer_ip: SocketAddr = "1.2.3.4:4130".parse().unwrap();
+        bootstrap.record_failure(peer_ip, ConnectionFailure::Timeout);
+
+        // Ensure the failed peer is not due immediately.
+        assert!(!bootstrap.is_due(&peer_ip, Instant::now()));
+
+        let window = Duration::from_secs(60);
+        let interval = Duration::from_secs(600);
+        let start = bootstrap.started;
+
+        // Ensure the diagnostic is not emitted within the window, or when a peer is connected.
+        assert!(bootstrap.next_diagnostic(start, 0, window, interval).is_none());
+        assert!(bootstrap.next_diagnostic(start + window, 1, window, interval).is_none());
+
+        // Ensure the diagnostic is emitted once after the window, and repeated after the interval.
+        let diagnostic = bootstrap.next_diagnostic(start + window, 0, window, interval).unwrap();
+        assert!(diagnostic.contains("'1.2.3.4:4130' - 1 attempt(s), last failure: timed out"));
+        assert!(diagnostic.contains(ConnectionFailure::Timeout.likely_cause()));
+        assert!(bootstrap.next_diagnostic(start + window + interval / 2, 0, window, interval).is_none());
+        assert!(bootstrap.next_diagnostic(start + window + interval, 0, window, interval).is_some());
+    }
+}
@@ -13,7 +13,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::messages::BlockRequest;
+use crate::{helpers::estimate_entries, messages::BlockRequest};
 use snarkvm::prelude::{Network, puzzle::SolutionID};
 
 use core::hash::Hash;
@@ -22,27 +22,40 @@ use parking_lot::RwLock;
 use std::{
     collections::{HashMap, HashSet, VecDeque},
     net::{IpAddr, SocketAddr},
+    ops::Deref,
+    sync::{
+        Arc,
+        atomic::{AtomicI64, AtomicU64, AtomicUsize, Ordering},
+    },
 };
 use time::{Duration, OffsetDateTime};
 
 /// The maximum number of items to store in a cache map.
-const MAX_CACHE_SIZE: usize = 1 << 17;
+/// The nominal capacity of each cache of recently-seen solutions and transactions.
+pub const MAX_CACHE_SIZE: usize = 1 << 17;
+
+/// The minimum interval between two cleanups of a cache of recent timestamps.
+const MIN_CLEANUP_INTERVAL: Duration = Duration::seconds(1);
+/// The maximum interval between two cleanups of a cache of recent timestamps, reached when it is idle.
+const MAX_CLEANUP_INTERVAL: Duration = Duration::minutes(5);
 
 /// A helper containing the peer IP and solution ID.
 type SolutionKey<N> = (SocketAddr, SolutionID<N>);
 /// A helper containing the peer IP and transaction ID.
 type TransactionKey<N> = (SocketAddr, <N as Network>::TransactionID);
+/// The counters of the bytes sent to and received from a peer.
+type BandwidthCounters = (Arc<AtomicU64>, Arc<AtomicU64>);
 
 #[derive(Debug)]
 pub struct Cache<N: Network> {
     /// The map of peer connections to their recent timestamps.
-    seen_inbound_connections: RwLock<HashMap<IpAddr, VecDeque<OffsetDateTime>>>,
+    seen_inbound_connections: RecentTimestamps<IpAddr>,
     /// The map of peer IPs to their recent timestamps.
-    seen_inbound_messages: RwLock<HashMap<SocketAddr, VecDeque<OffsetDateTime>>>,
+    seen_inbound_messages: RecentTimestamps<SocketAddr>,
     /// The map of peer IPs to their recent timestamps.
-    seen_inbound_puzzle_requests: RwLock<HashMap<SocketAddr, VecDeque<OffsetDateTime>>>,
+    seen_inbound_puzzle_requests: RecentTimestamps<SocketAddr>,
     /// The map of peer IPs to their recent timestamps.
-    seen_inbound_block_requests: RwLock<HashMap<SocketAddr, VecDeque<OffsetDateTime>>>,
+    seen_inbound_block_requests: RecentTimestamps<SocketAddr>,
     /// The map of solution IDs to their last seen timestamp.
     seen_inbound_solutions: RwLock<LinkedHashMap<SolutionKey<N>, OffsetDateTime>>,
     /// The map of transaction IDs to their last seen timestamp.
@@ -57,6 +70,12 @@ pub struct Cache<N: Network> {
     seen_outbound_transactions: RwLock<LinkedHashMap<TransactionKey<N>, OffsetDateTime>>,
     /// The map of peer IPs to the number of sent peer requests.
     seen_outbound_peer_requests: RwLock<HashMap<SocketAddr, u32>>,
+    /// The map of peer IPs to the recent timestamps of the solution acknowledgments sent to them.
+    seen_outbound_solution_acks: RecentTimestamps<SocketAddr>,
+    /// The map of peer IPs to the counters of the bytes sent to and received from them, since they connected.
+    peer_bandwidth: RwLock<HashMap<SocketAddr, BandwidthCounters>>,
+    /// The capacity of each cache of recently-seen solutions and transactions.
+    capacity: AtomicUsize,
 }
 
 impl<N: Network> Default for Cache<N> {
@@ -69,6 +88,7 @@ impl<N: Network> Default for Cache<N> {
 impl<N: Network> Cache<N> {
     const INBOUND_BLOCK_REQUEST_INTERVAL: i64 = 60;
     const INBOUND_PUZZLE_REQUEST_INTERVAL: i64 = 60;
+    const OUTBOUND_SOLUTION_ACK_INTERVAL: i64 = 60;
 
     /// Initializes a new instance of the cache.
     pub fn new() -> Self {
@@ -84,6 +104,9 @@ impl<N: Network> Cache<N> {
             seen_outbound_solutions: RwLock::new(LinkedHashMap::with_capacity(MAX_CACHE_SIZE)),
             seen_outbound_transactions: RwLock::new(LinkedHashMap::with_capacity(MAX_CACHE_SIZE)),
             seen_outbound_peer_requests: Default::default(),
+            seen_outbound_solution_acks: Default::default(),
+            peer_bandwidth: Default::default(),
+            capacity: AtomicUsize::new(MAX_CACHE_SIZE),
         }
     }
 }
@@ -91,27 +114,29 @@ impl<N: Network> Cache<N> {
 impl<N: Network> Cache<N> {
     /// Inserts a new timestamp for the given peer connection, returning the number of recent connection requests.
     pub fn insert_inbound_connection(&self, peer_ip: IpAddr, interval_in_secs: i64) -> usize {
-        Self::retain_and_insert(&self.seen_inbound_connections, peer_ip, interval_in_secs)
+        self.seen_inbound_connections.insert(peer_ip, interval_in_secs, self.capacity(), OffsetDateTime::now_utc())
     }
 
     /// Inserts a new timestamp for the given peer message, returning the number of recent messages.
     pub fn insert_inbound_message(&self, peer_ip: SocketAddr, interval_in_secs: i64) -> usize {
-        Self::retain_and_insert(&self.seen_inbound_messages, peer_ip, interval_in_secs)
+        self.seen_inbound_messages.insert(peer_ip, interval_in_secs, self.capacity(), OffsetDateTime::now_utc())
     }
 
     /// Inserts a new timestamp for the given peer IP, returning the number of recent requests.
     pub fn insert_inbound_puzzle_request(&self, peer_ip: SocketAddr) -> usize {
-        Self::retain_and_insert(&self.seen_inbound_puzzle_requests, peer_ip, Self::INBOUND_PUZZLE_REQUEST_INTERVAL)
+        let interval_in_secs = Self::INBOUND_PUZZLE_REQUEST_INTERVAL;
+        self.seen_inbound_puzzle_requests.insert(peer_ip, interval_in_secs, self.capacity(), OffsetDateTime::now_utc())
     }
 
     /// Inserts a new timestamp for the given peer IP, returning the number of recent block requests.
     pub fn insert_inbound_block_request(&self, peer_ip: SocketAddr) -> usize {
-        Self::retain_and_insert(&self.seen_inbound_block_requests, peer_ip, Self::INBOUND_BLOCK_REQUEST_INTERVAL)
+        let interval_in_secs = Self::INBOUND_BLOCK_REQUEST_INTERVAL;
+        self.seen_inbound_block_requests.insert(peer_ip, interval_in_secs, self.capacity(), OffsetDateTime::now_utc())
     }
 
     /// Inserts a solution ID into the cache, returning the previously seen timestamp if it existed.
     pub fn insert_inbound_solution(&self, peer_ip: SocketAddr, solution_id: SolutionID<N>) -> Option<OffsetDateTime> {
-        Self::refresh_and_insert(&self.seen_inbound_solutions, (peer_ip, solution_id))
+        Self::refresh_and_insert(&self.seen_inbound_solutions, (peer_ip, solution_id), self.capacity())
     }
 
     /// Inserts a transaction ID into the cache, returning the previously seen timestamp if it existed.
@@ -120,14 +145,15 @@ impl<N: Network> Cache<N> {
         peer_ip: SocketAddr,
         transaction: N::TransactionID,
     ) -> Option<OffsetDateTime> {
-        Self::refresh_and_insert(&self.seen_inbound_transactions, (peer_ip, transaction))
+        Self::refresh_and_insert(&self.seen_inbound_transactions, (peer_ip, transaction), self.capacity())
     }
 }
 
 impl<N: Network> Cache<N> {
     /// Returns `true` if the cache contains the block request for the given peer.
     pub fn contains_inbound_block_request(&self, peer_ip: &SocketAddr) -> bool {
-        Self::retain(&self.seen_inbound_block_requests, *peer_ip, Self::INBOUND_BLOCK_REQUEST_INTERVAL) > 0
+        self.seen_inbound_block_requests.count(peer_ip, Self::INBOUND_BLOCK_REQUEST_INTERVAL, OffsetDateTime::now_utc())
+            > 0
     }
 
     /// Returns the number of recent block requests for the given peer.
@@ -171,7 +197,19 @@ impl<N: Network> Cache<N> {
 
     /// Inserts a solution ID into the cache, returning the previously seen timestamp if it existed.
     pub fn insert_outbound_solution(&self, peer_ip: SocketAddr, solution_id: SolutionID<N>) -> Option<OffsetDateTime> {
-        Self::refresh_and_insert(&self.seen_outbound_solutions, (peer_ip, solution_id))
+        Self::refresh_and_insert(&self.seen_outbound_solutions, (peer_ip, solution_id), self.capacity())
+    }
+
+    /// Returns `true` if the given solution ID was sent to the given peer, and is still in the cache.
+    pub fn contains_outbound_solution(&self, peer_ip: SocketAddr, solution_id: SolutionID<N>) -> bool {
+        self.seen_outbound_solutions.read().contains_key(&(peer_ip, solution_id))
+    }
+
+    /// Inserts a new timestamp for the solution acknowledgment sent to the given peer IP,
+    /// returning the number of recent solution acknowledgments.
+    pub fn insert_outbound_solution_ack(&self, peer_ip: SocketAddr) -> usize {
+        let interval_in_secs = Self::OUTBOUND_SOLUTION_ACK_INTERVAL;
+        self.seen_outbound_solution_acks.insert(peer_ip, interval_in_secs, self.capacity(), OffsetDateTime::now_utc())
     }
 
     /// Inserts a transaction ID into the cache, returning the previously seen timestamp if it existed.
@@ -180,7 +218,7 @@ impl<N: Network> Cache<N> {
         peer_ip: SocketAddr,
         transaction: N::TransactionID,
     ) -> Option<OffsetDateTime> {
-        Self::refresh_and_insert(&self.seen_outbound_transactions, (peer_ip, transaction))
+        Self::refresh_and_insert(&self.seen_outbound_transactions, (peer_ip, transaction), self.capacity())
     }
 
     /// Returns `true` if the cache contains a peer request from the given peer.
@@ -201,50 +239,122 @@ impl<N: Network> Cache<N> {
     /// Removes all cache entries applicable to the given key.
     pub fn clear_peer_entries(&self, peer_ip: SocketAddr) {
         self.seen_outbound_block_requests.write().remove(&peer_ip);
+        self.peer_bandwidth.write().remove(&peer_ip);
     }
 }
 
 impl<N: Network> Cache<N> {
-    /// Insert a new timestamp for the given key, returning the number of recent entries.
-    fn retain_and_insert<K: Eq + Hash + Clone>(
-        map: &RwLock<HashMap<K, VecDeque<OffsetDateTime>>>,
-        key: K,
-        interval_in_secs: i64,
-    ) -> usize {
-        // Fetch the current timestamp.
-        let now = OffsetDateTime::now_utc();
-
-        let mut map_write = map.write();
-        // Load the entry for the key.
-        let timestamps = map_write.entry(key).or_default();
-        // Insert the new timestamp.
-        timestamps.push_back(now);
-        // Retain only the timestamps that are within the recent interval.
-        while timestamps.front().map_or(false, |t| now - *t > Duration::seconds(interval_in_secs)) {
-            timestamps.pop_front();
+    /// Returns the counters of the bytes sent to and received from the given peer IP, initializing them if needed.
+    ///
+    /// Note: The counters are handed to the codecs of the connection, which update them without any lock.
+    pub fn bandwidth_counters(&self, peer_ip: SocketAddr) -> BandwidthCounters {
+        if let Some(counters) = self.peer_bandwidth.read().get(&peer_ip) {
+            return counters.clone();
         }
-        // Return the frequency of recent requests.
-        timestamps.len()
+        self.peer_bandwidth.write().entry(peer_ip).or_default().clone()
     }
 
-    /// Returns the number of recent entries.
-    fn retain<K: Eq + Hash + Clone>(
-        map: &RwLock<HashMap<K, VecDeque<OffsetDateTime>>>,
-        key: K,
-        interval_in_secs: i64,
-    ) -> usize {
-        // Fetch the current timestamp.
-        let now = OffsetDateTime::now_utc();
+    /// Returns the number of bytes sent to and received from the given peer IP, since it connected.
+    pub fn bandwidth(&self, peer_ip: &SocketAddr) -> (u64, u64) {
+        self.peer_bandwidth
+            .read()
+            .get(peer_ip)
+            .map(|(sent, received)| (sent.load(Ordering::Relaxed), received.load(Ordering::Relaxed)))
+            .unwrap_or_default()
+    }
+}
 
-        let mut map_write = map.write();
-        // Load the entry for the key.
-        let timestamps = map_write.entry(key).or_default();
-        // Retain only the timestamps that are within the recent interval.
-        while timestamps.front().map_or(false, |t| now - *t > Duration::seconds(interval_in_secs)) {
-            timestamps.pop_front();
+impl<N: Network> Cache<N> {
+    /// Returns the capacity of each cache of recently-seen solutions and transactions.
+    pub fn capacity(&self) -> usize {
+        self.capacity.load(Ordering::Relaxed)
+    }
+
+    /// Sets the capacity of each cache of recently-seen solutions and transactions,
+    /// evicting the oldest entries beyond it.
+    pub fn set_capacity(&self, capacity: usize) {
+        // Ensure the cache can always hold the latest entry.
+        let capacity = capacity.max(2);
+        self.capacity.store(capacity, Ordering::Relaxed);
+        Self::refresh(&self.seen_inbound_solutions, capacity);
+        Self::refresh(&self.seen_inbound_transactions, capacity);
+        Self::refresh(&self.seen_outbound_solutions, capacity);
+        Self::refresh(&self.seen_outbound_transactions, capacity);
+    }
+
+    /// Cleans up the caches of recent timestamps that are due for it, and returns the time until the next cleanup.
+    ///
+    /// Each cache is cleaned up on its own schedule, which is adapted to its insert rate.
+    pub fn cleanup(&self) -> std::time::Duration {
+        let now = OffsetDateTime::now_utc();
+        let next_cleanup = self.cleanup_at(now);
+        (next_cleanup - now).try_into().unwrap_or_default()
+    }
+
+    /// Cleans up the caches of recent timestamps that are due for it at the given time,
+    /// and returns the time of the next cleanup.
+    fn cleanup_at(&self, now: OffsetDateTime) -> OffsetDateTime {
+        let capacity = self.capacity();
+        [
+            self.seen_inbound_connections.cleanup_if_due(capacity, now),
+            self.seen_inbound_messages.cleanup_if_due(capacity, now),
+            self.seen_inbound_puzzle_requests.cleanup_if_due(capacity, now),
+            self.seen_inbound_block_requests.cleanup_if_due(capacity, now),
+            self.seen_outbound_solution_acks.cleanup_if_due(capacity, now),
+        ]
+        .into_iter()
+        .min()
+        .unwrap_or(now + MAX_CLEANUP_INTERVAL)
+    }
+
+    /// Updates the occupancy metrics of each cache.
+    #[cfg(feature = "metrics")]
+    pub fn update_metrics(&self) {
+        let occupancy = [
+            ("inbound_connections", self.seen_inbound_connections.read().len()),
+            ("inbound_messages", self.seen_inbound_messages.read().len()),
+            ("inbound_puzzle_requests", self.seen_inbound_puzzle_requests.read().len()),
+            ("inbound_block_requests", self.seen_inbound_block_requests.read().len()),
+            ("inbound_solutions", self.seen_inbound_solutions.read().len()),
+            ("inbound_transactions", self.seen_inbound_transactions.read().len()),
+            ("outbound_solutions", self.seen_outbound_solutions.read().len()),
+            ("outbound_transactions", self.seen_outbound_transactions.read().len()),
+            ("outbound_solution_acks", self.seen_outbound_solution_acks.read().len()),
+        ];
+        for (cache, len) in occupancy {
+            metrics::gauge_label(metrics::router::CACHE_OCCUPANCY, "cache", cache.to_string(), len as f64);
         }
-        // Return the frequency of recent requests.
-        timestamps.len()
+    }
+
+    /// Returns the estimated memory in bytes of the cache.
+    pub fn memory_usage(&self) -> u64 {
+        // Estimate the caches of recently-seen solutions and transactions.
+        let solutions = self.seen_inbound_solutions.read().len() + self.seen_outbound_solutions.read().len();
+        let transactions = self.seen_inbound_transactions.read().len() + self.seen_outbound_transactions.read().len();
+        // Estimate the maps of outbound requests per peer.
+        let block_requests = self.seen_outbound_block_requests.read();
+        let num_block_requests = block_requests.values().map(HashSet::len).sum::<usize>();
+        let counters = self.seen_outbound_puzzle_requests.read().len() + self.seen_outbound_peer_requests.read().len();
+
+        Self::estimate_timestamps(&self.seen_inbound_connections)
+            + Self::estimate_timestamps(&self.seen_inbound_messages)
+            + Self::estimate_timestamps(&self.seen_inbound_puzzle_requests)
+            + Self::estimate_timestamps(&self.seen_inbound_block_requests)
+            + Self::estimate_timestamps(&self.seen_outbound_solution_acks)
+            + estimate_entries::<SolutionKey<N>, OffsetDateTime>(solutions)
+            + estimate_entries::<TransactionKey<N>, OffsetDateTime>(transactions)
+            + estimate_entries::<SocketAddr, HashSet<BlockRequest>>(block_requests.len())
+            + estimate_entries::<BlockRequest, ()>(num_block_requests)
+            + estimate_entries::<SocketAddr, u32>(counters)
+            + estimate_entries::<SocketAddr, BandwidthCounters>(self.peer_bandwidth.read().len())
+    }
+
+    /// Returns the estimated memory in bytes of the given map of recent timestamps.
+    fn estimate_timestamps<K>(map: &RecentTimestamps<K>) -> u64 {
+        let map = map.read();
+        let num_timestamps = map.values().map(VecDeque::len).sum::<usize>();
+        estimate_entries::<K, VecDeque<OffsetDateTime>>(map.len())
+            + estimate_entries::<OffsetDateTime, ()>(num_timestamps)
     }
 
     /// Increments the key's counter in the map, returning the updated counter.
@@ -273,10 +383,10 @@ impl<N: Network> Cache<N> {
         value
     }
 
-    /// Updates the map by enforcing the maximum cache size.
-    fn refresh<K: Eq + Hash, V>(map: &RwLock<LinkedHashMap<K, V>>) {
+    /// Updates the map by enforcing the given cache capacity.
+    fn refresh<K: Eq + Hash, V>(map: &RwLock<LinkedHashMap<K, V>>, capacity: usize) {
         let mut map_write = map.write();
-        while map_write.len() >= MAX_CACHE_SIZE {
+        while map_write.len() >= capacity {
             map_write.pop_front();
         }
     }
@@ -286,16 +396,152 @@ impl<N: Network> Cache<N> {
     fn refresh_and_insert<K: Eq + Hash>(
         map: &RwLock<LinkedHashMap<K, OffsetDateTime>>,
         key: K,
+        capacity: usize,
     ) -> Option<OffsetDateTime> {
         // Insert the key, and return the previous timestamp if it existed.
         let previous_timestamp = map.write().insert(key, OffsetDateTime::now_utc());
         // Refresh the cache.
-        Self::refresh(map);
+        Self::refresh(map, capacity);
         // Return the previous timestamp.
         previous_timestamp
     }
 }
 
+/// A map of keys to their recent timestamps, which is cleaned up on a schedule adapted to its insert rate.
+#[derive(Debug)]
+struct RecentTimestamps<K> {
+    /// The map of keys to their recent timestamps.
+    map: RwLock<HashMap<K, VecDeque<OffsetDateTime>>>,
+    /// The largest interval of the inserted timestamps in seconds, after which they expire.
+    ttl_in_secs: AtomicI64,
+    /// The number of inserts since the last cleanup.
+    num_inserts: AtomicUsize,
+    /// The times of the last and the next cleanups.
+    schedule: RwLock<(OffsetDateTime, OffsetDateTime)>,
+}
+
+impl<K> Default for RecentTimestamps<K> {
+    fn default() -> Self {
+        let now = OffsetDateTime::now_utc();
+        Self {
+            map: Default::default(),
+            ttl_in_secs: Default::default(),
+            num_inserts: Default::default(),
+            schedule: RwLock::new((now, now + MAX_CLEANUP_INTERVAL)),
+        }
+    }
+}
+
+impl<K> Deref for RecentTimestamps<K> {
+    type Target = RwLock<HashMap<K, VecDeque<OffsetDateTime>>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.map
+    }
+}
+
+impl<K: Eq + Hash + Clone> RecentTimestamps<K> {
+    /// Inserts a new timestamp for the given key, returning the number of recent entries.
+    ///
+    /// If the keys exceed the capacity by more than the tolerance, the map is cleaned up right away.
+    fn insert(&self, key: K, interval_in_secs: i64, capacity: usize, now: OffsetDateTime) -> usize {
+        self.ttl_in_secs.fetch_max(interval_in_secs, Ordering::Relaxed);
+        self.num_inserts.fetch_add(1, Ordering::Relaxed);
+
+        let mut map_write = self.map.write();
+        // Load the entry for the key.
+        let timestamps = map_write.entry(key).or_default();
+        // Insert the new timestamp.
+        timestamps.push_back(now);
+        // Retain only the timestamps that are within the recent interval.
+        while timestamps.front().map_or(false, |t| now - *t > Duration::seconds(interval_in_secs)) {
+            timestamps.pop_front();
+        }
+        let num_timestamps = timestamps.len();
+
+        // Clean up the map, if it outgrew its capacity in between two cleanups.
+        let exceeds_capacity = map_write.len() > capacity + capacity_tolerance(capacity);
+        drop(map_write);
+        if exceeds_capacity {
+            self.cleanup(capacity, now);
+        }
+        num_timestamps
+    }
+
+    /// Returns the number of recent entries for the given key.
+    fn count(&self, key: &K, interval_in_secs: i64, now: OffsetDateTime) -> usize {
+        let mut map_write = self.map.write();
+        let Some(timestamps) = map_write.get_mut(key) else {
+            return 0;
+        };
+        // Retain only the timestamps that are within the recent interval.
+        while timestamps.front().map_or(false, |t| now - *t > Duration::seconds(interval_in_secs)) {
+            timestamps.pop_front();
+        }
+        // Return the frequency of recent requests.
+        timestamps.len()
+    }
+
+    /// Cleans up the map if it is due for it at the given time, and returns the time of the next cleanup.
+    fn cleanup_if_due(&self, capacity: usize, now: OffsetDateTime) -> OffsetDateTime {
+        let next_cleanup = self.schedule.read().1;
+        match next_cleanup <= now {
+            true => self.cleanup(capacity, now),
+            false => next_cleanup,
+        }
+    }
+
+    /// Removes the expired timestamps and the keys left without any, evicts the least recently seen keys
+    /// beyond the capacity, and schedules the next cleanup, which is returned.
+    fn cleanup(&self, capacity: usize, now: OffsetDateTime) -> OffsetDateTime {
+        let ttl = Duration::seconds(self.ttl_in_secs.load(Ordering::Relaxed));
+
+        let mut map_write = self.map.write();
+        map_write.retain(|_, timestamps| {
+            while timestamps.front().map_or(false, |t| now - *t > ttl) {
+                timestamps.pop_front();
+            }
+            !timestamps.is_empty()
+        });
+        if map_write.len() > capacity {
+            let mut last_seen: Vec<_> =
+                map_write.iter().filter_map(|(key, timestamps)| Some((*timestamps.back()?, key.clone()))).collect();
+            last_seen.sort_unstable_by_key(|(timestamp, _)| *timestamp);
+            for (_, key) in &last_seen[..map_write.len() - capacity] {
+                map_write.remove(key);
+            }
+        }
+        let len = map_write.len();
+        drop(map_write);
+
+        // Schedule the next cleanup, based on the insert rate since the last one.
+        let mut schedule = self.schedule.write();
+        let num_inserts = self.num_inserts.swap(0, Ordering::Relaxed);
+        let interval = cleanup_interval(len, capacity, num_inserts, now - schedule.0, ttl);
+        *schedule = (now, now + interval);
+        schedule.1
+    }
+}
+
+/// Returns the number of keys a cache of recent timestamps may exceed its capacity by, in between two cleanups.
+const fn capacity_tolerance(capacity: usize) -> usize {
+    capacity / 16
+}
+
+/// Returns the interval until the next cleanup of a cache holding `len` keys out of `capacity`,
+/// which received `num_inserts` in the `elapsed` time since its last cleanup, and whose entries expire after `ttl`.
+fn cleanup_interval(len: usize, capacity: usize, num_inserts: usize, elapsed: Duration, ttl: Duration) -> Duration {
+    // An idle cache only needs a cleanup once its entries expired.
+    let mut interval = if len > 0 { ttl } else { MAX_CLEANUP_INTERVAL };
+    // A busy cache needs a cleanup before it reaches its capacity, keeping half of the remaining time as a margin.
+    if num_inserts > 0 {
+        let rate = num_inserts as f64 / elapsed.as_seconds_f64().max(MIN_CLEANUP_INTERVAL.as_seconds_f64());
+        let until_full = capacity.saturating_sub(len) as f64 / rate;
+        interval = interval.min(Duration::seconds_f64(until_full / 2.0));
+    }
+    interval.clamp(MIN_CLEANUP_INTERVAL, MAX_CLEANUP_INTERVAL)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -390,6 +636,22 @@ mod tests {
 
         // Check that the cache still contains the solution.
         assert_eq!(cache.seen_outbound_solutions.read().len(), 1);
+
+        // Check that the solution is only recorded for the peer it was sent to.
+        assert!(cache.contains_outbound_solution(peer_ip, solution_id));
+        assert!(!cache.contains_outbound_solution(SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 1235), solution_id));
+    }
+
+    #[test]
+    fn test_outbound_solution_ack() {
+        let cache = Cache::<CurrentNetwork>::default();
+        let peer_ip = SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 1234);
+
+        // Check that the solution acknowledgments are counted per peer.
+        assert_eq!(cache.insert_outbound_solution_ack(peer_ip), 1);
+        assert_eq!(cache.insert_outbound_solution_ack(peer_ip), 2);
+        assert_eq!(cache.insert_outbound_solution_ack(SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 1235)), 1);
+        assert_eq!(cache.seen_outbound_solution_acks.read().len(), 2);
     }
 
     #[test]
@@ -444,4 +706,124 @@ mod tests {
         // Check the cache is empty.
         assert!(!cache.contains_outbound_peer_request(peer_ip));
     }
+
+    #[test]
+    fn test_bandwidth() {
+        let cache = Cache::<CurrentNetwork>::default();
+        let peer_ip = SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 1234);
+        assert_eq!(cache.bandwidth(&peer_ip), (0, 0));
+
+        // Update the counters, as the codecs of a connection do.
+        let (sent, received) = cache.bandwidth_counters(peer_ip);
+        sent.fetch_add(100, Ordering::Relaxed);
+        received.fetch_add(40, Ordering::Relaxed);
+        cache.bandwidth_counters(peer_ip).1.fetch_add(2, Ordering::Relaxed);
+        assert_eq!(cache.bandwidth(&peer_ip), (100, 42));
+
+        // Ensure the counters are reset once the peer disconnects.
+        cache.clear_peer_entries(peer_ip);
+        assert_eq!(cache.bandwidth(&peer_ip), (0, 0));
+        sent.fetch_add(100, Ordering::Relaxed);
+        assert_eq!(cache.bandwidth(&peer_ip), (0, 0));
+    }
+
+    #[test]
+    fn test_capacity_and_memory_usage() {
+        let cache = Cache::<CurrentNetwork>::default();
+        assert_eq!(cache.memory_usage(), 0);
+
+        // Fill the caches of recently-seen solutions.
+        for i in 0..1_000 {
+            let peer_ip = SocketAddr::new(Ipv4Addr::LOCALHOST.into(), i);
+            cache.insert_inbound_solution(peer_ip, SolutionID::<CurrentNetwork>::from(i as u64));
+            cache.insert_outbound_solution(peer_ip, SolutionID::<CurrentNetwork>::from(i as u64));
+        }
+        // Ensure the memory usage tracks the entries.
+        let usage = cache.memory_usage();
+        assert_eq!(usage, estimate_entries::<SolutionKey<CurrentNetwork>, OffsetDateTime>(2_000));
+
+        // Ensure shrinking the capacity evicts the oldest entries, and reduces the memory usage.
+        cache.set_capacity(101);
+        assert_eq!(cache.seen_inbound_solutions.read().len(), 100);
+        assert!(cache.seen_inbound_solutions.read().contains_key(&(
+            SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 999),
+            SolutionID::<CurrentNetwork>::from(999)
+        )));
+        assert_eq!(cache.memory_usage(), usage / 10);
+
+        // Ensure the capacity is enforced on insertion.
+        let peer_ip = SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 1_000);
+        cache.insert_inbound_solution(peer_ip, SolutionID::<CurrentNetwork>::from(1_000));
+        assert_eq!(cache.seen_inbound_solutions.read().len(), 100);
+    }
+
+    #[test]
+    fn test_cleanup_interval() {
+        let ttl = Duration::seconds(60);
+        // Ensure an idle cache is cleaned up once its entries expired, or as late as possible if it is empty.
+        assert_eq!(cleanup_interval(0, 1_000, 0, Duration::seconds(10), ttl), MAX_CLEANUP_INTERVAL);
+        assert_eq!(cleanup_interval(10, 1_000, 0, Duration::seconds(10), ttl), ttl);
+        // Ensure a busy cache is cleaned up sooner, within the bounds.
+        let interval = cleanup_interval(100, 1_000, 100, Duration::seconds(10), ttl);
+        assert_eq!(interval, Duration::seconds(45));
+        assert_eq!(cleanup_interval(100, 1_000, 100_000, Duration::seconds(10), ttl), MIN_CLEANUP_INTERVAL);
+        assert_eq!(cleanup_interval(0, 1_000, 1, Duration::hours(1), Duration::hours(1)), MAX_CLEANUP_INTERVAL);
+    }
+
+    #[test]
+    fn test_cleanup_schedule_adapts_to_insert_rate() {
+        const CAPACITY: usize = 1_000;
+        const INTERVAL_IN_SECS: i64 = 60;
+        let start = OffsetDateTime::now_utc();
+
+        // Simulate a low insert rate of one new key every 5 seconds, for 2 minutes.
+        let timestamps = RecentTimestamps::<u32>::default();
+        assert_eq!(timestamps.cleanup(CAPACITY, start) - start, MAX_CLEANUP_INTERVAL);
+        for i in 0..24 {
+            let now = start + Duration::seconds(5 * i64::from(i));
+            timestamps.insert(i, INTERVAL_IN_SECS, CAPACITY, now);
+            timestamps.cleanup_if_due(CAPACITY, now);
+        }
+        // Ensure the cleanups are as infrequent as the expiry of the entries allows.
+        let now = start + Duration::seconds(120);
+        assert_eq!(timestamps.cleanup(CAPACITY, now) - now, Duration::seconds(INTERVAL_IN_SECS));
+        // Ensure the cleanups become as infrequent as possible, once the entries expired.
+        let now = now + Duration::seconds(INTERVAL_IN_SECS + 1);
+        assert_eq!(timestamps.cleanup(CAPACITY, now) - now, MAX_CLEANUP_INTERVAL);
+        assert!(timestamps.read().is_empty());
+
+        // Simulate a high insert rate of 10 new keys every millisecond, for 3 seconds.
+        let timestamps = RecentTimestamps::<u32>::default();
+        timestamps.cleanup(CAPACITY, start);
+        for i in 0..30_000 {
+            let now = start + Duration::milliseconds(i64::from(i / 10));
+            timestamps.insert(i, INTERVAL_IN_SECS, CAPACITY, now);
+            timestamps.cleanup_if_due(CAPACITY, now);
+            // Ensure the capacity is never exceeded by more than the tolerance.
+            assert!(timestamps.read().len() <= CAPACITY + capacity_tolerance(CAPACITY));
+            // Ensure the cleanups become as frequent as possible, once the load is observed.
+            if i >= 2 * CAPACITY as u32 {
+                let (last_cleanup, next_cleanup) = *timestamps.schedule.read();
+                assert_eq!(next_cleanup - last_cleanup, MIN_CLEANUP_INTERVAL);
+            }
+        }
+        // Ensure the most recently seen keys are the ones retained.
+        assert!(timestamps.read().contains_key(&29_999));
+    }
+
+    #[test]
+    fn test_cleanup_removes_expired_keys() {
+        let cache = Cache::<CurrentNetwork>::default();
+        let peer_ip = SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 1234);
+        let now = OffsetDateTime::now_utc();
+
+        // Insert a block request, and ensure it is retained while no cleanup is due.
+        cache.seen_inbound_block_requests.insert(peer_ip, 60, cache.capacity(), now);
+        assert!(cache.cleanup_at(now + Duration::seconds(10)) > now + Duration::seconds(10));
+        assert_eq!(cache.seen_inbound_block_requests.read().len(), 1);
+
+        // Ensure the expired block request, and its peer, are removed once a cleanup is due.
+        cache.cleanup_at(now + MAX_CLEANUP_INTERVAL);
+        assert_eq!(cache.seen_inbound_block_requests.read().len(), 0);
+    }
 }
@@ -0,0 +1,157 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::{Duration, Instant};
+
+/// The metadata for each candidate peer.
+#[derive(Copy, Clone, Debug)]
+pub struct CandidatePeer {
+    /// The timestamp of when the candidate peer was last inserted or re-learned.
+    last_updated: Instant,
+    /// The flag indicating whether the node has ever successfully connected to the candidate peer.
+    has_connected: bool,
+    /// The timestamp until which the candidate peer is not dialed, if it recently announced it is shutting down.
+    cooldown_until: Option<Instant>,
+}
+
+impl CandidatePeer {
+    /// Initializes a new instance of `CandidatePeer`.
+    pub fn new(has_connected: bool) -> Self {
+        Self { last_updated: Instant::now(), has_connected, cooldown_until: None }
+    }
+
+    /// Initializes a new instance of `CandidatePeer`, last inserted or re-learned the given time ago.
+    pub fn with_age(has_connected: bool, age: Duration) -> Self {
+        let now = Instant::now();
+        Self { last_updated: now.checked_sub(age).unwrap_or(now), has_connected, cooldown_until: None }
+    }
+
+    /// Returns the candidate peer, which is not dialed until the given cooldown has elapsed.
+    pub fn with_cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown_until = Instant::now().checked_add(cooldown);
+        self
+    }
+
+    /// Returns the timestamp of when the candidate peer was last inserted or re-learned.
+    pub const fn last_updated(&self) -> Instant {
+        self.last_updated
+    }
+
+    /// Returns `true` if the node has ever successfully connected to the candidate peer.
+    pub const fn has_connected(&self) -> bool {
+        self.has_connected
+    }
+
+    /// Returns the time elapsed since the candidate peer was last inserted or re-learned.
+    pub fn age(&self) -> Duration {
+        self.last_updated.elapsed()
+    }
+
+    /// Returns `true` if the candidate peer is still cooling down at the given time, and should not be dialed.
+    pub fn is_cooling_down(&self, now: Instant) -> bool {
+        self.cooldown_until.is_some_and(|cooldown_until| now < cooldown_until)
+    }
+
+    /// Returns `true` if the candidate peer is older than the applicable maximum age.
+    /// Candidate peers that have successfully connected in the past are subject to `max_age_connected`,
+    /// while those that never connected are subject to `max_age`.
+    pub fn is_expired(&self, now: Instant, max_age: Duration, max_age_connected: Duration) -> bool {
+        let age = now.saturating_duration_since(self.last_updated);
+        match self.has_connected {
+            true => age > max_age_connected,
+            false => age > max_age,
+        }
+    }
+}
+
+impl CandidatePeer {
+    /// Refreshes the last updated timestamp of the candidate peer.
+    pub fn refresh(&mut self) {
+        self.last_updated = Instant::now();
+    }
+
+    /// Marks the candidate peer as having successfully connected in the past.
+    pub fn set_has_connected(&mut self) {
+        self.has_connected = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAX_AGE: Duration = Duration::from_secs(60);
+    const MAX_AGE_CONNECTED: Duration = Duration::from_secs(600);
+
+    #[test]
+    fn test_is_expired_at_boundary() {
+        let candidate = CandidatePeer::new(false);
+        let start = candidate.last_updated();
+
+        // The candidate is not expired exactly at the maximum age.
+        assert!(!candidate.is_expired(start + MAX_AGE, MAX_AGE, MAX_AGE_CONNECTED));
+        // The candidate is expired right after the maximum age.
+        assert!(candidate.is_expired(start + MAX_AGE + Duration::from_millis(1), MAX_AGE, MAX_AGE_CONNECTED));
+    }
+
+    #[test]
+    fn test_is_expired_previously_connected() {
+        let candidate = CandidatePeer::new(true);
+        let start = candidate.last_updated();
+
+        // The previously-connected candidate is retained beyond the regular maximum age.
+        assert!(!candidate.is_expired(start + MAX_AGE + Duration::from_secs(1), MAX_AGE, MAX_AGE_CONNECTED));
+        // The previously-connected candidate is not expired exactly at its maximum age.
+        assert!(!candidate.is_expired(start + MAX_AGE_CONNECTED, MAX_AGE, MAX_AGE_CONNECTED));
+        // The previously-connected candidate is expired right after its maximum age.
+        assert!(candidate.is_expired(start + MAX_AGE_CONNECTED + Duration::from_millis(1), MAX_AGE, MAX_AGE_CONNECTED));
+    }
+
+    #[test]
+    fn test_refresh() {
+        let mut candidate = CandidatePeer::new(false);
+        let start = candidate.last_updated();
+
+        // Wait, then refresh the candidate.
+        std::thread::sleep(Duration::from_millis(10));
+        candidate.refresh();
+
+        // Ensure the timestamp moved forward, and the candidate is no longer expired at the original boundary.
+        assert!(candidate.last_updated() > start);
+        assert!(!candidate.is_expired(start + MAX_AGE + Duration::from_millis(1), MAX_AGE, MAX_AGE_CONNECTED));
+
+        // Ensure the connection flag is sticky.
+        candidate.set_has_connected();
+        assert!(candidate.has_connected());
+    }
+
+    #[test]
+    fn test_cooldown() {
+        let now = Instant::now();
+        // Ensure a candidate is not cooling down by default.
+        assert!(!CandidatePeer::new(true).is_cooling_down(now));
+
+        // Ensure a candidate is cooling down until its cooldown elapses.
+        let candidate = CandidatePeer::new(true).with_cooldown(Duration::from_secs(5));
+        assert!(candidate.is_cooling_down(now));
+        assert!(candidate.is_cooling_down(now + Duration::from_secs(4)));
+        assert!(!candidate.is_cooling_down(now + Duration::from_secs(6)));
+
+        // Ensure a refresh does not cut the cooldown short.
+        let mut candidate = candidate;
+        candidate.refresh();
+        assert!(candidate.is_cooling_down(now));
+    }
+}
@@ -0,0 +1,214 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use indexmap::IndexMap;
+use parking_lot::Mutex;
+use std::{
+    collections::VecDeque,
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
+
+/// The number of most recent unconfirmed transmissions of a peer that the duplicate ratio is computed over.
+/// A peer is only judged once it has sent this many unconfirmed transmissions.
+pub const DUPLICATE_WINDOW_SIZE: usize = 500;
+/// The ratio of duplicate unconfirmed transmissions in the window above which a peer is put on cooldown.
+pub const MAX_DUPLICATE_RATIO: f64 = 0.95;
+/// The time in seconds for which the unconfirmed transmissions of a peer on cooldown are ignored.
+pub const DUPLICATE_COOLDOWN_IN_SECS: u64 = 300;
+/// The number of offenses at which a peer is disconnected, instead of put on cooldown.
+pub const MAX_DUPLICATE_OFFENSES: u32 = 3;
+/// The maximum number of peers whose unconfirmed transmissions are tracked.
+pub const MAX_TRACKED_DUPLICATE_PEERS: usize = 1_000;
+
+/// The outcome of recording an unconfirmed transmission from a peer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DuplicateOutcome {
+    /// The peer is within the limits.
+    Ok,
+    /// The peer has exceeded the duplicate ratio, and is put on cooldown.
+    Cooldown { num_offenses: u32 },
+    /// The peer has exceeded the duplicate ratio too many times, and is to be disconnected.
+    Disconnect { num_offenses: u32 },
+}
+
+/// The record of the recent unconfirmed transmissions of a peer.
+#[derive(Clone, Debug, Default)]
+struct DuplicateStats {
+    /// Whether each of the most recent unconfirmed transmissions was a duplicate, from the oldest.
+    outcomes: VecDeque<bool>,
+    /// The number of duplicates in `outcomes`.
+    num_duplicates: usize,
+    /// The time until which the unconfirmed transmissions of the peer are ignored, if any.
+    cooldown_until: Option<Instant>,
+    /// The number of times the peer has exceeded the duplicate ratio.
+    num_offenses: u32,
+}
+
+impl DuplicateStats {
+    /// Returns the ratio of duplicates in the window, if any transmissions were recorded.
+    fn ratio(&self) -> Option<f64> {
+        match self.outcomes.is_empty() {
+            true => None,
+            false => Some(self.num_duplicates as f64 / self.outcomes.len() as f64),
+        }
+    }
+}
+
+/// The tracker of peers that repeatedly send unconfirmed transmissions that were already seen.
+///
+/// The record of a peer outlives its connection, so that a peer cannot reset its offenses by reconnecting.
+#[derive(Default)]
+pub struct DuplicateTransmissions {
+    /// The record of each peer, in the order they were last recorded.
+    stats: Mutex<IndexMap<SocketAddr, DuplicateStats>>,
+}
+
+impl DuplicateTransmissions {
+    /// Returns `true` if the unconfirmed transmissions of the given peer are to be ignored.
+    pub fn is_cooling_down(&self, peer_ip: &SocketAddr, now: Instant) -> bool {
+        self.stats
+            .lock()
+            .get(peer_ip)
+            .and_then(|stats| stats.cooldown_until)
+            .is_some_and(|cooldown_until| now < cooldown_until)
+    }
+
+    /// Records an unconfirmed transmission from the given peer, and whether it was a duplicate.
+    pub fn insert(&self, peer_ip: SocketAddr, is_duplicate: bool, now: Instant) -> DuplicateOutcome {
+        let mut stats = self.stats.lock();
+        // Move the peer to the back, so that the least recently recorded peers are evicted first.
+        let mut peer_stats = stats.shift_remove(&peer_ip).unwrap_or_default();
+        // Update the window.
+        peer_stats.outcomes.push_back(is_duplicate);
+        peer_stats.num_duplicates += is_duplicate as usize;
+        if peer_stats.outcomes.len() > DUPLICATE_WINDOW_SIZE {
+            peer_stats.num_duplicates -= peer_stats.outcomes.pop_front().unwrap_or_default() as usize;
+        }
+        // Judge the peer, once the window is full.
+        let mut outcome = DuplicateOutcome::Ok;
+        if peer_stats.outcomes.len() >= DUPLICATE_WINDOW_SIZE
+            && peer_stats.ratio().is_some_and(|ratio| ratio > MAX_DUPLICATE_RATIO)
+        {
+            peer_stats.num_offenses += 1;
+            let num_offenses = peer_stats.num_offenses;
+            outcome = match num_offenses >= MAX_DUPLICATE_OFFENSES {
+                true => DuplicateOutcome::Disconnect { num_offenses },
+                false => DuplicateOutcome::Cooldown { num_offenses },
+            };
+            // Start the cooldown, and judge the peer afresh after it.
+            peer_stats.cooldown_until = Some(now + Duration::from_secs(DUPLICATE_COOLDOWN_IN_SECS));
+            peer_stats.outcomes.clear();
+            peer_stats.num_duplicates = 0;
+        }
+        stats.insert(peer_ip, peer_stats);
+        // Evict the least recently recorded peers.
+        while stats.len() > MAX_TRACKED_DUPLICATE_PEERS {
+            stats.shift_remove_index(0);
+        }
+        outcome
+    }
+
+    /// Returns the ratio of duplicates among the recent unconfirmed transmissions of the given peer, if any.
+    pub fn ratio(&self, peer_ip: &SocketAddr) -> Option<f64> {
+        self.stats.lock().get(peer_ip).and_then(DuplicateStats::ratio)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the sample peer with the given index.
+    fn sample_peer(index: u16) -> SocketAddr {
+        SocketAddr::from(([127, 0, 0, 1], 4130 + index))
+    }
+
+    /// Sends the given number of unconfirmed transmissions from the peer, skipping them while it is on cooldown,
+    /// and returns the outcomes that were not `Ok`.
+    fn send(
+        duplicates: &DuplicateTransmissions,
+        peer_ip: SocketAddr,
+        num_transmissions: usize,
+        is_duplicate: impl Fn(usize) -> bool,
+        now: Instant,
+    ) -> Vec<DuplicateOutcome> {
+        (0..num_transmissions)
+            .filter(|_| !duplicates.is_cooling_down(&peer_ip, now))
+            .map(|i| duplicates.insert(peer_ip, is_duplicate(i), now))
+            .filter(|outcome| *outcome != DuplicateOutcome::Ok)
+            .collect()
+    }
+
+    #[test]
+    fn test_duplicate_sender_is_cooled_down() {
+        let duplicates = DuplicateTransmissions::default();
+        let peer_ip = sample_peer(0);
+        let mut now = Instant::now();
+
+        // Ensure a pure duplicate sender trips the cooldown once the window is full.
+        let outcomes = send(&duplicates, peer_ip, DUPLICATE_WINDOW_SIZE * 2, |_| true, now);
+        assert_eq!(outcomes, vec![DuplicateOutcome::Cooldown { num_offenses: 1 }]);
+        assert!(duplicates.is_cooling_down(&peer_ip, now));
+        assert!(!duplicates.is_cooling_down(&sample_peer(1), now));
+
+        // Ensure the cooldown expires, and repeat offenses escalate to a disconnect.
+        now += Duration::from_secs(DUPLICATE_COOLDOWN_IN_SECS);
+        assert!(!duplicates.is_cooling_down(&peer_ip, now));
+        let outcomes = send(&duplicates, peer_ip, DUPLICATE_WINDOW_SIZE, |_| true, now);
+        assert_eq!(outcomes, vec![DuplicateOutcome::Cooldown { num_offenses: 2 }]);
+        now += Duration::from_secs(DUPLICATE_COOLDOWN_IN_SECS);
+        let outcomes = send(&duplicates, peer_ip, DUPLICATE_WINDOW_SIZE, |_| true, now);
+        assert_eq!(outcomes, vec![DuplicateOutcome::Disconnect { num_offenses: MAX_DUPLICATE_OFFENSES }]);
+    }
+
+    #[test]
+    fn test_mixed_sender_is_not_cooled_down() {
+        let duplicates = DuplicateTransmissions::default();
+        let peer_ip = sample_peer(0);
+        let now = Instant::now();
+
+        // Ensure a sender with 1 in 10 novel transmissions never trips the cooldown.
+        let outcomes = send(&duplicates, peer_ip, DUPLICATE_WINDOW_SIZE * 10, |i| i % 10 != 0, now);
+        assert!(outcomes.is_empty());
+        assert!(!duplicates.is_cooling_down(&peer_ip, now));
+        assert_eq!(duplicates.ratio(&peer_ip), Some(0.9));
+    }
+
+    #[test]
+    fn test_low_volume_sender_is_not_cooled_down() {
+        let duplicates = DuplicateTransmissions::default();
+        let peer_ip = sample_peer(0);
+        let now = Instant::now();
+
+        // Ensure a fresh peer has no ratio.
+        assert_eq!(duplicates.ratio(&peer_ip), None);
+        // Ensure a pure duplicate sender is not judged before the window is full.
+        let outcomes = send(&duplicates, peer_ip, DUPLICATE_WINDOW_SIZE - 1, |_| true, now);
+        assert!(outcomes.is_empty());
+        assert!(!duplicates.is_cooling_down(&peer_ip, now));
+        assert_eq!(duplicates.ratio(&peer_ip), Some(1.0));
+    }
+
+    #[test]
+    fn test_tracked_peers_are_bounded() {
+        let duplicates = DuplicateTransmissions::default();
+        let now = Instant::now();
+        for i in 0..(MAX_TRACKED_DUPLICATE_PEERS as u16 * 2) {
+            duplicates.insert(sample_peer(i), true, now);
+        }
+        assert_eq!(duplicates.stats.lock().len(), MAX_TRACKED_DUPLICATE_PEERS);
+    }
+}
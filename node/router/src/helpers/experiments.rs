@@ -0,0 +1,261 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::{Result, anyhow, bail, ensure};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::BTreeMap,
+    fmt,
+    net::{IpAddr, SocketAddr},
+    str::FromStr,
+};
+
+/// The experiment on sending early block announcements to the peers that negotiated them.
+pub const BLOCK_ANNOUNCE_EXPERIMENT: &str = "block_announce";
+
+/// The experiments known to the node, with their default rollout.
+pub const KNOWN_EXPERIMENTS: [(&str, Rollout); 1] = [(BLOCK_ANNOUNCE_EXPERIMENT, Rollout::ON)];
+
+/// The share of the peers an experiment is enabled for, as a percentage.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Rollout(u8);
+
+impl Rollout {
+    /// The rollout of a disabled experiment.
+    pub const OFF: Self = Self(0);
+    /// The rollout of an experiment enabled for every peer.
+    pub const ON: Self = Self(100);
+
+    /// Returns the rollout to the given percentage of the peers.
+    pub fn percent(percent: u8) -> Result<Self> {
+        ensure!(percent <= 100, "The rollout percentage ({percent}%) must be at most 100%");
+        Ok(Self(percent))
+    }
+
+    /// Returns the percentage of the peers the experiment is enabled for.
+    pub const fn as_percent(&self) -> u8 {
+        self.0
+    }
+}
+
+impl FromStr for Rollout {
+    type Err = anyhow::Error;
+
+    /// Parses a rollout, i.e. `on`, `off`, or a percentage such as `25%`.
+    fn from_str(rollout: &str) -> Result<Self> {
+        match rollout.trim() {
+            "on" => Ok(Self::ON),
+            "off" => Ok(Self::OFF),
+            rollout => {
+                let percent = rollout
+                    .strip_suffix('%')
+                    .and_then(|percent| percent.parse::<u8>().ok())
+                    .ok_or_else(|| anyhow!("Invalid rollout '{rollout}' (expected 'on', 'off', or a percentage)"))?;
+                Self::percent(percent)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Rollout {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Self::ON => write!(f, "on"),
+            Self::OFF => write!(f, "off"),
+            Self(percent) => write!(f, "{percent}%"),
+        }
+    }
+}
+
+/// The experiment flags of the node, which enable protocol-compatible behaviors for a share of its peers.
+///
+/// The assignment of a peer to an experiment is a deterministic function of the peer IP, the experiment name,
+/// and a local salt, so that a peer remains in (or out of) an experiment, and nodes with different salts select
+/// different peers. The assignment is derived from SHA-256, rather than the hasher of the standard library whose
+/// output may change between Rust releases, so that it also survives the upgrades of the node.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Experiments {
+    /// The map of experiment names to their rollout.
+    rollouts: BTreeMap<&'static str, Rollout>,
+    /// The salt of the peer assignments.
+    salt: u64,
+}
+
+impl Default for Experiments {
+    /// Initializes the known experiments with their default rollout.
+    fn default() -> Self {
+        Self { rollouts: KNOWN_EXPERIMENTS.into_iter().collect(), salt: 0 }
+    }
+}
+
+impl Experiments {
+    /// Salts the peer assignments with the given seed, e.g. the address of the node.
+    pub fn salted(mut self, seed: impl AsRef<[u8]>) -> Self {
+        self.salt = digest_to_u64(&Sha256::digest(seed.as_ref()));
+        self
+    }
+
+    /// Returns the rollout of the given experiment, if it is known.
+    pub fn rollout(&self, name: &str) -> Option<Rollout> {
+        self.rollouts.get(name).copied()
+    }
+
+    /// Returns the experiments and their rollout.
+    pub fn rollouts(&self) -> impl Iterator<Item = (&'static str, Rollout)> + '_ {
+        self.rollouts.iter().map(|(name, rollout)| (*name, *rollout))
+    }
+
+    /// Returns `true` if the given experiment is enabled for the given peer.
+    /// Note: An unknown experiment is never enabled.
+    pub fn enabled_for(&self, peer_ip: SocketAddr, name: &str) -> bool {
+        match self.rollout(name) {
+            None | Some(Rollout::OFF) => false,
+            Some(Rollout::ON) => true,
+            Some(rollout) => self.bucket(peer_ip, name) < rollout.as_percent(),
+        }
+    }
+
+    /// Returns the bucket in `0..100` the given peer is assigned to for the given experiment.
+    fn bucket(&self, peer_ip: SocketAddr, name: &str) -> u8 {
+        let mut hasher = Sha256::new();
+        hasher.update(self.salt.to_le_bytes());
+        match peer_ip.ip() {
+            IpAddr::V4(ip) => hasher.update(ip.octets()),
+            IpAddr::V6(ip) => hasher.update(ip.octets()),
+        }
+        hasher.update(peer_ip.port().to_le_bytes());
+        hasher.update(name.as_bytes());
+        (digest_to_u64(&hasher.finalize()) % 100) as u8
+    }
+}
+
+/// Returns the first 8 bytes of the given digest, as a little-endian integer.
+fn digest_to_u64(digest: &[u8]) -> u64 {
+    let mut prefix = [0u8; 8];
+    prefix.copy_from_slice(&digest[..8]);
+    u64::from_le_bytes(prefix)
+}
+
+impl FromStr for Experiments {
+    type Err = anyhow::Error;
+
+    /// Parses a comma-separated list of experiment rollouts, e.g. `block_announce=25%,other=on`.
+    /// Note: The experiments that are not listed keep their default rollout.
+    fn from_str(experiments: &str) -> Result<Self> {
+        let mut parsed = Self::default();
+        let mut seen = Vec::new();
+        for entry in experiments.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+            let Some((name, rollout)) = entry.split_once('=') else {
+                bail!("Invalid experiment '{entry}' (expected '<name>=<on|off|percentage>')");
+            };
+            let name = name.trim();
+            let Some((name, _)) = KNOWN_EXPERIMENTS.iter().find(|(known, _)| *known == name) else {
+                let known = KNOWN_EXPERIMENTS.map(|(name, _)| name).join(", ");
+                bail!("Unknown experiment '{name}' (the known experiments are: {known})");
+            };
+            ensure!(!seen.contains(name), "The experiment '{name}' is configured more than once");
+            seen.push(*name);
+            parsed.rollouts.insert(*name, rollout.parse()?);
+        }
+        Ok(parsed)
+    }
+}
+
+impl fmt::Display for Experiments {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let rollouts: Vec<_> = self.rollouts().map(|(name, rollout)| format!("{name}={rollout}")).collect();
+        write!(f, "{}", rollouts.join(","))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the given number of synthetic peer IPs.
+    fn sample_peers(num_peers: u32) -> impl Iterator<Item = SocketAddr> {
+        (0..num_peers).map(|i| SocketAddr::from(([10, (i >> 16) as u8, (i >> 8) as u8, i as u8], 4130)))
+    }
+
+    #[test]
+    fn test_parse_experiments() {
+        // The default rollouts are kept for an empty configuration.
+        assert_eq!("".parse::<Experiments>().unwrap(), Experiments::default());
+        assert_eq!(Experiments::default().rollout(BLOCK_ANNOUNCE_EXPERIMENT), Some(Rollout::ON));
+
+        // The rollouts are parsed as 'on', 'off', or a percentage.
+        for (input, expected) in [("on", Rollout::ON), ("off", Rollout::OFF), ("25%", Rollout::percent(25).unwrap())] {
+            let experiments: Experiments = format!(" {BLOCK_ANNOUNCE_EXPERIMENT} = {input} ,").parse().unwrap();
+            assert_eq!(experiments.rollout(BLOCK_ANNOUNCE_EXPERIMENT), Some(expected));
+            assert_eq!(experiments.to_string(), format!("{BLOCK_ANNOUNCE_EXPERIMENT}={}", input.trim()));
+        }
+
+        // Invalid configurations are rejected.
+        for (input, error) in [
+            ("block_announce", "expected '<name>=<on|off|percentage>'"),
+            ("block_announce=yes", "Invalid rollout 'yes'"),
+            ("block_announce=25", "Invalid rollout '25'"),
+            ("block_announce=101%", "must be at most 100%"),
+            ("gossip_v2=on", "Unknown experiment 'gossip_v2'"),
+            ("block_announce=on,block_announce=off", "configured more than once"),
+        ] {
+            let result = input.parse::<Experiments>();
+            assert!(result.as_ref().is_err_and(|e| e.to_string().contains(error)), "{input}: {result:?}");
+        }
+    }
+
+    #[test]
+    fn test_deterministic_assignment() {
+        let experiments: Experiments = "block_announce=50%".parse().unwrap();
+        let first = experiments.clone().salted("node-1");
+        let second = experiments.clone().salted("node-2");
+
+        let assign = |experiments: &Experiments| -> Vec<bool> {
+            sample_peers(1_000).map(|peer_ip| experiments.enabled_for(peer_ip, BLOCK_ANNOUNCE_EXPERIMENT)).collect()
+        };
+        // The same salt assigns the same peers.
+        assert_eq!(assign(&first), assign(&experiments.clone().salted("node-1")));
+        // A different salt assigns different peers.
+        assert_ne!(assign(&first), assign(&second));
+        // An unknown experiment is never enabled.
+        assert!(sample_peers(1_000).all(|peer_ip| !first.enabled_for(peer_ip, "gossip_v2")));
+    }
+
+    #[test]
+    fn test_stable_assignment() {
+        // Ensure the salt and the buckets match the SHA-256 digests they were pinned to, across Rust releases.
+        let experiments = Experiments::default().salted("node");
+        assert_eq!(experiments.salt, 17222627293779025492);
+        for (peer_ip, bucket) in [("1.2.3.4:4130", 28), ("10.0.0.1:4133", 31), ("[::1]:4130", 76)] {
+            assert_eq!(experiments.bucket(peer_ip.parse().unwrap(), BLOCK_ANNOUNCE_EXPERIMENT), bucket, "{peer_ip}");
+        }
+    }
+
+    #[test]
+    fn test_rollout_accuracy() {
+        const NUM_PEERS: u32 = 100_000;
+
+        for percent in [0, 1, 10, 25, 50, 75, 100] {
+            let experiments: Experiments = format!("block_announce={percent}%").parse().unwrap();
+            let experiments = experiments.salted("node");
+            let num_enabled = sample_peers(NUM_PEERS)
+                .filter(|peer_ip| experiments.enabled_for(*peer_ip, BLOCK_ANNOUNCE_EXPERIMENT))
+                .count();
+            // Ensure the share of the enabled peers is within half a percent of the rollout.
+            let share = num_enabled as f64 * 100.0 / NUM_PEERS as f64;
+            assert!((share - percent as f64).abs() < 0.5, "{share}% of the peers are enabled for a {percent}% rollout");
+        }
+    }
+}
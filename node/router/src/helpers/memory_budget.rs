@@ -0,0 +1,215 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+/// The percentage of the maximum pool memory at which the consumers are shrunk by one more step.
+pub const MEMORY_HIGH_WATERMARK_PERCENT: u64 = 90;
+/// The percentage of the maximum pool memory below which the consumers are restored by one step.
+pub const MEMORY_LOW_WATERMARK_PERCENT: u64 = 70;
+/// The approximate overhead in bytes of an entry in a hash map or LRU cache, beyond its key and value.
+pub const ENTRY_OVERHEAD_IN_BYTES: u64 = 32;
+
+/// A bounded structure whose memory counts towards the maximum pool memory.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum MemoryConsumer {
+    /// The candidate peers of the router.
+    CandidatePeers = 0,
+    /// The caches of the recently-seen messages of the router.
+    RouterCache,
+    /// The recently-seen solutions and transactions of consensus.
+    SeenTransmissions,
+    /// The inbound queues of the unconfirmed solutions and transactions of consensus.
+    TransmissionsQueue,
+}
+
+/// The number of memory consumers.
+pub const NUM_MEMORY_CONSUMERS: usize = 4;
+
+impl MemoryConsumer {
+    /// The memory consumers.
+    pub const ALL: [Self; NUM_MEMORY_CONSUMERS] =
+        [Self::CandidatePeers, Self::RouterCache, Self::SeenTransmissions, Self::TransmissionsQueue];
+}
+
+/// The steps by which the consumers are shrunk as the pool memory approaches its maximum, in order.
+///
+/// Each step caps a consumer to a percentage of its nominal capacity. The most elastic consumers are shrunk
+/// first: the candidate peers are easily re-learned, the seen caches only deduplicate the gossip, and the
+/// transmissions queues hold the unconfirmed transmissions, so they are shrunk last and never emptied.
+/// The steps are undone in the reverse order, as the memory pressure drops.
+pub const MEMORY_SHRINK_STEPS: [(MemoryConsumer, u64); 8] = [
+    (MemoryConsumer::CandidatePeers, 50),
+    (MemoryConsumer::CandidatePeers, 10),
+    (MemoryConsumer::RouterCache, 50),
+    (MemoryConsumer::SeenTransmissions, 50),
+    (MemoryConsumer::RouterCache, 10),
+    (MemoryConsumer::SeenTransmissions, 10),
+    (MemoryConsumer::TransmissionsQueue, 50),
+    (MemoryConsumer::TransmissionsQueue, 25),
+];
+
+/// The global accounting of the memory used by the memory pool and the deduplication caches.
+///
+/// The accounting is approximate and advisory: each consumer reports an estimate of its memory,
+/// and is shrunk (or restored) by one step each time the budget is rebalanced, in the order of
+/// [`MEMORY_SHRINK_STEPS`]. It does not guarantee the node stays within the maximum pool memory.
+#[derive(Debug, Default)]
+pub struct MemoryBudget {
+    /// The maximum pool memory in bytes, if any.
+    max_bytes: Option<u64>,
+    /// The estimated memory in bytes of each consumer.
+    usage: [AtomicU64; NUM_MEMORY_CONSUMERS],
+    /// The number of shrink steps in effect.
+    shrink_level: AtomicUsize,
+}
+
+impl MemoryBudget {
+    /// Initializes a new memory budget, with the given maximum pool memory in bytes.
+    pub fn new(max_bytes: Option<u64>) -> Self {
+        Self { max_bytes, ..Default::default() }
+    }
+
+    /// Returns the maximum pool memory in bytes, if any.
+    pub const fn max_bytes(&self) -> Option<u64> {
+        self.max_bytes
+    }
+
+    /// Returns the estimated memory in bytes of the given consumer.
+    pub fn usage(&self, consumer: MemoryConsumer) -> u64 {
+        self.usage[consumer as usize].load(Ordering::Relaxed)
+    }
+
+    /// Returns the estimated memory in bytes of all consumers.
+    pub fn total_usage(&self) -> u64 {
+        self.usage.iter().map(|usage| usage.load(Ordering::Relaxed)).sum()
+    }
+
+    /// Returns the number of shrink steps in effect.
+    pub fn shrink_level(&self) -> usize {
+        self.shrink_level.load(Ordering::Relaxed)
+    }
+
+    /// Returns the percentage of its nominal capacity the given consumer is capped to.
+    pub fn capacity_percent(&self, consumer: MemoryConsumer) -> u64 {
+        MEMORY_SHRINK_STEPS[..self.shrink_level()]
+            .iter()
+            .filter(|(step_consumer, _)| *step_consumer == consumer)
+            .map(|(_, percent)| *percent)
+            .min()
+            .unwrap_or(100)
+    }
+
+    /// Returns the capacity of the given consumer, given its nominal capacity.
+    pub fn capacity(&self, consumer: MemoryConsumer, nominal_capacity: usize) -> usize {
+        (nominal_capacity as u64 * self.capacity_percent(consumer) / 100).max(1) as usize
+    }
+}
+
+impl MemoryBudget {
+    /// Updates the estimated memory in bytes of the given consumer.
+    pub fn set_usage(&self, consumer: MemoryConsumer, bytes: u64) {
+        self.usage[consumer as usize].store(bytes, Ordering::Relaxed);
+    }
+
+    /// Shrinks the consumers by one step if the pool memory is approaching its maximum, or restores them
+    /// by one step if the memory pressure dropped, returning `Some(true)` or `Some(false)` respectively.
+    pub fn rebalance(&self) -> Option<bool> {
+        let total_usage = self.total_usage();
+        #[cfg(feature = "metrics")]
+        metrics::gauge(metrics::memory::POOL_BYTES, total_usage as f64);
+
+        let max_bytes = self.max_bytes?;
+        let shrink_level = self.shrink_level();
+        let result = if total_usage >= max_bytes.saturating_mul(MEMORY_HIGH_WATERMARK_PERCENT) / 100
+            && shrink_level < MEMORY_SHRINK_STEPS.len()
+        {
+            self.shrink_level.store(shrink_level + 1, Ordering::Relaxed);
+            Some(true)
+        } else if total_usage < max_bytes.saturating_mul(MEMORY_LOW_WATERMARK_PERCENT) / 100 && shrink_level > 0 {
+            self.shrink_level.store(shrink_level - 1, Ordering::Relaxed);
+            Some(false)
+        } else {
+            None
+        };
+
+        #[cfg(feature = "metrics")]
+        metrics::gauge(metrics::memory::POOL_SHRINK_LEVEL, self.shrink_level() as f64);
+        result
+    }
+}
+
+/// Returns the estimated memory in bytes of the given number of entries of a hash map or LRU cache.
+pub const fn estimate_entries<K, V>(num_entries: usize) -> u64 {
+    num_entries as u64 * (std::mem::size_of::<K>() as u64 + std::mem::size_of::<V>() as u64 + ENTRY_OVERHEAD_IN_BYTES)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shrink_order() {
+        let budget = MemoryBudget::new(Some(1_000));
+        budget.set_usage(MemoryConsumer::CandidatePeers, 500);
+        budget.set_usage(MemoryConsumer::TransmissionsQueue, 450);
+        assert_eq!(budget.total_usage(), 950);
+
+        // Ensure each rebalance under pressure applies the next shrink step, in order.
+        let mut levels = vec![];
+        while budget.rebalance() == Some(true) {
+            levels.push(MemoryConsumer::ALL.map(|consumer| budget.capacity_percent(consumer)));
+        }
+        assert_eq!(levels, vec![
+            [50, 100, 100, 100],
+            [10, 100, 100, 100],
+            [10, 50, 100, 100],
+            [10, 50, 50, 100],
+            [10, 10, 50, 100],
+            [10, 10, 10, 100],
+            [10, 10, 10, 50],
+            [10, 10, 10, 25],
+        ]);
+        assert_eq!(budget.capacity(MemoryConsumer::TransmissionsQueue, 1024), 256);
+        assert_eq!(budget.capacity(MemoryConsumer::CandidatePeers, 1), 1);
+
+        // Ensure the steps are held between the watermarks.
+        budget.set_usage(MemoryConsumer::CandidatePeers, 300);
+        assert_eq!(budget.rebalance(), None);
+        assert_eq!(budget.shrink_level(), MEMORY_SHRINK_STEPS.len());
+
+        // Ensure the steps are undone in the reverse order, once the pressure drops.
+        budget.set_usage(MemoryConsumer::CandidatePeers, 0);
+        assert_eq!(budget.rebalance(), Some(false));
+        assert_eq!(budget.capacity_percent(MemoryConsumer::TransmissionsQueue), 50);
+        while budget.rebalance() == Some(false) {}
+        assert_eq!(MemoryConsumer::ALL.map(|consumer| budget.capacity_percent(consumer)), [100; 4]);
+    }
+
+    #[test]
+    fn test_unlimited_budget() {
+        let budget = MemoryBudget::default();
+        budget.set_usage(MemoryConsumer::RouterCache, u64::MAX / 2);
+        // Ensure the consumers are never shrunk without a maximum pool memory.
+        assert_eq!(budget.rebalance(), None);
+        assert_eq!(budget.capacity(MemoryConsumer::RouterCache, 1 << 17), 1 << 17);
+    }
+
+    #[test]
+    fn test_estimates() {
+        assert_eq!(estimate_entries::<u64, ()>(10), 10 * (8 + ENTRY_OVERHEAD_IN_BYTES));
+    }
+}
@@ -0,0 +1,296 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::messages::{Message, NodeType};
+use snarkvm::prelude::Network;
+
+use indexmap::IndexMap;
+use parking_lot::Mutex;
+use std::net::SocketAddr;
+
+/// The number of rejected messages at which a peer is disconnected.
+pub const MAX_MESSAGE_POLICY_VIOLATIONS: u32 = 10;
+/// The maximum number of peers whose rejected messages are tracked.
+pub const MAX_TRACKED_VIOLATING_PEERS: usize = 1_000;
+
+/// The kind of a router message, without its contents.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[repr(u16)]
+pub enum MessageKind {
+    BlockRequest = 0,
+    BlockResponse,
+    ChallengeRequest,
+    ChallengeResponse,
+    Disconnect,
+    PeerRequest,
+    PeerResponse,
+    Ping,
+    Pong,
+    PuzzleRequest,
+    PuzzleResponse,
+    UnconfirmedSolution,
+    UnconfirmedTransaction,
+    BlockAnnounce,
+    SolutionAck,
+}
+
+/// The number of message kinds.
+pub const NUM_MESSAGE_KINDS: usize = 15;
+
+impl MessageKind {
+    /// Returns the kind of the given message.
+    pub fn of<N: Network>(message: &Message<N>) -> Self {
+        match message {
+            Message::BlockRequest(..) => Self::BlockRequest,
+            Message::BlockResponse(..) => Self::BlockResponse,
+            Message::ChallengeRequest(..) => Self::ChallengeRequest,
+            Message::ChallengeResponse(..) => Self::ChallengeResponse,
+            Message::Disconnect(..) => Self::Disconnect,
+            Message::PeerRequest(..) => Self::PeerRequest,
+            Message::PeerResponse(..) => Self::PeerResponse,
+            Message::Ping(..) => Self::Ping,
+            Message::Pong(..) => Self::Pong,
+            Message::PuzzleRequest(..) => Self::PuzzleRequest,
+            Message::PuzzleResponse(..) => Self::PuzzleResponse,
+            Message::UnconfirmedSolution(..) => Self::UnconfirmedSolution,
+            Message::UnconfirmedTransaction(..) => Self::UnconfirmedTransaction,
+            Message::BlockAnnounce(..) => Self::BlockAnnounce,
+            Message::SolutionAck(..) => Self::SolutionAck,
+        }
+    }
+
+    /// Returns the acceptance policy of the message kind.
+    pub const fn policy(self) -> MessagePolicy {
+        MESSAGE_POLICIES[self as usize]
+    }
+
+    /// Returns `true` if a node of the given type accepts this kind of message from a peer of the given type.
+    pub const fn is_accepted(self, node_type: NodeType, peer_type: NodeType) -> bool {
+        self.policy().senders(node_type).contains(peer_type)
+    }
+}
+
+/// A set of node types.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct NodeTypes(u8);
+
+impl NodeTypes {
+    /// The set of all node types.
+    pub const ALL: Self = Self::of(NodeType::Client).with(NodeType::Prover).with(NodeType::Validator);
+    /// The set of node types that maintain a ledger.
+    pub const FULL_NODES: Self = Self::of(NodeType::Client).with(NodeType::Validator);
+    /// The empty set of node types.
+    pub const NONE: Self = Self(0);
+
+    /// Returns the set containing only the given node type.
+    pub const fn of(node_type: NodeType) -> Self {
+        Self(1 << node_type as u8)
+    }
+
+    /// Returns the set with the given node type added.
+    pub const fn with(self, node_type: NodeType) -> Self {
+        Self(self.0 | Self::of(node_type).0)
+    }
+
+    /// Returns `true` if the set contains the given node type.
+    pub const fn contains(self, node_type: NodeType) -> bool {
+        self.0 & Self::of(node_type).0 != 0
+    }
+}
+
+/// The node types from which each node type accepts a kind of message.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct MessagePolicy {
+    /// The kind of message.
+    pub kind: MessageKind,
+    /// The node types from which a client accepts the message.
+    pub client: NodeTypes,
+    /// The node types from which a prover accepts the message.
+    pub prover: NodeTypes,
+    /// The node types from which a validator accepts the message.
+    pub validator: NodeTypes,
+}
+
+impl MessagePolicy {
+    /// Initializes a policy that accepts the message from all node types.
+    const fn all(kind: MessageKind) -> Self {
+        Self { kind, client: NodeTypes::ALL, prover: NodeTypes::ALL, validator: NodeTypes::ALL }
+    }
+
+    /// Returns the node types from which a node of the given type accepts the message.
+    pub const fn senders(&self, node_type: NodeType) -> NodeTypes {
+        match node_type {
+            NodeType::Client => self.client,
+            NodeType::Prover => self.prover,
+            NodeType::Validator => self.validator,
+        }
+    }
+}
+
+/// The acceptance matrix of the router messages, indexed by message kind.
+///
+/// A message that is not accepted is dropped before it is dispatched to its handler. Combinations that the
+/// handlers already treat as protocol violations (e.g. a prover receiving a `BlockRequest`) are left to them.
+pub const MESSAGE_POLICIES: [MessagePolicy; NUM_MESSAGE_KINDS] = [
+    // Provers do not sync blocks, so they never request any.
+    MessagePolicy {
+        kind: MessageKind::BlockRequest,
+        client: NodeTypes::FULL_NODES,
+        prover: NodeTypes::ALL,
+        validator: NodeTypes::FULL_NODES,
+    },
+    // Provers do not serve blocks, so they never respond with any.
+    MessagePolicy {
+        kind: MessageKind::BlockResponse,
+        client: NodeTypes::FULL_NODES,
+        prover: NodeTypes::ALL,
+        validator: NodeTypes::FULL_NODES,
+    },
+    MessagePolicy::all(MessageKind::ChallengeRequest),
+    MessagePolicy::all(MessageKind::ChallengeResponse),
+    MessagePolicy::all(MessageKind::Disconnect),
+    MessagePolicy::all(MessageKind::PeerRequest),
+    MessagePolicy::all(MessageKind::PeerResponse),
+    MessagePolicy::all(MessageKind::Ping),
+    MessagePolicy::all(MessageKind::Pong),
+    MessagePolicy::all(MessageKind::PuzzleRequest),
+    // Provers do not serve the puzzle, so a prover never responds to the puzzle request of another prover.
+    MessagePolicy { prover: NodeTypes::FULL_NODES, ..MessagePolicy::all(MessageKind::PuzzleResponse) },
+    MessagePolicy::all(MessageKind::UnconfirmedSolution),
+    MessagePolicy::all(MessageKind::UnconfirmedTransaction),
+    // Only validators announce the blocks they committed, to the other validators first, and then to clients.
+    MessagePolicy {
+        kind: MessageKind::BlockAnnounce,
+        client: NodeTypes::of(NodeType::Validator),
+        prover: NodeTypes::ALL,
+        validator: NodeTypes::of(NodeType::Validator),
+    },
+    // Only provers submit solutions to be acknowledged, and only full nodes acknowledge them.
+    MessagePolicy {
+        kind: MessageKind::SolutionAck,
+        client: NodeTypes::NONE,
+        prover: NodeTypes::FULL_NODES,
+        validator: NodeTypes::NONE,
+    },
+];
+
+/// The tracker of the messages each peer sent that were rejected by the acceptance matrix.
+///
+/// The record of a peer outlives its connection, so that a peer cannot reset its violations by reconnecting.
+#[derive(Default)]
+pub struct MessagePolicyViolations {
+    /// The number of violations of each peer, in the order they were last recorded.
+    violations: Mutex<IndexMap<SocketAddr, u32>>,
+}
+
+impl MessagePolicyViolations {
+    /// Records a rejected message from the given peer, and returns its number of violations.
+    pub fn insert(&self, peer_ip: SocketAddr) -> u32 {
+        let mut violations = self.violations.lock();
+        // Move the peer to the back, so that the least recently recorded peers are evicted first.
+        let num_violations = violations.shift_remove(&peer_ip).unwrap_or_default().saturating_add(1);
+        violations.insert(peer_ip, num_violations);
+        // Evict the least recently recorded peers.
+        while violations.len() > MAX_TRACKED_VIOLATING_PEERS {
+            violations.shift_remove_index(0);
+        }
+        num_violations
+    }
+
+    /// Returns the number of violations of the given peer.
+    pub fn get(&self, peer_ip: &SocketAddr) -> u32 {
+        self.violations.lock().get(peer_ip).copied().unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NODE_TYPES: [NodeType; 3] = [NodeType::Client, NodeType::Prover, NodeType::Validator];
+
+    #[test]
+    fn test_message_policies_are_indexed_by_kind() {
+        // Ensure each message kind has exactly one policy, at its index.
+        for (index, policy) in MESSAGE_POLICIES.iter().enumerate() {
+            assert_eq!(policy.kind as usize, index);
+            assert_eq!(policy.kind.policy(), *policy);
+        }
+    }
+
+    #[test]
+    fn test_message_policies() {
+        // Ensure the ping and disconnect are always accepted, so a peer can always be handshaken and dropped.
+        for node_type in NODE_TYPES {
+            for peer_type in NODE_TYPES {
+                assert!(MessageKind::Ping.is_accepted(node_type, peer_type));
+                assert!(MessageKind::Disconnect.is_accepted(node_type, peer_type));
+            }
+        }
+
+        // Ensure the full nodes reject blocks requested or served by provers.
+        for node_type in [NodeType::Client, NodeType::Validator] {
+            for kind in [MessageKind::BlockRequest, MessageKind::BlockResponse] {
+                assert!(!kind.is_accepted(node_type, NodeType::Prover));
+                assert!(kind.is_accepted(node_type, NodeType::Client));
+                assert!(kind.is_accepted(node_type, NodeType::Validator));
+            }
+        }
+        // Ensure a prover rejects a puzzle response from another prover.
+        assert!(!MessageKind::PuzzleResponse.is_accepted(NodeType::Prover, NodeType::Prover));
+        assert!(MessageKind::PuzzleResponse.is_accepted(NodeType::Prover, NodeType::Client));
+        // Ensure clients and validators only accept block announcements from validators.
+        for node_type in [NodeType::Client, NodeType::Validator] {
+            assert!(MessageKind::BlockAnnounce.is_accepted(node_type, NodeType::Validator));
+            assert!(!MessageKind::BlockAnnounce.is_accepted(node_type, NodeType::Client));
+            assert!(!MessageKind::BlockAnnounce.is_accepted(node_type, NodeType::Prover));
+        }
+        // Ensure only provers accept solution acknowledgments, and only from full nodes.
+        for peer_type in NODE_TYPES {
+            assert!(!MessageKind::SolutionAck.is_accepted(NodeType::Client, peer_type));
+            assert!(!MessageKind::SolutionAck.is_accepted(NodeType::Validator, peer_type));
+            assert_eq!(MessageKind::SolutionAck.is_accepted(NodeType::Prover, peer_type), !peer_type.is_prover());
+        }
+
+        // Ensure the rejected combinations are exactly the ones above.
+        let num_rejected = MESSAGE_POLICIES
+            .iter()
+            .flat_map(|policy| NODE_TYPES.map(|node_type| policy.senders(node_type)))
+            .flat_map(|senders| NODE_TYPES.map(|peer_type| senders.contains(peer_type)))
+            .filter(|is_accepted| !is_accepted)
+            .count();
+        assert_eq!(num_rejected, 16);
+    }
+
+    #[test]
+    fn test_violations_escalate() {
+        let violations = MessagePolicyViolations::default();
+        let peer_ip = SocketAddr::from(([127, 0, 0, 1], 4130));
+
+        // Ensure the violations of a peer accumulate, independently of other peers.
+        for num_violations in 1..=MAX_MESSAGE_POLICY_VIOLATIONS {
+            assert_eq!(violations.insert(peer_ip), num_violations);
+        }
+        assert_eq!(violations.get(&peer_ip), MAX_MESSAGE_POLICY_VIOLATIONS);
+        assert_eq!(violations.get(&SocketAddr::from(([127, 0, 0, 1], 4131))), 0);
+
+        // Ensure the tracked peers are bounded.
+        for port in 0..(MAX_TRACKED_VIOLATING_PEERS as u16 * 2) {
+            violations.insert(SocketAddr::from(([127, 0, 0, 2], port)));
+        }
+        assert_eq!(violations.violations.lock().len(), MAX_TRACKED_VIOLATING_PEERS);
+        assert_eq!(violations.get(&peer_ip), 0);
+    }
+}
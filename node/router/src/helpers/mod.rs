@@ -13,11 +13,71 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod account_status;
+pub use account_status::*;
+
+mod ban_list;
+pub use ban_list::*;
+
+mod bootstrap;
+pub use bootstrap::*;
+
 mod cache;
-pub use cache::Cache;
+pub use cache::{Cache, MAX_CACHE_SIZE};
+
+mod candidate_peer;
+pub use candidate_peer::*;
+
+mod duplicate_identity;
+pub use duplicate_identity::*;
+
+mod duplicate_transmissions;
+pub use duplicate_transmissions::*;
+
+mod experiments;
+pub use experiments::*;
+
+mod memory_budget;
+pub use memory_budget::*;
+
+mod message_policy;
+pub use message_policy::*;
 
 mod peer;
 pub use peer::*;
 
+mod peer_events;
+pub use peer_events::*;
+
+mod peer_export;
+pub use peer_export::*;
+
+mod peer_identities;
+pub use peer_identities::*;
+
+mod peer_limits;
+pub use peer_limits::*;
+
+mod recent_nonces;
+pub use recent_nonces::*;
+
+mod relay_gate;
+pub use relay_gate::*;
+
+mod reload;
+pub use reload::*;
+
 mod resolver;
 pub use resolver::*;
+
+mod sync_progress;
+pub use sync_progress::*;
+
+mod sync_summary;
+pub use sync_summary::*;
+
+mod supervisor;
+pub use supervisor::*;
+
+mod trace_scopes;
+pub use trace_scopes::*;
@@ -13,7 +13,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::messages::{ChallengeRequest, NodeType};
+use crate::messages::{ChallengeRequest, DisconnectReason, Features, NodeType};
 use snarkvm::prelude::{Address, Network};
 
 use std::{net::SocketAddr, time::Instant};
@@ -29,22 +29,31 @@ pub struct Peer<N: Network> {
     node_type: NodeType,
     /// The message version of the peer.
     version: u32,
+    /// The optional protocol features advertised by the peer.
+    features: Features,
+    /// The maximum frame size negotiated with the peer, in bytes.
+    max_frame_size: usize,
     /// The timestamp of the first message received from the peer.
     first_seen: Instant,
     /// The timestamp of the last message received from this peer.
     last_seen: Instant,
+    /// The first disconnect reason sent to or received from the peer, if any.
+    disconnect_reason: Option<DisconnectReason>,
 }
 
 impl<N: Network> Peer<N> {
     /// Initializes a new instance of `Peer`.
-    pub fn new(listening_ip: SocketAddr, challenge_request: &ChallengeRequest<N>) -> Self {
+    pub fn new(listening_ip: SocketAddr, challenge_request: &ChallengeRequest<N>, max_frame_size: usize) -> Self {
         Self {
             peer_ip: listening_ip,
             address: challenge_request.address,
             node_type: challenge_request.node_type,
             version: challenge_request.version,
+            features: challenge_request.features,
+            max_frame_size,
             first_seen: Instant::now(),
             last_seen: Instant::now(),
+            disconnect_reason: None,
         }
     }
 
@@ -83,6 +92,16 @@ impl<N: Network> Peer<N> {
         self.version
     }
 
+    /// Returns the optional protocol features advertised by the peer.
+    pub const fn features(&self) -> Features {
+        self.features
+    }
+
+    /// Returns the maximum frame size negotiated with the peer, in bytes.
+    pub const fn max_frame_size(&self) -> usize {
+        self.max_frame_size
+    }
+
     /// Returns the first seen timestamp of the peer.
     pub fn first_seen(&self) -> Instant {
         self.first_seen
@@ -92,6 +111,11 @@ impl<N: Network> Peer<N> {
     pub fn last_seen(&self) -> Instant {
         self.last_seen
     }
+
+    /// Returns the first disconnect reason sent to or received from the peer, if any.
+    pub const fn disconnect_reason(&self) -> Option<DisconnectReason> {
+        self.disconnect_reason
+    }
 }
 
 impl<N: Network> Peer<N> {
@@ -109,4 +133,9 @@ impl<N: Network> Peer<N> {
     pub fn set_last_seen(&mut self, last_seen: Instant) {
         self.last_seen = last_seen;
     }
+
+    /// Records the given disconnect reason, unless a disconnect reason was already recorded.
+    pub fn set_disconnect_reason(&mut self, reason: DisconnectReason) {
+        self.disconnect_reason.get_or_insert(reason);
+    }
 }
@@ -0,0 +1,66 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    ConnectionFailure,
+    messages::{DisconnectReason, NodeType},
+};
+use snarkvm::prelude::{Address, Network};
+
+use std::net::SocketAddr;
+
+/// The capacity of the channel of the peer events.
+/// Note: A subscriber that falls further behind misses the oldest events, instead of blocking the router.
+pub const PEER_EVENTS_CAPACITY: usize = 1_024;
+
+/// The cause of the restriction of a peer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RestrictionCause {
+    /// The peer exceeded the maximum number of connection attempts, with the given number of attempts.
+    TooManyConnectionAttempts(usize),
+    /// The restriction was imported from the peer knowledge of another node.
+    Imported,
+    /// The peer was restricted by the operator, or by an embedder of the router.
+    Manual,
+    /// The peer presented a history that rewinds the given number of blocks, beyond the maximum reorg depth.
+    ReorgDepthExceeded(u32),
+}
+
+/// A change in the lifecycle of the connection to a peer, as published by the router.
+///
+/// The events are self-contained, so that a subscriber does not need to query the router to make use of them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PeerEvent<N: Network> {
+    /// The handshake with the peer succeeded, and the peer was added to the connected peers.
+    Connected { peer_ip: SocketAddr, address: Address<N>, node_type: NodeType, version: u32 },
+    /// The peer was removed from the connected peers, with the disconnect reason exchanged with it, if any.
+    Disconnected { peer_ip: SocketAddr, reason: Option<DisconnectReason> },
+    /// The peer was added to the restricted peers.
+    Restricted { peer_ip: SocketAddr, cause: RestrictionCause },
+    /// The handshake with the peer failed. The address is the listener address of the peer, if it is known.
+    HandshakeFailed { peer_addr: SocketAddr, error_kind: ConnectionFailure },
+}
+
+impl<N: Network> PeerEvent<N> {
+    /// Returns the IP address of the peer.
+    pub const fn peer_ip(&self) -> SocketAddr {
+        match self {
+            Self::Connected { peer_ip, .. } | Self::Disconnected { peer_ip, .. } | Self::Restricted { peer_ip, .. } => {
+                *peer_ip
+            }
+            Self::HandshakeFailed { peer_addr, .. } => *peer_addr,
+        }
+    }
+}
@@ -0,0 +1,136 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::{Result, anyhow, ensure};
+use serde::{Deserialize, Serialize};
+use std::{fmt, path::Path};
+
+/// The current version of the peer export format.
+pub const PEER_EXPORT_VERSION: u32 = 1;
+
+/// The peer knowledge of a node, exported to seed another node with, e.g. a replacement node.
+///
+/// The peer IPs are kept as strings, so that an invalid entry is dropped on import instead of failing the import.
+/// Note: Unknown fields are ignored, so that a node can import the export of a newer node.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeerExport {
+    /// The version of the format.
+    pub version: u32,
+    /// The candidate peers, with their quality metadata.
+    #[serde(default)]
+    pub candidate_peers: Vec<ExportedCandidatePeer>,
+    /// The trusted peers.
+    #[serde(default)]
+    pub trusted_peers: Vec<String>,
+    /// The restricted peers, with the remaining duration of their restriction.
+    #[serde(default)]
+    pub restricted_peers: Vec<ExportedRestrictedPeer>,
+}
+
+impl PeerExport {
+    /// Loads the peer export from the given file.
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|error| anyhow!("Failed to read the peer export '{}' - {error}", path.display()))?;
+        let export: Self = serde_json::from_str(&contents)
+            .map_err(|error| anyhow!("Failed to parse the peer export '{}' - {error}", path.display()))?;
+        ensure!(export.version > 0, "The peer export '{}' has an invalid version", path.display());
+        Ok(export)
+    }
+}
+
+/// A candidate peer, with its quality metadata.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportedCandidatePeer {
+    /// The IP of the peer.
+    pub ip: String,
+    /// Whether the exporting node has ever successfully connected to the peer.
+    #[serde(default)]
+    pub has_connected: bool,
+    /// The time in seconds since the peer was last inserted or re-learned.
+    #[serde(default)]
+    pub age_secs: u64,
+}
+
+/// A restricted peer, with the remaining duration of its restriction.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportedRestrictedPeer {
+    /// The IP of the peer.
+    pub ip: String,
+    /// The remaining duration in seconds of the restriction.
+    #[serde(default)]
+    pub remaining_secs: u64,
+}
+
+/// The outcome of an import of peer knowledge.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct PeerImportSummary {
+    /// The number of candidate peers inserted, including the trusted peers of the exporting node.
+    pub num_candidates: usize,
+    /// The number of restricted peers inserted.
+    pub num_restricted: usize,
+    /// The number of entries dropped, as invalid or in conflict with the state of the node.
+    pub num_dropped: usize,
+}
+
+impl PeerImportSummary {
+    /// Returns `true` if the import inserted no peer.
+    pub fn is_empty(&self) -> bool {
+        self.num_candidates == 0 && self.num_restricted == 0
+    }
+}
+
+impl fmt::Display for PeerImportSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} candidate peers and {} restricted peers ({} entries dropped)",
+            self.num_candidates, self.num_restricted, self.num_dropped
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unknown_fields_are_ignored() {
+        // An export of a newer node, with unknown fields at every level.
+        let json = r#"{
+            "version": 2,
+            "exported_at": 1700000000,
+            "candidate_peers": [{ "ip": "1.2.3.4:4130", "has_connected": true, "age_secs": 60, "latency_ms": 12 }],
+            "restricted_peers": [{ "ip": "5.6.7.8:4130", "remaining_secs": 30, "reason": "spam" }],
+            "banned_addresses": []
+        }"#;
+        let export: PeerExport = serde_json::from_str(json).unwrap();
+        assert_eq!(export.version, 2);
+        assert_eq!(export.candidate_peers, vec![ExportedCandidatePeer {
+            ip: "1.2.3.4:4130".to_string(),
+            has_connected: true,
+            age_secs: 60
+        }]);
+        assert!(export.trusted_peers.is_empty());
+        assert_eq!(export.restricted_peers, vec![ExportedRestrictedPeer {
+            ip: "5.6.7.8:4130".to_string(),
+            remaining_secs: 30
+        }]);
+
+        // Ensure the export round-trips through its JSON representation.
+        let json = serde_json::to_string(&export).unwrap();
+        assert_eq!(serde_json::from_str::<PeerExport>(&json).unwrap(), export);
+    }
+}
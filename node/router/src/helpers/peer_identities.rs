@@ -0,0 +1,150 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{Address, Network};
+
+use indexmap::IndexMap;
+use std::{net::SocketAddr, time::Instant};
+
+/// The maximum number of account addresses whose peer IPs are tracked.
+pub const MAX_TRACKED_ACCOUNTS: usize = 1_000;
+/// The maximum number of recent peer IPs tracked for each account address.
+pub const MAX_PEER_IPS_PER_ACCOUNT: usize = 8;
+
+/// The record of the peer IPs that each account address was authenticated from in the handshake.
+///
+/// This only records what the peers proved to the node, and allows the node to recognize
+/// a peer that moves between IPs, or that rotates IPs to evade restrictions.
+pub struct PeerIdentities<N: Network> {
+    /// The peer IPs of each account address, with the time they were last seen,
+    /// both in the order they were last seen.
+    accounts: IndexMap<Address<N>, IndexMap<SocketAddr, Instant>>,
+}
+
+impl<N: Network> Default for PeerIdentities<N> {
+    /// Initializes an empty record.
+    fn default() -> Self {
+        Self { accounts: Default::default() }
+    }
+}
+
+impl<N: Network> PeerIdentities<N> {
+    /// Records that the given account address was authenticated from the given peer IP.
+    pub fn insert(&mut self, address: Address<N>, peer_ip: SocketAddr, now: Instant) {
+        // Move the account to the back, so that the least recently seen accounts are evicted first.
+        let mut peer_ips = self.accounts.shift_remove(&address).unwrap_or_default();
+        peer_ips.shift_remove(&peer_ip);
+        peer_ips.insert(peer_ip, now);
+        // Evict the least recently seen peer IPs of the account.
+        while peer_ips.len() > MAX_PEER_IPS_PER_ACCOUNT {
+            peer_ips.shift_remove_index(0);
+        }
+        self.accounts.insert(address, peer_ips);
+        // Evict the least recently seen accounts.
+        while self.accounts.len() > MAX_TRACKED_ACCOUNTS {
+            self.accounts.shift_remove_index(0);
+        }
+    }
+
+    /// Returns the peer IPs the given account address was seen at, with the time they were last seen,
+    /// from the most recent.
+    pub fn peer_ips(&self, address: &Address<N>) -> Vec<(SocketAddr, Instant)> {
+        self.accounts
+            .get(address)
+            .map(|peer_ips| peer_ips.iter().rev().map(|(peer_ip, seen)| (*peer_ip, *seen)).collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns the account address most recently seen at the given peer IP, if any.
+    pub fn address(&self, peer_ip: &SocketAddr) -> Option<Address<N>> {
+        self.accounts
+            .iter()
+            .filter_map(|(address, peer_ips)| peer_ips.get(peer_ip).map(|seen| (*address, *seen)))
+            .max_by_key(|(_, seen)| *seen)
+            .map(|(address, _)| address)
+    }
+
+    /// Returns the number of tracked account addresses.
+    pub fn num_accounts(&self) -> usize {
+        self.accounts.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{MainnetV0, PrivateKey};
+
+    use std::time::Duration;
+
+    type CurrentNetwork = MainnetV0;
+
+    /// Returns a sample account address.
+    fn sample_address() -> Address<CurrentNetwork> {
+        let rng = &mut rand::thread_rng();
+        Address::try_from(PrivateKey::<CurrentNetwork>::new(rng).unwrap()).unwrap()
+    }
+
+    /// Returns the sample peer IP with the given index.
+    fn sample_peer_ip(index: u16) -> SocketAddr {
+        SocketAddr::from(([127, 0, 0, 1], 4130 + index))
+    }
+
+    #[test]
+    fn test_peer_identities() {
+        let mut identities = PeerIdentities::<CurrentNetwork>::default();
+        let (address_0, address_1) = (sample_address(), sample_address());
+        let now = Instant::now();
+
+        // The first account moves from the first peer IP to the second.
+        identities.insert(address_0, sample_peer_ip(0), now);
+        identities.insert(address_0, sample_peer_ip(1), now + Duration::from_secs(1));
+        assert_eq!(identities.peer_ips(&address_0), vec![
+            (sample_peer_ip(1), now + Duration::from_secs(1)),
+            (sample_peer_ip(0), now)
+        ]);
+        assert_eq!(identities.address(&sample_peer_ip(0)), Some(address_0));
+        assert_eq!(identities.address(&sample_peer_ip(1)), Some(address_0));
+
+        // The second account takes over the first peer IP.
+        identities.insert(address_1, sample_peer_ip(0), now + Duration::from_secs(2));
+        assert_eq!(identities.address(&sample_peer_ip(0)), Some(address_1));
+        assert_eq!(identities.address(&sample_peer_ip(2)), None);
+        assert!(identities.peer_ips(&sample_address()).is_empty());
+    }
+
+    #[test]
+    fn test_peer_identities_are_bounded() {
+        let mut identities = PeerIdentities::<CurrentNetwork>::default();
+        let address = sample_address();
+        let now = Instant::now();
+
+        // Ensure the peer IPs of an account are bounded, keeping the most recent.
+        for i in 0..(MAX_PEER_IPS_PER_ACCOUNT as u16 * 2) {
+            identities.insert(address, sample_peer_ip(i), now);
+        }
+        let peer_ips = identities.peer_ips(&address);
+        assert_eq!(peer_ips.len(), MAX_PEER_IPS_PER_ACCOUNT);
+        assert_eq!(peer_ips[0].0, sample_peer_ip(MAX_PEER_IPS_PER_ACCOUNT as u16 * 2 - 1));
+        assert_eq!(identities.address(&sample_peer_ip(0)), None);
+
+        // Ensure the accounts are bounded, keeping the most recent.
+        for _ in 0..MAX_TRACKED_ACCOUNTS {
+            identities.insert(sample_address(), sample_peer_ip(0), now);
+        }
+        assert_eq!(identities.num_accounts(), MAX_TRACKED_ACCOUNTS);
+        assert!(identities.peer_ips(&address).is_empty());
+    }
+}
@@ -0,0 +1,201 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::messages::NodeType;
+use snarkos_node_tcp::HandshakeLimits;
+
+use anyhow::{Result, bail};
+use std::ops::RangeInclusive;
+
+/// The minimum number of peers required to maintain connections with.
+pub const MINIMUM_NUMBER_OF_PEERS: usize = 3;
+/// The maximum number of peers recommended for a prover, which only needs a few peers to sync and receive puzzles.
+pub const MAXIMUM_RECOMMENDED_PROVER_PEERS: usize = 50;
+/// The maximum number of peers recommended for a client or a validator.
+pub const MAXIMUM_RECOMMENDED_PEERS: usize = 1_000;
+/// The number of peers recommended for a validator on top of the committee members, e.g. for clients.
+pub const VALIDATOR_PEER_MARGIN: usize = 20;
+
+/// The limits on the number of connected peers of a node, derived from its node type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PeerLimits {
+    /// The node type.
+    node_type: NodeType,
+    /// The minimum number of peers required to maintain connections with.
+    minimum: usize,
+    /// The median number of peers to maintain connections with.
+    median: usize,
+    /// The maximum number of peers permitted to maintain connections with.
+    maximum: usize,
+    /// The maximum number of provers to maintain connections with.
+    maximum_provers: usize,
+    /// The number of members in the committee, if known.
+    committee_size: Option<usize>,
+    /// The limits on the inbound connections performing the handshake at the same time.
+    handshake_limits: HandshakeLimits,
+}
+
+impl PeerLimits {
+    /// Returns the default maximum number of peers for the given node type.
+    pub const fn default_max_peers(node_type: NodeType) -> u16 {
+        match node_type {
+            NodeType::Client | NodeType::Prover => 21,
+            NodeType::Validator => 200,
+        }
+    }
+
+    /// Initializes the peer limits for the given node type, with the given maximum number of peers, if any.
+    pub fn new(node_type: NodeType, max_peers: Option<u16>) -> Self {
+        // Determine the maximum number of peers, which must allow for at least one peer.
+        let maximum = max_peers.unwrap_or_else(|| Self::default_max_peers(node_type)).max(1) as usize;
+        // Determine the minimum and median number of peers.
+        let minimum = MINIMUM_NUMBER_OF_PEERS.min(maximum);
+        let median = (maximum / 2).max(minimum);
+        // Determine the maximum number of provers. A prover has no use for other provers.
+        let maximum_provers = match node_type {
+            NodeType::Prover => 0,
+            NodeType::Client | NodeType::Validator => maximum / 4,
+        };
+        Self {
+            node_type,
+            minimum,
+            median,
+            maximum,
+            maximum_provers,
+            committee_size: None,
+            handshake_limits: Default::default(),
+        }
+    }
+
+    /// Sets the number of members in the committee, which a validator must have room to connect to.
+    pub fn with_committee_size(mut self, committee_size: usize) -> Self {
+        self.committee_size = Some(committee_size);
+        self
+    }
+
+    /// Sets the limits on the inbound connections performing the handshake at the same time.
+    pub fn with_handshake_limits(mut self, handshake_limits: HandshakeLimits) -> Self {
+        self.handshake_limits = handshake_limits;
+        self
+    }
+
+    /// Returns the node type.
+    pub const fn node_type(&self) -> NodeType {
+        self.node_type
+    }
+
+    /// Returns the minimum number of peers required to maintain connections with.
+    pub const fn minimum(&self) -> usize {
+        self.minimum
+    }
+
+    /// Returns the median number of peers to maintain connections with.
+    pub const fn median(&self) -> usize {
+        self.median
+    }
+
+    /// Returns the maximum number of peers permitted to maintain connections with.
+    pub const fn maximum(&self) -> usize {
+        self.maximum
+    }
+
+    /// Returns the maximum number of provers to maintain connections with.
+    pub const fn maximum_provers(&self) -> usize {
+        self.maximum_provers
+    }
+
+    /// Returns the limits on the inbound connections performing the handshake at the same time.
+    pub const fn handshake_limits(&self) -> HandshakeLimits {
+        self.handshake_limits
+    }
+
+    /// Returns the recommended range for the maximum number of peers of the node type.
+    pub fn recommended_range(&self) -> RangeInclusive<usize> {
+        match self.node_type {
+            NodeType::Client => MINIMUM_NUMBER_OF_PEERS..=MAXIMUM_RECOMMENDED_PEERS,
+            NodeType::Prover => MINIMUM_NUMBER_OF_PEERS..=MAXIMUM_RECOMMENDED_PROVER_PEERS,
+            NodeType::Validator => {
+                let committee_size = self.committee_size.unwrap_or_default();
+                committee_size.saturating_add(VALIDATOR_PEER_MARGIN).max(MINIMUM_NUMBER_OF_PEERS)
+                    ..=MAXIMUM_RECOMMENDED_PEERS
+            }
+        }
+    }
+
+    /// Ensures the maximum number of peers is within the recommended range of the node type.
+    pub fn check(&self) -> Result<()> {
+        let range = self.recommended_range();
+        if !range.contains(&self.maximum) {
+            bail!(
+                "The maximum number of peers ({}) is inappropriate for {} - the recommended range is {} to {}",
+                self.maximum,
+                self.node_type.description(),
+                range.start(),
+                range.end()
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_limits_are_recommended() {
+        for node_type in [NodeType::Client, NodeType::Prover, NodeType::Validator] {
+            let limits = PeerLimits::new(node_type, None);
+            assert_eq!(limits.maximum(), PeerLimits::default_max_peers(node_type) as usize);
+            assert!(limits.check().is_ok(), "The default limits of {} must be recommended", node_type.description());
+        }
+        // Ensure the default of a validator has room for a typical committee.
+        assert!(PeerLimits::new(NodeType::Validator, None).with_committee_size(100).check().is_ok());
+    }
+
+    #[test]
+    fn test_heartbeat_targets() {
+        // A client keeps a quarter of its peers as provers.
+        let limits = PeerLimits::new(NodeType::Client, None);
+        assert_eq!((limits.minimum(), limits.median(), limits.maximum(), limits.maximum_provers()), (3, 10, 21, 5));
+        // A prover does not keep any provers.
+        let limits = PeerLimits::new(NodeType::Prover, None);
+        assert_eq!((limits.minimum(), limits.median(), limits.maximum(), limits.maximum_provers()), (3, 10, 21, 0));
+        // A validator keeps many more peers.
+        let limits = PeerLimits::new(NodeType::Validator, None);
+        assert_eq!((limits.minimum(), limits.median(), limits.maximum(), limits.maximum_provers()), (3, 100, 200, 50));
+        // The targets follow the configured maximum.
+        let limits = PeerLimits::new(NodeType::Validator, Some(400));
+        assert_eq!((limits.minimum(), limits.median(), limits.maximum(), limits.maximum_provers()), (3, 200, 400, 100));
+        // The targets remain consistent for a tiny maximum.
+        let limits = PeerLimits::new(NodeType::Client, Some(0));
+        assert_eq!((limits.minimum(), limits.median(), limits.maximum(), limits.maximum_provers()), (1, 1, 1, 0));
+    }
+
+    #[test]
+    fn test_check_mismatched_limits() {
+        // A prover with a validator's configuration.
+        let error = PeerLimits::new(NodeType::Prover, Some(200)).check().unwrap_err();
+        assert!(error.to_string().contains("recommended range is 3 to 50"), "{error}");
+        assert!(PeerLimits::new(NodeType::Prover, Some(50)).check().is_ok());
+        // A validator without room for the committee.
+        let limits = PeerLimits::new(NodeType::Validator, Some(50)).with_committee_size(40);
+        let error = limits.check().unwrap_err();
+        assert!(error.to_string().contains("recommended range is 60 to 1000"), "{error}");
+        assert!(PeerLimits::new(NodeType::Validator, Some(60)).with_committee_size(40).check().is_ok());
+        // A client with too few peers.
+        assert!(PeerLimits::new(NodeType::Client, Some(2)).check().is_err());
+    }
+}
@@ -0,0 +1,70 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use indexmap::IndexSet;
+use parking_lot::Mutex;
+
+/// The maximum number of recent handshake nonces of the peers that are remembered.
+pub const MAX_RECENT_NONCES: usize = 4_096;
+
+/// The bounded record of the nonces the peers recently used in their handshakes.
+///
+/// A nonce that was already used in a handshake indicates a replayed challenge request or response,
+/// as honest peers sample a fresh random nonce for each handshake.
+#[derive(Debug, Default)]
+pub struct RecentNonces {
+    /// The recent nonces, from the oldest.
+    nonces: Mutex<IndexSet<u64>>,
+}
+
+impl RecentNonces {
+    /// Returns `true` if the given nonce was recently used in a handshake.
+    pub fn contains(&self, nonce: u64) -> bool {
+        self.nonces.lock().contains(&nonce)
+    }
+
+    /// Records the given nonce, and returns `false` if it was recently used in a handshake.
+    pub fn insert(&self, nonce: u64) -> bool {
+        let mut nonces = self.nonces.lock();
+        if !nonces.insert(nonce) {
+            return false;
+        }
+        while nonces.len() > MAX_RECENT_NONCES {
+            nonces.shift_remove_index(0);
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recent_nonces() {
+        let nonces = RecentNonces::default();
+
+        // Ensure a nonce is only accepted once.
+        assert!(!nonces.contains(1));
+        assert!(nonces.insert(1));
+        assert!(nonces.contains(1));
+        assert!(!nonces.insert(1));
+
+        // Ensure the oldest nonces are forgotten beyond the maximum.
+        (0..MAX_RECENT_NONCES as u64).for_each(|nonce| assert!(nonces.insert(100 + nonce)));
+        assert!(!nonces.contains(1));
+        assert!(nonces.contains(100));
+    }
+}
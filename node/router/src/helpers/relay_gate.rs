@@ -0,0 +1,137 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+
+/// The default number of blocks the node may be behind the median peer height, before its gossip is suppressed.
+pub const DEFAULT_RELAY_GATE_BLOCKS: u32 = 100;
+
+/// The gate on the gossip of unconfirmed transmissions and blocks, while the node is significantly behind.
+///
+/// A node that is far behind relays long-stale items, which wastes the bandwidth of its peers, and
+/// pollutes their deduplication caches. The gate is engaged while the node is more than the threshold
+/// number of blocks behind the median peer height, and is lifted once the node is within the threshold.
+/// Note: The unconfirmed transmissions are still accepted and queued locally while the gate is engaged.
+pub struct RelayGate {
+    /// The number of blocks the node may be behind, before its gossip is suppressed.
+    threshold: AtomicU32,
+    /// Whether the gate is engaged, as of the latest update.
+    is_engaged: AtomicBool,
+    /// The number of blocks the node was behind, as of the latest update.
+    num_blocks_behind: AtomicU32,
+    /// The number of messages that were suppressed by the gate.
+    num_suppressed: AtomicU64,
+}
+
+impl Default for RelayGate {
+    fn default() -> Self {
+        Self::new(DEFAULT_RELAY_GATE_BLOCKS)
+    }
+}
+
+impl RelayGate {
+    /// Initializes a new relay gate, with the given threshold.
+    pub fn new(threshold: u32) -> Self {
+        Self {
+            threshold: AtomicU32::new(threshold),
+            is_engaged: Default::default(),
+            num_blocks_behind: Default::default(),
+            num_suppressed: Default::default(),
+        }
+    }
+
+    /// Returns the number of blocks the node may be behind, before its gossip is suppressed.
+    pub fn threshold(&self) -> u32 {
+        self.threshold.load(Ordering::Relaxed)
+    }
+
+    /// Sets the number of blocks the node may be behind, before its gossip is suppressed.
+    pub fn set_threshold(&self, threshold: u32) {
+        self.threshold.store(threshold, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if the gate is engaged, as of the latest update.
+    pub fn is_engaged(&self) -> bool {
+        self.is_engaged.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of blocks the node was behind, as of the latest update.
+    pub fn num_blocks_behind(&self) -> u32 {
+        self.num_blocks_behind.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of messages that were suppressed by the gate.
+    pub fn num_suppressed(&self) -> u64 {
+        self.num_suppressed.load(Ordering::Relaxed)
+    }
+
+    /// Updates the gate with the number of blocks the node is behind, and returns `true` if the gate is engaged.
+    pub fn update(&self, num_blocks_behind: u32) -> bool {
+        let threshold = self.threshold();
+        let is_engaged = num_blocks_behind > threshold;
+        self.num_blocks_behind.store(num_blocks_behind, Ordering::Relaxed);
+        // Log the transitions of the gate.
+        if self.is_engaged.swap(is_engaged, Ordering::Relaxed) != is_engaged {
+            match is_engaged {
+                true => info!(
+                    "Suppressing the gossip of transactions, solutions, and blocks - \
+                     the node is {num_blocks_behind} blocks behind (more than {threshold})"
+                ),
+                false => info!("Resuming the gossip of transactions, solutions, and blocks - the node caught up"),
+            }
+        }
+        is_engaged
+    }
+
+    /// Records that the given number of messages were suppressed by the gate.
+    pub fn record_suppressed(&self, num_messages: u64) {
+        self.num_suppressed.fetch_add(num_messages, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relay_gate() {
+        let gate = RelayGate::new(10);
+        assert!(!gate.is_engaged());
+
+        // Ensure the gate is not engaged within the threshold.
+        assert!(!gate.update(0));
+        assert!(!gate.update(10));
+        assert!(!gate.is_engaged());
+
+        // Ensure the gate is engaged beyond the threshold.
+        assert!(gate.update(11));
+        assert!(gate.is_engaged());
+        assert_eq!(gate.num_blocks_behind(), 11);
+
+        // Ensure the gate is lifted once the node is within the threshold.
+        assert!(!gate.update(3));
+        assert!(!gate.is_engaged());
+
+        // Ensure the threshold can be changed.
+        gate.set_threshold(2);
+        assert_eq!(gate.threshold(), 2);
+        assert!(gate.update(3));
+
+        // Ensure the suppressed messages are counted.
+        gate.record_suppressed(2);
+        gate.record_suppressed(1);
+        assert_eq!(gate.num_suppressed(), 3);
+    }
+}
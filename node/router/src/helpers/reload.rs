@@ -0,0 +1,214 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::panic_message;
+
+use anyhow::Result;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::{
+    fmt,
+    panic::{AssertUnwindSafe, catch_unwind},
+    sync::Arc,
+};
+
+/// The closure that re-reads, validates, and applies the configuration of a reloadable item,
+/// returning a description of the changes, or `None` if the configuration is unchanged.
+type Reload = Arc<dyn Fn() -> Result<Option<String>> + Send + Sync>;
+
+/// The outcome of the reload of an item.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ReloadStatus {
+    /// The configuration of the item changed as described.
+    Changed { changes: String },
+    /// The configuration of the item is unchanged.
+    Unchanged,
+    /// The reload failed, and the previous configuration of the item remains active.
+    Failed { error: String },
+}
+
+/// The outcome of the reload of a named item.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ReloadOutcome {
+    /// The name of the item.
+    pub name: String,
+    /// The outcome of the reload.
+    #[serde(flatten)]
+    pub status: ReloadStatus,
+}
+
+/// The outcomes of a reload of every registered item, in the order of their registration.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct ReloadSummary {
+    /// The outcome of each item.
+    pub items: Vec<ReloadOutcome>,
+}
+
+impl ReloadSummary {
+    /// Returns the number of items whose configuration changed.
+    pub fn num_changed(&self) -> usize {
+        self.items.iter().filter(|item| matches!(item.status, ReloadStatus::Changed { .. })).count()
+    }
+
+    /// Returns the number of items that failed to reload.
+    pub fn num_failed(&self) -> usize {
+        self.items.iter().filter(|item| matches!(item.status, ReloadStatus::Failed { .. })).count()
+    }
+
+    /// Returns the outcome of the item with the given name, if it was reloaded.
+    pub fn get(&self, name: &str) -> Option<&ReloadStatus> {
+        self.items.iter().find(|item| item.name == name).map(|item| &item.status)
+    }
+}
+
+impl fmt::Display for ReloadSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Reloaded {} item(s) - {} changed, {} failed",
+            self.items.len(),
+            self.num_changed(),
+            self.num_failed()
+        )?;
+        for item in &self.items {
+            match &item.status {
+                ReloadStatus::Changed { changes } => write!(f, "\n  '{}' changed: {changes}", item.name)?,
+                ReloadStatus::Unchanged => write!(f, "\n  '{}' is unchanged", item.name)?,
+                ReloadStatus::Failed { error } => write!(f, "\n  '{}' failed: {error}", item.name)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The registry of the items whose configuration can be reloaded at runtime, e.g. on `SIGHUP`.
+///
+/// Each item registers a closure that re-reads its configuration source, validates it in full,
+/// and only then swaps it in, so that a failed reload leaves the previous configuration active.
+/// The items are reloaded independently, so that the failure of one item does not block the others.
+#[derive(Clone, Default)]
+pub struct ReloadRegistry {
+    /// The registered items, in the order of their registration.
+    items: Arc<Mutex<Vec<(String, Reload)>>>,
+    /// The lock held during a reload, so that concurrent reloads do not interleave.
+    reloading: Arc<Mutex<()>>,
+}
+
+impl ReloadRegistry {
+    /// Registers the item with the given name, replacing any item registered with the same name.
+    pub fn register<F>(&self, name: &str, reload: F)
+    where
+        F: Fn() -> Result<Option<String>> + Send + Sync + 'static,
+    {
+        let mut items = self.items.lock();
+        items.retain(|(item_name, _)| item_name != name);
+        items.push((name.to_string(), Arc::new(reload)));
+    }
+
+    /// Returns the names of the registered items, in the order of their registration.
+    pub fn names(&self) -> Vec<String> {
+        self.items.lock().iter().map(|(name, _)| name.clone()).collect()
+    }
+
+    /// Reloads every registered item, and logs the summary.
+    ///
+    /// Note: The items may read their configuration from the disk, so this is expected to run on a blocking thread.
+    pub fn reload_all(&self) -> ReloadSummary {
+        let _reloading = self.reloading.lock();
+        // Release the registry while the items are reloaded, so that an item may register another one.
+        let items = self.items.lock().clone();
+        let items = items
+            .into_iter()
+            .map(|(name, reload)| {
+                let status = match catch_unwind(AssertUnwindSafe(&*reload)) {
+                    Ok(Ok(Some(changes))) => ReloadStatus::Changed { changes },
+                    Ok(Ok(None)) => ReloadStatus::Unchanged,
+                    Ok(Err(error)) => ReloadStatus::Failed { error: error.to_string() },
+                    Err(payload) => ReloadStatus::Failed { error: format!("panicked - {}", panic_message(&*payload)) },
+                };
+                ReloadOutcome { name, status }
+            })
+            .collect();
+        let summary = ReloadSummary { items };
+        match summary.num_failed() {
+            0 => info!("{summary}"),
+            _ => warn!("{summary}"),
+        }
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::bail;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[test]
+    fn test_reload_all() {
+        let registry = ReloadRegistry::default();
+        let value = Arc::new(AtomicU32::new(1));
+
+        // Register an item that changes, one that is unchanged, one that fails, and one that panics.
+        let value_ = value.clone();
+        registry.register("changed", move || {
+            let previous = value_.swap(2, Ordering::SeqCst);
+            Ok((previous != 2).then(|| format!("{previous} -> 2")))
+        });
+        registry.register("failed", || bail!("the file is malformed"));
+        registry.register("panicked", || panic!("the item is broken"));
+        registry.register("unchanged", || Ok(None));
+        assert_eq!(registry.names(), ["changed", "failed", "panicked", "unchanged"]);
+
+        // Ensure every item is reloaded, regardless of the failures.
+        let summary = registry.reload_all();
+        assert_eq!(summary.items.len(), 4);
+        assert_eq!(summary.get("changed"), Some(&ReloadStatus::Changed { changes: "1 -> 2".to_string() }));
+        assert_eq!(summary.get("failed"), Some(&ReloadStatus::Failed { error: "the file is malformed".to_string() }));
+        assert_eq!(
+            summary.get("panicked"),
+            Some(&ReloadStatus::Failed { error: "panicked - the item is broken".to_string() })
+        );
+        assert_eq!(summary.get("unchanged"), Some(&ReloadStatus::Unchanged));
+        assert_eq!((summary.num_changed(), summary.num_failed()), (1, 2));
+        assert_eq!(value.load(Ordering::SeqCst), 2);
+
+        // Ensure a reload with no change reports the item as unchanged.
+        assert_eq!(registry.reload_all().get("changed"), Some(&ReloadStatus::Unchanged));
+
+        // Ensure an item registered again replaces the previous one.
+        registry.register("failed", || Ok(None));
+        assert_eq!(registry.names(), ["changed", "panicked", "unchanged", "failed"]);
+        assert_eq!(registry.reload_all().get("failed"), Some(&ReloadStatus::Unchanged));
+    }
+
+    #[test]
+    fn test_reload_summary_serialization() {
+        let summary = ReloadSummary {
+            items: vec![
+                ReloadOutcome { name: "a".to_string(), status: ReloadStatus::Changed { changes: "x".to_string() } },
+                ReloadOutcome { name: "b".to_string(), status: ReloadStatus::Unchanged },
+            ],
+        };
+        assert_eq!(
+            serde_json::to_value(&summary).unwrap(),
+            serde_json::json!({ "items": [
+                { "name": "a", "status": "changed", "changes": "x" },
+                { "name": "b", "status": "unchanged" },
+            ] })
+        );
+    }
+}
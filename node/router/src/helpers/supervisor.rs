@@ -0,0 +1,329 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use futures::FutureExt;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::{
+    any::Any,
+    future::Future,
+    ops::Deref,
+    panic::AssertUnwindSafe,
+    sync::{
+        Arc,
+        atomic::{AtomicU32, Ordering},
+    },
+    time::Duration,
+};
+use tokio::{sync::Notify, task::JoinHandle, time::Instant};
+
+/// The time allotted in seconds for the supervised tasks to terminate on shutdown.
+pub const TASK_SHUTDOWN_TIMEOUT_IN_SECS: u64 = 5;
+/// The delay in milliseconds before a failed task is restarted.
+const TASK_RESTART_DELAY_IN_MS: u64 = 100;
+
+/// The policy applied when a supervised task panics or exits unexpectedly.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskPolicy {
+    /// The task is not critical: it may run to completion, and a panic is only logged.
+    Auxiliary,
+    /// The task is critical, and is restarted up to the given number of times before the node is shut down.
+    Restart { max_restarts: u32 },
+    /// The task is critical, and the node is shut down if it fails.
+    Shutdown,
+}
+
+impl TaskPolicy {
+    /// Returns `true` if the node depends on the task running until shutdown.
+    pub const fn is_critical(&self) -> bool {
+        !matches!(self, Self::Auxiliary)
+    }
+}
+
+/// The state of a supervised task.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct TaskInfo {
+    /// The component that spawned the task.
+    pub component: &'static str,
+    /// The name of the task.
+    pub name: String,
+    /// The policy applied when the task fails.
+    pub policy: TaskPolicy,
+    /// The number of times the task has been restarted.
+    pub num_restarts: u32,
+    /// Whether the task is still running.
+    pub is_running: bool,
+}
+
+/// A task registered with the supervisor.
+struct SupervisedTask {
+    /// The name of the task.
+    name: String,
+    /// The policy applied when the task fails.
+    policy: TaskPolicy,
+    /// The number of times the task has been restarted.
+    num_restarts: Arc<AtomicU32>,
+    /// The handle of the task.
+    handle: JoinHandle<()>,
+}
+
+/// A supervisor for the long-running tasks of a component.
+///
+/// Each task is spawned from a factory, so that it can be restarted after a panic or an unexpected exit,
+/// according to its policy. Once a critical task fails for good, the failure is reported to any caller
+/// of `critical_failure`, which is expected to shut down the node.
+#[derive(Clone)]
+pub struct TaskSupervisor(Arc<InnerTaskSupervisor>);
+
+impl Deref for TaskSupervisor {
+    type Target = Arc<InnerTaskSupervisor>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+pub struct InnerTaskSupervisor {
+    /// The name of the component that owns the supervisor.
+    component: &'static str,
+    /// The supervised tasks, in the order of their registration.
+    tasks: Mutex<Vec<SupervisedTask>>,
+    /// The reason the first critical task failed, if one has failed.
+    failure: Mutex<Option<String>>,
+    /// The notifier for a critical failure.
+    failure_notify: Notify,
+}
+
+impl TaskSupervisor {
+    /// Initializes a new supervisor for the given component.
+    pub fn new(component: &'static str) -> Self {
+        Self(Arc::new(InnerTaskSupervisor {
+            component,
+            tasks: Default::default(),
+            failure: Default::default(),
+            failure_notify: Notify::new(),
+        }))
+    }
+
+    /// Returns the name of the component that owns the supervisor.
+    pub fn component(&self) -> &'static str {
+        self.component
+    }
+
+    /// Spawns a task with the given name and policy, from the given factory.
+    /// The factory is invoked again for each restart of the task.
+    pub fn spawn<F, T>(&self, name: &str, policy: TaskPolicy, factory: F)
+    where
+        F: Fn() -> T + Send + 'static,
+        T: Future<Output = ()> + Send + 'static,
+    {
+        let num_restarts = Arc::new(AtomicU32::new(0));
+
+        let supervisor = self.clone();
+        let task_name = name.to_string();
+        let task_num_restarts = num_restarts.clone();
+        let handle = tokio::spawn(async move {
+            loop {
+                // Run the task, catching any panic.
+                let cause = match AssertUnwindSafe(factory()).catch_unwind().await {
+                    // A non-critical task may run to completion.
+                    Ok(()) if !policy.is_critical() => return,
+                    Ok(()) => "exited unexpectedly".to_string(),
+                    Err(payload) => format!("panicked - {}", panic_message(&*payload)),
+                };
+                error!("Task '{task_name}' in the {} {cause}", supervisor.component);
+                #[cfg(feature = "metrics")]
+                metrics::increment_counter_label(metrics::tasks::FAILURES, "task", task_name.clone());
+
+                match policy {
+                    TaskPolicy::Auxiliary => return,
+                    TaskPolicy::Restart { max_restarts }
+                        if task_num_restarts.load(Ordering::Relaxed) < max_restarts =>
+                    {
+                        let num_restarts = task_num_restarts.fetch_add(1, Ordering::Relaxed) + 1;
+                        warn!(
+                            "Restarting task '{task_name}' in the {} ({num_restarts}/{max_restarts})",
+                            supervisor.component
+                        );
+                        tokio::time::sleep(Duration::from_millis(TASK_RESTART_DELAY_IN_MS)).await;
+                    }
+                    // The task is critical, and may not be restarted.
+                    _ => {
+                        supervisor.report_failure(format!(
+                            "critical task '{task_name}' in the {} {cause}",
+                            supervisor.component
+                        ));
+                        return;
+                    }
+                }
+            }
+        });
+
+        let mut tasks = self.tasks.lock();
+        // Remove the non-critical tasks that have run to completion.
+        tasks.retain(|task| task.policy.is_critical() || !task.handle.is_finished());
+        tasks.push(SupervisedTask { name: name.to_string(), policy, num_restarts, handle });
+    }
+
+    /// Returns the state of the supervised tasks, in the order of their registration.
+    pub fn tasks(&self) -> Vec<TaskInfo> {
+        self.tasks
+            .lock()
+            .iter()
+            .map(|task| TaskInfo {
+                component: self.component,
+                name: task.name.clone(),
+                policy: task.policy,
+                num_restarts: task.num_restarts.load(Ordering::Relaxed),
+                is_running: !task.handle.is_finished(),
+            })
+            .collect()
+    }
+
+    /// Returns the reason a critical task failed, if one has failed.
+    pub fn failure(&self) -> Option<String> {
+        self.failure.lock().clone()
+    }
+
+    /// Waits until a critical task has failed, and returns the reason.
+    pub async fn critical_failure(&self) -> String {
+        loop {
+            // Register for the notification before checking, so that a failure is not missed.
+            let notified = self.failure_notify.notified();
+            if let Some(reason) = self.failure() {
+                return reason;
+            }
+            notified.await;
+        }
+    }
+
+    /// Records the failure of a critical task, and notifies any waiters.
+    fn report_failure(&self, reason: String) {
+        // Retain the first failure.
+        self.failure.lock().get_or_insert(reason);
+        self.failure_notify.notify_waiters();
+    }
+
+    /// Aborts the supervised tasks in the reverse order of their registration,
+    /// and awaits their termination until the given timeout has elapsed.
+    pub async fn shut_down(&self, timeout: Duration) {
+        let tasks = std::mem::take(&mut *self.tasks.lock());
+        let deadline = Instant::now() + timeout;
+        for task in tasks.into_iter().rev() {
+            task.handle.abort();
+            if tokio::time::timeout_at(deadline, task.handle).await.is_err() {
+                warn!("Task '{}' in the {} did not terminate within {timeout:?}", task.name, self.component);
+            }
+        }
+    }
+}
+
+/// Returns the message of the given panic payload.
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    match (payload.downcast_ref::<&str>(), payload.downcast_ref::<String>()) {
+        (Some(message), _) => message,
+        (_, Some(message)) => message,
+        (None, None) => "unknown panic",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_restart_then_shutdown() {
+        let supervisor = TaskSupervisor::new("test");
+        let num_runs = Arc::new(AtomicU32::new(0));
+
+        // Spawn a critical task that always panics.
+        let num_runs_ = num_runs.clone();
+        supervisor.spawn("faulty", TaskPolicy::Restart { max_restarts: 2 }, move || {
+            num_runs_.fetch_add(1, Ordering::SeqCst);
+            async { panic!("the task was killed") }
+        });
+
+        // Ensure the task is restarted until its budget is exhausted, and the failure is reported.
+        let reason = tokio::time::timeout(Duration::from_secs(5), supervisor.critical_failure()).await.unwrap();
+        assert_eq!(reason, "critical task 'faulty' in the test panicked - the task was killed");
+        assert_eq!(num_runs.load(Ordering::SeqCst), 3);
+        let tasks = supervisor.tasks();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].num_restarts, 2);
+        assert!(!tasks[0].is_running);
+    }
+
+    #[tokio::test]
+    async fn test_critical_exit() {
+        let supervisor = TaskSupervisor::new("test");
+
+        // Spawn a critical task that exits.
+        supervisor.spawn("exiting", TaskPolicy::Shutdown, || async {});
+
+        // Ensure the node is shut down without a restart.
+        let reason = tokio::time::timeout(Duration::from_secs(5), supervisor.critical_failure()).await.unwrap();
+        assert_eq!(reason, "critical task 'exiting' in the test exited unexpectedly");
+        assert_eq!(supervisor.tasks()[0].num_restarts, 0);
+    }
+
+    #[tokio::test]
+    async fn test_auxiliary_failure() {
+        let supervisor = TaskSupervisor::new("test");
+
+        // Spawn auxiliary tasks that complete and panic.
+        supervisor.spawn("complete", TaskPolicy::Auxiliary, || async {});
+        supervisor.spawn("faulty", TaskPolicy::Auxiliary, || async { panic!("the task was killed") });
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        // Ensure no failure is reported.
+        assert!(supervisor.failure().is_none());
+        // Ensure the finished tasks are removed on the next registration.
+        supervisor.spawn("pending", TaskPolicy::Auxiliary, futures::future::pending);
+        let names = supervisor.tasks().into_iter().map(|task| task.name).collect::<Vec<_>>();
+        assert_eq!(names, vec!["pending"]);
+    }
+
+    #[tokio::test]
+    async fn test_shut_down_order() {
+        /// Records the name of the task when it is dropped.
+        struct Guard(&'static str, Arc<Mutex<Vec<&'static str>>>);
+        impl Drop for Guard {
+            fn drop(&mut self) {
+                self.1.lock().push(self.0);
+            }
+        }
+
+        let supervisor = TaskSupervisor::new("test");
+        let dropped = Arc::new(Mutex::new(Vec::new()));
+
+        for name in ["first", "second", "third"] {
+            let dropped = dropped.clone();
+            supervisor.spawn(name, TaskPolicy::Shutdown, move || {
+                let guard = Guard(name, dropped.clone());
+                async move {
+                    let _guard = guard;
+                    futures::future::pending::<()>().await
+                }
+            });
+        }
+
+        // Ensure the tasks are terminated in the reverse order, without reporting a failure.
+        supervisor.shut_down(Duration::from_secs(5)).await;
+        assert_eq!(*dropped.lock(), vec!["third", "second", "first"]);
+        assert!(supervisor.tasks().is_empty());
+        assert!(supervisor.failure().is_none());
+    }
+}
@@ -0,0 +1,25 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+/// The progress of the block sync of a node, as tracked by its sync module.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct SyncProgress {
+    /// The median block height reported by the peers, if any peer reported its block locators.
+    ///
+    /// Note: Unlike the greatest peer height, a single peer claiming a height it does not have cannot inflate it.
+    pub peer_height: Option<u32>,
+    /// The recent number of blocks the ledger advanced by per second, if it was sampled over a long enough window.
+    pub blocks_per_sec: Option<f64>,
+}
@@ -0,0 +1,56 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::net::SocketAddr;
+
+/// The summary of a forced sync against a specific peer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SyncSummary {
+    /// The IP address of the peer that was synced from.
+    peer_ip: SocketAddr,
+    /// The latest block height of this node, at the time of the sync.
+    our_height: u32,
+    /// The latest block height advertised by the peer.
+    peer_height: u32,
+    /// The number of blocks requested from the peer.
+    num_blocks_requested: usize,
+}
+
+impl SyncSummary {
+    /// Initializes a new instance of `SyncSummary`.
+    pub const fn new(peer_ip: SocketAddr, our_height: u32, peer_height: u32, num_blocks_requested: usize) -> Self {
+        Self { peer_ip, our_height, peer_height, num_blocks_requested }
+    }
+
+    /// Returns the IP address of the peer that was synced from.
+    pub const fn peer_ip(&self) -> SocketAddr {
+        self.peer_ip
+    }
+
+    /// Returns the latest block height of this node, at the time of the sync.
+    pub const fn our_height(&self) -> u32 {
+        self.our_height
+    }
+
+    /// Returns the latest block height advertised by the peer.
+    pub const fn peer_height(&self) -> u32 {
+        self.peer_height
+    }
+
+    /// Returns the number of blocks requested from the peer.
+    pub const fn num_blocks_requested(&self) -> usize {
+        self.num_blocks_requested
+    }
+}
@@ -0,0 +1,354 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use parking_lot::{Mutex, RwLock, const_mutex};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    net::SocketAddr,
+    sync::{
+        OnceLock,
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+    },
+    time::{Duration, Instant},
+};
+use tracing::Span;
+
+/// The name of the spans of the trace scopes, within which the filters of the loggers elevate the log events.
+pub const TRACE_SCOPE_SPAN: &str = "trace_scope";
+/// The maximum duration in seconds of a trace scope.
+pub const MAX_TRACE_SCOPE_DURATION_IN_SECS: u64 = 60 * 60;
+/// The maximum number of active trace scopes.
+pub const MAX_TRACE_SCOPES: usize = 16;
+
+/// The level of the log events of a trace scope.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TraceLevel {
+    Debug,
+    Trace,
+}
+
+impl TraceLevel {
+    /// The levels of the trace scopes.
+    pub const ALL: [Self; 2] = [Self::Debug, Self::Trace];
+
+    /// Returns the name of the level.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Debug => "debug",
+            Self::Trace => "trace",
+        }
+    }
+
+    /// Returns the filter directive that elevates the log events within the spans of the scopes of this level.
+    pub fn directive(&self) -> String {
+        format!("[{TRACE_SCOPE_SPAN}{{level={0}}}]={0}", self.as_str())
+    }
+
+    /// Returns a span, within which the log events are elevated to this level while a scope of the level is active.
+    pub fn span(&self) -> Span {
+        // Note: The name must be a literal, and matches `TRACE_SCOPE_SPAN`.
+        tracing::info_span!("trace_scope", level = self.as_str())
+    }
+}
+
+/// The reason a trace scope could not be activated.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TraceScopeError {
+    /// The duration of the scope is zero, or exceeds `MAX_TRACE_SCOPE_DURATION_IN_SECS`.
+    InvalidDuration,
+    /// There are already `MAX_TRACE_SCOPES` active scopes.
+    Full,
+}
+
+impl fmt::Display for TraceScopeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidDuration => write!(
+                f,
+                "The duration of a trace scope must be between 1 and {MAX_TRACE_SCOPE_DURATION_IN_SECS} seconds"
+            ),
+            Self::Full => write!(f, "There are already {MAX_TRACE_SCOPES} active trace scopes"),
+        }
+    }
+}
+
+impl std::error::Error for TraceScopeError {}
+
+/// The number of active trace scopes of each level, across the trace scopes of the process.
+static NUM_SCOPES_PER_LEVEL: Mutex<[usize; TraceLevel::ALL.len()]> = const_mutex([0; TraceLevel::ALL.len()]);
+/// The callback reloading the filters of the loggers, with the levels of the active trace scopes.
+static RELOAD_FILTERS: OnceLock<Box<dyn Fn(&[TraceLevel]) + Send + Sync>> = OnceLock::new();
+
+/// Sets the callback reloading the filters of the loggers, whenever the levels of the active trace scopes change.
+/// The filters are expected to include the [`TraceLevel::directive`] of each of the given levels.
+pub fn set_trace_filter_reload(reload: impl Fn(&[TraceLevel]) + Send + Sync + 'static) {
+    if RELOAD_FILTERS.set(Box::new(reload)).is_err() {
+        warn!("The filters of the trace scopes are already reloaded by another logger");
+    }
+}
+
+/// Updates the number of active scopes of each level, and reloads the filters if the active levels changed.
+fn update_active_levels(added: Option<TraceLevel>, removed: impl IntoIterator<Item = TraceLevel>) {
+    let mut num_scopes = NUM_SCOPES_PER_LEVEL.lock();
+    let previous = *num_scopes;
+    if let Some(level) = added {
+        num_scopes[level as usize] += 1;
+    }
+    for level in removed {
+        num_scopes[level as usize] = num_scopes[level as usize].saturating_sub(1);
+    }
+    // Reload while holding the lock, so that the reloads are applied in order.
+    let is_active = |counts: &[usize], level: TraceLevel| counts[level as usize] > 0;
+    if TraceLevel::ALL.iter().any(|level| is_active(&previous, *level) != is_active(&*num_scopes, *level)) {
+        if let Some(reload) = RELOAD_FILTERS.get() {
+            let levels: Vec<_> = TraceLevel::ALL.into_iter().filter(|level| is_active(&*num_scopes, *level)).collect();
+            reload(&levels);
+        }
+    }
+}
+
+/// The subject of a trace scope.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TraceTarget {
+    /// The messages sent to and received from the peer with the given listener IP.
+    Peer(SocketAddr),
+    /// The REST requests with a path starting with the given prefix, relative to the network (e.g. `/block`).
+    Route(String),
+    /// The messages of the given kind (e.g. `BlockRequest`), sent to and received from any peer.
+    Event(String),
+}
+
+impl TraceTarget {
+    /// Returns `true` if the target covers the given message, sent to or received from the given peer.
+    fn matches_message(&self, peer_ip: SocketAddr, name: &str) -> bool {
+        match self {
+            Self::Peer(ip) => *ip == peer_ip,
+            Self::Event(event) => event == name,
+            Self::Route(..) => false,
+        }
+    }
+
+    /// Returns `true` if the target covers the REST requests of the given path, relative to the network.
+    fn matches_route(&self, path: &str) -> bool {
+        match self {
+            Self::Route(prefix) => path.starts_with(prefix.as_str()),
+            Self::Peer(..) | Self::Event(..) => false,
+        }
+    }
+}
+
+impl fmt::Display for TraceTarget {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Peer(peer_ip) => write!(f, "peer '{peer_ip}'"),
+            Self::Route(prefix) => write!(f, "route '{prefix}'"),
+            Self::Event(name) => write!(f, "event '{name}'"),
+        }
+    }
+}
+
+/// An active trace scope.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct TraceScope {
+    /// The ID of the scope.
+    pub id: u64,
+    /// The subject of the scope.
+    #[serde(flatten)]
+    pub target: TraceTarget,
+    /// The level of the log events of the scope.
+    pub level: TraceLevel,
+    /// The number of seconds until the scope expires.
+    pub expires_in_secs: u64,
+}
+
+/// A trace scope, with its expiry.
+struct ScopeEntry {
+    id: u64,
+    target: TraceTarget,
+    level: TraceLevel,
+    expires_at: Instant,
+}
+
+/// The trace scopes of the node, which elevate the logging of a single peer, REST route or message kind
+/// for a bounded duration, without raising the verbosity of the whole node.
+///
+/// The call sites check for a matching scope, and run within the [`TraceLevel::span`] of its level, as the filters
+/// of the loggers can not tell the peer or the route an event relates to. While a scope of a level is active,
+/// the filters are reloaded with its [`TraceLevel::directive`], which elevates the existing log events of the spans.
+#[derive(Default)]
+pub struct TraceScopes {
+    /// The ID of the next scope.
+    next_id: AtomicU64,
+    /// The number of scopes, including the expired ones that are not pruned yet.
+    /// Note: This is checked ahead of the scopes, so the call sites are not slowed down while there are none.
+    num_scopes: AtomicUsize,
+    /// The scopes.
+    scopes: RwLock<Vec<ScopeEntry>>,
+}
+
+impl TraceScopes {
+    /// Activates a scope on the given target at the given level, until the given duration has elapsed.
+    pub fn insert(
+        &self,
+        target: TraceTarget,
+        level: TraceLevel,
+        duration: Duration,
+    ) -> Result<TraceScope, TraceScopeError> {
+        if duration.is_zero() || duration > Duration::from_secs(MAX_TRACE_SCOPE_DURATION_IN_SECS) {
+            return Err(TraceScopeError::InvalidDuration);
+        }
+        let now = Instant::now();
+        let mut scopes = self.scopes.write();
+        Self::retain_active(&mut scopes, now);
+        if scopes.len() >= MAX_TRACE_SCOPES {
+            return Err(TraceScopeError::Full);
+        }
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        info!("Enabled {level:?} logging for {target} for {}s", duration.as_secs());
+        let entry = ScopeEntry { id, target, level, expires_at: now + duration };
+        let scope = Self::to_scope(&entry, now);
+        scopes.push(entry);
+        self.num_scopes.store(scopes.len(), Ordering::Relaxed);
+        update_active_levels(Some(level), None);
+        Ok(scope)
+    }
+
+    /// Returns the active scopes, in the order they were activated.
+    pub fn active(&self) -> Vec<TraceScope> {
+        let now = Instant::now();
+        self.prune(now);
+        self.scopes.read().iter().map(|entry| Self::to_scope(entry, now)).collect()
+    }
+
+    /// Returns the level of the active scopes covering the given message, sent to or received from the given peer.
+    pub fn level_for_message(&self, peer_ip: SocketAddr, name: &str) -> Option<TraceLevel> {
+        self.level_for(|target| target.matches_message(peer_ip, name))
+    }
+
+    /// Returns the level of the active scopes covering the REST requests of the given path, relative to the network.
+    pub fn level_for_route(&self, path: &str) -> Option<TraceLevel> {
+        self.level_for(|target| target.matches_route(path))
+    }
+
+    /// Returns the highest level of the active scopes with a matching target, if any.
+    fn level_for(&self, matches: impl Fn(&TraceTarget) -> bool) -> Option<TraceLevel> {
+        if self.num_scopes.load(Ordering::Relaxed) == 0 {
+            return None;
+        }
+        let now = Instant::now();
+        let (level, has_expired) = {
+            let scopes = self.scopes.read();
+            let level = scopes
+                .iter()
+                .filter(|scope| scope.expires_at > now && matches(&scope.target))
+                .map(|scope| scope.level)
+                .max();
+            (level, scopes.iter().any(|scope| scope.expires_at <= now))
+        };
+        if has_expired {
+            self.prune(now);
+        }
+        level
+    }
+
+    /// Removes the scopes that expired by the given time.
+    fn prune(&self, now: Instant) {
+        let mut scopes = self.scopes.write();
+        Self::retain_active(&mut scopes, now);
+        self.num_scopes.store(scopes.len(), Ordering::Relaxed);
+    }
+
+    /// Removes the given scopes that expired by the given time, and reloads the filters of the loggers accordingly.
+    fn retain_active(scopes: &mut Vec<ScopeEntry>, now: Instant) {
+        let mut expired = Vec::new();
+        scopes.retain(|scope| {
+            let is_active = scope.expires_at > now;
+            if !is_active {
+                info!("The {:?} logging for {} has expired", scope.level, scope.target);
+                expired.push(scope.level);
+            }
+            is_active
+        });
+        if !expired.is_empty() {
+            update_active_levels(None, expired);
+        }
+    }
+
+    /// Returns the view of the given scope, as of the given time.
+    fn to_scope(entry: &ScopeEntry, now: Instant) -> TraceScope {
+        TraceScope {
+            id: entry.id,
+            target: entry.target.clone(),
+            level: entry.level,
+            expires_in_secs: entry.expires_at.saturating_duration_since(now).as_secs(),
+        }
+    }
+}
+
+impl Drop for TraceScopes {
+    fn drop(&mut self) {
+        // Release the levels of the scopes that are still active, as their elevated logging ends with them.
+        update_active_levels(None, self.scopes.get_mut().drain(..).map(|scope| scope.level));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trace_scopes() {
+        let scopes = TraceScopes::default();
+        let (peer, other) = ("1.1.1.1:4130".parse().unwrap(), "2.2.2.2:4130".parse().unwrap());
+        assert_eq!(scopes.level_for_message(peer, "Ping"), None);
+
+        // Ensure the durations are bounded.
+        let error = scopes.insert(TraceTarget::Peer(peer), TraceLevel::Trace, Duration::ZERO).unwrap_err();
+        assert_eq!(error, TraceScopeError::InvalidDuration);
+        let too_long = Duration::from_secs(MAX_TRACE_SCOPE_DURATION_IN_SECS + 1);
+        let error = scopes.insert(TraceTarget::Peer(peer), TraceLevel::Trace, too_long).unwrap_err();
+        assert_eq!(error, TraceScopeError::InvalidDuration);
+
+        // Ensure each scope covers its target only, at the highest level of the matching scopes.
+        let minute = Duration::from_secs(60);
+        scopes.insert(TraceTarget::Peer(peer), TraceLevel::Debug, minute).unwrap();
+        scopes.insert(TraceTarget::Event("BlockRequest".to_string()), TraceLevel::Trace, minute).unwrap();
+        scopes.insert(TraceTarget::Route("/block".to_string()), TraceLevel::Debug, minute).unwrap();
+        assert_eq!(scopes.level_for_message(peer, "Ping"), Some(TraceLevel::Debug));
+        assert_eq!(scopes.level_for_message(peer, "BlockRequest"), Some(TraceLevel::Trace));
+        assert_eq!(scopes.level_for_message(other, "BlockRequest"), Some(TraceLevel::Trace));
+        assert_eq!(scopes.level_for_message(other, "Ping"), None);
+        assert_eq!(scopes.level_for_route("/block/latest"), Some(TraceLevel::Debug));
+        assert_eq!(scopes.level_for_route("/transaction/broadcast"), None);
+
+        // Ensure the active scopes are listed in order.
+        let active = scopes.active();
+        assert_eq!(active.iter().map(|scope| scope.id).collect::<Vec<_>>(), vec![0, 1, 2]);
+        let json = serde_json::to_value(&active[0]).unwrap();
+        assert_eq!(json["peer"], "1.1.1.1:4130");
+        assert_eq!(json["level"], "debug");
+        assert!(json["expires_in_secs"].as_u64().unwrap() <= 60);
+
+        // Ensure the number of scopes is bounded.
+        for _ in active.len()..MAX_TRACE_SCOPES {
+            scopes.insert(TraceTarget::Peer(other), TraceLevel::Debug, minute).unwrap();
+        }
+        let error = scopes.insert(TraceTarget::Peer(other), TraceLevel::Debug, minute).unwrap_err();
+        assert_eq!(error, TraceScopeError::Full);
+    }
+}
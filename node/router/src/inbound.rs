@@ -14,31 +14,40 @@
 // limitations under the License.
 
 use crate::{
+    DuplicateOutcome,
+    MAX_MESSAGE_POLICY_VIOLATIONS,
+    MessageKind,
     Outbound,
     Peer,
     messages::{
+        BlockAnnounce,
         BlockRequest,
         BlockResponse,
         DataBlocks,
+        DisconnectReason,
+        Features,
         Message,
         PeerResponse,
         Ping,
         Pong,
+        SolutionAck,
+        SolutionStatus,
         UnconfirmedSolution,
         UnconfirmedTransaction,
     },
 };
-use snarkos_node_tcp::protocols::Reading;
+use snarkos_node_tcp::protocols::{MessagePriority, Reading};
 use snarkvm::prelude::{
     Network,
     block::{Block, Header, Transaction},
-    puzzle::Solution,
+    puzzle::{Puzzle, Solution, SolutionID},
 };
 
 use anyhow::{Result, anyhow, bail};
 use snarkos_node_tcp::is_bogon_ip;
 use std::net::SocketAddr;
 use tokio::task::spawn_blocking;
+use tracing::Instrument;
 
 /// The max number of peers to send in a `PeerResponse` message.
 const MAX_PEERS_TO_SEND: usize = u8::MAX as usize;
@@ -47,18 +56,55 @@ const MAX_PEERS_TO_SEND: usize = u8::MAX as usize;
 /// processing incoming transactions and solutions.
 pub const SYNC_LENIENCY: u32 = 10;
 
+/// Returns the priority of the given inbound message: a `Disconnect` ends the connection, the peer
+/// liveness and discovery messages are processed ahead of the queued blocks and unconfirmed data.
+pub fn inbound_message_priority<N: Network>(message: &Message<N>) -> MessagePriority {
+    match message {
+        Message::Disconnect(..) => MessagePriority::Terminal,
+        Message::Ping(..) | Message::Pong(..) | Message::PeerRequest(..) | Message::PeerResponse(..) => {
+            MessagePriority::Control
+        }
+        _ => MessagePriority::Bulk,
+    }
+}
+
+/// Returns the status of the given solution, checked against the given epoch hash and proof target.
+///
+/// The cheap checks are performed first, so that a stale or insufficient solution is not verified.
+pub fn check_solution_status<N: Network>(
+    puzzle: &Puzzle<N>,
+    solution: &Solution<N>,
+    epoch_hash: N::BlockHash,
+    proof_target: u64,
+) -> SolutionStatus {
+    if solution.epoch_hash() != epoch_hash {
+        SolutionStatus::StaleEpoch
+    } else if solution.target() < proof_target {
+        SolutionStatus::BelowTarget
+    } else if puzzle.check_solution(solution, epoch_hash, proof_target).is_err() {
+        SolutionStatus::Rejected
+    } else {
+        SolutionStatus::Accepted
+    }
+}
+
 #[async_trait]
 pub trait Inbound<N: Network>: Reading + Outbound<N> {
     /// The maximum number of puzzle requests per interval.
     const MAXIMUM_PUZZLE_REQUESTS_PER_INTERVAL: usize = 5;
     /// The maximum number of block requests per interval.
     const MAXIMUM_BLOCK_REQUESTS_PER_INTERVAL: usize = 256;
+    /// The maximum number of solution acknowledgments sent to a peer per interval.
+    const MAXIMUM_SOLUTION_ACKS_PER_INTERVAL: usize = 60;
     /// The duration in seconds to sleep in between ping requests with a connected peer.
     const PING_SLEEP_IN_SECS: u64 = 20; // 20 seconds
     /// The time frame to enforce the `MESSAGE_LIMIT`.
     const MESSAGE_LIMIT_TIME_FRAME_IN_SECS: i64 = 5;
     /// The maximum number of messages accepted within `MESSAGE_LIMIT_TIME_FRAME_IN_SECS`.
     const MESSAGE_LIMIT: usize = 500;
+    /// If the flag is set, a node that disallows external peers answers a `PeerRequest` with its
+    /// connected trusted peers. Otherwise, it answers with an empty list.
+    const SHARE_TRUSTED_PEERS_IF_RESTRICTED: bool = true;
 
     /// Handles the inbound message from the peer.
     async fn inbound(&self, peer_addr: SocketAddr, message: Message<N>) -> Result<()> {
@@ -67,7 +113,15 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
             Some(peer_ip) => peer_ip,
             None => bail!("Unable to resolve the (ambiguous) peer address '{peer_addr}'"),
         };
+        // Handle the message within the span of the trace scopes covering it, if any, to elevate its logging.
+        match self.router().trace_scopes().level_for_message(peer_ip, &message.name()) {
+            Some(level) => self.handle_inbound(peer_ip, message).instrument(level.span()).await,
+            None => self.handle_inbound(peer_ip, message).await,
+        }
+    }
 
+    /// Handles the inbound message from the peer with the given listener IP.
+    async fn handle_inbound(&self, peer_ip: SocketAddr, message: Message<N>) -> Result<()> {
         // Drop the peer, if they have sent more than `MESSAGE_LIMIT` messages
         // in the last `MESSAGE_LIMIT_TIME_FRAME_IN_SECS` seconds.
         let num_messages = self.router().cache.insert_inbound_message(peer_ip, Self::MESSAGE_LIMIT_TIME_FRAME_IN_SECS);
@@ -80,6 +134,11 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
         // Update the last seen timestamp of the peer.
         self.router().update_last_seen_for_connected_peer(peer_ip);
 
+        // Drop the message, if it is not accepted from the peer, given the node types.
+        if !self.check_message_policy(peer_ip, MessageKind::of(&message))? {
+            return Ok(());
+        }
+
         // This match statement handles the inbound message by deserializing the message,
         // checking that the message is valid, and then calling the appropriate (trait) handler.
         match message {
@@ -137,11 +196,30 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
                     false => bail!("Peer '{peer_ip}' sent an invalid block response"),
                 }
             }
+            Message::BlockAnnounce(message) => {
+                // Ensure this node negotiated block announcements, as they are only sent to the peers that did.
+                if !self.router().features().contains(Features::BLOCK_ANNOUNCE) {
+                    bail!("Peer '{peer_ip}' is not following the protocol (unsolicited block announcement)")
+                }
+                // Handle the block announcement.
+                match self.block_announce(peer_ip, message).await {
+                    true => Ok(()),
+                    false => bail!("Peer '{peer_ip}' sent an invalid block announcement"),
+                }
+            }
             Message::ChallengeRequest(..) | Message::ChallengeResponse(..) => {
                 // Disconnect as the peer is not following the protocol.
                 bail!("Peer '{peer_ip}' is not following the protocol")
             }
             Message::Disconnect(message) => {
+                // Record the reason for the peer event, before disconnecting.
+                self.router().insert_disconnect_reason(peer_ip, message.reason);
+                // A peer that is shutting down is not violating the protocol, so it is disconnected gracefully.
+                if message.reason == DisconnectReason::ShuttingDown {
+                    info!("Disconnecting from '{peer_ip}' (peer is shutting down)");
+                    self.router().disconnect(peer_ip);
+                    return Ok(());
+                }
                 bail!("{:?}", message.reason)
             }
             Message::PeerRequest(..) => match self.peer_request(peer_ip) {
@@ -164,7 +242,7 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
             }
             Message::Ping(message) => {
                 // Ensure the message protocol version is not outdated.
-                if message.version < Message::<N>::VERSION {
+                if message.version < Message::<N>::MINIMUM_VERSION {
                     bail!("Dropping '{peer_ip}' on message version {} (outdated)", message.version);
                 }
 
@@ -236,13 +314,22 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
                 // Do not process unconfirmed solutions if the node is too far behind.
                 if self.num_blocks_behind() > SYNC_LENIENCY {
                     trace!("Skipped processing unconfirmed solution '{}' (node is syncing)", message.solution_id);
+                    self.acknowledge_solution(peer_ip, message.solution_id, SolutionStatus::Syncing);
+                    return Ok(());
+                }
+                // Do not process unconfirmed solutions if the peer is on cooldown for sending duplicates.
+                if self.router().is_duplicate_cooldown(&peer_ip) {
+                    trace!("Skipped processing unconfirmed solution '{}' (peer is on cooldown)", message.solution_id);
                     return Ok(());
                 }
                 // Update the timestamp for the unconfirmed solution.
                 let seen_before = self.router().cache.insert_inbound_solution(peer_ip, message.solution_id).is_some();
+                // Ensure the peer is not repeatedly sending solutions that were already seen.
+                self.check_duplicate_transmission(peer_ip, seen_before)?;
                 // Determine whether to propagate the solution.
                 if seen_before {
                     trace!("Skipping 'UnconfirmedSolution' from '{peer_ip}'");
+                    self.acknowledge_solution(peer_ip, message.solution_id, SolutionStatus::Duplicate);
                     return Ok(());
                 }
                 // Clone the serialized message.
@@ -262,15 +349,39 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
                     false => bail!("Peer '{peer_ip}' sent an invalid unconfirmed solution"),
                 }
             }
+            Message::SolutionAck(message) => {
+                // Ensure this node negotiated solution acknowledgments, as they are only sent to the peers that did.
+                if !self.router().features().contains(Features::SOLUTION_ACK) {
+                    bail!("Peer '{peer_ip}' is not following the protocol (unsolicited solution acknowledgment)")
+                }
+                // Ensure the acknowledged solution was sent to the peer.
+                if !self.router().cache.contains_outbound_solution(peer_ip, message.solution_id) {
+                    bail!("Peer '{peer_ip}' is not following the protocol (unexpected solution acknowledgment)")
+                }
+                match self.solution_ack(peer_ip, message) {
+                    true => Ok(()),
+                    false => bail!("Peer '{peer_ip}' sent an invalid solution acknowledgment"),
+                }
+            }
             Message::UnconfirmedTransaction(message) => {
                 // Do not process unconfirmed transactions if the node is too far behind.
                 if self.num_blocks_behind() > SYNC_LENIENCY {
                     trace!("Skipped processing unconfirmed transaction '{}' (node is syncing)", message.transaction_id);
                     return Ok(());
                 }
+                // Do not process unconfirmed transactions if the peer is on cooldown for sending duplicates.
+                if self.router().is_duplicate_cooldown(&peer_ip) {
+                    trace!(
+                        "Skipped processing unconfirmed transaction '{}' (peer is on cooldown)",
+                        message.transaction_id
+                    );
+                    return Ok(());
+                }
                 // Update the timestamp for the unconfirmed transaction.
                 let seen_before =
                     self.router().cache.insert_inbound_transaction(peer_ip, message.transaction_id).is_some();
+                // Ensure the peer is not repeatedly sending transactions that were already seen.
+                self.check_duplicate_transmission(peer_ip, seen_before)?;
                 // Determine whether to propagate the transaction.
                 if seen_before {
                     trace!("Skipping 'UnconfirmedTransaction' from '{peer_ip}'");
@@ -296,16 +407,93 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
         }
     }
 
+    /// Records whether an unconfirmed transmission from the peer was seen before, and puts the peer on
+    /// cooldown if it is repeatedly sending duplicates, or returns an error if it keeps doing so.
+    fn check_duplicate_transmission(&self, peer_ip: SocketAddr, seen_before: bool) -> Result<()> {
+        match self.router().insert_unconfirmed_transmission(peer_ip, seen_before) {
+            DuplicateOutcome::Ok => Ok(()),
+            DuplicateOutcome::Cooldown { num_offenses } => {
+                warn!("Ignoring unconfirmed transmissions from '{peer_ip}' for sending duplicates ({num_offenses})");
+                Ok(())
+            }
+            DuplicateOutcome::Disconnect { num_offenses } => {
+                bail!("Dropping '{peer_ip}' for repeatedly sending duplicate transmissions ({num_offenses})")
+            }
+        }
+    }
+
+    /// Sends the acknowledgment of the given solution to the peer, if the peer is the prover that submitted it,
+    /// and negotiated solution acknowledgments.
+    ///
+    /// Note: The acknowledgments are only sent to the direct submitter, and are rate-limited, so that they
+    /// can never be amplified by relaying or resubmitting solutions.
+    fn acknowledge_solution(&self, peer_ip: SocketAddr, solution_id: SolutionID<N>, status: SolutionStatus) {
+        // Ensure this node acknowledges solutions, as provers do not.
+        let router = self.router();
+        if router.node_type().is_prover() || !router.features().contains(Features::SOLUTION_ACK) {
+            return;
+        }
+        // Ensure the peer is a prover that negotiated solution acknowledgments, and so submitted the solution itself.
+        match router.get_connected_peer(&peer_ip) {
+            Some(peer) if peer.is_prover() && peer.features().contains(Features::SOLUTION_ACK) => (),
+            _ => return,
+        }
+        // Ensure the peer is not sent more acknowledgments than the limit.
+        let frequency = router.cache.insert_outbound_solution_ack(peer_ip);
+        if frequency > Self::MAXIMUM_SOLUTION_ACKS_PER_INTERVAL {
+            trace!("Skipped acknowledging solution '{solution_id}' to '{peer_ip}' (rate-limited)");
+            return;
+        }
+        self.send(peer_ip, Message::SolutionAck(SolutionAck { solution_id, status }));
+    }
+
+    /// Returns `true` if the given kind of message is accepted from the peer, according to the acceptance matrix.
+    /// Otherwise, records the violation, and returns an error if the peer keeps sending rejected messages.
+    fn check_message_policy(&self, peer_ip: SocketAddr, kind: MessageKind) -> Result<bool> {
+        // The node type of a peer is only known once it is connected.
+        let Some(peer_type) = self.router().get_connected_peer_node_type(&peer_ip) else {
+            return Ok(true);
+        };
+        if kind.is_accepted(self.router().node_type(), peer_type) {
+            return Ok(true);
+        }
+        let num_violations = self.router().insert_message_policy_violation(peer_ip);
+        if num_violations >= MAX_MESSAGE_POLICY_VIOLATIONS {
+            bail!("Dropping '{peer_ip}' for repeatedly sending rejected messages ({num_violations})")
+        }
+        debug!("Rejected '{kind:?}' from '{peer_ip}' ({peer_type}) - {num_violations} violation(s)");
+        Ok(false)
+    }
+
+    /// Handles a `BlockAnnounce` message.
+    /// By default, the announcement is ignored, and the block is synced through the block locators.
+    async fn block_announce(&self, _peer_ip: SocketAddr, _message: BlockAnnounce<N>) -> bool {
+        true
+    }
+
     /// Handles a `BlockRequest` message.
     fn block_request(&self, peer_ip: SocketAddr, _message: BlockRequest) -> bool;
 
     /// Handles a `BlockResponse` message.
     fn block_response(&self, peer_ip: SocketAddr, _blocks: Vec<Block<N>>) -> bool;
 
+    /// Handles a `SolutionAck` message.
+    /// By default, the acknowledgment is ignored.
+    fn solution_ack(&self, _peer_ip: SocketAddr, _message: SolutionAck<N>) -> bool {
+        true
+    }
+
     /// Handles a `PeerRequest` message.
     fn peer_request(&self, peer_ip: SocketAddr) -> bool {
         // Retrieve the connected peers.
-        let peers = self.router().connected_peers();
+        let peers = match self.router().allow_external_peers() {
+            true => self.router().connected_peers(),
+            // If external peers are not allowed, only share the connected trusted peers, if permitted.
+            false => match Self::SHARE_TRUSTED_PEERS_IF_RESTRICTED {
+                true => self.router().connected_peers().into_iter().filter(|ip| self.router().is_trusted(ip)).collect(),
+                false => vec![],
+            },
+        };
         // Filter out invalid addresses.
         let peers = match self.router().is_dev() {
             // In development mode, relax the validity requirements to make operating devnets more flexible.
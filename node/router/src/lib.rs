@@ -39,23 +39,33 @@ pub use outbound::*;
 mod routing;
 pub use routing::*;
 
-use crate::messages::NodeType;
+use crate::messages::{DisconnectReason, Features, MAXIMUM_MESSAGE_SIZE, MessageCodec, NodeType};
 use snarkos_account::Account;
-use snarkos_node_tcp::{Config, Tcp, is_bogon_ip, is_unspecified_or_broadcast_ip};
+use snarkos_node_tcp::{Config, ConnectionSide, FdExhaustionStatus, Tcp, is_bogon_ip, is_unspecified_or_broadcast_ip};
 use snarkvm::prelude::{Address, Network, PrivateKey, ViewKey};
 
-use anyhow::{Result, bail};
+use anyhow::{Result, bail, ensure};
+use colored::Colorize;
 use parking_lot::{Mutex, RwLock};
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     future::Future,
-    net::SocketAddr,
+    io,
+    net::{IpAddr, SocketAddr},
     ops::Deref,
+    path::PathBuf,
     str::FromStr,
-    sync::Arc,
-    time::Instant,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
+use time::OffsetDateTime;
+use tokio::{
+    sync::{broadcast, oneshot},
+    task::JoinHandle,
 };
-use tokio::task::JoinHandle;
 
 #[derive(Clone)]
 pub struct Router<N: Network>(Arc<InnerRouter<N>>);
@@ -73,36 +83,73 @@ pub struct InnerRouter<N: Network> {
     tcp: Tcp,
     /// The node type.
     node_type: NodeType,
+    /// The limits on the number of connected peers.
+    peer_limits: PeerLimits,
     /// The account of the node.
     account: Account<N>,
     /// The cache.
     cache: Cache<N>,
     /// The resolver.
     resolver: Resolver,
-    /// The set of trusted peers.
-    trusted_peers: HashSet<SocketAddr>,
+    /// The set of trusted peers, which may be updated at runtime.
+    trusted_peers: RwLock<HashSet<SocketAddr>>,
     /// The map of connected peer IPs to their peer handlers.
     connected_peers: RwLock<HashMap<SocketAddr, Peer<N>>>,
     /// The set of handshaking peers. While `Tcp` already recognizes the connecting IP addresses
     /// and prevents duplicate outbound connection attempts to the same IP address, it is unable to
     /// prevent simultaneous "two-way" connections between two peers (i.e. both nodes simultaneously
-    /// attempt to connect to each other). This set is used to prevent this from happening.
-    connecting_peers: Mutex<HashSet<SocketAddr>>,
-    /// The set of candidate peer IPs.
-    candidate_peers: RwLock<HashSet<SocketAddr>>,
+    /// attempt to connect to each other). This map of peer IPs to the side of the peer in the
+    /// connection is used to resolve this deterministically (see `Router::is_preferred_initiator`).
+    connecting_peers: Mutex<HashMap<SocketAddr, ConnectionSide>>,
+    /// The map of candidate peer IPs to their metadata.
+    candidate_peers: RwLock<HashMap<SocketAddr, CandidatePeer>>,
     /// The set of restricted peer IPs.
     restricted_peers: RwLock<HashMap<SocketAddr, Instant>>,
-    /// The spawned handles.
-    handles: Mutex<Vec<JoinHandle<()>>>,
+    /// If the flag is set, the account address of a restricted peer is restricted as well.
+    restrict_by_account: AtomicBool,
+    /// The set of restricted account addresses.
+    restricted_addresses: RwLock<HashMap<Address<N>, Instant>>,
+    /// The IPs banned by the operator of the node, persisted across restarts.
+    ban_list: BanList,
+    /// The record of the peer IPs each account address was authenticated from.
+    peer_identities: RwLock<PeerIdentities<N>>,
+    /// The tracker of other nodes that authenticated as the account of this node.
+    duplicate_identity: DuplicateIdentity,
+    /// The nonces the peers recently used in their handshakes.
+    recent_nonces: RecentNonces,
+    /// The tracker of peers that repeatedly send already-seen unconfirmed transmissions.
+    duplicate_transmissions: DuplicateTransmissions,
+    /// The tracker of peers that send messages rejected by the acceptance matrix.
+    message_policy_violations: MessagePolicyViolations,
+    /// The accounting of the memory used by the memory pool and the deduplication caches.
+    memory_budget: Arc<MemoryBudget>,
+    /// The gate on the gossip of the node, while it is significantly behind.
+    relay_gate: RelayGate,
+    /// The state of the bootstrap phase.
+    bootstrap: Mutex<Bootstrap>,
+    /// The trace scopes, which elevate the logging of the messages of a peer or of a kind, for a bounded duration.
+    trace_scopes: Arc<TraceScopes>,
+    /// The sender of the peer events, to the subscribers of the router.
+    peer_events: broadcast::Sender<PeerEvent<N>>,
+    /// The supervisor of the spawned tasks.
+    supervisor: TaskSupervisor,
+    /// The registry of the configuration items of the node that can be reloaded at runtime.
+    reloads: ReloadRegistry,
     /// If the flag is set, the node will periodically evict more external peers.
     rotate_external_peers: bool,
     /// If the flag is set, the node will engage in P2P gossip to request more peers.
     allow_external_peers: bool,
     /// The boolean flag for the development mode.
     is_dev: bool,
+    /// The optional protocol features advertised by the node.
+    features: Features,
+    /// The experiment flags of the node.
+    experiments: Experiments,
 }
 
 impl<N: Network> Router<N> {
+    /// The factor applied to the age of the imported candidate peers, as their quality was observed by another node.
+    const IMPORTED_CANDIDATE_AGE_FACTOR: u64 = 2;
     /// The maximum number of candidate peers permitted to be stored in the node.
     const MAXIMUM_CANDIDATE_PEERS: usize = 10_000;
     /// The maximum number of connection failures permitted by an inbound connecting peer.
@@ -112,6 +159,13 @@ impl<N: Network> Router<N> {
     const RADIO_SILENCE_IN_SECS: u64 = 150; // 2.5 minutes
 }
 
+impl<N: Network> Router<N> {
+    /// The duration in seconds for which a peer that announced it is shutting down is not re-dialed.
+    pub const SHUTDOWN_COOLDOWN_IN_SECS: u64 = 5;
+    /// The maximum duration in milliseconds to wait for the shutdown notifications to be delivered to the peers.
+    pub const SHUTDOWN_NOTIFICATION_TIMEOUT_IN_MS: u64 = 500;
+}
+
 impl<N: Network> Router<N> {
     /// Initializes a new `Router` instance.
     #[allow(clippy::too_many_arguments)]
@@ -120,30 +174,87 @@ impl<N: Network> Router<N> {
         node_type: NodeType,
         account: Account<N>,
         trusted_peers: &[SocketAddr],
-        max_peers: u16,
+        peer_limits: PeerLimits,
+        strict_config: bool,
         rotate_external_peers: bool,
         allow_external_peers: bool,
         is_dev: bool,
+        memory_budget: Arc<MemoryBudget>,
+        features: Features,
+        experiments: Experiments,
+        ban_list_path: Option<PathBuf>,
     ) -> Result<Self> {
+        // Ensure the peer limits are derived from the node type.
+        ensure!(peer_limits.node_type() == node_type, "The peer limits do not match {}", node_type.description());
+        // Ensure the maximum number of peers is appropriate for the node type.
+        if let Err(error) = peer_limits.check() {
+            if strict_config {
+                bail!("{error} (refusing to start with '--strict-config')");
+            }
+            warn!("{error} (adjust '--max-peers', or pass '--strict-config' to refuse to start)");
+        }
+        // Log the implications of disallowing external peers.
+        if !allow_external_peers {
+            info!(
+                "External peers are disallowed - the node will only connect to and accept its {} trusted peer(s), \
+                 will not dial bootstrap peers, will not request peers, and will only share its trusted peers",
+                trusted_peers.len()
+            );
+        }
+        // Salt the experiment assignments with the node address, so that nodes select different peers.
+        let experiments = experiments.salted(account.address().to_string());
+        // Load the banned IPs, if the ban list is persisted.
+        let ban_list = BanList::open(ban_list_path, OffsetDateTime::now_utc().unix_timestamp())?;
         // Initialize the TCP stack.
-        let tcp = Tcp::new(Config::new(node_ip, max_peers));
+        let config = Config::new(node_ip, peer_limits.maximum().try_into()?);
+        let tcp = Tcp::new(Config { handshake_limits: peer_limits.handshake_limits(), ..config });
         // Initialize the router.
-        Ok(Self(Arc::new(InnerRouter {
+        let router = Self(Arc::new(InnerRouter {
             tcp,
             node_type,
+            peer_limits,
             account,
             cache: Default::default(),
             resolver: Default::default(),
-            trusted_peers: trusted_peers.iter().copied().collect(),
+            trusted_peers: RwLock::new(trusted_peers.iter().copied().collect()),
             connected_peers: Default::default(),
             connecting_peers: Default::default(),
             candidate_peers: Default::default(),
             restricted_peers: Default::default(),
-            handles: Default::default(),
+            restrict_by_account: Default::default(),
+            restricted_addresses: Default::default(),
+            ban_list,
+            peer_identities: Default::default(),
+            duplicate_identity: Default::default(),
+            recent_nonces: Default::default(),
+            duplicate_transmissions: Default::default(),
+            message_policy_violations: Default::default(),
+            memory_budget,
+            relay_gate: Default::default(),
+            bootstrap: Default::default(),
+            trace_scopes: Default::default(),
+            peer_events: broadcast::channel(PEER_EVENTS_CAPACITY).0,
+            supervisor: TaskSupervisor::new("router"),
+            reloads: Default::default(),
             rotate_external_peers,
             allow_external_peers,
             is_dev,
-        })))
+            features,
+            experiments,
+        }));
+        // Reload the operator's ban list, once the operator edited it.
+        // Note: The registry holds a weak reference, as it is owned by the router.
+        if router.ban_list.path().is_some() {
+            let router_ = Arc::downgrade(&router.0);
+            router.reloads.register("ban_list", move || match router_.upgrade() {
+                Some(router) => Self(router).reload_ban_list(),
+                None => bail!("The router is shut down"),
+            });
+        }
+        // Update the peer metrics from the peer events.
+        #[cfg(feature = "metrics")]
+        router.spawn_peer_metrics();
+        Ok(router)
     }
 }
 
@@ -163,12 +274,28 @@ impl<N: Network> Router<N> {
                 // Remove the peer from the candidate peers.
                 Ok(()) => {
                     router.remove_candidate_peer(peer_ip);
+                    // Record the successful connection attempt, if this is a bootstrap peer.
+                    if router.is_bootstrap_target(&peer_ip) {
+                        router.bootstrap.lock().record_success(peer_ip);
+                    }
+                    true
+                }
+                // If the connection initiated by the peer was kept instead, this is not a failure.
+                Err(_) if router.is_connected(&peer_ip) => {
+                    debug!("Kept the simultaneous connection initiated by '{peer_ip}'");
+                    if router.is_bootstrap_target(&peer_ip) {
+                        router.bootstrap.lock().record_success(peer_ip);
+                    }
                     true
                 }
                 // If the connection was not allowed, log the error.
                 Err(error) => {
                     router.connecting_peers.lock().remove(&peer_ip);
                     warn!("Unable to connect to '{peer_ip}' - {error}");
+                    // Record the reason for the failed connection attempt, if this is a bootstrap peer.
+                    if router.is_bootstrap_target(&peer_ip) {
+                        router.bootstrap.lock().record_failure(peer_ip, ConnectionFailure::from_io_error(&error));
+                    }
                     false
                 }
             }
@@ -193,13 +320,46 @@ impl<N: Network> Router<N> {
         if self.is_restricted(&peer_ip) {
             bail!("Dropping connection attempt to '{peer_ip}' (restricted)")
         }
+        // Ensure the IP of the peer is not banned, on any port.
+        if self.is_banned(&peer_ip.ip()) {
+            bail!("Dropping connection attempt to '{peer_ip}' (banned)")
+        }
+        // Ensure the peer is not cooling down, after announcing it is shutting down.
+        if self.get_candidate_peer(&peer_ip).is_some_and(|peer| peer.is_cooling_down(Instant::now())) {
+            bail!("Dropping connection attempt to '{peer_ip}' (shut down recently)")
+        }
         // Ensure the node is not already connecting to this peer.
-        if !self.connecting_peers.lock().insert(peer_ip) {
-            bail!("Dropping connection attempt to '{peer_ip}' (already shaking hands as the initiator)")
+        let mut connecting_peers = self.connecting_peers.lock();
+        if connecting_peers.contains_key(&peer_ip) {
+            bail!("Dropping connection attempt to '{peer_ip}' (already shaking hands)")
         }
+        connecting_peers.insert(peer_ip, ConnectionSide::Responder);
         Ok(())
     }
 
+    /// Attempts to connect to the bootstrap and trusted peers that are not connected,
+    /// and whose backoff since the last failed connection attempt has elapsed.
+    pub fn dial_bootstrap_peers(&self) {
+        let now = Instant::now();
+        for peer_ip in self.bootstrap_targets() {
+            // Skip the peer if it is already connected, or a connection attempt is in progress.
+            if self.is_connected(&peer_ip) || self.is_connecting(&peer_ip) {
+                continue;
+            }
+            // Skip the peer if its backoff has not elapsed.
+            if !self.bootstrap.lock().is_due(&peer_ip, now) {
+                continue;
+            }
+            self.connect(peer_ip);
+        }
+    }
+
+    /// Returns the bootstrap diagnostic if no peers are connected after the given window has elapsed,
+    /// and no diagnostic has been emitted within the given interval.
+    pub fn next_bootstrap_diagnostic(&self, window: Duration, interval: Duration) -> Option<String> {
+        self.bootstrap.lock().next_diagnostic(Instant::now(), self.number_of_connected_peers(), window, interval)
+    }
+
     /// Disconnects from the given peer IP, if the peer is connected.
     pub fn disconnect(&self, peer_ip: SocketAddr) -> JoinHandle<bool> {
         let router = self.clone();
@@ -225,6 +385,14 @@ impl<N: Network> Router<N> {
         self.tcp.listening_addr().expect("The TCP listener is not enabled")
     }
 
+    /// Returns the IP address of this node, or the configured one if the listener is not enabled yet.
+    pub fn listener_ip(&self) -> Option<SocketAddr> {
+        self.tcp.listening_addr().ok().or_else(|| {
+            let config = self.tcp.config();
+            Some(SocketAddr::new(config.listener_ip?, config.desired_listening_port?))
+        })
+    }
+
     /// Returns `true` if the given IP is this node.
     pub fn is_local_ip(&self, ip: &SocketAddr) -> bool {
         *ip == self.local_ip()
@@ -241,6 +409,11 @@ impl<N: Network> Router<N> {
         self.node_type
     }
 
+    /// Returns the limits on the number of connected peers.
+    pub fn peer_limits(&self) -> &PeerLimits {
+        &self.peer_limits
+    }
+
     /// Returns the account private key of the node.
     pub fn private_key(&self) -> &PrivateKey<N> {
         self.account.private_key()
@@ -261,6 +434,29 @@ impl<N: Network> Router<N> {
         self.is_dev
     }
 
+    /// Returns the optional protocol features advertised by the node.
+    pub const fn features(&self) -> Features {
+        self.features
+    }
+
+    /// Returns the experiment flags of the node.
+    pub fn experiments(&self) -> &Experiments {
+        &self.experiments
+    }
+
+    /// Returns the experiments, with their rollout and the number of connected peers they are enabled for.
+    pub fn experiment_assignments(&self) -> Vec<(&'static str, Rollout, usize)> {
+        let connected_peers = self.connected_peers();
+        self.experiments
+            .rollouts()
+            .map(|(name, rollout)| {
+                let num_enabled =
+                    connected_peers.iter().filter(|peer_ip| self.experiments.enabled_for(**peer_ip, name)).count();
+                (name, rollout, num_enabled)
+            })
+            .collect()
+    }
+
     /// Returns `true` if the node is periodically evicting more external peers.
     pub fn rotate_external_peers(&self) -> bool {
         self.rotate_external_peers
@@ -303,7 +499,7 @@ impl<N: Network> Router<N> {
 
     /// Returns `true` if the node is currently connecting to the given peer IP.
     pub fn is_connecting(&self, ip: &SocketAddr) -> bool {
-        self.connecting_peers.lock().contains(ip)
+        self.connecting_peers.lock().contains_key(ip)
     }
 
     /// Returns `true` if the given IP is restricted.
@@ -315,9 +511,35 @@ impl<N: Network> Router<N> {
             .unwrap_or(false)
     }
 
+    /// Returns `true` if the account address of a restricted peer is restricted as well.
+    pub fn restrict_by_account(&self) -> bool {
+        self.restrict_by_account.load(Ordering::Relaxed)
+    }
+
+    /// Sets whether the account address of a restricted peer is restricted as well.
+    pub fn set_restrict_by_account(&self, restrict_by_account: bool) {
+        self.restrict_by_account.store(restrict_by_account, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if the given account address is restricted.
+    pub fn is_restricted_address(&self, address: &Address<N>) -> bool {
+        self.restrict_by_account()
+            && self
+                .restricted_addresses
+                .read()
+                .get(address)
+                .map(|time| time.elapsed().as_secs() < Self::RADIO_SILENCE_IN_SECS)
+                .unwrap_or(false)
+    }
+
+    /// Returns `true` if the given IP is banned, on any port.
+    pub fn is_banned(&self, ip: &IpAddr) -> bool {
+        self.ban_list.is_banned(ip, OffsetDateTime::now_utc().unix_timestamp())
+    }
+
     /// Returns `true` if the given IP is trusted.
     pub fn is_trusted(&self, ip: &SocketAddr) -> bool {
-        self.trusted_peers.contains(ip)
+        self.trusted_peers.read().contains(ip)
     }
 
     /// Returns the maximum number of connected peers.
@@ -325,6 +547,16 @@ impl<N: Network> Router<N> {
         self.tcp.config().max_connections as usize
     }
 
+    /// Returns `true` if the file descriptors of the node are exhausted, as reported by the connections of the router.
+    pub fn is_fd_exhausted(&self) -> bool {
+        self.tcp.fd_exhaustion().is_exhausted(Instant::now())
+    }
+
+    /// Returns a snapshot of the exhaustion of the file descriptors, as reported by the connections of the router.
+    pub fn fd_exhaustion(&self) -> FdExhaustionStatus {
+        self.tcp.fd_exhaustion().status(Instant::now())
+    }
+
     /// Returns the number of connected peers.
     pub fn number_of_connected_peers(&self) -> usize {
         self.connected_peers.read().len()
@@ -360,6 +592,11 @@ impl<N: Network> Router<N> {
         self.connected_peers.read().get(ip).cloned()
     }
 
+    /// Returns the node type of the connected peer given the peer IP, if it exists.
+    pub fn get_connected_peer_node_type(&self, ip: &SocketAddr) -> Option<NodeType> {
+        self.connected_peers.read().get(ip).map(|peer| peer.node_type())
+    }
+
     /// Returns the connected peers.
     pub fn get_connected_peers(&self) -> Vec<Peer<N>> {
         self.connected_peers.read().values().cloned().collect()
@@ -385,9 +622,66 @@ impl<N: Network> Router<N> {
         self.connected_peers.read().iter().filter(|(_, peer)| peer.is_client()).map(|(ip, _)| *ip).collect()
     }
 
+    /// Returns the list of connected clients that negotiated the given features with this node.
+    pub fn connected_clients_with(&self, features: Features) -> Vec<SocketAddr> {
+        // Ensure this node advertised the features as well.
+        if !self.features.contains(features) {
+            return vec![];
+        }
+        self.connected_peers
+            .read()
+            .iter()
+            .filter(|(_, peer)| peer.is_client() && peer.features().contains(features))
+            .map(|(ip, _)| *ip)
+            .collect()
+    }
+
+    /// Returns the list of connected peers that negotiated the given features, in the order a new block reaches them:
+    /// the committee members first, then the other validators, then the clients and provers.
+    pub fn connected_peers_by_priority_with(
+        &self,
+        features: Features,
+        is_committee_member: impl Fn(&Address<N>) -> bool,
+    ) -> Vec<SocketAddr> {
+        // Ensure this node advertised the features as well.
+        if !self.features.contains(features) {
+            return vec![];
+        }
+        let mut peers = self
+            .connected_peers
+            .read()
+            .iter()
+            .filter(|(_, peer)| peer.features().contains(features))
+            .map(|(ip, peer)| match peer.is_validator() {
+                true if is_committee_member(&peer.address()) => (0u8, *ip),
+                true => (1, *ip),
+                false => (2, *ip),
+            })
+            .collect::<Vec<_>>();
+        // Note: The sort is stable, so the peers of the same priority keep their order.
+        peers.sort_by_key(|(priority, _)| *priority);
+        peers.into_iter().map(|(_, ip)| ip).collect()
+    }
+
     /// Returns the list of candidate peers.
     pub fn candidate_peers(&self) -> HashSet<SocketAddr> {
-        self.candidate_peers.read().clone()
+        self.candidate_peers.read().keys().copied().collect()
+    }
+
+    /// Returns the list of candidate peers that may be dialed, i.e. that are not cooling down.
+    pub fn dialable_candidate_peers(&self) -> HashSet<SocketAddr> {
+        let now = Instant::now();
+        self.candidate_peers
+            .read()
+            .iter()
+            .filter(|(_, candidate)| !candidate.is_cooling_down(now))
+            .map(|(peer_ip, _)| *peer_ip)
+            .collect()
+    }
+
+    /// Returns the candidate peer metadata given the peer IP, if it exists.
+    pub fn get_candidate_peer(&self, ip: &SocketAddr) -> Option<CandidatePeer> {
+        self.candidate_peers.read().get(ip).copied()
     }
 
     /// Returns the list of restricted peers.
@@ -395,9 +689,191 @@ impl<N: Network> Router<N> {
         self.restricted_peers.read().keys().copied().collect()
     }
 
-    /// Returns the list of trusted peers.
-    pub fn trusted_peers(&self) -> &HashSet<SocketAddr> {
-        &self.trusted_peers
+    /// Returns the restricted peers whose restriction has not expired, sorted by IP, with the time elapsed since
+    /// they were restricted, and the remaining duration in seconds of their restriction.
+    pub fn active_restricted_peers(&self) -> Vec<(SocketAddr, Duration, u64)> {
+        let mut restricted_peers: Vec<_> = self
+            .restricted_peers
+            .read()
+            .iter()
+            .filter_map(|(ip, since)| {
+                let elapsed = since.elapsed();
+                let remaining_secs = Self::RADIO_SILENCE_IN_SECS.saturating_sub(elapsed.as_secs());
+                (remaining_secs > 0).then_some((*ip, elapsed, remaining_secs))
+            })
+            .collect();
+        restricted_peers.sort_unstable_by_key(|(ip, ..)| *ip);
+        restricted_peers
+    }
+
+    /// Returns the banned IPs whose ban has not expired, with the expiry of their ban.
+    pub fn banned_ips(&self) -> Vec<BannedIp> {
+        self.ban_list.banned_ips(OffsetDateTime::now_utc().unix_timestamp())
+    }
+
+    /// Returns the list of restricted account addresses.
+    pub fn restricted_addresses(&self) -> Vec<Address<N>> {
+        self.restricted_addresses.read().keys().copied().collect()
+    }
+
+    /// Returns the peer IPs the given account address was authenticated from, with the time they were last seen,
+    /// from the most recent.
+    pub fn peer_ips_of_address(&self, address: &Address<N>) -> Vec<(SocketAddr, Instant)> {
+        self.peer_identities.read().peer_ips(address)
+    }
+
+    /// Returns the account address most recently authenticated from the given peer IP, if any.
+    pub fn address_of_peer_ip(&self, peer_ip: &SocketAddr) -> Option<Address<N>> {
+        match self.connected_peers.read().get(peer_ip) {
+            Some(peer) => Some(peer.address()),
+            None => self.peer_identities.read().address(peer_ip),
+        }
+    }
+
+    /// Returns the gate on the gossip of the node, while it is significantly behind.
+    pub fn relay_gate(&self) -> &RelayGate {
+        &self.relay_gate
+    }
+
+    /// Returns the trace scopes of the node.
+    pub fn trace_scopes(&self) -> &Arc<TraceScopes> {
+        &self.trace_scopes
+    }
+
+    /// Returns the nonces the peers recently used in their handshakes.
+    pub fn recent_nonces(&self) -> &RecentNonces {
+        &self.recent_nonces
+    }
+
+    /// Returns the tracker of other nodes that authenticated as the account of this node.
+    pub fn duplicate_identity(&self) -> &DuplicateIdentity {
+        &self.duplicate_identity
+    }
+
+    /// Returns the peer IPs of the other nodes that recently authenticated as the account of this node,
+    /// from the most recent.
+    pub fn duplicate_identity_peers(&self) -> Vec<SocketAddr> {
+        self.duplicate_identity.peer_ips(Instant::now())
+    }
+
+    /// Refreshes the connected peers that authenticated as the account of this node,
+    /// so that the duplicate identity remains active while they stay connected.
+    pub fn refresh_duplicate_identity(&self) {
+        if self.node_type.is_prover() {
+            return;
+        }
+        let now = Instant::now();
+        for peer in self.connected_peers.read().values() {
+            if peer.address() == self.address() && !peer.node_type().is_prover() {
+                self.duplicate_identity.report(peer.ip(), now);
+            }
+        }
+    }
+
+    /// Checks if the given peer authenticated as the account of this node, or as the account of another
+    /// connected peer, and reports it.
+    ///
+    /// Provers commonly share an account across machines, so they are not checked.
+    fn check_duplicate_identity(&self, peer: &Peer<N>) {
+        if self.node_type.is_prover() || peer.node_type().is_prover() {
+            return;
+        }
+        let (peer_ip, address) = (peer.ip(), peer.address());
+        // Check if the peer is another node using the account of this node.
+        if address == self.address() {
+            if self.duplicate_identity.report(peer_ip, Instant::now()) {
+                let message = format!("Another node is using this account ({address}) at '{peer_ip}'");
+                warn!("{}", message.bold().red());
+            }
+            #[cfg(feature = "metrics")]
+            metrics::increment_counter_label(metrics::router::DUPLICATE_IDENTITIES, "kind", "own_account".to_string());
+            return;
+        }
+        // Check if another connected peer authenticated as the same account.
+        let other_peer_ips: Vec<_> = self
+            .connected_peers
+            .read()
+            .values()
+            .filter(|other| other.ip() != peer_ip && other.address() == address && !other.node_type().is_prover())
+            .map(|other| other.ip())
+            .collect();
+        if !other_peer_ips.is_empty() {
+            warn!("Peer '{peer_ip}' is using the same account ({address}) as the connected peer(s) {other_peer_ips:?}");
+            #[cfg(feature = "metrics")]
+            metrics::increment_counter_label(
+                metrics::router::DUPLICATE_IDENTITIES,
+                "kind",
+                "shared_account".to_string(),
+            );
+        }
+    }
+
+    /// Returns `true` if the unconfirmed transmissions of the given peer are to be ignored,
+    /// as the peer is on cooldown for sending too many duplicates.
+    pub fn is_duplicate_cooldown(&self, peer_ip: &SocketAddr) -> bool {
+        self.duplicate_transmissions.is_cooling_down(peer_ip, Instant::now())
+    }
+
+    /// Records an unconfirmed transmission from the given peer, and whether it was seen before.
+    pub fn insert_unconfirmed_transmission(&self, peer_ip: SocketAddr, seen_before: bool) -> DuplicateOutcome {
+        self.duplicate_transmissions.insert(peer_ip, seen_before, Instant::now())
+    }
+
+    /// Records a message from the given peer that was rejected by the acceptance matrix,
+    /// and returns the number of violations of the peer.
+    pub fn insert_message_policy_violation(&self, peer_ip: SocketAddr) -> u32 {
+        self.message_policy_violations.insert(peer_ip)
+    }
+
+    /// Returns the set of trusted peers.
+    pub fn trusted_peers(&self) -> HashSet<SocketAddr> {
+        self.trusted_peers.read().clone()
+    }
+
+    /// Inserts the given peer into the trusted peers, returning `true` if it was not trusted already.
+    /// Note: The heartbeat connects to the new trusted peer on its next tick.
+    pub fn insert_trusted_peer(&self, peer_ip: SocketAddr) -> bool {
+        let is_inserted = self.trusted_peers.write().insert(peer_ip);
+        if is_inserted {
+            info!("Trusting the peer '{peer_ip}'");
+        }
+        is_inserted
+    }
+
+    /// Removes the given peer from the trusted peers, returning `true` if it was trusted.
+    /// Note: The peer is not disconnected, but it is no longer protected from eviction.
+    pub fn remove_trusted_peer(&self, peer_ip: &SocketAddr) -> bool {
+        let is_removed = self.trusted_peers.write().remove(peer_ip);
+        if is_removed {
+            info!("No longer trusting the peer '{peer_ip}'");
+        }
+        is_removed
+    }
+
+    /// Returns the bootstrap and trusted peers that the node dials during the bootstrap phase.
+    pub fn bootstrap_targets(&self) -> Vec<SocketAddr> {
+        let trusted_peers = self.trusted_peers();
+        let mut targets: Vec<_> = trusted_peers.iter().copied().collect();
+        // Include the bootstrap peers, if the node is allowed to connect to external peers.
+        if self.allow_external_peers {
+            targets.extend(self.bootstrap_peers().into_iter().filter(|peer_ip| !trusted_peers.contains(peer_ip)));
+        }
+        targets
+    }
+
+    /// Returns `true` if the given peer IP is a bootstrap or trusted peer.
+    pub fn is_bootstrap_target(&self, peer_ip: &SocketAddr) -> bool {
+        self.is_trusted(peer_ip) || (self.allow_external_peers && self.bootstrap_peers().contains(peer_ip))
+    }
+
+    /// Returns the connection attempts to the bootstrap and trusted peers.
+    pub fn bootstrap_attempts(&self) -> BTreeMap<SocketAddr, BootstrapAttempts> {
+        self.bootstrap.lock().attempts().clone()
+    }
+
+    /// Returns a diagnostic summarizing the connection attempts to the bootstrap and trusted peers.
+    pub fn bootstrap_diagnostic(&self) -> String {
+        self.bootstrap.lock().diagnostic()
     }
 
     /// Returns the list of bootstrap peers.
@@ -436,16 +912,115 @@ impl<N: Network> Router<N> {
         }
     }
 
-    /// Returns the list of metrics for the connected peers.
-    pub fn connected_metrics(&self) -> Vec<(SocketAddr, NodeType)> {
-        self.connected_peers.read().iter().map(|(ip, peer)| (*ip, peer.node_type())).collect()
+    /// Returns the list of metrics for the connected peers, including the ratio of duplicates
+    /// among their recent unconfirmed transmissions, if any, the maximum frame size negotiated with them,
+    /// the numbers of bytes sent to and received from them since they connected, and their account address.
+    #[allow(clippy::type_complexity)]
+    pub fn connected_metrics(&self) -> Vec<(SocketAddr, NodeType, Option<f64>, usize, u64, u64, Address<N>)> {
+        self.connected_peers
+            .read()
+            .iter()
+            .map(|(ip, peer)| {
+                let duplicate_ratio = self.duplicate_transmissions.ratio(ip);
+                let (bytes_sent, bytes_received) = self.cache.bandwidth(ip);
+                (
+                    *ip,
+                    peer.node_type(),
+                    duplicate_ratio,
+                    peer.max_frame_size(),
+                    bytes_sent,
+                    bytes_received,
+                    peer.address(),
+                )
+            })
+            .collect()
+    }
+
+    /// Returns the codec of the messages received from the peer connected on the given (ambiguous) address,
+    /// which is held to the negotiated frame size and counts the bytes received from the peer.
+    pub fn inbound_codec(&self, peer_addr: SocketAddr) -> MessageCodec<N> {
+        let codec = MessageCodec::with_max_frame_size(self.max_frame_size(peer_addr));
+        match self.resolve_to_listener(&peer_addr) {
+            Some(peer_ip) => codec.with_byte_counter(self.cache.bandwidth_counters(peer_ip).1),
+            None => codec,
+        }
+    }
+
+    /// Returns the codec of the messages sent to the peer connected on the given (ambiguous) address,
+    /// which is held to the negotiated frame size and counts the bytes sent to the peer.
+    pub fn outbound_codec(&self, peer_addr: SocketAddr) -> MessageCodec<N> {
+        let codec = MessageCodec::with_max_frame_size(self.max_frame_size(peer_addr));
+        match self.resolve_to_listener(&peer_addr) {
+            Some(peer_ip) => codec.with_byte_counter(self.cache.bandwidth_counters(peer_ip).0),
+            None => codec,
+        }
+    }
+
+    /// Returns the maximum frame size negotiated with the peer connected on the given (ambiguous) address.
+    /// Note: An address that does not resolve to a connected peer is held to `MAXIMUM_MESSAGE_SIZE`.
+    pub fn max_frame_size(&self, peer_addr: SocketAddr) -> usize {
+        self.resolve_to_listener(&peer_addr)
+            .and_then(|peer_ip| self.connected_peers.read().get(&peer_ip).map(|peer| peer.max_frame_size()))
+            .unwrap_or(MAXIMUM_MESSAGE_SIZE)
+    }
+
+    /// Subscribes to the peer events of the router.
+    ///
+    /// A subscriber that falls behind by more than `PEER_EVENTS_CAPACITY` events misses the oldest ones,
+    /// and is notified of it by a `RecvError::Lagged` error, as the router never waits for its subscribers.
+    pub fn subscribe_peer_events(&self) -> broadcast::Receiver<PeerEvent<N>> {
+        self.peer_events.subscribe()
+    }
+
+    /// Publishes the given peer event to the subscribers, if any.
+    fn publish_peer_event(&self, event: PeerEvent<N>) {
+        // Note: The send fails only if there are no subscribers, in which case the event is dropped.
+        let _ = self.peer_events.send(event);
     }
 
+    /// Spawns a task updating the metrics of the connected and restricted peers on each peer event.
     #[cfg(feature = "metrics")]
-    fn update_metrics(&self) {
+    fn spawn_peer_metrics(&self) {
+        let router = self.clone();
+        self.spawn("peer_metrics", TaskPolicy::Auxiliary, move || {
+            let router = router.clone();
+            let mut peer_events = router.subscribe_peer_events();
+            async move {
+                // Note: A lagged subscriber refreshes the metrics all the same, as they are read from the router.
+                while !matches!(peer_events.recv().await, Err(broadcast::error::RecvError::Closed)) {
+                    router.update_peer_metrics();
+                }
+            }
+        });
+    }
+
+    #[cfg(feature = "metrics")]
+    fn update_peer_metrics(&self) {
         metrics::gauge(metrics::router::CONNECTED, self.connected_peers.read().len() as f64);
-        metrics::gauge(metrics::router::CANDIDATE, self.candidate_peers.read().len() as f64);
         metrics::gauge(metrics::router::RESTRICTED, self.restricted_peers.read().len() as f64);
+
+        // Report the number of connected peers each experiment is enabled for.
+        for (name, _, num_enabled) in self.experiment_assignments() {
+            metrics::gauge_label(metrics::router::EXPERIMENT_PEERS, "experiment", name.to_string(), num_enabled as f64);
+        }
+    }
+
+    #[cfg(feature = "metrics")]
+    fn update_metrics(&self) {
+        metrics::gauge(metrics::router::CANDIDATE, self.candidate_peers.read().len() as f64);
+
+        // Compute the distribution of the candidate peer ages.
+        let (mut under_1h, mut under_24h, mut over_24h) = (0usize, 0usize, 0usize);
+        for candidate in self.candidate_peers.read().values() {
+            match candidate.age().as_secs() {
+                age if age < 60 * 60 => under_1h += 1,
+                age if age < 24 * 60 * 60 => under_24h += 1,
+                _ => over_24h += 1,
+            }
+        }
+        metrics::gauge(metrics::router::CANDIDATE_AGE_UNDER_1H, under_1h as f64);
+        metrics::gauge(metrics::router::CANDIDATE_AGE_UNDER_24H, under_24h as f64);
+        metrics::gauge(metrics::router::CANDIDATE_AGE_OVER_24H, over_24h as f64);
     }
 
     /// Inserts the given peer into the connected peers.
@@ -453,6 +1028,17 @@ impl<N: Network> Router<N> {
         let peer_ip = peer.ip();
         // Adds a bidirectional map between the listener address and (ambiguous) peer address.
         self.resolver.insert_peer(peer_ip, peer_addr);
+        // Record the account address the peer authenticated as.
+        self.peer_identities.write().insert(peer.address(), peer_ip, Instant::now());
+        // Check if the account of the peer is already used by this node or by another connected peer.
+        self.check_duplicate_identity(&peer);
+        // Prepare the event announcing the peer.
+        let event = PeerEvent::Connected {
+            peer_ip,
+            address: peer.address(),
+            node_type: peer.node_type(),
+            version: peer.version(),
+        };
         // Add an entry for this `Peer` in the connected peers.
         self.connected_peers.write().insert(peer_ip, peer);
         // Remove this peer from the candidate peers, if it exists.
@@ -461,38 +1047,115 @@ impl<N: Network> Router<N> {
         self.restricted_peers.write().remove(&peer_ip);
         #[cfg(feature = "metrics")]
         self.update_metrics();
+        self.publish_peer_event(event);
     }
 
     /// Inserts the given peer IPs to the set of candidate peers.
+    /// If a given peer IP is already a candidate peer, its timestamp is refreshed.
     ///
     /// This method skips adding any given peers if the combined size exceeds the threshold,
     /// as the peer providing this list could be subverting the protocol.
+    ///
+    /// If external peers are not allowed, only trusted peer IPs are inserted, regardless of the source.
     pub fn insert_candidate_peers(&self, peers: &[SocketAddr]) {
-        // Compute the maximum number of candidate peers.
-        let max_candidate_peers = Self::MAXIMUM_CANDIDATE_PEERS.saturating_sub(self.number_of_candidate_peers());
-        // Ensure the combined number of peers does not surpass the threshold.
-        let eligible_peers = peers
-            .iter()
-            .filter(|peer_ip| {
-                // Ensure the peer is not itself, is not already connected, and is not restricted.
-                !self.is_local_ip(peer_ip) && !self.is_connected(peer_ip) && !self.is_restricted(peer_ip)
-            })
-            .take(max_candidate_peers);
+        // Filter out the ineligible peers.
+        let eligible_peers = peers.iter().filter(|peer_ip| {
+            // Ensure the peer is not itself, is not already connected, and is not restricted or banned.
+            !self.is_local_ip(peer_ip) && !self.is_connected(peer_ip) && !self.is_restricted(peer_ip)
+                && !self.is_banned(&peer_ip.ip())
+                // Ensure the peer is trusted, if external peers are not allowed.
+                && (self.allow_external_peers || self.is_trusted(peer_ip))
+        });
+
+        let mut candidate_peers = self.candidate_peers.write();
+        for peer_ip in eligible_peers {
+            match candidate_peers.get_mut(peer_ip) {
+                // If the peer is already a candidate, refresh its timestamp.
+                Some(candidate) => candidate.refresh(),
+                // Otherwise, insert the peer if the combined number of peers does not surpass the threshold.
+                None => {
+                    if candidate_peers.len() < self.maximum_candidate_peers() {
+                        candidate_peers.insert(*peer_ip, CandidatePeer::new(false));
+                    }
+                }
+            }
+        }
+        drop(candidate_peers);
+        #[cfg(feature = "metrics")]
+        self.update_metrics();
+    }
 
-        // Proceed to insert the eligible candidate peer IPs.
-        self.candidate_peers.write().extend(eligible_peers);
+    /// Bans the given IP on every port, for the given duration, or until it is unbanned if `None`.
+    ///
+    /// The connected peers and the candidate peers on the IP are dropped, and the ban is persisted,
+    /// so that it survives a restart of the node.
+    pub fn ban_ip(&self, ip: IpAddr, duration: Option<Duration>) {
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        let expires_at = duration.map(|duration| now.saturating_add(duration.as_secs().try_into().unwrap_or(i64::MAX)));
+        self.ban_list.ban(ip, expires_at);
+        match duration {
+            Some(duration) => info!("Banned '{ip}' for {} seconds", duration.as_secs()),
+            None => info!("Banned '{ip}' until it is unbanned"),
+        }
+        // Drop the candidate peers on the IP, and disconnect from the connected peers on the IP.
+        self.candidate_peers.write().retain(|peer_ip, _| peer_ip.ip() != ip);
+        for peer_ip in self.connected_peers().into_iter().filter(|peer_ip| peer_ip.ip() == ip) {
+            self.disconnect(peer_ip);
+        }
         #[cfg(feature = "metrics")]
         self.update_metrics();
     }
 
-    /// Inserts the given peer into the restricted peers.
-    pub fn insert_restricted_peer(&self, peer_ip: SocketAddr) {
+    /// Unbans the given IP, returning `true` if it was banned.
+    pub fn unban_ip(&self, ip: IpAddr) -> bool {
+        let was_banned = self.ban_list.unban(&ip);
+        if was_banned {
+            info!("Unbanned '{ip}'");
+        }
+        was_banned
+    }
+
+    /// Replaces the operator's bans with the ones in the operator's ban list (see `operator_ban_list_path`),
+    /// returning a description of the changes, if any.
+    ///
+    /// The connected peers and the candidate peers on the newly banned IPs are dropped.
+    pub fn reload_ban_list(&self) -> Result<Option<String>> {
+        let changes = self.ban_list.reload(OffsetDateTime::now_utc().unix_timestamp())?;
+        for ip in &changes.banned {
+            self.candidate_peers.write().retain(|peer_ip, _| peer_ip.ip() != *ip);
+            for peer_ip in self.connected_peers().into_iter().filter(|peer_ip| peer_ip.ip() == *ip) {
+                self.disconnect(peer_ip);
+            }
+        }
+        #[cfg(feature = "metrics")]
+        self.update_metrics();
+        Ok((!changes.is_empty()).then(|| changes.to_string()))
+    }
+
+    /// Removes the bans that have expired, returning the number of removed bans.
+    pub fn remove_expired_bans(&self) -> usize {
+        self.ban_list.prune(OffsetDateTime::now_utc().unix_timestamp())
+    }
+
+    /// Inserts the given peer into the restricted peers, for the given cause.
+    ///
+    /// If restricting by account is enabled and the account address of the peer is known, it is restricted
+    /// as well, so that the peer cannot evade the restriction by connecting from another IP.
+    pub fn insert_restricted_peer(&self, peer_ip: SocketAddr, cause: RestrictionCause) {
         // Remove this peer from the candidate peers, if it exists.
         self.candidate_peers.write().remove(&peer_ip);
         // Add the peer to the restricted peers.
         self.restricted_peers.write().insert(peer_ip, Instant::now());
+        // Add the account address of the peer to the restricted addresses, unless it is the account of this node,
+        // which peers in development setups may share.
+        if let Some(address) =
+            self.address_of_peer_ip(&peer_ip).filter(|address| self.restrict_by_account() && *address != self.address())
+        {
+            self.restricted_addresses.write().insert(address, Instant::now());
+        }
         #[cfg(feature = "metrics")]
         self.update_metrics();
+        self.publish_peer_event(PeerEvent::Restricted { peer_ip, cause });
     }
 
     /// Updates the connected peer with the given function.
@@ -520,18 +1183,38 @@ impl<N: Network> Router<N> {
         }
     }
 
+    /// Records the given disconnect reason, sent to or received from the connected peer, for its peer event.
+    pub fn insert_disconnect_reason(&self, peer_ip: SocketAddr, reason: DisconnectReason) {
+        if let Some(peer) = self.connected_peers.write().get_mut(&peer_ip) {
+            peer.set_disconnect_reason(reason);
+        }
+    }
+
     /// Removes the connected peer and adds them to the candidate peers.
     pub fn remove_connected_peer(&self, peer_ip: SocketAddr) {
         // Removes the bidirectional map between the listener address and (ambiguous) peer address.
         self.resolver.remove_peer(&peer_ip);
         // Remove this peer from the connected peers, if it exists.
-        self.connected_peers.write().remove(&peer_ip);
-        // Add the peer to the candidate peers.
-        self.candidate_peers.write().insert(peer_ip);
+        let peer = self.connected_peers.write().remove(&peer_ip);
+        // Add the peer to the candidate peers, noting that the node has successfully connected to it.
+        // If external peers are not allowed, only trusted peers are retained as candidates.
+        if self.allow_external_peers || self.is_trusted(&peer_ip) {
+            let candidate = match peer.as_ref().and_then(Peer::disconnect_reason) {
+                // A peer that is shutting down is not re-dialed until it has had the time to restart.
+                Some(DisconnectReason::ShuttingDown) => {
+                    CandidatePeer::new(true).with_cooldown(Duration::from_secs(Self::SHUTDOWN_COOLDOWN_IN_SECS))
+                }
+                _ => CandidatePeer::new(true),
+            };
+            self.candidate_peers.write().insert(peer_ip, candidate);
+        }
         // Clear cached entries applicable to the peer.
         self.cache.clear_peer_entries(peer_ip);
         #[cfg(feature = "metrics")]
         self.update_metrics();
+        if let Some(peer) = peer {
+            self.publish_peer_event(PeerEvent::Disconnected { peer_ip, reason: peer.disconnect_reason() });
+        }
     }
 
     #[cfg(feature = "test")]
@@ -541,6 +1224,14 @@ impl<N: Network> Router<N> {
         self.update_metrics();
     }
 
+    /// Ends the cooldowns of the candidate peers, as if they had elapsed.
+    #[cfg(feature = "test")]
+    pub fn clear_candidate_cooldowns(&self) {
+        self.candidate_peers.write().values_mut().for_each(|candidate| {
+            *candidate = candidate.with_cooldown(Duration::ZERO);
+        });
+    }
+
     /// Removes the given address from the candidate peers, if it exists.
     pub fn remove_candidate_peer(&self, peer_ip: SocketAddr) {
         self.candidate_peers.write().remove(&peer_ip);
@@ -548,16 +1239,240 @@ impl<N: Network> Router<N> {
         self.update_metrics();
     }
 
-    /// Spawns a task with the given future; it should only be used for long-running tasks.
-    pub fn spawn<T: Future<Output = ()> + Send + 'static>(&self, future: T) {
-        self.handles.lock().push(tokio::spawn(future));
+    /// Removes the candidate peers that are older than the applicable maximum age,
+    /// returning the number of candidate peers that were removed.
+    ///
+    /// Candidate peers that the node has successfully connected to in the past are retained
+    /// until `max_age_connected`, while those that never connected are retained until `max_age`.
+    pub fn remove_expired_candidate_peers(&self, max_age: Duration, max_age_connected: Duration) -> usize {
+        let now = Instant::now();
+        let mut candidate_peers = self.candidate_peers.write();
+        let num_candidates = candidate_peers.len();
+        candidate_peers.retain(|_, candidate| !candidate.is_expired(now, max_age, max_age_connected));
+        let num_removed = num_candidates - candidate_peers.len();
+        drop(candidate_peers);
+        #[cfg(feature = "metrics")]
+        self.update_metrics();
+        num_removed
+    }
+
+    /// Returns the peer knowledge of the node, to seed another node with.
+    pub fn export_peers(&self) -> PeerExport {
+        let mut candidate_peers: Vec<_> = self.candidate_peers.read().iter().map(|(ip, peer)| (*ip, *peer)).collect();
+        candidate_peers.sort_unstable_by_key(|(ip, _)| *ip);
+        let mut trusted_peers: Vec<_> = self.trusted_peers().into_iter().collect();
+        trusted_peers.sort_unstable();
+        // Export the restrictions that have not expired, with their remaining duration.
+        let restricted_peers = self.active_restricted_peers();
+
+        PeerExport {
+            version: PEER_EXPORT_VERSION,
+            candidate_peers: candidate_peers
+                .into_iter()
+                .map(|(ip, peer)| ExportedCandidatePeer {
+                    ip: ip.to_string(),
+                    has_connected: peer.has_connected(),
+                    age_secs: peer.age().as_secs(),
+                })
+                .collect(),
+            trusted_peers: trusted_peers.iter().map(ToString::to_string).collect(),
+            restricted_peers: restricted_peers
+                .into_iter()
+                .map(|(ip, _, remaining_secs)| ExportedRestrictedPeer { ip: ip.to_string(), remaining_secs })
+                .collect(),
+        }
+    }
+
+    /// Merges the given peer knowledge of another node into the node.
+    ///
+    /// The entries are subject to the usual validity checks, and the state of this node wins any conflict, i.e.
+    /// a restricted, connected, or candidate peer is left as is, and a trusted peer is never restricted.
+    /// The trusted peers of the other node are imported as candidate peers, and the age of the imported candidate
+    /// peers is multiplied by `IMPORTED_CANDIDATE_AGE_FACTOR`, so that they expire before the local ones.
+    pub fn import_peers(&self, export: &PeerExport) -> PeerImportSummary {
+        let mut summary = PeerImportSummary::default();
+        // Parses the given peer IP, ensuring it is valid.
+        let parse = |ip: &str| -> Option<SocketAddr> {
+            let ip = ip.parse().ok()?;
+            let is_valid = match self.is_dev {
+                // In development mode, relax the validity requirements to make operating devnets more flexible.
+                true => !self.is_local_ip(&ip) && !is_bogon_ip(ip.ip()),
+                // In production mode, ensure the peer IP is valid.
+                false => self.is_valid_peer_ip(&ip),
+            };
+            is_valid.then_some(ip)
+        };
+
+        // Import the restricted peers first, so that they take precedence over the imported candidate peers.
+        let now = Instant::now();
+        for entry in &export.restricted_peers {
+            let remaining_secs = entry.remaining_secs.min(Self::RADIO_SILENCE_IN_SECS);
+            let peer_ip = parse(&entry.ip).filter(|ip| {
+                remaining_secs > 0 && !self.is_trusted(ip) && !self.is_connected(ip) && !self.is_restricted(ip)
+            });
+            let Some(peer_ip) = peer_ip else {
+                summary.num_dropped += 1;
+                continue;
+            };
+            // Backdate the restriction, so that it expires after the remaining duration.
+            let elapsed = Duration::from_secs(Self::RADIO_SILENCE_IN_SECS - remaining_secs);
+            self.candidate_peers.write().remove(&peer_ip);
+            self.restricted_peers.write().insert(peer_ip, now.checked_sub(elapsed).unwrap_or(now));
+            self.publish_peer_event(PeerEvent::Restricted { peer_ip, cause: RestrictionCause::Imported });
+            summary.num_restricted += 1;
+        }
+
+        // Filter out the ineligible candidate peers, as in `insert_candidate_peers`.
+        let candidates = export
+            .candidate_peers
+            .iter()
+            .map(|entry| (entry.ip.as_str(), entry.has_connected, entry.age_secs))
+            .chain(export.trusted_peers.iter().map(|ip| (ip.as_str(), false, 0)));
+        let mut eligible_peers = Vec::new();
+        for (ip, has_connected, age_secs) in candidates {
+            let peer_ip = parse(ip).filter(|ip| {
+                !self.is_connected(ip) && !self.is_restricted(ip) && (self.allow_external_peers || self.is_trusted(ip))
+            });
+            match peer_ip {
+                Some(peer_ip) => eligible_peers.push((peer_ip, has_connected, age_secs)),
+                None => summary.num_dropped += 1,
+            }
+        }
+        // Insert the eligible candidate peers, with their discounted age.
+        let maximum_candidate_peers = self.maximum_candidate_peers();
+        let mut candidate_peers = self.candidate_peers.write();
+        for (peer_ip, has_connected, age_secs) in eligible_peers {
+            if candidate_peers.contains_key(&peer_ip) || candidate_peers.len() >= maximum_candidate_peers {
+                summary.num_dropped += 1;
+                continue;
+            }
+            let age = Duration::from_secs(age_secs.saturating_mul(Self::IMPORTED_CANDIDATE_AGE_FACTOR));
+            candidate_peers.insert(peer_ip, CandidatePeer::with_age(has_connected, age));
+            summary.num_candidates += 1;
+        }
+        drop(candidate_peers);
+        #[cfg(feature = "metrics")]
+        self.update_metrics();
+        summary
+    }
+
+    /// Returns the maximum number of candidate peers, as shrunk under memory pressure.
+    fn maximum_candidate_peers(&self) -> usize {
+        self.memory_budget.capacity(MemoryConsumer::CandidatePeers, Self::MAXIMUM_CANDIDATE_PEERS)
+    }
+
+    /// Returns the accounting of the memory used by the memory pool and the deduplication caches.
+    pub fn memory_budget(&self) -> &Arc<MemoryBudget> {
+        &self.memory_budget
+    }
+
+    /// Reports the memory used by the candidate peers and the cache, rebalances the memory budget,
+    /// and shrinks (or restores) their capacities accordingly.
+    pub fn update_memory_budget(&self) {
+        self.report_memory_usage();
+        match self.memory_budget.rebalance() {
+            Some(true) => warn!(
+                "Pool memory is approaching its maximum ({} of {} bytes) - shrinking the pools and caches (level {})",
+                self.memory_budget.total_usage(),
+                self.memory_budget.max_bytes().unwrap_or_default(),
+                self.memory_budget.shrink_level()
+            ),
+            Some(false) => info!(
+                "Pool memory has dropped ({} bytes) - restoring the pools and caches (level {})",
+                self.memory_budget.total_usage(),
+                self.memory_budget.shrink_level()
+            ),
+            None => return,
+        }
+
+        // Evict the candidate peers beyond the capacity, starting with the ones never connected to, then the oldest.
+        let maximum_candidate_peers = self.maximum_candidate_peers();
+        let mut candidate_peers = self.candidate_peers.write();
+        if candidate_peers.len() > maximum_candidate_peers {
+            let mut candidates: Vec<_> = candidate_peers.iter().map(|(ip, candidate)| (*ip, *candidate)).collect();
+            candidates.sort_unstable_by_key(|(_, candidate)| (candidate.has_connected(), candidate.last_updated()));
+            let num_evicted = candidates.len() - maximum_candidate_peers;
+            for (peer_ip, _) in &candidates[..num_evicted] {
+                candidate_peers.remove(peer_ip);
+            }
+        }
+        drop(candidate_peers);
+        // Resize the caches of recently-seen solutions and transactions.
+        self.cache.set_capacity(self.memory_budget.capacity(MemoryConsumer::RouterCache, MAX_CACHE_SIZE));
+        self.report_memory_usage();
+        #[cfg(feature = "metrics")]
+        self.update_metrics();
+    }
+
+    /// Reports the estimated memory used by the candidate peers and the cache.
+    fn report_memory_usage(&self) {
+        let num_candidates = self.candidate_peers.read().len();
+        self.memory_budget
+            .set_usage(MemoryConsumer::CandidatePeers, estimate_entries::<SocketAddr, CandidatePeer>(num_candidates));
+        self.memory_budget.set_usage(MemoryConsumer::RouterCache, self.cache.memory_usage());
+    }
+
+    /// Returns the supervisor of the spawned tasks.
+    pub fn supervisor(&self) -> &TaskSupervisor {
+        &self.supervisor
+    }
+
+    /// Returns the registry of the configuration items of the node that can be reloaded at runtime.
+    pub fn reloads(&self) -> &ReloadRegistry {
+        &self.reloads
+    }
+
+    /// Registers the peer export at the given path for reloads, so that the peers added to it since the node
+    /// started are imported without a restart.
+    ///
+    /// The export is read and validated in full before any peer is imported, so that a malformed file imports
+    /// nothing. As an import never overrides the state of the node, a reload only adds the new peers of the export.
+    pub fn register_peer_import(&self, path: PathBuf) {
+        // Note: The registry holds a weak reference, as it is owned by the router.
+        let router = Arc::downgrade(&self.0);
+        self.reloads.register("peer_import", move || {
+            let Some(router) = router.upgrade() else { bail!("The router is shut down") };
+            let summary = Self(router).import_peers(&PeerExport::load(&path)?);
+            Ok((!summary.is_empty()).then(|| format!("imported {summary}")))
+        });
+    }
+
+    /// Spawns a supervised task with the given name and policy, from the given factory;
+    /// it should only be used for long-running tasks.
+    pub fn spawn<F, T>(&self, name: &str, policy: TaskPolicy, factory: F)
+    where
+        F: Fn() -> T + Send + 'static,
+        T: Future<Output = ()> + Send + 'static,
+    {
+        self.supervisor.spawn(name, policy, factory);
+    }
+
+    /// Waits for the given shutdown notifications to be delivered to the peers, up to the shutdown notification
+    /// timeout, so that the peers learn the node is shutting down before its connections are closed.
+    pub async fn await_shutdown_notifications(&self, notifications: Vec<oneshot::Receiver<io::Result<()>>>) {
+        let timeout = Duration::from_millis(Self::SHUTDOWN_NOTIFICATION_TIMEOUT_IN_MS);
+        let delivered = tokio::time::timeout(timeout, async move {
+            for notification in notifications {
+                let _ = notification.await;
+            }
+        });
+        if delivered.await.is_err() {
+            debug!("Timed out delivering the shutdown notifications to the peers");
+        }
     }
 
     /// Shuts down the router.
     pub async fn shut_down(&self) {
         info!("Shutting down the router...");
+        // Report the trace scopes that are still active, as their elevated logging ends with the node.
+        for scope in self.trace_scopes.active() {
+            info!(
+                "Trace scope {} on {} was active at shutdown ({}s left)",
+                scope.id, scope.target, scope.expires_in_secs
+            );
+        }
         // Abort the tasks.
-        self.handles.lock().iter().for_each(|handle| handle.abort());
+        self.supervisor.shut_down(Duration::from_secs(TASK_SHUTDOWN_TIMEOUT_IN_SECS)).await;
         // Close the listener.
         self.tcp.shut_down().await;
     }
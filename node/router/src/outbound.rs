@@ -14,12 +14,17 @@
 // limitations under the License.
 
 use crate::{
+    BLOCK_ANNOUNCE_EXPERIMENT,
     Router,
-    messages::{Message, Ping},
+    SyncProgress,
+    messages::{BlockAnnounce, DisconnectReason, Features, Message, Ping},
 };
 use snarkos_node_sync_locators::BlockLocators;
 use snarkos_node_tcp::protocols::Writing;
-use snarkvm::prelude::Network;
+use snarkvm::{
+    ledger::committee::Committee,
+    prelude::{Network, block::Block},
+};
 use std::io;
 
 use std::net::SocketAddr;
@@ -32,9 +37,29 @@ pub trait Outbound<N: Network>: Writing<Message = Message<N>> {
     /// Returns `true` if the node is synced up to the latest block (within the given tolerance).
     fn is_block_synced(&self) -> bool;
 
-    /// Returns the number of blocks this node is behind the greatest peer height.
+    /// Returns the number of blocks this node is behind the median peer height.
+    ///
+    /// Note: This gates the gossip and the REST broadcasts of the node, so it must not be measured against the
+    /// greatest peer height, which a single peer can inflate.
     fn num_blocks_behind(&self) -> u32;
 
+    /// Returns the progress of the block sync of this node.
+    fn sync_progress(&self) -> SyncProgress;
+
+    /// Returns the latest committee, whose members are sent the gossip first, if it is known to this node.
+    fn latest_committee(&self) -> Option<Committee<N>> {
+        None
+    }
+
+    /// Returns the connected peers that negotiated the given features, in the order they are sent the gossip:
+    /// the members of the latest committee first, then the other validators, then the clients and provers.
+    fn connected_peers_by_priority(&self, features: Features) -> Vec<SocketAddr> {
+        let committee = self.latest_committee();
+        self.router().connected_peers_by_priority_with(features, |address| {
+            committee.as_ref().is_some_and(|committee| committee.is_committee_member(*address))
+        })
+    }
+
     /// Sends a "Ping" message to the given peer.
     fn send_ping(&self, peer_ip: SocketAddr, block_locators: Option<BlockLocators<N>>) {
         self.send(peer_ip, Message::Ping(Ping::new(self.router().node_type(), block_locators)));
@@ -70,8 +95,14 @@ pub trait Outbound<N: Network>: Writing<Message = Message<N>> {
         if matches!(message, Message::PeerRequest(_)) {
             self.router().cache.increment_outbound_peer_requests(peer_ip);
         }
+        // If the message type is a disconnect, record its reason for the peer event.
+        if let Message::Disconnect(disconnect) = &message {
+            self.router().insert_disconnect_reason(peer_ip, disconnect.reason);
+        }
         // Retrieve the message name.
         let name = message.name();
+        // Send the message within the span of the trace scopes covering it, if any, to elevate its logging.
+        let _span = self.router().trace_scopes().level_for_message(peer_ip, &name).map(|level| level.span().entered());
         // Send the message to the peer.
         trace!("Sending '{name}' to '{peer_ip}'");
         let result = self.unicast(peer_addr, message);
@@ -84,7 +115,22 @@ pub trait Outbound<N: Network>: Writing<Message = Message<N>> {
         result.ok()
     }
 
+    /// Sends a `Disconnect` message to every connected peer, noting that the node is shutting down.
+    ///
+    /// The caller is provided with a [`oneshot::Receiver`] per notified peer, which can be used to determine
+    /// when the notifications have been delivered (see `Router::await_shutdown_notifications`).
+    fn notify_shutdown(&self) -> Vec<oneshot::Receiver<io::Result<()>>> {
+        self.router()
+            .connected_peers()
+            .into_iter()
+            .filter_map(|peer_ip| self.send(peer_ip, Message::Disconnect(DisconnectReason::ShuttingDown.into())))
+            .collect()
+    }
+
     /// Sends the given message to every connected peer, excluding the sender and any specified peer IPs.
+    ///
+    /// The committee members and the other validators are sent the message before the clients and provers.
+    /// Note: The order only applies to the sends of this call, and no peer is held back to favor another.
     fn propagate(&self, message: Message<N>, excluded_peers: &[SocketAddr]) {
         // TODO (howardwu): Serialize large messages once only.
         // // Perform ahead-of-time, non-blocking serialization just once for applicable objects.
@@ -102,8 +148,13 @@ pub trait Outbound<N: Network>: Writing<Message = Message<N>> {
         //     }
         // }
 
-        // Prepare the peers to send to.
-        let connected_peers = self.router().connected_peers();
+        // Suppress the gossip, if the node is significantly behind.
+        if self.is_gossip_suppressed(&message) {
+            return;
+        }
+
+        // Prepare the peers to send to, in the order of their priority.
+        let connected_peers = self.connected_peers_by_priority(Features::NONE);
         let peers = connected_peers.iter().filter(|peer_ip| !excluded_peers.contains(peer_ip));
 
         // Iterate through all peers that are not the sender and excluded peers.
@@ -130,6 +181,11 @@ pub trait Outbound<N: Network>: Writing<Message = Message<N>> {
         //     }
         // }
 
+        // Suppress the gossip, if the node is significantly behind.
+        if self.is_gossip_suppressed(&message) {
+            return;
+        }
+
         // Prepare the peers to send to.
         let connected_validators = self.router().connected_validators();
         let peers = connected_validators.iter().filter(|peer_ip| !excluded_peers.contains(peer_ip));
@@ -140,6 +196,51 @@ pub trait Outbound<N: Network>: Writing<Message = Message<N>> {
         }
     }
 
+    /// Sends a compact announcement of the given block to every connected peer that negotiated block announcements,
+    /// and that the block announcement experiment is enabled for.
+    ///
+    /// The committee members and the other validators are sent the announcement before the clients and provers.
+    /// Note: The order only applies to the sends of this call, and no peer is held back to favor another.
+    fn announce_block(&self, block: &Block<N>) {
+        let message = Message::BlockAnnounce(BlockAnnounce::new(block));
+        // Suppress the announcement, if the node is significantly behind.
+        if self.is_gossip_suppressed(&message) {
+            return;
+        }
+        let experiments = self.router().experiments();
+        for peer_ip in self.connected_peers_by_priority(Features::BLOCK_ANNOUNCE) {
+            if experiments.enabled_for(peer_ip, BLOCK_ANNOUNCE_EXPERIMENT) {
+                self.send(peer_ip, message.clone());
+            }
+        }
+    }
+
+    /// Returns `true` if the gossip of the given message is suppressed, because the node is significantly behind.
+    ///
+    /// Note: Only the gossip of unconfirmed transmissions and blocks is suppressed by the router.
+    /// The BFT messages of a validator are sent by its gateway, and are never suppressed.
+    fn is_gossip_suppressed(&self, message: &Message<N>) -> bool {
+        // Ensure the message is gossip that is subject to the relay gate.
+        if !matches!(
+            message,
+            Message::UnconfirmedSolution(_) | Message::UnconfirmedTransaction(_) | Message::BlockAnnounce(_)
+        ) {
+            return false;
+        }
+        // Update the relay gate with the current sync status.
+        let relay_gate = self.router().relay_gate();
+        if !relay_gate.update(self.num_blocks_behind()) {
+            return false;
+        }
+        relay_gate.record_suppressed(1);
+        debug!(
+            "Suppressed the gossip of '{}' (the node is {} blocks behind)",
+            message.name(),
+            relay_gate.num_blocks_behind()
+        );
+        true
+    }
+
     /// Returns `true` if the message can be sent.
     fn can_send(&self, peer_ip: SocketAddr, message: &Message<N>) -> bool {
         // Ensure the peer is connected before sending.
@@ -13,19 +13,27 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{Heartbeat, Inbound, Outbound};
+use crate::{AccountStatus, Heartbeat, Inbound, Outbound, SyncSummary, TaskPolicy, TaskSupervisor};
+use snarkos_node_sync_locators::BlockLocators;
 use snarkos_node_tcp::{
     P2P,
     protocols::{Disconnect, Handshake, OnConnect},
 };
 use snarkvm::prelude::Network;
 
+use anyhow::{Result, bail};
 use core::time::Duration;
+use std::net::SocketAddr;
 
 #[async_trait]
 pub trait Routing<N: Network>:
     P2P + Disconnect + OnConnect + Handshake + Inbound<N> + Outbound<N> + Heartbeat<N>
 {
+    /// The duration in seconds after which a diagnostic is emitted, if no peers have connected.
+    const BOOTSTRAP_DIAGNOSTIC_WINDOW_IN_SECS: u64 = 120; // 2 minutes
+    /// The interval in seconds at which the diagnostic is repeated, while no peers are connected.
+    const BOOTSTRAP_DIAGNOSTIC_INTERVAL_IN_SECS: u64 = 900; // 15 minutes
+
     /// Initialize the routing.
     async fn initialize_routing(&self) {
         // Enable the TCP protocols.
@@ -36,8 +44,12 @@ pub trait Routing<N: Network>:
         self.enable_on_connect().await;
         // Enable the TCP listener. Note: This must be called after the above protocols.
         self.enable_listener().await;
+        // Initialize the bootstrap phase.
+        self.initialize_bootstrap();
         // Initialize the heartbeat.
         self.initialize_heartbeat();
+        // Initialize the cache maintenance.
+        self.initialize_cache_maintenance();
     }
 
     // Start listening for inbound connections.
@@ -45,16 +57,95 @@ pub trait Routing<N: Network>:
         self.tcp().enable_listener().await.expect("Failed to enable the TCP listener");
     }
 
+    /// Initialize the bootstrap phase, which dials the bootstrap and trusted peers with an exponential backoff,
+    /// and periodically emits a diagnostic until a peer has connected.
+    fn initialize_bootstrap(&self) {
+        // Skip the bootstrap phase if there are no peers to dial.
+        if self.router().bootstrap_targets().is_empty() {
+            return;
+        }
+        let self_clone = self.clone();
+        self.router().spawn("bootstrap", TaskPolicy::Auxiliary, move || {
+            let self_clone = self_clone.clone();
+            async move {
+                let window = Duration::from_secs(Self::BOOTSTRAP_DIAGNOSTIC_WINDOW_IN_SECS);
+                let interval = Duration::from_secs(Self::BOOTSTRAP_DIAGNOSTIC_INTERVAL_IN_SECS);
+                // End the bootstrap phase once a peer has connected.
+                while self_clone.router().number_of_connected_peers() == 0 {
+                    // Dial the bootstrap peers whose backoff has elapsed.
+                    self_clone.router().dial_bootstrap_peers();
+                    // Emit the diagnostic, if no peers have connected within the window.
+                    if let Some(diagnostic) = self_clone.router().next_bootstrap_diagnostic(window, interval) {
+                        warn!("{diagnostic}");
+                    }
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                }
+            }
+        });
+    }
+
     /// Initialize a new instance of the heartbeat.
     fn initialize_heartbeat(&self) {
         let self_clone = self.clone();
-        self.router().spawn(async move {
-            loop {
-                // Process a heartbeat in the router.
-                self_clone.heartbeat();
-                // Sleep for `HEARTBEAT_IN_SECS` seconds.
-                tokio::time::sleep(Duration::from_secs(Self::HEARTBEAT_IN_SECS)).await;
+        self.router().spawn("heartbeat", TaskPolicy::Restart { max_restarts: 3 }, move || {
+            let self_clone = self_clone.clone();
+            async move {
+                loop {
+                    // Process a heartbeat in the router.
+                    self_clone.heartbeat();
+                    // Sleep for `HEARTBEAT_IN_SECS` seconds.
+                    tokio::time::sleep(Duration::from_secs(Self::HEARTBEAT_IN_SECS)).await;
+                }
+            }
+        });
+    }
+
+    /// Initialize a new instance of the cache maintenance, which cleans up each cache of the router
+    /// whenever it is due for it.
+    fn initialize_cache_maintenance(&self) {
+        let self_clone = self.clone();
+        self.router().spawn("cache-maintenance", TaskPolicy::Restart { max_restarts: 3 }, move || {
+            let self_clone = self_clone.clone();
+            async move {
+                loop {
+                    // Clean up the caches that are due for it.
+                    let wait = self_clone.router().cache.cleanup();
+                    #[cfg(feature = "metrics")]
+                    self_clone.router().cache.update_metrics();
+                    // Sleep until the next cleanup is due.
+                    tokio::time::sleep(wait).await;
+                }
             }
         });
     }
+
+    /// Forces an immediate block sync against the given connected peer, and returns a summary of the block requests.
+    /// By default, forced syncs are not supported, and node types that sync via the router must override this method.
+    async fn sync_from_peer(&self, peer_ip: SocketAddr) -> Result<SyncSummary> {
+        bail!("Unable to sync from '{peer_ip}' - forced syncs are not supported by a {}", self.router().node_type())
+    }
+
+    /// Returns the supervisors of the tasks of the node, other than the REST server.
+    /// By default, only the router tasks are supervised, and node types with more components must override this method.
+    fn supervisors(&self) -> Vec<TaskSupervisor> {
+        vec![self.router().supervisor().clone()]
+    }
+
+    /// Returns the status of the node account, as observed in the local ledger.
+    /// By default, the account status is not tracked, and node types with a ledger must override this method.
+    fn account_status(&self) -> Option<AccountStatus> {
+        None
+    }
+
+    /// Returns `true` if a read-back of a critical write detected that the storage of the node is corrupted.
+    /// By default, the writes are not read back, and node types with a ledger must override this method.
+    fn is_storage_corrupted(&self) -> bool {
+        false
+    }
+
+    /// Returns the current block locators of the node.
+    /// By default, block locators are not supported, and node types that sync via the router must override this method.
+    fn block_locators(&self) -> Result<BlockLocators<N>> {
+        bail!("Block locators are not supported by a {}", self.router().node_type())
+    }
 }
@@ -0,0 +1,171 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+use common::*;
+
+use snarkos_node_router::{
+    BannedIp,
+    PeerLimits,
+    ReloadStatus,
+    Router,
+    messages::{Features, NodeType},
+    operator_ban_list_path,
+};
+use snarkos_node_tcp::P2P;
+use snarkvm::prelude::MainnetV0 as CurrentNetwork;
+
+use deadline::deadline;
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    path::PathBuf,
+    time::Duration,
+};
+
+/// Initializes a devnet client router persisting its ban list to the given path, listening on a random port.
+async fn router(ban_list_path: PathBuf) -> TestRouter<CurrentNetwork> {
+    let router: TestRouter<CurrentNetwork> = Router::new(
+        SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 0),
+        NodeType::Client,
+        sample_account(),
+        &[],
+        PeerLimits::new(NodeType::Client, Some(10)),
+        false,
+        false,
+        true,
+        true,
+        Default::default(),
+        Features::NONE,
+        Default::default(),
+        Some(ban_list_path),
+    )
+    .await
+    .expect("couldn't create the router")
+    .into();
+    router.tcp().enable_listener().await.unwrap();
+    router
+}
+
+#[tokio::test]
+async fn test_ban_ip() {
+    let path = std::env::temp_dir().join(format!("snarkos-ban-list-{}.json", rand::random::<u64>()));
+    let localhost = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+    let node0 = router(path.clone()).await;
+    let node1 = client(0, 2).await;
+    node1.tcp().enable_listener().await.unwrap();
+
+    // Ban the IP of node1, and ensure node0 neither dials it, on any port, nor accepts its connections.
+    node0.ban_ip(localhost, None);
+    assert!(node0.is_banned(&localhost));
+    assert!(node0.connect(node1.local_ip()).is_none());
+    assert!(node0.connect(SocketAddr::new(localhost, 4130)).is_none());
+    node1.connect(node0.local_ip());
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(node0.number_of_connected_peers(), 0);
+    // Ensure node0 does not learn a candidate peer on the banned IP.
+    node0.insert_candidate_peers(&[SocketAddr::new(localhost, 4131)]);
+    assert_eq!(node0.number_of_candidate_peers(), 0);
+
+    // Restart node0, and ensure the ban is restored from the ban list.
+    drop(node0);
+    let node0 = router(path.clone()).await;
+    assert!(node0.is_banned(&localhost));
+    assert_eq!(node0.banned_ips(), vec![BannedIp { ip: localhost, expires_at: None }]);
+    assert!(node0.connect(node1.local_ip()).is_none());
+
+    // Unban the IP, and ensure node0 connects to node1 again.
+    assert!(node0.unban_ip(localhost));
+    assert!(!node0.unban_ip(localhost));
+    assert!(node0.connect(node1.local_ip()).is_some());
+    deadline!(Duration::from_secs(5), move || node0.number_of_connected_peers() == 1);
+
+    let _ = std::fs::remove_file(path);
+}
+
+#[tokio::test]
+async fn test_ban_ip_expires() {
+    let path = std::env::temp_dir().join(format!("snarkos-ban-list-{}.json", rand::random::<u64>()));
+    let ip = IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4));
+
+    // Ban an IP for a second, and ensure the ban expires.
+    let node = router(path.clone()).await;
+    node.ban_ip(ip, Some(Duration::from_secs(1)));
+    assert!(node.is_banned(&ip));
+    assert_eq!(node.remove_expired_bans(), 0);
+    deadline!(Duration::from_secs(5), move || !node.is_banned(&ip));
+
+    // Ensure the expired ban is pruned on load.
+    let node = router(path.clone()).await;
+    assert!(node.banned_ips().is_empty());
+    assert_eq!(node.remove_expired_bans(), 0);
+
+    let _ = std::fs::remove_file(path);
+}
+
+#[tokio::test]
+async fn test_reload_ban_list() {
+    let path = std::env::temp_dir().join(format!("snarkos-ban-list-{}.json", rand::random::<u64>()));
+    let localhost = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+    let node0 = router(path.clone()).await;
+    let node1 = client(0, 2).await;
+    node1.tcp().enable_listener().await.unwrap();
+    assert_eq!(node0.reloads().names(), ["ban_list"]);
+
+    // Connect node0 to node1.
+    node0.connect(node1.local_ip());
+    let node0_ = node0.clone();
+    deadline!(Duration::from_secs(5), move || node0_.number_of_connected_peers() == 1);
+
+    // Ban the IP of node1 in the operator's ban list, and ensure the reload applies the ban and drops node1.
+    let operator_path = operator_ban_list_path(&path);
+    std::fs::write(&operator_path, r#"{ "version": 1, "bans": [{ "ip": "127.0.0.1" }] }"#).unwrap();
+    let summary = node0.reloads().reload_all();
+    assert_eq!(summary.get("ban_list"), Some(&ReloadStatus::Changed { changes: "banned 127.0.0.1".to_string() }));
+    assert!(node0.is_banned(&localhost));
+    let node0_ = node0.clone();
+    deadline!(Duration::from_secs(5), move || node0_.number_of_connected_peers() == 0);
+
+    // Ban another IP at runtime, and ensure it does not overwrite the operator's ban list.
+    let runtime_ip = IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4));
+    node0.ban_ip(runtime_ip, None);
+    assert_eq!(
+        std::fs::read_to_string(&operator_path).unwrap(),
+        r#"{ "version": 1, "bans": [{ "ip": "127.0.0.1" }] }"#
+    );
+    assert_eq!(node0.reloads().reload_all().get("ban_list"), Some(&ReloadStatus::Unchanged));
+
+    // Ensure a malformed file fails to reload, and keeps the bans.
+    std::fs::write(&operator_path, r#"{ "version": 1, "bans": [{ "ip": "127.0.0."#).unwrap();
+    let summary = node0.reloads().reload_all();
+    assert!(matches!(summary.get("ban_list"), Some(ReloadStatus::Failed { .. })));
+    assert_eq!(summary.num_failed(), 1);
+    assert_eq!(node0.banned_ips(), vec![BannedIp { ip: runtime_ip, expires_at: None }, BannedIp {
+        ip: localhost,
+        expires_at: None
+    }]);
+
+    // Lift the ban in the operator's ban list, and ensure node0 connects to node1 again, and keeps the ban at runtime.
+    std::fs::write(&operator_path, r#"{ "version": 1, "bans": [] }"#).unwrap();
+    let summary = node0.reloads().reload_all();
+    assert_eq!(summary.get("ban_list"), Some(&ReloadStatus::Changed { changes: "unbanned 127.0.0.1".to_string() }));
+    assert!(node0.connect(node1.local_ip()).is_some());
+    assert!(node0.is_banned(&runtime_ip));
+    deadline!(Duration::from_secs(5), move || node0.number_of_connected_peers() == 1);
+
+    let _ = std::fs::remove_file(path);
+    let _ = std::fs::remove_file(operator_path);
+}
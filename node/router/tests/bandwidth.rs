@@ -0,0 +1,74 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+use common::*;
+
+use snarkos_account::Account;
+use snarkos_node_router::{
+    Outbound,
+    messages::{Message, PeerRequest},
+};
+use snarkos_node_tcp::{
+    P2P,
+    protocols::{Disconnect, Handshake, Reading, Writing},
+};
+use snarkvm::utilities::TestRng;
+
+use core::time::Duration;
+use deadline::deadline;
+
+#[tokio::test]
+async fn test_bandwidth_per_peer() {
+    // Create 2 routers, with distinct accounts.
+    let rng = &mut TestRng::default();
+    let node0 = validator_with_account(0, 2, Account::new(rng).unwrap()).await;
+    let node1 = validator_with_account(0, 2, Account::new(rng).unwrap()).await;
+    for node in [&node0, &node1] {
+        node.enable_handshake().await;
+        node.enable_reading().await;
+        node.enable_writing().await;
+        node.enable_disconnect().await;
+        node.tcp().enable_listener().await.unwrap();
+    }
+    let (node0_ip, node1_ip) = (node0.local_ip(), node1.local_ip());
+
+    // Connect node1 to node0.
+    assert!(node1.connect(node0_ip).unwrap().await.unwrap());
+    let (node0_, node1_) = (node0.clone(), node1.clone());
+    deadline!(Duration::from_secs(5), move || node0_.is_connected(&node1_ip) && node1_.is_connected(&node0_ip));
+
+    // Send a message from node1 to node0.
+    node1.send(node0_ip, Message::PeerRequest(PeerRequest));
+
+    // Ensure both sides account for the bytes of the message, once it is delivered.
+    let (node0_, node1_) = (node0.clone(), node1.clone());
+    deadline!(Duration::from_secs(5), move || {
+        let (bytes_sent, bytes_received) = (node1_.connected_metrics()[0].4, node0_.connected_metrics()[0].5);
+        bytes_sent > 0 && bytes_sent == bytes_received
+    });
+
+    // Reconnect node1 to node0.
+    node1.disconnect(node0_ip).await.unwrap();
+    let (node0_, node1_) = (node0.clone(), node1.clone());
+    deadline!(Duration::from_secs(5), move || !node0_.is_connected(&node1_ip) && !node1_.is_connected(&node0_ip));
+    assert!(node1.connect(node0_ip).unwrap().await.unwrap());
+    let (node0_, node1_) = (node0.clone(), node1.clone());
+    deadline!(Duration::from_secs(5), move || node0_.is_connected(&node1_ip) && node1_.is_connected(&node0_ip));
+
+    // Ensure the counters were reset on the disconnect.
+    assert_eq!(node1.connected_metrics()[0].4, 0);
+    assert_eq!(node0.connected_metrics()[0].5, 0);
+}
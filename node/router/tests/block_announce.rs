@@ -0,0 +1,105 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+use common::*;
+
+use snarkos_node_router::{
+    Inbound,
+    PeerLimits,
+    Router,
+    messages::{BlockAnnounce, Features, Message, NodeType},
+};
+use snarkos_node_tcp::{P2P, protocols::Handshake};
+use snarkvm::prelude::MainnetV0 as CurrentNetwork;
+
+use deadline::deadline;
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    time::Duration,
+};
+
+/// Initializes a router of the given type, advertising the given features, and listening on a random port.
+async fn router(node_type: NodeType, features: Features) -> TestRouter<CurrentNetwork> {
+    let router: TestRouter<CurrentNetwork> = Router::new(
+        SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 0),
+        node_type,
+        sample_account(),
+        &[],
+        PeerLimits::new(node_type, Some(10)),
+        false,
+        false,
+        true,
+        true,
+        Default::default(),
+        features,
+        Default::default(),
+        None,
+    )
+    .await
+    .expect("couldn't create the router")
+    .into();
+    router.enable_handshake().await;
+    router.tcp().enable_listener().await.unwrap();
+    router
+}
+
+#[tokio::test]
+async fn test_block_announce_is_negotiated() {
+    // Create a validator, and clients with and without block announcements.
+    let validator = router(NodeType::Validator, Features::BLOCK_ANNOUNCE).await;
+    let announced_client = router(NodeType::Client, Features::BLOCK_ANNOUNCE).await;
+    let gossip_client = router(NodeType::Client, Features::NONE).await;
+
+    // Connect the clients to the validator.
+    announced_client.connect(validator.local_ip());
+    gossip_client.connect(validator.local_ip());
+    let validator_ = validator.clone();
+    deadline!(Duration::from_secs(5), move || validator_.number_of_connected_peers() == 2);
+
+    // Ensure the validator only announces blocks to the client that negotiated them.
+    assert_eq!(validator.connected_clients_with(Features::BLOCK_ANNOUNCE), vec![announced_client.local_ip()]);
+    assert_eq!(validator.connected_clients().len(), 2);
+    // Ensure the clients do not announce blocks to the validator.
+    assert!(announced_client.connected_clients_with(Features::BLOCK_ANNOUNCE).is_empty());
+
+    // Ensure a validator that did not negotiate block announcements does not announce blocks.
+    let gossip_validator = router(NodeType::Validator, Features::NONE).await;
+    announced_client.connect(gossip_validator.local_ip());
+    let gossip_validator_ = gossip_validator.clone();
+    deadline!(Duration::from_secs(5), move || gossip_validator_.number_of_connected_peers() == 1);
+    assert!(gossip_validator.connected_clients_with(Features::BLOCK_ANNOUNCE).is_empty());
+}
+
+#[tokio::test]
+async fn test_unsolicited_block_announce_is_rejected() {
+    let validator = router(NodeType::Validator, Features::NONE).await;
+    let client = router(NodeType::Client, Features::NONE).await;
+
+    // Connect the client to the validator.
+    client.connect(validator.local_ip());
+    let client_ = client.clone();
+    deadline!(Duration::from_secs(5), move || client_.number_of_connected_peers() == 1);
+
+    // Ensure a block announcement is a protocol violation for a client that did not negotiate them.
+    let announcement = BlockAnnounce {
+        height: 1,
+        hash: sample_genesis_block::<CurrentNetwork>().hash(),
+        timestamp: 0,
+        num_transactions: 0,
+    };
+    let error = client.inbound(validator.local_ip(), Message::BlockAnnounce(announcement)).await.unwrap_err();
+    assert!(error.to_string().contains("unsolicited block announcement"));
+}
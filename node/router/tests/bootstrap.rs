@@ -0,0 +1,66 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+use common::*;
+
+use snarkos_node_router::ConnectionFailure;
+use snarkos_node_tcp::P2P;
+
+use core::time::Duration;
+use deadline::deadline;
+use std::net::{SocketAddr, TcpListener};
+
+/// Returns a local address that is not accepting connections.
+fn unreachable_addr() -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    listener.local_addr().unwrap()
+}
+
+#[tokio::test]
+async fn test_bootstrap_diagnostic_with_unreachable_peers() {
+    let unreachable = [unreachable_addr(), unreachable_addr()];
+
+    // Create a router that trusts the unreachable addresses.
+    let node = validator(0, 2, &unreachable, false).await;
+    node.tcp().enable_listener().await.unwrap();
+    assert_eq!(node.bootstrap_targets().len(), 2);
+
+    // Dial the bootstrap peers, and wait for both attempts to fail.
+    node.dial_bootstrap_peers();
+    let node_ = node.clone();
+    deadline!(Duration::from_secs(5), move || {
+        let attempts = node_.bootstrap_attempts();
+        attempts.len() == 2 && attempts.values().all(|attempts| attempts.last_failure().is_some())
+    });
+
+    // Ensure the failed peers are not dialed again before their backoff elapses.
+    node.dial_bootstrap_peers();
+    assert!(node.bootstrap_attempts().values().all(|attempts| attempts.num_attempts() == 1));
+
+    // Ensure the diagnostic is not emitted before the window elapses.
+    let long = Duration::from_secs(3600);
+    assert!(node.next_bootstrap_diagnostic(long, long).is_none());
+
+    // Ensure the diagnostic is emitted once the window elapses, and contains the per-address reasons.
+    let diagnostic = node.next_bootstrap_diagnostic(Duration::ZERO, long).unwrap();
+    for peer_ip in unreachable {
+        assert!(diagnostic.contains(&format!("'{peer_ip}' - 1 attempt(s), last failure: connection refused")));
+    }
+    assert!(diagnostic.contains(ConnectionFailure::Refused.likely_cause()));
+
+    // Ensure the diagnostic is emitted only once within the interval.
+    assert!(node.next_bootstrap_diagnostic(Duration::ZERO, long).is_none());
+}
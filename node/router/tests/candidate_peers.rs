@@ -0,0 +1,67 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+use common::*;
+
+use snarkos_node_tcp::P2P;
+
+use core::time::Duration;
+use std::net::SocketAddr;
+
+#[tokio::test]
+async fn test_candidate_peer_refreshed_when_regossiped() {
+    // Create a router.
+    let node = client(0, 2).await;
+    node.tcp().enable_listener().await.unwrap();
+
+    let peer_ip: SocketAddr = "1.2.3.4:4130".parse().unwrap();
+
+    // Insert the candidate peer.
+    node.insert_candidate_peers(&[peer_ip]);
+    assert_eq!(node.number_of_candidate_peers(), 1);
+    let first = node.get_candidate_peer(&peer_ip).unwrap();
+    assert!(!first.has_connected());
+
+    // Sleep briefly, then re-gossip the same candidate peer.
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    node.insert_candidate_peers(&[peer_ip]);
+
+    // Ensure the candidate peer was not duplicated, and its timestamp was refreshed.
+    assert_eq!(node.number_of_candidate_peers(), 1);
+    let second = node.get_candidate_peer(&peer_ip).unwrap();
+    assert!(second.last_updated() > first.last_updated());
+}
+
+#[tokio::test]
+async fn test_expired_candidate_peers_are_removed() {
+    // Create a router.
+    let node = client(0, 2).await;
+    node.tcp().enable_listener().await.unwrap();
+
+    let peer_ip: SocketAddr = "1.2.3.4:4130".parse().unwrap();
+
+    // Insert the candidate peer.
+    node.insert_candidate_peers(&[peer_ip]);
+
+    // Ensure the candidate peer is retained within its maximum age.
+    assert_eq!(node.remove_expired_candidate_peers(Duration::from_secs(60), Duration::from_secs(60)), 0);
+    assert!(node.candidate_peers().contains(&peer_ip));
+
+    // Ensure a candidate peer that never connected is not subject to the longer retention.
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    assert_eq!(node.remove_expired_candidate_peers(Duration::ZERO, Duration::from_secs(60)), 1);
+    assert!(!node.candidate_peers().contains(&peer_ip));
+}
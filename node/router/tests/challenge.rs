@@ -0,0 +1,175 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+use common::*;
+
+use snarkos_account::Account;
+use snarkos_node_router::messages::{
+    ChallengeRequest,
+    ChallengeResponse,
+    Disconnect,
+    DisconnectReason,
+    Features,
+    Message,
+    MessageCodec,
+    NodeType,
+};
+use snarkos_node_tcp::{P2P, protocols::Handshake};
+use snarkvm::{
+    ledger::narwhal::Data,
+    prelude::{Field, MainnetV0 as CurrentNetwork},
+    utilities::TestRng,
+};
+
+use core::{str::FromStr, time::Duration};
+use deadline::deadline;
+use futures_util::{SinkExt, StreamExt};
+use rand::Rng;
+use std::net::SocketAddr;
+use tokio::net::TcpStream;
+use tokio_util::codec::Framed;
+
+/// Shakes hands with the node at the given address, on behalf of the given account, and returns the stream,
+/// or the message the node sent instead of its challenge.
+async fn shake_hands(
+    node_ip: SocketAddr,
+    account: &Account<CurrentNetwork>,
+    listener_port: u16,
+    version: u32,
+    request_nonce: u64,
+    response_nonce: u64,
+    delay: Duration,
+) -> Result<Framed<TcpStream, MessageCodec<CurrentNetwork>>, Option<Message<CurrentNetwork>>> {
+    let rng = &mut TestRng::default();
+    let stream = TcpStream::connect(node_ip).await.unwrap();
+    let mut framed = Framed::new(stream, MessageCodec::<CurrentNetwork>::handshake());
+
+    // Send the challenge request, on the given protocol version.
+    let mut request =
+        ChallengeRequest::new(listener_port, NodeType::Client, account.address(), request_nonce, Features::NONE);
+    request.version = version;
+    framed.send(Message::ChallengeRequest(request)).await.unwrap();
+    match framed.next().await {
+        Some(Ok(Message::ChallengeResponse(_))) => (),
+        message => return Err(message.and_then(Result::ok)),
+    }
+    let Some(Ok(Message::ChallengeRequest(peer_request))) = framed.next().await else { panic!("Expected a request") };
+
+    // Sign the challenge response in the format of the lower protocol version of both sides.
+    tokio::time::sleep(delay).await;
+    let data = ChallengeResponse::signed_bytes(
+        version,
+        peer_request.version,
+        account.address(),
+        peer_request.address,
+        peer_request.nonce,
+        response_nonce,
+    )
+    .unwrap();
+    let response = ChallengeResponse {
+        genesis_header: *sample_genesis_block::<CurrentNetwork>().header(),
+        restrictions_id: Field::from_str(
+            "7562506206353711030068167991213732850758501012603348777370400520506564970105field",
+        )
+        .unwrap(),
+        signature: Data::Object(account.sign_bytes(&data, rng).unwrap()),
+        nonce: response_nonce,
+        max_frame_size: None,
+    };
+    framed.send(Message::ChallengeResponse(response)).await.unwrap();
+    Ok(framed)
+}
+
+#[tokio::test]
+async fn test_legacy_version_handshake() {
+    let node = client(0, 4).await;
+    node.enable_handshake().await;
+    node.tcp().enable_listener().await.unwrap();
+
+    // Shake hands on the minimum version, with the signature format that only covers the nonces.
+    let rng = &mut TestRng::default();
+    let account = Account::<CurrentNetwork>::new(rng).unwrap();
+    let version = Message::<CurrentNetwork>::MINIMUM_VERSION;
+    assert!(version < Message::<CurrentNetwork>::FRESH_HANDSHAKE_VERSION);
+    let result = shake_hands(node.local_ip(), &account, 4130, version, rng.gen(), rng.gen(), Duration::ZERO).await;
+    assert!(result.is_ok());
+
+    // Ensure the node accepts the peer, until the minimum version is raised.
+    let node_ = node.clone();
+    deadline!(Duration::from_secs(5), move || node_.number_of_connected_peers() == 1);
+}
+
+#[tokio::test]
+async fn test_replayed_challenge_is_rejected() {
+    let node = client(0, 4).await;
+    node.enable_handshake().await;
+    node.tcp().enable_listener().await.unwrap();
+
+    // Complete a handshake.
+    let rng = &mut TestRng::default();
+    let account = Account::<CurrentNetwork>::new(rng).unwrap();
+    let version = Message::<CurrentNetwork>::VERSION;
+    let (request_nonce, response_nonce) = (rng.gen(), rng.gen());
+    let result =
+        shake_hands(node.local_ip(), &account, 4130, version, request_nonce, response_nonce, Duration::ZERO).await;
+    assert!(result.is_ok());
+    let node_ = node.clone();
+    deadline!(Duration::from_secs(5), move || node_.number_of_connected_peers() == 1);
+
+    // Replay the challenge request, and ensure the node rejects it.
+    let result = shake_hands(node.local_ip(), &account, 4131, version, request_nonce, rng.gen(), Duration::ZERO).await;
+    let Err(Some(Message::Disconnect(Disconnect { reason }))) = result else { panic!("Expected a disconnect") };
+    assert_eq!(reason, DisconnectReason::ProtocolViolation);
+
+    // Replay the response nonce in a fresh handshake, and ensure the node rejects it.
+    let Ok(mut framed) =
+        shake_hands(node.local_ip(), &account, 4132, version, rng.gen(), response_nonce, Duration::ZERO).await
+    else {
+        panic!("Expected a challenge from the node")
+    };
+    let Some(Ok(Message::Disconnect(Disconnect { reason }))) = framed.next().await else {
+        panic!("Expected a disconnect")
+    };
+    assert_eq!(reason, DisconnectReason::InvalidChallengeResponse);
+
+    // Ensure only the original handshake succeeded.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(node.number_of_connected_peers(), 1);
+}
+
+#[tokio::test]
+async fn test_late_challenge_response_is_rejected() {
+    let node = client(0, 4).await;
+    node.enable_handshake().await;
+    node.tcp().enable_listener().await.unwrap();
+
+    // Send the challenge response after the deadline, but before the handshake times out.
+    let rng = &mut TestRng::default();
+    let account = Account::<CurrentNetwork>::new(rng).unwrap();
+    let version = Message::<CurrentNetwork>::VERSION;
+    let delay = Duration::from_millis(2_300);
+    let Ok(mut framed) = shake_hands(node.local_ip(), &account, 4130, version, rng.gen(), rng.gen(), delay).await
+    else {
+        panic!("Expected a challenge from the node")
+    };
+
+    // Ensure the node rejects the late response.
+    let Some(Ok(Message::Disconnect(Disconnect { reason }))) = framed.next().await else {
+        panic!("Expected a disconnect")
+    };
+    assert_eq!(reason, DisconnectReason::InvalidChallengeResponse);
+    assert_eq!(node.number_of_connected_peers(), 0);
+}
@@ -24,7 +24,11 @@ use std::{
 };
 
 use snarkos_account::Account;
-use snarkos_node_router::{Router, messages::NodeType};
+use snarkos_node_router::{
+    PeerLimits,
+    Router,
+    messages::{Features, NodeType},
+};
 use snarkvm::prelude::{FromBytes, MainnetV0 as CurrentNetwork, Network, block::Block};
 
 /// A helper macro to print the TCP listening address, along with the connected and connecting peers.
@@ -77,10 +81,15 @@ pub async fn client(listening_port: u16, max_peers: u16) -> TestRouter<CurrentNe
         NodeType::Client,
         sample_account(),
         &[],
-        max_peers,
+        PeerLimits::new(NodeType::Client, Some(max_peers)),
+        false,
         false,
         true,
         true,
+        Default::default(),
+        Features::NONE,
+        Default::default(),
+        None,
     )
     .await
     .expect("couldn't create client router")
@@ -95,16 +104,49 @@ pub async fn prover(listening_port: u16, max_peers: u16) -> TestRouter<CurrentNe
         NodeType::Prover,
         sample_account(),
         &[],
-        max_peers,
+        PeerLimits::new(NodeType::Prover, Some(max_peers)),
+        false,
         false,
         true,
         true,
+        Default::default(),
+        Features::NONE,
+        Default::default(),
+        None,
     )
     .await
     .expect("couldn't create prover router")
     .into()
 }
 
+/// Initializes a validator router with the given account. Setting the `listening_port = 0` will result in a random
+/// port being assigned.
+#[allow(dead_code)]
+pub async fn validator_with_account(
+    listening_port: u16,
+    max_peers: u16,
+    account: Account<CurrentNetwork>,
+) -> TestRouter<CurrentNetwork> {
+    Router::new(
+        SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), listening_port),
+        NodeType::Validator,
+        account,
+        &[],
+        PeerLimits::new(NodeType::Validator, Some(max_peers)),
+        false,
+        false,
+        true,
+        true,
+        Default::default(),
+        Features::NONE,
+        Default::default(),
+        None,
+    )
+    .await
+    .expect("couldn't create validator router")
+    .into()
+}
+
 /// Initializes a validator router. Setting the `listening_port = 0` will result in a random port being assigned.
 #[allow(dead_code)]
 pub async fn validator(
@@ -118,10 +160,15 @@ pub async fn validator(
         NodeType::Validator,
         sample_account(),
         trusted_peers,
-        max_peers,
+        PeerLimits::new(NodeType::Validator, Some(max_peers)),
+        false,
         false,
         allow_external_peers,
         true,
+        Default::default(),
+        Features::NONE,
+        Default::default(),
+        None,
     )
     .await
     .expect("couldn't create validator router")
@@ -20,6 +20,7 @@ use snarkos_node_router::{
     Outbound,
     Router,
     Routing,
+    SyncProgress,
     messages::{
         BlockRequest,
         DisconnectReason,
@@ -46,15 +47,37 @@ use snarkvm::prelude::{
 };
 
 use async_trait::async_trait;
-use std::{io, net::SocketAddr, str::FromStr};
+use std::{
+    io,
+    net::SocketAddr,
+    str::FromStr,
+    sync::{
+        Arc,
+        atomic::{AtomicU32, AtomicUsize, Ordering},
+    },
+};
 use tracing::*;
 
+/// The test router, with the number of blocks it is behind, and the number of unconfirmed transactions it received.
 #[derive(Clone)]
-pub struct TestRouter<N: Network>(Router<N>);
+pub struct TestRouter<N: Network>(Router<N>, Arc<AtomicU32>, Arc<AtomicUsize>);
 
 impl<N: Network> From<Router<N>> for TestRouter<N> {
     fn from(router: Router<N>) -> Self {
-        Self(router)
+        Self(router, Default::default(), Default::default())
+    }
+}
+
+#[allow(dead_code)]
+impl<N: Network> TestRouter<N> {
+    /// Sets the number of blocks the router is behind the median peer height.
+    pub fn set_num_blocks_behind(&self, num_blocks_behind: u32) {
+        self.1.store(num_blocks_behind, Ordering::SeqCst);
+    }
+
+    /// Returns the number of unconfirmed transactions the router received.
+    pub fn num_unconfirmed_transactions(&self) -> usize {
+        self.2.load(Ordering::SeqCst)
     }
 }
 
@@ -115,8 +138,9 @@ impl<N: Network> Writing for TestRouter<N> {
 
     /// Creates an [`Encoder`] used to write the outbound messages to the target stream.
     /// The `side` parameter indicates the connection side **from the node's perspective**.
-    fn codec(&self, _addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
-        Default::default()
+    fn codec(&self, addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+        // Note: The handshake has completed, so the frame size limit negotiated with the peer is known.
+        self.router().outbound_codec(addr)
     }
 }
 
@@ -127,8 +151,9 @@ impl<N: Network> Reading for TestRouter<N> {
 
     /// Creates a [`Decoder`] used to interpret messages from the network.
     /// The `side` param indicates the connection side **from the node's perspective**.
-    fn codec(&self, _peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
-        Default::default()
+    fn codec(&self, peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+        // Note: The handshake has completed, so the frame size limit negotiated with the peer is known.
+        self.router().inbound_codec(peer_addr)
     }
 
     /// Processes a message received from the network.
@@ -160,9 +185,14 @@ impl<N: Network> Outbound<N> for TestRouter<N> {
         true
     }
 
-    /// Returns the number of blocks this node is behind the greatest peer height.
+    /// Returns the number of blocks this node is behind the median peer height.
     fn num_blocks_behind(&self) -> u32 {
-        0
+        self.1.load(Ordering::SeqCst)
+    }
+
+    /// Returns the progress of the block sync of this node.
+    fn sync_progress(&self) -> SyncProgress {
+        SyncProgress::default()
     }
 }
 
@@ -215,6 +245,7 @@ impl<N: Network> Inbound<N> for TestRouter<N> {
         _serialized: UnconfirmedTransaction<N>,
         _transaction: Transaction<N>,
     ) -> bool {
+        self.2.fetch_add(1, Ordering::SeqCst);
         true
     }
 }
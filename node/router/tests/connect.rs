@@ -16,7 +16,16 @@
 mod common;
 use common::*;
 
-use snarkos_node_tcp::{P2P, protocols::Handshake};
+use snarkos_account::Account;
+use snarkos_node_router::{
+    RestrictionCause,
+    messages::{MAXIMUM_MESSAGE_SIZE, NodeType},
+};
+use snarkos_node_tcp::{
+    P2P,
+    protocols::{Disconnect, Handshake},
+};
+use snarkvm::{prelude::Network, utilities::TestRng};
 
 use core::time::Duration;
 use deadline::deadline;
@@ -221,38 +230,148 @@ async fn test_validator_connection() {
     }
 }
 
-#[ignore]
+/// The number of times the simultaneous connection attempts are repeated.
+const NUM_SIMULTANEOUS_ATTEMPTS: usize = 20;
+
+/// Repeatedly dials the given routers to each other simultaneously, and ensures they always converge
+/// to exactly one stable connection.
+async fn assert_simultaneous_connections_converge<N: Network>(node0: TestRouter<N>, node1: TestRouter<N>) {
+    // Enable handshake and disconnect protocols.
+    node0.enable_handshake().await;
+    node1.enable_handshake().await;
+    node0.enable_disconnect().await;
+    node1.enable_disconnect().await;
+
+    // Start listening.
+    node0.tcp().enable_listener().await.unwrap();
+    node1.tcp().enable_listener().await.unwrap();
+    let (node0_ip, node1_ip) = (node0.local_ip(), node1.local_ip());
+
+    for _ in 0..NUM_SIMULTANEOUS_ATTEMPTS {
+        // Connect node0 to node1, and node1 to node0, simultaneously.
+        let handles = [node0.connect(node1_ip), node1.connect(node0_ip)];
+        for handle in handles.into_iter().flatten() {
+            handle.await.unwrap();
+        }
+
+        // Await for both nodes to be connected.
+        let (node0_, node1_) = (node0.clone(), node1.clone());
+        deadline!(Duration::from_secs(5), move || {
+            node0_.is_connected(&node1_ip)
+                && node1_.is_connected(&node0_ip)
+                && node0_.tcp().num_connecting() == 0
+                && node1_.tcp().num_connecting() == 0
+        });
+
+        // Ensure exactly one connection survives on both sides, and remains stable.
+        for _ in 0..5 {
+            print_tcp!(node0);
+            print_tcp!(node1);
+
+            // Check the TCP level.
+            assert_eq!(node0.tcp().num_connected(), 1);
+            assert_eq!(node1.tcp().num_connected(), 1);
+
+            // Check the router level.
+            assert_eq!(node0.number_of_connected_peers(), 1);
+            assert_eq!(node1.number_of_connected_peers(), 1);
+
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+
+        // Ensure neither node restricted the other for the dropped connection.
+        assert!(!node0.is_restricted(&node1_ip));
+        assert!(!node1.is_restricted(&node0_ip));
+
+        // Disconnect the nodes.
+        node0.disconnect(node1_ip).await.unwrap();
+        node1.disconnect(node0_ip).await.unwrap();
+
+        // Await for node1 and node0 to be disconnected.
+        let (node0_, node1_) = (node0.clone(), node1.clone());
+        deadline!(Duration::from_secs(5), move || {
+            node0_.tcp().num_connected() == 0
+                && node1_.tcp().num_connected() == 0
+                && node0_.number_of_connected_peers() == 0
+                && node1_.number_of_connected_peers() == 0
+        });
+    }
+}
+
 #[tokio::test]
 async fn test_connect_simultaneously_with_handshake() {
-    // Create 2 routers.
+    // Create 2 routers, with the same account.
     let node0 = validator(0, 2, &[], true).await;
     let node1 = client(0, 2).await;
     assert_eq!(node0.number_of_connected_peers(), 0);
     assert_eq!(node1.number_of_connected_peers(), 0);
 
-    // Enable handshake protocol.
-    node0.enable_handshake().await;
-    node1.enable_handshake().await;
-
-    {
-        // Connect node0 to node1.
-        node0.connect(node1.local_ip());
-        // Connect node1 to node0.
-        node1.connect(node0.local_ip());
-        // Sleep briefly.
-        tokio::time::sleep(Duration::from_millis(100)).await;
-
-        print_tcp!(node0);
-        print_tcp!(node1);
+    assert_simultaneous_connections_converge(node0, node1).await;
+}
 
-        // Check the TCP level.
-        assert_eq!(node0.tcp().num_connected(), 1);
-        assert_eq!(node0.tcp().num_connecting(), 0);
-        assert_eq!(node1.tcp().num_connected(), 1);
-        assert_eq!(node1.tcp().num_connecting(), 0);
+#[tokio::test]
+async fn test_connect_simultaneously_with_handshake_and_distinct_accounts() {
+    // Create 2 routers, with distinct accounts.
+    let rng = &mut TestRng::default();
+    let node0 = validator_with_account(0, 2, Account::new(rng).unwrap()).await;
+    let node1 = validator_with_account(0, 2, Account::new(rng).unwrap()).await;
+    assert_ne!(node0.address(), node1.address());
+
+    assert_simultaneous_connections_converge(node0, node1).await;
+}
 
-        // Check the router level.
-        assert_eq!(node0.number_of_connected_peers(), 1);
-        assert_eq!(node1.number_of_connected_peers(), 1);
+#[tokio::test]
+async fn test_restriction_follows_account() {
+    // Create a router, and 2 routers with the same account, listening on distinct IPs.
+    let rng = &mut TestRng::default();
+    let node0 = validator_with_account(0, 3, Account::new(rng).unwrap()).await;
+    let account = Account::new(rng).unwrap();
+    let node1 = validator_with_account(0, 3, account.clone()).await;
+    let node2 = validator_with_account(0, 3, account.clone()).await;
+    for node in [&node0, &node1, &node2] {
+        node.enable_handshake().await;
+        node.tcp().enable_listener().await.unwrap();
     }
+    let (node0_ip, node1_ip, node2_ip) = (node0.local_ip(), node1.local_ip(), node2.local_ip());
+
+    // Connect node1 to node0, and then disconnect.
+    assert!(node1.connect(node0_ip).unwrap().await.unwrap());
+    let node0_ = node0.clone();
+    deadline!(Duration::from_secs(5), move || node0_.is_connected(&node1_ip));
+    // Ensure the account address is exposed in the metrics of the peer.
+    let metrics = (node1_ip, NodeType::Validator, None, MAXIMUM_MESSAGE_SIZE, account.address());
+    assert!(node0.connected_metrics().iter().any(|(ip, node_type, ratio, frame_size, _, _, address)| {
+        (*ip, *node_type, *ratio, *frame_size, *address) == metrics
+    }));
+    node1.disconnect(node0_ip).await.unwrap();
+    let (node0_, node1_) = (node0.clone(), node1.clone());
+    deadline!(Duration::from_secs(5), move || !node0_.is_connected(&node1_ip) && !node1_.is_connected(&node0_ip));
+
+    // Connect node2 to node0, as the same account from another IP.
+    assert!(node2.connect(node0_ip).unwrap().await.unwrap());
+    let node0_ = node0.clone();
+    deadline!(Duration::from_secs(5), move || node0_.is_connected(&node2_ip));
+
+    // Ensure node0 recognizes both IPs as the same account.
+    let peer_ips: Vec<_> = node0.peer_ips_of_address(&account.address()).into_iter().map(|(ip, _)| ip).collect();
+    assert_eq!(peer_ips, vec![node2_ip, node1_ip]);
+    assert_eq!(node0.address_of_peer_ip(&node1_ip), Some(account.address()));
+
+    // Restrict node2, and ensure its account is not restricted, as restricting by account is disabled by default.
+    node0.insert_restricted_peer(node2_ip, RestrictionCause::Manual);
+    assert!(!node0.is_restricted_address(&account.address()));
+
+    // Restrict node2 again with restricting by account enabled, and disconnect it.
+    node0.set_restrict_by_account(true);
+    node0.insert_restricted_peer(node2_ip, RestrictionCause::Manual);
+    assert!(node0.is_restricted_address(&account.address()));
+    node0.disconnect(node2_ip).await.unwrap();
+    let (node0_, node2_) = (node0.clone(), node2.clone());
+    deadline!(Duration::from_secs(5), move || !node0_.is_connected(&node2_ip) && !node2_.is_connected(&node0_ip));
+
+    // Ensure the restriction carries over to node1, although its IP is not restricted.
+    assert!(!node0.is_restricted(&node1_ip));
+    assert!(!node1.connect(node0_ip).unwrap().await.unwrap());
+    assert!(!node0.is_connected(&node1_ip));
+    assert_eq!(node0.number_of_connected_peers(), 0);
 }
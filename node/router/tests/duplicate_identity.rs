@@ -0,0 +1,107 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+use common::*;
+
+use snarkos_account::Account;
+use snarkos_node_tcp::{P2P, protocols::Handshake};
+use snarkvm::utilities::TestRng;
+
+use core::time::Duration;
+use deadline::deadline;
+use std::time::Instant;
+
+#[tokio::test]
+async fn test_duplicate_identity() {
+    // Create 2 validators with the same account.
+    let node0 = validator(0, 2, &[], true).await;
+    let node1 = validator(0, 2, &[], true).await;
+    assert_eq!(node0.address(), node1.address());
+
+    // Enable handshake protocol.
+    node0.enable_handshake().await;
+    node1.enable_handshake().await;
+
+    // Start listening.
+    node0.tcp().enable_listener().await.unwrap();
+    node1.tcp().enable_listener().await.unwrap();
+
+    // Connect node0 to node1.
+    node0.connect(node1.local_ip());
+    let node0_ = node0.clone();
+    let node1_ip = node1.local_ip();
+    deadline!(Duration::from_secs(5), move || { node0_.is_connected(&node1_ip) });
+    let node1_ = node1.clone();
+    let node0_ip = node0.local_ip();
+    deadline!(Duration::from_secs(5), move || { node1_.is_connected(&node0_ip) });
+
+    // Ensure both nodes report the other one as using their account.
+    assert!(node0.duplicate_identity().is_active(Instant::now()));
+    assert_eq!(node0.duplicate_identity_peers(), vec![node1.local_ip()]);
+    assert_eq!(node1.duplicate_identity_peers(), vec![node0.local_ip()]);
+}
+
+#[tokio::test]
+async fn test_no_duplicate_identity() {
+    let mut rng = TestRng::default();
+
+    // Create 2 validators with different accounts.
+    let node0 = validator(0, 2, &[], true).await;
+    let node1 = validator_with_account(0, 2, Account::new(&mut rng).unwrap()).await;
+
+    // Enable handshake protocol.
+    node0.enable_handshake().await;
+    node1.enable_handshake().await;
+
+    // Start listening.
+    node0.tcp().enable_listener().await.unwrap();
+    node1.tcp().enable_listener().await.unwrap();
+
+    // Connect node0 to node1.
+    node0.connect(node1.local_ip());
+    let node1_ = node1.clone();
+    let node0_ip = node0.local_ip();
+    deadline!(Duration::from_secs(5), move || { node1_.is_connected(&node0_ip) });
+
+    // Ensure neither node reports a duplicate identity.
+    assert!(node0.duplicate_identity_peers().is_empty());
+    assert!(node1.duplicate_identity_peers().is_empty());
+}
+
+#[tokio::test]
+async fn test_shared_account_with_prover() {
+    // Create a client and a prover with the same account.
+    let node0 = client(0, 2).await;
+    let node1 = prover(0, 2).await;
+
+    // Enable handshake protocol.
+    node0.enable_handshake().await;
+    node1.enable_handshake().await;
+
+    // Start listening.
+    node0.tcp().enable_listener().await.unwrap();
+    node1.tcp().enable_listener().await.unwrap();
+
+    // Connect node0 to node1.
+    node0.connect(node1.local_ip());
+    let node1_ = node1.clone();
+    let node0_ip = node0.local_ip();
+    deadline!(Duration::from_secs(5), move || { node1_.is_connected(&node0_ip) });
+
+    // Ensure a prover sharing the account is not reported, as provers commonly share an account.
+    assert!(node0.duplicate_identity_peers().is_empty());
+    assert!(node1.duplicate_identity_peers().is_empty());
+}
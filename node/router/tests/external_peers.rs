@@ -0,0 +1,81 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+use common::*;
+
+use snarkos_node_router::Heartbeat;
+use snarkos_node_tcp::{P2P, protocols::Handshake};
+
+use core::time::Duration;
+use deadline::deadline;
+use std::net::SocketAddr;
+
+#[tokio::test]
+async fn test_restricted_node_only_inserts_trusted_candidates() {
+    let trusted_ip: SocketAddr = "1.2.3.4:4130".parse().unwrap();
+    let external_ip: SocketAddr = "5.6.7.8:4130".parse().unwrap();
+
+    // Create a router that disallows external peers.
+    let node = validator(0, 2, &[trusted_ip], false).await;
+    node.tcp().enable_listener().await.unwrap();
+
+    // Insert both a trusted and an external candidate peer.
+    node.insert_candidate_peers(&[trusted_ip, external_ip]);
+
+    // Ensure only the trusted peer was inserted.
+    assert_eq!(node.number_of_candidate_peers(), 1);
+    assert!(node.candidate_peers().contains(&trusted_ip));
+    assert!(!node.candidate_peers().contains(&external_ip));
+}
+
+#[tokio::test]
+async fn test_restricted_node_stays_within_configured_set() {
+    // Create an open router, which will be trusted by the restricted router.
+    let node0 = validator(0, 2, &[], true).await;
+    node0.enable_handshake().await;
+    node0.tcp().enable_listener().await.unwrap();
+    let addr0 = node0.local_ip();
+
+    // Create a restricted router, trusting only the first router.
+    let node1 = validator(0, 2, &[addr0], false).await;
+    node1.enable_handshake().await;
+    node1.tcp().enable_listener().await.unwrap();
+    let addr1 = node1.local_ip();
+
+    // Create an external router, unknown to the restricted router.
+    let node2 = client(0, 2).await;
+    node2.enable_handshake().await;
+    node2.tcp().enable_listener().await.unwrap();
+
+    // Gossip the external router to the restricted router; it should be ignored.
+    node1.insert_candidate_peers(&[node2.local_ip()]);
+    assert_eq!(node1.number_of_candidate_peers(), 0);
+
+    // Run a heartbeat on the restricted router, which should only dial its trusted peer.
+    node1.heartbeat();
+    let node1_ = node1.clone();
+    deadline!(Duration::from_secs(5), move || node1_.is_connected(&addr0));
+    assert_eq!(node1.number_of_connected_peers(), 1);
+    assert!(!node1.is_connecting(&node2.local_ip()));
+
+    // Ensure the external router is unable to connect to the restricted router.
+    let Ok(res) = node2.connect(addr1).unwrap().await else {
+        panic!("Connection failed for the wrong reasons.");
+    };
+    assert!(!res, "Connection was accepted when it should not have been.");
+    assert_eq!(node1.number_of_connected_peers(), 1);
+    assert!(!node1.is_connected(&node2.local_ip()));
+}
@@ -0,0 +1,60 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+use common::*;
+
+use snarkos_node_router::Heartbeat;
+use snarkos_node_tcp::{P2P, protocols::Handshake};
+
+use core::time::Duration;
+use deadline::deadline;
+use std::{io, time::Instant};
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_fd_exhaustion_sheds_lowest_value_peers() {
+    // Create a client, connected to a validator and two clients.
+    let node = client(0, 10).await;
+    node.enable_handshake().await;
+    node.tcp().enable_listener().await.unwrap();
+
+    let validator = validator(0, 10, &[], true).await;
+    let peers = [client(0, 10).await, client(0, 10).await];
+    for peer in std::iter::once(&validator).chain(&peers) {
+        peer.enable_handshake().await;
+        peer.tcp().enable_listener().await.unwrap();
+        node.connect(peer.local_ip());
+    }
+    let node_ = node.clone();
+    deadline!(Duration::from_secs(5), move || node_.number_of_connected_peers() == 3);
+
+    // Ensure no peer is shed while the file descriptors are available.
+    node.handle_fd_exhaustion();
+    assert_eq!(node.number_of_connected_peers(), 3);
+    assert!(!node.fd_exhaustion().is_exhausted);
+
+    // Simulate the exhaustion of the file descriptors, i.e. `EMFILE`.
+    assert!(node.tcp().fd_exhaustion().record_error(&io::Error::from_raw_os_error(24), true, Instant::now()));
+    let status = node.fd_exhaustion();
+    assert!(status.is_exhausted);
+    assert_eq!(status.num_errors, 1);
+
+    // Ensure a client is shed, while the validator is kept.
+    node.handle_fd_exhaustion();
+    let node_ = node.clone();
+    deadline!(Duration::from_secs(5), move || node_.number_of_connected_peers() == 2);
+    assert!(node.is_connected(&validator.local_ip()));
+}
@@ -0,0 +1,128 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+use common::*;
+
+use snarkos_account::Account;
+use snarkos_node_router::messages::{
+    ChallengeRequest,
+    ChallengeResponse,
+    Features,
+    MINIMUM_MAX_FRAME_SIZE,
+    Message,
+    MessageCodec,
+    NodeType,
+    PROVER_MAX_FRAME_SIZE,
+};
+use snarkos_node_tcp::{
+    P2P,
+    protocols::{Disconnect, Handshake, Reading, Writing},
+};
+use snarkvm::{
+    ledger::narwhal::Data,
+    prelude::{Field, MainnetV0 as CurrentNetwork},
+    utilities::TestRng,
+};
+
+use core::{str::FromStr, time::Duration};
+use deadline::deadline;
+use futures_util::{SinkExt, StreamExt};
+use rand::Rng;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+use tokio_util::codec::Framed;
+
+#[tokio::test]
+async fn test_frame_size_is_negotiated() {
+    // Create a client and a prover.
+    let node0 = client(0, 2).await;
+    let node1 = prover(0, 2).await;
+    for node in [&node0, &node1] {
+        node.enable_handshake().await;
+        node.tcp().enable_listener().await.unwrap();
+    }
+
+    // Connect the prover to the client.
+    assert!(node1.connect(node0.local_ip()).unwrap().await.unwrap());
+    let (node0_, node1_) = (node0.clone(), node1.clone());
+    deadline!(Duration::from_secs(5), move || node0_.number_of_connected_peers() == 1
+        && node1_.number_of_connected_peers() == 1);
+
+    // Ensure both sides settled on the stricter limit of the prover connection, and expose it in the metrics.
+    assert_eq!(node0.connected_metrics()[0].3, PROVER_MAX_FRAME_SIZE);
+    assert_eq!(node1.connected_metrics()[0].3, PROVER_MAX_FRAME_SIZE);
+}
+
+#[tokio::test]
+async fn test_oversized_frame_disconnects() {
+    // Create a client that reads from its peers.
+    let node = client(0, 2).await;
+    node.enable_handshake().await;
+    node.enable_reading().await;
+    node.enable_writing().await;
+    node.enable_disconnect().await;
+    node.tcp().enable_listener().await.unwrap();
+
+    // Shake hands on behalf of a prover, stating the smallest limit.
+    let rng = &mut TestRng::default();
+    let account = Account::<CurrentNetwork>::new(rng).unwrap();
+    let stream = TcpStream::connect(node.local_ip()).await.unwrap();
+    let mut framed = Framed::new(stream, MessageCodec::<CurrentNetwork>::handshake());
+    let request = ChallengeRequest::new(4130, NodeType::Prover, account.address(), rng.gen(), Features::NONE);
+    framed.send(Message::ChallengeRequest(request)).await.unwrap();
+    let Some(Ok(Message::ChallengeResponse(_))) = framed.next().await else { panic!("Expected a challenge response") };
+    let Some(Ok(Message::ChallengeRequest(peer_request))) = framed.next().await else {
+        panic!("Expected a challenge request")
+    };
+    let nonce: u64 = rng.gen();
+    let data = ChallengeResponse::signed_bytes(
+        Message::<CurrentNetwork>::VERSION,
+        peer_request.version,
+        account.address(),
+        peer_request.address,
+        peer_request.nonce,
+        nonce,
+    )
+    .unwrap();
+    let signature = account.sign_bytes(&data, rng).unwrap();
+    let response = ChallengeResponse {
+        genesis_header: *sample_genesis_block::<CurrentNetwork>().header(),
+        restrictions_id: Field::from_str(
+            "7562506206353711030068167991213732850758501012603348777370400520506564970105field",
+        )
+        .unwrap(),
+        signature: Data::Object(signature),
+        nonce,
+        max_frame_size: Some(MINIMUM_MAX_FRAME_SIZE as u32),
+    };
+    framed.send(Message::ChallengeResponse(response)).await.unwrap();
+
+    // Ensure the node negotiated the small limit.
+    let node_ = node.clone();
+    deadline!(Duration::from_secs(5), move || node_.number_of_connected_peers() == 1);
+    assert_eq!(node.connected_metrics()[0].3, MINIMUM_MAX_FRAME_SIZE);
+
+    // Announce a frame above the limit, and send no payload.
+    let mut stream = framed.into_inner();
+    stream.write_all(&((MINIMUM_MAX_FRAME_SIZE + 1) as u32).to_le_bytes()).await.unwrap();
+
+    // Ensure the node disconnects on the length of the frame alone.
+    let node_ = node.clone();
+    deadline!(Duration::from_secs(5), move || node_.number_of_connected_peers() == 0);
+    assert_eq!(stream.read(&mut [0u8; 1]).await.unwrap_or_default(), 0);
+}
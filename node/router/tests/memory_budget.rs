@@ -0,0 +1,94 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+use common::*;
+
+use snarkos_node_router::{
+    CandidatePeer,
+    MemoryBudget,
+    MemoryConsumer,
+    PeerLimits,
+    Router,
+    estimate_entries,
+    messages::{Features, NodeType},
+};
+use snarkvm::prelude::MainnetV0 as CurrentNetwork;
+
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    sync::Arc,
+};
+
+/// Initializes a client router with the given maximum pool memory.
+async fn router(max_pool_memory: u64) -> Router<CurrentNetwork> {
+    Router::new(
+        SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 0),
+        NodeType::Client,
+        sample_account(),
+        &[],
+        PeerLimits::new(NodeType::Client, None),
+        false,
+        false,
+        true,
+        true,
+        Arc::new(MemoryBudget::new(Some(max_pool_memory))),
+        Features::NONE,
+        Default::default(),
+        None,
+    )
+    .await
+    .expect("couldn't create the router")
+}
+
+/// Returns the given number of distinct candidate peer IPs.
+fn sample_peers(num_peers: u16) -> Vec<SocketAddr> {
+    (0..num_peers).map(|port| SocketAddr::new(IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4)), port)).collect()
+}
+
+#[tokio::test]
+async fn test_candidate_peers_are_shrunk_first() {
+    let candidate_size = estimate_entries::<SocketAddr, CandidatePeer>(1);
+    let router = router(1_200 * candidate_size).await;
+    let budget = router.memory_budget().clone();
+
+    // Fill the candidate peers, and ensure the gauge tracks them.
+    router.insert_candidate_peers(&sample_peers(u16::MAX));
+    assert_eq!(router.number_of_candidate_peers(), 10_000);
+    router.update_memory_budget();
+    assert_eq!(budget.usage(MemoryConsumer::CandidatePeers), 5_000 * candidate_size);
+
+    // Ensure the candidate peers are shrunk until the pool memory is below the high watermark,
+    // while the caches are left untouched.
+    router.update_memory_budget();
+    assert_eq!(router.number_of_candidate_peers(), 1_000);
+    assert_eq!(budget.shrink_level(), 2);
+    assert_eq!(budget.capacity_percent(MemoryConsumer::RouterCache), 100);
+    assert_eq!(budget.total_usage(), 1_000 * candidate_size + budget.usage(MemoryConsumer::RouterCache));
+
+    // Ensure the shrunk capacity is enforced on insertion.
+    router.insert_candidate_peers(&sample_peers(u16::MAX));
+    assert_eq!(router.number_of_candidate_peers(), 1_000);
+
+    // Ensure the candidate peers are restored once the pressure drops.
+    for peer_ip in router.candidate_peers() {
+        router.remove_candidate_peer(peer_ip);
+    }
+    router.update_memory_budget();
+    router.update_memory_budget();
+    assert_eq!(budget.shrink_level(), 0);
+    router.insert_candidate_peers(&sample_peers(u16::MAX));
+    assert_eq!(router.number_of_candidate_peers(), 10_000);
+}
@@ -0,0 +1,61 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+use common::*;
+
+use snarkos_node_router::{
+    Inbound,
+    MAX_MESSAGE_POLICY_VIOLATIONS,
+    messages::{BlockRequest, Message, NodeType, PeerRequest},
+};
+use snarkos_node_tcp::{P2P, protocols::Handshake};
+
+use core::time::Duration;
+
+#[tokio::test]
+async fn test_rejected_messages_escalate_to_disconnect() {
+    // Create a client and a prover.
+    let node0 = client(0, 2).await;
+    let node1 = prover(0, 2).await;
+
+    // Enable handshake protocol.
+    node0.enable_handshake().await;
+    node1.enable_handshake().await;
+
+    // Start listening.
+    node0.tcp().enable_listener().await.unwrap();
+    node1.tcp().enable_listener().await.unwrap();
+
+    // Connect node0 to node1.
+    node0.connect(node1.local_ip());
+    // Sleep briefly.
+    tokio::time::sleep(Duration::from_millis(1000)).await;
+
+    // Ensure the client knows the peer is a prover.
+    let peer_ip = node1.local_ip();
+    assert_eq!(node0.get_connected_peer_node_type(&peer_ip), Some(NodeType::Prover));
+
+    // Ensure the messages that are accepted from a prover are still dispatched.
+    node0.inbound(peer_ip, Message::PeerRequest(PeerRequest)).await.unwrap();
+
+    // Ensure a block request from a prover is dropped, until the violations escalate to a disconnect.
+    let block_request = || Message::BlockRequest(BlockRequest { start_height: 0, end_height: 1 });
+    for _ in 1..MAX_MESSAGE_POLICY_VIOLATIONS {
+        node0.inbound(peer_ip, block_request()).await.unwrap();
+    }
+    let error = node0.inbound(peer_ip, block_request()).await.unwrap_err();
+    assert!(error.to_string().contains("repeatedly sending rejected messages"));
+}
@@ -0,0 +1,83 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+use common::*;
+
+use snarkos_node_router::{
+    ConnectionFailure,
+    messages::{ChallengeRequest, Disconnect, DisconnectReason, Features, Message, MessageCodec, NodeType},
+};
+use snarkos_node_tcp::{P2P, protocols::Handshake};
+use snarkvm::prelude::{MainnetV0 as CurrentNetwork, Network, TestnetV0};
+
+use core::time::Duration;
+use deadline::deadline;
+use futures_util::{SinkExt, StreamExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_util::codec::Framed;
+
+#[tokio::test]
+async fn test_responder_rejects_another_network() {
+    // Create a router.
+    let node = client(0, 2).await;
+    node.enable_handshake().await;
+    node.tcp().enable_listener().await.unwrap();
+
+    // Send a challenge request on behalf of a node on another network.
+    let stream = TcpStream::connect(node.local_ip()).await.unwrap();
+    let mut framed = Framed::new(stream, MessageCodec::<CurrentNetwork>::handshake());
+    let mut request = ChallengeRequest::new(4130, NodeType::Client, sample_account().address(), 0, Features::NONE);
+    request.network = TestnetV0::ID;
+    framed.send(Message::ChallengeRequest(request)).await.unwrap();
+
+    // Ensure the node rejects the request right away, naming its own network.
+    match framed.next().await {
+        Some(Ok(Message::Disconnect(Disconnect { reason }))) => {
+            assert_eq!(reason, DisconnectReason::NetworkMismatch(CurrentNetwork::ID))
+        }
+        message => panic!("Expected a disconnect, received {message:?}"),
+    }
+    assert_eq!(node.number_of_connected_peers(), 0);
+}
+
+#[tokio::test]
+async fn test_initiator_reports_another_network() {
+    // Start a listener, standing in for a peer on another network.
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let peer_ip = listener.local_addr().unwrap();
+
+    // Create a router that trusts the peer.
+    let node = validator(0, 2, &[peer_ip], false).await;
+    node.enable_handshake().await;
+    node.tcp().enable_listener().await.unwrap();
+    node.dial_bootstrap_peers();
+
+    // Ensure the challenge request of the node carries its network, and reject it.
+    let (stream, _) = listener.accept().await.unwrap();
+    let mut framed = Framed::new(stream, MessageCodec::<CurrentNetwork>::handshake());
+    match framed.next().await {
+        Some(Ok(Message::ChallengeRequest(request))) => assert_eq!(request.network, CurrentNetwork::ID),
+        message => panic!("Expected a challenge request, received {message:?}"),
+    }
+    framed.send(DisconnectReason::NetworkMismatch(TestnetV0::ID).into()).await.unwrap();
+
+    // Ensure the node records the network mismatch as the reason of the failure.
+    let node_ = node.clone();
+    deadline!(Duration::from_secs(5), move || {
+        node_.bootstrap_attempts().get(&peer_ip).and_then(|attempts| attempts.last_failure().cloned())
+            == Some(ConnectionFailure::NetworkMismatch(TestnetV0::ID))
+    });
+}
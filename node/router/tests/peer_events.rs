@@ -0,0 +1,109 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+use common::*;
+
+use snarkos_account::Account;
+use snarkos_node_router::{
+    ConnectionFailure,
+    Outbound,
+    PeerEvent,
+    RestrictionCause,
+    messages::{DisconnectReason, Message, NodeType},
+};
+use snarkos_node_tcp::{
+    P2P,
+    protocols::{Disconnect, Handshake, Reading, Writing},
+};
+use snarkvm::{prelude::MainnetV0 as CurrentNetwork, utilities::TestRng};
+
+use core::time::Duration;
+use deadline::deadline;
+use tokio::sync::broadcast;
+
+/// Returns the next peer event, failing if none is published in time.
+async fn next_event(events: &mut broadcast::Receiver<PeerEvent<CurrentNetwork>>) -> PeerEvent<CurrentNetwork> {
+    tokio::time::timeout(Duration::from_secs(5), events.recv()).await.expect("no peer event in time").unwrap()
+}
+
+#[tokio::test]
+async fn test_peer_events() {
+    // Create 2 routers, with distinct accounts.
+    let rng = &mut TestRng::default();
+    let node0 = validator_with_account(0, 2, Account::new(rng).unwrap()).await;
+    let node1 = validator_with_account(0, 2, Account::new(rng).unwrap()).await;
+    for node in [&node0, &node1] {
+        node.enable_handshake().await;
+        node.enable_reading().await;
+        node.enable_writing().await;
+        node.enable_disconnect().await;
+        node.tcp().enable_listener().await.unwrap();
+    }
+    let (node0_ip, node1_ip) = (node0.local_ip(), node1.local_ip());
+    let mut events = node0.subscribe_peer_events();
+
+    // Connect node1 to node0.
+    assert!(node1.connect(node0_ip).unwrap().await.unwrap());
+    let expected = PeerEvent::Connected {
+        peer_ip: node1_ip,
+        address: node1.address(),
+        node_type: NodeType::Validator,
+        version: Message::<CurrentNetwork>::VERSION,
+    };
+    assert_eq!(next_event(&mut events).await, expected);
+
+    // Disconnect node1 from node0, with a reason.
+    let node1_ = node1.clone();
+    deadline!(Duration::from_secs(5), move || node1_.is_connected(&node0_ip));
+    node1.send(node0_ip, Message::Disconnect(DisconnectReason::PeerRefresh.into()));
+    let expected = PeerEvent::Disconnected { peer_ip: node1_ip, reason: Some(DisconnectReason::PeerRefresh) };
+    assert_eq!(next_event(&mut events).await, expected);
+
+    // Restrict node1.
+    node0.insert_restricted_peer(node1_ip, RestrictionCause::Manual);
+    let expected = PeerEvent::Restricted { peer_ip: node1_ip, cause: RestrictionCause::Manual };
+    assert_eq!(next_event(&mut events).await, expected);
+
+    // Ensure the handshake of the restricted node1 fails.
+    let node1_ = node1.clone();
+    deadline!(Duration::from_secs(5), move || !node1_.is_connected(&node0_ip));
+    assert!(!node1.connect(node0_ip).unwrap().await.unwrap());
+    let expected = PeerEvent::HandshakeFailed { peer_addr: node1_ip, error_kind: ConnectionFailure::HandshakeRejected };
+    assert_eq!(next_event(&mut events).await, expected);
+
+    // Ensure no other events were published.
+    assert!(matches!(events.try_recv(), Err(broadcast::error::TryRecvError::Empty)));
+}
+
+#[tokio::test]
+async fn test_peer_events_lagged() {
+    let node = validator(0, 2, &[], true).await;
+    let mut events = node.subscribe_peer_events();
+
+    // Publish more events than the capacity of the channel, without receiving them.
+    let num_events = snarkos_node_router::PEER_EVENTS_CAPACITY + 1;
+    for i in 0..num_events {
+        node.insert_restricted_peer(
+            format!("1.2.{}.{}:4130", i / 256, i % 256).parse().unwrap(),
+            RestrictionCause::Manual,
+        );
+    }
+
+    // Ensure the router was not blocked, and the subscriber is notified that it missed the oldest event.
+    assert_eq!(node.number_of_restricted_peers(), num_events);
+    assert!(matches!(events.recv().await, Err(broadcast::error::RecvError::Lagged(1))));
+    assert!(matches!(events.recv().await, Ok(PeerEvent::Restricted { .. })));
+}
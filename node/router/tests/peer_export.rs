@@ -0,0 +1,158 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+use common::*;
+
+use snarkos_node_router::{
+    PeerExport,
+    PeerImportSummary,
+    PeerLimits,
+    ReloadStatus,
+    RestrictionCause,
+    Router,
+    messages::{Features, NodeType},
+};
+use snarkos_node_tcp::P2P;
+use snarkvm::prelude::MainnetV0 as CurrentNetwork;
+
+use serde_json::json;
+use std::{
+    collections::HashSet,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    time::Duration,
+};
+
+/// Initializes a devnet client router with the given trusted peers, listening on a random port.
+async fn router(trusted_peers: &[SocketAddr]) -> TestRouter<CurrentNetwork> {
+    let router: TestRouter<CurrentNetwork> = Router::new(
+        SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 0),
+        NodeType::Client,
+        sample_account(),
+        trusted_peers,
+        PeerLimits::new(NodeType::Client, Some(10)),
+        false,
+        false,
+        true,
+        true,
+        Default::default(),
+        Features::NONE,
+        Default::default(),
+        None,
+    )
+    .await
+    .expect("couldn't create the router")
+    .into();
+    router.tcp().enable_listener().await.unwrap();
+    router
+}
+
+/// Returns the given peer IP as a `SocketAddr`.
+fn ip(ip: &str) -> SocketAddr {
+    ip.parse().unwrap()
+}
+
+#[tokio::test]
+async fn test_peer_export_and_import() {
+    // Seed the old node with candidate and restricted peers.
+    let old_node = router(&[ip("8.8.8.8:4130")]).await;
+    old_node.insert_candidate_peers(&[ip("1.1.1.1:4130"), ip("2.2.2.2:4130"), ip("3.3.3.3:4130")]);
+    old_node.insert_restricted_peer(ip("3.3.3.3:4130"), RestrictionCause::Manual);
+
+    // Export the peer knowledge of the old node.
+    let export = old_node.export_peers();
+    assert_eq!(export.version, 1);
+    let candidates: Vec<_> = export.candidate_peers.iter().map(|peer| peer.ip.as_str()).collect();
+    assert_eq!(candidates, ["1.1.1.1:4130", "2.2.2.2:4130"]);
+    assert_eq!(export.trusted_peers, ["8.8.8.8:4130"]);
+    assert_eq!(export.restricted_peers.len(), 1);
+    assert_eq!(export.restricted_peers[0].ip, "3.3.3.3:4130");
+    assert!((1..=150).contains(&export.restricted_peers[0].remaining_secs));
+
+    // Initialize a fresh node, with its own trusted peer and restriction.
+    let new_node = router(&[ip("4.4.4.4:4130")]).await;
+    new_node.insert_restricted_peer(ip("1.1.1.1:4130"), RestrictionCause::Manual);
+
+    // Amend the export in its JSON representation, with invalid entries and unknown fields.
+    let mut json = serde_json::to_value(&export).unwrap();
+    json["exported_by"] = json!("a newer node");
+    json["candidate_peers"][1]["age_secs"] = json!(100);
+    let candidate_peers = json["candidate_peers"].as_array_mut().unwrap();
+    candidate_peers.push(json!({ "ip": "not-an-ip" }));
+    candidate_peers.push(json!({ "ip": "10.0.0.1:4130", "has_connected": true }));
+    candidate_peers.push(json!({ "ip": new_node.local_ip().to_string() }));
+    let restricted_peers = json["restricted_peers"].as_array_mut().unwrap();
+    restricted_peers.push(json!({ "ip": "192.168.0.1:4130", "remaining_secs": 60 }));
+    restricted_peers.push(json!({ "ip": "4.4.4.4:4130", "remaining_secs": 60 }));
+    let export: PeerExport = serde_json::from_value(json).unwrap();
+
+    // Import the peer knowledge into the fresh node.
+    let summary = new_node.import_peers(&export);
+    assert_eq!(summary, PeerImportSummary { num_candidates: 2, num_restricted: 1, num_dropped: 6 });
+
+    // Ensure the valid candidates transferred, including the trusted peer of the old node,
+    // while the locally restricted candidate and the invalid entries were dropped.
+    assert_eq!(new_node.candidate_peers(), HashSet::from([ip("2.2.2.2:4130"), ip("8.8.8.8:4130")]));
+    // Ensure the imported age is discounted.
+    assert!(new_node.get_candidate_peer(&ip("2.2.2.2:4130")).unwrap().age() >= Duration::from_secs(200));
+
+    // Ensure the restriction transferred, but a trusted peer of the fresh node was not restricted.
+    assert!(new_node.is_restricted(&ip("3.3.3.3:4130")));
+    assert!(new_node.is_restricted(&ip("1.1.1.1:4130")));
+    assert!(!new_node.is_restricted(&ip("4.4.4.4:4130")));
+    assert!(!new_node.is_restricted(&ip("192.168.0.1:4130")));
+
+    // Ensure a repeated import is idempotent.
+    let summary = new_node.import_peers(&export);
+    assert_eq!((summary.num_candidates, summary.num_restricted), (0, 0));
+}
+
+#[tokio::test]
+async fn test_reload_peer_import() {
+    let path = std::env::temp_dir().join(format!("snarkos-peer-export-{}.json", rand::random::<u64>()));
+    std::fs::write(&path, r#"{ "version": 1, "candidate_peers": [{ "ip": "1.1.1.1:4130" }] }"#).unwrap();
+
+    // Seed the node with the peer export, as on startup, and register the export for reloads.
+    let node = router(&[]).await;
+    let summary = node.import_peers(&PeerExport::load(&path).unwrap());
+    assert_eq!(summary.num_candidates, 1);
+    node.register_peer_import(path.clone());
+    assert_eq!(node.reloads().names(), ["peer_import"]);
+
+    // Ensure a reload of the same export changes nothing.
+    assert_eq!(node.reloads().reload_all().get("peer_import"), Some(&ReloadStatus::Unchanged));
+
+    // Add peers to the export, and ensure the reload imports them.
+    let export = json!({ "version": 1, "candidate_peers": [{ "ip": "1.1.1.1:4130" }, { "ip": "2.2.2.2:4130" }],
+        "restricted_peers": [{ "ip": "3.3.3.3:4130", "remaining_secs": 60 }] });
+    std::fs::write(&path, export.to_string()).unwrap();
+    let summary = node.reloads().reload_all();
+    assert_eq!(
+        summary.get("peer_import"),
+        Some(&ReloadStatus::Changed {
+            changes: "imported 1 candidate peers and 1 restricted peers (1 entries dropped)".to_string()
+        })
+    );
+    assert_eq!(node.candidate_peers(), HashSet::from([ip("1.1.1.1:4130"), ip("2.2.2.2:4130")]));
+    assert!(node.is_restricted(&ip("3.3.3.3:4130")));
+
+    // Ensure a malformed export fails to reload, and imports nothing.
+    std::fs::write(&path, r#"{ "version": 1, "candidate_peers": [{ "ip": "4.4.4.4:4130" }"#).unwrap();
+    let summary = node.reloads().reload_all();
+    assert!(matches!(summary.get("peer_import"), Some(ReloadStatus::Failed { .. })));
+    assert_eq!(node.number_of_candidate_peers(), 2);
+
+    let _ = std::fs::remove_file(path);
+}
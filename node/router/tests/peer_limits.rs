@@ -0,0 +1,104 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+use common::*;
+
+use snarkos_node_router::{
+    PeerLimits,
+    Router,
+    messages::{Features, NodeType},
+};
+use snarkos_node_tcp::{HandshakeLimits, P2P};
+use snarkvm::prelude::MainnetV0 as CurrentNetwork;
+
+use anyhow::Result;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+/// Initializes a router of the given type with the given peer limits.
+async fn router(node_type: NodeType, peer_limits: PeerLimits, strict_config: bool) -> Result<Router<CurrentNetwork>> {
+    Router::new(
+        SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 0),
+        node_type,
+        sample_account(),
+        &[],
+        peer_limits,
+        strict_config,
+        false,
+        true,
+        true,
+        Default::default(),
+        Features::NONE,
+        Default::default(),
+        None,
+    )
+    .await
+}
+
+#[tokio::test]
+async fn test_mismatched_peer_limits() {
+    // A prover with a validator's configuration starts with a warning, unless the configuration is strict.
+    let limits = PeerLimits::new(NodeType::Prover, Some(200));
+    let router = router(NodeType::Prover, limits.clone(), false).await.unwrap();
+    assert_eq!(router.peer_limits().maximum(), 200);
+    let error = router(NodeType::Prover, limits, true).await.unwrap_err();
+    assert!(error.to_string().contains("--strict-config"), "{error}");
+
+    // A validator without room for the committee refuses to start, if the configuration is strict.
+    let limits = PeerLimits::new(NodeType::Validator, Some(30)).with_committee_size(40);
+    assert!(router(NodeType::Validator, limits.clone(), false).await.is_ok());
+    assert!(router(NodeType::Validator, limits, true).await.is_err());
+
+    // The default limits start, even if the configuration is strict.
+    for node_type in [NodeType::Client, NodeType::Prover, NodeType::Validator] {
+        assert!(router(node_type, PeerLimits::new(node_type, None), true).await.is_ok());
+    }
+
+    // The peer limits must be derived from the node type of the router.
+    assert!(router(NodeType::Client, PeerLimits::new(NodeType::Prover, None), false).await.is_err());
+}
+
+#[tokio::test]
+async fn test_heartbeat_targets_per_node_type() {
+    let client = router(NodeType::Client, PeerLimits::new(NodeType::Client, None), false).await.unwrap();
+    let prover = router(NodeType::Prover, PeerLimits::new(NodeType::Prover, None), false).await.unwrap();
+    let validator = router(NodeType::Validator, PeerLimits::new(NodeType::Validator, None), false).await.unwrap();
+
+    // Ensure the connection limit of the TCP stack matches the heartbeat targets.
+    for node in [&client, &prover, &validator] {
+        assert_eq!(node.tcp().config().max_connections as usize, node.peer_limits().maximum());
+    }
+    // Ensure the targets differ by node type.
+    assert_eq!(client.peer_limits().median(), 10);
+    assert_eq!(client.peer_limits().maximum_provers(), 5);
+    assert_eq!(prover.peer_limits().maximum_provers(), 0);
+    assert_eq!(validator.peer_limits().median(), 100);
+    assert_eq!(validator.peer_limits().maximum_provers(), 50);
+}
+
+#[tokio::test]
+async fn test_handshake_limits_reach_the_tcp_stack() {
+    // The default peer limits carry the default handshake limits.
+    let default = router(NodeType::Client, PeerLimits::new(NodeType::Client, None), false).await.unwrap();
+    assert_eq!(default.tcp().handshake_limits(), HandshakeLimits::default());
+    assert_eq!(default.tcp().config().handshake_limits, HandshakeLimits::default());
+
+    // Ensure the configured handshake limits are applied by the TCP stack of the router.
+    let limits = HandshakeLimits { max_concurrent: 4, slot_timeout_ms: 50 };
+    let peer_limits = PeerLimits::new(NodeType::Client, None).with_handshake_limits(limits);
+    let configured = router(NodeType::Client, peer_limits, false).await.unwrap();
+    assert_eq!(configured.peer_limits().handshake_limits(), limits);
+    assert_eq!(configured.tcp().handshake_limits(), limits);
+}
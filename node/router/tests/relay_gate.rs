@@ -0,0 +1,71 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+use common::*;
+
+use snarkos_node_router::{
+    Outbound,
+    messages::{Message, UnconfirmedTransaction},
+};
+use snarkos_node_tcp::{
+    P2P,
+    protocols::{Handshake, Reading, Writing},
+};
+use snarkvm::prelude::MainnetV0 as CurrentNetwork;
+
+use core::time::Duration;
+use deadline::deadline;
+
+#[tokio::test]
+async fn test_relay_gate() {
+    // Create 2 clients, where the sender gates its gossip beyond 10 blocks behind.
+    let sender = client(0, 2).await;
+    let receiver = client(0, 2).await;
+    sender.relay_gate().set_threshold(10);
+    for node in [&sender, &receiver] {
+        node.enable_handshake().await;
+        node.enable_reading().await;
+        node.enable_writing().await;
+        node.tcp().enable_listener().await.unwrap();
+    }
+
+    // Connect the sender to the receiver.
+    sender.connect(receiver.local_ip());
+    let (sender_, receiver_) = (sender.clone(), receiver.clone());
+    deadline!(Duration::from_secs(5), move || sender_.number_of_connected_peers() == 1
+        && receiver_.number_of_connected_peers() == 1);
+
+    // Prepare an unconfirmed transaction.
+    let block = sample_genesis_block::<CurrentNetwork>();
+    let transaction = block.transactions().iter().next().unwrap().transaction().clone();
+    let message = Message::UnconfirmedTransaction(UnconfirmedTransaction::from(transaction));
+
+    // Ensure the sender does not propagate the transaction, while it is significantly behind.
+    sender.set_num_blocks_behind(1_000);
+    sender.propagate(message.clone(), &[]);
+    assert!(sender.relay_gate().is_engaged());
+    assert_eq!(sender.relay_gate().num_suppressed(), 1);
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    assert_eq!(receiver.num_unconfirmed_transactions(), 0);
+
+    // Ensure the sender resumes propagating the transaction, once it caught up.
+    sender.set_num_blocks_behind(10);
+    sender.propagate(message, &[]);
+    assert!(!sender.relay_gate().is_engaged());
+    assert_eq!(sender.relay_gate().num_suppressed(), 1);
+    let receiver_ = receiver.clone();
+    deadline!(Duration::from_secs(5), move || receiver_.num_unconfirmed_transactions() == 1);
+}
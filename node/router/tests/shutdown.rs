@@ -0,0 +1,86 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+use common::*;
+
+use snarkos_account::Account;
+use snarkos_node_router::{
+    Heartbeat,
+    Outbound,
+    PeerEvent,
+    Router,
+    messages::{DisconnectReason, NodeType},
+};
+use snarkos_node_tcp::{
+    P2P,
+    protocols::{Disconnect, Handshake, Reading, Writing},
+};
+use snarkvm::{prelude::MainnetV0 as CurrentNetwork, utilities::TestRng};
+
+use core::time::Duration;
+use deadline::deadline;
+use std::time::Instant;
+
+#[tokio::test]
+async fn test_shutdown_notification() {
+    // Create 2 routers, with distinct accounts.
+    let rng = &mut TestRng::default();
+    let node0 = validator_with_account(0, 2, Account::new(rng).unwrap()).await;
+    let node1 = validator_with_account(0, 2, Account::new(rng).unwrap()).await;
+    for node in [&node0, &node1] {
+        node.enable_handshake().await;
+        node.enable_reading().await;
+        node.enable_writing().await;
+        node.enable_disconnect().await;
+        node.tcp().enable_listener().await.unwrap();
+    }
+    let (node0_ip, node1_ip) = (node0.local_ip(), node1.local_ip());
+    let mut events = node0.subscribe_peer_events();
+
+    // Connect node1 to node0.
+    assert!(node1.connect(node0_ip).unwrap().await.unwrap());
+    let event = tokio::time::timeout(Duration::from_secs(5), events.recv()).await.unwrap().unwrap();
+    assert!(matches!(event, PeerEvent::Connected { node_type: NodeType::Validator, .. }));
+    let node1_ = node1.clone();
+    deadline!(Duration::from_secs(5), move || node1_.is_connected(&node0_ip));
+
+    // Notify node0 that node1 is shutting down.
+    let notifications = node1.notify_shutdown();
+    assert_eq!(notifications.len(), 1);
+    node1.await_shutdown_notifications(notifications).await;
+
+    // Ensure node0 sees the reason, and disconnects from node1 without restricting it.
+    let event = tokio::time::timeout(Duration::from_secs(5), events.recv()).await.unwrap().unwrap();
+    assert_eq!(event, PeerEvent::Disconnected { peer_ip: node1_ip, reason: Some(DisconnectReason::ShuttingDown) });
+    assert!(!node0.is_connected(&node1_ip));
+    assert!(!node0.is_restricted(&node1_ip));
+
+    // Ensure node1 is a candidate peer right away, but is not re-dialed during the cooldown.
+    assert!(node0.candidate_peers().contains(&node1_ip));
+    assert!(!node0.dialable_candidate_peers().contains(&node1_ip));
+    assert!(node0.connect(node1_ip).is_none());
+
+    // Ensure the cooldown lasts no longer than the shutdown cooldown.
+    let cooldown = Duration::from_secs(Router::<CurrentNetwork>::SHUTDOWN_COOLDOWN_IN_SECS);
+    assert!(!node0.get_candidate_peer(&node1_ip).unwrap().is_cooling_down(Instant::now() + cooldown));
+
+    // Ensure node0 re-dials node1 once the cooldown has elapsed.
+    node0.clear_candidate_cooldowns();
+    assert!(node0.dialable_candidate_peers().contains(&node1_ip));
+    node0.handle_connected_peers();
+    let node0_ = node0.clone();
+    deadline!(Duration::from_secs(5), move || node0_.is_connected(&node1_ip));
+}
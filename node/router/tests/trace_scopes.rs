@@ -0,0 +1,137 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+use common::*;
+
+use snarkos_account::Account;
+use snarkos_node_router::{
+    Outbound,
+    TraceLevel,
+    TraceTarget,
+    messages::{Message, PeerRequest},
+    set_trace_filter_reload,
+};
+use snarkos_node_tcp::{
+    P2P,
+    protocols::{Disconnect, Handshake, Reading, Writing},
+};
+use snarkvm::utilities::TestRng;
+
+use core::time::Duration;
+use deadline::deadline;
+use parking_lot::Mutex;
+use std::{fmt, sync::Arc};
+use tracing::{
+    Event,
+    Subscriber,
+    field::{Field, Visit},
+};
+use tracing_subscriber::{
+    EnvFilter,
+    layer::{Context, Layer, SubscriberExt},
+    reload,
+    util::SubscriberInitExt,
+};
+
+/// A layer capturing the messages of the log events it admits.
+#[derive(Clone, Default)]
+struct CapturedEvents(Arc<Mutex<Vec<String>>>);
+
+impl CapturedEvents {
+    /// Returns the number of captured messages that contain the given text.
+    fn count(&self, text: &str) -> usize {
+        self.0.lock().iter().filter(|message| message.contains(text)).count()
+    }
+}
+
+impl<S: Subscriber> Layer<S> for CapturedEvents {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        self.0.lock().push(visitor.0);
+    }
+}
+
+/// Returns the filter of the captured events, at the `info` verbosity, with the directives of the given levels.
+fn info_filter(scope_levels: &[TraceLevel]) -> EnvFilter {
+    let filter = EnvFilter::new("info");
+    scope_levels.iter().fold(filter, |filter, level| filter.add_directive(level.directive().parse().unwrap()))
+}
+
+/// A visitor recording the message of a log event.
+#[derive(Default)]
+struct MessageVisitor(String);
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.0 = format!("{value:?}");
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_peer_trace_scope() {
+    // Capture the log events at the `info` verbosity, through a filter that is reloaded as the trace scopes change.
+    let events = CapturedEvents::default();
+    let (filter, handle) = reload::Layer::new(info_filter(&[]));
+    let _guard = tracing_subscriber::registry().with(events.clone().with_filter(filter)).set_default();
+    set_trace_filter_reload(move |levels| handle.reload(info_filter(levels)).unwrap());
+
+    // Create 3 routers, with distinct accounts.
+    let rng = &mut TestRng::default();
+    let node0 = validator_with_account(0, 3, Account::new(rng).unwrap()).await;
+    let node1 = validator_with_account(0, 3, Account::new(rng).unwrap()).await;
+    let node2 = validator_with_account(0, 3, Account::new(rng).unwrap()).await;
+    for node in [&node0, &node1, &node2] {
+        node.enable_handshake().await;
+        node.enable_reading().await;
+        node.enable_writing().await;
+        node.enable_disconnect().await;
+        node.tcp().enable_listener().await.unwrap();
+    }
+    let (node0_ip, node1_ip, node2_ip) = (node0.local_ip(), node1.local_ip(), node2.local_ip());
+    let received_from_node1 = format!("Received 'PeerRequest' from '{node1_ip}'");
+    let received_from_node2 = format!("Received 'PeerRequest' from '{node2_ip}'");
+
+    // Connect node1 and node2 to node0.
+    assert!(node1.connect(node0_ip).unwrap().await.unwrap());
+    assert!(node2.connect(node0_ip).unwrap().await.unwrap());
+    let node0_ = node0.clone();
+    deadline!(Duration::from_secs(5), move || node0_.is_connected(&node1_ip) && node0_.is_connected(&node2_ip));
+
+    // Elevate the logging of node1 on node0.
+    let duration = Duration::from_secs(2);
+    let scope = node0.trace_scopes().insert(TraceTarget::Peer(node1_ip), TraceLevel::Trace, duration).unwrap();
+    assert_eq!(node0.trace_scopes().active().iter().map(|scope| scope.id).collect::<Vec<_>>(), vec![scope.id]);
+
+    // Send a message from both peers to node0.
+    node2.send(node0_ip, Message::PeerRequest(PeerRequest));
+    node1.send(node0_ip, Message::PeerRequest(PeerRequest));
+
+    // Ensure the existing `trace` event of the message of node1 is elevated, and the one of node2 is not.
+    let (events_, received) = (events.clone(), received_from_node1.clone());
+    deadline!(Duration::from_secs(1), move || events_.count(&received) == 1);
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(events.count(&received_from_node2), 0);
+
+    // Ensure the scope expires on schedule.
+    tokio::time::sleep(duration).await;
+    assert!(node0.trace_scopes().active().is_empty());
+    node1.send(node0_ip, Message::PeerRequest(PeerRequest));
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(events.count(&received_from_node1), 1);
+}
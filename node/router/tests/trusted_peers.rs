@@ -0,0 +1,54 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+use common::*;
+
+use snarkos_node_router::Heartbeat;
+use snarkos_node_tcp::{P2P, protocols::Handshake};
+
+use core::time::Duration;
+use deadline::deadline;
+
+#[tokio::test]
+async fn test_trusted_peers_at_runtime() {
+    // Create two routers, without trusted peers.
+    let node0 = client(0, 2).await;
+    node0.enable_handshake().await;
+    node0.tcp().enable_listener().await.unwrap();
+    let addr0 = node0.local_ip();
+
+    let node1 = client(0, 2).await;
+    node1.enable_handshake().await;
+    node1.tcp().enable_listener().await.unwrap();
+    assert!(node1.trusted_peers().is_empty());
+
+    // Trust the first router at runtime.
+    assert!(node1.insert_trusted_peer(addr0));
+    assert!(!node1.insert_trusted_peer(addr0));
+    assert!(node1.is_trusted(&addr0));
+
+    // Ensure the heartbeat connects to the new trusted peer.
+    node1.handle_trusted_peers();
+    let node1_ = node1.clone();
+    deadline!(Duration::from_secs(5), move || node1_.is_connected(&addr0));
+
+    // Ensure the removed trusted peer remains connected.
+    assert!(node1.remove_trusted_peer(&addr0));
+    assert!(!node1.remove_trusted_peer(&addr0));
+    assert!(!node1.is_trusted(&addr0));
+    assert!(node1.trusted_peers().is_empty());
+    assert!(node1.is_connected(&addr0));
+}
@@ -0,0 +1,92 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkos_node_router::{AccountStatus, messages::NodeType};
+use snarkvm::prelude::{
+    Address,
+    Identifier,
+    Ledger,
+    Literal,
+    Network,
+    Plaintext,
+    ProgramID,
+    Value,
+    store::ConsensusStorage,
+};
+
+use anyhow::{Result, bail};
+use std::str::FromStr;
+
+/// The interval in seconds between checks of whether the node has synced, to re-evaluate the account status.
+pub const ACCOUNT_RECHECK_INTERVAL_IN_SECS: u64 = 30;
+
+/// Returns the status of the given address, as observed in the given (local) ledger.
+pub fn account_status<N: Network, C: ConsensusStorage<N>>(
+    ledger: &Ledger<N, C>,
+    address: Address<N>,
+    is_synced: bool,
+) -> Result<AccountStatus> {
+    // Prepare the lookup of the address in the `credits.aleo` mappings.
+    let credits = ProgramID::from_str("credits.aleo")?;
+    let key = Plaintext::from(Literal::Address(address));
+    let get_value = |mapping: &str| -> Result<Option<Value<N>>> {
+        ledger.vm().finalize_store().get_value_confirmed(credits, Identifier::from_str(mapping)?, &key)
+    };
+
+    // Determine if the address has a non-zero public balance.
+    let has_public_balance = match get_value("account")? {
+        Some(Value::Plaintext(Plaintext::Literal(Literal::U64(amount), _))) => *amount > 0,
+        _ => false,
+    };
+
+    Ok(AccountStatus::new(
+        ledger.latest_height(),
+        is_synced,
+        ledger.latest_committee()?.is_committee_member(address),
+        get_value("bonded")?.is_some(),
+        has_public_balance,
+    ))
+}
+
+/// Logs the account status summary, and checks that a validator has a bond or stake.
+///
+/// If the validator has no bond or stake, a warning is logged, unless `strict` is set and the ledger has
+/// synced, in which case an error is returned. Until the ledger has synced, the check is deferred,
+/// as the local ledger may not yet include the bond.
+pub fn check_account_status<N: Network>(
+    node_type: NodeType,
+    address: Address<N>,
+    status: &AccountStatus,
+    strict: bool,
+) -> Result<()> {
+    info!("{}", status.summary(node_type, address));
+
+    // Ensure the validator has a bond or stake.
+    if node_type.is_validator() && !status.is_staked() {
+        match (status.is_synced(), strict) {
+            (true, true) => bail!(
+                "Validator '{address}' has no bond or stake on {} - ensure the private key is for this network",
+                N::NAME
+            ),
+            (true, false) => warn!("Validator '{address}' has no bond or stake on {}", N::NAME),
+            (false, _) => warn!(
+                "Validator '{address}' has no bond or stake on {} as of block {} (re-checking once synced)",
+                N::NAME,
+                status.height()
+            ),
+        }
+    }
+    Ok(())
+}
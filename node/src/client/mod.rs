@@ -15,20 +15,30 @@
 
 mod router;
 
-use crate::traits::NodeInterface;
+use crate::{
+    EpochHashCache,
+    traits::{NodeInterface, NodeLifecycle},
+};
 use snarkos_account::Account;
-use snarkos_node_bft::ledger_service::CoreLedgerService;
-use snarkos_node_rest::Rest;
+use snarkos_node_bft::ledger_service::{CoreLedgerService, WriteVerifier};
+use snarkos_node_rest::{LogFileStatus, NodeConfig, Rest};
 use snarkos_node_router::{
+    AccountStatus,
+    Experiments,
     Heartbeat,
     Inbound,
+    MemoryBudget,
     Outbound,
+    PeerLimits,
+    ReloadRegistry,
     Router,
     Routing,
-    messages::{Message, NodeType, UnconfirmedSolution},
+    ban_list_path,
+    messages::{Features, Message, NodeType, UnconfirmedSolution},
 };
-use snarkos_node_sync::{BlockSync, BlockSyncMode};
+use snarkos_node_sync::{BlockSync, BlockSyncMode, MAX_SYNC_JOURNAL_BYTES, SyncJournal, sync_journal_path};
 use snarkos_node_tcp::{
+    HandshakeLimits,
     P2P,
     protocols::{Disconnect, Handshake, OnConnect, Reading, Writing},
 };
@@ -45,9 +55,10 @@ use snarkvm::{
 use aleo_std::StorageMode;
 use anyhow::Result;
 use core::future::Future;
-use parking_lot::Mutex;
+use parking_lot::{Mutex, RwLock};
 use std::{
     net::SocketAddr,
+    path::PathBuf,
     sync::{Arc, atomic::AtomicBool},
 };
 use tokio::task::JoinHandle;
@@ -63,10 +74,16 @@ pub struct Client<N: Network, C: ConsensusStorage<N>> {
     rest: Option<Rest<N, C, Self>>,
     /// The sync module.
     sync: Arc<BlockSync<N>>,
+    /// The verifier of the critical storage writes.
+    write_verifier: Arc<WriteVerifier>,
     /// The genesis block.
     genesis: Block<N>,
     /// The puzzle.
     puzzle: Puzzle<N>,
+    /// The status of the node account, as observed in the local ledger.
+    account_status: Arc<RwLock<Option<AccountStatus>>>,
+    /// The epoch hash of the latest block, served in the puzzle responses.
+    epoch_hash: Arc<EpochHashCache<N>>,
     /// The spawned handles.
     handles: Arc<Mutex<Vec<JoinHandle<()>>>>,
     /// The shutdown signal.
@@ -79,12 +96,23 @@ impl<N: Network, C: ConsensusStorage<N>> Client<N, C> {
         node_ip: SocketAddr,
         rest_ip: Option<SocketAddr>,
         rest_rps: u32,
+        broadcast_journal: Option<PathBuf>,
+        recent_blocks: usize,
+        log_file: Option<LogFileStatus>,
         account: Account<N>,
         trusted_peers: &[SocketAddr],
+        max_peers: Option<u16>,
+        handshake_limits: HandshakeLimits,
+        max_pool_memory: Option<u64>,
+        strict_config: bool,
         genesis: Block<N>,
         cdn: Option<String>,
         storage_mode: StorageMode,
+        verify_writes: bool,
+        max_reorg_depth: u32,
         rotate_external_peers: bool,
+        early_block_announce: bool,
+        experiments: Experiments,
         shutdown: Arc<AtomicBool>,
     ) -> Result<Self> {
         // Initialize the signal handler.
@@ -104,12 +132,32 @@ impl<N: Network, C: ConsensusStorage<N>> Client<N, C> {
             }
         }
 
+        // Initialize the verifier of the critical storage writes.
+        let write_verifier = Arc::new(WriteVerifier::new(verify_writes));
         // Initialize the ledger service.
-        let ledger_service = Arc::new(CoreLedgerService::<N, C>::new(ledger.clone(), shutdown.clone()));
-        // Initialize the sync module.
-        let sync = BlockSync::new(BlockSyncMode::Router, ledger_service.clone());
+        let ledger_service = Arc::new(
+            CoreLedgerService::<N, C>::new(ledger.clone(), shutdown.clone())
+                .with_write_verifier(write_verifier.clone()),
+        );
+        // Initialize the sync module, with the journal of the block responses staged before a restart,
+        // and rejecting the peers that present a history that forks deeper than the maximum reorg depth.
+        let mut sync =
+            BlockSync::new(BlockSyncMode::Router, ledger_service.clone()).with_max_reorg_depth(max_reorg_depth);
+        match SyncJournal::open(sync_journal_path(N::ID, &storage_mode), MAX_SYNC_JOURNAL_BYTES) {
+            Ok(journal) => sync = sync.with_journal(journal),
+            Err(error) => warn!("Unable to open the sync journal - {error}"),
+        }
+        // Apply the staged block responses that extend the ledger, before any blocks are requested.
+        sync.resume_from_journal();
         // Determine if the client should allow external peers.
         let allow_external_peers = true;
+        // Determine if the client should request the blocks announced by its validators, ahead of the block gossip.
+        // The client always acknowledges the solutions submitted by the provers that negotiated it.
+        let features = match early_block_announce {
+            true => Features::BLOCK_ANNOUNCE,
+            false => Features::NONE,
+        }
+        .with(Features::SOLUTION_ACK);
 
         // Initialize the node router.
         let router = Router::new(
@@ -117,34 +165,74 @@ impl<N: Network, C: ConsensusStorage<N>> Client<N, C> {
             NodeType::Client,
             account,
             trusted_peers,
-            Self::MAXIMUM_NUMBER_OF_PEERS as u16,
+            PeerLimits::new(NodeType::Client, max_peers).with_handshake_limits(handshake_limits),
+            strict_config,
             rotate_external_peers,
             allow_external_peers,
             matches!(storage_mode, StorageMode::Development(_)),
+            Arc::new(MemoryBudget::new(max_pool_memory)),
+            features,
+            experiments,
+            Some(ban_list_path(N::ID, &storage_mode)),
         )
         .await?;
+        // Warn if the limit of open files is too low for the connections.
+        crate::check_fd_limit(router.max_connected_peers());
+        // Determine the effective configuration of the node.
+        let config = NodeConfig::new(
+            NodeType::Client,
+            router.address(),
+            Some(&router),
+            None,
+            &storage_mode,
+            log_file,
+            &crate::compiled_features(),
+        );
         // Initialize the node.
         let mut node = Self {
             ledger: ledger.clone(),
             router,
             rest: None,
             sync: Arc::new(sync),
+            write_verifier,
             genesis,
             puzzle: ledger.puzzle().clone(),
+            account_status: Default::default(),
+            epoch_hash: Default::default(),
             handles: Default::default(),
             shutdown,
         };
+        // Check the account against the local ledger.
+        node.initialize_account_check()?;
 
         // Initialize the REST server.
         if let Some(rest_ip) = rest_ip {
-            node.rest = Some(Rest::start(rest_ip, rest_rps, None, ledger.clone(), Arc::new(node.clone())).await?);
+            node.rest = Some(
+                Rest::start(
+                    rest_ip,
+                    rest_rps,
+                    None,
+                    ledger.clone(),
+                    Some(Arc::new(node.clone())),
+                    broadcast_journal,
+                    recent_blocks,
+                    config.clone(),
+                )
+                .await?,
+            );
         }
+        // Log the effective configuration of the node.
+        info!("Node configuration: {}", node.rest.as_ref().map_or(&config, |rest| rest.config()));
         // Initialize the routing.
         node.initialize_routing().await;
         // Initialize the sync module.
         node.initialize_sync();
         // Initialize the notification message loop.
         node.handles.lock().push(crate::start_notification_message_loop());
+        // Shut down the node if a critical task fails.
+        let mut supervisors = vec![node.router.supervisor().clone()];
+        supervisors.extend(node.rest.as_ref().map(|rest| rest.supervisor().clone()));
+        node.handle_critical_failures(supervisors);
         // Pass the node to the signal handler.
         let _ = signal_node.set(node.clone());
         // Return the node.
@@ -183,14 +271,50 @@ impl<N: Network, C: ConsensusStorage<N>> Client<N, C> {
         }));
     }
 
+    /// Logs the account status against the local ledger, and re-evaluates it once the node has synced.
+    fn initialize_account_check(&self) -> Result<()> {
+        // Check the account status against the local ledger.
+        let status = crate::account_status(&self.ledger, self.address(), false)?;
+        *self.account_status.write() = Some(status);
+        crate::check_account_status(self.node_type(), self.address(), &status, false)?;
+
+        // Re-evaluate the account status once the node has synced.
+        let self_ = self.clone();
+        self.spawn(async move {
+            loop {
+                tokio::time::sleep(std::time::Duration::from_secs(crate::ACCOUNT_RECHECK_INTERVAL_IN_SECS)).await;
+                // Wait until the node has synced.
+                if !self_.sync.is_block_synced() {
+                    continue;
+                }
+                match crate::account_status(&self_.ledger, self_.address(), true) {
+                    Ok(status) => {
+                        *self_.account_status.write() = Some(status);
+                        info!("{}", status.summary(self_.node_type(), self_.address()));
+                        break;
+                    }
+                    Err(error) => warn!("Failed to re-check the account status - {error}"),
+                }
+            }
+        });
+        Ok(())
+    }
+
     /// Spawns a task with the given future; it should only be used for long-running tasks.
     pub fn spawn<T: Future<Output = ()> + Send + 'static>(&self, future: T) {
         self.handles.lock().push(tokio::spawn(future));
     }
 }
 
+impl<N: Network, C: ConsensusStorage<N>> NodeInterface<N> for Client<N, C> {}
+
 #[async_trait]
-impl<N: Network, C: ConsensusStorage<N>> NodeInterface<N> for Client<N, C> {
+impl<N: Network, C: ConsensusStorage<N>> NodeLifecycle for Client<N, C> {
+    /// Returns the registry of the configuration items that can be reloaded at runtime.
+    fn reloads(&self) -> Option<&ReloadRegistry> {
+        Some(self.router().reloads())
+    }
+
     /// Shuts down the node.
     async fn shut_down(&self) {
         info!("Shutting down...");
@@ -199,10 +323,19 @@ impl<N: Network, C: ConsensusStorage<N>> NodeInterface<N> for Client<N, C> {
         trace!("Shutting down the node...");
         self.shutdown.store(true, std::sync::atomic::Ordering::Release);
 
+        // Shut down the REST server.
+        if let Some(rest) = &self.rest {
+            rest.shut_down().await;
+        }
+
         // Abort the tasks.
         trace!("Shutting down the validator...");
         self.handles.lock().iter().for_each(|handle| handle.abort());
 
+        // Notify the peers that the node is shutting down, before closing the connections.
+        trace!("Notifying the peers...");
+        self.router.await_shutdown_notifications(self.notify_shutdown()).await;
+
         // Shut down the router.
         self.router.shut_down().await;
 
@@ -14,9 +14,16 @@
 // limitations under the License.
 
 use super::*;
+use snarkos_node_bft::ledger_service::{LedgerReadError, read_blocks, retry_transient};
 use snarkos_node_router::{
+    RestrictionCause,
     Routing,
+    SyncProgress,
+    SyncSummary,
+    check_solution_status,
+    inbound_message_priority,
     messages::{
+        BlockAnnounce,
         BlockRequest,
         BlockResponse,
         DataBlocks,
@@ -26,16 +33,18 @@ use snarkos_node_router::{
         Ping,
         Pong,
         PuzzleResponse,
+        SolutionStatus,
         UnconfirmedTransaction,
     },
 };
-use snarkos_node_sync::communication_service::CommunicationService;
-use snarkos_node_tcp::{Connection, ConnectionSide, Tcp};
+use snarkos_node_sync::{ReorgDepthExceeded, communication_service::CommunicationService, locators::BlockLocators};
+use snarkos_node_tcp::{Connection, ConnectionSide, Tcp, protocols::MessagePriority};
 use snarkvm::{
     ledger::narwhal::Data,
     prelude::{Network, block::Transaction},
 };
 
+use anyhow::{Result, bail};
 use std::{io, net::SocketAddr, time::Duration};
 
 impl<N: Network, C: ConsensusStorage<N>> P2P for Client<N, C> {
@@ -104,8 +113,9 @@ impl<N: Network, C: ConsensusStorage<N>> Writing for Client<N, C> {
 
     /// Creates an [`Encoder`] used to write the outbound messages to the target stream.
     /// The `side` parameter indicates the connection side **from the node's perspective**.
-    fn codec(&self, _addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
-        Default::default()
+    fn codec(&self, addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+        // Note: The handshake has completed, so the frame size limit negotiated with the peer is known.
+        self.router().outbound_codec(addr)
     }
 }
 
@@ -116,8 +126,14 @@ impl<N: Network, C: ConsensusStorage<N>> Reading for Client<N, C> {
 
     /// Creates a [`Decoder`] used to interpret messages from the network.
     /// The `side` param indicates the connection side **from the node's perspective**.
-    fn codec(&self, _peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
-        Default::default()
+    fn codec(&self, peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+        // Note: The handshake has completed, so the frame size limit negotiated with the peer is known.
+        self.router().inbound_codec(peer_addr)
+    }
+
+    /// Classifies a message received from the network, so that control messages skip the queued bulk data.
+    fn message_priority(&self, message: &Self::Message) -> MessagePriority {
+        inbound_message_priority(message)
     }
 
     /// Processes a message received from the network.
@@ -180,7 +196,38 @@ impl<N: Network, C: ConsensusStorage<N>> CommunicationService for Client<N, C> {
 }
 
 #[async_trait]
-impl<N: Network, C: ConsensusStorage<N>> Routing<N> for Client<N, C> {}
+impl<N: Network, C: ConsensusStorage<N>> Routing<N> for Client<N, C> {
+    /// Forces an immediate block sync against the given connected peer, and returns a summary of the block requests.
+    async fn sync_from_peer(&self, peer_ip: SocketAddr) -> Result<SyncSummary> {
+        // Ensure the peer is connected.
+        if !self.router().is_connected(&peer_ip) {
+            bail!("Unable to sync from '{peer_ip}' - the peer is not connected")
+        }
+        // Request the missing blocks from the peer.
+        let summary = self.sync.sync_from_peer(self, peer_ip).await?;
+        // Send our latest block locators to the peer, to prompt a refresh of the block locator exchange.
+        match self.sync.get_block_locators() {
+            Ok(block_locators) => self.send_ping(peer_ip, Some(block_locators)),
+            Err(e) => error!("Failed to get block locators for '{peer_ip}' - {e}"),
+        }
+        Ok(summary)
+    }
+
+    /// Returns the status of the node account, as observed in the local ledger.
+    fn account_status(&self) -> Option<AccountStatus> {
+        *self.account_status.read()
+    }
+
+    /// Returns `true` if a read-back of a critical write detected that the storage of the node is corrupted.
+    fn is_storage_corrupted(&self) -> bool {
+        self.write_verifier.is_corrupted()
+    }
+
+    /// Returns the current block locators of the node.
+    fn block_locators(&self) -> Result<BlockLocators<N>> {
+        self.sync.get_block_locators()
+    }
+}
 
 impl<N: Network, C: ConsensusStorage<N>> Heartbeat<N> for Client<N, C> {}
 
@@ -195,21 +242,39 @@ impl<N: Network, C: ConsensusStorage<N>> Outbound<N> for Client<N, C> {
         self.sync.is_block_synced()
     }
 
-    /// Returns the number of blocks this node is behind the greatest peer height.
+    /// Returns the number of blocks this node is behind the median peer height.
     fn num_blocks_behind(&self) -> u32 {
-        self.sync.num_blocks_behind()
+        self.sync.num_blocks_behind_median()
+    }
+
+    /// Returns the progress of the block sync of this node.
+    fn sync_progress(&self) -> SyncProgress {
+        self.sync.progress()
     }
 }
 
 #[async_trait]
 impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Client<N, C> {
+    /// Requests the announced block right away, ahead of the block locators of the validator.
+    async fn block_announce(&self, peer_ip: SocketAddr, message: BlockAnnounce<N>) -> bool {
+        trace!("Received the announcement of block {} ({}) from '{peer_ip}'", message.height, message.hash);
+        // Note: The announcement is only a hint, so it is never treated as invalid.
+        self.sync.request_announced_block(self, peer_ip, message.height).await;
+        true
+    }
+
     /// Handles a `BlockRequest` message.
     fn block_request(&self, peer_ip: SocketAddr, message: BlockRequest) -> bool {
         let BlockRequest { start_height, end_height } = &message;
 
-        // Retrieve the blocks within the requested range.
-        let blocks = match self.ledger.get_blocks(*start_height..*end_height) {
+        // Retrieve the blocks within the requested range, reading them again on a transient failure.
+        let blocks = match retry_transient(|| read_blocks(&self.ledger, *start_height..*end_height)) {
             Ok(blocks) => Data::Object(DataBlocks(blocks)),
+            // If the ledger failed to read the blocks, the peer is not at fault, and may request them again.
+            Err(error) if LedgerReadError::is_unavailable(&error) => {
+                error!("Unable to serve blocks {start_height} to {end_height} to '{peer_ip}' - {error}");
+                return true;
+            }
             Err(error) => {
                 error!("Failed to retrieve blocks {start_height} to {end_height} from the ledger - {error}");
                 return false;
@@ -240,6 +305,16 @@ impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Client<N, C> {
             if let Some(block_locators) = message.block_locators {
                 // Check the block locators are valid, and update the peer in the sync pool.
                 if let Err(error) = self.sync.update_peer_locators(peer_ip, block_locators) {
+                    // Restrict a peer that presents an alternative history of the finalized blocks.
+                    if let Some(rejection) = ReorgDepthExceeded::of(&error) {
+                        error!(
+                            "Rejected the alternative history of peer '{peer_ip}' - {rejection}. Blocks are final once \
+                             certified by the committee, so the peer is either malicious or on another network"
+                        );
+                        self.router()
+                            .insert_restricted_peer(peer_ip, RestrictionCause::ReorgDepthExceeded(rejection.depth));
+                        return false;
+                    }
                     warn!("Peer '{peer_ip}' sent invalid block locators: {error}");
                     return false;
                 }
@@ -271,19 +346,18 @@ impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Client<N, C> {
         true
     }
 
-    /// Retrieves the latest epoch hash and latest block header, and returns the puzzle response to the peer.
+    /// Returns the puzzle response to the peer, with the latest block header and the epoch hash anchored at its height.
     fn puzzle_request(&self, peer_ip: SocketAddr) -> bool {
-        // Retrieve the latest epoch hash.
-        let epoch_hash = match self.ledger.latest_epoch_hash() {
-            Ok(epoch_hash) => epoch_hash,
+        // Retrieve the latest block header and its epoch hash.
+        let (block_header, (_, epoch_hash)) = match self.epoch_hash.latest(&self.ledger) {
+            Ok(latest) => latest,
             Err(error) => {
                 error!("Failed to prepare a puzzle request for '{peer_ip}': {error}");
                 return false;
             }
         };
-        // Retrieve the latest block header.
-        let block_header = Data::Object(self.ledger.latest_header());
         // Send the `PuzzleResponse` message to the peer.
+        let block_header = Data::Object(block_header);
         Outbound::send(self, peer_ip, Message::PuzzleResponse(PuzzleResponse { epoch_hash, block_header }));
         true
     }
@@ -301,28 +375,32 @@ impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Client<N, C> {
         serialized: UnconfirmedSolution<N>,
         solution: Solution<N>,
     ) -> bool {
-        // Retrieve the latest epoch hash.
-        if let Ok(epoch_hash) = self.ledger.latest_epoch_hash() {
+        // Retrieve the latest block header and its epoch hash.
+        if let Ok((header, (height, epoch_hash))) = self.epoch_hash.latest(&self.ledger) {
             // Retrieve the latest proof target.
-            let proof_target = self.ledger.latest_block().header().proof_target();
+            let proof_target = header.proof_target();
             // Ensure that the solution is valid for the given epoch.
             let puzzle = self.puzzle.clone();
-            let is_valid =
-                tokio::task::spawn_blocking(move || puzzle.check_solution(&solution, epoch_hash, proof_target)).await;
+            let status = tokio::task::spawn_blocking(move || {
+                check_solution_status(&puzzle, &solution, epoch_hash, proof_target)
+            })
+            .await;
 
-            match is_valid {
+            match status {
                 // If the solution is valid, propagate the `UnconfirmedSolution`.
-                Ok(Ok(())) => {
+                Ok(SolutionStatus::Accepted) => {
+                    self.acknowledge_solution(peer_ip, serialized.solution_id, SolutionStatus::Accepted);
                     let message = Message::UnconfirmedSolution(serialized);
                     // Propagate the "UnconfirmedSolution".
                     self.propagate(message, &[peer_ip]);
                 }
-                Ok(Err(_)) => {
-                    trace!("Invalid solution '{}' for the proof target.", solution.id())
+                Ok(status) => {
+                    trace!("Invalid solution '{}' for the proof target of block {height} ({status})", solution.id());
+                    self.acknowledge_solution(peer_ip, serialized.solution_id, status);
                 }
                 // If error occurs after the first 10 blocks of the epoch, log it as a warning, otherwise ignore.
                 Err(error) => {
-                    if self.ledger.latest_height() % N::NUM_BLOCKS_PER_EPOCH > 10 {
+                    if height % N::NUM_BLOCKS_PER_EPOCH > 10 {
                         warn!("Failed to verify the solution - {error}")
                     }
                 }
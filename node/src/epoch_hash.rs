@@ -0,0 +1,200 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{Ledger, Network, block::Header, store::ConsensusStorage};
+
+use anyhow::Result;
+use parking_lot::RwLock;
+
+/// The epoch hash (i.e. the puzzle challenge) of the latest block, cached with the block height it was computed for.
+///
+/// The epoch hash only changes on epoch boundaries, so it is reused for the later heights of the same epoch.
+/// As the chain may be rewound (e.g. on a rollback in development mode), the cached height is validated against
+/// the tip on every read, and the epoch hash is recomputed if the tip moved backwards. Reading from a ledger also
+/// checks that the cached block is still in it, and invalidates the cache if the ledger was rewound through it.
+pub struct EpochHashCache<N: Network> {
+    /// The block height, the hash of the block at that height, and the epoch hash computed for it.
+    cached: RwLock<Option<(u32, N::BlockHash, N::BlockHash)>>,
+}
+
+impl<N: Network> Default for EpochHashCache<N> {
+    fn default() -> Self {
+        Self { cached: Default::default() }
+    }
+}
+
+impl<N: Network> EpochHashCache<N> {
+    /// Returns the epoch hash for the given tip (i.e. its height and block hash), along with the height it is
+    /// anchored at. If the cached epoch hash is stale, it is recomputed with the given function.
+    pub fn get(
+        &self,
+        (tip_height, tip_hash): (u32, N::BlockHash),
+        compute: impl FnOnce(u32) -> Result<N::BlockHash>,
+    ) -> Result<(u32, N::BlockHash)> {
+        // Reuse the cached epoch hash, if it was computed for a height at or below the tip, in the same epoch.
+        if let Some((height, _, epoch_hash)) = *self.cached.read() {
+            if height <= tip_height && height / N::NUM_BLOCKS_PER_EPOCH == tip_height / N::NUM_BLOCKS_PER_EPOCH {
+                return Ok((tip_height, epoch_hash));
+            }
+        }
+        // Otherwise, recompute the epoch hash for the tip.
+        let epoch_hash = compute(tip_height)?;
+        *self.cached.write() = Some((tip_height, tip_hash, epoch_hash));
+        Ok((tip_height, epoch_hash))
+    }
+
+    /// Returns the latest block header of the given ledger, along with the epoch hash and the height it is
+    /// anchored at.
+    pub fn latest<C: ConsensusStorage<N>>(&self, ledger: &Ledger<N, C>) -> Result<(Header<N>, (u32, N::BlockHash))> {
+        // Invalidate the cached epoch hash, if the ledger was rewound through the block it was computed for.
+        let cached = self.cached.read().map(|(height, hash, _)| (height, hash));
+        if let Some((height, hash)) = cached {
+            if ledger.get_hash(height).ok() != Some(hash) {
+                self.invalidate();
+            }
+        }
+        let header = ledger.latest_header();
+        let tip = (header.height(), ledger.latest_hash());
+        let anchored = self.get(tip, |height| ledger.get_epoch_hash(height))?;
+        Ok((header, anchored))
+    }
+
+    /// Invalidates the cached epoch hash.
+    ///
+    /// Note: This is called by [`Self::latest`] once it finds that the ledger was rewound, as a rewound chain may
+    /// advance past the cached height on a different fork before the next read, which would otherwise reuse the
+    /// stale hash. Any code path that rewinds the chain in place may call it directly.
+    pub fn invalidate(&self) {
+        *self.cached.write() = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkos_node_bft_ledger_service::test_helpers::{sample_genesis_block, sample_ledger_from_genesis};
+    use snarkvm::prelude::{MainnetV0, TestRng};
+
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaChaRng;
+
+    type CurrentNetwork = MainnetV0;
+    type BlockHash = <CurrentNetwork as Network>::BlockHash;
+
+    const EPOCH: u32 = <CurrentNetwork as Network>::NUM_BLOCKS_PER_EPOCH;
+
+    /// Returns the epoch hash for the given height of the given chain, i.e. the hash of the block preceding its epoch.
+    fn epoch_hash(chain: &[BlockHash], height: u32) -> Result<BlockHash> {
+        let epoch_start = height / EPOCH * EPOCH;
+        Ok(chain[epoch_start.saturating_sub(1) as usize])
+    }
+
+    /// Returns the tip of the given chain at the given height.
+    fn tip(chain: &[BlockHash], height: u32) -> (u32, BlockHash) {
+        (height, chain[height as usize])
+    }
+
+    /// Returns a chain of blocks up to the given height.
+    fn sample_chain(height: u32, rng: &mut TestRng) -> Vec<BlockHash> {
+        (0..=height).map(|_| rng.gen()).collect()
+    }
+
+    #[test]
+    fn test_epoch_hash_is_cached_within_an_epoch() {
+        let rng = &mut TestRng::default();
+        let chain = sample_chain(EPOCH + 10, rng);
+        let cache = EpochHashCache::<CurrentNetwork>::default();
+
+        // The epoch hash is computed once per epoch.
+        let mut num_computations = 0;
+        let mut get = |tip_height| {
+            cache.get(tip(&chain, tip_height), |height| {
+                num_computations += 1;
+                epoch_hash(&chain, height)
+            })
+        };
+        assert_eq!(get(EPOCH - 2).unwrap(), (EPOCH - 2, chain[0]));
+        assert_eq!(get(EPOCH - 1).unwrap(), (EPOCH - 1, chain[0]));
+        assert_eq!(get(EPOCH).unwrap(), (EPOCH, chain[EPOCH as usize - 1]));
+        assert_eq!(get(EPOCH + 10).unwrap(), (EPOCH + 10, chain[EPOCH as usize - 1]));
+        assert_eq!(num_computations, 2);
+    }
+
+    #[test]
+    fn test_rollback_across_epoch_boundary() {
+        let rng = &mut TestRng::default();
+        let mut chain = sample_chain(EPOCH + 5, rng);
+        let cache = EpochHashCache::<CurrentNetwork>::default();
+
+        // Read the epoch hash past the epoch boundary.
+        let (_, original) = cache.get(tip(&chain, EPOCH + 5), |height| epoch_hash(&chain, height)).unwrap();
+        assert_eq!(original, chain[EPOCH as usize - 1]);
+
+        // Roll back below the epoch boundary, and ensure the next read detects that the tip moved backwards.
+        chain.truncate(EPOCH as usize - 5);
+        assert_eq!(
+            cache.get(tip(&chain, EPOCH - 6), |height| epoch_hash(&chain, height)).unwrap(),
+            (EPOCH - 6, chain[0])
+        );
+
+        // Advance past the epoch boundary on a different fork, and ensure the read reflects the new fork.
+        chain.extend(sample_chain(10, rng));
+        let (height, epoch_hash_after) =
+            cache.get(tip(&chain, EPOCH + 5), |height| epoch_hash(&chain, height)).unwrap();
+        assert_eq!((height, epoch_hash_after), (EPOCH + 5, chain[EPOCH as usize - 1]));
+        assert_ne!(epoch_hash_after, original);
+    }
+
+    #[test]
+    fn test_invalidate_on_rewind() {
+        let rng = &mut TestRng::default();
+        let mut chain = sample_chain(EPOCH + 5, rng);
+        let cache = EpochHashCache::<CurrentNetwork>::default();
+        let (_, original) = cache.get(tip(&chain, EPOCH + 5), |height| epoch_hash(&chain, height)).unwrap();
+
+        // Rewind below the epoch boundary, and advance past the cached height on a different fork, without a read.
+        chain.truncate(EPOCH as usize - 10);
+        cache.invalidate();
+        chain.extend(sample_chain(17, rng));
+
+        // Ensure the next read reflects the post-rollback chain.
+        let (height, epoch_hash_after) =
+            cache.get(tip(&chain, EPOCH + 7), |height| epoch_hash(&chain, height)).unwrap();
+        assert_eq!((height, epoch_hash_after), (EPOCH + 7, chain[EPOCH as usize - 1]));
+        assert_ne!(epoch_hash_after, original);
+    }
+
+    #[test]
+    fn test_latest_after_ledger_rollback() {
+        let rng = &mut ChaChaRng::seed_from_u64(1234567890u64);
+        let (private_key, genesis) = sample_genesis_block::<CurrentNetwork, _>(rng);
+
+        let original = sample_ledger_from_genesis(&private_key, &genesis, 3, rng);
+        // The same ledger, rolled back and advanced past its previous tip on a different fork.
+        let forked = sample_ledger_from_genesis(&private_key, &genesis, 5, rng);
+        assert_ne!(original.get_hash(3).unwrap(), forked.get_hash(3).unwrap());
+
+        let cache = EpochHashCache::<CurrentNetwork>::default();
+        let (header, (height, epoch_hash)) = cache.latest(&original).unwrap();
+        assert_eq!((header.height(), height), (3, 3));
+        assert_eq!(epoch_hash, original.get_epoch_hash(3).unwrap());
+
+        // Ensure the cache is invalidated, as the block it was computed for is no longer in the ledger.
+        let (header, (height, epoch_hash)) = cache.latest(&forked).unwrap();
+        assert_eq!((header.height(), height), (5, 5));
+        assert_eq!(epoch_hash, forked.get_epoch_hash(5).unwrap());
+        assert_eq!(*cache.cached.read(), Some((5, forked.get_hash(5).unwrap(), epoch_hash)));
+    }
+}
@@ -0,0 +1,62 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkos_node_consensus::{MemoryAccounting, MemoryPool, TaskFactory, TaskFuture, TaskKind, TaskSpawner};
+use snarkos_node_router::{MemoryBudget, MemoryConsumer, TASK_SHUTDOWN_TIMEOUT_IN_SECS, TaskPolicy, TaskSupervisor};
+
+use std::{sync::Arc, time::Duration};
+
+/// The spawner of the consensus tasks, which supervises them with the given supervisor.
+#[derive(Clone)]
+pub struct SupervisedTasks(pub TaskSupervisor);
+
+impl TaskSpawner for SupervisedTasks {
+    fn spawn_task(&self, name: &str, kind: TaskKind, factory: TaskFactory) {
+        let policy = match kind {
+            TaskKind::Auxiliary => TaskPolicy::Auxiliary,
+            TaskKind::Critical { max_restarts } => TaskPolicy::Restart { max_restarts },
+        };
+        self.0.spawn(name, policy, factory);
+    }
+
+    fn shut_down(&self) -> TaskFuture {
+        let supervisor = self.0.clone();
+        Box::pin(async move { supervisor.shut_down(Duration::from_secs(TASK_SHUTDOWN_TIMEOUT_IN_SECS)).await })
+    }
+}
+
+/// The accounting of the consensus memory in the given memory budget, which is shared with the router.
+#[derive(Clone)]
+pub struct SharedMemoryBudget(pub Arc<MemoryBudget>);
+
+impl SharedMemoryBudget {
+    /// Returns the consumer of the memory budget corresponding to the given structure of consensus.
+    const fn consumer(pool: MemoryPool) -> MemoryConsumer {
+        match pool {
+            MemoryPool::SeenTransmissions => MemoryConsumer::SeenTransmissions,
+            MemoryPool::TransmissionsQueue => MemoryConsumer::TransmissionsQueue,
+        }
+    }
+}
+
+impl MemoryAccounting for SharedMemoryBudget {
+    fn capacity(&self, pool: MemoryPool, nominal_capacity: usize) -> usize {
+        self.0.capacity(Self::consumer(pool), nominal_capacity)
+    }
+
+    fn set_usage(&self, pool: MemoryPool, bytes: u64) {
+        self.0.set_usage(Self::consumer(pool), bytes)
+    }
+}
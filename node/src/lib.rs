@@ -31,12 +31,24 @@ pub use snarkos_node_sync as sync;
 pub use snarkos_node_tcp as tcp;
 pub use snarkvm;
 
+mod account;
+pub use account::*;
+
 mod client;
 pub use client::*;
 
+mod epoch_hash;
+pub use epoch_hash::*;
+
+mod hooks;
+pub use hooks::*;
+
 mod prover;
 pub use prover::*;
 
+mod safe;
+pub use safe::*;
+
 mod validator;
 pub use validator::*;
 
@@ -48,6 +60,40 @@ pub use traits::*;
 
 use aleo_std::StorageMode;
 
+/// Returns the optional features the node was compiled with, as reported in the node configuration.
+pub fn compiled_features() -> Vec<&'static str> {
+    [("metrics", cfg!(feature = "metrics")), ("history", cfg!(feature = "history")), ("timer", cfg!(feature = "timer"))]
+        .into_iter()
+        .filter_map(|(feature, is_enabled)| is_enabled.then_some(feature))
+        .collect()
+}
+
+/// The number of file descriptors set aside for the storage (i.e. the files of RocksDB) and the REST server.
+pub const STORAGE_FD_ALLOWANCE: u64 = 1024;
+
+/// Warns if the limit of open files of the process is too low for the given maximum number of connections,
+/// along with the files of the storage, as the connections would then fail once the limit is reached.
+#[cfg(target_os = "linux")]
+pub fn check_fd_limit(max_connections: usize) {
+    use nix::sys::resource::{Resource, getrlimit};
+
+    let Ok((limit, _)) = getrlimit(Resource::RLIMIT_NOFILE) else {
+        return;
+    };
+    let required = max_connections as u64 + STORAGE_FD_ALLOWANCE;
+    if limit < required {
+        warn!(
+            "The limit of open files ({limit}) is too low for {max_connections} connections and the storage - \
+             raise it to at least {required} (e.g. `ulimit -n 65536`, or `LimitNOFILE` in the systemd unit)"
+        );
+    }
+}
+
+/// Warns if the limit of open files of the process is too low for the given maximum number of connections.
+/// Note: The limit is only read on Linux.
+#[cfg(not(target_os = "linux"))]
+pub fn check_fd_limit(_max_connections: usize) {}
+
 /// A helper to log instructions to recover.
 pub fn log_clean_error(storage_mode: &StorageMode) {
     match storage_mode {
@@ -13,9 +13,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{Client, Prover, Validator, traits::NodeInterface};
+use crate::{Client, HealthAlertConfig, Prover, ProvingPoolConfig, SafeNode, Validator, traits::NodeInterface};
 use snarkos_account::Account;
-use snarkos_node_router::messages::NodeType;
+use snarkos_node_bft::helpers::ValidatorsResponseMode;
+use snarkos_node_consensus::{MempoolPolicy, MempoolPolicyFile};
+use snarkos_node_rest::LogFileStatus;
+use snarkos_node_router::{Experiments, Outbound, Router, messages::NodeType};
+use snarkos_node_tcp::HandshakeLimits;
 use snarkvm::prelude::{
     Address,
     Network,
@@ -29,7 +33,9 @@ use aleo_std::StorageMode;
 use anyhow::Result;
 use std::{
     net::SocketAddr,
+    path::PathBuf,
     sync::{Arc, atomic::AtomicBool},
+    time::Duration,
 };
 
 pub enum Node<N: Network> {
@@ -39,6 +45,8 @@ pub enum Node<N: Network> {
     Prover(Arc<Prover<N, ConsensusMemory<N>>>),
     /// A client node is a full node, capable of querying with the network.
     Client(Arc<Client<N, ConsensusDB<N>>>),
+    /// A node in safe mode serves its local ledger, with all networking disabled.
+    Safe(Arc<SafeNode<N, ConsensusDB<N>>>),
 }
 
 impl<N: Network> Node<N> {
@@ -48,14 +56,32 @@ impl<N: Network> Node<N> {
         bft_ip: Option<SocketAddr>,
         rest_ip: Option<SocketAddr>,
         rest_rps: u32,
+        broadcast_journal: Option<PathBuf>,
+        recent_blocks: usize,
+        log_file: Option<LogFileStatus>,
         account: Account<N>,
         trusted_peers: &[SocketAddr],
+        max_peers: Option<u16>,
+        handshake_limits: HandshakeLimits,
+        max_pool_memory: Option<u64>,
+        strict_config: bool,
         trusted_validators: &[SocketAddr],
+        validators_response: ValidatorsResponseMode,
+        pause_on_duplicate_identity: bool,
+        mempool_policy: Arc<dyn MempoolPolicy<N>>,
+        mempool_policy_file: Option<MempoolPolicyFile>,
+        inbound_queue_ttl: Duration,
+        mempool_file: Option<PathBuf>,
         genesis: Block<N>,
         cdn: Option<String>,
         storage_mode: StorageMode,
+        verify_writes: bool,
         allow_external_peers: bool,
+        early_block_announce: bool,
+        experiments: Experiments,
+        health_alert: Option<HealthAlertConfig>,
         dev_txs: bool,
+        strict_account: bool,
         shutdown: Arc<AtomicBool>,
     ) -> Result<Self> {
         Ok(Self::Validator(Arc::new(
@@ -64,14 +90,32 @@ impl<N: Network> Node<N> {
                 bft_ip,
                 rest_ip,
                 rest_rps,
+                broadcast_journal,
+                recent_blocks,
+                log_file,
                 account,
                 trusted_peers,
+                max_peers,
+                handshake_limits,
+                max_pool_memory,
+                strict_config,
                 trusted_validators,
+                validators_response,
+                pause_on_duplicate_identity,
+                mempool_policy,
+                mempool_policy_file,
+                inbound_queue_ttl,
+                mempool_file,
                 genesis,
                 cdn,
                 storage_mode,
+                verify_writes,
                 allow_external_peers,
+                early_block_announce,
+                experiments,
+                health_alert,
                 dev_txs,
+                strict_account,
                 shutdown,
             )
             .await?,
@@ -83,11 +127,33 @@ impl<N: Network> Node<N> {
         node_ip: SocketAddr,
         account: Account<N>,
         trusted_peers: &[SocketAddr],
+        max_peers: Option<u16>,
+        handshake_limits: HandshakeLimits,
+        max_pool_memory: Option<u64>,
+        strict_config: bool,
         genesis: Block<N>,
         storage_mode: StorageMode,
+        experiments: Experiments,
+        proving_pool: ProvingPoolConfig,
         shutdown: Arc<AtomicBool>,
     ) -> Result<Self> {
-        Ok(Self::Prover(Arc::new(Prover::new(node_ip, account, trusted_peers, genesis, storage_mode, shutdown).await?)))
+        Ok(Self::Prover(Arc::new(
+            Prover::new(
+                node_ip,
+                account,
+                trusted_peers,
+                max_peers,
+                handshake_limits,
+                max_pool_memory,
+                strict_config,
+                genesis,
+                storage_mode,
+                experiments,
+                proving_pool,
+                shutdown,
+            )
+            .await?,
+        )))
     }
 
     /// Initializes a new client node.
@@ -95,12 +161,23 @@ impl<N: Network> Node<N> {
         node_ip: SocketAddr,
         rest_ip: Option<SocketAddr>,
         rest_rps: u32,
+        broadcast_journal: Option<PathBuf>,
+        recent_blocks: usize,
+        log_file: Option<LogFileStatus>,
         account: Account<N>,
         trusted_peers: &[SocketAddr],
+        max_peers: Option<u16>,
+        handshake_limits: HandshakeLimits,
+        max_pool_memory: Option<u64>,
+        strict_config: bool,
         genesis: Block<N>,
         cdn: Option<String>,
         storage_mode: StorageMode,
+        verify_writes: bool,
+        max_reorg_depth: u32,
         rotate_external_peers: bool,
+        early_block_announce: bool,
+        experiments: Experiments,
         shutdown: Arc<AtomicBool>,
     ) -> Result<Self> {
         Ok(Self::Client(Arc::new(
@@ -108,12 +185,51 @@ impl<N: Network> Node<N> {
                 node_ip,
                 rest_ip,
                 rest_rps,
+                broadcast_journal,
+                recent_blocks,
+                log_file,
                 account,
                 trusted_peers,
+                max_peers,
+                handshake_limits,
+                max_pool_memory,
+                strict_config,
                 genesis,
                 cdn,
                 storage_mode,
+                verify_writes,
+                max_reorg_depth,
                 rotate_external_peers,
+                early_block_announce,
+                experiments,
+                shutdown,
+            )
+            .await?,
+        )))
+    }
+
+    /// Initializes a new node in safe mode, with all networking disabled.
+    pub async fn new_safe(
+        node_type: NodeType,
+        rest_ip: Option<SocketAddr>,
+        rest_rps: u32,
+        recent_blocks: usize,
+        log_file: Option<LogFileStatus>,
+        account: Account<N>,
+        genesis: Block<N>,
+        storage_mode: StorageMode,
+        shutdown: Arc<AtomicBool>,
+    ) -> Result<Self> {
+        Ok(Self::Safe(Arc::new(
+            SafeNode::new(
+                node_type,
+                rest_ip,
+                rest_rps,
+                recent_blocks,
+                log_file,
+                account,
+                genesis,
+                storage_mode,
                 shutdown,
             )
             .await?,
@@ -126,6 +242,7 @@ impl<N: Network> Node<N> {
             Self::Validator(validator) => validator.node_type(),
             Self::Prover(prover) => prover.node_type(),
             Self::Client(client) => client.node_type(),
+            Self::Safe(node) => node.node_type(),
         }
     }
 
@@ -135,6 +252,7 @@ impl<N: Network> Node<N> {
             Self::Validator(node) => node.private_key(),
             Self::Prover(node) => node.private_key(),
             Self::Client(node) => node.private_key(),
+            Self::Safe(node) => node.private_key(),
         }
     }
 
@@ -144,6 +262,7 @@ impl<N: Network> Node<N> {
             Self::Validator(node) => node.view_key(),
             Self::Prover(node) => node.view_key(),
             Self::Client(node) => node.view_key(),
+            Self::Safe(node) => node.view_key(),
         }
     }
 
@@ -153,6 +272,7 @@ impl<N: Network> Node<N> {
             Self::Validator(node) => node.address(),
             Self::Prover(node) => node.address(),
             Self::Client(node) => node.address(),
+            Self::Safe(node) => node.address(),
         }
     }
 
@@ -162,6 +282,17 @@ impl<N: Network> Node<N> {
             Self::Validator(node) => node.is_dev(),
             Self::Prover(node) => node.is_dev(),
             Self::Client(node) => node.is_dev(),
+            Self::Safe(node) => node.is_dev(),
+        }
+    }
+
+    /// Returns the router of the node, unless the node is in safe mode.
+    pub fn router(&self) -> Option<&Router<N>> {
+        match self {
+            Self::Validator(node) => Some(node.router()),
+            Self::Prover(node) => Some(node.router()),
+            Self::Client(node) => Some(node.router()),
+            Self::Safe(_) => None,
         }
     }
 }
@@ -13,21 +13,37 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod proving_pool;
+pub use proving_pool::*;
+
+mod puzzle_peers;
+use puzzle_peers::*;
+
+mod solution_acks;
+use solution_acks::*;
+
 mod router;
 
-use crate::traits::NodeInterface;
+use crate::traits::{NodeInterface, NodeLifecycle};
 use snarkos_account::Account;
 use snarkos_node_bft::ledger_service::ProverLedgerService;
+use snarkos_node_rest::NodeConfig;
 use snarkos_node_router::{
+    Experiments,
     Heartbeat,
     Inbound,
+    MemoryBudget,
     Outbound,
+    PeerLimits,
+    ReloadRegistry,
     Router,
     Routing,
-    messages::{Message, NodeType, UnconfirmedSolution},
+    ban_list_path,
+    messages::{Features, Message, NodeType, UnconfirmedSolution},
 };
 use snarkos_node_sync::{BlockSync, BlockSyncMode};
 use snarkos_node_tcp::{
+    HandshakeLimits,
     P2P,
     protocols::{Disconnect, Handshake, OnConnect, Reading, Writing},
 };
@@ -58,6 +74,9 @@ use std::{
 };
 use tokio::task::JoinHandle;
 
+/// The interval in seconds at which the breakdown of the solution acknowledgments is logged, if it changed.
+const SOLUTION_ACKS_REPORT_INTERVAL_IN_SECS: u64 = 60;
+
 /// A prover is a light node, capable of producing proofs for consensus.
 #[derive(Clone)]
 pub struct Prover<N: Network, C: ConsensusStorage<N>> {
@@ -73,10 +92,16 @@ pub struct Prover<N: Network, C: ConsensusStorage<N>> {
     latest_epoch_hash: Arc<RwLock<Option<N::BlockHash>>>,
     /// The latest block header.
     latest_block_header: Arc<RwLock<Option<Header<N>>>>,
+    /// The selector of the peer to request the puzzle from.
+    puzzle_peers: Arc<PuzzlePeers>,
+    /// The counters of the acknowledgments of the submitted solutions.
+    solution_acks: Arc<SolutionAcks<N>>,
     /// The number of puzzle instances.
     puzzle_instances: Arc<AtomicU8>,
     /// The maximum number of puzzle instances.
     max_puzzle_instances: u8,
+    /// The thread pool dedicated to proving.
+    proving_pool: Arc<ProvingPool>,
     /// The spawned handles.
     handles: Arc<Mutex<Vec<JoinHandle<()>>>>,
     /// The shutdown signal.
@@ -91,8 +116,14 @@ impl<N: Network, C: ConsensusStorage<N>> Prover<N, C> {
         node_ip: SocketAddr,
         account: Account<N>,
         trusted_peers: &[SocketAddr],
+        max_peers: Option<u16>,
+        handshake_limits: HandshakeLimits,
+        max_pool_memory: Option<u64>,
+        strict_config: bool,
         genesis: Block<N>,
         storage_mode: StorageMode,
+        experiments: Experiments,
+        proving_pool: ProvingPoolConfig,
         shutdown: Arc<AtomicBool>,
     ) -> Result<Self> {
         // Initialize the signal handler.
@@ -113,14 +144,36 @@ impl<N: Network, C: ConsensusStorage<N>> Prover<N, C> {
             NodeType::Prover,
             account,
             trusted_peers,
-            Self::MAXIMUM_NUMBER_OF_PEERS as u16,
+            PeerLimits::new(NodeType::Prover, max_peers).with_handshake_limits(handshake_limits),
+            strict_config,
             rotate_external_peers,
             allow_external_peers,
             matches!(storage_mode, StorageMode::Development(_)),
+            Arc::new(MemoryBudget::new(max_pool_memory)),
+            // Request the acknowledgment of the submitted solutions, to report their outcomes.
+            Features::SOLUTION_ACK,
+            experiments,
+            Some(ban_list_path(N::ID, &storage_mode)),
         )
         .await?;
+        // Warn if the limit of open files is too low for the connections.
+        crate::check_fd_limit(router.max_connected_peers());
+        // Initialize the thread pool dedicated to proving.
+        let proving_pool = ProvingPool::new(proving_pool)?;
+        // Log the effective configuration of the node.
+        let mut config = NodeConfig::new(
+            NodeType::Prover,
+            router.address(),
+            Some(&router),
+            None,
+            &storage_mode,
+            None,
+            &crate::compiled_features(),
+        );
+        config.proving = Some(proving_pool.config());
+        info!("Node configuration: {config}");
         // Compute the maximum number of puzzle instances.
-        let max_puzzle_instances = num_cpus::get().saturating_sub(2).clamp(1, 6);
+        let max_puzzle_instances = proving_pool.num_threads().saturating_sub(2).clamp(1, 6);
         // Initialize the node.
         let node = Self {
             router,
@@ -129,8 +182,11 @@ impl<N: Network, C: ConsensusStorage<N>> Prover<N, C> {
             puzzle: VM::<N, C>::new_puzzle()?,
             latest_epoch_hash: Default::default(),
             latest_block_header: Default::default(),
+            puzzle_peers: Default::default(),
+            solution_acks: Default::default(),
             puzzle_instances: Default::default(),
             max_puzzle_instances: u8::try_from(max_puzzle_instances)?,
+            proving_pool: Arc::new(proving_pool),
             handles: Default::default(),
             shutdown,
             _phantom: Default::default(),
@@ -139,8 +195,12 @@ impl<N: Network, C: ConsensusStorage<N>> Prover<N, C> {
         node.initialize_routing().await;
         // Initialize the puzzle.
         node.initialize_puzzle().await;
+        // Initialize the report of the solution acknowledgments.
+        node.initialize_solution_acks_report();
         // Initialize the notification message loop.
         node.handles.lock().push(crate::start_notification_message_loop());
+        // Shut down the node if a critical task fails.
+        node.handle_critical_failures(vec![node.router.supervisor().clone()]);
         // Pass the node to the signal handler.
         let _ = signal_node.set(node.clone());
         // Return the node.
@@ -148,8 +208,15 @@ impl<N: Network, C: ConsensusStorage<N>> Prover<N, C> {
     }
 }
 
+impl<N: Network, C: ConsensusStorage<N>> NodeInterface<N> for Prover<N, C> {}
+
 #[async_trait]
-impl<N: Network, C: ConsensusStorage<N>> NodeInterface<N> for Prover<N, C> {
+impl<N: Network, C: ConsensusStorage<N>> NodeLifecycle for Prover<N, C> {
+    /// Returns the registry of the configuration items that can be reloaded at runtime.
+    fn reloads(&self) -> Option<&ReloadRegistry> {
+        Some(self.router().reloads())
+    }
+
     /// Shuts down the node.
     async fn shut_down(&self) {
         info!("Shutting down...");
@@ -162,6 +229,10 @@ impl<N: Network, C: ConsensusStorage<N>> NodeInterface<N> for Prover<N, C> {
         debug!("Shutting down the prover...");
         self.handles.lock().iter().for_each(|handle| handle.abort());
 
+        // Notify the peers that the node is shutting down, before closing the connections.
+        debug!("Notifying the peers...");
+        self.router.await_shutdown_notifications(self.notify_shutdown()).await;
+
         // Shut down the router.
         self.router.shut_down().await;
 
@@ -180,6 +251,22 @@ impl<N: Network, C: ConsensusStorage<N>> Prover<N, C> {
         }
     }
 
+    /// Periodically logs the breakdown of the acknowledgments of the submitted solutions, if it changed.
+    fn initialize_solution_acks_report(&self) {
+        let solution_acks = self.solution_acks.clone();
+        self.handles.lock().push(tokio::spawn(async move {
+            let mut last_total = 0;
+            loop {
+                tokio::time::sleep(Duration::from_secs(SOLUTION_ACKS_REPORT_INTERVAL_IN_SECS)).await;
+                let total = solution_acks.total();
+                if total != last_total {
+                    info!("Solution acknowledgments - {solution_acks}");
+                    last_total = total;
+                }
+            }
+        }));
+    }
+
     /// Executes an instance of the puzzle.
     async fn puzzle_loop(&self) {
         loop {
@@ -208,10 +295,12 @@ impl<N: Network, C: ConsensusStorage<N>> Prover<N, C> {
 
             // If the latest epoch hash and latest state exists, then proceed to generate a solution.
             if let (Some(epoch_hash), Some((coinbase_target, proof_target))) = (latest_epoch_hash, latest_state) {
-                // Execute the puzzle.
+                // Execute the puzzle, on the proving thread pool.
                 let prover = self.clone();
                 let result = tokio::task::spawn_blocking(move || {
-                    prover.puzzle_iteration(epoch_hash, coinbase_target, proof_target, &mut OsRng)
+                    prover
+                        .proving_pool
+                        .install(|| prover.puzzle_iteration(epoch_hash, coinbase_target, proof_target, &mut OsRng))
                 })
                 .await;
 
@@ -265,6 +354,8 @@ impl<N: Network, C: ConsensusStorage<N>> Prover<N, C> {
 
     /// Broadcasts the solution to the network.
     fn broadcast_solution(&self, solution: Solution<N>) {
+        // Record the solution, so that its acknowledgments are counted.
+        self.solution_acks.insert_submitted(solution.id());
         // Prepare the unconfirmed solution message.
         let message = Message::UnconfirmedSolution(UnconfirmedSolution {
             solution_id: solution.id(),
@@ -0,0 +1,244 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkos_node_rest::ProvingConfig;
+
+use anyhow::{Result, ensure};
+use std::{
+    collections::BTreeSet,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+/// The stack size of each proving thread, which matches the one of the global thread pool.
+const PROVING_THREAD_STACK_SIZE: usize = 8 * 1024 * 1024;
+
+/// The configuration of the thread pool dedicated to proving.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProvingPoolConfig {
+    /// The number of proving threads, if it is bounded.
+    num_threads: Option<usize>,
+    /// The cores to pin the proving threads to, if any.
+    cores: Option<Vec<usize>>,
+}
+
+impl ProvingPoolConfig {
+    /// Initializes the configuration, from the optional number of proving threads and cores to pin them to.
+    pub fn new(num_threads: Option<usize>, cores: Option<Vec<usize>>) -> Result<Self> {
+        ensure!(num_threads != Some(0), "The number of proving threads must be at least 1");
+        ensure!(cores.as_ref().map_or(true, |cores| !cores.is_empty()), "The list of proving cores must not be empty");
+        Ok(Self { num_threads, cores })
+    }
+
+    /// Parses a list of cores, in the format of `taskset` and `/sys/devices/system/node/*/cpulist`, e.g. `0-7,16-23`.
+    /// Note: Listing the cores of a single NUMA node keeps the proving threads on one socket.
+    pub fn parse_cores(cpu_list: &str) -> Result<Vec<usize>> {
+        let mut cores = BTreeSet::new();
+        for range in cpu_list.split(',').map(str::trim) {
+            let (start, end) = match range.split_once('-') {
+                Some((start, end)) => (start.trim().parse::<usize>()?, end.trim().parse::<usize>()?),
+                None => {
+                    let core = range.parse::<usize>()?;
+                    (core, core)
+                }
+            };
+            ensure!(start <= end, "Invalid range of cores '{range}'");
+            cores.extend(start..=end);
+        }
+        Ok(cores.into_iter().collect())
+    }
+
+    /// Returns the number of proving threads: the given number, or one per pinned core,
+    /// or (by default) one per core of the machine, like the global thread pool.
+    pub fn num_threads(&self) -> usize {
+        match (self.num_threads, &self.cores) {
+            (Some(num_threads), _) => num_threads,
+            (None, Some(cores)) => cores.len(),
+            (None, None) => num_cpus::get(),
+        }
+    }
+
+    /// Returns the cores to pin the proving threads to, if any.
+    pub fn cores(&self) -> Option<&[usize]> {
+        self.cores.as_deref()
+    }
+}
+
+/// The thread pool dedicated to proving, which is distinct from the global thread pool,
+/// so that generating solutions cannot starve the other work of the node, e.g. processing messages.
+pub struct ProvingPool {
+    /// The thread pool.
+    pool: rayon::ThreadPool,
+    /// The configuration of the thread pool.
+    config: ProvingPoolConfig,
+    /// Whether the proving threads were pinned to their cores.
+    is_pinned: Arc<AtomicBool>,
+}
+
+impl ProvingPool {
+    /// The prefix of the names of the proving threads.
+    pub const THREAD_NAME_PREFIX: &'static str = "proving-";
+
+    /// Initializes the proving thread pool, pinning its threads to the configured cores, where supported.
+    pub fn new(config: ProvingPoolConfig) -> Result<Self> {
+        // If pinning is not supported on this platform, the cores only bound the number of threads.
+        let cores = match config.cores() {
+            Some(_) if !Self::is_pinning_supported() => {
+                warn!("Pinning the proving threads to cores is not supported on this platform, skipping it");
+                None
+            }
+            cores => cores.map(<[usize]>::to_vec),
+        };
+        let is_pinned = Arc::new(AtomicBool::new(cores.is_some()));
+
+        let is_pinned_ = is_pinned.clone();
+        let pool = rayon::ThreadPoolBuilder::new()
+            .stack_size(PROVING_THREAD_STACK_SIZE)
+            .num_threads(config.num_threads())
+            .thread_name(|index| format!("{}{index}", Self::THREAD_NAME_PREFIX))
+            .start_handler(move |index| {
+                // Assign the threads to the cores round-robin, in case there are more threads than cores.
+                if let Some(cores) = &cores {
+                    let core = cores[index % cores.len()];
+                    if let Err(error) = pin_current_thread(core) {
+                        warn!("Failed to pin proving thread {index} to core {core} - {error}");
+                        is_pinned_.store(false, Ordering::Relaxed);
+                    }
+                }
+            })
+            .build()?;
+        // Wait for the proving threads to start, so that the outcome of the pinning is known.
+        pool.broadcast(|_| ());
+
+        Ok(Self { pool, config, is_pinned })
+    }
+
+    /// Returns `true` if the proving threads can be pinned to cores on this platform.
+    pub const fn is_pinning_supported() -> bool {
+        cfg!(target_os = "linux")
+    }
+
+    /// Returns the number of proving threads.
+    pub fn num_threads(&self) -> usize {
+        self.pool.current_num_threads()
+    }
+
+    /// Returns the effective configuration of the proving thread pool, e.g. to be reported.
+    pub fn config(&self) -> ProvingConfig {
+        ProvingConfig {
+            num_threads: self.num_threads(),
+            cores: self.config.cores().map(<[usize]>::to_vec),
+            is_pinned: self.is_pinned.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Executes the given proving work on the proving thread pool, including its parallel iterators.
+    pub fn install<R: Send>(&self, work: impl FnOnce() -> R + Send) -> R {
+        self.pool.install(work)
+    }
+}
+
+/// Pins the current thread to the given core.
+#[cfg(target_os = "linux")]
+fn pin_current_thread(core: usize) -> Result<()> {
+    use nix::{
+        sched::{CpuSet, sched_setaffinity},
+        unistd::Pid,
+    };
+
+    let mut cpu_set = CpuSet::new();
+    cpu_set.set(core)?;
+    // Note: The PID `0` refers to the calling thread.
+    sched_setaffinity(Pid::from_raw(0), &cpu_set)?;
+    Ok(())
+}
+
+/// Pins the current thread to the given core.
+#[cfg(not(target_os = "linux"))]
+fn pin_current_thread(core: usize) -> Result<()> {
+    anyhow::bail!("Pinning to core {core} is not supported on this platform")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rayon::prelude::*;
+
+    #[test]
+    fn test_parse_cores() {
+        assert_eq!(ProvingPoolConfig::parse_cores("3").unwrap(), vec![3]);
+        assert_eq!(ProvingPoolConfig::parse_cores("0-3,8, 10-11").unwrap(), vec![0, 1, 2, 3, 8, 10, 11]);
+        // Ensure the overlapping ranges are deduplicated.
+        assert_eq!(ProvingPoolConfig::parse_cores("2-4,0-3").unwrap(), vec![0, 1, 2, 3, 4]);
+        // Ensure the invalid lists are rejected.
+        for cpu_list in ["", "a", "3-1", "0-", "-1", "0,,1"] {
+            assert!(ProvingPoolConfig::parse_cores(cpu_list).is_err(), "{cpu_list}");
+        }
+    }
+
+    #[test]
+    fn test_num_threads() {
+        // Ensure the default matches the global thread pool.
+        assert_eq!(ProvingPoolConfig::default().num_threads(), num_cpus::get());
+        // Ensure the cores bound the number of threads, unless it is given.
+        assert_eq!(ProvingPoolConfig::new(None, Some(vec![0, 1])).unwrap().num_threads(), 2);
+        assert_eq!(ProvingPoolConfig::new(Some(3), Some(vec![0, 1])).unwrap().num_threads(), 3);
+        // Ensure the empty configurations are rejected.
+        assert!(ProvingPoolConfig::new(Some(0), None).is_err());
+        assert!(ProvingPoolConfig::new(None, Some(vec![])).is_err());
+    }
+
+    #[test]
+    fn test_pool_size() {
+        let pool = ProvingPool::new(ProvingPoolConfig::new(Some(3), None).unwrap()).unwrap();
+        assert_eq!(pool.num_threads(), 3);
+        // Ensure the parallel iterators of the proving work are bounded by the proving threads.
+        assert_eq!(pool.install(rayon::current_num_threads), 3);
+
+        let config = pool.config();
+        assert_eq!(config.num_threads, 3);
+        assert_eq!(config.cores, None);
+        assert!(!config.is_pinned);
+    }
+
+    #[test]
+    fn test_pool_separation() {
+        let pool = ProvingPool::new(ProvingPoolConfig::new(Some(2), None).unwrap()).unwrap();
+
+        // Ensure the proving work, including its parallel iterators, only runs on the proving threads.
+        let thread_names: Vec<_> = pool.install(|| {
+            (0..256).into_par_iter().map(|_| std::thread::current().name().map(ToString::to_string)).collect()
+        });
+        assert!(thread_names.iter().all(|name| name.as_ref().unwrap().starts_with(ProvingPool::THREAD_NAME_PREFIX)));
+
+        // Ensure the other work does not run on the proving threads.
+        let thread_names: Vec<_> =
+            (0..256).into_par_iter().map(|_| std::thread::current().name().map(ToString::to_string)).collect();
+        assert!(thread_names.iter().flatten().all(|name| !name.starts_with(ProvingPool::THREAD_NAME_PREFIX)));
+    }
+
+    #[test]
+    fn test_pool_pinning() {
+        let pool = ProvingPool::new(ProvingPoolConfig::new(Some(2), Some(vec![0])).unwrap()).unwrap();
+        let config = pool.config();
+        assert_eq!(config.num_threads, 2);
+        assert_eq!(config.cores, Some(vec![0]));
+        // Ensure the threads are reported as pinned only where it is supported.
+        assert_eq!(config.is_pinned, ProvingPool::is_pinning_supported());
+    }
+}
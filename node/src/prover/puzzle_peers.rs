@@ -0,0 +1,259 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use indexmap::IndexMap;
+use parking_lot::Mutex;
+use rand::Rng;
+use std::{net::SocketAddr, time::Instant};
+
+/// The maximum number of peers whose puzzle request outcomes are tracked.
+pub const MAX_TRACKED_PUZZLE_PEERS: usize = 64;
+/// The maximum number of blocks a peer may be behind the highest sync peer, to be requested the puzzle.
+pub const MAX_PUZZLE_PEER_BLOCKS_BEHIND: u32 = 1;
+/// The time in seconds after which an unanswered puzzle request is considered to have timed out.
+pub const PUZZLE_REQUEST_TIMEOUT_IN_SECS: u64 = 5;
+/// The weight of a peer with no record of responsiveness, so that new peers are still tried.
+const MIN_PUZZLE_PEER_WEIGHT: f64 = 0.1;
+/// The smoothing factor of the responsiveness score, applied to each new outcome.
+const RESPONSIVENESS_SMOOTHING: f64 = 0.5;
+
+/// The record of the puzzle requests sent to a peer.
+#[derive(Clone, Debug, Default)]
+struct PuzzlePeerStats {
+    /// The responsiveness score in `[0, 1]`, as a moving average of the recent outcomes,
+    /// where a timeout scores `0`, and a response scores higher the lower its latency.
+    score: f64,
+    /// The time the pending puzzle request was sent, if any.
+    pending_since: Option<Instant>,
+    /// Whether the most recent puzzle request timed out.
+    timed_out: bool,
+}
+
+impl PuzzlePeerStats {
+    /// Returns the selection weight of the peer.
+    fn weight(&self) -> f64 {
+        MIN_PUZZLE_PEER_WEIGHT + self.score
+    }
+
+    /// Updates the responsiveness score with the given outcome.
+    fn update_score(&mut self, outcome: f64) {
+        self.score = (1.0 - RESPONSIVENESS_SMOOTHING) * self.score + RESPONSIVENESS_SMOOTHING * outcome;
+    }
+}
+
+/// The selector of the peer to request the puzzle from.
+///
+/// Each request goes to a peer chosen at random, weighted by its recent responsiveness,
+/// so that the load is spread over the peers, and a peer that stops responding is rotated away from.
+#[derive(Default)]
+pub struct PuzzlePeers {
+    /// The puzzle request outcomes of each peer, in the order they were last requested.
+    stats: Mutex<IndexMap<SocketAddr, PuzzlePeerStats>>,
+}
+
+impl PuzzlePeers {
+    /// Returns the peer to request the puzzle from, out of the given eligible peers.
+    pub fn select<R: Rng>(&self, eligible_peers: &[SocketAddr], now: Instant, rng: &mut R) -> Option<SocketAddr> {
+        let mut stats = self.stats.lock();
+
+        // Record the timeout of any pending request that has gone unanswered.
+        for peer_stats in stats.values_mut() {
+            if let Some(pending_since) = peer_stats.pending_since {
+                if now.saturating_duration_since(pending_since).as_secs() >= PUZZLE_REQUEST_TIMEOUT_IN_SECS {
+                    peer_stats.pending_since = None;
+                    peer_stats.timed_out = true;
+                    peer_stats.update_score(0.0);
+                }
+            }
+        }
+
+        // Rotate away from any peer whose most recent request timed out, unless no other peer is eligible.
+        let has_timed_out = |peer: &SocketAddr| stats.get(peer).is_some_and(|peer_stats| peer_stats.timed_out);
+        let mut candidates: Vec<_> = eligible_peers.iter().filter(|peer| !has_timed_out(peer)).copied().collect();
+        if candidates.is_empty() {
+            candidates = eligible_peers.to_vec();
+        }
+
+        // Choose a candidate at random, in proportion to its weight.
+        let weights: Vec<_> = candidates
+            .iter()
+            .map(|peer| stats.get(peer).map_or(MIN_PUZZLE_PEER_WEIGHT, PuzzlePeerStats::weight))
+            .collect();
+        let total_weight: f64 = weights.iter().sum();
+        if total_weight <= 0.0 {
+            return None;
+        }
+        let mut target = rng.gen_range(0.0..total_weight);
+        candidates.into_iter().zip(weights).find_map(|(peer, weight)| match target < weight {
+            true => Some(peer),
+            false => {
+                target -= weight;
+                None
+            }
+        })
+    }
+
+    /// Records that a puzzle request was sent to the given peer.
+    pub fn insert_request(&self, peer_ip: SocketAddr, now: Instant) {
+        let mut stats = self.stats.lock();
+        // Move the peer to the back, so that the least recently requested peers are evicted first.
+        let mut peer_stats = stats.shift_remove(&peer_ip).unwrap_or_default();
+        peer_stats.pending_since = Some(now);
+        stats.insert(peer_ip, peer_stats);
+        // Evict the least recently requested peers.
+        while stats.len() > MAX_TRACKED_PUZZLE_PEERS {
+            stats.shift_remove_index(0);
+        }
+    }
+
+    /// Records that a puzzle response was received from the given peer.
+    pub fn insert_response(&self, peer_ip: SocketAddr, now: Instant) {
+        if let Some(peer_stats) = self.stats.lock().get_mut(&peer_ip) {
+            // A response to a request that already timed out still counts, as the peer is responsive.
+            let latency =
+                peer_stats.pending_since.take().map(|pending_since| now.saturating_duration_since(pending_since));
+            let latency_in_secs =
+                latency.map_or(PUZZLE_REQUEST_TIMEOUT_IN_SECS as f64, |latency| latency.as_secs_f64());
+            peer_stats.timed_out = false;
+            peer_stats.update_score(1.0 / (1.0 + latency_in_secs));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::SeedableRng;
+    use rand_chacha::ChaChaRng;
+    use std::time::Duration;
+
+    /// Returns the sample peer with the given index.
+    fn sample_peer(index: u16) -> SocketAddr {
+        SocketAddr::from(([127, 0, 0, 1], 4130 + index))
+    }
+
+    /// Sends a puzzle request to the selected peer, and returns it.
+    fn request(puzzle_peers: &PuzzlePeers, peers: &[SocketAddr], now: Instant, rng: &mut ChaChaRng) -> SocketAddr {
+        let peer = puzzle_peers.select(peers, now, rng).unwrap();
+        puzzle_peers.insert_request(peer, now);
+        peer
+    }
+
+    #[test]
+    fn test_distribution_matches_weights() {
+        let rng = &mut ChaChaRng::seed_from_u64(1234567890u64);
+        let puzzle_peers = PuzzlePeers::default();
+        let peers: Vec<_> = (0..3).map(sample_peer).collect();
+        let mut now = Instant::now();
+
+        // The first peer responds instantly, the second after 1 second, and the third never.
+        for (peer, latency) in [(peers[0], Some(0)), (peers[1], Some(1)), (peers[2], None)] {
+            for _ in 0..8 {
+                puzzle_peers.insert_request(peer, now);
+                if let Some(latency) = latency {
+                    puzzle_peers.insert_response(peer, now + Duration::from_secs(latency));
+                }
+                now += Duration::from_secs(PUZZLE_REQUEST_TIMEOUT_IN_SECS);
+                // Ensure the timeouts are recorded.
+                puzzle_peers.select(&peers, now, rng);
+            }
+        }
+
+        // Sample the selection without sending requests, so that the weights do not change.
+        const NUM_SAMPLES: usize = 10_000;
+        let mut counts = [0usize; 3];
+        for _ in 0..NUM_SAMPLES {
+            let peer = puzzle_peers.select(&peers[..2], now, rng).unwrap();
+            counts[peers.iter().position(|p| *p == peer).unwrap()] += 1;
+        }
+        // Ensure the distribution roughly matches the weights (~1.1 : ~0.6).
+        let score = 1.0 - (1.0 - RESPONSIVENESS_SMOOTHING).powi(8);
+        let (weight_0, weight_1) = (MIN_PUZZLE_PEER_WEIGHT + score, MIN_PUZZLE_PEER_WEIGHT + score / 2.0);
+        let expected = weight_0 / (weight_0 + weight_1);
+        let observed = counts[0] as f64 / NUM_SAMPLES as f64;
+        assert!((observed - expected).abs() < 0.03, "observed {observed}, expected {expected}");
+
+        // Ensure the unresponsive peer is not selected while a responsive peer is eligible.
+        for _ in 0..100 {
+            assert_ne!(puzzle_peers.select(&peers, now, rng), Some(peers[2]));
+        }
+        // Ensure the unresponsive peer is still selected if it is the only eligible peer.
+        assert_eq!(puzzle_peers.select(&peers[2..], now, rng), Some(peers[2]));
+    }
+
+    #[test]
+    fn test_new_peers_are_tried() {
+        let rng = &mut ChaChaRng::seed_from_u64(1234567890u64);
+        let puzzle_peers = PuzzlePeers::default();
+        let peers: Vec<_> = (0..2).map(sample_peer).collect();
+        let now = Instant::now();
+
+        // Establish the first peer as responsive.
+        for _ in 0..8 {
+            puzzle_peers.insert_request(peers[0], now);
+            puzzle_peers.insert_response(peers[0], now);
+        }
+        // Ensure the new peer is still selected some of the time.
+        let num_selected = (0..1_000).filter(|_| puzzle_peers.select(&peers, now, rng) == Some(peers[1])).count();
+        assert!(num_selected > 50 && num_selected < 150, "num_selected = {num_selected}");
+    }
+
+    #[test]
+    fn test_failover_after_timeout() {
+        let rng = &mut ChaChaRng::seed_from_u64(1234567890u64);
+        let puzzle_peers = PuzzlePeers::default();
+        let peers: Vec<_> = (0..4).map(sample_peer).collect();
+        let mut now = Instant::now();
+
+        // Make each peer responsive, with the first peer favored.
+        for (i, peer) in peers.iter().enumerate() {
+            puzzle_peers.insert_request(*peer, now);
+            puzzle_peers.insert_response(*peer, now + Duration::from_secs(i as u64 * 2));
+        }
+
+        // Let the favored peer stop responding, once it is selected.
+        let favored = peers[0];
+        loop {
+            let peer = request(&puzzle_peers, &peers, now, rng);
+            if peer != favored {
+                puzzle_peers.insert_response(peer, now);
+            }
+            now += Duration::from_secs(PUZZLE_REQUEST_TIMEOUT_IN_SECS);
+            if peer == favored {
+                break;
+            }
+        }
+        // Ensure the very next request goes to another peer.
+        let peer = request(&puzzle_peers, &peers, now, rng);
+        assert_ne!(peer, favored);
+        puzzle_peers.insert_response(peer, now);
+
+        // Ensure the favored peer is selected again once it responds.
+        puzzle_peers.insert_request(favored, now);
+        puzzle_peers.insert_response(favored, now);
+        assert!((0..100).any(|_| puzzle_peers.select(&peers, now, rng) == Some(favored)));
+    }
+
+    #[test]
+    fn test_tracked_peers_are_bounded() {
+        let puzzle_peers = PuzzlePeers::default();
+        let now = Instant::now();
+        for i in 0..(MAX_TRACKED_PUZZLE_PEERS as u16 * 2) {
+            puzzle_peers.insert_request(sample_peer(i), now);
+        }
+        assert_eq!(puzzle_peers.stats.lock().len(), MAX_TRACKED_PUZZLE_PEERS);
+    }
+}
@@ -15,20 +15,25 @@
 
 use super::*;
 
-use snarkos_node_router::messages::{
-    BlockRequest,
-    DisconnectReason,
-    Message,
-    MessageCodec,
-    Ping,
-    Pong,
-    PuzzleRequest,
-    UnconfirmedTransaction,
+use snarkos_node_router::{
+    SyncProgress,
+    inbound_message_priority,
+    messages::{
+        BlockRequest,
+        DisconnectReason,
+        Message,
+        MessageCodec,
+        Ping,
+        Pong,
+        PuzzleRequest,
+        SolutionAck,
+        UnconfirmedTransaction,
+    },
 };
-use snarkos_node_tcp::{Connection, ConnectionSide, Tcp};
+use snarkos_node_tcp::{Connection, ConnectionSide, Tcp, protocols::MessagePriority};
 use snarkvm::prelude::{Field, Network, Zero, block::Transaction};
 
-use std::{io, net::SocketAddr};
+use std::{io, net::SocketAddr, time::Instant};
 
 impl<N: Network, C: ConsensusStorage<N>> P2P for Prover<N, C> {
     /// Returns a reference to the TCP instance.
@@ -84,8 +89,9 @@ impl<N: Network, C: ConsensusStorage<N>> Writing for Prover<N, C> {
 
     /// Creates an [`Encoder`] used to write the outbound messages to the target stream.
     /// The `side` parameter indicates the connection side **from the node's perspective**.
-    fn codec(&self, _addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
-        Default::default()
+    fn codec(&self, addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+        // Note: The handshake has completed, so the frame size limit negotiated with the peer is known.
+        self.router().outbound_codec(addr)
     }
 }
 
@@ -96,8 +102,14 @@ impl<N: Network, C: ConsensusStorage<N>> Reading for Prover<N, C> {
 
     /// Creates a [`Decoder`] used to interpret messages from the network.
     /// The `side` param indicates the connection side **from the node's perspective**.
-    fn codec(&self, _peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
-        Default::default()
+    fn codec(&self, peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+        // Note: The handshake has completed, so the frame size limit negotiated with the peer is known.
+        self.router().inbound_codec(peer_addr)
+    }
+
+    /// Classifies a message received from the network, so that control messages skip the queued bulk data.
+    fn message_priority(&self, message: &Self::Message) -> MessagePriority {
+        inbound_message_priority(message)
     }
 
     /// Processes a message received from the network.
@@ -123,9 +135,18 @@ impl<N: Network, C: ConsensusStorage<N>> Heartbeat<N> for Prover<N, C> {
     fn handle_puzzle_request(&self) {
         // Find the sync peers.
         if let Some((sync_peers, _)) = self.sync.find_sync_peers() {
-            // Choose the peer with the highest block height.
-            if let Some((peer_ip, _)) = sync_peers.into_iter().max_by_key(|(_, height)| *height) {
+            // Determine the eligible peers, which are the peers at (or near) the highest block height.
+            let Some(max_height) = sync_peers.values().max().copied() else { return };
+            let eligible_peers: Vec<_> = sync_peers
+                .into_iter()
+                .filter(|(_, height)| height.saturating_add(MAX_PUZZLE_PEER_BLOCKS_BEHIND) >= max_height)
+                .map(|(peer_ip, _)| peer_ip)
+                .collect();
+            // Choose a peer at random, weighted by its responsiveness to the recent puzzle requests.
+            let now = Instant::now();
+            if let Some(peer_ip) = self.puzzle_peers.select(&eligible_peers, now, &mut rand::thread_rng()) {
                 // Request the puzzle from the peer.
+                self.puzzle_peers.insert_request(peer_ip, now);
                 Outbound::send(self, peer_ip, Message::PuzzleRequest(PuzzleRequest));
             }
         }
@@ -143,10 +164,15 @@ impl<N: Network, C: ConsensusStorage<N>> Outbound<N> for Prover<N, C> {
         true
     }
 
-    /// Returns the number of blocks this node is behind the greatest peer height.
+    /// Returns the number of blocks this node is behind the median peer height.
     fn num_blocks_behind(&self) -> u32 {
         0
     }
+
+    /// Returns the progress of the block sync of this node.
+    fn sync_progress(&self) -> SyncProgress {
+        SyncProgress::default()
+    }
 }
 
 #[async_trait]
@@ -215,6 +241,9 @@ impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Prover<N, C> {
             header.proof_target()
         );
 
+        // Record the response, to favor the responsive peers in the subsequent puzzle requests.
+        self.puzzle_peers.insert_response(peer_ip, Instant::now());
+
         // Save the latest epoch hash in the node.
         self.latest_epoch_hash.write().replace(epoch_hash);
         // Save the latest block header in the node.
@@ -265,6 +294,21 @@ impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Prover<N, C> {
         true
     }
 
+    /// Records the outcome of a solution submitted by this prover.
+    fn solution_ack(&self, peer_ip: SocketAddr, message: SolutionAck<N>) -> bool {
+        let SolutionAck { solution_id, status } = message;
+        // Ignore the acknowledgments of the solutions this prover relayed, rather than found.
+        if !self.solution_acks.record(solution_id, status) {
+            trace!("Ignoring the acknowledgment of relayed solution '{}' from '{peer_ip}'", fmt_id(solution_id));
+            return true;
+        }
+        match status.is_accepted() {
+            true => debug!("Solution '{}' was accepted by '{peer_ip}'", fmt_id(solution_id)),
+            false => info!("Solution '{}' was dropped by '{peer_ip}' ({status})", fmt_id(solution_id)),
+        }
+        true
+    }
+
     /// Handles an `UnconfirmedTransaction` message.
     async fn unconfirmed_transaction(
         &self,
@@ -0,0 +1,141 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkos_node_router::messages::SolutionStatus;
+use snarkvm::prelude::{Network, puzzle::SolutionID};
+
+use indexmap::IndexSet;
+use parking_lot::Mutex;
+use std::{
+    fmt,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+/// The maximum number of solutions submitted by the prover, whose acknowledgments are counted.
+pub const MAX_TRACKED_SUBMITTED_SOLUTIONS: usize = 1 << 10;
+
+/// The counters of the acknowledgments of the solutions submitted by the prover, by status.
+///
+/// Only the acknowledgments of the solutions this prover found are counted, not of those it relayed.
+pub struct SolutionAcks<N: Network> {
+    /// The recently submitted solutions, in the order they were submitted.
+    submitted: Mutex<IndexSet<SolutionID<N>>>,
+    /// The number of acknowledgments of each status, in the order of `SolutionStatus::ALL`.
+    counts: [AtomicU64; SolutionStatus::ALL.len()],
+}
+
+impl<N: Network> Default for SolutionAcks<N> {
+    fn default() -> Self {
+        Self { submitted: Default::default(), counts: Default::default() }
+    }
+}
+
+impl<N: Network> SolutionAcks<N> {
+    /// Records that the prover submitted the given solution.
+    pub fn insert_submitted(&self, solution_id: SolutionID<N>) {
+        let mut submitted = self.submitted.lock();
+        submitted.insert(solution_id);
+        // Evict the least recently submitted solutions.
+        while submitted.len() > MAX_TRACKED_SUBMITTED_SOLUTIONS {
+            submitted.shift_remove_index(0);
+        }
+    }
+
+    /// Counts the acknowledgment of the given solution with the given status.
+    /// Returns `false` if the solution was not submitted by the prover, in which case it is not counted.
+    pub fn record(&self, solution_id: SolutionID<N>, status: SolutionStatus) -> bool {
+        if !self.submitted.lock().contains(&solution_id) {
+            return false;
+        }
+        self.counts[Self::index(status)].fetch_add(1, Ordering::Relaxed);
+        #[cfg(feature = "metrics")]
+        metrics::increment_counter_label(metrics::prover::SOLUTION_ACKS, "status", status.to_string());
+        true
+    }
+
+    /// Returns the number of acknowledgments with the given status.
+    pub fn count(&self, status: SolutionStatus) -> u64 {
+        self.counts[Self::index(status)].load(Ordering::Relaxed)
+    }
+
+    /// Returns the total number of acknowledgments.
+    pub fn total(&self) -> u64 {
+        self.counts.iter().map(|count| count.load(Ordering::Relaxed)).sum()
+    }
+
+    /// Returns the index of the given status in the counters.
+    fn index(status: SolutionStatus) -> usize {
+        SolutionStatus::ALL.iter().position(|candidate| *candidate == status).unwrap_or_default()
+    }
+}
+
+impl<N: Network> fmt::Display for SolutionAcks<N> {
+    /// Formats the breakdown of the acknowledgments, e.g. "3 accepted, 1 stale epoch".
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let breakdown: Vec<_> = SolutionStatus::ALL
+            .into_iter()
+            .map(|status| (status, self.count(status)))
+            .filter(|(_, count)| *count > 0)
+            .map(|(status, count)| format!("{count} {status}"))
+            .collect();
+        match breakdown.is_empty() {
+            true => write!(f, "none"),
+            false => write!(f, "{}", breakdown.join(", ")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::MainnetV0;
+
+    type CurrentNetwork = MainnetV0;
+
+    #[test]
+    fn test_solution_acks() {
+        let acks = SolutionAcks::<CurrentNetwork>::default();
+        let (first, second) = (SolutionID::from(1), SolutionID::from(2));
+        assert_eq!(acks.to_string(), "none");
+
+        // Ensure the acknowledgments of solutions that were not submitted are not counted.
+        assert!(!acks.record(first, SolutionStatus::Accepted));
+        assert_eq!(acks.total(), 0);
+
+        // Ensure the acknowledgments are counted by status.
+        acks.insert_submitted(first);
+        acks.insert_submitted(second);
+        assert!(acks.record(first, SolutionStatus::Accepted));
+        assert!(acks.record(second, SolutionStatus::StaleEpoch));
+        assert!(acks.record(second, SolutionStatus::Duplicate));
+        assert!(acks.record(first, SolutionStatus::Accepted));
+        assert_eq!(acks.count(SolutionStatus::Accepted), 2);
+        assert_eq!(acks.count(SolutionStatus::BelowTarget), 0);
+        assert_eq!(acks.total(), 4);
+        assert_eq!(acks.to_string(), "2 accepted, 1 duplicate, 1 stale epoch");
+    }
+
+    #[test]
+    fn test_submitted_solutions_are_bounded() {
+        let acks = SolutionAcks::<CurrentNetwork>::default();
+        for id in 0..(MAX_TRACKED_SUBMITTED_SOLUTIONS as u64 + 1) {
+            acks.insert_submitted(SolutionID::from(id));
+        }
+        // Ensure the least recently submitted solution is no longer tracked.
+        assert_eq!(acks.submitted.lock().len(), MAX_TRACKED_SUBMITTED_SOLUTIONS);
+        assert!(!acks.record(SolutionID::from(0), SolutionStatus::Accepted));
+        assert!(acks.record(SolutionID::from(1), SolutionStatus::Accepted));
+    }
+}
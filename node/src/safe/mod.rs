@@ -0,0 +1,228 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{Client, traits::NodeLifecycle};
+use snarkos_account::Account;
+use snarkos_node_rest::{LogFileStatus, NodeConfig, Rest};
+use snarkos_node_router::messages::NodeType;
+use snarkvm::{
+    console::network::Network,
+    ledger::{Ledger, block::Block, store::ConsensusStorage},
+    prelude::{Address, PrivateKey, ViewKey},
+};
+
+use aleo_std::{StorageMode, aleo_ledger_dir};
+use anyhow::{Context, Result};
+use std::{
+    fs,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    sync::{Arc, atomic::AtomicBool},
+};
+
+/// A node in safe mode serves its local ledger over the REST server, with all networking disabled.
+///
+/// It does not initialize a router, a gateway, or a sync module, so it never connects to peers nor advances
+/// its ledger, which allows an operator to inspect a possibly damaged ledger.
+#[derive(Clone)]
+pub struct SafeNode<N: Network, C: ConsensusStorage<N>> {
+    /// The node type the node was started as.
+    node_type: NodeType,
+    /// The account of the node.
+    account: Account<N>,
+    /// The ledger of the node.
+    ledger: Ledger<N, C>,
+    /// The REST server of the node.
+    /// Note: The routing type is never instantiated, as a node in safe mode has no router.
+    rest: Option<Rest<N, C, Client<N, C>>>,
+    /// The storage mode of the ledger.
+    storage_mode: StorageMode,
+    /// The shutdown signal.
+    shutdown: Arc<AtomicBool>,
+}
+
+impl<N: Network, C: ConsensusStorage<N>> SafeNode<N, C> {
+    /// Initializes a new node in safe mode.
+    pub async fn new(
+        node_type: NodeType,
+        rest_ip: Option<SocketAddr>,
+        rest_rps: u32,
+        recent_blocks: usize,
+        log_file: Option<LogFileStatus>,
+        account: Account<N>,
+        genesis: Block<N>,
+        storage_mode: StorageMode,
+        shutdown: Arc<AtomicBool>,
+    ) -> Result<Self> {
+        // Initialize the signal handler.
+        let signal_node = Self::handle_signals(shutdown.clone());
+
+        // Initialize the ledger from a snapshot, as the storage does not support opening it read-only.
+        let snapshot_path = snapshot_ledger(&aleo_ledger_dir(N::ID, storage_mode.clone()))?;
+        let ledger = Ledger::<N, C>::load(genesis, StorageMode::Custom(snapshot_path.clone()))?;
+        warn!(
+            "Safe mode - networking is disabled, serving the ledger at height {} from '{}'",
+            ledger.latest_height(),
+            snapshot_path.display()
+        );
+
+        // Determine the effective configuration of the node, without routing nor consensus.
+        let config = NodeConfig::new(
+            node_type,
+            account.address(),
+            None,
+            None,
+            &storage_mode,
+            log_file,
+            &crate::compiled_features(),
+        );
+
+        // Initialize the node.
+        let mut node = Self { node_type, account, ledger: ledger.clone(), rest: None, storage_mode, shutdown };
+        // Initialize the REST server, without consensus nor routing, and without a broadcast journal.
+        if let Some(rest_ip) = rest_ip {
+            node.rest =
+                Some(Rest::start(rest_ip, rest_rps, None, ledger, None, None, recent_blocks, config.clone()).await?);
+        }
+        // Log the effective configuration of the node.
+        info!("Node configuration: {}", node.rest.as_ref().map_or(&config, |rest| rest.config()));
+        // Shut down the node if a critical task fails.
+        node.handle_critical_failures(node.rest.iter().map(|rest| rest.supervisor().clone()).collect());
+        // Pass the node to the signal handler.
+        let _ = signal_node.set(node.clone());
+        // Return the node.
+        Ok(node)
+    }
+
+    /// Returns the ledger.
+    pub fn ledger(&self) -> &Ledger<N, C> {
+        &self.ledger
+    }
+
+    /// Returns the REST server.
+    pub fn rest(&self) -> &Option<Rest<N, C, Client<N, C>>> {
+        &self.rest
+    }
+
+    /// Returns the node type the node was started as.
+    pub fn node_type(&self) -> NodeType {
+        self.node_type
+    }
+
+    /// Returns the account private key of the node.
+    pub fn private_key(&self) -> &PrivateKey<N> {
+        self.account.private_key()
+    }
+
+    /// Returns the account view key of the node.
+    pub fn view_key(&self) -> &ViewKey<N> {
+        self.account.view_key()
+    }
+
+    /// Returns the account address of the node.
+    pub fn address(&self) -> Address<N> {
+        self.account.address()
+    }
+
+    /// Returns `true` if the node is in development mode.
+    pub fn is_dev(&self) -> bool {
+        matches!(self.storage_mode, StorageMode::Development(_))
+    }
+}
+
+/// Creates a snapshot of the ledger at the given path, and returns the path of the snapshot.
+///
+/// The node opens the snapshot in place of the ledger, so that the ledger itself is never written to,
+/// e.g. by a compaction or a recovery of the storage. The table files of the ledger are immutable,
+/// and are hard-linked into the snapshot, while its other files, such as its manifest and write-ahead log,
+/// are copied.
+fn snapshot_ledger(ledger_path: &Path) -> Result<PathBuf> {
+    let mut name = ledger_path.file_name().context("The ledger path has no file name")?.to_os_string();
+    name.push(".safe-mode");
+    let snapshot_path = ledger_path.with_file_name(name);
+    // Remove the snapshot of a previous run, as the ledger may have changed since.
+    if snapshot_path.exists() {
+        fs::remove_dir_all(&snapshot_path)
+            .with_context(|| format!("Failed to remove the previous snapshot '{}'", snapshot_path.display()))?;
+    }
+    fs::create_dir_all(&snapshot_path)?;
+    // Note: A ledger that does not exist yet results in an empty snapshot.
+    if ledger_path.exists() {
+        for entry in fs::read_dir(ledger_path)? {
+            let source = entry?.path();
+            let target = snapshot_path.join(source.file_name().unwrap_or_default());
+            let is_table = source.extension().is_some_and(|extension| extension == "sst");
+            // Hard-link the table files, unless the snapshot is on another file system.
+            if !(is_table && fs::hard_link(&source, &target).is_ok()) {
+                fs::copy(&source, &target)
+                    .with_context(|| format!("Failed to copy '{}' into the snapshot", source.display()))?;
+            }
+        }
+    }
+    Ok(snapshot_path)
+}
+
+#[async_trait]
+impl<N: Network, C: ConsensusStorage<N>> NodeLifecycle for SafeNode<N, C> {
+    /// Shuts down the node.
+    async fn shut_down(&self) {
+        info!("Shutting down...");
+
+        // Shut down the node.
+        trace!("Shutting down the node...");
+        self.shutdown.store(true, std::sync::atomic::Ordering::Release);
+
+        // Shut down the REST server.
+        if let Some(rest) = &self.rest {
+            rest.shut_down().await;
+        }
+
+        info!("Node has shut down.");
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    use std::os::unix::fs::MetadataExt;
+
+    #[test]
+    fn test_snapshot_ledger() {
+        let ledger_path = std::env::temp_dir().join(format!("snarkos-safe-mode-{}", rand::random::<u64>()));
+        fs::create_dir_all(&ledger_path).unwrap();
+        fs::write(ledger_path.join("000001.sst"), b"table").unwrap();
+        fs::write(ledger_path.join("CURRENT"), b"MANIFEST-000002").unwrap();
+
+        // Ensure the table files are hard-linked, and the other files are copied.
+        let snapshot_path = snapshot_ledger(&ledger_path).unwrap();
+        let inode = |path: PathBuf| fs::metadata(path).unwrap().ino();
+        assert_eq!(inode(snapshot_path.join("000001.sst")), inode(ledger_path.join("000001.sst")));
+        assert_ne!(inode(snapshot_path.join("CURRENT")), inode(ledger_path.join("CURRENT")));
+
+        // Ensure writing to the snapshot leaves the ledger unchanged.
+        fs::write(snapshot_path.join("CURRENT"), b"MANIFEST-000003").unwrap();
+        fs::remove_file(snapshot_path.join("000001.sst")).unwrap();
+        assert_eq!(fs::read(ledger_path.join("CURRENT")).unwrap(), b"MANIFEST-000002");
+        assert_eq!(fs::read(ledger_path.join("000001.sst")).unwrap(), b"table");
+
+        // Ensure a new snapshot replaces the previous one.
+        let snapshot_path = snapshot_ledger(&ledger_path).unwrap();
+        assert_eq!(fs::read(snapshot_path.join("CURRENT")).unwrap(), b"MANIFEST-000002");
+
+        fs::remove_dir_all(ledger_path).unwrap();
+        fs::remove_dir_all(snapshot_path).unwrap();
+    }
+}
@@ -13,7 +13,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use snarkos_node_router::{Routing, messages::NodeType};
+use snarkos_node_router::{ReloadRegistry, Routing, TaskSupervisor, messages::NodeType};
 use snarkvm::prelude::{Address, Network, PrivateKey, ViewKey};
 
 use once_cell::sync::OnceCell;
@@ -27,8 +27,7 @@ use std::{
     time::Duration,
 };
 
-#[async_trait]
-pub trait NodeInterface<N: Network>: Routing<N> {
+pub trait NodeInterface<N: Network>: Routing<N> + NodeLifecycle {
     /// Returns the node type.
     fn node_type(&self) -> NodeType {
         self.router().node_type()
@@ -53,8 +52,13 @@ pub trait NodeInterface<N: Network>: Routing<N> {
     fn is_dev(&self) -> bool {
         self.router().is_dev()
     }
+}
 
-    /// Handles OS signals for the node to intercept and perform a clean shutdown.
+/// The lifecycle of a node, which is independent of whether its networking is enabled.
+#[async_trait]
+pub trait NodeLifecycle: Clone + Send + Sync + 'static {
+    /// Handles OS signals for the node to intercept and perform a clean shutdown,
+    /// or, on `SIGHUP`, to reload its configuration.
     /// The optional `shutdown_flag` flag can be used to cleanly terminate the syncing process.
     fn handle_signals(shutdown_flag: Arc<AtomicBool>) -> Arc<OnceCell<Self>> {
         // In order for the signal handler to be started as early as possible, a reference to the node needs
@@ -65,11 +69,10 @@ pub trait NodeInterface<N: Network>: Routing<N> {
         fn signal_listener() -> impl Future<Output = io::Result<()>> {
             use tokio::signal::unix::{SignalKind, signal};
 
-            // Handle SIGINT, SIGTERM, SIGQUIT, and SIGHUP.
+            // Handle SIGINT, SIGTERM, and SIGQUIT.
             let mut s_int = signal(SignalKind::interrupt()).unwrap();
             let mut s_term = signal(SignalKind::terminate()).unwrap();
             let mut s_quit = signal(SignalKind::quit()).unwrap();
-            let mut s_hup = signal(SignalKind::hangup()).unwrap();
 
             // Return when any of the signals above is received.
             async move {
@@ -77,11 +80,32 @@ pub trait NodeInterface<N: Network>: Routing<N> {
                     _ = s_int.recv() => (),
                     _ = s_term.recv() => (),
                     _ = s_quit.recv() => (),
-                    _ = s_hup.recv() => (),
                 );
                 Ok(())
             }
         }
+
+        // Reload the configuration of the node on each SIGHUP.
+        #[cfg(target_family = "unix")]
+        {
+            use tokio::signal::unix::{SignalKind, signal};
+
+            let mut s_hup = signal(SignalKind::hangup()).unwrap();
+            let node = node.clone();
+            tokio::task::spawn(async move {
+                while s_hup.recv().await.is_some() {
+                    let Some(reloads) = node.get().and_then(|node| node.reloads()).cloned() else {
+                        warn!("Ignoring SIGHUP, as the node has no configuration to reload");
+                        continue;
+                    };
+                    info!("Reloading the configuration of the node (SIGHUP)...");
+                    // Note: The summary is logged by the registry.
+                    if let Err(error) = tokio::task::spawn_blocking(move || reloads.reload_all()).await {
+                        error!("Failed to reload the configuration of the node - {error}");
+                    }
+                }
+            });
+        }
         #[cfg(not(target_family = "unix"))]
         fn signal_listener() -> impl Future<Output = io::Result<()>> {
             tokio::signal::ctrl_c()
@@ -117,6 +141,28 @@ pub trait NodeInterface<N: Network>: Routing<N> {
         node
     }
 
+    /// Shuts down the node and terminates the process, once a critical task of any of the given supervisors fails.
+    fn handle_critical_failures(&self, supervisors: Vec<TaskSupervisor>) {
+        let node = self.clone();
+        // Note: The handle is not retained by the node, as the task must outlive the shutdown it initiates.
+        tokio::spawn(async move {
+            // Wait for the first critical failure.
+            let failures = supervisors.iter().map(|supervisor| Box::pin(supervisor.critical_failure()));
+            let (reason, ..) = futures_util::future::select_all(failures).await;
+            error!("Shutting down the node, as a {reason}");
+
+            // Shut down the node.
+            node.shut_down().await;
+            // Terminate the process, signalling the failure.
+            std::process::exit(1);
+        });
+    }
+
+    /// Returns the registry of the configuration items that can be reloaded at runtime, if the node has any.
+    fn reloads(&self) -> Option<&ReloadRegistry> {
+        None
+    }
+
     /// Shuts down the node.
     async fn shut_down(&self);
 }
@@ -0,0 +1,107 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    collections::BTreeMap,
+    time::{Duration, Instant},
+};
+
+/// The maximum number of block heights whose arrivals are tracked.
+pub const MAX_TRACKED_BLOCK_ARRIVALS: usize = 128;
+
+/// The way a block reached the validator.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BlockArrival {
+    /// The validator committed the block itself.
+    Committed,
+    /// A peer announced the block to the validator.
+    Announced,
+}
+
+/// The times a validator committed blocks, and first received their announcements from its peers.
+///
+/// The delay between the two shows how far the validator is from the front of the block propagation,
+/// and should stay close to zero for the validators of the committee.
+#[derive(Debug, Default)]
+pub struct BlockArrivals {
+    /// The time of the commit and of the first announcement of each block height, if any.
+    arrivals: BTreeMap<u32, (Option<Instant>, Option<Instant>)>,
+}
+
+impl BlockArrivals {
+    /// Records the arrival of the block at the given height, and returns the delay between its commit
+    /// and its first announcement, once both are known.
+    ///
+    /// Note: Only the first arrival of each kind is kept, so the delay is returned at most once per height.
+    pub fn record(&mut self, height: u32, arrival: BlockArrival, now: Instant) -> Option<Duration> {
+        let (committed, announced) = self.arrivals.entry(height).or_default();
+        let slot = match arrival {
+            BlockArrival::Committed => &mut *committed,
+            BlockArrival::Announced => &mut *announced,
+        };
+        if slot.is_some() {
+            return None;
+        }
+        *slot = Some(now);
+        let delay = match (*committed, *announced) {
+            (Some(committed), Some(announced)) => {
+                Some(announced.checked_duration_since(committed).unwrap_or_else(|| committed - announced))
+            }
+            _ => None,
+        };
+        // Forget the lowest heights beyond the limit.
+        while self.arrivals.len() > MAX_TRACKED_BLOCK_ARRIVALS {
+            self.arrivals.pop_first();
+        }
+        delay
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_arrival_delay() {
+        let mut arrivals = BlockArrivals::default();
+        let start = Instant::now();
+
+        // Ensure the delay is returned once the block is both committed and announced.
+        assert_eq!(arrivals.record(1, BlockArrival::Committed, start), None);
+        let delay = arrivals.record(1, BlockArrival::Announced, start + Duration::from_millis(30));
+        assert_eq!(delay, Some(Duration::from_millis(30)));
+        // Ensure the announcements of other peers do not return the delay again.
+        assert_eq!(arrivals.record(1, BlockArrival::Announced, start + Duration::from_millis(40)), None);
+
+        // Ensure an announcement ahead of the commit returns the delay as well.
+        assert_eq!(arrivals.record(2, BlockArrival::Announced, start), None);
+        let delay = arrivals.record(2, BlockArrival::Committed, start + Duration::from_millis(20));
+        assert_eq!(delay, Some(Duration::from_millis(20)));
+    }
+
+    #[test]
+    fn test_block_arrivals_are_bounded() {
+        let mut arrivals = BlockArrivals::default();
+        let start = Instant::now();
+
+        // Announce more blocks than are tracked.
+        for height in 0..MAX_TRACKED_BLOCK_ARRIVALS as u32 + 10 {
+            arrivals.record(height, BlockArrival::Announced, start);
+        }
+        assert_eq!(arrivals.arrivals.len(), MAX_TRACKED_BLOCK_ARRIVALS);
+        // Ensure the lowest heights were forgotten.
+        assert_eq!(arrivals.arrivals.keys().next(), Some(&10));
+    }
+}
@@ -0,0 +1,206 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkos_node_consensus::BlockProductionHealth;
+use snarkvm::prelude::{Address, Network};
+
+use anyhow::{Result, bail};
+use serde_json::{Value, json};
+use std::time::{Duration, Instant};
+
+/// The interval in seconds between updates of the block-production health.
+pub const HEALTH_UPDATE_INTERVAL_IN_SECS: u64 = 15;
+/// The margin above the threshold that the health score must reach for the validator to be considered recovered.
+pub const HEALTH_RECOVERY_MARGIN: u8 = 10;
+/// The minimum interval in seconds between two alerts, to avoid alert storms when the score flaps.
+pub const MIN_HEALTH_ALERT_INTERVAL_IN_SECS: u64 = 300;
+/// The timeout in seconds of a request to the alert webhook.
+const HEALTH_ALERT_TIMEOUT_IN_SECS: u64 = 10;
+
+/// The configuration of the health alerts of a validator.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HealthAlertConfig {
+    /// The URL the alerts are POSTed to.
+    pub webhook: String,
+    /// The health score below which the validator is considered unhealthy.
+    pub threshold: u8,
+}
+
+/// The alerter on the block-production health of a validator.
+///
+/// An alert is sent when the score crosses below the threshold, and again once the score recovers above
+/// the threshold plus a margin. Consecutive alerts are at least `MIN_HEALTH_ALERT_INTERVAL_IN_SECS` apart;
+/// a crossing within that interval is reported once the interval has elapsed, if it still holds.
+#[derive(Debug)]
+pub struct HealthAlerter {
+    /// The configuration of the alerts.
+    config: HealthAlertConfig,
+    /// Whether the validator was last reported as unhealthy.
+    is_unhealthy: bool,
+    /// The time of the last alert, if any.
+    last_alert: Option<Instant>,
+}
+
+impl HealthAlerter {
+    /// Initializes a new alerter, with the given configuration.
+    pub const fn new(config: HealthAlertConfig) -> Self {
+        Self { config, is_unhealthy: false, last_alert: None }
+    }
+
+    /// Returns the configuration of the alerts.
+    pub const fn config(&self) -> &HealthAlertConfig {
+        &self.config
+    }
+
+    /// Updates the alerter with the current health of the validator at the given time,
+    /// returning the payload of the alert to send, if any.
+    pub fn update<N: Network>(
+        &mut self,
+        address: Address<N>,
+        health: &BlockProductionHealth,
+        now: Instant,
+    ) -> Option<Value> {
+        // Determine the status of the validator, with a hysteresis to avoid flapping.
+        let is_unhealthy = match self.is_unhealthy {
+            false => health.score < self.config.threshold,
+            true => health.score < self.config.threshold.saturating_add(HEALTH_RECOVERY_MARGIN),
+        };
+        if is_unhealthy == self.is_unhealthy {
+            return None;
+        }
+        // Defer the alert, if the previous alert is too recent.
+        let min_interval = Duration::from_secs(MIN_HEALTH_ALERT_INTERVAL_IN_SECS);
+        if self.last_alert.is_some_and(|last_alert| now.saturating_duration_since(last_alert) < min_interval) {
+            return None;
+        }
+        self.is_unhealthy = is_unhealthy;
+        self.last_alert = Some(now);
+        Some(json!({
+            "event": if is_unhealthy { "unhealthy" } else { "recovered" },
+            "address": address.to_string(),
+            "score": health.score,
+            "threshold": self.config.threshold,
+            "components": health.components,
+            "timestamp": time::OffsetDateTime::now_utc().unix_timestamp(),
+        }))
+    }
+}
+
+/// POSTs the given alert payload to the given webhook.
+pub async fn send_health_alert(client: &reqwest::Client, webhook: &str, payload: &Value) -> Result<()> {
+    let response =
+        client.post(webhook).json(payload).timeout(Duration::from_secs(HEALTH_ALERT_TIMEOUT_IN_SECS)).send().await?;
+    if !response.status().is_success() {
+        bail!("The alert webhook responded with status {}", response.status());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkos_node_consensus::HealthComponents;
+    use snarkvm::prelude::{MainnetV0, TestRng, Uniform};
+
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    type CurrentNetwork = MainnetV0;
+
+    /// Returns a health with the given score.
+    /// Note: The alerter only acts on the score, so the components need not add up to it.
+    fn sample_health(score: u8) -> BlockProductionHealth {
+        BlockProductionHealth { score, components: HealthComponents { connectivity_ratio: 0.5, ..Default::default() } }
+    }
+
+    fn sample_alerter() -> HealthAlerter {
+        HealthAlerter::new(HealthAlertConfig { webhook: "http://127.0.0.1:0".to_string(), threshold: 50 })
+    }
+
+    #[test]
+    fn test_threshold_crossing() {
+        let address = Address::<CurrentNetwork>::rand(&mut TestRng::default());
+        let mut alerter = sample_alerter();
+        let start = Instant::now();
+
+        // Ensure a healthy validator does not alert.
+        assert_eq!(alerter.update(address, &sample_health(100), start), None);
+        assert_eq!(alerter.update(address, &sample_health(50), start), None);
+
+        // Ensure crossing below the threshold alerts once.
+        let alert = alerter.update(address, &sample_health(49), start).unwrap();
+        assert_eq!(alert["event"], "unhealthy");
+        assert_eq!(alert["score"], 49);
+        assert_eq!(alert["threshold"], 50);
+        assert_eq!(alert["address"], address.to_string());
+        assert_eq!(alert["components"]["connectivity_ratio"], 0.5);
+        assert_eq!(alerter.update(address, &sample_health(10), start), None);
+
+        // Ensure a recovery within the margin does not alert.
+        let later = start + Duration::from_secs(MIN_HEALTH_ALERT_INTERVAL_IN_SECS);
+        assert_eq!(alerter.update(address, &sample_health(50 + HEALTH_RECOVERY_MARGIN - 1), later), None);
+        // Ensure a recovery above the margin alerts.
+        let alert = alerter.update(address, &sample_health(50 + HEALTH_RECOVERY_MARGIN), later).unwrap();
+        assert_eq!(alert["event"], "recovered");
+    }
+
+    #[test]
+    fn test_alert_rate_limit() {
+        let address = Address::<CurrentNetwork>::rand(&mut TestRng::default());
+        let mut alerter = sample_alerter();
+        let start = Instant::now();
+
+        assert!(alerter.update(address, &sample_health(0), start).is_some());
+        // Ensure a flapping score does not alert again within the interval.
+        let soon = start + Duration::from_secs(MIN_HEALTH_ALERT_INTERVAL_IN_SECS - 1);
+        assert_eq!(alerter.update(address, &sample_health(100), soon), None);
+        assert_eq!(alerter.update(address, &sample_health(0), soon), None);
+        // Ensure the deferred recovery is reported once the interval has elapsed, if it still holds.
+        let later = start + Duration::from_secs(MIN_HEALTH_ALERT_INTERVAL_IN_SECS);
+        assert_eq!(alerter.update(address, &sample_health(0), later), None);
+        assert_eq!(alerter.update(address, &sample_health(100), later).unwrap()["event"], "recovered");
+    }
+
+    #[tokio::test]
+    async fn test_send_health_alert() {
+        // Start a webhook that records the request.
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let webhook = format!("http://{}/alerts", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0u8; 4096];
+            // Read the request, until its JSON body is complete.
+            while !request.ends_with(b"}") {
+                let num_bytes = stream.read(&mut buffer).await.unwrap();
+                request.extend_from_slice(&buffer[..num_bytes]);
+            }
+            stream.write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n").await.unwrap();
+            String::from_utf8(request).unwrap()
+        });
+
+        let payload = json!({ "event": "unhealthy", "score": 42 });
+        send_health_alert(&reqwest::Client::new(), &webhook, &payload).await.unwrap();
+
+        // Ensure the payload was POSTed as JSON.
+        let request = server.await.unwrap();
+        assert!(request.starts_with("POST /alerts HTTP/1.1"));
+        assert!(request.to_lowercase().contains("content-type: application/json"));
+        let body = request.split("\r\n\r\n").nth(1).unwrap();
+        assert_eq!(serde_json::from_str::<Value>(body).unwrap(), payload);
+    }
+}
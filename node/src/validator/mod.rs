@@ -13,23 +13,46 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod arrivals;
+use arrivals::*;
+
+mod health;
+pub use health::*;
+
 mod router;
 
-use crate::traits::NodeInterface;
+use crate::{
+    EpochHashCache,
+    SharedMemoryBudget,
+    SupervisedTasks,
+    traits::{NodeInterface, NodeLifecycle},
+};
 use snarkos_account::Account;
-use snarkos_node_bft::{helpers::init_primary_channels, ledger_service::CoreLedgerService, spawn_blocking};
-use snarkos_node_consensus::Consensus;
-use snarkos_node_rest::Rest;
+use snarkos_node_bft::{
+    helpers::{ValidatorsResponseMode, init_primary_channels},
+    ledger_service::{CoreLedgerService, WriteVerifier},
+    spawn_blocking,
+};
+use snarkos_node_consensus::{Consensus, MempoolPolicy, MempoolPolicyFile};
+use snarkos_node_rest::{LogFileStatus, NodeConfig, Rest};
 use snarkos_node_router::{
+    AccountStatus,
+    Experiments,
     Heartbeat,
     Inbound,
+    MemoryBudget,
     Outbound,
+    PeerLimits,
+    ReloadRegistry,
     Router,
     Routing,
-    messages::{NodeType, PuzzleResponse, UnconfirmedSolution, UnconfirmedTransaction},
+    TaskSupervisor,
+    ban_list_path,
+    messages::{Features, NodeType, PuzzleResponse, UnconfirmedSolution, UnconfirmedTransaction},
 };
 use snarkos_node_sync::{BlockSync, BlockSyncMode};
 use snarkos_node_tcp::{
+    HandshakeLimits,
     P2P,
     protocols::{Disconnect, Handshake, OnConnect, Reading, Writing},
 };
@@ -44,11 +67,12 @@ use snarkvm::prelude::{
 use aleo_std::StorageMode;
 use anyhow::Result;
 use core::future::Future;
-use parking_lot::Mutex;
+use parking_lot::{Mutex, RwLock};
 use std::{
     net::SocketAddr,
+    path::PathBuf,
     sync::{Arc, atomic::AtomicBool},
-    time::Duration,
+    time::{Duration, Instant},
 };
 use tokio::task::JoinHandle;
 
@@ -59,12 +83,24 @@ pub struct Validator<N: Network, C: ConsensusStorage<N>> {
     ledger: Ledger<N, C>,
     /// The consensus module of the node.
     consensus: Consensus<N>,
+    /// The supervisor of the consensus tasks.
+    consensus_supervisor: TaskSupervisor,
     /// The router of the node.
     router: Router<N>,
     /// The REST server of the node.
     rest: Option<Rest<N, C, Self>>,
     /// The sync module.
     sync: BlockSync<N>,
+    /// The verifier of the critical storage writes.
+    write_verifier: Arc<WriteVerifier>,
+    /// The file the unconfirmed inbound transmissions are saved to on shutdown, and loaded from on startup, if any.
+    mempool_file: Option<PathBuf>,
+    /// The status of the node account, as observed in the local ledger.
+    account_status: Arc<RwLock<Option<AccountStatus>>>,
+    /// The epoch hash of the latest block, served in the puzzle responses.
+    epoch_hash: Arc<EpochHashCache<N>>,
+    /// The times this node committed blocks and received their announcements.
+    block_arrivals: Arc<Mutex<BlockArrivals>>,
     /// The spawned handles.
     handles: Arc<Mutex<Vec<JoinHandle<()>>>>,
     /// The shutdown signal.
@@ -78,14 +114,32 @@ impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
         bft_ip: Option<SocketAddr>,
         rest_ip: Option<SocketAddr>,
         rest_rps: u32,
+        broadcast_journal: Option<PathBuf>,
+        recent_blocks: usize,
+        log_file: Option<LogFileStatus>,
         account: Account<N>,
         trusted_peers: &[SocketAddr],
+        max_peers: Option<u16>,
+        handshake_limits: HandshakeLimits,
+        max_pool_memory: Option<u64>,
+        strict_config: bool,
         trusted_validators: &[SocketAddr],
+        validators_response: ValidatorsResponseMode,
+        pause_on_duplicate_identity: bool,
+        mempool_policy: Arc<dyn MempoolPolicy<N>>,
+        mempool_policy_file: Option<MempoolPolicyFile>,
+        inbound_queue_ttl: Duration,
+        mempool_file: Option<PathBuf>,
         genesis: Block<N>,
         cdn: Option<String>,
         storage_mode: StorageMode,
+        verify_writes: bool,
         allow_external_peers: bool,
+        early_block_announce: bool,
+        experiments: Experiments,
+        health_alert: Option<HealthAlertConfig>,
         dev_txs: bool,
+        strict_account: bool,
         shutdown: Arc<AtomicBool>,
     ) -> Result<Self> {
         // Initialize the signal handler.
@@ -105,20 +159,56 @@ impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
             }
         }
 
+        // Initialize the verifier of the critical storage writes, shared by the ledger and the BFT storage.
+        let write_verifier = Arc::new(WriteVerifier::new(verify_writes));
         // Initialize the ledger service.
-        let ledger_service = Arc::new(CoreLedgerService::new(ledger.clone(), shutdown.clone()));
+        let ledger_service = Arc::new(
+            CoreLedgerService::new(ledger.clone(), shutdown.clone()).with_write_verifier(write_verifier.clone()),
+        );
         // Initialize the sync module.
         let sync = BlockSync::new(BlockSyncMode::Gateway, ledger_service.clone());
 
+        // Initialize the memory budget, shared by consensus and the router.
+        let memory_budget = Arc::new(MemoryBudget::new(max_pool_memory));
+        // Initialize the supervisor of the consensus tasks.
+        let consensus_supervisor = TaskSupervisor::new("consensus");
         // Initialize the consensus.
-        let mut consensus =
-            Consensus::new(account.clone(), ledger_service, bft_ip, trusted_validators, storage_mode.clone())?;
+        let mut consensus = Consensus::new(
+            account.clone(),
+            ledger_service,
+            bft_ip,
+            trusted_validators,
+            storage_mode.clone(),
+            Arc::new(SharedMemoryBudget(memory_budget.clone())),
+            Arc::new(SupervisedTasks(consensus_supervisor.clone())),
+            mempool_policy,
+            inbound_queue_ttl,
+            write_verifier.clone(),
+        )?;
+        // Set the level of detail of the validators responses to peers outside the committee.
+        consensus.bft().primary().gateway().set_validators_response_mode(validators_response);
+        // Set the limits on the inbound validators performing the handshake at the same time.
+        consensus.bft().primary().gateway().tcp().set_handshake_limits(handshake_limits);
+        // Set whether the validator pauses while another validator is using its account.
+        consensus.bft().primary().set_pause_on_duplicate_identity(pause_on_duplicate_identity);
         // Initialize the primary channels.
         let (primary_sender, primary_receiver) = init_primary_channels::<N>();
         // Start the consensus.
         consensus.run(primary_sender, primary_receiver).await?;
         // Determine if the validator should rotate external peers.
         let rotate_external_peers = false;
+        // Determine the peer limits, which must leave room for the committee members.
+        let committee_size = ledger.latest_committee()?.num_members();
+        let peer_limits = PeerLimits::new(NodeType::Validator, max_peers)
+            .with_committee_size(committee_size)
+            .with_handshake_limits(handshake_limits);
+        // Determine if the validator should announce the blocks it committed to its clients, ahead of the block gossip.
+        // The validator always acknowledges the solutions submitted by the provers that negotiated it.
+        let features = match early_block_announce {
+            true => Features::BLOCK_ANNOUNCE,
+            false => Features::NONE,
+        }
+        .with(Features::SOLUTION_ACK);
 
         // Initialize the node router.
         let router = Router::new(
@@ -126,35 +216,88 @@ impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
             NodeType::Validator,
             account,
             trusted_peers,
-            Self::MAXIMUM_NUMBER_OF_PEERS as u16,
+            peer_limits,
+            strict_config,
             rotate_external_peers,
             allow_external_peers,
             matches!(storage_mode, StorageMode::Development(_)),
+            memory_budget,
+            features,
+            experiments,
+            Some(ban_list_path(N::ID, &storage_mode)),
         )
         .await?;
+        // Warn if the limit of open files is too low for the connections of the router and the gateway.
+        crate::check_fd_limit(router.max_connected_peers() + consensus.bft().primary().gateway().max_connected_peers());
+
+        // Determine the effective configuration of the node.
+        let config = NodeConfig::new(
+            NodeType::Validator,
+            router.address(),
+            Some(&router),
+            Some(&consensus),
+            &storage_mode,
+            log_file,
+            &crate::compiled_features(),
+        );
 
         // Initialize the node.
         let mut node = Self {
             ledger: ledger.clone(),
             consensus: consensus.clone(),
+            consensus_supervisor,
             router,
             rest: None,
             sync,
+            write_verifier,
+            mempool_file,
+            account_status: Default::default(),
+            epoch_hash: Default::default(),
+            block_arrivals: Default::default(),
             handles: Default::default(),
             shutdown,
         };
+        // Reload the mempool policy from its file, if any.
+        if let Some(mempool_policy_file) = mempool_policy_file {
+            node.initialize_mempool_policy_reload(mempool_policy_file);
+        }
+        // Check the account against the local ledger.
+        node.initialize_account_check(strict_account)?;
         // Initialize the transaction pool.
         node.initialize_transaction_pool(storage_mode, dev_txs)?;
+        // Load the memory pool saved on the previous shutdown.
+        node.load_mempool().await;
 
         // Initialize the REST server.
         if let Some(rest_ip) = rest_ip {
-            node.rest =
-                Some(Rest::start(rest_ip, rest_rps, Some(consensus), ledger.clone(), Arc::new(node.clone())).await?);
+            node.rest = Some(
+                Rest::start(
+                    rest_ip,
+                    rest_rps,
+                    Some(consensus),
+                    ledger.clone(),
+                    Some(Arc::new(node.clone())),
+                    broadcast_journal,
+                    recent_blocks,
+                    config.clone(),
+                )
+                .await?,
+            );
         }
+        // Log the effective configuration of the node.
+        info!("Node configuration: {}", node.rest.as_ref().map_or(&config, |rest| rest.config()));
         // Initialize the routing.
         node.initialize_routing().await;
+        // Initialize the block announcements.
+        node.initialize_block_announcements()?;
+        // Initialize the block-production health monitor.
+        node.initialize_health_monitor(health_alert);
         // Initialize the notification message loop.
         node.handles.lock().push(crate::start_notification_message_loop());
+        // Shut down the node if a critical task fails.
+        let mut supervisors = node.supervisors();
+        supervisors.extend(node.rest.as_ref().map(|rest| rest.supervisor().clone()));
+        node.handle_critical_failures(supervisors);
         // Pass the node to the signal handler.
         let _ = signal_node.set(node.clone());
         // Return the node.
@@ -411,14 +554,148 @@ impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
         Ok(())
     }
 
+    /// Checks the account status against the local ledger, and re-checks it once the node has synced.
+    fn initialize_account_check(&self, strict: bool) -> Result<()> {
+        // Check the account status against the local ledger.
+        let status = crate::account_status(&self.ledger, self.address(), false)?;
+        *self.account_status.write() = Some(status);
+        crate::check_account_status(self.node_type(), self.address(), &status, strict)?;
+
+        // Re-check the account status once the node has synced.
+        let self_ = self.clone();
+        self.spawn(async move {
+            loop {
+                tokio::time::sleep(Duration::from_secs(crate::ACCOUNT_RECHECK_INTERVAL_IN_SECS)).await;
+                // Wait until the node has synced.
+                if !self_.sync.is_block_synced() {
+                    continue;
+                }
+                let status = match crate::account_status(&self_.ledger, self_.address(), true) {
+                    Ok(status) => status,
+                    Err(error) => {
+                        warn!("Failed to re-check the account status - {error}");
+                        continue;
+                    }
+                };
+                *self_.account_status.write() = Some(status);
+                if let Err(error) = crate::check_account_status(self_.node_type(), self_.address(), &status, strict) {
+                    error!("{error}");
+                    // Shut down the node, as it is misconfigured.
+                    self_.shut_down().await;
+                    std::process::exit(1);
+                }
+                break;
+            }
+        });
+        Ok(())
+    }
+
+    /// Announces the blocks committed by this node to the connected peers, if block announcements are enabled.
+    /// The committee members and the other validators are announced each block ahead of the clients and provers.
+    ///
+    /// Note: The blocks synced from peers are not announced, as they are already propagated by the block gossip.
+    /// The committee members are not notified through the gateway, as they commit the same blocks from the DAG.
+    fn initialize_block_announcements(&self) -> Result<()> {
+        // Return early if block announcements are disabled.
+        if !self.router.features().contains(Features::BLOCK_ANNOUNCE) {
+            return Ok(());
+        }
+        // Subscribe to the blocks committed by consensus.
+        let mut committed_blocks = self.consensus.subscribe_committed_blocks()?;
+        let self_ = self.clone();
+        self.spawn(async move {
+            while let Some(block) = committed_blocks.recv().await {
+                self_.record_block_arrival(block.height(), BlockArrival::Committed);
+                self_.announce_block(&block);
+            }
+        });
+        Ok(())
+    }
+
+    /// Records the arrival of the block at the given height, along with the delay between its commit
+    /// by this node and its first announcement by a peer, once both are known.
+    fn record_block_arrival(&self, height: u32, arrival: BlockArrival) {
+        if let Some(delay) = self.block_arrivals.lock().record(height, arrival, Instant::now()) {
+            trace!("Block {height} was committed and announced {} ms apart", delay.as_millis());
+            #[cfg(feature = "metrics")]
+            metrics::histogram(metrics::blocks::ANNOUNCE_DELAY, delay.as_secs_f64());
+        }
+    }
+
+    /// Periodically updates the block-production health of the validator, and alerts on it if a webhook is configured.
+    /// Note: The health is purely observational, and has no impact on consensus.
+    fn initialize_health_monitor(&self, health_alert: Option<HealthAlertConfig>) {
+        let mut alerter = health_alert.map(HealthAlerter::new);
+        let client = reqwest::Client::new();
+        let self_ = self.clone();
+        self.spawn(async move {
+            loop {
+                tokio::time::sleep(Duration::from_secs(HEALTH_UPDATE_INTERVAL_IN_SECS)).await;
+                let health = self_
+                    .consensus
+                    .block_production_health(self_.sync.is_block_synced(), self_.router.is_fd_exhausted());
+                #[cfg(feature = "metrics")]
+                metrics::gauge(metrics::consensus::HEALTH_SCORE, health.score as f64);
+
+                // Alert on the health, if the score crossed the threshold.
+                let Some(alerter) = alerter.as_mut() else {
+                    continue;
+                };
+                if let Some(payload) = alerter.update(self_.address(), &health, Instant::now()) {
+                    info!("Sending a block-production health alert (score {})", health.score);
+                    if let Err(error) = send_health_alert(&client, &alerter.config().webhook, &payload).await {
+                        warn!("Failed to send the block-production health alert - {error}");
+                    }
+                }
+            }
+        });
+    }
+
+    /// Registers the mempool policy file for reloads, so that an edited policy applies without a restart.
+    fn initialize_mempool_policy_reload(&self, mempool_policy_file: MempoolPolicyFile) {
+        let consensus = self.consensus.clone();
+        self.router.reloads().register("mempool_policy", move || {
+            let Some((mempool_policy, changes)) = mempool_policy_file.reload::<N>()? else { return Ok(None) };
+            consensus.set_mempool_policy(mempool_policy);
+            Ok(Some(changes))
+        });
+    }
+
+    /// Loads the unconfirmed inbound transmissions from the memory pool file, if it is set and exists.
+    ///
+    /// Note: The node starts even if the saved memory pool cannot be loaded, so a failure is only logged.
+    async fn load_mempool(&self) {
+        let Some(path) = self.mempool_file.as_deref().filter(|path| path.exists()) else { return };
+        match self.consensus.load_mempool(path).await {
+            Ok(num_loaded) => info!("Loaded {num_loaded} unconfirmed transmissions from '{}'", path.display()),
+            Err(error) => warn!("Failed to load the memory pool from '{}' - {error}", path.display()),
+        }
+    }
+
+    /// Saves the unconfirmed inbound transmissions to the memory pool file, if it is set.
+    fn save_mempool(&self) {
+        let Some(path) = &self.mempool_file else { return };
+        match self.consensus.save_mempool(path) {
+            Ok(num_saved) => info!("Saved {num_saved} unconfirmed transmissions to '{}'", path.display()),
+            Err(error) => warn!("Failed to save the memory pool to '{}' - {error}", path.display()),
+        }
+    }
+
     /// Spawns a task with the given future; it should only be used for long-running tasks.
     pub fn spawn<T: Future<Output = ()> + Send + 'static>(&self, future: T) {
         self.handles.lock().push(tokio::spawn(future));
     }
 }
 
+impl<N: Network, C: ConsensusStorage<N>> NodeInterface<N> for Validator<N, C> {}
+
 #[async_trait]
-impl<N: Network, C: ConsensusStorage<N>> NodeInterface<N> for Validator<N, C> {
+impl<N: Network, C: ConsensusStorage<N>> NodeLifecycle for Validator<N, C> {
+    /// Returns the registry of the configuration items that can be reloaded at runtime.
+    fn reloads(&self) -> Option<&ReloadRegistry> {
+        Some(self.router().reloads())
+    }
+
     /// Shuts down the node.
     async fn shut_down(&self) {
         info!("Shutting down...");
@@ -427,17 +704,29 @@ impl<N: Network, C: ConsensusStorage<N>> NodeInterface<N> for Validator<N, C> {
         trace!("Shutting down the node...");
         self.shutdown.store(true, std::sync::atomic::Ordering::Release);
 
+        // Shut down the REST server.
+        if let Some(rest) = &self.rest {
+            rest.shut_down().await;
+        }
+
         // Abort the tasks.
         trace!("Shutting down the validator...");
         self.handles.lock().iter().for_each(|handle| handle.abort());
 
-        // Shut down the router.
-        self.router.shut_down().await;
+        // Save the memory pool, before consensus closes the channels to the BFT.
+        self.save_mempool();
 
-        // Shut down consensus.
+        // Shut down consensus first, which notifies the committee before closing the gateway.
         trace!("Shutting down consensus...");
         self.consensus.shut_down().await;
 
+        // Notify the peers that the node is shutting down, before closing the connections.
+        trace!("Notifying the peers...");
+        self.router.await_shutdown_notifications(self.notify_shutdown()).await;
+
+        // Shut down the router.
+        self.router.shut_down().await;
+
         info!("Node has shut down.");
     }
 }
@@ -445,6 +734,8 @@ impl<N: Network, C: ConsensusStorage<N>> NodeInterface<N> for Validator<N, C> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use snarkos_node_consensus::{DEFAULT_INBOUND_QUEUE_TTL_IN_SECS, DefaultMempoolPolicy};
+    use snarkos_node_rest::DEFAULT_RECENT_BLOCKS_CAPACITY;
     use snarkvm::prelude::{
         MainnetV0,
         VM,
@@ -484,14 +775,32 @@ mod tests {
             None,
             Some(rest),
             10,
+            None,
+            DEFAULT_RECENT_BLOCKS_CAPACITY,
+            None,
             account,
             &[],
+            None,
+            Default::default(),
+            None,
+            false,
             &[],
+            ValidatorsResponseMode::Full,
+            false,
+            Arc::new(DefaultMempoolPolicy),
+            None,
+            Duration::from_secs(DEFAULT_INBOUND_QUEUE_TTL_IN_SECS),
+            None,
             genesis,
             None,
             storage_mode,
             false,
+            false,
+            false,
+            Default::default(),
+            None,
             dev_txs,
+            false,
             Default::default(),
         )
         .await
@@ -14,20 +14,30 @@
 // limitations under the License.
 
 use super::*;
-use snarkos_node_router::messages::{
-    BlockRequest,
-    BlockResponse,
-    DataBlocks,
-    DisconnectReason,
-    Message,
-    MessageCodec,
-    Ping,
-    Pong,
-    UnconfirmedTransaction,
+use snarkos_node_bft::ledger_service::{LedgerReadError, read_blocks, retry_transient};
+use snarkos_node_consensus::{DeploymentRateExceeded, SolutionOutcome, SolutionRejection};
+use snarkos_node_router::{
+    SyncProgress,
+    TaskSupervisor,
+    inbound_message_priority,
+    messages::{
+        BlockAnnounce,
+        BlockRequest,
+        BlockResponse,
+        DataBlocks,
+        DisconnectReason,
+        Message,
+        MessageCodec,
+        Ping,
+        Pong,
+        SolutionStatus,
+        UnconfirmedTransaction,
+    },
 };
-use snarkos_node_tcp::{Connection, ConnectionSide, Tcp};
+use snarkos_node_sync::locators::BlockLocators;
+use snarkos_node_tcp::{Connection, ConnectionSide, Tcp, protocols::MessagePriority};
 use snarkvm::{
-    ledger::narwhal::Data,
+    ledger::{committee::Committee, narwhal::Data},
     prelude::{Network, block::Transaction, error},
 };
 
@@ -95,8 +105,9 @@ impl<N: Network, C: ConsensusStorage<N>> Writing for Validator<N, C> {
 
     /// Creates an [`Encoder`] used to write the outbound messages to the target stream.
     /// The `side` parameter indicates the connection side **from the node's perspective**.
-    fn codec(&self, _addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
-        Default::default()
+    fn codec(&self, addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+        // Note: The handshake has completed, so the frame size limit negotiated with the peer is known.
+        self.router().outbound_codec(addr)
     }
 }
 
@@ -107,8 +118,14 @@ impl<N: Network, C: ConsensusStorage<N>> Reading for Validator<N, C> {
 
     /// Creates a [`Decoder`] used to interpret messages from the network.
     /// The `side` param indicates the connection side **from the node's perspective**.
-    fn codec(&self, _peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
-        Default::default()
+    fn codec(&self, peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+        // Note: The handshake has completed, so the frame size limit negotiated with the peer is known.
+        self.router().inbound_codec(peer_addr)
+    }
+
+    /// Classifies a message received from the network, so that control messages skip the queued bulk data.
+    fn message_priority(&self, message: &Self::Message) -> MessagePriority {
+        inbound_message_priority(message)
     }
 
     /// Processes a message received from the network.
@@ -146,13 +163,30 @@ impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
 }
 
 #[async_trait]
-impl<N: Network, C: ConsensusStorage<N>> Routing<N> for Validator<N, C> {}
+impl<N: Network, C: ConsensusStorage<N>> Routing<N> for Validator<N, C> {
+    /// Returns the supervisors of the tasks of the router and consensus.
+    fn supervisors(&self) -> Vec<TaskSupervisor> {
+        vec![self.router().supervisor().clone(), self.consensus_supervisor.clone()]
+    }
+
+    /// Returns the status of the node account, as observed in the local ledger.
+    fn account_status(&self) -> Option<AccountStatus> {
+        *self.account_status.read()
+    }
+
+    /// Returns `true` if a read-back of a critical write detected that the storage of the node is corrupted.
+    fn is_storage_corrupted(&self) -> bool {
+        self.write_verifier.is_corrupted()
+    }
 
-impl<N: Network, C: ConsensusStorage<N>> Heartbeat<N> for Validator<N, C> {
-    /// The maximum number of peers permitted to maintain connections with.
-    const MAXIMUM_NUMBER_OF_PEERS: usize = 200;
+    /// Returns the current block locators of the node.
+    fn block_locators(&self) -> Result<BlockLocators<N>> {
+        self.sync.get_block_locators()
+    }
 }
 
+impl<N: Network, C: ConsensusStorage<N>> Heartbeat<N> for Validator<N, C> {}
+
 impl<N: Network, C: ConsensusStorage<N>> Outbound<N> for Validator<N, C> {
     /// Returns a reference to the router.
     fn router(&self) -> &Router<N> {
@@ -164,21 +198,44 @@ impl<N: Network, C: ConsensusStorage<N>> Outbound<N> for Validator<N, C> {
         self.sync.is_block_synced()
     }
 
-    /// Returns the number of blocks this node is behind the greatest peer height.
+    /// Returns the number of blocks this node is behind the median peer height.
     fn num_blocks_behind(&self) -> u32 {
-        self.sync.num_blocks_behind()
+        self.sync.num_blocks_behind_median()
+    }
+
+    /// Returns the progress of the block sync of this node.
+    fn sync_progress(&self) -> SyncProgress {
+        self.consensus.bft().primary().sync_progress()
+    }
+
+    /// Returns the latest committee in the ledger.
+    fn latest_committee(&self) -> Option<Committee<N>> {
+        self.ledger.latest_committee().ok()
     }
 }
 
 #[async_trait]
 impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Validator<N, C> {
+    /// Records the delay between the commit of the announced block by this node and its announcement by the peer.
+    async fn block_announce(&self, peer_ip: SocketAddr, message: BlockAnnounce<N>) -> bool {
+        trace!("Received the announcement of block {} ({}) from '{peer_ip}'", message.height, message.hash);
+        // Note: The announcement is only a hint, as this node commits or syncs the block on its own.
+        self.record_block_arrival(message.height, BlockArrival::Announced);
+        true
+    }
+
     /// Retrieves the blocks within the block request range, and returns the block response to the peer.
     fn block_request(&self, peer_ip: SocketAddr, message: BlockRequest) -> bool {
         let BlockRequest { start_height, end_height } = &message;
 
-        // Retrieve the blocks within the requested range.
-        let blocks = match self.ledger.get_blocks(*start_height..*end_height) {
+        // Retrieve the blocks within the requested range, reading them again on a transient failure.
+        let blocks = match retry_transient(|| read_blocks(&self.ledger, *start_height..*end_height)) {
             Ok(blocks) => Data::Object(DataBlocks(blocks)),
+            // If the ledger failed to read the blocks, the peer is not at fault, and may request them again.
+            Err(error) if LedgerReadError::is_unavailable(&error) => {
+                error!("Unable to serve blocks {start_height} to {end_height} to '{peer_ip}' - {error}");
+                return true;
+            }
             Err(error) => {
                 error!("Failed to retrieve blocks {start_height} to {end_height} from the ledger - {error}");
                 return false;
@@ -240,19 +297,18 @@ impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Validator<N, C> {
         true
     }
 
-    /// Retrieves the latest epoch hash and latest block header, and returns the puzzle response to the peer.
+    /// Returns the puzzle response to the peer, with the latest block header and the epoch hash anchored at its height.
     fn puzzle_request(&self, peer_ip: SocketAddr) -> bool {
-        // Retrieve the latest epoch hash.
-        let epoch_hash = match self.ledger.latest_epoch_hash() {
-            Ok(epoch_hash) => epoch_hash,
+        // Retrieve the latest block header and its epoch hash.
+        let (block_header, (_, epoch_hash)) = match self.epoch_hash.latest(&self.ledger) {
+            Ok(latest) => latest,
             Err(error) => {
                 error!("Failed to prepare a puzzle request for '{peer_ip}': {error}");
                 return false;
             }
         };
-        // Retrieve the latest block header.
-        let block_header = Data::Object(self.ledger.latest_header());
         // Send the `PuzzleResponse` message to the peer.
+        let block_header = Data::Object(block_header);
         Outbound::send(self, peer_ip, Message::PuzzleResponse(PuzzleResponse { epoch_hash, block_header }));
         true
     }
@@ -271,9 +327,18 @@ impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Validator<N, C> {
         solution: Solution<N>,
     ) -> bool {
         // Add the unconfirmed solution to the memory pool.
-        if let Err(error) = self.consensus.add_unconfirmed_solution(solution).await {
-            trace!("[UnconfirmedSolution] {error}");
-            return true; // Maintain the connection.
+        // Note: The validity of the solution is only checked once it is sent to the primary, so a queued
+        // solution is acknowledged as accepted.
+        let solution_id = serialized.solution_id;
+        match self.consensus.add_unconfirmed_solution(solution).await {
+            Ok(outcome) => self.acknowledge_solution(peer_ip, solution_id, solution_status(outcome)),
+            Err(error) => {
+                trace!("[UnconfirmedSolution] {error}");
+                let outcome =
+                    SolutionRejection::of(&error).map_or(SolutionOutcome::Rejected, |rejection| rejection.status);
+                self.acknowledge_solution(peer_ip, solution_id, solution_status(outcome));
+                return true; // Maintain the connection.
+            }
         }
         let message = Message::UnconfirmedSolution(serialized);
         // Propagate the "UnconfirmedSolution" to the connected validators.
@@ -288,9 +353,15 @@ impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Validator<N, C> {
         serialized: UnconfirmedTransaction<N>,
         transaction: Transaction<N>,
     ) -> bool {
+        // Note: The deployments of the trusted peers, such as the validator's own clients, are not rate limited.
+        let origin = (!self.router().is_trusted(&peer_ip)).then_some(peer_ip);
         // Add the unconfirmed transaction to the memory pool.
-        if let Err(error) = self.consensus.add_unconfirmed_transaction(transaction).await {
-            trace!("[UnconfirmedTransaction] {error}");
+        if let Err(error) = self.consensus.add_unconfirmed_transaction(transaction, origin).await {
+            // Note: The deployments beyond the rate limit of the peer are dropped, as it may be an honest relay.
+            match DeploymentRateExceeded::of(&error) {
+                Some(rejection) => debug!("[UnconfirmedTransaction] {rejection}"),
+                None => trace!("[UnconfirmedTransaction] {error}"),
+            }
             return true; // Maintain the connection.
         }
         let message = Message::UnconfirmedTransaction(serialized);
@@ -299,3 +370,13 @@ impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Validator<N, C> {
         true
     }
 }
+
+/// Returns the status acknowledged to the prover of a solution, from its outcome in the memory pool.
+const fn solution_status(outcome: SolutionOutcome) -> SolutionStatus {
+    match outcome {
+        SolutionOutcome::Accepted => SolutionStatus::Accepted,
+        SolutionOutcome::Duplicate => SolutionStatus::Duplicate,
+        SolutionOutcome::QueueFull => SolutionStatus::QueueFull,
+        SolutionOutcome::Rejected => SolutionStatus::Rejected,
+    }
+}
@@ -114,6 +114,27 @@ impl<N: Network> BlockLocators<N> {
     }
 }
 
+impl<N: Network> BlockLocators<N> {
+    /// Returns the common ancestor of this and the given block locators, along with the height of the first
+    /// bifurcation in their block history, if any. Only the heights present in both block locators are compared.
+    ///
+    /// Attention: Please do not optimize this loop, as it performs fork-detection. In addition,
+    /// by iterating upwards, it also early-terminates malicious block locators at the *first* point
+    /// of bifurcation in their ledger history, which is a critical safety guarantee provided here.
+    pub fn find_common_ancestor(&self, other: &Self) -> (u32, Option<u32>) {
+        let mut ancestor = 0;
+        for (height, hash) in other.clone().into_iter() {
+            if let Some(expected_hash) = self.get_hash(height) {
+                match expected_hash == hash {
+                    true => ancestor = height,
+                    false => return (ancestor, Some(height)), // fork
+                }
+            }
+        }
+        (ancestor, None)
+    }
+}
+
 impl<N: Network> BlockLocators<N> {
     /// Checks the old and new block locators share a consistent view of block history.
     /// This function assumes the given block locators are well-formed.
@@ -20,3 +20,6 @@ extern crate tracing;
 
 mod block_locators;
 pub use block_locators::*;
+
+mod relationship;
+pub use relationship::*;
@@ -0,0 +1,125 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::BlockLocators;
+use snarkvm::prelude::Network;
+
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// The relationship of the block history of one set of block locators to another.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "relationship", rename_all = "snake_case")]
+pub enum LocatorsRelationship {
+    /// Both share the same block history, up to the same latest height.
+    Equal { height: u32 },
+    /// Both share the same block history, and this one extends beyond the other one.
+    Ahead { common_ancestor: u32, num_blocks: u32 },
+    /// Both share the same block history, and the other one extends beyond this one.
+    Behind { common_ancestor: u32, num_blocks: u32 },
+    /// Both share the same block history up to the common ancestor, and diverge at the fork height.
+    Forked { common_ancestor: u32, fork_height: u32 },
+    /// Both have a different genesis block, and share no block history.
+    Incomparable,
+}
+
+impl<N: Network> BlockLocators<N> {
+    /// Returns the relationship of this block history to the given block locators.
+    /// This function assumes the given block locators are well-formed.
+    pub fn compare(&self, other: &Self) -> LocatorsRelationship {
+        match self.find_common_ancestor(other) {
+            // If the genesis blocks differ, the block histories are incomparable.
+            (_, Some(0)) => LocatorsRelationship::Incomparable,
+            (common_ancestor, Some(fork_height)) => LocatorsRelationship::Forked { common_ancestor, fork_height },
+            (common_ancestor, None) => {
+                let (height, other_height) = (self.latest_locator_height(), other.latest_locator_height());
+                match height.cmp(&other_height) {
+                    Ordering::Equal => LocatorsRelationship::Equal { height },
+                    Ordering::Greater => {
+                        LocatorsRelationship::Ahead { common_ancestor, num_blocks: height - other_height }
+                    }
+                    Ordering::Less => {
+                        LocatorsRelationship::Behind { common_ancestor, num_blocks: other_height - height }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        CHECKPOINT_INTERVAL,
+        test_helpers::{sample_block_locators, sample_block_locators_with_fork},
+    };
+    use snarkvm::prelude::Field;
+
+    use indexmap::indexmap;
+
+    type CurrentNetwork = snarkvm::prelude::MainnetV0;
+
+    #[test]
+    fn test_compare_equal() {
+        for height in [0, 10, 100, CHECKPOINT_INTERVAL + 1] {
+            let locators = sample_block_locators(height);
+            assert_eq!(locators.compare(&locators), LocatorsRelationship::Equal { height });
+        }
+    }
+
+    #[test]
+    fn test_compare_ahead_and_behind() {
+        let (locators_10, locators_15) = (sample_block_locators(10), sample_block_locators(15));
+        assert_eq!(locators_15.compare(&locators_10), LocatorsRelationship::Ahead {
+            common_ancestor: 10,
+            num_blocks: 5
+        });
+        assert_eq!(locators_10.compare(&locators_15), LocatorsRelationship::Behind {
+            common_ancestor: 10,
+            num_blocks: 5
+        });
+
+        // Ensure the common ancestor falls back to the checkpoints, if the recent blocks do not overlap.
+        let (locators_low, locators_high) =
+            (sample_block_locators(500), sample_block_locators(CHECKPOINT_INTERVAL + 1));
+        assert_eq!(locators_high.compare(&locators_low), LocatorsRelationship::Ahead {
+            common_ancestor: 0,
+            num_blocks: CHECKPOINT_INTERVAL + 1 - 500
+        });
+    }
+
+    #[test]
+    fn test_compare_forked() {
+        let locators = sample_block_locators(20);
+        let forked_locators = sample_block_locators_with_fork(25, 13);
+        assert_eq!(locators.compare(&forked_locators), LocatorsRelationship::Forked {
+            common_ancestor: 12,
+            fork_height: 13
+        });
+        assert_eq!(forked_locators.compare(&locators), LocatorsRelationship::Forked {
+            common_ancestor: 12,
+            fork_height: 13
+        });
+    }
+
+    #[test]
+    fn test_compare_incomparable() {
+        let locators = sample_block_locators(0);
+        let hash = (-Field::<CurrentNetwork>::from_u32(1)).into();
+        let other_locators = BlockLocators::<CurrentNetwork>::new(indexmap![0 => hash], indexmap![0 => hash]).unwrap();
+        assert_eq!(locators.compare(&other_locators), LocatorsRelationship::Incomparable);
+    }
+}
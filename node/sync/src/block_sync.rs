@@ -14,11 +14,12 @@
 // limitations under the License.
 
 use crate::{
-    helpers::{PeerPair, PrepareSyncRequest, SyncRequest},
+    SyncJournal,
+    helpers::{PeerPair, PrepareSyncRequest, ReorgDepthExceeded, SyncRequest, SyncThroughput},
     locators::BlockLocators,
 };
 use snarkos_node_bft_ledger_service::LedgerService;
-use snarkos_node_router::messages::DataBlocks;
+use snarkos_node_router::{SyncProgress, SyncSummary, messages::DataBlocks};
 use snarkos_node_sync_communication_service::CommunicationService;
 use snarkos_node_sync_locators::{CHECKPOINT_INTERVAL, NUM_RECENT_BLOCKS};
 use snarkvm::prelude::{Network, block::Block};
@@ -35,7 +36,7 @@ use std::{
         Arc,
         atomic::{AtomicBool, AtomicU32, Ordering},
     },
-    time::Instant,
+    time::{Duration, Instant},
 };
 
 #[cfg(not(test))]
@@ -46,6 +47,7 @@ const EXTRA_REDUNDANCY_FACTOR: usize = REDUNDANCY_FACTOR * 3;
 const NUM_SYNC_CANDIDATE_PEERS: usize = REDUNDANCY_FACTOR * 5;
 
 const BLOCK_REQUEST_TIMEOUT_IN_SECS: u64 = 600; // 600 seconds
+const ANNOUNCED_BLOCK_REQUEST_TIMEOUT_IN_SECS: u64 = 10; // 10 seconds
 const MAX_BLOCK_REQUESTS: usize = 50; // 50 requests
 
 /// The maximum number of blocks tolerated before the primary is considered behind its peers.
@@ -109,8 +111,14 @@ pub struct BlockSync<N: Network> {
     is_block_synced: Arc<AtomicBool>,
     /// The number of blocks the peer is behind the greatest peer height.
     num_blocks_behind: Arc<AtomicU32>,
+    /// The recent samples of the ledger height, to estimate the throughput of the sync.
+    throughput: Arc<Mutex<SyncThroughput>>,
     /// The lock to guarantee advance_with_sync_blocks() is called only once at a time.
     advance_with_sync_blocks_lock: Arc<Mutex<()>>,
+    /// The journal of the block responses that were received and validated, but not yet applied, if any.
+    journal: Option<Arc<SyncJournal>>,
+    /// The maximum depth of a fork from the canonical ledger in the block locators of a peer, if it is guarded.
+    max_reorg_depth: Option<u32>,
 }
 
 impl<N: Network> BlockSync<N> {
@@ -126,16 +134,39 @@ impl<N: Network> BlockSync<N> {
             request_timestamps: Default::default(),
             is_block_synced: Default::default(),
             num_blocks_behind: Default::default(),
+            throughput: Default::default(),
             advance_with_sync_blocks_lock: Default::default(),
+            journal: None,
+            max_reorg_depth: None,
         }
     }
 
+    /// Sets the journal that stages the block responses, so that they are not requested again after a restart.
+    pub fn with_journal(mut self, journal: SyncJournal) -> Self {
+        self.journal = Some(Arc::new(journal));
+        self
+    }
+
+    /// Sets the maximum depth of a fork from the canonical ledger, that the block locators of a peer may have.
+    /// The block locators of a peer with a deeper fork are rejected, rather than followed. As this only concerns
+    /// the blocks that are already canonical, a node syncing from genesis follows the history of any peer.
+    pub fn with_max_reorg_depth(mut self, max_reorg_depth: u32) -> Self {
+        self.max_reorg_depth = Some(max_reorg_depth);
+        self
+    }
+
     /// Returns the block sync mode.
     #[inline]
     pub const fn mode(&self) -> BlockSyncMode {
         self.mode
     }
 
+    /// Returns the journal of the staged block responses, if any.
+    #[inline]
+    pub fn journal(&self) -> Option<&SyncJournal> {
+        self.journal.as_deref()
+    }
+
     /// Returns `true` if the node is synced up to the latest block (within the given tolerance).
     #[inline]
     pub fn is_block_synced(&self) -> bool {
@@ -147,6 +178,28 @@ impl<N: Network> BlockSync<N> {
     pub fn num_blocks_behind(&self) -> u32 {
         self.num_blocks_behind.load(Ordering::SeqCst)
     }
+
+    /// Returns the median of the latest block heights of the peers, if any peer sent its block locators.
+    ///
+    /// Note: Unlike the greatest peer height, which drives the block requests, the (lower) median is reported by at
+    /// least half of the peers, so a minority of peers claiming a height they do not have cannot inflate it.
+    pub fn median_peer_height(&self) -> Option<u32> {
+        let mut heights =
+            self.locators.read().values().map(|locators| locators.latest_locator_height()).collect::<Vec<_>>();
+        heights.sort_unstable();
+        heights.get(heights.len().saturating_sub(1) / 2).copied()
+    }
+
+    /// Returns the number of blocks the node is behind the median peer height.
+    pub fn num_blocks_behind_median(&self) -> u32 {
+        let median_peer_height = self.median_peer_height().unwrap_or_default();
+        median_peer_height.saturating_sub(self.canon.latest_block_height())
+    }
+
+    /// Returns the progress of the sync, i.e. the median peer height and the recent throughput.
+    pub fn progress(&self) -> SyncProgress {
+        SyncProgress { peer_height: self.median_peer_height(), blocks_per_sec: self.throughput.lock().blocks_per_sec() }
+    }
 }
 
 #[allow(dead_code)]
@@ -196,6 +249,10 @@ impl<N: Network> BlockSync<N> {
 
 impl<N: Network> BlockSync<N> {
     /// Returns the block locators.
+    ///
+    /// Note: The block locators are retrieved on async paths, so a transient failure to read a block hash is not
+    /// retried here. It is surfaced as a [`LedgerReadError`](snarkos_node_bft_ledger_service::LedgerReadError),
+    /// and the block locators are retrieved again on the next ping.
     #[inline]
     pub fn get_block_locators(&self) -> Result<BlockLocators<N>> {
         // Retrieve the latest block height.
@@ -251,59 +308,83 @@ impl<N: Network> BlockSync<N> {
             return;
         }
 
-        // Process the block requests.
-        'outer: for requests in block_requests.chunks(DataBlocks::<N>::MAXIMUM_NUMBER_OF_BLOCKS as usize) {
-            // Retrieve the starting height and the sync IPs.
-            let (start_height, max_num_sync_ips) = match requests.first() {
-                Some((height, (_, _, max_num_sync_ips))) => (*height, *max_num_sync_ips),
-                None => {
-                    warn!("Block sync failed - no block requests");
-                    break 'outer;
-                }
-            };
-
-            // Use a randomly sampled subset of the sync IPs.
-            let sync_ips: IndexSet<_> = sync_peers
-                .keys()
-                .copied()
-                .choose_multiple(&mut rand::thread_rng(), max_num_sync_ips)
-                .into_iter()
-                .collect();
-
-            // Calculate the end height.
-            let end_height = start_height.saturating_add(requests.len() as u32);
+        // Send the block requests to the sync peers.
+        self.send_block_requests(communication, &block_requests, &sync_peers).await;
+    }
 
-            // Insert the chunk of block requests.
-            for (height, (hash, previous_hash, _)) in requests.iter() {
-                // Insert the block request into the sync pool using the sync IPs from the last block request in the chunk.
-                if let Err(error) = self.insert_block_request(*height, (*hash, *previous_hash, sync_ips.clone())) {
-                    warn!("Block sync failed - {error}");
-                    // Break out of the loop.
-                    break 'outer;
-                }
-            }
+    /// Forces an immediate block sync against the given peer, bypassing the sync peer selection.
+    /// This uses the latest block locators advertised by the peer, and requests the missing blocks only from that peer.
+    /// Returns an error, without sending any block requests, if the peer has not advertised its block locators,
+    /// or if the peer is not ahead of this node.
+    pub async fn sync_from_peer<C: CommunicationService>(
+        &self,
+        communication: &C,
+        peer_ip: SocketAddr,
+    ) -> Result<SyncSummary> {
+        // Retrieve the block locators of the peer.
+        let Some(peer_locators) = self.locators.read().get(&peer_ip).cloned() else {
+            bail!("Unable to sync from '{peer_ip}' - the peer has not advertised its block locators yet")
+        };
+        // Retrieve the latest block height of the peer and of this node.
+        let peer_height = peer_locators.latest_locator_height();
+        let our_height = self.canon.latest_block_height();
+        // Ensure the peer is ahead of this node.
+        if peer_height <= our_height {
+            bail!(
+                "Unable to sync from '{peer_ip}' - the peer is not ahead (peer at {peer_height}, node at {our_height})"
+            )
+        }
 
-            /* Send the block request to the peers */
+        // Remove timed out block requests.
+        self.remove_timed_out_block_requests();
+        // Construct the block requests, using the peer as the sole sync peer.
+        let sync_peers = IndexMap::from([(peer_ip, peer_locators)]);
+        let block_requests = self.construct_requests(&sync_peers, peer_height);
+        // Send the block requests to the peer.
+        let num_blocks_requested = self.send_block_requests(communication, &block_requests, &sync_peers).await;
+        debug!("Forced a sync from '{peer_ip}' - requested {num_blocks_requested} blocks (at block {our_height})");
+
+        Ok(SyncSummary::new(peer_ip, our_height, peer_height, num_blocks_requested))
+    }
 
-            // Construct the message.
-            let message = C::prepare_block_request(start_height, end_height);
-            // Send the message to the peers.
-            for sync_ip in sync_ips {
-                let sender = communication.send(sync_ip, message.clone()).await;
-                // If the send fails for any peer, remove the block request from the sync pool.
-                if sender.is_none() {
-                    warn!("Failed to send block request to peer '{sync_ip}'");
-                    // Remove the entire block request from the sync pool.
-                    for height in start_height..end_height {
-                        self.remove_block_request(height);
-                    }
-                    // Break out of the loop.
-                    break 'outer;
-                }
-            }
-            // Sleep for 10 milliseconds to avoid triggering spam detection.
-            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    /// Requests the block at the given height from the peer that announced it, ahead of the block locators of the peer.
+    /// The announcement is only a hint: the block is only requested if it is the next block of this node,
+    /// and the response is checked against the latest canonical block hash, rather than an announced hash.
+    /// Returns `true` if the block was requested.
+    pub async fn request_announced_block<C: CommunicationService>(
+        &self,
+        communication: &C,
+        peer_ip: SocketAddr,
+        height: u32,
+    ) -> bool {
+        // Retrieve the latest block height of this node.
+        let latest_height = self.canon.latest_block_height();
+        // Ignore the announcement unless it is for the next block, as the block locators cover the other cases.
+        if height != latest_height.saturating_add(1) {
+            return false;
         }
+        // Retrieve the latest block hash, which must be the previous hash of the announced block.
+        let Ok(previous_hash) = self.canon.get_block_hash(latest_height) else {
+            return false;
+        };
+        // Insert the block request, unless the block was already requested.
+        if let Err(error) = self.insert_block_request(height, (None, Some(previous_hash), IndexSet::from([peer_ip]))) {
+            trace!("Skipping the block announced by '{peer_ip}' - {error}");
+            return false;
+        }
+        // Expire the request early, so that an unresponsive peer does not hold up the regular block sync.
+        let timeout = Duration::from_secs(BLOCK_REQUEST_TIMEOUT_IN_SECS - ANNOUNCED_BLOCK_REQUEST_TIMEOUT_IN_SECS);
+        if let Some(timestamp) = Instant::now().checked_sub(timeout) {
+            self.request_timestamps.write().insert(height, timestamp);
+        }
+        // Send the block request to the peer.
+        if communication.send(peer_ip, C::prepare_block_request(height, height + 1)).await.is_none() {
+            warn!("Failed to request the block announced by '{peer_ip}'");
+            self.remove_block_request(height);
+            return false;
+        }
+        debug!("Requested the block {height} announced by '{peer_ip}'");
+        true
     }
 
     /// Processes the block response from the given peer IP.
@@ -365,8 +446,64 @@ impl<N: Network> BlockSync<N> {
             }
             // Update the latest height.
             current_height = self.canon.latest_block_height();
+            // Remove the applied blocks from the sync journal.
+            if let Some(journal) = &self.journal {
+                journal.remove_through(current_height);
+            }
         }
     }
+
+    /// Applies the staged blocks of the sync journal that extend the canonical ledger, and returns their number.
+    /// This is meant to be called on startup, before any block requests are sent, so that the blocks that were
+    /// received before a restart are not requested again. The other staged blocks are discarded, as they no longer
+    /// extend the canonical ledger.
+    pub fn resume_from_journal(&self) -> u32 {
+        let Some(journal) = &self.journal else {
+            return 0;
+        };
+        // Acquire the lock to ensure the ledger is not advanced concurrently.
+        let _lock = self.advance_with_sync_blocks_lock.lock();
+
+        // Apply the staged blocks that extend the canonical ledger, in order.
+        let start_height = self.canon.latest_block_height();
+        let mut current_height = start_height;
+        loop {
+            let next_height = current_height + 1;
+            let block = match journal.load::<N>(next_height) {
+                Ok(Some(block)) => block,
+                Ok(None) => break,
+                Err(error) => {
+                    warn!("{error}");
+                    break;
+                }
+            };
+            // Ensure the staged block extends the canonical ledger.
+            if self.canon.get_block_hash(current_height).ok() != Some(block.previous_hash()) {
+                debug!("Discarding the staged block {next_height}, as it does not extend the ledger");
+                break;
+            }
+            // Check the next block.
+            if let Err(error) = self.canon.check_next_block(&block) {
+                warn!("The staged block ({next_height}) is invalid - {error}");
+                break;
+            }
+            // Attempt to advance to the next block.
+            if let Err(error) = self.canon.advance_to_next_block(&block) {
+                warn!("{error}");
+                break;
+            }
+            // Update the latest height.
+            current_height = self.canon.latest_block_height();
+        }
+
+        // Discard the staged blocks, as they were either applied, or no longer extend the canonical ledger.
+        journal.clear();
+        let num_applied = current_height.saturating_sub(start_height);
+        if num_applied > 0 {
+            info!("Applied {num_applied} staged block(s) from the sync journal (at block {current_height})");
+        }
+        num_applied
+    }
 }
 
 impl<N: Network> BlockSync<N> {
@@ -404,14 +541,28 @@ impl<N: Network> BlockSync<N> {
         // by iterating upwards, it also early-terminates malicious block locators at the *first* point
         // of bifurcation in their ledger history, which is a critical safety guarantee provided here.
         let mut ancestor = 0;
+        let mut fork_height = None;
         for (height, hash) in locators.clone().into_iter() {
             if let Ok(canon_hash) = self.canon.get_block_hash(height) {
                 match canon_hash == hash {
                     true => ancestor = height,
-                    false => break, // fork
+                    false => {
+                        // The history of the peer may conflict with the ledger right after the common ancestor.
+                        fork_height = Some(if height == 0 { 0 } else { ancestor + 1 });
+                        break; // fork
+                    }
                 }
             }
         }
+        // Reject the block locators, if following them would rewind the ledger deeper than the maximum reorg depth.
+        if let (Some(fork_height), Some(max_depth)) = (fork_height, self.max_reorg_depth) {
+            let depth = self.canon.latest_block_height().saturating_add(1).saturating_sub(fork_height);
+            if depth > max_depth {
+                // Remove the peer from the sync pool, so that no blocks are requested from it.
+                self.remove_peer(&peer_ip);
+                return Err(ReorgDepthExceeded { fork_height, depth, max_depth }.into());
+            }
+        }
         // Update the common ancestor entry for this node.
         self.common_ancestors.write().insert(PeerPair(DUMMY_SELF_IP, peer_ip), ancestor);
 
@@ -423,15 +574,7 @@ impl<N: Network> BlockSync<N> {
                 continue;
             }
             // Compute the common ancestor with the other peer.
-            let mut ancestor = 0;
-            for (height, hash) in other_locators.clone().into_iter() {
-                if let Some(expected_hash) = locators.get_hash(height) {
-                    match expected_hash == hash {
-                        true => ancestor = height,
-                        false => break, // fork
-                    }
-                }
-            }
+            let (ancestor, _) = locators.find_common_ancestor(other_locators);
             common_ancestors.insert(PeerPair(peer_ip, *other_ip), ancestor);
         }
 
@@ -474,6 +617,75 @@ impl<N: Network> BlockSync<N> {
         }
     }
 
+    /// Sends the given block requests to the sync peers, and returns the number of blocks requested.
+    async fn send_block_requests<C: CommunicationService>(
+        &self,
+        communication: &C,
+        block_requests: &[(u32, PrepareSyncRequest<N>)],
+        sync_peers: &IndexMap<SocketAddr, BlockLocators<N>>,
+    ) -> usize {
+        // Track the number of blocks requested.
+        let mut num_blocks_requested = 0;
+
+        // Process the block requests.
+        'outer: for requests in block_requests.chunks(DataBlocks::<N>::MAXIMUM_NUMBER_OF_BLOCKS as usize) {
+            // Retrieve the starting height and the sync IPs.
+            let (start_height, max_num_sync_ips) = match requests.first() {
+                Some((height, (_, _, max_num_sync_ips))) => (*height, *max_num_sync_ips),
+                None => {
+                    warn!("Block sync failed - no block requests");
+                    break 'outer;
+                }
+            };
+
+            // Use a randomly sampled subset of the sync IPs.
+            let sync_ips: IndexSet<_> = sync_peers
+                .keys()
+                .copied()
+                .choose_multiple(&mut rand::thread_rng(), max_num_sync_ips)
+                .into_iter()
+                .collect();
+
+            // Calculate the end height.
+            let end_height = start_height.saturating_add(requests.len() as u32);
+
+            // Insert the chunk of block requests.
+            for (height, (hash, previous_hash, _)) in requests.iter() {
+                // Insert the block request into the sync pool using the sync IPs from the last block request in the chunk.
+                if let Err(error) = self.insert_block_request(*height, (*hash, *previous_hash, sync_ips.clone())) {
+                    warn!("Block sync failed - {error}");
+                    // Break out of the loop.
+                    break 'outer;
+                }
+            }
+
+            /* Send the block request to the peers */
+
+            // Construct the message.
+            let message = C::prepare_block_request(start_height, end_height);
+            // Send the message to the peers.
+            for sync_ip in sync_ips {
+                let sender = communication.send(sync_ip, message.clone()).await;
+                // If the send fails for any peer, remove the block request from the sync pool.
+                if sender.is_none() {
+                    warn!("Failed to send block request to peer '{sync_ip}'");
+                    // Remove the entire block request from the sync pool.
+                    for height in start_height..end_height {
+                        self.remove_block_request(height);
+                    }
+                    // Break out of the loop.
+                    break 'outer;
+                }
+            }
+            // Update the number of blocks requested.
+            num_blocks_requested += requests.len();
+            // Sleep for 10 milliseconds to avoid triggering spam detection.
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+
+        num_blocks_requested
+    }
+
     /// Updates the state of `is_block_synced` for the sync module.
     fn update_is_block_synced(&self, greatest_peer_height: u32, max_blocks_behind: u32) {
         // Retrieve the latest block height.
@@ -487,6 +699,8 @@ impl<N: Network> BlockSync<N> {
         let is_synced = num_blocks_behind <= max_blocks_behind;
         // Update the num blocks behind.
         self.num_blocks_behind.store(num_blocks_behind, Ordering::SeqCst);
+        // Sample the ledger height, to estimate the throughput of the sync.
+        self.throughput.lock().record(Instant::now(), canon_height);
         // Update the sync status.
         self.is_block_synced.store(is_synced, Ordering::SeqCst);
         // Update the `IS_SYNCED` metric.
@@ -541,6 +755,16 @@ impl<N: Network> BlockSync<N> {
                 bail!("Candidate block {height} from '{peer_ip}' is malformed");
             }
         }
+        // Drop the write lock on the responses map.
+        drop(responses);
+
+        // Stage the candidate block in the sync journal, so that it is not requested again after a restart.
+        // Note: The block is only queued here, as the sync journal writes its files on a dedicated thread.
+        if let Some(journal) = &self.journal {
+            if let Err(error) = journal.stage(&block) {
+                warn!("Unable to stage block {height} in the sync journal - {error}");
+            }
+        }
 
         Ok(())
     }
@@ -943,13 +1167,22 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::locators::{
-        CHECKPOINT_INTERVAL,
-        NUM_RECENT_BLOCKS,
-        test_helpers::{sample_block_locators, sample_block_locators_with_fork},
+    use crate::{
+        MAX_SYNC_JOURNAL_BYTES,
+        locators::{
+            CHECKPOINT_INTERVAL,
+            LocatorsRelationship,
+            NUM_RECENT_BLOCKS,
+            test_helpers::{sample_block_locators, sample_block_locators_with_fork},
+        },
+    };
+    use snarkos_node_bft_ledger_service::{
+        CoreLedgerService,
+        LedgerReadError,
+        MockLedgerService,
+        test_helpers::{sample_genesis_block, sample_ledger_from_genesis},
     };
-    use snarkos_node_bft_ledger_service::MockLedgerService;
-    use snarkvm::prelude::{Field, TestRng};
+    use snarkvm::prelude::{Field, FromBytes, TestRng};
 
     use indexmap::{IndexSet, indexset};
     use snarkvm::ledger::committee::Committee;
@@ -957,6 +1190,32 @@ mod tests {
 
     type CurrentNetwork = snarkvm::prelude::MainnetV0;
 
+    /// A communication service that records the block requests it sends.
+    #[derive(Default)]
+    struct SampleCommunication {
+        sent: Mutex<Vec<(SocketAddr, (u32, u32))>>,
+    }
+
+    #[async_trait::async_trait]
+    impl CommunicationService for SampleCommunication {
+        type Message = (u32, u32);
+
+        fn prepare_block_request(start: u32, end: u32) -> Self::Message {
+            (start, end)
+        }
+
+        async fn send(
+            &self,
+            peer_ip: SocketAddr,
+            message: Self::Message,
+        ) -> Option<tokio::sync::oneshot::Receiver<std::io::Result<()>>> {
+            self.sent.lock().push((peer_ip, message));
+            let (tx, rx) = tokio::sync::oneshot::channel();
+            tx.send(Ok(())).ok();
+            Some(rx)
+        }
+    }
+
     /// Returns the peer IP for the sync pool.
     fn sample_peer_ip(id: u16) -> SocketAddr {
         assert_ne!(id, 0, "The peer ID must not be 0 (reserved for local IP in testing)");
@@ -1061,6 +1320,20 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_get_block_locators_surfaces_transient_failures() {
+        let ledger = Arc::new(sample_ledger_service(20));
+        let sync = BlockSync::<CurrentNetwork>::new(BlockSyncMode::Router, ledger.clone());
+
+        // Ensure a transient failure is surfaced as such, without blocking on a retry.
+        ledger.fail_transiently(1);
+        let error = sync.get_block_locators().unwrap_err();
+        assert!(LedgerReadError::is_transient(&error));
+
+        // Ensure the block locators are built on the next attempt, once the failure has passed.
+        assert_eq!(sync.get_block_locators().unwrap().latest_locator_height(), 20);
+    }
+
     #[test]
     fn test_prepare_block_requests() {
         for num_peers in 0..111 {
@@ -1319,6 +1592,103 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_update_peer_locators_with_max_reorg_depth() {
+        let sync = sample_sync_at_height(20).with_max_reorg_depth(1);
+        let (peer_1, peer_2, peer_3) = (sample_peer_ip(1), sample_peer_ip(2), sample_peer_ip(3));
+
+        // Ensure the block locators that extend the ledger are followed.
+        sync.update_peer_locators(peer_1, sample_block_locators(30)).unwrap();
+        // Ensure the block locators that only conflict with the latest block are tolerated.
+        sync.update_peer_locators(peer_2, sample_block_locators_with_fork(25, 20)).unwrap();
+        assert_eq!(sync.locators.read().len(), 2);
+
+        // Ensure a deep alternative branch is rejected, and the peer is removed from the sync pool.
+        let error = sync.update_peer_locators(peer_3, sample_block_locators_with_fork(30, 10)).unwrap_err();
+        let rejection = ReorgDepthExceeded { fork_height: 10, depth: 11, max_depth: 1 };
+        assert_eq!(ReorgDepthExceeded::of(&error), Some(&rejection));
+        assert!(!sync.locators.read().contains_key(&peer_3));
+        // Ensure the same block locators are rejected again, rather than skipped as unchanged.
+        assert!(sync.update_peer_locators(peer_3, sample_block_locators_with_fork(30, 10)).is_err());
+
+        // Ensure a shallow fork is rejected under a stricter depth.
+        let sync = sample_sync_at_height(20).with_max_reorg_depth(0);
+        let error = sync.update_peer_locators(peer_2, sample_block_locators_with_fork(25, 20)).unwrap_err();
+        assert_eq!(ReorgDepthExceeded::of(&error).map(|rejection| rejection.depth), Some(1));
+
+        // Ensure a node syncing from genesis follows the history of any peer.
+        let sync = sample_sync_at_height(0).with_max_reorg_depth(0);
+        sync.update_peer_locators(peer_3, sample_block_locators_with_fork(30, 10)).unwrap();
+
+        // Ensure the depth is not guarded by default.
+        let sync = sample_sync_at_height(20);
+        sync.update_peer_locators(peer_3, sample_block_locators_with_fork(30, 10)).unwrap();
+    }
+
+    #[test]
+    fn test_compare_locators_matches_sync() {
+        let sync = sample_sync_at_height(10);
+        let locators = sync.get_block_locators().unwrap();
+
+        // Add a peer that is behind.
+        let peer_1 = sample_peer_ip(1);
+        sync.update_peer_locators(peer_1, sample_block_locators(5)).unwrap();
+        assert_eq!(locators.compare(&sample_block_locators(5)), LocatorsRelationship::Ahead {
+            common_ancestor: 5,
+            num_blocks: 5
+        });
+        assert_eq!(sync.get_common_ancestor(DUMMY_SELF_IP, peer_1), Some(5));
+        // Ensure the node does not sync from a peer that is behind.
+        assert!(sync.find_sync_peers().is_none());
+
+        // Add a peer that is ahead.
+        let peer_2 = sample_peer_ip(2);
+        sync.update_peer_locators(peer_2, sample_block_locators(20)).unwrap();
+        assert_eq!(locators.compare(&sample_block_locators(20)), LocatorsRelationship::Behind {
+            common_ancestor: 10,
+            num_blocks: 10
+        });
+        assert_eq!(sync.get_common_ancestor(DUMMY_SELF_IP, peer_2), Some(10));
+        // Ensure the node syncs from a peer that is ahead.
+        let (sync_peers, _) = sync.find_sync_peers().unwrap();
+        assert!(sync_peers.contains_key(&peer_2));
+
+        // Add a peer that is forked.
+        let peer_3 = sample_peer_ip(3);
+        sync.update_peer_locators(peer_3, sample_block_locators_with_fork(20, 8)).unwrap();
+        assert_eq!(locators.compare(&sample_block_locators_with_fork(20, 8)), LocatorsRelationship::Forked {
+            common_ancestor: 7,
+            fork_height: 8
+        });
+        assert_eq!(sync.get_common_ancestor(DUMMY_SELF_IP, peer_3), Some(7));
+        // Ensure the peer-to-peer comparison matches the sync module.
+        let (ancestor, fork_height) =
+            sample_block_locators(20).find_common_ancestor(&sample_block_locators_with_fork(20, 8));
+        assert_eq!((ancestor, fork_height), (7, Some(8)));
+        assert_eq!(sync.get_common_ancestor(peer_3, peer_2), Some(ancestor));
+    }
+
+    #[test]
+    fn test_num_blocks_behind_median() {
+        let sync = sample_sync_at_height(10);
+        assert_eq!(sync.median_peer_height(), None);
+        assert_eq!(sync.num_blocks_behind_median(), 0);
+
+        // Ensure a single peer claiming a huge height does not put the node behind the median.
+        sync.update_peer_locators(sample_peer_ip(1), sample_block_locators(10)).unwrap();
+        sync.update_peer_locators(sample_peer_ip(2), sample_block_locators(12)).unwrap();
+        sync.update_peer_locators(sample_peer_ip(3), sample_block_locators(1_000_000)).unwrap();
+        assert_eq!(sync.median_peer_height(), Some(12));
+        assert_eq!(sync.num_blocks_behind_median(), 2);
+        assert_eq!(sync.progress().peer_height, Some(12));
+
+        // Ensure the node is behind once half of the peers are ahead.
+        sync.update_peer_locators(sample_peer_ip(4), sample_block_locators(500)).unwrap();
+        sync.update_peer_locators(sample_peer_ip(5), sample_block_locators(600)).unwrap();
+        assert_eq!(sync.median_peer_height(), Some(500));
+        assert_eq!(sync.num_blocks_behind_median(), 490);
+    }
+
     #[test]
     fn test_remove_peer() {
         let sync = sample_sync_at_height(0);
@@ -1409,4 +1779,145 @@ mod tests {
     }
 
     // TODO: duplicate responses, ensure fails.
+
+    #[tokio::test]
+    async fn test_sync_from_peer() {
+        let sync = sample_sync_at_height(0);
+        let communication = SampleCommunication::default();
+
+        // Insert a peer that is ahead, and another that is behind.
+        let peer1_ip = sample_peer_ip(1);
+        let peer2_ip = sample_peer_ip(2);
+        sync.update_peer_locators(peer1_ip, sample_block_locators(10)).unwrap();
+        sync.update_peer_locators(peer2_ip, sample_block_locators(0)).unwrap();
+
+        // Force a sync from the peer that is ahead.
+        let summary = sync.sync_from_peer(&communication, peer1_ip).await.unwrap();
+        assert_eq!(summary.our_height(), 0);
+        assert_eq!(summary.peer_height(), 10);
+        assert_eq!(summary.num_blocks_requested(), 10);
+
+        // Ensure the block requests were only sent to the given peer.
+        assert_eq!(*communication.sent.lock(), vec![(peer1_ip, (1, 6)), (peer1_ip, (6, 11))]);
+        for height in 1..=10 {
+            let (_, _, sync_ips) = sync.get_block_request(height).unwrap();
+            assert_eq!(sync_ips, indexset![peer1_ip]);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sync_from_peer_fails() {
+        let sync = sample_sync_at_height(5);
+        let communication = SampleCommunication::default();
+
+        // Ensure a peer without block locators is rejected.
+        let peer1_ip = sample_peer_ip(1);
+        assert!(sync.sync_from_peer(&communication, peer1_ip).await.is_err());
+
+        // Ensure a peer that is not ahead is rejected.
+        sync.update_peer_locators(peer1_ip, sample_block_locators(5)).unwrap();
+        assert!(sync.sync_from_peer(&communication, peer1_ip).await.is_err());
+
+        // Ensure no block requests were sent or inserted.
+        assert!(communication.sent.lock().is_empty());
+        assert!(sync.requests.read().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_request_announced_block() {
+        let sync = sample_sync_at_height(5);
+        let communication = SampleCommunication::default();
+        let peer1_ip = sample_peer_ip(1);
+        let peer2_ip = sample_peer_ip(2);
+
+        // Ensure the announcements of blocks other than the next one are ignored.
+        for height in [0, 5, 7] {
+            assert!(!sync.request_announced_block(&communication, peer1_ip, height).await);
+        }
+
+        // Ensure the next block is requested from the announcing peer only, even without its block locators.
+        assert!(sync.request_announced_block(&communication, peer1_ip, 6).await);
+        assert_eq!(*communication.sent.lock(), vec![(peer1_ip, (6, 7))]);
+        let (hash, previous_hash, sync_ips) = sync.get_block_request(6).unwrap();
+        assert_eq!(hash, None);
+        assert_eq!(previous_hash, Some(sync.canon.get_block_hash(5).unwrap()));
+        assert_eq!(sync_ips, indexset![peer1_ip]);
+
+        // Ensure the same block is not requested twice.
+        assert!(!sync.request_announced_block(&communication, peer2_ip, 6).await);
+        assert_eq!(communication.sent.lock().len(), 1);
+
+        // Ensure the request expires early, if the peer does not respond.
+        let timestamp = sync.get_block_request_timestamp(6).unwrap();
+        assert!(
+            timestamp.elapsed().as_secs() >= BLOCK_REQUEST_TIMEOUT_IN_SECS - ANNOUNCED_BLOCK_REQUEST_TIMEOUT_IN_SECS
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resume_from_journal() {
+        let rng = &mut TestRng::default();
+
+        // Initialize the ledger of a peer that is 5 blocks ahead, and the ledger of the node at genesis.
+        let (private_key, genesis) = sample_genesis_block::<CurrentNetwork, _>(rng);
+        let peer_ledger = sample_ledger_from_genesis(&private_key, &genesis, 5, rng);
+        let ledger = sample_ledger_from_genesis(&private_key, &genesis, 0, rng);
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let peer_service = Arc::new(CoreLedgerService::new(peer_ledger.clone(), shutdown.clone()));
+        let peer_locators = BlockSync::new(BlockSyncMode::Router, peer_service).get_block_locators().unwrap();
+        let service: Arc<dyn LedgerService<CurrentNetwork>> =
+            Arc::new(CoreLedgerService::new(ledger.clone(), shutdown));
+        let path = std::env::temp_dir().join(format!("snarkos-block-sync-journal-{}", rand::random::<u64>()));
+        let open_journal = || SyncJournal::open(path.clone(), MAX_SYNC_JOURNAL_BYTES).unwrap();
+        let peer_ip = sample_peer_ip(1);
+
+        // Request the blocks from the peer, and stage its block responses without applying them.
+        let sync = BlockSync::new(BlockSyncMode::Router, service.clone()).with_journal(open_journal());
+        let communication = SampleCommunication::default();
+        sync.update_peer_locators(peer_ip, peer_locators.clone()).unwrap();
+        sync.sync_from_peer(&communication, peer_ip).await.unwrap();
+        let blocks = (1..=5).map(|height| peer_ledger.get_block(height).unwrap()).collect();
+        sync.process_block_response(peer_ip, blocks).unwrap();
+        assert_eq!(sync.journal().unwrap().heights(), vec![1, 2, 3, 4, 5]);
+        assert_eq!(ledger.latest_height(), 0);
+        let num_requests = communication.sent.lock().len();
+        assert!(num_requests > 0);
+
+        // Restart the node, once the staged blocks are written, and ensure the staged blocks are applied.
+        drop(sync);
+        let sync = BlockSync::new(BlockSyncMode::Router, service).with_journal(open_journal());
+        assert_eq!(sync.resume_from_journal(), 5);
+        assert_eq!(ledger.latest_hash(), peer_ledger.latest_hash());
+        assert!(sync.journal().unwrap().is_empty());
+
+        // Ensure the blocks are not requested again.
+        sync.update_peer_locators(peer_ip, peer_locators).unwrap();
+        sync.try_block_sync(&communication).await;
+        assert_eq!(communication.sent.lock().len(), num_requests);
+        drop(sync);
+
+        std::fs::remove_dir_all(path).unwrap();
+    }
+
+    #[test]
+    fn test_resume_from_journal_discards_stale_blocks() {
+        let sync = sample_sync_at_height(5);
+        let path = std::env::temp_dir().join(format!("snarkos-block-sync-journal-{}", rand::random::<u64>()));
+
+        // Stage a block that does not extend the canonical ledger, before a restart.
+        let journal = SyncJournal::open(path.clone(), MAX_SYNC_JOURNAL_BYTES).unwrap();
+        let block = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        journal.stage(&block).unwrap();
+        drop(journal);
+        let sync = sync.with_journal(SyncJournal::open(path.clone(), MAX_SYNC_JOURNAL_BYTES).unwrap());
+
+        // Ensure the stale block is discarded, without advancing the ledger.
+        assert_eq!(sync.resume_from_journal(), 0);
+        assert_eq!(sync.canon.latest_block_height(), 5);
+        assert!(sync.journal().unwrap().is_empty());
+        drop(sync);
+        assert_eq!(std::fs::read_dir(&path).unwrap().count(), 0);
+
+        std::fs::remove_dir_all(path).unwrap();
+    }
 }
@@ -13,6 +13,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod reorg_guard;
+pub use reorg_guard::*;
+
+mod throughput;
+pub use throughput::*;
+
 use snarkvm::prelude::Network;
 
 use core::hash::Hash;
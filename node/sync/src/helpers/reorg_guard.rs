@@ -0,0 +1,51 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+/// The default maximum depth of a fork from the canonical ledger, that the history presented by a peer may have.
+/// Note: A block is final once it is certified by a quorum of the committee, so an honest peer never presents a
+/// history that rewinds the ledger; the one block of leeway tolerates a peer that disagrees on the latest block.
+pub const DEFAULT_MAX_REORG_DEPTH: u32 = 1;
+
+/// The rejection of a history that forks from the canonical ledger deeper than the maximum reorg depth.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReorgDepthExceeded {
+    /// The first height at which the history may conflict with the canonical ledger.
+    pub fork_height: u32,
+    /// The number of canonical blocks the history would rewind.
+    pub depth: u32,
+    /// The maximum reorg depth.
+    pub max_depth: u32,
+}
+
+impl ReorgDepthExceeded {
+    /// Returns the rejection the given error originates from, if any.
+    pub fn of(error: &anyhow::Error) -> Option<&Self> {
+        error.downcast_ref::<Self>()
+    }
+}
+
+impl fmt::Display for ReorgDepthExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "The history forks from the ledger at block {}, which rewinds {} blocks (the maximum reorg depth is {})",
+            self.fork_height, self.depth, self.max_depth
+        )
+    }
+}
+
+impl std::error::Error for ReorgDepthExceeded {}
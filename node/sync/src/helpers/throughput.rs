@@ -0,0 +1,92 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+/// The number of (timestamp, height) samples retained to estimate the throughput of the block sync.
+pub const NUM_THROUGHPUT_SAMPLES: usize = 16;
+/// The minimum interval between two samples, so that the retained samples span a meaningful window.
+const MIN_SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The ring buffer of the recent (timestamp, ledger height) samples of the block sync, to estimate its throughput.
+#[derive(Debug, Default)]
+pub struct SyncThroughput {
+    /// The samples, from the oldest to the most recent.
+    samples: VecDeque<(Instant, u32)>,
+}
+
+impl SyncThroughput {
+    /// Records the ledger height at the given instant, unless the previous sample is too recent.
+    ///
+    /// The samples taken before the ledger height decreased are discarded, as they no longer describe its progress.
+    pub fn record(&mut self, now: Instant, height: u32) {
+        match self.samples.back() {
+            Some((_, last_height)) if height < *last_height => self.samples.clear(),
+            Some((last_timestamp, _)) if now.saturating_duration_since(*last_timestamp) < MIN_SAMPLE_INTERVAL => return,
+            _ => (),
+        }
+        if self.samples.len() == NUM_THROUGHPUT_SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back((now, height));
+    }
+
+    /// Returns the number of blocks the ledger advanced by per second over the retained samples,
+    /// or `None` if fewer than two samples were taken.
+    pub fn blocks_per_sec(&self) -> Option<f64> {
+        let ((first_timestamp, first_height), (last_timestamp, last_height)) =
+            (self.samples.front()?, self.samples.back()?);
+        let elapsed = last_timestamp.saturating_duration_since(*first_timestamp).as_secs_f64();
+        (elapsed > 0.0).then(|| last_height.saturating_sub(*first_height) as f64 / elapsed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sync_throughput() {
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+        let mut throughput = SyncThroughput::default();
+        assert_eq!(throughput.blocks_per_sec(), None);
+
+        // Ensure a single sample does not yield an estimate.
+        throughput.record(at(0), 100);
+        assert_eq!(throughput.blocks_per_sec(), None);
+        // Ensure the samples taken too soon after the previous one are skipped.
+        throughput.record(start + Duration::from_millis(500), 200);
+        assert_eq!(throughput.blocks_per_sec(), None);
+        throughput.record(at(2), 120);
+        assert_eq!(throughput.blocks_per_sec(), Some(10.0));
+
+        // Ensure the oldest samples are evicted once the buffer is full.
+        for i in 1..NUM_THROUGHPUT_SAMPLES as u64 {
+            throughput.record(at(2 + i), 120 + 5 * i as u32);
+        }
+        assert_eq!(throughput.samples.len(), NUM_THROUGHPUT_SAMPLES);
+        assert_eq!(throughput.blocks_per_sec(), Some(5.0));
+
+        // Ensure the samples are discarded once the ledger height decreases.
+        throughput.record(at(100), 50);
+        assert_eq!(throughput.blocks_per_sec(), None);
+        throughput.record(at(104), 70);
+        assert_eq!(throughput.blocks_per_sec(), Some(5.0));
+    }
+}
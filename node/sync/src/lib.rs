@@ -21,8 +21,14 @@ extern crate tracing;
 pub use snarkos_node_sync_communication_service as communication_service;
 pub use snarkos_node_sync_locators as locators;
 
+// The progress of the block sync is reported through the router, so its type is defined there.
+pub use snarkos_node_router::SyncProgress;
+
 mod block_sync;
 pub use block_sync::*;
 
 mod helpers;
 pub use helpers::*;
+
+mod sync_journal;
+pub use sync_journal::*;
@@ -0,0 +1,402 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snarkvm::prelude::{FromBytes, Network, ToBytes, block::Block};
+
+use aleo_std::{StorageMode, aleo_ledger_dir};
+use anyhow::{Result, bail, ensure};
+use indexmap::IndexMap;
+use parking_lot::Mutex;
+use std::{
+    fs,
+    io,
+    path::{Path, PathBuf},
+    thread::JoinHandle,
+};
+use tokio::sync::mpsc;
+
+/// The version of the serialization of the staged blocks.
+pub const SYNC_JOURNAL_VERSION: u8 = 1;
+/// The default maximum number of bytes of the staged blocks, beyond which the highest staged blocks are evicted.
+pub const MAX_SYNC_JOURNAL_BYTES: u64 = 1 << 30; // 1 GiB
+
+/// The maximum number of queued writes of the sync journal, beyond which blocks are not staged.
+const SYNC_JOURNAL_CHANNEL_CAPACITY: usize = 256;
+
+/// The file extension of a staged block.
+const STAGED_BLOCK_EXTENSION: &str = "block";
+/// The file extension of a staged block that is being written.
+const PARTIAL_BLOCK_EXTENSION: &str = "partial";
+
+/// Returns the path of the sync journal, next to the ledger of the given network and storage mode.
+pub fn sync_journal_path(network: u16, storage_mode: &StorageMode) -> PathBuf {
+    // Obtain the path to the ledger.
+    let mut path = aleo_ledger_dir(network, storage_mode.clone());
+    // Name the sync journal after the ledger, so that every ledger has its own sync journal.
+    let name = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+    path.set_file_name(format!("{name}-sync-journal"));
+    path
+}
+
+/// The journal of the block responses that were received and validated, but not yet applied to the ledger.
+///
+/// Each staged block is stored in its own file, keyed by its height, so that a node that is restarted
+/// during a sync applies the staged blocks instead of requesting them again. The staged blocks are
+/// bounded in size, and the highest staged blocks are evicted first, as the lowest ones are needed to resume.
+///
+/// The files are written and removed by a dedicated writer thread, in the order they are queued, so that
+/// staging a block response never blocks the sync on the filesystem. A block is not staged if the writer
+/// falls behind, which only means it is requested again after a restart.
+#[derive(Debug)]
+pub struct SyncJournal {
+    /// The directory of the staged blocks.
+    path: PathBuf,
+    /// The maximum number of bytes of the staged blocks.
+    max_bytes: u64,
+    /// The map of the staged block heights to their number of bytes, including the queued writes.
+    staged: Mutex<IndexMap<u32, u64>>,
+    /// The sender of the writes to the writer thread, which is dropped to stop the writer.
+    sender: Option<mpsc::Sender<JournalWrite>>,
+    /// The handle of the writer thread, which is joined on drop, so that the queued writes are not lost.
+    writer: Option<JoinHandle<()>>,
+}
+
+/// A write of the sync journal, which is applied by the writer thread.
+#[derive(Debug)]
+enum JournalWrite {
+    /// Writes the serialized block at the given height.
+    Stage(u32, Vec<u8>),
+    /// Removes the staged block at the given height.
+    Remove(u32),
+}
+
+impl SyncJournal {
+    /// Opens the sync journal in the given directory, with the staged blocks of a previous run, if any.
+    pub fn open(path: PathBuf, max_bytes: u64) -> Result<Self> {
+        // Ensure the directory of the staged blocks exists.
+        fs::create_dir_all(&path)?;
+
+        // Collect the staged blocks, and remove the blocks that were only partially written.
+        let mut staged = Vec::new();
+        for entry in fs::read_dir(&path)? {
+            let file_path = entry?.path();
+            let extension = file_path.extension().and_then(|extension| extension.to_str());
+            let height = file_path.file_stem().and_then(|stem| stem.to_str()).and_then(|stem| stem.parse().ok());
+            match (extension, height) {
+                (Some(STAGED_BLOCK_EXTENSION), Some(height)) => staged.push((height, fs::metadata(&file_path)?.len())),
+                (Some(PARTIAL_BLOCK_EXTENSION), _) => fs::remove_file(&file_path)?,
+                _ => {}
+            }
+        }
+        // Order the staged blocks of the previous run by height, as their staging order is not recorded.
+        staged.sort_unstable();
+
+        // Spawn the writer on a dedicated thread, as its I/O is blocking.
+        let (sender, receiver) = mpsc::channel(SYNC_JOURNAL_CHANNEL_CAPACITY);
+        let writer_path = path.clone();
+        let writer =
+            std::thread::Builder::new().name("sync-journal".into()).spawn(move || run_writer(writer_path, receiver))?;
+
+        let journal = Self {
+            path,
+            max_bytes,
+            staged: Mutex::new(staged.into_iter().collect()),
+            sender: Some(sender),
+            writer: Some(writer),
+        };
+        if !journal.is_empty() {
+            info!("Found {} staged block(s) in the sync journal at {}", journal.len(), journal.path.display());
+        }
+        // Ensure the staged blocks are within the maximum number of bytes, in case it was lowered.
+        journal.evict_highest();
+        Ok(journal)
+    }
+
+    /// Returns the directory of the staged blocks.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the maximum number of bytes of the staged blocks.
+    pub const fn max_bytes(&self) -> u64 {
+        self.max_bytes
+    }
+
+    /// Returns the number of staged blocks.
+    pub fn len(&self) -> usize {
+        self.staged.lock().len()
+    }
+
+    /// Returns `true` if there are no staged blocks.
+    pub fn is_empty(&self) -> bool {
+        self.staged.lock().is_empty()
+    }
+
+    /// Returns the number of bytes of the staged blocks.
+    pub fn num_bytes(&self) -> u64 {
+        self.staged.lock().values().sum()
+    }
+
+    /// Returns `true` if a block is staged at the given height.
+    pub fn contains(&self, height: u32) -> bool {
+        self.staged.lock().contains_key(&height)
+    }
+
+    /// Returns the heights of the staged blocks, in increasing order.
+    pub fn heights(&self) -> Vec<u32> {
+        let mut heights: Vec<_> = self.staged.lock().keys().copied().collect();
+        heights.sort_unstable();
+        heights
+    }
+
+    /// Queues the given block to be staged, evicting the highest staged blocks if the maximum number of bytes
+    /// is exceeded. A block that is already staged at the same height is not written again.
+    pub fn stage<N: Network>(&self, block: &Block<N>) -> Result<()> {
+        let height = block.height();
+        if self.contains(height) {
+            return Ok(());
+        }
+        // Serialize the block, with the version and height of the staged block.
+        let mut bytes = Vec::new();
+        SYNC_JOURNAL_VERSION.write_le(&mut bytes)?;
+        height.write_le(&mut bytes)?;
+        block.write_le(&mut bytes)?;
+        let num_bytes = bytes.len() as u64;
+        // Ensure a single block does not exceed the maximum number of bytes.
+        ensure!(num_bytes <= self.max_bytes, "Block {height} exceeds the size of the sync journal ({num_bytes} bytes)");
+
+        // Queue the block for the writer, without waiting for it to be written.
+        self.staged.lock().insert(height, num_bytes);
+        if !self.send(JournalWrite::Stage(height, bytes)) {
+            self.staged.lock().shift_remove(&height);
+            bail!("Block {height} was not staged, as the sync journal writer is behind");
+        }
+
+        // Evict the highest staged blocks, if the maximum number of bytes is exceeded.
+        self.evict_highest();
+        Ok(())
+    }
+
+    /// Returns the staged block at the given height, if it exists.
+    /// A staged block that cannot be read, or that was staged by another version, is removed.
+    /// Note: This reads the files of the previous run, and is meant to be called on startup, before any staging.
+    pub fn load<N: Network>(&self, height: u32) -> Result<Option<Block<N>>> {
+        if !self.contains(height) {
+            return Ok(None);
+        }
+        match self.read_block(height) {
+            Ok(block) => Ok(Some(block)),
+            Err(error) => {
+                self.remove(height);
+                bail!("Removed the unreadable staged block {height} - {error}")
+            }
+        }
+    }
+
+    /// Queues the removal of the staged block at the given height, if it exists.
+    /// If the writer is behind, the file is left behind, and discarded on the next restart.
+    pub fn remove(&self, height: u32) {
+        if self.staged.lock().shift_remove(&height).is_some() && !self.send(JournalWrite::Remove(height)) {
+            trace!("The removal of the staged block {height} was skipped, as the sync journal writer is behind");
+        }
+    }
+
+    /// Removes the staged blocks at or below the given height, as they were applied to the ledger.
+    pub fn remove_through(&self, height: u32) {
+        let heights: Vec<_> = self.staged.lock().keys().copied().filter(|staged| *staged <= height).collect();
+        heights.into_iter().for_each(|height| self.remove(height))
+    }
+
+    /// Removes all of the staged blocks.
+    pub fn clear(&self) {
+        let heights: Vec<_> = self.staged.lock().keys().copied().collect();
+        heights.into_iter().for_each(|height| self.remove(height))
+    }
+
+    /// Queues the given write for the writer, and returns `false` if the writer is behind.
+    fn send(&self, write: JournalWrite) -> bool {
+        self.sender.as_ref().is_some_and(|sender| sender.try_send(write).is_ok())
+    }
+
+    /// Reads the staged block at the given height from its file.
+    fn read_block<N: Network>(&self, height: u32) -> Result<Block<N>> {
+        let bytes = fs::read(file_path(&self.path, height, STAGED_BLOCK_EXTENSION))?;
+        let mut reader = &bytes[..];
+        // Ensure the staged block was written by this version.
+        let version = u8::read_le(&mut reader)?;
+        ensure!(version == SYNC_JOURNAL_VERSION, "Unsupported sync journal version {version}");
+        // Ensure the staged block is at the expected height.
+        let staged_height = u32::read_le(&mut reader)?;
+        let block = Block::read_le(&mut reader)?;
+        ensure!(staged_height == height && block.height() == height, "Mismatching height in staged block {height}");
+        Ok(block)
+    }
+
+    /// Evicts the highest staged blocks, until the staged blocks are within the maximum number of bytes.
+    /// The lowest staged blocks are kept, as a restart can only apply the staged blocks that extend the ledger.
+    fn evict_highest(&self) {
+        while self.num_bytes() > self.max_bytes {
+            let Some(height) = self.staged.lock().keys().max().copied() else {
+                break;
+            };
+            trace!("Evicting the staged block {height} from the sync journal");
+            self.remove(height);
+        }
+    }
+}
+
+impl Drop for SyncJournal {
+    /// Stops the writer, once the queued writes are applied.
+    fn drop(&mut self) {
+        self.sender.take();
+        if let Some(writer) = self.writer.take() {
+            if writer.join().is_err() {
+                error!("The sync journal writer panicked");
+            }
+        }
+    }
+}
+
+/// Returns the path of the file of the staged block at the given height.
+fn file_path(path: &Path, height: u32, extension: &str) -> PathBuf {
+    path.join(format!("{height}.{extension}"))
+}
+
+/// Applies the received writes to the directory of the staged blocks, until the sender is dropped.
+fn run_writer(path: PathBuf, mut receiver: mpsc::Receiver<JournalWrite>) {
+    while let Some(write) = receiver.blocking_recv() {
+        match write {
+            JournalWrite::Stage(height, bytes) => {
+                // Write the block to a partial file first, so that a crash never leaves a truncated staged block.
+                let partial_path = file_path(&path, height, PARTIAL_BLOCK_EXTENSION);
+                let result = fs::write(&partial_path, bytes)
+                    .and_then(|_| fs::rename(&partial_path, file_path(&path, height, STAGED_BLOCK_EXTENSION)));
+                if let Err(error) = result {
+                    warn!("Unable to stage block {height} in the sync journal - {error}");
+                }
+            }
+            JournalWrite::Remove(height) => match fs::remove_file(file_path(&path, height, STAGED_BLOCK_EXTENSION)) {
+                Err(error) if error.kind() != io::ErrorKind::NotFound => {
+                    warn!("Unable to remove block {height} from the sync journal - {error}")
+                }
+                _ => {}
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::MainnetV0;
+
+    type CurrentNetwork = MainnetV0;
+
+    /// Returns a sample directory for the sync journal.
+    fn sample_journal_path() -> PathBuf {
+        std::env::temp_dir().join(format!("snarkos-sync-journal-{}", rand::random::<u64>()))
+    }
+
+    /// Returns the genesis block, as a sample block.
+    fn sample_block() -> Block<CurrentNetwork> {
+        Block::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_stage_and_load() {
+        let path = sample_journal_path();
+        let block = sample_block();
+
+        // Ensure a staged block is loaded, and survives a restart.
+        let journal = SyncJournal::open(path.clone(), MAX_SYNC_JOURNAL_BYTES).unwrap();
+        assert!(journal.load::<CurrentNetwork>(0).unwrap().is_none());
+        journal.stage(&block).unwrap();
+        assert_eq!(journal.heights(), vec![0]);
+        drop(journal);
+        let journal = SyncJournal::open(path.clone(), MAX_SYNC_JOURNAL_BYTES).unwrap();
+        assert_eq!(journal.load::<CurrentNetwork>(0).unwrap(), Some(block));
+
+        // Ensure the staged block is removed once applied.
+        journal.remove_through(0);
+        assert!(journal.is_empty());
+        drop(journal);
+        assert_eq!(fs::read_dir(&path).unwrap().count(), 0);
+
+        fs::remove_dir_all(path).unwrap();
+    }
+
+    #[test]
+    fn test_stage_is_versioned() {
+        let path = sample_journal_path();
+        let journal = SyncJournal::open(path.clone(), MAX_SYNC_JOURNAL_BYTES).unwrap();
+        journal.stage(&sample_block()).unwrap();
+        drop(journal);
+
+        // Ensure the serialization is deterministic, and starts with the version and the height.
+        let bytes = fs::read(path.join("0.block")).unwrap();
+        assert_eq!(bytes[0], SYNC_JOURNAL_VERSION);
+        assert_eq!(&bytes[1..5], &0u32.to_le_bytes());
+        assert_eq!(&bytes[5..], &sample_block().to_bytes_le().unwrap()[..]);
+
+        // Ensure a block staged by another version is discarded.
+        let mut bytes = bytes;
+        bytes[0] = SYNC_JOURNAL_VERSION + 1;
+        fs::write(path.join("0.block"), bytes).unwrap();
+        let journal = SyncJournal::open(path.clone(), MAX_SYNC_JOURNAL_BYTES).unwrap();
+        assert!(journal.load::<CurrentNetwork>(0).is_err());
+        assert!(journal.is_empty());
+        drop(journal);
+
+        // Ensure a partially written block is discarded on restart.
+        fs::write(path.join("1.partial"), [0u8; 4]).unwrap();
+        let journal = SyncJournal::open(path.clone(), MAX_SYNC_JOURNAL_BYTES).unwrap();
+        assert!(journal.is_empty());
+        drop(journal);
+        assert_eq!(fs::read_dir(&path).unwrap().count(), 0);
+
+        fs::remove_dir_all(path).unwrap();
+    }
+
+    #[test]
+    fn test_evict_highest() {
+        let path = sample_journal_path();
+        let journal = SyncJournal::open(path.clone(), MAX_SYNC_JOURNAL_BYTES).unwrap();
+        journal.stage(&sample_block()).unwrap();
+        let num_bytes = journal.num_bytes();
+        journal.clear();
+        drop(journal);
+
+        // Stage the same block under 3 heights, in a journal that fits 2 of them.
+        let journal = SyncJournal::open(path.clone(), 2 * num_bytes).unwrap();
+        for height in [3, 5, 4] {
+            journal.staged.lock().insert(height, num_bytes);
+            journal.evict_highest();
+        }
+        // Ensure the highest staged block was evicted, as the lowest ones are needed to resume.
+        assert_eq!(journal.heights(), vec![3, 4]);
+        assert!(journal.num_bytes() <= journal.max_bytes());
+        journal.clear();
+        drop(journal);
+
+        // Ensure a block larger than the journal is not staged.
+        let journal = SyncJournal::open(path.clone(), num_bytes - 1).unwrap();
+        assert!(journal.is_empty());
+        assert!(journal.stage(&sample_block()).is_err());
+        assert!(journal.is_empty());
+        drop(journal);
+
+        fs::remove_dir_all(path).unwrap();
+    }
+}
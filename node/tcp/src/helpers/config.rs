@@ -55,6 +55,38 @@ pub struct Config {
     pub max_connections: u16,
     /// The maximum time (in milliseconds) allowed to establish a raw (before the [`Handshake`] protocol) TCP connection.
     pub connection_timeout_ms: u16,
+    /// The limits on the inbound connections performing the [`Handshake`] protocol at the same time.
+    pub handshake_limits: HandshakeLimits,
+}
+
+/// The limits on the inbound connections performing the [`Handshake`] protocol at the same time.
+///
+/// note: These bound the handshake work under a flood of inbound connections, independently of
+/// [`Config::max_connections`]. Outbound connections are not subject to them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HandshakeLimits {
+    /// The maximum number of inbound connections performing the handshake at the same time.
+    pub max_concurrent: u16,
+    /// The maximum time (in milliseconds) an inbound connection waits for a handshake slot before it is dropped.
+    pub slot_timeout_ms: u16,
+}
+
+impl HandshakeLimits {
+    /// The default maximum number of inbound connections performing the handshake at the same time.
+    pub const DEFAULT_MAX_CONCURRENT: u16 = 16;
+    /// The default maximum time (in milliseconds) an inbound connection waits for a handshake slot.
+    pub const DEFAULT_SLOT_TIMEOUT_MS: u16 = 200;
+
+    /// Returns the number of handshake slots, which is at least one.
+    pub fn num_slots(&self) -> usize {
+        self.max_concurrent.max(1).into()
+    }
+}
+
+impl Default for HandshakeLimits {
+    fn default() -> Self {
+        Self { max_concurrent: Self::DEFAULT_MAX_CONCURRENT, slot_timeout_ms: Self::DEFAULT_SLOT_TIMEOUT_MS }
+    }
 }
 
 impl Config {
@@ -85,6 +117,7 @@ impl Default for Config {
             fatal_io_errors: vec![ConnectionReset, ConnectionAborted, BrokenPipe, InvalidData, UnexpectedEof],
             max_connections: 100,
             connection_timeout_ms: 1_000,
+            handshake_limits: Default::default(),
         }
     }
 }
@@ -0,0 +1,213 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use parking_lot::Mutex;
+use std::{
+    io,
+    sync::atomic::{AtomicU64, Ordering::Relaxed},
+    time::{Duration, Instant},
+};
+
+/// The initial backoff of the accept loop in milliseconds, once the file descriptors are exhausted.
+pub const MIN_ACCEPT_BACKOFF_IN_MS: u64 = 10;
+/// The maximum backoff of the accept loop in milliseconds, while the file descriptors remain exhausted.
+pub const MAX_ACCEPT_BACKOFF_IN_MS: u64 = 5_000;
+/// The time in seconds without an exhaustion error, after which the file descriptors are considered available again.
+pub const FD_EXHAUSTION_RECOVERY_IN_SECS: u64 = 30;
+/// The minimum interval in seconds between two logs of the exhaustion of the file descriptors.
+pub const FD_EXHAUSTION_LOG_INTERVAL_IN_SECS: u64 = 60;
+
+/// The error number of a process that reached its limit of open file descriptors.
+#[cfg(unix)]
+const EMFILE: i32 = 24;
+/// The error number of a system that reached its limit of open files.
+#[cfg(unix)]
+const ENFILE: i32 = 23;
+/// The error number of a process that reached its limit of open sockets.
+#[cfg(windows)]
+const WSAEMFILE: i32 = 10024;
+
+/// Returns `true` if the given error is caused by the exhaustion of the file descriptors,
+/// i.e. `EMFILE` (the limit of the process) or `ENFILE` (the limit of the system).
+pub fn is_fd_exhaustion(error: &io::Error) -> bool {
+    #[cfg(unix)]
+    let codes = [EMFILE, ENFILE];
+    #[cfg(windows)]
+    let codes = [WSAEMFILE];
+    #[cfg(not(any(unix, windows)))]
+    let codes: [i32; 0] = [];
+    error.raw_os_error().is_some_and(|code| codes.contains(&code))
+}
+
+/// A snapshot of the exhaustion of the file descriptors.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FdExhaustionStatus {
+    /// Whether the file descriptors are currently exhausted.
+    pub is_exhausted: bool,
+    /// The total number of exhaustion errors.
+    pub num_errors: u64,
+    /// The number of seconds since the most recent exhaustion error, if any.
+    pub secs_since_last_error: Option<u64>,
+    /// The current backoff of the accept loop in milliseconds, if it is backing off.
+    pub accept_backoff_ms: Option<u64>,
+}
+
+/// Tracks the exhaustion of the file descriptors, as reported by the accept and connect calls.
+///
+/// The exhaustion is considered over once no exhaustion error occurred for the recovery period;
+/// meanwhile, the accept loop backs off with increasing sleeps.
+#[derive(Default)]
+pub struct FdExhaustion {
+    /// The total number of exhaustion errors.
+    num_errors: AtomicU64,
+    /// The state of the ongoing exhaustion.
+    state: Mutex<FdExhaustionState>,
+}
+
+#[derive(Default)]
+struct FdExhaustionState {
+    /// The time of the most recent exhaustion error.
+    last_error: Option<Instant>,
+    /// The number of consecutive exhaustion errors of the accept loop.
+    num_consecutive_accept_errors: u32,
+    /// The time of the most recent log of the exhaustion.
+    last_log: Option<Instant>,
+}
+
+impl FdExhaustion {
+    /// Records the given error of an accept or connect call at the given time, if it is caused by
+    /// the exhaustion of the file descriptors. Returns `true` if the error was recorded.
+    pub fn record_error(&self, error: &io::Error, is_accept: bool, now: Instant) -> bool {
+        if !is_fd_exhaustion(error) {
+            return false;
+        }
+        self.num_errors.fetch_add(1, Relaxed);
+        #[cfg(feature = "metrics")]
+        metrics::increment_counter(metrics::tcp::FD_EXHAUSTION_ERRORS);
+
+        let mut state = self.state.lock();
+        state.last_error = Some(now);
+        if is_accept {
+            state.num_consecutive_accept_errors = state.num_consecutive_accept_errors.saturating_add(1);
+        }
+        true
+    }
+
+    /// Records a successful accept call, which resets the backoff of the accept loop.
+    pub fn record_accept(&self) {
+        self.state.lock().num_consecutive_accept_errors = 0;
+    }
+
+    /// Returns `true` if the exhaustion should be logged at the given time, as the logs are rate-limited.
+    pub fn should_log(&self, now: Instant) -> bool {
+        let mut state = self.state.lock();
+        let interval = Duration::from_secs(FD_EXHAUSTION_LOG_INTERVAL_IN_SECS);
+        if state.last_log.is_some_and(|last_log| now.saturating_duration_since(last_log) < interval) {
+            return false;
+        }
+        state.last_log = Some(now);
+        true
+    }
+
+    /// Returns the backoff of the accept loop, which doubles with each consecutive exhaustion error.
+    pub fn accept_backoff(&self) -> Option<Duration> {
+        match self.state.lock().num_consecutive_accept_errors {
+            0 => None,
+            num_errors => {
+                let backoff = MIN_ACCEPT_BACKOFF_IN_MS.saturating_mul(1u64 << (num_errors - 1).min(16));
+                Some(Duration::from_millis(backoff.min(MAX_ACCEPT_BACKOFF_IN_MS)))
+            }
+        }
+    }
+
+    /// Returns `true` if the file descriptors are exhausted at the given time.
+    pub fn is_exhausted(&self, now: Instant) -> bool {
+        let recovery = Duration::from_secs(FD_EXHAUSTION_RECOVERY_IN_SECS);
+        self.state.lock().last_error.is_some_and(|last_error| now.saturating_duration_since(last_error) < recovery)
+    }
+
+    /// Returns the total number of exhaustion errors.
+    pub fn num_errors(&self) -> u64 {
+        self.num_errors.load(Relaxed)
+    }
+
+    /// Returns a snapshot of the exhaustion at the given time.
+    pub fn status(&self, now: Instant) -> FdExhaustionStatus {
+        let last_error = self.state.lock().last_error;
+        FdExhaustionStatus {
+            is_exhausted: self.is_exhausted(now),
+            num_errors: self.num_errors(),
+            secs_since_last_error: last_error.map(|last_error| now.saturating_duration_since(last_error).as_secs()),
+            accept_backoff_ms: self.accept_backoff().map(|backoff| backoff.as_millis() as u64),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns an error caused by the exhaustion of the file descriptors.
+    #[cfg(unix)]
+    fn emfile() -> io::Error {
+        io::Error::from_raw_os_error(EMFILE)
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_fd_exhaustion() {
+        let exhaustion = FdExhaustion::default();
+        let now = Instant::now();
+
+        // Ensure the other errors are ignored.
+        assert!(!exhaustion.record_error(&io::ErrorKind::ConnectionRefused.into(), true, now));
+        assert!(!exhaustion.is_exhausted(now));
+        assert_eq!(exhaustion.accept_backoff(), None);
+
+        // Ensure the backoff increases with the consecutive errors, up to its maximum.
+        let backoffs: Vec<_> = (0..12)
+            .map(|_| {
+                assert!(exhaustion.record_error(&emfile(), true, now));
+                exhaustion.accept_backoff().unwrap().as_millis() as u64
+            })
+            .collect();
+        assert_eq!(backoffs[..4], [10, 20, 40, 80]);
+        assert_eq!(*backoffs.last().unwrap(), MAX_ACCEPT_BACKOFF_IN_MS);
+        assert!(exhaustion.is_exhausted(now));
+
+        // Ensure the connect errors do not increase the backoff.
+        assert!(exhaustion.record_error(&io::Error::from_raw_os_error(ENFILE), false, now));
+        assert_eq!(exhaustion.accept_backoff().unwrap().as_millis() as u64, MAX_ACCEPT_BACKOFF_IN_MS);
+
+        // Ensure the logs are rate-limited.
+        assert!(exhaustion.should_log(now));
+        assert!(!exhaustion.should_log(now + Duration::from_secs(1)));
+        assert!(exhaustion.should_log(now + Duration::from_secs(FD_EXHAUSTION_LOG_INTERVAL_IN_SECS)));
+
+        // Ensure a successful accept resets the backoff, but not the exhaustion.
+        exhaustion.record_accept();
+        assert_eq!(exhaustion.accept_backoff(), None);
+        let status = exhaustion.status(now + Duration::from_secs(1));
+        assert_eq!(status, FdExhaustionStatus {
+            is_exhausted: true,
+            num_errors: 13,
+            secs_since_last_error: Some(1),
+            accept_backoff_ms: None,
+        });
+
+        // Ensure the exhaustion is over after the recovery period.
+        assert!(!exhaustion.is_exhausted(now + Duration::from_secs(FD_EXHAUSTION_RECOVERY_IN_SECS)));
+    }
+}
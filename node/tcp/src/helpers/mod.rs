@@ -14,11 +14,14 @@
 // limitations under the License.
 
 mod config;
-pub use config::Config;
+pub use config::{Config, HandshakeLimits};
 
 pub mod connections;
 pub use connections::{Connection, ConnectionSide};
 
+mod fd_exhaustion;
+pub use fd_exhaustion::*;
+
 mod known_peers;
 pub use known_peers::KnownPeers;
 
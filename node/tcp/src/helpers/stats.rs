@@ -28,6 +28,8 @@ pub struct Stats {
     bytes_received: AtomicU64,
     /// The number of failures.
     failures: AtomicU64,
+    /// The number of inbound connections dropped for lack of a handshake slot.
+    shed_handshakes: AtomicU64,
 }
 
 impl Stats {
@@ -52,6 +54,11 @@ impl Stats {
         self.failures.load(Relaxed)
     }
 
+    /// Returns the number of inbound connections dropped for lack of a handshake slot.
+    pub fn shed_handshakes(&self) -> u64 {
+        self.shed_handshakes.load(Relaxed)
+    }
+
     /// Registers a sent message of the provided `size` in bytes.
     pub fn register_sent_message(&self, size: usize) {
         self.msgs_sent.fetch_add(1, Relaxed);
@@ -68,4 +75,9 @@ impl Stats {
     pub fn register_failure(&self) {
         self.failures.fetch_add(1, Relaxed);
     }
+
+    /// Registers an inbound connection dropped for lack of a handshake slot.
+    pub fn register_shed_handshake(&self) {
+        self.shed_handshakes.fetch_add(1, Relaxed);
+    }
 }
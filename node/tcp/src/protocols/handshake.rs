@@ -18,17 +18,39 @@ use std::{io, time::Duration};
 use tokio::{
     io::{AsyncRead, AsyncWrite, split},
     net::TcpStream,
-    sync::{mpsc, oneshot},
+    sync::{OwnedSemaphorePermit, mpsc, oneshot},
     time::timeout,
 };
 use tracing::*;
 
 use crate::{
     Connection,
+    ConnectionSide,
     P2P,
     protocols::{ProtocolHandler, ReturnableConnection},
 };
 
+/// A slot for an inbound connection to perform the handshake in, released when it is dropped.
+pub(crate) struct HandshakeSlot {
+    _permit: OwnedSemaphorePermit,
+}
+
+impl HandshakeSlot {
+    /// Initializes a handshake slot from the given permit.
+    pub(crate) fn new(permit: OwnedSemaphorePermit) -> Self {
+        #[cfg(feature = "metrics")]
+        metrics::increment_gauge(metrics::tcp::HANDSHAKES, 1f64);
+        Self { _permit: permit }
+    }
+}
+
+impl Drop for HandshakeSlot {
+    fn drop(&mut self) {
+        #[cfg(feature = "metrics")]
+        metrics::decrement_gauge(metrics::tcp::HANDSHAKES, 1f64);
+    }
+}
+
 /// Can be used to specify and enable network handshakes. Upon establishing a connection, both sides will
 /// need to adhere to the specified handshake rules in order to finalize the connection and be able to send
 /// or receive any messages.
@@ -60,6 +82,24 @@ where
 
                 let node = self_clone.clone();
                 tokio::spawn(async move {
+                    // Wait for a slot before any work is done on an inbound connection, to bound the work of a flood.
+                    // Note: The slot is released once the handshake is over, however it ends.
+                    let _slot = match !conn.side() {
+                        ConnectionSide::Initiator => None,
+                        ConnectionSide::Responder => match node.tcp().acquire_handshake_slot().await {
+                            Some(slot) => Some(slot),
+                            None => {
+                                debug!(parent: node.tcp().span(), "dropping {addr}, as too many handshakes are ongoing");
+                                // Drop the connection right away, which closes its stream.
+                                drop(conn);
+                                if result_sender.send(Err(io::ErrorKind::ConnectionRefused.into())).is_err() {
+                                    unreachable!("couldn't return a Connection to the Tcp");
+                                }
+                                return;
+                            }
+                        },
+                    };
+
                     debug!(parent: node.tcp().span(), "shaking hands with {} as the {:?}", addr, !conn.side());
                     let result = timeout(Duration::from_millis(Self::TIMEOUT_MS), node.perform_handshake(conn)).await;
 
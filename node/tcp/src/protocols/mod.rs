@@ -32,8 +32,9 @@ mod writing;
 
 pub use disconnect::Disconnect;
 pub use handshake::Handshake;
+pub(crate) use handshake::HandshakeSlot;
 pub use on_connect::OnConnect;
-pub use reading::Reading;
+pub use reading::{MessagePriority, Reading};
 pub use writing::Writing;
 
 #[derive(Default)]
@@ -40,6 +40,10 @@ use tracing::*;
 /// which is immediately queued (with a [`Reading::MESSAGE_QUEUE_DEPTH`] limit) to be processed by
 /// [`Reading::process_message`]. The configured fatal IO errors result in an immediate disconnect
 /// (in order to e.g. avoid accidentally reading "borked" messages).
+///
+/// Messages classified as control messages by [`Reading::message_priority`] are queued separately (with a
+/// [`Reading::CONTROL_QUEUE_DEPTH`] limit), and processed ahead of the queued bulk messages, so they wait for
+/// at most the bulk message that is being processed.
 #[async_trait]
 pub trait Reading: P2P
 where
@@ -52,6 +56,17 @@ where
     /// The default value is 1024.
     const MESSAGE_QUEUE_DEPTH: usize = 1024;
 
+    /// The depth of per-connection queues used to process inbound control messages ahead of the bulk messages.
+    ///
+    /// The default value is 64.
+    const CONTROL_QUEUE_DEPTH: usize = 64;
+
+    /// The maximum number of consecutive control messages processed ahead of the bulk messages, so that a flood
+    /// of control messages cannot starve the bulk messages either.
+    ///
+    /// The default value is 16.
+    const MAX_CONSECUTIVE_CONTROL_MESSAGES: usize = 16;
+
     /// The initial size of a per-connection buffer for reading inbound messages. Can be set to the maximum expected size
     /// of the inbound message in order to only allocate it once.
     ///
@@ -94,10 +109,95 @@ where
     /// The `side` param indicates the connection side **from the node's perspective**.
     fn codec(&self, addr: SocketAddr, side: ConnectionSide) -> Self::Codec;
 
+    /// Classifies an inbound message as it is decoded, which determines the queue it is processed from.
+    /// By default, every message is a bulk message, so the messages are processed in the order they were received.
+    fn message_priority(&self, _message: &Self::Message) -> MessagePriority {
+        MessagePriority::Bulk
+    }
+
     /// Processes an inbound message. Can be used to update state, send replies etc.
     async fn process_message(&self, source: SocketAddr, message: Self::Message) -> io::Result<()>;
 }
 
+/// The priority of an inbound message, which determines the queue it is processed from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MessagePriority {
+    /// A bulk message, e.g. a block or a transaction, processed in the order it was received.
+    Bulk,
+    /// A control message, e.g. a ping, processed ahead of the queued bulk messages.
+    Control,
+    /// A control message that ends the connection, e.g. a disconnect, which discards the queued bulk messages.
+    Terminal,
+}
+
+/// The per-connection queues of the inbound messages.
+struct InboundQueues<M> {
+    /// The queue of the control messages, along with their priority.
+    control: mpsc::Receiver<(M, MessagePriority)>,
+    /// The queue of the bulk messages.
+    bulk: mpsc::Receiver<M>,
+    /// The number of control messages processed since the last bulk message.
+    num_consecutive_control: usize,
+    /// The maximum number of consecutive control messages processed ahead of the bulk messages.
+    max_consecutive_control: usize,
+    /// Whether a terminal message was received, after which the bulk messages are discarded.
+    is_terminated: bool,
+}
+
+impl<M> InboundQueues<M> {
+    /// Initializes the inbound queues.
+    fn new(
+        control: mpsc::Receiver<(M, MessagePriority)>,
+        bulk: mpsc::Receiver<M>,
+        max_consecutive_control: usize,
+    ) -> Self {
+        Self { control, bulk, num_consecutive_control: 0, max_consecutive_control, is_terminated: false }
+    }
+
+    /// Returns the next message to process, along with the number of bulk messages discarded before it,
+    /// or `None` once both queues are closed.
+    async fn next(&mut self) -> Option<(M, usize)> {
+        let mut num_discarded = 0;
+        loop {
+            // Prefer the control messages, unless too many of them were processed in a row.
+            let next = match self.num_consecutive_control < self.max_consecutive_control {
+                true => tokio::select! {
+                    biased;
+                    Some(entry) = self.control.recv() => Some(entry),
+                    Some(message) = self.bulk.recv() => Some((message, MessagePriority::Bulk)),
+                    else => None,
+                },
+                false => tokio::select! {
+                    biased;
+                    Some(message) = self.bulk.recv() => Some((message, MessagePriority::Bulk)),
+                    Some(entry) = self.control.recv() => Some(entry),
+                    else => None,
+                },
+            };
+            let (message, priority) = next?;
+
+            match priority {
+                // Discard the bulk messages of a connection that was ended.
+                MessagePriority::Bulk if self.is_terminated => {
+                    num_discarded += 1;
+                    continue;
+                }
+                MessagePriority::Bulk => self.num_consecutive_control = 0,
+                MessagePriority::Control => self.num_consecutive_control += 1,
+                MessagePriority::Terminal => {
+                    self.num_consecutive_control += 1;
+                    self.is_terminated = true;
+                    // Discard the queued bulk messages, as the connection was ended.
+                    while self.bulk.try_recv().is_ok() {
+                        num_discarded += 1;
+                    }
+                }
+            }
+            return Some((message, num_discarded));
+        }
+    }
+}
+
 /// This trait is used to restrict access to methods that would otherwise be public in [`Reading`].
 #[async_trait]
 trait ReadingInternal: Reading {
@@ -129,7 +229,13 @@ impl<R: Reading> ReadingInternal for R {
             framed.read_buffer_mut().reserve(Self::INITIAL_BUFFER_SIZE);
         }
 
-        let (inbound_message_sender, mut inbound_message_receiver) = mpsc::channel(Self::MESSAGE_QUEUE_DEPTH);
+        let (inbound_message_sender, inbound_message_receiver) = mpsc::channel(Self::MESSAGE_QUEUE_DEPTH);
+        let (inbound_control_sender, inbound_control_receiver) = mpsc::channel(Self::CONTROL_QUEUE_DEPTH);
+        let mut inbound_queues = InboundQueues::new(
+            inbound_control_receiver,
+            inbound_message_receiver,
+            Self::MAX_CONSECUTIVE_CONTROL_MESSAGES,
+        );
 
         // use a channel to know when the processing task is ready
         let (tx_processing, rx_processing) = oneshot::channel::<()>();
@@ -141,7 +247,12 @@ impl<R: Reading> ReadingInternal for R {
             trace!(parent: node.span(), "spawned a task for processing messages from {addr}");
             tx_processing.send(()).unwrap(); // safe; the channel was just opened
 
-            while let Some(msg) = inbound_message_receiver.recv().await {
+            while let Some((msg, num_discarded)) = inbound_queues.next().await {
+                if num_discarded > 0 {
+                    debug!(parent: node.span(), "discarded {num_discarded} queued messages from {addr}");
+                    #[cfg(feature = "metrics")]
+                    metrics::decrement_gauge(metrics::tcp::TCP_TASKS, num_discarded as f64);
+                }
                 if let Err(e) = self_clone.process_message(addr, msg).await {
                     error!(parent: node.span(), "can't process a message from {addr}: {e}");
                     node.known_peers().register_failure(addr);
@@ -157,6 +268,7 @@ impl<R: Reading> ReadingInternal for R {
         let (tx_reader, rx_reader) = oneshot::channel::<()>();
 
         // the task for reading messages from a stream
+        let self_clone = self.clone();
         let node = self.tcp().clone();
         let reader_task = tokio::spawn(async move {
             trace!(parent: node.span(), "spawned a task for reading messages from {addr}");
@@ -169,8 +281,12 @@ impl<R: Reading> ReadingInternal for R {
             while let Some(bytes) = framed.next().await {
                 match bytes {
                     Ok(msg) => {
-                        // send the message for further processing
-                        if let Err(e) = inbound_message_sender.try_send(msg) {
+                        // send the message for further processing, via the queue of its priority
+                        let result = match self_clone.message_priority(&msg) {
+                            MessagePriority::Bulk => inbound_message_sender.try_send(msg).map_err(|e| e.to_string()),
+                            priority => inbound_control_sender.try_send((msg, priority)).map_err(|e| e.to_string()),
+                        };
+                        if let Err(e) = result {
                             error!(parent: node.span(), "can't process a message from {addr}: {e}");
                             node.stats().register_failure();
                         }
@@ -240,3 +356,68 @@ impl<D: Decoder> Decoder for CountingCodec<D> {
         Ok(ret)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    /// The number of queued bulk messages.
+    const NUM_BULK_MESSAGES: usize = 1000;
+
+    /// Returns the inbound queues, along with the senders of the control and bulk messages.
+    fn sample_queues(
+        max_consecutive_control: usize,
+    ) -> (mpsc::Sender<(u32, MessagePriority)>, mpsc::Sender<u32>, InboundQueues<u32>) {
+        let (control_sender, control_receiver) = mpsc::channel(64);
+        let (bulk_sender, bulk_receiver) = mpsc::channel(NUM_BULK_MESSAGES);
+        (control_sender, bulk_sender, InboundQueues::new(control_receiver, bulk_receiver, max_consecutive_control))
+    }
+
+    #[tokio::test]
+    async fn test_disconnect_preempts_bulk_backlog() {
+        let (control_sender, bulk_sender, mut queues) = sample_queues(16);
+
+        // Enqueue a large bulk backlog, followed by a disconnect.
+        for i in 0..NUM_BULK_MESSAGES as u32 {
+            bulk_sender.try_send(i).unwrap();
+        }
+        control_sender.try_send((u32::MAX, MessagePriority::Terminal)).unwrap();
+
+        // Ensure the disconnect is processed first, discarding the backlog.
+        let next = tokio::time::timeout(Duration::from_millis(100), queues.next()).await.unwrap();
+        assert_eq!(next, Some((u32::MAX, NUM_BULK_MESSAGES)));
+
+        // Ensure the bulk messages received after the disconnect are discarded as well.
+        bulk_sender.try_send(0).unwrap();
+        control_sender.try_send((1, MessagePriority::Control)).unwrap();
+        assert_eq!(queues.next().await, Some((1, 1)));
+
+        // Ensure the queues end once they are closed.
+        drop((control_sender, bulk_sender));
+        assert_eq!(queues.next().await, None);
+    }
+
+    #[tokio::test]
+    async fn test_control_ahead_of_bulk() {
+        let (control_sender, bulk_sender, mut queues) = sample_queues(2);
+
+        // Enqueue a few bulk messages, followed by more control messages than may be processed in a row.
+        for i in 0..3 {
+            bulk_sender.try_send(i).unwrap();
+        }
+        for i in 10..14 {
+            control_sender.try_send((i, MessagePriority::Control)).unwrap();
+        }
+
+        // Ensure the control messages are processed ahead of the bulk messages, without starving them.
+        let mut order = vec![];
+        drop((control_sender, bulk_sender));
+        while let Some((message, num_discarded)) = queues.next().await {
+            assert_eq!(num_discarded, 0);
+            order.push(message);
+        }
+        assert_eq!(order, vec![10, 11, 0, 12, 13, 1, 2]);
+    }
+}
@@ -23,15 +23,16 @@ use std::{
         Arc,
         atomic::{AtomicUsize, Ordering::*},
     },
-    time::Duration,
+    time::{Duration, Instant},
 };
 
+use async_trait::async_trait;
 use once_cell::sync::OnceCell;
 use parking_lot::Mutex;
 use tokio::{
     io::split,
     net::{TcpListener, TcpStream},
-    sync::oneshot,
+    sync::{Semaphore, oneshot},
     task::JoinHandle,
     time::timeout,
 };
@@ -39,10 +40,12 @@ use tracing::*;
 
 use crate::{
     Config,
+    FdExhaustion,
+    HandshakeLimits,
     KnownPeers,
     Stats,
     connections::{Connection, ConnectionSide, Connections},
-    protocols::{Protocol, Protocols},
+    protocols::{HandshakeSlot, Protocol, Protocols},
 };
 
 // A sequential numeric identifier assigned to `Tcp`s that were not provided with a name.
@@ -72,12 +75,18 @@ pub struct InnerTcp {
     pub(crate) protocols: Protocols,
     /// A set of connections that have not been finalized yet.
     connecting: Mutex<HashSet<SocketAddr>>,
+    /// The slots for the inbound connections to perform the handshake in.
+    handshake_slots: Arc<Semaphore>,
+    /// The limits on the inbound handshakes, initialized from the configuration.
+    handshake_limits: Mutex<HandshakeLimits>,
     /// Contains objects related to the node's active connections.
     connections: Connections,
     /// Collects statistics related to the node's peers.
     known_peers: KnownPeers,
     /// Collects statistics related to the node itself.
     stats: Stats,
+    /// Tracks the exhaustion of the file descriptors.
+    fd_exhaustion: FdExhaustion,
     /// The node's tasks.
     pub(crate) tasks: Mutex<Vec<JoinHandle<()>>>,
 }
@@ -93,6 +102,10 @@ impl Tcp {
         // Create a tracing span containing the node's name.
         let span = crate::helpers::create_span(config.name.as_deref().unwrap());
 
+        // Initialize the handshake slots.
+        let handshake_limits = config.handshake_limits;
+        let handshake_slots = Arc::new(Semaphore::new(handshake_limits.num_slots()));
+
         // Initialize the Tcp stack.
         let tcp = Tcp(Arc::new(InnerTcp {
             span,
@@ -100,9 +113,12 @@ impl Tcp {
             listening_addr: Default::default(),
             protocols: Default::default(),
             connecting: Default::default(),
+            handshake_slots,
+            handshake_limits: Mutex::new(handshake_limits),
             connections: Default::default(),
             known_peers: Default::default(),
             stats: Default::default(),
+            fd_exhaustion: Default::default(),
             tasks: Default::default(),
         }));
 
@@ -150,6 +166,35 @@ impl Tcp {
         self.connecting.lock().len()
     }
 
+    /// Returns the number of inbound connections that are currently performing the handshake.
+    pub fn num_handshaking(&self) -> usize {
+        self.handshake_limits.lock().num_slots().saturating_sub(self.handshake_slots.available_permits())
+    }
+
+    /// Returns the limits on the inbound handshakes.
+    pub fn handshake_limits(&self) -> HandshakeLimits {
+        *self.handshake_limits.lock()
+    }
+
+    /// Sets the limits on the inbound handshakes, e.g. from the configuration of the node,
+    /// for the stacks that are initialized before it is known.
+    ///
+    /// note: This is meant to be called before the listener is enabled. If the number of slots is lowered
+    /// while handshakes are ongoing, only the slots that are free at the time are removed.
+    pub fn set_handshake_limits(&self, limits: HandshakeLimits) {
+        let mut current = self.handshake_limits.lock();
+        let (old_slots, new_slots) = (current.num_slots(), limits.num_slots());
+        if new_slots > old_slots {
+            self.handshake_slots.add_permits(new_slots - old_slots);
+        } else if new_slots < old_slots {
+            let num_removed = (old_slots - new_slots).min(self.handshake_slots.available_permits());
+            if let Ok(permits) = self.handshake_slots.try_acquire_many(num_removed as u32) {
+                permits.forget();
+            }
+        }
+        *current = limits;
+    }
+
     /// Returns a list containing addresses of active connections.
     pub fn connected_addrs(&self) -> Vec<SocketAddr> {
         self.connections.addrs()
@@ -172,6 +217,12 @@ impl Tcp {
         &self.stats
     }
 
+    /// Returns a reference to the tracker of the exhaustion of the file descriptors.
+    #[inline]
+    pub fn fd_exhaustion(&self) -> &FdExhaustion {
+        &self.fd_exhaustion
+    }
+
     /// Returns the tracing [`Span`] associated with Tcp.
     #[inline]
     pub fn span(&self) -> &Span {
@@ -232,18 +283,21 @@ impl Tcp {
         // Otherwise default to the system's default interface.
         let res = if let Some(listen_ip) = self.config().listener_ip {
             let sock =
-                if listen_ip.is_ipv4() { tokio::net::TcpSocket::new_v4()? } else { tokio::net::TcpSocket::new_v6()? };
-            sock.bind(SocketAddr::new(listen_ip, 0))?;
-            timeout(timeout_duration, sock.connect(addr)).await
+                if listen_ip.is_ipv4() { tokio::net::TcpSocket::new_v4() } else { tokio::net::TcpSocket::new_v6() };
+            match sock.and_then(|sock| sock.bind(SocketAddr::new(listen_ip, 0)).map(|_| sock)) {
+                Ok(sock) => timeout(timeout_duration, sock.connect(addr)).await,
+                Err(err) => Ok(Err(err)),
+            }
         } else {
             timeout(timeout_duration, TcpStream::connect(addr)).await
         };
 
         let stream = match res {
             Ok(Ok(stream)) => Ok(stream),
-            Ok(err) => {
+            Ok(Err(err)) => {
                 self.connecting.lock().remove(&addr);
-                err
+                self.register_fd_exhaustion(&err, false);
+                Err(err)
             }
             Err(err) => {
                 self.connecting.lock().remove(&addr);
@@ -324,13 +378,7 @@ impl Tcp {
             trace!(parent: tcp.span(), "Spawned the listening task");
             tx.send(()).unwrap(); // safe; the channel was just opened
 
-            loop {
-                // Await for a new connection.
-                match listener.accept().await {
-                    Ok((stream, addr)) => tcp.handle_connection(stream, addr),
-                    Err(e) => error!(parent: tcp.span(), "Failed to accept a connection: {e}"),
-                }
-            }
+            tcp.accept_loop(listener).await;
         });
         self.tasks.lock().push(listening_task);
         let _ = rx.await;
@@ -339,6 +387,43 @@ impl Tcp {
         Ok(listening_addr)
     }
 
+    /// Accepts the incoming connections, backing off while the file descriptors are exhausted.
+    async fn accept_loop(&self, mut listener: impl Accept) {
+        loop {
+            // Await for a new connection.
+            match listener.accept().await {
+                Ok((stream, addr)) => {
+                    self.fd_exhaustion.record_accept();
+                    self.handle_connection(stream, addr);
+                }
+                // Note: While the file descriptors are exhausted, the accept calls fail at once, so the loop backs off.
+                Err(e) if self.register_fd_exhaustion(&e, true) => {
+                    if let Some(backoff) = self.fd_exhaustion.accept_backoff() {
+                        tokio::time::sleep(backoff).await;
+                    }
+                }
+                Err(e) => error!(parent: self.span(), "Failed to accept a connection: {e}"),
+            }
+        }
+    }
+
+    /// Records the given error of an accept or connect call, if it is caused by the exhaustion of the file descriptors,
+    /// in which case it is logged (at most once per interval) and `true` is returned.
+    fn register_fd_exhaustion(&self, error: &io::Error, is_accept: bool) -> bool {
+        let now = Instant::now();
+        if !self.fd_exhaustion.record_error(error, is_accept, now) {
+            return false;
+        }
+        if self.fd_exhaustion.should_log(now) {
+            error!(
+                parent: self.span(),
+                "The file descriptors are exhausted ({error}) - connections are failing; raise the limit of open files \
+                 (e.g. `ulimit -n 65536`, or `LimitNOFILE` in the systemd unit)"
+            );
+        }
+        true
+    }
+
     /// Creates an instance of `TcpListener` based on the node's configuration.
     async fn create_listener(&self, listener_ip: IpAddr) -> io::Result<TcpListener> {
         debug!("Creating a TCP listener on {listener_ip}...");
@@ -393,6 +478,22 @@ impl Tcp {
         });
     }
 
+    /// Waits for a slot to perform the handshake of an inbound connection in, for up to the configured time.
+    /// Returns `None` if every slot remained taken, in which case the connection is to be dropped.
+    pub(crate) async fn acquire_handshake_slot(&self) -> Option<HandshakeSlot> {
+        let wait = Duration::from_millis(self.handshake_limits.lock().slot_timeout_ms.into());
+        match timeout(wait, self.handshake_slots.clone().acquire_owned()).await {
+            Ok(Ok(permit)) => Some(HandshakeSlot::new(permit)),
+            // Note: The semaphore is never closed, so only the wait can run out.
+            _ => {
+                self.stats.register_shed_handshake();
+                #[cfg(feature = "metrics")]
+                metrics::increment_counter(metrics::tcp::SHED_HANDSHAKES);
+                None
+            }
+        }
+    }
+
     /// Checks if the given IP address is the same as the listening address of this `Tcp`.
     fn is_self_connect(&self, addr: SocketAddr) -> bool {
         // SAFETY: if we're opening connections, this should never fail.
@@ -504,6 +605,20 @@ impl Tcp {
     }
 }
 
+/// A source of inbound connections, which abstracts over the listener.
+#[async_trait]
+trait Accept: Send + 'static {
+    /// Accepts a new inbound connection.
+    async fn accept(&mut self) -> io::Result<(TcpStream, SocketAddr)>;
+}
+
+#[async_trait]
+impl Accept for TcpListener {
+    async fn accept(&mut self) -> io::Result<(TcpStream, SocketAddr)> {
+        TcpListener::accept(self).await
+    }
+}
+
 impl fmt::Debug for Tcp {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "The TCP stack config: {:?}", self.config)
@@ -513,9 +628,35 @@ impl fmt::Debug for Tcp {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{MIN_ACCEPT_BACKOFF_IN_MS, P2P, protocols::Handshake};
 
     use std::net::{IpAddr, Ipv4Addr};
 
+    /// A node with slow handshakes, keeping track of the most handshakes it performed at once.
+    #[derive(Clone)]
+    struct SlowHandshakes {
+        tcp: Tcp,
+        ongoing: Arc<AtomicUsize>,
+        max_ongoing: Arc<AtomicUsize>,
+    }
+
+    impl P2P for SlowHandshakes {
+        fn tcp(&self) -> &Tcp {
+            &self.tcp
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Handshake for SlowHandshakes {
+        async fn perform_handshake(&self, _conn: Connection) -> io::Result<Connection> {
+            let ongoing = self.ongoing.fetch_add(1, SeqCst) + 1;
+            self.max_ongoing.fetch_max(ongoing, SeqCst);
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            self.ongoing.fetch_sub(1, SeqCst);
+            Err(io::ErrorKind::PermissionDenied.into())
+        }
+    }
+
     #[tokio::test]
     async fn test_new() {
         let tcp = Tcp::new(Config {
@@ -716,4 +857,123 @@ mod tests {
         assert!(tcp.is_connected(peer_ip));
         assert!(!tcp.is_connecting(peer_ip));
     }
+
+    #[tokio::test]
+    async fn test_handshake_flood_is_bounded() {
+        let node = SlowHandshakes {
+            tcp: Tcp::new(Config {
+                listener_ip: Some(IpAddr::V4(Ipv4Addr::LOCALHOST)),
+                max_connections: 1000,
+                handshake_limits: HandshakeLimits { max_concurrent: 4, slot_timeout_ms: 50 },
+                ..Default::default()
+            }),
+            ongoing: Default::default(),
+            max_ongoing: Default::default(),
+        };
+        node.enable_handshake().await;
+        let node_ip = node.tcp().enable_listener().await.unwrap();
+        let num_tasks = node.tcp().tasks.lock().len();
+
+        // Open hundreds of connections at once.
+        let streams = futures_util::future::join_all((0..300).map(|_| TcpStream::connect(node_ip))).await;
+        assert!(streams.iter().all(|stream| stream.is_ok()));
+
+        // Wait for every connection to be either handshaken or dropped.
+        for _ in 0..100 {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            if node.tcp().num_connecting() == 0 {
+                break;
+            }
+        }
+
+        // Ensure the handshakes were bounded, and the connections over the bound were shed.
+        assert!(node.max_ongoing.load(SeqCst) <= 4);
+        assert!(node.tcp().stats().shed_handshakes() > 0);
+
+        // Ensure nothing was left behind.
+        assert_eq!(node.tcp().num_handshaking(), 0);
+        assert_eq!(node.tcp().num_connecting(), 0);
+        assert_eq!(node.tcp().num_connected(), 0);
+        assert_eq!(node.tcp().tasks.lock().len(), num_tasks);
+    }
+
+    #[tokio::test]
+    async fn test_set_handshake_limits() {
+        let tcp = Tcp::new(Default::default());
+        assert_eq!(tcp.handshake_limits(), HandshakeLimits::default());
+
+        // Ensure the slots follow the limits, whether they are raised or lowered.
+        for max_concurrent in [32, 4, 0] {
+            let limits = HandshakeLimits { max_concurrent, slot_timeout_ms: 10 };
+            tcp.set_handshake_limits(limits);
+            assert_eq!(tcp.handshake_limits(), limits);
+            assert_eq!(tcp.handshake_slots.available_permits(), limits.num_slots());
+            assert_eq!(tcp.num_handshaking(), 0);
+        }
+    }
+
+    /// A listener that fails with the exhaustion of the file descriptors a number of times, before accepting.
+    #[cfg(unix)]
+    struct ExhaustedListener {
+        listener: TcpListener,
+        num_failures: usize,
+        attempts: Arc<Mutex<Vec<Instant>>>,
+    }
+
+    #[cfg(unix)]
+    #[async_trait]
+    impl Accept for ExhaustedListener {
+        async fn accept(&mut self) -> io::Result<(TcpStream, SocketAddr)> {
+            self.attempts.lock().push(Instant::now());
+            match self.num_failures {
+                0 => self.listener.accept().await,
+                _ => {
+                    self.num_failures -= 1;
+                    // Note: This is `EMFILE`, i.e. "Too many open files".
+                    Err(io::Error::from_raw_os_error(24))
+                }
+            }
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_accept_backs_off_on_fd_exhaustion() {
+        let tcp = Tcp::new(Config::default());
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let node_ip = listener.local_addr().unwrap();
+        tcp.listening_addr.set(node_ip).unwrap();
+
+        // Accept the connections on a listener that fails a few times first.
+        let attempts = Arc::new(Mutex::new(Vec::new()));
+        let exhausted = ExhaustedListener { listener, num_failures: 5, attempts: attempts.clone() };
+        let tcp_ = tcp.clone();
+        let accept_task = tokio::spawn(async move { tcp_.accept_loop(exhausted).await });
+
+        // Ensure the connection is eventually accepted.
+        let _stream = TcpStream::connect(node_ip).await.unwrap();
+        for _ in 0..50 {
+            if tcp.num_connected() == 1 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert_eq!(tcp.num_connected(), 1);
+
+        // Ensure the accept loop backed off, with increasing sleeps.
+        let attempts = attempts.lock().clone();
+        assert!(attempts.len() >= 6);
+        let gaps: Vec<_> = attempts.windows(2).take(5).map(|pair| pair[1] - pair[0]).collect();
+        for (i, gap) in gaps.iter().enumerate() {
+            assert!(*gap >= Duration::from_millis(MIN_ACCEPT_BACKOFF_IN_MS << i), "Insufficient backoff: {gaps:?}");
+        }
+
+        // Ensure the exhaustion is reported, and the backoff is reset by the successful accept.
+        let status = tcp.fd_exhaustion().status(Instant::now());
+        assert!(status.is_exhausted);
+        assert_eq!(status.num_errors, 5);
+        assert_eq!(status.accept_backoff_ms, None);
+
+        accept_task.abort();
+    }
 }
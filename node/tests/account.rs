@@ -0,0 +1,65 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![recursion_limit = "256"]
+
+#[allow(dead_code)]
+mod common;
+use common::sample_genesis_block;
+
+use snarkos_account::Account;
+use snarkos_node::{account_status, check_account_status};
+use snarkos_node_router::messages::NodeType;
+use snarkvm::prelude::{Ledger, MainnetV0 as CurrentNetwork, store::helpers::memory::ConsensusMemory};
+
+use aleo_std::StorageMode;
+
+/// Loads a ledger containing only the genesis block.
+fn sample_ledger() -> Ledger<CurrentNetwork, ConsensusMemory<CurrentNetwork>> {
+    Ledger::load(sample_genesis_block(), StorageMode::Production).unwrap()
+}
+
+#[test]
+fn test_committee_member_passes_strict_check() {
+    let ledger = sample_ledger();
+    let address = *ledger.latest_committee().unwrap().members().keys().next().unwrap();
+
+    // Ensure the genesis committee member is staked, and passes the strict check once synced.
+    let status = account_status(&ledger, address, true).unwrap();
+    assert!(status.is_committee_member());
+    assert!(status.is_staked());
+    assert!(check_account_status(NodeType::Validator, address, &status, true).is_ok());
+}
+
+#[test]
+fn test_unstaked_validator_is_rejected_once_synced() {
+    let ledger = sample_ledger();
+    // Sample an account that is not bonded on this network.
+    let address = Account::<CurrentNetwork>::new(&mut rand::thread_rng()).unwrap().address();
+
+    // Ensure the check is deferred while the ledger has not synced, even in strict mode.
+    let status = account_status(&ledger, address, false).unwrap();
+    assert_eq!(status.height(), 0);
+    assert!(!status.is_staked());
+    assert!(check_account_status(NodeType::Validator, address, &status, true).is_ok());
+
+    // Ensure the re-check after sync rejects the validator in strict mode, and only warns otherwise.
+    let status = account_status(&ledger, address, true).unwrap();
+    assert!(check_account_status(NodeType::Validator, address, &status, true).is_err());
+    assert!(check_account_status(NodeType::Validator, address, &status, false).is_ok());
+
+    // Ensure other node types are not required to be staked.
+    assert!(check_account_status(NodeType::Client, address, &status, true).is_ok());
+}
@@ -0,0 +1,163 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![recursion_limit = "256"]
+
+#[allow(dead_code)]
+mod common;
+use common::{sample_account, sample_genesis_block};
+
+use snarkos_node::Client;
+use snarkos_node_rest::{Claims, NodeConfig, Rest, admin_routes};
+use snarkos_node_router::{MAX_TRACE_SCOPES, messages::NodeType};
+use snarkvm::prelude::{Ledger, MainnetV0 as CurrentNetwork, store::helpers::memory::ConsensusMemory};
+
+use aleo_std::StorageMode;
+use reqwest::StatusCode;
+use serde_json::json;
+use std::net::SocketAddr;
+
+type CurrentLedger = ConsensusMemory<CurrentNetwork>;
+
+#[tokio::test]
+async fn test_admin_actions_are_audited() {
+    // Initialize the state of the routes, without consensus nor routing.
+    let account = sample_account();
+    let ledger =
+        Ledger::<CurrentNetwork, CurrentLedger>::load(sample_genesis_block(), StorageMode::Production).unwrap();
+    let config = NodeConfig::new(NodeType::Client, account.address(), None, None, &StorageMode::Production, None, &[]);
+    let rest =
+        Rest::<CurrentNetwork, CurrentLedger, Client<CurrentNetwork, CurrentLedger>>::new(None, ledger, None, config)
+            .unwrap();
+
+    // Mount the administrative routes in a bare application, under the network.
+    let app = axum::Router::new().nest("/mainnet", admin_routes(rest).into());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await.unwrap();
+    });
+    let base_url = format!("http://{address}/mainnet");
+    let client = reqwest::Client::new();
+    let admin_token = Claims::new(account.address());
+
+    // Perform an action that succeeds, one that fails, and one that is unavailable without routing.
+    let scope = json!({ "route": "/block", "level": "debug", "duration_in_secs": 60 });
+    let response = client
+        .post(format!("{base_url}/node/trace"))
+        .bearer_auth(admin_token.to_jwt_string().unwrap())
+        .json(&scope)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let invalid_scope = json!({ "route": "block", "level": "debug", "duration_in_secs": 60 });
+    let response = client
+        .post(format!("{base_url}/node/trace"))
+        .bearer_auth(admin_token.to_jwt_string().unwrap())
+        .json(&invalid_scope)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let response = client
+        .post(format!("{base_url}/node/trusted_peers"))
+        .bearer_auth(admin_token.to_jwt_string().unwrap())
+        .json(&json!({ "insert": ["1.2.3.4:4130"] }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    // Ensure the requests that read the state of the node are not recorded.
+    let response = client
+        .get(format!("{base_url}/node/trace"))
+        .bearer_auth(admin_token.to_jwt_string().unwrap())
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Ensure the unauthenticated requests are rejected.
+    let response = client.post(format!("{base_url}/node/trace")).json(&scope).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // Ensure only the authenticated actions are recorded, in order.
+    let response = client
+        .get(format!("{base_url}/node/audit"))
+        .bearer_auth(admin_token.to_jwt_string().unwrap())
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let audit: serde_json::Value = serde_json::from_str(&response.text().await.unwrap()).unwrap();
+    assert!(audit["path"].is_null());
+    assert_eq!(audit["num_dropped"], 0);
+    let records = audit["records"].as_array().unwrap();
+    let summary = records
+        .iter()
+        .map(|record| {
+            (
+                record["method"].as_str().unwrap(),
+                record["endpoint"].as_str().unwrap(),
+                record["outcome"].as_str().unwrap(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(summary, [
+        ("POST", "/node/trace", "succeeded"),
+        ("POST", "/node/trace", "failed"),
+        ("POST", "/node/trusted_peers", "failed"),
+    ]);
+    // Ensure the records identify the tokens, and their parameters.
+    for record in records {
+        assert_eq!(record["subject"], account.address().to_string());
+        assert_eq!(record["source_ip"], "127.0.0.1");
+        assert_eq!(record["token_id"], admin_token.token_id());
+    }
+    assert_eq!(records[1]["status"], 400);
+    assert_eq!(records[0]["parameters"]["body"], scope);
+    assert_eq!(records[1]["parameters"]["body"], invalid_scope);
+
+    // Ensure a scope with an invalid duration is rejected as a bad request.
+    let response = client
+        .post(format!("{base_url}/node/trace"))
+        .bearer_auth(admin_token.to_jwt_string().unwrap())
+        .json(&json!({ "route": "/block", "level": "debug", "duration_in_secs": 0 }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // Ensure a scope beyond the capacity of the table is rejected, until the active scopes expire.
+    for _ in 1..MAX_TRACE_SCOPES {
+        let response = client
+            .post(format!("{base_url}/node/trace"))
+            .bearer_auth(admin_token.to_jwt_string().unwrap())
+            .json(&scope)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+    let response = client
+        .post(format!("{base_url}/node/trace"))
+        .bearer_auth(admin_token.to_jwt_string().unwrap())
+        .json(&scope)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+}
@@ -0,0 +1,154 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[allow(dead_code)]
+mod common;
+use common::{sample_account, sample_genesis_block, test_peer::TestPeer};
+
+use snarkos_account::Account;
+use snarkos_node_router::{
+    Outbound,
+    messages::{BlockAnnounce, BlockRequest, Features, Message, NodeType, PeerRequest},
+};
+use snarkos_node_tcp::P2P;
+use snarkvm::prelude::{Address, MainnetV0 as CurrentNetwork};
+
+use deadline::deadline;
+use pea2pea::{Pea2Pea, protocols::Writing};
+use std::{net::SocketAddr, time::Duration};
+
+/// Returns `true` if the test peer received a message matching the given predicate, over its only connection.
+fn received(peer: &TestPeer, predicate: impl Fn(&Message<CurrentNetwork>) -> bool) -> bool {
+    let Some(peer_addr) = peer.node().connected_addrs().first().copied() else { return false };
+    peer.received_from(peer_addr).iter().any(predicate)
+}
+
+/// Returns `true` if the test peer received an announcement of the genesis block, over its only connection.
+fn received_genesis_announcement(peer: &TestPeer) -> bool {
+    received(peer, |message| matches!(message, Message::BlockAnnounce(announcement) if announcement.height == 0))
+}
+
+/// Returns `true` if the test peer received a request for block 1 from the given peer address.
+fn requested_block_1(peer: &TestPeer, peer_addr: SocketAddr) -> bool {
+    peer.received_from(peer_addr)
+        .iter()
+        .any(|message| matches!(message, Message::BlockRequest(BlockRequest { start_height: 1, end_height: 2 })))
+}
+
+#[tokio::test]
+async fn test_announced_client_requests_block_ahead_of_gossip() {
+    // Spin up a client that negotiates block announcements, and a gossip-only client.
+    let early_client = common::node::client_with_early_block_announce(true).await;
+    let gossip_client = common::node::client().await;
+
+    // Spin up a test peer acting as a validator that negotiates block announcements.
+    let validator = TestPeer::with_features(NodeType::Validator, sample_account(), Features::BLOCK_ANNOUNCE).await;
+    let validator_addr = validator.node().listening_addr().unwrap();
+
+    // Connect the clients to the validator, one at a time, to tell their connections apart.
+    early_client.router().connect(validator_addr).unwrap().await.unwrap();
+    let validator_clone = validator.clone();
+    deadline!(Duration::from_secs(5), move || validator_clone.node().num_connected() == 1);
+    let early_addr = *validator.node().connected_addrs().first().unwrap();
+    gossip_client.router().connect(validator_addr).unwrap().await.unwrap();
+    let validator_clone = validator.clone();
+    deadline!(Duration::from_secs(5), move || validator_clone.node().num_connected() == 2);
+    let gossip_addr = validator.node().connected_addrs().into_iter().find(|addr| *addr != early_addr).unwrap();
+
+    // Announce block 1 to the client that negotiated block announcements.
+    let announcement =
+        BlockAnnounce { height: 1, hash: sample_genesis_block().hash(), timestamp: 0, num_transactions: 0 };
+    assert!(validator.unicast(early_addr, Message::BlockAnnounce(announcement)).is_ok());
+
+    // Ensure the announced client requests the block right away, from the validator.
+    let validator_clone = validator.clone();
+    deadline!(Duration::from_secs(5), move || requested_block_1(&validator_clone, early_addr));
+    assert_eq!(early_client.router().number_of_connected_peers(), 1);
+
+    // Ensure the gossip-only client has not requested the block, as it only learns of it from the block locators.
+    assert!(!requested_block_1(&validator, gossip_addr));
+
+    // Ensure the gossip-only client treats an announcement as a protocol violation, as it did not negotiate them.
+    assert!(validator.unicast(gossip_addr, Message::BlockAnnounce(announcement)).is_ok());
+    let gossip_client_clone = gossip_client.clone();
+    deadline!(Duration::from_secs(5), move || gossip_client_clone.router().number_of_connected_peers() == 0);
+}
+
+#[tokio::test]
+async fn test_validator_announces_to_validators_before_clients() {
+    // Spin up a validator that announces the blocks it commits.
+    let validator = common::node::validator_with_early_block_announce(true).await;
+
+    // Spin up test peers that negotiate block announcements: a client, a validator, and a committee member.
+    let rng = &mut rand::thread_rng();
+    let client = TestPeer::with_features(NodeType::Client, Account::new(rng).unwrap(), Features::BLOCK_ANNOUNCE).await;
+    let peer_validator =
+        TestPeer::with_features(NodeType::Validator, Account::new(rng).unwrap(), Features::BLOCK_ANNOUNCE).await;
+    let member =
+        TestPeer::with_features(NodeType::Validator, Account::new(rng).unwrap(), Features::BLOCK_ANNOUNCE).await;
+
+    // Connect the validator to the peers, in the reverse order of their priority.
+    for peer in [&client, &peer_validator, &member] {
+        validator.router().connect(peer.node().listening_addr().unwrap()).unwrap().await.unwrap();
+    }
+    let validator_clone = validator.clone();
+    deadline!(Duration::from_secs(5), move || validator_clone.router().number_of_connected_peers() == 3);
+
+    // Ensure the committee member comes first, then the other validator, and the client last.
+    let member_address = member.address();
+    let is_committee_member = |address: &Address<CurrentNetwork>| *address == member_address;
+    let recipients = validator.router().connected_peers_by_priority_with(Features::BLOCK_ANNOUNCE, is_committee_member);
+    let [member_addr, validator_addr, client_addr] =
+        [&member, &peer_validator, &client].map(|peer| peer.node().listening_addr().unwrap());
+    assert_eq!(recipients, vec![member_addr, validator_addr, client_addr]);
+
+    // Announce the genesis block, and ensure every peer receives the announcement.
+    validator.announce_block(&sample_genesis_block());
+    for peer in [member, peer_validator, client] {
+        deadline!(Duration::from_secs(5), move || received_genesis_announcement(&peer));
+    }
+}
+
+#[tokio::test]
+async fn test_validator_propagates_to_validators_before_clients() {
+    // Spin up a validator with the default configuration, which does not announce the blocks it commits.
+    let validator = common::node::validator().await;
+
+    // Spin up test peers without any features: a client and a validator.
+    let rng = &mut rand::thread_rng();
+    let client = TestPeer::new(NodeType::Client, Account::new(rng).unwrap()).await;
+    let peer_validator = TestPeer::new(NodeType::Validator, Account::new(rng).unwrap()).await;
+
+    // Connect the validator to the peers, in the reverse order of their priority.
+    for peer in [&client, &peer_validator] {
+        validator.router().connect(peer.node().listening_addr().unwrap()).unwrap().await.unwrap();
+    }
+    let validator_clone = validator.clone();
+    deadline!(Duration::from_secs(5), move || validator_clone.router().number_of_connected_peers() == 2);
+
+    // Ensure the gossip of the validator reaches the other validator ahead of the client,
+    // through the committee of its own ledger.
+    let [validator_addr, client_addr] = [&peer_validator, &client].map(|peer| peer.node().listening_addr().unwrap());
+    assert_eq!(validator.connected_peers_by_priority(Features::NONE), vec![validator_addr, client_addr]);
+
+    // Propagate a message on the default path, and ensure every peer receives it.
+    validator.propagate(Message::PeerRequest(PeerRequest), &[]);
+    for peer in [peer_validator, client] {
+        deadline!(Duration::from_secs(5), move || received(&peer, |message| matches!(
+            message,
+            Message::PeerRequest(_)
+        )));
+    }
+}
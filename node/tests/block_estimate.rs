@@ -0,0 +1,73 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![recursion_limit = "256"]
+
+#[allow(dead_code)]
+mod common;
+use common::sample_account;
+
+use snarkos_node::Client;
+use snarkos_node_bft_ledger_service::test_helpers::sample_ledger;
+use snarkos_node_rest::{NodeConfig, Rest, block_routes};
+use snarkos_node_router::messages::NodeType;
+use snarkvm::prelude::{Ledger, MainnetV0 as CurrentNetwork, store::helpers::memory::ConsensusMemory};
+
+use aleo_std::StorageMode;
+use rand::SeedableRng;
+use rand_chacha::ChaChaRng;
+use reqwest::{StatusCode, header::RETRY_AFTER};
+use std::net::SocketAddr;
+
+type CurrentLedger = ConsensusMemory<CurrentNetwork>;
+
+/// Serves the block routes over the given ledger, without consensus nor routing, and returns their address.
+async fn serve_block_routes(ledger: Ledger<CurrentNetwork, CurrentLedger>) -> SocketAddr {
+    let config =
+        NodeConfig::new(NodeType::Client, sample_account().address(), None, None, &StorageMode::Production, None, &[]);
+    let rest =
+        Rest::<CurrentNetwork, CurrentLedger, Client<CurrentNetwork, CurrentLedger>>::new(None, ledger, None, config)
+            .unwrap();
+    let app = axum::Router::new().nest("/mainnet", block_routes(rest).into());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await.unwrap();
+    });
+    address
+}
+
+#[tokio::test]
+async fn test_block_estimate_of_genesis_ledger() {
+    // Ensure a ledger with only the genesis block is reported as unavailable, rather than as a server fault.
+    let rng = &mut ChaChaRng::seed_from_u64(1234567890u64);
+    let (_, ledger) = sample_ledger::<CurrentNetwork, _>(0, rng);
+    let address = serve_block_routes(ledger).await;
+    let response = reqwest::get(format!("http://{address}/mainnet/block/next/estimate")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert!(response.headers().contains_key(RETRY_AFTER));
+}
+
+#[tokio::test]
+async fn test_block_estimate_of_ledger() {
+    // Ensure the next block is estimated once the ledger has a block interval.
+    let rng = &mut ChaChaRng::seed_from_u64(1234567890u64);
+    let (_, ledger) = sample_ledger::<CurrentNetwork, _>(2, rng);
+    let address = serve_block_routes(ledger).await;
+    let response = reqwest::get(format!("http://{address}/mainnet/block/next/estimate")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let estimate: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(estimate["latest_height"], 2);
+}
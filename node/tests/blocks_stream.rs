@@ -0,0 +1,89 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![recursion_limit = "256"]
+
+#[allow(dead_code)]
+mod common;
+use common::sample_account;
+
+use snarkos_node::Client;
+use snarkos_node_bft_ledger_service::test_helpers::sample_ledger;
+use snarkos_node_rest::{NodeConfig, Rest, block_routes};
+use snarkos_node_router::messages::NodeType;
+use snarkvm::prelude::{Block, MainnetV0 as CurrentNetwork, store::helpers::memory::ConsensusMemory};
+
+use aleo_std::StorageMode;
+use rand::SeedableRng;
+use rand_chacha::ChaChaRng;
+use reqwest::StatusCode;
+use std::net::SocketAddr;
+
+type CurrentLedger = ConsensusMemory<CurrentNetwork>;
+
+/// The number of blocks on top of the genesis block, which spans more than one chunk of a streamed response.
+const NUM_BLOCKS: u32 = 12;
+
+#[tokio::test]
+async fn test_blocks_are_streamed() {
+    // Initialize the state of the routes, without consensus nor routing, over a ledger with a few blocks.
+    let rng = &mut ChaChaRng::seed_from_u64(1234567890u64);
+    let (_, ledger) = sample_ledger::<CurrentNetwork, _>(NUM_BLOCKS, rng);
+    let config =
+        NodeConfig::new(NodeType::Client, sample_account().address(), None, None, &StorageMode::Production, None, &[]);
+    let rest = Rest::<CurrentNetwork, CurrentLedger, Client<CurrentNetwork, CurrentLedger>>::new(
+        None,
+        ledger.clone(),
+        None,
+        config,
+    )
+    .unwrap();
+
+    // Mount the block routes in a bare application, under the network.
+    let app = axum::Router::new().nest("/mainnet", block_routes(rest).into());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await.unwrap();
+    });
+    let blocks_url = |start: u32, end: u32| format!("http://{address}/mainnet/blocks?start={start}&end={end}");
+    let client = reqwest::Client::new();
+
+    // Ensure the whole ledger is streamed, in the pretty format of the collected blocks.
+    let response = client.get(blocks_url(0, NUM_BLOCKS + 1)).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.text().await.unwrap();
+    let expected = (0..=NUM_BLOCKS).map(|height| ledger.get_block(height).unwrap()).collect::<Vec<_>>();
+    assert_eq!(body, serde_json::to_string_pretty(&expected).unwrap());
+    let blocks: Vec<Block<CurrentNetwork>> = serde_json::from_str(&body).unwrap();
+    assert_eq!(blocks, expected);
+
+    // Ensure an empty range is an empty array.
+    let response = client.get(blocks_url(3, 3)).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.text().await.unwrap(), "[]");
+
+    // Ensure the invalid ranges are rejected.
+    assert_eq!(client.get(blocks_url(3, 2)).send().await.unwrap().status(), StatusCode::BAD_REQUEST);
+    assert_eq!(client.get(blocks_url(0, 51)).send().await.unwrap().status(), StatusCode::BAD_REQUEST);
+
+    // Ensure a range beyond the latest block fails, before the response starts if its first chunk fails.
+    let response = client.get(blocks_url(NUM_BLOCKS + 1, NUM_BLOCKS + 2)).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    // Otherwise, the stream is aborted, and the body is incomplete.
+    let response = client.get(blocks_url(0, NUM_BLOCKS + 5)).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.text().await.is_err());
+}
@@ -0,0 +1,61 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![recursion_limit = "256"]
+
+#[allow(dead_code)]
+mod common;
+use common::{sample_account, sample_genesis_block};
+
+use snarkos_node::Client;
+use snarkos_node_rest::{Claims, NodeConfig, Rest, admin_routes};
+use snarkos_node_router::messages::NodeType;
+use snarkvm::prelude::{Ledger, MainnetV0 as CurrentNetwork, store::helpers::memory::ConsensusMemory};
+
+use aleo_std::StorageMode;
+use reqwest::{StatusCode, header::RETRY_AFTER};
+use std::net::SocketAddr;
+
+type CurrentLedger = ConsensusMemory<CurrentNetwork>;
+
+#[tokio::test]
+async fn test_disabled_broadcast_journal() {
+    // Initialize the state of the routes, without consensus nor routing, and without a broadcast journal.
+    let account = sample_account();
+    let ledger =
+        Ledger::<CurrentNetwork, CurrentLedger>::load(sample_genesis_block(), StorageMode::Production).unwrap();
+    let config = NodeConfig::new(NodeType::Client, account.address(), None, None, &StorageMode::Production, None, &[]);
+    let rest =
+        Rest::<CurrentNetwork, CurrentLedger, Client<CurrentNetwork, CurrentLedger>>::new(None, ledger, None, config)
+            .unwrap();
+
+    // Mount the administrative routes in a bare application, under the network.
+    let app = axum::Router::new().nest("/mainnet", admin_routes(rest).into());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await.unwrap();
+    });
+
+    // Ensure the disabled journal is reported as unavailable, without a retry, rather than as a server fault.
+    let response = reqwest::Client::new()
+        .get(format!("http://{address}/mainnet/node/broadcast-journal"))
+        .bearer_auth(Claims::new(account.address()).to_jwt_string().unwrap())
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert!(!response.headers().contains_key(RETRY_AFTER));
+}
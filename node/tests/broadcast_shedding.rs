@@ -0,0 +1,86 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![recursion_limit = "256"]
+
+#[allow(dead_code)]
+mod common;
+use common::{sample_account, sample_genesis_block};
+
+use snarkos_node::Client;
+use snarkos_node_consensus::MempoolPressure;
+use snarkos_node_rest::{NodeConfig, Rest, transaction_routes};
+use snarkos_node_router::messages::NodeType;
+use snarkvm::prelude::{Ledger, MainnetV0 as CurrentNetwork, store::helpers::memory::ConsensusMemory};
+
+use aleo_std::StorageMode;
+use reqwest::{StatusCode, header::RETRY_AFTER};
+use std::{net::SocketAddr, sync::Arc, time::Instant};
+
+type CurrentLedger = ConsensusMemory<CurrentNetwork>;
+
+/// Returns the status code and the `Retry-After` header of a broadcast of the given body to the given route.
+async fn broadcast(client: &reqwest::Client, url: &str, body: &'static str) -> (StatusCode, Option<String>) {
+    let response = client.post(url).header("Content-Type", "application/json").body(body).send().await.unwrap();
+    let retry_after = response.headers().get(RETRY_AFTER).map(|value| value.to_str().unwrap().to_string());
+    (response.status(), retry_after)
+}
+
+#[tokio::test]
+async fn test_broadcasts_are_shed_under_pressure() {
+    // Initialize the state of the routes, without consensus nor routing, under the given memory pool pressure.
+    let ledger =
+        Ledger::<CurrentNetwork, CurrentLedger>::load(sample_genesis_block(), StorageMode::Production).unwrap();
+    let config =
+        NodeConfig::new(NodeType::Client, sample_account().address(), None, None, &StorageMode::Production, None, &[]);
+    let mut rest =
+        Rest::<CurrentNetwork, CurrentLedger, Client<CurrentNetwork, CurrentLedger>>::new(None, ledger, None, config)
+            .unwrap();
+    let pressure = Arc::new(MempoolPressure::default());
+    rest.set_mempool_pressure(pressure.clone());
+
+    // Mount the transaction routes in a bare application, under the network.
+    let app = axum::Router::new().nest("/mainnet", transaction_routes(rest).into());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await.unwrap();
+    });
+    let transaction_url = format!("http://{address}/mainnet/transaction/broadcast");
+    let solution_url = format!("http://{address}/mainnet/solution/broadcast");
+    let client = reqwest::Client::new();
+
+    // Without pressure, the malformed broadcasts reach the deserialization of their body.
+    assert_eq!(broadcast(&client, &transaction_url, "not a transaction").await.0, StatusCode::BAD_REQUEST);
+
+    // Saturate the transactions queue, which drained 60 transmissions over the last minute, i.e. one per second.
+    pressure.transactions().update(1000, 1000);
+    pressure.transactions().record_drained(60, Instant::now());
+    // The first broadcast of the IP is let through, under its allowance.
+    assert_eq!(broadcast(&client, &transaction_url, "not a transaction").await.0, StatusCode::BAD_REQUEST);
+    // The next broadcasts of the IP are shed, before their body is even deserialized.
+    let (status, retry_after) = broadcast(&client, &transaction_url, "not a transaction").await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(retry_after.as_deref(), Some("60"));
+    assert_eq!(pressure.transactions().num_shed(), 1);
+    // The solutions queue is not under pressure, so the solution broadcasts are not shed.
+    assert_eq!(broadcast(&client, &solution_url, "not a solution").await.0, StatusCode::BAD_REQUEST);
+    assert_eq!(pressure.solutions().num_shed(), 0);
+
+    // Once the queue drains below the high watermark, the broadcasts are processed again.
+    pressure.transactions().update(100, 1000);
+    assert_eq!(broadcast(&client, &transaction_url, "not a transaction").await.0, StatusCode::BAD_REQUEST);
+    assert_eq!(pressure.transactions().num_shed(), 1);
+}
@@ -15,23 +15,60 @@
 
 use crate::common::test_peer::sample_genesis_block;
 use snarkos_account::Account;
-use snarkos_node::{Client, Prover, Validator};
+use snarkos_node::{
+    Client,
+    Prover,
+    Validator,
+    bft::helpers::ValidatorsResponseMode,
+    consensus::{DEFAULT_INBOUND_QUEUE_TTL_IN_SECS, DefaultMempoolPolicy},
+    sync::DEFAULT_MAX_REORG_DEPTH,
+};
 use snarkvm::prelude::{MainnetV0 as CurrentNetwork, store::helpers::memory::ConsensusMemory};
 
 use aleo_std::StorageMode;
-use std::str::FromStr;
+use std::{str::FromStr, sync::Arc, time::Duration};
 
 pub async fn client() -> Client<CurrentNetwork, ConsensusMemory<CurrentNetwork>> {
+    client_with_early_block_announce(false).await
+}
+
+pub async fn client_with_early_block_announce(
+    early_block_announce: bool,
+) -> Client<CurrentNetwork, ConsensusMemory<CurrentNetwork>> {
+    client_with(early_block_announce, DEFAULT_MAX_REORG_DEPTH).await
+}
+
+pub async fn client_with_max_reorg_depth(
+    max_reorg_depth: u32,
+) -> Client<CurrentNetwork, ConsensusMemory<CurrentNetwork>> {
+    client_with(false, max_reorg_depth).await
+}
+
+async fn client_with(
+    early_block_announce: bool,
+    max_reorg_depth: u32,
+) -> Client<CurrentNetwork, ConsensusMemory<CurrentNetwork>> {
     Client::new(
         "127.0.0.1:0".parse().unwrap(),
         None,
         10,
+        None, // No broadcast journal.
+        0,    // No recent block summaries.
+        None, // No log file.
         Account::<CurrentNetwork>::from_str("APrivateKey1zkp2oVPTci9kKcUprnbzMwq95Di1MQERpYBhEeqvkrDirK1").unwrap(),
         &[],
+        None,               // The default peer limits.
+        Default::default(), // The default handshake limits.
+        None,               // No maximum pool memory.
+        false,              // No strict configuration check.
         sample_genesis_block(),
         None, // No CDN.
         StorageMode::Production,
+        false, // No write verification.
+        max_reorg_depth,
         false, // No extra peer rotation.
+        early_block_announce,
+        Default::default(), // The default experiments.
         Default::default(),
     )
     .await
@@ -43,8 +80,14 @@ pub async fn prover() -> Prover<CurrentNetwork, ConsensusMemory<CurrentNetwork>>
         "127.0.0.1:0".parse().unwrap(),
         Account::<CurrentNetwork>::from_str("APrivateKey1zkp2oVPTci9kKcUprnbzMwq95Di1MQERpYBhEeqvkrDirK1").unwrap(),
         &[],
+        None,               // The default peer limits.
+        Default::default(), // The default handshake limits.
+        None,               // No maximum pool memory.
+        false,              // No strict configuration check.
         sample_genesis_block(),
         StorageMode::Production,
+        Default::default(), // The default experiments.
+        Default::default(), // The default proving thread pool.
         Default::default(),
     )
     .await
@@ -52,19 +95,43 @@ pub async fn prover() -> Prover<CurrentNetwork, ConsensusMemory<CurrentNetwork>>
 }
 
 pub async fn validator() -> Validator<CurrentNetwork, ConsensusMemory<CurrentNetwork>> {
+    validator_with_early_block_announce(false).await
+}
+
+pub async fn validator_with_early_block_announce(
+    early_block_announce: bool,
+) -> Validator<CurrentNetwork, ConsensusMemory<CurrentNetwork>> {
     Validator::new(
         "127.0.0.1:0".parse().unwrap(),
         None,
         None,
         10,
+        None, // No broadcast journal.
+        0,    // No recent block summaries.
+        None, // No log file.
         Account::<CurrentNetwork>::from_str("APrivateKey1zkp2oVPTci9kKcUprnbzMwq95Di1MQERpYBhEeqvkrDirK1").unwrap(),
         &[],
+        None,               // The default peer limits.
+        Default::default(), // The default handshake limits.
+        None,               // No maximum pool memory.
+        false,              // No strict configuration check.
         &[],
+        ValidatorsResponseMode::Full,
+        false, // No pause on a duplicate identity.
+        Arc::new(DefaultMempoolPolicy),
+        None, // No mempool policy file.
+        Duration::from_secs(DEFAULT_INBOUND_QUEUE_TTL_IN_SECS),
+        None,                   // No memory pool file.
         sample_genesis_block(), // Should load the current network's genesis block.
         None,                   // No CDN.
         StorageMode::Production,
+        false, // No write verification.
         true,  // This test requires validators to connect to peers.
-        false, // No dev traffic in production mode.
+        early_block_announce,
+        Default::default(), // The default experiments.
+        None,               // No health alerts.
+        false,              // No dev traffic in production mode.
+        false,              // No strict account check.
         Default::default(),
     )
     .await
@@ -16,7 +16,7 @@
 use snarkos_account::Account;
 use snarkos_node_router::{
     expect_message,
-    messages::{ChallengeRequest, ChallengeResponse, Message, MessageCodec, MessageTrait, NodeType},
+    messages::{ChallengeRequest, ChallengeResponse, Features, Message, MessageCodec, MessageTrait, NodeType},
 };
 use snarkvm::{
     ledger::narwhal::Data,
@@ -27,6 +27,7 @@ use std::{
     io,
     net::{IpAddr, Ipv4Addr, SocketAddr},
     str::FromStr,
+    sync::{Arc, Mutex},
 };
 
 use futures_util::{TryStreamExt, sink::SinkExt};
@@ -59,6 +60,8 @@ pub struct TestPeer {
     node: Node,
     node_type: NodeType,
     account: Account<CurrentNetwork>,
+    features: Features,
+    received: Arc<Mutex<Vec<(SocketAddr, Message<CurrentNetwork>)>>>,
 }
 
 impl Pea2Pea for TestPeer {
@@ -81,6 +84,10 @@ impl TestPeer {
     }
 
     pub async fn new(node_type: NodeType, account: Account<CurrentNetwork>) -> Self {
+        Self::with_features(node_type, account, Features::NONE).await
+    }
+
+    pub async fn with_features(node_type: NodeType, account: Account<CurrentNetwork>, features: Features) -> Self {
         let peer = Self {
             node: Node::new(Config {
                 max_connections: 200,
@@ -89,6 +96,8 @@ impl TestPeer {
             }),
             node_type,
             account,
+            features,
+            received: Default::default(),
         };
 
         peer.enable_handshake().await;
@@ -112,6 +121,12 @@ impl TestPeer {
     pub fn address(&self) -> Address<CurrentNetwork> {
         self.account.address()
     }
+
+    /// Returns the messages received from the given peer address.
+    pub fn received_from(&self, peer_addr: SocketAddr) -> Vec<Message<CurrentNetwork>> {
+        let received = self.received.lock().unwrap();
+        received.iter().filter(|(addr, _)| *addr == peer_addr).map(|(_, message)| message.clone()).collect()
+    }
 }
 
 impl Handshake for TestPeer {
@@ -137,7 +152,8 @@ impl Handshake for TestPeer {
         match node_side {
             ConnectionSide::Initiator => {
                 // Send a challenge request to the peer.
-                let our_request = ChallengeRequest::new(local_ip.port(), self.node_type(), self.address(), rng.gen());
+                let our_request =
+                    ChallengeRequest::new(local_ip.port(), self.node_type(), self.address(), rng.gen(), self.features);
                 framed.send(Message::ChallengeRequest(our_request)).await?;
 
                 // Receive the peer's challenge bundle.
@@ -146,7 +162,14 @@ impl Handshake for TestPeer {
 
                 // Sign the nonce.
                 let response_nonce: u64 = rng.gen();
-                let data = [peer_request.nonce.to_le_bytes(), response_nonce.to_le_bytes()].concat();
+                let data = ChallengeResponse::signed_bytes(
+                    Message::<CurrentNetwork>::VERSION,
+                    peer_request.version,
+                    self.address(),
+                    peer_request.address,
+                    peer_request.nonce,
+                    response_nonce,
+                )?;
                 let signature = self.account().sign_bytes(&data, rng).unwrap();
 
                 // Send the challenge response.
@@ -155,6 +178,8 @@ impl Handshake for TestPeer {
                     restrictions_id,
                     signature: Data::Object(signature),
                     nonce: response_nonce,
+                    // Note: The test peer does not negotiate a frame size, so it is held to the global limit.
+                    max_frame_size: None,
                 };
                 framed.send(Message::ChallengeResponse(our_response)).await?;
             }
@@ -164,7 +189,14 @@ impl Handshake for TestPeer {
 
                 // Sign the nonce.
                 let response_nonce: u64 = rng.gen();
-                let data = [peer_request.nonce.to_le_bytes(), response_nonce.to_le_bytes()].concat();
+                let data = ChallengeResponse::signed_bytes(
+                    Message::<CurrentNetwork>::VERSION,
+                    peer_request.version,
+                    self.address(),
+                    peer_request.address,
+                    peer_request.nonce,
+                    response_nonce,
+                )?;
                 let signature = self.account().sign_bytes(&data, rng).unwrap();
 
                 // Send our challenge bundle.
@@ -173,9 +205,11 @@ impl Handshake for TestPeer {
                     restrictions_id,
                     signature: Data::Object(signature),
                     nonce: response_nonce,
+                    max_frame_size: None,
                 };
                 framed.send(Message::ChallengeResponse(our_response)).await?;
-                let our_request = ChallengeRequest::new(local_ip.port(), self.node_type(), self.address(), rng.gen());
+                let our_request =
+                    ChallengeRequest::new(local_ip.port(), self.node_type(), self.address(), rng.gen(), self.features);
                 framed.send(Message::ChallengeRequest(our_request)).await?;
 
                 // Listen for the challenge response.
@@ -204,7 +238,8 @@ impl Reading for TestPeer {
         Default::default()
     }
 
-    async fn process_message(&self, _peer_ip: SocketAddr, _message: Self::Message) -> io::Result<()> {
+    async fn process_message(&self, peer_addr: SocketAddr, message: Self::Message) -> io::Result<()> {
+        self.received.lock().unwrap().push((peer_addr, message));
         Ok(())
     }
 }
@@ -0,0 +1,111 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![recursion_limit = "256"]
+
+#[allow(dead_code)]
+mod common;
+use common::test_peer::TestPeer;
+
+use snarkos_node_bft_ledger_service::test_helpers::{sample_ledger, sample_transaction_with_priority_fee};
+use snarkos_node_consensus::MAX_DEPLOYMENTS_PER_PEER;
+use snarkos_node_router::messages::{Message, UnconfirmedTransaction};
+use snarkos_node_tcp::P2P;
+use snarkvm::prelude::{MainnetV0 as CurrentNetwork, Network, Program, block::Transaction};
+
+use deadline::deadline;
+use pea2pea::{Pea2Pea, protocols::Writing};
+use rand::SeedableRng;
+use rand_chacha::ChaChaRng;
+use std::{collections::HashSet, net::SocketAddr, str::FromStr, time::Duration};
+
+/// Returns the given number of distinct deployments, standing in for the deployments of as many developers.
+fn sample_deployments(num_deployments: usize) -> Vec<Transaction<CurrentNetwork>> {
+    let rng = &mut ChaChaRng::seed_from_u64(1234567890u64);
+    let (private_key, ledger) = sample_ledger::<CurrentNetwork, _>(0, rng);
+    let program = Program::<CurrentNetwork>::from_str(
+        r"
+program deployment_rate_test.aleo;
+
+function compute:
+    input r0 as u32.private;
+    output r0 as u32.private;",
+    )
+    .unwrap();
+    let deployment = ledger.vm().deploy(&private_key, &program, None, 0, None, rng).unwrap();
+    (0..num_deployments).map(|i| sample_transaction_with_priority_fee(&deployment, i as u64)).collect()
+}
+
+/// Sends the given transactions from the test peer, over its only connection.
+fn send(peer: &TestPeer, transactions: &[Transaction<CurrentNetwork>]) {
+    let node_addr = *peer.node().connected_addrs().first().unwrap();
+    for transaction in transactions {
+        let message = Message::UnconfirmedTransaction(UnconfirmedTransaction::from(transaction.clone()));
+        assert!(peer.unicast(node_addr, message).is_ok());
+    }
+}
+
+/// Returns the IDs of the transactions that the test peer received from the given node address.
+fn received(peer: &TestPeer, node_addr: SocketAddr) -> HashSet<<CurrentNetwork as Network>::TransactionID> {
+    peer.received_from(node_addr)
+        .into_iter()
+        .filter_map(|message| match message {
+            Message::UnconfirmedTransaction(message) => Some(message.transaction_id),
+            _ => None,
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_deployments_beyond_the_rate_limit_are_dropped() {
+    // Spin up a validator, with a relaying client, a trusted client, and a validator observing the propagation.
+    let validator = common::node::validator().await;
+    let (relay, trusted, observer) = (TestPeer::client().await, TestPeer::client().await, TestPeer::validator().await);
+    let trusted_addr = trusted.node().listening_addr().unwrap();
+    validator.router().insert_trusted_peer(trusted_addr);
+    for peer in [&relay, &trusted, &observer] {
+        validator.router().connect(peer.node().listening_addr().unwrap()).unwrap().await.unwrap();
+    }
+    let validator_clone = validator.clone();
+    deadline!(Duration::from_secs(5), move || validator_clone.router().number_of_connected_peers() == 3);
+    let validator_addr = *observer.node().connected_addrs().first().unwrap();
+
+    // Relay more deployments than the rate limit, from both clients.
+    let num_deployments = MAX_DEPLOYMENTS_PER_PEER + 1;
+    let deployments = sample_deployments(2 * num_deployments);
+    let (relayed, trusted_deployments) = deployments.split_at(num_deployments);
+    send(&relay, relayed);
+    send(&trusted, trusted_deployments);
+
+    // Ensure the deployments of the relay are admitted up to its rate limit, and those of the trusted client are all
+    // admitted, as they are propagated to the observer.
+    let expected_len = MAX_DEPLOYMENTS_PER_PEER + num_deployments;
+    let observer_clone = observer.clone();
+    deadline!(Duration::from_secs(10), move || received(&observer_clone, validator_addr).len() >= expected_len);
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    let propagated = received(&observer, validator_addr);
+    assert_eq!(propagated.len(), expected_len);
+    assert_eq!(
+        relayed.iter().filter(|transaction| propagated.contains(&transaction.id())).count(),
+        MAX_DEPLOYMENTS_PER_PEER
+    );
+    assert!(trusted_deployments.iter().all(|transaction| propagated.contains(&transaction.id())));
+
+    // Ensure the relay is neither restricted nor disconnected, as only its extra deployment was dropped.
+    let relay_addr = relay.node().listening_addr().unwrap();
+    assert!(!validator.router().is_restricted(&relay_addr));
+    assert!(!validator.router().is_restricted(&trusted_addr));
+    assert_eq!(validator.router().number_of_connected_peers(), 3);
+}
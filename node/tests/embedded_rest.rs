@@ -0,0 +1,60 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![recursion_limit = "256"]
+
+#[allow(dead_code)]
+mod common;
+use common::{sample_account, sample_genesis_block};
+
+use snarkos_node::Client;
+use snarkos_node_rest::{NodeConfig, Rest, block_routes};
+use snarkos_node_router::messages::NodeType;
+use snarkvm::prelude::{Ledger, MainnetV0 as CurrentNetwork, store::helpers::memory::ConsensusMemory};
+
+use aleo_std::StorageMode;
+use std::net::SocketAddr;
+
+type CurrentLedger = ConsensusMemory<CurrentNetwork>;
+
+#[tokio::test]
+async fn test_block_routes_in_external_app() {
+    // Initialize the state of the routes, without consensus nor routing.
+    let genesis = sample_genesis_block();
+    let ledger = Ledger::<CurrentNetwork, CurrentLedger>::load(genesis.clone(), StorageMode::Production).unwrap();
+    let config =
+        NodeConfig::new(NodeType::Client, sample_account().address(), None, None, &StorageMode::Production, None, &[]);
+    let rest =
+        Rest::<CurrentNetwork, CurrentLedger, Client<CurrentNetwork, CurrentLedger>>::new(None, ledger, None, config)
+            .unwrap();
+
+    // Mount the block routes in a bare application, under its own prefix.
+    let app = axum::Router::new().nest("/api", block_routes(rest).into());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await.unwrap();
+    });
+
+    // Ensure the latest block is served.
+    let response = reqwest::get(format!("http://{address}/api/block/latest")).await.unwrap();
+    assert!(response.status().is_success());
+    let block: serde_json::Value = serde_json::from_str(&response.text().await.unwrap()).unwrap();
+    assert_eq!(block["block_hash"], genesis.hash().to_string());
+
+    // Ensure the routes of the other groups are not mounted.
+    let response = reqwest::get(format!("http://{address}/api/node/status")).await.unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+}
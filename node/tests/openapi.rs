@@ -0,0 +1,297 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![recursion_limit = "256"]
+
+#[allow(dead_code)]
+mod common;
+use common::{sample_account, sample_genesis_block};
+
+use snarkos_node::Client;
+use snarkos_node_rest::{
+    BlockEstimate,
+    BlockSummary,
+    CommitteePage,
+    ConfidenceWindow,
+    FeeEstimate,
+    FeePercentiles,
+    LatestBlockInfo,
+    NodeConfig,
+    Rest,
+    RoundProgress,
+    SyncStatus,
+    routes,
+};
+use snarkos_node_router::messages::NodeType;
+use snarkvm::prelude::{Ledger, MainnetV0 as CurrentNetwork, store::helpers::memory::ConsensusMemory};
+
+use aleo_std::StorageMode;
+use serde_json::Value;
+use std::{collections::HashSet, net::SocketAddr};
+
+type CurrentLedger = ConsensusMemory<CurrentNetwork>;
+
+/// The environment variable with the path to write the OpenAPI specification to, e.g. to validate it in CI.
+const OPENAPI_SPEC_PATH: &str = "OPENAPI_SPEC_PATH";
+
+/// Serves the routes of the REST server, without consensus nor routing, and returns its address and ledger.
+async fn serve_rest() -> (SocketAddr, Ledger<CurrentNetwork, CurrentLedger>) {
+    let ledger =
+        Ledger::<CurrentNetwork, CurrentLedger>::load(sample_genesis_block(), StorageMode::Production).unwrap();
+    let config =
+        NodeConfig::new(NodeType::Client, sample_account().address(), None, None, &StorageMode::Production, None, &[]);
+    let rest = Rest::<CurrentNetwork, CurrentLedger, Client<CurrentNetwork, CurrentLedger>>::new(
+        None,
+        ledger.clone(),
+        None,
+        config,
+    )
+    .unwrap();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, routes(rest).into_make_service_with_connect_info::<SocketAddr>()).await.unwrap();
+    });
+    (address, ledger)
+}
+
+/// Validates the given JSON value against the given schema of the specification, and returns the first mismatch.
+///
+/// Every property of an object schema is expected, as the response types have no optional fields.
+fn validate(spec: &Value, schema: &Value, value: &Value, location: &str) -> Result<(), String> {
+    if value.is_null() {
+        return match schema["nullable"].as_bool() {
+            Some(true) => Ok(()),
+            _ => Err(format!("'{location}' is null, but is not nullable")),
+        };
+    }
+    if let Some(reference) = schema["$ref"].as_str() {
+        let Some(schema) = spec.pointer(reference.trim_start_matches('#')) else {
+            return Err(format!("Unknown reference '{reference}'"));
+        };
+        return validate(spec, schema, value, location);
+    }
+    if let Some(schemas) = schema["oneOf"].as_array() {
+        return match schemas.iter().any(|schema| validate(spec, schema, value, location).is_ok()) {
+            true => Ok(()),
+            false => Err(format!("'{location}' matches none of its schemas")),
+        };
+    }
+
+    let is_valid_type = match schema["type"].as_str() {
+        Some("integer") => value.is_i64() || value.is_u64(),
+        Some("number") => value.is_number(),
+        Some("string") => value.is_string(),
+        Some("boolean") => value.is_boolean(),
+        Some("array") => value.is_array(),
+        Some("object") => value.is_object(),
+        _ => true,
+    };
+    if !is_valid_type {
+        return Err(format!("'{location}' is not of type {}: {value}", schema["type"]));
+    }
+    if let Some(variants) = schema["enum"].as_array() {
+        if !variants.contains(value) {
+            return Err(format!("'{location}' is not one of {}: {value}", schema["enum"]));
+        }
+    }
+    if let (Some(items), Some(values)) = (schema.get("items"), value.as_array()) {
+        for (index, value) in values.iter().enumerate() {
+            validate(spec, items, value, &format!("{location}/{index}"))?;
+        }
+    }
+    if let Some(object) = value.as_object() {
+        // Note: An object schema without properties documents an object in its snarkVM JSON encoding.
+        if let Some(properties) = schema["properties"].as_object().filter(|properties| !properties.is_empty()) {
+            if let Some(key) = object.keys().find(|key| !properties.contains_key(*key)) {
+                return Err(format!("'{location}/{key}' is not documented"));
+            }
+            for (key, property) in properties {
+                let Some(value) = object.get(key) else {
+                    return Err(format!("'{location}/{key}' is documented, but missing"));
+                };
+                validate(spec, property, value, &format!("{location}/{key}"))?;
+            }
+        }
+        if let Some(property) = schema.get("additionalProperties") {
+            for (key, value) in object {
+                validate(spec, property, value, &format!("{location}/{key}"))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Fetches the OpenAPI specification served at the given address, and returns it with its raw body.
+async fn fetch_spec(address: SocketAddr) -> (Value, String) {
+    let response = reqwest::get(format!("http://{address}/mainnet/openapi.json")).await.unwrap();
+    assert!(response.status().is_success());
+    let body = response.text().await.unwrap();
+    (serde_json::from_str(&body).unwrap(), body)
+}
+
+#[tokio::test]
+async fn test_openapi_spec_is_served() {
+    let (address, _) = serve_rest().await;
+
+    // Fetch the specification.
+    let (spec, body) = fetch_spec(address).await;
+    if let Ok(path) = std::env::var(OPENAPI_SPEC_PATH) {
+        std::fs::write(path, &body).unwrap();
+    }
+
+    // Ensure the specification lists the routes of every group, and itself.
+    let paths = spec["paths"].as_object().unwrap();
+    for path in [
+        "/mainnet/node/address",
+        "/mainnet/block/{height_or_hash}",
+        "/mainnet/transaction/broadcast",
+        "/mainnet/find/blockHash/{tx_id}",
+        "/mainnet/peers/count",
+        "/mainnet/node/status",
+        "/mainnet/program/{id}",
+        "/mainnet/statePaths",
+        "/mainnet/openapi.json",
+    ] {
+        assert!(paths.contains_key(path), "The specification does not list '{path}'");
+    }
+    // Ensure the administrative routes require a JSON web token, and the others do not.
+    assert_eq!(paths["/mainnet/node/address"]["get"]["security"], serde_json::json!([{ "bearerAuth": [] }]));
+    assert!(paths["/mainnet/node/status"]["get"]["security"].is_null());
+
+    let mut operation_ids = HashSet::new();
+    for (path, operations) in paths {
+        // Collect the path parameters declared in the template.
+        let template: HashSet<_> = path
+            .split('/')
+            .filter_map(|segment| segment.strip_prefix('{').and_then(|segment| segment.strip_suffix('}')))
+            .collect();
+        for (method, operation) in operations.as_object().unwrap() {
+            // Ensure the operation IDs are unique.
+            assert!(operation_ids.insert(operation["operationId"].as_str().unwrap().to_string()));
+            // Ensure the path parameters match the template.
+            let declared: HashSet<_> = operation["parameters"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter(|parameter| parameter["in"] == "path")
+                .map(|parameter| parameter["name"].as_str().unwrap())
+                .collect();
+            assert_eq!(declared, template, "{method} {path}");
+            // Ensure every operation has a successful response and an error response.
+            assert!(operation["responses"]["200"].is_object(), "{method} {path}");
+            assert!(operation["responses"]["500"].is_object(), "{method} {path}");
+            // Ensure every documented route is registered, i.e. is not rejected by the router itself.
+            if method == "get" && template.is_empty() && operation["security"].is_null() {
+                let response = reqwest::get(format!("http://{address}{path}")).await.unwrap();
+                assert_ne!(response.status(), reqwest::StatusCode::NOT_FOUND, "{method} {path}");
+            }
+        }
+    }
+
+    // Ensure every referenced component exists.
+    for (index, _) in body.match_indices("#/components/") {
+        let reference = &body[index + 2..];
+        let reference = &reference[..reference.find('"').unwrap()];
+        assert!(spec.pointer(&format!("/{reference}")).is_some(), "Unknown reference '{reference}'");
+    }
+}
+
+#[tokio::test]
+async fn test_openapi_schemas_match_responses() {
+    let (address, ledger) = serve_rest().await;
+    let (spec, _) = fetch_spec(address).await;
+
+    // Ensure the served responses match the schemas of their routes.
+    for (route, path) in [
+        ("/mainnet/block/height/latest", "/mainnet/block/height/latest"),
+        ("/mainnet/block/hash/latest", "/mainnet/block/hash/latest"),
+        ("/mainnet/block/latest", "/mainnet/block/latest"),
+        ("/mainnet/block/latest/info", "/mainnet/block/latest/info"),
+        ("/mainnet/block/{height_or_hash}", "/mainnet/block/0"),
+        ("/mainnet/blocks/recent", "/mainnet/blocks/recent"),
+        ("/mainnet/stateRoot/latest", "/mainnet/stateRoot/latest"),
+        ("/mainnet/committee/latest", "/mainnet/committee/latest"),
+        ("/mainnet/committee/latest", "/mainnet/committee/latest?limit=1"),
+    ] {
+        let response = reqwest::get(format!("http://{address}{path}")).await.unwrap();
+        assert!(response.status().is_success(), "{path}");
+        let value: Value = response.json().await.unwrap();
+        let schema = &spec["paths"][route]["get"]["responses"]["200"]["content"]["application/json"]["schema"];
+        assert!(schema.is_object(), "The specification does not document the response of '{route}'");
+        validate(&spec, schema, &value, path).unwrap();
+    }
+
+    // Ensure the response types, with every optional field set, match their component schemas.
+    let genesis = ledger.get_block(0).unwrap();
+    let committee = ledger.latest_committee().unwrap();
+    let fees = FeePercentiles::new(vec![1, 2, 3]);
+    let responses = [
+        ("LatestBlockInfo", serde_json::to_value(LatestBlockInfo::new(&genesis)).unwrap()),
+        ("BlockSummary", serde_json::to_value(BlockSummary::new(&genesis)).unwrap()),
+        ("CommitteePage", serde_json::to_value(CommitteePage::new(&committee, 0, 1)).unwrap()),
+        (
+            "SyncProgress",
+            serde_json::to_value(SyncStatus {
+                latest_height: 0,
+                peer_height: Some(10),
+                num_blocks_remaining: 10,
+                blocks_per_sec: Some(2.5),
+                estimated_completion: Some(4),
+                is_synced: false,
+            })
+            .unwrap(),
+        ),
+        (
+            "BlockEstimate",
+            serde_json::to_value(BlockEstimate {
+                latest_height: 1,
+                latest_timestamp: 10,
+                latest_round: 2,
+                average_block_interval: 3.5,
+                median_block_interval: 3.0,
+                estimated_next_block_timestamp: 13,
+                confidence_window: ConfidenceWindow { earliest: 12, latest: 15 },
+                rounds_per_block: 2.0,
+                num_intervals: 1,
+                num_outliers: 0,
+                round_progress: Some(RoundProgress {
+                    current_round: 3,
+                    round_age: 1,
+                    typical_round_duration: 1.5,
+                    estimated_next_block_timestamp: 12,
+                }),
+            })
+            .unwrap(),
+        ),
+        (
+            "FeeEstimate",
+            serde_json::to_value(FeeEstimate {
+                is_fee_prioritized: true,
+                queue_depth: Some(3),
+                memory_pool: fees,
+                latest_height: 0,
+                num_blocks: 1,
+                recent_blocks: fees,
+            })
+            .unwrap(),
+        ),
+    ];
+    for (name, value) in responses {
+        let schema = &spec["components"]["schemas"][name];
+        assert!(schema.is_object(), "The specification does not document '{name}'");
+        validate(&spec, schema, &value, name).unwrap();
+    }
+}
@@ -0,0 +1,117 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![recursion_limit = "256"]
+
+#[allow(dead_code)]
+mod common;
+use common::sample_account;
+
+use snarkos_node::Client;
+use snarkos_node_bft_ledger_service::test_helpers::sample_ledger;
+use snarkos_node_rest::{NodeConfig, Rest};
+use snarkos_node_router::messages::NodeType;
+use snarkvm::prelude::{Field, MainnetV0 as CurrentNetwork, Network, Program, store::helpers::memory::ConsensusMemory};
+
+use aleo_std::StorageMode;
+use rand::SeedableRng;
+use rand_chacha::ChaChaRng;
+use serde_json::{Value, json};
+use std::{net::SocketAddr, str::FromStr, time::Duration};
+
+type CurrentLedger = ConsensusMemory<CurrentNetwork>;
+
+#[tokio::test]
+async fn test_rejected_deployments_endpoints() {
+    let rng = &mut ChaChaRng::seed_from_u64(1234567890u64);
+    let (private_key, ledger) = sample_ledger::<CurrentNetwork, _>(0, rng);
+
+    // Deploy the same program twice in a block, so that the second deployment collides with the first one.
+    let program = Program::<CurrentNetwork>::from_str(
+        r"
+program rejected_deployment_test.aleo;
+
+function compute:
+    input r0 as u32.private;
+    input r1 as u32.private;
+    add r0 r1 into r2;
+    output r2 as u32.private;",
+    )
+    .unwrap();
+    let first = ledger.vm().deploy(&private_key, &program, None, 0, None, rng).unwrap();
+    let second = ledger.vm().deploy(&private_key, &program, None, 0, None, rng).unwrap();
+    let transactions = vec![first.clone(), second.clone()];
+    let block = ledger.prepare_advance_to_next_beacon_block(&private_key, vec![], vec![], transactions, rng).unwrap();
+    ledger.advance_to_next_block(&block).unwrap();
+    let fee_transaction_id = block
+        .transactions()
+        .iter()
+        .find(|confirmed| confirmed.is_rejected())
+        .map(|confirmed| confirmed.transaction().id())
+        .unwrap();
+
+    // Start the server, which follows the ledger for the rejected deployments.
+    let rest_ip: SocketAddr = {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.local_addr().unwrap()
+    };
+    let config =
+        NodeConfig::new(NodeType::Client, sample_account().address(), None, None, &StorageMode::Production, None, &[]);
+    let _rest = Rest::<CurrentNetwork, CurrentLedger, Client<CurrentNetwork, CurrentLedger>>::start(
+        rest_ip, 100, None, ledger, None, None, 0, config,
+    )
+    .await
+    .unwrap();
+    let client = reqwest::Client::new();
+    let get = |path: String| {
+        let client = client.clone();
+        async move {
+            let response = client.get(format!("http://{rest_ip}/mainnet/{path}")).send().await.unwrap();
+            assert!(response.status().is_success(), "GET {path} - {}", response.status());
+            response.json::<Value>().await.unwrap()
+        }
+    };
+
+    // Ensure the first deployment is accepted.
+    assert_eq!(
+        get(format!("deployment/{}/status", first.id())).await,
+        json!({ "status": "accepted", "program_id": "rejected_deployment_test.aleo", "height": 1 })
+    );
+    // Ensure the second deployment is rejected, by the ID it was broadcast with.
+    let rejected = json!({
+        "transaction_id": second.id().to_string(),
+        "fee_transaction_id": fee_transaction_id.to_string(),
+        "program_id": "rejected_deployment_test.aleo",
+        "height": 1,
+    });
+    let mut status = rejected.clone();
+    status["status"] = json!("rejected");
+    assert_eq!(get(format!("deployment/{}/status", second.id())).await, status);
+    // Ensure a transaction that is not in a block is unknown.
+    let unknown = <CurrentNetwork as Network>::TransactionID::from(Field::from_u64(7));
+    assert_eq!(get(format!("deployment/{unknown}/status")).await, json!({ "status": "unknown" }));
+
+    // Ensure the rejected deployment reaches the recent history, once the server scanned the block.
+    let mut recent = get("deployments/rejected/recent".to_string()).await;
+    for _ in 0..50 {
+        if recent != json!([]) {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        recent = get("deployments/rejected/recent".to_string()).await;
+    }
+    assert_eq!(recent, json!([rejected]));
+    assert_eq!(get("deployments/rejected/recent?limit=0".to_string()).await, json!([]));
+}
@@ -0,0 +1,62 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[allow(dead_code)]
+mod common;
+use common::{sample_genesis_block, test_peer::TestPeer};
+
+use snarkos_node_router::messages::{Message, NodeType, Ping};
+use snarkos_node_sync::locators::BlockLocators;
+use snarkos_node_tcp::P2P;
+use snarkvm::prelude::{Field, MainnetV0 as CurrentNetwork};
+
+use deadline::deadline;
+use pea2pea::{Pea2Pea, protocols::Writing};
+use std::time::Duration;
+
+#[tokio::test]
+async fn test_client_rejects_history_beyond_max_reorg_depth() {
+    // Spin up a client that does not tolerate any reorg, so that a conflicting genesis block exceeds the depth.
+    let client = common::node::client_with_max_reorg_depth(0).await;
+
+    // Spin up a test peer that follows the ledger of the client, and one that presents an alternative history.
+    let honest = TestPeer::validator().await;
+    let malicious = TestPeer::validator().await;
+    let honest_ip = honest.node().listening_addr().unwrap();
+    let malicious_ip = malicious.node().listening_addr().unwrap();
+    for peer_ip in [honest_ip, malicious_ip] {
+        client.router().connect(peer_ip).unwrap().await.unwrap();
+    }
+    let client_clone = client.clone();
+    deadline!(Duration::from_secs(5), move || client_clone.router().number_of_connected_peers() == 2);
+
+    // Send the block locators of each history to the client.
+    let honest_locators = BlockLocators::new_genesis(sample_genesis_block().hash());
+    let malicious_locators = BlockLocators::new_genesis(Field::<CurrentNetwork>::from_u32(1).into());
+    for (peer, locators) in [(&honest, honest_locators), (&malicious, malicious_locators)] {
+        let client_addr = *peer.node().connected_addrs().first().unwrap();
+        let ping = Ping::new(NodeType::Validator, Some(locators));
+        assert!(peer.unicast(client_addr, Message::Ping(ping)).is_ok());
+    }
+
+    // Ensure the client disconnects from the peer with the alternative history, and restricts it.
+    let client_clone = client.clone();
+    deadline!(Duration::from_secs(5), move || !client_clone.router().is_connected(&malicious_ip));
+    assert!(client.router().is_restricted(&malicious_ip));
+
+    // Ensure the client keeps following the peer that extends its ledger.
+    assert!(client.router().is_connected(&honest_ip));
+    assert!(!client.router().is_restricted(&honest_ip));
+}
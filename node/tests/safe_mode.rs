@@ -0,0 +1,131 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(target_os = "linux")]
+#![recursion_limit = "256"]
+
+#[allow(dead_code)]
+mod common;
+use common::{sample_account, sample_genesis_block};
+
+use snarkos_node::SafeNode;
+use snarkos_node_rest::{Claims, SAFE_MODE_ERROR};
+use snarkos_node_router::messages::NodeType;
+use snarkvm::prelude::{MainnetV0 as CurrentNetwork, store::helpers::rocksdb::ConsensusDB};
+
+use aleo_std::StorageMode;
+use reqwest::{StatusCode, header::RETRY_AFTER};
+use std::{collections::HashSet, net::SocketAddr};
+
+/// Returns the ports of the TCP sockets this process is listening on.
+fn listening_ports() -> HashSet<u16> {
+    // Collect the inodes of the sockets owned by this process.
+    let inodes: HashSet<String> = std::fs::read_dir("/proc/self/fd")
+        .unwrap()
+        .filter_map(|entry| std::fs::read_link(entry.ok()?.path()).ok())
+        .filter_map(|link| {
+            let link = link.to_string_lossy().to_string();
+            Some(link.strip_prefix("socket:[")?.strip_suffix(']')?.to_string())
+        })
+        .collect();
+    // Select the listening sockets among them, i.e. those in the `LISTEN` (0A) state.
+    let mut ports = HashSet::new();
+    for table in ["/proc/net/tcp", "/proc/net/tcp6"] {
+        let Ok(table) = std::fs::read_to_string(table) else { continue };
+        for line in table.lines().skip(1) {
+            let fields: Vec<_> = line.split_whitespace().collect();
+            let (local_address, state, inode) = (fields[1], fields[3], fields[9]);
+            if state == "0A" && inodes.contains(inode) {
+                let port = local_address.rsplit(':').next().unwrap();
+                ports.insert(u16::from_str_radix(port, 16).unwrap());
+            }
+        }
+    }
+    ports
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_safe_mode_serves_ledger_without_networking() {
+    // Select an available port for the REST server.
+    let rest_ip: SocketAddr = {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.local_addr().unwrap()
+    };
+    // Initialize a node in safe mode, on a fresh data directory.
+    let storage = std::env::temp_dir().join(format!("snarkos-safe-mode-{}", rand::random::<u64>()));
+    let account = sample_account();
+    let node = SafeNode::<CurrentNetwork, ConsensusDB<CurrentNetwork>>::new(
+        NodeType::Validator,
+        Some(rest_ip),
+        10,
+        0,    // No recent block summaries.
+        None, // No log file.
+        account.clone(),
+        sample_genesis_block(),
+        StorageMode::Custom(storage.clone()),
+        Default::default(),
+    )
+    .await
+    .unwrap();
+    assert!(node.rest().as_ref().unwrap().is_safe_mode());
+    // Ensure the ledger is served from a snapshot, leaving the data directory untouched.
+    let snapshot = storage.with_file_name(format!("{}.safe-mode", storage.file_name().unwrap().to_string_lossy()));
+    assert!(snapshot.exists());
+    assert!(!storage.exists());
+
+    // Ensure the blocks are served.
+    let base_url = format!("http://{rest_ip}/mainnet");
+    let client = reqwest::Client::new();
+    let response = client.get(format!("{base_url}/block/height/latest")).send().await.unwrap();
+    assert!(response.status().is_success());
+    assert_eq!(response.text().await.unwrap(), "0");
+    let response = client.get(format!("{base_url}/block/0")).send().await.unwrap();
+    assert!(response.status().is_success());
+    let block: serde_json::Value = serde_json::from_str(&response.text().await.unwrap()).unwrap();
+    assert_eq!(block["block_hash"], sample_genesis_block().hash().to_string());
+
+    // Ensure the status reports the mode.
+    let response = client.get(format!("{base_url}/node/status")).send().await.unwrap();
+    let status: serde_json::Value = serde_json::from_str(&response.text().await.unwrap()).unwrap();
+    assert_eq!(status["mode"], "safe");
+    assert_eq!(status["latest_height"], 0);
+
+    // Ensure the configuration requires a JSON web token.
+    let response = client.get(format!("{base_url}/node/config")).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    // Ensure the configuration reports the custom storage, without the secrets of the node.
+    let token = Claims::new(account.address()).to_jwt_string().unwrap();
+    let response = client.get(format!("{base_url}/node/config")).bearer_auth(token).send().await.unwrap();
+    assert!(response.status().is_success());
+    let text = response.text().await.unwrap();
+    assert!(!text.contains(&account.private_key().to_string()));
+    assert!(!text.contains(&account.view_key().to_string()));
+    let config: serde_json::Value = serde_json::from_str(&text).unwrap();
+    assert_eq!(config["storage"]["mode"], "custom");
+    assert_eq!(config["storage"]["path"], storage.display().to_string());
+    assert_eq!(config["rest"]["rest_ip"], rest_ip.to_string());
+    assert!(config["router"].is_null());
+
+    // Ensure the routes that require networking are refused.
+    let response = client.get(format!("{base_url}/peers/count")).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert!(response.headers().get(RETRY_AFTER).is_none());
+    assert!(response.text().await.unwrap().contains(SAFE_MODE_ERROR));
+
+    // Ensure the REST server is the only listener.
+    assert_eq!(listening_ports(), HashSet::from([rest_ip.port()]));
+
+    let _ = std::fs::remove_dir_all(snapshot);
+}
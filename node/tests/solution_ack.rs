@@ -0,0 +1,129 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[allow(dead_code)]
+mod common;
+use common::test_peer::TestPeer;
+
+use snarkos_account::Account;
+use snarkos_node_router::messages::{Features, Message, NodeType, SolutionAck, SolutionStatus, UnconfirmedSolution};
+use snarkos_node_tcp::P2P;
+use snarkvm::{
+    ledger::narwhal::Data,
+    prelude::{MainnetV0 as CurrentNetwork, Network, puzzle::Solution, store::helpers::memory::ConsensusMemory},
+    synthesizer::VM,
+};
+
+use deadline::deadline;
+use pea2pea::{Pea2Pea, protocols::Writing};
+use rand::Rng;
+use std::{net::SocketAddr, time::Duration};
+
+/// Returns a solution for the given epoch hash, found by the given prover, without a minimum proof target.
+fn sample_solution(prover: &TestPeer, epoch_hash: <CurrentNetwork as Network>::BlockHash) -> Solution<CurrentNetwork> {
+    let puzzle = VM::<CurrentNetwork, ConsensusMemory<CurrentNetwork>>::new_puzzle().unwrap();
+    puzzle.prove(epoch_hash, prover.address(), rand::thread_rng().gen(), None).unwrap()
+}
+
+/// Submits the given solution from the test peer, over its only connection, and returns the address of the node.
+fn submit(prover: &TestPeer, solution: Solution<CurrentNetwork>) -> SocketAddr {
+    let node_addr = *prover.node().connected_addrs().first().unwrap();
+    let message = UnconfirmedSolution { solution_id: solution.id(), solution: Data::Object(solution) };
+    assert!(prover.unicast(node_addr, Message::UnconfirmedSolution(message)).is_ok());
+    node_addr
+}
+
+/// Returns the statuses that the test peer received for the given solution, from the given node address.
+fn statuses(prover: &TestPeer, node_addr: SocketAddr, solution: &Solution<CurrentNetwork>) -> Vec<SolutionStatus> {
+    prover
+        .received_from(node_addr)
+        .into_iter()
+        .filter_map(|message| match message {
+            Message::SolutionAck(SolutionAck { solution_id, status }) if solution_id == solution.id() => Some(status),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Waits until the test peer received the given statuses for the given solution, from the given node address.
+fn expect_statuses(
+    prover: &TestPeer,
+    node_addr: SocketAddr,
+    solution: &Solution<CurrentNetwork>,
+    expected: &[SolutionStatus],
+) {
+    let (prover, solution, expected) = (prover.clone(), *solution, expected.to_vec());
+    deadline!(Duration::from_secs(5), move || statuses(&prover, node_addr, &solution) == expected);
+}
+
+#[tokio::test]
+async fn test_client_acknowledges_solutions() {
+    // Spin up a client, and test peers acting as provers, with and without solution acknowledgments.
+    let client = common::node::client().await;
+    let rng = &mut rand::thread_rng();
+    let prover = TestPeer::with_features(NodeType::Prover, Account::new(rng).unwrap(), Features::SOLUTION_ACK).await;
+    let legacy_prover = TestPeer::new(NodeType::Prover, Account::new(rng).unwrap()).await;
+    for peer in [&prover, &legacy_prover] {
+        client.router().connect(peer.node().listening_addr().unwrap()).unwrap().await.unwrap();
+    }
+    let client_clone = client.clone();
+    deadline!(Duration::from_secs(5), move || client_clone.router().number_of_connected_peers() == 2);
+
+    // Ensure a solution for another epoch is acknowledged as stale.
+    let stale_solution = sample_solution(&prover, Default::default());
+    let client_addr = submit(&prover, stale_solution);
+    expect_statuses(&prover, client_addr, &stale_solution, &[SolutionStatus::StaleEpoch]);
+
+    // Ensure a solution that does not meet the proof target of the genesis block is acknowledged as such.
+    let epoch_hash = client.ledger().latest_epoch_hash().unwrap();
+    let solution = sample_solution(&prover, epoch_hash);
+    submit(&prover, solution);
+    expect_statuses(&prover, client_addr, &solution, &[SolutionStatus::BelowTarget]);
+
+    // Ensure a resubmitted solution is acknowledged as a duplicate.
+    submit(&prover, solution);
+    expect_statuses(&prover, client_addr, &solution, &[SolutionStatus::BelowTarget, SolutionStatus::Duplicate]);
+
+    // Ensure a prover that did not negotiate solution acknowledgments does not receive any,
+    // by the time a prover that did received the acknowledgment of a solution submitted afterwards.
+    let legacy_solution = sample_solution(&legacy_prover, epoch_hash);
+    let legacy_client_addr = submit(&legacy_prover, legacy_solution);
+    let solution = sample_solution(&prover, epoch_hash);
+    submit(&prover, solution);
+    expect_statuses(&prover, client_addr, &solution, &[SolutionStatus::BelowTarget]);
+    assert!(statuses(&legacy_prover, legacy_client_addr, &legacy_solution).is_empty());
+    assert_eq!(client.router().number_of_connected_peers(), 2);
+}
+
+#[tokio::test]
+async fn test_validator_acknowledges_solutions() {
+    // Spin up a validator, and a test peer acting as a prover that negotiates solution acknowledgments.
+    let validator = common::node::validator().await;
+    let rng = &mut rand::thread_rng();
+    let prover = TestPeer::with_features(NodeType::Prover, Account::new(rng).unwrap(), Features::SOLUTION_ACK).await;
+    validator.router().connect(prover.node().listening_addr().unwrap()).unwrap().await.unwrap();
+    let validator_clone = validator.clone();
+    deadline!(Duration::from_secs(5), move || validator_clone.router().number_of_connected_peers() == 1);
+
+    // Ensure a solution is acknowledged once it is queued in the memory pool.
+    let epoch_hash = validator.ledger().latest_epoch_hash().unwrap();
+    let solution = sample_solution(&prover, epoch_hash);
+    let validator_addr = submit(&prover, solution);
+    expect_statuses(&prover, validator_addr, &solution, &[SolutionStatus::Accepted]);
+
+    // Ensure a resubmitted solution is acknowledged as a duplicate.
+    submit(&prover, solution);
+    expect_statuses(&prover, validator_addr, &solution, &[SolutionStatus::Accepted, SolutionStatus::Duplicate]);
+}
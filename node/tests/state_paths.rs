@@ -0,0 +1,91 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![recursion_limit = "256"]
+
+#[allow(dead_code)]
+mod common;
+use common::sample_account;
+
+use snarkos_node::Client;
+use snarkos_node_bft_ledger_service::test_helpers::{sample_genesis_block, sample_ledger_from_genesis};
+use snarkos_node_rest::{MAX_STATE_PATHS, NodeConfig, Rest};
+use snarkos_node_router::messages::NodeType;
+use snarkvm::prelude::{
+    Field,
+    MainnetV0 as CurrentNetwork,
+    Network,
+    StatePath,
+    Zero,
+    store::helpers::memory::ConsensusMemory,
+};
+
+use aleo_std::StorageMode;
+use rand::SeedableRng;
+use rand_chacha::ChaChaRng;
+use reqwest::StatusCode;
+use std::net::SocketAddr;
+
+type CurrentLedger = ConsensusMemory<CurrentNetwork>;
+
+#[tokio::test]
+async fn test_state_paths_batch_within_rate_limit() {
+    // Initialize a ledger with a development genesis block, which contains records.
+    let rng = &mut ChaChaRng::seed_from_u64(1234567890u64);
+    let (private_key, genesis) = sample_genesis_block::<CurrentNetwork, _>(rng);
+    let ledger = sample_ledger_from_genesis(&private_key, &genesis, 0, rng);
+
+    // Start the server, with a burst of requests smaller than a full batch.
+    let rest_ip: SocketAddr = {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.local_addr().unwrap()
+    };
+    let config =
+        NodeConfig::new(NodeType::Client, sample_account().address(), None, None, &StorageMode::Production, None, &[]);
+    let _rest = Rest::<CurrentNetwork, CurrentLedger, Client<CurrentNetwork, CurrentLedger>>::start(
+        rest_ip, 10, None, ledger, None, None, 0, config,
+    )
+    .await
+    .unwrap();
+    let url = format!("http://{rest_ip}/mainnet/statePaths");
+    let client = reqwest::Client::new();
+
+    // Fill a full batch with the commitments of the genesis block.
+    let commitments: Vec<Field<CurrentNetwork>> =
+        genesis.transactions().commitments().copied().cycle().take(MAX_STATE_PATHS).collect();
+    assert_eq!(commitments.len(), MAX_STATE_PATHS);
+
+    // Ensure a full batch is served, against a single state root.
+    let response = client.post(&url).json(&commitments).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = response.json().await.unwrap();
+    let state_root: <CurrentNetwork as Network>::StateRoot =
+        serde_json::from_value(body["global_state_root"].clone()).unwrap();
+    let state_paths: Vec<StatePath<CurrentNetwork>> = serde_json::from_value(body["state_paths"].clone()).unwrap();
+    assert_eq!(state_paths.len(), MAX_STATE_PATHS);
+    for state_path in state_paths {
+        assert_eq!(state_path.global_state_root(), state_root);
+        state_path.verify(true, Field::zero()).unwrap();
+    }
+
+    // Ensure another full batch right away exceeds the rate limit.
+    let response = client.post(&url).json(&commitments).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+    // Ensure a batch beyond the maximum size is rejected as malformed.
+    let commitments: Vec<_> = commitments.iter().copied().cycle().take(MAX_STATE_PATHS + 1).collect();
+    let response = client.post(&url).json(&commitments).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,80 @@
+// Copyright 2024 Aleo Network Foundation
+// This file is part of the snarkOS library.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at:
+
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![recursion_limit = "256"]
+
+#[allow(dead_code)]
+mod common;
+use common::{node::client, sample_account};
+
+use snarkos_node::Client;
+use snarkos_node_rest::{Claims, NodeConfig, Rest, admin_routes};
+use snarkos_node_router::{Routing, messages::NodeType};
+use snarkvm::prelude::{MainnetV0 as CurrentNetwork, store::helpers::memory::ConsensusMemory};
+
+use aleo_std::StorageMode;
+use reqwest::StatusCode;
+use serde_json::{Value, json};
+use std::{net::SocketAddr, sync::Arc};
+
+type CurrentLedger = ConsensusMemory<CurrentNetwork>;
+
+#[tokio::test]
+async fn test_update_trusted_peers_route() {
+    // Initialize the state of the routes, with the routing of a client node.
+    let node = client().await;
+    let local_ip = node.router().local_ip();
+    let account = sample_account();
+    let config = NodeConfig::new(NodeType::Client, account.address(), None, None, &StorageMode::Production, None, &[]);
+    let rest = Rest::<CurrentNetwork, CurrentLedger, Client<CurrentNetwork, CurrentLedger>>::new(
+        None,
+        node.ledger().clone(),
+        Some(Arc::new(node.clone())),
+        config,
+    )
+    .unwrap();
+
+    // Mount the administrative routes in a bare application, under the network.
+    let app = axum::Router::new().nest("/mainnet", admin_routes(rest).into());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await.unwrap();
+    });
+    let url = format!("http://{address}/mainnet/node/trusted_peers");
+    let client = reqwest::Client::new();
+    let token = Claims::new(account.address()).to_jwt_string().unwrap();
+    let update = |body: Value| client.post(&url).bearer_auth(&token).json(&body).send();
+
+    // Ensure a peer is trusted.
+    let response = update(json!({ "insert": ["1.2.3.4:4130"] })).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let trusted: Value = serde_json::from_str(&response.text().await.unwrap()).unwrap();
+    assert_eq!(trusted, json!({ "trusted_peers": ["1.2.3.4:4130"], "num_inserted": 1, "num_removed": 0 }));
+
+    // Ensure the invalid peers are rejected as bad requests, without updating any peer.
+    for invalid_ip in [local_ip.to_string(), "0.0.0.0:4130".to_string(), "5.6.7.8:0".to_string()] {
+        let response =
+            update(json!({ "insert": ["5.6.7.8:4130", invalid_ip], "remove": ["1.2.3.4:4130"] })).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{invalid_ip}");
+    }
+    assert_eq!(node.router().trusted_peers(), ["1.2.3.4:4130".parse().unwrap()].into());
+
+    // Ensure the peer is no longer trusted.
+    let response = update(json!({ "remove": ["1.2.3.4:4130"] })).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let trusted: Value = serde_json::from_str(&response.text().await.unwrap()).unwrap();
+    assert_eq!(trusted, json!({ "trusted_peers": [], "num_inserted": 0, "num_removed": 1 }));
+}